@@ -0,0 +1,22 @@
+use std::fmt;
+
+fn clamp(value: i32, low: i32, high: i32) -> i32 {
+    if value < low {
+        return low;
+    }
+    if value > high {
+        return high;
+    }
+    value
+}
+
+fn area(width: i32, height: i32) -> i32 {
+    width + height
+}
+
+fn main() {
+    let low = 0;
+    let size = area(3, 4);
+    let fitted = clamp(area(size, 2), low, 100);
+    fmt::print(fitted);
+}
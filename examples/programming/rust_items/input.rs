@@ -0,0 +1,24 @@
+use std::fmt;
+
+pub const LIMIT: usize = 16;
+
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn origin() -> Point {
+    Point { x: 0, y: 0 }
+}
+
+fn unused(p: Point) -> i32 {
+    p.x + p.y
+}
+
+fn find(limit: usize) -> Option<Point> {
+    let start = origin();
+    if limit > LIMIT {
+        return None;
+    }
+    start
+}
@@ -0,0 +1,20 @@
+mod shapes {
+    pub struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    impl Add for Point {
+        fn add(self: Point, other: Point) -> Point {
+            Point { x: self.x + other.x, y: self.y + other.y }
+        }
+    }
+
+    impl Point {
+        fn scaled(self: Point, k: i32) -> Point {
+            let scale = |v| v + k;
+            let shift = |v, d| v + d;
+            Point { x: scale(self.x), y: shift(self.y, k) }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+fn first() {
+    a
+}
+}
+
+fn second() {
+    b
+}
@@ -0,0 +1,7 @@
+fn area(width: i32, height: i32) -> i32 {
+    width + height
+)
+
+fn main() {
+    area(3, 4);
+}
@@ -0,0 +1,209 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for AnalysisWorkspace functionality
+/// </summary>
+public class AnalysisWorkspaceTests
+{
+    private const string ModuleGrammar = """
+        DeclarationRules: definition
+        ExportRules: export
+        ImportQuery: import > IDENTIFIER
+
+        <program> ::= <item> | <program> <item>
+        <item> ::= <import> | <export> | <definition> | <statement>
+        <import> ::= "use" <IDENTIFIER> ";"
+        <export> ::= "pub" <definition>
+        <definition> ::= "let" <IDENTIFIER> "=" <expr> ";"
+        <statement> ::= "print" <expr> ";"
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER>
+        <COMMENT> ::= /#[^\n]*/ => { skip }
+        """;
+
+    private const string FileA = "pub let base = 1;\nlet hidden = 2;\nprint hidden;\n";
+    private const string FileB = "use a;\npub let twice = base + base;\n";
+    private const string FileC = "use b;\nprint twice + 1;\n";
+
+    [Fact]
+    public void SetDocument_ThreeFiles_ResolvesReferencesAcrossFiles()
+    {
+        // Act
+        var workspace = CreateWorkspace();
+
+        // Assert
+        Assert.All(workspace.Documents, d => Assert.Empty(d.Diagnostics));
+
+        var b = workspace.GetDocument("b.mod")!;
+        Assert.Equal(2, b.Resolved.Count);
+        Assert.All(b.Resolved, r => Assert.Equal("a.mod", r.DeclaringPath));
+        Assert.Equal("twice", Assert.Single(workspace.GetDocument("c.mod")!.Resolved).Declaration.Name);
+
+        Assert.Equal("b.mod", Assert.Single(workspace.FindExports("twice")).Path);
+        Assert.Empty(workspace.FindExports("hidden"));
+        Assert.Equal(new[] { "c.mod" }, workspace.GetImporters("b"));
+    }
+
+    [Fact]
+    public void SetDocument_RenameExport_ReportsUnresolvedReferenceInImporter()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+
+        // Act
+        var reresolved = workspace.SetDocument("a.mod", FileA.Replace("base", "root"));
+
+        // Assert
+        Assert.Equal(new[] { "a.mod", "b.mod" }, reresolved);
+        Assert.Empty(workspace.GetDiagnostics("a.mod"));
+        Assert.Empty(workspace.GetDiagnostics("c.mod"));
+
+        var diagnostic = Assert.Single(workspace.GetDiagnostics("b.mod"));
+        Assert.Equal(DiagnosticCodes.UnresolvedReference, diagnostic.Code);
+        Assert.Contains("searched: b.mod, a.mod", diagnostic.Message);
+        Assert.Equal(new[] { "b.mod", "a.mod" }, (List<string>)diagnostic.Data["searched"]);
+        Assert.Equal("b.mod", diagnostic.Location!.SourceFile);
+        Assert.Equal(2, diagnostic.Location.Line);
+    }
+
+    [Fact]
+    public void SetDocument_UnchangedExports_OnlyReresolvesEditedFile()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+
+        // Act
+        var reresolved = workspace.SetDocument("a.mod", FileA.Replace("2", "3"));
+
+        // Assert
+        Assert.Equal(new[] { "a.mod" }, reresolved);
+        Assert.Equal(2, workspace.GetDocument("b.mod")!.Resolved.Count);
+    }
+
+    [Fact]
+    public void RemoveDocument_ImportedFile_ReportsUnresolvedImport()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+
+        // Act
+        var reresolved = workspace.RemoveDocument("b.mod");
+
+        // Assert
+        Assert.Equal(new[] { "c.mod" }, reresolved);
+        var diagnostics = workspace.GetDiagnostics("c.mod");
+        Assert.Contains(diagnostics, d => d.Code == DiagnosticCodes.UnresolvedImport && d.Message.Contains("'b'"));
+        Assert.Contains(diagnostics, d => d.Code == DiagnosticCodes.UnresolvedReference && d.Message.Contains("'twice'"));
+        Assert.Empty(workspace.FindExports("twice"));
+    }
+
+    [Fact]
+    public void SetDocument_CommentEdit_DoesNotRecomputeSymbolIndex()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+        Assert.Single(workspace.FindExports("base"));
+        var indexed = workspace.QueryStatistics[WorkspaceQueries.SymbolIndex].Executions;
+        var cutoffs = workspace.QueryStatistics[WorkspaceQueries.Symbols].Cutoffs;
+
+        // Act
+        var reresolved = workspace.SetDocument("a.mod", "# the base value\n" + FileA);
+        var export = Assert.Single(workspace.FindExports("base"));
+
+        // Assert
+        Assert.Equal(indexed, workspace.QueryStatistics[WorkspaceQueries.SymbolIndex].Executions);
+        Assert.Equal(cutoffs + 1, workspace.QueryStatistics[WorkspaceQueries.Symbols].Cutoffs);
+        Assert.Equal(2, export.Declaration.Location!.Line);
+
+        // The importer's references now resolve to the moved declaration; the file importing it is untouched.
+        Assert.Equal(new[] { "a.mod", "b.mod" }, reresolved);
+        Assert.All(workspace.GetDocument("b.mod")!.Resolved, r => Assert.Equal(2, r.Declaration.Location!.Line));
+    }
+
+    [Fact]
+    public void SetDocument_UnchangedText_RecomputesNothing()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+        var parses = workspace.QueryStatistics[WorkspaceQueries.Parse].Executions;
+
+        // Act
+        var reresolved = workspace.SetDocument("b.mod", FileB);
+
+        // Assert
+        Assert.Empty(reresolved);
+        Assert.Equal(parses, workspace.QueryStatistics[WorkspaceQueries.Parse].Executions);
+        Assert.Equal(2, workspace.GetDocument("b.mod")!.Resolved.Count);
+    }
+
+    [Fact]
+    public void Trim_RemovedDocument_ForgetsItsCachedAnalysis()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+        workspace.RemoveDocument("c.mod");
+        var cached = workspace.CachedQueryCount;
+
+        // Act
+        var forgotten = workspace.Trim();
+
+        // Assert
+        Assert.True(forgotten > 0);
+        Assert.Equal(cached - forgotten, workspace.CachedQueryCount);
+        Assert.Null(workspace.GetDocument("c.mod"));
+        Assert.Equal("b.mod", Assert.Single(workspace.FindExports("twice")).Path);
+        workspace.SetDocument("c.mod", FileC);
+        Assert.Equal("twice", Assert.Single(workspace.GetDocument("c.mod")!.Resolved).Declaration.Name);
+    }
+
+    [Fact]
+    public void Interner_NameUsedInSeveralFiles_IsHeldOnce()
+    {
+        // Arrange
+        var interner = new SharedInterner();
+
+        // Act
+        var workspace = CreateWorkspace(interner);
+
+        // Assert
+        var declared = workspace.GetDocument("a.mod")!.Symbols.Lookup("base")!.Declarations[0].Name;
+        var referenced = workspace.GetDocument("b.mod")!.Symbols.Lookup("base")!.References[0].Name;
+        Assert.Same(declared, referenced);
+        Assert.True(interner.TryGet("twice", out var twice));
+        Assert.Same(twice, workspace.GetDocument("c.mod")!.Resolved[0].Reference.Name);
+    }
+
+    private static AnalysisWorkspace CreateWorkspace(SharedInterner? interner = null)
+    {
+        var grammar = new GrammarFileReader().Read(ModuleGrammar);
+        var workspace = new AnalysisWorkspace(new GeneralizedParser(CompiledGrammar.Compile(grammar))) { Interner = interner };
+        workspace.SetDocument("c.mod", FileC);
+        workspace.SetDocument("b.mod", FileB);
+        workspace.SetDocument("a.mod", FileA);
+        return workspace;
+    }
+}
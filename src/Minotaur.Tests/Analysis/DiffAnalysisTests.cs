@@ -0,0 +1,196 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using System.Text.Json.Nodes;
+using Xunit;
+using Minotaur.Analysis;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for diff-aware analysis functionality
+/// </summary>
+public class DiffAnalysisTests
+{
+    private const string FunctionGrammar = """
+        DeclarationRules: statement, function
+        <program> ::= <function> | <program> <function>
+        <function> ::= "fn" <IDENTIFIER> "{" <body> "}"
+        <body> ::= <statement> | <body> <statement>
+        <statement> ::= <IDENTIFIER> "=" <expr> ";"
+        <expr> ::= <NUMBER> | <IDENTIFIER>
+        """;
+
+    private const string Original = """
+        fn first {
+          a = 1;
+          b = a;
+        }
+        fn second {
+          c = 2;
+        }
+
+        """;
+
+    [Fact]
+    public void Analyze_MovedFunction_KeepsItsDiagnosticsPreExisting()
+    {
+        // Arrange
+        const string moved = """
+            fn second {
+              c = 2;
+            }
+            fn first {
+              a = 1;
+              b = a;
+              d = 3;
+            }
+
+            """;
+
+        // Act
+        var analysis = DiffAnalysis.Analyze(CreateParser(), Original, moved);
+
+        // Assert
+        var reported = Assert.Single(analysis.Reported);
+        Assert.Equal(DiagnosticCodes.UnusedSymbol, reported.Code);
+        Assert.Contains("'d'", reported.Message);
+        Assert.Equal(7, reported.Location!.Line);
+
+        var unusedC = Assert.Single(analysis.Diagnostics, d => d.Diagnostic.Message.Contains("'c'"));
+        Assert.Equal(DiagnosticChange.PreExisting, unusedC.Change);
+        Assert.Equal(2, unusedC.Diagnostic.Location!.Line);
+        Assert.Equal(4, analysis.Get(DiagnosticChange.PreExisting).Count());
+        Assert.Empty(analysis.Get(DiagnosticChange.Fixed));
+    }
+
+    [Fact]
+    public void Analyze_Patch_RecoversTheOldVersionAndReportsOnlyAddedProblems()
+    {
+        // Arrange
+        const string changed = """
+            fn first {
+              a = 1;
+              b = a;
+            }
+            fn second {
+              e = 4;
+            }
+
+            """;
+        var patch = UnifiedDiff.Parse("""
+            diff --git a/src/main.fn b/src/main.fn
+            --- a/src/main.fn
+            +++ b/src/main.fn
+            @@ -4,4 +4,4 @@
+             }
+             fn second {
+            -  c = 2;
+            +  e = 4;
+             }
+            """);
+
+        // Act
+        var fileDiff = patch.Find("/work/repo/src/main.fn")!;
+        var analysis = DiffAnalysis.Analyze(CreateParser(), fileDiff, changed);
+
+        // Assert
+        Assert.Equal(Original, fileDiff.ReverseApply(changed));
+        Assert.Equal(new[] { new LineRange(6, 1) }, analysis.ChangedLines.Ranges);
+        Assert.Contains("'e'", Assert.Single(analysis.Reported).Message);
+        Assert.Contains("'c'", Assert.Single(analysis.Get(DiagnosticChange.Fixed)).Diagnostic.Message);
+    }
+
+    [Fact]
+    public void Analyze_NewProblemNextToADeletion_IsReportedOnlyWithContextLines()
+    {
+        // Arrange
+        var changed = Original.Replace("  b = a;\n", string.Empty);
+        var parser = CreateParser();
+
+        // Act
+        var strict = DiffAnalysis.Analyze(parser, Original, changed);
+        var lenient = DiffAnalysis.Analyze(parser, Original, changed, new DiffAnalysisOptions { ContextLines = 1 });
+
+        // Assert
+        Assert.Equal(new[] { new LineRange(3, 0) }, strict.ChangedLines.Ranges);
+        var unusedA = Assert.Single(strict.Get(DiagnosticChange.New));
+        Assert.Contains("'a'", unusedA.Diagnostic.Message);
+        Assert.False(unusedA.IsOnChangedLine);
+        Assert.Empty(strict.Reported);
+        Assert.Contains("'b'", Assert.Single(strict.Get(DiagnosticChange.Fixed)).Diagnostic.Message);
+        Assert.Contains("'a'", Assert.Single(lenient.Reported).Message);
+    }
+
+    [Fact]
+    public void Classify_IdenticalDiagnosticsOnRepeatedLines_MatchByCount()
+    {
+        // Arrange
+        var oldText = "x = 1;\n";
+        var newText = "x = 1;\nx = 1;\n";
+        var oldDiagnostics = new[] { Unused("x", oldText, 0) };
+        var newDiagnostics = new[] { Unused("x", newText, 0), Unused("x", newText, 7) };
+
+        // Act
+        var analysis = DiffAnalysis.Classify(oldText, oldDiagnostics, newText, newDiagnostics, ChangedLines.Between(oldText, newText));
+
+        // Assert
+        Assert.Equal(new[] { DiagnosticChange.PreExisting, DiagnosticChange.New }, analysis.Diagnostics.Select(d => d.Change));
+        Assert.Equal(2, Assert.Single(analysis.Reported).Location!.Line);
+    }
+
+    [Fact]
+    public void Fingerprint_IgnoresIndentationAndIsWrittenToSarif()
+    {
+        // Arrange
+        const string text = "x = 1;\n";
+        const string reindented = "\n    x  =  1;\n";
+        var source = SourceDecoder.Decode(Encoding.UTF8.GetBytes(reindented));
+        var formatter = new DiagnosticFormatter { Source = source };
+
+        // Act
+        var before = Assert.Single(DiagnosticFingerprint.Compute(new[] { Unused("x", text, 0) }, text));
+        var after = Assert.Single(DiagnosticFingerprint.Compute(new[] { Unused("x", reindented, 5) }, reindented));
+        var sarif = JsonNode.Parse(formatter.ToSarif(new[] { Unused("x", reindented, 5) }))!;
+
+        // Assert
+        Assert.Equal(before, after);
+        Assert.EndsWith(":1", after);
+        Assert.Equal(after, (string)sarif["runs"]![0]!["results"]![0]!["partialFingerprints"]![DiagnosticFingerprint.SarifKey]!);
+    }
+
+    private static Diagnostic Unused(string name, string text, int offset)
+    {
+        return new Diagnostic
+        {
+            Code = DiagnosticCodes.UnusedSymbol,
+            Severity = DiagnosticSeverity.Warning,
+            Message = $"'{name}' is declared but never used",
+            Location = new LineIndex(text).GetPosition(offset, name.Length)
+        };
+    }
+
+    private static GeneralizedParser CreateParser()
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(FunctionGrammar)));
+    }
+}
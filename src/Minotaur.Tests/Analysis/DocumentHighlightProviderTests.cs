@@ -0,0 +1,144 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for document highlight functionality
+/// </summary>
+public class DocumentHighlightProviderTests
+{
+    private const string Shadowing = """
+        fn scale(width: i32) -> i32 {
+            let total = width + 1;
+            if total > 0 {
+                let total = width;
+                return total + total;
+            }
+            total
+        }
+        """;
+
+    private const string Declarations = "DeclarationRules: statement, parameter, function, constant, struct, field\n";
+
+    [Fact]
+    public void GetHighlights_ShadowingLocal_HighlightsOnlyTheInnerOccurrences()
+    {
+        // Arrange
+        var document = Analyze(Declarations + ReadExampleGrammar(), Shadowing);
+
+        // Act
+        var highlights = DocumentHighlightProvider.GetHighlights(document.Parse, Shadowing.IndexOf("return total") + 8, document.Symbols);
+
+        // Assert
+        Assert.Equal(new[] { (4, DocumentHighlightKind.Write), (5, DocumentHighlightKind.Read), (5, DocumentHighlightKind.Read) }, Lines(highlights));
+    }
+
+    [Fact]
+    public void GetHighlights_OuterVariable_LeavesTheShadowingLocalOut()
+    {
+        // Arrange
+        var document = Analyze(Declarations + ReadExampleGrammar(), Shadowing);
+
+        // Act
+        var highlights = DocumentHighlightProvider.GetHighlights(document.Parse, Shadowing.LastIndexOf("total"), document.Symbols);
+
+        // Assert
+        Assert.Equal(new[] { (2, DocumentHighlightKind.Write), (3, DocumentHighlightKind.Read), (7, DocumentHighlightKind.Read) }, Lines(highlights));
+    }
+
+    [Fact]
+    public void GetHighlights_Parameter_IsVisibleInNestedBlocks()
+    {
+        // Arrange
+        var document = Analyze(Declarations + ReadExampleGrammar(), Shadowing);
+
+        // Act
+        var highlights = DocumentHighlightProvider.GetHighlights(document.Parse, Shadowing.IndexOf("width"), document.Symbols);
+
+        // Assert
+        Assert.Equal(new[] { 1, 2, 4 }, highlights.Select(h => h.Location.Line));
+    }
+
+    [Fact]
+    public void GetHighlights_NoDeclarationRules_MatchesTextInTheNearestScope()
+    {
+        // Arrange
+        var document = Analyze(ReadExampleGrammar(), Shadowing);
+
+        // Act
+        var inner = DocumentHighlightProvider.GetHighlights(document.Parse, Shadowing.IndexOf("return total") + 8, document.Symbols);
+        var keyword = DocumentHighlightProvider.GetHighlights(document.Parse, Shadowing.IndexOf("return") + 2, document.Symbols);
+
+        // Assert
+        Assert.Equal(new[] { 4, 5, 5 }, inner.Select(h => h.Location.Line));
+        Assert.All(inner, h => Assert.Equal(DocumentHighlightKind.Text, h.Kind));
+        Assert.Empty(keyword);
+    }
+
+    [Fact]
+    public void GetHighlights_AssignmentRules_MarkAssignmentTargetsAsWrites()
+    {
+        // Arrange
+        var grammar = """
+            DeclarationRules: declaration
+            AssignmentRules: assignment
+
+            <program> ::= <statement> | <program> <statement>
+            <statement> ::= <declaration> | <assignment> | <print>
+            <declaration> ::= "var" <IDENTIFIER> ";"
+            <assignment> ::= <IDENTIFIER> "=" <IDENTIFIER> ";"
+            <print> ::= "print" <IDENTIFIER> ";"
+            """;
+        const string source = "var a;\na = a;\nprint a;\n";
+        var document = Analyze(grammar, source);
+
+        // Act
+        var highlights = DocumentHighlightProvider.GetHighlights(document.Parse, source.IndexOf("print a") + 6, document.Symbols);
+
+        // Assert
+        Assert.Equal(
+            new[] { DocumentHighlightKind.Write, DocumentHighlightKind.Write, DocumentHighlightKind.Read, DocumentHighlightKind.Read },
+            highlights.Select(h => h.Kind));
+    }
+
+    private static WorkspaceDocument Analyze(string grammarText, string source)
+    {
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(grammarText));
+        var workspace = new AnalysisWorkspace(new GeneralizedParser(grammar));
+        workspace.SetDocument("scale.rs", source);
+        var document = workspace.GetDocument("scale.rs")!;
+        Assert.True(document.Parse.IsSuccess);
+        return document;
+    }
+
+    private static IEnumerable<(int Line, DocumentHighlightKind Kind)> Lines(IReadOnlyList<DocumentHighlight> highlights)
+    {
+        return highlights.Select(h => (h.Location.Line, h.Kind));
+    }
+
+    private static string ReadExampleGrammar([CallerFilePath] string path = "")
+    {
+        return File.ReadAllText(Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", "rust_items.grammar"));
+    }
+}
@@ -0,0 +1,247 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for incremental pass invalidation functionality
+/// </summary>
+public class IncrementalPassTests
+{
+    private const string FunctionGrammar = """
+        <program> ::= <function> | <program> <function>
+        <function> ::= "fn" <IDENTIFIER> "{" <statements> "}"
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= <IDENTIFIER> "=" <NUMBER> ";" | "if" <IDENTIFIER> "{" <statements> "}"
+        """;
+
+    private const string TwoFunctions = """
+        fn first {
+            a = 1;
+        }
+        fn second {
+            b = 2;
+            if b { c = 3; }
+        }
+        """;
+
+    [Fact]
+    public void Run_EditInOneFunction_RerunsTreeLocalPassOnlyForThatFunction()
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateParser(), TwoFunctions);
+        var cfg = new BlockCountPass();
+        var manager = new PassManager();
+        manager.Register(cfg);
+        manager.Run(parser.Current, "two.fn");
+        var second = FindFunction(parser.Current.Tree!, "second");
+        cfg.Analyzed.Clear();
+
+        // Act
+        var edited = parser.ApplyEdit(new TextEdit(TwoFunctions.IndexOf("a = 1;"), 6, "a = 1;\n    if a { x = 4; }"));
+        var after = manager.Run(edited, "two.fn");
+
+        // Assert
+        Assert.Equal(new[] { "first" }, cfg.Analyzed);
+        var execution = Assert.Single(after.Executions);
+        Assert.Equal(1, execution.UnitsRun);
+        Assert.Equal(1, execution.UnitsReused);
+        var blocks = after.Context.GetResult<UnitResults>(BlockCountPass.PassName);
+        Assert.Equal(2, blocks.Get<int>(FindFunction(edited.Tree!, "first").Id));
+        Assert.Equal(2, blocks.Get<int>(second.Id));
+        var statistics = manager.CacheStatistics[BlockCountPass.PassName];
+        Assert.Equal(2, statistics.Runs);
+        Assert.Equal(3, statistics.UnitRuns);
+        Assert.Equal(1, statistics.UnitHits);
+    }
+
+    [Fact]
+    public void Run_ReusedUnit_MovesItsDiagnosticsWithItsText()
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateParser(), TwoFunctions);
+        var manager = new PassManager();
+        manager.Register(new BlockCountPass());
+        manager.Run(parser.Current, "two.fn");
+
+        // Act
+        var edited = parser.ApplyEdit(new TextEdit(TwoFunctions.IndexOf("a = 1;"), 0, "z = 0;\n    "));
+        var run = manager.Run(edited, "two.fn");
+
+        // Assert
+        Assert.Equal(1, run.Executions[0].UnitsReused);
+        var second = FindFunction(edited.Tree!, "second");
+        var diagnostic = Assert.Single(run.Diagnostics, d => d.Message == "second has 2 block(s)");
+        Assert.Equal(second.SourcePosition!.Offset, diagnostic.Location!.Offset);
+        Assert.Equal(second.SourcePosition.Line, diagnostic.Location.Line);
+        Assert.Equal(BlockCountPass.PassName, diagnostic.Data["pass"]);
+    }
+
+    [Fact]
+    public void Run_UnchangedInputs_ReusesResultsAndEqualResultsStopInvalidation()
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateParser(), TwoFunctions);
+        var runs = new Dictionary<string, int>();
+        var manager = new PassManager();
+        manager.Register(new ProductPass("count", new[] { AnalysisProducts.Tree }, runs, c => c.Parse.Tree!.Children.Count));
+        manager.Register(new ProductPass("report", new[] { "count" }, runs, c => $"{c.GetResult<int>("count")} top-level node(s)"));
+        manager.Register(new ProductPass("settings", Array.Empty<string>(), runs, _ => "defaults"));
+        manager.Run(parser.Current, "two.fn");
+
+        // Act
+        var run = manager.Run(parser.ApplyEdit(new TextEdit(TwoFunctions.IndexOf("1;"), 1, "42")), "two.fn");
+
+        // Assert
+        Assert.Equal(new[] { false, true, true }, run.Executions.Select(e => e.Reused));
+        Assert.Equal(2, runs["count"]);
+        Assert.Equal(1, runs["report"]);
+        Assert.Equal(1, runs["settings"]);
+        Assert.Equal("2 top-level node(s)", run.Context.GetResult<string>("report"));
+        Assert.Equal(0.5, manager.CacheStatistics["settings"].HitRate);
+    }
+
+    [Fact]
+    public void Run_AnnotationLayer_InvalidatesReadersWhenTheLayerChanges()
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateParser(), TwoFunctions);
+        var runs = new Dictionary<string, int>();
+        var layer = AnalysisProducts.AnnotationLayer("names");
+        var manager = new PassManager();
+        manager.Register(new ProductPass("reader", new[] { layer }, runs, c => c.Annotations.Count(a => a.Key == "names")));
+        manager.Register(new ProductPass("names", new[] { AnalysisProducts.Tree }, runs, c =>
+        {
+            foreach (var function in Descendants(c.Parse.Tree!).OfType<NonTerminalNode>().Where(n => n.RuleName == "function"))
+            {
+                c.Annotate("names", FunctionName(function), null);
+            }
+
+            return null;
+        }, layer));
+        manager.Run(parser.Current, "two.fn");
+
+        // Act
+        var unchanged = manager.Run(parser.ApplyEdit(new TextEdit(TwoFunctions.IndexOf("1;"), 1, "7")), "two.fn");
+        var renamed = manager.Run(parser.ApplyEdit(new TextEdit(parser.Text.IndexOf("first"), 5, "third")), "two.fn");
+
+        // Assert
+        Assert.Equal(new[] { "names", "reader" }, unchanged.Executions.Select(e => e.Name));
+        Assert.True(unchanged.Executions[1].Reused);
+        Assert.False(renamed.Executions[1].Reused);
+        Assert.Equal(2, runs["reader"]);
+    }
+
+    [Fact]
+    public void GetExecutionOrder_UnknownInputProduct_Throws()
+    {
+        // Arrange
+        var manager = new PassManager();
+        manager.Register(new ProductPass("reader", new[] { "nowhere" }, new Dictionary<string, int>(), _ => null));
+
+        // Act & Assert
+        Assert.Contains("nowhere", Assert.Throws<InvalidOperationException>(() => manager.GetExecutionOrder()).Message);
+    }
+
+    private static GeneralizedParser CreateParser()
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(FunctionGrammar)));
+    }
+
+    private static CognitiveGraphNode FindFunction(CognitiveGraphNode tree, string name)
+    {
+        return Descendants(tree).OfType<NonTerminalNode>().Single(n => n.RuleName == "function" && FunctionName(n) == name);
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Descendants(CognitiveGraphNode node)
+    {
+        yield return node;
+        foreach (var descendant in node.Children.SelectMany(Descendants))
+        {
+            yield return descendant;
+        }
+    }
+
+    private static string FunctionName(CognitiveGraphNode function)
+    {
+        return function.Children.OfType<TerminalNode>().Single(t => t.TokenType == "IDENTIFIER").Text;
+    }
+
+    // Counts the blocks of each function, standing in for a control flow graph built per function.
+    private sealed class BlockCountPass : TreeLocalAnalysisPass
+    {
+        public const string PassName = "cfg";
+
+        public List<string> Analyzed { get; } = new();
+
+        public override string Name => PassName;
+
+        public override bool IsUnit(CognitiveGraphNode node) => node is NonTerminalNode { RuleName: "function" };
+
+        public override object? RunUnit(AnalysisContext context, CognitiveGraphNode unit)
+        {
+            var name = FunctionName(unit);
+            Analyzed.Add(name);
+            var blocks = Descendants(unit).OfType<TerminalNode>().Count(t => t.Text == "{");
+            context.Report(new Diagnostic
+            {
+                Code = "T0001",
+                Severity = DiagnosticSeverity.Information,
+                Message = $"{name} has {blocks} block(s)",
+                Location = unit.SourcePosition
+            });
+
+            return blocks;
+        }
+    }
+
+    private sealed class ProductPass : IIncrementalAnalysisPass
+    {
+        private readonly Dictionary<string, int> _runs;
+        private readonly Func<AnalysisContext, object?> _run;
+
+        public ProductPass(string name, IReadOnlyList<string> inputs, Dictionary<string, int> runs, Func<AnalysisContext, object?> run, params string[] outputs)
+        {
+            Name = name;
+            Inputs = inputs;
+            Outputs = outputs;
+            _runs = runs;
+            _run = run;
+        }
+
+        public string Name { get; }
+
+        public IReadOnlyList<string> Dependencies => Array.Empty<string>();
+
+        public IReadOnlyList<string> Inputs { get; }
+
+        public IReadOnlyList<string> Outputs { get; }
+
+        public object? Run(AnalysisContext context)
+        {
+            _runs[Name] = _runs.GetValueOrDefault(Name) + 1;
+            return _run(context);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for inlay hint functionality
+/// </summary>
+public class InlayHintProviderTests
+{
+    private const string Hints = "DeclarationRules: statement, parameter, function, constant, struct, field\nCallRules: call\nParameterRules: parameter\n";
+
+    [Fact]
+    public void GetInlayHints_Calls_NameEachArgumentByItsParameter()
+    {
+        // Arrange
+        var source = ReadExample("calls.rs");
+        var workspace = Analyze(Hints + ReadExample("rust_items.grammar"), source);
+
+        // Act
+        var hints = workspace.GetInlayHints("calls.rs");
+
+        // Assert
+        Assert.Equal(
+            new[] { (19, "width:"), (19, "height:"), (20, "value:"), (20, "width:"), (20, "height:"), (20, "high:") },
+            hints.Select(h => (h.Position.Line, h.Label)));
+        Assert.All(hints, h => Assert.Equal(InlayHintKind.Parameter, h.Kind));
+        Assert.All(hints, h => Assert.Equal(0, h.Position.Length));
+        Assert.Equal(source.IndexOf("area(size"), hints[2].Position.Offset);
+        Assert.Equal(source.IndexOf("size, 2"), hints[3].Position.Offset);
+        Assert.Equal(source.IndexOf("100"), hints[5].Position.Offset);
+        Assert.Equal("Parameter high of clamp", hints[5].Tooltip);
+    }
+
+    [Fact]
+    public void GetInlayHints_Range_OnlyVisitsOverlappingNodes()
+    {
+        // Arrange
+        var source = ReadExample("calls.rs");
+        var workspace = Analyze(Hints + ReadExample("rust_items.grammar"), source);
+        var start = source.IndexOf("let size");
+        var range = new TextRange(start, source.IndexOf('\n', start) - start);
+        var recorder = new RecordingProducer(workspace.InlayHintProducers.Single());
+
+        // Act
+        var hints = InlayHintProvider.GetHints(workspace.GetDocument("calls.rs")!.Run.Context, new[] { recorder }, range);
+
+        // Assert
+        Assert.Equal(new[] { "width:", "height:" }, hints.Select(h => h.Label));
+        var visited = Assert.Single(recorder.Visited);
+        Assert.Equal(19, visited.SourcePosition!.Line);
+    }
+
+    [Fact]
+    public void GetInlayHints_NoCallRules_ProducesNoHints()
+    {
+        // Arrange
+        var workspace = Analyze(ReadExample("rust_items.grammar"), ReadExample("calls.rs"));
+
+        // Act
+        var hints = workspace.GetInlayHints("calls.rs");
+
+        // Assert
+        Assert.Empty(workspace.InlayHintProducers.SelectMany(p => p.GetHintRules(workspace.GetDocument("calls.rs")!.Run.Context)));
+        Assert.Empty(hints);
+    }
+
+    [Fact]
+    public void GetInlayHints_UnknownPath_ReturnsEmpty()
+    {
+        // Arrange
+        var workspace = Analyze(Hints + ReadExample("rust_items.grammar"), ReadExample("calls.rs"));
+
+        // Act
+        var hints = workspace.GetInlayHints("missing.rs");
+
+        // Assert
+        Assert.Empty(hints);
+    }
+
+    private static AnalysisWorkspace Analyze(string grammarText, string source)
+    {
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(grammarText));
+        var workspace = new AnalysisWorkspace(new GeneralizedParser(grammar));
+        workspace.SetDocument("calls.rs", source);
+        Assert.True(workspace.GetDocument("calls.rs")!.Parse.IsSuccess);
+        return workspace;
+    }
+
+    private static string ReadExample(string file, [CallerFilePath] string path = "")
+    {
+        return File.ReadAllText(Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", file));
+    }
+
+    private sealed class RecordingProducer : IInlayHintProducer
+    {
+        private readonly IInlayHintProducer _inner;
+
+        public RecordingProducer(IInlayHintProducer inner)
+        {
+            _inner = inner;
+        }
+
+        public List<CognitiveGraphNode> Visited { get; } = new();
+
+        public IReadOnlySet<string> GetHintRules(AnalysisContext context)
+        {
+            return _inner.GetHintRules(context);
+        }
+
+        public IEnumerable<InlayHint> GetHints(AnalysisContext context, CognitiveGraphNode node)
+        {
+            Visited.Add(node);
+            return _inner.GetHints(context, node);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using System.Text.Json;
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for ModuleGraph functionality, over the toy project in Fixtures/modules whose lib/strings and
+/// lib/format import each other
+/// </summary>
+public class ModuleGraphTests
+{
+    [Fact]
+    public void GetModuleGraph_FixtureProject_ResolvesRelativeImports()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+
+        // Act
+        var graph = workspace.GetModuleGraph();
+
+        // Assert
+        Assert.Equal(new[] { "lib/format.toy", "lib/math.toy", "lib/strings.toy", "main.toy", "util.toy" }, graph.Modules);
+        Assert.Empty(graph.Unresolved);
+        Assert.Equal(new[] { "lib/math.toy", "lib/strings.toy" }, graph.DependenciesOf("main.toy"));
+        Assert.Equal(new[] { "util.toy" }, graph.DependenciesOf("lib/math.toy"));
+        Assert.Equal(new[] { "lib/format.toy", "main.toy" }, graph.DependentsOf("lib/strings.toy"));
+        Assert.Empty(graph.DependentsOf("main.toy"));
+
+        var dependency = graph.Dependencies.First(d => d.From == "main.toy");
+        Assert.Equal("./lib/math", dependency.Specifier);
+        Assert.Equal(1, dependency.Location!.Line);
+
+        // References resolve through the resolved imports, so no file reports an error.
+        Assert.All(workspace.Documents, d => Assert.Empty(d.Diagnostics));
+        Assert.Equal("lib/math.toy", workspace.GetDocument("main.toy")!.Resolved.First(r => r.Reference.Name == "add").DeclaringPath);
+    }
+
+    [Fact]
+    public void GetModuleGraph_FixtureProject_DetectsCycle()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+
+        // Act
+        var graph = workspace.GetModuleGraph();
+
+        // Assert
+        var cycle = Assert.Single(graph.Cycles);
+        Assert.Equal(new[] { "lib/format.toy", "lib/strings.toy" }, cycle);
+
+        var diagnostic = Assert.Single(graph.Diagnostics);
+        Assert.Equal(DiagnosticCodes.ImportCycle, diagnostic.Code);
+        Assert.Equal(DiagnosticSeverity.Warning, diagnostic.Severity);
+        Assert.Equal("lib/format.toy", diagnostic.Data["path"]);
+        Assert.Equal(1, diagnostic.Location!.Line);
+    }
+
+    [Fact]
+    public void TopologicalOrder_FixtureProject_ListsDependenciesFirst()
+    {
+        // Arrange
+        var graph = CreateWorkspace().GetModuleGraph();
+
+        // Act
+        var order = graph.TopologicalOrder().ToList();
+
+        // Assert
+        Assert.Equal(graph.Modules.Count, order.Count);
+        Assert.True(order.IndexOf("util.toy") < order.IndexOf("lib/math.toy"));
+        Assert.True(order.IndexOf("lib/math.toy") < order.IndexOf("main.toy"));
+        Assert.True(order.IndexOf("lib/strings.toy") < order.IndexOf("main.toy"));
+        Assert.Equal(1, Math.Abs(order.IndexOf("lib/strings.toy") - order.IndexOf("lib/format.toy")));
+    }
+
+    [Fact]
+    public void SetDocument_BareSpecifier_ReportsResolverReason()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+
+        // Act
+        workspace.SetDocument("tools.toy", "import \"std\";\nimport \"./missing\";\n");
+
+        // Assert
+        var diagnostics = workspace.GetDiagnostics("tools.toy");
+        Assert.Equal(2, diagnostics.Count);
+        Assert.All(diagnostics, d => Assert.Equal(DiagnosticCodes.UnresolvedImport, d.Code));
+        Assert.Contains("'std' is not a relative path", diagnostics[0].Message);
+        Assert.EndsWith((string)diagnostics[0].Data["reason"], diagnostics[0].Message);
+        Assert.Contains("resolves to missing.toy, which is not in the workspace", diagnostics[1].Message);
+
+        var unresolved = workspace.GetModuleGraph().Unresolved;
+        Assert.Equal(new[] { "std", "./missing" }, unresolved.Select(u => u.Specifier));
+        Assert.All(unresolved, u => Assert.Equal("tools.toy", u.From));
+    }
+
+    [Fact]
+    public void Resolvers_RegisteredResolver_ResolvesBareSpecifiers()
+    {
+        // Arrange
+        var workspace = CreateWorkspace(resolvers => resolvers.Register("Toy", (specifier, importingPath) =>
+            specifier == "std"
+                ? ModuleResolution.Resolved("util.toy")
+                : ModuleResolverRegistry.Relative(specifier, importingPath)));
+
+        // Act
+        workspace.SetDocument("tools.toy", "import \"std\";\nprint one;\n");
+
+        // Assert
+        Assert.Empty(workspace.GetDiagnostics("tools.toy"));
+        Assert.Equal(new[] { "lib/math.toy", "tools.toy" }, workspace.GetModuleGraph().DependentsOf("util.toy"));
+    }
+
+    [Fact]
+    public void SetDocument_ImportedFileChanges_ReresolvesImportersByPath()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+
+        // Act
+        var reresolved = workspace.SetDocument("util.toy", "pub let two = 2;\n");
+
+        // Assert
+        Assert.Equal(new[] { "lib/math.toy", "util.toy" }, reresolved);
+        Assert.Equal(DiagnosticCodes.UnresolvedReference, Assert.Single(workspace.GetDiagnostics("lib/math.toy")).Code);
+    }
+
+    [Fact]
+    public void ToJson_FixtureProject_ListsModulesDependenciesAndCycles()
+    {
+        // Arrange
+        var graph = CreateWorkspace().GetModuleGraph();
+
+        // Act
+        using var json = JsonDocument.Parse(graph.ToJson());
+
+        // Assert
+        var root = json.RootElement;
+        Assert.Equal(5, root.GetProperty("modules").GetArrayLength());
+        Assert.Equal(5, root.GetProperty("dependencies").GetArrayLength());
+        var first = root.GetProperty("dependencies")[0];
+        Assert.Equal("lib/format.toy", first.GetProperty("from").GetString());
+        Assert.Equal("lib/strings.toy", first.GetProperty("to").GetString());
+        Assert.Equal("./strings", first.GetProperty("specifier").GetString());
+        Assert.Equal("lib/format.toy", root.GetProperty("cycles")[0][0].GetString());
+    }
+
+    [Fact]
+    public void ToDot_FixtureProject_MarksCycleEdges()
+    {
+        // Arrange
+        var graph = CreateWorkspace().GetModuleGraph();
+
+        // Act
+        var dot = graph.ToDot();
+
+        // Assert
+        Assert.StartsWith("digraph modules {", dot);
+        Assert.Contains("\"main.toy\" -> \"lib/math.toy\";", dot);
+        Assert.Contains("\"lib/strings.toy\" -> \"lib/format.toy\" [color=red];", dot);
+        Assert.Contains("\"lib/format.toy\" -> \"lib/strings.toy\" [color=red];", dot);
+    }
+
+    [Fact]
+    public void Compile_ImportAnnotationNamingMissingSymbol_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("<program> ::= <import>\n<import> ::= \"import\" <IDENTIFIER>\n// @import(<STRING>)");
+
+        // Act & Assert
+        var exception = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+        Assert.Contains("which alternative 1 of <import> does not contain", exception.Message);
+    }
+
+    private static AnalysisWorkspace CreateWorkspace(Action<ModuleResolverRegistry>? configure = null)
+    {
+        var root = FixtureDirectory();
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(File.ReadAllText(Path.Combine(root, "toy.grammar"))));
+        var workspace = new AnalysisWorkspace(new GeneralizedParser(grammar));
+        configure?.Invoke(workspace.Resolvers);
+
+        foreach (var file in Directory.EnumerateFiles(root, "*.toy", SearchOption.AllDirectories).Order(StringComparer.Ordinal))
+        {
+            workspace.SetDocument(Path.GetRelativePath(root, file).Replace(Path.DirectorySeparatorChar, '/'), File.ReadAllText(file));
+        }
+
+        return workspace;
+    }
+
+    private static string FixtureDirectory([CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "Fixtures", "modules");
+    }
+}
@@ -0,0 +1,231 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Collections.Concurrent;
+using System.Diagnostics;
+using System.Text;
+using Xunit;
+using Xunit.Abstractions;
+using Minotaur.Analysis.Passes;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for partitioned analysis pass functionality
+/// </summary>
+public class PartitionedPassTests
+{
+    private const string FunctionGrammar = """
+        DeclarationRules: function, definition
+
+        <program> ::= <function> | <program> <function>
+        <function> ::= "fn" <IDENTIFIER> "{" <statements> "}"
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= <definition> | "print" <IDENTIFIER> ";" | "call" <IDENTIFIER> ";"
+        <definition> ::= "let" <IDENTIFIER> "=" <NUMBER> ";"
+        """;
+
+    private readonly ITestOutputHelper _output;
+
+    public PartitionedPassTests(ITestOutputHelper output)
+    {
+        _output = output;
+    }
+
+    [Fact]
+    public void Run_LintInParallel_MatchesSequentialOutput()
+    {
+        // Arrange
+        var parse = Parse(GenerateFunctions(200));
+
+        // Act
+        var sequential = CreateManager(maxDegreeOfParallelism: 1).Run(parse);
+        var parallel = CreateManager(maxDegreeOfParallelism: 8).Run(parse);
+
+        // Assert
+        Assert.All(parallel.Executions, e => Assert.Equal(PassStatus.Succeeded, e.Status));
+        Assert.Equal(Describe(sequential), Describe(parallel));
+        Assert.Contains(parallel.Context.Diagnostics, d => d.Code == DiagnosticCodes.UnusedSymbol);
+        Assert.Contains(parallel.Context.Diagnostics, d => d.Code == DiagnosticCodes.UndeclaredSymbol);
+    }
+
+    [Fact]
+    public void Run_SymbolTablePass_ListsOccurrencesInSourceOrder()
+    {
+        // Arrange
+        var parse = Parse(GenerateFunctions(50));
+        var direct = new AnalysisContext(parse);
+
+        // Act
+        var run = CreateManager(maxDegreeOfParallelism: 8).Run(parse);
+        var expected = (SymbolTable)new SymbolTablePass().Run(direct)!;
+
+        // Assert
+        var table = run.Context.GetResult<SymbolTable>(SymbolTablePass.PassName);
+        var offsets = table.Occurrences.Select(o => o.Location!.Offset).ToList();
+        Assert.Equal(offsets.OrderBy(o => o), offsets);
+        Assert.Equal(expected.Occurrences.Select(o => (o.Name, o.Kind, o.Location!.Offset)), table.Occurrences.Select(o => (o.Name, o.Kind, o.Location!.Offset)));
+        Assert.Equal(direct.Annotations.Select(a => (a.Key, a.Value)), run.Context.Annotations.Select(a => (a.Key, a.Value)));
+    }
+
+    [Fact]
+    public void Run_DefaultPartitions_AreTheTopLevelItems()
+    {
+        // Arrange
+        var parse = Parse(GenerateFunctions(5));
+        var pass = new FunctionNamePass();
+        var manager = new PassManager(new PassManagerOptions { MaxDegreeOfParallelism = 4 });
+        manager.Register(pass);
+
+        // Act
+        var run = manager.Run(parse);
+
+        // Assert
+        Assert.Equal(5, Assert.Single(run.Executions).Partitions);
+        Assert.Equal(new[] { "f0", "f1", "f2", "f3", "f4" }, run.Context.GetResult<IReadOnlyList<string>>(FunctionNamePass.PassName));
+        Assert.Equal(new[] { "f0", "f1", "f2", "f3", "f4" }, run.Context.Annotations.Select(a => a.Value));
+        Assert.All(run.Context.Annotations, a => Assert.Equal(FunctionNamePass.PassName, a.Pass));
+        Assert.Equal(5, pass.Contexts.Distinct().Count());
+    }
+
+    [Fact]
+    public void Run_PassWithoutPartitioning_RunsSequentially()
+    {
+        // Arrange
+        var parse = Parse(GenerateFunctions(3));
+
+        // Act
+        var run = CreateManager(maxDegreeOfParallelism: 8).Run(parse);
+
+        // Assert
+        Assert.Equal(0, run.Executions.Single(e => e.Name == DirectivePass.PassName).Partitions);
+        Assert.Equal(3, run.Executions.Single(e => e.Name == SymbolTablePass.PassName).Partitions);
+        Assert.Equal(3, run.Executions.Single(e => e.Name == LintPass.PassName).Partitions);
+    }
+
+    [Fact]
+    public void Run_PartitionThrows_ReportsThePassFailure()
+    {
+        // Arrange
+        var parse = Parse(GenerateFunctions(4));
+        var manager = new PassManager(new PassManagerOptions { MaxDegreeOfParallelism = 4 });
+        manager.Register(new FunctionNamePass { FailOn = "f2" });
+
+        // Act
+        var run = manager.Run(parse);
+
+        // Assert
+        var execution = Assert.Single(run.Executions);
+        Assert.Equal(PassStatus.Failed, execution.Status);
+        Assert.Equal("cannot analyze f2", execution.Error);
+        Assert.Empty(run.Context.Annotations);
+        Assert.Contains(run.Context.Diagnostics, d => d.Code == DiagnosticCodes.AnalysisPassFailed);
+    }
+
+    [Fact]
+    public void Run_LintOverFiveThousandFunctions_Benchmark()
+    {
+        // Arrange
+        var parse = Parse(GenerateFunctions(5000));
+        CreateManager(maxDegreeOfParallelism: 1).Run(parse);
+
+        // Act
+        var sequentialWatch = Stopwatch.StartNew();
+        var sequential = CreateManager(maxDegreeOfParallelism: 1).Run(parse);
+        sequentialWatch.Stop();
+
+        var parallelWatch = Stopwatch.StartNew();
+        var parallel = CreateManager(Environment.ProcessorCount).Run(parse);
+        parallelWatch.Stop();
+
+        // Assert
+        var speedup = sequentialWatch.Elapsed.TotalMilliseconds / Math.Max(parallelWatch.Elapsed.TotalMilliseconds, 0.001);
+        _output.WriteLine($"sequential: {sequentialWatch.Elapsed.TotalMilliseconds:F0} ms, parallel on {Environment.ProcessorCount} threads: {parallelWatch.Elapsed.TotalMilliseconds:F0} ms, speedup: {speedup:F2}x");
+
+        Assert.Equal(5000, parallel.Executions.Single(e => e.Name == LintPass.PassName).Partitions);
+        Assert.Equal(Describe(sequential), Describe(parallel));
+    }
+
+    private static PassManager CreateManager(int maxDegreeOfParallelism)
+    {
+        var manager = new PassManager(new PassManagerOptions { MaxDegreeOfParallelism = maxDegreeOfParallelism, EnableCache = false });
+        manager.Register(new DirectivePass());
+        manager.Register(new SymbolTablePass());
+        manager.Register(new LintPass());
+        return manager;
+    }
+
+    private static ParseResult Parse(string input)
+    {
+        var parse = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(FunctionGrammar))).Parse(input);
+        Assert.True(parse.IsSuccess);
+        return parse;
+    }
+
+    private static string GenerateFunctions(int count)
+    {
+        // Every function calls the next, leaves one definition unused and prints an undeclared name.
+        var text = new StringBuilder();
+        for (var i = 0; i < count; i++)
+        {
+            text.Append($"fn f{i} {{\n    let a{i} = 1;\n    let unused{i} = 2;\n    print a{i};\n    print missing{i};\n    call f{(i + 1) % count};\n}}\n");
+        }
+
+        return text.ToString();
+    }
+
+    private static List<string> Describe(AnalysisRun run)
+    {
+        return run.Context.Diagnostics
+            .Select(d => $"{d.Data.GetValueOrDefault("pass")} {d.Code} {d.Location?.Offset} {d.Message}")
+            .Concat(run.Context.Annotations.Select(a => $"{a.Pass} {a.Key} {a.Location?.Offset} {a.Value}"))
+            .ToList();
+    }
+
+    private sealed class FunctionNamePass : PartitionedAnalysisPass
+    {
+        public const string PassName = "function-names";
+
+        public string? FailOn { get; init; }
+
+        public ConcurrentBag<AnalysisContext> Contexts { get; } = new();
+
+        public override string Name => PassName;
+
+        public override object? RunPartition(AnalysisContext context, CognitiveGraphNode partition, object? state)
+        {
+            Contexts.Add(context);
+            var name = partition.Children.OfType<TerminalNode>().First(t => t.TokenType == "IDENTIFIER").Text;
+            if (name == FailOn)
+            {
+                throw new InvalidOperationException($"cannot analyze {name}");
+            }
+
+            context.Annotate("function", name, partition.SourcePosition);
+            return name;
+        }
+
+        public override object? Merge(AnalysisContext context, IReadOnlyList<object?> results, object? state)
+        {
+            return results.Cast<string>().ToList();
+        }
+    }
+}
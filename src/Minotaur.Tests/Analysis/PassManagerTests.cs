@@ -0,0 +1,237 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Reflection;
+using System.Reflection.Emit;
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+[assembly: AnalysisPassPlugin(PassRegistry.AbiVersion)]
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for PassManager functionality
+/// </summary>
+public class PassManagerTests
+{
+    private const string AssignmentGrammar = """
+        DeclarationRules: statement
+
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <IDENTIFIER> "=" <expr> ";"
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER>
+        """;
+
+    [Fact]
+    public void GetExecutionOrder_RunsDependenciesFirst()
+    {
+        // Arrange
+        var manager = new PassManager();
+        manager.Register(new DelegatePass("report", new[] { "types" }));
+        manager.Register(new DelegatePass("types", new[] { "symbols" }));
+        manager.Register(new DelegatePass("symbols"));
+        manager.Register(new DelegatePass("metrics"));
+
+        // Act
+        var order = manager.GetExecutionOrder().Select(p => p.Name);
+
+        // Assert
+        Assert.Equal(new[] { "symbols", "types", "report", "metrics" }, order);
+    }
+
+    [Fact]
+    public void GetExecutionOrder_WithCycleOrUnknownDependency_Throws()
+    {
+        // Arrange
+        var cyclic = new PassManager();
+        cyclic.Register(new DelegatePass("a", new[] { "b" }));
+        cyclic.Register(new DelegatePass("b", new[] { "a" }));
+
+        var missing = new PassManager();
+        missing.Register(new DelegatePass("a", new[] { "nowhere" }));
+
+        // Act & Assert
+        Assert.Contains("cycle", Assert.Throws<InvalidOperationException>(() => cyclic.GetExecutionOrder()).Message);
+        Assert.Contains("nowhere", Assert.Throws<InvalidOperationException>(() => missing.GetExecutionOrder()).Message);
+        Assert.Throws<ArgumentException>(() => missing.Register(new DelegatePass("a")));
+    }
+
+    [Fact]
+    public void Run_BuiltInPasses_BuildSymbolTableAndLint()
+    {
+        // Arrange
+        var manager = new PassManager();
+        manager.RegisterBuiltInPasses();
+        var parse = Parse("a = 1;\nb = a + c;\n");
+
+        // Act
+        var run = manager.Run(parse, "sample.txt");
+
+        // Assert
+        Assert.True(run.Succeeded);
+        Assert.Equal(new[] { DirectivePass.PassName, SymbolTablePass.PassName, LintPass.PassName }, run.Executions.Select(e => e.Name));
+
+        var table = run.Context.GetResult<SymbolTable>(SymbolTablePass.PassName);
+        Assert.Single(table.Lookup("a")!.Declarations);
+        Assert.Single(table.Lookup("a")!.References);
+        Assert.Equal(2, table.Lookup("a")!.References[0].Location!.Line);
+
+        Assert.Contains(run.Diagnostics, d => d.Code == DiagnosticCodes.UnusedSymbol && d.Message.Contains("'b'"));
+        Assert.Contains(run.Diagnostics, d => d.Code == DiagnosticCodes.UndeclaredSymbol && d.Message.Contains("'c'"));
+        Assert.All(run.Diagnostics, d => Assert.Equal(LintPass.PassName, d.Data["pass"]));
+        Assert.Equal(4, run.Annotations.Count(a => a.Pass == SymbolTablePass.PassName));
+    }
+
+    [Fact]
+    public void Run_FailingPass_SkipsDependents()
+    {
+        // Arrange
+        var manager = new PassManager();
+        manager.Register(new DelegatePass("broken", run: _ => throw new InvalidOperationException("boom")));
+        manager.Register(new DelegatePass("dependent", new[] { "broken" }));
+        manager.Register(new DelegatePass("independent"));
+
+        // Act
+        var run = manager.Run(Parse("a = 1;"));
+
+        // Assert
+        Assert.False(run.Succeeded);
+        Assert.Equal(new[] { PassStatus.Failed, PassStatus.Skipped, PassStatus.Succeeded }, run.Executions.Select(e => e.Status));
+        var diagnostic = Assert.Single(run.Diagnostics);
+        Assert.Equal(DiagnosticCodes.AnalysisPassFailed, diagnostic.Code);
+        Assert.Contains("boom", diagnostic.Message);
+    }
+
+    [Fact]
+    public void Run_SameFileAndInput_ReturnsCachedRun()
+    {
+        // Arrange
+        var runs = 0;
+        var manager = new PassManager();
+        manager.Register(new DelegatePass("counter", run: _ => ++runs));
+
+        // Act
+        var first = manager.Run(Parse("a = 1;"), "a.txt");
+        var second = manager.Run(Parse("a = 1;"), "a.txt");
+        var otherFile = manager.Run(Parse("a = 1;"), "b.txt");
+        var changed = manager.Run(Parse("a = 2;"), "a.txt");
+        manager.Invalidate("a.txt");
+        var invalidated = manager.Run(Parse("a = 2;"), "a.txt");
+
+        // Assert
+        Assert.False(first.FromCache);
+        Assert.True(second.FromCache);
+        Assert.Same(first.Context, second.Context);
+        Assert.False(otherFile.FromCache);
+        Assert.False(changed.FromCache);
+        Assert.False(invalidated.FromCache);
+        Assert.Equal(4, runs);
+    }
+
+    [Fact]
+    public void LoadPlugin_DynamicLoadingDisabled_IsRefused()
+    {
+        // Arrange
+        var manager = new PassManager();
+
+        // Act
+        var result = manager.LoadPlugin(typeof(PassManagerTests).Assembly.Location);
+
+        // Assert
+        Assert.False(result.Success);
+        Assert.Contains("disabled", result.Error);
+        Assert.Empty(manager.Passes);
+    }
+
+    [Fact]
+    public void LoadPlugin_MatchingAbiVersion_DiscoversPasses()
+    {
+        // Act
+        var result = PassRegistry.LoadPlugin(typeof(PassManagerTests).Assembly);
+
+        // Assert
+        Assert.True(result.Success);
+        Assert.Equal(PassRegistry.AbiVersion, result.AbiVersion);
+        Assert.Contains(result.Passes, p => p.Name == TokenCountPass.PassName);
+    }
+
+    [Fact]
+    public void LoadPlugin_MismatchedOrMissingAbiVersion_IsRefused()
+    {
+        // Arrange
+        var future = AssemblyBuilder.DefineDynamicAssembly(new AssemblyName("FuturePassPlugin"), AssemblyBuilderAccess.Run);
+        var constructor = typeof(AnalysisPassPluginAttribute).GetConstructor(new[] { typeof(int) })!;
+        future.SetCustomAttribute(new CustomAttributeBuilder(constructor, new object[] { PassRegistry.AbiVersion + 1 }));
+
+        // Act
+        var mismatched = PassRegistry.LoadPlugin(future);
+        var missing = PassRegistry.LoadPlugin(typeof(string).Assembly);
+
+        // Assert
+        Assert.False(mismatched.Success);
+        Assert.Equal(PassRegistry.AbiVersion + 1, mismatched.AbiVersion);
+        Assert.Contains("ABI version", mismatched.Error);
+        Assert.Empty(mismatched.Passes);
+
+        Assert.False(missing.Success);
+        Assert.Null(missing.AbiVersion);
+    }
+
+    private static ParseResult Parse(string input)
+    {
+        var grammar = new GrammarFileReader().Read(AssignmentGrammar);
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar)).Parse(input);
+    }
+
+    private sealed class DelegatePass : IAnalysisPass
+    {
+        private readonly Func<AnalysisContext, object?> _run;
+
+        public DelegatePass(string name, IReadOnlyList<string>? dependencies = null, Func<AnalysisContext, object?>? run = null)
+        {
+            Name = name;
+            Dependencies = dependencies ?? Array.Empty<string>();
+            _run = run ?? (_ => null);
+        }
+
+        public string Name { get; }
+
+        public IReadOnlyList<string> Dependencies { get; }
+
+        public object? Run(AnalysisContext context) => _run(context);
+    }
+}
+
+/// <summary>
+/// A pass discovered from this test assembly acting as a plugin.
+/// </summary>
+[AnalysisPass]
+public class TokenCountPass : IAnalysisPass
+{
+    public const string PassName = "test.token-count";
+
+    public string Name => PassName;
+
+    public IReadOnlyList<string> Dependencies => Array.Empty<string>();
+
+    public object? Run(AnalysisContext context) => context.Parse.Tokens.Count;
+}
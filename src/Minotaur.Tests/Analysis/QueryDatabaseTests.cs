@@ -0,0 +1,132 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Analysis.Passes;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for QueryDatabase functionality
+/// </summary>
+public class QueryDatabaseTests
+{
+    [Fact]
+    public void Get_UnrelatedInputChanged_ReusesMemoizedValue()
+    {
+        // Arrange
+        var database = new QueryDatabase();
+        var text = Query<string, string>.Input("text");
+        var length = Query<string, int>.Derived("length", (db, path) => db.Get(text, path).Length);
+        database.Set(text, "a", "abc");
+        database.Set(text, "b", "de");
+        Assert.Equal(3, database.Get(length, "a"));
+
+        // Act
+        database.Set(text, "b", "fgh");
+        var reused = database.Get(length, "a");
+        database.Set(text, "a", "abcd");
+        var recomputed = database.Get(length, "a");
+
+        // Assert
+        Assert.Equal(3, reused);
+        Assert.Equal(4, recomputed);
+        Assert.Equal(2, database.Statistics["length"].Executions);
+        Assert.Equal(1, database.Statistics["length"].Hits);
+    }
+
+    [Fact]
+    public void Get_RecomputedValueUnchanged_CutsOffDependents()
+    {
+        // Arrange
+        var database = new QueryDatabase();
+        var text = Query<string, string>.Input("text");
+        var words = Query<string, IReadOnlyList<string>>.Derived(
+            "words",
+            (db, path) => db.Get(text, path).Split(' ', StringSplitOptions.RemoveEmptyEntries),
+            (a, b) => a.SequenceEqual(b));
+        var count = Query<string, int>.Derived("count", (db, path) => db.Get(words, path).Count);
+        database.Set(text, "a", "one two");
+        Assert.Equal(2, database.Get(count, "a"));
+
+        // Act
+        database.Set(text, "a", "one  two ");
+        var value = database.Get(count, "a");
+
+        // Assert
+        Assert.Equal(2, value);
+        Assert.Equal(2, database.Statistics["words"].Executions);
+        Assert.Equal(1, database.Statistics["words"].Cutoffs);
+        Assert.Equal(1, database.Statistics["count"].Executions);
+    }
+
+    [Fact]
+    public void Set_EqualValue_StartsNoRevision()
+    {
+        // Arrange
+        var database = new QueryDatabase();
+        var text = Query<string, string>.Input("text");
+        database.Set(text, "a", "abc");
+
+        // Act
+        var changed = database.Set(text, "a", "abc");
+
+        // Assert
+        Assert.False(changed);
+        Assert.Equal(1, database.Revision);
+    }
+
+    [Fact]
+    public void Get_QueryReadingItself_ThrowsWithTheCycle()
+    {
+        // Arrange
+        var database = new QueryDatabase();
+        Query<int, int>? even = null;
+        var odd = Query<int, int>.Derived("odd", (db, n) => db.Get(even!, n));
+        even = Query<int, int>.Derived("even", (db, n) => db.Get(odd, n));
+
+        // Act & Assert
+        var ex = Assert.Throws<InvalidOperationException>(() => database.Get(even, 1));
+        Assert.Equal("Query cycle: even(1) -> odd(1) -> even(1)", ex.Message);
+    }
+
+    [Fact]
+    public void Evict_ValueAnotherDependsOn_IsKept()
+    {
+        // Arrange
+        var database = new QueryDatabase();
+        var text = Query<string, string>.Input("text");
+        var length = Query<string, int>.Derived("length", (db, path) => db.Get(text, path).Length);
+        var first = Query<string, int>.Derived("first", (db, _) => db.Get(length, "a"));
+        database.Set(text, "a", "abc");
+        database.Set(text, "b", "de");
+        database.Get(length, "b");
+        database.Get(first, string.Empty);
+        var count = database.Count;
+
+        // Act
+        var evicted = database.Evict((query, _) => query == "length");
+        var recomputed = database.Get(length, "b");
+
+        // Assert
+        Assert.Equal(1, evicted);
+        Assert.Equal(count - 1, database.Count);
+        Assert.Equal(2, recomputed);
+        Assert.Equal(3, database.Statistics["length"].Executions);
+        Assert.Equal(3, database.Get(first, string.Empty));
+    }
+}
@@ -0,0 +1,189 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.Analysis.Refactoring;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis.Refactoring;
+
+/// <summary>
+/// Tests for SymbolRename and WorkspaceEdit functionality over a three-file fixture on disk
+/// </summary>
+public sealed class SymbolRenameTests : IDisposable
+{
+    private const string ModuleGrammar = """
+        DeclarationRules: definition
+        ExportRules: export
+        ImportQuery: import > IDENTIFIER
+
+        <program> ::= <item> | <program> <item>
+        <item> ::= <import> | <export> | <definition> | <statement>
+        <import> ::= "use" <IDENTIFIER> ";"
+        <export> ::= "pub" <definition>
+        <definition> ::= "let" <IDENTIFIER> "=" <expr> ";"
+        <statement> ::= "print" <expr> ";"
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER>
+        """;
+
+    private const string FileA = "pub let base = 1;\nlet hidden = 2;\nprint hidden;\n";
+    private const string FileB = "use a;\npub let twice = base + base;\n";
+    private const string FileC = "use b;\nprint twice + 1;\n";
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"rename_{Guid.NewGuid():N}");
+    private readonly GeneralizedParser _parser = new(CompiledGrammar.Compile(new GrammarFileReader().Read(ModuleGrammar)));
+
+    public SymbolRenameTests()
+    {
+        Directory.CreateDirectory(_directory);
+        File.WriteAllText(PathOf("a"), FileA);
+        File.WriteAllText(PathOf("b"), FileB);
+        File.WriteAllText(PathOf("c"), FileC);
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_directory, recursive: true);
+    }
+
+    [Fact]
+    public void Create_ReferenceToImportedSymbol_RenamesDeclarationAndEveryImportingReference()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+
+        // Act
+        var edit = SymbolRename.Create(workspace, PathOf("b"), FileB.IndexOf("base", StringComparison.Ordinal), "root");
+        var preview = edit.Apply(new WorkspaceEditOptions { DryRun = true });
+        var result = edit.Apply(new WorkspaceEditOptions { Verifier = _parser });
+
+        // Assert
+        Assert.Equal(new[] { PathOf("a"), PathOf("b") }, edit.Files.Select(f => f.Path));
+        Assert.True(preview.IsDryRun);
+        Assert.False(preview.Applied);
+        Assert.Contains("-pub let base = 1;\n+pub let root = 1;\n", preview.Diffs[PathOf("a")]);
+        Assert.Contains("-pub let twice = base + base;\n+pub let twice = root + root;\n", preview.Diffs[PathOf("b")]);
+
+        Assert.True(result.Applied, result.Error);
+        Assert.Equal(FileA.Replace("base", "root"), File.ReadAllText(PathOf("a")));
+        Assert.Equal(FileB.Replace("base", "root"), File.ReadAllText(PathOf("b")));
+        Assert.Equal(FileC, File.ReadAllText(PathOf("c")));
+        Assert.Equal(new[] { "a.mod", "b.mod", "c.mod" }, Directory.GetFiles(_directory).Select(Path.GetFileName).OrderBy(n => n, StringComparer.Ordinal));
+
+        var reanalyzed = CreateWorkspace();
+        Assert.All(reanalyzed.Documents, d => Assert.Empty(d.Diagnostics));
+    }
+
+    [Fact]
+    public void Create_SymbolUsedAcrossFiles_RenamesItFromItsDeclaration()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+
+        // Act
+        var edit = SymbolRename.Create(workspace, PathOf("b"), FileB.IndexOf("twice", StringComparison.Ordinal) + 2, "double");
+
+        // Assert
+        var b = edit.Files.Single(f => f.Path == PathOf("b"));
+        var c = edit.Files.Single(f => f.Path == PathOf("c"));
+        Assert.Equal("use a;\npub let double = base + base;\n", b.ApplyTo(FileB));
+        Assert.Equal("use b;\nprint double + 1;\n", c.ApplyTo(FileC));
+        Assert.DoesNotContain(edit.Files, f => f.Path == PathOf("a"));
+    }
+
+    [Fact]
+    public void Create_NewNameAlreadyUsed_Throws()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+
+        // Act & Assert
+        Assert.Throws<InvalidOperationException>(() => SymbolRename.Create(workspace, PathOf("a"), 8, "twice"));
+        Assert.Throws<ArgumentException>(() => SymbolRename.Create(workspace, PathOf("a"), 8, "print"));
+    }
+
+    [Fact]
+    public void Apply_FileChangedSinceAnalysis_ReportsTheConflictAndWritesNothing()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+        var edit = SymbolRename.Create(workspace, PathOf("a"), 8, "root");
+        File.WriteAllText(PathOf("b"), FileB + "print twice;\n");
+
+        // Act
+        var result = edit.Apply();
+
+        // Assert
+        Assert.False(result.Applied);
+        var conflict = Assert.Single(result.Conflicts);
+        Assert.Equal(PathOf("b"), conflict.Path);
+        Assert.Equal(WorkspaceEditConflictKind.Changed, conflict.Kind);
+        Assert.Equal(conflict, Assert.Single(edit.Validate()));
+        Assert.Equal(FileA, File.ReadAllText(PathOf("a")));
+    }
+
+    [Fact]
+    public void Apply_EditIntroducingSyntaxError_RollsBackEveryFile()
+    {
+        // Arrange
+        var edit = new WorkspaceEdit();
+        edit.Add(PathOf("a"), FileA, new TextEdit(0, 3, "export"));
+        edit.Add(PathOf("c"), FileC, new TextEdit(6, 0, "\n"));
+
+        // Act
+        var result = edit.Apply(new WorkspaceEditOptions { Verifier = _parser });
+
+        // Assert
+        Assert.False(result.Applied);
+        Assert.NotNull(result.Error);
+        Assert.Equal(new[] { PathOf("a") }, result.NewErrors.Keys);
+        Assert.Equal(FileA, File.ReadAllText(PathOf("a")));
+        Assert.Equal(FileC, File.ReadAllText(PathOf("c")));
+        Assert.Equal(3, Directory.GetFiles(_directory).Length);
+    }
+
+    [Fact]
+    public void Add_OverlappingEdits_Throws()
+    {
+        // Arrange
+        var edit = new WorkspaceEdit();
+        edit.Add(PathOf("a"), FileA, new TextEdit(4, 3, "var"));
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => edit.Add(PathOf("a"), FileA, new TextEdit(6, 2, "x")));
+        Assert.Throws<ArgumentException>(() => edit.Add(PathOf("a"), FileA + " ", new TextEdit(0, 1, "x")));
+    }
+
+    private AnalysisWorkspace CreateWorkspace()
+    {
+        var workspace = new AnalysisWorkspace(_parser);
+        foreach (var name in new[] { "c", "b", "a" })
+        {
+            workspace.SetDocument(PathOf(name), File.ReadAllText(PathOf(name)));
+        }
+
+        return workspace;
+    }
+
+    private string PathOf(string module)
+    {
+        return Path.Combine(_directory, module + ".mod");
+    }
+}
@@ -0,0 +1,219 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+using System.Text.Json;
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for SymbolIndexExporter functionality
+/// </summary>
+public sealed class SymbolIndexExporterTests : IDisposable
+{
+    private const string ModuleGrammar = """
+        Grammar: Toy
+        DeclarationRules: module, definition
+        ExportRules: export
+        ImportQuery: import > IDENTIFIER
+
+        <program> ::= <item> | <program> <item>
+        <item> ::= <import> | <export> | <module> | <definition> | <statement>
+        <import> ::= "use" <IDENTIFIER> ";"
+        <module> ::= "mod" <IDENTIFIER> "{" <program> "}"
+        <export> ::= "pub" <definition>
+        <definition> ::= "let" <IDENTIFIER> "=" <expr> ";"
+        <statement> ::= "print" <expr> ";"
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER>
+        <COMMENT> ::= /#[^\n]*/ => { skip }
+        """;
+
+    private const string FileA = "# The base value.\n# Shared by every file.\npub let base = 1;\nmod shapes {\n  let area = base;\n  let area = 2;\n}\n";
+    private const string FileB = "use a;\npub let twice = base + base;\n";
+    private const string FileC = "let unrelated = 3;\nprint unrelated;\n";
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"index_{Guid.NewGuid():N}");
+
+    public void Dispose()
+    {
+        if (Directory.Exists(_directory))
+        {
+            Directory.Delete(_directory, recursive: true);
+        }
+    }
+
+    [Fact]
+    public void Index_NestedDeclarations_NamesMonikersByPathAndContainers()
+    {
+        // Arrange
+        var exporter = new SymbolIndexExporter(CreateWorkspace());
+
+        // Act
+        var index = exporter.Index("a.mod")!;
+
+        // Assert
+        Assert.Equal(
+            new[] { "minotaur Toy a.mod . base.", "minotaur Toy a.mod . shapes.", "minotaur Toy a.mod . shapes/area.", "minotaur Toy a.mod . shapes/area(1)." },
+            index.Symbols.Select(s => s.Moniker));
+        Assert.Equal("The base value.\nShared by every file.", index.Symbols[0].Documentation);
+        Assert.True(index.Symbols[0].IsExported);
+        Assert.Null(index.Symbols[1].Documentation);
+        Assert.Equal("module", index.Symbols[1].Kind);
+
+        // The reference to base inside shapes names the declaration at the top of the file.
+        var reference = Assert.Single(index.Occurrences, o => !o.IsDefinition);
+        Assert.Equal("minotaur Toy a.mod . base.", reference.Moniker);
+        Assert.Equal(5, reference.Range!.Line);
+    }
+
+    [Fact]
+    public void Index_CrossFileReferences_UseDeclaringFileMonikers()
+    {
+        // Arrange
+        var exporter = new SymbolIndexExporter(CreateWorkspace());
+
+        // Act
+        var index = exporter.Index("b.mod")!;
+
+        // Assert
+        Assert.Equal(new[] { "a.mod" }, index.Dependencies);
+        Assert.Equal(
+            new[] { "minotaur Toy b.mod . twice.", "minotaur Toy a.mod . base.", "minotaur Toy a.mod . base." },
+            index.Occurrences.Select(o => o.Moniker));
+
+        using var json = JsonDocument.Parse(index.ToJson());
+        var roles = json.RootElement.GetProperty("occurrences").EnumerateArray().Select(o => o.GetProperty("role").GetString());
+        Assert.Equal(new[] { "definition", "reference", "reference" }, roles);
+    }
+
+    [Fact]
+    public void Index_UnrelatedFileRenamed_KeepsMonikersElsewhere()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+        var exporter = new SymbolIndexExporter(workspace);
+        var before = new[] { "a.mod", "b.mod" }.Select(p => exporter.Index(p)!).SelectMany(Monikers).ToList();
+
+        // Act
+        workspace.RemoveDocument("c.mod");
+        workspace.SetDocument("0_renamed.mod", FileC);
+        var after = new[] { "a.mod", "b.mod" }.Select(p => exporter.Index(p)!).SelectMany(Monikers).ToList();
+
+        // Assert
+        Assert.Equal(before, after);
+        Assert.Equal("minotaur Toy 0_renamed.mod . unrelated.", exporter.Index("0_renamed.mod")!.Symbols[0].Moniker);
+    }
+
+    [Fact]
+    public void Export_Incremental_WritesOnlyFilesThatChanged()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+        var exporter = new SymbolIndexExporter(workspace);
+        var first = exporter.Export(_directory);
+
+        // Act
+        workspace.SetDocument("a.mod", FileA.Replace("let area = 2", "let area = 3"));
+        var second = exporter.Export(_directory);
+
+        // Assert
+        Assert.Equal(new[] { "a.mod", "b.mod", "c.mod" }, first.Emitted);
+        Assert.Equal("No manifest was found", first.FallbackReason);
+        Assert.True(File.Exists(Path.Combine(_directory, SymbolIndexExporter.ManifestFileName)));
+
+        // The exports of a.mod are unchanged, so b.mod, which depends on it, is kept.
+        Assert.Equal(new[] { "a.mod" }, second.Emitted);
+        Assert.Equal(new[] { "b.mod", "c.mod" }, second.Skipped);
+        Assert.Null(second.FallbackReason);
+    }
+
+    [Fact]
+    public void Export_DependencyExportsChanged_WritesDependents()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+        var exporter = new SymbolIndexExporter(workspace);
+        exporter.Export(_directory);
+
+        // Act
+        workspace.SetDocument("a.mod", "pub let extra = 0;\n" + FileA);
+        var result = exporter.Export(_directory);
+
+        // Assert
+        Assert.Equal(new[] { "a.mod", "b.mod" }, result.Emitted);
+        Assert.Equal(new[] { "c.mod" }, result.Skipped);
+    }
+
+    [Fact]
+    public void Export_FileRenamed_WritesNewIndexAndDeletesOld()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+        var exporter = new SymbolIndexExporter(workspace);
+        exporter.Export(_directory);
+
+        // Act
+        workspace.RemoveDocument("c.mod");
+        workspace.SetDocument("lib/d.mod", FileC);
+        var result = exporter.Export(_directory);
+
+        // Assert
+        Assert.Equal(new[] { "lib/d.mod" }, result.Emitted);
+        Assert.Equal(new[] { "a.mod", "b.mod" }, result.Skipped);
+        Assert.Equal(new[] { "c.mod" }, result.Removed);
+        Assert.False(File.Exists(SymbolIndexExporter.GetIndexFile(_directory, "c.mod")));
+        Assert.True(File.Exists(SymbolIndexExporter.GetIndexFile(_directory, "lib/d.mod")));
+    }
+
+    [Fact]
+    public void Export_ManifestOfAnotherGrammar_WritesEveryFile()
+    {
+        // Arrange
+        new SymbolIndexExporter(CreateWorkspace()).Export(_directory);
+        var grammar = new GrammarFileReader().Read(ModuleGrammar.Replace("\"print\"", "\"show\""));
+        var workspace = new AnalysisWorkspace(new GeneralizedParser(CompiledGrammar.Compile(grammar)));
+        workspace.SetDocument("a.mod", FileA);
+
+        // Act
+        var result = new SymbolIndexExporter(workspace).Export(_directory);
+
+        // Assert
+        Assert.Equal(new[] { "a.mod" }, result.Emitted);
+        Assert.Equal(new[] { "b.mod", "c.mod" }, result.Removed);
+        Assert.Contains("another grammar", result.FallbackReason);
+    }
+
+    private static IEnumerable<string> Monikers(IndexedDocument index)
+    {
+        return index.Symbols.Select(s => s.Moniker).Concat(index.Occurrences.Select(o => $"{o.Moniker}@{o.Range!.Offset}"));
+    }
+
+    private static AnalysisWorkspace CreateWorkspace()
+    {
+        var grammar = new GrammarFileReader().Read(ModuleGrammar);
+        var workspace = new AnalysisWorkspace(new GeneralizedParser(CompiledGrammar.Compile(grammar)));
+        workspace.SetDocument("a.mod", FileA);
+        workspace.SetDocument("b.mod", FileB);
+        workspace.SetDocument("c.mod", FileC);
+        return workspace;
+    }
+}
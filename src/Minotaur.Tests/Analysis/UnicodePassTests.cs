@@ -0,0 +1,132 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Analysis.Passes;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for UnicodePass functionality
+/// </summary>
+public class UnicodePassTests
+{
+    private const string AssignmentGrammar = """
+        DeclarationRules: statement
+
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <IDENTIFIER> "=" <expr> ";"
+        <expr> ::= <NUMBER> | <IDENTIFIER>
+        <IDENTIFIER> ::= /[\p{L}_][\p{L}\p{M}\p{N}_]*/
+        <COMMENT> ::= /#[^\n]*/ => { skip }
+        <WS> ::= /\s+/ => { skip }
+        """;
+
+    [Fact]
+    public void Run_CyrillicLookAlike_ReportsConfusableIdentifier()
+    {
+        // Arrange
+        var manager = CreateManager();
+
+        // Act
+        var run = manager.Run(Parse("a = 1;\n\u0430 = a;\n"), "sample.txt");
+
+        // Assert
+        var diagnostic = Assert.Single(run.Diagnostics, d => d.Code == DiagnosticCodes.ConfusableIdentifier);
+        Assert.Equal(DiagnosticSeverity.Warning, diagnostic.Severity);
+        Assert.Equal(2, diagnostic.Location!.Line);
+        Assert.Contains("U+0430", diagnostic.Message);
+        Assert.Equal(1, ((SourcePosition)diagnostic.Data["related"]).Line);
+    }
+
+    [Fact]
+    public void Run_DifferentlyNormalizedSpellings_ReportsAndMergesByPolicy()
+    {
+        // Arrange
+        const string input = "\u00E9 = 1;\nb = e\u0301;\n";
+
+        // Act
+        var unnormalized = CreateManager().Run(Parse(input), "sample.txt");
+        var normalized = CreateManager().Run(Parse(input, "IdentifierNormalization: NFC\n"), "sample.txt");
+
+        // Assert
+        var warning = Assert.Single(unnormalized.Diagnostics, d => d.Code == DiagnosticCodes.UnnormalizedIdentifier);
+        Assert.Contains("different symbols", warning.Message);
+        Assert.Empty(unnormalized.Context.GetResult<SymbolTable>(SymbolTablePass.PassName).Lookup("\u00E9")!.References);
+
+        warning = Assert.Single(normalized.Diagnostics, d => d.Code == DiagnosticCodes.UnnormalizedIdentifier);
+        Assert.Contains("same symbol", warning.Message);
+        Assert.Equal(2, warning.Location!.Line);
+        Assert.Single(normalized.Context.GetResult<SymbolTable>(SymbolTablePass.PassName).Lookup("\u00E9")!.References);
+        Assert.DoesNotContain(normalized.Diagnostics, d => d.Code == DiagnosticCodes.ConfusableIdentifier);
+    }
+
+    [Fact]
+    public void Run_BidiControlInComment_ReportsError()
+    {
+        // Arrange
+        var manager = CreateManager();
+
+        // Act
+        var run = manager.Run(Parse("a = 1; # \u202E;1 = b\n"), "sample.txt");
+
+        // Assert
+        var diagnostic = Assert.Single(run.Diagnostics, d => d.Code == DiagnosticCodes.BidiControlCharacter);
+        Assert.Equal(DiagnosticSeverity.Error, diagnostic.Severity);
+        Assert.Equal(10, diagnostic.Location!.Column);
+        Assert.Equal(0x202E, (int)diagnostic.Data["codePoint"]);
+    }
+
+    [Fact]
+    public void GetNormalizationForm_UnknownPolicy_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("IdentifierNormalization: NFX\n" + AssignmentGrammar);
+
+        // Act & Assert
+        Assert.Contains("NFX", Assert.Throws<InvalidOperationException>(() => UnicodePass.GetNormalizationForm(grammar)).Message);
+        Assert.Null(UnicodePass.GetNormalizationForm(new GrammarFileReader().Read(AssignmentGrammar)));
+    }
+
+    [Fact]
+    public void CreateBuiltInPasses_DoesNotIncludeUnicodePass()
+    {
+        // Act
+        var passes = PassRegistry.CreateBuiltInPasses();
+
+        // Assert
+        Assert.DoesNotContain(passes, p => p.Name == UnicodePass.PassName);
+    }
+
+    private static PassManager CreateManager()
+    {
+        var manager = new PassManager();
+        manager.Register(new SymbolTablePass());
+        manager.Register(new UnicodePass());
+        return manager;
+    }
+
+    private static ParseResult Parse(string input, string headers = "")
+    {
+        var grammar = new GrammarFileReader().Read(headers + AssignmentGrammar);
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar)).Parse(input);
+    }
+}
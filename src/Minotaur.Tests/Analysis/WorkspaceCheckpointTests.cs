@@ -0,0 +1,214 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using Xunit;
+using Xunit.Abstractions;
+using Minotaur.Analysis.Passes;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for workspace checkpoint functionality
+/// </summary>
+public sealed class WorkspaceCheckpointTests : IDisposable
+{
+    private const string ModuleGrammar = """
+        DeclarationRules: definition
+        ExportRules: export
+        ImportQuery: import > IDENTIFIER
+
+        <program> ::= <item> | <program> <item>
+        <item> ::= <import> | <export> | <definition> | <statement>
+        <import> ::= "use" <IDENTIFIER> ";"
+        <export> ::= "pub" <definition>
+        <definition> ::= "let" <IDENTIFIER> "=" <expr> ";"
+        <statement> ::= "print" <expr> ";"
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER>
+        """;
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"checkpoint_{Guid.NewGuid():N}");
+    private readonly ITestOutputHelper _output;
+
+    public WorkspaceCheckpointTests(ITestOutputHelper output)
+    {
+        _output = output;
+        Directory.CreateDirectory(_directory);
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_directory, recursive: true);
+    }
+
+    [Fact]
+    public void RestoreCheckpoint_UnchangedWorkspace_ReusesEveryDocument()
+    {
+        // Arrange
+        var files = GenerateFiles(2000);
+        var checkpoint = Path.Combine(_directory, "workspace.checkpoint");
+
+        var coldWatch = Stopwatch.StartNew();
+        var cold = CreateWorkspace();
+        foreach (var (path, text) in files)
+        {
+            cold.SetDocument(path, text);
+        }
+
+        coldWatch.Stop();
+        cold.SaveCheckpoint(checkpoint);
+
+        // Act
+        var warm = CreateWorkspace();
+        var result = warm.RestoreCheckpoint(checkpoint, files);
+
+        // Assert
+        _output.WriteLine($"cold start: {coldWatch.Elapsed.TotalMilliseconds:F0} ms, warm start: {result.Elapsed.TotalMilliseconds:F0} ms, checkpoint: {new FileInfo(checkpoint).Length / 1024} KiB");
+
+        Assert.True(result.IsWarm, result.FallbackReason);
+        Assert.Equal(2000, result.Reused.Count);
+        Assert.Empty(result.Reanalyzed);
+        Assert.All(warm.Documents, d => Assert.True(d.Run.FromCache, d.Path));
+        AssertSameState(cold, warm);
+    }
+
+    [Fact]
+    public void RestoreCheckpoint_ChangedFile_ReanalyzesOnlyThatFile()
+    {
+        // Arrange
+        var files = GenerateFiles(3);
+        var checkpoint = Path.Combine(_directory, "workspace.checkpoint");
+        var original = CreateWorkspace();
+        foreach (var (path, text) in files)
+        {
+            original.SetDocument(path, text);
+        }
+
+        original.SaveCheckpoint(checkpoint);
+        files["m1.mod"] = "use m0;\npub let w1 = v0;\n";
+        files["m3.mod"] = "use m2;\nprint v2;\n";
+
+        // Act
+        var warm = CreateWorkspace();
+        var result = warm.RestoreCheckpoint(checkpoint, files);
+
+        // Assert
+        Assert.True(result.IsWarm);
+        Assert.Equal(new[] { "m0.mod", "m2.mod" }, result.Reused);
+        Assert.Equal(new[] { "m1.mod", "m3.mod" }, result.Reanalyzed);
+        Assert.Contains(warm.GetDiagnostics("m2.mod"), d => d.Code == DiagnosticCodes.UnresolvedReference && d.Message.Contains("'v1'"));
+        Assert.Equal("m1.mod", Assert.Single(warm.FindExports("w1")).Path);
+
+        var cold = CreateWorkspace();
+        foreach (var (path, text) in files)
+        {
+            cold.SetDocument(path, text);
+        }
+
+        AssertSameState(cold, warm);
+    }
+
+    [Fact]
+    public void RestoreCheckpoint_UnusableCheckpoint_FallsBackToColdStart()
+    {
+        // Arrange
+        var files = GenerateFiles(3);
+        var checkpoint = Path.Combine(_directory, "workspace.checkpoint");
+        var original = CreateWorkspace();
+        foreach (var (path, text) in files)
+        {
+            original.SetDocument(path, text);
+        }
+
+        original.SaveCheckpoint(checkpoint);
+        var bytes = File.ReadAllBytes(checkpoint);
+
+        var corrupt = (byte[])bytes.Clone();
+        corrupt[^40] ^= 0xFF;
+        File.WriteAllBytes(Path.Combine(_directory, "corrupt.checkpoint"), corrupt);
+
+        // The magic string takes a length byte and 19 characters; the format version follows.
+        var otherVersion = (byte[])bytes.Clone();
+        otherVersion[20]++;
+        File.WriteAllBytes(Path.Combine(_directory, "version.checkpoint"), otherVersion);
+
+        var otherGrammar = new AnalysisWorkspace(new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read("Variant: other\n" + ModuleGrammar))));
+
+        // Act
+        var corruptResult = CreateWorkspace().RestoreCheckpoint(Path.Combine(_directory, "corrupt.checkpoint"), files);
+        var versionResult = CreateWorkspace().RestoreCheckpoint(Path.Combine(_directory, "version.checkpoint"), files);
+        var missingResult = CreateWorkspace().RestoreCheckpoint(Path.Combine(_directory, "missing.checkpoint"), files);
+        var grammarResult = otherGrammar.RestoreCheckpoint(checkpoint, files);
+
+        // Assert
+        Assert.Contains("corrupt", corruptResult.FallbackReason);
+        Assert.Contains("version", versionResult.FallbackReason);
+        Assert.NotNull(missingResult.FallbackReason);
+        Assert.Contains("grammar", grammarResult.FallbackReason);
+        Assert.All(new[] { corruptResult, versionResult, missingResult, grammarResult }, r =>
+        {
+            Assert.False(r.IsWarm);
+            Assert.Empty(r.Reused);
+            Assert.Equal(3, r.Reanalyzed.Count);
+        });
+        Assert.Empty(otherGrammar.GetDiagnostics("m2.mod"));
+    }
+
+    private static Dictionary<string, string> GenerateFiles(int count)
+    {
+        var files = new Dictionary<string, string>(StringComparer.Ordinal) { ["m0.mod"] = "pub let v0 = 1;\n" };
+        for (var i = 1; i < count; i++)
+        {
+            files[$"m{i}.mod"] = $"use m{i - 1};\npub let v{i} = v{i - 1} + {i};\nprint v{i};\n";
+        }
+
+        return files;
+    }
+
+    private static AnalysisWorkspace CreateWorkspace()
+    {
+        var grammar = new GrammarFileReader().Read(ModuleGrammar);
+        return new AnalysisWorkspace(new GeneralizedParser(CompiledGrammar.Compile(grammar)));
+    }
+
+    private static void AssertSameState(AnalysisWorkspace expected, AnalysisWorkspace actual)
+    {
+        Assert.Equal(expected.Documents.Select(d => d.Path), actual.Documents.Select(d => d.Path));
+        foreach (var document in expected.Documents)
+        {
+            var restored = actual.GetDocument(document.Path)!;
+            Assert.Equal(Describe(document.Diagnostics), Describe(restored.Diagnostics));
+            Assert.Equal(
+                document.Resolved.Select(r => (r.Reference.Name, r.Reference.Location!.Line, r.DeclaringPath)),
+                restored.Resolved.Select(r => (r.Reference.Name, r.Reference.Location!.Line, r.DeclaringPath)));
+            Assert.Equal(
+                document.Symbols.Occurrences.Select(o => (o.Name, o.Kind, o.Location!.Offset, o.IsExported)),
+                restored.Symbols.Occurrences.Select(o => (o.Name, o.Kind, o.Location!.Offset, o.IsExported)));
+            Assert.Equal(SExpression.Format(document.Parse.Tree!), SExpression.Format(restored.Parse.Tree!));
+        }
+    }
+
+    private static IEnumerable<string> Describe(IEnumerable<Diagnostic> diagnostics)
+    {
+        return diagnostics.Select(d => $"{d.Code} {d.Location?.Line}:{d.Location?.Column} {d.Message}");
+    }
+}
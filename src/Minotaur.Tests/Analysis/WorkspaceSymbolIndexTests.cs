@@ -0,0 +1,257 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using System.Globalization;
+using Xunit;
+using Xunit.Abstractions;
+using Minotaur.Analysis.Passes;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Analysis;
+
+/// <summary>
+/// Tests for WorkspaceSymbolIndex functionality
+/// </summary>
+public sealed class WorkspaceSymbolIndexTests : IDisposable
+{
+    private const string ModuleGrammar = """
+        DeclarationRules: module, definition
+        ExportRules: export
+
+        <program> ::= <item> | <program> <item>
+        <item> ::= <module> | <export> | <definition>
+        <module> ::= "mod" <IDENTIFIER> "{" <program> "}"
+        <export> ::= "pub" <definition>
+        <definition> ::= "let" <IDENTIFIER> "=" <NUMBER> ";"
+        """;
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"symbols_{Guid.NewGuid():N}");
+    private readonly ITestOutputHelper _output;
+
+    public WorkspaceSymbolIndexTests(ITestOutputHelper output)
+    {
+        _output = output;
+        Directory.CreateDirectory(_directory);
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_directory, recursive: true);
+    }
+
+    [Fact]
+    public void Search_MatchKinds_RanksExactThenPrefixSubstringAndSubsequence()
+    {
+        // Arrange
+        var index = new WorkspaceSymbolIndex();
+        index.Set("a.rs", new[] { Entry("packageRelease"), Entry("reparse"), Entry("prase"), Entry("parseExpr"), Entry("parse") });
+
+        // Act
+        var matches = index.Search("parse");
+
+        // Assert
+        Assert.Equal(new[] { "parse", "parseExpr", "reparse", "packageRelease" }, matches.Select(m => m.Symbol.Name));
+        Assert.Equal(
+            new[] { SymbolMatchKind.Exact, SymbolMatchKind.Prefix, SymbolMatchKind.Substring, SymbolMatchKind.Subsequence },
+            matches.Select(m => m.Match));
+    }
+
+    [Fact]
+    public void Search_Subsequence_PrefersWordStartsOverLettersInsideWords()
+    {
+        // Arrange
+        var index = new WorkspaceSymbolIndex();
+        index.Set("a.rs", new[] { Entry("gettext"), Entry("getTokenTable") });
+
+        // Act
+        var matches = index.Search("gtt");
+
+        // Assert
+        Assert.Equal(new[] { "getTokenTable", "gettext" }, matches.Select(m => m.Symbol.Name));
+        Assert.Equal(new[] { 30, 1020 }, matches.Select(m => m.Penalty));
+    }
+
+    [Fact]
+    public void Search_EqualMatches_BreaksTiesByCaseExportKindPathAndOffset()
+    {
+        // Arrange
+        var index = new WorkspaceSymbolIndex { KindOrder = new[] { "function" } };
+        index.Set("c.rs", new[] { Entry("run", "function", 50, "c.rs"), Entry("run", "function", 10, "c.rs") });
+        index.Set("a.rs", new[] { Entry("run", "parameter"), Entry("run", "function", 30) });
+        index.Set("b.rs", new[] { Entry("Run", "function", 0, "b.rs") });
+        index.Set("z.rs", new[] { Entry("run", "parameter", 0, "z.rs") with { IsExported = true } });
+
+        // Act
+        var first = index.Search("run");
+        var second = index.Search("run");
+
+        // Assert
+        Assert.Equal(
+            new[] { "z.rs:run:0", "a.rs:run:30", "c.rs:run:10", "c.rs:run:50", "a.rs:run:0", "b.rs:Run:0" },
+            first.Select(m => $"{m.Symbol.Path}:{m.Symbol.Name}:{m.Symbol.Location!.Offset}"));
+        Assert.Equal(first, second);
+        Assert.Equal(1, first[^1].Penalty);
+    }
+
+    [Fact]
+    public void Search_Limit_KeepsTheBestMatches()
+    {
+        // Arrange
+        var index = new WorkspaceSymbolIndex();
+        index.Set("a.rs", Enumerable.Range(0, 50).Select(i => Entry($"item{i}")).Append(Entry("item")));
+
+        // Act
+        var matches = index.Search("item", limit: 3);
+
+        // Assert
+        Assert.Equal(new[] { "item", "item0", "item1" }, matches.Select(m => m.Symbol.Name));
+    }
+
+    [Fact]
+    public void Search_UnicodeCase_FoldsIndependentlyOfTheCurrentCulture()
+    {
+        // Arrange
+        var index = new WorkspaceSymbolIndex();
+        index.Set("a.rs", new[] { Entry("ΟΔΟΣ"), Entry("STRAẞE"), Entry("index") });
+        var culture = CultureInfo.CurrentCulture;
+
+        try
+        {
+            CultureInfo.CurrentCulture = new CultureInfo("tr-TR");
+
+            // Act
+            var greek = index.Search("οδος");
+            var german = index.Search("straße");
+            var dotted = index.Search("INDEX");
+
+            // Assert
+            Assert.Equal(SymbolMatchKind.Exact, Assert.Single(greek).Match);
+            Assert.Equal(1, greek[0].Penalty);
+            Assert.Equal("STRAẞE", Assert.Single(german).Symbol.Name);
+            Assert.Equal("index", Assert.Single(dotted).Symbol.Name);
+        }
+        finally
+        {
+            CultureInfo.CurrentCulture = culture;
+        }
+    }
+
+    [Fact]
+    public void SymbolIndex_WorkspaceEdits_UpdatesIncrementallyWithKindsAndContainers()
+    {
+        // Arrange
+        var workspace = CreateWorkspace();
+        workspace.SetDocument("a.mod", "mod shapes { pub let circleArea = 1; let radius = 2; }\n");
+        workspace.SetDocument("b.mod", "let drawCircle = 3;\n");
+
+        // Act
+        var before = workspace.SymbolIndex.Search("circ");
+        workspace.SetDocument("b.mod", "let paint = 3;\n");
+        var edited = workspace.SymbolIndex.Search("circ");
+        workspace.RemoveDocument("a.mod");
+        var removed = workspace.SymbolIndex.Search("circ");
+
+        // Assert
+        Assert.Equal(new[] { "circleArea", "drawCircle" }, before.Select(m => m.Symbol.Name));
+        var area = before[0].Symbol;
+        Assert.Equal(("definition", "shapes", "a.mod", true), (area.Kind, area.Container, area.Path, area.IsExported));
+        Assert.Equal((1, 22), (area.Location!.Line, area.Location.Column));
+        Assert.Null(Assert.Single(workspace.SymbolIndex.Search("shapes")).Symbol.Container);
+        Assert.Equal("circleArea", Assert.Single(edited).Symbol.Name);
+        Assert.Empty(removed);
+        Assert.Equal(1, workspace.SymbolIndex.Count);
+    }
+
+    [Fact]
+    public void SymbolIndex_RestoredCheckpoint_AnswersLikeTheSavedWorkspace()
+    {
+        // Arrange
+        var files = new Dictionary<string, string>(StringComparer.Ordinal)
+        {
+            ["a.mod"] = "mod shapes { pub let circleArea = 1; }\n",
+            ["b.mod"] = "let drawCircle = 3;\n"
+        };
+        var checkpoint = Path.Combine(_directory, "workspace.checkpoint");
+        var original = CreateWorkspace();
+        foreach (var (path, text) in files)
+        {
+            original.SetDocument(path, text);
+        }
+
+        original.SaveCheckpoint(checkpoint);
+
+        // Act
+        var warm = CreateWorkspace();
+        var result = warm.RestoreCheckpoint(checkpoint, files);
+
+        // Assert
+        Assert.Equal(2, result.Reused.Count);
+        Assert.Equal(Describe(original.SymbolIndex.Search("c")), Describe(warm.SymbolIndex.Search("c")));
+    }
+
+    [Fact]
+    public void Search_HundredThousandSymbols_Benchmark()
+    {
+        // Arrange
+        var verbs = new[] { "get", "set", "parse", "build", "find", "load", "render", "update", "create", "resolve" };
+        var nouns = new[] { "Symbol", "Token", "Tree", "Node", "Table", "Grammar", "Scope", "Module", "Buffer", "Cache" };
+        var index = new WorkspaceSymbolIndex();
+        for (var file = 0; file < 1000; file++)
+        {
+            index.Set($"src/f{file}.rs", Enumerable.Range(0, 100).Select(i =>
+            {
+                var n = file * 100 + i;
+                return Entry($"{verbs[n % 10]}{nouns[n / 10 % 10]}{nouns[n / 100 % 10]}{n / 1000}", "function", i * 40, $"src/f{file}.rs");
+            }));
+        }
+
+        var queries = new[] { "gst", "parseTokenTree", "rsvcache", "bldnode", "xyz", "updMod", "t", "load99" };
+        index.Search("warm");
+
+        // Act
+        var watch = Stopwatch.StartNew();
+        var found = queries.Select(q => index.Search(q)).ToList();
+        watch.Stop();
+
+        // Assert
+        var average = watch.Elapsed.TotalMilliseconds / queries.Length;
+        _output.WriteLine($"{index.Count} symbols, {average:F2} ms per query");
+        Assert.Equal(100_000, index.Count);
+        Assert.Equal("parseTokenTree", found[1][0].Symbol.Name[..14]);
+        Assert.Empty(found[4]);
+        Assert.All(found.Where(f => f.Count > 0), f => Assert.True(f.Count <= 100));
+    }
+
+    private static SymbolIndexEntry Entry(string name, string kind = "function", int offset = 0, string path = "a.rs")
+    {
+        return new SymbolIndexEntry(name, kind, null, path, new SourcePosition(1, offset + 1, offset, name.Length));
+    }
+
+    private static IEnumerable<string> Describe(IEnumerable<WorkspaceSymbolMatch> matches)
+    {
+        return matches.Select(m => $"{m.Match} {m.Symbol.Name} {m.Symbol.Kind} {m.Symbol.Container} {m.Symbol.Path} {m.Symbol.Location?.Line}:{m.Symbol.Location?.Column} {m.Symbol.IsExported}");
+    }
+
+    private static AnalysisWorkspace CreateWorkspace()
+    {
+        var grammar = new GrammarFileReader().Read(ModuleGrammar);
+        return new AnalysisWorkspace(new GeneralizedParser(CompiledGrammar.Compile(grammar)));
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Api;
+using Minotaur.Api.Interop;
+
+namespace Minotaur.Tests.Api;
+
+/// <summary>
+/// Tests for the embedding API facade
+/// </summary>
+public class EmbeddingApiTests
+{
+    private const string SumGrammar = """
+        Grammar: Sums
+        StartRule: sum
+        <sum> ::= <NUMBER> | <sum> "+" <NUMBER>
+        <NUMBER> ::= /[0-9]+/
+        <WHITESPACE> ::= /\s+/ => { skip }
+        """;
+
+    [Fact]
+    public void Parse_ValidText_ExposesTreeWithTextAndSpans()
+    {
+        // Arrange
+        var parser = LanguageGrammar.FromText(SumGrammar).CreateParser();
+
+        // Act
+        var outcome = parser.Parse("1 + 22", "sum.txt");
+
+        // Assert
+        Assert.True(outcome.IsSuccess);
+        Assert.Empty(outcome.Diagnostics);
+        var root = Assert.IsType<SyntaxNode>(outcome.Root);
+        Assert.Equal("sum", root.Name);
+        Assert.Equal("1 + 22", root.Text);
+        Assert.Null(root.Parent);
+        var tokens = root.Descendants().Where(n => n.IsToken).ToList();
+        Assert.Equal(new[] { "1", "+", "22" }, tokens.Select(t => t.Text));
+        Assert.Same(root, tokens[^1].Parent);
+        Assert.Equal("sum.txt:1:5", tokens[^1].Span?.ToString());
+    }
+
+    [Fact]
+    public void Parse_InvalidText_ReportsDiagnosticsWithoutTree()
+    {
+        // Arrange
+        var parser = LanguageGrammar.FromText(SumGrammar).CreateParser();
+
+        // Act
+        var outcome = parser.Parse("1 +");
+
+        // Assert
+        Assert.False(outcome.IsSuccess);
+        var diagnostic = Assert.Single(outcome.Diagnostics.Where(d => d.Level == DiagnosticLevel.Error));
+        Assert.False(string.IsNullOrEmpty(diagnostic.Code));
+        Assert.Contains(diagnostic.Code, diagnostic.ToString());
+    }
+
+    [Fact]
+    public void FromText_UndefinedStartRule_ThrowsGrammarLoadException()
+    {
+        // Act
+        var exception = Assert.Throws<GrammarLoadException>(() => LanguageGrammar.FromText(SumGrammar, "product"));
+
+        // Assert
+        Assert.Contains("product", exception.Message);
+        Assert.IsAssignableFrom<ArgumentException>(exception.InnerException);
+    }
+
+    [Fact]
+    public void ApiInterop_RoundTripsEngineTypes()
+    {
+        // Arrange
+        var grammar = LanguageGrammar.FromText(SumGrammar);
+        var outcome = grammar.CreateParser().Parse("3");
+
+        // Act
+        var compiled = ApiInterop.GetCompiledGrammar(grammar);
+        var result = ApiInterop.GetParseResult(outcome);
+
+        // Assert
+        Assert.Equal(grammar.Rules, ApiInterop.FromCompiled(compiled).Rules);
+        Assert.Same(result.Tree, ApiInterop.GetNode(outcome.Root!));
+    }
+
+    [Fact]
+    public void AnalysisSession_SetDocument_ReportsDiagnosticsPerDocument()
+    {
+        // Arrange
+        var session = new AnalysisSession(LanguageGrammar.FromText(SumGrammar).CreateParser());
+
+        // Act
+        session.SetDocument("b.txt", "1 +");
+        session.SetDocument("a.txt", "1 + 2");
+
+        // Assert
+        Assert.Equal(new[] { "a.txt", "b.txt" }, session.Documents);
+        Assert.DoesNotContain(session.GetDiagnostics("a.txt"), d => d.Level == DiagnosticLevel.Error);
+        Assert.Contains(session.GetDiagnostics("b.txt"), d => d.Level == DiagnosticLevel.Error);
+        Assert.Empty(session.GetDiagnostics("missing.txt"));
+    }
+}
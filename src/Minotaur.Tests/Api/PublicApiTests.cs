@@ -0,0 +1,159 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Reflection;
+using System.Runtime.CompilerServices;
+using System.Text;
+using Xunit;
+using Minotaur.Api;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Api;
+
+/// <summary>
+/// Tests for the public API listing of the embedding surface
+/// </summary>
+public class PublicApiTests
+{
+    private const BindingFlags Declared = BindingFlags.Public | BindingFlags.Instance | BindingFlags.Static | BindingFlags.DeclaredOnly;
+
+    private readonly NullabilityInfoContext _nullability = new();
+
+    [Fact]
+    public void EmbeddingSurface_MatchesApprovedListing()
+    {
+        // Arrange
+        var types = typeof(MinotaurApi).Assembly.GetExportedTypes()
+            .Where(t => t.Namespace?.StartsWith("Minotaur.Api", StringComparison.Ordinal) == true)
+            .OrderBy(t => t.FullName, StringComparer.Ordinal);
+
+        // Act
+        var listing = new StringBuilder();
+        foreach (var type in types)
+        {
+            listing.Append(listing.Length > 0 ? "\n" : string.Empty).Append(DescribeType(type)).Append('\n');
+            foreach (var member in DescribeMembers(type).Order(StringComparer.Ordinal))
+            {
+                listing.Append("  ").Append(member).Append('\n');
+            }
+        }
+
+        // Assert
+        Snapshot.AssertMatches(listing.ToString(), Path.Combine(TestDirectory(), "Snapshots", "PublicApi.txt"));
+    }
+
+    [Fact]
+    public void EmbeddingSurface_ExposesNoSettersOrEngineTypesOutsideInterop()
+    {
+        // Arrange
+        var types = typeof(MinotaurApi).Assembly.GetExportedTypes().Where(t => t.Namespace == "Minotaur.Api");
+
+        // Act
+        var leaks = types
+            .SelectMany(t => t.GetProperties(Declared).Where(p => p.SetMethod?.IsPublic == true).Select(p => $"{t.Name}.{p.Name} setter")
+                .Concat(t.GetMembers(Declared).SelectMany(SignatureTypes).Where(IsEngineType).Select(s => $"{t.Name} uses {s.FullName}")))
+            .ToList();
+
+        // Assert
+        Assert.Empty(leaks);
+    }
+
+    private static string DescribeType(Type type)
+    {
+        var kind = type.IsEnum ? "enum"
+            : type.IsInterface ? "interface"
+            : type.IsValueType ? "struct"
+            : type.IsAbstract && type.IsSealed ? "static class"
+            : type.IsSealed ? "sealed class"
+            : "class";
+        var baseType = type.BaseType is { } b && b != typeof(object) && b != typeof(Enum) && b != typeof(ValueType) ? $" : {b.Name}" : string.Empty;
+        return $"{kind} {type.FullName}{baseType}";
+    }
+
+    private IEnumerable<string> DescribeMembers(Type type)
+    {
+        if (type.IsEnum)
+        {
+            return type.GetFields(BindingFlags.Public | BindingFlags.Static)
+                .Select(f => $"value {f.Name} = {Convert.ToInt64(f.GetRawConstantValue())}");
+        }
+
+        var constructors = type.GetConstructors(Declared).Select(c => $"ctor({DescribeParameters(c)})");
+        var methods = type.GetMethods(Declared).Where(m => !m.IsSpecialName)
+            .Select(m => $"{(m.IsStatic ? "static " : string.Empty)}method {m.Name}({DescribeParameters(m)}) : {DescribeType(m.ReturnType, _nullability.Create(m.ReturnParameter))}");
+        var properties = type.GetProperties(Declared)
+            .Select(p => $"property {p.Name} : {DescribeType(p.PropertyType, _nullability.Create(p))}");
+        var fields = type.GetFields(Declared).Where(f => !f.IsSpecialName)
+            .Select(f => f.IsLiteral
+                ? $"const {f.Name} : {f.FieldType.Name} = {DescribeValue(f.GetRawConstantValue(), f.FieldType)}"
+                : $"field {f.Name} : {DescribeType(f.FieldType, _nullability.Create(f))}");
+        return constructors.Concat(methods).Concat(properties).Concat(fields);
+    }
+
+    private string DescribeParameters(MethodBase method)
+    {
+        return string.Join(", ", method.GetParameters().Select(p =>
+            $"{DescribeType(p.ParameterType, _nullability.Create(p))} {p.Name}{(p.HasDefaultValue ? $" = {DescribeValue(p.DefaultValue, p.ParameterType)}" : string.Empty)}"));
+    }
+
+    private static string DescribeType(Type type, NullabilityInfo nullability)
+    {
+        if (Nullable.GetUnderlyingType(type) is { } underlying)
+        {
+            return $"{underlying.Name}?";
+        }
+
+        var name = type.IsGenericType
+            ? $"{type.Name[..type.Name.IndexOf('`')]}<{string.Join(", ", type.GetGenericArguments().Select((a, i) => DescribeType(a, nullability.GenericTypeArguments[i])))}>"
+            : type.Name;
+        return !type.IsValueType && nullability.ReadState == NullabilityState.Nullable ? $"{name}?" : name;
+    }
+
+    private static string DescribeValue(object? value, Type type)
+    {
+        return value switch
+        {
+            null => type.IsValueType ? "default" : "null",
+            string text => $"\"{text}\"",
+            bool flag => flag ? "true" : "false",
+            _ => Convert.ToString(value, System.Globalization.CultureInfo.InvariantCulture) ?? string.Empty
+        };
+    }
+
+    private static IEnumerable<Type> SignatureTypes(MemberInfo member)
+    {
+        var types = member switch
+        {
+            MethodInfo m when !m.IsSpecialName => m.GetParameters().Select(p => p.ParameterType).Append(m.ReturnType),
+            ConstructorInfo c => c.GetParameters().Select(p => p.ParameterType),
+            PropertyInfo p => new[] { p.PropertyType },
+            FieldInfo f => new[] { f.FieldType },
+            _ => Enumerable.Empty<Type>()
+        };
+        return types.SelectMany(t => t.IsGenericType ? t.GetGenericArguments().Prepend(t) : new[] { t });
+    }
+
+    private static bool IsEngineType(Type type)
+    {
+        return type.Assembly == typeof(MinotaurApi).Assembly && type.Namespace?.StartsWith("Minotaur.Api", StringComparison.Ordinal) != true;
+    }
+
+    private static string TestDirectory([CallerFilePath] string path = "")
+    {
+        return Path.GetDirectoryName(path)!;
+    }
+}
@@ -0,0 +1,5 @@
+fn main() {
+	let x = 1;		if x {
+  	call(x);
+	}
+}
\ No newline at end of file
@@ -0,0 +1,144 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Tests.Core;
+
+/// <summary>
+/// Tests for mixed line ending and tab width handling in line/column math and caret rendering.
+/// The fixture ends its lines with CRLF, CR, LF, CRLF, LF and nothing, and indents with tabs.
+/// </summary>
+public class LineEndingTests
+{
+    private static readonly string Source = File.ReadAllText(Fixture("mixed.rs"));
+
+    [Fact]
+    public void LineIndex_RecordsEveryLineEndingStyle()
+    {
+        // Act
+        var index = new LineIndex(Source);
+
+        // Assert
+        Assert.Equal(6, index.LineCount);
+        Assert.Equal(
+            new[] { LineEnding.CrLf, LineEnding.Cr, LineEnding.Lf, LineEnding.CrLf, LineEnding.Lf, LineEnding.None },
+            index.LineEndings);
+        Assert.True(index.HasMixedLineEndings);
+        Assert.Equal(LineEnding.Lf, index.DominantLineEnding);
+        Assert.Equal("\tlet x = 1;", index.GetLineText(2));
+        Assert.Equal("  \tcall(x);", index.GetLineText(4));
+        Assert.Equal("}", index.GetLineText(6));
+    }
+
+    [Fact]
+    public void LineIndex_AndFromOffset_AgreeOnEveryOffset()
+    {
+        // Arrange
+        var index = new LineIndex(Source);
+
+        // Act & Assert
+        for (var offset = 0; offset <= Source.Length; offset++)
+        {
+            var position = SourcePosition.FromOffset(offset, 0, Source);
+            Assert.Equal(index.GetLineColumn(offset), (position.Line, position.Column));
+        }
+    }
+
+    [Fact]
+    public void LineIndex_TreatsLoneCrAsLineBreak()
+    {
+        // Arrange
+        var index = new LineIndex(Source);
+        var x = Source.IndexOf("x {", StringComparison.Ordinal);
+
+        // Act
+        var (line, column) = index.GetLineColumn(x);
+
+        // Assert
+        Assert.Equal((3, 6), (line, column));
+        Assert.Equal(LineEnding.Cr, index.GetLineEnding(2));
+        Assert.False(new LineIndex("a\nb\n").HasMixedLineEndings);
+    }
+
+    [Fact]
+    public void SourcePosition_VisualColumn_ExpandsTabsToTabStops()
+    {
+        // Arrange
+        var call = Source.IndexOf("call", StringComparison.Ordinal);
+        var position = new LineIndex(Source).GetPosition(call, 4);
+        var x = SourcePosition.FromOffset(Source.IndexOf("x {", StringComparison.Ordinal), 1, Source);
+
+        // Act & Assert
+        Assert.Equal(4, position.Column);
+        Assert.Equal(5, position.GetVisualColumn(Source));
+        Assert.Equal(9, position.GetVisualEndColumn(Source));
+        Assert.Equal(9, position.GetVisualColumn(Source, tabWidth: 8));
+        Assert.Equal(12, x.GetVisualColumn(Source));
+        Assert.Equal(12, new LineIndex(Source).GetVisualColumn(x.Offset));
+    }
+
+    [Fact]
+    public void FormatSnippet_AlignsCaretsUnderTabIndentedSource()
+    {
+        // Arrange
+        var call = Source.IndexOf("call", StringComparison.Ordinal);
+        var diagnostic = new Diagnostic
+        {
+            Code = "E0001",
+            Message = "unknown function",
+            Location = new LineIndex(Source).GetPosition(call, 4)
+        };
+
+        // Act
+        var text = new DiagnosticFormatter().FormatSnippet(diagnostic, Source);
+
+        // Assert
+        Assert.Equal("4:4: error E0001: unknown function\n4 |     call(x);\n  |     ^^^^", text);
+    }
+
+    [Fact]
+    public void FormatSnippet_ClipsSpanAtLoneCrLineEnd()
+    {
+        // Arrange
+        var one = Source.IndexOf("1;", StringComparison.Ordinal);
+        var diagnostic = new Diagnostic
+        {
+            Code = "E0002",
+            Message = "statement runs past the line",
+            Location = SourcePosition.FromOffset(one, 5, Source)
+        };
+
+        // Act
+        var text = new DiagnosticFormatter { TabWidth = 2 }.FormatSnippet(diagnostic, Source);
+
+        // Assert
+        Assert.Equal("2:10: error E0002: statement runs past the line\n2 |   let x = 1;\n  |           ^^", text);
+    }
+
+    private static string Fixture(string name)
+    {
+        return Path.Combine(TestDirectory(), "Fixtures", "line-endings", name);
+    }
+
+    private static string TestDirectory([CallerFilePath] string path = "")
+    {
+        return Path.GetDirectoryName(path)!;
+    }
+}
@@ -0,0 +1,85 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+
+namespace Minotaur.Tests.Core;
+
+/// <summary>
+/// Tests for rope functionality
+/// </summary>
+public class RopeTests
+{
+    [Fact]
+    public void Replace_LeavesOriginalUnchanged()
+    {
+        // Arrange
+        var rope = Rope.FromString("hello world");
+
+        // Act
+        var edited = rope.Replace(6, 5, "rope");
+
+        // Assert
+        Assert.Equal("hello rope", edited.ToString());
+        Assert.Equal("hello world", rope.ToString());
+    }
+
+    [Fact]
+    public void GetOffset_PositionPastEndOfLine_ReturnsMinusOne()
+    {
+        // Arrange
+        var rope = Rope.FromString("ab\ncde\n");
+
+        // Act & Assert
+        Assert.Equal(2, rope.GetOffset(0, 2));
+        Assert.Equal(6, rope.GetOffset(1, 3));
+        Assert.Equal(7, rope.GetOffset(2, 0));
+        Assert.Equal(-1, rope.GetOffset(0, 3));
+        Assert.Equal(-1, rope.GetOffset(3, 0));
+        Assert.Equal((1, 2), rope.GetPosition(5));
+    }
+
+    [Fact]
+    public void Replace_RandomEdits_MatchesStringEdits()
+    {
+        // Arrange
+        var random = new Random(7);
+        var expected = string.Concat(Enumerable.Range(0, 3000).Select(i => i % 17 == 0 ? '\n' : (char)('a' + i % 26)));
+        var rope = Rope.FromString(expected);
+
+        for (var i = 0; i < 2000; i++)
+        {
+            // Act
+            var offset = random.Next(expected.Length + 1);
+            var length = random.Next(Math.Min(20, expected.Length - offset) + 1);
+            var text = random.Next(4) == 0 ? "\nxy\n" : new string('z', random.Next(5));
+            expected = string.Concat(expected.AsSpan(0, offset), text, expected.AsSpan(offset + length));
+            rope = rope.Replace(offset, length, text);
+
+            // Assert
+            var probe = random.Next(expected.Length + 1);
+            var line = expected[..probe].Count(c => c == '\n');
+            var lineStart = probe == 0 ? 0 : expected.LastIndexOf('\n', probe - 1) + 1;
+            Assert.Equal((line, probe - lineStart), rope.GetPosition(probe));
+            Assert.Equal(probe, rope.GetOffset(line, probe - lineStart));
+        }
+
+        Assert.Equal(expected, rope.ToString());
+        Assert.Equal(expected.Count(c => c == '\n'), rope.LineBreakCount);
+    }
+}
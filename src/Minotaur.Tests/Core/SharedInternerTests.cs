@@ -0,0 +1,137 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Monitoring;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Core;
+
+/// <summary>
+/// Tests for shared interner functionality
+/// </summary>
+public class SharedInternerTests
+{
+    private const string StatementGrammar = """
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= "let" <IDENTIFIER> "=" <IDENTIFIER> ";"
+        """;
+
+    [Fact]
+    public void Intern_SameTextAsStringAndSpan_ReturnsOneInstance()
+    {
+        // Arrange
+        var interner = new SharedInterner();
+        var source = "let total = total;";
+
+        // Act
+        var first = interner.Intern(new string("total".ToCharArray()));
+        var second = interner.Intern(source.AsSpan(4, 5));
+        var third = interner.Intern(source.AsSpan(12, 5));
+
+        // Assert
+        Assert.Same(first, second);
+        Assert.Same(first, third);
+        Assert.Equal(1, interner.Count);
+        Assert.Equal(1, interner.GetMemoryReport().Get(MemoryCategories.InternedStrings)!.Count);
+    }
+
+    [Fact]
+    public void Collect_StringsNotLive_AreDropped()
+    {
+        // Arrange
+        var interner = new SharedInterner(shardCount: 3);
+        var kept = interner.Intern("kept");
+        interner.Intern("dropped");
+
+        // Act
+        var dropped = interner.Collect(new[] { "kept", "never interned" });
+
+        // Assert
+        Assert.Equal(1, dropped);
+        Assert.Equal(1, interner.Count);
+        Assert.True(interner.TryGet("kept", out var interned));
+        Assert.Same(kept, interned);
+        Assert.False(interner.TryGet("dropped", out _));
+        Assert.Equal(1, interner.Collections);
+    }
+
+    [Fact]
+    public void Parse_WithInterner_SharesIdentifierTextAcrossFiles()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(StatementGrammar)));
+        var interner = new SharedInterner();
+        var options = new ParseOptions { Interner = interner };
+
+        // Act
+        var a = parser.Parse("let width = height;", options);
+        var b = parser.Parse("let height = width;", options);
+
+        // Assert
+        Assert.Same(a.Tokens[1].Text, b.Tokens[3].Text);
+        Assert.Same(a.Tokens[3].Text, b.Tokens[1].Text);
+        Assert.Equal(2, interner.Count);
+    }
+
+    [Fact]
+    public async Task Collect_DuringParallelParses_KeepsLiveStringsAndParsesStayCorrect()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(StatementGrammar)));
+        var interner = new SharedInterner(shardCount: 4);
+        var pinned = Enumerable.Range(0, 32).Select(i => interner.Intern($"pinned{i}")).ToList();
+        using var done = new CancellationTokenSource();
+
+        // Act
+        var collector = Task.Run(() =>
+        {
+            var collections = 0;
+            while (!done.IsCancellationRequested || collections < 10)
+            {
+                interner.Collect(pinned);
+                collections++;
+                Assert.All(pinned, p => Assert.True(interner.TryGet(p, out var interned) && ReferenceEquals(interned, p)));
+            }
+        });
+
+        var parses = Enumerable.Range(0, 8).Select(worker => Task.Run(() =>
+        {
+            for (var i = 0; i < 200; i++)
+            {
+                var name = $"n{(worker * 200 + i) % 300}";
+                var input = $"let {name} = pinned{i % 32};\nlet x{i} = {name};";
+                var result = parser.Parse(input, new ParseOptions { Interner = interner });
+                Assert.True(result.IsSuccess);
+                Assert.Equal(new[] { name, $"pinned{i % 32}", $"x{i}", name }, result.Tokens.Where(t => t.Kind == "IDENTIFIER").Select(t => t.Text));
+            }
+        })).ToList();
+
+        await Task.WhenAll(parses);
+        done.Cancel();
+        await collector;
+        var remaining = interner.Collect(Array.Empty<string>());
+
+        // Assert
+        Assert.True(interner.Collections >= 11);
+        Assert.True(remaining >= pinned.Count);
+        Assert.Equal(0, interner.Count);
+        Assert.All(pinned, p => Assert.False(interner.TryGet(p, out _)));
+    }
+}
@@ -0,0 +1,221 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using System.Text;
+using System.Text.Json.Nodes;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Tests.Core;
+
+/// <summary>
+/// Tests for SourceDecoder functionality
+/// </summary>
+public class SourceDecoderTests
+{
+    private const string FixtureText = "let café = \"naïve\";\n";
+
+    [Theory]
+    [InlineData("utf8-bom.txt", null, "utf-8", true)]
+    [InlineData("utf16le-bom.txt", null, "utf-16", true)]
+    [InlineData("windows-1252.txt", "windows-1252", "windows-1252", false)]
+    public async Task DecodeFileAsync_ShouldDecodeFixtureToSameText(string file, string? encodingName, string expectedEncoding, bool hadBom)
+    {
+        // Arrange
+        var options = new SourceDecodeOptions
+        {
+            Encoding = encodingName != null ? SourceDecoder.GetEncoding(encodingName) : null
+        };
+
+        // Act
+        var source = await SourceDecoder.DecodeFileAsync(Fixture(file), options);
+
+        // Assert
+        Assert.Equal(FixtureText, source.Text);
+        Assert.Equal(expectedEncoding, source.Encoding.WebName);
+        Assert.Equal(hadBom, source.HadByteOrderMark);
+        Assert.Empty(source.Diagnostics);
+        Assert.Equal(new FileInfo(Fixture(file)).Length, source.ByteLength);
+    }
+
+    [Fact]
+    public void GetByteOffset_ShouldAccountForBomAndMultiByteCharacters()
+    {
+        // Arrange
+        var utf8 = SourceDecoder.Decode(File.ReadAllBytes(Fixture("utf8-bom.txt")));
+        var utf16 = SourceDecoder.Decode(File.ReadAllBytes(Fixture("utf16le-bom.txt")));
+        var cp1252 = SourceDecoder.Decode(
+            File.ReadAllBytes(Fixture("windows-1252.txt")),
+            new SourceDecodeOptions { Encoding = SourceDecoder.GetEncoding("windows-1252") });
+        var equals = FixtureText.IndexOf('=');
+
+        // Act & Assert
+        Assert.Equal(3, utf8.GetByteOffset(0));
+        Assert.Equal(3 + equals + 1, utf8.GetByteOffset(equals));
+        Assert.Equal(2 + 2 * equals, utf16.GetByteOffset(equals));
+        Assert.Equal(equals, cp1252.GetByteOffset(equals));
+        Assert.Equal(utf8.ByteLength, utf8.GetByteOffset(utf8.Text.Length));
+    }
+
+    [Fact]
+    public void GetByteSpan_ShouldMapParsePositionToOriginalBytes()
+    {
+        // Arrange
+        var source = SourceDecoder.Decode(File.ReadAllBytes(Fixture("utf8-bom.txt")));
+        var start = source.Text.IndexOf("naïve", StringComparison.Ordinal);
+        var position = new LineIndex(source.Text).GetPosition(start, "naïve".Length);
+
+        // Act
+        var (offset, length) = source.GetByteSpan(position);
+
+        // Assert
+        Assert.Equal(3 + Encoding.UTF8.GetByteCount(source.Text[..start]), offset);
+        Assert.Equal(6, length);
+    }
+
+    [Fact]
+    public void Decode_WithoutBom_ShouldDefaultToUtf8()
+    {
+        // Arrange
+        var bytes = Encoding.UTF8.GetBytes("x = \U0001F600;");
+
+        // Act
+        var source = SourceDecoder.Decode(bytes);
+
+        // Assert
+        Assert.Equal("x = \U0001F600;", source.Text);
+        Assert.False(source.HadByteOrderMark);
+        Assert.Equal(4, source.GetByteOffset(4));
+        Assert.Equal(4, source.GetByteOffset(5));
+        Assert.Equal(8, source.GetByteOffset(6));
+    }
+
+    [Fact]
+    public void Decode_WithUtf16BigEndianBom_ShouldDecodeSurrogatePairs()
+    {
+        // Arrange
+        var bytes = new byte[] { 0xFE, 0xFF }.Concat(Encoding.BigEndianUnicode.GetBytes("a\U0001F600b")).ToArray();
+
+        // Act
+        var source = SourceDecoder.Decode(bytes);
+
+        // Assert
+        Assert.Equal("a\U0001F600b", source.Text);
+        Assert.Equal("utf-16BE", source.Encoding.WebName);
+        Assert.Equal(4, source.GetByteOffset(1));
+        Assert.Equal(8, source.GetByteOffset(3));
+    }
+
+    [Fact]
+    public void Decode_WithInvalidBytesAndErrorPolicy_ShouldReportByteOffset()
+    {
+        // Arrange
+        var bytes = new byte[] { (byte)'a', (byte)'b', 0xFF, (byte)'c' };
+
+        // Act
+        var ex = Assert.Throws<SourceDecodingException>(() =>
+            SourceDecoder.Decode(bytes, new SourceDecodeOptions { SourceFile = "input.txt" }));
+
+        // Assert
+        Assert.Equal(2, ex.ByteOffset);
+        Assert.Equal("input.txt", ex.SourceFile);
+        Assert.Contains("0xFF at byte 2", ex.Message);
+        Assert.Equal(DiagnosticCodes.InvalidEncoding, ex.ToDiagnostic().Code);
+    }
+
+    [Fact]
+    public void Decode_WithInvalidBytesAndSubstitutePolicy_ShouldReplaceAndWarn()
+    {
+        // Arrange
+        var bytes = new byte[] { (byte)'a', (byte)'\n', 0xC3, (byte)'b' };
+
+        // Act
+        var source = SourceDecoder.Decode(bytes, new SourceDecodeOptions
+        {
+            InvalidBytes = InvalidBytePolicy.Substitute,
+            SourceFile = "input.txt"
+        });
+
+        // Assert
+        Assert.Equal("a\n�b", source.Text);
+        var warning = Assert.Single(source.Diagnostics);
+        Assert.Equal(DiagnosticCodes.SubstitutedBytes, warning.Code);
+        Assert.Equal(DiagnosticSeverity.Warning, warning.Severity);
+        Assert.Equal((2, 1), (warning.Location!.Line, warning.Location.Column));
+        Assert.Equal("input.txt", warning.Location.SourceFile);
+        Assert.Equal(2, warning.Data["byteOffset"]);
+        Assert.Equal(3, source.GetByteOffset(3));
+    }
+
+    [Fact]
+    public void Decode_WithOddUtf16Length_ShouldTreatTrailingByteAsInvalid()
+    {
+        // Arrange
+        var bytes = new byte[] { 0xFF, 0xFE, (byte)'a', 0, (byte)'b' };
+
+        // Act
+        var ex = Assert.Throws<SourceDecodingException>(() => SourceDecoder.Decode(bytes));
+
+        // Assert
+        Assert.Equal(4, ex.ByteOffset);
+    }
+
+    [Fact]
+    public void Decode_WithExplicitEncodingDifferentFromBom_ShouldKeepBomBytes()
+    {
+        // Arrange
+        var bytes = new byte[] { 0xEF, 0xBB, 0xBF, (byte)'x' };
+
+        // Act
+        var source = SourceDecoder.Decode(bytes, new SourceDecodeOptions { Encoding = SourceDecoder.GetEncoding("windows-1252") });
+
+        // Assert
+        Assert.False(source.HadByteOrderMark);
+        Assert.Equal("ï»¿x", source.Text);
+    }
+
+    [Fact]
+    public void ToSarif_WithDecodedSource_ShouldIncludeByteRegion()
+    {
+        // Arrange
+        var source = SourceDecoder.Decode(
+            new byte[] { 0xEF, 0xBB, 0xBF, 0xC3, 0xA9, 0xFF },
+            new SourceDecodeOptions { InvalidBytes = InvalidBytePolicy.Substitute });
+        var formatter = new DiagnosticFormatter { Source = source };
+
+        // Act
+        var sarif = JsonNode.Parse(formatter.ToSarif(source.Diagnostics))!;
+
+        // Assert
+        var region = sarif["runs"]![0]!["results"]![0]!["locations"]![0]!["physicalLocation"]!["region"]!;
+        Assert.Equal(1, (int)region["charOffset"]!);
+        Assert.Equal(5, (int)region["byteOffset"]!);
+        Assert.Equal(1, (int)region["byteLength"]!);
+    }
+
+    private static string Fixture(string name)
+    {
+        return Path.Combine(TestDirectory(), "Fixtures", "encoding", name);
+    }
+
+    private static string TestDirectory([CallerFilePath] string path = "")
+    {
+        return Path.GetDirectoryName(path)!;
+    }
+}
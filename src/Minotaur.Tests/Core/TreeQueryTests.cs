@@ -0,0 +1,93 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+
+namespace Minotaur.Tests.Core;
+
+/// <summary>
+/// Tests for TreeQuery functionality
+/// </summary>
+public class TreeQueryTests
+{
+    [Fact]
+    public void Select_DescendantAndChildSteps_MatchInDocumentOrder()
+    {
+        // Arrange
+        var tree = CreateTree();
+
+        // Act
+        var descendants = TreeQuery.Parse("import IDENTIFIER").Select(tree).Cast<TerminalNode>();
+        var children = TreeQuery.Parse("import > IDENTIFIER").Select(tree).Cast<TerminalNode>();
+
+        // Assert
+        Assert.Equal(new[] { "std", "io", "fmt" }, descendants.Select(t => t.Text));
+        Assert.Equal(new[] { "fmt" }, children.Select(t => t.Text));
+    }
+
+    [Fact]
+    public void Select_LiteralAndWildcardSteps_MatchTokenKinds()
+    {
+        // Arrange
+        var tree = CreateTree();
+
+        // Act
+        var keywords = TreeQuery.Parse("program > * > 'use'").Select(tree).ToList();
+
+        // Assert
+        Assert.Equal(2, keywords.Count);
+        Assert.All(keywords, k => Assert.Equal("\"use\"", ((TerminalNode)k).TokenType));
+        Assert.True(TreeQuery.Parse("path").IsMatch(tree.Children[0].Children[1], tree));
+    }
+
+    [Theory]
+    [InlineData("")]
+    [InlineData("> import")]
+    [InlineData("import >")]
+    [InlineData("import > > IDENTIFIER")]
+    [InlineData("\"use")]
+    public void Parse_MalformedSelector_Throws(string selector)
+    {
+        // Act & Assert
+        Assert.Throws<FormatException>(() => TreeQuery.Parse(selector));
+    }
+
+    // program(import("use", path(std, "::", io), ";"), import("use", fmt, ";"))
+    private static NonTerminalNode CreateTree()
+    {
+        var program = new NonTerminalNode("program");
+
+        var first = new NonTerminalNode("import");
+        var path = new NonTerminalNode("path");
+        path.AddChild(new TerminalNode("std", "IDENTIFIER"));
+        path.AddChild(new TerminalNode("::", "\"::\""));
+        path.AddChild(new TerminalNode("io", "IDENTIFIER"));
+        first.AddChild(new TerminalNode("use", "\"use\""));
+        first.AddChild(path);
+        first.AddChild(new TerminalNode(";", "\";\""));
+
+        var second = new NonTerminalNode("import");
+        second.AddChild(new TerminalNode("use", "\"use\""));
+        second.AddChild(new TerminalNode("fmt", "IDENTIFIER"));
+        second.AddChild(new TerminalNode(";", "\";\""));
+
+        program.AddChild(first);
+        program.AddChild(second);
+        return program;
+    }
+}
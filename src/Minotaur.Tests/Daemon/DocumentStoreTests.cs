@@ -0,0 +1,252 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Daemon;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Daemon;
+
+/// <summary>
+/// Tests for document store functionality
+/// </summary>
+public class DocumentStoreTests
+{
+    private const string StatementGrammar = """
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= "let" <IDENTIFIER> "=" <value> ";"
+        <value> ::= <IDENTIFIER> | <NUMBER>
+        """;
+
+    [Fact]
+    public void Change_VersionsOutOfOrder_AppliesThemOnceTheGapIsFilled()
+    {
+        // Arrange
+        var store = new DocumentStore();
+        store.Open("a.txt", 1, "let a = 1;\n");
+
+        // Act
+        var early = store.Change("a.txt", 3, new[] { Replace(0, 10, 0, 10, " let c = a;") });
+        var late = store.Change("a.txt", 2, new[] { Replace(0, 8, 0, 9, "2") });
+        var stale = store.Change("a.txt", 2, new[] { Replace(0, 0, 0, 0, "x") });
+
+        // Assert
+        Assert.Equal(DocumentChangeStatus.Buffered, early.Status);
+        Assert.Equal(1, early.Version);
+        Assert.Equal(new DocumentChangeResult(DocumentChangeStatus.Applied, 3), late);
+        Assert.Equal(DocumentChangeStatus.Stale, stale.Status);
+        Assert.Equal("let a = 2; let c = a;\n", store.GetCurrent("a.txt")!.Text.ToString());
+    }
+
+    [Fact]
+    public void Change_RangeOutsideText_RequiresResyncAndKeepsText()
+    {
+        // Arrange
+        var store = new DocumentStore();
+        store.Open("a.txt", 1, "let a = 1;\n");
+
+        // Act
+        var result = store.Change("a.txt", 2, new[] { Replace(0, 0, 0, 0, "// "), Replace(0, 4, 0, 40, "b") });
+        var resynced = store.Resync("a.txt", 7, "let b = 1;\n");
+        var next = store.Change("a.txt", 8, new[] { Replace(0, 4, 0, 5, "c") });
+
+        // Assert
+        Assert.Equal(DocumentChangeStatus.ResyncRequired, result.Status);
+        Assert.Contains("Change 2 of version 2", result.Reason);
+        Assert.Equal(new DocumentChangeResult(DocumentChangeStatus.Applied, 7), resynced);
+        Assert.Equal(DocumentChangeStatus.Applied, next.Status);
+        Assert.Equal("let c = 1;\n", store.GetCurrent("a.txt")!.Text.ToString());
+        Assert.Null(store.GetVersion("a.txt", 1));
+    }
+
+    [Fact]
+    public void Change_FullTextAfterGap_IsAppliedWithoutWaiting()
+    {
+        // Arrange
+        var store = new DocumentStore();
+        store.Open("a.txt", 1, "let a = 1;\n");
+        store.Change("a.txt", 3, new[] { Replace(0, 4, 0, 5, "b") });
+
+        // Act
+        var result = store.Change("a.txt", 5, new[] { Replace(0, 0, 0, 99, "ignored"), DocumentChange.FullText("let z = 0;\n") });
+
+        // Assert
+        Assert.Equal(new DocumentChangeResult(DocumentChangeStatus.Applied, 5), result);
+        Assert.Equal("let z = 0;\n", store.GetCurrent("a.txt")!.Text.ToString());
+        Assert.Equal(DocumentChangeStatus.Stale, store.Change("a.txt", 4, new[] { Replace(0, 0, 0, 0, "x") }).Status);
+    }
+
+    [Fact]
+    public void Change_DocumentNotOpen_RequiresResync()
+    {
+        // Arrange
+        var store = new DocumentStore();
+        store.Open("a.txt", 1, "let a = 1;\n");
+        store.Close("a.txt");
+
+        // Act
+        var result = store.Change("a.txt", 2, new[] { Replace(0, 0, 0, 0, "x") });
+
+        // Assert
+        Assert.Equal(new DocumentChangeResult(DocumentChangeStatus.ResyncRequired, -1, "Document 'a.txt' is not open"), result);
+    }
+
+    [Fact]
+    public void MapRange_LateResultForOlderVersion_FollowsLaterEdits()
+    {
+        // Arrange
+        var store = new DocumentStore();
+        store.Open("a.txt", 1, "let a = 1;\nlet b = a;\n");
+        store.Change("a.txt", 2, new[] { Replace(0, 0, 0, 0, "// c\n") });
+        store.Change("a.txt", 3, new[] { Replace(2, 8, 2, 9, "x") });
+
+        // Act
+        var moved = store.MapRange("a.txt", 1, new TextRange(4, 1));
+        var edited = store.MapRange("a.txt", 1, new TextRange(19, 1));
+        var dropped = store.MapRange("a.txt", 0, new TextRange(4, 1));
+
+        // Assert
+        Assert.Equal(new TextRange(9, 1), moved);
+        Assert.Null(edited);
+        Assert.Null(dropped);
+    }
+
+    [Fact]
+    public void Change_EditBatch_ReparsesWithComposedEdit()
+    {
+        // Arrange
+        var store = new DocumentStore(CreateParser());
+        store.Open("a.txt", 1, "let a = 1;\nlet b = 2;\n");
+
+        // Act
+        store.Change("a.txt", 2, new[] { Replace(1, 8, 1, 9, "a"), Replace(0, 4, 0, 5, "c"), Replace(1, 8, 1, 9, "c") });
+        var parse = store.GetParse("a.txt")!;
+
+        // Assert
+        Assert.True(parse.IsSuccess);
+        Assert.Equal("let c = 1;\nlet b = c;\n", parse.Input);
+    }
+
+    [Theory]
+    [InlineData(1)]
+    [InlineData(2)]
+    [InlineData(3)]
+    public void Change_RandomBatchesDeliveredOutOfOrder_MatchReferenceText(int seed)
+    {
+        // Arrange
+        var random = new Random(seed);
+        var store = new DocumentStore(CreateParser(), new DocumentStoreOptions { RetainedVersions = 100 });
+        var (initial, versions) = GenerateVersions(random, 60);
+        store.Open("a.txt", 1, initial);
+
+        // Act
+        foreach (var (version, changes, _) in Shuffle(random, versions))
+        {
+            store.Change("a.txt", version, changes);
+        }
+
+        // Assert
+        Assert.Equal(versions[^1].Text, store.GetCurrent("a.txt")!.Text.ToString());
+        Assert.Equal(versions[^1].Text, store.GetParse("a.txt")!.Input);
+        foreach (var (version, _, text) in versions)
+        {
+            // Versions overtaken by a later whole-text version are never applied, so they are not retained.
+            if (store.GetVersion("a.txt", version) is { } kept)
+            {
+                Assert.Equal(text, kept.Text.ToString());
+            }
+        }
+    }
+
+    [Fact]
+    public async Task Change_ConcurrentDeliveryAcrossDocuments_MatchesReferenceText()
+    {
+        // Arrange
+        var store = new DocumentStore(CreateParser());
+        var documents = Enumerable.Range(0, 4).Select(i =>
+        {
+            var (initial, versions) = GenerateVersions(new Random(100 + i), 40);
+            store.Open($"d{i}.txt", 1, initial);
+            return (Path: $"d{i}.txt", Versions: versions);
+        }).ToList();
+
+        // Act
+        await Task.WhenAll(documents.SelectMany(d => d.Versions.Select(v => Task.Run(() => store.Change(d.Path, v.Version, v.Changes)))));
+
+        // Assert
+        foreach (var (path, versions) in documents)
+        {
+            Assert.Equal(versions[^1].Text, store.GetCurrent(path)!.Text.ToString());
+            Assert.Equal(versions[^1].Text, store.GetParse(path)!.Input);
+        }
+    }
+
+    private static (string Initial, List<(int Version, IReadOnlyList<DocumentChange> Changes, string Text)> Versions) GenerateVersions(Random random, int count)
+    {
+        const string Alphabet = "let a=1;\n";
+        var text = "let a = 1;\n";
+        var initial = text;
+        var versions = new List<(int, IReadOnlyList<DocumentChange>, string)>();
+        for (var version = 2; version < count + 2; version++)
+        {
+            var changes = new List<DocumentChange>();
+            for (var i = random.Next(1, 4); i > 0; i--)
+            {
+                var insert = new string(Enumerable.Range(0, random.Next(4)).Select(_ => Alphabet[random.Next(Alphabet.Length)]).ToArray());
+                if (random.Next(20) == 0)
+                {
+                    changes.Add(DocumentChange.FullText(insert));
+                    text = insert;
+                    continue;
+                }
+
+                var start = random.Next(text.Length + 1);
+                var end = start + random.Next(Math.Min(6, text.Length - start) + 1);
+                changes.Add(DocumentChange.Replace(Position(text, start), Position(text, end), insert));
+                text = string.Concat(text.AsSpan(0, start), insert, text.AsSpan(end));
+            }
+
+            versions.Add((version, changes, text));
+        }
+
+        return (initial, versions);
+    }
+
+    private static IEnumerable<T> Shuffle<T>(Random random, IReadOnlyList<T> items)
+    {
+        // Items move at most a few places, as notifications handled on different threads do.
+        var order = items.Select((item, index) => (item, key: index + random.Next(5))).OrderBy(p => p.key).Select(p => p.item);
+        return order.ToList();
+    }
+
+    private static DocumentPosition Position(string text, int offset)
+    {
+        var lineStart = offset == 0 ? 0 : text.LastIndexOf('\n', offset - 1) + 1;
+        return new DocumentPosition(text.AsSpan(0, offset).Count('\n'), offset - lineStart);
+    }
+
+    private static DocumentChange Replace(int startLine, int startCharacter, int endLine, int endCharacter, string text)
+    {
+        return DocumentChange.Replace(new DocumentPosition(startLine, startCharacter), new DocumentPosition(endLine, endCharacter), text);
+    }
+
+    private static GeneralizedParser CreateParser()
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(StatementGrammar)));
+    }
+}
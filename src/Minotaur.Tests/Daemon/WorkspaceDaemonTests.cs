@@ -0,0 +1,315 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.Daemon;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Monitoring;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Daemon;
+
+/// <summary>
+/// Tests for workspace daemon functionality
+/// </summary>
+public sealed class WorkspaceDaemonTests : IDisposable
+{
+    private const string ModuleGrammar = """
+        DeclarationRules: definition
+        ExportRules: export
+        ImportQuery: import > IDENTIFIER
+
+        <program> ::= <item> | <program> <item>
+        <item> ::= <import> | <export> | <definition> | <statement>
+        <import> ::= "use" <IDENTIFIER> ";"
+        <export> ::= "pub" <definition>
+        <definition> ::= "let" <IDENTIFIER> "=" <expr> ";"
+        <statement> ::= "print" <expr> ";"
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER>
+        """;
+
+    private static readonly TimeSpan Timeout = TimeSpan.FromSeconds(30);
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"daemon_{Guid.NewGuid():N}");
+    private readonly string _socket = Path.Combine(Path.GetTempPath(), $"mtd_{Guid.NewGuid():N}.sock");
+
+    public WorkspaceDaemonTests()
+    {
+        Directory.CreateDirectory(_directory);
+        File.WriteAllText(Path.Combine(_directory, "m0.mod"), "pub let v0 = 1;\n");
+        File.WriteAllText(Path.Combine(_directory, "m1.mod"), "use m0;\nprint v0;\n");
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_directory, recursive: true);
+        File.Delete(_socket);
+    }
+
+    [Fact]
+    public async Task Diagnostics_FileEditedOnDisk_ReportsTheUpdatedState()
+    {
+        // Arrange
+        var (daemon, run) = await StartAsync();
+        await using var client = await DaemonClient.ConnectAsync(_socket);
+        Assert.Empty(await client.GetDiagnosticsAsync("m1.mod"));
+        var generation = daemon.Generation;
+
+        // Act
+        File.WriteAllText(Path.Combine(_directory, "m0.mod"), "pub let w0 = 1;\n");
+        var diagnostics = await PollAsync(() => client.GetDiagnosticsAsync("m1.mod"), d => d.Count > 0);
+
+        // Assert
+        var diagnostic = Assert.Single(diagnostics);
+        Assert.Equal(DiagnosticCodes.UnresolvedReference, diagnostic.Code);
+        Assert.Contains("'v0'", diagnostic.Message);
+        Assert.Equal("m1.mod", diagnostic.Location!.SourceFile);
+        Assert.True(daemon.Generation > generation);
+        Assert.Equal("m0.mod", Assert.Single(await client.FindExportsAsync("w0")));
+
+        await client.ShutdownAsync();
+        await run.WaitAsync(Timeout);
+    }
+
+    [Fact]
+    public async Task Diagnostics_FileCreatedAndDeleted_AddsAndRemovesTheDocument()
+    {
+        // Arrange
+        var (_, run) = await StartAsync();
+        await using var client = await DaemonClient.ConnectAsync(_socket);
+
+        // Act
+        File.WriteAllText(Path.Combine(_directory, "m2.mod"), "use m0;\nprint v9;\n");
+        var created = await PollAsync(() => client.GetDiagnosticsAsync("m2.mod"), d => d.Count > 0);
+        File.Delete(Path.Combine(_directory, "m2.mod"));
+        var remaining = await PollAsync(() => client.InvokeAsync("diagnostics"), r => r.GetProperty("files").GetArrayLength() == 2);
+
+        // Assert
+        Assert.Contains("'v9'", Assert.Single(created).Message);
+        Assert.DoesNotContain(remaining.GetProperty("files").EnumerateArray(), f => f.GetProperty("path").GetString() == "m2.mod");
+
+        await client.ShutdownAsync();
+        await run.WaitAsync(Timeout);
+    }
+
+    [Fact]
+    public async Task Requests_DuringALongParse_AreAnsweredWithoutWaitingForIt()
+    {
+        // Arrange
+        var (_, run) = await StartAsync();
+        await using var client = await DaemonClient.ConnectAsync(_socket);
+        var unsaved = new StringBuilder("use m0;\n");
+        for (var i = 0; i < 20_000; i++)
+        {
+            unsaved.Append("print v0 + ").Append(i).Append(";\n");
+        }
+
+        // Act
+        var longParse = client.ParseFileAsync("m1.mod", unsaved.ToString());
+        var diagnostics = client.GetDiagnosticsAsync("m1.mod");
+        var first = await Task.WhenAny(longParse, diagnostics);
+        var parsed = await longParse.WaitAsync(Timeout);
+
+        // Assert
+        Assert.Same(diagnostics, first);
+        Assert.True(parsed.IsSuccess);
+        Assert.Empty(parsed.Diagnostics);
+        Assert.Equal(new[] { "m0" }, await client.QueryAsync("m1.mod", "import > IDENTIFIER"));
+
+        await client.ShutdownAsync();
+        await run.WaitAsync(Timeout);
+    }
+
+    [Fact]
+    public async Task GetHighlightsAsync_Declaration_ReturnsItsOccurrences()
+    {
+        // Arrange
+        File.WriteAllText(Path.Combine(_directory, "m2.mod"), "let a = 1;\nprint a + a;\n");
+        var (_, run) = await StartAsync();
+        await using var client = await DaemonClient.ConnectAsync(_socket);
+
+        // Act
+        var highlights = await client.GetHighlightsAsync("m2.mod", 1, 5);
+
+        // Assert
+        Assert.Equal(new[] { DocumentHighlightKind.Write, DocumentHighlightKind.Read, DocumentHighlightKind.Read }, highlights.Select(h => h.Kind));
+        Assert.Equal(new[] { 1, 2, 2 }, highlights.Select(h => h.Location.Line));
+        Assert.Equal("m2.mod", highlights[0].Location.SourceFile);
+
+        await client.ShutdownAsync();
+        await run.WaitAsync(Timeout);
+    }
+
+    [Fact]
+    public async Task ParseFileAsync_OpenDocumentChangedOutOfOrder_ParsesTheLatestVersion()
+    {
+        // Arrange
+        var (_, run) = await StartAsync();
+        await using var client = await DaemonClient.ConnectAsync(_socket);
+        await client.OpenDocumentAsync("m1.mod", 1, "use m0;\nprint v0;\n");
+
+        // Act
+        var early = await client.ChangeDocumentAsync("m1.mod", 3, new[] { DocumentChange.Replace(new(1, 8), new(1, 8), " +") });
+        var late = await client.ChangeDocumentAsync("m1.mod", 2, new[] { DocumentChange.Replace(new(1, 8), new(1, 8), " + 1") });
+        var invalid = await client.ChangeDocumentAsync("m1.mod", 4, new[] { DocumentChange.Replace(new(5, 0), new(5, 0), "x") });
+        var (isSuccess, diagnostics) = await client.ParseFileAsync("m1.mod");
+
+        // Assert
+        Assert.Equal(DocumentChangeStatus.Buffered, early.Status);
+        Assert.Equal(new DocumentChangeResult(DocumentChangeStatus.Applied, 3), late);
+        Assert.Equal(DocumentChangeStatus.ResyncRequired, invalid.Status);
+        Assert.False(isSuccess);
+        Assert.NotEmpty(diagnostics);
+        Assert.True(await client.CloseDocumentAsync("m1.mod"));
+        Assert.True((await client.ParseFileAsync("m1.mod")).IsSuccess);
+
+        await client.ShutdownAsync();
+        await run.WaitAsync(Timeout);
+    }
+
+    [Fact]
+    public async Task InvokeAsync_UnknownMethodOrMissingParameter_ReturnsAnError()
+    {
+        // Arrange
+        var (_, run) = await StartAsync();
+        await using var client = await DaemonClient.ConnectAsync(_socket);
+
+        // Act
+        var unknown = await Assert.ThrowsAsync<DaemonException>(() => client.InvokeAsync("format"));
+        var missing = await Assert.ThrowsAsync<DaemonException>(() => client.InvokeAsync("query", new { path = "m1.mod" }));
+        var outside = await Assert.ThrowsAsync<DaemonException>(() => client.QueryAsync("m9.mod", "import"));
+
+        // Assert
+        Assert.Equal(-32601, unknown.Code);
+        Assert.Equal(-32602, missing.Code);
+        Assert.Contains("'selector'", missing.Message);
+        Assert.Contains("not in the workspace", outside.Message);
+
+        await client.ShutdownAsync();
+        await run.WaitAsync(Timeout);
+        Assert.False(File.Exists(_socket));
+    }
+
+    [Fact]
+    public async Task CollectGarbage_AllDocumentsClosed_ReturnsToBaseline()
+    {
+        // Arrange
+        var (daemon, run) = await StartAsync();
+        await using var client = await DaemonClient.ConnectAsync(_socket);
+        daemon.CollectGarbage();
+        var baseline = daemon.GetMemoryReport();
+        for (var i = 0; i < 50; i++)
+        {
+            await client.OpenDocumentAsync($"open{i}.mod", 1, $"let local{i} = 1;\nprint local{i} + v0;\n");
+        }
+
+        var opened = daemon.GetMemoryReport();
+        for (var i = 0; i < 50; i++)
+        {
+            await client.CloseDocumentAsync($"open{i}.mod");
+        }
+
+        // Act
+        var result = daemon.CollectGarbage();
+        var closed = daemon.GetMemoryReport();
+
+        // Assert
+        Assert.Equal(50, opened.Get(MemoryCategories.OpenDocuments)!.Count);
+        Assert.True(opened.Get(MemoryCategories.InternedStrings)!.Count >= baseline.Get(MemoryCategories.InternedStrings)!.Count + 50);
+        Assert.Equal(50, result.DroppedStrings);
+        Assert.Equal(0, closed.Get(MemoryCategories.OpenDocuments)!.Count);
+        Assert.Equal(baseline.Get(MemoryCategories.InternedStrings)!.Count, closed.Get(MemoryCategories.InternedStrings)!.Count);
+        Assert.Equal(baseline.Get(MemoryCategories.CachedQueries)!.Count, closed.Get(MemoryCategories.CachedQueries)!.Count);
+
+        await client.ShutdownAsync();
+        await run.WaitAsync(Timeout);
+    }
+
+    [Fact]
+    public async Task CollectGarbage_DuringParallelParses_AnswersEveryRequestCorrectly()
+    {
+        // Arrange
+        var (daemon, run) = await StartAsync(new WorkspaceDaemonOptions { SearchPattern = "*.mod", CollectionThreshold = 1 });
+        await using var client = await DaemonClient.ConnectAsync(_socket);
+        using var done = new CancellationTokenSource();
+        var collector = Task.Run(async () =>
+        {
+            while (!done.IsCancellationRequested)
+            {
+                daemon.CollectGarbage();
+                await Task.Yield();
+            }
+        });
+
+        // Act
+        var requests = Enumerable.Range(0, 200).Select(async i =>
+        {
+            var path = $"p{i % 20}.mod";
+            var valid = i % 3 != 0;
+            var text = valid ? $"let name{i % 40} = v0;\nprint name{i % 40};\n" : $"let name{i % 40} = ;\n";
+            var (isSuccess, diagnostics) = await client.ParseFileAsync(path, text);
+            if (i % 2 == 0)
+            {
+                await client.OpenDocumentAsync(path, i, text);
+                await client.CloseDocumentAsync(path);
+            }
+
+            return (Valid: valid, isSuccess, diagnostics.Count);
+        }).ToList();
+        var results = await Task.WhenAll(requests);
+        done.Cancel();
+        await collector;
+
+        // Assert
+        Assert.All(results, r => Assert.Equal(r.Valid, r.isSuccess));
+        Assert.All(results.Where(r => r.Valid), r => Assert.Equal(0, r.Count));
+        Assert.True(daemon.Collections > 0);
+        Assert.Equal("m0.mod", Assert.Single(await client.FindExportsAsync("v0")));
+        Assert.Empty(await client.GetDiagnosticsAsync("m1.mod"));
+
+        await client.ShutdownAsync();
+        await run.WaitAsync(Timeout);
+    }
+
+    private async Task<(WorkspaceDaemon Daemon, Task Run)> StartAsync(WorkspaceDaemonOptions? options = null)
+    {
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(ModuleGrammar));
+        var daemon = new WorkspaceDaemon(new GeneralizedParser(grammar), _directory, options ?? new WorkspaceDaemonOptions { SearchPattern = "*.mod" });
+        var run = daemon.RunAsync(_socket);
+        await daemon.Started.WaitAsync(Timeout);
+        return (daemon, run);
+    }
+
+    private static async Task<T> PollAsync<T>(Func<Task<T>> request, Func<T, bool> done)
+    {
+        var deadline = DateTime.UtcNow + Timeout;
+        while (true)
+        {
+            var result = await request();
+            if (done(result) || DateTime.UtcNow > deadline)
+            {
+                return result;
+            }
+
+            await Task.Delay(20);
+        }
+    }
+}
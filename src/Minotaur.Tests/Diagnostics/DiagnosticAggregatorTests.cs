@@ -0,0 +1,168 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Tests.Diagnostics;
+
+/// <summary>
+/// Tests for diagnostic aggregation functionality
+/// </summary>
+public class DiagnosticAggregatorTests
+{
+    [Fact]
+    public void Complete_CodeRepeatedInFile_CollapsesPastFileLimit()
+    {
+        // Arrange
+        var aggregator = new DiagnosticAggregator(new DiagnosticLimits { MaxPerCodePerFile = 10 });
+        aggregator.Add("a.rs", Unused("a.rs", 15));
+
+        // Act
+        var aggregation = aggregator.Complete();
+
+        // Assert
+        Assert.Equal(11, aggregation.Reported.Count);
+        Assert.All(aggregation.Reported.Take(10), d => Assert.False(d.Data.ContainsKey("aggregate")));
+        var aggregate = aggregation.Reported[10];
+        Assert.Equal(DiagnosticCodes.UnusedSymbol, aggregate.Code);
+        Assert.Equal(DiagnosticSeverity.Warning, aggregate.Severity);
+        Assert.Equal(5, aggregate.Data["count"]);
+        Assert.StartsWith("5 more W0002 diagnostics not shown (15 in 1 files); e.g. at a.rs:11:1, a.rs:12:1", aggregate.Message);
+        Assert.Equal(5, ((List<SourcePosition>)aggregate.Data["locations"]).Count);
+        Assert.Equal(new DiagnosticCodeTotal(DiagnosticCodes.UnusedSymbol, 15, 10, 1), Assert.Single(aggregation.Totals));
+        Assert.Null(aggregation.All);
+    }
+
+    [Fact]
+    public void Complete_CodeRepeatedInRun_KeepsFirstFilesInPathOrder()
+    {
+        // Arrange
+        var aggregator = new DiagnosticAggregator(new DiagnosticLimits { MaxPerCodePerRun = 6, SampledLocations = 2 });
+        aggregator.Add("c.rs", Unused("c.rs", 4));
+        aggregator.Add("a.rs", Unused("a.rs", 4));
+        aggregator.Add("b.rs", Unused("b.rs", 4));
+
+        // Act
+        var aggregation = aggregator.Complete();
+
+        // Assert
+        Assert.Equal(7, aggregation.Reported.Count);
+        Assert.Equal(
+            new[] { "a.rs", "a.rs", "a.rs", "a.rs", "b.rs", "b.rs" },
+            aggregation.Reported.Take(6).Select(d => d.Location!.SourceFile));
+        Assert.Equal(6, aggregation.Reported[6].Data["count"]);
+        Assert.Equal(2, ((List<SourcePosition>)aggregation.Reported[6].Data["locations"]).Count);
+        var total = Assert.Single(aggregation.Totals);
+        Assert.Equal((12, 6, 6, 3), (total.Total, total.Reported, total.Collapsed, total.Files));
+    }
+
+    [Fact]
+    public void Complete_KeepAll_ReturnsEveryDiagnosticInFileOrder()
+    {
+        // Arrange
+        var aggregator = new DiagnosticAggregator(new DiagnosticLimits { MaxPerCodePerFile = 1 }, keepAll: true);
+        aggregator.Add("b.rs", Unused("b.rs", 3));
+        aggregator.Add("a.rs", Unused("a.rs", 2));
+
+        // Act
+        var aggregation = aggregator.Complete();
+
+        // Assert
+        Assert.Equal(3, aggregation.Reported.Count);
+        Assert.Equal(5, aggregation.All!.Count);
+        Assert.Equal(new[] { "a.rs", "a.rs", "b.rs", "b.rs", "b.rs" }, aggregation.All.Select(d => d.Location!.SourceFile));
+    }
+
+    [Fact]
+    public void AddParallel_ManyFiles_MergesToSequentialResult()
+    {
+        // Arrange
+        var limits = new DiagnosticLimits { MaxPerCodePerFile = 3, MaxPerCodePerRun = 40, SampledLocations = 4 };
+        var files = Enumerable.Range(0, 64).Select(i => $"src/file{i:D2}.rs").ToList();
+        var sequential = new DiagnosticAggregator(limits);
+        foreach (var file in files)
+        {
+            sequential.Add(file, Mixed(file));
+        }
+
+        var parallel = new DiagnosticAggregator(limits);
+
+        // Act
+        parallel.AddParallel(files.AsEnumerable().Reverse(), file => (file, Mixed(file)), maxDegreeOfParallelism: 8);
+
+        // Assert
+        var expected = sequential.Complete();
+        var actual = parallel.Complete();
+        Assert.Equal(expected.Totals, actual.Totals);
+        Assert.Equal(expected.Reported.Select(d => d.ToString()), actual.Reported.Select(d => d.ToString()));
+        Assert.Equal(
+            new[] { (DiagnosticCodes.UnusedSymbol, 64 * 7, 40, 64), (DiagnosticCodes.UndeclaredSymbol, 64 * 2, 40, 64) }.OrderBy(t => t.Item1),
+            actual.Totals.Select(t => (t.Code, t.Total, t.Reported, t.Files)));
+        Assert.Equal(64 * 7 - 40, actual.Reported.Single(d => d.Code == DiagnosticCodes.UnusedSymbol && d.Data.ContainsKey("aggregate")).Data["count"]);
+    }
+
+    [Fact]
+    public void Merge_SplitRun_EqualsSingleAggregator()
+    {
+        // Arrange
+        var limits = new DiagnosticLimits { MaxPerCodePerFile = 2, MaxPerCodePerRun = 5, SampledLocations = 3 };
+        var whole = new DiagnosticAggregator(limits);
+        var first = new DiagnosticAggregator(limits);
+        var second = new DiagnosticAggregator(limits);
+        foreach (var (file, index) in new[] { "a.rs", "b.rs", "c.rs", "d.rs" }.Select((f, i) => (f, i)))
+        {
+            whole.Add(file, Unused(file, 4));
+            (index % 2 == 0 ? second : first).Add(file, Unused(file, 4));
+        }
+
+        // Act
+        first.Merge(second);
+
+        // Assert
+        var expected = whole.Complete();
+        var actual = first.Complete();
+        Assert.Equal(expected.Totals, actual.Totals);
+        Assert.Equal(expected.Reported.Select(d => d.ToString()), actual.Reported.Select(d => d.ToString()));
+        Assert.Equal(expected.Reported[^1].Message, actual.Reported[^1].Message);
+    }
+
+    private static List<Diagnostic> Unused(string file, int count)
+    {
+        return Enumerable.Range(0, count).Select(i => new Diagnostic
+        {
+            Code = DiagnosticCodes.UnusedSymbol,
+            Severity = DiagnosticSeverity.Warning,
+            Message = $"'x{i}' is declared but never used",
+            Location = new SourcePosition(i + 1, 1, i * 10, 2) { SourceFile = file }
+        }).ToList();
+    }
+
+    private static List<Diagnostic> Mixed(string file)
+    {
+        var diagnostics = Unused(file, 7);
+        diagnostics.AddRange(Enumerable.Range(0, 2).Select(i => new Diagnostic
+        {
+            Code = DiagnosticCodes.UndeclaredSymbol,
+            Severity = DiagnosticSeverity.Warning,
+            Message = $"'{Path.GetFileNameWithoutExtension(file)}_{i}' is used but never declared",
+            Location = new SourcePosition(20 + i, 5, 200 + i * 10, 4) { SourceFile = file }
+        }));
+        return diagnostics;
+    }
+}
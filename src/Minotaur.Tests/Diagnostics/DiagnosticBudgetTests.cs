@@ -0,0 +1,148 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Projects.Grammar;
+
+namespace Minotaur.Tests.Diagnostics;
+
+/// <summary>
+/// Tests for diagnostic budget functionality
+/// </summary>
+public class DiagnosticBudgetTests
+{
+    [Fact]
+    public void Parse_JsonWithRustGrammar_ReportsWrongGrammarOnce()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+        var json = File.ReadAllText(ExamplePath("data_formats", "json", "complex_structure.json"));
+        var unlimited = parser.Parse(json);
+
+        // Act
+        var result = parser.Parse(json, new ParseOptions { DiagnosticBudget = new DiagnosticBudget() });
+
+        // Assert
+        Assert.True(unlimited.Diagnostics.Count > 100);
+        Assert.Equal(new[] { DiagnosticCodes.WrongGrammar, DiagnosticCodes.TooManyErrors }, result.Diagnostics.Select(d => d.Code));
+        Assert.StartsWith("This file does not appear to be RustItems", result.Diagnostics[0].Message);
+        Assert.Contains("grammar detection", result.Diagnostics[0].Message);
+        Assert.Equal(unlimited.Diagnostics.Count, result.Diagnostics[1].Data["suppressed"]);
+        Assert.Contains($"{DiagnosticCodes.UnrecognizedCharacter} (", result.Diagnostics[1].Message);
+    }
+
+    [Fact]
+    public void Parse_RustWithOneTypo_DoesNotReportWrongGrammar()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+        var rust = File.ReadAllText(ExamplePath("programming", "rust_items", "input.rs")).Replace("let start", "let start start");
+
+        // Act
+        var result = parser.Parse(rust, new ParseOptions { DiagnosticBudget = new DiagnosticBudget() });
+
+        // Assert
+        var error = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, error.Code);
+    }
+
+    [Fact]
+    public void Parse_ErrorsPastFileLimit_AreSummarizedAndTreeIsComplete()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+        var rust = File.ReadAllText(ExamplePath("programming", "rust_items", "input.rs")) + "\n@@@@@@@@@@ $$\n";
+        var budget = new DiagnosticBudget(new DiagnosticLimits { MaxErrorsPerFile = 3, WrongGrammarWindow = 0 });
+
+        // Act
+        var result = parser.Parse(rust, new ParseOptions { DiagnosticBudget = budget });
+
+        // Assert
+        Assert.NotNull(result.Tree);
+        Assert.Equal(4, result.Diagnostics.Count);
+        Assert.All(result.Diagnostics.Take(3), d => Assert.Equal(DiagnosticCodes.UnrecognizedCharacter, d.Code));
+        var summary = result.Diagnostics[3];
+        Assert.Equal(DiagnosticCodes.TooManyErrors, summary.Code);
+        Assert.Equal("Suppressed 9 additional errors; most frequent: E0003 (9)", summary.Message);
+        Assert.Equal(4, summary.Location!.Column);
+        Assert.Equal(9, budget.SuppressedErrors);
+        Assert.Equal(3, budget.ReportedErrors);
+    }
+
+    [Fact]
+    public void Apply_RunLimit_SharedAcrossParses()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+        var rust = "fn f() {}\n@@@@\n";
+        var budget = new DiagnosticBudget(new DiagnosticLimits { MaxErrorsPerRun = 5, WrongGrammarWindow = 0 });
+
+        // Act
+        var first = parser.Parse(rust, new ParseOptions { DiagnosticBudget = budget });
+        var second = parser.Parse(rust, new ParseOptions { DiagnosticBudget = budget });
+        var summary = budget.CreateRunSummary();
+
+        // Assert
+        Assert.Equal(4, first.Diagnostics.Count);
+        Assert.Equal(new[] { DiagnosticCodes.UnrecognizedCharacter, DiagnosticCodes.TooManyErrors }, second.Diagnostics.Select(d => d.Code));
+        Assert.NotNull(summary);
+        Assert.Equal("Suppressed 3 errors after reporting 5; most frequent: E0003 (3)", summary!.Message);
+    }
+
+    [Fact]
+    public async Task LoadFromFileAsync_DiagnosticLimits_ReadsThresholds()
+    {
+        // Arrange
+        var path = Path.GetTempFileName();
+        await File.WriteAllTextAsync(path, """
+            {
+              "diagnosticLimits": { "maxErrorsPerFile": 20, "maxErrorsPerRun": 200, "wrongGrammarWindow": 40, "wrongGrammarErrorDensity": 0.5 }
+            }
+            """);
+
+        try
+        {
+            // Act
+            var configuration = await GrammarConfiguration.LoadFromFileAsync(path);
+
+            // Assert
+            Assert.Equal(20, configuration.DiagnosticLimits.MaxErrorsPerFile);
+            Assert.Equal(200, configuration.DiagnosticLimits.MaxErrorsPerRun);
+            Assert.Equal(40, configuration.DiagnosticLimits.WrongGrammarWindow);
+            Assert.Equal(0.5, configuration.DiagnosticLimits.WrongGrammarErrorDensity);
+        }
+        finally
+        {
+            File.Delete(path);
+        }
+    }
+
+    private static GeneralizedParser CreateRustParser()
+    {
+        var grammar = File.ReadAllText(ExamplePath("programming", "rust_items", "rust_items.grammar"));
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(grammar)));
+    }
+
+    private static string ExamplePath(string category, string folder, string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", category, folder, file);
+    }
+}
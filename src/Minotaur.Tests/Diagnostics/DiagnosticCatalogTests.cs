@@ -0,0 +1,136 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Reflection;
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Diagnostics;
+
+/// <summary>
+/// Tests for diagnostic catalog functionality
+/// </summary>
+public class DiagnosticCatalogTests
+{
+    [Fact]
+    public void Default_RegistersEveryDiagnosticCode()
+    {
+        // Arrange
+        var codes = typeof(DiagnosticCodes)
+            .GetFields(BindingFlags.Public | BindingFlags.Static)
+            .Where(f => f.IsLiteral)
+            .Select(f => (string)f.GetRawConstantValue()!);
+
+        // Act & Assert
+        Assert.All(codes, code =>
+        {
+            var descriptor = DiagnosticCatalog.Default.Get(code);
+            Assert.NotNull(descriptor);
+            Assert.Equal(code.StartsWith('W') ? DiagnosticSeverity.Warning : DiagnosticSeverity.Error, descriptor.Severity);
+            Assert.False(string.IsNullOrWhiteSpace(descriptor.Explanation));
+        });
+    }
+
+    [Fact]
+    public void Default_ExamplesFailAndAreFixedAsClaimed()
+    {
+        // Arrange
+        var examples = DiagnosticCatalog.Default.Descriptors.Where(d => d.Example != null).ToList();
+
+        // Act & Assert
+        Assert.NotEmpty(examples);
+        Assert.All(examples, descriptor =>
+        {
+            var example = descriptor.Example!;
+            var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(example.Grammar)));
+            Assert.Contains(parser.Parse(example.Failing).Diagnostics, d => d.Code == descriptor.Code);
+            Assert.DoesNotContain(parser.Parse(example.Fixed).Diagnostics, d => d.Severity == DiagnosticSeverity.Error);
+        });
+    }
+
+    [Fact]
+    public void Get_LowerCaseCode_FindsDescriptor()
+    {
+        // Act
+        var descriptor = DiagnosticCatalog.Default.Get("e0001");
+
+        // Assert
+        Assert.NotNull(descriptor);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, descriptor.Code);
+        Assert.Null(descriptor.Grammar);
+    }
+
+    [Fact]
+    public void RegisterGrammar_DiagnosticMetadata_RegistersGrammarCodes()
+    {
+        // Arrange
+        var catalog = new DiagnosticCatalog();
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read("""
+            Grammar: Statements
+            Diagnostic_STM001: Missing ';' after {statement} | Every statement ends with a semicolon.
+            Diagnostic_WSTM002: Empty block
+
+            <program> ::= <IDENTIFIER> ";"
+            """));
+
+        // Act
+        var registered = catalog.RegisterGrammar(grammar);
+
+        // Assert
+        Assert.Equal(2, registered.Count);
+        var error = catalog.Get("STM001");
+        Assert.NotNull(error);
+        Assert.Equal(DiagnosticSeverity.Error, error.Severity);
+        Assert.Equal("Missing ';' after {statement}", error.Message);
+        Assert.Equal("Every statement ends with a semicolon.", error.Explanation);
+        Assert.Equal("Statements", error.Grammar);
+        Assert.Equal(DiagnosticSeverity.Warning, catalog.Get("WSTM002")!.Severity);
+    }
+
+    [Fact]
+    public void Register_SameCodeWithOtherMeaning_Throws()
+    {
+        // Arrange
+        var catalog = new DiagnosticCatalog();
+        catalog.Register(new DiagnosticDescriptor("X001", DiagnosticSeverity.Error, "First", "First meaning"));
+
+        // Act
+        catalog.Register(new DiagnosticDescriptor("X001", DiagnosticSeverity.Error, "First", "First meaning"));
+        var exception = Assert.Throws<InvalidOperationException>(() =>
+            catalog.Register(new DiagnosticDescriptor("x001", DiagnosticSeverity.Error, "Second", "Second meaning")));
+
+        // Assert
+        Assert.Contains("X001", exception.Message);
+        Assert.Equal("First", catalog.Get("X001")!.Message);
+    }
+
+    [Fact]
+    public void ToSarif_RegisteredCode_DescribesRule()
+    {
+        // Arrange
+        var diagnostic = new Diagnostic { Code = DiagnosticCodes.UnexpectedToken, Message = "Unexpected ';'" };
+
+        // Act
+        var sarif = new DiagnosticFormatter().ToSarif(new[] { diagnostic });
+
+        // Assert
+        Assert.Contains("\"rules\"", sarif);
+        Assert.Contains("\"fullDescription\"", sarif);
+    }
+}
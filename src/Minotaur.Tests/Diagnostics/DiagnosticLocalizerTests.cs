@@ -0,0 +1,236 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Reflection;
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Diagnostics;
+
+/// <summary>
+/// Tests for diagnostic localizer functionality
+/// </summary>
+public sealed class DiagnosticLocalizerTests : IDisposable
+{
+    private const string StatementGrammar = """
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= "let" <IDENTIFIER> "=" <NUMBER> ";"
+        """;
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"localizer_{Guid.NewGuid():N}");
+
+    public void Dispose()
+    {
+        if (Directory.Exists(_directory))
+        {
+            Directory.Delete(_directory, recursive: true);
+        }
+    }
+
+    [Fact]
+    public void Render_SameDiagnosticInTwoLocales_SubstitutesPlaceholders()
+    {
+        // Arrange
+        var diagnostic = new Diagnostic { Code = DiagnosticCodes.UnusedSymbol }.WithMessage(DiagnosticCodes.UnusedSymbol, ("name", "total"));
+
+        // Act
+        var english = new DiagnosticLocalizer("en").Render(diagnostic);
+        var german = new DiagnosticLocalizer("de").Render(diagnostic);
+
+        // Assert
+        Assert.Equal("'total' is declared but never used", english);
+        Assert.Equal("'total' wird deklariert, aber nie verwendet", german);
+        Assert.Equal(english, diagnostic.Message);
+    }
+
+    [Fact]
+    public void Render_ParserError_TranslatesExpectedTerminals()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(StatementGrammar)));
+        var diagnostic = parser.Parse("let x = ;").Diagnostics.Single(d => d.Code == DiagnosticCodes.UnexpectedToken);
+
+        // Act
+        var german = new DiagnosticLocalizer("de").Render(diagnostic);
+
+        // Assert
+        Assert.StartsWith("Unexpected", diagnostic.Message);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, diagnostic.MessageKey);
+        Assert.StartsWith("Unerwartetes", german);
+        Assert.Contains("; erwartet: NUMBER", german);
+    }
+
+    [Fact]
+    public void Render_Counts_ChoosesPluralForm()
+    {
+        // Arrange
+        var localizer = new DiagnosticLocalizer("de");
+        Diagnostic Ambiguous(int count) => new Diagnostic { Code = DiagnosticCodes.AmbiguousParse }
+            .WithMessage(DiagnosticCodes.AmbiguousParse, ("rule", "expr"), ("count", count));
+
+        // Act
+        var one = localizer.Render(Ambiguous(1));
+        var three = localizer.Render(Ambiguous(3));
+
+        // Assert
+        Assert.Equal("Mehrdeutiges Parsen von <expr>: 1 Ableitung", one);
+        Assert.Equal("Mehrdeutiges Parsen von <expr>: 3 Ableitungen", three);
+        Assert.Equal("Ambiguous parse of <expr>: 3 derivations", Ambiguous(3).Message);
+    }
+
+    [Theory]
+    [InlineData("en", 1, "one")]
+    [InlineData("en", 0, "other")]
+    [InlineData("fr", 0, "one")]
+    [InlineData("ru", 21, "one")]
+    [InlineData("ru", 3, "few")]
+    [InlineData("ru", 11, "many")]
+    [InlineData("pl", 22, "few")]
+    [InlineData("ja", 1, "other")]
+    public void PluralRules_Select_ReturnsCategory(string locale, long count, string expected)
+    {
+        // Act & Assert
+        Assert.Equal(expected, PluralRules.Select(locale, count));
+    }
+
+    [Fact]
+    public void Render_LocaleWithoutCatalog_FallsBackToEnglishWithWarning()
+    {
+        // Arrange
+        var localizer = new DiagnosticLocalizer("fr-CA");
+        var diagnostic = new Diagnostic { Code = DiagnosticCodes.UnusedSymbol }.WithMessage(DiagnosticCodes.UnusedSymbol, ("name", "x"));
+
+        // Act
+        var message = localizer.Render(diagnostic);
+
+        // Assert
+        Assert.Equal("'x' is declared but never used", message);
+        Assert.Equal(new[] { "fr-CA", "fr", "en" }, localizer.FallbackChain);
+        Assert.Single(localizer.Warnings, w => w.Contains("'fr-CA'") && w.Contains(DiagnosticCodes.UnusedSymbol));
+    }
+
+    [Fact]
+    public void Render_RegionalLocale_UsesLanguageCatalogWithoutWarning()
+    {
+        // Arrange
+        var localizer = new DiagnosticLocalizer("de_AT.UTF-8");
+        var diagnostic = new Diagnostic { Code = DiagnosticCodes.UndeclaredSymbol }.WithMessage(DiagnosticCodes.UndeclaredSymbol, ("name", "y"));
+
+        // Act
+        var message = localizer.Render(diagnostic);
+
+        // Assert
+        Assert.Equal("de-AT", localizer.Locale);
+        Assert.Equal("'y' wird verwendet, aber nie deklariert", message);
+        Assert.Empty(localizer.Warnings);
+    }
+
+    [Fact]
+    public void Render_BrokenTranslation_FallsBackWithoutThrowing()
+    {
+        // Arrange
+        var localizer = new DiagnosticLocalizer("de").AddCatalog(MessageCatalog.Parse("W0002 = '{unknown}' ist unbenutzt", "de"));
+        var diagnostic = new Diagnostic { Code = DiagnosticCodes.UnusedSymbol }.WithMessage(DiagnosticCodes.UnusedSymbol, ("name", "x"));
+
+        // Act
+        var message = localizer.Render(diagnostic);
+
+        // Assert
+        Assert.Equal("'x' wird deklariert, aber nie verwendet", message);
+        Assert.Single(localizer.Warnings, w => w.Contains("cannot be formatted"));
+    }
+
+    [Fact]
+    public void LoadGrammarCatalogs_CatalogNextToGrammar_TranslatesGrammarCodes()
+    {
+        // Arrange
+        Directory.CreateDirectory(_directory);
+        var grammarPath = Path.Combine(_directory, "toy.grammar");
+        File.WriteAllText(grammarPath, StatementGrammar);
+        File.WriteAllText(Path.Combine(_directory, "toy.de.messages"),
+            "TOY001 = {count, plural, one {# Anweisung} other {# Anweisungen}} ohne Wirkung\n");
+        var english = MessageCatalog.Parse("TOY001 = {count, plural, one {# statement has} other {# statements have}} no effect", "en");
+        var diagnostic = new Diagnostic { Code = "TOY001" }.WithMessage(english, "TOY001", ("count", 2));
+        var localizer = new DiagnosticLocalizer("de");
+
+        // Act
+        var loaded = localizer.LoadGrammarCatalogs(grammarPath);
+
+        // Assert
+        Assert.Equal(1, loaded);
+        Assert.Equal("2 statements have no effect", diagnostic.Message);
+        Assert.Equal("2 Anweisungen ohne Wirkung", localizer.Render(diagnostic));
+    }
+
+    [Fact]
+    public void Format_FormattableArgument_UsesLocaleCulture()
+    {
+        // Act
+        var english = MessageFormat.Format("{ratio}", new Dictionary<string, object> { ["ratio"] = 0.5 }, CultureInfo.GetCultureInfo("en"));
+        var escaped = MessageFormat.Format(@"\{literal\} {names}", new Dictionary<string, object> { ["names"] = new[] { "a", "b" } });
+
+        // Assert
+        Assert.Equal("0.5", english);
+        Assert.Equal("{literal} a, b", escaped);
+    }
+
+    [Fact]
+    public void Parse_LineWithoutKey_ThrowsFormatException()
+    {
+        // Act & Assert
+        var exception = Assert.Throws<FormatException>(() => MessageCatalog.Parse("# comment\njust text\n", "de"));
+        Assert.Contains("line 2", exception.Message);
+    }
+
+    [Theory]
+    [InlineData("de_DE.UTF-8", "de-DE")]
+    [InlineData("C", "en")]
+    [InlineData("pt-br", "pt-BR")]
+    [InlineData("zh-Hant-TW", "zh-Hant-TW")]
+    public void NormalizeLocale_PosixAndBcp47Names_Normalizes(string locale, string expected)
+    {
+        // Act & Assert
+        Assert.Equal(expected, DiagnosticLocalizer.NormalizeLocale(locale));
+    }
+
+    [Fact]
+    public void BuiltInCatalogs_EveryCodeHasEnglishMessageAndEveryTemplateFormats()
+    {
+        // Arrange
+        var codes = typeof(DiagnosticCodes)
+            .GetFields(BindingFlags.Public | BindingFlags.Static)
+            .Where(f => f.IsLiteral)
+            .Select(f => (string)f.GetRawConstantValue()!);
+        var english = MessageCatalog.English;
+
+        // Act & Assert
+        Assert.Contains("de", MessageCatalog.BuiltInLocales);
+        Assert.All(codes, code => Assert.True(english.TryGetMessage(code, out _), $"No English message for {code}"));
+        Assert.All(MessageCatalog.BuiltInLocales.Select(l => MessageCatalog.GetBuiltIn(l)!), catalog =>
+        {
+            foreach (var (key, template) in catalog.Messages)
+            {
+                Assert.True(english.TryGetMessage(key, out var source), $"'{catalog.Locale}' message {key} is not in English");
+                var arguments = MessageFormat.GetArgumentNames(source).ToDictionary(n => n, _ => (object)new[] { "a", "b" });
+                MessageFormat.Format(template, arguments, catalog.Culture);
+            }
+        });
+    }
+}
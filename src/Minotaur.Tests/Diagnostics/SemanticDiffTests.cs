@@ -0,0 +1,168 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Diagnostics;
+
+/// <summary>
+/// Tests for semantic diff functionality
+/// </summary>
+public class SemanticDiffTests
+{
+    internal const string OldRust = """
+        fn origin() -> Point {
+            Point { x: 0, y: 0 }
+        }
+
+        fn unused(p: Point) -> i32 {
+            p.x + p.y
+        }
+
+        fn find(limit: usize) -> Option<Point> {
+            let start = origin();
+            if limit > LIMIT {
+                return None;
+            }
+            start
+        }
+
+        """;
+
+    internal const string NewRust = """
+        fn origin() -> Point {
+            Point { x: 0, y: 0 }
+        }
+
+        fn find(limit: usize) -> Option<Point> {
+            let start = origin();
+            if limit > LIMIT {
+                return None;
+            }
+            start
+        }
+
+        fn unused(p: Point) -> i32 {
+            p.x + p.y + 1
+        }
+
+        """;
+
+    internal const string BindingGrammar = """
+        <program> ::= <bindings>
+        <bindings> ::= <binding> | <bindings> <binding>
+        <binding> ::= <IDENTIFIER> "=" <value> ";"
+        <value> ::= <STRING> | <NUMBER>
+        """;
+
+    [Fact]
+    public void Create_FunctionMovedAndEdited_ReportsMoveWithTokenHighlights()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+
+        // Act
+        var diff = SemanticDiff.Create(parser.Parse(OldRust), parser.Parse(NewRust));
+
+        // Assert
+        var move = Assert.Single(diff.Moves);
+        Assert.Equal("item", move.Rule);
+        Assert.Equal(5, move.OldLocation.Line);
+        Assert.Equal(13, move.NewLocation.Line);
+
+        var movedFrom = diff.Lines.Where(l => l.Kind == SemanticDiffLineKind.MovedFrom).ToList();
+        Assert.Equal(new[] { "fn unused(p: Point) -> i32 {", "    p.x + p.y", "}" }, movedFrom.Select(l => l.Text));
+        Assert.Equal(new int?[] { 5, 6, 7 }, movedFrom.Select(l => l.OldLine));
+        Assert.All(movedFrom, l => Assert.Empty(l.Highlights));
+
+        var movedTo = diff.Lines.Where(l => l.Kind == SemanticDiffLineKind.MovedTo).ToList();
+        Assert.Equal(new[] { "fn unused(p: Point) -> i32 {", "    p.x + p.y + 1", "}" }, movedTo.Select(l => l.Text));
+        Assert.Equal(new[] { new TextRange(14, 1), new TextRange(16, 1) }, movedTo[1].Highlights);
+        Assert.All(movedTo, l => Assert.Equal(0, l.Move));
+
+        // Only blank lines are left deleted or inserted; the rest of the file is unchanged.
+        Assert.All(diff.Lines.Where(l => l.Kind is SemanticDiffLineKind.Deleted or SemanticDiffLineKind.Inserted), l => Assert.Empty(l.Text.Trim()));
+        Assert.Contains(diff.Lines, l => l.Kind == SemanticDiffLineKind.Unchanged && l.OldLine == 9 && l.NewLine == 5);
+    }
+
+    [Fact]
+    public void Create_EditInsideMultilineString_AlignsOnTokens()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(BindingGrammar)));
+        var oldText = "a = \"first\nb = 2;\";\nb = 2;\n";
+        var newText = "a = \"first\nb = 3;\";\nb = 2;\n";
+
+        // Act
+        var diff = SemanticDiff.Create(parser.Parse(oldText), parser.Parse(newText));
+
+        // Assert
+        Assert.Equal(
+            new (SemanticDiffLineKind, int?, int?, string)[]
+            {
+                (SemanticDiffLineKind.Deleted, 1, null, "a = \"first\nb = 2;\";"),
+                (SemanticDiffLineKind.Inserted, null, 1, "a = \"first\nb = 3;\";"),
+                (SemanticDiffLineKind.Unchanged, 3, 3, "b = 2;"),
+                (SemanticDiffLineKind.Unchanged, 4, 4, string.Empty)
+            },
+            diff.Lines.Select(l => (l.Kind, l.OldLine, l.NewLine, l.Text)));
+        Assert.Equal(new[] { new TextRange(4, 14) }, diff.Lines[0].Highlights);
+        Assert.Equal(new[] { new TextRange(4, 14) }, diff.Lines[1].Highlights);
+        Assert.Empty(diff.Moves);
+    }
+
+    [Fact]
+    public void Create_WhitespaceOnlyChange_HasNoChanges()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+
+        // Act
+        var diff = SemanticDiff.Create(parser.Parse("fn f() -> i32 {\n    1\n}\n"), parser.Parse("fn f()  ->  i32 {\n\t1\n}\n"));
+
+        // Assert
+        Assert.False(diff.HasChanges);
+    }
+
+    [Fact]
+    public void Create_EditInPlace_IsNotAMove()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+
+        // Act
+        var diff = SemanticDiff.Create(parser.Parse(OldRust), parser.Parse(OldRust.Replace("p.x + p.y", "p.y + p.x + 2")));
+
+        // Assert
+        Assert.Empty(diff.Moves);
+        var deleted = Assert.Single(diff.Lines, l => l.Kind == SemanticDiffLineKind.Deleted);
+        var inserted = Assert.Single(diff.Lines, l => l.Kind == SemanticDiffLineKind.Inserted);
+        Assert.Equal("    p.x + p.y", deleted.Text);
+        Assert.Equal("    p.y + p.x + 2", inserted.Text);
+        Assert.NotEmpty(inserted.Highlights);
+    }
+
+    internal static GeneralizedParser CreateRustParser([CallerFilePath] string path = "")
+    {
+        var file = Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", "rust_items.grammar");
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(File.ReadAllText(file))));
+    }
+}
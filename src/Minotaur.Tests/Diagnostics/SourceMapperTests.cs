@@ -0,0 +1,175 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.Json.Nodes;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.GrammarGeneration.Models;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Diagnostics;
+
+/// <summary>
+/// Tests for SourceMapper and DiagnosticFormatter functionality
+/// </summary>
+public class SourceMapperTests
+{
+    // Generated line 1 comes from template line 3, lines 2 and 3 both come from template line 1,
+    // and line 4 merges partial line 2 with template line 4.
+    private const string ReorderingMap = """
+        {
+          "version": 3,
+          "file": "out.txt",
+          "sourceRoot": "templates",
+          "sources": ["template.tt", "partial.tt"],
+          "names": ["total"],
+          "mappings": "AAEA;AAFI;EAAeA;ACCnB,SDEE"
+        }
+        """;
+
+    private const string AssignmentGrammar = """
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <IDENTIFIER> "=" <NUMBER> ";"
+        <DIRECTIVE> ::= /#[^\n]*/ => { skip }
+        <WS> ::= /\s+/ => { skip }
+        """;
+
+    [Theory]
+    [InlineData(1, 1, "templates/template.tt", 3, 1)]
+    [InlineData(1, 7, "templates/template.tt", 3, 7)]
+    [InlineData(2, 1, "templates/template.tt", 1, 5)]
+    [InlineData(4, 4, "templates/partial.tt", 2, 4)]
+    [InlineData(4, 12, "templates/template.tt", 4, 5)]
+    public void FromJson_ReorderedAndMergedLines_MapsToOriginal(int line, int column, string file, int originalLine, int originalColumn)
+    {
+        // Arrange
+        var mapper = SourceMapper.FromJson(ReorderingMap);
+
+        // Act
+        var original = mapper.Map(line, column);
+
+        // Assert
+        Assert.Equal(new OriginalLocation(file, originalLine, originalColumn), original);
+    }
+
+    [Fact]
+    public void FromJson_NamesAndUnmappedPositions_AreReported()
+    {
+        // Arrange
+        var mapper = SourceMapper.FromJson(ReorderingMap);
+
+        // Act & Assert
+        Assert.Null(mapper.Map(3, 1));
+        Assert.Null(mapper.Map(5, 1));
+        Assert.Equal(new OriginalLocation("templates/template.tt", 1, 22) { Name = "total" }, mapper.Map(3, 5));
+        Assert.Equal("out.txt", mapper.GeneratedFile);
+        Assert.Equal(new[] { "templates/template.tt", "templates/partial.tt" }, mapper.Sources);
+    }
+
+    [Theory]
+    [InlineData("""{ "version": 2, "sources": [], "mappings": "" }""")]
+    [InlineData("""{ "version": 3, "sources": ["a"], "mappings": "AA" }""")]
+    [InlineData("""{ "version": 3, "sources": ["a"], "mappings": "AAAA!" }""")]
+    [InlineData("""{ "version": 3, "sections": [] }""")]
+    public void FromJson_MalformedMap_Throws(string json)
+    {
+        // Act & Assert
+        Assert.Throws<FormatException>(() => SourceMapper.FromJson(json));
+    }
+
+    [Fact]
+    public void FromLineDirectives_DefaultDirective_MapsFollowingLines()
+    {
+        // Arrange
+        const string generated = "// header\n#line 10 \"view.tpl\"\na = 1;\nb = 2;\n#line 3\nc = 3;\n#line default\nd = 4;\n";
+
+        // Act
+        var mapper = SourceMapper.FromLineDirectives(generated, "view.g.txt");
+
+        // Assert
+        Assert.Null(mapper.Map(1, 1));
+        Assert.Equal(new OriginalLocation("view.tpl", 10, 1), mapper.Map(3, 1));
+        Assert.Equal(new OriginalLocation("view.tpl", 11, 3), mapper.Map(4, 3));
+        Assert.Equal(new OriginalLocation("view.tpl", 3, 1), mapper.Map(6, 1));
+        Assert.Null(mapper.Map(8, 1));
+    }
+
+    [Fact]
+    public void FromLineDirectives_GrammarDirective_UsesGrammarPattern()
+    {
+        // Arrange
+        var grammar = new Grammar();
+        grammar.Metadata["LineDirective"] = @"^//@source (?<file>\S+):(?<line>\d+)$";
+
+        // Act
+        var mapper = SourceMapper.FromLineDirectives("//@source a.tpl:7\nx\n#line 1 \"ignored\"\ny\n", grammar);
+
+        // Assert
+        Assert.Equal(new OriginalLocation("a.tpl", 7, 1), mapper.Map(2, 1));
+        Assert.Equal(new OriginalLocation("a.tpl", 9, 1), mapper.Map(4, 1));
+    }
+
+    [Fact]
+    public void DiagnosticFormatter_MappedSyntaxError_OffersGeneratedAndOriginalLocations()
+    {
+        // Arrange
+        const string generated = "#line 20 \"model.tpl\"\na = 1;\nb = ;\n";
+        var grammar = new GrammarFileReader().Read(AssignmentGrammar);
+        var result = new GeneralizedParser(CompiledGrammar.Compile(grammar)).Parse(generated, new ParseOptions { SourceFile = "model.g.txt" });
+        var formatter = new DiagnosticFormatter(SourceMapper.FromLineDirectives(generated, grammar, "model.g.txt"));
+        var diagnostic = Assert.Single(result.Diagnostics);
+
+        // Act
+        var text = formatter.Format(diagnostic);
+        var hover = formatter.FormatHover(diagnostic.Location!, "statement");
+        var sarif = JsonNode.Parse(formatter.ToSarif(result.Diagnostics))!;
+
+        // Assert
+        Assert.StartsWith("model.g.txt:3:5: error E0001: ", text);
+        Assert.EndsWith(" (original: model.tpl:21:5)", text);
+        Assert.Equal("statement\n\nGenerated: `model.g.txt:3:5`  \nOriginal: `model.tpl:21:5`", hover);
+
+        var sarifResult = sarif["runs"]![0]!["results"]![0]!;
+        Assert.Equal("E0001", (string?)sarifResult["ruleId"]);
+        Assert.Equal("model.g.txt", (string?)sarifResult["locations"]![0]!["physicalLocation"]!["artifactLocation"]!["uri"]);
+        Assert.Equal(3, (int)sarifResult["locations"]![0]!["physicalLocation"]!["region"]!["startLine"]!);
+        var related = sarifResult["relatedLocations"]![0]!["physicalLocation"]!;
+        Assert.Equal("model.tpl", (string?)related["artifactLocation"]!["uri"]);
+        Assert.Equal(21, (int)related["region"]!["startLine"]!);
+    }
+
+    [Fact]
+    public void DiagnosticFormatter_WithoutMapper_RendersGeneratedLocationOnly()
+    {
+        // Arrange
+        var diagnostic = new Diagnostic
+        {
+            Code = "W0002",
+            Severity = DiagnosticSeverity.Warning,
+            Message = "'x' is declared but never used",
+            Location = new SourcePosition(2, 3, 9, 1)
+        };
+
+        // Act
+        var text = new DiagnosticFormatter().Format(diagnostic);
+
+        // Assert
+        Assert.Equal("2:3: warning W0002: 'x' is declared but never used", text);
+    }
+}
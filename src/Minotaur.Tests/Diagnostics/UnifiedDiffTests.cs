@@ -0,0 +1,97 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Tests.Diagnostics;
+
+/// <summary>
+/// Tests for unified diff functionality
+/// </summary>
+public class UnifiedDiffTests
+{
+    private const string Patch = """
+        commit 1234abcd
+        Author: Someone <someone@example.com>
+
+            Rename and tidy
+
+        diff --git a/lib/one.txt b/lib/one.txt
+        --- a/lib/one.txt	2026-01-01 00:00:00
+        +++ b/lib/one.txt	2026-01-02 00:00:00
+        @@ -1,3 +1,4 @@
+         alpha
+        -beta
+        +BETA
+        +gamma
+         delta
+        @@ -10 +10,0 @@
+        -omega
+        --- /dev/null
+        +++ b/lib/two.txt
+        @@ -0,0 +1 @@
+        +new
+        """;
+
+    [Fact]
+    public void Parse_MultiFilePatch_ReadsPathsHunksAndChangedLines()
+    {
+        // Act
+        var diff = UnifiedDiff.Parse(Patch);
+
+        // Assert
+        Assert.Equal(2, diff.Files.Count);
+        var one = diff.Find("lib/one.txt")!;
+        Assert.Equal("lib/one.txt", one.OldPath);
+        Assert.Equal(2, one.Hunks.Count);
+        Assert.Equal(new[] { new LineRange(2, 2), new LineRange(11, 0) }, one.GetChangedLines().Ranges);
+
+        var two = diff.Find("C:\\repo\\lib\\two.txt")!;
+        Assert.Null(two.OldPath);
+        Assert.Equal(new[] { new LineRange(1, 1) }, two.GetChangedLines().Ranges);
+        Assert.Null(diff.Find("lib/three.txt"));
+    }
+
+    [Fact]
+    public void ReverseApply_UndoesAdditionsAndDeletions()
+    {
+        // Arrange
+        var one = UnifiedDiff.Parse(Patch).Find("lib/one.txt")!;
+        var newText = "alpha\nBETA\ngamma\ndelta\n5\n6\n7\n8\n9\n10\n11";
+        var oldText = "alpha\nbeta\ndelta\n5\n6\n7\n8\n9\n10\nomega\n11";
+
+        // Act & Assert
+        Assert.Equal(oldText, one.ReverseApply(newText));
+        Assert.Throws<InvalidOperationException>(() => one.ReverseApply("alpha\nbeta\ndelta\n"));
+    }
+
+    [Fact]
+    public void IsPatch_DistinguishesPatchesFromSources()
+    {
+        // Act & Assert
+        Assert.True(UnifiedDiff.IsPatch(Patch));
+        Assert.False(UnifiedDiff.IsPatch("fn main {\n  a = 1;\n}\n"));
+    }
+
+    [Fact]
+    public void Parse_TruncatedHunk_Throws()
+    {
+        // Act & Assert
+        Assert.Throws<FormatException>(() => UnifiedDiff.Parse("@@ -1,3 +1,3 @@\n a\n-b\n"));
+    }
+}
@@ -0,0 +1,123 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Tests.GrammarGeneration;
+
+/// <summary>
+/// Tests for GrammarFileReader functionality
+/// </summary>
+public class GrammarFileReaderTests
+{
+    [Fact]
+    public void Read_GrammarFile_ParsesHeaderRulesAndTokens()
+    {
+        // Arrange
+        var content = """
+            Grammar: Sample
+            TokenSplitter: Space
+
+            /*
+             * Sample grammar
+             */
+
+            Keywords: let, in
+
+            <program> ::= <binding>
+                | <program> <binding>
+                // Examples: let x = 1
+
+            <binding> ::= "let" <IDENTIFIER> "=" <NUMBER>
+
+            <IDENTIFIER> ::= /[a-z]+/
+            <WHITESPACE> ::= /\s+/ => { skip }
+            """;
+        var reader = new GrammarFileReader();
+
+        // Act
+        var grammar = reader.Read(content);
+
+        // Assert
+        Assert.Equal("Sample", grammar.Name);
+        Assert.Equal("Space", grammar.Metadata["TokenSplitter"]);
+        Assert.Equal(new[] { "program", "binding" }, grammar.ProductionRules.Rules.Select(r => r.Name));
+        Assert.Equal(new[] { "<binding>", "<program> <binding>" }, grammar.ProductionRules.GetRule("program")!.Alternatives);
+
+        var identifier = grammar.TokenRules.Patterns.Single(p => p.Name == "IDENTIFIER");
+        Assert.Equal("[a-z]+", identifier.Pattern);
+        Assert.Equal(TokenType.Identifier, identifier.Type);
+        Assert.Equal(TokenType.Whitespace, grammar.TokenRules.Patterns.Single(p => p.Name == "WHITESPACE").Type);
+        Assert.Equal(2, grammar.TokenRules.GetPatternsByType(TokenType.Keyword).Count());
+    }
+
+    [Fact]
+    public void Read_GeneratedGrammarFile_RoundTripsRulesAndTokens()
+    {
+        // Arrange
+        var original = new Grammar { Name = "RoundTrip", Language = "Test" };
+        original.ProductionRules.AddRule(new ProductionRule { Name = "program", Alternatives = { "<statement>", "<program> <statement>" } });
+        original.ProductionRules.AddRule(new ProductionRule { Name = "statement", Alternatives = { "<IDENTIFIER> \";\"" } });
+        original.TokenRules.AddPattern(new TokenPattern { Name = "IDENTIFIER", Pattern = "[a-z]+", Type = TokenType.Identifier });
+        original.TokenRules.AddPattern(new TokenPattern { Name = "WHITESPACE", Pattern = @"\s+", Type = TokenType.Whitespace });
+        var text = new GrammarGenerator().GenerateGrammarFile(original);
+
+        // Act
+        var grammar = new GrammarFileReader().Read(text);
+
+        // Assert
+        Assert.Equal("RoundTrip", grammar.Name);
+        Assert.Equal(
+            original.ProductionRules.Rules.Select(r => (r.Name, string.Join(" | ", r.Alternatives))).OrderBy(r => r.Name),
+            grammar.ProductionRules.Rules.Select(r => (r.Name, string.Join(" | ", r.Alternatives))).OrderBy(r => r.Name));
+        Assert.Equal(
+            original.TokenRules.Patterns.Select(p => (p.Name, p.Pattern, p.Type)).OrderBy(p => p.Name),
+            grammar.TokenRules.Patterns.Select(p => (p.Name, p.Pattern, p.Type)).OrderBy(p => p.Name));
+    }
+
+    [Fact]
+    public void SplitAlternatives_IgnoresSeparatorsInQuotesAndRegexes()
+    {
+        // Act
+        var alternatives = GrammarFileReader.SplitAlternatives(@" ""|"" <a> | /x|y/ <b> | <c> ""\"""" ");
+
+        // Assert
+        Assert.Equal(new[] { @"""|"" <a>", "/x|y/ <b>", @"<c> ""\""""" }, alternatives);
+    }
+
+    [Fact]
+    public void Read_AlternativeActions_AreBoundPerAlternative()
+    {
+        // Arrange
+        const string content = """
+            <expr> ::= <expr> "+" <term> => { add } | <term>
+            <expr> ::= "-" <expr> => { negate }
+            <WS> ::= /\s+/ => { skip }
+            """;
+
+        // Act
+        var grammar = new GrammarFileReader().Read(content);
+
+        // Assert
+        var expr = grammar.ProductionRules.GetRule("expr")!;
+        Assert.Equal(new[] { "<expr> \"+\" <term>", "<term>", "\"-\" <expr>" }, expr.Alternatives);
+        Assert.Equal(new Dictionary<int, string> { [0] = "add", [2] = "negate" }, expr.Actions);
+        Assert.Equal(TokenType.Whitespace, grammar.TokenRules.Patterns.Single(p => p.Name == "WS").Type);
+    }
+}
@@ -0,0 +1,255 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.Json.Nodes;
+using System.Xml;
+using System.Xml.Linq;
+using System.Xml.Schema;
+using Xunit;
+using Minotaur.GrammarGeneration.Analysis;
+using Minotaur.GrammarGeneration.Export;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Tests.GrammarGeneration;
+
+/// <summary>
+/// Tests for RuleDependencyGraph and GrammarGraphExporter
+/// </summary>
+public class GrammarGraphExporterTests
+{
+    // Subset of the GraphML schema covering the elements and attributes the exporter emits.
+    private const string GraphMLSchema = """
+        <?xml version="1.0" encoding="utf-8"?>
+        <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+                   targetNamespace="http://graphml.graphdrawing.org/xmlns"
+                   xmlns="http://graphml.graphdrawing.org/xmlns"
+                   elementFormDefault="qualified">
+          <xs:element name="graphml">
+            <xs:complexType>
+              <xs:sequence>
+                <xs:element name="key" minOccurs="0" maxOccurs="unbounded">
+                  <xs:complexType>
+                    <xs:attribute name="id" type="xs:string" use="required"/>
+                    <xs:attribute name="for" use="required">
+                      <xs:simpleType>
+                        <xs:restriction base="xs:string">
+                          <xs:enumeration value="node"/>
+                          <xs:enumeration value="edge"/>
+                          <xs:enumeration value="graph"/>
+                        </xs:restriction>
+                      </xs:simpleType>
+                    </xs:attribute>
+                    <xs:attribute name="attr.name" type="xs:string" use="required"/>
+                    <xs:attribute name="attr.type" type="xs:string" use="required"/>
+                  </xs:complexType>
+                </xs:element>
+                <xs:element name="graph">
+                  <xs:complexType>
+                    <xs:sequence>
+                      <xs:element name="node" minOccurs="0" maxOccurs="unbounded">
+                        <xs:complexType>
+                          <xs:sequence>
+                            <xs:element ref="data" minOccurs="0" maxOccurs="unbounded"/>
+                          </xs:sequence>
+                          <xs:attribute name="id" type="xs:string" use="required"/>
+                        </xs:complexType>
+                      </xs:element>
+                      <xs:element name="edge" minOccurs="0" maxOccurs="unbounded">
+                        <xs:complexType>
+                          <xs:sequence>
+                            <xs:element ref="data" minOccurs="0" maxOccurs="unbounded"/>
+                          </xs:sequence>
+                          <xs:attribute name="id" type="xs:string"/>
+                          <xs:attribute name="source" type="xs:string" use="required"/>
+                          <xs:attribute name="target" type="xs:string" use="required"/>
+                        </xs:complexType>
+                      </xs:element>
+                    </xs:sequence>
+                    <xs:attribute name="id" type="xs:string"/>
+                    <xs:attribute name="edgedefault" use="required">
+                      <xs:simpleType>
+                        <xs:restriction base="xs:string">
+                          <xs:enumeration value="directed"/>
+                          <xs:enumeration value="undirected"/>
+                        </xs:restriction>
+                      </xs:simpleType>
+                    </xs:attribute>
+                  </xs:complexType>
+                </xs:element>
+              </xs:sequence>
+            </xs:complexType>
+          </xs:element>
+          <xs:element name="data">
+            <xs:complexType>
+              <xs:simpleContent>
+                <xs:extension base="xs:string">
+                  <xs:attribute name="key" type="xs:string" use="required"/>
+                </xs:extension>
+              </xs:simpleContent>
+            </xs:complexType>
+          </xs:element>
+        </xs:schema>
+        """;
+
+    [Fact]
+    public void Build_WithKnownGrammar_ProducesHandCheckedEdges()
+    {
+        // Arrange
+        var grammar = CreateExpressionGrammar();
+
+        // Act
+        var graph = RuleDependencyGraph.Build(grammar);
+
+        // Assert
+        var edges = graph.Edges.Select(e => $"{e.Source}->{e.Target}x{e.Multiplicity}").ToList();
+        Assert.Equal(new[]
+        {
+            "program->statementx2",
+            "program->programx1",
+            "statement->exprx1",
+            "expr->termx2",
+            "expr->exprx1",
+            "term->NUMBERx1",
+            "term->exprx1"
+        }, edges);
+    }
+
+    [Fact]
+    public void Build_WithKnownGrammar_ComputesComponentsAndRecursion()
+    {
+        // Arrange
+        var grammar = CreateExpressionGrammar();
+
+        // Act
+        var graph = RuleDependencyGraph.Build(grammar);
+
+        // Assert
+        Assert.Equal(RuleKind.Start, graph.GetNode("program")!.Kind);
+        Assert.Equal(RuleKind.Token, graph.GetNode("NUMBER")!.Kind);
+        Assert.True(graph.GetNode("program")!.IsRecursive);
+        Assert.False(graph.GetNode("statement")!.IsRecursive);
+        Assert.True(graph.GetNode("expr")!.IsRecursive);
+        Assert.True(graph.GetNode("term")!.IsRecursive);
+        Assert.Equal(graph.GetNode("expr")!.ComponentId, graph.GetNode("term")!.ComponentId);
+        Assert.NotEqual(graph.GetNode("expr")!.ComponentId, graph.GetNode("statement")!.ComponentId);
+        Assert.Equal(4, graph.StronglyConnectedComponents.Count);
+        Assert.Equal(new[] { "expr", "term" }, graph.StronglyConnectedComponents[graph.GetNode("expr")!.ComponentId]);
+        Assert.Equal(3, graph.GetNode("expr")!.ReferenceCount);
+    }
+
+    [Fact]
+    public void Build_WithUndefinedReference_MarksNodeUndefined()
+    {
+        // Arrange
+        var grammar = CreateExpressionGrammar();
+        grammar.ProductionRules.Rules[1].Alternatives.Add("<missing_rule>");
+
+        // Act
+        var graph = RuleDependencyGraph.Build(grammar);
+
+        // Assert
+        Assert.Equal(RuleKind.Undefined, graph.GetNode("missing_rule")!.Kind);
+    }
+
+    [Fact]
+    public void ExportGraphML_ValidatesAgainstGraphMLSchema()
+    {
+        // Arrange
+        var graph = RuleDependencyGraph.Build(CreateExpressionGrammar());
+        var exporter = new GrammarGraphExporter(new Dictionary<string, long> { ["expr"] = 42 });
+
+        // Act
+        var graphml = exporter.ExportGraphML(graph);
+
+        // Assert
+        var schemas = new XmlSchemaSet();
+        schemas.Add(null, XmlReader.Create(new StringReader(GraphMLSchema)));
+        var document = XDocument.Parse(graphml);
+        var errors = new List<string>();
+        document.Validate(schemas, (_, e) => errors.Add(e.Message));
+        Assert.Empty(errors);
+    }
+
+    [Fact]
+    public void ExportGraphML_EmitsAttributesForNodesAndEdges()
+    {
+        // Arrange
+        var graph = RuleDependencyGraph.Build(CreateExpressionGrammar());
+        var exporter = new GrammarGraphExporter(new Dictionary<string, long> { ["expr"] = 42 });
+        var ns = GrammarGraphExporter.GraphMLNamespace;
+
+        // Act
+        var document = XDocument.Parse(exporter.ExportGraphML(graph));
+
+        // Assert
+        var exprNode = document.Descendants(ns + "node").Single(n => (string?)n.Attribute("id") == "expr");
+        string DataValue(XElement element, string key) =>
+            element.Elements(ns + "data").Single(d => (string?)d.Attribute("key") == key).Value;
+
+        Assert.Equal("production", DataValue(exprNode, "kind"));
+        Assert.Equal("2", DataValue(exprNode, "alternatives"));
+        Assert.Equal("true", DataValue(exprNode, "recursive"));
+        Assert.Equal("42", DataValue(exprNode, "usage"));
+
+        var programEdge = document.Descendants(ns + "edge")
+            .Single(e => (string?)e.Attribute("source") == "program" && (string?)e.Attribute("target") == "statement");
+        Assert.Equal("2", DataValue(programEdge, "multiplicity"));
+    }
+
+    [Fact]
+    public void ExportJson_ProducesNodeLinkStructure()
+    {
+        // Arrange
+        var graph = RuleDependencyGraph.Build(CreateExpressionGrammar());
+        var exporter = new GrammarGraphExporter();
+
+        // Act
+        var json = JsonNode.Parse(exporter.ExportJson(graph))!.AsObject();
+
+        // Assert
+        Assert.True(json["directed"]!.GetValue<bool>());
+        var nodes = json["nodes"]!.AsArray();
+        var links = json["links"]!.AsArray();
+        Assert.Equal(new[] { "program", "statement", "expr", "term", "NUMBER" },
+            nodes.Select(n => n!["id"]!.GetValue<string>()));
+        Assert.All(nodes, n => Assert.Null(n!["usage"]));
+        Assert.Equal(7, links.Count);
+        Assert.Equal(2, links[0]!["multiplicity"]!.GetValue<int>());
+
+        var components = json["graph"]!["components"]!.AsArray();
+        Assert.Contains(components, c => c!["members"]!.AsArray().Count == 2);
+    }
+
+    private static Grammar CreateExpressionGrammar()
+    {
+        return new Grammar
+        {
+            Name = "Expressions",
+            TokenRules = new TokenDefinitions(new[]
+            {
+                new TokenPattern { Name = "NUMBER", Pattern = "[0-9]+", Type = TokenType.Literal }
+            }),
+            ProductionRules = new ProductionRules(new[]
+            {
+                new ProductionRule { Name = "program", Alternatives = new List<string> { "<statement>", "<statement> <program>" } },
+                new ProductionRule { Name = "statement", Alternatives = new List<string> { "<expr> \";\"" } },
+                new ProductionRule { Name = "expr", Alternatives = new List<string> { "<term> \"+\" <expr>", "<term>" } },
+                new ProductionRule { Name = "term", Alternatives = new List<string> { "<NUMBER>", "\"(\" <expr> \")\"" } }
+            })
+        };
+    }
+}
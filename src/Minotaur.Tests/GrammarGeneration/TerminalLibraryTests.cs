@@ -0,0 +1,250 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.GrammarGeneration.Models;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.GrammarGeneration;
+
+/// <summary>
+/// Tests for terminal library functionality
+/// </summary>
+public class TerminalLibraryTests
+{
+    private const string Items = """
+        <program> ::= <item> | <program> <item>
+        <item> ::= <NUMBER> | <STRING> | <IDENTIFIER>
+        """;
+
+    [Fact]
+    public void Read_TerminalsHeader_AddsLibraryPatternsWithTheirProvenance()
+    {
+        // Arrange
+        var content = "Grammar: Json\nTerminals: std::json_number, std::dq_string(escapes = json)\n" + Items;
+
+        // Act
+        var grammar = new GrammarFileReader().Read(content);
+
+        // Assert
+        var number = Assert.Single(grammar.TokenRules.Patterns, p => p.Name == "NUMBER");
+        var text = Assert.Single(grammar.TokenRules.Patterns, p => p.Name == "STRING");
+        Assert.Equal("std::json_number", number.Library);
+        Assert.Equal("std::dq_string(escapes = json)", text.Library);
+        Assert.Contains(grammar.Annotations, a => a.Target == "STRING" && a.Kind == GrammarAnnotationKind.Expected && a.Text == "a string");
+    }
+
+    [Fact]
+    public void Read_DefaultParameter_IsSpelledOutInTheProvenance()
+    {
+        // Act
+        var grammar = new GrammarFileReader().Read("Terminals: std::c_numeric\n" + Items);
+
+        // Assert
+        Assert.Equal("std::c_numeric(separators = none)", Assert.Single(grammar.TokenRules.Patterns).Library);
+    }
+
+    [Fact]
+    public void Read_TokenDefinedByTheGrammar_TakesPrecedence()
+    {
+        // Arrange
+        var content = "Terminals: std::c_numeric, std::json_number\n" + Items + "\n<NUMBER> ::= /[0-9]+/\n";
+
+        // Act
+        var grammar = new GrammarFileReader().Read(content);
+
+        // Assert
+        var number = Assert.Single(grammar.TokenRules.Patterns);
+        Assert.Equal("[0-9]+", number.Pattern);
+        Assert.Null(number.Library);
+    }
+
+    [Theory]
+    [InlineData("Terminals: std::nope\n", "std::nope")]
+    [InlineData("Terminals: std::c_numeric(base = 16)\n", "base")]
+    [InlineData("Terminals: std::dq_string(escapes = python)\n", "python")]
+    [InlineData("Terminals: std::dq_string(escapes json)\n", "name = value")]
+    public void Read_UnresolvableReference_Throws(string header, string mentioned)
+    {
+        // Act & Assert
+        var ex = Assert.Throws<ArgumentException>(() => new GrammarFileReader().Read(header + Items));
+        Assert.Contains(mentioned, ex.Message);
+    }
+
+    [Fact]
+    public void Read_RegisteredLibrary_IsImportedByName()
+    {
+        // Arrange
+        var library = TerminalLibrary.FromGrammar(new GrammarFileReader().Read("""
+            Grammar: acme::ids
+            <IDENTIFIER> ::= /[a-z]+(-[a-z]+)*/
+            // @description A kebab-case name.
+            """));
+        var reader = new GrammarFileReader(new TerminalLibraryRegistry().Register(library));
+
+        // Act
+        var grammar = CompiledGrammar.Compile(reader.Read("Terminals: acme::ids\n" + Items));
+
+        // Assert
+        Assert.Equal(new[] { "max-width" }, Lex(grammar, "max-width").Select(t => t.Text));
+        Assert.Equal("A kebab-case name. (from acme::ids)", grammar.Docs.Get("IDENTIFIER")?.Description);
+    }
+
+    [Theory]
+    [InlineData("0x1.8p3", "none", "0x1.8p3")]
+    [InlineData("0x.8p-2", "none", "0x.8p-2")]
+    [InlineData("0x1p+10L", "none", "0x1p+10L")]
+    [InlineData("0x1.8", "none", "0x1")]
+    [InlineData("0x1F", "none", "0x1F")]
+    [InlineData("0b102", "none", "0b10")]
+    [InlineData("42ull", "none", "42ull")]
+    [InlineData("12lu", "none", "12lu")]
+    [InlineData("1.5e-3f", "none", "1.5e-3f")]
+    [InlineData(".5", "none", ".5")]
+    [InlineData("1.", "none", "1.")]
+    [InlineData("1e", "none", "1")]
+    [InlineData("1_000", "none", "1")]
+    [InlineData("1_000_000", "underscore", "1_000_000")]
+    [InlineData("0xFF_FF", "underscore", "0xFF_FF")]
+    [InlineData("3.141_592", "underscore", "3.141_592")]
+    [InlineData("1__0", "underscore", "1")]
+    [InlineData("1_", "underscore", "1")]
+    public void CNumeric_SpecEdgeCases_MatchTheLongestValidConstant(string input, string separators, string expected)
+    {
+        // Arrange
+        var grammar = Compile($"Terminals: std::c_numeric(separators = {separators}), std::c_identifier\n");
+
+        // Act
+        var tokens = Lex(grammar, input);
+
+        // Assert
+        Assert.Equal("NUMBER", tokens[0].Kind);
+        Assert.Equal(expected, tokens[0].Text);
+    }
+
+    [Theory]
+    [InlineData("-0", "-0")]
+    [InlineData("12.5e+3", "12.5e+3")]
+    [InlineData("01", "0")]
+    [InlineData("1.", "1")]
+    [InlineData("1e+", "1")]
+    public void JsonNumber_SpecEdgeCases_MatchTheLongestValidNumber(string input, string expected)
+    {
+        // Arrange
+        var grammar = Compile("Terminals: std::json_number, std::c_identifier\n");
+
+        // Act
+        var tokens = Lex(grammar, input);
+
+        // Assert
+        Assert.Equal("NUMBER", tokens[0].Kind);
+        Assert.Equal(expected, tokens[0].Text);
+    }
+
+    [Theory]
+    [InlineData("json", "\"\\uD83D\\uDE00\"", true)]
+    [InlineData("json", "\"😀\"", true)]
+    [InlineData("json", "\"a\\/b\"", true)]
+    [InlineData("json", "\"\\uD83D\"", false)]
+    [InlineData("json", "\"\\uDE00\"", false)]
+    [InlineData("json", "\"\\x41\"", false)]
+    [InlineData("json", "\"a\tb\"", false)]
+    [InlineData("c", "\"\\x41\\101\\?\"", true)]
+    [InlineData("c", "\"\\U0001F600\"", true)]
+    [InlineData("c", "\"a\\\nb\"", true)]
+    [InlineData("c", "\"\\q\"", false)]
+    [InlineData("c", "\"a\nb\"", false)]
+    public void DqString_Escapes_FollowTheDialect(string escapes, string input, bool valid)
+    {
+        // Arrange
+        var grammar = Compile($"Terminals: std::dq_string(escapes = {escapes})\n");
+
+        // Act
+        var result = new GrammarLexer(grammar).Tokenize(input);
+
+        // Assert
+        var whole = result.Diagnostics.Count == 0 && result.Tokens.Count == 1 && result.Tokens[0].Kind == "STRING";
+        Assert.Equal(valid, whole);
+    }
+
+    [Fact]
+    public void CComments_AreSkippedAndDoNotNest()
+    {
+        // Arrange
+        var grammar = Compile("Terminals: std::c_numeric, std::c_comments\n");
+
+        // Act
+        var tokens = Lex(grammar, "1 // one\n/* a /* b */ 2");
+
+        // Assert
+        Assert.Equal(new[] { "1", "2" }, tokens.Select(t => t.Text));
+    }
+
+    [Fact]
+    public void StandardLibraries_ExamplesValidateAsDocumentation()
+    {
+        // Arrange
+        var grammar = Compile("Terminals: std::c_numeric, std::dq_string, std::c_identifier, std::c_comments\n");
+
+        // Act
+        var failures = GrammarDocs.Validate(grammar);
+
+        // Assert
+        Assert.Empty(failures);
+        Assert.Contains(grammar.Source.Annotations, a => a.Target == "NUMBER" && a.Kind == GrammarAnnotationKind.Example);
+        Assert.Equal("A C string literal. (from std::dq_string(escapes = c))", grammar.Docs.Get("STRING")?.Description);
+    }
+
+    [Theory]
+    [InlineData("std::c_numeric", "separators", "underscore")]
+    [InlineData("std::dq_string", "escapes", "json")]
+    [InlineData("std::json_number", null, null)]
+    public void StandardLibraries_EveryExampleLexesAsOneToken(string name, string? parameter, string? value)
+    {
+        // Arrange
+        var library = new TerminalLibraryRegistry().Get(name)!;
+        var arguments = new Dictionary<string, string>();
+        if (parameter != null)
+        {
+            arguments[parameter] = value!;
+        }
+
+        var terminals = library.Create(arguments, out var reference);
+        var grammar = Compile($"Terminals: {reference}\n");
+
+        // Act & Assert
+        foreach (var terminal in terminals)
+        {
+            foreach (var example in terminal.Examples)
+            {
+                var token = Assert.Single(Lex(grammar, example));
+                Assert.Equal(terminal.Name, token.Kind);
+            }
+        }
+    }
+
+    private static CompiledGrammar Compile(string header)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(header + Items));
+    }
+
+    private static IReadOnlyList<Token> Lex(CompiledGrammar grammar, string input)
+    {
+        return new GrammarLexer(grammar).Tokenize(input).Tokens;
+    }
+}
@@ -0,0 +1,192 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Runtime.CompilerServices;
+using System.Text;
+using System.Text.Json;
+using Xunit;
+using Minotaur.Grammars;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Grammars;
+
+/// <summary>
+/// Tests for built-in grammar conformance functionality. Runs the curated JSONTestSuite and YAML test-suite
+/// cases under Fixtures and checks the conformance report in Snapshots/conformance.txt, which documents them.
+/// </summary>
+public class ConformanceTests
+{
+    [Fact]
+    public void Conformance_CuratedSuites_MatchTheReport()
+    {
+        // Arrange
+        var report = new StringBuilder();
+        var failures = new List<string>();
+
+        // Act
+        RunJsonSuite(report, failures);
+        report.AppendLine();
+        RunYamlSuite(report, failures);
+
+        // Assert
+        Assert.Empty(failures);
+        Snapshot.AssertMatches(report.ToString(), Path.Combine(TestDirectory(), "Snapshots", "conformance.txt"));
+    }
+
+    private static void RunJsonSuite(StringBuilder report, List<string> failures)
+    {
+        // y_ cases must be accepted and round-trip, n_ cases rejected; i_ cases may go either way.
+        var files = Directory.GetFiles(Path.Combine(TestDirectory(), "Fixtures", "json-test-suite"), "*.json")
+            .OrderBy(f => Path.GetFileName(f), StringComparer.Ordinal)
+            .ToList();
+        var passed = 0;
+        report.AppendLine("JSONTestSuite");
+
+        foreach (var file in files)
+        {
+            var name = Path.GetFileName(file);
+            var text = File.ReadAllText(file);
+            string outcome;
+            try
+            {
+                var value = JsonValue.Parse(text, name);
+                outcome = JsonValue.Parse(value.ToString()).ToString() == value.ToString() ? "accepted" : "accepted, round trip differs";
+            }
+            catch (FormatException)
+            {
+                outcome = "rejected";
+            }
+
+            var pass = name[0] switch
+            {
+                'y' => outcome == "accepted",
+                'n' => outcome == "rejected",
+                _ => true
+            };
+            passed += pass ? 1 : 0;
+            if (!pass)
+            {
+                failures.Add($"{name}: {outcome}");
+            }
+
+            report.AppendLine($"  {(pass ? "pass" : "FAIL")}  {name}: {outcome}");
+        }
+
+        report.AppendLine($"  {passed}/{files.Count} passed");
+    }
+
+    private static void RunYamlSuite(StringBuilder report, List<string> failures)
+    {
+        // A case with in.json must read as those documents; a case with an error file must be rejected.
+        var cases = Directory.GetDirectories(Path.Combine(TestDirectory(), "Fixtures", "yaml-test-suite"))
+            .OrderBy(d => Path.GetFileName(d), StringComparer.Ordinal)
+            .ToList();
+        var passed = 0;
+        report.AppendLine("YAML test-suite");
+
+        foreach (var directory in cases)
+        {
+            var name = Path.GetFileName(directory);
+            var yaml = File.ReadAllText(Path.Combine(directory, "in.yaml"));
+            var expectError = File.Exists(Path.Combine(directory, "error"));
+            string outcome;
+            try
+            {
+                var stream = YamlStream.Parse(yaml, name);
+                if (expectError)
+                {
+                    outcome = "accepted";
+                }
+                else
+                {
+                    var expected = JsonValue.Parse(File.ReadAllText(Path.Combine(directory, "in.json")));
+                    var actual = "[" + string.Join(",", stream.Documents.Select(Canonical)) + "]";
+                    outcome = actual == Canonical(expected) ? "matched" : $"differs: {actual}";
+                }
+            }
+            catch (FormatException)
+            {
+                outcome = "rejected";
+            }
+
+            var pass = outcome == (expectError ? "rejected" : "matched");
+            passed += pass ? 1 : 0;
+            if (!pass)
+            {
+                failures.Add($"{name}: {outcome}");
+            }
+
+            report.AppendLine($"  {(pass ? "pass" : "FAIL")}  {name}: {outcome}");
+        }
+
+        report.AppendLine($"  {passed}/{cases.Count} passed");
+    }
+
+    private static string Canonical(YamlNode node)
+    {
+        node = node.Resolve();
+        switch (node.Kind)
+        {
+            case YamlNodeKind.Sequence:
+                return "[" + string.Join(",", node.Items.Select(Canonical)) + "]";
+            case YamlNodeKind.Mapping:
+                return "{" + string.Join(",", node.Entries.Select(e => Quote(e.Key.Resolve().Value) + ":" + Canonical(e.Value))) + "}";
+        }
+
+        if (node.Style != YamlScalarStyle.Plain)
+        {
+            return Quote(node.Value);
+        }
+
+        if (node.IsNull)
+        {
+            return "null";
+        }
+
+        if (node.TryGetBoolean(out var boolean))
+        {
+            return boolean ? "true" : "false";
+        }
+
+        return node.TryGetDouble(out var number) ? number.ToString("R", CultureInfo.InvariantCulture) : Quote(node.Value);
+    }
+
+    private static string Canonical(JsonValue value)
+    {
+        return value.Kind switch
+        {
+            JsonKind.Array => "[" + string.Join(",", value.Items.Select(Canonical)) + "]",
+            JsonKind.Object => "{" + string.Join(",", value.Properties.Select(p => Quote(p.Name) + ":" + Canonical(p.Value))) + "}",
+            JsonKind.String => Quote(value.GetString()),
+            JsonKind.Number => value.GetDouble().ToString("R", CultureInfo.InvariantCulture),
+            JsonKind.True => "true",
+            JsonKind.False => "false",
+            _ => "null"
+        };
+    }
+
+    private static string Quote(string text)
+    {
+        return JsonSerializer.Serialize(text);
+    }
+
+    private static string TestDirectory([CallerFilePath] string path = "")
+    {
+        return Path.GetDirectoryName(path)!;
+    }
+}
@@ -0,0 +1,172 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.Grammars;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Grammars;
+
+/// <summary>
+/// Tests for built-in JSON grammar functionality
+/// </summary>
+public class JsonValueTests
+{
+    [Fact]
+    public void Parse_Document_ExposesTypedValues()
+    {
+        // Arrange
+        const string json = """{"name": "Minotaur", "tags": ["parser", "graph"], "stars": 42, "ratio": -1.5e2, "archived": false, "owner": null}""";
+
+        // Act
+        var value = JsonValue.Parse(json);
+
+        // Assert
+        Assert.Equal(JsonKind.Object, value.Kind);
+        Assert.Equal("Minotaur", value["name"].GetString());
+        Assert.Equal("graph", value["tags"][1].GetString());
+        Assert.True(value["stars"].TryGetInt64(out var stars));
+        Assert.Equal(42, stars);
+        Assert.Equal(-150, value["ratio"].GetDouble());
+        Assert.False(value["ratio"].TryGetInt64(out _));
+        Assert.False(value["archived"].GetBoolean());
+        Assert.Equal(JsonKind.Null, value["owner"].Kind);
+    }
+
+    [Fact]
+    public void Parse_Escapes_AreDecodedIncludingSurrogatePairs()
+    {
+        // Arrange
+        const string json = """["a\"b\\c\/d\n\t", "\u00e9\uD834\uDD1E"]""";
+
+        // Act
+        var value = JsonValue.Parse(json);
+
+        // Assert
+        Assert.Equal("a\"b\\c/d\n\t", value[0].GetString());
+        Assert.Equal("é𝄞", value[1].GetString());
+        Assert.Equal("\"\\u00e9\\uD834\\uDD1E\"", value[1].SourceText);
+    }
+
+    [Fact]
+    public void Parse_Values_KeepTheirSourceTextAndPositions()
+    {
+        // Arrange
+        const string json = "{\n  \"id\" : 1.50,\n  \"tags\": [ ]\n}";
+
+        // Act
+        var value = JsonValue.Parse(json);
+
+        // Assert
+        Assert.Equal(json, value.SourceText);
+        Assert.Equal("1.50", value["id"].SourceText);
+        Assert.Equal("[ ]", value["tags"].SourceText);
+        Assert.Equal(3, value["tags"].Position!.Line);
+        Assert.Equal(2, value.Properties[0].NamePosition!.Line);
+        Assert.Equal("{\"id\":1.50,\"tags\":[]}", value.ToString());
+    }
+
+    [Fact]
+    public void Parse_DeeplyNestedArrays_DoesNotRecurse()
+    {
+        // Arrange
+        const int depth = 5000;
+        var json = new string('[', depth) + new string(']', depth);
+
+        // Act
+        var value = JsonValue.Parse(json);
+
+        // Assert
+        var innermost = value;
+        for (var i = 1; i < depth; i++)
+        {
+            innermost = innermost[0];
+        }
+        Assert.Empty(innermost.Items);
+        Assert.Equal(json, value.ToString());
+    }
+
+    [Fact]
+    public void Value_TerminalEdgeCases_FollowRfc8259()
+    {
+        // Act & Assert
+        RuleTest.For(BuiltInGrammars.Json, "value")
+            .Ok("\"\"", "\"\\/\"", "\"\\uD83D\\uDE00\"", "\"café 😀\"", "-0", "0.5", "1E+2", "-12.5e-3", "null")
+            .OkWithTree("-0", """(value (NUMBER "-0"))""")
+            .OkWithTree("\"\\n\"", """(value (STRING "\"\\n\""))""")
+            .Err("\"unterminated", "\"tab\there\"", "\"\\q\"", "\"\\x41\"", "01", "1.", ".5", "+1", "1e", "NaN", "True", "'a'")
+            .Assert();
+    }
+
+    [Theory]
+    [InlineData("[1,]")]
+    [InlineData("{'a': 1}")]
+    [InlineData("01")]
+    [InlineData("\"tab\there\"")]
+    public void Parse_InvalidJson_ThrowsWithPosition(string json)
+    {
+        // Act & Assert
+        var ex = Assert.Throws<FormatException>(() => JsonValue.Parse(json));
+        Assert.StartsWith("Invalid JSON at line 1", ex.Message);
+    }
+
+    [Fact]
+    public void Accessors_WrongKind_Throw()
+    {
+        // Arrange
+        var value = JsonValue.Parse("""{"a": [1], "a": [2]}""");
+
+        // Act & Assert
+        Assert.Equal("2", value["a"][0].SourceText);
+        Assert.Throws<KeyNotFoundException>(() => value["b"]);
+        Assert.Throws<InvalidOperationException>(() => value[0]);
+        Assert.Throws<InvalidOperationException>(() => value["a"].GetString());
+    }
+
+    [Fact]
+    public void BuiltInGrammars_Json_ListsItsAnnotatedExamples()
+    {
+        // Act
+        var grammar = BuiltInGrammars.Json;
+
+        // Assert
+        Assert.Contains(BuiltInGrammars.JsonName, BuiltInGrammars.Names);
+        Assert.Equal("Json", grammar.Source.Name);
+        Assert.Throws<ArgumentException>(() => BuiltInGrammars.ReadSource("Toml"));
+    }
+
+    [Fact]
+    public void Parse_StrictFeature_ReportsDuplicateMemberNames()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(BuiltInGrammars.Json);
+        const string json = """{"id": 1, "tags": [], "id": 2}""";
+
+        // Act
+        var lenient = parser.Parse(json);
+        var strict = parser.Parse(json, new ParseOptions { Features = new[] { BuiltInGrammars.JsonStrictFeature } });
+
+        // Assert
+        Assert.True(lenient.IsSuccess);
+        var error = Assert.Single(strict.Diagnostics);
+        Assert.Equal(DiagnosticCodes.DuplicateKey, error.Code);
+        Assert.Equal(22, error.Location!.Offset);
+        Assert.Equal("""{"id": 1, "tags": []}""", QuickFix.Apply(json, strict.Diagnostics).Text);
+    }
+}
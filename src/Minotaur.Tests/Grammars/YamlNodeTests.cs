@@ -0,0 +1,196 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Grammars;
+
+namespace Minotaur.Tests.Grammars;
+
+/// <summary>
+/// Tests for built-in YAML grammar functionality
+/// </summary>
+public class YamlNodeTests
+{
+    [Fact]
+    public void Parse_BlockStructure_NestsByIndentation()
+    {
+        // Arrange
+        const string yaml = """
+            name: Minotaur
+            tags:
+              - parser
+              - graph
+            owner:
+              login: develapp
+              teams: [core, docs]
+            """;
+
+        // Act
+        var root = YamlNode.Parse(yaml);
+
+        // Assert
+        Assert.Equal(YamlNodeKind.Mapping, root.Kind);
+        Assert.Equal("Minotaur", root["name"].Value);
+        Assert.Equal(new[] { "parser", "graph" }, root["tags"].Items.Select(i => i.Value));
+        Assert.Equal("develapp", root["owner"]["login"].Value);
+        Assert.Equal("docs", root["owner"]["teams"][1].Value);
+    }
+
+    [Fact]
+    public void Parse_CompactSequenceEntries_LineUpWithTheLinesBelow()
+    {
+        // Arrange
+        const string yaml = """
+            - name: a
+              size: 1
+            - name: b
+            """;
+
+        // Act
+        var root = YamlNode.Parse(yaml);
+
+        // Assert
+        Assert.Equal(2, root.Items.Count);
+        Assert.Equal(2, root[0].Entries.Count);
+        Assert.Equal("b", root[1]["name"].Value);
+    }
+
+    [Fact]
+    public void Parse_CoreSchemaScalars_ResolveToTypedValues()
+    {
+        // Arrange
+        const string yaml = "int: 0x1F\nfloat: -.inf\nbool: True\nnull: ~\nquoted: \"42\"\ntagged: !!str 7";
+
+        // Act
+        var root = YamlNode.Parse(yaml);
+
+        // Assert
+        Assert.True(root["int"].TryGetInt64(out var number));
+        Assert.Equal(31, number);
+        Assert.True(root["float"].TryGetDouble(out var infinity));
+        Assert.Equal(double.NegativeInfinity, infinity);
+        Assert.True(root["bool"].TryGetBoolean(out var flag));
+        Assert.True(flag);
+        Assert.True(root["null"].IsNull);
+        Assert.False(root["quoted"].TryGetInt64(out _));
+        Assert.False(root["tagged"].TryGetInt64(out _));
+        Assert.Equal("!!str", root["tagged"].Tag);
+    }
+
+    [Fact]
+    public void Parse_Aliases_ResolveToTheirAnchors()
+    {
+        // Arrange
+        const string yaml = """
+            defaults: &defaults
+              adapter: postgres
+            development: *defaults
+            """;
+
+        // Act
+        var root = YamlNode.Parse(yaml);
+
+        // Assert
+        var alias = root["development"];
+        Assert.Equal(YamlNodeKind.Alias, alias.Kind);
+        Assert.Same(root["defaults"], alias.Target);
+        Assert.Equal("defaults", root["defaults"].Anchor);
+        Assert.Equal("postgres", alias["adapter"].Value);
+        Assert.Throws<FormatException>(() => YamlNode.Parse("a: *missing"));
+    }
+
+    [Fact]
+    public void Parse_BlockScalars_ApplyStyleAndChomping()
+    {
+        // Arrange
+        const string yaml = "literal: |\n  one\n   two\n\nfolded: >-\n  one\n  two\n\n  three\nkept: |+\n  end\n\n";
+
+        // Act
+        var root = YamlNode.Parse(yaml);
+
+        // Assert
+        Assert.Equal("one\n two\n", root["literal"].Value);
+        Assert.Equal(YamlScalarStyle.Literal, root["literal"].Style);
+        Assert.Equal("one two\nthree", root["folded"].Value);
+        Assert.Equal(YamlScalarStyle.Folded, root["folded"].Style);
+        Assert.Equal("end\n\n", root["kept"].Value);
+    }
+
+    [Fact]
+    public void Parse_QuotedScalars_AreUnescapedAndFolded()
+    {
+        // Arrange
+        const string yaml = "double: \"tab\\there \\u263A\\U0001D11E\"\nsingle: 'it''s\n  folded'";
+
+        // Act
+        var root = YamlNode.Parse(yaml);
+
+        // Assert
+        Assert.Equal("tab\there ☺𝄞", root["double"].Value);
+        Assert.Equal("it's folded", root["single"].Value);
+        Assert.Equal("'it''s\n  folded'", root["single"].SourceText);
+    }
+
+    [Fact]
+    public void Parse_Comments_AttachToTheNodesTheyDescribe()
+    {
+        // Arrange
+        const string yaml = """
+            # The project
+            name: Minotaur # short name
+            # Its labels
+            tags: [a, b]
+            # end of file
+            """;
+
+        // Act
+        var stream = YamlStream.Parse(yaml);
+
+        // Assert
+        var root = Assert.Single(stream.Documents);
+        var name = root.Entries[0];
+        Assert.Equal(new[] { "The project" }, name.Key.LeadingComments);
+        Assert.Equal("short name", name.Value.TrailingComment);
+        Assert.Equal(new[] { "Its labels" }, root.Entries[1].Key.LeadingComments);
+        Assert.Equal(new[] { "end of file" }, stream.Comments);
+    }
+
+    [Fact]
+    public void Parse_Stream_ReadsEachDocument()
+    {
+        // Arrange
+        const string yaml = "a: 1\n---\n- b\n--- c\n...\n";
+
+        // Act
+        var stream = YamlStream.Parse(yaml);
+
+        // Assert
+        Assert.Equal(3, stream.Documents.Count);
+        Assert.Equal("1", stream.Documents[0]["a"].Value);
+        Assert.Equal("b", stream.Documents[1][0].Value);
+        Assert.Equal("c", stream.Documents[2].Value);
+        Assert.Throws<FormatException>(() => YamlNode.Parse(yaml));
+    }
+
+    [Fact]
+    public void Parse_InvalidYaml_ThrowsWithPosition()
+    {
+        // Act & Assert
+        var ex = Assert.Throws<FormatException>(() => YamlNode.Parse("a: [b, c\n"));
+        Assert.StartsWith("Invalid YAML at line", ex.Message);
+    }
+}
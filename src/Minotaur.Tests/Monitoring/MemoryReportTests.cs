@@ -0,0 +1,176 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.GrammarGeneration.Models;
+using Minotaur.Monitoring;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Monitoring;
+
+/// <summary>
+/// Tests for MemoryReport functionality
+/// </summary>
+public class MemoryReportTests
+{
+    private const string AssignmentGrammar = """
+        Grammar: Assignments
+        StartRule: program
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <IDENTIFIER> "=" <NUMBER> ";"
+        // @description Assigns a number to a name.
+        // @example x = 1;
+        """;
+
+    [Fact]
+    public void GetMemoryReport_Parse_ArenaBytesMatchAnIndependentAllocationCount()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(AssignmentGrammar)));
+        var input = string.Concat(Enumerable.Range(0, 300).Select(i => $"x{i} = {i};\n"));
+        parser.Parse(input);
+        var parse = parser.Parse(input);
+        Rebuild(parse.Tree!);
+
+        // Act
+        var report = parse.GetMemoryReport();
+        var arenaBytes = report.Get(MemoryCategories.TreeNodes)!.Bytes + report.Get(MemoryCategories.ChildLists)!.Bytes;
+        var counted = Rebuild(parse.Tree!);
+
+        // Assert
+        Assert.True(MemoryReport.IsAccountingEnabled);
+        Assert.Equal(1 + 300 + 300 * 5, report.Get(MemoryCategories.TreeNodes)!.Count);
+        Assert.InRange(arenaBytes, (long)(counted * 0.95), (long)(counted * 1.05));
+        Assert.Equal(1200, report.Get(MemoryCategories.Tokens)!.Count);
+        Assert.True(report.Get(MemoryCategories.Tokens)!.Bytes > 0);
+        Assert.True(report.Get(MemoryCategories.Forest)!.Bytes > 0);
+    }
+
+    [Fact]
+    public void GetMemoryReport_GrammarReadFromText_AccountsEachPart()
+    {
+        // Act
+        var grammar = new GrammarFileReader().Read(AssignmentGrammar);
+        var report = grammar.GetMemoryReport();
+
+        // Assert
+        Assert.Equal(2, report.Get(MemoryCategories.ProductionRules)!.Count);
+        Assert.Equal(2, report.Get(MemoryCategories.Annotations)!.Count);
+        Assert.Equal(1, report.Get(MemoryCategories.Metadata)!.Count);
+        Assert.True(report.Get(MemoryCategories.ProductionRules)!.Bytes > 0);
+        Assert.True(report.Get(MemoryCategories.Annotations)!.Bytes > 0);
+        Assert.True(report.Get(MemoryCategories.Metadata)!.Bytes > 0);
+        Assert.Equal(report.Entries.Sum(e => e.Bytes), report.TotalBytes);
+    }
+
+    [Fact]
+    public void GetMemoryReport_GrammarBuiltInCode_HasCountsButNoBytes()
+    {
+        // Arrange
+        var grammar = new Grammar { Name = "Built" };
+        grammar.ProductionRules.AddRule(new ProductionRule { Name = "program", Alternatives = { "<IDENTIFIER>" } });
+
+        // Act
+        var report = grammar.GetMemoryReport();
+
+        // Assert
+        Assert.Equal(1, report.Get(MemoryCategories.ProductionRules)!.Count);
+        Assert.Equal(0, report.TotalBytes);
+    }
+
+    [Fact]
+    public void GetMemoryReport_CompiledGrammar_CountsItemStatesAndTheLexerOnceItIsBuilt()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(AssignmentGrammar));
+        var beforeParsing = grammar.GetMemoryReport();
+
+        // Act
+        new GeneralizedParser(grammar).Parse("a = 1;");
+        var report = grammar.GetMemoryReport();
+
+        // Assert
+        Assert.Equal(2, report.Get(MemoryCategories.Rules)!.Count);
+        Assert.Equal(2 + 3 + 5, report.Get(MemoryCategories.Alternatives)!.Count);
+        Assert.True(report.Get(MemoryCategories.Alternatives)!.Bytes > 0);
+        Assert.Equal(0, beforeParsing.Get(MemoryCategories.Lexer)!.Bytes);
+        Assert.True(report.Get(MemoryCategories.Lexer)!.Bytes > 0);
+    }
+
+    [Fact]
+    public void ToString_Report_ListsEachPartAndTheTotal()
+    {
+        // Arrange
+        var report = new MemoryReport("Parse of <program>", new[]
+        {
+            new MemoryReportEntry(MemoryCategories.Tokens, 1200, 98_304),
+            new MemoryReportEntry(MemoryCategories.TreeNodes, 1801, 456_000)
+        });
+
+        // Act
+        var text = report.ToString();
+
+        // Assert
+        var lines = text.Split('\n');
+        Assert.Equal("Parse of <program>:", lines[0].TrimEnd());
+        Assert.Contains("tokens", lines[1]);
+        Assert.Contains("1,200", lines[1]);
+        Assert.Contains("98,304 B", lines[1]);
+        Assert.StartsWith("  total", lines[3]);
+        Assert.EndsWith("554,304 B", lines[3]);
+    }
+
+    // Builds a copy of the tree the way the parser's tree builder does and returns the bytes the copy allocated,
+    // counted by the runtime; the source nodes are listed beforehand so the walk itself is not counted.
+    private static long Rebuild(CognitiveGraphNode tree)
+    {
+        var nodes = new List<(CognitiveGraphNode Node, int Parent)> { (tree, -1) };
+        for (var i = 0; i < nodes.Count; i++)
+        {
+            foreach (var child in nodes[i].Node.Children)
+            {
+                nodes.Add((child, i));
+            }
+        }
+
+        var copies = new CognitiveGraphNode[nodes.Count];
+        var before = GC.GetAllocatedBytesForCurrentThread();
+        for (var i = 0; i < nodes.Count; i++)
+        {
+            var (node, parent) = nodes[i];
+            var position = node.SourcePosition!;
+            CognitiveGraphNode copy = node is NonTerminalNode nonTerminal
+                ? new NonTerminalNode(nonTerminal.RuleName, nonTerminal.ProductionIndex)
+                : new TerminalNode(((TerminalNode)node).Text, ((TerminalNode)node).TokenType);
+            copy.SourcePosition = new SourcePosition(position.Line, position.Column, position.Offset, position.Length)
+            {
+                EndLine = position.EndLine,
+                EndColumn = position.EndColumn,
+                SourceFile = position.SourceFile
+            };
+            copies[i] = copy;
+            if (parent >= 0)
+            {
+                copies[parent].AddChild(copy);
+            }
+        }
+
+        return GC.GetAllocatedBytesForCurrentThread() - before;
+    }
+}
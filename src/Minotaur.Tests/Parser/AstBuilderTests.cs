@@ -0,0 +1,123 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for AstBuilder functionality
+/// </summary>
+public class AstBuilderTests
+{
+    [Fact]
+    public void Finish_RustFunction_PrintsSourceThatReparsesToAnEqualTree()
+    {
+        // Arrange
+        var grammar = LoadRustItems();
+        var ast = new AstBuilder(grammar);
+        NonTerminalNode Parameter(string name) => ast.Node("parameter").Child(ast.Identifier(name)).Child(ast.Identifier("i32")).Finish();
+
+        // Act
+        var function = ast.Node("function")
+            .Field("IDENTIFIER", ast.Identifier("add"))
+            .Field("parameters", ast.List("parameters", new[] { Parameter("a"), Parameter("b") }))
+            .Field("return_type", ast.Node("return_type").Child(ast.Identifier("i32")))
+            .Field("block", ast.Node("block")
+                .Child(ast.List("statements", new[]
+                {
+                    ast.Node("statement")
+                        .Child(ast.Identifier("c"))
+                        .Child(ast.Node("sum").Child(ast.Identifier("a")).Child(ast.Identifier("b")))
+                        .Finish()
+                }))
+                .Child(ast.Identifier("c")));
+        var crate = ast.Node("crate").Child(function).Finish();
+        var printed = ast.Print(crate);
+        var reparsed = new GeneralizedParser(grammar).Parse(printed);
+
+        // Assert
+        Assert.Equal("fn add(a: i32, b: i32) -> i32 {\n    let c = a + b;\n    c\n}\n", printed);
+        Assert.True(reparsed.IsSuccess, printed);
+        Assert.Equal(SExpression.Format(reparsed.Tree!), SExpression.Format(crate));
+    }
+
+    [Fact]
+    public void Finish_ChildrenFitNoAlternative_NamesTheClosestAlternative()
+    {
+        // Arrange
+        var ast = new AstBuilder(LoadRustItems());
+        var node = ast.Node("function").Child(ast.Identifier("main")).Child(ast.Node("return_type").Child(ast.Identifier("i32")));
+
+        // Act
+        var error = Assert.Throws<AstValidationException>(() => node.Finish());
+
+        // Assert
+        Assert.Equal("function", error.Rule);
+        Assert.Equal(1, error.ClosestAlternative!.Index);
+        Assert.Equal(new[] { "<block>" }, error.Missing);
+        Assert.Empty(error.Unexpected);
+        Assert.Equal("Children (<IDENTIFIER> <return_type>) fit no alternative of <function>; closest is <function> ::= \"fn\" <IDENTIFIER> \"(\" \")\" <return_type> <block> (missing <block>)", error.Message);
+    }
+
+    [Fact]
+    public void Child_IncompleteNode_DefersValidationUntilFinish()
+    {
+        // Arrange
+        var ast = new AstBuilder(LoadRustItems());
+        var path = ast.Node("path");
+
+        // Act
+        path.Child(ast.Identifier("std"));
+        path.Child(ast.Identifier("io"));
+        var partial = path.Child(ast.Identifier("Read"));
+
+        // Assert
+        Assert.Throws<AstValidationException>(() => partial.Finish());
+        var nested = ast.Node("path").Child(ast.Node("path").Child(ast.Identifier("std"))).Child(ast.Identifier("io")).Finish();
+        Assert.Equal(1, nested.ProductionIndex);
+        Assert.Equal(new[] { "path", "\"::\"", "IDENTIFIER" }, nested.Children.Select(c => c is NonTerminalNode n ? n.RuleName : ((TerminalNode)c).TokenType));
+    }
+
+    [Theory]
+    [InlineData("IDENTIFIER", "fn")]
+    [InlineData("IDENTIFIER", "a b")]
+    [InlineData("LIFETIME", "'a")]
+    public void Token_TextNotLexingAsTheKind_Throws(string kind, string text)
+    {
+        // Arrange
+        var ast = new AstBuilder(LoadRustItems());
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => ast.Token(kind, text));
+    }
+
+    private static CompiledGrammar LoadRustItems()
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(File.ReadAllText(ExamplePath("rust_items.grammar"))));
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", file);
+    }
+}
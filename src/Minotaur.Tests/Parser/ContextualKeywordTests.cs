@@ -0,0 +1,115 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for contextual keyword functionality
+/// </summary>
+public class ContextualKeywordTests
+{
+    private const string UnionGrammar = """
+        <items> ::= <item> | <items> <item>
+        <item> ::= <union_definition> | <struct_definition> | <function>
+        <union_definition> ::= "union" <IDENTIFIER> "{" <fields> "}"
+        <struct_definition> ::= "struct" <IDENTIFIER> "{" <fields> "}"
+        <fields> ::= <field> | <fields> "," <field>
+        <field> ::= <IDENTIFIER> ":" <IDENTIFIER>
+        <function> ::= "fn" <IDENTIFIER> "(" ")" "{" <statement> "}"
+        <statement> ::= "let" <IDENTIFIER> "=" <IDENTIFIER> ";"
+        """;
+
+    private const string UnionInput = """
+        union IntOrFloat { i: u32, f: f32 }
+        struct union { union: u32 }
+        fn union() { let union = union; }
+        """;
+
+    [Fact]
+    public void Parse_ContextualKeyword_IsKeywordOnlyWhereItsRuleExpectsIt()
+    {
+        // Arrange
+        var parser = CreateParser("ContextualKeywords: union = union_definition\n");
+
+        // Act
+        var result = parser.Parse(UnionInput);
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var unions = Terminals(result.Tree!).Where(t => t.Text == "union").ToList();
+        Assert.Equal(6, unions.Count);
+        Assert.Equal("\"union\"", unions[0].TokenType);
+        Assert.Equal("union_definition", ((NonTerminalNode)unions[0].Parent!).RuleName);
+        Assert.All(unions.Skip(1), t => Assert.Equal("IDENTIFIER", t.TokenType));
+        Assert.All(result.Tokens.Where(t => t.Text == "union"), t => Assert.Equal("IDENTIFIER", t.Kind));
+    }
+
+    [Fact]
+    public void Parse_ReservedKeyword_RejectsIdentifierUse()
+    {
+        // Arrange
+        var parser = CreateParser();
+
+        // Act
+        var result = parser.Parse(UnionInput);
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Contains("Unexpected \"union\" 'union'", result.Diagnostics[0].Message);
+    }
+
+    [Fact]
+    public void Parse_SyntaxError_ExpectsContextualKeywordOnlyInItsContext()
+    {
+        // Arrange
+        var parser = CreateParser("ContextualKeywords: union = union_definition\n");
+
+        // Act
+        var itemStart = parser.Parse("; union U { a: b }");
+        var expression = parser.Parse("fn f() { let x = ; }");
+
+        // Assert
+        Assert.Equal(new[] { "\"fn\"", "\"struct\"", "\"union\"" }, (List<string>)itemStart.Diagnostics[0].Data["expected"]);
+        Assert.Equal(new[] { "IDENTIFIER" }, (List<string>)expression.Diagnostics[0].Data["expected"]);
+    }
+
+    [Fact]
+    public void Compile_ContextualKeywordOfUndefinedRule_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("ContextualKeywords: union = union_item\n" + UnionGrammar);
+
+        // Act & Assert
+        Assert.Contains("union_item", Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar)).Message);
+    }
+
+    private static GeneralizedParser CreateParser(string headers = "")
+    {
+        var grammar = new GrammarFileReader().Read(headers + UnionGrammar);
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+
+    private static IEnumerable<TerminalNode> Terminals(CognitiveGraphNode node)
+    {
+        return node is TerminalNode terminal ? new[] { terminal } : node.Children.SelectMany(Terminals);
+    }
+}
@@ -0,0 +1,273 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Visitors;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for parsing and traversing deeply nested input without recursion
+/// </summary>
+public class DeepNestingTests
+{
+    private const int Depth = 100_000;
+
+    private const string ParenthesesGrammar = """
+        <expr> ::= "(" <expr> ")" | <NUMBER>
+        """;
+
+    [Fact]
+    public void Parse_DeeplyNestedExpression_BuildsTreeAndCanBeDropped()
+    {
+        // Arrange
+        var parser = CreateParser(ParenthesesGrammar);
+
+        // Act
+        var weak = ParseAndRelease(parser);
+        GC.Collect();
+        GC.WaitForPendingFinalizers();
+        GC.Collect();
+
+        // Assert
+        Assert.False(weak.IsAlive);
+    }
+
+    [Fact]
+    public void Parse_DeeplyNestedExpression_HasOneRuleNodePerLevel()
+    {
+        // Arrange
+        var parser = CreateParser(ParenthesesGrammar);
+
+        // Act
+        var result = parser.Parse(Nested(Depth));
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var (depth, innermost) = Descend(result.Tree!);
+        Assert.Equal(Depth + 1, depth);
+        Assert.Equal("1", Assert.IsType<TerminalNode>(Assert.Single(innermost.Children)).Text);
+    }
+
+    [Fact]
+    public void Clone_DeeplyNestedTree_CopiesEveryLevel()
+    {
+        // Arrange
+        var tree = CreateParser(ParenthesesGrammar).Parse(Nested(Depth)).Tree!;
+
+        // Act
+        var clone = tree.Clone();
+
+        // Assert
+        Assert.NotSame(tree, clone);
+        Assert.Equal(Depth + 1, Descend(clone).Depth);
+    }
+
+    [Fact]
+    public void FindNodeAt_DeeplyNestedTree_ReturnsInnermostNode()
+    {
+        // Arrange
+        var tree = CreateParser(ParenthesesGrammar).Parse(Nested(Depth)).Tree!;
+
+        // Act
+        var node = tree.FindNodeAt(new SourcePosition(1, Depth + 1, Depth, 1));
+
+        // Assert
+        var terminal = Assert.IsType<TerminalNode>(node);
+        Assert.Equal("1", terminal.Text);
+    }
+
+    [Fact]
+    public void Visit_DeeplyNestedTree_CallsHooksInDepthFirstOrder()
+    {
+        // Arrange
+        var tree = CreateParser(ParenthesesGrammar).Parse(Nested(Depth)).Tree!;
+        var visitor = new OrderVisitor();
+
+        // Act
+        tree.Accept(visitor);
+
+        // Assert
+        Assert.Equal(3 * Depth + 2, visitor.Entered.Count);
+        Assert.Equal(visitor.Entered.Count, visitor.Left.Count);
+        Assert.Same(tree, visitor.Entered[0]);
+        Assert.Same(tree, visitor.Left[^1]);
+        Assert.Equal("(", ((TerminalNode)visitor.Entered[1]).Text);
+        Assert.Same(visitor.Entered[1], visitor.Left[0]);
+    }
+
+    [Fact]
+    public void Visit_NestedTree_KeepsChildOrderAroundTerminals()
+    {
+        // Arrange
+        var tree = CreateParser(ParenthesesGrammar).Parse("((2))").Tree!;
+        var visitor = new OrderVisitor();
+
+        // Act
+        visitor.Visit(tree);
+
+        // Assert
+        var entered = visitor.Entered.Select(n => n is TerminalNode t ? t.Text : ((NonTerminalNode)n).RuleName);
+        var left = visitor.Left.Select(n => n is TerminalNode t ? t.Text : ((NonTerminalNode)n).RuleName);
+        Assert.Equal(new[] { "expr", "(", "expr", "(", "expr", "2", ")", ")" }, entered);
+        Assert.Equal(new[] { "(", "(", "2", "expr", ")", "expr", ")", "expr" }, left);
+    }
+
+    [Fact]
+    public void Visit_OverriddenVisitChildren_FinishesEachChildBeforeContinuing()
+    {
+        // Arrange
+        var tree = CreateParser(ParenthesesGrammar).Parse("((2))").Tree!;
+        var visitor = new ChildCountingVisitor();
+
+        // Act
+        visitor.Visit(tree);
+
+        // Assert
+        Assert.Equal(
+            new[]
+            {
+                "enter expr", "enter (", "leave (", "after child (", "enter expr", "enter (", "leave (", "after child (",
+                "enter expr", "enter 2", "leave 2", "after child 2", "leave expr", "after child expr", "enter )", "leave )",
+                "after child )", "leave expr", "after child expr", "enter )", "leave )", "after child )", "leave expr"
+            },
+            visitor.Events);
+    }
+
+    [Fact]
+    public void Visit_OverriddenVisit_RunsPostAfterDescendants()
+    {
+        // Arrange
+        var tree = CreateParser(ParenthesesGrammar).Parse("((2))").Tree!;
+        var visitor = new PrePostVisitor();
+
+        // Act
+        visitor.Visit(tree);
+
+        // Assert
+        Assert.Equal(
+            new[]
+            {
+                "pre expr", "pre (", "post (", "pre expr", "pre (", "post (", "pre expr", "pre 2", "post 2", "post expr",
+                "pre )", "post )", "post expr", "pre )", "post )", "post expr"
+            },
+            visitor.Events);
+    }
+
+    [MethodImpl(MethodImplOptions.NoInlining)]
+    private static WeakReference ParseAndRelease(GeneralizedParser parser)
+    {
+        var result = parser.Parse(Nested(Depth));
+        Assert.True(result.IsSuccess);
+        return new WeakReference(result.Tree);
+    }
+
+    private static string Nested(int depth)
+    {
+        return new string('(', depth) + "1" + new string(')', depth);
+    }
+
+    private static (int Depth, CognitiveGraphNode Innermost) Descend(CognitiveGraphNode root)
+    {
+        var depth = 1;
+        var current = root;
+        while (current.Children.OfType<NonTerminalNode>().FirstOrDefault() is { } child)
+        {
+            current = child;
+            depth++;
+        }
+
+        return (depth, current);
+    }
+
+    private static CompiledGrammar Compile(string grammar)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(grammar));
+    }
+
+    private static GeneralizedParser CreateParser(string grammar)
+    {
+        return new GeneralizedParser(Compile(grammar));
+    }
+
+    private sealed class ChildCountingVisitor : CognitiveGraphVisitorBase
+    {
+        public List<string> Events { get; } = new();
+
+        public override void VisitChildren(CognitiveGraphNode node)
+        {
+            foreach (var child in node.Children)
+            {
+                Visit(child);
+                Events.Add($"after child {Name(child)}");
+            }
+        }
+
+        protected override void BeforeVisitNode(CognitiveGraphNode node)
+        {
+            Events.Add($"enter {Name(node)}");
+        }
+
+        protected override void AfterVisitNode(CognitiveGraphNode node)
+        {
+            Events.Add($"leave {Name(node)}");
+        }
+
+        private static string Name(CognitiveGraphNode node)
+        {
+            return node is TerminalNode terminal ? terminal.Text : ((NonTerminalNode)node).RuleName;
+        }
+    }
+
+    private sealed class PrePostVisitor : CognitiveGraphVisitorBase
+    {
+        public List<string> Events { get; } = new();
+
+        public override void Visit(CognitiveGraphNode node)
+        {
+            Events.Add($"pre {Name(node)}");
+            base.Visit(node);
+            Events.Add($"post {Name(node)}");
+        }
+
+        private static string Name(CognitiveGraphNode node)
+        {
+            return node is TerminalNode terminal ? terminal.Text : ((NonTerminalNode)node).RuleName;
+        }
+    }
+
+    private sealed class OrderVisitor : CognitiveGraphVisitorBase
+    {
+        public List<CognitiveGraphNode> Entered { get; } = new();
+
+        public List<CognitiveGraphNode> Left { get; } = new();
+
+        protected override void BeforeVisitNode(CognitiveGraphNode node)
+        {
+            Entered.Add(node);
+        }
+
+        protected override void AfterVisitNode(CognitiveGraphNode node)
+        {
+            Left.Add(node);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for captured delimiter functionality
+/// </summary>
+public class DelimiterPolicyTests
+{
+    private const string ShellGrammar = """
+        Delimiters: <HEREDOC> captures tag lines <HEREDOC_BODY> <HEREDOC_END>
+        <script> ::= <command> | <script> <command>
+        <command> ::= <WORD> <HEREDOC> <HEREDOC_BODY> <HEREDOC_END> | <WORD> <WORD>
+        <HEREDOC> ::= /<<-?([A-Za-z_]+)/
+        <WORD> ::= /[A-Za-z0-9_.]+/
+        """;
+
+    private const string XmlGrammar = """
+        Delimiters: <OPEN_TAG> captures name; <CLOSE_TAG> matches name
+        <document> ::= <element>
+        <element> ::= <OPEN_TAG> ">" <content> <CLOSE_TAG> ">"
+        <content> ::= <content> <item> | ε
+        <item> ::= <element> | <TEXT>
+        <OPEN_TAG> ::= /<([A-Za-z]+)/
+        <CLOSE_TAG> ::= /<\/([A-Za-z]+)/
+        <TEXT> ::= /[^<>]+/
+        """;
+
+    [Fact]
+    public void Parse_Heredoc_ReadsBodyUntilTerminatorLine()
+    {
+        // Arrange
+        var parser = CreateParser(ShellGrammar);
+
+        // Act
+        var result = parser.Parse("cat <<EOF\nhello $name\n  EOF is not the end\nEOF\necho done\n");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(new[] { "WORD", "HEREDOC", "HEREDOC_BODY", "HEREDOC_END", "WORD", "WORD" }, result.Tokens.Select(t => t.Kind));
+        Assert.Equal("hello $name\n  EOF is not the end\n", result.Tokens[2].Text);
+        Assert.Equal(new Token("HEREDOC_END", "EOF", 43), result.Tokens[3]);
+        Assert.Equal(47, result.Tokens[4].Offset);
+    }
+
+    [Fact]
+    public void Tokenize_HeredocsOnOneLine_ReadsBodiesInOrder()
+    {
+        // Arrange
+        var lexer = new GrammarLexer(Compile(ShellGrammar));
+
+        // Act
+        var result = lexer.Tokenize("paste <<A <<-B\na1\nA\n\tb1\n\tB\n");
+
+        // Assert
+        Assert.Empty(result.Diagnostics);
+        Assert.Equal(
+            new[] { "WORD", "HEREDOC", "HEREDOC", "HEREDOC_BODY", "HEREDOC_END", "HEREDOC_BODY", "HEREDOC_END" },
+            result.Tokens.Select(t => t.Kind));
+        Assert.Equal("a1\n", result.Tokens[3].Text);
+        Assert.Equal("\tb1\n", result.Tokens[5].Text);
+        Assert.Equal("B", result.Tokens[6].Text);
+    }
+
+    [Fact]
+    public void Parse_UnterminatedHeredoc_ReportsExpectedDelimiter()
+    {
+        // Arrange
+        var parser = CreateParser(ShellGrammar);
+
+        // Act
+        var result = parser.Parse("cat <<EOF\nhello\n");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        var diagnostic = Assert.Single(result.Diagnostics, d => d.Code == DiagnosticCodes.MismatchedDelimiter);
+        Assert.Equal("EOF", diagnostic.Data["expected"]);
+        Assert.Equal(6, ((SourcePosition)diagnostic.Data["related"]).Offset);
+        Assert.Equal(16, diagnostic.Location!.Offset);
+    }
+
+    [Fact]
+    public void Parse_MatchingEndTags_Succeeds()
+    {
+        // Arrange
+        var parser = CreateParser(XmlGrammar);
+
+        // Act
+        var result = parser.Parse("<a>hi <b>x</b> there</a>");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+    }
+
+    [Fact]
+    public void Parse_MismatchedEndTag_ReportsBothDelimiters()
+    {
+        // Arrange
+        var parser = CreateParser(XmlGrammar);
+
+        // Act
+        var result = parser.Parse("<a></b>");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.MismatchedDelimiter, diagnostic.Code);
+        Assert.Equal("Closing delimiter 'b' does not match 'a' opened at line 1, column 2", diagnostic.Message);
+        Assert.Equal("a", diagnostic.Data["expected"]);
+        Assert.Equal("b", diagnostic.Data["found"]);
+        Assert.Equal(5, diagnostic.Location!.Offset);
+        Assert.Equal(1, ((SourcePosition)diagnostic.Data["related"]).Offset);
+    }
+
+    [Fact]
+    public void Parse_EndTagClosingOuterElement_ComparesWithInnermostOpenTag()
+    {
+        // Arrange
+        var parser = CreateParser(XmlGrammar);
+
+        // Act
+        var result = parser.Parse("<a><b></a></b>");
+
+        // Assert
+        var mismatches = result.Diagnostics.Where(d => d.Code == DiagnosticCodes.MismatchedDelimiter).ToList();
+        Assert.Equal(2, mismatches.Count);
+        Assert.Equal(("b", "a"), ((string)mismatches[0].Data["expected"], (string)mismatches[0].Data["found"]));
+        Assert.Equal(("a", "b"), ((string)mismatches[1].Data["expected"], (string)mismatches[1].Data["found"]));
+    }
+
+    [Fact]
+    public void ApplyEdit_RenamedEndTag_RelexesAgainstOpenTag()
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateParser(XmlGrammar), "<a><b>x</b></a>");
+
+        // Act
+        var result = parser.ApplyEdit(new TextEdit(9, 1, "c"));
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Equal("c", Assert.Single(result.Diagnostics).Data["found"]);
+    }
+
+    [Theory]
+    [InlineData("Delimiters: <CLOSE_TAG> matches name")]
+    [InlineData("Delimiters: <OPEN_TAG> grabs name")]
+    [InlineData("Delimiters: <MISSING> captures name")]
+    [InlineData("Delimiters: <OPEN_TAG> captures name lines <TEXT>")]
+    public void Create_InvalidDelimiters_Throws(string header)
+    {
+        // Arrange
+        var grammar = Compile(XmlGrammar.Replace(XmlGrammar.Split('\n')[0], header));
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => new GrammarLexer(grammar));
+    }
+
+    private static CompiledGrammar Compile(string grammar)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(grammar));
+    }
+
+    private static GeneralizedParser CreateParser(string grammar)
+    {
+        return new GeneralizedParser(Compile(grammar));
+    }
+}
@@ -0,0 +1,298 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for unclosed delimiter recovery functionality
+/// </summary>
+public class DelimiterRecoveryTests
+{
+    private const string Recovery = "DelimiterRecovery: outdent\n";
+    private const string StackRecovery = "DelimiterRecovery: stack\n";
+
+    private const string Unclosed = """
+        fn second(x: i32) -> i32 {
+            let b = x;
+            if b > 0 {
+            b
+        }
+
+        fn third(y: i32) -> i32 {
+            y
+        }
+        """;
+
+    [Fact]
+    public void Parse_UnclosedBlock_ClosesItAtTheNextOutdent()
+    {
+        // Arrange
+        var parser = CreateParser(Recovery + ReadExampleGrammar());
+
+        // Act
+        var result = parser.Parse(Unclosed);
+
+        // Assert
+        Assert.NotNull(result.Tree);
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnclosedDelimiter, diagnostic.Code);
+        Assert.Equal("'{' opened at line 3, column 14 is never closed; assuming a missing '}' before this line", diagnostic.Message);
+        Assert.Equal((4, 5), (diagnostic.Location!.Line, diagnostic.Location.Column));
+        var region = Assert.IsType<TextRange>(diagnostic.Data["region"]);
+        Assert.Equal(Unclosed.IndexOf("if b > 0 {") + 9, region.Start);
+
+        var closer = Assert.Single(result.Tokens, t => t.IsSynthetic);
+        Assert.Equal("\"}\"", closer.Kind);
+        Assert.Equal(region.End, closer.Offset);
+    }
+
+    [Fact]
+    public void Parse_UnclosedBlock_LeavesTheFollowingFunctionsParsed()
+    {
+        // Arrange
+        var parser = CreateParser(Recovery + ReadExampleGrammar());
+
+        // Act
+        var result = parser.Parse(Unclosed);
+
+        // Assert
+        var functions = Functions(result.Tree!);
+        Assert.Equal(new[] { 1, 7 }, functions.Select(f => f.SourcePosition!.Line));
+        Assert.Equal(5, functions[0].SourcePosition!.EndLine);
+    }
+
+    [Fact]
+    public void Parse_BlockOpenAtEndOfInput_ClosesItThere()
+    {
+        // Arrange
+        var parser = CreateParser(Recovery + ReadExampleGrammar());
+        const string input = "fn main() {\n    let a = 1;\n";
+
+        // Act
+        var result = parser.Parse(input);
+
+        // Assert
+        Assert.NotNull(result.Tree);
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnclosedDelimiter, diagnostic.Code);
+        Assert.Equal(input.Length - 1, Assert.Single(result.Tokens, t => t.IsSynthetic).Offset);
+    }
+
+    [Fact]
+    public void Parse_WithoutRecoveryMetadata_FailsAsBefore()
+    {
+        // Arrange
+        var parser = CreateParser(ReadExampleGrammar());
+
+        // Act
+        var result = parser.Parse(Unclosed);
+
+        // Assert
+        Assert.Null(result.Tree);
+        Assert.DoesNotContain(result.Diagnostics, d => d.Code == DiagnosticCodes.UnclosedDelimiter);
+    }
+
+    [Fact]
+    public void Parse_ValidInput_IsLeftAlone()
+    {
+        // Arrange
+        var parser = CreateParser(Recovery + ReadExampleGrammar());
+        const string input = "fn main() {\n    if a > 0 {\n        a\n    }\n}\n";
+
+        // Act
+        var result = parser.Parse(input);
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.DoesNotContain(result.Tokens, t => t.IsSynthetic);
+    }
+
+    [Fact]
+    public void FromGrammar_UnknownMode_Throws()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read("DelimiterRecovery: sometimes\n" + ReadExampleGrammar()));
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => new GeneralizedParser(grammar));
+    }
+
+    [Fact]
+    public void FromGrammar_Outdent_UsesTheGrammarsBracketPairs()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(Recovery + ReadExampleGrammar()));
+
+        // Act
+        var policy = DelimiterRecoveryPolicy.FromGrammar(grammar);
+
+        // Assert
+        Assert.NotNull(policy);
+        Assert.Contains(policy!.Brackets, p => p.Open == "{" && p.Close == "}");
+        Assert.Contains(policy.Brackets, p => p.Open == "(" && p.Close == ")");
+        Assert.DoesNotContain(policy.Brackets, p => p.Open == "<");
+    }
+
+    [Fact]
+    public void Parse_StackMode_BraceDeletedFromExample_LeavesTheOtherItemsClean()
+    {
+        // Arrange
+        var parser = CreateParser(StackRecovery + ReadExampleGrammar());
+        var example = ReadExample("calls.rs");
+        var input = example.Remove(example.IndexOf("        return low;\n    }\n") + "        return low;\n".Length, "    }\n".Length);
+
+        // Act
+        var result = parser.Parse(input);
+
+        // Assert
+        Assert.NotNull(result.Tree);
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnclosedDelimiter, diagnostic.Code);
+        Assert.EndsWith("assuming a missing '}' before this line", diagnostic.Message);
+        Assert.Equal((6, 5), (diagnostic.Location!.Line, diagnostic.Location.Column));
+
+        var items = Nodes(result.Tree!, "item");
+        Assert.Equal(4, items.Count);
+        var clean = items.Where(i => diagnostic.Location.Line < i.SourcePosition!.Line || diagnostic.Location.Line > i.SourcePosition.EndLine);
+        Assert.Equal(3, clean.Count());
+        Assert.Equal(3, Functions(result.Tree!).Count);
+    }
+
+    [Fact]
+    public void Parse_StackMode_MismatchedCloser_TakesItForTheMatchingOne()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(StackRecovery + ReadExampleGrammar()));
+        var path = ExamplePath(Path.Combine("recovery", "mismatched_closer.rs"));
+
+        // Act
+        var result = new GeneralizedParser(grammar).Parse(File.ReadAllText(path).ReplaceLineEndings("\n"));
+
+        // Assert
+        ParseSnapshot.AssertExpectedDiagnostics(grammar, path);
+        Assert.Equal(new[] { 1, 5 }, Functions(result.Tree!).Select(f => f.SourcePosition!.Line));
+    }
+
+    [Fact]
+    public void Parse_StackMode_ExtraCloser_DropsIt()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(StackRecovery + ReadExampleGrammar()));
+        var path = ExamplePath(Path.Combine("recovery", "extra_closer.rs"));
+
+        // Act
+        var result = new GeneralizedParser(grammar).Parse(File.ReadAllText(path).ReplaceLineEndings("\n"));
+
+        // Assert
+        ParseSnapshot.AssertExpectedDiagnostics(grammar, path);
+        Assert.Equal(new[] { 1, 6 }, Functions(result.Tree!).Select(f => f.SourcePosition!.Line));
+    }
+
+    [Fact]
+    public void Parse_OutdentMode_ExtraCloser_FailsAsBefore()
+    {
+        // Arrange
+        var parser = CreateParser(Recovery + ReadExampleGrammar());
+
+        // Act
+        var result = parser.Parse(ReadExample(Path.Combine("recovery", "extra_closer.rs")));
+
+        // Assert
+        Assert.Null(result.Tree);
+        Assert.DoesNotContain(result.Diagnostics, d => d.Code == DiagnosticCodes.UnexpectedDelimiter);
+    }
+
+    [Fact]
+    public void FromGrammar_Stack_ReadsTheLookahead()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(StackRecovery + "DelimiterRecoveryLookahead: 32\n" + ReadExampleGrammar()));
+
+        // Act
+        var policy = DelimiterRecoveryPolicy.FromGrammar(grammar);
+
+        // Assert
+        Assert.NotNull(policy);
+        Assert.Equal(DelimiterRecoveryMode.Stack, policy!.Mode);
+        Assert.Equal(32, policy.Lookahead);
+    }
+
+    [Fact]
+    public void FromGrammar_ZeroLookahead_Throws()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(StackRecovery + "DelimiterRecoveryLookahead: 0\n" + ReadExampleGrammar()));
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => DelimiterRecoveryPolicy.FromGrammar(grammar));
+    }
+
+    private static List<NonTerminalNode> Functions(CognitiveGraphNode tree)
+    {
+        return Nodes(tree, "function");
+    }
+
+    private static List<NonTerminalNode> Nodes(CognitiveGraphNode tree, string rule)
+    {
+        var nodes = new List<NonTerminalNode>();
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(tree);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node is NonTerminalNode found && found.RuleName == rule)
+            {
+                nodes.Add(found);
+            }
+
+            foreach (var child in node.Children)
+            {
+                pending.Push(child);
+            }
+        }
+
+        return nodes.OrderBy(f => f.SourcePosition!.Offset).ToList();
+    }
+
+    private static GeneralizedParser CreateParser(string grammarText)
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(grammarText)));
+    }
+
+    private static string ReadExampleGrammar()
+    {
+        return ReadExample("rust_items.grammar");
+    }
+
+    private static string ReadExample(string file)
+    {
+        return File.ReadAllText(ExamplePath(file));
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", file);
+    }
+}
@@ -0,0 +1,106 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for DerivationExplanation functionality
+/// </summary>
+public class DerivationExplanationTests
+{
+    [Fact]
+    public async Task ExplainNode_OperatorExpression_NamesResponsibleDeclarations()
+    {
+        // Arrange
+        var grammar = await new GrammarFileReader().ReadFileAsync(ExamplePath("fixity.grammar"));
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(grammar));
+        var result = parser.Parse("infixl 6 -;\ninfixl 7 *;\nprefix 9 -;\nx = 1 - -2 * 3;\n", new ParseOptions { RecordDerivations = true });
+        var expression = Descendants(result.Tree!).OfType<NonTerminalNode>().Single(n => n.RuleName == "binding").Children[2];
+
+        // Act
+        var explanation = result.ExplainNode(expression.Id);
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var grouping = explanation.Steps.Where(s => s.Kind == DerivationDecisionKind.Precedence).ToList();
+        Assert.Equal(2, grouping.Count);
+        Assert.Equal(new[] { "infixl 7 *", "infixl 6 -" }, grouping[0].Declarations.Select(d => d.ToString()));
+        Assert.Equal(0, grouping[0].Depth);
+        Assert.Equal(new[] { "prefix 9 -", "infixl 7 *" }, grouping[1].Declarations.Select(d => d.ToString()));
+        Assert.Equal(1, grouping[1].Depth);
+
+        var operand = explanation.Steps.First(s => s.Rule == "operand");
+        Assert.Equal(DerivationDecisionKind.Alternative, operand.Kind);
+        Assert.Equal("1", operand.Lookahead);
+
+        var text = explanation.ToString();
+        Assert.StartsWith("<expr> applies '-' as infixl 6 -", text);
+        Assert.Contains("<expr> groups '*' (infixl 7 *) into its right operand because it binds tighter than '-' (infixl 6 -)", text);
+        Assert.Contains("  <expr> groups '-' (prefix 9 -) into its left operand because it binds tighter than '*' (infixl 7 *)", text);
+    }
+
+    [Fact]
+    public void ExplainNode_AmbiguousInput_ReportsOrdering()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read("""
+            <statement> ::= "if" <IDENTIFIER> "then" <statement> | "if" <IDENTIFIER> "then" <statement> "else" <statement> | <IDENTIFIER>
+            """)));
+        var result = parser.Parse("if a then if b then x else y", new ParseOptions { RecordDerivations = true });
+
+        // Act
+        var step = result.ExplainNode(result.Tree!.Id).Steps[0];
+
+        // Assert
+        Assert.Equal(DerivationDecisionKind.Ordering, step.Kind);
+        Assert.Equal(2, step.Derivations);
+        Assert.Equal(0, step.Alternative!.Index);
+        Assert.Equal("if", step.Lookahead);
+        Assert.EndsWith("the earliest of 2 derivations", step.Reason);
+    }
+
+    [Fact]
+    public void ExplainNode_WithoutRecording_Throws()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read("<start> ::= <IDENTIFIER>")));
+        var result = parser.Parse("x");
+
+        // Act & Assert
+        Assert.Throws<InvalidOperationException>(() => result.ExplainNode(result.Tree!.Id));
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Descendants(CognitiveGraphNode node)
+    {
+        yield return node;
+        foreach (var descendant in node.Children.SelectMany(Descendants))
+        {
+            yield return descendant;
+        }
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "fixity", file);
+    }
+}
@@ -0,0 +1,251 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for comment directive functionality
+/// </summary>
+public class DirectiveTests
+{
+    private const string BlockGrammar = """
+        Directives: "// minotaur:"
+        DeclarationRules: statement
+        <COMMENT> ::= /\/\/[^\n]*/
+        <program> ::= <item> | <program> <item>
+        <item> ::= <statement> | <block>
+        <block> ::= "{" <program> "}"
+        <statement> ::= <IDENTIFIER> "=" <NUMBER> ";"
+        """;
+
+    [Fact]
+    public void Read_RegionSpanningNestedScopes_ContainsTheCodeBetweenItsDirectives()
+    {
+        // Arrange
+        const string input = """
+            a = 1;
+            {
+              // minotaur: begin fast
+              b = 2;
+              {
+                c = 3;
+              }
+            }
+            // minotaur: end fast
+            d = 4;
+            """;
+        var parse = Parse(input);
+
+        // Act
+        var directives = DirectiveSet.Read(parse);
+
+        // Assert
+        Assert.True(parse.IsSuccess);
+        Assert.Empty(directives.Diagnostics);
+        var region = Assert.Single(directives.Regions);
+        Assert.Equal("fast", region.Name);
+        Assert.Equal(DirectiveScope.RegionStart, region.Start.Scope);
+        Assert.Equal(DirectiveScope.RegionEnd, region.End.Scope);
+        Assert.True(region.Contains(input.IndexOf("b =", StringComparison.Ordinal)));
+        Assert.True(region.Contains(input.IndexOf("c =", StringComparison.Ordinal)));
+        Assert.False(region.Contains(input.IndexOf("a =", StringComparison.Ordinal)));
+        Assert.False(region.Contains(input.IndexOf("d =", StringComparison.Ordinal)));
+        Assert.Same(region.Start, Assert.Single(directives.GetApplying("fast", input.IndexOf("c =", StringComparison.Ordinal))));
+    }
+
+    [Fact]
+    public void Read_NestedRegionsOfOneName_PairInnermostFirst()
+    {
+        // Arrange
+        const string input = """
+            // minotaur: begin fast
+            a = 1;
+            {
+              // minotaur: begin fast
+              b = 2;
+              // minotaur: end fast
+            }
+            c = 3;
+            // minotaur: end fast
+            """;
+
+        // Act
+        var directives = DirectiveSet.Read(Parse(input));
+
+        // Assert
+        Assert.Empty(directives.Diagnostics);
+        Assert.Equal(2, directives.Regions.Count);
+        Assert.Equal(new[] { 1, 4 }, directives.Regions.Select(r => r.Start.Location.Line));
+        Assert.Equal(new[] { 9, 6 }, directives.Regions.Select(r => r.End.Location.Line));
+        Assert.Equal(2, directives.GetApplying("fast", input.IndexOf("b =", StringComparison.Ordinal)).Count());
+        Assert.Single(directives.GetApplying("fast", input.IndexOf("c =", StringComparison.Ordinal)));
+    }
+
+    [Theory]
+    [InlineData("// minotaur: allow reason=", "gives no value for 'reason'")]
+    [InlineData("// minotaur:", "has no name")]
+    [InlineData("// minotaur: 9lives", "has an invalid name '9lives'")]
+    [InlineData("// minotaur: allow \"W0002", "has an unterminated quote")]
+    public void Read_MalformedDirective_ReportsALocatedWarning(string comment, string reason)
+    {
+        // Arrange
+        var input = $"a = 1;\n  {comment}\nb = 2;";
+
+        // Act
+        var directives = DirectiveSet.Read(Parse(input));
+
+        // Assert
+        Assert.Empty(directives.Directives);
+        var diagnostic = Assert.Single(directives.Diagnostics);
+        Assert.Equal(DiagnosticCodes.MalformedDirective, diagnostic.Code);
+        Assert.Equal(DiagnosticSeverity.Warning, diagnostic.Severity);
+        Assert.Contains(reason, diagnostic.Message);
+        Assert.Equal(2, diagnostic.Location!.Line);
+        Assert.Equal(3, diagnostic.Location.Column);
+        Assert.Equal(comment.Length, diagnostic.Location.Length);
+    }
+
+    [Fact]
+    public void Read_UnpairedRegionDirectives_ReportUnmatchedWarnings()
+    {
+        // Arrange
+        const string input = "// minotaur: end fast\na = 1;\n// minotaur: begin slow\nb = 2;";
+
+        // Act
+        var directives = DirectiveSet.Read(Parse(input));
+
+        // Assert
+        Assert.Empty(directives.Regions);
+        Assert.Equal(2, directives.Diagnostics.Count);
+        Assert.All(directives.Diagnostics, d => Assert.Equal(DiagnosticCodes.UnmatchedDirective, d.Code));
+        Assert.Equal(new[] { 1, 3 }, directives.Diagnostics.Select(d => d.Location!.Line));
+    }
+
+    [Fact]
+    public void Read_NextNodeDirectives_AttachToTheCodeTheyPrecedeOrTrail()
+    {
+        // Arrange
+        const string input = """
+            {
+              // minotaur: inline
+              b = 2;
+              c = 3;
+            }
+            d = 4; // minotaur: cold level=2
+            e = 5;
+            """;
+        var parse = Parse(input);
+
+        // Act
+        var directives = DirectiveSet.Read(parse);
+
+        // Assert
+        var leading = Assert.Single(directives.Get("inline"));
+        Assert.Equal(DirectiveScope.NextNode, leading.Scope);
+        Assert.Equal("b = 2;", TextOf(input, leading));
+
+        var trailing = Assert.Single(directives.Get("cold"));
+        Assert.Equal("d = 4;", TextOf(input, trailing));
+        Assert.Equal("2", trailing.Arguments["level"]);
+        Assert.Single(directives.GetApplying("cold", input.IndexOf("4;", StringComparison.Ordinal)));
+        Assert.Empty(directives.GetApplying("cold", input.IndexOf("e =", StringComparison.Ordinal)));
+    }
+
+    [Fact]
+    public void Read_FileDirective_AppliesEverywhere()
+    {
+        // Arrange
+        const string input = "a = 1;\n// minotaur: file dialect strict \"two words\"\nb = 2;";
+        var parse = Parse(input);
+
+        // Act
+        var directives = DirectiveSet.Read(parse);
+
+        // Assert
+        var directive = Assert.Single(directives.GetFileDirectives("dialect"));
+        Assert.Equal(new[] { "strict", "two words" }, directive.Values);
+        Assert.Same(parse.Tree, directive.Target);
+        Assert.Same(directive, Assert.Single(directives.GetApplying("dialect", 0)));
+    }
+
+    [Fact]
+    public void Read_CommentsWithoutTheDeclaredPrefix_AreNotDirectives()
+    {
+        // Arrange
+        const string input = "// minotaur is a parser\n// another: allow\na = 1;";
+
+        // Act
+        var directives = DirectiveSet.Read(Parse(input));
+
+        // Assert
+        Assert.Empty(directives.Directives);
+        Assert.Empty(directives.Diagnostics);
+    }
+
+    [Fact]
+    public void Lint_AllowDirective_SuppressesTheListedWarningsWhereItApplies()
+    {
+        // Arrange
+        const string input = """
+            a = 1; // minotaur: allow W0002
+            // minotaur: begin allow
+            b = 2;
+            // minotaur: end allow
+            // minotaur: allow W0001
+            c = 3;
+            """;
+        var manager = new PassManager();
+        manager.RegisterBuiltInPasses();
+
+        // Act
+        var run = manager.Run(Parse(input));
+
+        // Assert
+        var unused = Assert.Single(run.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnusedSymbol, unused.Code);
+        Assert.Contains("'c'", unused.Message);
+    }
+
+    [Fact]
+    public void Compile_InvalidDirectivesHeader_Throws()
+    {
+        // Arrange
+        var unquoted = new GrammarFileReader().Read("Directives: // minotaur:\n<program> ::= <IDENTIFIER>");
+        var badPattern = new GrammarFileReader().Read("Directives: \"// minotaur:\" /(unclosed/\n<program> ::= <IDENTIFIER>");
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(unquoted));
+        Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(badPattern));
+    }
+
+    private static string TextOf(string input, Directive directive)
+    {
+        var position = directive.Target!.SourcePosition!;
+        return input.Substring(position.Offset, position.Length);
+    }
+
+    private static ParseResult Parse(string input)
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(BlockGrammar))).Parse(input);
+    }
+}
@@ -0,0 +1,181 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for multi-document parsing, over the JSON Lines and SQL script fixtures in Fixtures/documents
+/// </summary>
+public class DocumentParsingTests
+{
+    [Fact]
+    public void ParseDocuments_JsonLines_ParsesEveryLine()
+    {
+        // Arrange
+        var parser = CreateParser("jsonl.grammar");
+
+        // Act
+        var documents = parser.ParseDocuments(ReadFixture("records.jsonl")).ToList();
+
+        // Assert
+        Assert.Equal(5, documents.Count);
+        Assert.Equal(new[] { 1, 2, 3, 4, 5 }, documents.Select(d => d.Location.Line));
+        Assert.All(documents, d => Assert.Equal("value", d.Parse.StartRule));
+    }
+
+    [Fact]
+    public void ParseDocuments_JsonLinesWithBrokenRecord_OnlyThatRecordFails()
+    {
+        // Arrange
+        var parser = CreateParser("jsonl.grammar");
+
+        // Act
+        var documents = parser.ParseDocuments(ReadFixture("records.jsonl")).ToList();
+
+        // Assert
+        Assert.Equal(new[] { true, true, false, true, true }, documents.Select(d => d.Parse.IsSuccess));
+        var error = Assert.Single(documents[2].Parse.Diagnostics, d => d.Severity == DiagnosticSeverity.Error);
+        Assert.Equal((3, 28), (error.Location!.Line, error.Location.Column));
+        Assert.All(documents.Where(d => d.Index != 2), d => Assert.Empty(d.Parse.Diagnostics));
+    }
+
+    [Fact]
+    public void ParseDocuments_SqlScript_SplitsAtSeparators()
+    {
+        // Arrange
+        var parser = CreateParser("sql.grammar");
+        var input = ReadFixture("script.sql");
+
+        // Act
+        var documents = parser.ParseDocuments(input).ToList();
+
+        // Assert
+        Assert.Equal(4, documents.Count);
+        Assert.Equal(new[] { true, true, false, true }, documents.Select(d => d.Parse.IsSuccess));
+        Assert.DoesNotContain(documents.SelectMany(d => d.Parse.Tokens), t => t.Text == ";");
+
+        var last = documents[3].Location;
+        Assert.Equal("SELECT *\n  FROM orders", input.Substring(last.Offset, last.Length).ReplaceLineEndings("\n"));
+        Assert.Equal((4, 5), (last.Line, last.EndLine));
+    }
+
+    [Fact]
+    public void ParseDocuments_SqlScriptWithBrokenStatement_ReportsErrorOnItsLine()
+    {
+        // Arrange
+        var parser = CreateParser("sql.grammar");
+
+        // Act
+        var broken = parser.ParseDocuments(ReadFixture("script.sql")).Single(d => !d.Parse.IsSuccess);
+
+        // Assert
+        Assert.Equal(2, broken.Index);
+        var error = Assert.Single(broken.Parse.Diagnostics, d => d.Severity == DiagnosticSeverity.Error);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, error.Code);
+        Assert.Equal(3, error.Location!.Line);
+    }
+
+    [Fact]
+    public void ParseDocuments_TruncatedDocument_ReportsEndWhereDocumentEnds()
+    {
+        // Arrange
+        var parser = CreateParser("sql.grammar");
+
+        // Act
+        var documents = parser.ParseDocuments("SELECT * FROM;\nSELECT * FROM users;\n").ToList();
+
+        // Assert
+        var error = Assert.Single(documents[0].Parse.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnexpectedEndOfInput, error.Code);
+        Assert.Equal(13, error.Location!.Offset);
+        Assert.True(documents[1].Parse.IsSuccess);
+    }
+
+    [Fact]
+    public void ParseDocuments_EmptyDocuments_AreSkipped()
+    {
+        // Arrange
+        var parser = CreateParser("sql.grammar");
+
+        // Act
+        var documents = parser.ParseDocuments(";;SELECT * FROM users;;\n").ToList();
+
+        // Assert
+        var document = Assert.Single(documents);
+        Assert.Equal(0, document.Index);
+        Assert.True(document.Parse.IsSuccess);
+    }
+
+    [Fact]
+    public void Compile_DocumentsPolicy_IsExposed()
+    {
+        // Act
+        var newline = CreateParser("jsonl.grammar").Grammar.Documents!;
+        var separated = CreateParser("sql.grammar").Grammar.Documents!;
+
+        // Assert
+        Assert.Equal("value", newline.Rule.Name);
+        Assert.Null(newline.Separator);
+        Assert.Equal("<value> newline", newline.ToString());
+        Assert.Equal("\";\"", separated.Separator!.Key);
+    }
+
+    [Fact]
+    public void ParseDocuments_GrammarWithoutDocuments_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("Grammar: Plain\n<start> ::= <NUMBER>\n");
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(grammar));
+
+        // Act & Assert
+        Assert.Throws<InvalidOperationException>(() => parser.ParseDocuments("1"));
+    }
+
+    [Theory]
+    [InlineData("<start>")]
+    [InlineData("\";\" newline")]
+    [InlineData("<start> <start>")]
+    [InlineData("<undefined> \";\"")]
+    public void Compile_MalformedDocuments_Throws(string declaration)
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read($"Grammar: Broken\nDocuments: {declaration}\n<start> ::= <NUMBER> \";\"\n");
+
+        // Act
+        var ex = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+
+        // Assert
+        Assert.Contains("Documents", ex.Message);
+    }
+
+    private static GeneralizedParser CreateParser(string grammarFile)
+    {
+        var grammar = new GrammarFileReader().Read(ReadFixture(grammarFile));
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+
+    private static string ReadFixture(string file, [CallerFilePath] string path = "")
+    {
+        return File.ReadAllText(Path.Combine(Path.GetDirectoryName(path)!, "Fixtures", "documents", file));
+    }
+}
@@ -0,0 +1,198 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for grammar entry point functionality
+/// </summary>
+public class EntryPointTests
+{
+    private const string SignatureGrammar = """
+        EntryPoints: file = program; expression = expr prefix; type = type
+        <program> ::= <items>
+        <items> ::= <item> | <items> <item>
+        <item> ::= "fn" <IDENTIFIER> "(" <params> ")" ":" <type> "=" <expr> ";"
+        <params> ::= <param> | <params> "," <param>
+        <param> ::= <IDENTIFIER> ":" <type>
+        <type> ::= <IDENTIFIER> | <type> "[" "]" | "fn" "(" <types> ")" ":" <type>
+        <types> ::= <type> | <types> "," <type>
+        <expr> ::= <term> | <expr> "+" <term>
+        <term> ::= <IDENTIFIER> | <NUMBER> | <IDENTIFIER> "(" <args> ")" | "(" <expr> ")"
+        <args> ::= <expr> | <args> "," <expr>
+        """;
+
+    [Fact]
+    public void Compile_EntryPoints_AreDeclaredWithRulesAndEnds()
+    {
+        // Act
+        var grammar = Compile(SignatureGrammar);
+
+        // Assert
+        Assert.Equal(new[] { "file", "expression", "type" }, grammar.EntryPoints.Keys);
+        Assert.Equal("expression = expr prefix", grammar.EntryPoints["expression"].ToString());
+        Assert.Equal(EntryPointEnd.Complete, grammar.EntryPoints["type"].End);
+        Assert.Equal(new[] { "type", "types" }, grammar.EntryPoints["type"].Rules.Select(r => r.Name));
+        Assert.Equal(new[] { "expr", "term", "args" }, grammar.EntryPoints["expression"].Rules.Select(r => r.Name));
+        Assert.Equal(new[] { "\"(\"", "\")\"", "\"+\"", "\",\"", "IDENTIFIER", "NUMBER" }, grammar.EntryPoints["expression"].Terminals);
+        Assert.Equal("program", grammar.StartRule);
+    }
+
+    [Fact]
+    public void Compile_EntryPointTables_ShareStatesInsteadOfDuplicatingThem()
+    {
+        // Arrange
+        var grammar = Compile(SignatureGrammar);
+        var naive = grammar.EntryPoints.Values.Sum(e => e.StateCount);
+
+        // Act
+        var shared = grammar.EntryPointStateCount;
+
+        // Assert
+        // Every rule is reachable from the file entry point, so the expression and type tables add no states.
+        Assert.Equal(grammar.EntryPoints["file"].StateCount, shared);
+        Assert.Equal(grammar.Rules.SelectMany(r => r.Alternatives).Sum(a => a.Symbols.Count + 1), shared);
+        Assert.Equal(shared + grammar.EntryPoints["expression"].StateCount + grammar.EntryPoints["type"].StateCount, naive);
+        Assert.True(shared < naive);
+    }
+
+    [Fact]
+    public void ForEntry_Expression_ParsesLongestPrefix()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(Compile(SignatureGrammar)).ForEntry("expression");
+
+        // Act
+        var result = parser.Parse("a + f(1, b) ) ; trailing");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal("expression", parser.Entry!.Name);
+        Assert.Equal("expr", result.StartRule);
+        Assert.Equal(11, result.ParsedLength);
+        Assert.Equal(0, result.Tree!.SourcePosition!.Offset);
+        Assert.Equal(11, result.Tree.SourcePosition.Length);
+    }
+
+    [Fact]
+    public void ForEntry_Type_MustConsumeAllInput()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(Compile(SignatureGrammar)).ForEntry("type");
+
+        // Act
+        var complete = parser.Parse("fn(int[], str): bool");
+        var trailing = parser.Parse("int[] extra");
+
+        // Assert
+        Assert.True(complete.IsSuccess);
+        Assert.Equal(complete.Input.Length, complete.ParsedLength);
+        Assert.False(trailing.IsSuccess);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, Assert.Single(trailing.Diagnostics).Code);
+        Assert.Equal(0, trailing.ParsedLength);
+    }
+
+    [Fact]
+    public void Parse_EndOption_OverridesEntryPoint()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(Compile(SignatureGrammar)).ForEntry("expression");
+
+        // Act
+        var result = parser.Parse("a + b c", new ParseOptions { End = EntryPointEnd.Complete });
+
+        // Assert
+        Assert.False(result.IsSuccess);
+    }
+
+    [Fact]
+    public void ForEntry_SharesGrammarWithFileParser()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(Compile(SignatureGrammar));
+
+        // Act
+        var file = parser.Parse("fn add(a: int, b: int): int = a + b;");
+        var expression = parser.ForEntry("expression");
+
+        // Assert
+        Assert.True(file.IsSuccess);
+        Assert.Null(parser.Entry);
+        Assert.Same(parser.Grammar, expression.Grammar);
+    }
+
+    [Fact]
+    public void Compile_EntryPointName_SelectsItsRule()
+    {
+        // Act
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(SignatureGrammar), "expression");
+
+        // Assert
+        Assert.Equal("expr", grammar.StartRule);
+    }
+
+    [Fact]
+    public void ForEntry_UnknownName_Throws()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(Compile(SignatureGrammar));
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => parser.ForEntry("statement"));
+    }
+
+    [Fact]
+    public void ForRule_UndeclaredRule_ParsesWholeInputWithCachedTable()
+    {
+        // Arrange
+        var grammar = Compile(SignatureGrammar);
+        var parser = new GeneralizedParser(grammar);
+
+        // Act
+        var whole = parser.ForRule("param").Parse("xs: int[]");
+        var trailing = parser.ForRule("param").Parse("xs: int[] extra");
+
+        // Assert
+        Assert.True(whole.IsSuccess);
+        Assert.False(trailing.IsSuccess);
+        Assert.Same(grammar.GetRuleEntry("param"), grammar.GetRuleEntry("param"));
+        Assert.Equal(new[] { "param", "type", "types" }, grammar.GetRuleEntry("param").Rules.Select(r => r.Name));
+        Assert.Same(grammar.EntryPoints["type"], grammar.GetRuleEntry("type"));
+        Assert.Throws<ArgumentException>(() => parser.ForRule("statement"));
+    }
+
+    [Theory]
+    [InlineData("EntryPoints: file = missing")]
+    [InlineData("EntryPoints: expression = expr partial")]
+    [InlineData("EntryPoints: file = program; file = items")]
+    [InlineData("EntryPoints: = program")]
+    public void Compile_InvalidEntryPoints_Throws(string header)
+    {
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => Compile(SignatureGrammar.Replace(SignatureGrammar.Split('\n')[0], header)));
+    }
+
+    private static CompiledGrammar Compile(string grammar)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(grammar));
+    }
+}
@@ -0,0 +1,198 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Grammars;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for escape schemes: decoding string tokens and reporting malformed escapes from the lexer.
+/// </summary>
+public class EscapeSchemeTests
+{
+    private const string StringGrammar = """
+        <program> ::= <STRING> | <program> <STRING>
+        <STRING> ::= /[br]?(?:"(?:[^"\\]|\\[\s\S])*"|'(?:[^'\\]|\\[\s\S])*')/
+        // @escapes({0})
+        """;
+
+    [Theory]
+    [InlineData("json", "\"tab\\t\\\"q\\\" \\u00e9\"", "tab\t\"q\" é")]
+    [InlineData("json", "\"\\uD83D\\uDE00\"", "😀")]
+    [InlineData("rust", "\"\\u{10FFFF}\"", "\U0010FFFF")]
+    [InlineData("rust", "\"\\u{1F_600}\\x41\\0\"", "😀A\0")]
+    [InlineData("rust", "\"one \\\n    two\"", "one two")]
+    [InlineData("rust", "r#\"no \\n \"here\"#", "no \\n \"here")]
+    [InlineData("c", "\"\\x41\\101\\?\\U0001F600\"", "AA?😀")]
+    [InlineData("python", "'''it's \\N{BULLET}'''", "it's \\N{BULLET}")]
+    [InlineData("python", "r'\\d+'", "\\d+")]
+    [InlineData("json, e = 1B", "\"\\e[0m\"", "\u001B[0m")]
+    public void Decode_ValidEscapes_ReplacesThem(string scheme, string text, string expected)
+    {
+        // Act
+        var decoded = EscapeScheme.Parse(scheme).Decode(text);
+
+        // Assert
+        Assert.Empty(decoded.Errors);
+        Assert.Equal(expected, decoded.Value);
+    }
+
+    [Theory]
+    [InlineData("json", "\"a\\qb\"", 2, 2, "unknown")]
+    [InlineData("json", "\"\\u12\"", 1, 4, "digits")]
+    [InlineData("rust", "\"\\u{110000}\"", 1, 10, "range")]
+    [InlineData("rust", "\"\\u{D800}\"", 1, 8, "surrogate")]
+    [InlineData("rust", "\"\\u{}\"", 1, 4, "braces")]
+    [InlineData("rust", "\"\\xFF\"", 1, 4, "byte")]
+    [InlineData("c", "\"x\\777\"", 2, 4, "octal")]
+    [InlineData("n = 0A", "\"\\t\"", 1, 2, "unknown")]
+    public void Decode_MalformedEscape_ReportsItsExactSpan(string scheme, string text, int offset, int length, string reason)
+    {
+        // Act
+        var decoded = EscapeScheme.Parse(scheme).Decode(text);
+
+        // Assert
+        var error = Assert.Single(decoded.Errors);
+        Assert.Equal((offset, length, reason), (error.Offset, error.Length, error.Reason));
+        Assert.Equal(DiagnosticSeverity.Error, error.Severity);
+        Assert.False(decoded.IsValid);
+        Assert.Contains(text.Substring(offset, length), decoded.Value);
+    }
+
+    [Fact]
+    public void Tokenize_InvalidNamedEscape_KeepsTokenAndReportsEscape()
+    {
+        // Arrange
+        var lexer = new GrammarLexer(Compile("rust"));
+
+        // Act
+        var result = lexer.Tokenize("\"ok\" \"a\\qb\"");
+
+        // Assert
+        Assert.Equal(new[] { "STRING", "STRING" }, result.Tokens.Select(t => t.Kind));
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.InvalidEscape, diagnostic.Code);
+        Assert.Equal((7, 2), (diagnostic.Location!.Offset, diagnostic.Location.Length));
+        Assert.Equal("Invalid escape sequence '\\q': it is not an escape in this string", diagnostic.Message);
+        Assert.Equal("a\\qb", result.Tokens[1].DecodedText);
+    }
+
+    [Fact]
+    public void Parse_JsonLoneSurrogate_WarnsAtEscapeAndSucceeds()
+    {
+        // Arrange
+        const string input = "[\"x\\uD800\"]";
+
+        // Act
+        var result = new GeneralizedParser(BuiltInGrammars.Json).Parse(input);
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var warning = Assert.Single(result.Diagnostics, d => d.Code == DiagnosticCodes.SuspiciousEscape);
+        Assert.Equal(DiagnosticSeverity.Warning, warning.Severity);
+        Assert.Equal((3, 6), (warning.Location!.Offset, warning.Location.Length));
+        Assert.Equal("x\uD800", JsonValue.Parse(input).Items[0].GetString());
+    }
+
+    [Fact]
+    public void Parse_JsonInvalidEscape_IsRejectedAtTheEscape()
+    {
+        // Act
+        var exception = Assert.Throws<FormatException>(() => JsonValue.Parse("{\"key\": \"a\\x41\"}"));
+
+        // Assert
+        Assert.Contains("column 11", exception.Message);
+        Assert.Contains("'\\x'", exception.Message);
+    }
+
+    [Fact]
+    public void DecodedText_IsDecodedOnceAndOnlyWithAScheme()
+    {
+        // Arrange
+        var tokens = new GrammarLexer(Compile("python")).Tokenize("'\\d' b'\\x41'").Tokens;
+        var plain = new Token("STRING", "\"\\n\"", 0);
+
+        // Act
+        var first = tokens[0].DecodedText;
+
+        // Assert
+        Assert.Same(first, tokens[0].DecodedText);
+        Assert.Equal("\\d", first);
+        Assert.Equal("A", tokens[1].DecodedText);
+        Assert.Null(plain.DecodedText);
+    }
+
+    [Fact]
+    public void Tokenize_PythonUnknownEscape_IsAWarning()
+    {
+        // Act
+        var result = new GrammarLexer(Compile("python")).Tokenize("'\\d'");
+
+        // Assert
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.SuspiciousEscape, diagnostic.Code);
+        Assert.Equal(DiagnosticSeverity.Warning, diagnostic.Severity);
+    }
+
+    [Theory]
+    [InlineData("yaml")]
+    [InlineData("n = 0A, json")]
+    [InlineData("nl = 0A")]
+    [InlineData("n = D800")]
+    [InlineData("n = 0A, n = 0D")]
+    public void Parse_MalformedDeclaration_Throws(string declaration)
+    {
+        // Act & Assert
+        Assert.Throws<FormatException>(() => EscapeScheme.Parse(declaration));
+    }
+
+    [Fact]
+    public void Compile_EscapesOnProductionRule_Throws()
+    {
+        // Arrange
+        const string grammar = """
+            <program> ::= <STRING>
+            // @escapes(json)
+            """;
+
+        // Act
+        var exception = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(new GrammarFileReader().Read(grammar)));
+
+        // Assert
+        Assert.Contains("not a token pattern", exception.Message);
+    }
+
+    [Fact]
+    public void Compile_EscapeScheme_ChangesFingerprint()
+    {
+        // Act
+        var json = Compile("json");
+        var rust = Compile("rust");
+
+        // Assert
+        Assert.Equal("json", json.GetEscapeScheme("STRING")?.Name);
+        Assert.NotEqual(json.Fingerprint, rust.Fingerprint);
+    }
+
+    private static CompiledGrammar Compile(string scheme)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(StringGrammar.Replace("{0}", scheme)));
+    }
+}
@@ -0,0 +1,141 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using System.Text;
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for expected-phrase annotations. The messages before and after annotating the Rust signature grammar
+/// are kept in Snapshots/expected-phrases.txt.
+/// </summary>
+public class ExpectedPhraseTests
+{
+    private const string RustSignatureGrammar = """
+        Grammar: RustSignatures
+        StartRule: items
+        <items> ::= <function> | <items> <function>
+        <function> ::= "fn" <IDENTIFIER> "(" <params> ")" <block>
+            | "fn" <IDENTIFIER> "(" <params> ")" "->" <type> <block>
+        <block> ::= "{" "}"
+        <params> ::= <param> | <params> "," <param>
+        <param> ::= <IDENTIFIER> ":" <type>
+        <type> ::= <path> | "&" <type> | "&" "mut" <type> | "*" "const" <type> | "[" <type> "]" | "(" ")" | "!" | "_"
+        // @expected("a type")
+            | "fn" "(" ")" | "impl" <path> | "dyn" <path>
+        <path> ::= <IDENTIFIER> | <path> "::" <IDENTIFIER> | <path> "<" <type> ">"
+        // @expected("a path")
+        """;
+
+    [Fact]
+    public void Parse_FunctionSignatureTypos_NameTheExpectedConstruct()
+    {
+        // Arrange
+        var plain = CreateParser(string.Join("\n", RustSignatureGrammar.Split('\n').Where(l => !l.Contains("@expected"))));
+        var annotated = CreateParser(RustSignatureGrammar);
+        var inputs = new[]
+        {
+            "fn parse(input: &str, len: ) -> usize {}",
+            "fn parse(input &str) {}",
+            "fn wrap(v: Vec<>) {}",
+            "fn add(a: i32, b: i32) ->"
+        };
+
+        // Act
+        var report = new StringBuilder();
+        foreach (var input in inputs)
+        {
+            report.AppendLine(input);
+            report.AppendLine($"  before: {Assert.Single(plain.Parse(input).Diagnostics).Message}");
+            report.AppendLine($"  after:  {Assert.Single(annotated.Parse(input).Diagnostics).Message}");
+        }
+
+        // Assert
+        Snapshot.AssertMatches(report.ToString(), Path.Combine(TestDirectory(), "Snapshots", "expected-phrases.txt"));
+    }
+
+    [Fact]
+    public void Parse_AnnotatedRuleDominatesTheError_KeepsTheTerminalsForTools()
+    {
+        // Arrange
+        var parser = CreateParser(RustSignatureGrammar);
+
+        // Act
+        var result = parser.Parse("fn parse(input: &str, len: ) -> usize {}");
+
+        // Assert
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, diagnostic.Code);
+        Assert.Equal(new[] { "a type" }, (IEnumerable<string>)diagnostic.Data["expectedPhrases"]);
+        Assert.Equal(10, ((IEnumerable<string>)diagnostic.Data["expected"]).Count());
+        Assert.Contains("IDENTIFIER", (IEnumerable<string>)diagnostic.Data["expected"]);
+    }
+
+    [Fact]
+    public void Parse_ErrorOutsideAnnotatedRules_UsesTokenPhrases()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            StartRule: assignment
+            <assignment> ::= <NAME> "=" <NAME> ";"
+            <NAME> ::= /[a-z]+/
+            // @expected an identifier
+            """);
+
+        // Act
+        var result = parser.Parse("a = ;");
+
+        // Assert
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal("Unexpected \";\" ';'; expected an identifier", diagnostic.Message);
+        Assert.Equal(new[] { "NAME" }, (IEnumerable<string>)diagnostic.Data["expected"]);
+    }
+
+    [Fact]
+    public void Compile_ConflictingPhrasesForOneRule_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            <start> ::= <IDENTIFIER>
+            // @expected("a name")
+            // @expected("an identifier")
+            """);
+
+        // Act
+        var ex = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+
+        // Assert
+        Assert.Contains("line 3", ex.Message);
+        Assert.Contains("already named 'a name'", ex.Message);
+    }
+
+    private static GeneralizedParser CreateParser(string grammar)
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(grammar)));
+    }
+
+    private static string TestDirectory([CallerFilePath] string path = "")
+    {
+        return Path.GetDirectoryName(path)!;
+    }
+}
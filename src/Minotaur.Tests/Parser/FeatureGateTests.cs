@@ -0,0 +1,184 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for grammar feature gating functionality
+/// </summary>
+public class FeatureGateTests
+{
+    private const string ChainingGrammar = """
+        Features: optional-chaining = <member> ::= <member> "?." <IDENTIFIER>; pipeline = <pipe>
+        FeaturePragma: /\/\/ *@enable ([\w, -]+)/
+        <COMMENT> ::= /\/\/[^\n]*/
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <member> ";" | <pipe> ";"
+        <pipe> ::= <member> "|>" <member>
+        <member> ::= <IDENTIFIER> | <member> "." <IDENTIFIER> | <member> "?." <IDENTIFIER>
+        """;
+
+    [Fact]
+    public void Compile_FeaturesHeader_GuardsNamedAlternativesAndRules()
+    {
+        // Arrange & Act
+        var grammar = Compile(ChainingGrammar);
+
+        // Assert
+        Assert.Equal(new[] { "optional-chaining", "pipeline" }, grammar.Features);
+        var member = grammar.GetRule("member")!;
+        Assert.Equal(new string?[] { null, null, "optional-chaining" }, member.Alternatives.Select(a => a.Feature));
+        Assert.All(grammar.GetRule("pipe")!.Alternatives, a => Assert.Equal("pipeline", a.Feature));
+    }
+
+    [Fact]
+    public void Parse_DisabledFeature_ReportsTheFeatureInsteadOfASyntaxError()
+    {
+        // Arrange
+        var parser = CreateParser(ChainingGrammar);
+
+        // Act
+        var result = parser.Parse("a?.b;");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        var error = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.FeatureDisabled, error.Code);
+        Assert.Equal("This syntax requires feature 'optional-chaining' (<member> ::= <member> \"?.\" <IDENTIFIER>)", error.Message);
+        Assert.Equal("optional-chaining", error.Data["feature"]);
+        Assert.Equal("member", error.Data["rule"]);
+        Assert.Equal(0, error.Location!.Offset);
+        Assert.Equal(4, error.Location.Length);
+    }
+
+    [Fact]
+    public void Parse_FeatureEnabledByOptions_ParsesGuardedAlternative()
+    {
+        // Arrange
+        var parser = CreateParser(ChainingGrammar);
+
+        // Act
+        var result = parser.Parse("a?.b.c;", new ParseOptions { Features = new[] { "optional-chaining" } });
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Empty(result.Diagnostics);
+    }
+
+    [Fact]
+    public void Parse_FeatureEnabledByPragma_ParsesGuardedAlternative()
+    {
+        // Arrange
+        var parser = CreateParser(ChainingGrammar);
+
+        // Act
+        var result = parser.Parse("// @enable optional-chaining, pipeline\na?.b |> c;");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+    }
+
+    [Fact]
+    public void Parse_DisabledRuleFeature_NamesTheRulesFeature()
+    {
+        // Arrange
+        var parser = CreateParser(ChainingGrammar);
+
+        // Act
+        var result = parser.Parse("a.b;\na |> b;", new ParseOptions { Features = new[] { "optional-chaining" } });
+
+        // Assert
+        var error = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.FeatureDisabled, error.Code);
+        Assert.Equal("pipeline", error.Data["feature"]);
+        Assert.Equal(2, error.Location!.Line);
+    }
+
+    [Fact]
+    public void Parse_UnguardedSyntax_IsUnaffectedByDisabledFeatures()
+    {
+        // Arrange
+        var parser = CreateParser(ChainingGrammar);
+
+        // Act
+        var valid = parser.Parse("a.b.c;");
+        var invalid = parser.Parse("a . ;");
+
+        // Assert
+        Assert.True(valid.IsSuccess);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, Assert.Single(invalid.Diagnostics).Code);
+    }
+
+    [Fact]
+    public void ReadFeaturePragmas_IgnoresUndeclaredFeatures()
+    {
+        // Arrange
+        var grammar = Compile(ChainingGrammar);
+
+        // Act
+        var features = grammar.ReadFeaturePragmas("// @enable pipeline, decorators\n// @enable pipeline");
+
+        // Assert
+        Assert.Equal(new[] { "pipeline" }, features);
+    }
+
+    [Fact]
+    public void Read_FeaturePragma_IsAFileDirective()
+    {
+        // Arrange
+        var parser = CreateParser(ChainingGrammar);
+        var parse = parser.Parse("// @enable pipeline\na |> b;");
+
+        // Act
+        var directive = Assert.Single(DirectiveSet.Read(parse).GetFileDirectives(DirectiveSyntax.FeaturePragmaName));
+
+        // Assert
+        Assert.True(parse.IsSuccess);
+        Assert.Equal(new[] { "pipeline" }, directive.Values);
+        Assert.Same(parse.Tree, directive.Target);
+    }
+
+    [Theory]
+    [InlineData("Features: optional-chaining")]
+    [InlineData("Features: optional-chaining = <missing>")]
+    [InlineData("Features: optional-chaining = <member> ::= <member> \"!.\" <IDENTIFIER>")]
+    [InlineData("Features: a = <member>; b = <member> ::= <IDENTIFIER>")]
+    [InlineData("FeaturePragma: /(unclosed/")]
+    public void Compile_InvalidFeatureHeader_Throws(string header)
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(header + "\n<member> ::= <IDENTIFIER> | <member> \".\" <IDENTIFIER>");
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+    }
+
+    private static CompiledGrammar Compile(string grammar)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(grammar));
+    }
+
+    private static GeneralizedParser CreateParser(string grammar)
+    {
+        return new GeneralizedParser(Compile(grammar));
+    }
+}
@@ -0,0 +1,5 @@
+SELECT id, name FROM users WHERE id = 1;
+INSERT INTO users VALUES (3, "carol");
+SELECT name FROM WHERE id = 2;
+SELECT *
+  FROM orders;
@@ -0,0 +1,2 @@
+#include "limits.h"
+#define LIMIT BASE + 10
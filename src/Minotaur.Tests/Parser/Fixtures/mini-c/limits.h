@@ -0,0 +1 @@
+#define BASE 32
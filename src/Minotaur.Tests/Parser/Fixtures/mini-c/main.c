@@ -0,0 +1,4 @@
+#include "config.h"
+
+int size = LIMIT + 1;
+int total = size + BASE;
@@ -0,0 +1,330 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Xunit.Abstractions;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for GeneralizedParser functionality
+/// </summary>
+public class GeneralizedParserTests
+{
+    private const string ExpressionGrammar = """
+        Grammar: Expressions
+
+        <program> ::= <expr>
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | "(" <expr> ")"
+        """;
+
+    private const string DanglingElseGrammar = """
+        <statement> ::= "if" <IDENTIFIER> "then" <statement> | "if" <IDENTIFIER> "then" <statement> "else" <statement> | <IDENTIFIER>
+        """;
+
+    private readonly ITestOutputHelper _output;
+
+    public GeneralizedParserTests(ITestOutputHelper output)
+    {
+        _output = output;
+    }
+
+    [Fact]
+    public void Parse_LeftRecursiveGrammar_BuildsTree()
+    {
+        // Arrange
+        var parser = CreateParser(ExpressionGrammar);
+
+        // Act
+        var result = parser.Parse("1 + (2 + 3)");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.False(result.IsAmbiguous);
+        Assert.Equal(7, result.Tokens.Count);
+
+        var root = Assert.IsType<NonTerminalNode>(result.Tree);
+        Assert.Equal("program", root.RuleName);
+        Assert.Equal(0, root.SourcePosition!.Offset);
+        Assert.Equal(11, root.SourcePosition.Length);
+
+        var expr = Assert.IsType<NonTerminalNode>(Assert.Single(root.Children));
+        Assert.Equal(0, expr.ProductionIndex);
+        Assert.Equal(3, expr.Children.Count);
+        Assert.Equal("+", Assert.IsType<TerminalNode>(expr.Children[1]).Text);
+    }
+
+    [Fact]
+    public void Parse_AmbiguousInput_KeepsAllDerivationsInForest()
+    {
+        // Arrange
+        var parser = CreateParser(DanglingElseGrammar);
+
+        // Act
+        var result = parser.Parse("if a then if b then x else y");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.True(result.IsAmbiguous);
+        Assert.Equal(1, result.Forest!.AmbiguityCount);
+        Assert.Equal(2, result.Forest.Root.Packed.Count);
+
+        // The tree uses the first alternative at the root, so the else binds to the nearest if.
+        var root = Assert.IsType<NonTerminalNode>(result.Tree);
+        Assert.Equal(0, root.ProductionIndex);
+        Assert.Equal(2, root.Metadata["ambiguous"]);
+        var inner = Assert.IsType<NonTerminalNode>(root.Children[3]);
+        Assert.Equal(1, inner.ProductionIndex);
+    }
+
+    [Fact]
+    public void Parse_SharedSubtrees_AreSameForestNode()
+    {
+        // Arrange
+        var parser = CreateParser(DanglingElseGrammar);
+
+        // Act
+        var result = parser.Parse("if a then if b then x else y");
+
+        // Assert
+        var packed = result.Forest!.Root.Packed;
+        var trailing = (SymbolForestNode)packed[1].Children[5];
+        var nested = (SymbolForestNode)packed[0].Children[3];
+        Assert.Same(trailing, nested.Packed[0].Children[5]);
+        Assert.Equal(result.Forest.Nodes.Count, result.Forest.Nodes.Select(n => n.Id).Distinct().Count());
+    }
+
+    [Fact]
+    public void Parse_UnexpectedToken_ReportsExpectedTerminals()
+    {
+        // Arrange
+        var parser = CreateParser(ExpressionGrammar);
+
+        // Act
+        var result = parser.Parse("1 + + 2");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Null(result.Forest);
+
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, diagnostic.Code);
+        Assert.Equal(1, diagnostic.Location!.Line);
+        Assert.Equal(5, diagnostic.Location.Column);
+        Assert.Equal(new[] { "\"(\"", "NUMBER" }, (IEnumerable<string>)diagnostic.Data["expected"]);
+    }
+
+    [Fact]
+    public void Parse_TruncatedInput_ReportsUnexpectedEndOfInput()
+    {
+        // Arrange
+        var parser = CreateParser(ExpressionGrammar);
+
+        // Act
+        var result = parser.Parse("(1 + 2");
+
+        // Assert
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnexpectedEndOfInput, diagnostic.Code);
+        Assert.Contains("\")\"", (IEnumerable<string>)diagnostic.Data["expected"]);
+    }
+
+    [Fact]
+    public void Parse_UnrecognizedCharacter_ReportsLexicalDiagnostic()
+    {
+        // Arrange
+        var parser = CreateParser(ExpressionGrammar);
+
+        // Act
+        var result = parser.Parse("1 + $2");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Contains(result.Diagnostics, d => d.Code == DiagnosticCodes.UnrecognizedCharacter);
+    }
+
+    [Fact]
+    public void Parse_NullableRules_AcceptEmptyDerivations()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            <list> ::= "[" <items> "]"
+            <items> ::= <item> <items> | ε
+            <item> ::= <modifier> <IDENTIFIER>
+            <modifier> ::= "mut" | ""
+            """);
+
+        // Act
+        var empty = parser.Parse("[]");
+        var filled = parser.Parse("[a mut b c]");
+
+        // Assert
+        Assert.True(empty.IsSuccess);
+        Assert.True(filled.IsSuccess);
+        Assert.False(filled.IsAmbiguous);
+    }
+
+    [Fact]
+    public void Parse_WithStartRuleOption_ParsesFragment()
+    {
+        // Arrange
+        var parser = CreateParser(ExpressionGrammar);
+
+        // Act
+        var result = parser.Parse("(4)", new ParseOptions { StartRule = "term" });
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal("term", result.StartRule);
+    }
+
+    [Fact]
+    public void Parse_PathologicalAmbiguityWithWatchdog_ReportsStallInGuiltyRule()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            <program> ::= <header> <list>
+            <header> ::= "begin"
+            <list> ::= <list> <list> | "a"
+            """);
+        var input = "begin" + string.Concat(Enumerable.Repeat(" a", 5000));
+
+        // Act
+        var result = parser.Parse(input, new ParseOptions { Watchdog = StrictWatchdog() });
+
+        // Assert
+        var stall = Assert.Single(result.Diagnostics);
+        var ruleStack = Assert.IsType<List<string>>(stall.Data["ruleStack"]);
+        Assert.False(result.IsSuccess);
+        Assert.Null(result.Tree);
+        Assert.Equal(DiagnosticCodes.ParseStalled, stall.Code);
+        Assert.Equal("program", ruleStack[0]);
+        Assert.Equal("list", ruleStack[^1]);
+        Assert.Contains("in program > list", stall.Message);
+    }
+
+    [Fact]
+    public void Parse_LinearGrammarWithWatchdog_CompletesWithoutStall()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            <program> ::= <header> <list>
+            <header> ::= "begin"
+            <list> ::= <list> "a" | "a"
+            """);
+        var input = "begin" + string.Concat(Enumerable.Repeat(" a", 5000));
+
+        // Act
+        var result = parser.Parse(input, new ParseOptions { Watchdog = StrictWatchdog() });
+
+        // Assert
+        Assert.True(result.IsSuccess);
+    }
+
+    [Fact]
+    public void Parse_Input_IsSharedWithResultWithoutCopying()
+    {
+        // Arrange
+        var parser = CreateParser(ExpressionGrammar);
+        var input = new string("1 + 2".ToCharArray());
+
+        // Act
+        var result = parser.Parse(input);
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Same(input, result.Input);
+    }
+
+    [Fact]
+    public void Parse_MemoryOverWholeString_SharesTheString()
+    {
+        // Arrange
+        var parser = CreateParser(ExpressionGrammar);
+        var input = new string("1 + 2".ToCharArray());
+
+        // Act
+        var result = parser.Parse(input.AsMemory());
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Same(input, result.Input);
+    }
+
+    [Fact]
+    public void Parse_MemoryOverSliceOrArray_ParsesTheSpannedText()
+    {
+        // Arrange
+        var parser = CreateParser(ExpressionGrammar);
+        var cached = "// header\n1 + 2";
+
+        // Act
+        var slice = parser.Parse(cached.AsMemory(10));
+        var array = parser.Parse(new ReadOnlyMemory<char>("3 + 4".ToCharArray()));
+
+        // Assert
+        Assert.True(slice.IsSuccess);
+        Assert.Equal("1 + 2", slice.Input);
+        Assert.Equal(0, slice.Tokens[0].Offset);
+        Assert.True(array.IsSuccess);
+        Assert.Equal("3 + 4", array.Input);
+    }
+
+    [Fact]
+    public void Parse_MemoryInput_CopyBenchmark()
+    {
+        // Arrange
+        var parser = CreateParser(ExpressionGrammar);
+        var cached = string.Join(" + ", Enumerable.Range(0, 5000));
+        var whole = cached.AsMemory();
+        var slice = ("// cached\n" + cached).AsMemory(10);
+        parser.Parse("1 + 2".AsMemory());
+
+        // Act
+        var wholeBytes = GC.GetAllocatedBytesForCurrentThread();
+        var shared = parser.Parse(whole);
+        wholeBytes = GC.GetAllocatedBytesForCurrentThread() - wholeBytes;
+        var sliceBytes = GC.GetAllocatedBytesForCurrentThread();
+        var copied = parser.Parse(slice);
+        sliceBytes = GC.GetAllocatedBytesForCurrentThread() - sliceBytes;
+
+        // Assert
+        _output.WriteLine($"{cached.Length} chars, {shared.Tokens.Count} tokens");
+        _output.WriteLine($"memory over the whole string: {wholeBytes / 1024} KiB allocated");
+        _output.WriteLine($"memory over a slice (one copy of {slice.Length * sizeof(char) / 1024} KiB): {sliceBytes / 1024} KiB allocated");
+
+        Assert.Same(cached, shared.Input);
+        Assert.Equal(cached, copied.Input);
+        Assert.Equal(shared.Tokens.Count, copied.Tokens.Count);
+    }
+
+    private static ParseWatchdogOptions StrictWatchdog()
+    {
+        return new ParseWatchdogOptions { Interval = TimeSpan.FromMilliseconds(200), MinTokensPerInterval = 2000 };
+    }
+
+    private static GeneralizedParser CreateParser(string grammarText)
+    {
+        var grammar = new GrammarFileReader().Read(grammarText);
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+}
@@ -0,0 +1,260 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.IO.Compression;
+using System.Text;
+using Xunit;
+using Minotaur.Parser;
+using Minotaur.Projects.Grammar;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for GrammarBundle functionality
+/// </summary>
+public sealed class GrammarBundleTests : IDisposable
+{
+    private static readonly string[] Inputs = { "x = 1 + 2;", "let y = x;\nz = (y + 3);", "x = ;", "let = 1;" };
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"bundle_{Guid.NewGuid():N}");
+
+    public GrammarBundleTests()
+    {
+        Directory.CreateDirectory(Path.Combine(_directory, "calc", "docs"));
+        Directory.CreateDirectory(Path.Combine(_directory, "calc", "queries"));
+        Write("calc/base.grammar", """
+            Grammar: base
+            StartRule: program
+
+            <program> ::= <statement> | <statement> <program>
+            <statement> ::= <IDENTIFIER> "=" <expr> ";"
+            <expr> ::= <expr> "+" <term> | <term>
+            <term> ::= <NUMBER> | <IDENTIFIER> | "(" <expr> ")"
+            """);
+        Write("calc/calc.grammar", """
+            Grammar: calc
+            Version: 1.4.0
+            Inherits: base
+
+            <statement> ::= <IDENTIFIER> "=" <expr> ";" | "let" <IDENTIFIER> "=" <expr> ";"
+            """);
+        Write("calc/calc.de.messages", "E0001.character = Unerwartetes Zeichen '{character}'\n");
+        Write("calc/detector.json", """[{ "name": "calc-let", "pattern": "^let\\s", "mapping": { "grammar": "calc.grammar", "confidence": 0.8 }, "priority": 5 }]""");
+        Write("calc/docs/statements.md", "# Statements\n\nAssignments end with a semicolon.\n");
+        Write("calc/queries/highlights.scm", "(statement \"let\" @keyword)\n");
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_directory, recursive: true);
+    }
+
+    [Fact]
+    public async Task LoadGrammar_RoundTrip_ParsesLikeLooseFiles()
+    {
+        // Arrange
+        var path = Path.Combine(_directory, "calc.mgb");
+        var manifest = GrammarBundle.Create(Path.Combine(_directory, "calc"), path);
+        var loose = (await GrammarContainer.LoadDirectoryAsync(Path.Combine(_directory, "calc"))).GetGrammar("calc")!;
+
+        // Act
+        using var bundle = GrammarBundle.Open(path);
+        var bundled = bundle.LoadGrammar();
+
+        // Assert
+        Assert.Equal("calc", manifest.Name);
+        Assert.Equal("1.4.0", manifest.Version);
+        Assert.Equal(loose.Fingerprint, bundled.Fingerprint);
+        Assert.Equal(manifest.Fingerprint, bundled.Fingerprint);
+        foreach (var input in Inputs)
+        {
+            Assert.Equal(
+                ParseTreeExport.ToJson(new GeneralizedParser(loose).Parse(input)),
+                ParseTreeExport.ToJson(new GeneralizedParser(bundled).Parse(input)));
+        }
+    }
+
+    [Fact]
+    public void LoadGrammar_ReadsOnlyGrammarSources()
+    {
+        // Arrange
+        var path = Path.Combine(_directory, "calc.mgb");
+        GrammarBundle.Create(Path.Combine(_directory, "calc"), path);
+        using var bundle = GrammarBundle.Open(path);
+
+        // Act
+        bundle.LoadGrammar();
+
+        // Assert
+        Assert.Equal(new[] { "base.grammar", "calc.grammar" }, bundle.LoadedArtifacts);
+        Assert.Equal(
+            new[] { "base.grammar", "calc.de.messages", "calc.grammar", "detector.json", "docs/statements.md", "queries/highlights.scm" },
+            bundle.Manifest.Artifacts.Select(a => a.Path));
+    }
+
+    [Fact]
+    public void ReadArtifacts_OnDemand_ReturnsBundledContent()
+    {
+        // Arrange
+        var path = Path.Combine(_directory, "calc.mgb");
+        GrammarBundle.Create(Path.Combine(_directory, "calc"), path);
+        using var bundle = GrammarBundle.Open(path);
+
+        // Act
+        var doc = Assert.Single(bundle.GetArtifacts(GrammarBundleArtifactKind.Doc));
+        var query = Assert.Single(bundle.GetArtifacts(GrammarBundleArtifactKind.Query));
+        var rule = Assert.Single(bundle.LoadDetectorRules());
+        var catalog = Assert.Single(bundle.LoadMessageCatalogs());
+
+        // Assert
+        Assert.StartsWith("# Statements", bundle.ReadText(doc.Path));
+        Assert.Equal("(statement \"let\" @keyword)\n", bundle.ReadText(query.Path));
+        Assert.Equal("calc-let", rule.Name);
+        Assert.Equal("calc.grammar", rule.Mapping.Grammar);
+        Assert.Equal("de", catalog.Locale);
+        Assert.Equal(new[] { "calc.de.messages", "detector.json", "docs/statements.md", "queries/highlights.scm" }, bundle.LoadedArtifacts);
+    }
+
+    [Fact]
+    public void Create_SameDirectoryTwice_WritesIdenticalBytes()
+    {
+        // Arrange
+        var first = Path.Combine(_directory, "first.mgb");
+        var second = Path.Combine(_directory, "second.mgb");
+
+        // Act
+        GrammarBundle.Create(Path.Combine(_directory, "calc"), first);
+        GrammarBundle.Create(Path.Combine(_directory, "calc"), second);
+
+        // Assert
+        Assert.Equal(File.ReadAllBytes(first), File.ReadAllBytes(second));
+    }
+
+    [Fact]
+    public void Open_OutsideCompatibilityRange_Throws()
+    {
+        // Arrange
+        Write("calc/bundle.json", """{ "name": "calc-next", "minotaur": ">=99.0.0 <100.0.0" }""");
+        var path = Path.Combine(_directory, "calc.mgb");
+        GrammarBundle.Create(Path.Combine(_directory, "calc"), path);
+
+        // Act
+        var exception = Assert.Throws<GrammarBundleException>(() => GrammarBundle.Open(path));
+
+        // Assert
+        Assert.Contains("calc-next", exception.Message);
+        Assert.Contains(">=99.0.0 <100.0.0", exception.Message);
+        using var future = GrammarBundle.Open(path, new GrammarVersion(99, 2));
+        Assert.Equal("calc", future.LoadGrammar().Name);
+    }
+
+    [Fact]
+    public void Open_DefaultRange_RejectsNextMajorVersion()
+    {
+        // Arrange
+        var path = Path.Combine(_directory, "calc.mgb");
+        GrammarBundle.Create(Path.Combine(_directory, "calc"), path);
+        var next = new GrammarVersion(GrammarBundle.RuntimeVersion.Major + 1);
+
+        // Act & Assert
+        Assert.Throws<GrammarBundleException>(() => GrammarBundle.Open(path, next));
+        GrammarBundle.Open(path, GrammarBundle.RuntimeVersion).Dispose();
+    }
+
+    [Fact]
+    public void ReadArtifact_TamperedContent_FailsIntegrityCheck()
+    {
+        // Arrange
+        var path = Path.Combine(_directory, "calc.mgb");
+        GrammarBundle.Create(Path.Combine(_directory, "calc"), path);
+        using (var archive = ZipFile.Open(path, ZipArchiveMode.Update))
+        {
+            archive.GetEntry("base.grammar")!.Delete();
+            using var stream = archive.CreateEntry("base.grammar").Open();
+            stream.Write(Encoding.UTF8.GetBytes("Grammar: base\nStartRule: program\n<program> ::= <NUMBER>\n"));
+        }
+
+        using var bundle = GrammarBundle.Open(path);
+
+        // Act
+        var exception = Assert.Throws<GrammarBundleException>(() => bundle.LoadGrammar());
+
+        // Assert
+        Assert.Contains("base.grammar", exception.Message);
+        Assert.Equal("Assignments end with a semicolon.", bundle.ReadText("docs/statements.md").Split('\n')[2]);
+    }
+
+    [Fact]
+    public void Open_UnlistedEntry_Throws()
+    {
+        // Arrange
+        var path = Path.Combine(_directory, "calc.mgb");
+        GrammarBundle.Create(Path.Combine(_directory, "calc"), path);
+        using (var archive = ZipFile.Open(path, ZipArchiveMode.Update))
+        {
+            archive.CreateEntry("extra.grammar");
+        }
+
+        // Act
+        var exception = Assert.Throws<GrammarBundleException>(() => GrammarBundle.Open(path));
+
+        // Assert
+        Assert.Contains("extra.grammar", exception.Message);
+    }
+
+    [Fact]
+    public void Create_GrammarsDoNotCompile_WritesNothing()
+    {
+        // Arrange
+        Write("calc/broken.grammar", "Grammar: broken\nInherits: missing\n<a> ::= \"a\"\n");
+        var path = Path.Combine(_directory, "calc.mgb");
+
+        // Act
+        var exception = Assert.Throws<GrammarBundleException>(() => GrammarBundle.Create(Path.Combine(_directory, "calc"), path));
+
+        // Assert
+        Assert.Contains("missing", exception.Message);
+        Assert.False(File.Exists(path));
+    }
+
+    [Fact]
+    public async Task LoadDirectoryAsync_WithBundles_LoadsBundledGrammarsAndReportsIncompatibleOnes()
+    {
+        // Arrange
+        var grammars = Path.Combine(_directory, "grammars");
+        Directory.CreateDirectory(grammars);
+        GrammarBundle.Create(Path.Combine(_directory, "calc"), Path.Combine(grammars, "calc.mgb"));
+        Write("calc/bundle.json", """{ "minotaur": ">=99.0.0" }""");
+        GrammarBundle.Create(Path.Combine(_directory, "calc"), Path.Combine(grammars, "future.mgb"));
+        Write("grammars/other.grammar", "Grammar: other\nStartRule: a\n<a> ::= <NUMBER>\n");
+
+        // Act
+        var container = await GrammarContainer.LoadDirectoryAsync(grammars);
+
+        // Assert
+        Assert.Equal(new[] { "base", "calc", "other" }, container.Grammars.Keys);
+        var failure = Assert.Single(container.Failures);
+        Assert.Equal("future", failure.Name);
+        Assert.IsType<GrammarBundleException>(failure.Exception);
+        Assert.True(new GeneralizedParser(container.GetGrammar("calc")!).Parse("let x = 1;").IsSuccess);
+    }
+
+    private void Write(string path, string content)
+    {
+        File.WriteAllText(Path.Combine(_directory, path), content);
+    }
+}
@@ -0,0 +1,241 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using System.Text;
+using Xunit;
+using Xunit.Abstractions;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for GrammarContainer functionality
+/// </summary>
+public sealed class GrammarContainerTests : IDisposable
+{
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"grammars_{Guid.NewGuid():N}");
+    private readonly ITestOutputHelper _output;
+
+    public GrammarContainerTests(ITestOutputHelper output)
+    {
+        _output = output;
+        Directory.CreateDirectory(_directory);
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_directory, recursive: true);
+    }
+
+    [Fact]
+    public async Task LoadDirectoryAsync_WithInheritsAndImports_ResolvesRulesAndMetadata()
+    {
+        // Arrange
+        Write("base", """
+            Grammar: base
+            StartRule: program
+
+            <program> ::= <statement> | <statement> <program>
+            <statement> ::= <IDENTIFIER> ";"
+            """);
+        Write("literals", """
+            Grammar: literals
+
+            <literal> ::= <NUMBER> | <STRING>
+            """);
+        Write("derived", """
+            Grammar: derived
+            Inherits: base
+            Imports: literals
+
+            <statement> ::= <IDENTIFIER> "=" <literal> ";"
+            """);
+
+        // Act
+        var container = await GrammarContainer.LoadDirectoryAsync(_directory);
+
+        // Assert
+        Assert.True(container.IsSuccess);
+        Assert.Equal(new[] { "base", "derived", "literals" }, container.Grammars.Keys);
+        Assert.Equal(new[] { new[] { "base", "literals" }, new[] { "derived" } }, container.BuildLevels);
+
+        var derived = container.GetGrammar("derived")!;
+        Assert.Equal("program", derived.StartRule);
+        Assert.Equal(new[] { "<IDENTIFIER> \"=\" <literal> \";\"" }, derived.GetRule("statement")!.Alternatives.Select(a => a.Text));
+        Assert.True(new GeneralizedParser(derived).Parse("x = 1; y = \"s\";").IsSuccess);
+        Assert.False(new GeneralizedParser(derived).Parse("x;").IsSuccess);
+    }
+
+    [Fact]
+    public async Task LoadDirectoryAsync_WithFailures_LoadsUnrelatedGrammars()
+    {
+        // Arrange
+        Write("ok", "Grammar: ok\n\n<program> ::= <IDENTIFIER>");
+        Write("empty", "Grammar: empty");
+        Write("uses_empty", "Grammar: uses_empty\nImports: empty\n\n<program> ::= <NUMBER>");
+        Write("missing", "Grammar: missing\nInherits: nowhere\n\n<program> ::= <NUMBER>");
+        Write("cycle_a", "Grammar: cycle_a\nImports: cycle_b\n\n<a> ::= <NUMBER>");
+        Write("cycle_b", "Grammar: cycle_b\nImports: cycle_a\n\n<b> ::= <NUMBER>");
+        Write("ok_copy", "Grammar: ok\n\n<program> ::= <NUMBER>");
+
+        // Act
+        var container = await GrammarContainer.LoadDirectoryAsync(_directory);
+
+        // Assert
+        Assert.False(container.IsSuccess);
+        Assert.Equal(new[] { "ok" }, container.Grammars.Keys);
+        Assert.Equal("<IDENTIFIER>", container.GetGrammar("ok")!.Rules[0].Alternatives[0].Text);
+
+        var failures = container.Failures.ToDictionary(f => Path.GetFileNameWithoutExtension(f.Path!));
+        Assert.Equal(new[] { "cycle_a", "cycle_b", "empty", "missing", "ok_copy", "uses_empty" }, failures.Keys.Order());
+        Assert.Contains("cycle", failures["cycle_a"].Message);
+        Assert.Contains("already defined", failures["ok_copy"].Message);
+        Assert.IsType<ArgumentException>(failures["empty"].Exception);
+        Assert.Contains("'nowhere' is not in the container", failures["missing"].Message);
+        Assert.Contains("'empty' failed to load", failures["uses_empty"].Message);
+    }
+
+    [Fact]
+    public async Task LoadAsync_WithUnreadableFile_ReportsFailure()
+    {
+        // Arrange
+        var ok = Write("ok", "Grammar: ok\n\n<program> ::= <IDENTIFIER>");
+        var missing = Path.Combine(_directory, "gone.grammar");
+
+        // Act
+        var container = await GrammarContainer.LoadAsync(new[] { missing, ok });
+
+        // Assert
+        Assert.NotNull(container.GetGrammar("ok"));
+        var failure = Assert.Single(container.Failures);
+        Assert.Equal("gone", failure.Name);
+        Assert.IsType<FileNotFoundException>(failure.Exception);
+    }
+
+    [Fact]
+    public void Load_InMemoryGrammars_MatchesFileReaderDependencies()
+    {
+        // Arrange
+        var reader = new GrammarFileReader();
+        var grammars = new[]
+        {
+            reader.Read("Grammar: b\nImports: a, c\nInherits: a\n\n<program> ::= <x>"),
+            reader.Read("Grammar: a\n\n<x> ::= <IDENTIFIER>"),
+            reader.Read("Grammar: c\n\n<y> ::= <NUMBER>")
+        };
+
+        // Act
+        var container = GrammarContainer.Load(grammars);
+
+        // Assert
+        Assert.Equal(new[] { "a", "c" }, GrammarContainer.GetDependencies(grammars[0]));
+        Assert.True(container.IsSuccess);
+        Assert.Equal(new[] { "program", "x", "y" }, container.GetGrammar("b")!.Rules.Select(r => r.Name));
+    }
+
+    [Fact]
+    public async Task LoadDirectoryAsync_FiftyGrammars_ParallelIsIdenticalToSequential()
+    {
+        // Arrange - ten independent bases, each extended by four grammars that also import the next base.
+        for (var b = 0; b < 10; b++)
+        {
+            Write($"base{b}", SyntheticGrammar($"base{b}", null, null, 120));
+            for (var d = 0; d < 4; d++)
+            {
+                Write($"lang{b}_{d}", SyntheticGrammar($"lang{b}_{d}", $"base{b}", $"base{(b + 1) % 10}", 120));
+            }
+        }
+
+        var sequentialOptions = new GrammarContainerOptions { MaxDegreeOfParallelism = 1 };
+        await GrammarContainer.LoadDirectoryAsync(_directory, sequentialOptions);
+        await GrammarContainer.LoadDirectoryAsync(_directory);
+
+        // Act
+        var sequentialTime = Stopwatch.StartNew();
+        var sequential = await GrammarContainer.LoadDirectoryAsync(_directory, sequentialOptions);
+        sequentialTime.Stop();
+
+        var parallelTime = Stopwatch.StartNew();
+        var parallel = await GrammarContainer.LoadDirectoryAsync(_directory);
+        parallelTime.Stop();
+
+        // Assert
+        _output.WriteLine($"sequential: {sequentialTime.Elapsed.TotalMilliseconds:F1} ms");
+        _output.WriteLine($"parallel ({Environment.ProcessorCount} threads): {parallelTime.Elapsed.TotalMilliseconds:F1} ms");
+
+        Assert.True(sequential.IsSuccess);
+        Assert.Equal(50, parallel.Grammars.Count);
+        Assert.Equal(2, parallel.BuildLevels.Count);
+        Assert.Equal(sequential.BuildLevels, parallel.BuildLevels);
+        Assert.Equal(
+            sequential.Grammars.Select(g => (g.Key, g.Value.Fingerprint)),
+            parallel.Grammars.Select(g => (g.Key, g.Value.Fingerprint)));
+    }
+
+    [Fact]
+    public async Task Fingerprint_RepeatedParallelLoads_IsStable()
+    {
+        // Arrange
+        for (var i = 0; i < 8; i++)
+        {
+            Write($"g{i}", SyntheticGrammar($"g{i}", i > 0 ? $"g{i - 1}" : null, i > 1 ? "g0" : null, 10));
+        }
+
+        // Act
+        var first = await GrammarContainer.LoadDirectoryAsync(_directory);
+        var second = await GrammarContainer.LoadDirectoryAsync(_directory);
+
+        // Assert
+        Assert.Equal(8, first.BuildLevels.Count);
+        Assert.Equal(first.Grammars.Values.Select(g => g.Fingerprint), second.Grammars.Values.Select(g => g.Fingerprint));
+        Assert.NotEqual(first.GetGrammar("g1")!.Fingerprint, first.GetGrammar("g2")!.Fingerprint);
+    }
+
+    private string Write(string fileName, string content)
+    {
+        var path = Path.Combine(_directory, fileName + ".grammar");
+        File.WriteAllText(path, content);
+        return path;
+    }
+
+    private static string SyntheticGrammar(string name, string? inherits, string? imports, int rules)
+    {
+        var text = new StringBuilder();
+        text.AppendLine($"Grammar: {name}");
+        if (inherits != null)
+        {
+            text.AppendLine($"Inherits: {inherits}");
+        }
+
+        if (imports != null)
+        {
+            text.AppendLine($"Imports: {imports}");
+        }
+
+        text.AppendLine();
+        text.AppendLine($"<program> ::= <{name}_r0> | <program> <{name}_r0>");
+        for (var i = 0; i < rules; i++)
+        {
+            var next = i + 1 < rules ? $"<{name}_r{i + 1}>" : "<IDENTIFIER>";
+            text.AppendLine($"<{name}_r{i}> ::= {next} \"op{i}\" {next} | \"(\" {next} \")\" | {next} | <NUMBER>");
+        }
+
+        return text.ToString();
+    }
+}
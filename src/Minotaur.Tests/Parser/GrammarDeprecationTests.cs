@@ -0,0 +1,224 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Visualization;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for GrammarDeprecation functionality
+/// </summary>
+public class GrammarDeprecationTests
+{
+    private const string EchoGrammar = """
+        Grammar: Echo
+        Directives: "// minotaur:"
+        StartRule: program
+        <COMMENT> ::= /\/\/[^\n]*/
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= "print" <expr> ";"
+        // @description Writes a value to the output.
+            | "echo" <expr> ";"
+        // @deprecated("Use print instead", since = "2.0", replace_with = "print $expr ;")
+        <expr> ::= <IDENTIFIER> | <NUMBER>
+        """;
+
+    [Fact]
+    public void Read_DeprecationAfterContinuationLine_AnnotatesTheAlternativesOnThatLine()
+    {
+        // Act
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(EchoGrammar));
+
+        // Assert
+        var statement = grammar.GetRule("statement")!;
+        Assert.Null(statement.Alternatives[0].Deprecation);
+        var deprecation = statement.Alternatives[1].Deprecation;
+        Assert.NotNull(deprecation);
+        Assert.False(deprecation!.AppliesToRule);
+        Assert.Equal("Use print instead", deprecation.Message);
+        Assert.Equal("2.0", deprecation.Since);
+        Assert.Equal("print $expr ;", deprecation.ReplaceWith);
+    }
+
+    [Fact]
+    public void Parse_DeprecatedAlternative_WarnsWithAQuickFixThatParses()
+    {
+        // Arrange
+        var parser = CreateParser(EchoGrammar);
+        const string input = "print a;\necho b;\n";
+
+        // Act
+        var result = parser.Parse(input);
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var warning = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.DeprecatedSyntax, warning.Code);
+        Assert.Equal(DiagnosticSeverity.Warning, warning.Severity);
+        Assert.Equal("<statement> ::= \"echo\" <expr> \";\" is deprecated since 2.0: Use print instead", warning.Message);
+        Assert.Equal(2, warning.Location!.Line);
+        Assert.Equal("2.0", warning.Data["since"]);
+
+        var fix = Assert.IsType<TextEdit>(warning.Data["fix"]);
+        var fixedInput = fix.Apply(input);
+        Assert.Equal("print a;\nprint b ;\n", fixedInput);
+        var reparsed = parser.Parse(fixedInput);
+        Assert.True(reparsed.IsSuccess);
+        Assert.Empty(reparsed.Diagnostics);
+    }
+
+    [Fact]
+    public void Parse_AllowDirective_SuppressesTheDeprecationWarning()
+    {
+        // Arrange
+        var parser = CreateParser(EchoGrammar);
+
+        // Act
+        var allowed = parser.Parse("// minotaur: allow W0011\necho a;\necho b;\n");
+        var otherCode = parser.Parse("// minotaur: allow W0002\necho a;\n");
+
+        // Assert
+        var warning = Assert.Single(allowed.Diagnostics);
+        Assert.Equal(3, warning.Location!.Line);
+        Assert.Equal(DiagnosticCodes.DeprecatedSyntax, Assert.Single(otherCode.Diagnostics).Code);
+    }
+
+    [Fact]
+    public void Parse_DeprecatedRuleWithoutReplacement_WarnsWithoutAFix()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            StartRule: program
+            <program> ::= <statement> | <program> <statement>
+            <statement> ::= "print" <expr> ";" | <legacy>
+            <legacy> ::= "goto" <IDENTIFIER> ";" | "gosub" <IDENTIFIER> ";"
+            // @deprecated "Jumps are going away"
+            <expr> ::= <IDENTIFIER>
+            """);
+
+        // Act
+        var result = parser.Parse("goto a;\ngosub b;\n");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(2, result.Diagnostics.Count);
+        Assert.All(result.Diagnostics, d => Assert.Equal("<legacy> is deprecated: Jumps are going away", d.Message));
+        Assert.All(result.Diagnostics, d => Assert.False(d.Data.ContainsKey("fix")));
+        Assert.True(parser.Grammar.GetRule("legacy")!.Alternatives.All(a => a.Deprecation!.AppliesToRule));
+    }
+
+    [Fact]
+    public void Compile_ReplacementWithUnknownLabel_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(EchoGrammar.Replace("print $expr ;", "print $value ;"));
+
+        // Act
+        var exception = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+
+        // Assert
+        Assert.Contains("line 9", exception.Message);
+        Assert.Contains("print $value ;", exception.Message);
+    }
+
+    [Theory]
+    [InlineData("(\"Old\", since = \"1.0\", replace_with = \"new $a\")", "Old", "1.0", "new $a")]
+    [InlineData("\"Say \\\"hi\\\"\"", "Say \"hi\"", null, null)]
+    [InlineData("(since = \"3\")", "", "3", null)]
+    public void Parse_ArgumentLists_ReadsMessageVersionAndReplacement(string text, string message, string? since, string? replaceWith)
+    {
+        // Act
+        var deprecation = GrammarDeprecation.Parse(text);
+
+        // Assert
+        Assert.Equal(message, deprecation.Message);
+        Assert.Equal(since, deprecation.Since);
+        Assert.Equal(replaceWith, deprecation.ReplaceWith);
+    }
+
+    [Theory]
+    [InlineData("(\"Old\"")]
+    [InlineData("(\"Old\", until = \"2\")")]
+    [InlineData("(Old)")]
+    [InlineData("(since = \"1\", since = \"2\")")]
+    public void Parse_MalformedArgumentLists_Throw(string text)
+    {
+        Assert.Throws<FormatException>(() => GrammarDeprecation.Parse(text));
+    }
+
+    [Fact]
+    public void GetLabels_RepeatedChildren_AreNumbered()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            <program> ::= <pair-item>
+            <pair-item> ::= "(" <expr> "," <expr> ")" <IDENTIFIER>
+            <expr> ::= <NUMBER>
+            """);
+        var alternative = parser.Grammar.GetRule("pair-item")!.Alternatives[0];
+
+        // Act
+        var labels = GrammarDeprecation.GetLabels(alternative, null);
+
+        // Assert
+        Assert.Equal(new[] { null, "expr1", null, "expr2", null, "IDENTIFIER" }, labels);
+    }
+
+    [Fact]
+    public void GetHover_DeprecatedAlternative_ShowsDescriptionDeprecationAndReplacement()
+    {
+        // Arrange
+        var parser = CreateParser(EchoGrammar);
+        var parse = parser.Parse("print a;\necho b;\n");
+
+        // Act
+        var hover = HoverProvider.GetHover(parse, parse.Input.IndexOf('b'));
+        var plain = HoverProvider.GetHover(parse, parse.Input.IndexOf('a'));
+
+        // Assert
+        Assert.NotNull(hover);
+        Assert.Equal(2, hover!.Location.Line);
+        Assert.Contains("Writes a value to the output.", hover.Contents);
+        Assert.Contains("**Deprecated since 2.0: Use print instead**", hover.Contents);
+        Assert.Contains("Replace with `print $expr ;`", hover.Contents);
+        Assert.NotNull(plain);
+        Assert.DoesNotContain("Deprecated", plain!.Contents);
+    }
+
+    [Fact]
+    public void Render_DeprecatedAlternative_ShowsDeprecationAndReplacement()
+    {
+        // Arrange
+        var grammar = CreateParser(EchoGrammar).Grammar;
+
+        // Act
+        var html = new GrammarDocsHtmlRenderer().Render(grammar);
+
+        // Assert
+        Assert.Contains("<p class=\"deprecated\">Deprecated since 2.0: Use print instead (<code>&quot;echo&quot; &lt;expr&gt; &quot;;&quot;</code>)</p>", html);
+        Assert.Contains("<pre>print $expr ;</pre>", html);
+    }
+
+    private static GeneralizedParser CreateParser(string grammar)
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(grammar)));
+    }
+}
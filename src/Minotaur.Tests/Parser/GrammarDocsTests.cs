@@ -0,0 +1,190 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.GrammarGeneration.Models;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for grammar documentation annotation functionality
+/// </summary>
+public class GrammarDocsTests
+{
+    internal const string StatementGrammar = """
+        Grammar: statements
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <if_statement> | <while_statement> | <expr> ";"
+        <if_statement> ::= "if" "(" <expr> ")" <statement>
+            // @description Runs a statement when the condition holds.
+            // @snippet if (${1:condition}) ${0}
+            // @example if (x) y;
+        <while_statement> ::= "while" "(" <expr> ")" <statement>
+            // @description Repeats a statement
+            // @description while the condition holds.
+            // @snippet while (${1:condition}) ${0}
+            // @example while (x) if (y) z;
+        <expr> ::= <IDENTIFIER> | <NUMBER>
+        <NUMBER> ::= /[0-9]+/
+            // @description An integer literal.
+            // @example 42
+        """;
+
+    [Fact]
+    public void Read_AnnotationComments_AttachToPrecedingDefinition()
+    {
+        // Arrange & Act
+        var grammar = new GrammarFileReader().Read(StatementGrammar);
+
+        // Assert
+        Assert.Equal(9, grammar.Annotations.Count);
+        var snippet = grammar.Annotations[1];
+        Assert.Equal(GrammarAnnotationKind.Snippet, snippet.Kind);
+        Assert.Equal("if_statement", snippet.Target);
+        Assert.Equal("if (${1:condition}) ${0}", snippet.Text);
+        Assert.Equal(6, snippet.Line);
+        Assert.Equal("NUMBER", grammar.Annotations[^1].Target);
+    }
+
+    [Fact]
+    public void Docs_CollectsAnnotationsPerSymbol()
+    {
+        // Arrange
+        var grammar = Compile(StatementGrammar);
+
+        // Act
+        var docs = grammar.Docs;
+
+        // Assert
+        Assert.Equal(new[] { "if_statement", "while_statement", "NUMBER" }, docs.Symbols.Select(s => s.Name));
+        var loop = docs.Get("while_statement")!;
+        Assert.True(loop.IsRule);
+        Assert.Equal("Repeats a statement while the condition holds.", loop.Description);
+        Assert.Equal(new[] { "while (x) if (y) z;" }, loop.Examples);
+        Assert.False(docs.Get("NUMBER")!.IsRule);
+        Assert.Null(docs.Get("expr"));
+    }
+
+    [Fact]
+    public void GetForTerminal_Literal_ReturnsTheConstructItIntroduces()
+    {
+        // Arrange
+        var docs = Compile(StatementGrammar).Docs;
+
+        // Act & Assert
+        Assert.Equal("if_statement", docs.GetForTerminal("\"if\"")!.Name);
+        Assert.Equal("NUMBER", docs.GetForTerminal("NUMBER")!.Name);
+        Assert.Null(docs.GetForTerminal("\";\""));
+    }
+
+    [Fact]
+    public void Compile_BrokenRuleExample_FailsWithAnnotationLineAndDiagnostics()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(StatementGrammar.Replace("// @example if (x) y;", "// @example if x) y;"));
+
+        // Act
+        var error = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+
+        // Assert
+        Assert.StartsWith("Grammar 'statements' has examples that do not parse:", error.Message);
+        Assert.Contains("Example of <if_statement> at line 7 does not parse: if x) y;", error.Message);
+        Assert.Contains("1:4: error", error.Message);
+        Assert.Contains("Unexpected IDENTIFIER 'x'", error.Message);
+    }
+
+    [Fact]
+    public void Validate_TokenExample_MustLexAsOneTokenOfTheKind()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(StatementGrammar);
+        grammar.Annotations.Add(new GrammarAnnotation { Kind = GrammarAnnotationKind.Example, Target = "NUMBER", Text = "4 2", Line = 99 });
+
+        // Act
+        var error = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+
+        // Assert
+        Assert.Contains("Example of <NUMBER> at line 99 does not parse: 4 2", error.Message);
+        Assert.Contains("Expected one NUMBER token but found NUMBER NUMBER", error.Message);
+    }
+
+    [Fact]
+    public void Load_GrammarWithBrokenExample_IsReportedAsFailure()
+    {
+        // Arrange
+        var broken = new GrammarFileReader().Read(StatementGrammar.Replace("// @example 42", "// @example forty-two"));
+
+        // Act
+        var container = GrammarContainer.Load(new[] { broken });
+
+        // Assert
+        var failure = Assert.Single(container.Failures);
+        Assert.Equal("statements", failure.Name);
+        Assert.Contains("Example of <NUMBER> at line 16", failure.Message);
+    }
+
+    [Fact]
+    public void Complete_AfterStatement_OffersDocumentedSnippets()
+    {
+        // Arrange
+        var provider = new CompletionProvider(new GeneralizedParser(Compile(StatementGrammar)));
+
+        // Act
+        var items = provider.Complete("x; ");
+
+        // Assert
+        Assert.Equal(new[] { "if", "while" }, items.Select(i => i.Label));
+        Assert.Equal("if (${1:condition}) ${0}", items[0].Snippet);
+        Assert.Equal("Runs a statement when the condition holds.", items[0].Description);
+        Assert.Equal("\"if\"", items[0].Terminal);
+    }
+
+    [Fact]
+    public void Complete_PartialWord_OffersMatchingLiteralsOnly()
+    {
+        // Arrange
+        var provider = new CompletionProvider(new GeneralizedParser(Compile(StatementGrammar)));
+
+        // Act
+        var items = provider.Complete("if (x) wh");
+
+        // Assert
+        var item = Assert.Single(items);
+        Assert.Equal("while", item.Label);
+        Assert.Equal("while (${1:condition}) ${0}", item.Snippet);
+    }
+
+    [Fact]
+    public void Complete_InvalidPrefix_OffersNothing()
+    {
+        // Arrange
+        var provider = new CompletionProvider(new GeneralizedParser(Compile(StatementGrammar)));
+
+        // Act
+        var items = provider.Complete("if if ");
+
+        // Assert
+        Assert.Empty(items);
+    }
+
+    private static CompiledGrammar Compile(string grammar)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(grammar));
+    }
+}
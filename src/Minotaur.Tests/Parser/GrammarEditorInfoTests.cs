@@ -0,0 +1,143 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Grammars;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for GrammarEditorInfo functionality. The expected language configurations are kept in
+/// Fixtures/language-configuration.
+/// </summary>
+public class GrammarEditorInfoTests
+{
+    private const string RustGrammar = """
+        Grammar: RustItems
+        StartRule: items
+        <items> ::= <item> | <items> <item>
+        <item> ::= <function> | <struct>
+        <function> ::= "fn" <IDENTIFIER> <generics> "(" <params> ")" <block> | "fn" <IDENTIFIER> "(" <params> ")" <block>
+        <generics> ::= "<" <LIFETIME> ">"
+        <struct> ::= "struct" <IDENTIFIER> "{" <fields> "}"
+        <fields> ::= <field> | <fields> "," <field>
+        <field> ::= <IDENTIFIER> ":" <type>
+        <params> ::= ε | <field> | <params> "," <field>
+        <type> ::= <IDENTIFIER> | "&" <LIFETIME> <type> | "[" <type> ";" <NUMBER> "]"
+        <block> ::= "{" <statements> "}"
+        <statements> ::= ε | <statements> <statement>
+        <statement> ::= "let" <IDENTIFIER> "=" <expr> ";"
+        <expr> ::= <IDENTIFIER> | <NUMBER> | <STRING> | <CHAR> | <expr> "<" <expr> | "(" <expr> ")"
+        <STRING> ::= /"(?:\\.|[^"\\])*"/
+        <CHAR> ::= /'(?:\\.|[^'\\])'/
+        <LIFETIME> ::= /'[A-Za-z_][A-Za-z0-9_]*/
+        // @unpaired("'")
+        <LINE_COMMENT> ::= /\/\/[^\n]*/ => { skip }
+        <BLOCK_COMMENT> ::= /\/\*[\s\S]*?\*\// => { skip }
+        <WHITESPACE> ::= /\s+/ => { skip }
+        """;
+
+    [Fact]
+    public void ToLanguageConfiguration_Json_MatchesFixture()
+    {
+        // Arrange
+        var info = BuiltInGrammars.Json.EditorInfo;
+
+        // Act
+        var configuration = info.ToLanguageConfiguration();
+
+        // Assert
+        Snapshot.AssertMatches(configuration, FixturePath("json.json"));
+    }
+
+    [Fact]
+    public void ToLanguageConfiguration_Rust_MatchesFixture()
+    {
+        // Arrange
+        var info = Compile(RustGrammar).EditorInfo;
+
+        // Act
+        var configuration = info.ToLanguageConfiguration();
+
+        // Assert
+        Snapshot.AssertMatches(configuration, FixturePath("rust.json"));
+    }
+
+    [Fact]
+    public void Create_ComparisonOperator_IsNotPairedWithGenericsClose()
+    {
+        // Arrange
+        var info = Compile(RustGrammar).EditorInfo;
+
+        // Act
+        var opens = info.Brackets.Select(p => p.Open).ToList();
+
+        // Assert
+        Assert.Equal(new[] { "(", "{", "[" }, opens);
+        Assert.DoesNotContain(info.Brackets, p => p.Close == ";");
+    }
+
+    [Fact]
+    public void Create_WithoutUnpairedAnnotation_AutoClosesTheLifetimeQuote()
+    {
+        // Arrange
+        var grammar = string.Join("\n", RustGrammar.Split('\n').Where(l => !l.Contains("@unpaired")));
+
+        // Act
+        var annotated = Compile(RustGrammar).EditorInfo;
+        var plain = Compile(grammar).EditorInfo;
+
+        // Assert
+        Assert.Equal(new[] { "'" }, annotated.Unpaired);
+        Assert.DoesNotContain(annotated.AutoClosingPairs, p => p.Open == "'");
+        var quote = Assert.Single(plain.AutoClosingPairs, p => p.Open == "'");
+        Assert.Equal(new[] { "string", "comment" }, quote.NotIn);
+    }
+
+    [Theory]
+    [InlineData("fn f(", 5, ")")]
+    [InlineData("fn f(x", 5, null)]
+    [InlineData("let s = \"", 9, "\"")]
+    [InlineData("let s = \"abc\"", 13, null)]
+    [InlineData("let s = [1, 2", 9, null)]
+    [InlineData("let s = [", 9, "]")]
+    public void GetClosingText_TypedDelimiter_ClosesWhereAnEditorWould(string text, int offset, string? expected)
+    {
+        // Arrange
+        var info = Compile(RustGrammar).EditorInfo;
+
+        // Act
+        var close = info.GetClosingText(text, offset);
+
+        // Assert
+        Assert.Equal(expected, close);
+    }
+
+    private static CompiledGrammar Compile(string text)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(text));
+    }
+
+    private static string FixturePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "Fixtures", "language-configuration", file);
+    }
+}
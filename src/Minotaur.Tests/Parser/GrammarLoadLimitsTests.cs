@@ -0,0 +1,179 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for grammar load limit functionality
+/// </summary>
+public class GrammarLoadLimitsTests
+{
+    // Six nested counted repetitions: 50^6 states once expanded.
+    private const string ExplodingGrammar = """
+        <WORD> ::= /((((((a{1,50}){1,50}){1,50}){1,50}){1,50}){1,50}/
+        <start> ::= <WORD>
+        """;
+
+    [Fact]
+    public void Compile_NestedCountedRepetitions_TripsTheAutomatonLimitWithoutExpanding()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(ExplodingGrammar);
+        var limits = new GrammarLoadLimits { MaxAutomatonStates = 100_000 };
+        var memoryBefore = GC.GetAllocatedBytesForCurrentThread();
+
+        // Act
+        var ex = Assert.Throws<GrammarLimitExceededException>(() => CompiledGrammar.Compile(grammar, limits: limits));
+
+        // Assert
+        Assert.Equal(GrammarLoadLimit.AutomatonStates, ex.Limit);
+        Assert.Equal(100_000, ex.Maximum);
+        Assert.Equal("WORD", ex.Rule);
+        Assert.Contains("100000 automaton states while compiling 'WORD'", ex.Message);
+        Assert.Equal(100_001L, ex.Count);
+        Assert.True(GC.GetAllocatedBytesForCurrentThread() - memoryBefore < 10_000_000);
+    }
+
+    [Theory]
+    [InlineData(5, true)]
+    [InlineData(4, false)]
+    public void Compile_AutomatonLimit_CountsExpandedRepetitionsAndOptionalParts(long maximum, bool compiles)
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            <WORD> ::= /[a-z]{3}x?/
+            <start> ::= <WORD>
+            """);
+        var limits = new GrammarLoadLimits { MaxAutomatonStates = maximum };
+
+        // Act
+        var ex = Record.Exception(() => CompiledGrammar.Compile(grammar, limits: limits));
+
+        // Assert
+        Assert.Equal(compiles, ex == null);
+    }
+
+    [Fact]
+    public void Compile_TooManyRules_NamesTheFirstRuleOverTheLimit()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            <a> ::= <b>
+            <b> ::= <c>
+            <c> ::= <d>
+            <d> ::= "x"
+            """);
+
+        // Act
+        var ex = Assert.Throws<GrammarLimitExceededException>(() => CompiledGrammar.Compile(grammar, limits: new GrammarLoadLimits { MaxRules = 3 }));
+
+        // Assert
+        Assert.Equal(GrammarLoadLimit.Rules, ex.Limit);
+        Assert.Equal("d", ex.Rule);
+    }
+
+    [Theory]
+    [InlineData(6, true)]
+    [InlineData(5, false)]
+    public void Compile_TableEntryLimit_CountsEveryDotPosition(long maximum, bool compiles)
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            <sum> ::= <NUMBER> | <sum> "+" <NUMBER>
+            """);
+
+        // Act
+        var ex = Record.Exception(() => CompiledGrammar.Compile(grammar, limits: new GrammarLoadLimits { MaxTableEntries = maximum }));
+
+        // Assert
+        if (compiles)
+        {
+            Assert.Null(ex);
+        }
+        else
+        {
+            var limit = Assert.IsType<GrammarLimitExceededException>(ex);
+            Assert.Equal(GrammarLoadLimit.TableEntries, limit.Limit);
+            Assert.Equal("sum", limit.Rule);
+        }
+    }
+
+    [Fact]
+    public void Compile_BuildTimeExceeded_ThrowsAnArgumentException()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(ExplodingGrammar);
+
+        // Act
+        var ex = Assert.ThrowsAny<ArgumentException>(() => CompiledGrammar.Compile(grammar, limits: new GrammarLoadLimits { MaxBuildTime = TimeSpan.Zero }));
+
+        // Assert
+        Assert.Equal(GrammarLoadLimit.BuildTime, Assert.IsType<GrammarLimitExceededException>(ex).Limit);
+    }
+
+    [Fact]
+    public void Compile_RiskAboveMaximum_RefusesTheGrammarBeforeBuildingIt()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            <WORD> ::= /(\w+\s?)+;/
+            <start> ::= <WORD>
+            """);
+
+        // Act
+        var ex = Assert.Throws<GrammarLimitExceededException>(() => CompiledGrammar.Compile(grammar, limits: new GrammarLoadLimits { MaxRisk = GrammarRiskLevel.Medium }));
+
+        // Assert
+        Assert.Equal(GrammarLoadLimit.Risk, ex.Limit);
+        Assert.Equal("WORD", ex.Rule);
+        Assert.Contains("medium risk while compiling 'WORD'", ex.Message);
+        Assert.Equal(GrammarRiskKind.NestedQuantifier, ex.RiskReport!.Findings[0].Kind);
+    }
+
+    [Fact]
+    public void Compile_RiskWithinMaximum_LoadsTheGrammar()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            <WORD> ::= /[a-z]+(-[a-z]+)*/
+            <start> ::= <WORD> | <start> "," <WORD>
+            """);
+
+        // Act
+        var compiled = CompiledGrammar.Compile(grammar, limits: new GrammarLoadLimits { MaxRisk = GrammarRiskLevel.Low });
+
+        // Assert
+        Assert.Equal("start", compiled.StartRule);
+    }
+
+    [Fact]
+    public void Compile_NoLimits_LoadsThePathologicalGrammar()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(ExplodingGrammar);
+
+        // Act
+        var compiled = CompiledGrammar.Compile(grammar);
+
+        // Assert
+        Assert.Equal("start", compiled.StartRule);
+    }
+}
@@ -0,0 +1,162 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for GrammarPrecedence functionality
+/// </summary>
+public class GrammarPrecedenceTests
+{
+    private const string ExpressionRules =
+        "<e> ::= <e> \"+\" <e> | <e> \"*\" <e> | <e> \"^\" <e> | <e> \"==\" <e> | \"-\" <e> | <NUMBER>\n";
+
+    [Fact]
+    public void Parse_TighterOperatorOnTheRight_KeepsLooserOperatorAtTheRoot()
+    {
+        // Arrange
+        var parser = CreateParser("Precedence: left \"+\"; left \"*\"\n");
+
+        // Act
+        var result = parser.Parse("1 + 2 * 3");
+        var reversed = parser.Parse("1 * 2 + 3");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.False(result.IsAmbiguous);
+        Assert.Equal(0, Root(result).ProductionIndex);
+        Assert.Equal(1, Assert.IsType<NonTerminalNode>(Root(result).Children[2]).ProductionIndex);
+        Assert.False(reversed.IsAmbiguous);
+        Assert.Equal(0, Root(reversed).ProductionIndex);
+    }
+
+    [Fact]
+    public void Parse_Associativity_GroupsChainsOnTheDeclaredSide()
+    {
+        // Arrange
+        var parser = CreateParser("Precedence: left \"+\"; right \"^\"\n");
+
+        // Act
+        var left = parser.Parse("1 + 2 + 3");
+        var right = parser.Parse("1 ^ 2 ^ 3");
+
+        // Assert
+        Assert.False(left.IsAmbiguous);
+        Assert.IsType<NonTerminalNode>(Root(left).Children[0]);
+        Assert.IsType<TerminalNode>(Assert.IsType<NonTerminalNode>(Root(left).Children[2]).Children[0]);
+        Assert.False(right.IsAmbiguous);
+        Assert.Equal(2, Assert.IsType<NonTerminalNode>(Root(right).Children[2]).ProductionIndex);
+    }
+
+    [Fact]
+    public void Parse_PrefixOperatorLooserThanInfix_AppliesToTheWholeExpression()
+    {
+        // Arrange
+        var parser = CreateParser("Precedence: left \"-\"; left \"*\"\n");
+
+        // Act
+        var result = parser.Parse("- 1 * 2");
+
+        // Assert
+        Assert.False(result.IsAmbiguous);
+        Assert.Equal(4, Root(result).ProductionIndex);
+    }
+
+    [Fact]
+    public void Parse_ChainedNonAssociativeOperator_StaysAmbiguous()
+    {
+        // Arrange
+        var parser = CreateParser("Precedence: nonassoc \"==\"\n");
+
+        // Act
+        var result = parser.Parse("1 == 2 == 3");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.True(result.IsAmbiguous);
+    }
+
+    [Fact]
+    public void Parse_UndeclaredOperator_StaysAmbiguous()
+    {
+        // Arrange
+        var parser = CreateParser("Precedence: left \"+\"\n");
+
+        // Act
+        var result = parser.Parse("1 * 2 * 3");
+
+        // Assert
+        Assert.True(result.IsAmbiguous);
+    }
+
+    [Fact]
+    public void Compile_Precedence_ReadsLevelsFromLoosestToTightest()
+    {
+        // Act
+        var grammar = Compile("Precedence: left \"+\" \"-\"; right \"^\"\n");
+
+        // Assert
+        Assert.Equal(2, grammar.Precedence!.Levels.Count);
+        Assert.Equal(0, grammar.Precedence.GetLevel("\"-\""));
+        Assert.Equal(1, grammar.Precedence.GetLevel("\"^\""));
+        Assert.Null(grammar.Precedence.GetLevel("\"*\""));
+        Assert.Equal("left \"+\" \"-\"; right \"^\"", grammar.Precedence.ToString());
+    }
+
+    [Fact]
+    public void Compile_MalformedPrecedence_Throws()
+    {
+        Assert.Contains("must start with left, right or nonassoc", Assert.Throws<ArgumentException>(() => Compile("Precedence: infixl \"+\"\n")).Message);
+        Assert.Contains("only terminals have a precedence", Assert.Throws<ArgumentException>(() => Compile("Precedence: left <e>\n")).Message);
+        Assert.Contains("more than one precedence level", Assert.Throws<ArgumentException>(() => Compile("Precedence: left \"+\"; right \"+\"\n")).Message);
+    }
+
+    [Fact]
+    public void TryGetOperator_OperatorAlternatives_ReportsOperatorAndPosition()
+    {
+        // Arrange
+        var rule = Compile(string.Empty).GetRule("e")!;
+
+        // Act & Assert
+        Assert.True(GrammarPrecedence.TryGetOperator(rule.Alternatives[0], out var infix, out var position));
+        Assert.Equal("\"+\"", infix.Key);
+        Assert.Equal(OperatorFixity.Infix, position);
+        Assert.True(GrammarPrecedence.TryGetOperator(rule.Alternatives[4], out _, out position));
+        Assert.Equal(OperatorFixity.Prefix, position);
+        Assert.False(GrammarPrecedence.TryGetOperator(rule.Alternatives[5], out _, out _));
+    }
+
+    private static CompiledGrammar Compile(string headers)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(headers + ExpressionRules));
+    }
+
+    private static GeneralizedParser CreateParser(string headers)
+    {
+        return new GeneralizedParser(Compile(headers));
+    }
+
+    private static NonTerminalNode Root(ParseResult result)
+    {
+        return Assert.IsType<NonTerminalNode>(result.Tree);
+    }
+}
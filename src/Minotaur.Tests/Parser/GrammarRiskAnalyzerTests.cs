@@ -0,0 +1,144 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.Json.Nodes;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for grammar risk analysis functionality
+/// </summary>
+public class GrammarRiskAnalyzerTests
+{
+    [Theory]
+    [InlineData(@"(a+)+$", GrammarRiskKind.NestedQuantifier, "(a+)+")]
+    [InlineData(@"^(\w+\s?)+$", GrammarRiskKind.NestedQuantifier, @"(\w+\s?)+")]
+    [InlineData(@"([a-zA-Z]+)*@", GrammarRiskKind.NestedQuantifier, "([a-zA-Z]+)*")]
+    [InlineData(@"(\w|\d)+!", GrammarRiskKind.OverlappingAlternation, @"(\w|\d)+")]
+    [InlineData(@"(a|a)*b", GrammarRiskKind.OverlappingAlternation, "(a|a)*")]
+    public void AnalyzePattern_CatastrophicBacktracking_IsCritical(string pattern, GrammarRiskKind kind, string fragment)
+    {
+        // Act
+        var report = GrammarRiskAnalyzer.AnalyzePattern(pattern, "WORD");
+
+        // Assert
+        Assert.Equal(GrammarRiskLevel.Critical, report.Level);
+        var finding = report.Findings[0];
+        Assert.Equal(kind, finding.Kind);
+        Assert.Equal("WORD", finding.Subject);
+        Assert.True(finding.IsTerminal);
+        Assert.Equal(fragment, finding.Fragment);
+    }
+
+    [Theory]
+    [InlineData(@"[a-z]+(-[a-z]+)*")]
+    [InlineData(@"(\d+,)+")]
+    [InlineData(@"(\w+\s)+")]
+    [InlineData(@"(a|b)*c")]
+    [InlineData(@"[a-zA-Z_][a-zA-Z0-9_]*")]
+    [InlineData(@"""([^""\\]|\\.)*""")]
+    public void AnalyzePattern_BenignLookalike_IsNotFlagged(string pattern)
+    {
+        // Act
+        var report = GrammarRiskAnalyzer.AnalyzePattern(pattern);
+
+        // Assert
+        Assert.Empty(report.Findings);
+        Assert.Equal(GrammarRiskLevel.None, report.Level);
+    }
+
+    [Fact]
+    public void AnalyzePattern_AdjacentOverlappingQuantifiers_IsPolynomial()
+    {
+        // Act
+        var report = GrammarRiskAnalyzer.AnalyzePattern(@"\d+\s*\d+x");
+
+        // Assert
+        var finding = Assert.Single(report.Findings);
+        Assert.Equal(GrammarRiskKind.AdjacentQuantifiers, finding.Kind);
+        Assert.Equal(GrammarRiskLevel.Medium, finding.Level);
+        Assert.Equal(@"\d+\s*\d+", finding.Fragment);
+    }
+
+    [Fact]
+    public void AnalyzePattern_DistinguishingTailAfterRepetition_EstimatesExponentialAutomaton()
+    {
+        // Act
+        var blowup = GrammarRiskAnalyzer.AnalyzePattern("[ab]*a[ab]{20}", "BLOWUP");
+        var linear = GrammarRiskAnalyzer.AnalyzePattern(@"\w*\d{20}", "LINEAR");
+
+        // Assert
+        var finding = Assert.Single(blowup.Findings);
+        Assert.Equal(GrammarRiskKind.DfaBlowup, finding.Kind);
+        Assert.Equal(GrammarRiskLevel.High, finding.Level);
+        Assert.Equal("[ab]*a[ab]{20}", finding.Fragment);
+        Assert.True(blowup.EstimatedDfaStates["BLOWUP"] >= 1 << 21);
+        Assert.Empty(linear.Findings);
+        Assert.True(linear.EstimatedDfaStates["LINEAR"] < 100);
+    }
+
+    [Fact]
+    public void Analyze_Rules_FlagsShapesThatAreExponentialWithoutMemoization()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            <start> ::= <group> "x" | <group> "y"
+            <group> ::= "(" <start> ")" | "a" | <pair>
+            <pair> ::= <pair> <pair> | "b"
+            <list> ::= <wrapper> | "c"
+            <wrapper> ::= <list>
+            <sum> ::= <NUMBER> | <sum> "+" <NUMBER>
+            """);
+
+        // Act
+        var report = GrammarRiskAnalyzer.Analyze(grammar);
+
+        // Assert
+        Assert.Equal(GrammarRiskLevel.Critical, report.Level);
+        Assert.Contains(report.Findings, f => f is { Kind: GrammarRiskKind.NullableCycle, Subject: "list", IsTerminal: false });
+        Assert.Contains(report.Findings, f => f is { Kind: GrammarRiskKind.NullableCycle, Subject: "wrapper" });
+        var prefix = Assert.Single(report.Findings, f => f.Kind == GrammarRiskKind.SharedPrefixRecursion);
+        Assert.Equal(("start", "<group>", GrammarRiskLevel.High), (prefix.Subject, prefix.Fragment, prefix.Level));
+        var ambiguous = Assert.Single(report.Findings, f => f.Kind == GrammarRiskKind.AmbiguousRecursion);
+        Assert.Equal(("pair", GrammarRiskLevel.Medium), (ambiguous.Subject, ambiguous.Level));
+        Assert.DoesNotContain(report.Findings, f => f.Subject == "sum");
+    }
+
+    [Fact]
+    public void ToJson_ListsFindingsWithSubjectsAndFragments()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            <WORD> ::= /(a+)+b/
+            <start> ::= <WORD> /(x|x)*/
+            """);
+
+        // Act
+        var json = JsonNode.Parse(GrammarRiskAnalyzer.Analyze(grammar).ToJson())!;
+
+        // Assert
+        Assert.Equal("Critical", (string)json["level"]!);
+        var findings = json["findings"]!.AsArray();
+        Assert.Equal(2, findings.Count);
+        Assert.Contains(findings, f => (string)f!["subject"]! == "WORD" && (string)f["fragment"]! == "(a+)+" && (bool)f["terminal"]!);
+        Assert.Contains(findings, f => (string)f!["subject"]! == "/(x|x)*/" && (string)f["kind"]! == "OverlappingAlternation");
+        Assert.NotNull(json["estimatedDfaStates"]!["WORD"]);
+    }
+}
@@ -0,0 +1,233 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using System.Text;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Monitoring;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for grammar specialization functionality
+/// </summary>
+public class GrammarSpecializationTests
+{
+    private const int StatementKinds = 150;
+
+    private static readonly string[] Inputs =
+    {
+        "select a, b from t;",
+        "select a from t where a < = b;",
+        "select a from t where a <= b;",
+        "select a from t order by a;",
+        "select from t;",
+        "select a from t",
+        "alter7 x, y;",
+        "alter7 x <= 3;",
+        "select a from t; select b from u;"
+    };
+
+    [Fact]
+    public void Specialize_GeneratedGrammar_RemovesUnreachableRulesAndAlternatives()
+    {
+        // Arrange
+        var grammar = Compile(GenerateGrammar());
+
+        // Act
+        var specialization = grammar.Specialize(new[] { "query" }, Array.Empty<string>());
+
+        // Assert
+        var report = specialization.Report;
+        Assert.Equal(new[] { "select" }, report.EntryRules);
+        Assert.Equal(3 * StatementKinds + 2, report.RemovedRules.Count);
+        Assert.Contains("program", report.RemovedRules);
+        Assert.Contains("alter7", report.RemovedRules);
+        Assert.Equal(new[] { "<select> ::= \"select\" <columns> \"from\" <IDENTIFIER> \"order\" \"by\" <columns> \";\"" }, report.RemovedAlternatives);
+        Assert.Empty(report.RemovedTokenPatterns);
+        Assert.Contains("\"<=\"", report.RetainedTerminals);
+        Assert.Equal("retained_terminals", report.RetainedTerminalsRule);
+        Assert.True(report.RulesAfter * 20 < report.RulesBefore, report.ToString());
+        Assert.True(report.StatesAfter * 5 < report.StatesBefore, report.ToString());
+        Assert.Equal("select", specialization.Compiled.StartRule);
+        Assert.Equal(new[] { "query" }, specialization.Compiled.EntryPoints.Keys);
+        Assert.Empty(specialization.Compiled.Features);
+    }
+
+    [Fact]
+    public void Specialize_GeneratedGrammar_ReducesMemoryAndLoadTime()
+    {
+        // Arrange
+        var source = GenerateGrammar();
+        var specialized = Compile(source).Specialize(new[] { "query" }, new[] { "ordering" }).Grammar;
+
+        // Act
+        var originalTime = BestCompileTime(source);
+        var specializedTime = BestCompileTime(specialized);
+
+        // Assert
+        var original = Compile(source).GetMemoryReport();
+        var reduced = CompiledGrammar.Compile(specialized).GetMemoryReport();
+        Assert.True(reduced.Get(MemoryCategories.Rules)!.Count * 20 < original.Get(MemoryCategories.Rules)!.Count);
+        Assert.True(reduced.Get(MemoryCategories.Alternatives)!.Count * 5 < original.Get(MemoryCategories.Alternatives)!.Count);
+        Assert.True(specializedTime < originalTime, $"{specializedTime} vs {originalTime}");
+    }
+
+    [Theory]
+    [InlineData(false)]
+    [InlineData(true)]
+    public void Specialize_GeneratedGrammar_AcceptsTheSameInputs(bool ordering)
+    {
+        // Arrange
+        var grammar = Compile(GenerateGrammar());
+        var features = ordering ? new[] { "ordering" } : Array.Empty<string>();
+        var original = new GeneralizedParser(grammar);
+        var specialized = new GeneralizedParser(grammar.Specialize(new[] { "query" }, features).Compiled);
+
+        foreach (var input in Inputs)
+        {
+            // Act
+            var expected = original.Parse(input, new ParseOptions { StartRule = "select", Features = features }).IsSuccess;
+            var actual = specialized.Parse(input, new ParseOptions { Features = features }).IsSuccess;
+
+            // Assert
+            Assert.True(expected == actual, $"'{input}': original {expected}, specialized {actual}");
+        }
+
+        Assert.True(specialized.Parse("select a from t where a < = b;").IsSuccess);
+        Assert.False(specialized.Parse("select a from t where a <= b;").IsSuccess);
+        Assert.Equal(ordering, specialized.Parse("select a from t order by a;", new ParseOptions { Features = features }).IsSuccess);
+    }
+
+    [Fact]
+    public void Specialize_Annotations_FollowKeptRulesAndDropExamplesOfRemovedAlternatives()
+    {
+        // Arrange
+        var grammar = Compile("""
+            Features: spread = <items> ::= <items> "," "..." <IDENTIFIER>
+            <program> ::= <list> | <call>
+            <list> ::= "[" <items> "]"
+            // @example [a, b]
+            <items> ::= <IDENTIFIER> | <items> "," <IDENTIFIER> | <items> "," "..." <IDENTIFIER>
+            // @example a, ...b
+            // @description List items
+            <call> ::= <IDENTIFIER> "(" ")"
+            // @description A call
+            """);
+
+        // Act
+        var specialization = grammar.Specialize(new[] { "list" }, Array.Empty<string>());
+
+        // Assert
+        var kept = specialization.Grammar.Annotations.Select(a => $"{a.Kind} {a.Target}: {a.Text}");
+        Assert.Equal(new[] { "Example list: [a, b]", "Description items: List items" }, kept);
+        var dropped = Assert.Single(specialization.Report.DroppedExamples);
+        Assert.Equal("a, ...b", dropped.Text);
+        Assert.False(specialization.Grammar.Metadata.ContainsKey(CompiledGrammar.FeaturesKey));
+    }
+
+    [Fact]
+    public void Specialize_KeepExpectedRules_KeepsAnnotatedRulesOnlyFeaturesReach()
+    {
+        // Arrange
+        var grammar = Compile("""
+            Features: generics = <type> ::= <IDENTIFIER> "<" <arguments> ">"
+            <type> ::= <IDENTIFIER> | <IDENTIFIER> "<" <arguments> ">"
+            <arguments> ::= <type> | <arguments> "," <type>
+            // @expected("type arguments")
+            """);
+
+        // Act
+        var pruned = grammar.Specialize(new[] { "type" }, Array.Empty<string>());
+        var kept = grammar.Specialize(new[] { "type" }, Array.Empty<string>(), new SpecializationOptions { KeepExpectedRules = true });
+
+        // Assert
+        Assert.Equal(new[] { "arguments" }, pruned.Report.RemovedRules);
+        Assert.Empty(kept.Report.RemovedRules);
+        Assert.Equal("type arguments", kept.Compiled.GetRule("arguments")!.ExpectedPhrase);
+        Assert.True(new GeneralizedParser(kept.Compiled).Parse("a").IsSuccess);
+        Assert.False(new GeneralizedParser(kept.Compiled).Parse("a<b>").IsSuccess);
+    }
+
+    [Theory]
+    [InlineData("missing", "")]
+    [InlineData("query", "missing")]
+    public void Specialize_UnknownEntryPointOrFeature_Throws(string entryPoint, string feature)
+    {
+        // Arrange
+        var grammar = Compile(GenerateGrammar());
+        var features = feature.Length > 0 ? new[] { feature } : Array.Empty<string>();
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => grammar.Specialize(new[] { entryPoint }, features));
+    }
+
+    // A SQL-like language with many statement kinds, of which only <select> is reached from the "query" entry point.
+    private static string GenerateGrammar()
+    {
+        var grammar = new StringBuilder();
+        grammar.AppendLine("EntryPoints: query = select; script = program");
+        grammar.AppendLine("Features: ordering = <select> ::= \"select\" <columns> \"from\" <IDENTIFIER> \"order\" \"by\" <columns> \";\"");
+        grammar.AppendLine("<NUMBER> ::= /[0-9]+/");
+        grammar.AppendLine("<program> ::= <statement> | <program> <statement>");
+        grammar.Append("<statement> ::= <select>");
+        for (var i = 0; i < StatementKinds; i++)
+        {
+            grammar.Append($" | <alter{i}>");
+        }
+
+        grammar.AppendLine();
+        grammar.AppendLine("<select> ::= \"select\" <columns> \"from\" <IDENTIFIER> \";\"");
+        grammar.AppendLine("    | \"select\" <columns> \"from\" <IDENTIFIER> \"where\" <IDENTIFIER> \"<\" \"=\" <IDENTIFIER> \";\"");
+        grammar.AppendLine("    | \"select\" <columns> \"from\" <IDENTIFIER> \"order\" \"by\" <columns> \";\"");
+        grammar.AppendLine("<columns> ::= <IDENTIFIER> | <columns> \",\" <IDENTIFIER>");
+        for (var i = 0; i < StatementKinds; i++)
+        {
+            grammar.AppendLine($"<alter{i}> ::= \"alter{i}\" <targets{i}> \";\" | \"alter{i}\" <conditions{i}> \";\"");
+            grammar.AppendLine($"<targets{i}> ::= <IDENTIFIER> | <targets{i}> \",\" <IDENTIFIER>");
+            grammar.AppendLine($"<conditions{i}> ::= <IDENTIFIER> \"<=\" <NUMBER> | <conditions{i}> \"and\" <IDENTIFIER> \"<=\" <NUMBER>");
+        }
+
+        return grammar.ToString();
+    }
+
+    private static TimeSpan BestCompileTime(string grammar)
+    {
+        return BestCompileTime(new GrammarFileReader().Read(grammar));
+    }
+
+    private static TimeSpan BestCompileTime(Minotaur.GrammarGeneration.Models.Grammar grammar)
+    {
+        var best = TimeSpan.MaxValue;
+        for (var i = 0; i < 5; i++)
+        {
+            var watch = Stopwatch.StartNew();
+            CompiledGrammar.Compile(grammar);
+            watch.Stop();
+            best = watch.Elapsed < best ? watch.Elapsed : best;
+        }
+
+        return best;
+    }
+
+    private static CompiledGrammar Compile(string grammar)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(grammar));
+    }
+}
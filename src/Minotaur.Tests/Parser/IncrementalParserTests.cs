@@ -0,0 +1,356 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using System.Text;
+using Xunit;
+using Xunit.Abstractions;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for IncrementalParser functionality
+/// </summary>
+public class IncrementalParserTests
+{
+    private const string AssignmentGrammar = """
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <IDENTIFIER> "=" <expr> ";"
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER>
+        """;
+
+    private const string FunctionGrammar = """
+        <program> ::= <function> | <program> <function>
+        <function> ::= "fn" <IDENTIFIER> <block>
+        <block> ::= "{" <statements> "}" | "{" "}"
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= <IDENTIFIER> "=" <expr> ";" | <block>
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER> | "(" <expr> ")"
+        """;
+
+    private const string Document = "a = 1;\nb = 2;\nc = 3;\n";
+
+    private const string Functions = "fn a { x = 1; }\nfn b {\n  y = (2 + x);\n}\nfn c { z = y; }\n";
+
+    private readonly ITestOutputHelper _output;
+
+    public IncrementalParserTests(ITestOutputHelper output)
+    {
+        _output = output;
+    }
+
+    [Fact]
+    public void Constructor_MemoryOverWholeString_SharesTheString()
+    {
+        // Arrange
+        var text = new string(Document.ToCharArray());
+
+        // Act
+        var parser = new IncrementalParser(CreateGeneralizedParser(), text.AsMemory());
+        var slice = new IncrementalParser(CreateGeneralizedParser(), ("// cached\n" + Document).AsMemory(10));
+
+        // Assert
+        Assert.Same(text, parser.Text);
+        Assert.True(parser.Current.IsSuccess);
+        Assert.Equal(Document, slice.Text);
+        Assert.True(slice.Current.IsSuccess);
+    }
+
+    [Fact]
+    public void ApplyEdit_ReplaceToken_RelexesOnlyDamagedToken()
+    {
+        // Arrange
+        var parser = CreateParser(Document);
+
+        // Act
+        parser.ApplyEdit(new TextEdit(11, 1, "42"));
+
+        // Assert
+        var stats = parser.LastStats!.Value;
+        Assert.Equal(1, stats.Version);
+        Assert.Equal(new TextRange(11, 2), stats.DamagedRange);
+        Assert.Equal(new TextRange(10, 3), stats.RelexedRange);
+        Assert.Equal(1, stats.RelexedTokenCount);
+        Assert.Equal(12, stats.TokenCount);
+        Assert.Equal(15, stats.ReusedNodeCount);
+        Assert.Equal(9, stats.RebuiltNodeCount);
+        Assert.Equal(22, stats.DocumentLength);
+        Assert.True(stats.IsSuccess);
+    }
+
+    [Fact]
+    public void ApplyEdit_EditScript_ReportsStatsPerEdit()
+    {
+        // Arrange
+        var parser = CreateParser(Document);
+        var edits = TextEdit.ParseScript("""
+            [
+              { "offset": 11, "length": 1, "text": "42" },
+              { "offset": 22, "text": "d = 4;\n" },
+              { "offset": 7, "length": 8 }
+            ]
+            """);
+
+        // Act
+        foreach (var edit in edits)
+        {
+            parser.ApplyEdit(edit);
+        }
+
+        // Assert
+        Assert.Equal("a = 1;\nc = 3;\nd = 4;\n", parser.Text);
+        Assert.Equal(new[] { 1, 2, 3 }, parser.History.Select(s => s.Version));
+
+        var append = parser.History[1];
+        Assert.Equal(new TextRange(22, 7), append.DamagedRange);
+        Assert.Equal(new TextRange(21, 8), append.RelexedRange);
+        Assert.Equal(4, append.RelexedTokenCount);
+        Assert.Equal(24, append.ReusedNodeCount);
+        Assert.Equal(8, append.RebuiltNodeCount);
+
+        var delete = parser.History[2];
+        Assert.Equal(new TextRange(7, 0), delete.DamagedRange);
+        Assert.Equal(new TextRange(6, 1), delete.RelexedRange);
+        Assert.Equal(0, delete.RelexedTokenCount);
+        Assert.Equal(12, delete.TokenCount);
+        Assert.Equal(22, delete.ReusedNodeCount);
+        Assert.Equal(2, delete.RebuiltNodeCount);
+    }
+
+    [Fact]
+    public void ApplyEdit_ProducesSameTreeAsFullParse()
+    {
+        // Arrange
+        var parser = CreateParser(Document);
+        var edits = new[]
+        {
+            TextEdit.Insert(5, " + x"),
+            new TextEdit(7, 1, "bb"),
+            TextEdit.Insert(0, "z = 0;\n\n"),
+            TextEdit.Delete(8, 12)
+        };
+
+        foreach (var edit in edits)
+        {
+            // Act
+            parser.ApplyEdit(edit);
+
+            // Assert
+            var full = CreateGeneralizedParser().Parse(parser.Text);
+            Assert.True(parser.Current.IsSuccess);
+            Assert.Equal(Describe(full.Tree!), Describe(parser.Current.Tree!));
+            Assert.Equal(full.Tokens, parser.Current.Tokens);
+        }
+    }
+
+    [Fact]
+    public void ApplyEdit_SyntaxErrorThenFix_RecoversTree()
+    {
+        // Arrange
+        var parser = CreateParser(Document);
+
+        // Act
+        parser.ApplyEdit(TextEdit.Delete(5, 1));
+        var broken = parser.Current;
+        parser.ApplyEdit(TextEdit.Insert(5, ";"));
+
+        // Assert
+        Assert.False(broken.IsSuccess);
+        Assert.False(parser.History[0].IsSuccess);
+        Assert.True(parser.Current.IsSuccess);
+        Assert.Equal(0, parser.History[1].ReusedNodeCount);
+        Assert.Equal(Document, parser.Text);
+    }
+
+    [Fact]
+    public void ApplyEdit_UnrecognizedCharacter_KeepsLexicalDiagnosticsInPlace()
+    {
+        // Arrange
+        var parser = CreateParser("a = 1;\nb = $;\n");
+
+        // Act
+        parser.ApplyEdit(TextEdit.Insert(0, "x = 1;\n"));
+
+        // Assert
+        var diagnostic = Assert.Single(parser.Current.Diagnostics, d => d.Code == "E0003");
+        Assert.Equal(18, diagnostic.Location!.Offset);
+        Assert.Equal(3, diagnostic.Location.Line);
+    }
+
+    [Fact]
+    public void ReparseNode_FunctionBody_ReparsesOnlyTheBodyAndMatchesAFullParse()
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateFunctionParser(), Functions);
+        var body = FindRule(parser.Current.Tree!, "block", 1);
+        var later = FindRule(parser.Current.Tree!, "function", 2);
+
+        // Act
+        var result = parser.ReparseNode(body.Id, "{\n  y = 2;\n  { w = y + 1; }\n}");
+
+        // Assert
+        Assert.True(result.IsLocal);
+        Assert.Null(result.FallbackReason);
+        Assert.Equal("fn a { x = 1; }\nfn b {\n  y = 2;\n  { w = y + 1; }\n}\nfn c { z = y; }\n", parser.Text);
+
+        var full = CreateFunctionParser().Parse(parser.Text);
+        Assert.True(result.Parse.IsSuccess);
+        Assert.Equal(Describe(full.Tree!), Describe(result.Parse.Tree!));
+        Assert.Equal(full.Tokens, result.Parse.Tokens);
+        Assert.Equal(6, later.SourcePosition!.Line);
+
+        var stats = parser.LastStats!.Value;
+        Assert.Equal("block", stats.ReparsedRule);
+        Assert.Equal(14, stats.RelexedTokenCount);
+        Assert.True(stats.IsSuccess);
+    }
+
+    [Fact]
+    public void ReparseNode_FunctionBody_MapsTheOldBodyToTheNewOne()
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateFunctionParser(), Functions);
+        var body = FindRule(parser.Current.Tree!, "block", 1);
+        var later = FindRule(parser.Current.Tree!, "function", 2);
+
+        // Act
+        var result = parser.ReparseNode(body.Id, "{ y = 3; }");
+
+        // Assert
+        var replacement = FindRule(result.Parse.Tree!, "block", 1);
+        Assert.Equal(replacement.Id, result.Parse.NodeIdMap.Map(body.Id));
+        Assert.Contains(later.Id, result.Parse.NodeIdMap.Retained);
+        Assert.Null(body.Parent);
+    }
+
+    [Theory]
+    [InlineData("{ y = (2; }", "the new text has unbalanced brackets")]
+    [InlineData("{ y = 2; } fn d { }", "the new text is not a valid <block>")]
+    [InlineData("{ y = 2; } ", "the new text does not begin and end with a token")]
+    [InlineData("{ y = $; }", "the new text does not lex")]
+    public void ReparseNode_EditThatCouldReachBeyondTheNode_FallsBackToADocumentReparse(string text, string reason)
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateFunctionParser(), Functions);
+        var body = FindRule(parser.Current.Tree!, "block", 1);
+
+        // Act
+        var result = parser.ReparseNode(body.Id, text);
+
+        // Assert
+        Assert.False(result.IsLocal);
+        Assert.Equal(reason, result.FallbackReason);
+        Assert.Null(parser.LastStats!.Value.ReparsedRule);
+
+        var full = CreateFunctionParser().Parse(parser.Text);
+        Assert.Equal(full.IsSuccess, result.Parse.IsSuccess);
+        Assert.Equal(full.Tokens, result.Parse.Tokens);
+    }
+
+    [Fact]
+    public void ReparseNode_UnknownNode_Throws()
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateFunctionParser(), Functions);
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => parser.ReparseNode(Guid.NewGuid(), "{ }"));
+    }
+
+    [Fact]
+    public void ReparseNode_SingleFunctionEditInALargeFile_Benchmark()
+    {
+        // Arrange
+        var text = string.Concat(Enumerable.Range(0, 1000).Select(i => $"fn f{i} {{\n  x = {i} + y;\n  {{ z = (x + 1); }}\n}}\n"));
+        var localParser = new IncrementalParser(CreateFunctionParser(), text);
+        var documentParser = new IncrementalParser(CreateFunctionParser(), text);
+        var body = FindRule(localParser.Current.Tree!, "block", 1000);
+        var position = body.SourcePosition!;
+        const string newBody = "{\n  x = 500 + y + y;\n}";
+
+        // Act
+        var localWatch = Stopwatch.StartNew();
+        var local = localParser.ReparseNode(body.Id, newBody);
+        localWatch.Stop();
+        var documentWatch = Stopwatch.StartNew();
+        var document = documentParser.ApplyEdit(new TextEdit(position.Offset, position.Length, newBody));
+        documentWatch.Stop();
+
+        // Assert
+        _output.WriteLine($"{local.Parse.Tokens.Count} tokens");
+        _output.WriteLine($"node reparse: {localWatch.Elapsed.TotalMilliseconds:F1} ms");
+        _output.WriteLine($"incremental reparse: {documentWatch.Elapsed.TotalMilliseconds:F1} ms");
+
+        Assert.True(local.IsLocal);
+        Assert.Equal(Describe(document.Tree!), Describe(local.Parse.Tree!));
+    }
+
+    private static IncrementalParser CreateParser(string text)
+    {
+        return new IncrementalParser(CreateGeneralizedParser(), text);
+    }
+
+    private static GeneralizedParser CreateGeneralizedParser()
+    {
+        var grammar = new GrammarFileReader().Read(AssignmentGrammar);
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+
+    private static GeneralizedParser CreateFunctionParser()
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(FunctionGrammar)));
+    }
+
+    private static CognitiveGraphNode FindRule(CognitiveGraphNode tree, string rule, int index)
+    {
+        return Preorder(tree).Where(n => n is NonTerminalNode nonTerminal && nonTerminal.RuleName == rule).ElementAt(index);
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Preorder(CognitiveGraphNode node)
+    {
+        yield return node;
+        foreach (var descendant in node.Children.SelectMany(Preorder))
+        {
+            yield return descendant;
+        }
+    }
+
+    private static string Describe(CognitiveGraphNode node)
+    {
+        var builder = new StringBuilder();
+        Describe(node, builder);
+        return builder.ToString();
+    }
+
+    private static void Describe(CognitiveGraphNode node, StringBuilder builder)
+    {
+        var label = node is NonTerminalNode nonTerminal ? $"{nonTerminal.RuleName}/{nonTerminal.ProductionIndex}" : ((TerminalNode)node).Text;
+        var position = node.SourcePosition!;
+        builder.Append($"({label} {position.Line}:{position.Column}+{position.Length}");
+        foreach (var child in node.Children)
+        {
+            Describe(child, builder);
+        }
+
+        builder.Append(')');
+    }
+}
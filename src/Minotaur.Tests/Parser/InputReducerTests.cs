@@ -0,0 +1,156 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for InputReducer functionality
+/// </summary>
+public class InputReducerTests
+{
+    private const string StatementGrammar = """
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <IDENTIFIER> "=" <expr> ";" | "{" <program> "}"
+        <expr> ::= <term> | <expr> "+" <term>
+        <term> ::= <NUMBER> | <IDENTIFIER> | "(" <expr> ")"
+        """;
+
+    [Fact]
+    public void Reduce_PlantedTriggerToken_ShrinksToTrigger()
+    {
+        // Arrange
+        var input = SyntheticInput(200, "boom");
+
+        // Act
+        var result = InputReducer.Reduce(input, Grammar(), s => s.Contains("boom"));
+
+        // Assert
+        Assert.Equal("boom", result.Input);
+        Assert.True(result.UsedTree);
+        Assert.False(result.ReachedTestLimit);
+        Assert.Equal(input.Length, result.OriginalLength);
+    }
+
+    [Fact]
+    public void Reduce_PredicateRequiringValidParse_KeepsMinimalProgram()
+    {
+        // Arrange
+        var grammar = Grammar();
+        var parser = new GeneralizedParser(grammar);
+        var input = SyntheticInput(40, "boom");
+
+        // Act
+        var result = InputReducer.Reduce(input, grammar, s => s.Contains("boom") && parser.Parse(s).IsSuccess);
+
+        // Assert
+        Assert.True(parser.Parse(result.Input).IsSuccess);
+        Assert.Contains("boom", result.Input);
+        Assert.DoesNotContain(" ", result.Input);
+        Assert.True(result.Input.Length <= "{{boom=(x20+1);}}".Length, result.Input);
+    }
+
+    [Fact]
+    public void Reduce_UnparseableInput_FallsBackToTokens()
+    {
+        // Arrange
+        var input = "a = ; ; boom } } = 3 4 5";
+
+        // Act
+        var result = InputReducer.Reduce(input, Grammar(), s => s.Contains("boom"));
+
+        // Assert
+        Assert.False(result.UsedTree);
+        Assert.Equal("boom", result.Input);
+    }
+
+    [Fact]
+    public void Reduce_TestLimit_StopsWithFailingInput()
+    {
+        // Arrange
+        var input = SyntheticInput(50, "boom");
+
+        // Act
+        var result = InputReducer.Reduce(input, Grammar(), s => s.Contains("boom"), new ReductionOptions { MaxTests = 3 });
+
+        // Assert
+        Assert.True(result.ReachedTestLimit);
+        Assert.Equal(3, result.Tests);
+        Assert.Contains("boom", result.Input);
+    }
+
+    [Fact]
+    public void Reduce_ReportsProgressWithShrinkingLength()
+    {
+        // Arrange
+        var reports = new List<ReductionProgress>();
+        var options = new ReductionOptions { Progress = new SynchronousProgress(reports.Add) };
+
+        // Act
+        var result = InputReducer.Reduce(SyntheticInput(20, "boom"), Grammar(), s => s.Contains("boom"), options);
+
+        // Assert
+        Assert.Contains(reports, r => r.Phase == "tree depth 1");
+        Assert.Contains(reports, r => r.Phase == "tokens");
+        Assert.Equal(reports.Select(r => r.Length).OrderByDescending(l => l), reports.Select(r => r.Length));
+        Assert.Equal(result.Input.Length, reports[^1].Length);
+    }
+
+    [Fact]
+    public void Reduce_PredicatePassingOnOriginal_Throws()
+    {
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => InputReducer.Reduce("x = 1;", Grammar(), s => s.Contains("boom")));
+    }
+
+    private static CompiledGrammar Grammar()
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(StatementGrammar));
+    }
+
+    private static string SyntheticInput(int statements, string trigger)
+    {
+        var text = new StringBuilder();
+        for (var i = 0; i < statements; i++)
+        {
+            text.AppendLine(i == statements / 2
+                ? $"{{ {{ {trigger} = (x{i} + 1); }} }}"
+                : $"v{i} = v{Math.Max(i - 1, 0)} + {i} + (w + {i});");
+        }
+
+        return text.ToString();
+    }
+
+    private sealed class SynchronousProgress : IProgress<ReductionProgress>
+    {
+        private readonly Action<ReductionProgress> _report;
+
+        public SynchronousProgress(Action<ReductionProgress> report)
+        {
+            _report = report;
+        }
+
+        public void Report(ReductionProgress value)
+        {
+            _report(value);
+        }
+    }
+}
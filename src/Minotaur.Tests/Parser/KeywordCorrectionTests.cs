@@ -0,0 +1,146 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for misspelled keyword recovery functionality
+/// </summary>
+public class KeywordCorrectionTests
+{
+    private const string StatementGrammar = """
+        <program> ::= <statements>
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= "function" <IDENTIFIER> "(" ")" "{" <statements> "}" | "return" <expr> ";" | "let" <IDENTIFIER> "=" <expr> ";" | "var" <IDENTIFIER> "=" <expr> ";"
+        <expr> ::= <IDENTIFIER> | <NUMBER>
+        """;
+
+    [Fact]
+    public void Parse_MisspelledKeyword_SuggestsKeywordAndContinues()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar);
+
+        // Act
+        var result = parser.Parse("fucntion f() {\n  retrun x;\n}");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.NotNull(result.Tree);
+        Assert.Equal(2, result.Diagnostics.Count);
+        Assert.All(result.Diagnostics, d => Assert.Equal(DiagnosticCodes.MisspelledKeyword, d.Code));
+        Assert.Equal("Unexpected IDENTIFIER 'fucntion'; did you mean 'function'?", result.Diagnostics[0].Message);
+        Assert.Equal("Unexpected IDENTIFIER 'retrun'; did you mean 'return'?", result.Diagnostics[1].Message);
+        Assert.Equal(2, result.Diagnostics[1].Location!.Line);
+        Assert.Equal(3, result.Diagnostics[1].Location!.Column);
+        Assert.Equal("\"return\"", result.Tokens[5].Kind);
+        Assert.Equal("retrun", result.Tokens[5].Text);
+    }
+
+    [Fact]
+    public void Parse_MisspelledKeyword_QuickFixCorrectsSpelling()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar);
+        const string input = "let a = 1;\nretrun a;";
+
+        // Act
+        var result = parser.Parse(input);
+
+        // Assert
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal("return", diagnostic.Data["suggestion"]);
+        var fix = Assert.IsType<TextEdit>(diagnostic.Data["fix"]);
+        Assert.Equal(new TextEdit(11, 6, "return"), fix);
+
+        var fixedText = fix.Apply(input);
+        Assert.Equal("let a = 1;\nreturn a;", fixedText);
+        Assert.True(parser.Parse(fixedText).IsSuccess);
+    }
+
+    [Fact]
+    public void Parse_IdentifierAcceptable_DoesNotCorrect()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar.Replace("<statement> ::= ", "<statement> ::= <IDENTIFIER> \"=\" <expr> \";\" | "));
+
+        // Act
+        var assignment = parser.Parse("retrun = 1;");
+        var error = parser.Parse("retrun 1;");
+
+        // Assert
+        Assert.True(assignment.IsSuccess);
+        Assert.DoesNotContain(error.Diagnostics, d => d.Code == DiagnosticCodes.MisspelledKeyword);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, Assert.Single(error.Diagnostics).Code);
+        Assert.Null(error.Tree);
+    }
+
+    [Fact]
+    public void Parse_EquallyCloseKeywords_DoesNotCorrect()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar);
+
+        // Act
+        var result = parser.Parse("vet x = 1;");
+
+        // Assert
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, Assert.Single(result.Diagnostics).Code);
+    }
+
+    [Fact]
+    public void Parse_DistanceAboveLimit_DoesNotCorrect()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar);
+
+        // Act
+        var withinDefault = parser.Parse("rettun x;");
+        var limited = parser.Parse("rettun x;", new ParseOptions { KeywordCorrectionDistance = 1 });
+        var disabled = parser.Parse("retrun x;", new ParseOptions { KeywordCorrectionDistance = 0 });
+
+        // Assert
+        Assert.Equal(DiagnosticCodes.MisspelledKeyword, Assert.Single(withinDefault.Diagnostics).Code);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, Assert.Single(limited.Diagnostics).Code);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, Assert.Single(disabled.Diagnostics).Code);
+    }
+
+    [Fact]
+    public void Parse_KeywordCorrection_LeavesCallerTokensUnchanged()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar);
+        var tokens = new GrammarLexer(parser.Grammar).Tokenize("retrun x;").Tokens;
+
+        // Act
+        var result = parser.Parse("retrun x;", tokens);
+
+        // Assert
+        Assert.Equal("IDENTIFIER", tokens[0].Kind);
+        Assert.Equal("\"return\"", result.Tokens[0].Kind);
+    }
+
+    private static GeneralizedParser CreateParser(string grammar)
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(grammar)));
+    }
+}
@@ -0,0 +1,251 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using System.Text;
+using Xunit;
+using Xunit.Abstractions;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for lazy parsing of deferred rules
+/// </summary>
+public class LazyParsingTests
+{
+    private const string FunctionGrammar = """
+        Grammar: Functions
+        StartRule: program
+        Deferred: <block>
+        <program> ::= <function> | <program> <function>
+        <function> ::= "fn" <IDENTIFIER> <block>
+        <block> ::= "{" <statements> "}" | "{" "}"
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= <IDENTIFIER> "=" <expr> ";" | <block>
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER> | "(" <expr> ")"
+        """;
+
+    private const string Functions = "fn a { x = 1; }\nfn b {\n  y = (2 + x);\n  { z = y; }\n}\nfn c { }\n";
+
+    private readonly ITestOutputHelper _output;
+
+    public LazyParsingTests(ITestOutputHelper output)
+    {
+        _output = output;
+    }
+
+    [Fact]
+    public void Parse_Lazy_LeavesFunctionBodiesUnparsed()
+    {
+        // Arrange
+        var parser = CreateParser();
+
+        // Act
+        var result = parser.Parse(Functions, new ParseOptions { Lazy = true });
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var regions = Assert.IsType<DeferredRegions>(result.Deferred);
+        Assert.Equal(3, regions.Count);
+        Assert.Equal(0, regions.MaterializedCount);
+        Assert.All(Preorder(result.Tree!, materialize: false).OfType<DeferredNode>(), n => Assert.False(n.IsMaterialized));
+    }
+
+    [Fact]
+    public void Parse_WithoutLazy_DefersNothing()
+    {
+        // Act
+        var result = CreateParser().Parse(Functions);
+
+        // Assert
+        Assert.Null(result.Deferred);
+        Assert.Empty(Preorder(result.Tree!, materialize: false).OfType<DeferredNode>());
+    }
+
+    [Fact]
+    public void MaterializeAll_BuildsTheSameTreeAsAnEagerParse()
+    {
+        // Arrange
+        var parser = CreateParser();
+        var eager = parser.Parse(Functions);
+        var lazy = parser.Parse(Functions, new ParseOptions { Lazy = true });
+
+        // Act
+        var diagnostics = lazy.Deferred!.MaterializeAll();
+
+        // Assert
+        Assert.Empty(diagnostics);
+        Assert.Equal(4, lazy.Deferred.Count);
+        Assert.Equal(4, lazy.Deferred.MaterializedCount);
+        Assert.Equal(Describe(eager.Tree!), Describe(lazy.Tree!));
+    }
+
+    [Fact]
+    public void FindNodeAt_InsideDeferredRegion_MaterializesTransparently()
+    {
+        // Arrange
+        var result = CreateParser().Parse(Functions, new ParseOptions { Lazy = true });
+        var offset = Functions.IndexOf("2 + x", StringComparison.Ordinal);
+
+        // Act
+        var node = result.Tree!.FindNodeAt(new SourcePosition(0, 0, offset, 1));
+
+        // Assert
+        var terminal = Assert.IsType<TerminalNode>(node);
+        Assert.Equal("2", terminal.Text);
+        Assert.Equal(1, result.Deferred!.MaterializedCount);
+        Assert.Equal(4, result.Deferred.Count);
+    }
+
+    [Fact]
+    public void Diagnostics_ErrorInDeferredRegion_SurfaceWhenMaterialized()
+    {
+        // Arrange
+        const string text = "fn a { x = 1; }\nfn b { y = ; }\n";
+        var parser = CreateParser();
+        var lazy = parser.Parse(text, new ParseOptions { Lazy = true });
+        Assert.True(lazy.IsSuccess);
+
+        // Act
+        var diagnostics = lazy.Deferred!.MaterializeAll();
+
+        // Assert
+        var error = Assert.Single(diagnostics);
+        Assert.Equal(2, error.Location!.Line);
+        Assert.False(lazy.IsSuccess);
+        Assert.Contains("';'", error.Message);
+        Assert.Contains(error, lazy.Diagnostics);
+        Assert.False(parser.Parse(text).IsSuccess);
+        Assert.Empty(Assert.IsType<DeferredNode>(FindRule(lazy.Tree!, "block", 1)).Children);
+    }
+
+    [Fact]
+    public void OutlineAndFolding_UsePlaceholdersWithoutMaterializing()
+    {
+        // Arrange
+        var result = CreateParser().Parse(Functions, new ParseOptions { Lazy = true });
+
+        // Act
+        var outline = OutlineProvider.GetOutline(result);
+        var folding = FoldingProvider.GetFoldingRanges(result);
+
+        // Assert
+        var item = Assert.Single(outline);
+        Assert.Equal("fn b", item.Name);
+        Assert.Equal(new FoldingRange(2, 5, FoldingProvider.RegionKind), Assert.Single(folding));
+        Assert.Equal(0, result.Deferred!.MaterializedCount);
+    }
+
+    [Fact]
+    public void Children_ReadFromSeveralThreads_MaterializesOnce()
+    {
+        // Arrange
+        var result = CreateParser().Parse(Functions, new ParseOptions { Lazy = true });
+        var body = FindRule(result.Tree!, "block", 1);
+
+        // Act
+        var children = Enumerable.Range(0, 8).AsParallel().Select(_ => body.Children.ToList()).ToList();
+
+        // Assert
+        Assert.Equal(1, result.Deferred!.MaterializedCount);
+        Assert.All(children, c => Assert.Equal(children[0], c));
+        Assert.Equal(3, children[0].Count);
+    }
+
+    [Fact]
+    public void Compile_DeferredRuleWithoutBrackets_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(FunctionGrammar.Replace("Deferred: <block>", "Deferred: <statement>"));
+
+        // Act
+        var exception = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+
+        // Assert
+        Assert.Contains("<statement>", exception.Message);
+    }
+
+    [Fact]
+    public void Parse_LazyTwoThousandFunctions_Benchmark()
+    {
+        // Arrange
+        var text = string.Concat(Enumerable.Range(0, 2000).Select(i => $"fn f{i} {{\n  x = {i} + y;\n  {{ z = (x + 1); }}\n  w = z + x + {i};\n}}\n"));
+        var parser = CreateParser();
+        parser.Parse("fn warmup { }", new ParseOptions { Lazy = true });
+        parser.Parse("fn warmup { }");
+
+        // Act
+        var lazyWatch = Stopwatch.StartNew();
+        var lazy = parser.Parse(text, new ParseOptions { Lazy = true });
+        lazyWatch.Stop();
+        var eagerWatch = Stopwatch.StartNew();
+        var eager = parser.Parse(text);
+        eagerWatch.Stop();
+
+        // Assert
+        _output.WriteLine($"{eager.Tokens.Count} tokens");
+        _output.WriteLine($"lazy initial parse: {lazyWatch.Elapsed.TotalMilliseconds:F1} ms");
+        _output.WriteLine($"eager parse: {eagerWatch.Elapsed.TotalMilliseconds:F1} ms");
+
+        Assert.True(lazy.IsSuccess);
+        Assert.Equal(2000, lazy.Deferred!.Count);
+        Assert.Equal(0, lazy.Deferred.MaterializedCount);
+        Assert.Equal(2000, OutlineProvider.GetOutline(lazy).Count);
+    }
+
+    private static GeneralizedParser CreateParser()
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(FunctionGrammar)));
+    }
+
+    private static CognitiveGraphNode FindRule(CognitiveGraphNode tree, string rule, int index)
+    {
+        return Preorder(tree, materialize: false).Where(n => n is NonTerminalNode nonTerminal && nonTerminal.RuleName == rule).ElementAt(index);
+    }
+
+    // Without materializing, the walk stops at the regions a lazy parse has not parsed yet.
+    private static IEnumerable<CognitiveGraphNode> Preorder(CognitiveGraphNode node, bool materialize)
+    {
+        yield return node;
+        if (!materialize && node is DeferredNode { IsMaterialized: false })
+        {
+            yield break;
+        }
+
+        foreach (var descendant in node.Children.SelectMany(c => Preorder(c, materialize)))
+        {
+            yield return descendant;
+        }
+    }
+
+    private static string Describe(CognitiveGraphNode node)
+    {
+        var builder = new StringBuilder();
+        foreach (var current in Preorder(node, materialize: true))
+        {
+            var label = current is NonTerminalNode nonTerminal ? $"{nonTerminal.RuleName}/{nonTerminal.ProductionIndex}" : ((TerminalNode)current).Text;
+            var position = current.SourcePosition!;
+            builder.Append($"({label} {position.Line}:{position.Column}+{position.Length}) ");
+        }
+
+        return builder.ToString();
+    }
+}
@@ -0,0 +1,194 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for NodeIdMap functionality
+/// </summary>
+public class NodeIdMapTests
+{
+    private const string AssignmentGrammar = """
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <IDENTIFIER> "=" <expr> ";"
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER>
+        """;
+
+    private const string Document = "a = 1;\nb = 2;\nc = 3;\n";
+
+    private static readonly string[] Insertions = { "x", "42", " ", "\n", ";", "=", " + y", "d = 4;\n", "$" };
+
+    [Fact]
+    public void ApplyEdit_ReusedNodes_KeepTheirIds()
+    {
+        // Arrange
+        var parser = CreateParser(Document);
+        var oldTree = parser.Current.Tree!;
+        var oldStatementC = Find(oldTree, "statement", 14);
+
+        // Act
+        var result = parser.ApplyEdit(new TextEdit(11, 1, "42"));
+
+        // Assert
+        Assert.Same(oldStatementC, Find(result.Tree!, "statement", 14));
+        Assert.Equal(oldStatementC.Id, result.NodeIdMap.Map(oldStatementC.Id));
+        Assert.Contains(oldStatementC.Id, result.NodeIdMap.Retained);
+        Assert.Same(NodeIdMap.Empty, new GeneralizedParser(parser.Current.Grammar!).Parse(Document).NodeIdMap);
+    }
+
+    [Fact]
+    public void ApplyEdit_RebuiltNodes_MapToOverlappingReplacement()
+    {
+        // Arrange
+        var parser = CreateParser(Document);
+        var oldTree = parser.Current.Tree!;
+        var oldTerm = Find(oldTree, "term", 11);
+        var oldNumber = oldTerm.Children[0];
+
+        // Act
+        var result = parser.ApplyEdit(new TextEdit(11, 1, "42"));
+
+        // Assert
+        var newTerm = Find(result.Tree!, "term", 11);
+        Assert.NotEqual(oldTerm.Id, newTerm.Id);
+        Assert.Equal(newTerm.Id, result.NodeIdMap.Map(oldTerm.Id));
+        Assert.Equal(newTerm.Children[0].Id, result.NodeIdMap.Map(oldNumber.Id));
+        Assert.Equal("42", ((TerminalNode)newTerm.Children[0]).Text);
+        Assert.Equal(9, result.NodeIdMap.Replaced.Count);
+        Assert.Empty(result.NodeIdMap.Removed);
+    }
+
+    [Fact]
+    public void ApplyEdit_DeletedStatement_IsRemoved()
+    {
+        // Arrange
+        var parser = CreateParser(Document);
+        var oldStatementB = Find(parser.Current.Tree!, "statement", 7);
+
+        // Act
+        var result = parser.ApplyEdit(TextEdit.Delete(7, 7));
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Null(result.NodeIdMap.Map(oldStatementB.Id));
+        Assert.Contains(oldStatementB.Id, result.NodeIdMap.Removed);
+        Assert.All(oldStatementB.Children, child => Assert.Contains(child.Id, result.NodeIdMap.Removed));
+    }
+
+    [Theory]
+    [InlineData(1)]
+    [InlineData(7)]
+    [InlineData(42)]
+    [InlineData(1234)]
+    [InlineData(98765)]
+    public void ApplyEdit_RandomEdits_NeverReuseIdForDifferentNode(int seed)
+    {
+        // Arrange
+        var random = new Random(seed);
+        var parser = CreateParser(Document);
+        var signatures = new Dictionary<Guid, string>();
+        Record(parser.Current.Tree, signatures);
+
+        for (var step = 0; step < 40; step++)
+        {
+            var previous = AllNodes(parser.Current.Tree).ToDictionary(n => n.Id);
+            var edit = NextEdit(random, parser.Text);
+
+            // Act
+            var result = parser.ApplyEdit(edit);
+
+            // Assert
+            var current = AllNodes(result.Tree).ToList();
+            Assert.Equal(current.Count, current.Select(n => n.Id).Distinct().Count());
+            Record(result.Tree, signatures);
+
+            var map = result.NodeIdMap;
+            var accounted = map.Retained.Concat(map.Replaced.Keys).Concat(map.Removed).ToList();
+            Assert.Equal(accounted.Count, accounted.Distinct().Count());
+            Assert.Equal(previous.Keys.OrderBy(id => id), accounted.OrderBy(id => id));
+
+            var currentById = current.ToDictionary(n => n.Id);
+            Assert.All(map.Retained, id => Assert.Same(previous[id], currentById[id]));
+            Assert.Equal(map.Replaced.Count, map.Replaced.Values.Distinct().Count());
+            foreach (var (oldId, newId) in map.Replaced)
+            {
+                Assert.False(previous.ContainsKey(newId), $"Replacement {newId} reuses an id of the previous tree");
+                Assert.Equal(Kind(previous[oldId]), Kind(currentById[newId]));
+            }
+        }
+    }
+
+    private static TextEdit NextEdit(Random random, string text)
+    {
+        var offset = random.Next(text.Length + 1);
+        var length = Math.Min(random.Next(4), text.Length - offset);
+        var insertion = random.Next(3) == 0 && length > 0 ? string.Empty : Insertions[random.Next(Insertions.Length)];
+        return new TextEdit(offset, length, insertion);
+    }
+
+    // Ids are assigned once per node, so every sighting of an id must describe the same node.
+    private static void Record(CognitiveGraphNode? tree, Dictionary<Guid, string> signatures)
+    {
+        foreach (var node in AllNodes(tree))
+        {
+            var signature = node is NonTerminalNode nonTerminal
+                ? $"{nonTerminal.RuleName}/{nonTerminal.ProductionIndex}"
+                : $"{((TerminalNode)node).TokenType}:{((TerminalNode)node).Text}";
+
+            if (!signatures.TryAdd(node.Id, signature))
+            {
+                Assert.Equal(signatures[node.Id], signature);
+            }
+        }
+    }
+
+    private static string Kind(CognitiveGraphNode node)
+    {
+        return node is NonTerminalNode nonTerminal ? nonTerminal.RuleName : ((TerminalNode)node).TokenType;
+    }
+
+    private static IEnumerable<CognitiveGraphNode> AllNodes(CognitiveGraphNode? node)
+    {
+        if (node == null)
+        {
+            yield break;
+        }
+
+        yield return node;
+        foreach (var descendant in node.Children.SelectMany(AllNodes))
+        {
+            yield return descendant;
+        }
+    }
+
+    private static NonTerminalNode Find(CognitiveGraphNode tree, string rule, int offset)
+    {
+        return AllNodes(tree).OfType<NonTerminalNode>().First(n => n.RuleName == rule && n.SourcePosition!.Offset == offset);
+    }
+
+    private static IncrementalParser CreateParser(string text)
+    {
+        var grammar = new GrammarFileReader().Read(AssignmentGrammar);
+        return new IncrementalParser(new GeneralizedParser(CompiledGrammar.Compile(grammar)), text);
+    }
+}
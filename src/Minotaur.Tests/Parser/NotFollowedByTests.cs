@@ -0,0 +1,205 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for negative lookahead constraint functionality
+/// </summary>
+public class NotFollowedByTests
+{
+    // Without the constraint "a :b" is both a label followed by an expression and an expression followed by a command.
+    private const string LabelGrammar = """
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <label> | <expr_statement> | <command>
+        <label> ::= <IDENTIFIER> ":"
+        <expr_statement> ::= <expr> !FOLLOWED_BY(":")
+        <command> ::= ":" <IDENTIFIER>
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <IDENTIFIER> | <NUMBER>
+        """;
+
+    [Fact]
+    public void Compile_Constraint_IsReadApartFromTheSymbols()
+    {
+        // Arrange & Act
+        var grammar = Compile(LabelGrammar);
+
+        // Assert
+        var alternative = Assert.Single(grammar.GetRule("expr_statement")!.Alternatives);
+        Assert.Equal(new[] { "<expr>" }, alternative.Symbols.Select(s => s.ToString()));
+        Assert.Equal(new[] { "\":\"" }, alternative.NotFollowedBy.Select(s => s.Key));
+        Assert.All(grammar.GetRule("label")!.Alternatives, a => Assert.Empty(a.NotFollowedBy));
+        Assert.Empty(grammar.Warnings);
+    }
+
+    [Fact]
+    public void Parse_LabelBeforeExpression_IsNotAlsoAnExpressionBeforeACommand()
+    {
+        // Arrange
+        var constrained = new GeneralizedParser(Compile(LabelGrammar));
+        var unconstrained = new GeneralizedParser(Compile(LabelGrammar.Replace(" !FOLLOWED_BY(\":\")", string.Empty)));
+
+        // Act
+        var result = constrained.Parse("a :b");
+        var ambiguous = unconstrained.Parse("a :b");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.DoesNotContain(Descendants(result.Tree!).OfType<NonTerminalNode>(), n => n.Metadata.ContainsKey("ambiguous"));
+        var statements = Descendants(result.Tree!).OfType<NonTerminalNode>().Where(n => n.RuleName == "statement");
+        Assert.Equal(new[] { "label", "expr_statement" }, statements.Select(s => ((NonTerminalNode)s.Children[0]).RuleName));
+        Assert.True(ambiguous.IsSuccess);
+        Assert.Contains(Descendants(ambiguous.Tree!).OfType<NonTerminalNode>(), n => n.Metadata.ContainsKey("ambiguous"));
+    }
+
+    [Fact]
+    public void Parse_ConstrainedAlternativeBeforeForbiddenToken_KeepsNoFork()
+    {
+        // Arrange
+        var grammar = Compile(LabelGrammar);
+        var unconstrained = Compile(LabelGrammar.Replace(" !FOLLOWED_BY(\":\")", string.Empty));
+        var options = new ParseOptions { RecordEvents = true };
+
+        // Act
+        var result = new GeneralizedParser(grammar).Parse("a :b", options);
+        var control = new GeneralizedParser(unconstrained).Parse("a :b", options);
+
+        // Assert: after "a", only the label may go on; the expression statement's fork is never advanced.
+        Assert.DoesNotContain(CompletedStatements(result, grammar), e => e.Set == 1);
+        Assert.Contains(CompletedStatements(control, unconstrained), e => e.Set == 1);
+    }
+
+    [Fact]
+    public void Parse_ConstrainedAlternativeBeforeOtherTokens_IsReduced()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(Compile(LabelGrammar));
+
+        // Act
+        var result = parser.Parse(":quit x + 1 y");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Empty(result.Diagnostics);
+        var statements = Descendants(result.Tree!).OfType<NonTerminalNode>().Where(n => n.RuleName == "statement");
+        Assert.Equal(new[] { "command", "expr_statement", "expr_statement" }, statements.Select(s => ((NonTerminalNode)s.Children[0]).RuleName));
+    }
+
+    [Fact]
+    public void ParsePrefix_AfterConstrainedAlternative_DoesNotSuggestForbiddenTerminal()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(Compile(LabelGrammar));
+
+        // Act
+        var number = parser.ParsePrefix("1");
+        var identifier = parser.ParsePrefix("a");
+
+        // Assert: a command may follow any statement, but not an expression statement.
+        Assert.DoesNotContain("\":\"", number.Expected);
+        Assert.Contains("\"+\"", number.Expected);
+        Assert.Contains("NUMBER", number.Expected);
+        Assert.Contains("\":\"", identifier.Expected);
+    }
+
+    [Fact]
+    public void Parse_ForbiddenTokenAfterConstrainedAlternative_ReportsSyntaxErrorWithoutIt()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(Compile(LabelGrammar));
+
+        // Act
+        var result = parser.Parse("1 :quit");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        var error = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, error.Code);
+        Assert.Equal(2, error.Location!.Offset);
+        Assert.Equal(new[] { "\"+\"" }, (IEnumerable<string>)error.Data["expected"]);
+    }
+
+    [Fact]
+    public void Compile_ConstraintNothingListedCanFollow_WarnsItIsRedundant()
+    {
+        // Arrange
+        var text = LabelGrammar.Replace("!FOLLOWED_BY(\":\")", "!FOLLOWED_BY(\"=\" \";\")");
+
+        // Act
+        var grammar = Compile(text);
+
+        // Assert
+        var warning = Assert.Single(grammar.Warnings);
+        Assert.Contains("<expr_statement>", warning);
+        Assert.Contains("redundant", warning);
+    }
+
+    [Theory]
+    [InlineData("!FOLLOWED_BY(\":\"", "not closed")]
+    [InlineData("!FOLLOWED_BY(<term>)", "not a terminal")]
+    [InlineData("!FOLLOWED_BY(\":\") !FOLLOWED_BY(\"+\")", "more than once")]
+    public void Compile_MalformedConstraint_Throws(string constraint, string expected)
+    {
+        // Arrange
+        var text = LabelGrammar.Replace("!FOLLOWED_BY(\":\")", constraint);
+
+        // Act
+        var ex = Assert.Throws<ArgumentException>(() => Compile(text));
+
+        // Assert
+        Assert.Contains("<expr_statement>", ex.Message);
+        Assert.Contains(expected, ex.Message);
+    }
+
+    [Fact]
+    public void Compile_ConstraintInScannerlessGrammar_Throws()
+    {
+        // Arrange
+        var text = "Scannerless: true\n" + LabelGrammar;
+
+        // Act & Assert
+        var ex = Assert.Throws<ArgumentException>(() => Compile(text));
+        Assert.Contains("scannerless", ex.Message);
+    }
+
+    private static IEnumerable<ChartEvent> CompletedStatements(ParseResult result, CompiledGrammar grammar)
+    {
+        var program = grammar.GetRule("program")!.Index;
+        return result.EventLog!.Events.OfType<ChartEvent>().Where(e => e.Kind == ParseEventKind.Complete && e.Rule == program);
+    }
+
+    private static CompiledGrammar Compile(string text)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(text));
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Descendants(CognitiveGraphNode node)
+    {
+        yield return node;
+        foreach (var descendant in node.Children.SelectMany(Descendants))
+        {
+            yield return descendant;
+        }
+    }
+}
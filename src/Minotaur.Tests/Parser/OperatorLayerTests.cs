@@ -0,0 +1,233 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Analysis.Passes;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for OperatorLayer functionality
+/// </summary>
+public class OperatorLayerTests
+{
+    [Fact]
+    public async Task Parse_FixityExample_MatchesSnapshot()
+    {
+        await ParseSnapshot.AssertParseAsync(ExamplePath("fixity.grammar"), ExamplePath("input.txt"));
+    }
+
+    [Fact]
+    public async Task Parse_DeclaredInfixOperator_RecordsTableEntryOnNodes()
+    {
+        // Arrange
+        var parser = await CreateParserAsync();
+
+        // Act
+        var result = parser.Parse("infixl 6 <+>;\nx = 1 <+> 2 <+> 3;\n");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var declared = Assert.Single(result.Operators!.Entries);
+        Assert.Equal(new OperatorEntry(0, "<+>", OperatorFixity.InfixLeft, 6, 13, null), declared);
+
+        var root = Binding(result.Tree!, "x");
+        Assert.Same(declared, root.Metadata[OperatorLayer.EntryMetadataKey]);
+        Assert.Equal(-1, root.ProductionIndex);
+
+        var left = Assert.IsType<NonTerminalNode>(root.Children[0]);
+        Assert.Equal("expr", left.RuleName);
+        Assert.Same(declared, left.Metadata[OperatorLayer.EntryMetadataKey]);
+        Assert.Equal("1 <+> 2", Text(left));
+    }
+
+    [Fact]
+    public async Task Parse_OperatorUsedBeforeDeclaration_ReportsUnresolvedOperator()
+    {
+        // Arrange
+        var parser = await CreateParserAsync();
+
+        // Act
+        var result = parser.Parse("x = 1 <+> 2;\ninfixl 6 <+>;\ny = 1 <+> 2;\n");
+
+        // Assert
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnresolvedOperator, diagnostic.Code);
+        Assert.Contains("'<+>' is not declared", diagnostic.Message);
+        Assert.Equal(6, diagnostic.Location!.Offset);
+
+        Assert.False(Binding(result.Tree!, "x").Metadata.ContainsKey(OperatorLayer.EntryMetadataKey));
+        Assert.Equal(3, Binding(result.Tree!, "x").Children.Count);
+        Assert.True(Binding(result.Tree!, "y").Metadata.ContainsKey(OperatorLayer.EntryMetadataKey));
+    }
+
+    [Fact]
+    public async Task Parse_Redeclaration_OnlyAffectsLaterExpressions()
+    {
+        // Arrange
+        var parser = await CreateParserAsync();
+        var input = """
+            infixl 6 <+>;
+            infixl 7 <*>;
+            a = 1 <+> 2 <*> 3;
+            infixl 8 <+>;
+            b = 1 <+> 2 <*> 3;
+            """;
+
+        // Act
+        var result = parser.Parse(input);
+
+        // Assert
+        Assert.True(result.IsSuccess);
+
+        var a = Binding(result.Tree!, "a");
+        Assert.Equal("<+>", Entry(a).Symbol);
+        Assert.Equal("2 <*> 3", Text(a.Children[2]));
+
+        var b = Binding(result.Tree!, "b");
+        Assert.Equal("<*>", Entry(b).Symbol);
+        Assert.Equal("1 <+> 2", Text(b.Children[0]));
+        Assert.Equal(8, Entry((NonTerminalNode)b.Children[0]).Precedence);
+        Assert.NotEqual(Entry(a).Id, Entry((NonTerminalNode)b.Children[0]).Id);
+    }
+
+    [Fact]
+    public async Task Parse_PrefixAndRightAssociativeOperators_BindByPrecedence()
+    {
+        // Arrange
+        var parser = await CreateParserAsync();
+
+        // Act
+        var result = parser.Parse("infixr 5 ++;\nprefix 9 -;\nx = - a ++ b ++ c;\n");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var root = Binding(result.Tree!, "x");
+        Assert.Equal("- a", Text(root.Children[0]));
+        Assert.Equal(OperatorFixity.Prefix, Entry((NonTerminalNode)root.Children[0]).Fixity);
+        Assert.Equal("b ++ c", Text(root.Children[2]));
+    }
+
+    [Fact]
+    public async Task Parse_ChainedNonAssociativeOperator_ReportsUnresolvedOperator()
+    {
+        // Arrange
+        var parser = await CreateParserAsync();
+
+        // Act
+        var result = parser.Parse("infix 4 ==;\nx = a == b == c;\n");
+
+        // Assert
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnresolvedOperator, diagnostic.Code);
+        Assert.Contains("cannot be mixed", diagnostic.Message);
+        Assert.Equal(23, diagnostic.Location!.Offset);
+    }
+
+    [Fact]
+    public async Task Parse_HostLayer_SeedsTableAndRunsHandler()
+    {
+        // Arrange
+        var parser = await CreateParserAsync();
+        var table = new OperatorTable();
+        table.Define("<*>", OperatorFixity.InfixLeft, 7);
+        var declarations = new List<string>();
+        var layer = new OperatorLayer("expr", table: table).OnDeclaration("fixity_declaration", context =>
+        {
+            declarations.Add(Text(context.Declaration));
+            OperatorLayer.DeclareFixity(context);
+        });
+
+        // Act
+        var result = parser.Parse("infixl 6 <+>;\nx = 1 <*> 2 <+> 3;\n", new ParseOptions { Operators = layer });
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(new[] { "infixl 6 <+> ;" }, declarations);
+        Assert.Equal(2, result.Operators!.Entries.Count);
+        Assert.Single(table.Entries);
+        Assert.Equal("1 <*> 2", Text(Binding(result.Tree!, "x").Children[0]));
+    }
+
+    [Fact]
+    public async Task SetDocument_ImportedOperators_ApplyAndReparseImporters()
+    {
+        // Arrange
+        var workspace = new AnalysisWorkspace(await CreateParserAsync());
+        workspace.SetDocument("main.fx", "import ops;\nx = a <|> b <|> c;\n");
+        Assert.Equal(DiagnosticCodes.UnresolvedOperator, Assert.Single(workspace.GetDocument("main.fx")!.Parse.Diagnostics).Code);
+
+        // Act
+        var added = workspace.SetDocument("ops.fx", "infixr 5 <|>;\n");
+        var rightAssociative = Binding(workspace.GetDocument("main.fx")!.Parse.Tree!, "x");
+        var changed = workspace.SetDocument("ops.fx", "infixl 5 <|>;\n");
+        var leftAssociative = Binding(workspace.GetDocument("main.fx")!.Parse.Tree!, "x");
+
+        // Assert
+        Assert.Equal(new[] { "main.fx", "ops.fx" }, added);
+        Assert.Equal(new[] { "main.fx", "ops.fx" }, changed);
+        Assert.Empty(workspace.GetDocument("main.fx")!.Parse.Diagnostics);
+        Assert.Equal("b <|> c", Text(rightAssociative.Children[2]));
+        Assert.Equal("ops.fx", Entry(rightAssociative).SourceFile);
+        Assert.Equal("a <|> b", Text(leftAssociative.Children[0]));
+    }
+
+    private static NonTerminalNode Binding(CognitiveGraphNode root, string name)
+    {
+        var binding = Descendants(root)
+            .OfType<NonTerminalNode>()
+            .Single(n => n.RuleName == "binding" && ((TerminalNode)n.Children[0]).Text == name);
+        return Assert.IsType<NonTerminalNode>(binding.Children[2]);
+    }
+
+    private static OperatorEntry Entry(NonTerminalNode node)
+    {
+        return Assert.IsType<OperatorEntry>(node.Metadata[OperatorLayer.EntryMetadataKey]);
+    }
+
+    private static string Text(CognitiveGraphNode node)
+    {
+        return string.Join(" ", Descendants(node).OfType<TerminalNode>().Select(t => t.Text));
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Descendants(CognitiveGraphNode node)
+    {
+        yield return node;
+        foreach (var descendant in node.Children.SelectMany(Descendants))
+        {
+            yield return descendant;
+        }
+    }
+
+    private static async Task<GeneralizedParser> CreateParserAsync()
+    {
+        var grammar = await new GrammarFileReader().ReadFileAsync(ExamplePath("fixity.grammar"));
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "fixity", file);
+    }
+}
@@ -0,0 +1,162 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using Xunit;
+using Xunit.Abstractions;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for parallel recognition functionality
+/// </summary>
+public class ParallelParsingTests
+{
+    // Prepositional phrases attach to any noun phrase or verb phrase before them, noun compounds split anywhere
+    // and "saw" is both a noun and a verb, so the number of readings grows quickly with the sentence.
+    private const string EnglishGrammar = """
+        <sentence> ::= <noun_phrase> <verb_phrase> | <sentence> "and" <sentence>
+        <noun_phrase> ::= <determiner> <nominal> | <nominal> | <noun_phrase> <prepositional_phrase> | <noun_phrase> "and" <noun_phrase>
+        <nominal> ::= <noun> | <adjective> <nominal> | <nominal> <noun>
+        <verb_phrase> ::= <verb> <noun_phrase> | <verb> | <verb_phrase> <prepositional_phrase> | <verb_phrase> "and" <verb_phrase>
+        <prepositional_phrase> ::= <preposition> <noun_phrase>
+        <determiner> ::= "the" | "a"
+        <noun> ::= "man" | "dog" | "telescope" | "park" | "hill" | "saw" | "garden"
+        <adjective> ::= "old" | "big"
+        <verb> ::= "saw" | "walked"
+        <preposition> ::= "with" | "in" | "on" | "near"
+        """;
+
+    private readonly ITestOutputHelper _output;
+
+    public ParallelParsingTests(ITestOutputHelper output)
+    {
+        _output = output;
+    }
+
+    [Fact]
+    public void Parse_Corpus_ParallelResultsMatchSequentialResults()
+    {
+        // Arrange
+        var parser = CreateParser();
+        var parallel = new ParseOptions { Parallelism = new ParallelParseOptions { Threshold = 1, MaxDegreeOfParallelism = 4 } };
+
+        foreach (var sentence in Corpus())
+        {
+            // Act
+            var expected = parser.Parse(sentence);
+            var actual = parser.Parse(sentence, parallel);
+
+            // Assert
+            Assert.Equal(expected.IsSuccess, actual.IsSuccess);
+            Assert.Equal(expected.Diagnostics.Select(d => d.ToString()), actual.Diagnostics.Select(d => d.ToString()));
+            Assert.Equal(Describe(expected.Forest), Describe(actual.Forest));
+            if (expected.Tree != null)
+            {
+                Assert.Equal(ParseTreeExport.ToBytes(expected), ParseTreeExport.ToBytes(actual));
+            }
+        }
+    }
+
+    [Fact]
+    public void Parse_ParallelWithRecordedEvents_RecordsTheSequentialEvents()
+    {
+        // Arrange
+        var parser = CreateParser();
+        var sentence = "the man saw the dog with the telescope in the park";
+
+        // Act
+        var expected = parser.Parse(sentence, new ParseOptions { RecordEvents = true });
+        var actual = parser.Parse(sentence, new ParseOptions
+        {
+            RecordEvents = true,
+            Parallelism = new ParallelParseOptions { Threshold = 1, MaxDegreeOfParallelism = 4 }
+        });
+
+        // Assert
+        Assert.True(actual.IsSuccess);
+        Assert.Equal(expected.EventLog!.Events.Count, actual.EventLog!.Events.Count);
+        Assert.Equal(expected.EventLog.Events.Select(e => e.ToString()), actual.EventLog.Events.Select(e => e.ToString()));
+    }
+
+    [Fact]
+    public void Parse_HighlyAmbiguousSentence_ParallelBenchmark()
+    {
+        // Arrange
+        var parser = CreateParser();
+        var sentence = "the old man saw the big dog " + string.Join(" ", Enumerable.Range(0, 24).Select(i => Phrase(i)));
+        var parallel = new ParseOptions { Parallelism = new ParallelParseOptions { Threshold = 256 } };
+        parser.Parse("the man walked");
+
+        // Act
+        var sequentialWatch = Stopwatch.StartNew();
+        var expected = parser.Parse(sentence);
+        sequentialWatch.Stop();
+        var parallelWatch = Stopwatch.StartNew();
+        var actual = parser.Parse(sentence, parallel);
+        parallelWatch.Stop();
+
+        // Assert
+        _output.WriteLine($"{expected.Tokens.Count} tokens, {expected.Forest!.Nodes.Count} forest nodes, {expected.Forest.AmbiguityCount} ambiguous");
+        _output.WriteLine($"sequential: {sequentialWatch.Elapsed.TotalMilliseconds:F1} ms");
+        _output.WriteLine($"parallel ({Environment.ProcessorCount} threads): {parallelWatch.Elapsed.TotalMilliseconds:F1} ms");
+
+        Assert.True(expected.IsSuccess);
+        Assert.Equal(Describe(expected.Forest), Describe(actual.Forest));
+    }
+
+    private static GeneralizedParser CreateParser()
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(EnglishGrammar)));
+    }
+
+    private static string Phrase(int index)
+    {
+        var prepositions = new[] { "with", "in", "on", "near" };
+        var nouns = new[] { "telescope", "park", "hill", "garden", "saw" };
+        return $"{prepositions[index % prepositions.Length]} the {nouns[index % nouns.Length]}";
+    }
+
+    private static IEnumerable<string> Corpus()
+    {
+        yield return "the man walked";
+        yield return "the man saw the dog with the telescope";
+        yield return "a big old dog walked in the park and saw the man";
+        yield return "the man saw saw";
+        yield return "old man saw the dog and the man walked near the hill";
+        yield return "the man saw with";
+        yield return "the and";
+
+        var random = new Random(429);
+        for (var i = 0; i < 20; i++)
+        {
+            var phrases = Enumerable.Range(0, random.Next(1, 8)).Select(_ => Phrase(random.Next(20)));
+            var tail = random.Next(4) == 0 ? " the" : "";
+            yield return $"the man saw the dog {string.Join(" ", phrases)}{tail}";
+        }
+    }
+
+    private static List<string> Describe(ParseForest? forest)
+    {
+        return forest?.Nodes
+            .Select(n => $"{n.RuleName}[{n.Start},{n.End}] " + string.Join(" | ", n.Packed.Select(p =>
+                $"{p.Alternative.Index}:" + string.Join(",", p.Children.Select(c => $"{c.Start}-{c.End}")))))
+            .ToList() ?? new List<string>();
+    }
+}
@@ -0,0 +1,229 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for parse event log functionality
+/// </summary>
+public sealed class ParseLogTests : IDisposable
+{
+    private const string SumGrammar = """
+        <sum> ::= <sum> "+" <term> | <term>
+        <term> ::= <NUMBER> | "(" <sum> ")"
+        """;
+
+    private const string AmbiguousGrammar = """
+        <expr> ::= <expr> "+" <expr> | <NUMBER>
+        """;
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"parselog_{Guid.NewGuid():N}");
+
+    public ParseLogTests()
+    {
+        Directory.CreateDirectory(_directory);
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_directory, recursive: true);
+    }
+
+    [Fact]
+    public void Parse_WithoutRecording_HasNoEventLog()
+    {
+        // Arrange
+        var parser = CreateParser(SumGrammar);
+
+        // Act
+        var result = parser.Parse("1 + 2");
+
+        // Assert
+        Assert.Null(result.EventLog);
+    }
+
+    [Fact]
+    public void Replay_LoadedLog_YieldsTreeOfLiveParse()
+    {
+        // Arrange
+        var parser = CreateParser(AmbiguousGrammar);
+        var live = parser.Parse("1 + 2 + 3", Recording());
+        using var stream = new MemoryStream();
+        live.EventLog!.Save(stream);
+        stream.Position = 0;
+
+        // Act
+        var replayed = ParseLog.Load(stream, parser.Grammar).Replay();
+
+        // Assert
+        Assert.True(live.IsAmbiguous);
+        Assert.Equal(SExpression.Format(live.Tree!), SExpression.Format(replayed.Tree!));
+        Assert.Equal(Positions(live.Tree!), Positions(replayed.Tree!));
+        Assert.Equal(live.Tokens, replayed.Tokens);
+        Assert.True(replayed.IsSuccess);
+    }
+
+    [Fact]
+    public void Load_SavedFile_KeepsEventsTokensAndDiagnostics()
+    {
+        // Arrange
+        var parser = CreateParser(SumGrammar);
+        var live = parser.Parse("1 + (2 +", Recording("sum.txt"));
+        var path = Path.Combine(_directory, "failed.mparse");
+
+        // Act
+        live.EventLog!.Save(path);
+        var log = ParseLog.Load(path, parser.Grammar);
+
+        // Assert
+        Assert.Equal(live.EventLog.Events, log.Events);
+        Assert.Equal(live.Tokens, log.Tokens);
+        Assert.Equal("sum.txt", log.SourceFile);
+        Assert.All(log.Events, e => Assert.IsType<ChartEvent>(e));
+        var diagnostic = Assert.Single(log.Diagnostics);
+        Assert.Equal(live.Diagnostics[0].Code, diagnostic.Code);
+        Assert.Equal(live.Diagnostics[0].Message, diagnostic.Message);
+        Assert.Equal(live.Diagnostics[0].Location, diagnostic.Location);
+        Assert.Null(log.Replay().Tree);
+    }
+
+    [Fact]
+    public void Load_DifferentGrammar_IsRefused()
+    {
+        // Arrange
+        var parser = CreateParser(SumGrammar);
+        using var stream = new MemoryStream();
+        parser.Parse("1 + 2", Recording()).EventLog!.Save(stream);
+        stream.Position = 0;
+        var other = CompiledGrammar.Compile(new GrammarFileReader().Read(SumGrammar.Replace("\"+\"", "\"-\"")));
+
+        // Act & Assert
+        var exception = Assert.Throws<InvalidDataException>(() => ParseLog.Load(stream, other));
+        Assert.Contains("different grammar", exception.Message);
+    }
+
+    [Fact]
+    public void Load_TruncatedLog_Throws()
+    {
+        // Arrange
+        var parser = CreateParser(SumGrammar);
+        using var stream = new MemoryStream();
+        parser.Parse("1 + 2", Recording()).EventLog!.Save(stream);
+        var truncated = new MemoryStream(stream.ToArray()[..(int)(stream.Length / 2)]);
+
+        // Act & Assert
+        Assert.Throws<InvalidDataException>(() => ParseLog.Load(truncated, parser.Grammar));
+    }
+
+    [Fact]
+    public void StateAt_ScanEvent_ShowsPositionStackAndChart()
+    {
+        // Arrange
+        var parser = CreateParser(SumGrammar);
+        var log = parser.Parse("1 + (2)", Recording()).EventLog!;
+        var index = IndexOf(log, e => e is ChartEvent { Kind: ParseEventKind.Scan, Position: 3 });
+
+        // Act
+        var state = log.StateAt(index);
+
+        // Assert
+        Assert.Equal(3, state.Position);
+        Assert.Equal("2", state.Token!.Text);
+        Assert.Equal("<term> ::= <NUMBER> • @3", state.Item!.ToString());
+        Assert.Equal(new[] { "sum", "term", "sum", "term" }, state.Stack);
+        Assert.Equal(5, state.Chart.Count);
+        Assert.Contains(state.Chart[3], item => item.ToString() == "<term> ::= • <NUMBER> @3");
+        Assert.Null(state.Tree);
+    }
+
+    [Fact]
+    public void StateAt_TreeEvent_ShowsPartialTree()
+    {
+        // Arrange
+        var parser = CreateParser(SumGrammar);
+        var log = parser.Parse("1 + 2", Recording()).EventLog!;
+        var index = IndexOf(log, e => e is TreeEvent { Kind: ParseEventKind.Leaf, Name: "\"+\"" });
+
+        // Act
+        var state = log.StateAt(index);
+
+        // Assert
+        Assert.Equal(new[] { "sum" }, state.Stack);
+        Assert.Null(state.Item);
+        Assert.Equal(
+            """
+            (sum
+              (sum
+                (term
+                  (NUMBER "1")))
+              "+")
+
+            """.ReplaceLineEndings("\n"),
+            SExpression.Format(state.Tree!));
+    }
+
+    [Fact]
+    public void StateAt_IndexOutOfRange_Throws()
+    {
+        // Arrange
+        var log = CreateParser(SumGrammar).Parse("1", Recording()).EventLog!;
+
+        // Act & Assert
+        Assert.Throws<ArgumentOutOfRangeException>(() => log.StateAt(log.Events.Count));
+        Assert.Throws<ArgumentOutOfRangeException>(() => log.StateAt(-1));
+    }
+
+    private static GeneralizedParser CreateParser(string grammar)
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(grammar)));
+    }
+
+    private static ParseOptions Recording(string? sourceFile = null)
+    {
+        return new ParseOptions { RecordEvents = true, SourceFile = sourceFile };
+    }
+
+    private static int IndexOf(ParseLog log, Func<ParseEvent, bool> predicate)
+    {
+        for (var i = 0; i < log.Events.Count; i++)
+        {
+            if (predicate(log.Events[i]))
+            {
+                return i;
+            }
+        }
+
+        throw new InvalidOperationException("No matching event was recorded");
+    }
+
+    private static List<SourcePosition?> Positions(CognitiveGraphNode node)
+    {
+        var positions = new List<SourcePosition?> { node.SourcePosition };
+        foreach (var child in node.Children)
+        {
+            positions.AddRange(Positions(child));
+        }
+
+        return positions;
+    }
+}
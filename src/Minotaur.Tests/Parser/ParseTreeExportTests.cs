@@ -0,0 +1,302 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using System.Runtime.CompilerServices;
+using System.Text;
+using System.Text.Json;
+using Xunit;
+using Xunit.Abstractions;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for parse tree export and buffer reading functionality
+/// </summary>
+public class ParseTreeExportTests
+{
+    private const string SumGrammar = """
+        <sum> ::= <NUMBER> | <sum> "+" <NUMBER>
+        """;
+
+    private readonly ITestOutputHelper _output;
+
+    public ParseTreeExportTests(ITestOutputHelper output)
+    {
+        _output = output;
+    }
+
+    [Fact]
+    public void ToBytes_ParsedTree_RoundTripsThroughTheReader()
+    {
+        // Arrange
+        var result = Parse("1 + 22 + 333");
+
+        // Act
+        var buffer = ParseTreeBuffer.Open(ParseTreeExport.ToBytes(result));
+        var tree = buffer.ToTree();
+
+        // Assert
+        AssertSameTree(result.Tree!, tree);
+        Assert.Equal(Descendants(result.Tree!).Count(), buffer.NodeCount);
+        Assert.Equal(0, buffer.DiagnosticCount);
+    }
+
+    [Fact]
+    public void ToBytes_RepeatedNamesAndTexts_AreStoredOnce()
+    {
+        // Arrange
+        var result = Parse("1 + 1 + 1 + 1");
+
+        // Act
+        var buffer = ParseTreeBuffer.Open(ParseTreeExport.ToBytes(result));
+
+        // Assert
+        var distinct = Descendants(result.Tree!)
+            .SelectMany(n => n is TerminalNode t ? new[] { t.TokenType, t.Text } : new[] { ((NonTerminalNode)n).RuleName })
+            .Distinct()
+            .Count();
+        Assert.Equal(distinct, buffer.StringCount);
+    }
+
+    [Fact]
+    public void Root_Navigation_ReadsNodesWithoutDecodingTheTree()
+    {
+        // Arrange
+        var result = Parse("1 + 2");
+        var buffer = ParseTreeBuffer.Open(ParseTreeExport.ToBytes(result));
+
+        // Act
+        var root = buffer.Root;
+        var last = root.GetChild(root.ChildCount - 1);
+
+        // Assert
+        Assert.False(root.IsTerminal);
+        Assert.Equal("sum", root.Name);
+        Assert.True(root.NameUtf8.SequenceEqual("sum"u8));
+        Assert.Equal(1, root.ProductionIndex);
+        Assert.Equal(-1, root.ParentIndex);
+        Assert.True(last.IsTerminal);
+        Assert.Equal("2", last.Text);
+        Assert.Equal(root.Index, last.ParentIndex);
+        Assert.Equal(result.Tree!.Children[^1].SourcePosition, last.Span);
+    }
+
+    [Fact]
+    public void ToBytes_Diagnostics_AreIncludedWithTheirLocations()
+    {
+        // Arrange
+        var result = Parse("1 + 2");
+        var diagnostics = new List<Diagnostic>
+        {
+            new() { Code = DiagnosticCodes.AmbiguousParse, Severity = DiagnosticSeverity.Warning, Message = "Ambiguous parse of <sum>", Location = new SourcePosition(1, 1, 0, 5) { EndColumn = 6, SourceFile = "calc.txt" } },
+            new() { Code = DiagnosticCodes.UnexpectedToken, Message = "Unexpected token" }
+        };
+
+        // Act
+        var buffer = ParseTreeBuffer.Open(ParseTreeExport.ToBytes(result.Tree!, diagnostics));
+        var read = buffer.GetDiagnostics();
+
+        // Assert
+        Assert.Equal(2, read.Count);
+        Assert.Equal(DiagnosticCodes.AmbiguousParse, read[0].Code);
+        Assert.Equal(DiagnosticSeverity.Warning, read[0].Severity);
+        Assert.Equal("Ambiguous parse of <sum>", read[0].Message);
+        Assert.Equal(diagnostics[0].Location, read[0].Location);
+        Assert.Equal(DiagnosticSeverity.Error, read[1].Severity);
+        Assert.Null(read[1].Location);
+    }
+
+    [Theory]
+    [InlineData("v1.bin")]
+    [InlineData("v1-wide-records.bin")]
+    public void Open_FrozenVersion1Buffer_ReadsTreeAndDiagnostics(string fixture)
+    {
+        // Arrange
+        var bytes = File.ReadAllBytes(FixturePath(fixture));
+
+        // Act
+        var buffer = ParseTreeBuffer.Open(bytes);
+        var tree = buffer.ToTree();
+
+        // Assert
+        Assert.Equal(1, buffer.Version);
+        Assert.Equal(6, buffer.NodeCount);
+        var root = Assert.IsType<NonTerminalNode>(tree);
+        Assert.Equal("sum", root.RuleName);
+        Assert.Equal(1, root.ProductionIndex);
+        Assert.Equal(2, root.Metadata["ambiguous"]);
+        Assert.Equal(new SourcePosition(1, 1, 0, 200) { EndLine = 2, EndColumn = 61, SourceFile = "calc.txt" }, root.SourcePosition);
+        Assert.Equal(new[] { "sum", "+", "NUMBER", ";" }, root.Children.Select(c => c is TerminalNode t ? t.TokenType : ((NonTerminalNode)c).RuleName));
+
+        var number = Assert.IsType<TerminalNode>(root.Children[2]);
+        Assert.Equal("2é", number.Text);
+        Assert.Equal(new SourcePosition(1, 3, 2, 198) { EndLine = 2, EndColumn = 61 }, number.SourcePosition);
+        var terminator = Assert.IsType<TerminalNode>(root.Children[3]);
+        Assert.Equal(string.Empty, terminator.Text);
+        Assert.True(terminator.Metadata.ContainsKey(TerminatorPolicy.SyntheticMetadataKey));
+        var inner = Assert.IsType<NonTerminalNode>(root.Children[0]);
+        Assert.Equal("1", Assert.IsType<TerminalNode>(Assert.Single(inner.Children)).Text);
+
+        var diagnostics = buffer.GetDiagnostics();
+        Assert.Equal(new[] { "W0001", "E0001" }, diagnostics.Select(d => d.Code));
+        Assert.Equal(new[] { DiagnosticSeverity.Warning, DiagnosticSeverity.Error }, diagnostics.Select(d => d.Severity));
+        Assert.Equal("Expected \";\"", diagnostics[1].Message);
+        Assert.Equal("calc.txt", diagnostics[0].Location!.SourceFile);
+        Assert.Null(diagnostics[1].Location);
+    }
+
+    [Fact]
+    public void Open_NewerVersion_Throws()
+    {
+        // Arrange
+        var bytes = ParseTreeExport.ToBytes(Parse("1"));
+        bytes[4] = ParseTreeExport.FormatVersion + 1;
+
+        // Act & Assert
+        var ex = Assert.Throws<FormatException>(() => ParseTreeBuffer.Open(bytes));
+        Assert.Contains("Unsupported parse tree buffer version", ex.Message);
+    }
+
+    [Fact]
+    public void Open_TruncatedOrForeignBuffer_Throws()
+    {
+        // Arrange
+        var bytes = ParseTreeExport.ToBytes(Parse("1 + 2"));
+
+        // Act & Assert
+        Assert.Throws<FormatException>(() => ParseTreeBuffer.Open(bytes.AsMemory(0, 60)));
+        Assert.Throws<FormatException>(() => ParseTreeBuffer.Open(Encoding.UTF8.GetBytes("{\"tree\": {}}")));
+    }
+
+    [Fact]
+    public void ToJson_ParsedTree_WritesNestedNodesWithSpans()
+    {
+        // Arrange
+        var result = Parse("1 + 2");
+
+        // Act
+        using var json = JsonDocument.Parse(ParseTreeExport.ToJson(result));
+
+        // Assert
+        var tree = json.RootElement.GetProperty("tree");
+        Assert.Equal("sum", tree.GetProperty("rule").GetString());
+        var children = tree.GetProperty("children");
+        Assert.Equal(3, children.GetArrayLength());
+        Assert.Equal("2", children[2].GetProperty("text").GetString());
+        Assert.Equal(4, children[2].GetProperty("span").GetProperty("offset").GetInt32());
+        Assert.Equal(0, json.RootElement.GetProperty("diagnostics").GetArrayLength());
+    }
+
+    [Fact]
+    public void ToBytes_LargeTree_IsSmallerAndFasterToReadThanJson()
+    {
+        // Arrange
+        var result = Parse(string.Join(" + ", Enumerable.Range(0, 3000)));
+
+        // Act
+        var encodeWatch = Stopwatch.StartNew();
+        var bytes = ParseTreeExport.ToBytes(result);
+        encodeWatch.Stop();
+        var jsonWatch = Stopwatch.StartNew();
+        var json = Encoding.UTF8.GetByteCount(ParseTreeExport.ToJson(result));
+        jsonWatch.Stop();
+
+        var readWatch = Stopwatch.StartNew();
+        var tokens = CountTokens(ParseTreeBuffer.Open(bytes));
+        readWatch.Stop();
+        var jsonReadWatch = Stopwatch.StartNew();
+        using var document = JsonDocument.Parse(ParseTreeExport.ToJson(result));
+        jsonReadWatch.Stop();
+
+        // Assert
+        _output.WriteLine($"binary: {bytes.Length / 1024} KiB, written in {encodeWatch.Elapsed.TotalMilliseconds:F1} ms, walked in {readWatch.Elapsed.TotalMilliseconds:F1} ms");
+        _output.WriteLine($"json: {json / 1024} KiB, written in {jsonWatch.Elapsed.TotalMilliseconds:F1} ms, parsed in {jsonReadWatch.Elapsed.TotalMilliseconds:F1} ms");
+
+        Assert.Equal(result.Tokens.Count, tokens);
+        Assert.True(bytes.Length * 2 < json, $"binary form is {bytes.Length} bytes, JSON {json}");
+    }
+
+    private static ParseResult Parse(string input)
+    {
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(SumGrammar));
+        var result = new GeneralizedParser(grammar).Parse(input);
+        Assert.True(result.IsSuccess);
+        return result;
+    }
+
+    private static int CountTokens(ParseTreeBuffer buffer)
+    {
+        var count = 0;
+        for (var i = 0; i < buffer.NodeCount; i++)
+        {
+            count += buffer.GetNode(i).IsTerminal ? 1 : 0;
+        }
+
+        return count;
+    }
+
+    private static void AssertSameTree(CognitiveGraphNode expected, CognitiveGraphNode actual)
+    {
+        var expectedNodes = Descendants(expected).ToList();
+        var actualNodes = Descendants(actual).ToList();
+        Assert.Equal(expectedNodes.Count, actualNodes.Count);
+        for (var i = 0; i < expectedNodes.Count; i++)
+        {
+            Assert.Equal(expectedNodes[i].GetType(), actualNodes[i].GetType());
+            Assert.Equal(expectedNodes[i].SourcePosition, actualNodes[i].SourcePosition);
+            Assert.Equal(expectedNodes[i].Children.Count, actualNodes[i].Children.Count);
+            if (expectedNodes[i] is TerminalNode terminal)
+            {
+                var other = (TerminalNode)actualNodes[i];
+                Assert.Equal(terminal.TokenType, other.TokenType);
+                Assert.Equal(terminal.Text, other.Text);
+            }
+            else
+            {
+                var rule = (NonTerminalNode)expectedNodes[i];
+                var other = (NonTerminalNode)actualNodes[i];
+                Assert.Equal(rule.RuleName, other.RuleName);
+                Assert.Equal(rule.ProductionIndex, other.ProductionIndex);
+            }
+        }
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Descendants(CognitiveGraphNode root)
+    {
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(root);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            yield return node;
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+    }
+
+    private static string FixturePath(string name, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "Fixtures", "tree-buffer", name);
+    }
+}
@@ -0,0 +1,182 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for PositionMap functionality
+/// </summary>
+public class PositionMapTests
+{
+    private const string LongSignature =
+        "fn distance(first_point: Point, second_point: Point, scale: i32, offset: i32) -> i32 { let dx = first_point.x + second_point.x; dx + scale + offset }\n";
+
+    [Fact]
+    public void Format_RewrapsLongSignature_DiagnosticsMapToTheSameTokens()
+    {
+        // Arrange
+        var formatter = new SourceFormatter(LoadRustItems()) { MaxLineLength = 40 };
+        var lines = new LineIndex(LongSignature);
+        var scale = lines.GetPosition(LongSignature.IndexOf("scale:", StringComparison.Ordinal), "scale".Length);
+        var offset = lines.GetPosition(LongSignature.IndexOf("offset:", StringComparison.Ordinal), "offset".Length);
+        var tail = lines.GetPosition(LongSignature.LastIndexOf("offset", StringComparison.Ordinal), "offset".Length);
+
+        // Act
+        var result = formatter.Format(LongSignature);
+        var mappedScale = result.Map.MapForward(new TextRange(scale.Offset, scale.Length));
+        var mappedOffset = result.Map.MapForward(offset);
+        var mappedTail = result.Map.MapForward(tail);
+
+        // Assert
+        Assert.Equal("""
+            fn distance(first_point: Point,
+                second_point: Point, scale: i32,
+                offset: i32) -> i32 {
+                let dx = first_point.x + second_point.x;
+                dx + scale + offset
+            }
+
+            """.Replace("\r\n", "\n"), result.Text);
+        Assert.True(mappedScale.IsExact);
+        Assert.Equal("scale", result.Text.Substring(mappedScale.Range.Start, mappedScale.Range.Length));
+        Assert.Equal((3, 5), (mappedOffset.Line, mappedOffset.Column));
+        Assert.Equal("offset", result.Text.Substring(mappedOffset.Offset, mappedOffset.Length));
+        Assert.Equal((5, 18), (mappedTail.Line, mappedTail.Column));
+        Assert.Equal(scale.Offset, result.Map.MapBackward(mappedScale.Range).Range.Start);
+        Assert.Equal(tail.Offset, result.Map.MapBackward(mappedTail).Offset);
+    }
+
+    [Fact]
+    public void Format_SpanAcrossInsertedLineBreak_StaysExactAndCoversTheBreak()
+    {
+        // Arrange
+        var formatter = new SourceFormatter(LoadRustItems()) { MaxLineLength = 40 };
+        var start = LongSignature.IndexOf("scale:", StringComparison.Ordinal);
+        var end = LongSignature.IndexOf("offset:", StringComparison.Ordinal) + "offset".Length;
+
+        // Act
+        var result = formatter.Format(LongSignature);
+        var mapped = result.Map.MapForward(new TextRange(start, end - start));
+
+        // Assert
+        Assert.Equal(SpanMappingKind.Exact, mapped.Kind);
+        Assert.Equal("scale: i32,\n    offset", result.Text.Substring(mapped.Range.Start, mapped.Range.Length));
+    }
+
+    [Fact]
+    public void FromEdits_DeletionAndInsertion_ReportsDeletedSynthesizedAndBracketingSpans()
+    {
+        // Arrange
+        var edits = new[] { new TextEdit(0, 0, "delta "), new TextEdit(6, 5, string.Empty) };
+
+        // Act
+        var map = PositionMap.FromEdits("alpha beta gamma", edits);
+
+        // Assert
+        Assert.Equal("delta alpha gamma", map.Output);
+        Assert.Equal(new MappedSpan(new TextRange(12, 0), SpanMappingKind.Deleted), map.MapForward(new TextRange(6, 4)));
+        Assert.Equal(new MappedSpan(new TextRange(12, 5), SpanMappingKind.Exact), map.MapForward(new TextRange(11, 5)));
+        Assert.Equal(new MappedSpan(new TextRange(6, 6), SpanMappingKind.Bracketing), map.MapForward(new TextRange(0, 10)));
+        Assert.Equal(new MappedSpan(new TextRange(0, 0), SpanMappingKind.Synthesized), map.MapBackward(new TextRange(0, 5)));
+        Assert.Contains(map.Segments, segment => segment.IsDeleted);
+        Assert.Contains(map.Segments, segment => segment.IsSynthesized);
+    }
+
+    [Fact]
+    public void Identity_MapsEverySpanToItself()
+    {
+        // Arrange
+        var map = PositionMap.Identity("let x = 1;");
+
+        // Act
+        var forward = map.MapForward(new TextRange(4, 1));
+        var backward = map.MapBackward(new TextRange(8, 2));
+
+        // Assert
+        Assert.Equal(new MappedSpan(new TextRange(4, 1), SpanMappingKind.Exact), forward);
+        Assert.Equal(new MappedSpan(new TextRange(8, 2), SpanMappingKind.Exact), backward);
+    }
+
+    [Fact]
+    public void QuickFixApply_OverlappingFixes_AppliesFirstAndMapsRemainingDiagnostics()
+    {
+        // Arrange
+        const string text = "alpha beta gamma";
+        var diagnostics = new[]
+        {
+            Fix(new TextEdit(6, 5, string.Empty)),
+            Fix(new TextEdit(7, 2, "x")),
+            Fix(new TextEdit(0, 0, "delta ")),
+            new Diagnostic { Message = "no fix" }
+        };
+
+        // Act
+        var result = QuickFix.Apply(text, diagnostics);
+
+        // Assert
+        Assert.Equal("delta alpha gamma", result.Text);
+        Assert.Equal(2, result.Applied.Count);
+        Assert.Same(diagnostics[1], Assert.Single(result.Skipped));
+        Assert.Equal(new TextRange(12, 5), result.Map.MapForward(new TextRange(11, 5)).Range);
+    }
+
+    [Fact]
+    public void TreeEditorApply_ReplacedIdentifier_MapsUntouchedTokensExactly()
+    {
+        // Arrange
+        var grammar = LoadRustItems();
+        var parser = new GeneralizedParser(grammar);
+        const string text = "fn main() -> i32 {\n    let total = 1 + 2;\n    total\n}\n";
+        var original = parser.Parse(text);
+        var binding = TreeQuery.Parse("statement > IDENTIFIER").Select(original.Tree!).First();
+        var tail = text.LastIndexOf("total", StringComparison.Ordinal);
+
+        // Act
+        var result = new TreeEditor(parser, original).ReplaceNode(binding.Id, "sum").Apply();
+        var mappedTail = result.Map.MapForward(new TextRange(tail, "total".Length));
+        var mappedBinding = result.Map.MapForward(new TextRange(binding.SourcePosition!.Offset, binding.SourcePosition.Length));
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.True(mappedTail.IsExact);
+        Assert.Equal("total", result.Text.Substring(mappedTail.Range.Start, mappedTail.Range.Length));
+        Assert.Equal(SpanMappingKind.Bracketing, mappedBinding.Kind);
+        Assert.Equal("sum", result.Text.Substring(mappedBinding.Range.Start, mappedBinding.Range.Length));
+    }
+
+    private static Diagnostic Fix(TextEdit edit)
+    {
+        return new Diagnostic { Message = "fixable", Data = { [QuickFix.DataKey] = edit } };
+    }
+
+    private static CompiledGrammar LoadRustItems()
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(File.ReadAllText(ExamplePath("rust_items.grammar"))));
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", file);
+    }
+}
@@ -0,0 +1,125 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for Preamble functionality
+/// </summary>
+public class PreambleTests
+{
+    private const string StatementGrammar = """
+        <program> ::= <statement> <program> | ε
+        <statement> ::= <IDENTIFIER> ";"
+        """;
+
+    [Fact]
+    public void Parse_ByteOrderMarkAndShebang_SkipsBothWithFileOffsets()
+    {
+        // Arrange
+        var parser = CreateParser();
+        var input = "\uFEFF#!/usr/bin/env -S stmt --strict\nfirst;\nsecond;\n";
+
+        // Act
+        var result = parser.Parse(input, new ParseOptions { Preamble = new PreambleOptions() });
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(new[] { PreambleKind.ByteOrderMark, PreambleKind.Shebang }, result.Preamble.Parts.Select(p => p.Kind));
+        Assert.Equal(1, result.Preamble.Parts[1].Offset);
+        Assert.Equal(33, result.Preamble.Length);
+        Assert.Equal("/usr/bin/env -S stmt --strict", result.Preamble.Shebang);
+        Assert.Equal("stmt", result.Preamble.Interpreter);
+
+        var first = result.Tokens[0];
+        Assert.Equal("first", first.Text);
+        Assert.Equal(33, first.Offset);
+        var terminal = Descendants(result.Tree!).OfType<TerminalNode>().First();
+        Assert.Equal(2, terminal.SourcePosition!.Line);
+        Assert.Equal(1, terminal.SourcePosition.Column);
+    }
+
+    [Fact]
+    public void Parse_OnlyPreamble_IsEmptyAndValid()
+    {
+        // Arrange
+        var parser = CreateParser();
+
+        // Act
+        var result = parser.Parse("\uFEFF#!/bin/stmt", new ParseOptions { Preamble = new PreambleOptions() });
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Empty(result.Tokens);
+        Assert.Empty(result.Diagnostics);
+        Assert.Equal(12, result.Preamble.Length);
+        Assert.Empty(result.Tree!.Children);
+    }
+
+    [Fact]
+    public void Parse_ConfiguredPattern_FollowsShebangAndReportsErrorsAtFileOffsets()
+    {
+        // Arrange
+        var parser = CreateParser();
+        var options = new ParseOptions { Preamble = new PreambleOptions().AddPattern("php", @"<\?php\s*") };
+
+        // Act
+        var result = parser.Parse("#!/usr/bin/php\n<?php\nx;\n%", options);
+
+        // Assert
+        Assert.Equal("php", result.Preamble.Parts[1].Name);
+        Assert.Equal(21, result.Preamble.Length);
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnrecognizedCharacter, diagnostic.Code);
+        Assert.Equal(24, diagnostic.Location!.Offset);
+        Assert.Equal(4, diagnostic.Location.Line);
+    }
+
+    [Fact]
+    public void Parse_WithoutPreambleOptions_ParsesWholeInput()
+    {
+        // Arrange
+        var parser = CreateParser();
+
+        // Act
+        var result = parser.Parse("#!/bin/stmt\nx;\n");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Same(Preamble.Empty, result.Preamble);
+    }
+
+    private static GeneralizedParser CreateParser()
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(StatementGrammar)));
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Descendants(CognitiveGraphNode node)
+    {
+        yield return node;
+        foreach (var descendant in node.Children.SelectMany(Descendants))
+        {
+            yield return descendant;
+        }
+    }
+}
@@ -0,0 +1,181 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for GeneralizedParser.ParsePrefix functionality
+/// </summary>
+public class PrefixParsingTests
+{
+    private const string BlockGrammar = """
+        Grammar: Blocks
+        Categories: operator = "+" "*"; delimiter = "{" "}" ";"; value = <NUMBER> <IDENTIFIER>
+
+        <program> ::= <statements>
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= <block> | <expr> ";"
+        <block> ::= "{" <statements> "}" | "{" "}"
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <term> "*" <factor> | <factor>
+        <factor> ::= <NUMBER> | <IDENTIFIER>
+        """;
+
+    [Fact]
+    public void ParsePrefix_UnterminatedBlock_NeedsMoreInput()
+    {
+        // Arrange
+        var parser = CreateParser(BlockGrammar);
+
+        // Act
+        var result = parser.ParsePrefix("{ a; {\n  b;");
+
+        // Assert
+        Assert.Equal(PrefixStatus.Incomplete, result.Status);
+        Assert.True(result.NeedsMoreInput);
+        Assert.Equal(6, result.ValidTokenCount);
+        Assert.Equal(11, result.ValidLength);
+        Assert.Equal(new[] { "\"{\"", "\"}\"", "IDENTIFIER", "NUMBER" }, result.Expected);
+        Assert.Equal(new[] { "delimiter", "value" }, result.ExpectedByCategory.Keys);
+        Assert.Equal(new[] { "\"{\"", "\"}\"" }, result.ExpectedByCategory["delimiter"]);
+    }
+
+    [Fact]
+    public void ParsePrefix_DanglingBinaryOperator_ExpectsOperand()
+    {
+        // Arrange
+        var parser = CreateParser(BlockGrammar);
+
+        // Act
+        var result = parser.ParsePrefix("a * 2 +");
+
+        // Assert
+        Assert.Equal(PrefixStatus.Incomplete, result.Status);
+        Assert.Equal(7, result.ValidLength);
+        Assert.Equal(new[] { "IDENTIFIER", "NUMBER" }, result.Expected);
+        Assert.Equal(new[] { "IDENTIFIER", "NUMBER" }, Assert.Single(result.ExpectedByCategory).Value);
+    }
+
+    [Fact]
+    public void ParsePrefix_OperandWithoutTerminator_ExpectsOperatorOrTerminator()
+    {
+        // Arrange
+        var parser = CreateParser(BlockGrammar);
+
+        // Act
+        var result = parser.ParsePrefix("a + b");
+
+        // Assert
+        Assert.Equal(PrefixStatus.Incomplete, result.Status);
+        Assert.Equal(new[] { "\"*\"", "\"+\"" }, result.ExpectedByCategory["operator"]);
+        Assert.Equal(new[] { "\";\"" }, result.ExpectedByCategory["delimiter"]);
+    }
+
+    [Fact]
+    public void ParsePrefix_CompleteSentence_StillReportsContinuations()
+    {
+        // Arrange
+        var parser = CreateParser(BlockGrammar);
+
+        // Act
+        var result = parser.ParsePrefix("a;");
+
+        // Assert
+        Assert.Equal(PrefixStatus.Complete, result.Status);
+        Assert.True(result.IsComplete);
+        Assert.False(result.NeedsMoreInput);
+        Assert.Equal(2, result.ValidLength);
+        Assert.Equal(new[] { "\"{\"", "IDENTIFIER", "NUMBER" }, result.Expected);
+    }
+
+    [Fact]
+    public void ParsePrefix_InvalidToken_StopsBeforeIt()
+    {
+        // Arrange
+        var parser = CreateParser(BlockGrammar);
+
+        // Act
+        var result = parser.ParsePrefix("a + ; b;");
+
+        // Assert
+        Assert.Equal(PrefixStatus.Invalid, result.Status);
+        Assert.Equal(2, result.ValidTokenCount);
+        Assert.Equal(4, result.ValidLength);
+        Assert.Equal(new[] { "IDENTIFIER", "NUMBER" }, result.Expected);
+    }
+
+    [Fact]
+    public void ParsePrefix_LexicalError_StopsAtIt()
+    {
+        // Arrange
+        var parser = CreateParser(BlockGrammar);
+
+        // Act
+        var result = parser.ParsePrefix("a @ b;");
+
+        // Assert
+        Assert.Equal(PrefixStatus.Invalid, result.Status);
+        Assert.Equal(1, result.ValidTokenCount);
+        Assert.Equal(2, result.ValidLength);
+        Assert.Equal(new[] { "\"*\"", "\"+\"", "\";\"" }, result.Expected);
+    }
+
+    [Fact]
+    public void ParsePrefix_UndeclaredTerminals_UseTokenTypeCategory()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            Keywords: let
+
+            <program> ::= <LET> <IDENTIFIER> "=" <NUMBER>
+            """);
+
+        // Act
+        var result = parser.ParsePrefix("");
+
+        // Assert
+        Assert.Equal(PrefixStatus.Incomplete, result.Status);
+        Assert.Equal(0, result.ValidLength);
+        Assert.Equal(new[] { "LET" }, result.ExpectedByCategory["keyword"]);
+        Assert.Equal("other", parser.Grammar.GetTerminalCategory("\"=\""));
+    }
+
+    [Fact]
+    public void Compile_MalformedCategory_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            Categories: operator "+"
+
+            <program> ::= <NUMBER> "+" <NUMBER>
+            """);
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+    }
+
+    private static GeneralizedParser CreateParser(string grammarText)
+    {
+        var grammar = new GrammarFileReader().Read(grammarText);
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+}
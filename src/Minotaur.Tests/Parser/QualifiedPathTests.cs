@@ -0,0 +1,132 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for qualified path functionality
+/// </summary>
+public class QualifiedPathTests
+{
+    [Fact]
+    public void GetQualifiedPath_NodeThreeLevelsDeep_ListsEnclosingDeclarations()
+    {
+        // Arrange
+        var result = ParseExample();
+        var sum = Find(result, "sum", "self.x + other.x");
+
+        // Act
+        var path = result.GetQualifiedPath(sum);
+
+        // Assert
+        Assert.Equal("mod shapes > impl Add for Point > fn add", path.ToString());
+        Assert.Equal("shapes::impl Add for Point::add", path.GetQualifiedName());
+        Assert.Equal(new[] { "module", "impl", "function" }, path.Segments.Select(s => s.Kind));
+        Assert.Equal(new[] { 1, 7, 8 }, path.Segments.Select(s => s.Location.Line));
+    }
+
+    [Fact]
+    public void GetQualifiedPath_Closures_AreNumberedWithinTheirFunction()
+    {
+        // Arrange
+        var result = ParseExample();
+        var scale = Find(result, "closure", "|v| v + k");
+        var shift = Find(result, "closure", "|v, d|");
+
+        // Act
+        var first = result.GetQualifiedPath(scale.Children.Last());
+        var second = result.GetQualifiedPath(shift);
+
+        // Assert
+        Assert.Equal("mod shapes > impl Point > fn scaled > {closure#0}", first.ToString());
+        Assert.Equal("shapes::impl Point::scaled::{closure#1}", second.GetQualifiedName());
+        Assert.Equal(new PathSegment("closure", "{closure#1}", "{closure#1}", shift.SourcePosition!), second.Segments[^1]);
+    }
+
+    [Fact]
+    public void GetQualifiedPath_GrammarWithoutOutlineRules_IsEmpty()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read("<list> ::= <IDENTIFIER> | <list> <IDENTIFIER>"));
+        var result = new GeneralizedParser(grammar).Parse("a b");
+
+        // Act
+        var path = result.GetQualifiedPath(result.Tree!);
+
+        // Assert
+        Assert.True(path.IsEmpty);
+        Assert.Equal(string.Empty, path.ToString());
+    }
+
+    [Fact]
+    public void Parse_PathContext_AddsThePathToDiagnostics()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompileExample());
+        var input = "mod shapes {\n    fn broken() {\n        let s = \"\\q\";\n    }\n}\n";
+
+        // Act
+        var result = parser.Parse(input, new ParseOptions { PathContext = true });
+
+        // Assert
+        var diagnostic = Assert.Single(result.Diagnostics, d => d.Code == DiagnosticCodes.InvalidEscape);
+        Assert.Equal("mod shapes > fn broken", diagnostic.Data[QualifiedPath.DataKey].ToString());
+        Assert.Contains("(in mod shapes > fn broken)", new DiagnosticFormatter().Format(diagnostic));
+        Assert.DoesNotContain(parser.Parse(input).Diagnostics, d => d.Data.ContainsKey(QualifiedPath.DataKey));
+    }
+
+    [Theory]
+    [InlineData("<item> ::= \"fn\" <IDENTIFIER>\n// @breadcrumb(\"fn $IDENTIFIER\")\n", "not listed under Outline")]
+    [InlineData("Outline: <item>\n<item> ::= \"fn\" <IDENTIFIER>\n// @breadcrumb(\"fn $NAME\")\n", "$NAME")]
+    [InlineData("Outline: <item>\n<item> ::= \"fn\" <IDENTIFIER>\n// @breadcrumb(fn $IDENTIFIER)\n", "quoted template")]
+    public void Compile_InvalidBreadcrumb_Throws(string text, string mentioned)
+    {
+        // Act & Assert
+        var ex = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(new GrammarFileReader().Read(text)));
+        Assert.Contains(mentioned, ex.Message);
+    }
+
+    private static CompiledGrammar CompileExample()
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(File.ReadAllText(ExamplePath("rust_items.grammar"))));
+    }
+
+    private static ParseResult ParseExample()
+    {
+        var result = new GeneralizedParser(CompileExample()).Parse(File.ReadAllText(ExamplePath("paths.rs")).Replace("\r\n", "\n"));
+        Assert.True(result.IsSuccess, string.Join("\n", result.Diagnostics.Select(d => d.Message)));
+        return result;
+    }
+
+    private static CognitiveGraphNode Find(ParseResult result, string selector, string text)
+    {
+        return TreeQuery.Parse(selector).Select(result.Tree!).First(node =>
+            result.Input.Substring(node.SourcePosition!.Offset, node.SourcePosition.Length).StartsWith(text, StringComparison.Ordinal));
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", file);
+    }
+}
@@ -0,0 +1,251 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for scannerless parsing functionality
+/// </summary>
+public class ScannerlessParsingTests
+{
+    private const string StatementGrammar = """
+        Grammar: Statements
+        Scannerless: true
+        Layout: layout
+        StartRule: program
+
+        <program> ::= <layout> <statements> <layout>
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= <IDENT> "=" <NUMBER> ";" | "print" <IDENT> ";"
+        <layout> ::= /[ \t\n]*/
+        <IDENT> ::= /[a-z]+/
+        <NUMBER> ::= /[0-9]+/
+        """;
+
+    [Fact]
+    public void Parse_ScannerlessGrammar_ProducesOneTokenPerCharacter()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar);
+
+        // Act
+        var result = parser.Parse("a = 1;");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(6, result.Tokens.Count);
+        Assert.All(result.Tokens, token => Assert.Equal(GrammarLexer.CharacterKind, token.Kind));
+    }
+
+    [Fact]
+    public void Parse_LayoutRule_IsInsertedBetweenSymbols()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar);
+
+        // Act
+        var result = parser.Parse("  a=1;\n\tb =  22 ;\n");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.False(result.IsAmbiguous);
+
+        var statement = Statements(result.Tree!).Last();
+        Assert.Equal(new[] { "IDENT", "layout", "\"=\"", "layout", "NUMBER", "layout", "\";\"" }, statement.Children.Select(Kind));
+        Assert.Equal("  ", Assert.IsType<NonTerminalNode>(statement.Children[3]).Children.OfType<TerminalNode>().Single().Text);
+    }
+
+    [Fact]
+    public void Parse_PatternTerminal_CoversMatchedCharactersInOneLeaf()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar);
+
+        // Act
+        var result = parser.Parse("total = 1024;");
+
+        // Assert
+        var statement = Assert.Single(Statements(result.Tree!));
+        var name = Assert.IsType<TerminalNode>(statement.Children[0]);
+        Assert.Equal("total", name.Text);
+        Assert.Equal("IDENT", name.TokenType);
+        Assert.Equal(0, name.SourcePosition!.Offset);
+        Assert.Equal(5, name.SourcePosition.Length);
+
+        var number = Assert.IsType<TerminalNode>(statement.Children[4]);
+        Assert.Equal("1024", number.Text);
+        Assert.Equal(8, number.SourcePosition!.Offset);
+    }
+
+    [Fact]
+    public void Parse_KeywordInIdentifierPosition_IsAnIdentifier()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar);
+
+        // Act
+        var result = parser.Parse("print = 1; print print;");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.False(result.IsAmbiguous);
+
+        var statements = Statements(result.Tree!).ToList();
+        Assert.Equal(0, statements[0].ProductionIndex);
+        Assert.Equal("IDENT", Kind(statements[0].Children[0]));
+        Assert.Equal(1, statements[1].ProductionIndex);
+        Assert.Equal(new[] { "\"print\"", "layout", "IDENT", "layout", "\";\"" }, statements[1].Children.Select(Kind));
+    }
+
+    [Fact]
+    public void Parse_MissingTerminal_ReportsSyntaxError()
+    {
+        // Arrange
+        var parser = CreateParser(StatementGrammar);
+
+        // Act
+        var result = parser.Parse("a = ;");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Null(result.Tree);
+        Assert.Contains(result.Diagnostics, d => d.Location!.Offset == 4);
+    }
+
+    [Fact]
+    public void Compile_UndefinedLayoutRule_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            Scannerless: true
+            Layout: spacing
+
+            <program> ::= "a" "b"
+            """);
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+    }
+
+    [Fact]
+    public void Compile_LayoutWithoutScannerless_IsIgnored()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            Layout: layout
+
+            <program> ::= <IDENTIFIER> <IDENTIFIER>
+            <layout> ::= /[ ]*/
+            """);
+
+        // Act
+        var compiled = CompiledGrammar.Compile(grammar);
+
+        // Assert
+        Assert.False(compiled.IsScannerless);
+        Assert.Equal(2, compiled.GetRule("program")!.Alternatives[0].Symbols.Count);
+    }
+
+    [Fact]
+    public void ApplyEdit_ScannerlessGrammar_MatchesFullParse()
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateParser(StatementGrammar), "a = 1;\nb = 2;\nc = 3;\n");
+
+        // Act
+        var result = parser.ApplyEdit(new TextEdit(11, 1, "42"));
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var expected = CreateParser(StatementGrammar).Parse("a = 1;\nb = 42;\nc = 3;\n");
+        Assert.Equal(SExpression.Format(expected.Tree!), SExpression.Format(result.Tree!));
+        Assert.True(parser.LastStats!.Value.ReusedNodeCount > 0);
+    }
+
+    [Fact]
+    public async Task Parse_MarkdownSubsetExample_MatchesSnapshot()
+    {
+        await ParseSnapshot.AssertParseAsync(ExamplePath("markdown_subset.grammar"), ExamplePath("input.md"));
+    }
+
+    [Fact]
+    public async Task Parse_MarkdownSubset_AsteriskInCodeSpanIsText()
+    {
+        // Arrange
+        var grammar = await new GrammarFileReader().ReadFileAsync(ExamplePath("markdown_subset.grammar"));
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(grammar));
+
+        // Act
+        var result = parser.Parse("*a* `*b*`\n");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.False(result.IsAmbiguous);
+
+        var terminals = Terminals(result.Tree!).ToList();
+        Assert.Equal(new[] { "*", "a", "*", " ", "`", "*b*", "`", "\n" }, terminals.Select(t => t.Text));
+        Assert.Equal("CODE", terminals[5].TokenType);
+    }
+
+    private static IEnumerable<NonTerminalNode> Statements(CognitiveGraphNode root)
+    {
+        return Descendants(root).OfType<NonTerminalNode>().Where(n => n.RuleName == "statement").OrderBy(n => n.SourcePosition!.Offset);
+    }
+
+    private static IEnumerable<TerminalNode> Terminals(CognitiveGraphNode root)
+    {
+        return Descendants(root).OfType<TerminalNode>().OrderBy(n => n.SourcePosition!.Offset);
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Descendants(CognitiveGraphNode node)
+    {
+        yield return node;
+        foreach (var descendant in node.Children.SelectMany(Descendants))
+        {
+            yield return descendant;
+        }
+    }
+
+    private static string Kind(CognitiveGraphNode node)
+    {
+        return node switch
+        {
+            NonTerminalNode nonTerminal => nonTerminal.RuleName,
+            TerminalNode terminal => terminal.TokenType,
+            _ => node.NodeType
+        };
+    }
+
+    private static GeneralizedParser CreateParser(string grammarText)
+    {
+        var grammar = new GrammarFileReader().Read(grammarText);
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "markup", "markdown_subset", file);
+    }
+}
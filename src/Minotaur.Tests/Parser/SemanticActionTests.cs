@@ -0,0 +1,184 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for semantic action functionality
+/// </summary>
+public class SemanticActionTests
+{
+    private const string AmbiguousSubtraction = """
+        <expr> ::= <expr> "-" <expr> => { subtract } | <NUMBER> => { number }
+        """;
+
+    [Theory]
+    [InlineData("1 + 2 * 3", 7)]
+    [InlineData("(1 + 2) * 3", 9)]
+    [InlineData("8 / 2 / 2", 2)]
+    [InlineData("10 - 4 - 3", 3)]
+    [InlineData("-3 - -4", 1)]
+    [InlineData("2 * (3 + 4) - 10 / 4", 11.5)]
+    public void ParseWithActions_Calculator_EvaluatesExpression(string input, double expected)
+    {
+        // Arrange
+        var parser = CreateCalculator();
+
+        // Act
+        var result = parser.ParseWithActions(input, CalculatorActions());
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(expected, result.Value);
+        Assert.NotNull(result.Tree);
+    }
+
+    [Fact]
+    public void ParseWithActions_SyntaxError_ReturnsNoValue()
+    {
+        // Arrange
+        var parser = CreateCalculator();
+
+        // Act
+        var result = parser.ParseWithActions("1 + * 2", CalculatorActions());
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Null(result.Value);
+        Assert.Contains(result.Diagnostics, d => d.Code == DiagnosticCodes.UnexpectedToken);
+    }
+
+    [Fact]
+    public void ParseWithActions_ActionThrows_ReportsDiagnosticAtReduction()
+    {
+        // Arrange
+        var parser = CreateCalculator();
+        var actions = CalculatorActions().Register("divide", (_, v) =>
+            (double)v[2]! == 0 ? throw new DivideByZeroException() : (double)v[0]! / (double)v[2]!);
+
+        // Act
+        var result = parser.ParseWithActions("1 + 6 / 0", actions);
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Null(result.Value);
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.SemanticActionFailed, diagnostic.Code);
+        Assert.Equal("divide", diagnostic.Data["action"]);
+        Assert.Equal(4, diagnostic.Location!.Offset);
+        Assert.Equal(5, diagnostic.Location.Length);
+    }
+
+    [Fact]
+    public void ParseWithActions_CodeBindingAndContext_OverrideGrammarAction()
+    {
+        // Arrange
+        var parser = CreateCalculator();
+        var spans = new List<string>();
+        var actions = CalculatorActions()
+            .Register("describe", (context, v) =>
+            {
+                spans.Add(context.Text);
+                context.State["sums"] = (int)(context.State.TryGetValue("sums", out var n) ? n! : 0) + 1;
+                return $"({v[0]} plus {v[2]})";
+            })
+            .Bind("expr", 0, "describe");
+
+        // Act
+        var result = parser.ParseWithActions("1 + 2 + 3", actions);
+
+        // Assert
+        Assert.Equal("((1 plus 2) plus 3)", result.Value);
+        Assert.Equal(new[] { "1 + 2", "1 + 2 + 3" }, spans);
+        Assert.Equal(2, result.State["sums"]);
+    }
+
+    [Fact]
+    public void ParseWithActions_UnregisteredAction_Throws()
+    {
+        // Arrange
+        var parser = CreateCalculator();
+        var actions = new ActionRegistry().Register("number", (_, v) => v[0]);
+
+        // Act & Assert
+        var exception = Assert.Throws<InvalidOperationException>(() => parser.ParseWithActions("1", actions));
+        Assert.Contains("'add'", exception.Message);
+    }
+
+    [Fact]
+    public void ParseWithActions_AmbiguousInput_EvaluatesEveryDerivation()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(AmbiguousSubtraction)));
+        var numbers = 0;
+        var speculative = new List<bool>();
+        IReadOnlyList<object?>? candidates = null;
+        var actions = new ActionRegistry
+        {
+            ResolveAmbiguity = (_, values) =>
+            {
+                candidates = values;
+                return values.Max();
+            }
+        };
+        actions
+            .Register("number", (_, v) => { numbers++; return int.Parse((string)v[0]!, CultureInfo.InvariantCulture); }, pure: true)
+            .Register("subtract", (context, v) => { speculative.Add(context.IsSpeculative); return (int)v[0]! - (int)v[2]!; });
+
+        // Act
+        var result = parser.ParseWithActions("5 - 2 - 1", actions);
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(4, result.Value);
+        Assert.Equal(new object?[] { 2, 4 }, candidates!.OrderBy(v => v));
+        Assert.Equal(3, numbers);
+        Assert.Equal(4, speculative.Count);
+        Assert.All(speculative, Assert.True);
+    }
+
+    private static GeneralizedParser CreateCalculator()
+    {
+        var path = Path.Combine(TestDirectory(), "..", "..", "..", "examples", "programming", "calculator", "calculator.grammar");
+        var grammar = new GrammarFileReader().Read(File.ReadAllText(path));
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+
+    private static ActionRegistry CalculatorActions()
+    {
+        return new ActionRegistry()
+            .Register("number", (_, v) => double.Parse((string)v[0]!, CultureInfo.InvariantCulture), pure: true)
+            .Register("add", (_, v) => (double)v[0]! + (double)v[2]!, pure: true)
+            .Register("subtract", (_, v) => (double)v[0]! - (double)v[2]!, pure: true)
+            .Register("multiply", (_, v) => (double)v[0]! * (double)v[2]!, pure: true)
+            .Register("divide", (_, v) => (double)v[0]! / (double)v[2]!, pure: true)
+            .Register("negate", (_, v) => -(double)v[1]!, pure: true)
+            .Register("group", (_, v) => v[1], pure: true);
+    }
+
+    private static string TestDirectory([CallerFilePath] string path = "")
+    {
+        return Path.GetDirectoryName(path)!;
+    }
+}
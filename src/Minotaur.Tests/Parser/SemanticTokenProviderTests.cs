@@ -0,0 +1,121 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for semantic token functionality
+/// </summary>
+public class SemanticTokenProviderTests
+{
+    private const string Source = """
+        fn first() -> i32 {
+            let a = 1;
+            a
+        }
+
+        fn second(x: i32) -> i32 {
+            let b = x;
+            b
+        }
+
+        fn third(y: i32) -> i32 {
+            let c = y + 2;
+            c
+        }
+        """;
+
+    [Fact]
+    public void GetTokens_Identifiers_TakeTheRuleHoldingThem()
+    {
+        // Arrange
+        var parse = CreateParser("DelimiterRecovery: outdent\n").Parse(Source);
+
+        // Act
+        var tokens = SemanticTokenProvider.GetTokens(parse);
+
+        // Assert
+        Assert.True(parse.IsSuccess);
+        Assert.All(tokens, t => Assert.False(t.IsLexical));
+        Assert.Equal("function", TypeAt(tokens, Source.IndexOf("second")));
+        Assert.Equal("parameter", TypeAt(tokens, Source.IndexOf("x: i32")));
+        Assert.Equal("keyword", TypeAt(tokens, Source.IndexOf("fn second")));
+        Assert.Equal("operator", TypeAt(tokens, Source.IndexOf("->")));
+    }
+
+    [Fact]
+    public void GetTokens_TypingAnUnclosedBrace_LeavesTheFollowingFunctionUnchanged()
+    {
+        // Arrange
+        var parser = CreateParser("DelimiterRecovery: outdent\n");
+        var edited = Source.Insert(Source.IndexOf("    b\n}"), "    if b > 0 {\n");
+        var before = SemanticTokenProvider.GetTokens(parser.Parse(Source));
+
+        // Act
+        var parse = parser.Parse(edited);
+        var after = SemanticTokenProvider.GetTokens(parse);
+
+        // Assert
+        Assert.NotNull(parse.Tree);
+        Assert.Equal(After(before, Source), After(after, edited));
+        Assert.True(after.Single(t => t.Location.Offset == edited.IndexOf("{\n    b")).IsLexical);
+    }
+
+    [Fact]
+    public void GetTokens_WithoutRecovery_FallsBackToLexicalTypes()
+    {
+        // Arrange
+        var edited = Source.Insert(Source.IndexOf("    b\n}"), "    if b > 0 {\n");
+        var parse = CreateParser(string.Empty).Parse(edited);
+
+        // Act
+        var tokens = SemanticTokenProvider.GetTokens(parse);
+
+        // Assert
+        Assert.Null(parse.Tree);
+        Assert.Equal(parse.Tokens.Count, tokens.Count);
+        Assert.All(tokens, t => Assert.True(t.IsLexical));
+        Assert.Equal("identifier", TypeAt(tokens, edited.IndexOf("third")));
+        Assert.Equal("keyword", TypeAt(tokens, edited.IndexOf("fn third")));
+    }
+
+    // The tokens from the third function on, with positions relative to its start.
+    private static List<(int Offset, int Length, string Type, bool IsLexical)> After(IReadOnlyList<SemanticToken> tokens, string text)
+    {
+        var start = text.IndexOf("fn third");
+        return tokens
+            .Where(t => t.Location.Offset >= start)
+            .Select(t => (t.Location.Offset - start, t.Location.Length, t.Type, t.IsLexical))
+            .ToList();
+    }
+
+    private static string TypeAt(IReadOnlyList<SemanticToken> tokens, int offset)
+    {
+        return tokens.Single(t => t.Location.Offset == offset).Type;
+    }
+
+    private static GeneralizedParser CreateParser(string metadata, [CallerFilePath] string path = "")
+    {
+        var grammar = File.ReadAllText(Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", "rust_items.grammar"));
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(metadata + grammar)));
+    }
+}
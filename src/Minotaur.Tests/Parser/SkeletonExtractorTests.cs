@@ -0,0 +1,234 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using System.Text;
+using System.Text.Json;
+using Xunit;
+using Xunit.Abstractions;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for skeleton extraction functionality
+/// </summary>
+public class SkeletonExtractorTests
+{
+    private const string MiniRustGrammar = """
+        Grammar: MiniRust
+        StartRule: file
+        Deferred: <block>
+        Outline: <module> <function> <struct> <impl>
+        <file> ::= <item> | <file> <item>
+        <item> ::= <module> | <function> | <struct> | <impl>
+        <module> ::= "mod" <IDENTIFIER> "{" <items> "}"
+        <items> ::= <item> | <items> <item>
+        <function> ::= "fn" <IDENTIFIER> "(" <params> ")" <block> | "fn" <IDENTIFIER> "(" ")" <block>
+        <params> ::= <IDENTIFIER> | <params> "," <IDENTIFIER>
+        <struct> ::= "struct" <IDENTIFIER> "{" <fields> "}"
+        <fields> ::= <field> | <fields> <field>
+        <field> ::= <IDENTIFIER> ":" <IDENTIFIER> ","
+        <impl> ::= "impl" <IDENTIFIER> "{" <functions> "}"
+        <functions> ::= <function> | <functions> <function>
+        <block> ::= "{" <statements> "}" | "{" "}"
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= "let" <IDENTIFIER> "=" <expr> ";" | <block>
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER> | "(" <expr> ")"
+        """;
+
+    private const string Source = """
+        mod geometry {
+          struct Point {
+            x: f64,
+            y: f64,
+          }
+          impl Point {
+            fn new(x, y) {
+              let p = x + y;
+            }
+            fn origin() { }
+          }
+        }
+        fn main() {
+          let a = (1 + 2);
+          { let b = a; }
+        }
+
+        """;
+
+    private readonly ITestOutputHelper _output;
+
+    public SkeletonExtractorTests(ITestOutputHelper output)
+    {
+        _output = output;
+    }
+
+    [Fact]
+    public void Extract_NestedDeclarations_ReportsQualifiedPathsAndSignatures()
+    {
+        // Act
+        var entries = SkeletonExtractor.Extract(CreateParser(), Source);
+
+        // Assert
+        Assert.Equal(
+            new[] { "module geometry", "struct geometry::Point", "impl geometry::Point", "function geometry::Point::new", "function geometry::Point::origin", "function main" },
+            entries.Select(e => $"{e.Kind} {e.GetQualifiedName()}"));
+        Assert.Equal(
+            new[] { "mod geometry", "struct Point", "impl Point", "fn new(x, y)", "fn origin()", "fn main()" },
+            entries.Select(e => e.Signature));
+        Assert.Equal(new[] { 0, 1, 1, 2, 2, 0 }, entries.Select(e => e.Depth));
+
+        var main = entries[^1];
+        Assert.Equal(Source.IndexOf("fn main", StringComparison.Ordinal), main.Location.Offset);
+        Assert.Equal(Source.TrimEnd().Length, main.Location.Offset + main.Location.Length);
+    }
+
+    [Fact]
+    public void Extract_LeavesBodiesUnparsed()
+    {
+        // Arrange
+        var parse = CreateParser().Parse(Source, new ParseOptions { Lazy = true });
+
+        // Act
+        var entries = SkeletonExtractor.Extract(parse);
+
+        // Assert
+        Assert.Equal(6, entries.Count);
+        Assert.Equal(3, parse.Deferred!.Count);
+        Assert.Equal(0, parse.Deferred.MaterializedCount);
+    }
+
+    [Fact]
+    public void Extract_GeneratedCorpus_MatchesOutlineOfEagerParse()
+    {
+        // Arrange
+        var parser = CreateParser();
+
+        foreach (var text in Enumerable.Range(1, 8).Select(GenerateFile))
+        {
+            // Act
+            var lazy = parser.Parse(text, new ParseOptions { Lazy = true });
+            var skeleton = SkeletonExtractor.Extract(lazy);
+            var eager = parser.Parse(text);
+
+            // Assert
+            Assert.True(eager.IsSuccess);
+            Assert.Equal(0, lazy.Deferred!.MaterializedCount);
+            Assert.Equal(
+                Flatten(OutlineProvider.GetOutline(eager), 0).Select(e => (e.Item.Kind, e.Item.Location.Offset, e.Item.Location.Length, e.Depth)),
+                skeleton.Select(e => (e.Kind, e.Location.Offset, e.Location.Length, e.Depth)));
+        }
+    }
+
+    [Fact]
+    public void WriteJsonLines_WritesOneObjectPerEntry()
+    {
+        // Arrange
+        var entries = SkeletonExtractor.Extract(CreateParser(), Source);
+        var writer = new StringWriter();
+
+        // Act
+        SkeletonExtractor.WriteJsonLines(writer, entries, "src/geometry.rs");
+
+        // Assert
+        var lines = writer.ToString().Split('\n', StringSplitOptions.RemoveEmptyEntries);
+        Assert.Equal(entries.Count, lines.Length);
+        using var document = JsonDocument.Parse(lines[3]);
+        var root = document.RootElement;
+        Assert.Equal("src/geometry.rs", root.GetProperty("file").GetString());
+        Assert.Equal("new", root.GetProperty("name").GetString());
+        Assert.Equal("function", root.GetProperty("kind").GetString());
+        Assert.Equal("fn new(x, y)", root.GetProperty("signature").GetString());
+        Assert.Equal(new[] { "geometry", "Point", "new" }, root.GetProperty("path").EnumerateArray().Select(e => e.GetString()!));
+        Assert.Equal(7, root.GetProperty("span").GetProperty("line").GetInt32());
+    }
+
+    [Fact]
+    public void Compile_OutlineListsTerminal_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(MiniRustGrammar.Replace("Outline: <module>", "Outline: \"mod\""));
+
+        // Act
+        var exception = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+
+        // Assert
+        Assert.Contains("not a rule", exception.Message);
+    }
+
+    [Fact]
+    public void Extract_LargeCorpus_Benchmark()
+    {
+        // Arrange
+        var text = string.Concat(Enumerable.Range(0, 200).Select(GenerateFile));
+        var parser = CreateParser();
+        SkeletonExtractor.Extract(parser, Source);
+        OutlineProvider.GetOutline(parser.Parse(Source));
+
+        // Act
+        var skeletonWatch = Stopwatch.StartNew();
+        var skeleton = SkeletonExtractor.Extract(parser, text);
+        skeletonWatch.Stop();
+        var outlineWatch = Stopwatch.StartNew();
+        var outline = OutlineProvider.GetOutline(parser.Parse(text));
+        outlineWatch.Stop();
+
+        // Assert
+        _output.WriteLine($"{text.Length} characters, {skeleton.Count} declarations");
+        _output.WriteLine($"lazy skeleton: {skeletonWatch.Elapsed.TotalMilliseconds:F1} ms");
+        _output.WriteLine($"eager parse and outline: {outlineWatch.Elapsed.TotalMilliseconds:F1} ms");
+
+        Assert.Equal(Flatten(outline, 0).Count(), skeleton.Count);
+    }
+
+    private static GeneralizedParser CreateParser()
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(MiniRustGrammar)));
+    }
+
+    private static string GenerateFile(int seed)
+    {
+        var random = new Random(seed);
+        var builder = new StringBuilder();
+        builder.Append($"mod m{seed} {{\n");
+        var structs = random.Next(1, 4);
+        for (var s = 0; s < structs; s++)
+        {
+            builder.Append($"  struct S{s} {{\n    a: i32,\n    b: i32,\n  }}\n");
+            builder.Append($"  impl S{s} {{\n");
+            var functions = random.Next(1, 5);
+            for (var f = 0; f < functions; f++)
+            {
+                builder.Append($"    fn f{f}(a, b) {{\n      let x = a + {f};\n      {{ let y = (x + b); }}\n    }}\n");
+            }
+
+            builder.Append("  }\n");
+        }
+
+        builder.Append("}\n");
+        builder.Append($"fn main{seed}() {{\n  let z = {seed};\n}}\n");
+        return builder.ToString();
+    }
+
+    private static IEnumerable<(OutlineItem Item, int Depth)> Flatten(IEnumerable<OutlineItem> items, int depth)
+    {
+        return items.SelectMany(i => Flatten(i.Children, depth + 1).Prepend((i, depth)));
+    }
+}
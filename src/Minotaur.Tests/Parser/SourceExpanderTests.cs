@@ -0,0 +1,224 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for source expansion functionality, with a mini C preprocessor over Fixtures/mini-c.
+/// </summary>
+public class SourceExpanderTests
+{
+    [Fact]
+    public void Expand_TwoLevelIncludeAndMacro_ParsesExpandedTokens()
+    {
+        // Arrange
+        var parser = CreateParser();
+        var expander = new SourceExpander(parser, new MiniCExpander(ReadFixtures()));
+
+        // Act
+        var expanded = expander.Expand("main.c", File.ReadAllText(FixturePath("main.c")));
+        var result = expanded.Parse(parser);
+
+        // Assert
+        Assert.Empty(expanded.Diagnostics);
+        Assert.True(result.IsSuccess);
+        Assert.Equal("int size = 32 + 10 + 1 ;\nint total = size + 32 ;", expanded.Text);
+        Assert.Equal(new[] { "main.c", "config.h", "limits.h" }, expanded.Files.Keys);
+
+        var origin = expanded.Origins[3];
+        Assert.Equal("32", expanded.Tokens[3].Text);
+        Assert.Equal(("limits.h", 1, 14), (origin.Position.SourceFile, origin.Position.Line, origin.Position.Column));
+        Assert.Equal(
+            new[] { "in expansion of 'BASE' at config.h:2:15", "in expansion of 'LIMIT' at main.c:3:12" },
+            origin.Backtrace.Select(s => s.ToString()));
+        Assert.Equal(3, origin.UnexpandedPosition.Line);
+    }
+
+    [Fact]
+    public void GetUnexpandedText_Declaration_ShowsMacroUses()
+    {
+        // Arrange
+        var parser = CreateParser();
+        var expanded = new SourceExpander(parser, new MiniCExpander(ReadFixtures())).Expand("main.c", File.ReadAllText(FixturePath("main.c")));
+        var result = expanded.Parse(parser);
+
+        // Act
+        var declarations = Nodes(result.Tree!).Where(n => n is NonTerminalNode { RuleName: "declaration" }).ToList();
+
+        // Assert
+        Assert.Equal(2, declarations.Count);
+        Assert.Equal("int size = LIMIT + 1;", expanded.GetUnexpandedText(declarations[0]));
+        Assert.Equal("int total = size + BASE;", expanded.GetUnexpandedText(declarations[1]));
+    }
+
+    [Fact]
+    public void Parse_ErrorInMacroBody_IsReportedInDefinitionWithBacktrace()
+    {
+        // Arrange
+        var parser = CreateParser();
+        var files = new Dictionary<string, string> { ["bad.h"] = "#define BAD + 1\n" };
+        var expanded = new SourceExpander(parser, new MiniCExpander(files)).Expand("main.c", "#include \"bad.h\"\nint x = BAD;\n");
+
+        // Act
+        var result = expanded.Parse(parser);
+
+        // Assert
+        var error = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnexpectedToken, error.Code);
+        Assert.Equal(8, ((SourcePosition)error.Data["expandedLocation"]).Offset);
+        var text = ExpandedSource.Format(error);
+        Assert.StartsWith("bad.h:1:13: error E0001: ", text);
+        Assert.EndsWith("\n  in expansion of 'BAD' at main.c:2:9", text);
+    }
+
+    [Fact]
+    public void Expand_FileIncludingItself_ReportsCycleAndGoesOn()
+    {
+        // Arrange
+        var files = new Dictionary<string, string> { ["a.h"] = "#include \"a.h\"\nint x = 1;\n" };
+        var expander = new SourceExpander(CreateParser(), new MiniCExpander(files));
+
+        // Act
+        var expanded = expander.Expand("main.c", "#include \"a.h\"\n");
+
+        // Assert
+        var error = Assert.Single(expanded.Diagnostics);
+        Assert.Equal(DiagnosticCodes.RecursiveInclude, error.Code);
+        Assert.Equal("'a.h' includes itself through a.h -> a.h", error.Message);
+        Assert.Equal("a.h:1:1: error E0019: 'a.h' includes itself through a.h -> a.h\n  included from main.c:1:1", ExpandedSource.Format(error));
+        Assert.Equal("int x = 1 ;", expanded.Text);
+    }
+
+    [Fact]
+    public void Expand_MissingInclude_ReportsUnresolvedInclude()
+    {
+        // Arrange
+        var expander = new SourceExpander(CreateParser(), new MiniCExpander(new Dictionary<string, string>()));
+
+        // Act
+        var expanded = expander.Expand("main.c", "#include \"missing.h\"\nint x = 1;\n");
+
+        // Assert
+        var error = Assert.Single(expanded.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnresolvedInclude, error.Code);
+        Assert.Equal(("main.c", 1), (error.Location!.SourceFile, error.Location.Line));
+        Assert.Equal("int x = 1 ;", expanded.Text);
+    }
+
+    [Fact]
+    public void Expand_SelfReferentialMacro_IsNotExpandedAgain()
+    {
+        // Arrange
+        var expander = new SourceExpander(CreateParser(), new MiniCExpander(new Dictionary<string, string>()));
+
+        // Act
+        var expanded = expander.Expand("main.c", "#define X X + 1\nint y = X;\n");
+
+        // Assert
+        Assert.Empty(expanded.Diagnostics);
+        Assert.Equal("int y = X + 1 ;", expanded.Text);
+    }
+
+    [Fact]
+    public void Expand_IncludesPastDepthLimit_ReportsExpansionTooDeep()
+    {
+        // Arrange
+        var files = new Dictionary<string, string>
+        {
+            ["a.h"] = "#include \"b.h\"\n",
+            ["b.h"] = "#include \"c.h\"\n",
+            ["c.h"] = "int x = 1;\n"
+        };
+        var expander = new SourceExpander(CreateParser(), new MiniCExpander(files)) { MaxIncludeDepth = 2 };
+
+        // Act
+        var expanded = expander.Expand("main.c", "#include \"a.h\"\n");
+
+        // Assert
+        var error = Assert.Single(expanded.Diagnostics);
+        Assert.Equal(DiagnosticCodes.ExpansionTooDeep, error.Code);
+        Assert.Equal("b.h", error.Location!.SourceFile);
+        Assert.Empty(expanded.Tokens);
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Nodes(CognitiveGraphNode node)
+    {
+        yield return node;
+        foreach (var descendant in node.Children.SelectMany(Nodes))
+        {
+            yield return descendant;
+        }
+    }
+
+    private static Dictionary<string, string> ReadFixtures()
+    {
+        return new[] { "config.h", "limits.h" }.ToDictionary(name => name, name => File.ReadAllText(FixturePath(name)));
+    }
+
+    private static GeneralizedParser CreateParser()
+    {
+        var grammar = new GrammarFileReader().Read(File.ReadAllText(FixturePath("minic.grammar")));
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+
+    private static string FixturePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "Fixtures", "mini-c", file);
+    }
+
+    /// <summary>
+    /// Includes files from a dictionary and substitutes object-like macros.
+    /// </summary>
+    private sealed class MiniCExpander : Expander
+    {
+        private readonly Dictionary<string, string> _files;
+        private readonly Dictionary<string, IReadOnlyList<Token>> _macros = new(StringComparer.Ordinal);
+
+        public MiniCExpander(Dictionary<string, string> files)
+        {
+            _files = files;
+        }
+
+        public override string? OnDirective(string directive, NonTerminalNode node, IReadOnlyList<Token> tokens)
+        {
+            if (directive == "include")
+            {
+                return tokens[2].Text.Trim('"');
+            }
+
+            _macros[tokens[2].Text] = tokens.Skip(3).ToList();
+            return null;
+        }
+
+        public override IncludedSource? ResolveInclude(string target, string includingFile)
+        {
+            return _files.TryGetValue(target, out var text) ? new IncludedSource(target, text) : null;
+        }
+
+        public override IReadOnlyList<Token>? Substitute(Token token)
+        {
+            return token.Kind == "IDENTIFIER" && _macros.TryGetValue(token.Text, out var body) ? body : null;
+        }
+    }
+}
@@ -0,0 +1,112 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for stall hint functionality
+/// </summary>
+public class StallHintTests
+{
+    [Fact]
+    public void Parse_AmbiguousDoubleRecursion_HintsOneSidedRecursion()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            <program> ::= <header> <list>
+            <header> ::= "begin"
+            <list> ::= <list> <list> | "a"
+            """);
+        var input = "begin" + string.Concat(Enumerable.Repeat(" a", 5000));
+
+        // Act
+        var result = parser.Parse(input, new ParseOptions
+        {
+            Watchdog = new ParseWatchdogOptions { Interval = TimeSpan.FromMilliseconds(200), MinTokensPerInterval = 2000 }
+        });
+
+        // Assert
+        var stall = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.ParseStalled, stall.Code);
+        var hint = Assert.IsType<List<GrammarHint>>(stall.Data["hints"])[0];
+        Assert.Equal(GrammarHintKind.OneSidedRecursion, hint.Kind);
+        Assert.Equal("list", hint.Rule);
+        Assert.Equal("<list> <list>", hint.Alternative);
+        Assert.True(hint.Reentries > 1);
+        Assert.Contains("<list> ::= <list> <list_operand> | <list_operand> with <list_operand> ::= \"a\"", hint.Suggestion);
+    }
+
+    [Fact]
+    public void Parse_AlternativesWithCommonPrefix_HintsLeftFactoring()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            <e> ::= <t> "+" <e> | <t> "-" <e> | <t>
+            <t> ::= "(" <e> ")" | "x"
+            """);
+        var input = string.Concat(Enumerable.Repeat("(x + ", 200)) + "x" + new string(')', 200);
+
+        // Act
+        var result = parser.Parse(input, new ParseOptions { Watchdog = TrippingWatchdog() });
+
+        // Assert
+        var hints = Assert.IsType<List<GrammarHint>>(Assert.Single(result.Diagnostics).Data["hints"]);
+        var hint = Assert.Single(hints);
+        Assert.Equal(GrammarHintKind.LeftFactor, hint.Kind);
+        Assert.Equal("e", hint.Rule);
+        Assert.Equal(new[] { "<t>" }, hint.CommonPrefix);
+        Assert.Equal("left-factor the common prefix <t>: <e> ::= <t> <e_tail> with <e_tail> ::= \"+\" <e> | \"-\" <e> | ", hint.Suggestion);
+    }
+
+    [Fact]
+    public void Parse_LeftFactorHint_ComputesTheLongestCommonPrefixAndAnUnusedName()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            <stmts> ::= <stmt> | <stmts> <stmt>
+            <stmt> ::= "let" <IDENTIFIER> "=" <IDENTIFIER> ";" | "let" <IDENTIFIER> ";" | "let" <IDENTIFIER> "=" "(" ")" ";"
+            <stmt_tail> ::= ";"
+            """);
+        var input = string.Concat(Enumerable.Repeat("let a = b; let c; ", 100));
+
+        // Act
+        var result = parser.Parse(input, new ParseOptions { Watchdog = TrippingWatchdog() });
+
+        // Assert
+        var hint = Assert.IsType<List<GrammarHint>>(Assert.Single(result.Diagnostics).Data["hints"]).Single(h => h.Rule == "stmt");
+        Assert.Equal(new[] { "\"let\"", "<IDENTIFIER>" }, hint.CommonPrefix);
+        Assert.Contains("<stmt> ::= \"let\" <IDENTIFIER> <stmt_tail2>", hint.Suggestion);
+        Assert.StartsWith("LeftFactor <stmt> ::= ", hint.ToString());
+    }
+
+    // Trips at its first check, so the hints describe wherever the parser is after a few hundred steps.
+    private static ParseWatchdogOptions TrippingWatchdog()
+    {
+        return new ParseWatchdogOptions { Interval = TimeSpan.Zero, MinTokensPerInterval = int.MaxValue };
+    }
+
+    private static GeneralizedParser CreateParser(string grammarText)
+    {
+        var grammar = new GrammarFileReader().Read(grammarText);
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+}
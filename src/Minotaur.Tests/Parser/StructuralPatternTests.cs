@@ -0,0 +1,195 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for StructuralPattern functionality
+/// </summary>
+public class StructuralPatternTests
+{
+    private const string CallsGrammar = """
+        Grammar: Calls
+        StartRule: program
+        <COMMENT> ::= /\/\/[^\n]*/
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <expr> ";"
+        <expr> ::= <call> | <IDENTIFIER> | <STRING> | <NUMBER>
+        <call> ::= <IDENTIFIER> "(" <arguments> ")" | <IDENTIFIER> "(" ")"
+        <arguments> ::= <expr> | <arguments> "," <expr>
+        """;
+
+    [Fact]
+    public void FindAll_FunctionPattern_BindsNamesAndListsIncludingEmptyOnes()
+    {
+        // Arrange
+        var (parser, parse) = ParseExample();
+        var pattern = StructuralPattern.Compile(parser.Grammar, "fn $name($$params) -> $ret { $$body }");
+
+        // Act
+        var matches = pattern.FindAll(parse);
+
+        // Assert
+        Assert.Equal("function", pattern.Rule);
+        Assert.Equal(new[] { "name", "params", "ret", "body" }, pattern.Metavariables);
+        Assert.Equal(new[] { "origin", "unused", "find" }, matches.Select(m => m.Bindings["name"].Text));
+        var origin = matches[0].Bindings["params"];
+        Assert.True(origin.IsList);
+        Assert.Empty(origin.Nodes);
+        Assert.Equal("", origin.Text);
+        Assert.Equal("limit: usize", matches[2].Bindings["params"].Text);
+        Assert.Equal("Option<Point>", matches[2].Bindings["ret"].Text);
+        var body = matches[2].Bindings["body"];
+        Assert.Equal(2, body.Nodes.Count);
+        Assert.StartsWith("let start = origin();", body.Text);
+        Assert.EndsWith("start", body.Text);
+    }
+
+    [Fact]
+    public void FindAll_RepeatedMetavariable_MatchesOnlyEqualSubtrees()
+    {
+        // Arrange
+        var (parser, _) = ParseExample();
+        var parse = parser.Parse("""
+            fn twice(x: i32) -> i32 {
+                let y = x + x;
+                x + y
+            }
+            """);
+        var pattern = StructuralPattern.Compile(parser.Grammar, "$a + $a");
+
+        // Act
+        var matches = pattern.FindAll(parse);
+
+        // Assert
+        Assert.Equal("sum", pattern.Rule);
+        var match = Assert.Single(matches);
+        Assert.Equal("x + x", match.Text);
+    }
+
+    [Fact]
+    public void FindAll_NestedMatches_AreReportedOuterFirst()
+    {
+        // Arrange
+        var (parser, parse) = ParseCalls("old(old(c, d), e);");
+        var pattern = StructuralPattern.Compile(parser.Grammar, "old($x, $y)");
+
+        // Act
+        var matches = pattern.FindAll(parse);
+
+        // Assert
+        Assert.Equal("call", pattern.Rule);
+        Assert.Equal(new[] { "old(old(c, d), e)", "old(c, d)" }, matches.Select(m => m.Text));
+        Assert.Equal("old(c, d)", matches[0].Bindings["x"].Text);
+        Assert.Equal("c", matches[1].Bindings["x"].Text);
+    }
+
+    [Fact]
+    public void FindAll_IgnoresWhitespaceAndCommentsBetweenTokens()
+    {
+        // Arrange
+        var (parser, parse) = ParseCalls("old(a, // the second argument\n    b);");
+        var pattern = StructuralPattern.Compile(parser.Grammar, "call", "old( $x ,$y )");
+
+        // Act
+        var match = Assert.Single(pattern.FindAll(parse));
+
+        // Assert
+        Assert.Equal("a", match.Bindings["x"].Text);
+        Assert.Equal("b", match.Bindings["y"].Text);
+    }
+
+    [Fact]
+    public void RewriteAll_NestedMatches_RewritesBothAndLeavesStringsAndCommentsAlone()
+    {
+        // Arrange
+        var (parser, parse) = ParseCalls("""
+            log("call old(x, y) here"); // old(x, y) stays
+            old(a, b);
+            old(old(c, d), e);
+            """);
+        var pattern = StructuralPattern.Compile(parser.Grammar, "old($x, $y)");
+
+        // Act
+        var result = pattern.RewriteAll(parser, parse, "new($y, $x)");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal("""
+            log("call old(x, y) here"); // old(x, y) stays
+            new(b, a);
+            new(e, new(d, c));
+            """, result.Text);
+    }
+
+    [Fact]
+    public void RewriteAll_BoundCode_KeepsItsCommentsAndFormatting()
+    {
+        // Arrange
+        var (parser, parse) = ParseCalls("old(f(1, // one\n  2), b);");
+        var pattern = StructuralPattern.Compile(parser.Grammar, "old($x, $y)");
+
+        // Act
+        var result = pattern.RewriteAll(parser, parse, "new($x)");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal("new(f(1, // one\n  2));", result.Text);
+    }
+
+    [Fact]
+    public void Compile_InvalidPatterns_Throw()
+    {
+        // Arrange
+        var (parser, parse) = ParseCalls("old(a, b);");
+        var grammar = parser.Grammar;
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => StructuralPattern.Compile(grammar, "call", "old($x,"));
+        Assert.Throws<ArgumentException>(() => StructuralPattern.Compile(grammar, "lambda", "old($x)"));
+        Assert.Throws<ArgumentException>(() => StructuralPattern.Compile(grammar, "$x"));
+        var pattern = StructuralPattern.Compile(grammar, "old($x, $y)");
+        Assert.Throws<ArgumentException>(() => pattern.RewriteAll(parser, parse, "new($z)"));
+    }
+
+    private static (GeneralizedParser Parser, ParseResult Result) ParseCalls(string input)
+    {
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(CallsGrammar)));
+        var result = parser.Parse(input);
+        Assert.True(result.IsSuccess, string.Join("\n", result.Diagnostics.Select(d => d.Message)));
+        return (parser, result);
+    }
+
+    private static (GeneralizedParser Parser, ParseResult Result) ParseExample()
+    {
+        var grammar = new GrammarFileReader().Read(File.ReadAllText(ExamplePath("rust_items.grammar")));
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(grammar));
+        var result = parser.Parse(File.ReadAllText(ExamplePath("input.rs")).Replace("\r\n", "\n"));
+        Assert.True(result.IsSuccess);
+        return (parser, result);
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", file);
+    }
+}
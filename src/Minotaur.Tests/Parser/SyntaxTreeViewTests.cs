@@ -0,0 +1,164 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Visitors;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for CST and AST view functionality
+/// </summary>
+public class SyntaxTreeViewTests
+{
+    [Fact]
+    public void Ast_RustExample_DropsPunctuationAndInlinesWrappers()
+    {
+        // Arrange
+        var result = ParseExample();
+
+        // Act
+        var ast = result.Ast!;
+        var cst = result.GetTree(TreeView.Cst)!;
+
+        // Assert
+        var astTokens = Descendants(ast).OfType<TerminalNode>().Select(t => t.Text).ToList();
+        var cstTokens = Descendants(cst).OfType<TerminalNode>().Select(t => t.Text).ToList();
+        Assert.DoesNotContain(",", astTokens);
+        Assert.DoesNotContain("{", astTokens);
+        Assert.DoesNotContain("}", astTokens);
+        Assert.Contains(",", cstTokens);
+        Assert.Contains("}", cstTokens);
+        Assert.Equal(result.Tokens.Count, cstTokens.Count);
+
+        var astRules = Descendants(ast).OfType<NonTerminalNode>().Select(n => n.RuleName).ToHashSet();
+        var cstRules = Descendants(cst).OfType<NonTerminalNode>().Select(n => n.RuleName).ToHashSet();
+        Assert.DoesNotContain("item_kind", astRules);
+        Assert.DoesNotContain("term", astRules);
+        Assert.Contains("item_kind", cstRules);
+        Assert.Contains("term", cstRules);
+    }
+
+    [Fact]
+    public void Ast_InlinedWrapper_PutsItsChildrenInItsPlace()
+    {
+        // Arrange
+        var result = ParseExample();
+
+        // Act
+        var structs = Descendants(result.Ast!).OfType<NonTerminalNode>().First(n => n.RuleName == "item" && n.Children.Any(c => c is NonTerminalNode { RuleName: "struct" }));
+
+        // Assert
+        Assert.Equal(new[] { "visibility", "struct" }, structs.Children.Cast<NonTerminalNode>().Select(c => c.RuleName));
+        var name = Assert.IsType<NonTerminalNode>(structs.Children[1]).Children;
+        Assert.Equal(new[] { "struct", "Point", "fields" }, name.Select(c => c is TerminalNode t ? t.Text : ((NonTerminalNode)c).RuleName));
+    }
+
+    [Fact]
+    public void Ast_EveryNode_MapsBackToItsConcreteNodeWithTheSameSpan()
+    {
+        // Arrange
+        var result = ParseExample();
+
+        // Act
+        var nodes = Descendants(result.Ast!).ToList();
+
+        // Assert
+        Assert.Same(result.Tree, SyntaxTreeView.GetCstNode(result.Ast!));
+        Assert.All(nodes, node =>
+        {
+            var concrete = SyntaxTreeView.GetCstNode(node);
+            Assert.NotNull(concrete);
+            Assert.Equal(concrete!.SourcePosition, node.SourcePosition);
+            Assert.Contains(concrete, Descendants(result.Tree!));
+        });
+    }
+
+    [Fact]
+    public void Accept_Visitor_WalksTheAstUnlessAskedForTheCst()
+    {
+        // Arrange
+        var result = ParseExample();
+        var ast = new TokenCounter();
+        var cst = new TokenCounter();
+
+        // Act
+        result.Accept(ast);
+        result.Accept(cst, TreeView.Cst);
+
+        // Assert
+        Assert.Equal(Descendants(result.Ast!).OfType<TerminalNode>().Count(), ast.Count);
+        Assert.Equal(result.Tokens.Count, cst.Count);
+        Assert.True(ast.Count < cst.Count);
+    }
+
+    [Fact]
+    public void Compile_InlinedTerminal_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("""
+            Inline: <NUMBER>
+            <sum> ::= <NUMBER> | <sum> "+" <NUMBER>
+            """);
+
+        // Act & Assert
+        var ex = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+        Assert.Contains("only rules can be inlined", ex.Message);
+    }
+
+    private static ParseResult ParseExample()
+    {
+        var grammar = new GrammarFileReader().Read(File.ReadAllText(ExamplePath("rust_items.grammar")));
+        var result = new GeneralizedParser(CompiledGrammar.Compile(grammar)).Parse(File.ReadAllText(ExamplePath("input.rs")).Replace("\r\n", "\n"));
+        Assert.True(result.IsSuccess);
+        return result;
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Descendants(CognitiveGraphNode root)
+    {
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(root);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            yield return node;
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", file);
+    }
+
+    private sealed class TokenCounter : CognitiveGraphVisitorBase
+    {
+        public int Count { get; private set; }
+
+        protected override void BeforeVisitNode(CognitiveGraphNode node)
+        {
+            Count += node is TerminalNode ? 1 : 0;
+        }
+    }
+}
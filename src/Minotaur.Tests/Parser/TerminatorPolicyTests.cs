@@ -0,0 +1,215 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for automatic terminator insertion functionality
+/// </summary>
+public class TerminatorPolicyTests
+{
+    private const string ScriptGrammar = """
+        Terminator: ";"
+        NewlineSensitive: "return" "break"
+        TerminatorBefore: "}"
+        <program> ::= <statements>
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= "return" <expr> ";" | "return" ";" | "break" ";" | <expr> ";" | "function" <IDENTIFIER> "(" ")" "{" <statements> "}"
+        <expr> ::= <IDENTIFIER> | <NUMBER> | <expr> "+" <IDENTIFIER> | <IDENTIFIER> "(" ")"
+        """;
+
+    private const string LineEndGrammar = """
+        Terminator: ";"
+        TerminatorInsertion: line-end
+        TerminatorAfter: <IDENTIFIER> <NUMBER> ")"
+        <statements> ::= <statement> ";" | <statements> <statement> ";"
+        <statement> ::= <IDENTIFIER> "=" <expr>
+        <expr> ::= <IDENTIFIER> | <NUMBER> | <expr> "+" <expr> | <IDENTIFIER> "(" <args> ")"
+        <args> ::= <expr> | <args> "," <expr>
+        """;
+
+    [Fact]
+    public void Parse_ReturnFollowedByLineBreak_EndsReturnStatement()
+    {
+        // Arrange
+        var parser = CreateParser(ScriptGrammar);
+
+        // Act
+        var result = parser.Parse("function f() {\n  return\n  value\n}\n");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(
+            """
+            (program
+              (statements
+                (statement
+                  "function"
+                  (IDENTIFIER "f")
+                  "("
+                  ")"
+                  "{"
+                  (statements
+                    (statements
+                      (statement
+                        "return"
+                        ""))
+                    (statement
+                      (expr
+                        (IDENTIFIER "value"))
+                      ""))
+                  "}")))
+
+            """.ReplaceLineEndings("\n"),
+            SExpression.Format(result.Tree!));
+    }
+
+    [Fact]
+    public void Parse_InsertedTerminator_HasZeroWidthSpanAfterPrecedingToken()
+    {
+        // Arrange
+        var parser = CreateParser(ScriptGrammar);
+
+        // Act
+        var result = parser.Parse("return\nvalue");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var inserted = result.Tokens.Where(t => t.IsSynthetic).ToList();
+        Assert.Equal(new[] { 6, 12 }, inserted.Select(t => t.Offset));
+        Assert.All(inserted, t => Assert.Equal(0, t.Length));
+        Assert.All(inserted, t => Assert.Equal("\";\"", t.Kind));
+
+        var terminal = Terminals(result.Tree!).First(t => t.TokenType == "\";\"");
+        Assert.Equal(true, terminal.Metadata[TerminatorPolicy.SyntheticMetadataKey]);
+        Assert.Equal(new SourcePosition(1, 7, 6, 0), terminal.SourcePosition);
+    }
+
+    [Fact]
+    public void Parse_NextLineContinuesStatement_InsertsNoTerminatorAtLineBreak()
+    {
+        // Arrange
+        var parser = CreateParser(ScriptGrammar);
+
+        // Act
+        var result = parser.Parse("a\n+ b\nc");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(new[] { 5, 7 }, result.Tokens.Where(t => t.IsSynthetic).Select(t => t.Offset));
+        Assert.Equal(2, Statements(result.Tree!).Count());
+    }
+
+    [Fact]
+    public void Parse_ExplicitTerminators_InsertsNothing()
+    {
+        // Arrange
+        var parser = CreateParser(ScriptGrammar);
+
+        // Act
+        var result = parser.Parse("a;\nreturn;\nbreak;");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.DoesNotContain(result.Tokens, t => t.IsSynthetic);
+    }
+
+    [Fact]
+    public void Parse_OffendingTokenOnSameLine_IsSyntaxError()
+    {
+        // Arrange
+        var parser = CreateParser(ScriptGrammar);
+
+        // Act
+        var result = parser.Parse("a b");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Contains("Unexpected IDENTIFIER 'b'", result.Diagnostics[0].Message);
+    }
+
+    [Fact]
+    public void Parse_LineEndPolicy_InsertsAfterListedTokensAtLineEnds()
+    {
+        // Arrange
+        var parser = CreateParser(LineEndGrammar);
+
+        // Act
+        var result = parser.Parse("x = f(a,\n  b)\ny = 2");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(new[] { 13, 19 }, result.Tokens.Where(t => t.IsSynthetic).Select(t => t.Offset));
+    }
+
+    [Fact]
+    public void Parse_LineEndPolicy_InsertsEvenWhenNextLineCouldContinue()
+    {
+        // Arrange
+        var parser = CreateParser(LineEndGrammar);
+
+        // Act
+        var result = parser.Parse("x = a\n+ b");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Contains("Unexpected \"+\" '+'", result.Diagnostics[0].Message);
+    }
+
+    [Fact]
+    public void FromGrammar_TerminatorNotInGrammar_Throws()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(ScriptGrammar.Replace("Terminator: \";\"", "Terminator: \".\"")));
+
+        // Act & Assert
+        var exception = Assert.Throws<ArgumentException>(() => new GeneralizedParser(grammar));
+        Assert.Contains("is not a terminal", exception.Message);
+    }
+
+    [Fact]
+    public void FromGrammar_UnknownInsertionMode_Throws()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read("TerminatorInsertion: always\n" + ScriptGrammar));
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => TerminatorPolicy.FromGrammar(grammar));
+    }
+
+    private static GeneralizedParser CreateParser(string grammar)
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(grammar)));
+    }
+
+    private static IEnumerable<TerminalNode> Terminals(CognitiveGraphNode node)
+    {
+        return node is TerminalNode terminal ? new[] { terminal } : node.Children.SelectMany(Terminals);
+    }
+
+    private static IEnumerable<NonTerminalNode> Statements(CognitiveGraphNode node)
+    {
+        var own = node is NonTerminalNode { RuleName: "statement" } statement ? new[] { statement } : Array.Empty<NonTerminalNode>();
+        return own.Concat(node.Children.SelectMany(Statements));
+    }
+}
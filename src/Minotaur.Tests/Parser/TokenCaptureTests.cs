@@ -0,0 +1,121 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for terminal capture group functionality
+/// </summary>
+public class TokenCaptureTests
+{
+    private const string NumberGrammar = """
+        <program> ::= <item> | <program> <item>
+        <item> ::= <NUMBER_LITERAL> | <DATE> | <IDENTIFIER>
+        <NUMBER_LITERAL> ::= /(?<sign>[-+])?(?<integer>[0-9]+)(?:\.(?<fraction>[0-9]+))?(?:[eE](?<exponent>[-+]?[0-9]+))?/
+        <DATE> ::= /(?<year>[0-9]{4})-(?<month>[0-9]{2})-(?<day>[0-9]{2})/
+        """;
+
+    [Fact]
+    public void Tokenize_NumericLiterals_RecordCaptureSpans()
+    {
+        // Arrange
+        const string input = "42 -3.14 6.02e23 +1E-9";
+        var parser = CreateParser();
+
+        // Act
+        var tokens = parser.Parse(input).Tokens;
+
+        // Assert
+        Assert.Equal(new[] { "42", "-3.14", "6.02e23", "+1E-9" }, tokens.Select(t => t.Text));
+        Assert.All(tokens, t => Assert.Equal(new[] { "sign", "integer", "fraction", "exponent" }, t.CaptureNames));
+
+        Assert.Null(tokens[0].Capture("sign"));
+        Assert.Equal(new TextRange(0, 2), tokens[0].Capture("integer"));
+        Assert.Null(tokens[0].Capture("fraction"));
+        Assert.Null(tokens[0].Capture("exponent"));
+
+        Assert.Equal(new TextRange(3, 1), tokens[1].Capture("sign"));
+        Assert.Equal(new TextRange(4, 1), tokens[1].Capture("integer"));
+        Assert.Equal(new TextRange(6, 2), tokens[1].Capture("fraction"));
+        Assert.Null(tokens[1].Capture("exponent"));
+
+        Assert.Equal(new TextRange(14, 2), tokens[2].Capture("exponent"));
+        Assert.Equal("02", tokens[2].CaptureText("fraction"));
+
+        Assert.Equal(new TextRange(20, 2), tokens[3].Capture("exponent"));
+        Assert.Equal("-9", tokens[3].CaptureText("exponent"));
+        Assert.Equal("+", tokens[3].CaptureText("sign"));
+    }
+
+    [Fact]
+    public void Tokenize_DateAndPlainTokens_ExposeOnlyDeclaredCaptures()
+    {
+        // Arrange
+        var parser = CreateParser();
+
+        // Act
+        var tokens = parser.Parse("due 2026-10-15").Tokens;
+
+        // Assert
+        Assert.Empty(tokens[0].CaptureNames);
+        Assert.Null(tokens[0].Capture("year"));
+        Assert.Equal(new[] { "2026", "10", "15" }, new[] { "year", "month", "day" }.Select(tokens[1].CaptureText));
+        Assert.Null(tokens[1].Capture("exponent"));
+    }
+
+    [Fact]
+    public void Capture_MovesWithTheToken()
+    {
+        // Arrange
+        var token = CreateParser().Parse("1e5").Tokens[0];
+
+        // Act
+        var moved = token with { Offset = token.Offset + 10 };
+
+        // Assert
+        Assert.Equal(new TextRange(12, 1), moved.Capture("exponent"));
+        Assert.Equal("5", moved.CaptureText("exponent"));
+    }
+
+    [Fact]
+    public void Load_ParseLog_RestoresCaptures()
+    {
+        // Arrange
+        var parser = CreateParser();
+        var live = parser.Parse("7.5e-3 2026-01-31", new ParseOptions { RecordEvents = true });
+        using var stream = new MemoryStream();
+        live.EventLog!.Save(stream);
+        stream.Position = 0;
+
+        // Act
+        var log = ParseLog.Load(stream, parser.Grammar);
+
+        // Assert
+        Assert.Equal(live.Tokens, log.Tokens);
+        Assert.Equal("-3", log.Tokens[0].CaptureText("exponent"));
+        Assert.Equal("31", log.Tokens[1].CaptureText("day"));
+    }
+
+    private static GeneralizedParser CreateParser()
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(NumberGrammar)));
+    }
+}
@@ -0,0 +1,220 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for token filter pipeline functionality
+/// </summary>
+public class TokenFilterTests
+{
+    private const string CallGrammar = """
+        TokenFilters: concatenate-strings
+        <call> ::= <IDENTIFIER> "(" <STRING> ")"
+        """;
+
+    private const string StatementGrammar = """
+        TokenFilters: concatenate-strings, terminators
+        Terminator: ";"
+        TerminatorInsertion: line-end
+        TerminatorAfter: <STRING> ")"
+        <statements> ::= <statement> ";" | <statements> <statement> ";"
+        <statement> ::= <IDENTIFIER> "(" <STRING> ")" | <IDENTIFIER> "=" <STRING>
+        """;
+
+    [Fact]
+    public void Parse_AdjacentStrings_AreMergedIntoOneSpanningToken()
+    {
+        // Arrange
+        var parser = CreateParser(CallGrammar);
+
+        // Act
+        var result = parser.Parse("puts(\"a\" \"b\"\n  \"c\")");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(4, result.Tokens.Count);
+        Assert.Equal(new Token("STRING", "\"a\" \"b\"\n  \"c\"", 5), result.Tokens[2]);
+        var literal = Terminals(result.Tree!).Single(t => t.Text.StartsWith('"'));
+        Assert.Equal(5, literal.SourcePosition!.Offset);
+        Assert.Equal(13, literal.SourcePosition.Length);
+        Assert.Equal(2, literal.SourcePosition.EndLine);
+    }
+
+    [Fact]
+    public void Filter_StringConcatenation_ReadsOnlyAsFarAsItsOutput()
+    {
+        // Arrange
+        const string input = "\"a\" \"b\" x \"c\" y";
+        var tokens = new[]
+        {
+            new Token("STRING", "\"a\"", 0),
+            new Token("STRING", "\"b\"", 4),
+            new Token("IDENTIFIER", "x", 8),
+            new Token("STRING", "\"c\"", 10),
+            new Token("IDENTIFIER", "y", 14)
+        };
+        var read = 0;
+        var context = new TokenFilterContext(input, new LineIndex(input), null, new List<Diagnostic>());
+
+        // Act
+        var first = new StringConcatenationFilter().Filter(tokens.Select(t => { read++; return t; }), context).First();
+
+        // Assert
+        Assert.Equal(new Token("STRING", "\"a\" \"b\"", 0), first);
+        Assert.Equal(3, read);
+    }
+
+    [Fact]
+    public void Parse_PipelineOrder_ConcatenatesBeforeInsertingTerminators()
+    {
+        // Arrange
+        const string input = "s = \"a\"\n    \"b\"\nputs(\"c\")\n";
+        var parser = CreateParser(StatementGrammar);
+        var reversed = CreateParser(StatementGrammar.Replace("concatenate-strings, terminators", "terminators, concatenate-strings"));
+
+        // Act
+        var result = parser.Parse(input);
+        var reversedResult = reversed.Parse(input);
+
+        // Assert
+        Assert.Equal(new[] { typeof(StringConcatenationFilter), typeof(TerminatorPolicy) }, parser.TokenFilters.Select(f => f.GetType()));
+        Assert.True(result.IsSuccess);
+        Assert.Equal(2, result.Tokens.Count(t => t.IsSynthetic));
+        Assert.False(reversedResult.IsSuccess);
+    }
+
+    [Fact]
+    public void Parse_HostRegisteredFilter_RewritesTokens()
+    {
+        // Arrange
+        var grammar = Compile("""
+            TokenFilters: alternative-tokens
+            <expr> ::= <IDENTIFIER> | <expr> "&&" <IDENTIFIER>
+            """);
+        var filters = new TokenFilterRegistry().Register("alternative-tokens", _ => new AlternativeTokenFilter());
+        var parser = new GeneralizedParser(grammar, filters);
+
+        // Act
+        var result = parser.Parse("a and b && c");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(new[] { "IDENTIFIER", "\"&&\"", "IDENTIFIER", "\"&&\"", "IDENTIFIER" }, result.Tokens.Select(t => t.Kind));
+        Assert.Equal("and", result.Tokens[1].Text);
+    }
+
+    [Fact]
+    public void CreatePipeline_WithoutDeclaration_HoldsTerminatorsWhenDeclared()
+    {
+        // Arrange
+        var registry = new TokenFilterRegistry();
+
+        // Act
+        var terminated = registry.CreatePipeline(Compile(StatementGrammar.Replace("TokenFilters: concatenate-strings, terminators\n", "")));
+        var plain = registry.CreatePipeline(Compile(CallGrammar.Replace("TokenFilters: concatenate-strings\n", "")));
+
+        // Assert
+        Assert.IsType<TerminatorPolicy>(Assert.Single(terminated));
+        Assert.Empty(plain);
+    }
+
+    [Fact]
+    public void Parse_IndentationFilter_TurnsIndentationIntoBlocks()
+    {
+        // Arrange
+        var parser = CreateParser("""
+            TokenFilters: indentation
+            <block> ::= <line> | <block> <NEWLINE> <line>
+            <line> ::= <IDENTIFIER> | <IDENTIFIER> ":" <INDENT> <block> <DEDENT> | <IDENTIFIER> "(" <IDENTIFIER> <IDENTIFIER> ")"
+            """);
+
+        // Act
+        var result = parser.Parse("if:\n  a\n  b:\n    c (d\ne)\nf");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(
+            new[] { "IDENTIFIER", "\":\"", "INDENT", "IDENTIFIER", "NEWLINE", "IDENTIFIER", "\":\"", "INDENT", "IDENTIFIER", "\"(\"", "IDENTIFIER", "IDENTIFIER", "\")\"", "DEDENT", "DEDENT", "NEWLINE", "IDENTIFIER" },
+            result.Tokens.Select(t => t.Kind));
+        Assert.All(result.Tokens.Where(t => t.IsSynthetic), t => Assert.Empty(t.Text));
+        Assert.Equal(24, result.Tokens.First(t => t.Kind == "DEDENT").Offset);
+    }
+
+    [Fact]
+    public void Filter_IndentAfter_OpensBlockAtTheNextToken()
+    {
+        // Arrange
+        const string input = "- a\n  b\n- c";
+        var tokens = new[]
+        {
+            new Token("\"-\"", "-", 0),
+            new Token("IDENTIFIER", "a", 2),
+            new Token("IDENTIFIER", "b", 6),
+            new Token("\"-\"", "-", 8),
+            new Token("IDENTIFIER", "c", 10)
+        };
+        var context = new TokenFilterContext(input, new LineIndex(input), null, new List<Diagnostic>());
+
+        // Act
+        var kinds = new OffsideRuleFilter(new[] { "\"-\"" }).Filter(tokens, context).Select(t => t.Kind).ToList();
+
+        // Assert
+        Assert.Equal(new[] { "\"-\"", "INDENT", "IDENTIFIER", "NEWLINE", "IDENTIFIER", "DEDENT", "NEWLINE", "\"-\"", "INDENT", "IDENTIFIER", "DEDENT" }, kinds);
+    }
+
+    [Theory]
+    [InlineData("TokenFilters: trigraphs")]
+    [InlineData("TokenFilters: terminators")]
+    public void CreateParser_UnavailableFilter_Throws(string header)
+    {
+        // Arrange
+        var grammar = Compile(CallGrammar.Replace("TokenFilters: concatenate-strings", header));
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => new GeneralizedParser(grammar));
+    }
+
+    private static CompiledGrammar Compile(string grammar)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(grammar));
+    }
+
+    private static GeneralizedParser CreateParser(string grammar)
+    {
+        return new GeneralizedParser(Compile(grammar));
+    }
+
+    private static IEnumerable<TerminalNode> Terminals(CognitiveGraphNode node)
+    {
+        return node is TerminalNode terminal ? new[] { terminal } : node.Children.SelectMany(Terminals);
+    }
+
+    private sealed class AlternativeTokenFilter : ITokenFilter
+    {
+        public IEnumerable<Token> Filter(IEnumerable<Token> tokens, TokenFilterContext context)
+        {
+            return tokens.Select(t => t.Kind == "IDENTIFIER" && t.Text == "and" ? t with { Kind = "\"&&\"" } : t);
+        }
+    }
+}
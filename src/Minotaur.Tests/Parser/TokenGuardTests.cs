@@ -0,0 +1,132 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for token guard functionality
+/// </summary>
+public class TokenGuardTests
+{
+    private const string ScriptGrammar = """
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <expression> ";" | <IDENTIFIER> "=" <expression> ";"
+        <expression> ::= <term> | <expression> "/" <term>
+        <term> ::= <primary> | <term> "." <IDENTIFIER> | <term> "(" <arguments> ")"
+        <arguments> ::= <expression> | ε
+        <primary> ::= <IDENTIFIER> | <NUMBER> | <REGEX> | "(" <expression> ")"
+        """;
+
+    private const string Headers = """
+        Categories: operand = <IDENTIFIER> <NUMBER> <REGEX> ")"
+        TokenGuards: <REGEX> = !operand
+
+        """;
+
+    [Fact]
+    public void Parse_SlashAfterOperand_IsDivision()
+    {
+        // Arrange
+        var parser = CreateParser(Headers);
+
+        // Act
+        var result = parser.Parse("a / b / c;");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(new[] { "IDENTIFIER", "\"/\"", "IDENTIFIER", "\"/\"", "IDENTIFIER", "\";\"" }, result.Tokens.Select(t => t.Kind));
+    }
+
+    [Fact]
+    public void Parse_SlashAfterOperator_StartsRegexLiteral()
+    {
+        // Arrange
+        var parser = CreateParser(Headers);
+
+        // Act
+        var result = parser.Parse("x = /ab+c/g.test(s);");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        var regex = Assert.Single(result.Tokens, t => t.Kind == "REGEX");
+        Assert.Equal("/ab+c/g", regex.Text);
+        Assert.Equal(new[] { "\".\"", "IDENTIFIER", "\"(\"" }, result.Tokens.SkipWhile(t => t != regex).Skip(1).Take(3).Select(t => t.Kind));
+    }
+
+    [Fact]
+    public void Parse_SlashAtStartOfInput_StartsRegexLiteral()
+    {
+        // Arrange
+        var parser = CreateParser(Headers);
+
+        // Act
+        var result = parser.Parse("/[/]x/i.test(s) / 2;");
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal("/[/]x/i", result.Tokens[0].Text);
+        Assert.Equal("\"/\"", result.Tokens[^3].Kind);
+    }
+
+    [Fact]
+    public void Parse_WithoutGuard_ReadsDivisionAsRegexLiteral()
+    {
+        // Arrange
+        var parser = CreateParser();
+
+        // Act
+        var result = parser.Parse("a / b / c;");
+
+        // Assert
+        Assert.False(result.IsSuccess);
+        Assert.Equal("/ b /", result.Tokens[1].Text);
+    }
+
+    [Fact]
+    public void ApplyEdit_RemovedOperand_RelexesDivisionAsRegexLiteral()
+    {
+        // Arrange
+        var parser = new IncrementalParser(CreateParser(Headers), "x = a / b /g;");
+
+        // Act
+        var result = parser.ApplyEdit(new TextEdit(4, 2, string.Empty));
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Equal(new[] { "IDENTIFIER", "\"=\"", "REGEX", "\";\"" }, result.Tokens.Select(t => t.Kind));
+    }
+
+    [Fact]
+    public void Compile_GuardNamingUndeclaredCategory_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("Categories: operand = <IDENTIFIER>\nTokenGuards: <REGEX> = !operands\n" + ScriptGrammar);
+
+        // Act & Assert
+        Assert.Contains("operands", Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar)).Message);
+    }
+
+    private static GeneralizedParser CreateParser(string headers = "")
+    {
+        var grammar = new GrammarFileReader().Read(headers + ScriptGrammar);
+        return new GeneralizedParser(CompiledGrammar.Compile(grammar));
+    }
+}
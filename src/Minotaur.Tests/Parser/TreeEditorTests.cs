@@ -0,0 +1,150 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for TreeEditor functionality
+/// </summary>
+public class TreeEditorTests
+{
+    [Fact]
+    public void Apply_WrapExpressionAndDeleteFunction_ProducesCleanOutput()
+    {
+        // Arrange
+        var (parser, original) = ParseExample();
+        var tail = Find(original, "block > expr", "start");
+        var unused = Find(original, "item", "fn unused");
+
+        // Act
+        var result = new TreeEditor(parser, original)
+            .ReplaceNode(tail.Id, "Some(start)")
+            .DeleteNode(unused.Id)
+            .Apply();
+
+        // Assert
+        Assert.True(result.IsSuccess, string.Join("\n", result.Parse.Diagnostics.Select(d => d.Message)));
+        Assert.Equal(2, result.Edits.Count);
+        Assert.Equal("""
+            use std::fmt;
+
+            pub const LIMIT: usize = 16;
+
+            pub struct Point {
+                x: i32,
+                y: i32,
+            }
+
+            fn origin() -> Point {
+                Point { x: 0, y: 0 }
+            }
+
+            fn find(limit: usize) -> Option<Point> {
+                let start = origin();
+                if limit > LIMIT {
+                    return None;
+                }
+                Some(start)
+            }
+
+            """, result.Text);
+    }
+
+    [Fact]
+    public void InsertChild_InfersRuleAndCopiesSeparator()
+    {
+        // Arrange
+        var (parser, original) = ParseExample();
+        var point = Find(original, "struct", "struct Point");
+        var crate = original.Tree!;
+
+        // Act
+        var result = new TreeEditor(parser, original)
+            .InsertChild(point.Id, 4, "z: i32,")
+            .InsertChild(crate.Id, crate.Children.Count, """
+                fn twice(n: i32) -> i32 {
+                    n + n
+                }
+                """)
+            .Apply();
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Contains("    y: i32,\n    z: i32,\n}", result.Text);
+        Assert.EndsWith("    start\n}\n\nfn twice(n: i32) -> i32 {\n    n + n\n}\n", result.Text);
+    }
+
+    [Fact]
+    public void ReplaceNode_FragmentOfWrongRule_Throws()
+    {
+        // Arrange
+        var (parser, original) = ParseExample();
+        var editor = new TreeEditor(parser, original);
+        var tail = Find(original, "block > expr", "start");
+
+        // Act & Assert
+        var exception = Assert.Throws<ArgumentException>(() => editor.ReplaceNode(tail.Id, "let x = 1;"));
+        Assert.Contains("<expr>", exception.Message);
+        Assert.Empty(editor.Operations);
+    }
+
+    [Fact]
+    public void Edits_Overlapping_AreRejected()
+    {
+        // Arrange
+        var (parser, original) = ParseExample();
+        var unused = Find(original, "item", "fn unused");
+        var body = Find(original, "block > expr", "p.x + p.y");
+        var fields = Find(original, "fields", "x: i32,\n    y: i32,");
+        var editor = new TreeEditor(parser, original).DeleteNode(unused.Id);
+
+        // Act & Assert
+        var exception = Assert.Throws<InvalidOperationException>(() => editor.ReplaceNode(body.Id, "p.x"));
+        Assert.Contains("delete <item> at 14:1", exception.Message);
+        Assert.Throws<InvalidOperationException>(() => editor.InsertChild(body.Id, 0, "p.x"));
+
+        editor.DeleteNode(fields.Id);
+        Assert.Throws<InvalidOperationException>(() => editor.DeleteNode(fields.Id));
+        Assert.Equal(2, editor.Operations.Count);
+    }
+
+    private static (GeneralizedParser Parser, ParseResult Result) ParseExample()
+    {
+        var grammar = new GrammarFileReader().Read(File.ReadAllText(ExamplePath("rust_items.grammar")));
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(grammar));
+        var result = parser.Parse(File.ReadAllText(ExamplePath("input.rs")).Replace("\r\n", "\n"));
+        Assert.True(result.IsSuccess);
+        return (parser, result);
+    }
+
+    private static CognitiveGraphNode Find(ParseResult result, string selector, string text)
+    {
+        return TreeQuery.Parse(selector).Select(result.Tree!).First(node =>
+            result.Input.Substring(node.SourcePosition!.Offset, node.SourcePosition.Length).StartsWith(text, StringComparison.Ordinal));
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", file);
+    }
+}
@@ -0,0 +1,221 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.GrammarGeneration.Models;
+using Minotaur.Parser;
+using Minotaur.Projects.Grammar;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for grammar migration functionality
+/// </summary>
+public class TreeMigratorTests
+{
+    private const string OldGrammar = """
+        Grammar: Lang
+        Version: 1.0
+        <program> ::= <stmts>
+        <stmts> ::= <stmt> | <stmts> <stmt>
+        <stmt> ::= <decl> | <expr_stmt> | <pragma>
+        <decl> ::= "var" <IDENTIFIER> ";" | "fn" <IDENTIFIER> ";"
+        <expr_stmt> ::= <IDENTIFIER> ";"
+        <pragma> ::= "#" <IDENTIFIER>
+        """;
+
+    private const string NewGrammar = """
+        Grammar: Lang
+        Version: 2.0
+        <program> ::= <stmts>
+        <stmts> ::= <stmt> | <stmts> <stmt>
+        <stmt> ::= <var_decl> | <func_decl> | <expression_statement>
+        <var_decl> ::= "var" <IDENTIFIER> ";"
+        <func_decl> ::= "fn" <IDENTIFIER> ";"
+        <expression_statement> ::= <IDENTIFIER> ";"
+        """;
+
+    private const string Manifest = """
+        // Lang 1.0 to 2.0
+        migration 1.0 -> 2.0
+        rename <expr_stmt> -> <expression_statement>
+        split <decl> -> <var_decl> when "var" | <func_decl> when "fn"
+        remove <pragma>
+        """;
+
+    private static readonly GrammarVersion V1 = GrammarVersion.Parse("1.0");
+    private static readonly GrammarVersion V2 = GrammarVersion.Parse("2.0");
+
+    [Fact]
+    public void Migrate_RenameAndSplit_RewritesNodeKinds()
+    {
+        // Arrange
+        var tree = Parse(OldGrammar, "var a; fn f; b;");
+        var migrator = new TreeMigrator(MigrationManifest.Parse(Manifest));
+
+        // Act
+        var result = migrator.Migrate(tree, V1, V2);
+
+        // Assert
+        Assert.True(result.IsComplete);
+        Assert.Equal(
+            new[] { ("decl", "var_decl"), ("decl", "func_decl"), ("expr_stmt", "expression_statement") },
+            result.Changes.Select(c => (c.From, c.To)));
+        Assert.Equal("var_decl", result.Changes[0].Node.Metadata["ruleName"]);
+        Assert.Contains("decl", RuleNames(tree));
+        Assert.DoesNotContain("decl", RuleNames(result.Tree));
+    }
+
+    [Fact]
+    public void Migrate_RemovedRule_FlagsNodeAndKeepsItsKind()
+    {
+        // Arrange
+        var tree = Parse(OldGrammar, "# strict a;");
+        var migrator = new TreeMigrator(MigrationManifest.Parse(Manifest));
+
+        // Act
+        var result = migrator.Migrate(tree, V1, V2);
+
+        // Assert
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.UnmappedRule, diagnostic.Code);
+        Assert.Equal(DiagnosticSeverity.Warning, diagnostic.Severity);
+        Assert.Equal(0, diagnostic.Location!.Offset);
+        Assert.Contains("pragma", RuleNames(result.Tree));
+        Assert.False(result.IsComplete);
+    }
+
+    [Fact]
+    public void Migrate_SplitWithoutConditions_FlagsAmbiguousNodes()
+    {
+        // Arrange
+        var tree = Parse(OldGrammar, "var a;");
+        var manifest = MigrationManifest.Parse("""
+            migration 1.0 -> 2.0
+            split <decl> -> <var_decl> | <func_decl>
+            """);
+
+        // Act
+        var result = new TreeMigrator(manifest).Migrate(tree, V1, V2);
+
+        // Assert
+        var diagnostic = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.AmbiguousMigration, diagnostic.Code);
+        Assert.Equal(new[] { "var_decl", "func_decl" }, (IEnumerable<string>)diagnostic.Data["candidates"]);
+        var node = NonTerminals(result.Tree).Single(n => n.RuleName == "decl");
+        Assert.Equal(new[] { "var_decl", "func_decl" }, (IEnumerable<string>)node.Metadata[TreeMigrator.MigrationMetadataKey]);
+    }
+
+    [Fact]
+    public void Migrate_SeveralSteps_AppliesThemInOrder()
+    {
+        // Arrange
+        var tree = Parse(OldGrammar, "var a; b;");
+        var manifest = MigrationManifest.Parse(Manifest + """
+
+            migration 2.0 -> 3.0
+            rename <var_decl> -> <variable_declaration>
+            """);
+
+        // Act
+        var result = new TreeMigrator(manifest).Migrate(tree, V1, GrammarVersion.Parse("3.0"));
+
+        // Assert
+        Assert.Equal(
+            new[] { ("decl", "variable_declaration"), ("expr_stmt", "expression_statement") },
+            result.Changes.Select(c => (c.From, c.To)));
+        Assert.Throws<ArgumentException>(() => new TreeMigrator(manifest).Migrate(tree, V2, V1));
+    }
+
+    [Fact]
+    public void Check_CoveringManifest_ReportsNothing()
+    {
+        // Act
+        var diagnostics = MigrationManifest.Parse(Manifest).Check(Read(OldGrammar), Read(NewGrammar));
+
+        // Assert
+        Assert.Empty(diagnostics);
+    }
+
+    [Fact]
+    public void Check_UnmappedRemoval_ReportsWarning()
+    {
+        // Arrange
+        var manifest = MigrationManifest.Parse(Manifest.Replace("remove <pragma>", ""));
+
+        // Act
+        var diagnostics = manifest.Check(Read(OldGrammar), Read(NewGrammar));
+
+        // Assert
+        var diagnostic = Assert.Single(diagnostics);
+        Assert.Equal(DiagnosticCodes.UnmappedRule, diagnostic.Code);
+        Assert.Equal(DiagnosticSeverity.Warning, diagnostic.Severity);
+        Assert.Equal("pragma", diagnostic.Data["rule"]);
+    }
+
+    [Fact]
+    public void Check_MappingToUndefinedRule_ReportsError()
+    {
+        // Arrange
+        var manifest = MigrationManifest.Parse(Manifest.Replace("-> <expression_statement>", "-> <expr_statement>"));
+
+        // Act
+        var diagnostics = manifest.Check(Read(OldGrammar), Read(NewGrammar));
+
+        // Assert
+        var diagnostic = Assert.Single(diagnostics);
+        Assert.Equal(DiagnosticCodes.InvalidMigration, diagnostic.Code);
+        Assert.Contains("<expr_statement>", diagnostic.Message);
+    }
+
+    [Theory]
+    [InlineData("rename <a> -> <b>")]
+    [InlineData("migration 1.0 -> 2.0\nrename <a>")]
+    [InlineData("migration 1.0 -> 2.0\nrename <a> -> <b>\nremove <a>")]
+    [InlineData("migration 1.0 -> 2.0\nsplit <a> -> <b> when /x/ | <c>")]
+    public void Parse_MalformedManifest_Throws(string text)
+    {
+        // Act & Assert
+        Assert.Throws<FormatException>(() => MigrationManifest.Parse(text));
+    }
+
+    private static Grammar Read(string grammar)
+    {
+        return new GrammarFileReader().Read(grammar);
+    }
+
+    private static CognitiveGraphNode Parse(string grammar, string input)
+    {
+        var result = new GeneralizedParser(CompiledGrammar.Compile(Read(grammar))).Parse(input);
+        Assert.True(result.IsSuccess);
+        return result.Tree!;
+    }
+
+    private static IEnumerable<NonTerminalNode> NonTerminals(CognitiveGraphNode node)
+    {
+        var own = node is NonTerminalNode nonTerminal ? new[] { nonTerminal } : Array.Empty<NonTerminalNode>();
+        return own.Concat(node.Children.SelectMany(NonTerminals));
+    }
+
+    private static IEnumerable<string> RuleNames(CognitiveGraphNode node)
+    {
+        return NonTerminals(node).Select(n => n.RuleName);
+    }
+}
@@ -0,0 +1,200 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for two-phase parsing functionality
+/// </summary>
+public class TwoPhaseParserTests
+{
+    // "(T)*x" casts the dereference of x to T if a typedef declares T, and multiplies (T) by x otherwise.
+    private const string MiniCGrammar = """
+        Grammar: MiniC
+        SymbolPredicates: type_name = typedef; variable = !typedef
+
+        <program> ::= <item> | <program> <item>
+        <item> ::= <typedef> | <statement>
+        <typedef> ::= "typedef" <type> <IDENTIFIER> ";"
+        <type> ::= "int" | <type_name>
+        <type_name> ::= <IDENTIFIER>
+        <statement> ::= <expr> ";"
+        <expr> ::= <expr> "*" <unary> | <expr> "-" <unary> | <unary>
+        <unary> ::= "*" <unary> | "-" <unary> | "(" <type> ")" <unary> | <primary>
+        <primary> ::= <variable> | <NUMBER> | "(" <expr> ")"
+        <variable> ::= <IDENTIFIER>
+        """;
+
+    [Fact]
+    public void Compile_SymbolPredicates_AreReadPerRule()
+    {
+        // Arrange & Act
+        var grammar = Compile(MiniCGrammar);
+
+        // Assert
+        Assert.True(grammar.HasSymbolPredicates);
+        Assert.Equal(new SymbolPredicate("typedef", false), grammar.GetRule("type_name")!.SymbolPredicate);
+        Assert.Equal(new SymbolPredicate("typedef", true), grammar.GetRule("variable")!.SymbolPredicate);
+        Assert.Null(grammar.GetRule("primary")!.SymbolPredicate);
+    }
+
+    [Fact]
+    public void Parse_TypedefName_ReadsCast()
+    {
+        // Arrange
+        var parser = new TwoPhaseParser(new GeneralizedParser(Compile(MiniCGrammar)));
+
+        // Act
+        var result = parser.Parse("typedef int T;\n(T)*x;\n");
+
+        // Assert
+        Assert.True(result.FirstPhase.IsAmbiguous);
+        Assert.True(result.Symbols.IsDeclared("T", "typedef", 0));
+        Assert.True(result.Reparsed);
+        Assert.True(result.Result.IsSuccess);
+        Assert.False(result.Result.IsAmbiguous);
+        Assert.True(IsCast(result.Result));
+    }
+
+    [Fact]
+    public void Parse_UndeclaredName_ReadsMultiplication()
+    {
+        // Arrange
+        var parser = new TwoPhaseParser(new GeneralizedParser(Compile(MiniCGrammar)));
+
+        // Act
+        var result = parser.Parse("typedef int T;\n(a)*x;\n");
+
+        // Assert
+        Assert.True(result.Result.IsSuccess);
+        Assert.False(result.Result.IsAmbiguous);
+        Assert.False(IsCast(result.Result));
+        Assert.Contains(Descendants(result.Result.Tree!).OfType<NonTerminalNode>(), n => n.RuleName == "expr" && n.ProductionIndex == 0);
+    }
+
+    [Fact]
+    public void Parse_TypedefAfterUse_FollowsVisibilityPolicy()
+    {
+        // Arrange
+        var grammar = Compile(MiniCGrammar);
+        var forward = new TwoPhaseParser(new GeneralizedParser(grammar));
+        var declaredBefore = new TwoPhaseParser(new GeneralizedParser(grammar)) { Visibility = SymbolVisibility.DeclaredBefore };
+        const string input = "(T)*x;\ntypedef int T;\n";
+
+        // Act
+        var forwardResult = forward.Parse(input);
+        var declaredBeforeResult = declaredBefore.Parse(input);
+
+        // Assert
+        Assert.True(forwardResult.Result.IsSuccess);
+        Assert.True(IsCast(forwardResult.Result));
+        Assert.True(declaredBeforeResult.Result.IsSuccess);
+        Assert.False(IsCast(declaredBeforeResult.Result));
+    }
+
+    [Fact]
+    public void Parse_HostExtractor_AddsNamesDeclaredElsewhere()
+    {
+        // Arrange
+        var parser = new TwoPhaseParser(new GeneralizedParser(Compile(MiniCGrammar)))
+        {
+            Extractor = first => TwoPhaseParser.ExtractDeclarations(first).Add("size_t", "typedef")
+        };
+
+        // Act
+        var result = parser.Parse("(size_t)-x;\n");
+
+        // Assert
+        Assert.True(result.Result.IsSuccess);
+        Assert.True(IsCast(result.Result));
+    }
+
+    [Fact]
+    public void Parse_UnambiguousInputSatisfyingPredicates_SkipsSecondPhase()
+    {
+        // Arrange
+        var parser = new TwoPhaseParser(new GeneralizedParser(Compile(MiniCGrammar)));
+
+        // Act
+        var result = parser.Parse("typedef int T;\nx * y;\n");
+
+        // Assert
+        Assert.False(result.Reparsed);
+        Assert.Same(result.FirstPhase, result.Result);
+        Assert.True(result.Result.IsSuccess);
+    }
+
+    [Fact]
+    public void Parse_TypedefNameAsVariable_FailsInSecondPhase()
+    {
+        // Arrange
+        var parser = new TwoPhaseParser(new GeneralizedParser(Compile(MiniCGrammar)));
+
+        // Act
+        var result = parser.Parse("typedef int T;\nT * 2;\n");
+
+        // Assert
+        Assert.True(result.FirstPhase.IsSuccess);
+        Assert.True(result.Reparsed);
+        Assert.False(result.Result.IsSuccess);
+    }
+
+    [Fact]
+    public void Compile_PredicateOnRuleWithSeveralSymbols_Throws()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(MiniCGrammar.Replace("variable = !typedef", "primary = !typedef"));
+
+        // Act & Assert
+        var exception = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(grammar));
+        Assert.Contains("is not a single terminal", exception.Message);
+    }
+
+    [Fact]
+    public void Constructor_GrammarWithoutPredicates_Throws()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(Compile(MiniCGrammar.Replace("SymbolPredicates: type_name = typedef; variable = !typedef", string.Empty)));
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => new TwoPhaseParser(parser));
+    }
+
+    private static bool IsCast(ParseResult result)
+    {
+        return Descendants(result.Tree!).OfType<NonTerminalNode>().Any(n => n.RuleName == "unary" && n.ProductionIndex == 2);
+    }
+
+    private static CompiledGrammar Compile(string text)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(text));
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Descendants(CognitiveGraphNode node)
+    {
+        yield return node;
+        foreach (var descendant in node.Children.SelectMany(Descendants))
+        {
+            yield return descendant;
+        }
+    }
+}
@@ -0,0 +1,139 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for UniqueKeyConstraint functionality
+/// </summary>
+public class UniqueKeyConstraintTests
+{
+    private const string RecordGrammar = """
+        Grammar: Record
+        StartRule: record
+        <record> ::= "{" <fields> "}"
+        // @unique_by(<field>, <KEY>ARGUMENTS)
+        <fields> ::= <field> | <fields> "," <field>
+        <field> ::= <KEY> ":" <value>
+        <value> ::= <NUMBER> | <record>
+        <KEY> ::= /[\p{L}\p{M}]+/
+        """;
+
+    [Fact]
+    public void Parse_NonAdjacentDuplicate_PointsAtBothKeysWithARemovalFix()
+    {
+        // Arrange
+        var parser = CreateParser(string.Empty);
+        const string input = "{a: 1, b: 2, a: 3}";
+
+        // Act
+        var result = parser.Parse(input);
+
+        // Assert
+        var error = Assert.Single(result.Diagnostics);
+        Assert.Equal(DiagnosticCodes.DuplicateKey, error.Code);
+        Assert.Equal(DiagnosticSeverity.Error, error.Severity);
+        Assert.Equal(13, error.Location!.Offset);
+        Assert.Equal(1, Assert.IsType<SourcePosition>(error.Data["related"]).Offset);
+        Assert.Equal("Duplicate key a; a is already defined at line 1, column 2", error.Message);
+        Assert.Equal("{a: 1, b: 2}", QuickFix.Apply(input, result.Diagnostics).Text);
+    }
+
+    [Fact]
+    public void Parse_NestedContainers_CheckTheirOwnMembersOnly()
+    {
+        // Arrange
+        var parser = CreateParser(string.Empty);
+
+        // Act
+        var result = parser.Parse("{a: {a: 1}, b: {a: 2, b: 3, a: 4}}");
+
+        // Assert
+        var error = Assert.Single(result.Diagnostics);
+        Assert.Equal(28, error.Location!.Offset);
+        Assert.Equal(16, Assert.IsType<SourcePosition>(error.Data["related"]).Offset);
+    }
+
+    [Theory]
+    [InlineData("", "Key", "kEY", false)]
+    [InlineData(", ignore_case", "Key", "kEY", true)]
+    [InlineData("", "caf\u00e9", "cafe\u0301", false)]
+    [InlineData(", normalize", "caf\u00e9", "cafe\u0301", true)]
+    [InlineData(", normalize", "CAF\u00c9", "cafe\u0301", false)]
+    [InlineData(", ignore_case, normalize", "CAF\u00c9", "cafe\u0301", true)]
+    [InlineData(", normalize", "\ufb01le", "file", false)]
+    [InlineData(", normalize = \"NFKC\"", "\ufb01le", "file", true)]
+    public void Parse_KeysDifferingInSpelling_CollideAsNormalizationRequires(string arguments, string first, string second, bool collide)
+    {
+        // Arrange
+        var parser = CreateParser(arguments);
+
+        // Act
+        var result = parser.Parse($"{{{first}: 1, {second}: 2}}");
+
+        // Assert
+        Assert.Equal(collide ? 1 : 0, result.Diagnostics.Count(d => d.Code == DiagnosticCodes.DuplicateKey));
+    }
+
+    [Fact]
+    public void Parse_ConstraintBehindFeature_ChecksOnlyWhenEnabled()
+    {
+        // Arrange
+        var parser = CreateParser(", feature = \"strict\"");
+        const string input = "{a: 1, a: 2}";
+
+        // Act
+        var lenient = parser.Parse(input);
+        var strict = parser.Parse(input, new ParseOptions { Features = new[] { "strict" } });
+
+        // Assert
+        Assert.True(lenient.IsSuccess);
+        Assert.False(strict.IsSuccess);
+        Assert.Equal(DiagnosticCodes.DuplicateKey, Assert.Single(strict.Diagnostics).Code);
+    }
+
+    [Theory]
+    [InlineData(", sorted")]
+    [InlineData(", normalize = \"NFX\"")]
+    public void Compile_MalformedAnnotation_Throws(string arguments)
+    {
+        // Act & Assert
+        var ex = Assert.Throws<ArgumentException>(() => CreateParser(arguments));
+        Assert.Contains("Unique key annotation on line 4", ex.Message);
+    }
+
+    [Fact]
+    public void Compile_KeyMissingFromMember_Throws()
+    {
+        // Arrange
+        var grammar = RecordGrammar.Replace("<KEY>ARGUMENTS", "<NUMBER>");
+
+        // Act & Assert
+        var ex = Assert.Throws<ArgumentException>(() => CompiledGrammar.Compile(new GrammarFileReader().Read(grammar)));
+        Assert.Contains("which alternative 1 of <field> does not contain", ex.Message);
+    }
+
+    private static GeneralizedParser CreateParser(string arguments)
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(RecordGrammar.Replace("ARGUMENTS", arguments))));
+    }
+}
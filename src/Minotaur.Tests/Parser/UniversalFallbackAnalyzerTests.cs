@@ -0,0 +1,170 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Tests.Parser;
+
+/// <summary>
+/// Tests for UniversalFallbackAnalyzer functionality
+/// </summary>
+public class UniversalFallbackAnalyzerTests
+{
+    private const string UnknownLanguage = """
+        # Build script in an unknown language
+        task build {
+          deps = [compile, "lint {strict}"]
+          run(
+            "make all"
+          )
+        }
+
+        /* Release
+           steps */
+        task release
+        {
+          publish()
+        }
+        """;
+
+    [Fact]
+    public void GetOutline_UnknownLanguage_ListsMultiLineRegionsByTheirHeaders()
+    {
+        // Arrange
+        var parse = new UniversalFallbackAnalyzer().Analyze(UnknownLanguage);
+
+        // Act
+        var outline = OutlineProvider.GetOutline(parse);
+
+        // Assert
+        Assert.Equal(new[] { "task build", "task release" }, outline.Select(i => i.Name));
+        Assert.All(outline, i => Assert.Equal(UniversalFallbackAnalyzer.RegionRule, i.Kind));
+        Assert.Equal("run", Assert.Single(outline[0].Children).Name);
+        Assert.Empty(outline[1].Children);
+        Assert.Equal(12, outline[1].Location.Line);
+    }
+
+    [Fact]
+    public void GetFoldingRanges_UnknownLanguage_FoldsRegionsAndBlockComments()
+    {
+        // Arrange
+        var parse = new UniversalFallbackAnalyzer().Analyze(UnknownLanguage);
+
+        // Act
+        var ranges = FoldingProvider.GetFoldingRanges(parse);
+
+        // Assert
+        Assert.Equal(new[]
+        {
+            new FoldingRange(2, 7, FoldingProvider.RegionKind),
+            new FoldingRange(4, 6, FoldingProvider.RegionKind),
+            new FoldingRange(9, 10, FoldingProvider.CommentKind),
+            new FoldingRange(12, 14, FoldingProvider.RegionKind)
+        }, ranges);
+    }
+
+    [Fact]
+    public void Analyze_BracketsInStringsAndComments_AreNotRegions()
+    {
+        // Act
+        var parse = new UniversalFallbackAnalyzer().Analyze(UnknownLanguage);
+
+        // Assert
+        Assert.True(parse.IsSuccess);
+        Assert.Empty(parse.Diagnostics);
+        var offset = UnknownLanguage.IndexOf("strict", StringComparison.Ordinal);
+        var node = parse.Tree!.FindNodeAt(new SourcePosition(0, 0, offset, 1));
+        var terminal = Assert.IsType<TerminalNode>(node);
+        Assert.Equal(UniversalFallbackAnalyzer.StringKind, terminal.TokenType);
+        Assert.Equal("\"lint {strict}\"", terminal.Text);
+        var list = Assert.IsType<NonTerminalNode>(terminal.Parent);
+        Assert.Equal(1, list.ProductionIndex);
+        Assert.Equal(3, list.SourcePosition!.Line);
+    }
+
+    [Fact]
+    public void Analyze_UnbalancedBrackets_WarnsAndClosesOpenRegions()
+    {
+        // Act
+        var parse = new UniversalFallbackAnalyzer().Analyze("a ) { b ( c }\nd [");
+
+        // Assert
+        Assert.Equal(3, parse.Diagnostics.Count);
+        Assert.All(parse.Diagnostics, d => Assert.Equal(DiagnosticCodes.UnbalancedBracket, d.Code));
+        Assert.Equal("')' closes nothing", parse.Diagnostics[0].Message);
+        Assert.Equal("'(' is never closed", parse.Diagnostics[1].Message);
+        Assert.Equal("'[' is never closed", parse.Diagnostics[2].Message);
+
+        var braces = Assert.IsType<NonTerminalNode>(parse.Tree!.Children[2]);
+        Assert.Equal(new TextRange(4, 9), new TextRange(braces.SourcePosition!.Offset, braces.SourcePosition.Length));
+        var parens = Assert.IsType<NonTerminalNode>(braces.Children[2]);
+        Assert.Equal("( c", UnknownText(parse, parens));
+        Assert.Equal("[", UnknownText(parse, parse.Tree.Children[4]));
+    }
+
+    [Fact]
+    public void Analyze_CustomSyntax_UsesTheConfiguredCommentsAndQuotes()
+    {
+        // Arrange
+        var analyzer = new UniversalFallbackAnalyzer(new GenericLexerOptions
+        {
+            LineComments = { ";" },
+            StringQuotes = { '|' },
+            Brackets = new List<(string, string)> { ("begin", "end") }
+        });
+
+        // Act
+        var tokens = analyzer.Tokenize("begin x ; note (\n|a b| end");
+
+        // Assert
+        Assert.Equal(
+            new[] { "\"begin\"", "TEXT", "COMMENT", "STRING", "\"end\"" },
+            tokens.Select(t => t.Kind));
+        Assert.Equal("; note (", tokens[2].Text);
+    }
+
+    [Fact]
+    public void GetOutline_GrammarParse_ListsConstructsWithoutListRules()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read("""
+            <program> ::= <function> | <program> <function>
+            <function> ::= "fn" <IDENTIFIER> "{" <statements> "}"
+            <statements> ::= <statement> | <statements> <statement>
+            <statement> ::= <IDENTIFIER> "=" <NUMBER> ";"
+            """)));
+        var parse = parser.Parse("fn a {\n  x = 1;\n}\nfn b { y = 2; }\nfn c {\n  z = 3;\n}\n");
+
+        // Act
+        var outline = OutlineProvider.GetOutline(parse);
+        var ranges = FoldingProvider.GetFoldingRanges(parse);
+
+        // Assert
+        Assert.Equal(new[] { "fn a", "fn c" }, outline.Select(i => i.Name));
+        Assert.All(outline, i => Assert.Equal("function", i.Kind));
+        Assert.Equal(new[] { (1, 3), (5, 7) }, ranges.Select(r => (r.StartLine, r.EndLine)));
+    }
+
+    private static string UnknownText(ParseResult parse, CognitiveGraphNode node)
+    {
+        return parse.Input.Substring(node.SourcePosition!.Offset, node.SourcePosition.Length);
+    }
+}
@@ -0,0 +1,209 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Api;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Projects.Grammar;
+using Xunit;
+
+namespace Minotaur.Tests.Projects.Grammar;
+
+/// <summary>
+/// Tests for speculative grammar race functionality
+/// </summary>
+public sealed class GrammarRaceTests : IDisposable
+{
+    // The two grammars lex alike and neither header matches a content rule, so nothing short of parsing tells the
+    // headers apart; access specifiers are what C cannot parse.
+    private const string CGrammar = """
+        Grammar: C17
+        <header> ::= <declaration> | <header> <declaration>
+        <declaration> ::= "struct" <IDENTIFIER> "{" <fields> "}" ";" | <type> <IDENTIFIER> "(" ")" ";"
+        <fields> ::= <field> | <fields> <field>
+        <field> ::= <type> <IDENTIFIER> ";"
+        <type> ::= "int" | "char" | "struct" <IDENTIFIER>
+        """;
+
+    private const string CppGrammar = """
+        Grammar: Cpp20
+        <header> ::= <declaration> | <header> <declaration>
+        <declaration> ::= "struct" <IDENTIFIER> "{" <members> "}" ";" | <type> <IDENTIFIER> "(" ")" ";"
+        <field> ::= <type> <IDENTIFIER> ";"
+        <members> ::= <member> | <members> <member>
+        <member> ::= <field> | <access> ":"
+        <access> ::= "public" | "private"
+        <type> ::= "int" | "char" | "struct" <IDENTIFIER>
+        """;
+
+    private const string CHeader = "struct point { int x; int y; };\nint origin();\n";
+
+    private const string CppHeader = "struct widget {\npublic:\n    int width;\nprivate:\n    char tag;\n};\n";
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"race_{Guid.NewGuid():N}");
+
+    private readonly Dictionary<string, CompiledGrammar> _grammars = new()
+    {
+        ["C17.grammar"] = CompiledGrammar.Compile(new GrammarFileReader().Read(CGrammar)),
+        ["Cpp20.grammar"] = CompiledGrammar.Compile(new GrammarFileReader().Read(CppGrammar))
+    };
+
+    public GrammarRaceTests()
+    {
+        Directory.CreateDirectory(_directory);
+    }
+
+    public void Dispose()
+    {
+        if (Directory.Exists(_directory))
+        {
+            Directory.Delete(_directory, recursive: true);
+        }
+    }
+
+    [Fact]
+    public async Task ResolveOrRaceAsync_CppHeader_ParseSuccessOverridesExtension()
+    {
+        // Arrange
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var trace = await manager.ResolveOrRaceAsync(Path.Combine(_directory, "widget.h"), CppHeader, _directory, _grammars.GetValueOrDefault);
+
+        // Assert
+        Assert.Equal("C17.grammar", Assert.Single(trace.Steps, s => s.DetectorId == "file-extension").GrammarName);
+        Assert.Equal("Cpp20.grammar", trace.Result.GrammarName);
+        Assert.Equal("speculative-parse", trace.WinningDetectorId);
+        Assert.Contains("inconclusive", trace.Reason);
+
+        var race = Assert.IsType<GrammarRaceResult>(trace.Race);
+        Assert.Equal(CppHeader.Length, race.PrefixLength);
+        Assert.Equal(new[] { "C17.grammar", "Cpp20.grammar" }, race.Scores.Select(s => s.GrammarName));
+        Assert.True(race.Scores[1].IsClean);
+        Assert.Equal(RaceOutcome.Completed, race.Scores[0].Outcome);
+        Assert.True(race.Scores[0].ErrorCount > 0);
+        Assert.True(race.Scores[0].Score < race.Scores[1].Score);
+        Assert.Contains("2. Cpp20.grammar: Completed <- chosen", trace.ToNarrative());
+        Assert.Contains("\"race\"", trace.ToJson());
+    }
+
+    [Fact]
+    public async Task ResolveOrRaceAsync_HeaderBothParse_KeepsDetectedGrammar()
+    {
+        // Arrange
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var trace = await manager.ResolveOrRaceAsync(Path.Combine(_directory, "point.h"), CHeader, _directory, _grammars.GetValueOrDefault);
+
+        // Assert
+        Assert.Equal("C17.grammar", trace.Result.GrammarName);
+        Assert.True(trace.Race!.Scores[0].IsClean);
+        Assert.Equal(new[] { "Cpp20.grammar" }, trace.Result.FallbackGrammars);
+    }
+
+    [Fact]
+    public async Task ResolveOrRaceAsync_SameContentTwice_ReusesCachedDecision()
+    {
+        // Arrange
+        using var manager = GrammarDetectionManager.CreateDefault();
+        var path = Path.Combine(_directory, "widget.h");
+
+        // Act
+        var first = await manager.ResolveOrRaceAsync(path, CppHeader, _directory, _grammars.GetValueOrDefault);
+        var second = await manager.ResolveOrRaceAsync(path, CppHeader, _directory, _grammars.GetValueOrDefault);
+        var edited = await manager.ResolveOrRaceAsync(path, CHeader, _directory, _grammars.GetValueOrDefault);
+
+        // Assert
+        Assert.False(first.Race!.FromCache);
+        Assert.True(second.Race!.FromCache);
+        Assert.Equal("Cpp20.grammar", second.Result.GrammarName);
+        Assert.Contains("cached", second.Reason);
+        Assert.False(edited.Race!.FromCache);
+        Assert.Equal("C17.grammar", edited.Result.GrammarName);
+        Assert.Equal(1, (int)manager.GetStatistics()["decisionsCached"]);
+    }
+
+    [Fact]
+    public async Task ResolveOrRaceAsync_DecisiveDetection_DoesNotParse()
+    {
+        // Arrange
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var trace = await manager.ResolveOrRaceAsync(Path.Combine(_directory, "point.c"), CHeader, _directory, _grammars.GetValueOrDefault);
+
+        // Assert
+        Assert.Null(trace.Race);
+        Assert.Equal("C17.grammar", trace.Result.GrammarName);
+        Assert.Equal("file-extension", trace.WinningDetectorId);
+    }
+
+    [Fact]
+    public async Task ResolveOrRaceAsync_Cancelled_StopsTheParses()
+    {
+        // Arrange
+        using var manager = GrammarDetectionManager.CreateDefault();
+        using var cancellation = new CancellationTokenSource();
+        cancellation.Cancel();
+
+        // Act & Assert
+        await Assert.ThrowsAnyAsync<OperationCanceledException>(() =>
+            manager.ResolveOrRaceAsync(Path.Combine(_directory, "widget.h"), CppHeader, _directory, _grammars.GetValueOrDefault, cancellationToken: cancellation.Token));
+    }
+
+    [Fact]
+    public void Parse_CancelledToken_ThrowsBeforeRecognizing()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(_grammars["C17.grammar"]);
+        var options = new ParseOptions { CancellationToken = new CancellationToken(canceled: true) };
+
+        // Act & Assert
+        Assert.Throws<OperationCanceledException>(() => parser.Parse(CHeader, options));
+    }
+
+    [Fact]
+    public async Task ResolveOrRaceAsync_LongText_ParsesPrefixUpToLastLineBreak()
+    {
+        // Arrange
+        using var manager = GrammarDetectionManager.CreateDefault();
+        var options = new GrammarRaceOptions { PrefixLength = CHeader.Length + 5 };
+
+        // Act
+        var trace = await manager.ResolveOrRaceAsync(Path.Combine(_directory, "point.h"), CHeader + CHeader, _directory, _grammars.GetValueOrDefault, options);
+
+        // Assert
+        Assert.Equal(CHeader.Length, trace.Race!.PrefixLength);
+        Assert.Equal(1.0, trace.Race.Scores[0].Coverage);
+    }
+
+    [Fact]
+    public async Task GrammarCatalog_ResolveOrRaceAsync_ReturnsWinningGrammar()
+    {
+        // Arrange
+        File.WriteAllText(Path.Combine(_directory, "C17.grammar"), CGrammar);
+        File.WriteAllText(Path.Combine(_directory, "Cpp20.grammar"), CppGrammar);
+        var catalog = await GrammarCatalog.LoadDirectoryAsync(_directory);
+
+        // Act
+        var grammar = await catalog.ResolveOrRaceAsync(Path.Combine(_directory, "widget.h"), CppHeader, _directory);
+
+        // Assert
+        Assert.Equal("Cpp20", grammar?.Name);
+    }
+}
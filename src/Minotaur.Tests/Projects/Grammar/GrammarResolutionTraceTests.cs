@@ -0,0 +1,329 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.Json;
+using Minotaur.Parser;
+using Minotaur.Projects;
+using Minotaur.Projects.Grammar;
+using Minotaur.Projects.Grammar.Detectors;
+using Xunit;
+
+namespace Minotaur.Tests.Projects.Grammar;
+
+/// <summary>
+/// Tests for explainable grammar resolution functionality
+/// </summary>
+public sealed class GrammarResolutionTraceTests : IDisposable
+{
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"resolution_{Guid.NewGuid():N}");
+
+    public GrammarResolutionTraceTests()
+    {
+        Directory.CreateDirectory(_directory);
+    }
+
+    public void Dispose()
+    {
+        if (Directory.Exists(_directory))
+        {
+            Directory.Delete(_directory, recursive: true);
+        }
+    }
+
+    [Fact]
+    public async Task ExplainAsync_BuiltInContentRule_WinsOnHigherConfidence()
+    {
+        // Arrange
+        var file = WriteFile("Program.cs", "namespace Demo;\n");
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var trace = await manager.ExplainAsync(file, _directory);
+
+        // Assert
+        Assert.True(trace.Result.IsSuccessful);
+        Assert.Equal("CSharp10.grammar", trace.Result.GrammarName);
+        Assert.Equal("content-based", trace.WinningDetectorId);
+        Assert.Null(trace.ConfigurationPath);
+        Assert.Contains("highest confidence", trace.Reason);
+        Assert.Contains("file-extension", trace.Reason);
+
+        Assert.Equal(new[] { "content-based", "file-extension" }, trace.Steps.Select(s => s.DetectorId));
+        Assert.All(trace.Steps, step => Assert.Equal(DetectorOutcome.Matched, step.Outcome));
+
+        var evidence = Assert.Single(trace.Winner!.Evidence, e => e.Detail.Contains("matched line"));
+        Assert.Equal("built-in content rules", evidence.Source);
+        Assert.Contains("csharp-namespace", evidence.Detail);
+        Assert.Contains("line 1: namespace Demo;", evidence.Detail);
+    }
+
+    [Fact]
+    public async Task ExplainAsync_ConfiguredContentRule_NamesConfigurationEntry()
+    {
+        // Arrange
+        var configPath = WriteFile("minotaur.grammar.json", """
+            {
+              "contentRules": [
+                { "name": "plain", "pattern": "^#!plain", "mapping": { "grammar": "Plain.grammar", "confidence": 0.4 } },
+                { "name": "mytool-shebang", "pattern": "^#!.*mytool", "priority": 50,
+                  "mapping": { "grammar": "MyTool.grammar", "version": "2.1", "confidence": 0.99 } }
+              ]
+            }
+            """);
+        var file = WriteFile("build.tool", "#!/usr/bin/env mytool\nrun all\n");
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var trace = await manager.ExplainAsync(file, _directory);
+
+        // Assert
+        Assert.Equal("MyTool.grammar", trace.Result.GrammarName);
+        Assert.Equal("2.1", trace.Result.Version?.OriginalString);
+        Assert.Equal("content-based", trace.WinningDetectorId);
+        Assert.Equal(configPath, trace.ConfigurationPath);
+        Assert.Contains("only detector", trace.Reason);
+
+        var extensionStep = Assert.Single(trace.Steps, s => s.DetectorId == "file-extension");
+        Assert.Equal(DetectorOutcome.NoMatch, extensionStep.Outcome);
+        Assert.Contains(extensionStep.Evidence, e => e.Source == "built-in extension mappings" && e.Detail.Contains("'.tool'"));
+
+        var evidence = trace.Winner!.Evidence[0];
+        Assert.Equal($"{configPath}: contentRules[1]", evidence.Source);
+        Assert.Contains("mytool-shebang", evidence.Detail);
+        Assert.Contains("line 1", evidence.Detail);
+    }
+
+    [Fact]
+    public async Task ExplainAsync_BuiltInExtensionMapping_WinsWhenContentDoesNotMatch()
+    {
+        // Arrange
+        var file = WriteFile("report.sql", "SELECT 1;\n");
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var trace = await manager.ExplainAsync(file, _directory);
+
+        // Assert
+        Assert.Equal("SQL.grammar", trace.Result.GrammarName);
+        Assert.Equal("file-extension", trace.WinningDetectorId);
+
+        var contentStep = trace.Steps[0];
+        Assert.Equal("content-based", contentStep.DetectorId);
+        Assert.Equal(DetectorOutcome.NoMatch, contentStep.Outcome);
+        Assert.Contains("None of", Assert.Single(contentStep.Evidence).Detail);
+
+        var winner = trace.Winner!;
+        Assert.Equal(DetectorOutcome.Matched, winner.Outcome);
+        Assert.Contains(winner.Evidence, e => e.Source == "built-in extension mappings" && e.Detail.Contains("'.sql' maps to SQL.grammar"));
+    }
+
+    [Fact]
+    public async Task ExplainAsync_ConfiguredExtensionMapping_OverridesContentRule()
+    {
+        // Arrange
+        var configPath = WriteFile("minotaur.grammar.json", """
+            {
+              "extensionMappings": {
+                ".cs": { "grammar": "CSharp12.grammar", "version": "12.0", "confidence": 1.0 }
+              }
+            }
+            """);
+        var file = WriteFile("Program.cs", "namespace Demo;\n");
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var trace = await manager.ExplainAsync(file, _directory);
+
+        // Assert
+        Assert.Equal("CSharp12.grammar", trace.Result.GrammarName);
+        Assert.Equal("12.0", trace.Result.Version?.OriginalString);
+        Assert.Equal("file-extension", trace.WinningDetectorId);
+        Assert.Contains("content-based", trace.Reason);
+
+        var evidence = Assert.Single(trace.Winner!.Evidence);
+        Assert.Equal($"{configPath}: extensionMappings[\".cs\"]", evidence.Source);
+        Assert.Contains("'.cs' maps to CSharp12.grammar 12.0", evidence.Detail);
+    }
+
+    [Fact]
+    public async Task ExplainAsync_EqualConfidence_WinsOnDetectorPriority()
+    {
+        // Arrange
+        var file = WriteFile("notes.cs", "x\n");
+        var detector = new CompositeGrammarDetector(new IGrammarDetector[]
+        {
+            new FileExtensionGrammarDetector(),
+            new ContentBasedGrammarDetector(),
+            new FixedGrammarDetector()
+        });
+
+        // Act
+        var trace = await detector.ExplainAsync(GrammarDetectionContext.Create(file, _directory));
+
+        // Assert
+        Assert.Equal("Notes.grammar", trace.Result.GrammarName);
+        Assert.Equal("fixed", trace.WinningDetectorId);
+        Assert.Contains("tied", trace.Reason);
+        Assert.Contains("file-extension (priority 100)", trace.Reason);
+        Assert.Equal(new[] { "content-based", "fixed", "file-extension" }, trace.Steps.Select(s => s.DetectorId));
+        Assert.Equal(new DetectionEvidence("test", "always notes"), Assert.Single(trace.Winner!.Evidence));
+    }
+
+    [Fact]
+    public async Task ExplainAsync_BelowMinimumConfidence_IsNotResolved()
+    {
+        // Arrange
+        WriteFile("minotaur.grammar.json", """
+            { "extensionMappings": { ".txt": { "grammar": "Text.grammar", "confidence": 0.3 } } }
+            """);
+        var file = WriteFile("readme.txt", "hello\n");
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var trace = await manager.ExplainAsync(file, _directory);
+
+        // Assert
+        Assert.False(trace.Result.IsSuccessful);
+        Assert.Null(trace.WinningDetectorId);
+        Assert.Null(trace.Winner);
+        Assert.Contains("minimum confidence", trace.Reason);
+        Assert.Equal(DetectorOutcome.BelowThreshold, trace.Steps.Single(s => s.DetectorId == "file-extension").Outcome);
+        Assert.StartsWith("Not resolved:", trace.ToNarrative().TrimEnd().Split('\n')[^1]);
+    }
+
+    [Fact]
+    public async Task ExplainAsync_UnknownLanguage_FallsBackToTheUniversalAnalyzer()
+    {
+        // Arrange
+        var file = WriteFile("build.zzq", "task build {\n  run()\n}\n");
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var trace = await manager.ExplainAsync(file, _directory);
+
+        // Assert
+        Assert.False(trace.Result.IsSuccessful);
+        Assert.True(trace.Result.IsFallback);
+        Assert.Equal(UniversalFallbackAnalyzer.Name, trace.Result.GrammarName);
+        Assert.Null(trace.WinningDetectorId);
+        Assert.EndsWith("falling back to universal-fallback", trace.ToNarrative().TrimEnd());
+        using var document = JsonDocument.Parse(trace.ToJson());
+        Assert.True(document.RootElement.GetProperty("fallback").GetBoolean());
+    }
+
+    [Fact]
+    public async Task DetectGrammarAsync_AnyGrammarMatched_IsNeverAFallback()
+    {
+        // Arrange
+        WriteFile("minotaur.grammar.json", """
+            { "extensionMappings": { ".txt": { "grammar": "Text.grammar", "confidence": 0.6 } } }
+            """);
+        var file = WriteFile("readme.txt", "hello\n");
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var result = await manager.DetectGrammarAsync(file, _directory);
+
+        // Assert
+        Assert.True(result.IsSuccessful);
+        Assert.False(result.IsFallback);
+        Assert.Equal("Text.grammar", result.GrammarName);
+    }
+
+    [Fact]
+    public async Task ToJson_WritesStepsAndEvidence()
+    {
+        // Arrange
+        var file = WriteFile("report.sql", "SELECT 1;\n");
+        using var manager = GrammarDetectionManager.CreateDefault();
+        var trace = await manager.ExplainAsync(file, _directory);
+
+        // Act
+        using var document = JsonDocument.Parse(trace.ToJson());
+
+        // Assert
+        var root = document.RootElement;
+        Assert.True(root.GetProperty("resolved").GetBoolean());
+        Assert.Equal("SQL.grammar", root.GetProperty("grammar").GetString());
+        Assert.Equal("file-extension", root.GetProperty("winner").GetString());
+        Assert.Equal(JsonValueKind.Null, root.GetProperty("configurationPath").ValueKind);
+
+        var steps = root.GetProperty("steps");
+        Assert.Equal(2, steps.GetArrayLength());
+        Assert.Equal("NoMatch", steps[0].GetProperty("outcome").GetString());
+        Assert.Equal("Matched", steps[1].GetProperty("outcome").GetString());
+        Assert.Equal("built-in extension mappings", steps[1].GetProperty("evidence")[0].GetProperty("source").GetString());
+    }
+
+    [Fact]
+    public async Task ExplainAsync_ShebangWithByteOrderMark_RoutesOnInterpreter()
+    {
+        // Arrange
+        var file = WriteFile("build", "\uFEFF#!/usr/bin/env python3\nprint('hi')\n");
+        using var manager = GrammarDetectionManager.CreateDefault();
+
+        // Act
+        var trace = await manager.ExplainAsync(file, _directory);
+
+        // Assert
+        Assert.Equal("Python311.grammar", trace.Result.GrammarName);
+        Assert.Equal("content-based", trace.WinningDetectorId);
+        Assert.Contains(trace.Winner!.Evidence, e => e.Detail.Contains("'#!/usr/bin/env python3' runs python3"));
+    }
+
+    [Fact]
+    public async Task ToNarrative_DescribesDetectorsInOrderAndConclusion()
+    {
+        // Arrange
+        var file = WriteFile("Program.cs", "namespace Demo;\n");
+        using var manager = GrammarDetectionManager.CreateDefault();
+        var trace = await manager.ExplainAsync(file, _directory);
+
+        // Act
+        var narrative = trace.ToNarrative();
+
+        // Assert
+        Assert.Contains("Configuration: none found", narrative);
+        Assert.Contains("1. content-based (priority 200): Matched <- chosen", narrative);
+        Assert.Contains("2. file-extension (priority 100): Matched", narrative);
+        Assert.Contains("Resolved to CSharp10.grammar 10.0 by content-based: it had the highest confidence", narrative);
+        Assert.True(narrative.IndexOf("csharp-namespace", StringComparison.Ordinal) < narrative.IndexOf("'.cs' maps to", StringComparison.Ordinal));
+    }
+
+    private string WriteFile(string name, string content)
+    {
+        var path = Path.Combine(_directory, name);
+        File.WriteAllText(path, content);
+        return path;
+    }
+
+    private sealed class FixedGrammarDetector : IGrammarDetector
+    {
+        public string DetectorId => "fixed";
+
+        public int Priority => 150;
+
+        public Task<GrammarDetectionResult> DetectGrammarAsync(GrammarDetectionContext context)
+        {
+            return Task.FromResult(GrammarDetectionResult.Success(
+                "Notes.grammar", confidence: 0.9, detectorId: DetectorId,
+                evidence: new[] { new DetectionEvidence("test", "always notes") }));
+        }
+
+        public bool CanDetect(GrammarDetectionContext context) => true;
+    }
+}
@@ -0,0 +1,51 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Projects.Grammar;
+using Xunit;
+
+namespace Minotaur.Tests.Projects.Grammar;
+
+public class VersionRangeTests
+{
+    [Theory]
+    [InlineData(">=1.2.0 <2.0.0", "1.2.0", true)]
+    [InlineData(">=1.2.0 <2.0.0", "1.9.7", true)]
+    [InlineData(">=1.2.0 <2.0.0", "2.0.0", false)]
+    [InlineData(">=1.2.0 <2.0.0", "1.1.9", false)]
+    [InlineData(">1.0 <=1.5", "1.5.0", true)]
+    [InlineData(">1.0 <=1.5", "1.0.0", false)]
+    [InlineData("1.3.0", "1.3.0", true)]
+    [InlineData("=1.3.0", "1.3.1", false)]
+    [InlineData("*", "42.0.0", true)]
+    public void Contains_ChecksEveryComparison(string range, string version, bool expected)
+    {
+        // Act
+        var contains = VersionRange.Parse(range).Contains(GrammarVersion.Parse(version));
+
+        // Assert
+        Assert.Equal(expected, contains);
+    }
+
+    [Fact]
+    public void Parse_InvalidVersion_Throws()
+    {
+        // Act & Assert
+        var exception = Assert.Throws<FormatException>(() => VersionRange.Parse(">=1.0 <next"));
+        Assert.Contains("<next", exception.Message);
+    }
+}
@@ -0,0 +1,184 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for AlternativeOrderOptimizer functionality
+/// </summary>
+public sealed class AlternativeOrderOptimizerTests : IDisposable
+{
+    private const string StatementGrammar =
+        "<program> ::= <statement> | <program> <statement>\n" +
+        "<statement> ::= \"print\" <value> \";\" | \"call\" <IDENTIFIER> \";\" | <IDENTIFIER> \"=\" <value> \";\" | <IDENTIFIER> \"+=\" <value> \";\"\n" +
+        "<value> ::= <IDENTIFIER> | <NUMBER>\n";
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"reorder_{Guid.NewGuid():N}");
+
+    public void Dispose()
+    {
+        if (Directory.Exists(_directory))
+        {
+            Directory.Delete(_directory, recursive: true);
+        }
+    }
+
+    [Fact]
+    public void FindOverlaps_SharedFirstTerminal_ReportsPair()
+    {
+        // Arrange
+        var grammar = Compile(StatementGrammar);
+        var optimizer = new AlternativeOrderOptimizer(grammar);
+
+        // Act
+        var overlaps = optimizer.FindOverlaps(grammar.GetRule("statement")!);
+
+        // Assert
+        var overlap = Assert.Single(overlaps);
+        Assert.Equal(2, overlap.First.Index);
+        Assert.Equal(3, overlap.Second.Index);
+        Assert.Equal(new[] { "IDENTIFIER" }, overlap.SharedTerminals);
+        Assert.False(overlap.Nullable);
+        Assert.Empty(optimizer.FindOverlaps(grammar.GetRule("value")!));
+    }
+
+    [Fact]
+    public void FindOverlaps_FirstThroughNullableRule_ReportsPair()
+    {
+        // Arrange
+        var grammar = Compile("""
+            <declaration> ::= <modifiers> "fn" <IDENTIFIER> | "fn" "(" ")" | "struct" <IDENTIFIER>
+            <modifiers> ::= "pub" <modifiers> | ε
+            """);
+        var optimizer = new AlternativeOrderOptimizer(grammar);
+
+        // Act
+        var overlaps = optimizer.FindOverlaps(grammar.GetRule("declaration")!);
+
+        // Assert
+        var overlap = Assert.Single(overlaps);
+        Assert.Equal(0, overlap.First.Index);
+        Assert.Equal(1, overlap.Second.Index);
+        Assert.Equal(new[] { "\"fn\"" }, overlap.SharedTerminals);
+    }
+
+    [Fact]
+    public void FindOverlaps_NullableAlternative_OverlapsEveryOther()
+    {
+        // Arrange
+        var grammar = Compile("""
+            <suffix> ::= ε | "?" | "!"
+            """);
+
+        // Act
+        var overlaps = new AlternativeOrderOptimizer(grammar).FindOverlaps(grammar.GetRule("suffix")!);
+
+        // Assert
+        Assert.Equal(2, overlaps.Count);
+        Assert.All(overlaps, o => Assert.True(o.Nullable));
+        Assert.All(overlaps, o => Assert.Equal(0, o.First.Index));
+    }
+
+    [Fact]
+    public void Propose_CorpusCounts_OrdersMostUsedFirstAndKeepsOverlaps()
+    {
+        // Arrange
+        var grammar = Compile(StatementGrammar);
+        var coverage = new GrammarCoverage(grammar);
+        coverage.Record(new GeneralizedParser(grammar).Parse("c += 2;\na = 1;\nb += a;\nd += 3;\n"));
+
+        // Act
+        var plan = new AlternativeOrderOptimizer(grammar).Propose(coverage);
+
+        // Assert
+        Assert.Null(plan.Refusal);
+        var statement = plan.Rules.Single(r => r.Rule.Name == "statement");
+        Assert.True(statement.IsChanged);
+        Assert.Equal(new[] { 2, 3, 0, 1 }, statement.Order.Select(a => a.Index));
+        Assert.Contains("can both start with IDENTIFIER", Assert.Single(statement.Overlaps).Reason);
+        Assert.False(plan.Rules.Single(r => r.Rule.Name == "program").IsChanged);
+    }
+
+    [Fact]
+    public void Propose_PegGrammar_RefusesEveryRule()
+    {
+        // Arrange
+        var grammar = Compile("Grammar: Imported\nFormatType: PEG\n" + StatementGrammar);
+        var coverage = new GrammarCoverage(grammar);
+        coverage.Record(new GeneralizedParser(grammar).Parse("a = 1;\n"));
+
+        // Act
+        var plan = new AlternativeOrderOptimizer(grammar).Propose(coverage);
+
+        // Assert
+        Assert.Contains("imported from PEG", plan.Refusal);
+        Assert.Empty(plan.Changes);
+        Assert.All(plan.Rules, r => Assert.NotNull(r.Refusal));
+    }
+
+    [Fact]
+    public void Propose_RuleWithActions_IsLeftAlone()
+    {
+        // Arrange
+        var grammar = Compile("""
+            <value> ::= <IDENTIFIER> => { name } | <NUMBER> => { number }
+            """);
+        var coverage = new GrammarCoverage(grammar);
+        coverage.Record(new GeneralizedParser(grammar).Parse("1"));
+
+        // Act
+        var plan = new AlternativeOrderOptimizer(grammar).Propose(coverage);
+
+        // Assert
+        var value = Assert.Single(plan.Rules);
+        Assert.False(value.IsChanged);
+        Assert.Contains("tied to its position", value.Refusal);
+    }
+
+    [Fact]
+    public void Optimize_Corpus_ReplaysBothOrdersAndRewritesGrammar()
+    {
+        // Arrange
+        Directory.CreateDirectory(_directory);
+        var grammarPath = Path.Combine(_directory, "statements.grammar");
+        File.WriteAllText(grammarPath, StatementGrammar);
+        var corpusDirectory = RegressionCorpus.GetDirectory(grammarPath);
+        RegressionCorpus.Add(Compile(StatementGrammar), "a = 1;\nb = a;\nc += 2;\n", corpusDirectory, new CorpusCaptureOptions { Anonymize = false });
+
+        // Act
+        var plan = AlternativeOrderOptimizer.Optimize(grammarPath, corpusDirectory);
+        var rewritten = plan.Apply(StatementGrammar);
+
+        // Assert
+        Assert.Equal(20, plan.AttemptsBefore);
+        Assert.Equal(13, plan.AttemptsAfter);
+        Assert.Equal(0.35, plan.Improvement, 3);
+        Assert.Contains("<statement> ::= <IDENTIFIER> \"=\" <value> \";\" | <IDENTIFIER> \"+=\" <value> \";\" | \"print\" <value> \";\" | \"call\" <IDENTIFIER> \";\"\n", rewritten);
+        Assert.Contains("<value> ::= <NUMBER> | <IDENTIFIER>\n", rewritten);
+        Assert.Equal(plan.AttemptsAfter, AlternativeOrderOptimizer.CountAttempts(new GeneralizedParser(Compile(rewritten)).Parse("a = 1;\nb = a;\nc += 2;\n")));
+    }
+
+    private static CompiledGrammar Compile(string text)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(text));
+    }
+}
@@ -0,0 +1,219 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for expected-diagnostics blocks
+/// </summary>
+public sealed class DiagnosticExpectationsTests : IDisposable
+{
+    private const string PairGrammar = "Grammar: Pairs\n<program> ::= <IDENTIFIER> \"=\" <NUMBER>";
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"expectations_{Guid.NewGuid():N}");
+
+    public DiagnosticExpectationsTests()
+    {
+        Directory.CreateDirectory(_directory);
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_directory, recursive: true);
+    }
+
+    [Fact]
+    public void Parse_Block_ReadsModeDiagnosticsAndRecoveredCount()
+    {
+        // Arrange
+        const string text = """
+            # negative test
+            mode lenient
+            error E0012 3:7 "did you mean \"return\"" # a '#' inside the message is kept
+            warning W0002
+            recovered 2
+            """;
+
+        // Act
+        var expectations = DiagnosticExpectations.Parse(text);
+
+        // Assert
+        Assert.Equal(ExpectationMode.Lenient, expectations.Mode);
+        Assert.Equal(
+            new[]
+            {
+                new ExpectedDiagnostic(DiagnosticSeverity.Error, "E0012", 3, 7, "did you mean \"return\""),
+                new ExpectedDiagnostic(DiagnosticSeverity.Warning, "W0002")
+            },
+            expectations.Diagnostics);
+        Assert.Equal(2, expectations.Recovered);
+    }
+
+    [Theory]
+    [InlineData("mode sometimes")]
+    [InlineData("error")]
+    [InlineData("error E0001 3")]
+    [InlineData("error E0001 3:7 unquoted")]
+    [InlineData("recovered -1")]
+    [InlineData("expect E0001")]
+    public void Parse_MalformedLine_ThrowsWithLineNumber(string line)
+    {
+        // Act & Assert
+        var ex = Assert.Throws<FormatException>(() => DiagnosticExpectations.Parse("mode strict\n" + line));
+        Assert.Contains("line 2", ex.Message);
+    }
+
+    [Fact]
+    public void Format_RoundTripsThroughParse()
+    {
+        // Arrange
+        var expectations = new DiagnosticExpectations { Mode = ExpectationMode.Lenient, Recovered = 1 };
+        expectations.Diagnostics.Add(new ExpectedDiagnostic(DiagnosticSeverity.Error, "E0001", 1, 5, "Unexpected IDENTIFIER 'y'\n"));
+
+        // Act
+        var text = expectations.Format();
+        var parsed = DiagnosticExpectations.Parse(text);
+
+        // Assert
+        Assert.Equal("mode lenient\nerror E0001 1:5 \"Unexpected IDENTIFIER 'y'\\n\"\nrecovered 1\n", text);
+        Assert.Equal(expectations.Diagnostics, parsed.Diagnostics);
+        Assert.Equal(expectations.Recovered, parsed.Recovered);
+    }
+
+    [Fact]
+    public void Check_MatchingDiagnostic_Passes()
+    {
+        // Arrange
+        var result = Parse("x = y");
+        var expectations = DiagnosticExpectations.Parse("error E0001 1:5 \"expected NUMBER\"\nrecovered 0\n");
+
+        // Act
+        var report = expectations.Check(result);
+
+        // Assert
+        Assert.Null(report);
+    }
+
+    [Fact]
+    public void Check_WrongPosition_ShowsExpectedAndActualSideBySide()
+    {
+        // Arrange
+        var result = Parse("x = y");
+        var expectations = DiagnosticExpectations.Parse("error E0001 1:3\n");
+
+        // Act
+        var report = expectations.Check(result);
+
+        // Assert
+        Assert.NotNull(report);
+        var lines = report!.Split('\n');
+        Assert.Equal("expected" + new string(' ', 7) + " | actual", lines[1]);
+        Assert.Equal("error E0001 1:3 ! (missing)", lines[2]);
+        Assert.Equal("(not expected)  ! error E0001 1:5: Unexpected IDENTIFIER 'y'; expected NUMBER", lines[3]);
+    }
+
+    [Fact]
+    public void Check_UnlistedDiagnostic_FailsOnlyInStrictMode()
+    {
+        // Arrange
+        var result = Parse("x = y");
+
+        // Act
+        var strict = DiagnosticExpectations.Parse("mode strict\n").Check(result);
+        var lenient = DiagnosticExpectations.Parse("mode lenient\n").Check(result);
+
+        // Assert
+        Assert.NotNull(strict);
+        Assert.Null(lenient);
+    }
+
+    [Fact]
+    public void Check_WrongRecoveredCount_ReportsBothCounts()
+    {
+        // Arrange
+        var result = Parse("x = y");
+        var expectations = DiagnosticExpectations.Parse("mode lenient\nrecovered 2\n");
+
+        // Act
+        var report = expectations.Check(result);
+
+        // Assert
+        Assert.NotNull(report);
+        Assert.Contains("recovered 2    ! recovered 0", report);
+    }
+
+    [Fact]
+    public void FromResult_RegeneratesABlockTheParseMeets()
+    {
+        // Arrange
+        var result = Parse("x = y");
+
+        // Act
+        var expectations = DiagnosticExpectations.FromResult(result, ExpectationMode.Strict, recovered: true);
+
+        // Assert
+        Assert.Equal("error E0001 1:5 \"Unexpected IDENTIFIER 'y'; expected NUMBER\"\nrecovered 0\n", expectations.Format());
+        Assert.Null(DiagnosticExpectations.Parse(expectations.Format()).Check(result));
+    }
+
+    [Fact]
+    public void AssertExpectedDiagnostics_MissingFile_ExplainsHowToCreateIt()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(PairGrammar));
+        var input = Path.Combine(_directory, "pair.txt");
+        File.WriteAllText(input, "x = y\n");
+
+        // Act
+        var ex = Assert.Throws<SnapshotMismatchException>(() => ParseSnapshot.AssertExpectedDiagnostics(grammar, input));
+
+        // Assert
+        Assert.Equal(input + DiagnosticExpectations.Extension, ex.SnapshotPath);
+        Assert.Contains($"{Snapshot.UpdateVariable}=1", ex.Message);
+    }
+
+    [Fact]
+    public void Verify_CorpusCaseWithExpectations_ChecksThem()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(PairGrammar));
+        var input = Path.Combine(_directory, "pair.txt");
+        File.WriteAllText(input, "x = y\n");
+        File.WriteAllText(input + DiagnosticExpectations.Extension, "error E0001 1:5\n");
+
+        // Act
+        var count = RegressionCorpus.Verify(grammar, _directory);
+        File.WriteAllText(input + DiagnosticExpectations.Extension, "error E0002 1:5\n");
+        var ex = Assert.Throws<SnapshotMismatchException>(() => RegressionCorpus.Verify(grammar, _directory));
+
+        // Assert
+        Assert.Equal(1, count);
+        Assert.Equal(input + DiagnosticExpectations.Extension, Assert.Single(RegressionCorpus.GetCases(_directory)).SnapshotPath);
+        Assert.Contains("error E0002 1:5 ! (missing)", ex.Message);
+    }
+
+    private static ParseResult Parse(string input)
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(PairGrammar))).Parse(input);
+    }
+}
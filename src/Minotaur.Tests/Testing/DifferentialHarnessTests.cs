@@ -0,0 +1,241 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for differential testing functionality
+/// </summary>
+public sealed class DifferentialHarnessTests : IDisposable
+{
+    private const string ToyGrammar = """
+        DifferentialItems: function=fn, record=struct
+
+        <program> ::= <item> | <program> <item>
+        <item> ::= <function> | <record>
+        <function> ::= "fn" <IDENTIFIER> "{" <body> "}"
+        <body> ::= <IDENTIFIER> | <body> <IDENTIFIER>
+        <record> ::= "struct" <IDENTIFIER> ";"
+        """;
+
+    private readonly string _corpus = Path.Combine(Path.GetTempPath(), $"corpus_{Guid.NewGuid():N}");
+
+    public DifferentialHarnessTests()
+    {
+        Directory.CreateDirectory(_corpus);
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_corpus, recursive: true);
+    }
+
+    [Fact]
+    public void Compare_IdenticalSummaries_IsMatch()
+    {
+        // Arrange
+        var summary = Summary(new[] { Item("fn", "main", 1, 1) }, "main", "x");
+
+        // Act
+        var comparison = DifferentialComparer.Compare("a.src", ("syn", summary), ("minotaur", summary));
+
+        // Assert
+        Assert.True(comparison.IsMatch);
+        Assert.Null(comparison.FirstDivergence);
+    }
+
+    [Fact]
+    public void Compare_DifferentSummaries_ReportsCountsKindsIdentifiersAndFirstDivergence()
+    {
+        // Arrange
+        var reference = Summary(new[] { Item("fn", "a", 1, 1), Item("struct", "B", 3, 1), Item("fn", "c", 5, 1) }, "a", "B", "c");
+        var candidate = Summary(new[] { Item("fn", "a", 1, 1), Item("fn", "c", 5, 1) }, "a", "c", "d");
+
+        // Act
+        var comparison = DifferentialComparer.Compare("a.src", ("syn", reference), ("minotaur", candidate));
+
+        // Assert
+        Assert.False(comparison.IsMatch);
+        Assert.Contains("item count: syn 3, minotaur 2", comparison.Differences);
+        Assert.Contains("kind 'struct': syn 1, minotaur 0", comparison.Differences);
+        Assert.DoesNotContain(comparison.Differences, d => d.StartsWith("kind 'fn'"));
+        Assert.Contains("identifiers only in syn: B", comparison.Differences);
+        Assert.Contains("identifiers only in minotaur: d", comparison.Differences);
+        Assert.Equal((Item("struct", "B", 3, 1), Item("fn", "c", 5, 1)), comparison.FirstDivergence);
+        Assert.Contains("first divergence: syn struct B @3:1, minotaur fn c @5:1", comparison.Differences);
+    }
+
+    [Fact]
+    public void Compare_FailedFrontend_ReportsOnlyTheFailure()
+    {
+        // Act
+        var comparison = DifferentialComparer.Compare(
+            "a.src",
+            ("syn", Summary(new[] { Item("fn", "a", 1, 1) }, "a")),
+            ("minotaur", FrontendSummary.Failed("1:4: error E0001: Unexpected token")));
+
+        // Assert
+        Assert.Equal(new[] { "minotaur failed: 1:4: error E0001: Unexpected token" }, comparison.Differences);
+    }
+
+    [Fact]
+    public void Compare_WithAllowlist_ToleratesListedDifferences()
+    {
+        // Arrange
+        var allowlist = DifferenceAllowlist.Parse("""
+            # syn-only items
+            ignore-kind verbatim
+            equivalent-kind macro macro_rules
+            ignore-name impl   # unnamed in syn
+            ignore-identifier r#type
+            ignore-positions
+            """);
+        var reference = Summary(new[] { Item("impl", null, 1, 1), Item("macro_rules", "m", 4, 1), Item("verbatim", null, 6, 1) }, "m", "r#type");
+        var candidate = Summary(new[] { Item("impl", "Foo", 1, 5), Item("macro", "m", 4, 1) }, "m");
+
+        // Act
+        var comparison = DifferentialComparer.Compare("a.rs", ("syn", reference), ("minotaur", candidate), allowlist);
+
+        // Assert
+        Assert.True(comparison.IsMatch, string.Join("\n", comparison.Differences));
+    }
+
+    [Theory]
+    [InlineData("ui/test.rs", true)]
+    [InlineData("ui/nested/test.rs", false)]
+    [InlineData("fuzz/a/b/c.rs", true)]
+    [InlineData("src/lib.rs", false)]
+    public void DifferenceAllowlist_Skip_MatchesGlobs(string path, bool skipped)
+    {
+        // Arrange
+        var allowlist = DifferenceAllowlist.Parse("skip ui/*.rs\nskip fuzz/**/*.rs");
+
+        // Act & Assert
+        Assert.Equal(skipped, allowlist.IsSkipped(path));
+    }
+
+    [Fact]
+    public void DifferenceAllowlist_UnknownDirective_Throws()
+    {
+        // Act
+        var ex = Assert.Throws<FormatException>(() => DifferenceAllowlist.Parse("ignore-kind fn\nallow everything"));
+
+        // Assert
+        Assert.Contains("line 2", ex.Message);
+    }
+
+    [Fact]
+    public void FrontendSummary_Json_RoundTrips()
+    {
+        // Arrange
+        var summary = Summary(new[] { Item("fn", "main", 2, 5), Item("impl", null, 7, 1) }, "main", "Foo");
+
+        // Act
+        var parsed = FrontendSummary.FromJson(summary.ToJson());
+
+        // Assert
+        Assert.Equal(summary.Items, parsed.Items);
+        Assert.Equal(summary.Identifiers.Order(), parsed.Identifiers.Order());
+        Assert.Equal("oops", FrontendSummary.FromJson("""{ "error": "oops" }""").Error);
+        Assert.Throws<FormatException>(() => FrontendSummary.FromJson("""{ "items": [{ "name": "x" }] }"""));
+        Assert.Throws<FormatException>(() => FrontendSummary.FromJson("not json"));
+    }
+
+    [Fact]
+    public void GrammarFrontend_UsesDifferentialItemsMetadata()
+    {
+        // Arrange
+        var frontend = new GrammarFrontend(ToyCompiledGrammar());
+
+        // Act
+        var summary = frontend.Summarize("a.toy", "struct Point;\nfn main { run stop }");
+
+        // Assert
+        Assert.Null(summary.Error);
+        Assert.Equal(new[] { Item("struct", "Point", 1, 1), Item("fn", "main", 2, 1) }, summary.Items);
+        Assert.Equal(new[] { "Point", "main", "run", "stop" }, summary.Identifiers.Order(StringComparer.Ordinal));
+        Assert.NotNull(frontend.Summarize("b.toy", "fn {").Error);
+    }
+
+    [Fact]
+    public void Run_CorpusWithReferenceCommand_ComparesEveryFileAndDumpsMismatches()
+    {
+        // Arrange
+        File.WriteAllText(Path.Combine(_corpus, "good.toy"), "fn main { x }");
+        File.WriteAllText(Path.Combine(_corpus, "bad.toy"), "struct S;");
+        Directory.CreateDirectory(Path.Combine(_corpus, "skipped"));
+        File.WriteAllText(Path.Combine(_corpus, "skipped", "ignored.toy"), "fn");
+
+        // The reference "parser" reads a summary stored next to each file.
+        File.WriteAllText(Path.Combine(_corpus, "good.toy.json"), Summary(new[] { Item("fn", "main", 1, 1) }, "main", "x").ToJson());
+        File.WriteAllText(Path.Combine(_corpus, "bad.toy.json"), Summary(new[] { Item("fn", "S", 1, 1) }, "S").ToJson());
+        var reference = new CommandFrontend("reference", OperatingSystem.IsWindows() ? "type {}.json" : "cat {}.json");
+        var harness = new DifferentialHarness(reference, new GrammarFrontend(ToyCompiledGrammar()), DifferenceAllowlist.Parse("skip skipped/*"));
+
+        // Act
+        var report = harness.Run(_corpus, "*.toy");
+
+        // Assert
+        Assert.Equal(new[] { "bad.toy", "good.toy" }, report.Files.Select(f => Path.GetFileName(f.Path)));
+        Assert.Equal(new[] { "skipped/ignored.toy" }, report.Skipped);
+        var mismatch = Assert.Single(report.Mismatches);
+        Assert.EndsWith("bad.toy", mismatch.Path);
+        Assert.Contains("kind 'struct': reference 0, minotaur 1", mismatch.Differences);
+
+        var dump = mismatch.Dump();
+        Assert.Contains("--- reference", dump);
+        Assert.Contains("--- minotaur", dump);
+        Assert.Contains("\"kind\": \"struct\"", dump);
+        Assert.StartsWith("2 files compared, 1 mismatches, 1 skipped", report.ToString());
+    }
+
+    [Fact]
+    public void RustGrammar_DifferentialItems_NameExistingRules()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read(File.ReadAllText(RustDifferentialTests.RepositoryPath(ReferenceLanguages.Rust.GrammarFile)));
+
+        // Act
+        var kinds = GrammarFrontend.ParseItemKinds(grammar.Metadata[GrammarFrontend.ItemsKey]);
+
+        // Assert
+        Assert.Contains("fn", kinds.Values);
+        Assert.All(kinds.Keys, rule => Assert.NotNull(grammar.ProductionRules.GetRule(rule)));
+        Assert.NotNull(grammar.ProductionRules.GetRule(grammar.Metadata[GrammarFrontend.IdentifierKey]));
+        Assert.NotNull(DifferenceAllowlist.Load(RustDifferentialTests.RepositoryPath(ReferenceLanguages.Rust.AllowlistFile!)));
+    }
+
+    private static CompiledGrammar ToyCompiledGrammar()
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(ToyGrammar));
+    }
+
+    private static SummaryItem Item(string kind, string? name, int line, int column)
+    {
+        return new SummaryItem(kind, name, line, column);
+    }
+
+    private static FrontendSummary Summary(SummaryItem[] items, params string[] identifiers)
+    {
+        return new FrontendSummary { Items = items, Identifiers = identifiers.ToHashSet() };
+    }
+}
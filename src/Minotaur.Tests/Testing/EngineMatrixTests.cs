@@ -0,0 +1,127 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for running corpora under several parse engines
+/// </summary>
+public sealed class EngineMatrixTests : IDisposable
+{
+    private const string FunctionGrammar = """
+        Grammar: Functions
+        StartRule: program
+        Deferred: <block>
+        <program> ::= <function> | <program> <function>
+        <function> ::= "fn" <IDENTIFIER> <block>
+        <block> ::= "{" <statements> "}" | "{" "}"
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= <IDENTIFIER> "=" <expr> ";" | <block>
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER> | "(" <expr> ")"
+        """;
+
+    private readonly string _corpus = Path.Combine(Path.GetTempPath(), $"engines_{Guid.NewGuid():N}");
+
+    public void Dispose()
+    {
+        if (Directory.Exists(_corpus))
+        {
+            Directory.Delete(_corpus, recursive: true);
+        }
+    }
+
+    [Fact]
+    public void Run_CorpusWithDeferredRules_EveryEngineAgrees()
+    {
+        // Arrange
+        var grammar = Compile(FunctionGrammar);
+        RegressionCorpus.Add(grammar, "fn a { x = 1; }\nfn b {\n  y = (2 + x);\n  { z = y; }\n}\n", _corpus);
+        RegressionCorpus.Add(grammar, "fn a { x = ; }\nfn b { }\n", _corpus, new CorpusCaptureOptions { Reduce = false });
+
+        // Act
+        var report = EngineMatrix.Run(grammar, _corpus);
+
+        // Assert
+        Assert.True(report.IsConsistent, string.Join("\n", report.Divergences.Select(d => d.Diff)));
+        Assert.Equal(new[] { "sequential", "parallel", "lazy", "incremental" }, report.Engines);
+        Assert.Empty(report.Skipped);
+        Assert.Equal(2, report.CaseCount);
+        Assert.All(report.Timings, t => Assert.Equal(2, t.Cases));
+    }
+
+    [Fact]
+    public void Run_GrammarWithoutDeferredRules_SkipsLazyWithReason()
+    {
+        // Arrange
+        var grammar = Compile(FunctionGrammar.Replace("Deferred: <block>\n", string.Empty));
+
+        // Act
+        var report = EngineMatrix.Run(grammar, new[] { ("one", "fn a { }") });
+
+        // Assert
+        var skipped = Assert.Single(report.Skipped);
+        Assert.Equal("lazy", skipped.Engine);
+        Assert.Contains("no deferred rules", skipped.Reason);
+        Assert.DoesNotContain(ParseEngine.Lazy, grammar.GetSupportedEngines());
+        Assert.Contains(ParseEngine.Lazy, Compile(FunctionGrammar).GetSupportedEngines());
+    }
+
+    [Fact]
+    public void Run_DivergingEngine_ReportsDiffNamingBothEngines()
+    {
+        // Arrange
+        var grammar = Compile(FunctionGrammar);
+        var engines = new[] { ParseEngine.Sequential, new AppendingEngine() };
+
+        // Act
+        var report = EngineMatrix.Run(grammar, new[] { ("same", "fn a { }"), ("other", "fn b { x = 1; }") }, engines);
+
+        // Assert
+        Assert.False(report.IsConsistent);
+        Assert.Equal(new[] { "same", "other" }, report.Divergences.Select(d => d.Case));
+        var divergence = report.Divergences[0];
+        Assert.Equal("sequential", divergence.Reference);
+        Assert.Equal("appending", divergence.Engine);
+        Assert.StartsWith("--- sequential\n+++ appending\n", divergence.Diff);
+        Assert.Contains(divergence.Diff.Split('\n'), l => l.StartsWith('+') && l.Contains("(IDENTIFIER \"z\")"));
+    }
+
+    private static CompiledGrammar Compile(string text)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(text));
+    }
+
+    // An engine with a bug: it parses one more function than the input has.
+    private sealed class AppendingEngine : ParseEngine
+    {
+        public AppendingEngine()
+            : base("appending")
+        {
+        }
+
+        public override ParseResult Parse(GeneralizedParser parser, string input, ParseOptions options)
+        {
+            return parser.Parse(input + "\nfn z { }", options);
+        }
+    }
+}
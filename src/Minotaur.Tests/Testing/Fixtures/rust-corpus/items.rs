@@ -0,0 +1,18 @@
+use std::fmt;
+
+pub const LIMIT: usize = 16;
+
+#[derive(Debug)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+enum Shape {
+    Dot(Point),
+    Line(Point, Point),
+}
+
+fn origin() -> Point {
+    Point { x: 0, y: 0 }
+}
@@ -0,0 +1,19 @@
+trait Area {
+    fn area(&self) -> f64;
+}
+
+struct Square {
+    side: f64,
+}
+
+impl Area for Square {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+}
+
+mod shapes {
+    pub fn unit() -> super::Square {
+        super::Square { side: 1.0 }
+    }
+}
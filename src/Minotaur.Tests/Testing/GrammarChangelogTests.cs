@@ -0,0 +1,147 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for GrammarChangelog functionality, over the Calc versions in Fixtures/changelog
+/// </summary>
+public class GrammarChangelogTests
+{
+    [Fact]
+    public void ToMarkdown_CalcVersions_MatchesSnapshot()
+    {
+        // Arrange
+        var changelog = new GrammarChangelog(Load("calc-1.2.grammar"), Load("calc-1.3.grammar"));
+
+        // Act
+        var markdown = changelog.ToMarkdown();
+
+        // Assert
+        Snapshot.AssertMatches(markdown, FixturePath("calc-1.3.changelog.md"));
+    }
+
+    [Fact]
+    public void GetChanges_AddedAlternative_ExampleIsRejectedByOldVersion()
+    {
+        // Arrange
+        var from = Load("calc-1.2.grammar");
+        var changelog = new GrammarChangelog(from, Load("calc-1.3.grammar"));
+
+        // Act
+        var added = changelog.GetChanges().Where(c => c.Kind == GrammarChangeKind.Added).ToList();
+
+        // Assert
+        Assert.Equal(3, added.Count);
+        Assert.All(added, c => Assert.False(new GeneralizedParser(from).Parse(c.Example!).IsSuccess));
+    }
+
+    [Fact]
+    public void GetChanges_SmallestExampleAcceptedByOldVersion_PrefersRandomSentenceItRejects()
+    {
+        // Arrange
+        var from = Compile("<e> ::= <NUMBER> | <IDENTIFIER> | <NUMBER> \"+\" <NUMBER>\n");
+        var to = Compile("<e> ::= <NUMBER> | <IDENTIFIER> | <NUMBER> \"+\" <NUMBER> | <e> \"+\" <e>\n");
+
+        // Act
+        var change = Assert.Single(new GrammarChangelog(from, to).GetChanges());
+
+        // Assert
+        Assert.NotEqual("0 + 0", change.Example);
+        Assert.False(new GeneralizedParser(from).Parse(change.Example!).IsSuccess);
+    }
+
+    [Fact]
+    public void GetChanges_NoSentenceRejectedByOldVersion_KeepsSmallestExample()
+    {
+        // Arrange
+        var from = Compile("<e> ::= <NUMBER> | <e> \"+\" <NUMBER>\n");
+        var to = Compile("<e> ::= <NUMBER> | <e> \"+\" <NUMBER> | <e> \"+\" <e>\n");
+
+        // Act
+        var change = Assert.Single(new GrammarChangelog(from, to).GetChanges());
+
+        // Assert
+        Assert.Equal("`<e> ::= <e> \"+\" <e>`", change.Description);
+        Assert.Equal("0 + 0", change.Example);
+    }
+
+    [Fact]
+    public void GetChanges_SameGrammar_ReportsNoChanges()
+    {
+        // Arrange
+        var grammar = Load("calc-1.3.grammar");
+
+        // Act
+        var changelog = new GrammarChangelog(grammar, Load("calc-1.3.grammar"));
+
+        // Assert
+        Assert.Empty(changelog.GetChanges());
+        Assert.EndsWith("No changes.\n", changelog.ToMarkdown());
+    }
+
+    [Fact]
+    public void GetChanges_AcceptedAmbiguityAdded_IsBehaviorChange()
+    {
+        // Arrange
+        var from = Compile("<e> ::= <e> \"+\" <e> | <NUMBER>\n");
+        var to = Compile("<e> ::= <e> \"+\" <e> | <NUMBER>\n// @ambiguous(\"sums are associative\")\n");
+
+        // Act
+        var change = Assert.Single(new GrammarChangelog(from, to).GetChanges());
+
+        // Assert
+        Assert.Equal(GrammarChangeKind.Behavior, change.Kind);
+        Assert.Equal("Ambiguities of `<e>` are now accepted and resolved to the earliest alternative", change.Description);
+    }
+
+    [Fact]
+    public void GetChanges_WholeRuleDeprecated_IsListedOnce()
+    {
+        // Arrange
+        var from = Compile("<s> ::= <legacy> | \"go\"\n<legacy> ::= \"goto\" <IDENTIFIER> | \"gosub\" <IDENTIFIER>\n");
+        var to = Compile("<s> ::= <legacy> | \"go\"\n<legacy> ::= \"goto\" <IDENTIFIER> | \"gosub\" <IDENTIFIER>\n// @deprecated \"Jumps are going away\"\n");
+
+        // Act
+        var change = Assert.Single(new GrammarChangelog(from, to).GetChanges());
+
+        // Assert
+        Assert.Equal(GrammarChangeKind.Deprecated, change.Kind);
+        Assert.Equal("`<legacy>`: Deprecated: Jumps are going away", change.Description);
+    }
+
+    private static CompiledGrammar Compile(string content)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(content));
+    }
+
+    private static CompiledGrammar Load(string file)
+    {
+        return Compile(File.ReadAllText(FixturePath(file)));
+    }
+
+    private static string FixturePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "Fixtures", "changelog", file);
+    }
+}
@@ -0,0 +1,157 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for GrammarConflictAssistant functionality
+/// </summary>
+public class GrammarConflictAssistantTests
+{
+    private const string CalculatorGrammar = "<e> ::= <e> \"+\" <e> | <e> \"*\" <e> | <NUMBER>\n";
+
+    private const string DanglingElseGrammar =
+        "<stmt> ::= \"if\" <IDENTIFIER> \"then\" <stmt> | \"if\" <IDENTIFIER> \"then\" <stmt> \"else\" <stmt> | \"go\"\n";
+
+    private const string DeclarationGrammar =
+        "<decl> ::= \"var\" <IDENTIFIER> | \"var\" <IDENTIFIER> <init>\n" +
+        "<init> ::= \"=\" <NUMBER> | ε\n";
+
+    [Fact]
+    public void FindConflicts_OperatorGrammar_ReportsBothDerivations()
+    {
+        // Arrange
+        var assistant = new GrammarConflictAssistant(Compile(CalculatorGrammar));
+
+        // Act
+        var conflicts = assistant.FindConflicts();
+
+        // Assert
+        var conflict = Assert.Single(conflicts, c => c.First.Index == 0 && c.Second.Index == 1);
+        Assert.Matches(@"^\S+ [+*] \S+ [+*] \S+$", conflict.Text);
+        Assert.Matches(@"^\(e (\S+|\(e \S+ \* \S+\)) \+ (\S+|\(e \S+ \* \S+\))\)$", conflict.FirstTree);
+        Assert.Matches(@"^\(e (\S+|\(e \S+ \+ \S+\)) \* (\S+|\(e \S+ \+ \S+\))\)$", conflict.SecondTree);
+        Assert.Equal("<e>: <e> \"+\" <e> / <e> \"*\" <e>", conflict.Key);
+        Assert.False(conflict.IsAccepted);
+        Assert.Contains(conflicts, c => c.First.Index == 0 && c.Second.Index == 0);
+    }
+
+    [Fact]
+    public void Resolve_PrecedenceDeclaration_RemovesConflictAndWritesHeader()
+    {
+        // Arrange
+        var assistant = new GrammarConflictAssistant(Compile(CalculatorGrammar));
+        var conflict = assistant.FindConflicts().Single(c => c.First.Index == 0 && c.Second.Index == 1);
+        var resolution = Assert.Single(assistant.Propose(conflict), r => r.Description.StartsWith("Declare that \"*\" binds tighter than \"+\""));
+
+        // Act
+        var outcome = assistant.Resolve(CalculatorGrammar, conflict, resolution);
+
+        // Assert
+        Assert.Equal(ConflictResolutionKind.Precedence, resolution.Kind);
+        Assert.True(outcome.IsSuccess);
+        Assert.StartsWith("Precedence: left \"+\"; left \"*\"\n", outcome.Content);
+        var result = new GeneralizedParser(Compile(outcome.Content)).Parse("1 + 2 * 3");
+        Assert.Equal(0, Assert.IsType<NonTerminalNode>(result.Tree).ProductionIndex);
+    }
+
+    [Fact]
+    public void Propose_SameOperator_OffersBothAssociativities()
+    {
+        // Arrange
+        var content = "Precedence: left \"*\"\n" + CalculatorGrammar;
+        var assistant = new GrammarConflictAssistant(Compile(content));
+        var conflict = assistant.FindConflicts().Single(c => c.First.Index == 0 && c.Second.Index == 0);
+
+        // Act
+        var resolutions = assistant.Propose(conflict).Where(r => r.Kind == ConflictResolutionKind.Precedence).ToList();
+        var outcome = assistant.Resolve(content, conflict, resolutions[1]);
+
+        // Assert
+        Assert.Equal(2, resolutions.Count);
+        Assert.Contains("left \"+\"", resolutions[0].Description);
+        Assert.Contains("right \"+\"", resolutions[1].Description);
+        Assert.True(outcome.Resolved);
+        Assert.StartsWith("Precedence: left \"*\"; right \"+\"\n", outcome.Content);
+    }
+
+    [Fact]
+    public void Resolve_AcceptDefault_AnnotatesRuleAndSilencesConflict()
+    {
+        // Arrange
+        var assistant = new GrammarConflictAssistant(Compile(DanglingElseGrammar)) { SamplesPerAlternative = 16 };
+        var conflict = assistant.FindConflicts().First(c => c.First.Index == 0 && c.Second.Index == 1);
+        var resolutions = assistant.Propose(conflict);
+
+        // Act
+        var outcome = assistant.Resolve(DanglingElseGrammar, conflict, resolutions.Single(r => r.Kind == ConflictResolutionKind.AcceptDefault));
+
+        // Assert
+        Assert.DoesNotContain(resolutions, r => r.Kind == ConflictResolutionKind.Precedence);
+        Assert.True(outcome.IsSuccess);
+        Assert.Contains("\n// @ambiguous(\"", outcome.Content);
+        var grammar = Compile(outcome.Content);
+        Assert.NotNull(grammar.GetRule("stmt")!.AcceptedAmbiguity);
+        Assert.All(new GrammarConflictAssistant(grammar) { SamplesPerAlternative = 16 }.FindConflicts(), c => Assert.True(c.IsAccepted));
+    }
+
+    [Fact]
+    public void Resolve_LeftFactoringThatKeepsAmbiguity_ReportsConflictUnresolved()
+    {
+        // Arrange
+        var assistant = new GrammarConflictAssistant(Compile(DanglingElseGrammar)) { SamplesPerAlternative = 16 };
+        var conflict = assistant.FindConflicts().First(c => c.First.Index == 0 && c.Second.Index == 1);
+        var resolution = Assert.Single(assistant.Propose(conflict), r => r.Kind == ConflictResolutionKind.LeftFactor);
+
+        // Act
+        var outcome = assistant.Resolve(DanglingElseGrammar, conflict, resolution);
+
+        // Assert
+        Assert.False(outcome.Resolved);
+        Assert.Contains("<stmt> ::= \"if\" <IDENTIFIER> \"then\" <stmt> <stmt_tail> | \"go\"", outcome.Content);
+        Assert.Contains("<stmt_tail> ::= ε | \"else\" <stmt>", outcome.Content);
+    }
+
+    [Fact]
+    public void Resolve_LeftFactoring_MergesTailCoveredByNullableRule()
+    {
+        // Arrange
+        var assistant = new GrammarConflictAssistant(Compile(DeclarationGrammar));
+        var conflict = Assert.Single(assistant.FindConflicts());
+        var resolution = Assert.Single(assistant.Propose(conflict), r => r.Kind == ConflictResolutionKind.LeftFactor);
+
+        // Act
+        var outcome = assistant.Resolve(DeclarationGrammar, conflict, resolution);
+
+        // Assert
+        Assert.Equal("decl", conflict.Rule.Name);
+        Assert.True(outcome.IsSuccess);
+        Assert.StartsWith("<decl> ::= \"var\" <IDENTIFIER> <init>\n", outcome.Content);
+        Assert.DoesNotContain("_tail", outcome.Content);
+    }
+
+    private static CompiledGrammar Compile(string text)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(text));
+    }
+}
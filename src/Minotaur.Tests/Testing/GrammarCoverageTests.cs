@@ -0,0 +1,150 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for grammar coverage and coverage enforcement functionality
+/// </summary>
+public sealed class GrammarCoverageTests : IDisposable
+{
+    private const string StatementGrammar =
+        "<program> ::= <statement> | <program> <statement>\n" +
+        "<statement> ::= <IDENTIFIER> \"=\" <value> \";\"\n" +
+        "    | \"print\" <value> \";\"\n" +
+        "<value> ::= <IDENTIFIER> | <NUMBER>\n" +
+        "<unused> ::= \"never\"\n";
+
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"coverage_{Guid.NewGuid():N}");
+
+    public void Dispose()
+    {
+        if (Directory.Exists(_directory))
+        {
+            Directory.Delete(_directory, recursive: true);
+        }
+    }
+
+    [Fact]
+    public void Check_UncoveredAlternative_FailsWithSpanAndSuggestedInput()
+    {
+        // Arrange
+        var grammarPath = WriteFixture();
+        var grammar = Compile(StatementGrammar);
+        var coverage = GrammarCoverage.Measure(grammar, RegressionCorpus.GetDirectory(grammarPath));
+
+        // Act
+        var report = coverage.Check(CoverageManifest.ForGrammar(grammarPath), grammarPath);
+
+        // Assert
+        Assert.False(report.IsSatisfied);
+        Assert.Equal(6, report.Total);
+        Assert.Equal(5, report.Covered);
+        var uncovered = Assert.Single(report.Uncovered);
+        Assert.Equal("statement", uncovered.Alternative.Rule.Name);
+        Assert.Equal(1, uncovered.Alternative.Index);
+        Assert.Equal((3, 7), (uncovered.Span!.Line, uncovered.Span.Column));
+        Assert.Equal("\"print\" <value> \";\"", File.ReadAllText(grammarPath).Substring(uncovered.Span.Offset, uncovered.Span.Length));
+
+        var suggestion = new GrammarCoverage(grammar);
+        suggestion.Record(new GeneralizedParser(grammar).Parse(uncovered.SuggestedInput!));
+        Assert.True(suggestion.GetCount(uncovered.Alternative) > 0);
+    }
+
+    [Fact]
+    public void Check_SuggestedInputCorpus_ReachesFullCoverage()
+    {
+        // Arrange
+        var grammarPath = WriteFixture();
+        var grammar = Compile(StatementGrammar);
+        var corpusDirectory = RegressionCorpus.GetDirectory(grammarPath);
+        var manifest = CoverageManifest.ForGrammar(grammarPath);
+        var suggested = GrammarCoverage.Measure(grammar, corpusDirectory).Check(manifest).Uncovered.Single().SuggestedInput!;
+
+        // Act
+        RegressionCorpus.Add(grammar, suggested, corpusDirectory, new CorpusCaptureOptions { Anonymize = false });
+        var report = GrammarCoverage.Measure(grammar, corpusDirectory).Check(manifest);
+
+        // Assert
+        Assert.True(report.IsSatisfied);
+        Assert.Empty(report.Uncovered);
+    }
+
+    [Fact]
+    public void Check_MinimumBelowCoverage_IsSatisfiedButListsUncovered()
+    {
+        // Arrange
+        var grammarPath = WriteFixture();
+        File.WriteAllText(CoverageManifest.GetPath(grammarPath), "minimum 80  # one alternative left for now\nallow unused\n");
+        var grammar = Compile(StatementGrammar);
+
+        // Act
+        var report = GrammarCoverage.Measure(grammar, RegressionCorpus.GetDirectory(grammarPath))
+            .Check(CoverageManifest.ForGrammar(grammarPath), grammarPath);
+
+        // Assert
+        Assert.True(report.IsSatisfied);
+        Assert.Equal(80, report.MinimumPercentage);
+        Assert.Single(report.Uncovered);
+    }
+
+    [Fact]
+    public void GenerateThrough_UnreachableAlternative_ReturnsNull()
+    {
+        // Arrange
+        var grammar = Compile(StatementGrammar);
+        var generator = new SentenceGenerator(grammar);
+
+        // Act
+        var sentence = generator.GenerateThrough(grammar.GetRule("unused")!.Alternatives[0], new Random(0));
+
+        // Assert
+        Assert.Null(sentence);
+        Assert.Null(GrammarCoverage.SuggestInput(grammar, grammar.GetRule("unused")!.Alternatives[0]));
+    }
+
+    [Fact]
+    public void ParseManifest_UnknownDirective_Throws()
+    {
+        // Act & Assert
+        var ex = Assert.Throws<FormatException>(() => CoverageManifest.Parse("minimum 90\nrequire everything\n"));
+        Assert.Contains("line 2", ex.Message);
+    }
+
+    private string WriteFixture()
+    {
+        // The corpus uses every alternative except "print", and the manifest allows the rule nothing references.
+        var grammarPath = Path.Combine(_directory, "statements.grammar");
+        Directory.CreateDirectory(_directory);
+        File.WriteAllText(grammarPath, StatementGrammar);
+        File.WriteAllText(CoverageManifest.GetPath(grammarPath), "allow unused\n");
+
+        var corpusDirectory = RegressionCorpus.GetDirectory(grammarPath);
+        RegressionCorpus.Add(Compile(StatementGrammar), "a = 1;\nb = a;\n", corpusDirectory, new CorpusCaptureOptions { Anonymize = false });
+        return grammarPath;
+    }
+
+    private static CompiledGrammar Compile(string text)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(text));
+    }
+}
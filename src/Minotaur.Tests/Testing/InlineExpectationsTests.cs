@@ -0,0 +1,239 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for inline expectation functionality
+/// </summary>
+public sealed class InlineExpectationsTests : IDisposable
+{
+    private const string SumGrammar = """
+        Grammar: Sums
+        <COMMENT> ::= /\/\/[^\n]*/
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <expr> ";"
+        <expr> ::= <binary_expr> | <primary>
+        <binary_expr> ::= <expr> "+" <primary>
+        <primary> ::= <NUMBER> | <STRING> | <IDENTIFIER>
+        """;
+
+    private readonly CompiledGrammar _grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(SumGrammar));
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"inline_{Guid.NewGuid():N}");
+
+    public InlineExpectationsTests()
+    {
+        Directory.CreateDirectory(_directory);
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_directory, recursive: true);
+    }
+
+    [Fact]
+    public void Check_MetAnnotations_Succeeds()
+    {
+        // Arrange
+        const string input = """
+            a + 1;
+            //^ expect: binary_expr
+            // ^^^ expect: statement
+            b;
+            // expect: statement
+            // no-warning:
+            """;
+
+        // Act
+        var report = Check(input);
+
+        // Assert
+        Assert.True(report.IsSuccess, report.ToString());
+        Assert.Equal(4, report.Expectations.Count);
+        Assert.Equal(input.IndexOf('+'), report.Expectations[0].Start);
+        Assert.Equal(input.IndexOf('+') + 1, report.Expectations[0].End);
+        Assert.Equal(1, report.Expectations[1].Line);
+        Assert.Equal(InlineExpectationKind.NoDiagnostic, report.Expectations[3].Kind);
+    }
+
+    [Theory]
+    [InlineData("a;\nc + ;  // error: E0001\n")]
+    [InlineData("a;\nc + ;\n//  ^ error: E0001\n")]
+    [InlineData("a +\n// error: E0002\n\n")]
+    public void Check_ExpectedError_Succeeds(string input)
+    {
+        // Act
+        var report = Check(input);
+
+        // Assert
+        Assert.True(report.IsSuccess, report.ToString());
+    }
+
+    [Fact]
+    public void Check_UnmetExpectation_ReportsThePointedAtLocation()
+    {
+        // Arrange
+        const string input = "a + 1;\n//^ expect: primary\n";
+
+        // Act
+        var mismatch = Assert.Single(Check(input).Mismatches);
+
+        // Assert
+        Assert.Equal((1, 3), (mismatch.Location.Line, mismatch.Location.Column));
+        Assert.Equal(2, mismatch.AnnotationLine);
+        Assert.StartsWith("1:3: expected primary at 1:3, found ", mismatch.ToString());
+        Assert.Contains("binary_expr", mismatch.Message);
+    }
+
+    [Fact]
+    public void Check_UnexpectedErrorAndMalformedAnnotation_AreReported()
+    {
+        // Arrange
+        const string input = "a + ;\n//  ^ expect:\n";
+
+        // Act
+        var report = Check(input);
+
+        // Assert
+        Assert.Equal(2, report.Mismatches.Count);
+        Assert.Contains(report.Mismatches, m => m.AnnotationLine == null && m.Message.StartsWith("unexpected error E0001") && m.Location.Column == 5);
+        Assert.Contains(report.Mismatches, m => m.AnnotationLine == 2 && m.Message.Contains("gives no node kind"));
+    }
+
+    [Theory]
+    [InlineData("\ta + 1;\n//    ^ expect: binary_expr\n", 4, true)]
+    [InlineData("\ta + 1;\n\t//^ expect: binary_expr\n", 4, true)]
+    [InlineData("\ta + 1;\n\t//^ expect: binary_expr\n", 8, true)]
+    [InlineData("\ta + 1;\n//    ^ expect: binary_expr\n", 8, false)]
+    [InlineData("  \ta + 1;\n//    ^ expect: binary_expr\n", 4, true)]
+    public void Check_Tabs_AdvanceToTheNextTabStop(string input, int tabWidth, bool met)
+    {
+        // Act
+        var report = Check(input, new InlineExpectationOptions { TabWidth = tabWidth, Bless = false });
+
+        // Assert
+        Assert.Equal(met, report.IsSuccess);
+        if (met)
+        {
+            Assert.Equal(input.IndexOf('+'), report.Expectations[0].Start);
+        }
+    }
+
+    [Theory]
+    [InlineData("\"名前\" + x;\n//     ^ expect: binary_expr\n")]
+    [InlineData("\"\U0001F600é\" + x;\n//    ^ expect: binary_expr\n")]
+    [InlineData("\"e\u0301\" + x;\n//  ^ expect: binary_expr\n")]
+    [InlineData("\"Grüße\" + x;\n//      ^ expect: binary_expr\n")]
+    public void Check_MultiByteCharacters_AreLinedUpAsDisplayed(string input)
+    {
+        // Act
+        var report = Check(input);
+
+        // Assert
+        Assert.True(report.IsSuccess, report.ToString());
+        Assert.Equal(input.IndexOf('+'), report.Expectations[0].Start);
+    }
+
+    [Theory]
+    [InlineData("\"名前\"", 2, 1, 1, 2)]
+    [InlineData("\"名前\"", 3, 1, 1, 2)]
+    [InlineData("\"名前\"", 4, 1, 2, 3)]
+    [InlineData("\"名前\"", 5, 2, 2, 4)]
+    [InlineData("\U0001F600éx", 3, 1, 2, 3)]
+    [InlineData("\U0001F600éx", 1, 3, 0, 3)]
+    [InlineData("\tx", 3, 1, 0, 1)]
+    [InlineData("ab", 3, 1, 2, 2)]
+    public void FindDisplayColumns_WideCombinedAndTabCharacters_CoverWholeCharacters(string line, int column, int width, int start, int end)
+    {
+        // Act
+        var found = InlineExpectations.FindDisplayColumns(line, column, width);
+
+        // Assert
+        Assert.Equal<(int, int)?>((start, end), found);
+    }
+
+    [Fact]
+    public void DisplayColumns_GraphemesAndPastTheEnd_AreCountedAsShown()
+    {
+        // Act & Assert
+        Assert.Null(InlineExpectations.FindDisplayColumns("ab", 4, 1));
+        Assert.Equal(7, InlineExpectations.GetDisplayColumn("\t\U0001F600x", 3, 4));
+        Assert.Equal(4, InlineExpectations.GetDisplayColumn("e\u0301\u0301名", 4));
+    }
+
+    [Theory]
+    [InlineData("a + 1;\n//^ expect: primary\nb;  // error: E0001\n", "a + 1;\n//^ expect: binary_expr\nb;\n")]
+    [InlineData("a;\n\tc + ;\n", "a;\n\tc + ;\n\t//  ^ error: E0001\n")]
+    [InlineData("a;\nb +", "a;\nb +\n// ^ error: E0002")]
+    public void Check_Bless_RewritesAnnotationsFromTheParse(string input, string expected)
+    {
+        // Act
+        var blessed = Check(input, new InlineExpectationOptions { Bless = true }).Blessed;
+
+        // Assert
+        Assert.Equal(expected, blessed);
+        Assert.True(Check(blessed!).IsSuccess, Check(blessed!).ToString());
+    }
+
+    [Fact]
+    public void Check_Bless_KeepsNodeExpectationsOfAFailedParse()
+    {
+        // Arrange
+        const string input = "a + 1;\n//^ expect: primary\nc + ;\n";
+
+        // Act
+        var report = Check(input, new InlineExpectationOptions { Bless = true });
+
+        // Assert
+        Assert.Contains(report.Mismatches, m => m.Message.EndsWith("but the parse produced no tree"));
+        Assert.Equal("a + 1;\n//^ expect: primary\nc + ;\n//  ^ error: E0001\n", report.Blessed);
+    }
+
+    [Fact]
+    public void Verify_Directory_BlessesThenPasses()
+    {
+        // Arrange
+        var ok = Path.Combine(_directory, "ok.toy");
+        var bad = Path.Combine(_directory, "bad.toy");
+        File.WriteAllText(ok, "a + b;\r\n//^ expect: primary\r\n// error: E0001\r\n");
+        File.WriteAllText(bad, "a;\r\nc + ;\r\n");
+
+        // Act
+        var failure = Assert.Throws<SnapshotMismatchException>(() => InlineExpectations.Verify(_grammar, _directory, "*.toy", new InlineExpectationOptions { Bless = false }));
+        InlineExpectations.Verify(_grammar, _directory, "*.toy", new InlineExpectationOptions { Bless = true });
+        var count = InlineExpectations.Verify(_grammar, _directory, "*.toy", new InlineExpectationOptions { Bless = false });
+
+        // Assert
+        Assert.StartsWith("2 of 2 annotated files failed", failure.Message);
+        Assert.Contains($"{ok}:1:3: expected primary at 1:3", failure.Message);
+        Assert.Contains($"{ok}:1:1: expected error E0001 at line 1, found none", failure.Message);
+        Assert.Contains($"{bad}:2:5: unexpected error E0001", failure.Message);
+        Assert.Equal(2, count);
+        Assert.Equal("a + b;\r\n//^ expect: binary_expr\r\n", File.ReadAllText(ok));
+        Assert.Equal("a;\r\nc + ;\r\n//  ^ error: E0001\r\n", File.ReadAllText(bad));
+    }
+
+    private InlineExpectationReport Check(string input, InlineExpectationOptions? options = null)
+    {
+        return InlineExpectations.Check(_grammar, input, options ?? new InlineExpectationOptions { Bless = false });
+    }
+}
@@ -0,0 +1,153 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for mutator functionality
+/// </summary>
+public class MutatorTests
+{
+    private const string CalculatorGrammar = """
+        Grammar: Calculator
+        StartRule: program
+        OperatorGroups: "+" "*", "(" "["
+
+        <program> ::= <expr>
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <term> "*" <factor> | <factor>
+        <factor> ::= <NUMBER> | <IDENTIFIER>
+        """;
+
+    [Fact]
+    public void Generate_RustItems_EveryMutantReparsesIntoDifferentTree()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+        var parse = parser.Parse(ReadExample("input.rs"));
+        var original = SExpression.Format(parse.Tree!);
+
+        // Act
+        var mutants = new Mutator(parser).Generate(parse, 50, seed: 7);
+
+        // Assert
+        Assert.NotEmpty(mutants);
+        foreach (var mutant in mutants)
+        {
+            var reparse = parser.Parse(mutant.Text);
+            Assert.True(reparse.IsSuccess, $"{mutant.Mutation}\n{mutant.Text}");
+            Assert.NotEqual(original, SExpression.Format(reparse.Tree!));
+        }
+
+        Assert.Equal(mutants.Count, mutants.Select(m => m.Text).Distinct().Count());
+        Assert.True(mutants.Select(m => m.Mutation.Kind).Distinct().Count() > 1);
+    }
+
+    [Fact]
+    public void Generate_SameSeed_ProducesSameMutants()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+        var parse = parser.Parse(ReadExample("input.rs"));
+        var mutator = new Mutator(parser);
+
+        // Act
+        var first = mutator.Generate(parse, 10, seed: 7);
+        var second = mutator.Generate(parse, 10, seed: 7);
+
+        // Assert
+        Assert.Equal(first.Select(m => m.Text), second.Select(m => m.Text));
+        Assert.Equal(first.Select(m => m.Mutation.ToString()), second.Select(m => m.Mutation.ToString()));
+    }
+
+    [Fact]
+    public void TryApply_EachKind_RewritesTheTargetedAlternative()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+        var parse = parser.Parse("fn find(limit: usize) -> bool {\n    let start = origin();\n    limit > start + 1\n}\n");
+        var mutator = new Mutator(parser);
+        var mutations = mutator.Enumerate(parse);
+
+        // Act
+        string Apply(MutationKind kind, string description) =>
+            mutator.TryApply(parse, mutations.Single(m => m.Kind == kind && m.Description == description))!.Text;
+
+        // Assert
+        Assert.Equal(
+            "fn find(limit: usize) -> bool {\n    let start = origin();\n    limit < start + 1\n}\n",
+            Apply(MutationKind.ReplaceOperator, "replace '>' with '<' in <expr>"));
+        Assert.Equal(
+            "fn find(limit: usize) -> bool {\n    let start = origin();\n    limit > 1 + start\n}\n",
+            Apply(MutationKind.SwapOperands, "swap the operands of <sum>"));
+        Assert.Equal(
+            "fn find(limit: usize) -> bool {\n    let start = origin();\n    let start = origin();\n    limit > start + 1\n}\n",
+            Apply(MutationKind.DuplicateElement, "duplicate the last element of <statements>"));
+        Assert.Equal(
+            "fn find(limit: usize) {\n    let start = origin();\n    limit > start + 1\n}\n",
+            Apply(MutationKind.RemoveOptional, "remove <return_type> from <function>"));
+    }
+
+    [Fact]
+    public void Enumerate_IdenticalOperands_DoesNotSwap()
+    {
+        // Arrange
+        var parser = CreateRustParser();
+        var parse = parser.Parse("fn double(x: i32) -> i32 {\n    x + x\n}\n");
+
+        // Act
+        var mutations = new Mutator(parser).Enumerate(parse);
+
+        // Assert
+        Assert.DoesNotContain(mutations, m => m.Kind == MutationKind.SwapOperands && m.Rule == "sum");
+    }
+
+    [Fact]
+    public void Generate_DeclaredOperatorGroup_ReplacesAcrossRules()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(CalculatorGrammar)));
+        var parse = parser.Parse("a + b");
+        var mutator = new Mutator(parser);
+
+        // Act
+        var mutants = mutator.Generate(parse, 10, seed: 1);
+
+        // Assert
+        Assert.Contains(mutator.OperatorGroups, g => g.SequenceEqual(new[] { "+", "*" }));
+        Assert.Contains(mutants, m => m.Text == "a * b" && m.Mutation.Kind == MutationKind.ReplaceOperator);
+        Assert.Contains(mutants, m => m.Text == "b + a" && m.Mutation.Kind == MutationKind.SwapOperands);
+        Assert.DoesNotContain(mutants, m => m.Text.Contains('['));
+    }
+
+    private static GeneralizedParser CreateRustParser()
+    {
+        return new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(ReadExample("rust_items.grammar"))));
+    }
+
+    private static string ReadExample(string file, [CallerFilePath] string path = "")
+    {
+        var text = File.ReadAllText(Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "rust_items", file));
+        return text.Replace("\r\n", "\n");
+    }
+}
@@ -0,0 +1,168 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for regression corpus and input anonymization functionality
+/// </summary>
+public sealed class RegressionCorpusTests : IDisposable
+{
+    private const string AssignmentGrammar = """
+        <program> ::= <statement> | <program> <statement>
+        <statement> ::= <IDENTIFIER> "=" <value> ";"
+        <value> ::= <IDENTIFIER> | <STRING> | <NUMBER>
+        """;
+
+    private readonly string _corpus = Path.Combine(Path.GetTempPath(), $"corpus_{Guid.NewGuid():N}");
+
+    public void Dispose()
+    {
+        if (Directory.Exists(_corpus))
+        {
+            Directory.Delete(_corpus, recursive: true);
+        }
+    }
+
+    [Fact]
+    public void Anonymize_RenamesIdentifiersConsistentlyAndScrubsStrings()
+    {
+        // Arrange
+        var grammar = Compile(AssignmentGrammar);
+
+        // Act
+        var result = InputAnonymizer.Anonymize("secret  =  \"password\";\nother = secret;\n", grammar);
+
+        // Assert
+        Assert.Equal("a = \"xxxxxxxx\";\nb = a;\n", result.Text);
+        Assert.Equal(2, result.RenamedIdentifiers);
+        Assert.Equal(1, result.ScrubbedStrings);
+    }
+
+    [Fact]
+    public void Anonymize_FailingInput_KeepsDiagnosticsAtSameTokens()
+    {
+        // Arrange
+        var grammar = Compile(AssignmentGrammar);
+        var parser = new GeneralizedParser(grammar);
+        var input = "count = 1;\ntotal = ;\n";
+
+        // Act
+        var result = InputAnonymizer.Anonymize(input, grammar);
+
+        // Assert
+        Assert.Equal("a = 1;\nb = ;\n", result.Text);
+        Assert.Equal(InputAnonymizer.Shape(parser.Parse(input)), InputAnonymizer.Shape(parser.Parse(result.Text)));
+    }
+
+    [Fact]
+    public void Anonymize_ReplacementMatchingLiteral_IsSkipped()
+    {
+        // Arrange
+        var grammar = Compile("<program> ::= \"a\" <IDENTIFIER>");
+
+        // Act
+        var result = InputAnonymizer.Anonymize("a name", grammar);
+
+        // Assert
+        Assert.Equal("a A", result.Text);
+    }
+
+    [Fact]
+    public void Add_FailingInput_StoresReducedAnonymizedCaseWithDiagnostics()
+    {
+        // Arrange
+        var grammar = Compile(AssignmentGrammar);
+
+        // Act
+        var corpusCase = RegressionCorpus.Add(grammar, "alpha = 1;\nbeta = = \"token\";\n", _corpus);
+        var again = RegressionCorpus.Add(grammar, "alpha = 1;\nbeta = = \"token\";\n", _corpus);
+
+        // Assert
+        var input = File.ReadAllText(corpusCase.InputPath);
+        Assert.True(corpusCase.ExpectsDiagnostics);
+        Assert.Equal(corpusCase, again);
+        Assert.DoesNotContain("alpha", input);
+        Assert.DoesNotContain("beta", input);
+        Assert.DoesNotContain("token", input);
+        Assert.True(input.Length < "alpha = 1;\nbeta = = \"token\";\n".Length);
+        Assert.StartsWith("error E0001", File.ReadAllText(corpusCase.SnapshotPath));
+        Assert.Single(RegressionCorpus.GetCases(_corpus));
+        Assert.Equal(1, RegressionCorpus.Verify(grammar, _corpus));
+    }
+
+    [Fact]
+    public void Add_ParsingInput_StoresTree()
+    {
+        // Arrange
+        var grammar = Compile(AssignmentGrammar);
+
+        // Act
+        var corpusCase = RegressionCorpus.Add(grammar, "x = 1;", _corpus);
+
+        // Assert
+        Assert.False(corpusCase.ExpectsDiagnostics);
+        Assert.Equal(corpusCase.InputPath + ParseSnapshot.TreeExtension, corpusCase.SnapshotPath);
+        Assert.Equal(1, RegressionCorpus.Verify(grammar, _corpus));
+    }
+
+    [Fact]
+    public void Verify_GrammarNowAcceptsRecordedFailure_Throws()
+    {
+        // Arrange
+        RegressionCorpus.Add(Compile(AssignmentGrammar), "x = ;", _corpus, new CorpusCaptureOptions { Reduce = false });
+        var relaxed = Compile(AssignmentGrammar + "\n<statement> ::= <IDENTIFIER> \"=\" \";\"");
+
+        // Act
+        var ex = Assert.Throws<SnapshotMismatchException>(() => RegressionCorpus.Verify(relaxed, _corpus));
+
+        // Assert
+        Assert.Contains("1 of 1 corpus cases failed", ex.Message);
+    }
+
+    [Fact]
+    public async Task Verify_ExampleCorpora_MatchSnapshots()
+    {
+        var examples = Path.Combine(TestDirectory(), "..", "..", "..", "examples");
+        foreach (var corpus in Directory.EnumerateDirectories(examples, RegressionCorpus.DirectoryName, SearchOption.AllDirectories))
+        {
+            foreach (var directory in Directory.EnumerateDirectories(corpus))
+            {
+                var grammarPath = Path.Combine(corpus, "..", Path.GetFileName(directory) + ".grammar");
+                var grammar = CompiledGrammar.Compile(await new GrammarFileReader().ReadFileAsync(grammarPath));
+
+                Assert.True(RegressionCorpus.Verify(grammar, directory) > 0);
+            }
+        }
+    }
+
+    private static CompiledGrammar Compile(string text)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(text));
+    }
+
+    private static string TestDirectory([CallerFilePath] string path = "")
+    {
+        return Path.GetDirectoryName(path)!;
+    }
+}
@@ -0,0 +1,122 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for round-trip property functionality
+/// </summary>
+public class RoundTripPropertyTests
+{
+    private const string ExpressionGrammar = """
+        <program> ::= <expr>
+        <expr> ::= <expr> "+" <term> | <term>
+        <term> ::= <NUMBER> | <IDENTIFIER> | <group>
+        <group> ::= "(" <expr> ")"
+        """;
+
+    [Fact]
+    public void Check_DefaultPrinter_RoundTrips()
+    {
+        // Arrange
+        var grammar = Compile(ExpressionGrammar);
+
+        // Act
+        var result = RoundTripProperty.Check(grammar, 50, seed: 7);
+
+        // Assert
+        Assert.True(result.IsSuccess, result.Failure?.ToString());
+        Assert.Equal(50, result.Iterations);
+    }
+
+    [Fact]
+    public void Generate_SameSeed_ProducesSameSentence()
+    {
+        // Arrange
+        var generator = new SentenceGenerator(Compile(ExpressionGrammar));
+
+        // Act
+        var first = generator.Generate(new Random(42));
+        var second = generator.Generate(new Random(42));
+
+        // Assert
+        Assert.Equal(first, second);
+        Assert.True(new GeneralizedParser(Compile(ExpressionGrammar)).Parse(first).IsSuccess);
+    }
+
+    [Fact]
+    public void Check_PrinterDroppingParentheses_ReportsReproducibleSeedAndShrinks()
+    {
+        // Arrange
+        var grammar = Compile(ExpressionGrammar);
+        var options = new RoundTripOptions { Printer = DropParentheses };
+
+        // Act
+        var result = RoundTripProperty.Check(grammar, 200, seed: 1, options);
+        var rerun = RoundTripProperty.Check(grammar, 1, result.Failure!.Seed, options);
+
+        // Assert
+        var failure = result.Failure;
+        Assert.Equal(RoundTripStage.Compare, failure.Stage);
+        Assert.Contains($"seed {failure.Seed}", failure.ToString());
+        Assert.Equal(failure.Input, rerun.Failure!.Input);
+        Assert.NotNull(failure.ReducedInput);
+        Assert.True(failure.ReducedInput!.Length <= failure.Input.Length);
+        Assert.Contains("(", failure.ReducedInput);
+    }
+
+    [Fact]
+    public void Check_ExcludedRule_IsNeverGenerated()
+    {
+        // Arrange
+        var grammar = Compile(ExpressionGrammar);
+        var options = new RoundTripOptions { Printer = DropParentheses, ExcludedRules = { "group" } };
+
+        // Act
+        var result = RoundTripProperty.Check(grammar, 200, seed: 1, options);
+
+        // Assert
+        Assert.True(result.IsSuccess, result.Failure?.ToString());
+    }
+
+    [Fact]
+    public void Generate_StartRuleOnlyReachesExcludedRules_Throws()
+    {
+        // Arrange
+        var generator = new SentenceGenerator(Compile(ExpressionGrammar), new SentenceGeneratorOptions { ExcludedRules = { "term" } });
+
+        // Act & Assert
+        Assert.False(generator.CanGenerate("program"));
+        Assert.Throws<InvalidOperationException>(() => generator.Generate(new Random(0)));
+    }
+
+    private static string DropParentheses(CognitiveGraphNode tree)
+    {
+        return RoundTripProperty.Print(tree).Replace("( ", string.Empty).Replace(" )", string.Empty);
+    }
+
+    private static CompiledGrammar Compile(string text)
+    {
+        return CompiledGrammar.Compile(new GrammarFileReader().Read(text));
+    }
+}
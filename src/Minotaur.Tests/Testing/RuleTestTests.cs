@@ -0,0 +1,119 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for single-rule test functionality
+/// </summary>
+public class RuleTestTests
+{
+    private static readonly CompiledGrammar SumGrammar = CompiledGrammar.Compile(new GrammarFileReader().Read("""
+        <program> ::= <sum> ";"
+        <sum> ::= <sum> "+" <term> | <term>
+        <term> ::= <NUMBER> | "(" <sum> ")"
+        """));
+
+    [Fact]
+    public void Run_CasesAsExpected_Succeeds()
+    {
+        // Arrange
+        var test = RuleTest.For(SumGrammar, "sum")
+            .Ok("1", "1 + (2 + 3)")
+            .OkWithTree("1 + 2", """
+                (sum
+                  (sum (term (NUMBER "1")))
+                  "+"
+                  (term (NUMBER "2")))
+                """)
+            .Err("1 +", "1;");
+
+        // Act
+        var report = test.Run();
+
+        // Assert
+        Assert.True(report.IsSuccess);
+        Assert.Equal(5, report.CaseCount);
+    }
+
+    [Fact]
+    public void Run_OkCaseWithTrailingInput_ShowsTokensAndPartialTree()
+    {
+        // Arrange
+        var test = RuleTest.For(SumGrammar, "sum").Ok("1 + 2 +");
+
+        // Act
+        var report = test.Run();
+
+        // Assert
+        var failure = Assert.Single(report.Failures);
+        Assert.True(failure.ExpectedSuccess);
+        Assert.Equal("expected to parse, but did not", failure.Reason);
+        Assert.Contains("NUMBER \"1\" at 0", failure.Details);
+        Assert.Contains("\"+\" \"+\" at 6", failure.Details);
+        Assert.Contains("tree of the first 5 of 7 characters", failure.Details);
+        Assert.Contains("(NUMBER \"2\")", failure.Details);
+    }
+
+    [Fact]
+    public void Run_WrongTreeOrUnexpectedSuccess_ReportsEachFailure()
+    {
+        // Arrange
+        var test = RuleTest.For(SumGrammar, "term")
+            .OkWithTree("(1)", """(term "(" (sum (term (NUMBER "2"))) ")")""")
+            .Err("7");
+
+        // Act
+        var report = test.Run();
+
+        // Assert
+        Assert.Equal(2, report.Failures.Count);
+        Assert.Contains("parsed to (term \"(\" (sum (term (NUMBER \"1\"))) \")\")", report.Failures[0].Reason);
+        Assert.Equal("expected not to parse, but did", report.Failures[1].Reason);
+        Assert.StartsWith("<term> of ", report.ToString());
+        Assert.Contains("0 of 2 cases passed", report.ToString());
+    }
+
+    [Fact]
+    public void Check_FailingCase_ThrowsWithReport()
+    {
+        // Arrange
+        var ok = new[] { "1", "+" };
+
+        // Act
+        var exception = Assert.Throws<RuleTestException>(() => RuleTest.Check(SumGrammar, "term", ok: ok));
+
+        // Assert
+        Assert.Equal("+", Assert.Single(exception.Report.Failures).Input);
+        Assert.Contains("tree: none", exception.Message);
+    }
+
+    [Fact]
+    public void For_UndefinedRule_Throws()
+    {
+        // Arrange
+        const string rule = "product";
+
+        // Act & Assert
+        Assert.Throws<ArgumentException>(() => RuleTest.For(SumGrammar, rule));
+    }
+}
@@ -0,0 +1,98 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// A fact that only runs when syn-dump is available, named by the MINOTAUR_SYN_DUMP environment variable.
+/// </summary>
+public sealed class SynFactAttribute : FactAttribute
+{
+    public SynFactAttribute()
+    {
+        if (string.IsNullOrEmpty(Environment.GetEnvironmentVariable(ReferenceLanguages.SynCommandVariable)))
+        {
+            Skip = $"Set {ReferenceLanguages.SynCommandVariable} to the syn-dump command to compare the Rust grammar with syn";
+        }
+    }
+}
+
+/// <summary>
+/// Tests comparing the Rust grammar with syn. Skipped unless MINOTAUR_SYN_DUMP is set, for example to
+/// "cargo run --quiet --release --locked --manifest-path tools/syn-dump/Cargo.toml --".
+/// </summary>
+public class RustDifferentialTests
+{
+    [SynFact]
+    public void SynDump_SummarizesCorpus()
+    {
+        // Arrange
+        var reference = ReferenceLanguages.Rust.CreateReference(RepositoryPath("."));
+        var path = Path.Combine(Corpus(), "items.rs");
+
+        // Act
+        var summary = reference.Summarize(path, File.ReadAllText(path));
+
+        // Assert
+        Assert.Null(summary.Error);
+        Assert.Equal(
+            new[] { "use", "const", "struct", "enum", "fn" },
+            summary.Items.Select(i => i.Kind));
+        Assert.Equal(new SummaryItem("struct", "Point", 6, 5), summary.Items[2]);
+        Assert.Contains("origin", summary.Identifiers);
+    }
+
+    [SynFact]
+    public void RustGrammar_AgreesWithSyn()
+    {
+        // Arrange
+        var language = ReferenceLanguages.Rust;
+        var grammar = new GrammarFileReader().Read(File.ReadAllText(RepositoryPath(language.GrammarFile)));
+        var harness = new DifferentialHarness(
+            language.CreateReference(RepositoryPath(".")),
+            new GrammarFrontend(CompiledGrammar.Compile(grammar)),
+            DifferenceAllowlist.Load(RepositoryPath(language.AllowlistFile!)));
+
+        // Act
+        var report = harness.Run(Corpus(), language.SearchPattern);
+
+        // Assert
+        Assert.Equal(2, report.Files.Count);
+        Assert.True(report.Mismatches.Count == 0, string.Join("\n", report.Mismatches.Select(m => m.Dump())));
+    }
+
+    internal static string RepositoryPath(string relativePath)
+    {
+        return Path.GetFullPath(Path.Combine(TestDirectory(), "..", "..", "..", relativePath));
+    }
+
+    private static string Corpus()
+    {
+        return RepositoryPath(Path.Combine("src", "Minotaur.Tests", "Testing", "Fixtures", "rust-corpus"));
+    }
+
+    private static string TestDirectory([CallerFilePath] string path = "")
+    {
+        return Path.GetDirectoryName(path)!;
+    }
+}
@@ -0,0 +1,161 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Testing;
+
+/// <summary>
+/// Tests for snapshot testing functionality
+/// </summary>
+public sealed class SnapshotTests : IDisposable
+{
+    private readonly string _directory = Path.Combine(Path.GetTempPath(), $"snapshots_{Guid.NewGuid():N}");
+
+    public SnapshotTests()
+    {
+        Directory.CreateDirectory(_directory);
+    }
+
+    public void Dispose()
+    {
+        Directory.Delete(_directory, recursive: true);
+    }
+
+    [Fact]
+    public void Diff_ChangedLine_ShowsHunkWithContext()
+    {
+        // Arrange
+        var expected = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        var actual = "a\nb\nc\nd\nE\nf\ng\nh\n";
+
+        // Act
+        var diff = Snapshot.Diff(expected, actual);
+
+        // Assert
+        Assert.Equal("--- expected\n+++ actual\n@@ -2,7 +2,7 @@\n  b\n  c\n  d\n- e\n+ E\n  f\n  g\n  h\n", diff);
+    }
+
+    [Fact]
+    public void Diff_DistantChanges_ShowsSeparateHunks()
+    {
+        // Arrange
+        var expected = string.Join("\n", Enumerable.Range(1, 20)) + "\n";
+        var actual = expected.Replace("\n2\n", "\ntwo\n").Replace("\n19\n", "\nnineteen\n");
+
+        // Act
+        var diff = Snapshot.Diff(expected, actual);
+
+        // Assert
+        Assert.Equal(2, diff.Split('\n').Count(line => line.StartsWith("@@")));
+        Assert.Contains("- 2\n+ two\n", diff);
+        Assert.Contains("- 19\n+ nineteen\n", diff);
+        Assert.DoesNotContain("  10\n", diff);
+    }
+
+    [Fact]
+    public void Diff_EqualTextWithDifferentLineEndings_IsEmpty()
+    {
+        // Act
+        var diff = Snapshot.Diff("a\r\nb\r\n", "a\nb\n");
+
+        // Assert
+        Assert.Equal(string.Empty, diff);
+    }
+
+    [Fact]
+    public void AssertMatches_MissingSnapshot_ExplainsHowToCreateIt()
+    {
+        // Arrange
+        var path = Path.Combine(_directory, "missing.snap");
+
+        // Act
+        var ex = Assert.Throws<SnapshotMismatchException>(() => Snapshot.AssertMatches("x\n", path));
+
+        // Assert
+        Assert.Equal(path, ex.SnapshotPath);
+        Assert.Contains($"{Snapshot.UpdateVariable}=1", ex.Message);
+        Assert.Contains("+ x", ex.Message);
+    }
+
+    [Fact]
+    public void AssertMatches_DifferentText_ReportsDiff()
+    {
+        // Arrange
+        var path = Path.Combine(_directory, "value.snap");
+        File.WriteAllText(path, "one\r\ntwo\r\n");
+
+        // Act
+        Snapshot.AssertMatches("one\ntwo\n", path);
+        var ex = Assert.Throws<SnapshotMismatchException>(() => Snapshot.AssertMatches("one\nthree\n", path));
+
+        // Assert
+        Assert.Contains("- two\n+ three\n", ex.Message);
+    }
+
+    [Fact]
+    public void Format_TerminalsAndRules_UsesCanonicalForm()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("<program> ::= <call>\n<call> ::= <IDENTIFIER> \"(\" <STRING> \")\"");
+        var result = new GeneralizedParser(CompiledGrammar.Compile(grammar)).Parse("print(\"a\\tb\")");
+
+        // Act
+        var text = SExpression.Format(result.Tree!);
+
+        // Assert
+        Assert.Equal("(program\n  (call\n    (IDENTIFIER \"print\")\n    \"(\"\n    (STRING \"\\\"a\\\\tb\\\"\")\n    \")\"))\n", text);
+    }
+
+    [Fact]
+    public void AssertParse_ContainerGrammar_ReportsDiagnosticsWhenParseFails()
+    {
+        // Arrange
+        var grammar = new GrammarFileReader().Read("Grammar: Pairs\n<program> ::= <IDENTIFIER> \"=\" <NUMBER>");
+        var container = GrammarContainer.Load(new[] { grammar });
+        var input = Path.Combine(_directory, "pair.txt");
+        File.WriteAllText(input, "x = y\n");
+
+        // Act
+        var ex = Assert.Throws<SnapshotMismatchException>(() => ParseSnapshot.AssertParse(container, "Pairs", input));
+
+        // Assert
+        Assert.Equal(input + ParseSnapshot.TreeExtension, ex.SnapshotPath);
+        Assert.Contains("error E0001 1:5: Unexpected IDENTIFIER 'y'; expected NUMBER", ex.Message);
+    }
+
+    [Fact]
+    public async Task AssertParse_DanglingElseExample_MatchesSnapshot()
+    {
+        await ParseSnapshot.AssertParseAsync(ExamplePath("dangling_else.grammar"), ExamplePath("input.txt"));
+    }
+
+    [Fact]
+    public async Task AssertExpectedDiagnostics_MissingStatementExample_MeetsExpectations()
+    {
+        await ParseSnapshot.AssertExpectedDiagnosticsAsync(ExamplePath("dangling_else.grammar"), ExamplePath("missing_statement.txt"));
+    }
+
+    private static string ExamplePath(string file, [CallerFilePath] string path = "")
+    {
+        return Path.Combine(Path.GetDirectoryName(path)!, "..", "..", "..", "examples", "programming", "dangling_else", file);
+    }
+}
@@ -0,0 +1,49 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Tests.Parser;
+using Minotaur.Visualization;
+
+namespace Minotaur.Tests.Visualization;
+
+/// <summary>
+/// Tests for GrammarDocsHtmlRenderer functionality
+/// </summary>
+public class GrammarDocsHtmlRendererTests
+{
+    [Fact]
+    public void Render_DocumentedGrammar_HasOneSectionPerSymbol()
+    {
+        // Arrange
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(GrammarDocsTests.StatementGrammar));
+
+        // Act
+        var html = new GrammarDocsHtmlRenderer().Render(grammar);
+
+        // Assert
+        Assert.StartsWith("<!DOCTYPE html>", html);
+        Assert.Contains("<title>statements reference</title>", html);
+        Assert.Contains("<h2 id=\"rule-if_statement\"><span class=\"rule\">&lt;if_statement&gt;</span></h2>", html);
+        Assert.Contains("<h2 id=\"token-NUMBER\"><span class=\"token\">NUMBER</span></h2>", html);
+        Assert.Contains("<p>Runs a statement when the condition holds.</p>", html);
+        Assert.Contains("<pre>while (x) if (y) z;</pre>", html);
+        Assert.Equal(3, html.Split("<h2 ").Length - 1);
+    }
+}
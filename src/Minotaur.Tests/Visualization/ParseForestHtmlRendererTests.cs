@@ -0,0 +1,122 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using System.Text.RegularExpressions;
+using Xunit;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Testing;
+
+namespace Minotaur.Tests.Visualization;
+
+/// <summary>
+/// Tests for ParseForestHtmlRenderer functionality
+/// </summary>
+public class ParseForestHtmlRendererTests
+{
+    [Fact]
+    public async Task Render_DanglingElseExample_MatchesSnapshot()
+    {
+        // Arrange
+        var result = await ParseDanglingElseExampleAsync();
+        var snapshotPath = Path.Combine(TestDirectory(), "Snapshots", "dangling_else_forest.html");
+
+        // Act
+        var html = Normalize(result.ForestHtml());
+
+        // Assert
+        Snapshot.AssertMatches(html, snapshotPath);
+    }
+
+    [Fact]
+    public async Task Render_DanglingElseExample_RendersSharedNodesOnce()
+    {
+        // Arrange
+        var result = await ParseDanglingElseExampleAsync();
+        var forest = result.Forest!;
+
+        // Act
+        var html = result.ForestHtml();
+
+        // Assert
+        Assert.Equal(1, forest.AmbiguityCount);
+        foreach (var node in forest.Nodes)
+        {
+            Assert.Equal(1, Regex.Matches(html, $"id=\"n{node.Id}\"").Count);
+        }
+
+        var condition = forest.Nodes.Single(n => n.RuleName == "condition" && n.Start == 1);
+        Assert.Equal(2, Regex.Matches(html, $"href=\"#n{condition.Id}\">&lt;condition&gt;").Count);
+    }
+
+    [Fact]
+    public async Task Render_DanglingElseExample_HighlightsDifferingRegion()
+    {
+        // Arrange
+        var result = await ParseDanglingElseExampleAsync();
+
+        // Act
+        var html = result.ForestHtml();
+
+        // Assert
+        Assert.Contains("<pre class=\"source\">if a then <mark>if b then x else y</mark>\n</pre>", html.ReplaceLineEndings("\n"));
+        Assert.Equal(2, Regex.Matches(html, "<input type=\"radio\"").Count);
+    }
+
+    [Fact]
+    public void Render_EscapesSourceAndGrammarText()
+    {
+        // Arrange
+        var reader = new GrammarFileReader();
+        var grammar = reader.Read("<program> ::= <item> | <program> <item>\n<item> ::= \"<\" | \"&\"");
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(grammar));
+        var result = parser.Parse("< & <");
+
+        // Act
+        var html = result.ForestHtml();
+
+        // Assert
+        Assert.True(result.IsSuccess);
+        Assert.Contains("<pre class=\"source\">&lt; &amp; &lt;</pre>", html);
+        Assert.Contains("&quot;&lt;&quot;", html);
+    }
+
+    private static async Task<ParseResult> ParseDanglingElseExampleAsync()
+    {
+        var exampleDirectory = Path.Combine(TestDirectory(), "..", "..", "..", "examples", "programming", "dangling_else");
+        var grammar = await new GrammarFileReader().ReadFileAsync(Path.Combine(exampleDirectory, "dangling_else.grammar"));
+        var input = await File.ReadAllTextAsync(Path.Combine(exampleDirectory, "input.txt"));
+
+        var result = new GeneralizedParser(CompiledGrammar.Compile(grammar)).Parse(input);
+        Assert.True(result.IsSuccess);
+        return result;
+    }
+
+    // Node and tab ids depend on forest numbering, so they are replaced before comparing.
+    private static string Normalize(string html)
+    {
+        html = html.ReplaceLineEndings("\n");
+        html = Regex.Replace(html, "((?:id|name|for|href)=\"#?[nt])\\d+(?:-\\d+)?", "$1*");
+        return Regex.Replace(html, "#\\d+(?=</a>)", "#*");
+    }
+
+    private static string TestDirectory([CallerFilePath] string path = "")
+    {
+        return Path.GetDirectoryName(path)!;
+    }
+}
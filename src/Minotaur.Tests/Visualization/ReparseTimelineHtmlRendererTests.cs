@@ -0,0 +1,71 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+using Xunit;
+using Minotaur.Parser;
+using Minotaur.Visualization;
+
+namespace Minotaur.Tests.Visualization;
+
+/// <summary>
+/// Tests for ReparseTimelineHtmlRenderer functionality
+/// </summary>
+public class ReparseTimelineHtmlRendererTests
+{
+    [Fact]
+    public void Render_History_WritesOneRowPerEdit()
+    {
+        // Arrange
+        var history = new[]
+        {
+            CreateStats(1, new TextEdit(10, 0, "<x>"), damaged: new TextRange(10, 3), relexed: new TextRange(8, 6), length: 100),
+            CreateStats(2, TextEdit.Delete(0, 50), damaged: new TextRange(0, 0), relexed: new TextRange(0, 4), length: 50)
+        };
+
+        // Act
+        var html = new ReparseTimelineHtmlRenderer().Render(history, "sample.txt");
+
+        // Assert
+        Assert.Equal(2, Regex.Matches(html, "<tr><td>").Count);
+        Assert.Contains("insert &quot;&lt;x&gt;&quot; at 10", html);
+        Assert.Contains("<div class=\"map\" style=\"width: 100.00%\"", html);
+        Assert.Contains("<div class=\"map\" style=\"width: 50.00%\"", html);
+        Assert.Contains("<span class=\"damaged\" style=\"left: 10.00%; width: 3.00%\">", html);
+        Assert.Contains("<span class=\"relexed\" style=\"left: 0.00%; width: 8.00%\">", html);
+        Assert.Contains("<td>75.0%</td>", html);
+    }
+
+    private static ReparseStats CreateStats(int version, TextEdit edit, TextRange damaged, TextRange relexed, int length)
+    {
+        return new ReparseStats
+        {
+            Version = version,
+            Edit = edit,
+            DamagedRange = damaged,
+            RelexedRange = relexed,
+            RelexedTokenCount = 2,
+            TokenCount = 20,
+            ReusedNodeCount = 30,
+            RebuiltNodeCount = 10,
+            DocumentLength = length,
+            IsSuccess = true,
+            LexTime = TimeSpan.FromMilliseconds(0.5),
+            ParseTime = TimeSpan.FromMilliseconds(2)
+        };
+    }
+}
@@ -0,0 +1,82 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Xunit;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+using Minotaur.Tests.Diagnostics;
+using Minotaur.Visualization;
+
+namespace Minotaur.Tests.Visualization;
+
+/// <summary>
+/// Tests for SemanticDiffRenderer functionality
+/// </summary>
+public class SemanticDiffRendererTests
+{
+    [Fact]
+    public void RenderTerminal_MovedFunction_MarksBothEnds()
+    {
+        // Arrange
+        var parser = SemanticDiffTests.CreateRustParser();
+        var diff = SemanticDiff.Create(parser.Parse(SemanticDiffTests.OldRust), parser.Parse(SemanticDiffTests.NewRust));
+        var renderer = new SemanticDiffRenderer();
+
+        // Act
+        var plain = renderer.RenderTerminal(diff, context: 1, color: false);
+        var colored = renderer.RenderTerminal(diff, context: 1);
+
+        // Assert
+        Assert.StartsWith("@@ -4 +4 @@\n", plain);
+        Assert.Contains("<<< <item> moved to line 13\n<fn unused(p: Point) -> i32 {\n<    p.x + p.y\n<}\n", plain);
+        Assert.Contains(">>> <item> moved from line 5\n>fn unused(p: Point) -> i32 {\n>    p.x + p.y + 1\n>}\n", plain);
+        Assert.DoesNotContain("let start", plain);
+        Assert.Contains("\u001b[36m>    p.x + p.y \u001b[7m+\u001b[27m \u001b[7m1\u001b[27m\u001b[0m\n", colored);
+    }
+
+    [Fact]
+    public void RenderTerminal_MultilineString_MarksEveryPhysicalLine()
+    {
+        // Arrange
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(SemanticDiffTests.BindingGrammar)));
+        var diff = SemanticDiff.Create(parser.Parse("a = \"first\nb = 2;\";\n"), parser.Parse("a = \"first\nb = 3;\";\n"));
+
+        // Act
+        var text = new SemanticDiffRenderer().RenderTerminal(diff, color: false);
+
+        // Assert
+        Assert.Equal("@@ -1 +1 @@\n-a = \"first\n-b = 2;\";\n+a = \"first\n+b = 3;\";\n \n", text);
+    }
+
+    [Fact]
+    public void RenderHtml_MovedFunction_LinksMoveEnds()
+    {
+        // Arrange
+        var parser = SemanticDiffTests.CreateRustParser();
+        var diff = SemanticDiff.Create(parser.Parse(SemanticDiffTests.OldRust), parser.Parse(SemanticDiffTests.NewRust));
+
+        // Act
+        var html = new SemanticDiffRenderer().RenderHtml(diff, "lib.rs");
+
+        // Assert
+        Assert.Contains("<title>Semantic diff: lib.rs</title>", html);
+        Assert.Contains("<div class=\"move\" id=\"move-0-from\"><a href=\"#move-0-to\">&lt;&lt;&lt; &lt;item&gt; moved to line 13</a></div>", html);
+        Assert.Contains("<div class=\"move\" id=\"move-0-to\"><a href=\"#move-0-from\">&gt;&gt;&gt; &lt;item&gt; moved from line 5</a></div>", html);
+        Assert.Contains("<span class=\"marker\">&gt;</span><span class=\"text\">    p.x + p.y <span class=\"change\">+</span> <span class=\"change\">1</span></span>", html);
+    }
+}
@@ -0,0 +1,218 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Analysis.Passes;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis;
+
+/// <summary>
+/// How a diagnostic of a changed file relates to the file's previous version.
+/// </summary>
+public enum DiagnosticChange
+{
+    /// <summary>
+    /// The old version did not have the diagnostic.
+    /// </summary>
+    New,
+
+    /// <summary>
+    /// The old version had the diagnostic too, possibly on another line.
+    /// </summary>
+    PreExisting,
+
+    /// <summary>
+    /// Only the old version had the diagnostic.
+    /// </summary>
+    Fixed
+}
+
+/// <summary>
+/// A diagnostic classified against the previous version of its file.
+/// </summary>
+/// <param name="Diagnostic">The diagnostic; for fixed ones, as reported on the old version.</param>
+/// <param name="Change">How it relates to the previous version.</param>
+/// <param name="Fingerprint">Its fingerprint (see <see cref="DiagnosticFingerprint"/>).</param>
+/// <param name="IsOnChangedLine">Whether it touches a changed line of the new version, or has no location.</param>
+public sealed record ClassifiedDiagnostic(Diagnostic Diagnostic, DiagnosticChange Change, string Fingerprint, bool IsOnChangedLine);
+
+/// <summary>
+/// Options for <see cref="DiffAnalysis"/>.
+/// </summary>
+public sealed class DiffAnalysisOptions
+{
+    /// <summary>
+    /// Gets or sets how many lines around each change count as changed.
+    /// </summary>
+    public int ContextLines { get; set; }
+
+    /// <summary>
+    /// Gets or sets the options both versions are parsed with.
+    /// </summary>
+    public ParseOptions? ParseOptions { get; set; }
+
+    /// <summary>
+    /// Gets or sets what is checked on each version's parse, or null for the parse diagnostics and the built-in
+    /// analysis passes.
+    /// </summary>
+    public Func<ParseResult, IEnumerable<Diagnostic>>? Check { get; set; }
+}
+
+/// <summary>
+/// Compares the diagnostics of two versions of a file so a review reports only the problems a change introduced.
+/// Diagnostics are matched by fingerprint, which survives lines moving elsewhere in the file, and reported only
+/// when they are new and on a changed line.
+/// </summary>
+public sealed class DiffAnalysis
+{
+    private DiffAnalysis(List<ClassifiedDiagnostic> diagnostics, ChangedLines changedLines, int contextLines)
+    {
+        Diagnostics = diagnostics;
+        ChangedLines = changedLines;
+        Reported = diagnostics
+            .Where(d => d.Change == DiagnosticChange.New && d.IsOnChangedLine)
+            .Select(d => d.Diagnostic)
+            .ToList();
+        ContextLines = contextLines;
+    }
+
+    /// <summary>
+    /// Gets the diagnostics of the new version followed by the fixed ones of the old version.
+    /// </summary>
+    public IReadOnlyList<ClassifiedDiagnostic> Diagnostics { get; }
+
+    /// <summary>
+    /// Gets the lines the change added or replaced.
+    /// </summary>
+    public ChangedLines ChangedLines { get; }
+
+    /// <summary>
+    /// Gets how many lines around each change counted as changed.
+    /// </summary>
+    public int ContextLines { get; }
+
+    /// <summary>
+    /// Gets the new diagnostics on changed lines: the ones a review of the change should report.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Reported { get; }
+
+    /// <summary>
+    /// Gets the diagnostics with a classification.
+    /// </summary>
+    /// <param name="change">The classification.</param>
+    /// <returns>The diagnostics, in order.</returns>
+    public IEnumerable<ClassifiedDiagnostic> Get(DiagnosticChange change)
+    {
+        return Diagnostics.Where(d => d.Change == change);
+    }
+
+    /// <summary>
+    /// Parses and checks two versions of a file.
+    /// </summary>
+    /// <param name="parser">The parser of the file's language.</param>
+    /// <param name="oldText">The old version.</param>
+    /// <param name="newText">The new version.</param>
+    /// <param name="options">Optional options.</param>
+    /// <returns>The classified diagnostics.</returns>
+    public static DiffAnalysis Analyze(GeneralizedParser parser, string oldText, string newText, DiffAnalysisOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+        ArgumentNullException.ThrowIfNull(oldText);
+        ArgumentNullException.ThrowIfNull(newText);
+
+        return Analyze(parser, oldText, newText, ChangedLines.Between(oldText, newText), options ?? new DiffAnalysisOptions());
+    }
+
+    /// <summary>
+    /// Parses and checks the new version of a file and the old version a patch recovers from it.
+    /// </summary>
+    /// <param name="parser">The parser of the file's language.</param>
+    /// <param name="patch">The file's changes.</param>
+    /// <param name="newText">The new version.</param>
+    /// <param name="options">Optional options.</param>
+    /// <returns>The classified diagnostics.</returns>
+    /// <exception cref="InvalidOperationException">The patch does not match the new version.</exception>
+    public static DiffAnalysis Analyze(GeneralizedParser parser, FileDiff patch, string newText, DiffAnalysisOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+        ArgumentNullException.ThrowIfNull(patch);
+        ArgumentNullException.ThrowIfNull(newText);
+
+        return Analyze(parser, patch.ReverseApply(newText), newText, patch.GetChangedLines(), options ?? new DiffAnalysisOptions());
+    }
+
+    /// <summary>
+    /// Classifies already computed diagnostics of two versions of a file.
+    /// </summary>
+    /// <param name="oldText">The old version.</param>
+    /// <param name="oldDiagnostics">The diagnostics of the old version.</param>
+    /// <param name="newText">The new version.</param>
+    /// <param name="newDiagnostics">The diagnostics of the new version.</param>
+    /// <param name="changedLines">The lines of the new version the change touched.</param>
+    /// <param name="contextLines">How many lines around each change count as changed.</param>
+    /// <returns>The classified diagnostics.</returns>
+    public static DiffAnalysis Classify(string oldText, IReadOnlyList<Diagnostic> oldDiagnostics, string newText, IReadOnlyList<Diagnostic> newDiagnostics, ChangedLines changedLines, int contextLines = 0)
+    {
+        ArgumentNullException.ThrowIfNull(oldText);
+        ArgumentNullException.ThrowIfNull(oldDiagnostics);
+        ArgumentNullException.ThrowIfNull(newText);
+        ArgumentNullException.ThrowIfNull(newDiagnostics);
+        ArgumentNullException.ThrowIfNull(changedLines);
+
+        var oldFingerprints = DiagnosticFingerprint.Compute(oldDiagnostics, oldText);
+        var newFingerprints = DiagnosticFingerprint.Compute(newDiagnostics, newText);
+        var before = oldFingerprints.ToHashSet(StringComparer.Ordinal);
+        var after = newFingerprints.ToHashSet(StringComparer.Ordinal);
+
+        var classified = new List<ClassifiedDiagnostic>();
+        for (var i = 0; i < newDiagnostics.Count; i++)
+        {
+            var diagnostic = newDiagnostics[i];
+            classified.Add(new ClassifiedDiagnostic(
+                diagnostic,
+                before.Contains(newFingerprints[i]) ? DiagnosticChange.PreExisting : DiagnosticChange.New,
+                newFingerprints[i],
+                diagnostic.Location == null || changedLines.Touches(diagnostic.Location, contextLines)));
+        }
+
+        for (var i = 0; i < oldDiagnostics.Count; i++)
+        {
+            if (!after.Contains(oldFingerprints[i]))
+            {
+                classified.Add(new ClassifiedDiagnostic(oldDiagnostics[i], DiagnosticChange.Fixed, oldFingerprints[i], false));
+            }
+        }
+
+        return new DiffAnalysis(classified, changedLines, contextLines);
+    }
+
+    private static DiffAnalysis Analyze(GeneralizedParser parser, string oldText, string newText, ChangedLines changedLines, DiffAnalysisOptions options)
+    {
+        var check = options.Check ?? CheckWithBuiltInPasses;
+        var oldDiagnostics = check(parser.Parse(oldText, options.ParseOptions)).ToList();
+        var newDiagnostics = check(parser.Parse(newText, options.ParseOptions)).ToList();
+        return Classify(oldText, oldDiagnostics, newText, newDiagnostics, changedLines, options.ContextLines);
+    }
+
+    private static IEnumerable<Diagnostic> CheckWithBuiltInPasses(ParseResult parse)
+    {
+        var passes = new PassManager();
+        passes.RegisterBuiltInPasses();
+        return parse.Diagnostics.Concat(passes.Run(parse).Diagnostics);
+    }
+}
@@ -0,0 +1,179 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// State shared by the passes of one analysis run: the parse result, pass results, diagnostics and annotations.
+/// </summary>
+public class AnalysisContext
+{
+    private readonly Dictionary<string, object?> _results;
+    private readonly List<Diagnostic> _diagnostics = new();
+    private readonly List<Annotation> _annotations = new();
+
+    /// <summary>
+    /// Initializes a new instance of the AnalysisContext class.
+    /// </summary>
+    /// <param name="parse">The parse result being analyzed.</param>
+    /// <param name="filePath">The path of the analyzed file, if known.</param>
+    public AnalysisContext(ParseResult parse, string? filePath = null)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+        Parse = parse;
+        FilePath = filePath;
+        _results = new Dictionary<string, object?>();
+    }
+
+    private AnalysisContext(AnalysisContext file)
+    {
+        Parse = file.Parse;
+        FilePath = file.FilePath;
+        CurrentPass = file.CurrentPass;
+        _results = file._results;
+    }
+
+    /// <summary>
+    /// Gets the parse result being analyzed.
+    /// </summary>
+    public ParseResult Parse { get; }
+
+    /// <summary>
+    /// Gets the path of the analyzed file, if known.
+    /// </summary>
+    public string? FilePath { get; }
+
+    /// <summary>
+    /// Gets the name of the pass currently running.
+    /// </summary>
+    public string? CurrentPass { get; internal set; }
+
+    /// <summary>
+    /// Gets the diagnostics reported so far.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics => _diagnostics;
+
+    /// <summary>
+    /// Gets the annotations reported so far.
+    /// </summary>
+    public IReadOnlyList<Annotation> Annotations => _annotations;
+
+    /// <summary>
+    /// Gets the names of the passes that have produced results.
+    /// </summary>
+    public IEnumerable<string> CompletedPasses => _results.Keys;
+
+    /// <summary>
+    /// Reports a diagnostic. The diagnostic is tagged with the name of the reporting pass.
+    /// </summary>
+    /// <param name="diagnostic">The diagnostic to report.</param>
+    public void Report(Diagnostic diagnostic)
+    {
+        ArgumentNullException.ThrowIfNull(diagnostic);
+
+        if (CurrentPass != null)
+        {
+            diagnostic.Data["pass"] = CurrentPass;
+        }
+
+        _diagnostics.Add(diagnostic);
+    }
+
+    /// <summary>
+    /// Attaches an annotation to a source range.
+    /// </summary>
+    /// <param name="key">The annotation kind.</param>
+    /// <param name="value">The annotation value.</param>
+    /// <param name="location">The annotated source range.</param>
+    public void Annotate(string key, object value, SourcePosition? location)
+    {
+        ArgumentNullException.ThrowIfNull(key);
+        ArgumentNullException.ThrowIfNull(value);
+
+        _annotations.Add(new Annotation(CurrentPass ?? string.Empty, key, value, location));
+    }
+
+    /// <summary>
+    /// Gets the result of a pass that has already run.
+    /// </summary>
+    /// <typeparam name="T">The result type.</typeparam>
+    /// <param name="passName">The name of the pass.</param>
+    /// <returns>The pass result.</returns>
+    public T GetResult<T>(string passName)
+    {
+        if (!_results.TryGetValue(passName, out var result))
+        {
+            throw new InvalidOperationException($"Pass '{passName}' has not run; declare it as a dependency");
+        }
+
+        return result is T typed
+            ? typed
+            : throw new InvalidOperationException($"Result of pass '{passName}' is not a {typeof(T).Name}");
+    }
+
+    /// <summary>
+    /// Tries to get the result of a pass.
+    /// </summary>
+    /// <typeparam name="T">The result type.</typeparam>
+    /// <param name="passName">The name of the pass.</param>
+    /// <param name="result">The pass result, if available.</param>
+    /// <returns>True if the pass has run and produced a result of the requested type.</returns>
+    public bool TryGetResult<T>(string passName, out T? result)
+    {
+        if (_results.TryGetValue(passName, out var value) && value is T typed)
+        {
+            result = typed;
+            return true;
+        }
+
+        result = default;
+        return false;
+    }
+
+    internal object? GetStoredResult(string passName)
+    {
+        return _results.GetValueOrDefault(passName);
+    }
+
+    internal void SetResult(string passName, object? result)
+    {
+        _results[passName] = result;
+    }
+
+    /// <summary>
+    /// Creates the scratch context of one partition of a <see cref="PartitionedAnalysisPass"/>: it reads this
+    /// context's pass results, which must not change while partitions run, and collects its own diagnostics and
+    /// annotations.
+    /// </summary>
+    internal AnalysisContext CreatePartition()
+    {
+        return new AnalysisContext(this);
+    }
+
+    /// <summary>
+    /// Adds the diagnostics and annotations reported to a partition's scratch context.
+    /// </summary>
+    internal void Absorb(AnalysisContext partition)
+    {
+        _diagnostics.AddRange(partition._diagnostics);
+        _annotations.AddRange(partition._annotations);
+    }
+}
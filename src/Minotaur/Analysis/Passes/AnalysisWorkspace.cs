@@ -0,0 +1,756 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Analyzes a set of files together so that symbols exported by one file resolve references in the files
+/// that import it. A file's module name is its file name without extension. In grammars with
+/// <c>// @import</c> rules, imports name module specifiers that the grammar's resolver in <see cref="Resolvers"/>
+/// maps to workspace paths instead.
+/// </summary>
+/// <remarks>
+/// The workspace is a <see cref="QueryDatabase"/> over the text of every file. Each file is parsed and run through
+/// the pass manager on its own by the <see cref="WorkspaceQueries.Document"/> query, its exports are collected by
+/// the <see cref="WorkspaceQueries.Exports"/> and <see cref="WorkspaceQueries.Symbols"/> queries into the global
+/// <see cref="WorkspaceQueries.SymbolIndex"/>, and its references are resolved against the files it imports by the
+/// <see cref="WorkspaceQueries.Diagnostics"/> query. Changing a file recomputes only the queries that read
+/// something that changed: an edit that leaves a file's exports as they were, like one to a comment, resolves no
+/// other file again and does not rebuild the symbol index. The state of every file can be saved to a checkpoint and
+/// restored into a new workspace, so that only files changed since the checkpoint are parsed and analyzed again.
+/// </remarks>
+public class AnalysisWorkspace
+{
+    private readonly GeneralizedParser _parser;
+    private readonly PassManager _passes;
+    private readonly QueryDatabase _queries = new();
+    private readonly Query<string, string?> _text = Query<string, string?>.Input(WorkspaceQueries.Text);
+    private readonly Query<string, IReadOnlyList<string>> _files = Query<string, IReadOnlyList<string>>.Input(WorkspaceQueries.Files, (a, b) => a.SequenceEqual(b));
+    private readonly Query<string, WorkspaceDocument?> _checkpoint = Query<string, WorkspaceDocument?>.Input(WorkspaceQueries.Checkpoint);
+    private readonly Query<string, WorkspaceDocument> _parse;
+    private readonly Query<string, IReadOnlyList<(string Symbol, OperatorFixity Fixity, int Precedence)>> _operators;
+    private readonly Query<string, WorkspaceDocument> _document;
+    private readonly Query<string, IReadOnlyList<SymbolOccurrence>> _exports;
+    private readonly Query<string, IReadOnlyList<string>> _symbols;
+    private readonly Query<string, IReadOnlyDictionary<string, IReadOnlyList<string>>> _symbolIndex;
+    private readonly Query<string, string?> _module;
+    private readonly Query<string, bool> _exists;
+    private readonly Query<string, DocumentResolution> _diagnostics;
+    private readonly WorkspaceSymbolIndex _searchIndex = new();
+    private readonly Dictionary<string, WorkspaceDocument> _indexed = new(StringComparer.Ordinal);
+    private readonly HashSet<string> _analyzed = new(StringComparer.Ordinal);
+    private readonly HashSet<string> _resolved = new(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Initializes a new instance of the AnalysisWorkspace class.
+    /// </summary>
+    /// <param name="parser">The parser used for every file.</param>
+    /// <param name="passes">The passes to run per file. If null, the built-in passes are used. The symbol table
+    /// and import passes are added if missing.</param>
+    public AnalysisWorkspace(GeneralizedParser parser, PassManager? passes = null)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+
+        _parser = parser;
+        if (passes == null)
+        {
+            passes = new PassManager();
+            passes.RegisterBuiltInPasses();
+        }
+
+        if (passes.Passes.All(p => p.Name != SymbolTablePass.PassName))
+        {
+            passes.Register(new SymbolTablePass());
+        }
+
+        if (passes.Passes.All(p => p.Name != ImportPass.PassName))
+        {
+            passes.Register(new ImportPass());
+        }
+
+        _passes = passes;
+        Operators = OperatorLayer.FromGrammar(parser.Grammar);
+
+        _parse = Query<string, WorkspaceDocument>.Derived(WorkspaceQueries.Parse, ComputeParse);
+        _operators = Query<string, IReadOnlyList<(string Symbol, OperatorFixity Fixity, int Precedence)>>.Derived(WorkspaceQueries.Operators, (db, path) => DeclaredOperators(db.Get(_parse, path)).ToList(), (a, b) => a.SequenceEqual(b));
+        _document = Query<string, WorkspaceDocument>.Derived(WorkspaceQueries.Document, ComputeDocument);
+        _exports = Query<string, IReadOnlyList<SymbolOccurrence>>.Derived(WorkspaceQueries.Exports, (db, path) => db.Get(_document, path).Symbols.Exports.ToList(), SameDeclarations);
+        _symbols = Query<string, IReadOnlyList<string>>.Derived(WorkspaceQueries.Symbols, (db, path) => db.Get(_exports, path).Select(e => e.Name).Distinct().Order(StringComparer.Ordinal).ToList(), (a, b) => a.SequenceEqual(b));
+        _symbolIndex = Query<string, IReadOnlyDictionary<string, IReadOnlyList<string>>>.Derived(WorkspaceQueries.SymbolIndex, ComputeSymbolIndex);
+        _module = Query<string, string?>.Derived(WorkspaceQueries.Module, (db, module) => (db.Get(_files, string.Empty) ?? Array.Empty<string>()).FirstOrDefault(p => GetModuleName(p) == module));
+        _exists = Query<string, bool>.Derived(WorkspaceQueries.Exists, (db, path) => db.Get(_text, path) != null);
+        _diagnostics = Query<string, DocumentResolution>.Derived(WorkspaceQueries.Diagnostics, ComputeDiagnostics);
+    }
+
+    /// <summary>
+    /// Gets the operator layer files are parsed with. Operators declared by a file also apply in the files
+    /// importing it, which are parsed again when those declarations change. Defaults to the layer described
+    /// by the grammar, if any.
+    /// </summary>
+    public OperatorLayer? Operators { get; init; }
+
+    /// <summary>
+    /// Gets the module resolvers used for grammars with <c>// @import</c> rules. Register a resolver before adding
+    /// documents; grammars without one resolve relative specifiers only.
+    /// </summary>
+    public ModuleResolverRegistry Resolvers { get; init; } = new();
+
+    /// <summary>
+    /// Gets the interner that the identifier text of parsed and restored documents is shared through, if any.
+    /// </summary>
+    public SharedInterner? Interner { get; init; }
+
+    /// <summary>
+    /// Gets the parser used for every file.
+    /// </summary>
+    internal GeneralizedParser Parser => _parser;
+
+    private bool ResolvesSpecifiers => _parser.Grammar.Rules.Any(r => r.ImportSpecifier != null);
+
+    private IReadOnlyList<string> Paths => _queries.Get(_files, string.Empty) ?? Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the fuzzy index of the declarations of every document, kept up to date as documents change. It is
+    /// rebuilt from the symbol tables of a restored checkpoint without parsing again.
+    /// </summary>
+    public WorkspaceSymbolIndex SymbolIndex => _searchIndex;
+
+    /// <summary>
+    /// Gets how often each of the workspace's queries, named in <see cref="WorkspaceQueries"/>, was computed and
+    /// reused.
+    /// </summary>
+    public IReadOnlyDictionary<string, QueryStatistics> QueryStatistics => _queries.Statistics;
+
+    /// <summary>
+    /// Gets the number of values the workspace's queries keep for reuse.
+    /// </summary>
+    public int CachedQueryCount => _queries.Count;
+
+    /// <summary>
+    /// Gets the documents in the workspace ordered by path.
+    /// </summary>
+    public IEnumerable<WorkspaceDocument> Documents => Paths.Select(p => _queries.Get(_document, p)).ToList();
+
+    /// <summary>
+    /// Gets the module name of a file path.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <returns>The file name without extension.</returns>
+    public static string GetModuleName(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        return System.IO.Path.GetFileNameWithoutExtension(path);
+    }
+
+    /// <summary>
+    /// Gets a document by path.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <returns>The document, or null if the path is not in the workspace.</returns>
+    public WorkspaceDocument? GetDocument(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        return _queries.Get(_exists, path) ? _queries.Get(_document, path) : null;
+    }
+
+    /// <summary>
+    /// Gets the diagnostics of a document, including cross-file resolution errors.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <returns>The diagnostics, or an empty list if the path is not in the workspace.</returns>
+    public IReadOnlyList<Diagnostic> GetDiagnostics(string path)
+    {
+        return GetDocument(path)?.Diagnostics ?? Array.Empty<Diagnostic>();
+    }
+
+    /// <summary>
+    /// Gets the passes that produce inline hints.
+    /// </summary>
+    public IReadOnlyList<IInlayHintProducer> InlayHintProducers => _passes.Passes.OfType<IInlayHintProducer>().ToList();
+
+    /// <summary>
+    /// Gets the inline hints of a document; see <see cref="InlayHintProvider"/>.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <param name="range">The range to get hints for, or null for the whole document.</param>
+    /// <returns>The hints in source order, or an empty list if the path is not in the workspace.</returns>
+    public IReadOnlyList<InlayHint> GetInlayHints(string path, TextRange? range = null)
+    {
+        return GetDocument(path) is { } document
+            ? InlayHintProvider.GetHints(document.Run.Context, InlayHintProducers, range)
+            : Array.Empty<InlayHint>();
+    }
+
+    /// <summary>
+    /// Gets the exported declarations of a symbol across the workspace.
+    /// </summary>
+    /// <param name="name">The symbol name.</param>
+    /// <returns>The exports ordered by path.</returns>
+    public IReadOnlyList<WorkspaceSymbol> FindExports(string name)
+    {
+        return _queries.Get(_symbolIndex, string.Empty).TryGetValue(name, out var paths)
+            ? paths.SelectMany(p => _queries.Get(_exports, p).Where(e => e.Name == name).Select(e => new WorkspaceSymbol(p, e))).ToList()
+            : Array.Empty<WorkspaceSymbol>();
+    }
+
+    /// <summary>
+    /// Gets the paths of the documents importing a module.
+    /// </summary>
+    /// <param name="module">The module name.</param>
+    /// <returns>The importing paths ordered by path.</returns>
+    public IReadOnlyList<string> GetImporters(string module)
+    {
+        return Documents
+            .Where(d => d.Imports.Any(i => ImportedModule(d.Path, i) == module))
+            .Select(d => d.Path)
+            .ToList();
+    }
+
+    /// <summary>
+    /// Builds the module dependency graph of the workspace from the imports of every document.
+    /// </summary>
+    /// <returns>The graph, with its cycles and the imports that did not resolve.</returns>
+    public ModuleGraph GetModuleGraph()
+    {
+        var dependencies = new List<ModuleDependency>();
+        var unresolved = new List<ModuleDependency>();
+        foreach (var document in Documents)
+        {
+            foreach (var import in document.Imports)
+            {
+                var (target, resolution) = FindImport(_queries, document.Path, import);
+                var dependency = new ModuleDependency(document.Path, target, import.Module, import.Location)
+                {
+                    Failure = target == null ? CreateUnresolvedImport(document.Path, import, resolution).Message : null
+                };
+                (target != null ? dependencies : unresolved).Add(dependency);
+            }
+        }
+
+        return new ModuleGraph(Paths, dependencies, unresolved);
+    }
+
+    /// <summary>
+    /// Adds or replaces a document and re-resolves the documents affected by the change.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <param name="text">The file content.</param>
+    /// <returns>The paths of the documents whose references were resolved again, ordered by path; empty if the
+    /// text is unchanged.</returns>
+    public IReadOnlyList<string> SetDocument(string path, string text)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        ArgumentNullException.ThrowIfNull(text);
+
+        _queries.Set(_text, path, text);
+        _queries.Set(_checkpoint, path, null);
+        if (!Paths.Contains(path))
+        {
+            _queries.Set(_files, string.Empty, Paths.Append(path).Order(StringComparer.Ordinal).ToList());
+        }
+
+        return Update();
+    }
+
+    /// <summary>
+    /// Removes a document and re-resolves the documents importing it.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <returns>The paths of the documents whose references were resolved again, ordered by path.</returns>
+    public IReadOnlyList<string> RemoveDocument(string path)
+    {
+        if (!Paths.Contains(path))
+        {
+            return Array.Empty<string>();
+        }
+
+        _queries.Set(_files, string.Empty, Paths.Where(p => p != path).ToList());
+        _queries.Set(_text, path, null);
+        _queries.Set(_checkpoint, path, null);
+        _passes.Invalidate(path);
+        return Update();
+    }
+
+    /// <summary>
+    /// Saves the analysis state of every document to a checkpoint file, replacing it if it exists.
+    /// </summary>
+    /// <param name="checkpointPath">The checkpoint file path.</param>
+    public void SaveCheckpoint(string checkpointPath)
+    {
+        ArgumentNullException.ThrowIfNull(checkpointPath);
+        WorkspaceCheckpoint.Write(checkpointPath, GetCheckpointSignature(), Documents);
+    }
+
+    /// <summary>
+    /// Fills an empty workspace from a checkpoint. Documents whose text hashes as it did when the checkpoint was
+    /// saved are taken from it; other files are parsed and analyzed, as are files importing a changed file when
+    /// operators are in use, and every document is then resolved. A checkpoint that is missing, corrupt, of
+    /// another format version, or made with another grammar or set of passes is ignored and every file analyzed.
+    /// </summary>
+    /// <param name="checkpointPath">The checkpoint file path.</param>
+    /// <param name="files">The current text of every file in the workspace, by path.</param>
+    /// <returns>Which documents were reused and which analyzed again.</returns>
+    /// <exception cref="InvalidOperationException">The workspace already has documents.</exception>
+    public CheckpointRestoreResult RestoreCheckpoint(string checkpointPath, IReadOnlyDictionary<string, string> files)
+    {
+        ArgumentNullException.ThrowIfNull(checkpointPath);
+        ArgumentNullException.ThrowIfNull(files);
+
+        if (Paths.Count > 0)
+        {
+            throw new InvalidOperationException("A checkpoint can only be restored into an empty workspace");
+        }
+
+        var watch = Stopwatch.StartNew();
+        Dictionary<string, WorkspaceDocument> restored;
+        string? fallbackReason = null;
+        try
+        {
+            restored = WorkspaceCheckpoint.Read(checkpointPath, GetCheckpointSignature(), _parser.Grammar, files, Interner);
+        }
+        catch (Exception e) when (e is IOException or InvalidDataException or UnauthorizedAccessException)
+        {
+            restored = new Dictionary<string, WorkspaceDocument>(StringComparer.Ordinal);
+            fallbackReason = e.Message;
+        }
+
+        foreach (var (path, text) in files)
+        {
+            _queries.Set(_text, path, text);
+            _queries.Set(_checkpoint, path, restored.GetValueOrDefault(path));
+        }
+
+        _queries.Set(_files, string.Empty, files.Keys.Order(StringComparer.Ordinal).ToList());
+        _analyzed.Clear();
+        Update();
+
+        return new CheckpointRestoreResult
+        {
+            Reused = restored.Keys.Where(p => !_analyzed.Contains(p)).Order(StringComparer.Ordinal).ToList(),
+            Reanalyzed = _analyzed.Order(StringComparer.Ordinal).ToList(),
+            FallbackReason = fallbackReason,
+            Elapsed = watch.Elapsed
+        };
+    }
+
+    /// <summary>
+    /// Forgets the cached analysis of files removed from the workspace: their parses, symbol tables and exports,
+    /// which the workspace's queries otherwise keep for as long as the workspace lives.
+    /// </summary>
+    /// <returns>The number of cached values forgotten.</returns>
+    public int Trim()
+    {
+        var live = Paths.ToHashSet(StringComparer.Ordinal);
+
+        // The file list, symbol index and module lookups are keyed by other things than paths and always stay.
+        return _queries.Evict((query, key) =>
+            query is not (WorkspaceQueries.Files or WorkspaceQueries.SymbolIndex or WorkspaceQueries.Module) && key is string path && !live.Contains(path));
+    }
+
+    private CheckpointSignature GetCheckpointSignature()
+    {
+        var passes = string.Join(",", _passes.GetExecutionOrder().Select(p => p.Name));
+        return new CheckpointSignature(_parser.Grammar.Fingerprint, passes, Operators != null);
+    }
+
+    /// <summary>
+    /// Brings the diagnostics of every document up to date after a change to the inputs.
+    /// </summary>
+    /// <returns>The paths of the documents whose references were resolved again, ordered by path.</returns>
+    private IReadOnlyList<string> Update()
+    {
+        _resolved.Clear();
+        foreach (var path in Paths)
+        {
+            var document = _queries.Get(_document, path);
+            var resolution = _queries.Get(_diagnostics, path);
+            document.Resolved = resolution.Resolved;
+            document.Diagnostics = resolution.Diagnostics;
+
+            if (!_indexed.TryGetValue(path, out var indexed) || indexed != document)
+            {
+                _searchIndex.Set(path, WorkspaceSymbolIndex.GetEntries(path, document.Symbols));
+                _indexed[path] = document;
+            }
+        }
+
+        foreach (var path in _indexed.Keys.Where(p => !_queries.Get(_exists, p)).ToList())
+        {
+            _searchIndex.Remove(path);
+            _indexed.Remove(path);
+        }
+
+        return _resolved.Order(StringComparer.Ordinal).ToList();
+    }
+
+    private WorkspaceDocument ComputeParse(QueryDatabase db, string path)
+    {
+        var text = db.Get(_text, path) ?? throw new InvalidOperationException($"'{path}' is not in the workspace");
+        return db.Get(_checkpoint, path) ?? Analyze(path, text, Operators);
+    }
+
+    private WorkspaceDocument ComputeDocument(QueryDatabase db, string path)
+    {
+        var document = db.Get(_parse, path);
+        if (Operators == null)
+        {
+            return document;
+        }
+
+        // Operators declared by imported files apply from the start of the importing file.
+        var targets = document.Imports
+            .Select(i => FindImport(db, path, i).Target)
+            .OfType<string>()
+            .Where(t => t != path)
+            .Distinct()
+            .ToList();
+
+        // A restored document was parsed with the operators of the files it imports as they were restored.
+        if (db.Get(_checkpoint, path) != null)
+        {
+            if (targets.All(t => db.Get(_checkpoint, t) != null))
+            {
+                return document;
+            }
+
+            document = Analyze(path, document.Parse.Input, Operators);
+        }
+
+        var imported = targets
+            .Select(t => OperatorsOf(t, db.Get(_operators, t)))
+            .Where(t => t.Entries.Count > 0)
+            .ToList();
+
+        if (imported.Count == 0)
+        {
+            return document;
+        }
+
+        // The pass results are cached by text, which is unchanged; the tree is not.
+        _passes.Invalidate(path);
+        return Analyze(path, document.Parse.Input, Operators.WithImports(imported));
+    }
+
+    private IReadOnlyDictionary<string, IReadOnlyList<string>> ComputeSymbolIndex(QueryDatabase db, string key)
+    {
+        var index = new Dictionary<string, List<string>>(StringComparer.Ordinal);
+        foreach (var path in db.Get(_files, key) ?? Array.Empty<string>())
+        {
+            foreach (var name in db.Get(_symbols, path))
+            {
+                if (!index.TryGetValue(name, out var paths))
+                {
+                    index[name] = paths = new List<string>();
+                }
+
+                paths.Add(path);
+            }
+        }
+
+        return index.ToDictionary(e => e.Key, e => (IReadOnlyList<string>)e.Value, StringComparer.Ordinal);
+    }
+
+    private WorkspaceDocument Analyze(string path, string text, OperatorLayer? operators)
+    {
+        _analyzed.Add(path);
+        var parse = _parser.Parse(text, new ParseOptions { SourceFile = path, Operators = operators, Interner = Interner });
+        var run = _passes.Run(parse, path);
+        run.Context.TryGetResult<SymbolTable>(SymbolTablePass.PassName, out var symbols);
+        run.Context.TryGetResult<IReadOnlyList<ModuleImport>>(ImportPass.PassName, out var imports);
+
+        return new WorkspaceDocument(path, GetModuleName(path), parse, run, symbols ?? new SymbolTable(), imports ?? Array.Empty<ModuleImport>());
+    }
+
+    private (string? Target, ModuleResolution? Resolution) FindImport(QueryDatabase db, string importingPath, ModuleImport import)
+    {
+        if (!ResolvesSpecifiers)
+        {
+            return (db.Get(_module, import.Module), null);
+        }
+
+        var resolution = Resolvers.Get(_parser.Grammar.Name)(import.Module, importingPath);
+        return (resolution.Path != null && db.Get(_exists, resolution.Path) ? resolution.Path : null, resolution);
+    }
+
+    private string ImportedModule(string importingPath, ModuleImport import)
+    {
+        if (!ResolvesSpecifiers)
+        {
+            return import.Module;
+        }
+
+        // Importers are looked up by the module name of a changed file, so resolved imports are keyed the same way.
+        var resolved = Resolvers.Get(_parser.Grammar.Name)(import.Module, importingPath).Path;
+        return resolved != null ? GetModuleName(resolved) : import.Module;
+    }
+
+    private static Diagnostic CreateUnresolvedImport(string importingPath, ModuleImport import, ModuleResolution? resolution)
+    {
+        var diagnostic = new Diagnostic
+        {
+            Code = DiagnosticCodes.UnresolvedImport,
+            Severity = DiagnosticSeverity.Error,
+            Location = import.Location,
+            Data = { ["module"] = import.Module }
+        };
+
+        return resolution switch
+        {
+            { Failure: not null } => diagnostic.WithMessage("E0007.failure", ("module", import.Module), ("file", importingPath), ("reason", resolution.Failure)),
+            { Path: not null } => diagnostic.WithMessage("E0007.path", ("module", import.Module), ("file", importingPath), ("path", resolution.Path)),
+            _ => diagnostic.WithMessage(DiagnosticCodes.UnresolvedImport, ("module", import.Module), ("file", importingPath))
+        };
+    }
+
+    private static IEnumerable<(string Symbol, OperatorFixity Fixity, int Precedence)> DeclaredOperators(WorkspaceDocument document)
+    {
+        return document.Parse.Operators?.Entries
+            .Where(e => e.SourceFile == document.Path)
+            .Select(e => (e.Symbol, e.Fixity, e.Precedence))
+            ?? Enumerable.Empty<(string, OperatorFixity, int)>();
+    }
+
+    private static OperatorTable OperatorsOf(string path, IEnumerable<(string Symbol, OperatorFixity Fixity, int Precedence)> operators)
+    {
+        var table = new OperatorTable();
+        foreach (var (symbol, fixity, precedence) in operators)
+        {
+            table.Define(symbol, fixity, precedence, 0, path);
+        }
+
+        return table;
+    }
+
+    private static bool SameDeclarations(IReadOnlyList<SymbolOccurrence> declarations, IReadOnlyList<SymbolOccurrence> others)
+    {
+        // Occurrences hold their tree's nodes, so declarations reparsed unchanged are compared by what they declare.
+        return declarations.Count == others.Count && declarations.Zip(others).All(p =>
+            p.First.Name == p.Second.Name && p.First.Rule == p.Second.Rule && p.First.IsExported == p.Second.IsExported && p.First.Location == p.Second.Location);
+    }
+
+    private DocumentResolution ComputeDiagnostics(QueryDatabase db, string path)
+    {
+        _resolved.Add(path);
+        var document = db.Get(_document, path);
+
+        // Single-file "undeclared" warnings are superseded by resolution against the whole workspace.
+        var diagnostics = document.Run.Diagnostics.Where(d => d.Code != DiagnosticCodes.UndeclaredSymbol).ToList();
+        var resolved = new List<CrossFileReference>();
+        var searched = new List<string> { path };
+        var importedPaths = new SortedSet<string>(StringComparer.Ordinal);
+
+        foreach (var import in document.Imports)
+        {
+            var (target, resolution) = FindImport(db, path, import);
+
+            if (target == null)
+            {
+                var diagnostic = CreateUnresolvedImport(path, import, resolution);
+
+                if (resolution?.Failure != null)
+                {
+                    diagnostic.Data["reason"] = resolution.Failure;
+                }
+
+                diagnostics.Add(diagnostic);
+            }
+            else if (importedPaths.Add(target))
+            {
+                searched.Add(target);
+            }
+        }
+
+        if (document.Symbols.HasDeclarations || document.Imports.Count > 0)
+        {
+            foreach (var symbol in document.Symbols.Symbols.Values.Where(s => s.Declarations.Count == 0).OrderBy(s => s.Name, StringComparer.Ordinal))
+            {
+                // Only the exports of imported files are read, so edits elsewhere do not resolve this file again.
+                var export = importedPaths
+                    .Select(p => (Path: p, Declaration: db.Get(_exports, p).FirstOrDefault(e => e.Name == symbol.Name)))
+                    .FirstOrDefault(e => e.Declaration != null);
+                if (export.Declaration != null)
+                {
+                    resolved.AddRange(symbol.References.Select(r => new CrossFileReference(r, export.Path, export.Declaration)));
+                    continue;
+                }
+
+                diagnostics.Add(new Diagnostic
+                {
+                    Code = DiagnosticCodes.UnresolvedReference,
+                    Severity = DiagnosticSeverity.Error,
+                    Location = symbol.References[0].Location,
+                    Data = { ["symbol"] = symbol.Name, ["searched"] = searched.ToList() }
+                }.WithMessage(DiagnosticCodes.UnresolvedReference, ("name", symbol.Name), ("file", path), ("searched", searched.ToList())));
+            }
+        }
+
+        return new DocumentResolution(resolved, diagnostics);
+    }
+
+    /// <summary>
+    /// The cross-file resolution of a document.
+    /// </summary>
+    private sealed record DocumentResolution(IReadOnlyList<CrossFileReference> Resolved, IReadOnlyList<Diagnostic> Diagnostics);
+}
+
+/// <summary>
+/// The names of the queries of an <see cref="AnalysisWorkspace"/>, under which
+/// <see cref="AnalysisWorkspace.QueryStatistics"/> reports them. Queries other than the inputs are keyed by file
+/// path unless noted.
+/// </summary>
+public static class WorkspaceQueries
+{
+    /// <summary>
+    /// The input holding the text of a file, or null once it is removed.
+    /// </summary>
+    public const string Text = "text";
+
+    /// <summary>
+    /// The input holding the paths of the workspace's files in ordinal order.
+    /// </summary>
+    public const string Files = "files";
+
+    /// <summary>
+    /// The input holding the document a checkpoint restored for a file, if any.
+    /// </summary>
+    public const string Checkpoint = "checkpoint";
+
+    /// <summary>
+    /// A file parsed and analyzed on its own, with the workspace's operators only.
+    /// </summary>
+    public const string Parse = "parse";
+
+    /// <summary>
+    /// The operators a file declares.
+    /// </summary>
+    public const string Operators = "operators";
+
+    /// <summary>
+    /// The tree and per-file analysis of a file, parsed again with the operators of the files it imports if they
+    /// declare any.
+    /// </summary>
+    public const string Document = "document";
+
+    /// <summary>
+    /// The exported declarations of a file, which only change when what they declare or where does.
+    /// </summary>
+    public const string Exports = "exports";
+
+    /// <summary>
+    /// The names a file exports, which only change when a declaration is added, removed or renamed.
+    /// </summary>
+    public const string Symbols = "symbols";
+
+    /// <summary>
+    /// The global symbol index from each exported name to the paths exporting it, keyed by the empty string.
+    /// </summary>
+    public const string SymbolIndex = "symbol-index";
+
+    /// <summary>
+    /// The path of the file a module name refers to, keyed by module name.
+    /// </summary>
+    public const string Module = "module";
+
+    /// <summary>
+    /// Whether a path is in the workspace.
+    /// </summary>
+    public const string Exists = "exists";
+
+    /// <summary>
+    /// The references of a file resolved against the files it imports, and its diagnostics.
+    /// </summary>
+    public const string Diagnostics = "diagnostics";
+}
+
+/// <summary>
+/// A file of an <see cref="AnalysisWorkspace"/> with its analysis and cross-file resolution results.
+/// </summary>
+public class WorkspaceDocument
+{
+    internal WorkspaceDocument(string path, string module, ParseResult parse, AnalysisRun run, SymbolTable symbols, IReadOnlyList<ModuleImport> imports)
+    {
+        Path = path;
+        Module = module;
+        Parse = parse;
+        Run = run;
+        Symbols = symbols;
+        Imports = imports;
+        ExportNames = symbols.Exports.Select(e => e.Name).ToHashSet(StringComparer.Ordinal);
+    }
+
+    /// <summary>
+    /// Gets the file path.
+    /// </summary>
+    public string Path { get; }
+
+    /// <summary>
+    /// Gets the module name other files import this file by.
+    /// </summary>
+    public string Module { get; }
+
+    /// <summary>
+    /// Gets the parse result.
+    /// </summary>
+    public ParseResult Parse { get; }
+
+    /// <summary>
+    /// Gets the per-file analysis run.
+    /// </summary>
+    public AnalysisRun Run { get; }
+
+    /// <summary>
+    /// Gets the symbol table of the file.
+    /// </summary>
+    public SymbolTable Symbols { get; }
+
+    /// <summary>
+    /// Gets the modules imported by the file.
+    /// </summary>
+    public IReadOnlyList<ModuleImport> Imports { get; }
+
+    /// <summary>
+    /// Gets the references resolved to declarations exported by imported files.
+    /// </summary>
+    public IReadOnlyList<CrossFileReference> Resolved { get; internal set; } = Array.Empty<CrossFileReference>();
+
+    /// <summary>
+    /// Gets the per-file diagnostics together with cross-file resolution errors.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics { get; internal set; } = Array.Empty<Diagnostic>();
+
+    internal HashSet<string> ExportNames { get; }
+}
+
+/// <summary>
+/// An exported declaration in the workspace's global index.
+/// </summary>
+/// <param name="Path">The path of the declaring file.</param>
+/// <param name="Declaration">The declaration.</param>
+public sealed record WorkspaceSymbol(string Path, SymbolOccurrence Declaration);
+
+/// <summary>
+/// A reference resolved to a declaration in another file.
+/// </summary>
+/// <param name="Reference">The reference.</param>
+/// <param name="DeclaringPath">The path of the file exporting the declaration.</param>
+/// <param name="Declaration">The exported declaration.</param>
+public sealed record CrossFileReference(SymbolOccurrence Reference, string DeclaringPath, SymbolOccurrence Declaration);
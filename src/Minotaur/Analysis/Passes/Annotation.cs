@@ -0,0 +1,29 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// A piece of information attached to a source range by an analysis pass, such as a resolved symbol or an inferred type.
+/// </summary>
+/// <param name="Pass">The name of the pass that produced the annotation.</param>
+/// <param name="Key">The annotation kind, e.g. "symbol.declaration".</param>
+/// <param name="Value">The annotation value.</param>
+/// <param name="Location">The annotated source range.</param>
+public sealed record Annotation(string Pass, string Key, object Value, SourcePosition? Location);
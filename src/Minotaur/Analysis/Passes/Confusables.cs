@@ -0,0 +1,69 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+using System.Text;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// The confusables table used by <see cref="UnicodePass"/>: a subset of the Unicode confusables data (UTS #39)
+/// mapping Cyrillic, Greek and Armenian letters, and the digits and capitals mistaken for Latin letters, to the
+/// character they look like. Compatibility forms such as fullwidth letters are covered by NFKD instead.
+/// </summary>
+internal static class Confusables
+{
+    private static readonly Dictionary<char, char> Prototypes = new()
+    {
+        // Digits and Latin capitals that look like Latin letters.
+        ['0'] = 'O', ['1'] = 'l', ['I'] = 'l', ['ı'] = 'i',
+
+        // Cyrillic.
+        ['А'] = 'A', ['В'] = 'B', ['Е'] = 'E', ['К'] = 'K', ['М'] = 'M', ['Н'] = 'H',
+        ['О'] = 'O', ['Р'] = 'P', ['С'] = 'C', ['Т'] = 'T', ['У'] = 'Y', ['Х'] = 'X',
+        ['Ѕ'] = 'S', ['І'] = 'l', ['Ј'] = 'J', ['Ԛ'] = 'Q', ['Ԝ'] = 'W',
+        ['а'] = 'a', ['е'] = 'e', ['о'] = 'o', ['р'] = 'p', ['с'] = 'c', ['у'] = 'y',
+        ['х'] = 'x', ['ѕ'] = 's', ['і'] = 'i', ['ј'] = 'j', ['һ'] = 'h', ['ӏ'] = 'l',
+        ['ԁ'] = 'd', ['ԛ'] = 'q', ['ԝ'] = 'w',
+
+        // Greek.
+        ['Α'] = 'A', ['Β'] = 'B', ['Ε'] = 'E', ['Ζ'] = 'Z', ['Η'] = 'H', ['Ι'] = 'l',
+        ['Κ'] = 'K', ['Μ'] = 'M', ['Ν'] = 'N', ['Ο'] = 'O', ['Ρ'] = 'P', ['Τ'] = 'T',
+        ['Υ'] = 'Y', ['Χ'] = 'X', ['α'] = 'a', ['ι'] = 'i', ['ν'] = 'v', ['ο'] = 'o',
+        ['ρ'] = 'p', ['υ'] = 'u',
+
+        // Armenian.
+        ['հ'] = 'h', ['ս'] = 'u', ['օ'] = 'o'
+    };
+
+    /// <summary>
+    /// Computes the skeleton of a string: two strings that look alike have equal skeletons.
+    /// </summary>
+    /// <param name="text">The string.</param>
+    /// <returns>The skeleton.</returns>
+    public static string Skeleton(string text)
+    {
+        var decomposed = text.Normalize(NormalizationForm.FormKD);
+        var mapped = new StringBuilder(decomposed.Length);
+        foreach (var c in decomposed)
+        {
+            mapped.Append(Prototypes.TryGetValue(c, out var prototype) ? prototype : c);
+        }
+
+        return mapped.ToString().Normalize(NormalizationForm.FormKD);
+    }
+}
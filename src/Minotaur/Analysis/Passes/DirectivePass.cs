@@ -0,0 +1,53 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Reads the directives in the comments of a file into a <see cref="DirectiveSet"/>, using the syntaxes its
+/// grammar declares, and reports the malformed and unpaired ones.
+/// </summary>
+[AnalysisPass]
+public class DirectivePass : IAnalysisPass
+{
+    /// <summary>
+    /// The name of the pass.
+    /// </summary>
+    public const string PassName = "directives";
+
+    /// <inheritdoc />
+    public string Name => PassName;
+
+    /// <inheritdoc />
+    public IReadOnlyList<string> Dependencies => Array.Empty<string>();
+
+    /// <inheritdoc />
+    public object? Run(AnalysisContext context)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+
+        var directives = DirectiveSet.Read(context.Parse);
+        foreach (var diagnostic in directives.Diagnostics)
+        {
+            context.Report(diagnostic);
+        }
+
+        return directives;
+    }
+}
@@ -0,0 +1,201 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// How a highlighted occurrence uses its symbol, as in the language server protocol's document highlights.
+/// </summary>
+public enum DocumentHighlightKind
+{
+    /// <summary>
+    /// A textual match whose use is not known.
+    /// </summary>
+    Text,
+
+    /// <summary>
+    /// The occurrence reads the symbol.
+    /// </summary>
+    Read,
+
+    /// <summary>
+    /// The occurrence declares or assigns the symbol.
+    /// </summary>
+    Write
+}
+
+/// <summary>
+/// An occurrence of the symbol under the cursor.
+/// </summary>
+/// <param name="Location">The occurrence's span.</param>
+/// <param name="Kind">How the occurrence uses the symbol.</param>
+public sealed record DocumentHighlight(SourcePosition Location, DocumentHighlightKind Kind);
+
+/// <summary>
+/// Finds the occurrences in a file of the symbol under the cursor.
+/// </summary>
+/// <remarks>
+/// <para>
+/// When the grammar declares "DeclarationRules", the identifier under the cursor is resolved with the file's
+/// <see cref="SymbolTable"/> through nested scopes: the nodes of the rules listed in the "ScopeRules" metadata,
+/// or otherwise of rules whose names contain "block", "scope", "body", "function" or "lambda", and the root. A
+/// declaration belongs to the scope enclosing its declaring node, so a function's name is visible beside the
+/// function and its parameters inside it. A reference binds to the closest scope's last declaration before it,
+/// or to a declaration further on if the scope has none before it. Every declaration and reference bound to the
+/// same declaration is highlighted: declarations as writes, references as reads, except that the first
+/// identifier directly under a rule listed in the "AssignmentRules" metadata is a write.
+/// </para>
+/// <para>
+/// Without "DeclarationRules", every identifier token with the kind and text of the one under the cursor is
+/// highlighted within the nearest enclosing scope.
+/// </para>
+/// </remarks>
+public static class DocumentHighlightProvider
+{
+    private static readonly Regex ScopeRuleName = new("block|scope|body|function|lambda", RegexOptions.IgnoreCase | RegexOptions.CultureInvariant);
+
+    /// <summary>
+    /// Gets the occurrences of the symbol at an offset.
+    /// </summary>
+    /// <param name="parse">The parse of the file.</param>
+    /// <param name="offset">The cursor offset; a cursor just after an identifier is on it.</param>
+    /// <param name="symbols">The file's symbol table, or null to match identifiers by text.</param>
+    /// <returns>The occurrences in source order, or an empty list if the cursor is not on an identifier.</returns>
+    public static IReadOnlyList<DocumentHighlight> GetHighlights(ParseResult parse, int offset, SymbolTable? symbols = null)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+
+        if (parse.Tree == null)
+        {
+            return Array.Empty<DocumentHighlight>();
+        }
+
+        var grammar = parse.Grammar?.Source;
+        var scopeRules = SymbolTablePass.SplitRules(grammar?.Metadata.GetValueOrDefault("ScopeRules"));
+        bool IsScope(CognitiveGraphNode node) =>
+            node.Parent == null || node is NonTerminalNode n && (scopeRules?.Contains(n.RuleName) ?? ScopeRuleName.IsMatch(n.RuleName));
+
+        var highlights = symbols != null && grammar?.Metadata.ContainsKey("DeclarationRules") == true
+            ? ResolveHighlights(symbols, offset, grammar.Metadata.GetValueOrDefault("AssignmentRules"), IsScope)
+            : null;
+
+        return highlights ?? MatchHighlights(parse.Tree, offset, SymbolTablePass.GetIdentifierKinds(grammar), IsScope);
+    }
+
+    private static List<DocumentHighlight>? ResolveHighlights(SymbolTable symbols, int offset, string? assignmentRules, Func<CognitiveGraphNode, bool> isScope)
+    {
+        var target = symbols.Occurrences.FirstOrDefault(o => Contains(o.Location, offset));
+        if (target == null)
+        {
+            return null;
+        }
+
+        var assignments = SymbolTablePass.SplitRules(assignmentRules) ?? new HashSet<string>();
+        var declarations = symbols.Lookup(target.Name)?.Declarations ?? new List<SymbolOccurrence>();
+        var scopes = new Dictionary<SymbolOccurrence, CognitiveGraphNode>(ReferenceEqualityComparer.Instance);
+        foreach (var declaration in declarations)
+        {
+            // A declaring node that is itself a scope, such as a function, declares its name in the scope around it.
+            var parent = declaration.Node.Parent;
+            scopes[declaration] = Ancestors(parent != null && isScope(parent) && parent.Parent != null ? parent : declaration.Node).First(isScope);
+        }
+
+        SymbolOccurrence? Bind(SymbolOccurrence occurrence)
+        {
+            if (occurrence.Kind == SymbolOccurrenceKind.Declaration)
+            {
+                return occurrence;
+            }
+
+            var start = occurrence.Location?.Offset ?? 0;
+            foreach (var scope in Ancestors(occurrence.Node).Where(isScope))
+            {
+                var visible = declarations.Where(d => ReferenceEquals(scopes[d], scope)).ToList();
+                if (visible.Count > 0)
+                {
+                    return visible.LastOrDefault(d => (d.Location?.Offset ?? 0) < start) ?? visible[0];
+                }
+            }
+
+            return null;
+        }
+
+        var binding = Bind(target);
+        return symbols.Occurrences
+            .Where(o => o.Name == target.Name && o.Location != null && ReferenceEquals(Bind(o), binding))
+            .Select(o => new DocumentHighlight(o.Location!, o.Kind == SymbolOccurrenceKind.Declaration || IsAssignmentTarget(o, assignments) ? DocumentHighlightKind.Write : DocumentHighlightKind.Read))
+            .OrderBy(h => h.Location.Offset)
+            .ToList();
+    }
+
+    private static bool IsAssignmentTarget(SymbolOccurrence occurrence, HashSet<string> assignments)
+    {
+        return assignments.Contains(occurrence.Rule)
+            && ReferenceEquals(occurrence.Node.Parent?.Children.OfType<TerminalNode>().FirstOrDefault(t => t.TokenType == occurrence.Node.TokenType), occurrence.Node);
+    }
+
+    private static IReadOnlyList<DocumentHighlight> MatchHighlights(CognitiveGraphNode root, int offset, HashSet<string> identifierKinds, Func<CognitiveGraphNode, bool> isScope)
+    {
+        var target = Terminals(root).FirstOrDefault(t => identifierKinds.Contains(t.TokenType) && Contains(t.SourcePosition, offset));
+        if (target == null)
+        {
+            return Array.Empty<DocumentHighlight>();
+        }
+
+        var scope = Ancestors(target).First(isScope);
+        return Terminals(scope)
+            .Where(t => t.TokenType == target.TokenType && t.Text == target.Text && t.SourcePosition != null)
+            .Select(t => new DocumentHighlight(t.SourcePosition!, DocumentHighlightKind.Text))
+            .ToList();
+    }
+
+    private static bool Contains(SourcePosition? span, int offset)
+    {
+        return span != null && span.Offset <= offset && offset <= span.Offset + span.Length;
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Ancestors(CognitiveGraphNode node)
+    {
+        for (var current = node.Parent; current != null; current = current.Parent)
+        {
+            yield return current;
+        }
+    }
+
+    private static IEnumerable<TerminalNode> Terminals(CognitiveGraphNode root)
+    {
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(root);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node is TerminalNode terminal)
+            {
+                yield return terminal;
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// An analysis pass run over a parse result by the <see cref="PassManager"/>.
+/// Passes are registered in code, discovered through <see cref="AnalysisPassAttribute"/>,
+/// or loaded from plugin assemblies at runtime.
+/// </summary>
+public interface IAnalysisPass
+{
+    /// <summary>
+    /// Gets the unique name of the pass. Other passes refer to it by this name.
+    /// </summary>
+    string Name { get; }
+
+    /// <summary>
+    /// Gets the names of the passes that must run before this one.
+    /// </summary>
+    IReadOnlyList<string> Dependencies { get; }
+
+    /// <summary>
+    /// Runs the pass. Diagnostics and annotations are reported through the context.
+    /// </summary>
+    /// <param name="context">The analysis context, holding the parse result and the results of dependencies.</param>
+    /// <returns>The result of the pass, made available to dependent passes; may be null.</returns>
+    object? Run(AnalysisContext context);
+}
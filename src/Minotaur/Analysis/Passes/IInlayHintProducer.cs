@@ -0,0 +1,43 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Maps nodes of a parse to the inline hints editors show beside them, such as parameter names at call sites.
+/// An analysis pass implementing this interface contributes the hints of its results to
+/// <see cref="InlayHintProvider"/>.
+/// </summary>
+public interface IInlayHintProducer
+{
+    /// <summary>
+    /// Gets the rules whose nodes the producer has hints for, usually those the grammar annotates in its metadata.
+    /// </summary>
+    /// <param name="context">The analysis context of the file, holding the results of its passes.</param>
+    /// <returns>The rule names; empty if the producer has no hints for the file.</returns>
+    IReadOnlySet<string> GetHintRules(AnalysisContext context);
+
+    /// <summary>
+    /// Gets the hints of a node of one of the <see cref="GetHintRules">hint rules</see>.
+    /// </summary>
+    /// <param name="context">The analysis context of the file.</param>
+    /// <param name="node">The node.</param>
+    /// <returns>The node's hints.</returns>
+    IEnumerable<InlayHint> GetHints(AnalysisContext context, CognitiveGraphNode node);
+}
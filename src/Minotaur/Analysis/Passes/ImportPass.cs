@@ -0,0 +1,137 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Finds the modules a file imports. Import declarations are the nodes of rules annotated with
+/// <c>// @import(&lt;STRING&gt;)</c>, whose named child holds the module specifier; grammars without such rules
+/// select import nodes with the <see cref="TreeQuery"/> in their "ImportQuery" metadata. The text of the specifier,
+/// without surrounding quotes, is the imported module.
+/// </summary>
+public class ImportPass : IAnalysisPass
+{
+    /// <summary>
+    /// The name of the pass.
+    /// </summary>
+    public const string PassName = "imports";
+
+    /// <inheritdoc />
+    public string Name => PassName;
+
+    /// <inheritdoc />
+    public IReadOnlyList<string> Dependencies => Array.Empty<string>();
+
+    /// <inheritdoc />
+    public object? Run(AnalysisContext context)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+
+        var imports = new List<ModuleImport>();
+        var grammar = context.Parse.Grammar;
+        if (context.Parse.Tree == null || grammar == null)
+        {
+            return imports;
+        }
+
+        IEnumerable<CognitiveGraphNode> specifiers;
+        if (grammar.Rules.Any(r => r.ImportSpecifier != null))
+        {
+            specifiers = FindDeclarations(context.Parse.Tree, grammar);
+        }
+        else
+        {
+            var query = grammar.Source.Metadata.GetValueOrDefault("ImportQuery");
+            if (string.IsNullOrWhiteSpace(query))
+            {
+                return imports;
+            }
+
+            specifiers = TreeQuery.Parse(query).Select(context.Parse.Tree);
+        }
+
+        foreach (var node in specifiers)
+        {
+            var module = string.Concat(Terminals(node).Select(t => t.Text)).Trim('"', '\'');
+            if (module.Length > 0)
+            {
+                imports.Add(new ModuleImport(module, node));
+                context.Annotate("import", module, node.SourcePosition);
+            }
+        }
+
+        return imports;
+    }
+
+    private static IEnumerable<CognitiveGraphNode> FindDeclarations(CognitiveGraphNode tree, CompiledGrammar grammar)
+    {
+        // Depth-first in source order, so imports are listed as written.
+        var stack = new Stack<CognitiveGraphNode>();
+        stack.Push(tree);
+        while (stack.Count > 0)
+        {
+            var node = stack.Pop();
+            var specifier = node is NonTerminalNode declaration ? grammar.GetRule(declaration.RuleName)?.ImportSpecifier : null;
+            var child = specifier != null ? node.Children.FirstOrDefault(c => IsSymbol(c, specifier)) : null;
+            if (child != null)
+            {
+                yield return child;
+            }
+
+            for (int i = node.Children.Count - 1; i >= 0; i--)
+            {
+                stack.Push(node.Children[i]);
+            }
+        }
+    }
+
+    private static bool IsSymbol(CognitiveGraphNode node, GrammarSymbol symbol)
+    {
+        return node switch
+        {
+            NonTerminalNode rule => symbol.Kind == GrammarSymbolKind.Rule && rule.RuleName == symbol.Name,
+            TerminalNode terminal => symbol.IsTerminal && terminal.TokenType == symbol.Key,
+            _ => false
+        };
+    }
+
+    private static IEnumerable<TerminalNode> Terminals(CognitiveGraphNode node)
+    {
+        if (node is TerminalNode terminal)
+        {
+            return new[] { terminal };
+        }
+
+        return node.Children.SelectMany(Terminals);
+    }
+}
+
+/// <summary>
+/// An import of a module by name.
+/// </summary>
+/// <param name="Module">The imported module name or specifier.</param>
+/// <param name="Node">The node naming the module.</param>
+public sealed record ModuleImport(string Module, CognitiveGraphNode Node)
+{
+    /// <summary>
+    /// Gets the source location of the import.
+    /// </summary>
+    public SourcePosition? Location => Node.SourcePosition;
+}
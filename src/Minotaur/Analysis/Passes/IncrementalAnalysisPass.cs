@@ -0,0 +1,227 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Names of the products analysis passes read and write, besides pass results, which are named by their pass.
+/// </summary>
+public static class AnalysisProducts
+{
+    /// <summary>
+    /// The parse tree of the analyzed file. It changes with every edit of the file.
+    /// </summary>
+    public const string Tree = "tree";
+
+    private const string AnnotationLayerPrefix = "annotations:";
+
+    /// <summary>
+    /// Gets the product holding the annotations with a key, e.g. <c>AnnotationLayer("symbol.declaration")</c>.
+    /// </summary>
+    /// <param name="key">The annotation key.</param>
+    /// <returns>The product name.</returns>
+    public static string AnnotationLayer(string key)
+    {
+        ArgumentNullException.ThrowIfNull(key);
+        return AnnotationLayerPrefix + key;
+    }
+
+    internal static bool TryGetAnnotationKey(string product, out string key)
+    {
+        key = product.StartsWith(AnnotationLayerPrefix, StringComparison.Ordinal) ? product[AnnotationLayerPrefix.Length..] : string.Empty;
+        return key.Length > 0;
+    }
+}
+
+/// <summary>
+/// An analysis pass that declares the products it reads and writes, so the <see cref="PassManager"/> can reuse its
+/// results after an edit that changed none of its inputs. Passes that declare nothing read the tree and their
+/// dependencies' results, so they run again after every edit.
+/// </summary>
+public interface IIncrementalAnalysisPass : IAnalysisPass
+{
+    /// <summary>
+    /// Gets the products the pass reads: <see cref="AnalysisProducts.Tree"/>, names of passes whose results it
+    /// reads, and <see cref="AnalysisProducts.AnnotationLayer"/> names. The pass runs after the passes producing
+    /// them.
+    /// </summary>
+    IReadOnlyList<string> Inputs { get; }
+
+    /// <summary>
+    /// Gets the annotation layers the pass writes, besides its result.
+    /// </summary>
+    IReadOnlyList<string> Outputs { get; }
+}
+
+/// <summary>
+/// A pass that analyzes subtrees of the parse tree, such as function bodies, independently of each other. After an
+/// edit the <see cref="PassManager"/> only runs it again for the units whose subtree changed, and carries over the
+/// results, diagnostics and annotations of the others, moved with their text. Unit results should therefore refer
+/// to tree nodes rather than copy their positions.
+/// </summary>
+public abstract class TreeLocalAnalysisPass : IIncrementalAnalysisPass
+{
+    /// <inheritdoc />
+    public abstract string Name { get; }
+
+    /// <inheritdoc />
+    public virtual IReadOnlyList<string> Dependencies => Array.Empty<string>();
+
+    /// <inheritdoc />
+    public virtual IReadOnlyList<string> Inputs => Dependencies.Prepend(AnalysisProducts.Tree).ToList();
+
+    /// <inheritdoc />
+    public virtual IReadOnlyList<string> Outputs => Array.Empty<string>();
+
+    /// <summary>
+    /// Determines whether a node is the root of a unit. Units are not searched for nested units.
+    /// </summary>
+    /// <param name="node">A node of the parse tree.</param>
+    /// <returns>True if the node's subtree is analyzed as one unit.</returns>
+    public abstract bool IsUnit(CognitiveGraphNode node);
+
+    /// <summary>
+    /// Analyzes one unit. Diagnostics and annotations are reported through the context.
+    /// </summary>
+    /// <param name="context">The analysis context.</param>
+    /// <param name="unit">The root node of the unit.</param>
+    /// <returns>The result for the unit; may be null.</returns>
+    public abstract object? RunUnit(AnalysisContext context, CognitiveGraphNode unit);
+
+    /// <summary>
+    /// Analyzes every unit of the tree.
+    /// </summary>
+    /// <param name="context">The analysis context.</param>
+    /// <returns>The <see cref="UnitResults"/> of the units.</returns>
+    public object? Run(AnalysisContext context)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+        return new UnitResults(FindUnits(context.Parse.Tree).ToDictionary(u => u.Id, u => RunUnit(context, u)));
+    }
+
+    internal IEnumerable<CognitiveGraphNode> FindUnits(CognitiveGraphNode? tree)
+    {
+        if (tree == null)
+        {
+            yield break;
+        }
+
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(tree);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (IsUnit(node))
+            {
+                yield return node;
+                continue;
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+    }
+}
+
+/// <summary>
+/// The result of a <see cref="TreeLocalAnalysisPass"/>: the result for each unit by the id of its root node.
+/// </summary>
+public sealed class UnitResults
+{
+    internal UnitResults(IReadOnlyDictionary<Guid, object?> results)
+    {
+        Results = results;
+    }
+
+    /// <summary>
+    /// Gets the result for each unit by the id of its root node.
+    /// </summary>
+    public IReadOnlyDictionary<Guid, object?> Results { get; }
+
+    /// <summary>
+    /// Gets the result for a unit.
+    /// </summary>
+    /// <typeparam name="T">The result type.</typeparam>
+    /// <param name="unitId">The id of the unit's root node.</param>
+    /// <returns>The result.</returns>
+    public T Get<T>(Guid unitId)
+    {
+        return Results.TryGetValue(unitId, out var result) && result is T typed
+            ? typed
+            : throw new KeyNotFoundException($"No unit result of type {typeof(T).Name} for node {unitId}");
+    }
+}
+
+/// <summary>
+/// How often the <see cref="PassManager"/> ran a pass and how often it reused an earlier result.
+/// </summary>
+public sealed class PassCacheStatistics
+{
+    internal PassCacheStatistics(string name)
+    {
+        Name = name;
+    }
+
+    /// <summary>
+    /// Gets the pass name.
+    /// </summary>
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets the number of runs in which the pass ran.
+    /// </summary>
+    public int Runs { get; internal set; }
+
+    /// <summary>
+    /// Gets the number of runs in which the pass's earlier result was reused.
+    /// </summary>
+    public int Hits { get; internal set; }
+
+    /// <summary>
+    /// Gets the number of units a <see cref="TreeLocalAnalysisPass"/> analyzed.
+    /// </summary>
+    public int UnitRuns { get; internal set; }
+
+    /// <summary>
+    /// Gets the number of units whose earlier result a <see cref="TreeLocalAnalysisPass"/> reused.
+    /// </summary>
+    public int UnitHits { get; internal set; }
+
+    /// <summary>
+    /// Gets the share of runs that reused the earlier result, or 0 before the first run.
+    /// </summary>
+    public double HitRate => Runs + Hits == 0 ? 0 : (double)Hits / (Runs + Hits);
+
+    /// <summary>
+    /// Returns the statistics as text.
+    /// </summary>
+    /// <returns>The text.</returns>
+    public override string ToString()
+    {
+        return $"{Name}: {Hits} hit(s), {Runs} run(s), units {UnitHits} hit(s), {UnitRuns} run(s)";
+    }
+}
+
+/// <summary>
+/// What a tree-local pass produced for one unit, with the unit's text to recognize it after an edit.
+/// </summary>
+internal sealed record UnitRecord(string Text, int Offset, object? Result, IReadOnlyList<Diagnostic> Diagnostics, IReadOnlyList<Annotation> Annotations);
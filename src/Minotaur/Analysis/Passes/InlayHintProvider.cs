@@ -0,0 +1,109 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// What an inline hint shows, as in the language server protocol's inlay hints.
+/// </summary>
+public enum InlayHintKind
+{
+    /// <summary>
+    /// An inferred type.
+    /// </summary>
+    Type = 1,
+
+    /// <summary>
+    /// The name of the parameter an argument is passed to.
+    /// </summary>
+    Parameter = 2
+}
+
+/// <summary>
+/// A hint shown inline in the text.
+/// </summary>
+/// <param name="Position">The empty span the hint is shown at.</param>
+/// <param name="Label">The hint text, such as <c>limit:</c>.</param>
+/// <param name="Kind">What the hint shows.</param>
+/// <param name="Tooltip">A longer description shown on hover, if any.</param>
+public sealed record InlayHint(SourcePosition Position, string Label, InlayHintKind Kind, string? Tooltip = null);
+
+/// <summary>
+/// Collects the inline hints of a file, or of a range of it, from <see cref="IInlayHintProducer"/>s.
+/// </summary>
+/// <remarks>
+/// Only the subtrees overlapping the requested range are walked, and producers are only asked about the nodes of
+/// their hint rules, so a request for the visible part of a large file does work in proportion to that part.
+/// </remarks>
+public static class InlayHintProvider
+{
+    /// <summary>
+    /// Gets the hints of an analyzed file.
+    /// </summary>
+    /// <param name="context">The analysis context of the file, such as <see cref="AnalysisRun.Context"/>.</param>
+    /// <param name="producers">The producers, usually the passes of the <see cref="PassManager"/> that implement
+    /// <see cref="IInlayHintProducer"/>.</param>
+    /// <param name="range">The range to get hints for, or null for the whole file. Hints at either end are
+    /// included.</param>
+    /// <returns>The hints in source order.</returns>
+    public static IReadOnlyList<InlayHint> GetHints(AnalysisContext context, IEnumerable<IInlayHintProducer> producers, TextRange? range = null)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+        ArgumentNullException.ThrowIfNull(producers);
+
+        var active = producers.Select(p => (Producer: p, Rules: p.GetHintRules(context))).Where(p => p.Rules.Count > 0).ToList();
+        if (context.Parse.Tree == null || active.Count == 0)
+        {
+            return Array.Empty<InlayHint>();
+        }
+
+        var hints = new List<InlayHint>();
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(context.Parse.Tree);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node is not NonTerminalNode nonTerminal || (range is { } span && node.SourcePosition is { } position && !Overlaps(position, span)))
+            {
+                continue;
+            }
+
+            foreach (var (producer, rules) in active)
+            {
+                if (rules.Contains(nonTerminal.RuleName))
+                {
+                    hints.AddRange(producer.GetHints(context, node).Where(h => range is not { } r || (r.Start <= h.Position.Offset && h.Position.Offset <= r.End)));
+                }
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+
+        return hints.OrderBy(h => h.Position.Offset).ToList();
+    }
+
+    private static bool Overlaps(SourcePosition position, TextRange range)
+    {
+        return position.Offset <= range.End && range.Start <= position.Offset + position.Length;
+    }
+}
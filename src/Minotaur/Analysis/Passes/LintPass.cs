@@ -0,0 +1,141 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Reports common problems: ambiguous parses, symbols declared but never used (unless exported),
+/// and references to symbols that are never declared. An <c>allow</c> directive silences the warnings it lists by
+/// code, or all of them when it lists none, wherever it applies.
+/// </summary>
+[AnalysisPass]
+public class LintPass : PartitionedAnalysisPass
+{
+    /// <summary>
+    /// The name of the pass.
+    /// </summary>
+    public const string PassName = "lint";
+
+    /// <summary>
+    /// The name of the directive that suppresses warnings, e.g. <c>// minotaur: allow W0002</c>.
+    /// </summary>
+    public const string AllowDirective = DirectiveSyntax.AllowName;
+
+    private static readonly string[] RequiredPasses = { SymbolTablePass.PassName, DirectivePass.PassName };
+
+    /// <inheritdoc />
+    public override string Name => PassName;
+
+    /// <inheritdoc />
+    public override IReadOnlyList<string> Dependencies => RequiredPasses;
+
+    /// <inheritdoc />
+    public override object? Prepare(AnalysisContext context, IReadOnlyList<CognitiveGraphNode> partitions)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+        ArgumentNullException.ThrowIfNull(partitions);
+
+        var directives = context.GetResult<DirectiveSet>(DirectivePass.PassName);
+        if (context.Parse.Tree != null)
+        {
+            ReportAmbiguities(context, directives, context.Parse.Tree, partitions.ToHashSet());
+        }
+
+        return directives;
+    }
+
+    /// <inheritdoc />
+    public override object? RunPartition(AnalysisContext context, CognitiveGraphNode partition, object? state)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+        ArgumentNullException.ThrowIfNull(partition);
+
+        ReportAmbiguities(context, (DirectiveSet)state!, partition, partitions: null);
+        return null;
+    }
+
+    /// <inheritdoc />
+    public override object? Merge(AnalysisContext context, IReadOnlyList<object?> results, object? state)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+
+        var directives = (DirectiveSet)state!;
+        var table = context.GetResult<SymbolTable>(SymbolTablePass.PassName);
+        foreach (var symbol in table.Symbols.Values.OrderBy(s => s.Name, StringComparer.Ordinal))
+        {
+            if (symbol.Declarations.Count > 0 && symbol.References.Count == 0 && !symbol.Declarations.Any(d => d.IsExported))
+            {
+                Report(context, directives, new Diagnostic
+                {
+                    Code = DiagnosticCodes.UnusedSymbol,
+                    Severity = DiagnosticSeverity.Warning,
+                    Location = symbol.Declarations[0].Location
+                }.WithMessage(DiagnosticCodes.UnusedSymbol, ("name", symbol.Name)));
+            }
+            else if (symbol.Declarations.Count == 0 && table.HasDeclarations)
+            {
+                // Only meaningful when the grammar has declarations at all.
+                Report(context, directives, new Diagnostic
+                {
+                    Code = DiagnosticCodes.UndeclaredSymbol,
+                    Severity = DiagnosticSeverity.Warning,
+                    Location = symbol.References[0].Location
+                }.WithMessage(DiagnosticCodes.UndeclaredSymbol, ("name", symbol.Name)));
+            }
+        }
+
+        return null;
+    }
+
+    private static void Report(AnalysisContext context, DirectiveSet directives, Diagnostic diagnostic)
+    {
+        var allowed = diagnostic.Location != null && directives
+            .GetApplying(AllowDirective, diagnostic.Location.Offset)
+            .Any(d => d.Values.Count == 0 || d.Values.Contains(diagnostic.Code, StringComparer.OrdinalIgnoreCase));
+        if (!allowed)
+        {
+            context.Report(diagnostic);
+        }
+    }
+
+    private static void ReportAmbiguities(AnalysisContext context, DirectiveSet directives, CognitiveGraphNode node, HashSet<CognitiveGraphNode>? partitions)
+    {
+        if (node is NonTerminalNode nonTerminal && nonTerminal.Metadata.TryGetValue("ambiguous", out var derivations)
+            && context.Parse.Grammar?.GetRule(nonTerminal.RuleName)?.AcceptedAmbiguity == null)
+        {
+            Report(context, directives, new Diagnostic
+            {
+                Code = DiagnosticCodes.AmbiguousParse,
+                Severity = DiagnosticSeverity.Warning,
+                Location = nonTerminal.SourcePosition
+            }.WithMessage(DiagnosticCodes.AmbiguousParse, ("rule", nonTerminal.RuleName), ("count", derivations)));
+        }
+
+        foreach (var child in node.Children)
+        {
+            // Partitions report their own ambiguities, after those of the rest of the tree.
+            if (partitions == null || !partitions.Contains(child))
+            {
+                ReportAmbiguities(context, directives, child, partitions);
+            }
+        }
+    }
+}
@@ -0,0 +1,311 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using System.Text.Json;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// An import of one workspace file by another.
+/// </summary>
+/// <param name="From">The path of the importing file.</param>
+/// <param name="To">The path of the imported file, or null if the import did not resolve.</param>
+/// <param name="Specifier">The module specifier as written.</param>
+/// <param name="Location">The source location of the specifier.</param>
+public sealed record ModuleDependency(string From, string? To, string Specifier, SourcePosition? Location)
+{
+    /// <summary>
+    /// Gets why the import did not resolve, or null if it did.
+    /// </summary>
+    public string? Failure { get; init; }
+}
+
+/// <summary>
+/// The module dependency graph of an <see cref="AnalysisWorkspace"/>, from <see cref="AnalysisWorkspace.GetModuleGraph"/>.
+/// Modules are workspace paths; an edge leads from an importing file to each file it imports.
+/// </summary>
+public sealed class ModuleGraph
+{
+    private readonly Dictionary<string, List<string>> _dependencies = new(StringComparer.Ordinal);
+    private readonly Dictionary<string, List<string>> _dependents = new(StringComparer.Ordinal);
+    private readonly List<IReadOnlyList<string>> _components;
+
+    internal ModuleGraph(IEnumerable<string> modules, IReadOnlyList<ModuleDependency> dependencies, IReadOnlyList<ModuleDependency> unresolved)
+    {
+        Modules = modules.OrderBy(m => m, StringComparer.Ordinal).ToList();
+        Dependencies = dependencies;
+        Unresolved = unresolved;
+
+        foreach (var module in Modules)
+        {
+            _dependencies[module] = new List<string>();
+            _dependents[module] = new List<string>();
+        }
+
+        foreach (var dependency in dependencies.DistinctBy(d => (d.From, d.To)))
+        {
+            _dependencies[dependency.From].Add(dependency.To!);
+            _dependents[dependency.To!].Add(dependency.From);
+        }
+
+        foreach (var list in _dependencies.Values.Concat(_dependents.Values))
+        {
+            list.Sort(StringComparer.Ordinal);
+        }
+
+        _components = FindComponents();
+        Cycles = _components
+            .Where(c => c.Count > 1 || _dependencies[c[0]].Contains(c[0]))
+            .OrderBy(c => c[0], StringComparer.Ordinal)
+            .ToList();
+    }
+
+    /// <summary>
+    /// Gets the paths of the modules ordered by path.
+    /// </summary>
+    public IReadOnlyList<string> Modules { get; }
+
+    /// <summary>
+    /// Gets the resolved imports, by importing path and then in source order.
+    /// </summary>
+    public IReadOnlyList<ModuleDependency> Dependencies { get; }
+
+    /// <summary>
+    /// Gets the imports that did not resolve to a workspace file, each with its <see cref="ModuleDependency.Failure"/>.
+    /// </summary>
+    public IReadOnlyList<ModuleDependency> Unresolved { get; }
+
+    /// <summary>
+    /// Gets the import cycles: the sets of modules that import each other, directly or transitively, including
+    /// modules importing themselves. Members are ordered by path and cycles by their first member.
+    /// </summary>
+    public IReadOnlyList<IReadOnlyList<string>> Cycles { get; }
+
+    /// <summary>
+    /// Gets a warning for each import cycle, located at the import by the first member of the cycle that leads
+    /// into the cycle.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics => Cycles.Select(cycle =>
+    {
+        var edge = Dependencies.First(d => d.From == cycle[0] && cycle.Contains(d.To!));
+        return new Diagnostic
+        {
+            Code = DiagnosticCodes.ImportCycle,
+            Severity = DiagnosticSeverity.Warning,
+            Location = edge.Location,
+            Data = { ["cycle"] = cycle.ToList(), ["path"] = edge.From }
+        }.WithMessage(DiagnosticCodes.ImportCycle, ("cycle", cycle.ToList()));
+    }).ToList();
+
+    /// <summary>
+    /// Gets the modules a module imports directly.
+    /// </summary>
+    /// <param name="module">The module path.</param>
+    /// <returns>The imported paths ordered by path; empty if the module is not in the graph.</returns>
+    public IReadOnlyList<string> DependenciesOf(string module)
+    {
+        return _dependencies.TryGetValue(module, out var dependencies) ? dependencies : Array.Empty<string>();
+    }
+
+    /// <summary>
+    /// Gets the modules importing a module directly.
+    /// </summary>
+    /// <param name="module">The module path.</param>
+    /// <returns>The importing paths ordered by path; empty if the module is not in the graph.</returns>
+    public IReadOnlyList<string> DependentsOf(string module)
+    {
+        return _dependents.TryGetValue(module, out var dependents) ? dependents : Array.Empty<string>();
+    }
+
+    /// <summary>
+    /// Orders the modules so that every module comes after the modules it imports. The members of a cycle, which
+    /// have no such order, are listed together by path.
+    /// </summary>
+    /// <returns>Every module once.</returns>
+    public IReadOnlyList<string> TopologicalOrder()
+    {
+        return _components.SelectMany(c => c).ToList();
+    }
+
+    /// <summary>
+    /// Writes the graph as JSON: <c>{"modules": [...], "dependencies": [{"from", "to", "specifier", "span"}],
+    /// "unresolved": [{"from", "specifier", "reason", "span"}], "cycles": [[...]]}</c>.
+    /// </summary>
+    /// <returns>The JSON text.</returns>
+    public string ToJson()
+    {
+        using var stream = new MemoryStream();
+        using (var writer = new Utf8JsonWriter(stream, new JsonWriterOptions { Indented = true }))
+        {
+            writer.WriteStartObject();
+            writer.WriteStartArray("modules");
+            foreach (var module in Modules)
+            {
+                writer.WriteStringValue(module);
+            }
+
+            writer.WriteEndArray();
+            writer.WriteStartArray("dependencies");
+            foreach (var dependency in Dependencies)
+            {
+                writer.WriteStartObject();
+                writer.WriteString("from", dependency.From);
+                writer.WriteString("to", dependency.To);
+                writer.WriteString("specifier", dependency.Specifier);
+                ParseTreeExport.WriteJsonSpan(writer, dependency.Location);
+                writer.WriteEndObject();
+            }
+
+            writer.WriteEndArray();
+            writer.WriteStartArray("unresolved");
+            foreach (var dependency in Unresolved)
+            {
+                writer.WriteStartObject();
+                writer.WriteString("from", dependency.From);
+                writer.WriteString("specifier", dependency.Specifier);
+                writer.WriteString("reason", dependency.Failure);
+                ParseTreeExport.WriteJsonSpan(writer, dependency.Location);
+                writer.WriteEndObject();
+            }
+
+            writer.WriteEndArray();
+            writer.WriteStartArray("cycles");
+            foreach (var cycle in Cycles)
+            {
+                writer.WriteStartArray();
+                foreach (var module in cycle)
+                {
+                    writer.WriteStringValue(module);
+                }
+
+                writer.WriteEndArray();
+            }
+
+            writer.WriteEndArray();
+            writer.WriteEndObject();
+        }
+
+        return Encoding.UTF8.GetString(stream.ToArray());
+    }
+
+    /// <summary>
+    /// Writes the graph in the Graphviz DOT language. Edges within a cycle are drawn red.
+    /// </summary>
+    /// <returns>The DOT text.</returns>
+    public string ToDot()
+    {
+        var cyclic = Cycles.SelectMany(c => c.Select(m => (Module: m, Cycle: c))).ToDictionary(e => e.Module, e => e.Cycle, StringComparer.Ordinal);
+        var dot = new StringBuilder();
+        dot.AppendLine("digraph modules {");
+        foreach (var module in Modules)
+        {
+            dot.AppendLine($"  {Quote(module)};");
+        }
+
+        foreach (var module in Modules)
+        {
+            foreach (var dependency in _dependencies[module])
+            {
+                var inCycle = cyclic.TryGetValue(module, out var cycle) && cycle.Contains(dependency);
+                dot.AppendLine($"  {Quote(module)} -> {Quote(dependency)}{(inCycle ? " [color=red]" : "")};");
+            }
+        }
+
+        dot.AppendLine("}");
+        return dot.ToString();
+    }
+
+    private static string Quote(string module)
+    {
+        return $"\"{module.Replace("\\", "\\\\").Replace("\"", "\\\"")}\"";
+    }
+
+    private List<IReadOnlyList<string>> FindComponents()
+    {
+        // Tarjan's algorithm, iterative so deep import chains cannot overflow the stack. It completes a strongly
+        // connected component only after every component it reaches, which lists dependencies first.
+        var components = new List<IReadOnlyList<string>>();
+        var index = new Dictionary<string, int>(StringComparer.Ordinal);
+        var lowLink = new Dictionary<string, int>(StringComparer.Ordinal);
+        var onStack = new HashSet<string>(StringComparer.Ordinal);
+        var stack = new Stack<string>();
+        var work = new Stack<(string Module, int Next)>();
+
+        foreach (var root in Modules.Where(m => !index.ContainsKey(m)))
+        {
+            Visit(root);
+            while (work.Count > 0)
+            {
+                var (module, next) = work.Pop();
+                var dependencies = _dependencies[module];
+                if (next < dependencies.Count)
+                {
+                    work.Push((module, next + 1));
+                    var dependency = dependencies[next];
+                    if (!index.ContainsKey(dependency))
+                    {
+                        Visit(dependency);
+                    }
+                    else if (onStack.Contains(dependency))
+                    {
+                        lowLink[module] = Math.Min(lowLink[module], index[dependency]);
+                    }
+
+                    continue;
+                }
+
+                if (lowLink[module] == index[module])
+                {
+                    var component = new List<string>();
+                    string member;
+                    do
+                    {
+                        member = stack.Pop();
+                        onStack.Remove(member);
+                        component.Add(member);
+                    }
+                    while (member != module);
+
+                    component.Sort(StringComparer.Ordinal);
+                    components.Add(component);
+                }
+
+                if (work.Count > 0)
+                {
+                    var parent = work.Peek().Module;
+                    lowLink[parent] = Math.Min(lowLink[parent], lowLink[module]);
+                }
+            }
+        }
+
+        return components;
+
+        void Visit(string module)
+        {
+            var order = index.Count;
+            index[module] = order;
+            lowLink[module] = order;
+            stack.Push(module);
+            onStack.Add(module);
+            work.Push((module, 0));
+        }
+    }
+}
@@ -0,0 +1,156 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Maps the module specifier of an import to the path of the imported file.
+/// </summary>
+/// <param name="specifier">The module specifier as written, without surrounding quotes.</param>
+/// <param name="importingPath">The workspace path of the importing file.</param>
+/// <returns>The resolved path, or why the specifier does not resolve.</returns>
+public delegate ModuleResolution ModuleResolver(string specifier, string importingPath);
+
+/// <summary>
+/// The outcome of resolving a module specifier.
+/// </summary>
+public sealed record ModuleResolution
+{
+    private ModuleResolution(string? path, string? failure)
+    {
+        Path = path;
+        Failure = failure;
+    }
+
+    /// <summary>
+    /// Gets the workspace path the specifier resolved to, or null if it did not resolve.
+    /// </summary>
+    public string? Path { get; }
+
+    /// <summary>
+    /// Gets why the specifier did not resolve, or null if it did.
+    /// </summary>
+    public string? Failure { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the specifier resolved.
+    /// </summary>
+    public bool IsResolved => Path != null;
+
+    /// <summary>
+    /// Creates a successful resolution.
+    /// </summary>
+    /// <param name="path">The workspace path of the imported file.</param>
+    /// <returns>The resolution.</returns>
+    public static ModuleResolution Resolved(string path)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(path);
+        return new ModuleResolution(path, null);
+    }
+
+    /// <summary>
+    /// Creates a failed resolution.
+    /// </summary>
+    /// <param name="reason">Why the specifier does not resolve, reported in the unresolved import diagnostic.</param>
+    /// <returns>The resolution.</returns>
+    public static ModuleResolution Unresolved(string reason)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(reason);
+        return new ModuleResolution(null, reason);
+    }
+}
+
+/// <summary>
+/// The module resolvers of an <see cref="AnalysisWorkspace"/> by grammar name. Grammars without a registered
+/// resolver use <see cref="Relative"/>.
+/// </summary>
+public sealed class ModuleResolverRegistry
+{
+    private readonly Dictionary<string, ModuleResolver> _resolvers = new(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Gets the default resolver. Specifiers starting with "./" or "../" resolve against the directory of the
+    /// importing file, taking its extension if they have none; other specifiers do not resolve.
+    /// </summary>
+    public static ModuleResolver Relative { get; } = ResolveRelative;
+
+    /// <summary>
+    /// Gets the names of the grammars with a registered resolver.
+    /// </summary>
+    public IEnumerable<string> GrammarNames => _resolvers.Keys;
+
+    /// <summary>
+    /// Registers the resolver of a grammar, replacing any resolver registered for it.
+    /// </summary>
+    /// <param name="grammarName">The grammar name.</param>
+    /// <param name="resolver">The resolver.</param>
+    /// <returns>This registry, for chaining.</returns>
+    public ModuleResolverRegistry Register(string grammarName, ModuleResolver resolver)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(grammarName);
+        ArgumentNullException.ThrowIfNull(resolver);
+
+        _resolvers[grammarName] = resolver;
+        return this;
+    }
+
+    /// <summary>
+    /// Gets the resolver of a grammar.
+    /// </summary>
+    /// <param name="grammarName">The grammar name.</param>
+    /// <returns>The registered resolver, or <see cref="Relative"/> if there is none.</returns>
+    public ModuleResolver Get(string grammarName)
+    {
+        ArgumentNullException.ThrowIfNull(grammarName);
+        return _resolvers.GetValueOrDefault(grammarName) ?? Relative;
+    }
+
+    private static ModuleResolution ResolveRelative(string specifier, string importingPath)
+    {
+        if (!specifier.StartsWith("./", StringComparison.Ordinal) && !specifier.StartsWith("../", StringComparison.Ordinal))
+        {
+            return ModuleResolution.Unresolved($"'{specifier}' is not a relative path and no module resolver is registered for the grammar");
+        }
+
+        var segments = importingPath.Replace('\\', '/').Split('/').ToList();
+        segments.RemoveAt(segments.Count - 1);
+        foreach (var segment in specifier.Split('/'))
+        {
+            if (segment == "..")
+            {
+                if (segments.Count == 0)
+                {
+                    return ModuleResolution.Unresolved($"'{specifier}' leaves the workspace root");
+                }
+
+                segments.RemoveAt(segments.Count - 1);
+            }
+            else if (segment is not ("." or ""))
+            {
+                segments.Add(segment);
+            }
+        }
+
+        var path = string.Join("/", segments);
+        if (System.IO.Path.GetExtension(path).Length == 0)
+        {
+            path += System.IO.Path.GetExtension(importingPath);
+        }
+
+        return ModuleResolution.Resolved(path);
+    }
+}
@@ -0,0 +1,124 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// A pass whose work over one file splits into partitions of the parse tree, by default its top-level items, that
+/// are analyzed independently of each other. The <see cref="PassManager"/> analyzes the partitions in parallel, each
+/// reporting to a scratch context of its own, then adds their diagnostics and annotations to the file's in partition
+/// order and merges their results, so the outcome is the same however many threads ran them. Partitions share the
+/// tree, the results of earlier passes and the prepared state, which they must only read.
+/// </summary>
+public abstract class PartitionedAnalysisPass : IAnalysisPass
+{
+    /// <inheritdoc />
+    public abstract string Name { get; }
+
+    /// <inheritdoc />
+    public virtual IReadOnlyList<string> Dependencies => Array.Empty<string>();
+
+    /// <summary>
+    /// Determines whether a node is the root of a partition. Partitions are not searched for nested partitions, and
+    /// the root of the tree is never one. By default every nonterminal that does not continue its parent's rule, as
+    /// the nodes of a recursive list do, is a partition, which makes the partitions the top-level items.
+    /// </summary>
+    /// <param name="node">A node of the parse tree.</param>
+    /// <returns>True if the node's subtree is analyzed as one partition.</returns>
+    public virtual bool IsPartition(CognitiveGraphNode node)
+    {
+        return node is NonTerminalNode item && (item.Parent as NonTerminalNode)?.RuleName != item.RuleName;
+    }
+
+    /// <summary>
+    /// Prepares the state the partitions share and analyzes the part of the tree outside them. Runs before any
+    /// partition, on the file's context.
+    /// </summary>
+    /// <param name="context">The analysis context.</param>
+    /// <param name="partitions">The partition roots in source order.</param>
+    /// <returns>The state passed to every partition and to the merge; may be null.</returns>
+    public virtual object? Prepare(AnalysisContext context, IReadOnlyList<CognitiveGraphNode> partitions)
+    {
+        return null;
+    }
+
+    /// <summary>
+    /// Analyzes one partition. May run concurrently with other partitions, on a context that only this partition
+    /// reports to.
+    /// </summary>
+    /// <param name="context">The partition's analysis context.</param>
+    /// <param name="partition">The root node of the partition.</param>
+    /// <param name="state">The state returned by <see cref="Prepare"/>.</param>
+    /// <returns>The result for the partition; may be null.</returns>
+    public abstract object? RunPartition(AnalysisContext context, CognitiveGraphNode partition, object? state);
+
+    /// <summary>
+    /// Combines the partition results into the result of the pass. Runs after every partition, on the file's context,
+    /// which by then holds the diagnostics and annotations of the partitions.
+    /// </summary>
+    /// <param name="context">The analysis context.</param>
+    /// <param name="results">The partition results, in the order of the partitions.</param>
+    /// <param name="state">The state returned by <see cref="Prepare"/>.</param>
+    /// <returns>The result of the pass; may be null.</returns>
+    public abstract object? Merge(AnalysisContext context, IReadOnlyList<object?> results, object? state);
+
+    /// <summary>
+    /// Analyzes every partition of the tree in order on the calling thread, with the same outcome as the
+    /// <see cref="PassManager"/>'s parallel run.
+    /// </summary>
+    /// <param name="context">The analysis context.</param>
+    /// <returns>The merged result.</returns>
+    public object? Run(AnalysisContext context)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+
+        var partitions = FindPartitions(context.Parse.Tree);
+        var state = Prepare(context, partitions);
+        var results = partitions.Select(p => RunPartition(context, p, state)).ToList();
+        return Merge(context, results, state);
+    }
+
+    internal List<CognitiveGraphNode> FindPartitions(CognitiveGraphNode? tree)
+    {
+        var partitions = new List<CognitiveGraphNode>();
+        if (tree == null)
+        {
+            return partitions;
+        }
+
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(tree);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node != tree && IsPartition(node))
+            {
+                partitions.Add(node);
+                continue;
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+
+        return partitions;
+    }
+}
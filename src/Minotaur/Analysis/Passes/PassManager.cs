@@ -0,0 +1,646 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using System.Runtime.ExceptionServices;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Options for the pass manager.
+/// </summary>
+public class PassManagerOptions
+{
+    /// <summary>
+    /// Gets or sets a value indicating whether passes may be loaded from plugin assemblies at runtime.
+    /// Disabled by default because plugin code runs with the host's permissions.
+    /// </summary>
+    public bool AllowDynamicLoading { get; set; }
+
+    /// <summary>
+    /// Gets or sets a value indicating whether analysis results are cached per file.
+    /// </summary>
+    public bool EnableCache { get; set; } = true;
+
+    /// <summary>
+    /// Gets or sets the largest number of partitions of a <see cref="PartitionedAnalysisPass"/> analyzed at once.
+    /// With 1 they are analyzed in order on the calling thread.
+    /// </summary>
+    public int MaxDegreeOfParallelism { get; set; } = Environment.ProcessorCount;
+}
+
+/// <summary>
+/// Orders analysis passes by their dependencies, runs them with per-pass timing, and caches results per file.
+/// When a cached file is analyzed again with new input, passes none of whose inputs changed are not run again,
+/// and <see cref="TreeLocalAnalysisPass"/>es only run again for the units whose subtree changed.
+/// <see cref="PartitionedAnalysisPass"/>es analyze their partitions in parallel; other passes run one at a time.
+/// </summary>
+public class PassManager
+{
+    private readonly PassManagerOptions _options;
+    private readonly List<IAnalysisPass> _passes = new();
+    private readonly Dictionary<string, IAnalysisPass> _passesByName = new(StringComparer.Ordinal);
+    private readonly Dictionary<string, AnalysisRun> _cache = new(StringComparer.Ordinal);
+    private readonly Dictionary<string, PassCacheStatistics> _statistics = new(StringComparer.Ordinal);
+    private IReadOnlyList<IAnalysisPass>? _executionOrder;
+
+    /// <summary>
+    /// Initializes a new instance of the PassManager class.
+    /// </summary>
+    /// <param name="options">Optional pass manager options.</param>
+    public PassManager(PassManagerOptions? options = null)
+    {
+        _options = options ?? new PassManagerOptions();
+    }
+
+    /// <summary>
+    /// Gets the registered passes in registration order.
+    /// </summary>
+    public IReadOnlyList<IAnalysisPass> Passes => _passes;
+
+    /// <summary>
+    /// Gets how often each registered pass ran and how often its earlier result was reused, by pass name.
+    /// </summary>
+    public IReadOnlyDictionary<string, PassCacheStatistics> CacheStatistics => _statistics;
+
+    /// <summary>
+    /// Registers a pass.
+    /// </summary>
+    /// <param name="pass">The pass to register.</param>
+    public void Register(IAnalysisPass pass)
+    {
+        ArgumentNullException.ThrowIfNull(pass);
+
+        if (string.IsNullOrWhiteSpace(pass.Name))
+        {
+            throw new ArgumentException("Analysis pass must have a name", nameof(pass));
+        }
+
+        if (!_passesByName.TryAdd(pass.Name, pass))
+        {
+            throw new ArgumentException($"An analysis pass named '{pass.Name}' is already registered", nameof(pass));
+        }
+
+        _passes.Add(pass);
+        _statistics[pass.Name] = new PassCacheStatistics(pass.Name);
+        _executionOrder = null;
+        _cache.Clear();
+    }
+
+    /// <summary>
+    /// Registers the passes shipped with Minotaur.
+    /// </summary>
+    public void RegisterBuiltInPasses()
+    {
+        foreach (var pass in PassRegistry.CreateBuiltInPasses())
+        {
+            Register(pass);
+        }
+    }
+
+    /// <summary>
+    /// Loads and registers the passes of a plugin assembly.
+    /// </summary>
+    /// <param name="assemblyPath">The path of the plugin assembly.</param>
+    /// <returns>The load result; refused if dynamic loading is disabled, the ABI version does not match, or a pass name is taken.</returns>
+    public PluginLoadResult LoadPlugin(string assemblyPath)
+    {
+        ArgumentNullException.ThrowIfNull(assemblyPath);
+
+        if (!_options.AllowDynamicLoading)
+        {
+            return PluginLoadResult.Refused(assemblyPath, "Dynamic pass loading is disabled (PassManagerOptions.AllowDynamicLoading)");
+        }
+
+        var result = PassRegistry.LoadPlugin(assemblyPath);
+        if (!result.Success)
+        {
+            return result;
+        }
+
+        var conflict = result.Passes.FirstOrDefault(p => _passesByName.ContainsKey(p.Name));
+        if (conflict != null)
+        {
+            return PluginLoadResult.Refused(assemblyPath, $"Plugin pass '{conflict.Name}' conflicts with a registered pass", result.AbiVersion);
+        }
+
+        foreach (var pass in result.Passes)
+        {
+            Register(pass);
+        }
+
+        return result;
+    }
+
+    /// <summary>
+    /// Gets the passes in the order they run: every pass after its dependencies and the producers of its inputs,
+    /// otherwise in registration order.
+    /// </summary>
+    /// <returns>The ordered passes.</returns>
+    public IReadOnlyList<IAnalysisPass> GetExecutionOrder()
+    {
+        if (_executionOrder != null)
+        {
+            return _executionOrder;
+        }
+
+        foreach (var pass in _passes)
+        {
+            var missing = pass.Dependencies.FirstOrDefault(d => !_passesByName.ContainsKey(d));
+            if (missing != null)
+            {
+                throw new InvalidOperationException($"Analysis pass '{pass.Name}' depends on unknown pass '{missing}'");
+            }
+
+            var unknown = GetInputs(pass).FirstOrDefault(i => i != AnalysisProducts.Tree && !AnalysisProducts.TryGetAnnotationKey(i, out _) && !_passesByName.ContainsKey(i));
+            if (unknown != null)
+            {
+                throw new InvalidOperationException($"Analysis pass '{pass.Name}' reads unknown product '{unknown}'");
+            }
+        }
+
+        var prerequisites = _passes.ToDictionary(p => p.Name, GetPrerequisites);
+        var order = new List<IAnalysisPass>();
+        var done = new HashSet<string>(StringComparer.Ordinal);
+        var pending = new List<IAnalysisPass>(_passes);
+
+        while (pending.Count > 0)
+        {
+            var next = pending.FirstOrDefault(p => prerequisites[p.Name].All(done.Contains));
+            if (next == null)
+            {
+                var cycle = string.Join(", ", pending.Select(p => p.Name));
+                throw new InvalidOperationException($"Analysis passes have a dependency cycle: {cycle}");
+            }
+
+            order.Add(next);
+            done.Add(next.Name);
+            pending.Remove(next);
+        }
+
+        _executionOrder = order;
+        return order;
+    }
+
+    /// <summary>
+    /// Runs all registered passes over a parse result. If the file was analyzed before with the same
+    /// input, the cached run is returned; if it was analyzed with other input, the results of passes whose
+    /// inputs did not change are reused.
+    /// </summary>
+    /// <param name="parse">The parse result to analyze.</param>
+    /// <param name="filePath">The path of the analyzed file, used as the cache key.</param>
+    /// <returns>The analysis run.</returns>
+    public AnalysisRun Run(ParseResult parse, string? filePath = null)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+
+        var order = GetExecutionOrder();
+        var cacheKey = filePath ?? string.Empty;
+
+        AnalysisRun? previous = null;
+        if (_options.EnableCache && filePath != null && _cache.TryGetValue(cacheKey, out var cached))
+        {
+            if (cached.Context.Parse.Input == parse.Input)
+            {
+                foreach (var pass in order)
+                {
+                    _statistics[pass.Name].Hits++;
+                }
+
+                return cached.AsCached();
+            }
+
+            previous = cached.Context.Parse.Grammar == parse.Grammar ? cached : null;
+        }
+
+        var context = new AnalysisContext(parse, filePath);
+        var executions = new List<PassExecution>();
+        var unavailable = new HashSet<string>(StringComparer.Ordinal);
+        var changed = new HashSet<string>(StringComparer.Ordinal) { AnalysisProducts.Tree };
+        var completed = new HashSet<string>(StringComparer.Ordinal);
+        var units = new Dictionary<string, IReadOnlyDictionary<Guid, UnitRecord>>(StringComparer.Ordinal);
+        var total = Stopwatch.StartNew();
+
+        foreach (var pass in order)
+        {
+            var blocking = pass.Dependencies.FirstOrDefault(unavailable.Contains);
+            if (blocking != null)
+            {
+                unavailable.Add(pass.Name);
+                changed.Add(pass.Name);
+                executions.Add(new PassExecution(pass.Name, PassStatus.Skipped, TimeSpan.Zero, $"Dependency '{blocking}' did not complete"));
+                continue;
+            }
+
+            var statistics = _statistics[pass.Name];
+            var earlier = previous?.Executions.FirstOrDefault(e => e.Name == pass.Name)?.Status == PassStatus.Succeeded ? previous : null;
+            var changedInputs = GetInputs(pass).Where(i => HasChanged(i, changed, completed, context, earlier)).ToList();
+            var watch = Stopwatch.StartNew();
+            context.CurrentPass = pass.Name;
+
+            try
+            {
+                if (earlier != null && changedInputs.Count == 0)
+                {
+                    Reuse(pass.Name, earlier, context);
+                    if (earlier.Units.TryGetValue(pass.Name, out var reused))
+                    {
+                        units[pass.Name] = reused;
+                    }
+
+                    statistics.Hits++;
+                    executions.Add(new PassExecution(pass.Name, PassStatus.Succeeded, watch.Elapsed, null) { Reused = true });
+                }
+                else if (pass is TreeLocalAnalysisPass treeLocal)
+                {
+                    // With nothing but the tree changed, units whose subtree is unchanged keep their results.
+                    var previousUnits = earlier != null && changedInputs.All(i => i == AnalysisProducts.Tree)
+                        ? earlier.Units.GetValueOrDefault(pass.Name)
+                        : null;
+                    var records = RunUnits(treeLocal, context, previousUnits, out var unitsRun);
+                    units[pass.Name] = records;
+                    context.SetResult(pass.Name, new UnitResults(records.ToDictionary(r => r.Key, r => r.Value.Result)));
+                    if (unitsRun > 0 || previousUnits == null || previousUnits.Count != records.Count || records.Keys.Any(k => !previousUnits.ContainsKey(k)))
+                    {
+                        changed.Add(pass.Name);
+                    }
+
+                    statistics.Runs++;
+                    statistics.UnitRuns += unitsRun;
+                    statistics.UnitHits += records.Count - unitsRun;
+                    executions.Add(new PassExecution(pass.Name, PassStatus.Succeeded, watch.Elapsed, null)
+                    {
+                        UnitsRun = unitsRun,
+                        UnitsReused = records.Count - unitsRun
+                    });
+                }
+                else
+                {
+                    var partitions = 0;
+                    var result = pass is PartitionedAnalysisPass partitioned
+                        ? RunPartitions(partitioned, context, out partitions)
+                        : pass.Run(context);
+                    context.SetResult(pass.Name, result);
+
+                    // Early cutoff: a result equal to the earlier one leaves the passes reading it alone.
+                    if (earlier == null || !Equals(result, earlier.Context.GetStoredResult(pass.Name)))
+                    {
+                        changed.Add(pass.Name);
+                    }
+
+                    statistics.Runs++;
+                    executions.Add(new PassExecution(pass.Name, PassStatus.Succeeded, watch.Elapsed, null) { Partitions = partitions });
+                }
+
+                completed.Add(pass.Name);
+            }
+            catch (Exception ex)
+            {
+                unavailable.Add(pass.Name);
+                changed.Add(pass.Name);
+                statistics.Runs++;
+                executions.Add(new PassExecution(pass.Name, PassStatus.Failed, watch.Elapsed, ex.Message));
+                context.Report(new Diagnostic
+                {
+                    Code = DiagnosticCodes.AnalysisPassFailed
+                }.WithMessage(DiagnosticCodes.AnalysisPassFailed, ("pass", pass.Name), ("exception", ex.Message)));
+            }
+            finally
+            {
+                context.CurrentPass = null;
+            }
+        }
+
+        var run = new AnalysisRun(context, executions, total.Elapsed, fromCache: false) { Units = units };
+
+        if (_options.EnableCache && filePath != null)
+        {
+            _cache[cacheKey] = run;
+        }
+
+        return run;
+    }
+
+    /// <summary>
+    /// Removes the cached analysis of a file.
+    /// </summary>
+    /// <param name="filePath">The file path.</param>
+    /// <returns>True if a cached run was removed.</returns>
+    public bool Invalidate(string filePath)
+    {
+        ArgumentNullException.ThrowIfNull(filePath);
+        return _cache.Remove(filePath);
+    }
+
+    /// <summary>
+    /// Removes all cached analyses.
+    /// </summary>
+    public void ClearCache()
+    {
+        _cache.Clear();
+    }
+
+    private static IReadOnlyList<string> GetInputs(IAnalysisPass pass)
+    {
+        return pass is IIncrementalAnalysisPass incremental
+            ? incremental.Inputs
+            : pass.Dependencies.Prepend(AnalysisProducts.Tree).ToList();
+    }
+
+    private HashSet<string> GetPrerequisites(IAnalysisPass pass)
+    {
+        var prerequisites = new HashSet<string>(pass.Dependencies, StringComparer.Ordinal);
+        foreach (var input in GetInputs(pass))
+        {
+            if (_passesByName.ContainsKey(input))
+            {
+                prerequisites.Add(input);
+            }
+            else if (AnalysisProducts.TryGetAnnotationKey(input, out _))
+            {
+                prerequisites.UnionWith(_passes
+                    .Where(p => p != pass && p is IIncrementalAnalysisPass producer && producer.Outputs.Contains(input))
+                    .Select(p => p.Name));
+            }
+        }
+
+        return prerequisites;
+    }
+
+    // Whether a product differs from the earlier run's. Annotation layers are compared as far as the passes that
+    // have run so far wrote them.
+    private static bool HasChanged(string input, HashSet<string> changed, HashSet<string> completed, AnalysisContext context, AnalysisRun? earlier)
+    {
+        if (earlier == null || changed.Contains(input))
+        {
+            return true;
+        }
+
+        if (!AnalysisProducts.TryGetAnnotationKey(input, out var key))
+        {
+            return false;
+        }
+
+        var current = context.Annotations.Where(a => a.Key == key);
+        var before = earlier.Annotations.Where(a => a.Key == key && completed.Contains(a.Pass));
+        return !current.SequenceEqual(before);
+    }
+
+    private static void Reuse(string passName, AnalysisRun earlier, AnalysisContext context)
+    {
+        context.SetResult(passName, earlier.Context.GetStoredResult(passName));
+        foreach (var diagnostic in earlier.Diagnostics.Where(d => Equals(d.Data.GetValueOrDefault("pass"), passName)))
+        {
+            context.Report(diagnostic);
+        }
+
+        foreach (var annotation in earlier.Annotations.Where(a => a.Pass == passName))
+        {
+            context.Annotate(annotation.Key, annotation.Value, annotation.Location);
+        }
+    }
+
+    private object? RunPartitions(PartitionedAnalysisPass pass, AnalysisContext context, out int count)
+    {
+        var partitions = pass.FindPartitions(context.Parse.Tree);
+        var state = pass.Prepare(context, partitions);
+        var scratch = new AnalysisContext[partitions.Count];
+        var results = new object?[partitions.Count];
+        count = partitions.Count;
+
+        void RunPartition(int index)
+        {
+            scratch[index] = context.CreatePartition();
+            results[index] = pass.RunPartition(scratch[index], partitions[index], state);
+        }
+
+        if (_options.MaxDegreeOfParallelism <= 1 || partitions.Count < 2)
+        {
+            for (var i = 0; i < partitions.Count; i++)
+            {
+                RunPartition(i);
+            }
+        }
+        else
+        {
+            try
+            {
+                Parallel.For(0, partitions.Count, new ParallelOptions { MaxDegreeOfParallelism = _options.MaxDegreeOfParallelism }, RunPartition);
+            }
+            catch (AggregateException ex) when (ex.InnerExceptions.Count > 0)
+            {
+                ExceptionDispatchInfo.Capture(ex.InnerExceptions[0]).Throw();
+            }
+        }
+
+        // Partition output joins the file's in source order, whichever partition finished first.
+        foreach (var partition in scratch)
+        {
+            context.Absorb(partition);
+        }
+
+        return pass.Merge(context, results, state);
+    }
+
+    private static Dictionary<Guid, UnitRecord> RunUnits(TreeLocalAnalysisPass pass, AnalysisContext context, IReadOnlyDictionary<Guid, UnitRecord>? previous, out int unitsRun)
+    {
+        var parse = context.Parse;
+        var records = new Dictionary<Guid, UnitRecord>();
+        LineIndex? lineIndex = null;
+        unitsRun = 0;
+
+        foreach (var unit in pass.FindUnits(parse.Tree))
+        {
+            var position = unit.SourcePosition;
+            var text = position != null && position.Offset + position.Length <= parse.Input.Length ? parse.Input.Substring(position.Offset, position.Length) : null;
+
+            // A unit keeps its node id only when the incremental parser carried its subtree over; the text guards
+            // against subtrees updated in place.
+            if (text != null && previous != null && previous.TryGetValue(unit.Id, out var record) && record.Text == text)
+            {
+                lineIndex ??= new LineIndex(parse.Input);
+                var moved = Move(record, position!.Offset, lineIndex);
+                foreach (var diagnostic in moved.Diagnostics)
+                {
+                    context.Report(diagnostic);
+                }
+
+                foreach (var annotation in moved.Annotations)
+                {
+                    context.Annotate(annotation.Key, annotation.Value, annotation.Location);
+                }
+
+                records[unit.Id] = moved;
+                continue;
+            }
+
+            var diagnosticCount = context.Diagnostics.Count;
+            var annotationCount = context.Annotations.Count;
+            var result = pass.RunUnit(context, unit);
+            unitsRun++;
+            records[unit.Id] = new UnitRecord(
+                text ?? string.Empty,
+                position?.Offset ?? 0,
+                result,
+                context.Diagnostics.Skip(diagnosticCount).ToList(),
+                context.Annotations.Skip(annotationCount).ToList());
+        }
+
+        return records;
+    }
+
+    private static UnitRecord Move(UnitRecord record, int offset, LineIndex lineIndex)
+    {
+        var delta = offset - record.Offset;
+        if (delta == 0)
+        {
+            return record;
+        }
+
+        SourcePosition? Shift(SourcePosition? location) => location == null
+            ? null
+            : lineIndex.GetPosition(location.Offset + delta, location.Length, location.SourceFile);
+
+        return record with
+        {
+            Offset = offset,
+            Diagnostics = record.Diagnostics.Select(d => new Diagnostic
+            {
+                Code = d.Code,
+                Severity = d.Severity,
+                Message = d.Message,
+                MessageKey = d.MessageKey,
+                MessageArguments = new Dictionary<string, object>(d.MessageArguments),
+                Location = Shift(d.Location),
+                Data = new Dictionary<string, object>(d.Data)
+            }).ToList(),
+            Annotations = record.Annotations.Select(a => a with { Location = Shift(a.Location) }).ToList()
+        };
+    }
+}
+
+/// <summary>
+/// The outcome of running the analysis passes over one file.
+/// </summary>
+public class AnalysisRun
+{
+    internal AnalysisRun(AnalysisContext context, IReadOnlyList<PassExecution> executions, TimeSpan totalTime, bool fromCache)
+    {
+        Context = context;
+        Executions = executions;
+        TotalTime = totalTime;
+        FromCache = fromCache;
+    }
+
+    /// <summary>
+    /// Gets the context holding pass results, diagnostics and annotations.
+    /// </summary>
+    public AnalysisContext Context { get; }
+
+    /// <summary>
+    /// Gets the execution record of every pass in execution order.
+    /// </summary>
+    public IReadOnlyList<PassExecution> Executions { get; }
+
+    /// <summary>
+    /// Gets the total time spent running passes.
+    /// </summary>
+    public TimeSpan TotalTime { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether this run was served from the per-file cache.
+    /// </summary>
+    public bool FromCache { get; }
+
+    /// <summary>
+    /// Gets the diagnostics reported by all passes.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics => Context.Diagnostics;
+
+    /// <summary>
+    /// Gets the annotations reported by all passes.
+    /// </summary>
+    public IReadOnlyList<Annotation> Annotations => Context.Annotations;
+
+    /// <summary>
+    /// Gets a value indicating whether every pass succeeded.
+    /// </summary>
+    public bool Succeeded => Executions.All(e => e.Status == PassStatus.Succeeded);
+
+    internal IReadOnlyDictionary<string, IReadOnlyDictionary<Guid, UnitRecord>> Units { get; init; } =
+        new Dictionary<string, IReadOnlyDictionary<Guid, UnitRecord>>();
+
+    internal AnalysisRun AsCached()
+    {
+        return new AnalysisRun(Context, Executions, TotalTime, fromCache: true) { Units = Units };
+    }
+}
+
+/// <summary>
+/// The execution record of a single pass.
+/// </summary>
+/// <param name="Name">The pass name.</param>
+/// <param name="Status">Whether the pass succeeded, failed or was skipped.</param>
+/// <param name="Duration">The time spent in the pass.</param>
+/// <param name="Error">The failure or skip reason.</param>
+public sealed record PassExecution(string Name, PassStatus Status, TimeSpan Duration, string? Error)
+{
+    /// <summary>
+    /// Gets a value indicating whether the pass's result was reused from the file's earlier run because none of
+    /// its inputs changed.
+    /// </summary>
+    public bool Reused { get; init; }
+
+    /// <summary>
+    /// Gets the number of units a <see cref="TreeLocalAnalysisPass"/> analyzed.
+    /// </summary>
+    public int UnitsRun { get; init; }
+
+    /// <summary>
+    /// Gets the number of units whose earlier result a <see cref="TreeLocalAnalysisPass"/> reused.
+    /// </summary>
+    public int UnitsReused { get; init; }
+
+    /// <summary>
+    /// Gets the number of partitions a <see cref="PartitionedAnalysisPass"/> analyzed.
+    /// </summary>
+    public int Partitions { get; init; }
+}
+
+/// <summary>
+/// Execution status of an analysis pass.
+/// </summary>
+public enum PassStatus
+{
+    /// <summary>
+    /// The pass ran to completion.
+    /// </summary>
+    Succeeded,
+
+    /// <summary>
+    /// The pass threw an exception.
+    /// </summary>
+    Failed,
+
+    /// <summary>
+    /// The pass did not run because a dependency failed or was skipped.
+    /// </summary>
+    Skipped
+}
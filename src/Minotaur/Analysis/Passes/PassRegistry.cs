@@ -0,0 +1,206 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Reflection;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Marks a class implementing <see cref="IAnalysisPass"/> for discovery by <see cref="PassRegistry"/>.
+/// The class must have a public parameterless constructor.
+/// </summary>
+[AttributeUsage(AttributeTargets.Class, Inherited = false)]
+public sealed class AnalysisPassAttribute : Attribute
+{
+}
+
+/// <summary>
+/// Declares an assembly as an analysis pass plugin built against a specific pass ABI version.
+/// </summary>
+[AttributeUsage(AttributeTargets.Assembly)]
+public sealed class AnalysisPassPluginAttribute : Attribute
+{
+    /// <summary>
+    /// Initializes a new instance of the AnalysisPassPluginAttribute class.
+    /// </summary>
+    /// <param name="abiVersion">The pass ABI version the plugin was built against.</param>
+    public AnalysisPassPluginAttribute(int abiVersion)
+    {
+        AbiVersion = abiVersion;
+    }
+
+    /// <summary>
+    /// Gets the pass ABI version the plugin was built against.
+    /// </summary>
+    public int AbiVersion { get; }
+}
+
+/// <summary>
+/// Discovers analysis passes in compiled-in assemblies and loads them from plugin assemblies.
+/// </summary>
+public static class PassRegistry
+{
+    /// <summary>
+    /// The pass ABI version supported by this host. Plugins declaring a different version are refused.
+    /// </summary>
+    public const int AbiVersion = 1;
+
+    /// <summary>
+    /// Creates the passes shipped with Minotaur.
+    /// </summary>
+    /// <returns>The built-in passes.</returns>
+    public static IReadOnlyList<IAnalysisPass> CreateBuiltInPasses()
+    {
+        return Discover(typeof(PassRegistry).Assembly);
+    }
+
+    /// <summary>
+    /// Creates an instance of every class marked with <see cref="AnalysisPassAttribute"/> in an assembly.
+    /// </summary>
+    /// <param name="assembly">The assembly to scan.</param>
+    /// <returns>The discovered passes, ordered by type name.</returns>
+    public static IReadOnlyList<IAnalysisPass> Discover(Assembly assembly)
+    {
+        ArgumentNullException.ThrowIfNull(assembly);
+
+        return assembly.GetTypes()
+            .Where(t => t.IsClass && !t.IsAbstract && typeof(IAnalysisPass).IsAssignableFrom(t))
+            .Where(t => t.GetCustomAttribute<AnalysisPassAttribute>() != null)
+            .OrderBy(t => t.FullName, StringComparer.Ordinal)
+            .Select(t => (IAnalysisPass)Activator.CreateInstance(t)!)
+            .ToList();
+    }
+
+    /// <summary>
+    /// Loads the passes of a plugin assembly file.
+    /// </summary>
+    /// <param name="assemblyPath">The path of the plugin assembly.</param>
+    /// <returns>The load result; refused if the file is not a plugin or targets another ABI version.</returns>
+    public static PluginLoadResult LoadPlugin(string assemblyPath)
+    {
+        ArgumentNullException.ThrowIfNull(assemblyPath);
+
+        Assembly assembly;
+        try
+        {
+            assembly = Assembly.LoadFrom(assemblyPath);
+        }
+        catch (Exception ex) when (ex is IOException or BadImageFormatException)
+        {
+            return PluginLoadResult.Refused(assemblyPath, $"Cannot load plugin assembly: {ex.Message}");
+        }
+
+        return LoadPlugin(assembly, assemblyPath);
+    }
+
+    /// <summary>
+    /// Loads the passes of an already loaded plugin assembly.
+    /// </summary>
+    /// <param name="assembly">The plugin assembly.</param>
+    /// <param name="source">A description of where the assembly came from, used in messages.</param>
+    /// <returns>The load result; refused if the assembly is not a plugin or targets another ABI version.</returns>
+    public static PluginLoadResult LoadPlugin(Assembly assembly, string? source = null)
+    {
+        ArgumentNullException.ThrowIfNull(assembly);
+        source ??= assembly.GetName().Name ?? assembly.FullName ?? "<unknown>";
+
+        // The version check must happen before any plugin type is touched.
+        var plugin = assembly.GetCustomAttribute<AnalysisPassPluginAttribute>();
+        if (plugin == null)
+        {
+            return PluginLoadResult.Refused(source, "Assembly is not an analysis pass plugin (missing [assembly: AnalysisPassPlugin])");
+        }
+
+        if (plugin.AbiVersion != AbiVersion)
+        {
+            return PluginLoadResult.Refused(source, $"Plugin targets pass ABI version {plugin.AbiVersion}, but this host supports version {AbiVersion}", plugin.AbiVersion);
+        }
+
+        try
+        {
+            return PluginLoadResult.Loaded(source, plugin.AbiVersion, Discover(assembly));
+        }
+        catch (Exception ex) when (ex is ReflectionTypeLoadException or MissingMethodException or TargetInvocationException)
+        {
+            return PluginLoadResult.Refused(source, $"Cannot create plugin passes: {ex.Message}", plugin.AbiVersion);
+        }
+    }
+}
+
+/// <summary>
+/// The result of loading an analysis pass plugin.
+/// </summary>
+public sealed class PluginLoadResult
+{
+    private PluginLoadResult(string source, bool success, int? abiVersion, IReadOnlyList<IAnalysisPass> passes, string? error)
+    {
+        Source = source;
+        Success = success;
+        AbiVersion = abiVersion;
+        Passes = passes;
+        Error = error;
+    }
+
+    /// <summary>
+    /// Gets the plugin source, such as the assembly path.
+    /// </summary>
+    public string Source { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the plugin was loaded.
+    /// </summary>
+    public bool Success { get; }
+
+    /// <summary>
+    /// Gets the ABI version declared by the plugin, if any.
+    /// </summary>
+    public int? AbiVersion { get; }
+
+    /// <summary>
+    /// Gets the passes provided by the plugin.
+    /// </summary>
+    public IReadOnlyList<IAnalysisPass> Passes { get; }
+
+    /// <summary>
+    /// Gets the reason the plugin was refused.
+    /// </summary>
+    public string? Error { get; }
+
+    /// <summary>
+    /// Creates a successful load result.
+    /// </summary>
+    /// <param name="source">The plugin source.</param>
+    /// <param name="abiVersion">The plugin ABI version.</param>
+    /// <param name="passes">The loaded passes.</param>
+    /// <returns>The load result.</returns>
+    public static PluginLoadResult Loaded(string source, int abiVersion, IReadOnlyList<IAnalysisPass> passes)
+    {
+        return new PluginLoadResult(source, true, abiVersion, passes, null);
+    }
+
+    /// <summary>
+    /// Creates a refused load result.
+    /// </summary>
+    /// <param name="source">The plugin source.</param>
+    /// <param name="error">The reason the plugin was refused.</param>
+    /// <param name="abiVersion">The plugin ABI version, if declared.</param>
+    /// <returns>The load result.</returns>
+    public static PluginLoadResult Refused(string source, string error, int? abiVersion = null)
+    {
+        return new PluginLoadResult(source, false, abiVersion, Array.Empty<IAnalysisPass>(), error);
+    }
+}
@@ -0,0 +1,411 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Memoizes demand-driven queries over a set of inputs. A derived query's value is computed on first read and
+/// remembered together with the queries it read; after an input changes, it is computed again only if one of
+/// those changed since, so an edit recomputes no more than what actually depends on it. A recomputed value that
+/// equals the previous one keeps its old revision (early cutoff), so queries reading it are not recomputed either.
+/// </summary>
+/// <remarks>
+/// Changes are counted in revisions: setting an input to a different value starts a new one. A memoized value is
+/// verified once per revision by checking, in the order they were read, that none of its dependencies changed
+/// after it was last verified. The database is not thread-safe.
+/// </remarks>
+public sealed class QueryDatabase
+{
+    private readonly Dictionary<(IQuery Query, object Key), Memo> _memos = new();
+    private readonly Dictionary<string, QueryStatistics> _statistics = new(StringComparer.Ordinal);
+    private readonly Stack<Frame> _active = new();
+
+    /// <summary>
+    /// Gets the current revision, which starts at zero and grows by one whenever an input changes.
+    /// </summary>
+    public long Revision { get; private set; }
+
+    /// <summary>
+    /// Gets how often each query was computed and reused, by query name.
+    /// </summary>
+    public IReadOnlyDictionary<string, QueryStatistics> Statistics => _statistics;
+
+    /// <summary>
+    /// Gets the number of memoized values, inputs included.
+    /// </summary>
+    public int Count => _memos.Count;
+
+    /// <summary>
+    /// Sets the value of an input, starting a new revision if it differs from the current one.
+    /// </summary>
+    /// <typeparam name="TKey">The key type of the input.</typeparam>
+    /// <typeparam name="TValue">The value type of the input.</typeparam>
+    /// <param name="input">The input query.</param>
+    /// <param name="key">The key.</param>
+    /// <param name="value">The new value.</param>
+    /// <returns>True if the value changed.</returns>
+    /// <exception cref="InvalidOperationException">The query is derived, or a query is being computed.</exception>
+    public bool Set<TKey, TValue>(Query<TKey, TValue> input, TKey key, TValue value)
+        where TKey : notnull
+    {
+        ArgumentNullException.ThrowIfNull(input);
+        ArgumentNullException.ThrowIfNull(key);
+
+        if (!input.IsInput)
+        {
+            throw new InvalidOperationException($"Query '{input.Name}' is derived and cannot be set");
+        }
+
+        if (_active.Count > 0)
+        {
+            throw new InvalidOperationException($"Input '{input.Name}' cannot be set while query '{_active.Peek().Memo.Query.Name}' is computed");
+        }
+
+        var memo = GetMemo(input, key);
+        if (memo.HasValue && memo.Query.ValuesEqual(memo.Value, value))
+        {
+            return false;
+        }
+
+        Revision++;
+        memo.Value = value;
+        memo.HasValue = true;
+        memo.ChangedAt = Revision;
+        return true;
+    }
+
+    /// <summary>
+    /// Gets the value of a query, computing it if it is not memoized or one of its dependencies changed. When
+    /// called while another query is computed, the read is recorded as a dependency of that query.
+    /// </summary>
+    /// <typeparam name="TKey">The key type of the query.</typeparam>
+    /// <typeparam name="TValue">The value type of the query.</typeparam>
+    /// <param name="query">The query.</param>
+    /// <param name="key">The key.</param>
+    /// <returns>The value; the default value for an input that was never set.</returns>
+    /// <exception cref="InvalidOperationException">The query depends on itself.</exception>
+    public TValue Get<TKey, TValue>(Query<TKey, TValue> query, TKey key)
+        where TKey : notnull
+    {
+        ArgumentNullException.ThrowIfNull(query);
+        ArgumentNullException.ThrowIfNull(key);
+
+        var memo = Refresh(GetMemo(query, key));
+        if (_active.TryPeek(out var frame) && frame.Read.Add(memo))
+        {
+            frame.Memo.Pending.Add(memo);
+        }
+
+        return memo.Value is TValue value ? value : default!;
+    }
+
+    /// <summary>
+    /// Forgets memoized values no other memoized value depends on, such as those computed for a file that is no
+    /// longer an input. A forgotten derived value is computed again if it is read again; a forgotten input reads
+    /// as the default value, so only inputs already reset to it should be forgotten.
+    /// </summary>
+    /// <param name="isDead">Tells, by query name and key, which values may be forgotten.</param>
+    /// <returns>The number of values forgotten.</returns>
+    /// <exception cref="InvalidOperationException">A query is being computed.</exception>
+    public int Evict(Func<string, object, bool> isDead)
+    {
+        ArgumentNullException.ThrowIfNull(isDead);
+
+        if (_active.Count > 0)
+        {
+            throw new InvalidOperationException($"Values cannot be forgotten while query '{_active.Peek().Memo.Query.Name}' is computed");
+        }
+
+        // Forgetting a value can leave the values it read without dependents, so this repeats until nothing changes.
+        var evicted = 0;
+        while (true)
+        {
+            var read = _memos.Values.SelectMany(m => m.Dependencies).ToHashSet();
+            var dead = _memos.Where(m => !read.Contains(m.Value) && isDead(m.Key.Query.Name, m.Key.Key)).Select(m => m.Key).ToList();
+            if (dead.Count == 0)
+            {
+                return evicted;
+            }
+
+            foreach (var key in dead)
+            {
+                _memos.Remove(key);
+            }
+
+            evicted += dead.Count;
+        }
+    }
+
+    private Memo GetMemo(IQuery query, object key)
+    {
+        if (!_memos.TryGetValue((query, key), out var memo))
+        {
+            _memos[(query, key)] = memo = new Memo(query, key);
+            if (!_statistics.ContainsKey(query.Name))
+            {
+                _statistics[query.Name] = new QueryStatistics(query.Name);
+            }
+        }
+
+        return memo;
+    }
+
+    private Memo Refresh(Memo memo)
+    {
+        if (memo.IsComputing)
+        {
+            var cycle = _active.Reverse().SkipWhile(f => f.Memo != memo).Select(f => f.Memo).Append(memo);
+            throw new InvalidOperationException($"Query cycle: {string.Join(" -> ", cycle)}");
+        }
+
+        if (memo.Query.IsInput || memo.VerifiedAt == Revision)
+        {
+            return memo;
+        }
+
+        var statistics = _statistics[memo.Query.Name];
+        if (memo.HasValue && IsUnchanged(memo))
+        {
+            memo.VerifiedAt = Revision;
+            statistics.Hits++;
+            return memo;
+        }
+
+        var frame = new Frame(memo);
+        memo.Pending = new List<Memo>();
+        memo.IsComputing = true;
+        _active.Push(frame);
+        object? value;
+        try
+        {
+            value = memo.Query.Compute(this, memo.Key);
+        }
+        finally
+        {
+            _active.Pop();
+            memo.IsComputing = false;
+        }
+
+        statistics.Executions++;
+        if (memo.HasValue && memo.Query.ValuesEqual(memo.Value, value))
+        {
+            // Early cutoff: dependents verified before this revision stay valid.
+            statistics.Cutoffs++;
+        }
+        else
+        {
+            memo.Value = value;
+            memo.HasValue = true;
+            memo.ChangedAt = Revision;
+        }
+
+        memo.Dependencies = memo.Pending;
+        memo.VerifiedAt = Revision;
+        return memo;
+    }
+
+    private bool IsUnchanged(Memo memo)
+    {
+        // Dependencies are checked in the order they were read, so a changed one stops the check before later
+        // reads that depended on it, and which the computation might no longer make, are brought up to date.
+        memo.IsComputing = true;
+        try
+        {
+            return memo.Dependencies.All(d => Refresh(d).ChangedAt <= memo.VerifiedAt);
+        }
+        finally
+        {
+            memo.IsComputing = false;
+        }
+    }
+
+    private sealed class Memo
+    {
+        public Memo(IQuery query, object key)
+        {
+            Query = query;
+            Key = key;
+        }
+
+        public IQuery Query { get; }
+
+        public object Key { get; }
+
+        public object? Value { get; set; }
+
+        public bool HasValue { get; set; }
+
+        public long ChangedAt { get; set; }
+
+        public long VerifiedAt { get; set; } = -1;
+
+        public bool IsComputing { get; set; }
+
+        public List<Memo> Dependencies { get; set; } = new();
+
+        public List<Memo> Pending { get; set; } = new();
+
+        public override string ToString()
+        {
+            return $"{Query.Name}({Key})";
+        }
+    }
+
+    private sealed class Frame
+    {
+        public Frame(Memo memo)
+        {
+            Memo = memo;
+        }
+
+        public Memo Memo { get; }
+
+        public HashSet<Memo> Read { get; } = new();
+    }
+}
+
+/// <summary>
+/// A query of a <see cref="QueryDatabase"/>: an input whose values are set from outside, or a derived query
+/// computed from other queries.
+/// </summary>
+/// <typeparam name="TKey">The key type, such as a file path.</typeparam>
+/// <typeparam name="TValue">The value type.</typeparam>
+public sealed class Query<TKey, TValue> : IQuery
+    where TKey : notnull
+{
+    private readonly Func<QueryDatabase, TKey, TValue>? _compute;
+    private readonly Func<TValue, TValue, bool> _equals;
+
+    private Query(string name, Func<QueryDatabase, TKey, TValue>? compute, Func<TValue, TValue, bool>? equals)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(name);
+
+        Name = name;
+        _compute = compute;
+        _equals = equals ?? EqualityComparer<TValue>.Default.Equals;
+    }
+
+    /// <summary>
+    /// Gets the query name statistics are reported under.
+    /// </summary>
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the query is an input.
+    /// </summary>
+    public bool IsInput => _compute == null;
+
+    /// <summary>
+    /// Creates an input query.
+    /// </summary>
+    /// <param name="name">The query name.</param>
+    /// <param name="equals">Determines whether a new value equals the current one, so setting it starts no new
+    /// revision; defaults to the value type's equality.</param>
+    /// <returns>The query.</returns>
+    public static Query<TKey, TValue> Input(string name, Func<TValue, TValue, bool>? equals = null)
+    {
+        return new Query<TKey, TValue>(name, null, equals);
+    }
+
+    /// <summary>
+    /// Creates a derived query.
+    /// </summary>
+    /// <param name="name">The query name.</param>
+    /// <param name="compute">Computes the value of a key, reading other queries through the database.</param>
+    /// <param name="equals">Determines whether a recomputed value equals the previous one, so queries reading it
+    /// need not be recomputed; defaults to the value type's equality.</param>
+    /// <returns>The query.</returns>
+    public static Query<TKey, TValue> Derived(string name, Func<QueryDatabase, TKey, TValue> compute, Func<TValue, TValue, bool>? equals = null)
+    {
+        ArgumentNullException.ThrowIfNull(compute);
+        return new Query<TKey, TValue>(name, compute, equals);
+    }
+
+    object? IQuery.Compute(QueryDatabase database, object key)
+    {
+        return _compute!(database, (TKey)key);
+    }
+
+    bool IQuery.ValuesEqual(object? value, object? other)
+    {
+        return _equals((TValue)value!, (TValue)other!);
+    }
+}
+
+/// <summary>
+/// The untyped view of a <see cref="Query{TKey, TValue}"/> the database memoizes through.
+/// </summary>
+internal interface IQuery
+{
+    /// <summary>
+    /// Gets the query name.
+    /// </summary>
+    string Name { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the query is an input.
+    /// </summary>
+    bool IsInput { get; }
+
+    /// <summary>
+    /// Computes the value of a key.
+    /// </summary>
+    /// <param name="database">The database to read other queries through.</param>
+    /// <param name="key">The key.</param>
+    /// <returns>The value.</returns>
+    object? Compute(QueryDatabase database, object key);
+
+    /// <summary>
+    /// Determines whether two values of the query are equal.
+    /// </summary>
+    /// <param name="value">A value.</param>
+    /// <param name="other">The other value.</param>
+    /// <returns>True if they are equal.</returns>
+    bool ValuesEqual(object? value, object? other);
+}
+
+/// <summary>
+/// How often a query of a <see cref="QueryDatabase"/> was computed and reused.
+/// </summary>
+public sealed class QueryStatistics
+{
+    internal QueryStatistics(string name)
+    {
+        Name = name;
+    }
+
+    /// <summary>
+    /// Gets the query name.
+    /// </summary>
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets the number of times a value of the query was computed.
+    /// </summary>
+    public int Executions { get; internal set; }
+
+    /// <summary>
+    /// Gets the number of times a memoized value was verified and reused without computing it again.
+    /// </summary>
+    public int Hits { get; internal set; }
+
+    /// <summary>
+    /// Gets the number of computations whose value equaled the previous one, so queries reading it were not
+    /// computed again.
+    /// </summary>
+    public int Cutoffs { get; internal set; }
+}
@@ -0,0 +1,590 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+using System.Security.Cryptography;
+using System.Text;
+using System.Text.Json;
+using Minotaur.Core;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// A declaration in an <see cref="IndexedDocument"/>.
+/// </summary>
+/// <param name="Moniker">The stable, workspace-unique name of the symbol; see <see cref="SymbolIndexExporter"/>.</param>
+/// <param name="Name">The declared name.</param>
+/// <param name="Kind">The rule of the node directly containing the declared identifier.</param>
+/// <param name="IsExported">Whether the declaration is visible to other files.</param>
+/// <param name="Documentation">The comments on the lines directly above the declaration without their comment
+/// markers, one line per comment line, or null if there are none.</param>
+public sealed record IndexedSymbol(string Moniker, string Name, string Kind, bool IsExported, string? Documentation);
+
+/// <summary>
+/// A definition of or reference to a symbol in an <see cref="IndexedDocument"/>.
+/// </summary>
+/// <param name="Moniker">The moniker of the symbol, which may be declared in another file.</param>
+/// <param name="Range">The span of the identifier.</param>
+/// <param name="IsDefinition">True for the declaration itself, false for a reference.</param>
+public sealed record IndexedOccurrence(string Moniker, SourcePosition? Range, bool IsDefinition);
+
+/// <summary>
+/// The code-search index of one workspace file, from <see cref="SymbolIndexExporter.Index"/>.
+/// </summary>
+public sealed class IndexedDocument
+{
+    internal IndexedDocument(string path, string hash, IReadOnlyList<string> dependencies, IReadOnlyList<IndexedSymbol> symbols, IReadOnlyList<IndexedOccurrence> occurrences)
+    {
+        Path = path;
+        Hash = hash;
+        Dependencies = dependencies;
+        Symbols = symbols;
+        Occurrences = occurrences;
+        ExportsHash = SymbolIndexExporter.Hash(string.Concat(symbols.Where(s => s.IsExported).Select(s => $"{s.Name}\t{s.Moniker}\n")));
+    }
+
+    /// <summary>
+    /// Gets the workspace path of the file.
+    /// </summary>
+    public string Path { get; }
+
+    /// <summary>
+    /// Gets the SHA-256 hash of the file's text, in lowercase hexadecimal.
+    /// </summary>
+    public string Hash { get; }
+
+    /// <summary>
+    /// Gets a hash of the file's exported names and their monikers, which the files depending on it reference.
+    /// </summary>
+    public string ExportsHash { get; }
+
+    /// <summary>
+    /// Gets the paths of the other files the file imports or references symbols of, ordered by path.
+    /// </summary>
+    public IReadOnlyList<string> Dependencies { get; }
+
+    /// <summary>
+    /// Gets the declarations of the file in source order.
+    /// </summary>
+    public IReadOnlyList<IndexedSymbol> Symbols { get; }
+
+    /// <summary>
+    /// Gets the definitions and resolved references of the file in source order.
+    /// </summary>
+    public IReadOnlyList<IndexedOccurrence> Occurrences { get; }
+
+    /// <summary>
+    /// Writes the index as JSON: <c>{"format", "path", "hash", "dependencies": [...], "symbols": [{"moniker",
+    /// "name", "kind", "exported", "documentation"}], "occurrences": [{"moniker", "role", "span"}]}</c>, where the
+    /// role is <c>definition</c> or <c>reference</c>.
+    /// </summary>
+    /// <returns>The JSON text.</returns>
+    public string ToJson()
+    {
+        using var stream = new MemoryStream();
+        using (var writer = new Utf8JsonWriter(stream, new JsonWriterOptions { Indented = true }))
+        {
+            writer.WriteStartObject();
+            writer.WriteNumber("format", SymbolIndexExporter.FormatVersion);
+            writer.WriteString("path", Path);
+            writer.WriteString("hash", Hash);
+            writer.WriteStartArray("dependencies");
+            foreach (var dependency in Dependencies)
+            {
+                writer.WriteStringValue(dependency);
+            }
+
+            writer.WriteEndArray();
+            writer.WriteStartArray("symbols");
+            foreach (var symbol in Symbols)
+            {
+                writer.WriteStartObject();
+                writer.WriteString("moniker", symbol.Moniker);
+                writer.WriteString("name", symbol.Name);
+                writer.WriteString("kind", symbol.Kind);
+                writer.WriteBoolean("exported", symbol.IsExported);
+                if (symbol.Documentation != null)
+                {
+                    writer.WriteString("documentation", symbol.Documentation);
+                }
+
+                writer.WriteEndObject();
+            }
+
+            writer.WriteEndArray();
+            writer.WriteStartArray("occurrences");
+            foreach (var occurrence in Occurrences)
+            {
+                writer.WriteStartObject();
+                writer.WriteString("moniker", occurrence.Moniker);
+                writer.WriteString("role", occurrence.IsDefinition ? "definition" : "reference");
+                ParseTreeExport.WriteJsonSpan(writer, occurrence.Range);
+                writer.WriteEndObject();
+            }
+
+            writer.WriteEndArray();
+            writer.WriteEndObject();
+        }
+
+        return Encoding.UTF8.GetString(stream.ToArray());
+    }
+}
+
+/// <summary>
+/// The outcome of <see cref="SymbolIndexExporter.Export"/>.
+/// </summary>
+public sealed class IndexExportResult
+{
+    /// <summary>
+    /// Gets the paths of the files whose index was written, ordered by path.
+    /// </summary>
+    public IReadOnlyList<string> Emitted { get; init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the paths of the files whose index was up to date and kept, ordered by path.
+    /// </summary>
+    public IReadOnlyList<string> Skipped { get; init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the paths of the files no longer in the workspace whose index was deleted, ordered by path.
+    /// </summary>
+    public IReadOnlyList<string> Removed { get; init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets why the previous manifest was not used, or null if it was used or the export was not incremental.
+    /// </summary>
+    public string? FallbackReason { get; init; }
+}
+
+/// <summary>
+/// Exports the symbols of an <see cref="AnalysisWorkspace"/> as code-search index files, one per source file, with
+/// a manifest that lets later exports write only the files that changed.
+/// </summary>
+/// <remarks>
+/// <para>
+/// Every declaration gets a moniker in the style of SCIP symbols: <c>minotaur &lt;grammar&gt; &lt;path&gt; .
+/// &lt;descriptors&gt;</c>, where the grammar name and the declaring file's path have their spaces doubled, and the
+/// descriptors are the names of the enclosing declarations each followed by <c>/</c> and then the declared name
+/// followed by <c>.</c>, as in <c>minotaur Toy lib/math.toy . shapes/area.</c>. Names other than letters, digits
+/// and <c>_+-$</c> are quoted in backticks. A name declared again in the same container gets the disambiguator
+/// <c>(1)</c>, <c>(2)</c> and so on in source order. A moniker therefore depends only on its own file's path and
+/// the declarations around it: edits to, additions of or renames of other files never change it.
+/// </para>
+/// <para>
+/// Definitions and references are the occurrences of the file's symbol table. A reference names the first
+/// declaration of its name in the same file, or the export it resolved to in an imported file; unresolved
+/// references are left out.
+/// </para>
+/// <para>
+/// <see cref="Export"/> writes <c>&lt;path&gt;.index.json</c> for every file under the output directory and a
+/// <see cref="ManifestFileName"/> recording, for each file, the hash of its text, the hash of its exports and its
+/// dependencies. An incremental export writes a file again only if its text, its dependencies or the exports of
+/// one of its dependencies changed since the manifest was written. Every export deletes the index of files the
+/// manifest lists that are gone; a manifest of another grammar fingerprint is not reused otherwise, and one of
+/// another format version not at all, so every file is written.
+/// </para>
+/// </remarks>
+public sealed class SymbolIndexExporter
+{
+    /// <summary>
+    /// The version of the index and manifest format; manifests of other versions are not read.
+    /// </summary>
+    public const int FormatVersion = 1;
+
+    /// <summary>
+    /// The file name of the manifest in the output directory.
+    /// </summary>
+    public const string ManifestFileName = "manifest.json";
+
+    /// <summary>
+    /// The suffix appended to a file's path to name its index file.
+    /// </summary>
+    public const string IndexFileSuffix = ".index.json";
+
+    private readonly AnalysisWorkspace _workspace;
+
+    /// <summary>
+    /// Initializes a new instance of the SymbolIndexExporter class.
+    /// </summary>
+    /// <param name="workspace">The workspace to export.</param>
+    public SymbolIndexExporter(AnalysisWorkspace workspace)
+    {
+        ArgumentNullException.ThrowIfNull(workspace);
+        _workspace = workspace;
+    }
+
+    /// <summary>
+    /// Builds the index of one file.
+    /// </summary>
+    /// <param name="path">The workspace path.</param>
+    /// <returns>The index, or null if the path is not in the workspace.</returns>
+    public IndexedDocument? Index(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        var document = _workspace.GetDocument(path);
+        return document == null ? null : Index(document, _workspace.GetModuleGraph(), new Dictionary<string, Dictionary<SymbolOccurrence, string>>(StringComparer.Ordinal));
+    }
+
+    /// <summary>
+    /// Writes the index of every file and the manifest to a directory.
+    /// </summary>
+    /// <param name="directory">The output directory, created if missing.</param>
+    /// <param name="incremental">True to write only the files that changed since the manifest in the directory
+    /// was written; false to write every file.</param>
+    /// <returns>Which files were written, kept and removed.</returns>
+    public IndexExportResult Export(string directory, bool incremental = true)
+    {
+        ArgumentNullException.ThrowIfNull(directory);
+
+        Directory.CreateDirectory(directory);
+        var manifestPath = System.IO.Path.Combine(directory, ManifestFileName);
+        var fingerprint = _workspace.Parser.Grammar.Fingerprint;
+
+        // A manifest that can be read names the index files to delete even when it cannot be reused.
+        Dictionary<string, ManifestEntry>? previous = null;
+        string? fallbackReason = null;
+        if (File.Exists(manifestPath))
+        {
+            try
+            {
+                (var previousFingerprint, previous) = ReadManifest(File.ReadAllText(manifestPath));
+                fallbackReason = previousFingerprint != fingerprint ? "The index was exported with another grammar" : null;
+            }
+            catch (FormatException e)
+            {
+                fallbackReason = e.Message;
+            }
+        }
+        else
+        {
+            fallbackReason = "No manifest was found";
+        }
+
+        var reuse = incremental && fallbackReason == null;
+        var graph = _workspace.GetModuleGraph();
+        var monikers = new Dictionary<string, Dictionary<SymbolOccurrence, string>>(StringComparer.Ordinal);
+        var documents = _workspace.Documents.Select(d => Index(d, graph, monikers)).ToList();
+        var current = documents.ToDictionary(d => d.Path, d => new ManifestEntry(d.Hash, d.ExportsHash, d.Dependencies), StringComparer.Ordinal);
+
+        var emitted = new List<string>();
+        var skipped = new List<string>();
+        foreach (var document in documents)
+        {
+            var file = GetIndexFile(directory, document.Path);
+            if (reuse && previous != null && previous.TryGetValue(document.Path, out var entry) && IsUpToDate(entry, current[document.Path], previous, current) && File.Exists(file))
+            {
+                skipped.Add(document.Path);
+                continue;
+            }
+
+            Directory.CreateDirectory(System.IO.Path.GetDirectoryName(file)!);
+            File.WriteAllText(file, document.ToJson());
+            emitted.Add(document.Path);
+        }
+
+        var removed = new List<string>();
+        foreach (var path in (previous?.Keys ?? Enumerable.Empty<string>()).Where(p => !current.ContainsKey(p)).Order(StringComparer.Ordinal))
+        {
+            var file = GetIndexFile(directory, path);
+            if (File.Exists(file))
+            {
+                File.Delete(file);
+            }
+
+            removed.Add(path);
+        }
+
+        File.WriteAllText(manifestPath, WriteManifest(fingerprint, current));
+        return new IndexExportResult { Emitted = emitted, Skipped = skipped, Removed = removed, FallbackReason = incremental ? fallbackReason : null };
+    }
+
+    /// <summary>
+    /// Gets the path of a file's index under an output directory.
+    /// </summary>
+    /// <param name="directory">The output directory.</param>
+    /// <param name="path">The workspace path of the file.</param>
+    /// <returns>The index file path.</returns>
+    public static string GetIndexFile(string directory, string path)
+    {
+        ArgumentNullException.ThrowIfNull(directory);
+        ArgumentNullException.ThrowIfNull(path);
+        return System.IO.Path.Combine(directory, path.Replace('/', System.IO.Path.DirectorySeparatorChar) + IndexFileSuffix);
+    }
+
+    internal static string Hash(string text)
+    {
+        return Convert.ToHexString(SHA256.HashData(Encoding.UTF8.GetBytes(text))).ToLowerInvariant();
+    }
+
+    private static bool IsUpToDate(ManifestEntry previous, ManifestEntry current, Dictionary<string, ManifestEntry> before, Dictionary<string, ManifestEntry> now)
+    {
+        return previous.Hash == current.Hash
+            && previous.Dependencies.SequenceEqual(current.Dependencies)
+            && current.Dependencies.All(d => before.TryGetValue(d, out var old) && now.TryGetValue(d, out var changed) && old.ExportsHash == changed.ExportsHash);
+    }
+
+    private IndexedDocument Index(WorkspaceDocument document, ModuleGraph graph, Dictionary<string, Dictionary<SymbolOccurrence, string>> monikers)
+    {
+        var own = GetMonikers(document, monikers);
+        var occurrences = new List<IndexedOccurrence>();
+        var resolved = new Dictionary<SymbolOccurrence, CrossFileReference>(ReferenceEqualityComparer.Instance);
+        foreach (var reference in document.Resolved)
+        {
+            resolved[reference.Reference] = reference;
+        }
+
+        foreach (var occurrence in document.Symbols.Occurrences)
+        {
+            string? moniker;
+            if (occurrence.Kind == SymbolOccurrenceKind.Declaration)
+            {
+                moniker = own[occurrence];
+            }
+            else if (resolved.TryGetValue(occurrence, out var reference))
+            {
+                // The reference resolved to the first export of its name, which the declaring file's
+                // monikers name by position rather than by identity.
+                var declaring = _workspace.GetDocument(reference.DeclaringPath);
+                var export = declaring?.Symbols.Exports.FirstOrDefault(e => e.Name == occurrence.Name);
+                moniker = export != null ? GetMonikers(declaring!, monikers)[export] : null;
+            }
+            else
+            {
+                var declaration = document.Symbols.Lookup(occurrence.Name)?.Declarations.FirstOrDefault();
+                moniker = declaration != null ? own[declaration] : null;
+            }
+
+            if (moniker != null)
+            {
+                occurrences.Add(new IndexedOccurrence(moniker, occurrence.Location, occurrence.Kind == SymbolOccurrenceKind.Declaration));
+            }
+        }
+
+        var documentation = GetDocumentation(document.Parse);
+        var symbols = document.Symbols.Occurrences
+            .Where(o => o.Kind == SymbolOccurrenceKind.Declaration)
+            .Select(o => new IndexedSymbol(own[o], o.Name, o.Rule, o.IsExported, documentation(o)))
+            .ToList();
+
+        var dependencies = graph.DependenciesOf(document.Path)
+            .Concat(document.Resolved.Select(r => r.DeclaringPath))
+            .Where(p => p != document.Path)
+            .Distinct()
+            .Order(StringComparer.Ordinal)
+            .ToList();
+
+        return new IndexedDocument(document.Path, Hash(document.Parse.Input), dependencies, symbols, occurrences);
+    }
+
+    private Dictionary<SymbolOccurrence, string> GetMonikers(WorkspaceDocument document, Dictionary<string, Dictionary<SymbolOccurrence, string>> cache)
+    {
+        if (cache.TryGetValue(document.Path, out var cached))
+        {
+            return cached;
+        }
+
+        var declarations = document.Symbols.Occurrences.Where(o => o.Kind == SymbolOccurrenceKind.Declaration).ToList();
+        var owners = new Dictionary<CognitiveGraphNode, SymbolOccurrence>(ReferenceEqualityComparer.Instance);
+        foreach (var declaration in declarations)
+        {
+            if (declaration.Node.Parent is { } owner)
+            {
+                owners.TryAdd(owner, declaration);
+            }
+        }
+
+        // Containers are named before what they contain by taking declarations by depth, and siblings are
+        // disambiguated in source order.
+        var containers = new Dictionary<SymbolOccurrence, (SymbolOccurrence? Container, int Depth)>(ReferenceEqualityComparer.Instance);
+        foreach (var declaration in declarations)
+        {
+            SymbolOccurrence? container = null;
+            var depth = 0;
+            for (var node = declaration.Node.Parent?.Parent; node != null; node = node.Parent)
+            {
+                if (owners.TryGetValue(node, out var owner))
+                {
+                    container ??= owner;
+                    depth++;
+                }
+            }
+
+            containers[declaration] = (container, depth);
+        }
+
+        var prefix = $"minotaur {EscapeHeader(_workspace.Parser.Grammar.Name)} {EscapeHeader(document.Path)} . ";
+        var descriptors = new Dictionary<SymbolOccurrence, string>(ReferenceEqualityComparer.Instance);
+        var seen = new Dictionary<string, int>(StringComparer.Ordinal);
+        foreach (var declaration in declarations.OrderBy(d => containers[d].Depth))
+        {
+            var container = containers[declaration].Container;
+            var scope = container != null ? descriptors[container] + "/" : string.Empty;
+            var name = EscapeName(declaration.Name);
+            var count = seen.GetValueOrDefault(scope + name);
+            seen[scope + name] = count + 1;
+            descriptors[declaration] = scope + name + (count > 0 ? $"({count})" : string.Empty);
+        }
+
+        var monikers = new Dictionary<SymbolOccurrence, string>(ReferenceEqualityComparer.Instance);
+        foreach (var declaration in declarations)
+        {
+            monikers[declaration] = prefix + descriptors[declaration] + ".";
+        }
+
+        cache[document.Path] = monikers;
+        return monikers;
+    }
+
+    private static Func<SymbolOccurrence, string?> GetDocumentation(ParseResult parse)
+    {
+        if (parse.Grammar == null)
+        {
+            return _ => null;
+        }
+
+        var input = parse.Input;
+        var lineIndex = new LineIndex(input);
+        var tokens = parse.Tokens.Where(t => !t.IsSynthetic).ToList();
+
+        // The comments that are alone on their lines, by the line they end on.
+        var comments = new Dictionary<int, (int Line, string Text)>();
+        foreach (var (offset, text) in DirectiveSet.ReadComments(parse.Grammar, input, tokens))
+        {
+            var start = offset;
+            while (start > 0 && input[start - 1] is ' ' or '\t')
+            {
+                start--;
+            }
+
+            if (start == 0 || input[start - 1] == '\n')
+            {
+                var trimmed = text.TrimEnd('\r', '\n');
+                comments[lineIndex.GetLineColumn(offset + trimmed.Length).Line] = (lineIndex.GetLineColumn(offset).Line, trimmed);
+            }
+        }
+
+        return declaration =>
+        {
+            var position = declaration.Node.Parent?.SourcePosition ?? declaration.Location;
+            if (position == null)
+            {
+                return null;
+            }
+
+            var lines = new List<string>();
+            for (var line = position.Line - 1; comments.TryGetValue(line, out var comment); line = comment.Line - 1)
+            {
+                lines.InsertRange(0, StripCommentMarkers(comment.Text));
+            }
+
+            return lines.Count > 0 ? string.Join("\n", lines) : null;
+        };
+    }
+
+    private static IEnumerable<string> StripCommentMarkers(string comment)
+    {
+        var text = comment.Trim();
+        if (text.StartsWith("/*", StringComparison.Ordinal))
+        {
+            text = text[2..];
+            text = text.EndsWith("*/", StringComparison.Ordinal) ? text[..^2] : text;
+            return text.Split('\n')
+                .Select(l => l.Trim().TrimStart('*').Trim())
+                .SkipWhile(l => l.Length == 0)
+                .Reverse()
+                .SkipWhile(l => l.Length == 0)
+                .Reverse();
+        }
+
+        return new[] { text.TrimStart('/', '#', '-', ';', '!').Trim() };
+    }
+
+    private static string EscapeHeader(string part)
+    {
+        return string.IsNullOrEmpty(part) ? "." : part.Replace(" ", "  ");
+    }
+
+    private static string EscapeName(string name)
+    {
+        return name.Length > 0 && name.All(c => char.IsLetterOrDigit(c) || c is '_' or '+' or '-' or '$')
+            ? name
+            : $"`{name.Replace("`", "``")}`";
+    }
+
+    private static (string Fingerprint, Dictionary<string, ManifestEntry> Files) ReadManifest(string json)
+    {
+        try
+        {
+            using var document = JsonDocument.Parse(json);
+            var root = document.RootElement;
+            var format = root.GetProperty("format").GetInt32();
+            if (format != FormatVersion)
+            {
+                throw new FormatException($"Index format {format} is not supported; this build reads format {FormatVersion}");
+            }
+
+            var files = root.GetProperty("files").EnumerateArray().ToDictionary(
+                f => f.GetProperty("path").GetString()!,
+                f => new ManifestEntry(
+                    f.GetProperty("hash").GetString()!,
+                    f.GetProperty("exports").GetString()!,
+                    f.GetProperty("dependencies").EnumerateArray().Select(d => d.GetString()!).ToList()),
+                StringComparer.Ordinal);
+            return (root.GetProperty("fingerprint").GetString()!, files);
+        }
+        catch (Exception ex) when (ex is JsonException or KeyNotFoundException or InvalidOperationException or ArgumentException)
+        {
+            throw new FormatException($"The index manifest is malformed: {ex.Message}", ex);
+        }
+    }
+
+    private static string WriteManifest(string fingerprint, Dictionary<string, ManifestEntry> files)
+    {
+        using var stream = new MemoryStream();
+        using (var writer = new Utf8JsonWriter(stream, new JsonWriterOptions { Indented = true }))
+        {
+            writer.WriteStartObject();
+            writer.WriteNumber("format", FormatVersion);
+            writer.WriteString("fingerprint", fingerprint);
+            writer.WriteStartArray("files");
+            foreach (var (path, entry) in files.OrderBy(f => f.Key, StringComparer.Ordinal))
+            {
+                writer.WriteStartObject();
+                writer.WriteString("path", path);
+                writer.WriteString("hash", entry.Hash);
+                writer.WriteString("exports", entry.ExportsHash);
+                writer.WriteStartArray("dependencies");
+                foreach (var dependency in entry.Dependencies)
+                {
+                    writer.WriteStringValue(dependency);
+                }
+
+                writer.WriteEndArray();
+                writer.WriteEndObject();
+            }
+
+            writer.WriteEndArray();
+            writer.WriteEndObject();
+        }
+
+        return Encoding.UTF8.GetString(stream.ToArray());
+    }
+
+    private sealed record ManifestEntry(string Hash, string ExportsHash, IReadOnlyList<string> Dependencies);
+}
@@ -0,0 +1,151 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// The identifiers of a document grouped by name, with their declarations and references.
+/// </summary>
+public class SymbolTable
+{
+    private readonly Dictionary<string, Symbol> _symbols = new(StringComparer.Ordinal);
+    private readonly List<SymbolOccurrence> _occurrences = new();
+
+    /// <summary>
+    /// Gets the symbols by name.
+    /// </summary>
+    public IReadOnlyDictionary<string, Symbol> Symbols => _symbols;
+
+    /// <summary>
+    /// Gets every occurrence in source order.
+    /// </summary>
+    public IReadOnlyList<SymbolOccurrence> Occurrences => _occurrences;
+
+    /// <summary>
+    /// Gets a value indicating whether any declaration was found.
+    /// </summary>
+    public bool HasDeclarations => _symbols.Values.Any(s => s.Declarations.Count > 0);
+
+    /// <summary>
+    /// Gets the declarations visible to other files.
+    /// </summary>
+    public IEnumerable<SymbolOccurrence> Exports => _occurrences.Where(o => o.IsExported);
+
+    /// <summary>
+    /// Looks up a symbol by name.
+    /// </summary>
+    /// <param name="name">The symbol name.</param>
+    /// <returns>The symbol, or null if the name does not occur.</returns>
+    public Symbol? Lookup(string name)
+    {
+        return _symbols.TryGetValue(name, out var symbol) ? symbol : null;
+    }
+
+    /// <summary>
+    /// Adds an occurrence to the table.
+    /// </summary>
+    /// <param name="occurrence">The occurrence to add.</param>
+    public void Add(SymbolOccurrence occurrence)
+    {
+        ArgumentNullException.ThrowIfNull(occurrence);
+
+        if (!_symbols.TryGetValue(occurrence.Name, out var symbol))
+        {
+            symbol = new Symbol(occurrence.Name);
+            _symbols[occurrence.Name] = symbol;
+        }
+
+        if (occurrence.Kind == SymbolOccurrenceKind.Declaration)
+        {
+            symbol.Declarations.Add(occurrence);
+        }
+        else
+        {
+            symbol.References.Add(occurrence);
+        }
+
+        _occurrences.Add(occurrence);
+    }
+}
+
+/// <summary>
+/// A named symbol with its declarations and references.
+/// </summary>
+public class Symbol
+{
+    /// <summary>
+    /// Initializes a new instance of the Symbol class.
+    /// </summary>
+    /// <param name="name">The symbol name.</param>
+    public Symbol(string name)
+    {
+        Name = name;
+    }
+
+    /// <summary>
+    /// Gets the symbol name.
+    /// </summary>
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets the declarations of the symbol.
+    /// </summary>
+    public List<SymbolOccurrence> Declarations { get; } = new();
+
+    /// <summary>
+    /// Gets the references to the symbol.
+    /// </summary>
+    public List<SymbolOccurrence> References { get; } = new();
+}
+
+/// <summary>
+/// A single occurrence of an identifier.
+/// </summary>
+/// <param name="Name">The identifier text.</param>
+/// <param name="Kind">Whether the occurrence declares or references the symbol.</param>
+/// <param name="Rule">The rule of the node directly containing the identifier.</param>
+/// <param name="Node">The terminal node of the identifier.</param>
+public sealed record SymbolOccurrence(string Name, SymbolOccurrenceKind Kind, string Rule, TerminalNode Node)
+{
+    /// <summary>
+    /// Gets the source location of the occurrence.
+    /// </summary>
+    public SourcePosition? Location => Node.SourcePosition;
+
+    /// <summary>
+    /// Gets a value indicating whether the declaration is visible to other files.
+    /// </summary>
+    public bool IsExported { get; init; }
+}
+
+/// <summary>
+/// Kinds of symbol occurrences.
+/// </summary>
+public enum SymbolOccurrenceKind
+{
+    /// <summary>
+    /// The occurrence introduces the symbol.
+    /// </summary>
+    Declaration,
+
+    /// <summary>
+    /// The occurrence uses the symbol.
+    /// </summary>
+    Reference
+}
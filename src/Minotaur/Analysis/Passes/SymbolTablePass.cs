@@ -0,0 +1,360 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Builds a <see cref="SymbolTable"/> from the identifier tokens of a parse tree.
+/// The first identifier directly under a declaration rule is a declaration; all others are references.
+/// Declaration rules are listed in the grammar's "DeclarationRules" metadata, or otherwise recognized by name
+/// (rules containing "decl", "def", "assign", "binding" or "param").
+/// Declarations inside rules listed in the "ExportRules" metadata are exported to other files, and identifiers
+/// selected by the "ImportQuery" metadata name imported modules rather than symbols. Symbol names are normalized
+/// as the "IdentifierNormalization" metadata says (see <see cref="UnicodePass"/>).
+/// When the grammar lists its call rules in the "CallRules" metadata and its parameter rules in "ParameterRules",
+/// the pass produces parameter name hints: each argument of a call to a function declared in the file is hinted
+/// with the name of the parameter it is passed to, the first identifier of that function's n-th parameter node.
+/// </summary>
+[AnalysisPass]
+public class SymbolTablePass : PartitionedAnalysisPass, IInlayHintProducer
+{
+    /// <summary>
+    /// The name of the pass.
+    /// </summary>
+    public const string PassName = "symbols";
+
+    private static readonly Regex DeclarationRuleName = new("decl|def|assign|binding|param", RegexOptions.IgnoreCase | RegexOptions.CultureInvariant);
+
+    /// <inheritdoc />
+    public override string Name => PassName;
+
+    /// <inheritdoc />
+    public override object? Prepare(AnalysisContext context, IReadOnlyList<CognitiveGraphNode> partitions)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+        ArgumentNullException.ThrowIfNull(partitions);
+
+        if (context.Parse.Tree == null)
+        {
+            return null;
+        }
+
+        var grammar = context.Parse.Grammar?.Source;
+        var identifierKinds = GetIdentifierKinds(grammar);
+        var declarationRules = SplitRules(grammar?.Metadata.GetValueOrDefault("DeclarationRules"));
+        var exportRules = SplitRules(grammar?.Metadata.GetValueOrDefault("ExportRules")) ?? new HashSet<string>();
+        var importQuery = grammar?.Metadata.GetValueOrDefault("ImportQuery") is { } query ? TreeQuery.Parse(query) : null;
+        var moduleNames = importQuery?.Select(context.Parse.Tree).ToHashSet() ?? new HashSet<CognitiveGraphNode>();
+
+        // Whether a partition starts out exported depends on the rules above it, worked out once per ancestor.
+        var exported = new Dictionary<CognitiveGraphNode, bool>();
+        if (exportRules.Count > 0)
+        {
+            foreach (var partition in partitions)
+            {
+                IsExported(partition.Parent, exportRules, exported);
+            }
+        }
+
+        return new CollectScope(identifierKinds, declarationRules, exportRules, moduleNames, UnicodePass.GetNormalizationForm(grammar), partitions, exported);
+    }
+
+    /// <inheritdoc />
+    public override object? RunPartition(AnalysisContext context, CognitiveGraphNode partition, object? state)
+    {
+        ArgumentNullException.ThrowIfNull(partition);
+
+        var scope = (CollectScope)state!;
+        var occurrences = new List<SymbolOccurrence>();
+        var exported = partition.Parent != null && scope.Exported.GetValueOrDefault(partition.Parent);
+        Collect(partition, occurrences, scope, exported, partitions: null);
+        return occurrences;
+    }
+
+    /// <inheritdoc />
+    public override object? Merge(AnalysisContext context, IReadOnlyList<object?> results, object? state)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+        ArgumentNullException.ThrowIfNull(results);
+
+        var table = new SymbolTable();
+        if (context.Parse.Tree == null || state is not CollectScope scope)
+        {
+            return table;
+        }
+
+        // Collecting the rest of the tree splices each partition's occurrences in where its subtree would have been
+        // walked, so the table lists them in the same order as a walk of the whole tree.
+        var partitions = new Dictionary<CognitiveGraphNode, List<SymbolOccurrence>>();
+        for (var i = 0; i < results.Count; i++)
+        {
+            partitions[scope.Partitions[i]] = (List<SymbolOccurrence>)results[i]!;
+        }
+
+        var collected = new List<SymbolOccurrence>();
+        Collect(context.Parse.Tree, collected, scope, exported: false, partitions);
+        foreach (var occurrence in collected)
+        {
+            table.Add(occurrence);
+            var key = occurrence.Kind == SymbolOccurrenceKind.Declaration ? "symbol.declaration" : "symbol.reference";
+            context.Annotate(key, occurrence.Name, occurrence.Location);
+        }
+
+        return table;
+    }
+
+    /// <summary>
+    /// Gets the token kinds that are identifiers: IDENTIFIER and the grammar's identifier token patterns.
+    /// </summary>
+    internal static HashSet<string> GetIdentifierKinds(Grammar? grammar)
+    {
+        var identifierKinds = new HashSet<string>(StringComparer.Ordinal) { "IDENTIFIER" };
+        if (grammar != null)
+        {
+            identifierKinds.UnionWith(grammar.TokenRules.GetPatternsByType(TokenType.Identifier).Select(p => p.Name));
+        }
+
+        return identifierKinds;
+    }
+
+    /// <inheritdoc />
+    public IReadOnlySet<string> GetHintRules(AnalysisContext context)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+
+        var metadata = context.Parse.Grammar?.Source.Metadata;
+        return metadata != null && metadata.ContainsKey("ParameterRules")
+            ? SplitRules(metadata.GetValueOrDefault("CallRules")) ?? new HashSet<string>()
+            : new HashSet<string>();
+    }
+
+    /// <inheritdoc />
+    public IEnumerable<InlayHint> GetHints(AnalysisContext context, CognitiveGraphNode node)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+        ArgumentNullException.ThrowIfNull(node);
+
+        if (!context.TryGetResult<SymbolTable>(PassName, out var table))
+        {
+            return Array.Empty<InlayHint>();
+        }
+
+        // The callee is the last identifier before the argument list, so "a::b(x)" calls b.
+        var grammar = context.Parse.Grammar?.Source;
+        var identifierKinds = GetIdentifierKinds(grammar);
+        var terminals = Terminals(node).ToList();
+        var open = terminals.FindIndex(t => t.Text == "(");
+        var callee = open > 0 ? terminals.Take(open).LastOrDefault(t => identifierKinds.Contains(t.TokenType)) : null;
+        if (callee == null)
+        {
+            return Array.Empty<InlayHint>();
+        }
+
+        var parameterRules = SplitRules(grammar?.Metadata.GetValueOrDefault("ParameterRules")) ?? new HashSet<string>();
+        var function = table!.Lookup(callee.Text)?.Declarations
+            .Select(d => d.Node.Parent)
+            .FirstOrDefault(p => p is NonTerminalNode declaring && !parameterRules.Contains(declaring.RuleName));
+        var parameters = function != null ? GetParameterNames(function, parameterRules, identifierKinds) : new List<string>();
+
+        var hints = new List<InlayHint>();
+        var arguments = SplitArguments(terminals, open);
+        for (var i = 0; i < Math.Min(arguments.Count, parameters.Count); i++)
+        {
+            // An argument that is just the parameter's name says it already.
+            var argument = arguments[i];
+            if (argument.Count == 0 || (argument.Count == 1 && argument[0].Text == parameters[i]) || argument[0].SourcePosition is not { } position)
+            {
+                continue;
+            }
+
+            var at = position with { Length = 0, EndLine = position.Line, EndColumn = position.Column };
+            hints.Add(new InlayHint(at, parameters[i] + ":", InlayHintKind.Parameter, $"Parameter {parameters[i]} of {callee.Text}"));
+        }
+
+        return hints;
+    }
+
+    internal static HashSet<string>? SplitRules(string? value)
+    {
+        return value?
+            .Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries)
+            .ToHashSet(StringComparer.Ordinal);
+    }
+
+    private static bool IsExported(CognitiveGraphNode? node, HashSet<string> exportRules, Dictionary<CognitiveGraphNode, bool> exported)
+    {
+        if (node == null)
+        {
+            return false;
+        }
+
+        // Iterative, as recursive lists make the chain of ancestors as long as the list.
+        var chain = new Stack<CognitiveGraphNode>();
+        for (var current = node; current != null && !exported.ContainsKey(current); current = current.Parent)
+        {
+            chain.Push(current);
+        }
+
+        while (chain.Count > 0)
+        {
+            var current = chain.Pop();
+            exported[current] = (current.Parent != null && exported[current.Parent])
+                || (current is NonTerminalNode nonTerminal && exportRules.Contains(nonTerminal.RuleName));
+        }
+
+        return exported[node];
+    }
+
+    private static List<string> GetParameterNames(CognitiveGraphNode function, HashSet<string> parameterRules, HashSet<string> identifierKinds)
+    {
+        var names = new List<string>();
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(function);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node is NonTerminalNode parameter && parameterRules.Contains(parameter.RuleName))
+            {
+                if (node.Children.OfType<TerminalNode>().FirstOrDefault(t => identifierKinds.Contains(t.TokenType)) is { } name)
+                {
+                    names.Add(name.Text);
+                }
+
+                continue;
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+
+        return names;
+    }
+
+    // The tokens of each argument between the opening parenthesis at index open and its closing one, split at
+    // the commas that are not nested in brackets.
+    private static List<List<TerminalNode>> SplitArguments(List<TerminalNode> terminals, int open)
+    {
+        var arguments = new List<List<TerminalNode>> { new() };
+        var depth = 0;
+        for (var i = open; i < terminals.Count; i++)
+        {
+            var text = terminals[i].Text;
+            if (text is ")" or "]" or "}")
+            {
+                depth--;
+                if (depth == 0)
+                {
+                    break;
+                }
+            }
+
+            if (depth == 1 && text == ",")
+            {
+                arguments.Add(new List<TerminalNode>());
+            }
+            else if (depth >= 1)
+            {
+                arguments[^1].Add(terminals[i]);
+            }
+
+            if (text is "(" or "[" or "{")
+            {
+                depth++;
+            }
+        }
+
+        return arguments;
+    }
+
+    private static IEnumerable<TerminalNode> Terminals(CognitiveGraphNode root)
+    {
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(root);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node is TerminalNode terminal)
+            {
+                yield return terminal;
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+    }
+
+    private static void Collect(
+        CognitiveGraphNode node,
+        List<SymbolOccurrence> occurrences,
+        CollectScope scope,
+        bool exported,
+        Dictionary<CognitiveGraphNode, List<SymbolOccurrence>>? partitions)
+    {
+        if (node is not NonTerminalNode nonTerminal)
+        {
+            return;
+        }
+
+        var isDeclaration = scope.DeclarationRules?.Contains(nonTerminal.RuleName) ?? DeclarationRuleName.IsMatch(nonTerminal.RuleName);
+        exported |= scope.ExportRules.Contains(nonTerminal.RuleName);
+
+        foreach (var child in node.Children)
+        {
+            if (child is TerminalNode terminal && scope.IdentifierKinds.Contains(terminal.TokenType))
+            {
+                if (scope.ModuleNames.Contains(terminal))
+                {
+                    continue;
+                }
+
+                var kind = isDeclaration ? SymbolOccurrenceKind.Declaration : SymbolOccurrenceKind.Reference;
+                var name = scope.Normalization is { } form ? terminal.Text.Normalize(form) : terminal.Text;
+                occurrences.Add(new SymbolOccurrence(name, kind, nonTerminal.RuleName, terminal)
+                {
+                    IsExported = isDeclaration && exported
+                });
+                isDeclaration = false;
+            }
+            else if (partitions != null && partitions.TryGetValue(child, out var collected))
+            {
+                occurrences.AddRange(collected);
+            }
+            else
+            {
+                Collect(child, occurrences, scope, exported, partitions);
+            }
+        }
+    }
+
+    private sealed record CollectScope(
+        HashSet<string> IdentifierKinds,
+        HashSet<string>? DeclarationRules,
+        HashSet<string> ExportRules,
+        HashSet<CognitiveGraphNode> ModuleNames,
+        NormalizationForm? Normalization,
+        IReadOnlyList<CognitiveGraphNode> Partitions,
+        Dictionary<CognitiveGraphNode, bool> Exported);
+}
@@ -0,0 +1,173 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+using System.Globalization;
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// Reports Unicode problems in identifiers and source text: identifiers that are spelled differently but equal
+/// after normalization, different identifiers that look alike according to the bundled confusables table, and
+/// bidirectional control characters that can make source display differently from how it parses.
+/// </summary>
+/// <remarks>
+/// The pass is opt-in: it is not discovered with the built-in passes and has to be registered. How identifiers
+/// are normalized for the symbol table is set per grammar by the "IdentifierNormalization" metadata (NFC, NFD,
+/// NFKC, NFKD or none, the default); symbol names are normalized while the tree keeps the original text.
+/// </remarks>
+public class UnicodePass : IAnalysisPass
+{
+    /// <summary>
+    /// The name of the pass.
+    /// </summary>
+    public const string PassName = "unicode";
+
+    /// <summary>
+    /// The metadata key of the identifier normalization policy.
+    /// </summary>
+    public const string NormalizationKey = "IdentifierNormalization";
+
+    private static readonly string[] RequiredPasses = { SymbolTablePass.PassName };
+
+    /// <inheritdoc />
+    public string Name => PassName;
+
+    /// <inheritdoc />
+    public IReadOnlyList<string> Dependencies => RequiredPasses;
+
+    /// <summary>
+    /// Gets the identifier normalization policy of a grammar.
+    /// </summary>
+    /// <param name="grammar">The grammar, if known.</param>
+    /// <returns>The normalization form, or null if identifiers are compared as written.</returns>
+    /// <exception cref="InvalidOperationException">The metadata names an unknown form.</exception>
+    public static NormalizationForm? GetNormalizationForm(Grammar? grammar)
+    {
+        var policy = grammar?.Metadata.GetValueOrDefault(NormalizationKey);
+        return policy?.ToUpperInvariant() switch
+        {
+            null or "NONE" => null,
+            "NFC" => NormalizationForm.FormC,
+            "NFD" => NormalizationForm.FormD,
+            "NFKC" => NormalizationForm.FormKC,
+            "NFKD" => NormalizationForm.FormKD,
+            _ => throw new InvalidOperationException($"Unknown identifier normalization '{policy}'; expected NFC, NFD, NFKC, NFKD or none")
+        };
+    }
+
+    /// <inheritdoc />
+    public object? Run(AnalysisContext context)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+
+        ReportBidiControls(context);
+
+        var table = context.GetResult<SymbolTable>(SymbolTablePass.PassName);
+        var form = GetNormalizationForm(context.Parse.Grammar?.Source);
+
+        // The first occurrence of every distinct spelling, in source order.
+        var spellings = new Dictionary<string, SymbolOccurrence>(StringComparer.Ordinal);
+        foreach (var occurrence in table.Occurrences.OrderBy(o => o.Location?.Offset ?? 0))
+        {
+            spellings.TryAdd(occurrence.Node.Text, occurrence);
+        }
+
+        foreach (var group in spellings.Values.GroupBy(o => o.Node.Text.Normalize(form ?? NormalizationForm.FormC)).Where(g => g.Count() > 1))
+        {
+            var first = group.First();
+            foreach (var other in group.Skip(1))
+            {
+                Report(context, DiagnosticCodes.UnnormalizedIdentifier, other, first,
+                    ("name", other.Node.Text), ("other", first.Node.Text), ("normalized", form != null ? "yes" : "no"));
+            }
+        }
+
+        // Spellings that only differ in normalization were reported above.
+        var names = spellings.Values.GroupBy(o => o.Name.Normalize(form ?? NormalizationForm.FormC)).Select(g => g.First()).ToList();
+        foreach (var group in names.GroupBy(o => Confusables.Skeleton(o.Name)).Where(g => g.Count() > 1))
+        {
+            var first = group.First();
+            foreach (var other in group.Skip(1))
+            {
+                Report(context, DiagnosticCodes.ConfusableIdentifier, other, first,
+                    ("name", other.Name), ("codePoints", Describe(other.Name)), ("other", first.Name), ("otherCodePoints", Describe(first.Name)));
+            }
+        }
+
+        return null;
+    }
+
+    private static void Report(AnalysisContext context, string code, SymbolOccurrence occurrence, SymbolOccurrence related, params (string Name, object Value)[] arguments)
+    {
+        var diagnostic = new Diagnostic
+        {
+            Code = code,
+            Severity = DiagnosticSeverity.Warning,
+            Location = occurrence.Location
+        };
+
+        if (related.Location is { } location)
+        {
+            diagnostic.WithMessage(code + ".related", arguments.Append(("line", location.Line)).Append(("column", location.Column)).ToArray());
+            diagnostic.Data["related"] = location;
+        }
+        else
+        {
+            diagnostic.WithMessage(code, arguments);
+        }
+
+        context.Report(diagnostic);
+    }
+
+    private static void ReportBidiControls(AnalysisContext context)
+    {
+        var text = context.Parse.Input;
+        LineIndex? lines = null;
+        for (var i = 0; i < text.Length; i++)
+        {
+            if (!IsBidiControl(text[i]))
+            {
+                continue;
+            }
+
+            lines ??= new LineIndex(text);
+            context.Report(new Diagnostic
+            {
+                Code = DiagnosticCodes.BidiControlCharacter,
+                Severity = DiagnosticSeverity.Error,
+                Location = lines.GetPosition(i, 1, context.FilePath),
+                Data = { ["codePoint"] = (int)text[i] }
+            }.WithMessage(DiagnosticCodes.BidiControlCharacter, ("codePoint", ((int)text[i]).ToString("X4", CultureInfo.InvariantCulture))));
+        }
+    }
+
+    // LRE, RLE, PDF, LRO, RLO, LRI, RLI, FSI, PDI and the implicit marks LRM, RLM and ALM.
+    private static bool IsBidiControl(char c)
+    {
+        return c is >= '\u202A' and <= '\u202E' or >= '\u2066' and <= '\u2069' or '\u200E' or '\u200F' or '\u061C';
+    }
+
+    private static string Describe(string name)
+    {
+        return string.Join(" ", name.Where(c => c > 0x7F).Distinct().Select(c => $"U+{(int)c:X4}").DefaultIfEmpty("ASCII"));
+    }
+}
@@ -0,0 +1,612 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Security.Cryptography;
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// The outcome of restoring an <see cref="AnalysisWorkspace"/> from a checkpoint.
+/// </summary>
+public sealed class CheckpointRestoreResult
+{
+    /// <summary>
+    /// Gets the paths of the documents taken from the checkpoint, ordered by path.
+    /// </summary>
+    public IReadOnlyList<string> Reused { get; init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the paths of the documents parsed and analyzed again because they were new, changed, imported
+    /// changed operators, or the checkpoint could not be used, ordered by path.
+    /// </summary>
+    public IReadOnlyList<string> Reanalyzed { get; init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets why the checkpoint was not used, or null if it was.
+    /// </summary>
+    public string? FallbackReason { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether the checkpoint was used.
+    /// </summary>
+    public bool IsWarm => FallbackReason == null;
+
+    /// <summary>
+    /// Gets the time the restore took, including reanalysis and cross-file resolution.
+    /// </summary>
+    public TimeSpan Elapsed { get; init; }
+}
+
+/// <summary>
+/// Reads and writes workspace checkpoints. A checkpoint holds, for every document, a hash of its text, its
+/// tokens, tree, operator table and parse diagnostics, its symbol table and imports, and the diagnostics and
+/// execution records of its analysis run; the global export index and import graph are rebuilt from those.
+/// Results of passes other than the symbol table and import passes are not kept.
+/// </summary>
+/// <remarks>
+/// The file starts with a magic string and <see cref="FormatVersion"/>, followed by the length of the payload,
+/// the payload and its SHA-256 hash. The payload records the grammar fingerprint and the pass signature it was
+/// made with; a checkpoint that differs in any of these, or fails its hash, is rejected as a whole.
+/// </remarks>
+internal static class WorkspaceCheckpoint
+{
+    /// <summary>
+    /// The version of the checkpoint format; checkpoints of other versions are not read.
+    /// </summary>
+    public const int FormatVersion = 1;
+
+    private const string Magic = "MINOTAUR-CHECKPOINT";
+
+    // Metadata set by the node constructors from the node's own properties.
+    private static readonly HashSet<string> ConstructorMetadata = new() { "ruleName", "productionIndex", "text", "tokenType" };
+
+    public static string Hash(string text)
+    {
+        return Convert.ToHexString(SHA256.HashData(Encoding.UTF8.GetBytes(text))).ToLowerInvariant();
+    }
+
+    public static void Write(string path, CheckpointSignature signature, IEnumerable<WorkspaceDocument> documents)
+    {
+        using var payload = new MemoryStream();
+        using (var writer = new BinaryWriter(payload, Encoding.UTF8, leaveOpen: true))
+        {
+            writer.Write(signature.GrammarFingerprint);
+            writer.Write(signature.Passes);
+            writer.Write(signature.HasOperators);
+
+            var list = documents.ToList();
+            writer.Write(list.Count);
+            foreach (var document in list)
+            {
+                WriteDocument(writer, document);
+            }
+        }
+
+        var bytes = payload.ToArray();
+        var temporary = path + ".tmp";
+        using (var writer = new BinaryWriter(File.Create(temporary), Encoding.UTF8))
+        {
+            writer.Write(Magic);
+            writer.Write(FormatVersion);
+            writer.Write(bytes.Length);
+            writer.Write(bytes);
+            writer.Write(SHA256.HashData(bytes));
+        }
+
+        File.Move(temporary, path, overwrite: true);
+    }
+
+    /// <summary>
+    /// Reads the documents of a checkpoint whose text is unchanged.
+    /// </summary>
+    /// <exception cref="InvalidDataException">The checkpoint is corrupt, of another version, or was made for
+    /// another grammar or pass configuration.</exception>
+    public static Dictionary<string, WorkspaceDocument> Read(string path, CheckpointSignature signature, CompiledGrammar grammar, IReadOnlyDictionary<string, string> files, SharedInterner? interner = null)
+    {
+        byte[] bytes;
+        using (var reader = new BinaryReader(File.OpenRead(path), Encoding.UTF8))
+        {
+            if (reader.ReadString() != Magic)
+            {
+                throw new InvalidDataException("The file is not a workspace checkpoint");
+            }
+
+            var version = reader.ReadInt32();
+            if (version != FormatVersion)
+            {
+                throw new InvalidDataException($"Checkpoint format version {version} is not supported; expected {FormatVersion}");
+            }
+
+            var length = reader.ReadInt32();
+            bytes = reader.ReadBytes(length);
+            var hash = reader.ReadBytes(32);
+            if (bytes.Length != length || !hash.AsSpan().SequenceEqual(SHA256.HashData(bytes)))
+            {
+                throw new InvalidDataException("The checkpoint is truncated or corrupt");
+            }
+        }
+
+        using var payload = new BinaryReader(new MemoryStream(bytes), Encoding.UTF8);
+        if (payload.ReadString() != signature.GrammarFingerprint)
+        {
+            throw new InvalidDataException("The checkpoint was made with a different grammar");
+        }
+
+        if (payload.ReadString() != signature.Passes || payload.ReadBoolean() != signature.HasOperators)
+        {
+            throw new InvalidDataException("The checkpoint was made with different analysis passes or operator settings");
+        }
+
+        var documents = new Dictionary<string, WorkspaceDocument>(StringComparer.Ordinal);
+        var count = payload.ReadInt32();
+        for (var i = 0; i < count; i++)
+        {
+            var documentPath = payload.ReadString();
+            var hash = payload.ReadString();
+            var text = files.GetValueOrDefault(documentPath);
+
+            // Every record is read in full to reach the next one; only unchanged documents are kept.
+            var document = ReadDocument(payload, grammar, documentPath, text != null && Hash(text) == hash ? text : null, interner);
+            if (document != null)
+            {
+                documents[documentPath] = document;
+            }
+        }
+
+        return documents;
+    }
+
+    private static void WriteDocument(BinaryWriter writer, WorkspaceDocument document)
+    {
+        var parse = document.Parse;
+        writer.Write(document.Path);
+        writer.Write(Hash(parse.Input));
+        writer.Write(parse.StartRule);
+
+        writer.Write(parse.Tokens.Count);
+        foreach (var token in parse.Tokens)
+        {
+            writer.Write(token.Kind);
+            writer.Write(token.Offset);
+            writer.Write(token.IsSynthetic ? -1 : token.Length);
+        }
+
+        WriteDiagnostics(writer, parse.Diagnostics);
+
+        var entries = parse.Operators?.Entries ?? Array.Empty<OperatorEntry>();
+        writer.Write(parse.Operators != null);
+        writer.Write(entries.Count);
+        foreach (var entry in entries)
+        {
+            writer.Write(entry.Symbol);
+            writer.Write((int)entry.Fixity);
+            writer.Write(entry.Precedence);
+            writer.Write(entry.EffectiveOffset);
+            WriteNullable(writer, entry.SourceFile);
+        }
+
+        var nodes = new Dictionary<CognitiveGraphNode, int>(ReferenceEqualityComparer.Instance);
+        writer.Write(parse.Tree != null);
+        if (parse.Tree != null)
+        {
+            WriteNode(writer, parse.Tree, nodes);
+        }
+
+        writer.Write(document.Symbols.Occurrences.Count);
+        foreach (var occurrence in document.Symbols.Occurrences)
+        {
+            writer.Write(occurrence.Name);
+            writer.Write((byte)occurrence.Kind);
+            writer.Write(occurrence.Rule);
+            writer.Write(nodes[occurrence.Node]);
+            writer.Write(occurrence.IsExported);
+        }
+
+        writer.Write(document.Imports.Count);
+        foreach (var import in document.Imports)
+        {
+            writer.Write(import.Module);
+            writer.Write(nodes[import.Node]);
+        }
+
+        writer.Write(document.Run.Executions.Count);
+        foreach (var execution in document.Run.Executions)
+        {
+            writer.Write(execution.Name);
+            writer.Write((byte)execution.Status);
+            WriteNullable(writer, execution.Error);
+        }
+
+        WriteDiagnostics(writer, document.Run.Diagnostics);
+    }
+
+    private static WorkspaceDocument? ReadDocument(BinaryReader reader, CompiledGrammar grammar, string path, string? text, SharedInterner? interner)
+    {
+        var lines = text != null ? new LineIndex(text) : null;
+        var startRule = reader.ReadString();
+        var identifierKinds = interner != null ? SymbolTablePass.GetIdentifierKinds(grammar.Source) : null;
+
+        var tokens = new Token[reader.ReadInt32()];
+        for (var i = 0; i < tokens.Length; i++)
+        {
+            var kind = reader.ReadString();
+            var offset = reader.ReadInt32();
+            var length = reader.ReadInt32();
+            tokens[i] = length < 0
+                ? new Token(kind, string.Empty, offset) { IsSynthetic = true }
+                : new Token(kind, Slice(text, offset, length, identifierKinds?.Contains(kind) == true ? interner : null), offset);
+        }
+
+        if (text != null)
+        {
+            grammar.Lexer.RestoreCaptures(tokens, text);
+        }
+
+        var parseDiagnostics = ReadDiagnostics(reader, lines);
+
+        var hasOperators = reader.ReadBoolean();
+        var operators = new OperatorTable();
+        var entryCount = reader.ReadInt32();
+        for (var i = 0; i < entryCount; i++)
+        {
+            var symbol = reader.ReadString();
+            var fixity = (OperatorFixity)reader.ReadInt32();
+            var precedence = reader.ReadInt32();
+            var effectiveOffset = reader.ReadInt32();
+            operators.Define(symbol, fixity, precedence, effectiveOffset, ReadNullable(reader));
+        }
+
+        var nodes = new List<CognitiveGraphNode>();
+        var tree = reader.ReadBoolean() ? ReadNode(reader, text, lines, path, operators, nodes, interner, identifierKinds) : null;
+
+        var symbols = new SymbolTable();
+        var occurrenceCount = reader.ReadInt32();
+        for (var i = 0; i < occurrenceCount; i++)
+        {
+            var name = reader.ReadString();
+            var kind = (SymbolOccurrenceKind)reader.ReadByte();
+            var rule = reader.ReadString();
+            var node = reader.ReadInt32();
+            var exported = reader.ReadBoolean();
+            if (text != null)
+            {
+                symbols.Add(new SymbolOccurrence(interner?.Intern(name) ?? name, kind, rule, (TerminalNode)nodes[node]) { IsExported = exported });
+            }
+        }
+
+        var imports = new List<ModuleImport>();
+        var importCount = reader.ReadInt32();
+        for (var i = 0; i < importCount; i++)
+        {
+            var module = reader.ReadString();
+            var node = reader.ReadInt32();
+            if (text != null)
+            {
+                imports.Add(new ModuleImport(module, nodes[node]));
+            }
+        }
+
+        var executions = new List<PassExecution>();
+        var executionCount = reader.ReadInt32();
+        for (var i = 0; i < executionCount; i++)
+        {
+            executions.Add(new PassExecution(reader.ReadString(), (PassStatus)reader.ReadByte(), TimeSpan.Zero, ReadNullable(reader)));
+        }
+
+        var runDiagnostics = ReadDiagnostics(reader, lines);
+        if (text == null)
+        {
+            return null;
+        }
+
+        var parse = new ParseResult
+        {
+            Input = text,
+            Grammar = grammar,
+            StartRule = startRule,
+            Tokens = tokens,
+            Tree = tree,
+            Operators = hasOperators ? operators : null,
+            Diagnostics = parseDiagnostics,
+            ParsedLength = tree != null ? text.Length : 0
+        };
+
+        var context = new AnalysisContext(parse, path);
+        foreach (var diagnostic in runDiagnostics)
+        {
+            context.Report(diagnostic);
+        }
+
+        context.SetResult(SymbolTablePass.PassName, symbols);
+        context.SetResult(ImportPass.PassName, (IReadOnlyList<ModuleImport>)imports);
+
+        var run = new AnalysisRun(context, executions, TimeSpan.Zero, fromCache: true);
+        return new WorkspaceDocument(path, AnalysisWorkspace.GetModuleName(path), parse, run, symbols, imports);
+    }
+
+    private static void WriteNode(BinaryWriter writer, CognitiveGraphNode node, Dictionary<CognitiveGraphNode, int> nodes)
+    {
+        nodes[node] = nodes.Count;
+        var terminal = node as TerminalNode;
+        writer.Write(terminal != null);
+        if (terminal != null)
+        {
+            writer.Write(terminal.TokenType);
+        }
+        else
+        {
+            var nonTerminal = (NonTerminalNode)node;
+            writer.Write(nonTerminal.RuleName);
+            writer.Write(nonTerminal.ProductionIndex);
+        }
+
+        writer.Write(node.SourcePosition?.Offset ?? -1);
+        writer.Write(node.SourcePosition?.Length ?? 0);
+        WriteMetadata(writer, node.Metadata);
+
+        writer.Write(node.Children.Count);
+        foreach (var child in node.Children)
+        {
+            WriteNode(writer, child, nodes);
+        }
+    }
+
+    private static CognitiveGraphNode ReadNode(
+        BinaryReader reader,
+        string? text,
+        LineIndex? lines,
+        string path,
+        OperatorTable operators,
+        List<CognitiveGraphNode> nodes,
+        SharedInterner? interner,
+        HashSet<string>? identifierKinds)
+    {
+        var isTerminal = reader.ReadBoolean();
+        var name = reader.ReadString();
+        var productionIndex = isTerminal ? 0 : reader.ReadInt32();
+        var offset = reader.ReadInt32();
+        var length = reader.ReadInt32();
+
+        CognitiveGraphNode node = isTerminal
+            ? new TerminalNode(offset >= 0 ? Slice(text, offset, length, identifierKinds?.Contains(name) == true ? interner : null) : string.Empty, name)
+            : new NonTerminalNode(name, productionIndex);
+        nodes.Add(node);
+
+        if (offset >= 0 && lines != null)
+        {
+            node.SourcePosition = lines.GetPosition(offset, length, path);
+        }
+
+        ReadMetadata(reader, node.Metadata, operators);
+
+        var childCount = reader.ReadInt32();
+        for (var i = 0; i < childCount; i++)
+        {
+            node.AddChild(ReadNode(reader, text, lines, path, operators, nodes, interner, identifierKinds));
+        }
+
+        return node;
+    }
+
+    // Identifier text is shared through the interner, like the lexer shares it when a document is parsed.
+    private static string Slice(string? text, int offset, int length, SharedInterner? interner)
+    {
+        return text == null ? string.Empty : interner != null ? interner.Intern(text.AsSpan(offset, length)) : text.Substring(offset, length);
+    }
+
+    // Only values of the types parsing itself stores are kept: strings, integers, booleans and operator entries.
+    private static void WriteMetadata(BinaryWriter writer, Dictionary<string, object> metadata)
+    {
+        var kept = metadata.Where(m => !ConstructorMetadata.Contains(m.Key) && m.Value is string or int or bool or OperatorEntry).ToList();
+        writer.Write(kept.Count);
+        foreach (var (key, value) in kept)
+        {
+            writer.Write(key);
+            WriteValue(writer, value);
+        }
+    }
+
+    private static void ReadMetadata(BinaryReader reader, Dictionary<string, object> metadata, OperatorTable operators)
+    {
+        var count = reader.ReadInt32();
+        for (var i = 0; i < count; i++)
+        {
+            var key = reader.ReadString();
+            if (ReadValue(reader, operators) is { } value)
+            {
+                metadata[key] = value;
+            }
+        }
+    }
+
+    private static void WriteDiagnostics(BinaryWriter writer, IReadOnlyList<Diagnostic> diagnostics)
+    {
+        writer.Write(diagnostics.Count);
+        foreach (var diagnostic in diagnostics)
+        {
+            writer.Write(diagnostic.Code);
+            writer.Write((int)diagnostic.Severity);
+            writer.Write(diagnostic.Message);
+            WritePosition(writer, diagnostic.Location);
+
+            var data = diagnostic.Data.Where(d => d.Value is string or int or bool or SourcePosition or IEnumerable<string>).ToList();
+            writer.Write(data.Count);
+            foreach (var (key, value) in data)
+            {
+                writer.Write(key);
+                WriteValue(writer, value);
+            }
+        }
+    }
+
+    private static List<Diagnostic> ReadDiagnostics(BinaryReader reader, LineIndex? lines)
+    {
+        var diagnostics = new List<Diagnostic>();
+        var count = reader.ReadInt32();
+        for (var i = 0; i < count; i++)
+        {
+            var diagnostic = new Diagnostic
+            {
+                Code = reader.ReadString(),
+                Severity = (DiagnosticSeverity)reader.ReadInt32(),
+                Message = reader.ReadString(),
+                Location = ReadPosition(reader, lines)
+            };
+
+            var dataCount = reader.ReadInt32();
+            for (var d = 0; d < dataCount; d++)
+            {
+                var key = reader.ReadString();
+                if (ReadValue(reader, null, lines) is { } value)
+                {
+                    diagnostic.Data[key] = value;
+                }
+            }
+
+            diagnostics.Add(diagnostic);
+        }
+
+        return diagnostics;
+    }
+
+    private static void WriteValue(BinaryWriter writer, object value)
+    {
+        switch (value)
+        {
+            case string text:
+                writer.Write((byte)0);
+                writer.Write(text);
+                break;
+
+            case int number:
+                writer.Write((byte)1);
+                writer.Write(number);
+                break;
+
+            case bool flag:
+                writer.Write((byte)2);
+                writer.Write(flag);
+                break;
+
+            case OperatorEntry entry:
+                writer.Write((byte)3);
+                writer.Write(entry.Id);
+                break;
+
+            case SourcePosition position:
+                writer.Write((byte)4);
+                WritePosition(writer, position);
+                break;
+
+            case IEnumerable<string> list:
+                var items = list.ToList();
+                writer.Write((byte)5);
+                writer.Write(items.Count);
+                foreach (var item in items)
+                {
+                    writer.Write(item);
+                }
+
+                break;
+        }
+    }
+
+    private static object? ReadValue(BinaryReader reader, OperatorTable? operators, LineIndex? lines = null)
+    {
+        switch (reader.ReadByte())
+        {
+            case 0:
+                return reader.ReadString();
+
+            case 1:
+                return reader.ReadInt32();
+
+            case 2:
+                return reader.ReadBoolean();
+
+            case 3:
+                var id = reader.ReadInt32();
+                return operators != null && id < operators.Entries.Count ? operators.Entries[id] : null;
+
+            case 4:
+                return ReadPosition(reader, lines);
+
+            case 5:
+                var items = new List<string>();
+                var count = reader.ReadInt32();
+                for (var i = 0; i < count; i++)
+                {
+                    items.Add(reader.ReadString());
+                }
+
+                return items;
+
+            default:
+                throw new InvalidDataException("The checkpoint holds a value of an unknown type");
+        }
+    }
+
+    private static void WritePosition(BinaryWriter writer, SourcePosition? position)
+    {
+        writer.Write(position != null);
+        if (position != null)
+        {
+            writer.Write(position.Offset);
+            writer.Write(position.Length);
+            WriteNullable(writer, position.SourceFile);
+        }
+    }
+
+    private static SourcePosition? ReadPosition(BinaryReader reader, LineIndex? lines)
+    {
+        if (!reader.ReadBoolean())
+        {
+            return null;
+        }
+
+        var offset = reader.ReadInt32();
+        var length = reader.ReadInt32();
+        var sourceFile = ReadNullable(reader);
+        return lines?.GetPosition(offset, length, sourceFile);
+    }
+
+    private static void WriteNullable(BinaryWriter writer, string? value)
+    {
+        writer.Write(value != null);
+        if (value != null)
+        {
+            writer.Write(value);
+        }
+    }
+
+    private static string? ReadNullable(BinaryReader reader)
+    {
+        return reader.ReadBoolean() ? reader.ReadString() : null;
+    }
+}
+
+/// <summary>
+/// What a checkpoint must have been made with to be usable.
+/// </summary>
+/// <param name="GrammarFingerprint">The fingerprint of the workspace grammar.</param>
+/// <param name="Passes">The names of the workspace passes in execution order.</param>
+/// <param name="HasOperators">Whether the workspace parses with an operator layer.</param>
+internal sealed record CheckpointSignature(string GrammarFingerprint, string Passes, bool HasOperators);
@@ -0,0 +1,462 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Analysis.Passes;
+
+/// <summary>
+/// A declaration in the <see cref="WorkspaceSymbolIndex"/>.
+/// </summary>
+/// <param name="Name">The declared name.</param>
+/// <param name="Kind">The rule of the node directly containing the declared identifier, such as <c>function</c>.</param>
+/// <param name="Container">The name of the nearest enclosing declaration, or null at the top level of the file.</param>
+/// <param name="Path">The path of the declaring file.</param>
+/// <param name="Location">The span of the declared identifier.</param>
+public sealed record SymbolIndexEntry(string Name, string Kind, string? Container, string Path, SourcePosition? Location)
+{
+    /// <summary>
+    /// Gets a value indicating whether the declaration is visible to other files.
+    /// </summary>
+    public bool IsExported { get; init; }
+}
+
+/// <summary>
+/// How well a query matches a symbol name, best first. Every kind ignores case.
+/// </summary>
+public enum SymbolMatchKind
+{
+    /// <summary>
+    /// The name is the query.
+    /// </summary>
+    Exact,
+
+    /// <summary>
+    /// The name starts with the query.
+    /// </summary>
+    Prefix,
+
+    /// <summary>
+    /// The name contains the query.
+    /// </summary>
+    Substring,
+
+    /// <summary>
+    /// The name contains the characters of the query in order, with gaps.
+    /// </summary>
+    Subsequence
+}
+
+/// <summary>
+/// A symbol found by <see cref="WorkspaceSymbolIndex.Search"/>.
+/// </summary>
+/// <param name="Symbol">The symbol.</param>
+/// <param name="Match">How the query matched the name.</param>
+/// <param name="Penalty">How far the match is from the best of its kind; lower is better. Case differences,
+/// matches that do not start at a word, and gaps between matched characters add to it.</param>
+public sealed record WorkspaceSymbolMatch(SymbolIndexEntry Symbol, SymbolMatchKind Match, int Penalty);
+
+/// <summary>
+/// A fuzzy index of the declarations of every file in a workspace, for <c>workspace/symbol</c> requests.
+/// </summary>
+/// <remarks>
+/// <para>
+/// A query matches a name when its characters occur in the name in order, ignoring case. Characters are compared
+/// after simple case folding of each UTF-16 code unit, lowering the upper case of the character, so that
+/// <c>ς</c>, <c>σ</c> and <c>Σ</c> match one another. Names are grouped, and each group keeps a 64-bit mask of
+/// the folded characters it contains, so that most names are rejected by one mask test before any matching.
+/// </para>
+/// <para>
+/// Results are ranked by <see cref="SymbolMatchKind"/>, then by penalty, then exported symbols before local ones
+/// and by the position of their kind in <see cref="KindOrder"/>, then shorter names first, and finally by name,
+/// path and offset in ordinal order, so that equal queries always give the same order. The index is safe to
+/// search while it is being updated.
+/// </para>
+/// </remarks>
+public sealed class WorkspaceSymbolIndex
+{
+    private readonly object _gate = new();
+    private readonly Dictionary<string, NameGroup> _groups = new(StringComparer.Ordinal);
+    private readonly List<NameGroup> _list = new();
+    private readonly Dictionary<string, IReadOnlyList<SymbolIndexEntry>> _files = new(StringComparer.Ordinal);
+    private int _count;
+
+    /// <summary>
+    /// Gets or sets the symbol kinds to rank first, in order; kinds not listed rank after them.
+    /// </summary>
+    public IReadOnlyList<string> KindOrder { get; set; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the number of indexed symbols.
+    /// </summary>
+    public int Count
+    {
+        get
+        {
+            lock (_gate)
+            {
+                return _count;
+            }
+        }
+    }
+
+    /// <summary>
+    /// Replaces the symbols of a file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <param name="symbols">The declarations of the file.</param>
+    public void Set(string path, IEnumerable<SymbolIndexEntry> symbols)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        ArgumentNullException.ThrowIfNull(symbols);
+
+        var list = symbols.ToList();
+        lock (_gate)
+        {
+            RemoveFile(path);
+            _files[path] = list;
+            foreach (var symbol in list)
+            {
+                if (!_groups.TryGetValue(symbol.Name, out var group))
+                {
+                    group = new NameGroup(symbol.Name, _list.Count);
+                    _groups[symbol.Name] = group;
+                    _list.Add(group);
+                }
+
+                group.Symbols.Add(symbol);
+            }
+
+            _count += list.Count;
+        }
+    }
+
+    /// <summary>
+    /// Removes the symbols of a file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <returns>True if the file had been indexed.</returns>
+    public bool Remove(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        lock (_gate)
+        {
+            return RemoveFile(path);
+        }
+    }
+
+    /// <summary>
+    /// Finds the symbols whose names match a query.
+    /// </summary>
+    /// <param name="query">The query; an empty query matches every symbol.</param>
+    /// <param name="limit">The maximum number of results.</param>
+    /// <returns>The best matches, best first.</returns>
+    public IReadOnlyList<WorkspaceSymbolMatch> Search(string query, int limit = 100)
+    {
+        ArgumentNullException.ThrowIfNull(query);
+        ArgumentOutOfRangeException.ThrowIfNegative(limit);
+
+        if (limit == 0)
+        {
+            return Array.Empty<WorkspaceSymbolMatch>();
+        }
+
+        var folded = Fold(query);
+        var mask = GetMask(folded);
+        var kinds = KindOrder.Select((kind, index) => (kind, index)).DistinctBy(k => k.kind).ToDictionary(k => k.kind, k => k.index, StringComparer.Ordinal);
+        var comparer = Comparer<WorkspaceSymbolMatch>.Create((a, b) => Compare(a, b, kinds));
+
+        // The queue's head is the worst match kept, which a better match replaces.
+        var best = new PriorityQueue<WorkspaceSymbolMatch, WorkspaceSymbolMatch>(Comparer<WorkspaceSymbolMatch>.Create((a, b) => comparer.Compare(b, a)));
+
+        lock (_gate)
+        {
+            foreach (var group in _list)
+            {
+                if ((group.Mask & mask) != mask || !TryMatch(group, query, folded, out var kind, out var penalty))
+                {
+                    continue;
+                }
+
+                foreach (var symbol in group.Symbols)
+                {
+                    var match = new WorkspaceSymbolMatch(symbol, kind, penalty);
+                    if (best.Count < limit)
+                    {
+                        best.Enqueue(match, match);
+                    }
+                    else
+                    {
+                        best.EnqueueDequeue(match, match);
+                    }
+                }
+            }
+        }
+
+        var results = best.UnorderedItems.Select(i => i.Element).ToList();
+        results.Sort(comparer);
+        return results;
+    }
+
+    /// <summary>
+    /// Gets the index entries of the declarations of a file: the kind of a declaration is the rule of the node
+    /// containing its identifier, and its container is the nearest declaration whose node encloses that node.
+    /// </summary>
+    internal static List<SymbolIndexEntry> GetEntries(string path, SymbolTable symbols)
+    {
+        var declarations = symbols.Occurrences.Where(o => o.Kind == SymbolOccurrenceKind.Declaration).ToList();
+        var owners = new Dictionary<CognitiveGraphNode, string>(ReferenceEqualityComparer.Instance);
+        foreach (var declaration in declarations)
+        {
+            if (declaration.Node.Parent is { } owner)
+            {
+                owners.TryAdd(owner, declaration.Name);
+            }
+        }
+
+        return declarations.Select(declaration =>
+        {
+            string? container = null;
+            for (var node = declaration.Node.Parent?.Parent; node != null && container == null; node = node.Parent)
+            {
+                container = owners.GetValueOrDefault(node);
+            }
+
+            return new SymbolIndexEntry(declaration.Name, declaration.Rule, container, path, declaration.Location) { IsExported = declaration.IsExported };
+        }).ToList();
+    }
+
+    /// <summary>
+    /// Folds the case of a text one UTF-16 code unit at a time; the result has the same length.
+    /// </summary>
+    internal static string Fold(string text)
+    {
+        return string.Create(text.Length, text, static (span, source) =>
+        {
+            for (var i = 0; i < source.Length; i++)
+            {
+                span[i] = char.ToLowerInvariant(char.ToUpperInvariant(source[i]));
+            }
+        });
+    }
+
+    private bool RemoveFile(string path)
+    {
+        if (!_files.Remove(path, out var previous))
+        {
+            return false;
+        }
+
+        foreach (var symbol in previous)
+        {
+            var group = _groups[symbol.Name];
+            group.Symbols.Remove(symbol);
+            if (group.Symbols.Count == 0)
+            {
+                // The last group takes the removed group's slot.
+                var last = _list[^1];
+                _list[group.Slot] = last;
+                last.Slot = group.Slot;
+                _list.RemoveAt(_list.Count - 1);
+                _groups.Remove(symbol.Name);
+            }
+        }
+
+        _count -= previous.Count;
+        return true;
+    }
+
+    private static bool TryMatch(NameGroup group, string query, string folded, out SymbolMatchKind kind, out int penalty)
+    {
+        var name = group.Name;
+        if (group.Folded == folded)
+        {
+            kind = SymbolMatchKind.Exact;
+            penalty = name == query ? 0 : 1;
+            return true;
+        }
+
+        if (group.Folded.StartsWith(folded, StringComparison.Ordinal))
+        {
+            kind = SymbolMatchKind.Prefix;
+            penalty = name.StartsWith(query, StringComparison.Ordinal) ? 0 : 1;
+            return true;
+        }
+
+        var index = group.Folded.IndexOf(folded, StringComparison.Ordinal);
+        if (index >= 0)
+        {
+            kind = SymbolMatchKind.Substring;
+            penalty = (IsWordStart(name, index) ? 0 : 1000) + index;
+            return true;
+        }
+
+        kind = SymbolMatchKind.Subsequence;
+        return TryMatchSubsequence(group, folded, out penalty);
+    }
+
+    // Each query character is matched right after the previous one if it can be, else at the next word start,
+    // else at its next occurrence, as long as the rest of the query still fits after it. Characters matched
+    // neither way cost 1000, and every run of consecutive matched characters costs 10.
+    private static bool TryMatchSubsequence(NameGroup group, string folded, out int penalty)
+    {
+        var name = group.Name;
+        var text = group.Folded;
+        penalty = 0;
+        if (folded.Length > text.Length)
+        {
+            return false;
+        }
+
+        Span<int> latest = folded.Length <= 64 ? stackalloc int[folded.Length] : new int[folded.Length];
+        var position = text.Length;
+        for (var q = folded.Length - 1; q >= 0; q--)
+        {
+            position = position > 0 ? text.LastIndexOf(folded[q], position - 1) : -1;
+            if (position < 0)
+            {
+                return false;
+            }
+
+            latest[q] = position;
+        }
+
+        var next = 0;
+        for (var q = 0; q < folded.Length; q++)
+        {
+            var chosen = q > 0 && next <= latest[q] && text[next] == folded[q] ? next : -1;
+            for (var j = next; chosen < 0 && j <= latest[q]; j++)
+            {
+                if (text[j] == folded[q] && IsWordStart(name, j))
+                {
+                    chosen = j;
+                }
+            }
+
+            if (chosen < 0)
+            {
+                chosen = text.IndexOf(folded[q], next);
+                penalty += 1000;
+            }
+
+            if (q == 0 || chosen != next)
+            {
+                penalty += 10;
+            }
+
+            next = chosen + 1;
+        }
+
+        return true;
+    }
+
+    private static bool IsWordStart(string name, int index)
+    {
+        if (index == 0)
+        {
+            return true;
+        }
+
+        var current = name[index];
+        var before = name[index - 1];
+        if (!char.IsLetterOrDigit(current))
+        {
+            return false;
+        }
+
+        return !char.IsLetterOrDigit(before)
+            || (char.IsLower(before) && char.IsUpper(current))
+            || char.IsDigit(before) != char.IsDigit(current)
+            || (char.IsUpper(before) && char.IsUpper(current) && index + 1 < name.Length && char.IsLower(name[index + 1]));
+    }
+
+    private static int Compare(WorkspaceSymbolMatch a, WorkspaceSymbolMatch b, Dictionary<string, int> kinds)
+    {
+        var order = a.Match.CompareTo(b.Match);
+        if (order == 0)
+        {
+            order = a.Penalty.CompareTo(b.Penalty);
+        }
+
+        if (order == 0)
+        {
+            order = b.Symbol.IsExported.CompareTo(a.Symbol.IsExported);
+        }
+
+        if (order == 0)
+        {
+            order = kinds.GetValueOrDefault(a.Symbol.Kind, kinds.Count).CompareTo(kinds.GetValueOrDefault(b.Symbol.Kind, kinds.Count));
+        }
+
+        if (order == 0)
+        {
+            order = a.Symbol.Name.Length.CompareTo(b.Symbol.Name.Length);
+        }
+
+        if (order == 0)
+        {
+            order = string.CompareOrdinal(a.Symbol.Name, b.Symbol.Name);
+        }
+
+        if (order == 0)
+        {
+            order = string.CompareOrdinal(a.Symbol.Path, b.Symbol.Path);
+        }
+
+        return order != 0 ? order : (a.Symbol.Location?.Offset ?? -1).CompareTo(b.Symbol.Location?.Offset ?? -1);
+    }
+
+    private static ulong GetMask(string folded)
+    {
+        var mask = 0UL;
+        foreach (var c in folded)
+        {
+            var bit = c switch
+            {
+                >= 'a' and <= 'z' => c - 'a',
+                >= '0' and <= '9' => 26 + c - '0',
+                '_' => 36,
+                _ => 37 + c % 27
+            };
+            mask |= 1UL << bit;
+        }
+
+        return mask;
+    }
+
+    private sealed class NameGroup
+    {
+        public NameGroup(string name, int slot)
+        {
+            Name = name;
+            Folded = Fold(name);
+            Mask = GetMask(Folded);
+            Slot = slot;
+        }
+
+        public string Name { get; }
+
+        public string Folded { get; }
+
+        public ulong Mask { get; }
+
+        public int Slot { get; set; }
+
+        public List<SymbolIndexEntry> Symbols { get; } = new();
+    }
+}
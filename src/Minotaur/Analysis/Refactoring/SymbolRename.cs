@@ -0,0 +1,97 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Analysis.Passes;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Refactoring;
+
+/// <summary>
+/// Renames a symbol of the source files of an <see cref="AnalysisWorkspace"/>: its declarations and references in
+/// the declaring file, and the references other files resolve to the declaration through their imports.
+/// </summary>
+/// <remarks>
+/// Symbols resolve the way the workspace resolves them: a name declared in a file refers to that declaration
+/// everywhere in the file, and a name the file does not declare refers to the export of an imported file.
+/// </remarks>
+public static class SymbolRename
+{
+    /// <summary>
+    /// Creates the edit renaming the symbol at a position.
+    /// </summary>
+    /// <param name="workspace">The analyzed workspace.</param>
+    /// <param name="path">The path of the file with the position.</param>
+    /// <param name="offset">An offset within an occurrence of the symbol.</param>
+    /// <param name="newName">The new name.</param>
+    /// <returns>The edit of every file mentioning the symbol, against the contents the workspace analyzed.</returns>
+    /// <exception cref="ArgumentException">The file is not in the workspace, no symbol occurs at the offset, or
+    /// the new name does not lex as a single token of the symbol's kind.</exception>
+    /// <exception cref="InvalidOperationException">A file the rename touches already has a symbol with the new
+    /// name, which the renamed occurrences would then refer to.</exception>
+    public static WorkspaceEdit Create(AnalysisWorkspace workspace, string path, int offset, string newName)
+    {
+        ArgumentNullException.ThrowIfNull(workspace);
+        ArgumentNullException.ThrowIfNull(path);
+        ArgumentNullException.ThrowIfNull(newName);
+
+        var document = workspace.GetDocument(path) ?? throw new ArgumentException($"{path} is not in the workspace", nameof(path));
+        var occurrence = document.Symbols.Occurrences.FirstOrDefault(o => o.Location is { } l && offset >= l.Offset && offset <= l.Offset + l.Length)
+            ?? throw new ArgumentException($"No symbol occurs at offset {offset} of {path}", nameof(offset));
+
+        var lexed = workspace.Parser.Lexer.Tokenize(newName);
+        if (lexed.Diagnostics.Count > 0 || lexed.Tokens.Count != 1 || lexed.Tokens[0].Length != newName.Length || lexed.Tokens[0].Kind != occurrence.Node.TokenType)
+        {
+            throw new ArgumentException($"'{newName}' is not a single {occurrence.Node.TokenType} token", nameof(newName));
+        }
+
+        // The declaring file is the one the occurrence's name resolves to.
+        var name = occurrence.Name;
+        var declaring = document;
+        if (document.Symbols.Lookup(name) is not { Declarations.Count: > 0 })
+        {
+            var resolved = document.Resolved.FirstOrDefault(r => ReferenceEquals(r.Reference, occurrence))
+                ?? throw new ArgumentException($"'{name}' at offset {offset} of {path} is not declared in the workspace", nameof(offset));
+            declaring = workspace.GetDocument(resolved.DeclaringPath)!;
+        }
+
+        var occurrences = new List<(WorkspaceDocument Document, SymbolOccurrence Occurrence)>();
+        occurrences.AddRange(declaring.Symbols.Lookup(name)!.Declarations.Concat(declaring.Symbols.Lookup(name)!.References).Select(o => (declaring, o)));
+        foreach (var other in workspace.Documents.Where(d => d != declaring))
+        {
+            occurrences.AddRange(other.Resolved
+                .Where(r => r.DeclaringPath == declaring.Path && r.Declaration.Name == name)
+                .Select(r => (other, r.Reference)));
+        }
+
+        var edit = new WorkspaceEdit();
+        foreach (var touched in occurrences.Select(o => o.Document).Distinct())
+        {
+            if (touched.Symbols.Lookup(newName) != null)
+            {
+                throw new InvalidOperationException($"{touched.Path} already has a symbol named '{newName}'");
+            }
+        }
+
+        foreach (var (owner, renamed) in occurrences)
+        {
+            var location = renamed.Location!;
+            edit.Add(owner.Path, owner.Parse.Input, new TextEdit(location.Offset, location.Length, newName));
+        }
+
+        return edit;
+    }
+}
@@ -0,0 +1,387 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Security.Cryptography;
+using System.Text;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Analysis.Refactoring;
+
+/// <summary>
+/// Text edits to several files, produced by a refactoring from the files' contents at analysis time and applied
+/// to the files on disk all together or not at all.
+/// </summary>
+/// <remarks>
+/// Each file's edits are relative to the content the refactoring saw, identified by its SHA-256 hash; a file
+/// that changed since is a conflict and nothing is written. Application writes every new content to a temporary
+/// file next to its target first and then moves the temporary files into place, keeping a backup of each
+/// original until all moves succeeded, so a failure part way restores the files already replaced. With a
+/// verifying parser, the written files are parsed again and the edit is rolled back if any of them has syntax
+/// errors it did not have before.
+/// </remarks>
+public sealed class WorkspaceEdit
+{
+    private const string TemporarySuffix = ".minotaur-edit";
+    private const string BackupSuffix = ".minotaur-backup";
+
+    private readonly SortedDictionary<string, WorkspaceFileEdit> _files = new(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Gets the edits of each file, ordered by path.
+    /// </summary>
+    public IReadOnlyList<WorkspaceFileEdit> Files => _files.Values.ToList();
+
+    /// <summary>
+    /// Adds an edit to a file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <param name="originalText">The file content the edit is relative to.</param>
+    /// <param name="edit">The edit.</param>
+    /// <exception cref="ArgumentException">Earlier edits of the file were relative to other content, or the edit
+    /// overlaps one of them or lies outside the content.</exception>
+    public void Add(string path, string originalText, TextEdit edit)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        ArgumentNullException.ThrowIfNull(originalText);
+        ArgumentNullException.ThrowIfNull(edit);
+
+        var hash = HashContent(originalText);
+        if (!_files.TryGetValue(path, out var file))
+        {
+            file = new WorkspaceFileEdit(path, hash);
+            _files[path] = file;
+        }
+        else if (file.ContentHash != hash)
+        {
+            throw new ArgumentException($"Edits to {path} were made against different contents", nameof(originalText));
+        }
+
+        if (edit.Offset < 0 || edit.Length < 0 || edit.Offset + edit.Length > originalText.Length)
+        {
+            throw new ArgumentException($"Edit range [{edit.Offset}, {edit.Offset + edit.Length}) is outside {path} of length {originalText.Length}", nameof(edit));
+        }
+
+        file.Add(edit);
+    }
+
+    /// <summary>
+    /// Computes the SHA-256 hash that identifies file content.
+    /// </summary>
+    /// <param name="text">The content.</param>
+    /// <returns>The lower-case hexadecimal hash of the content's UTF-8 bytes.</returns>
+    public static string HashContent(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+        return Convert.ToHexString(SHA256.HashData(Encoding.UTF8.GetBytes(text))).ToLowerInvariant();
+    }
+
+    /// <summary>
+    /// Checks the files on disk against the contents the edits were made for.
+    /// </summary>
+    /// <returns>The files that are missing or changed, ordered by path.</returns>
+    public IReadOnlyList<WorkspaceEditConflict> Validate()
+    {
+        return ReadFiles(out _);
+    }
+
+    /// <summary>
+    /// Applies the edits to the files on disk, or with <see cref="WorkspaceEditOptions.DryRun"/> only computes
+    /// what they would change.
+    /// </summary>
+    /// <param name="options">The application options, or null for the defaults.</param>
+    /// <returns>The outcome, with a unified diff of each file.</returns>
+    public WorkspaceEditResult Apply(WorkspaceEditOptions? options = null)
+    {
+        options ??= new WorkspaceEditOptions();
+
+        var conflicts = ReadFiles(out var originals);
+        if (conflicts.Count > 0)
+        {
+            return new WorkspaceEditResult { Conflicts = conflicts };
+        }
+
+        var updated = _files.Values.ToDictionary(f => f.Path, f => f.ApplyTo(originals[f.Path]), StringComparer.Ordinal);
+        var diffs = _files.Keys.ToDictionary(p => p, p => UnifiedDiff.Create(p, originals[p], updated[p]), StringComparer.Ordinal);
+        if (options.DryRun)
+        {
+            return new WorkspaceEditResult { Diffs = diffs, IsDryRun = true };
+        }
+
+        try
+        {
+            Commit(updated);
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            return new WorkspaceEditResult { Diffs = diffs, Error = ex.Message };
+        }
+
+        var newErrors = options.Verifier != null ? Verify(options.Verifier, originals) : new Dictionary<string, IReadOnlyList<Diagnostic>>();
+        if (newErrors.Count > 0)
+        {
+            Restore(originals);
+            return new WorkspaceEditResult { Diffs = diffs, NewErrors = newErrors, Error = $"The edit introduced syntax errors in {string.Join(", ", newErrors.Keys)} and was rolled back" };
+        }
+
+        return new WorkspaceEditResult { Diffs = diffs, Applied = true };
+    }
+
+    private List<WorkspaceEditConflict> ReadFiles(out Dictionary<string, string> contents)
+    {
+        contents = new Dictionary<string, string>(StringComparer.Ordinal);
+        var conflicts = new List<WorkspaceEditConflict>();
+        foreach (var file in _files.Values)
+        {
+            if (!File.Exists(file.Path))
+            {
+                conflicts.Add(new WorkspaceEditConflict(file.Path, WorkspaceEditConflictKind.Missing, $"{file.Path} no longer exists"));
+                continue;
+            }
+
+            var text = File.ReadAllText(file.Path);
+            if (HashContent(text) != file.ContentHash)
+            {
+                conflicts.Add(new WorkspaceEditConflict(file.Path, WorkspaceEditConflictKind.Changed, $"{file.Path} changed since it was analyzed"));
+                continue;
+            }
+
+            contents[file.Path] = text;
+        }
+
+        return conflicts;
+    }
+
+    private static void Commit(Dictionary<string, string> updated)
+    {
+        var written = new List<string>();
+        var replaced = new List<string>();
+        try
+        {
+            foreach (var (path, text) in updated)
+            {
+                File.WriteAllText(path + TemporarySuffix, text);
+                written.Add(path);
+            }
+
+            // Renames within a directory are atomic, so every file holds either its old or its new content.
+            foreach (var path in written)
+            {
+                File.Copy(path, path + BackupSuffix, overwrite: true);
+                File.Move(path + TemporarySuffix, path, overwrite: true);
+                replaced.Add(path);
+            }
+        }
+        catch
+        {
+            foreach (var path in replaced)
+            {
+                File.Move(path + BackupSuffix, path, overwrite: true);
+            }
+
+            foreach (var path in written.Except(replaced))
+            {
+                File.Delete(path + TemporarySuffix);
+                File.Delete(path + BackupSuffix);
+            }
+
+            throw;
+        }
+
+        foreach (var path in replaced)
+        {
+            File.Delete(path + BackupSuffix);
+        }
+    }
+
+    private static void Restore(Dictionary<string, string> originals)
+    {
+        foreach (var (path, text) in originals)
+        {
+            File.WriteAllText(path + TemporarySuffix, text);
+            File.Move(path + TemporarySuffix, path, overwrite: true);
+        }
+    }
+
+    // An error is new when the file has more errors with its code than before the edit; moved code keeps its
+    // errors but not their positions, so codes are compared rather than locations.
+    private static Dictionary<string, IReadOnlyList<Diagnostic>> Verify(GeneralizedParser parser, Dictionary<string, string> originals)
+    {
+        var newErrors = new Dictionary<string, IReadOnlyList<Diagnostic>>(StringComparer.Ordinal);
+        foreach (var (path, original) in originals)
+        {
+            var before = Errors(parser.Parse(original, new ParseOptions { SourceFile = path }))
+                .GroupBy(d => d.Code)
+                .ToDictionary(g => g.Key, g => g.Count());
+            var after = Errors(parser.Parse(File.ReadAllText(path), new ParseOptions { SourceFile = path }));
+            var introduced = after
+                .GroupBy(d => d.Code)
+                .SelectMany(g => g.Skip(before.GetValueOrDefault(g.Key)))
+                .ToList();
+            if (introduced.Count > 0)
+            {
+                newErrors[path] = introduced;
+            }
+        }
+
+        return newErrors;
+    }
+
+    private static IEnumerable<Diagnostic> Errors(ParseResult parse)
+    {
+        return parse.Diagnostics.Where(d => d.Severity == DiagnosticSeverity.Error);
+    }
+}
+
+/// <summary>
+/// The edits of one file of a <see cref="WorkspaceEdit"/>.
+/// </summary>
+public sealed class WorkspaceFileEdit
+{
+    private readonly List<TextEdit> _edits = new();
+
+    internal WorkspaceFileEdit(string path, string contentHash)
+    {
+        Path = path;
+        ContentHash = contentHash;
+    }
+
+    /// <summary>
+    /// Gets the file path.
+    /// </summary>
+    public string Path { get; }
+
+    /// <summary>
+    /// Gets the SHA-256 hash of the content the edits are relative to.
+    /// </summary>
+    public string ContentHash { get; }
+
+    /// <summary>
+    /// Gets the edits ordered by offset, each relative to the original content.
+    /// </summary>
+    public IReadOnlyList<TextEdit> Edits => _edits;
+
+    /// <summary>
+    /// Applies the edits to the original content.
+    /// </summary>
+    /// <param name="text">The content the edits are relative to.</param>
+    /// <returns>The edited content.</returns>
+    public string ApplyTo(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var result = new StringBuilder(text);
+        for (var i = _edits.Count - 1; i >= 0; i--)
+        {
+            var edit = _edits[i];
+            result.Remove(edit.Offset, edit.Length).Insert(edit.Offset, edit.NewText);
+        }
+
+        return result.ToString();
+    }
+
+    internal void Add(TextEdit edit)
+    {
+        // An insertion goes before a replacement starting at the same offset.
+        var index = _edits.FindIndex(e => e.Offset > edit.Offset || (e.Offset == edit.Offset && e.Length > edit.Length));
+        index = index < 0 ? _edits.Count : index;
+        var overlapsPrevious = index > 0 && _edits[index - 1].Offset + _edits[index - 1].Length > edit.Offset;
+        var overlapsNext = index < _edits.Count && edit.Offset + edit.Length > _edits[index].Offset;
+        if (overlapsPrevious || overlapsNext)
+        {
+            throw new ArgumentException($"Edit range [{edit.Offset}, {edit.Offset + edit.Length}) of {Path} overlaps another edit", nameof(edit));
+        }
+
+        _edits.Insert(index, edit);
+    }
+}
+
+/// <summary>
+/// Options for <see cref="WorkspaceEdit.Apply"/>.
+/// </summary>
+public sealed class WorkspaceEditOptions
+{
+    /// <summary>
+    /// Gets or sets a value indicating whether to only compute the diffs and leave the files alone.
+    /// </summary>
+    public bool DryRun { get; set; }
+
+    /// <summary>
+    /// Gets or sets the parser that checks the edited files for new syntax errors, or null to skip the check.
+    /// </summary>
+    public GeneralizedParser? Verifier { get; set; }
+}
+
+/// <summary>
+/// The outcome of applying a <see cref="WorkspaceEdit"/>.
+/// </summary>
+public sealed class WorkspaceEditResult
+{
+    /// <summary>
+    /// Gets a value indicating whether the files now hold the edited contents.
+    /// </summary>
+    public bool Applied { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether the edit was only previewed.
+    /// </summary>
+    public bool IsDryRun { get; init; }
+
+    /// <summary>
+    /// Gets the unified diff of each file by path; empty when there were conflicts.
+    /// </summary>
+    public IReadOnlyDictionary<string, string> Diffs { get; init; } = new Dictionary<string, string>();
+
+    /// <summary>
+    /// Gets the files that were missing or had changed since analysis, which kept the edit from being applied.
+    /// </summary>
+    public IReadOnlyList<WorkspaceEditConflict> Conflicts { get; init; } = Array.Empty<WorkspaceEditConflict>();
+
+    /// <summary>
+    /// Gets the syntax errors each edited file had after the edit but not before, which rolled it back.
+    /// </summary>
+    public IReadOnlyDictionary<string, IReadOnlyList<Diagnostic>> NewErrors { get; init; } = new Dictionary<string, IReadOnlyList<Diagnostic>>();
+
+    /// <summary>
+    /// Gets why the edit was not applied after the files were checked, or null.
+    /// </summary>
+    public string? Error { get; init; }
+}
+
+/// <summary>
+/// A file that kept a <see cref="WorkspaceEdit"/> from being applied.
+/// </summary>
+/// <param name="Path">The file path.</param>
+/// <param name="Kind">What is wrong with the file.</param>
+/// <param name="Message">A description of the conflict.</param>
+public sealed record WorkspaceEditConflict(string Path, WorkspaceEditConflictKind Kind, string Message);
+
+/// <summary>
+/// Kinds of <see cref="WorkspaceEditConflict"/>.
+/// </summary>
+public enum WorkspaceEditConflictKind
+{
+    /// <summary>
+    /// The file's content differs from the content the edits were made for.
+    /// </summary>
+    Changed,
+
+    /// <summary>
+    /// The file no longer exists.
+    /// </summary>
+    Missing
+}
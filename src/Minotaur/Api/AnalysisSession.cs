@@ -0,0 +1,82 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Analysis.Passes;
+
+namespace Minotaur.Api;
+
+/// <summary>
+/// A set of open documents analysed together, so that references resolve across files. A session is not
+/// thread-safe.
+/// </summary>
+public sealed class AnalysisSession
+{
+    /// <summary>
+    /// Initializes a new instance of the AnalysisSession class.
+    /// </summary>
+    /// <param name="parser">The parser for the session's documents.</param>
+    public AnalysisSession(SourceParser parser)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+
+        Parser = parser;
+        Workspace = new AnalysisWorkspace(parser.Inner);
+    }
+
+    /// <summary>
+    /// Gets the parser for the session's documents.
+    /// </summary>
+    public SourceParser Parser { get; }
+
+    /// <summary>
+    /// Gets the paths of the open documents, in path order.
+    /// </summary>
+    public IReadOnlyList<string> Documents => Workspace.Documents.Select(d => d.Path).ToList();
+
+    internal AnalysisWorkspace Workspace { get; }
+
+    /// <summary>
+    /// Opens or replaces a document and analyses the documents the change affects.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <param name="text">The document content.</param>
+    /// <returns>The paths of the documents analysed again, in path order.</returns>
+    public IReadOnlyList<string> SetDocument(string path, string text)
+    {
+        return Workspace.SetDocument(path, text);
+    }
+
+    /// <summary>
+    /// Closes a document and analyses the documents that imported it.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <returns>The paths of the documents analysed again, in path order.</returns>
+    public IReadOnlyList<string> RemoveDocument(string path)
+    {
+        return Workspace.RemoveDocument(path);
+    }
+
+    /// <summary>
+    /// Gets the parse and analysis diagnostics of an open document.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <returns>The diagnostics, or an empty list if the document is not open.</returns>
+    public IReadOnlyList<DiagnosticInfo> GetDiagnostics(string path)
+    {
+        return Workspace.GetDiagnostics(path).Select(d => new DiagnosticInfo(d)).ToList();
+    }
+}
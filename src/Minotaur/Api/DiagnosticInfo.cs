@@ -0,0 +1,96 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Api;
+
+/// <summary>
+/// How serious a diagnostic is. Later minor versions may add levels.
+/// </summary>
+public enum DiagnosticLevel
+{
+    /// <summary>
+    /// A suggestion.
+    /// </summary>
+    Hint,
+
+    /// <summary>
+    /// Information about the source.
+    /// </summary>
+    Information,
+
+    /// <summary>
+    /// A likely problem that does not stop parsing.
+    /// </summary>
+    Warning,
+
+    /// <summary>
+    /// An error.
+    /// </summary>
+    Error
+}
+
+/// <summary>
+/// A message about source text or a grammar.
+/// </summary>
+public sealed class DiagnosticInfo
+{
+    internal DiagnosticInfo(Diagnostic diagnostic)
+    {
+        Code = diagnostic.Code;
+        Level = diagnostic.Severity switch
+        {
+            DiagnosticSeverity.Hint => DiagnosticLevel.Hint,
+            DiagnosticSeverity.Information => DiagnosticLevel.Information,
+            DiagnosticSeverity.Warning => DiagnosticLevel.Warning,
+            _ => DiagnosticLevel.Error
+        };
+        Message = diagnostic.Message;
+        Span = diagnostic.Location != null ? new SourceSpan(diagnostic.Location) : null;
+    }
+
+    /// <summary>
+    /// Gets the diagnostic code, such as "E0001". Codes are stable across versions.
+    /// </summary>
+    public string Code { get; }
+
+    /// <summary>
+    /// Gets how serious the diagnostic is.
+    /// </summary>
+    public DiagnosticLevel Level { get; }
+
+    /// <summary>
+    /// Gets the message. Messages may be reworded in any release; match on <see cref="Code"/> instead.
+    /// </summary>
+    public string Message { get; }
+
+    /// <summary>
+    /// Gets where the diagnostic applies, or null if it has no location.
+    /// </summary>
+    public SourceSpan? Span { get; }
+
+    /// <summary>
+    /// Returns the diagnostic as "span: level code: message".
+    /// </summary>
+    /// <returns>The formatted diagnostic.</returns>
+    public override string ToString()
+    {
+        var level = Level.ToString().ToLowerInvariant();
+        return Span != null ? $"{Span}: {level} {Code}: {Message}" : $"{level} {Code}: {Message}";
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Parser;
+using Minotaur.Projects.Grammar;
+
+namespace Minotaur.Api;
+
+/// <summary>
+/// A set of grammars loaded from a directory, and the routing of source files to them.
+/// </summary>
+public sealed class GrammarCatalog
+{
+    private readonly Dictionary<string, LanguageGrammar> _grammars;
+    private readonly GrammarDetectionManager _detection = GrammarDetectionManager.CreateDefault();
+
+    private GrammarCatalog(GrammarContainer container)
+    {
+        Container = container;
+        _grammars = new Dictionary<string, LanguageGrammar>(StringComparer.OrdinalIgnoreCase);
+        foreach (var (name, grammar) in container.Grammars.OrderBy(g => g.Key, StringComparer.Ordinal))
+        {
+            _grammars.TryAdd(name, new LanguageGrammar(grammar));
+        }
+
+        Failures = container.Failures.Select(f => $"{f.Path ?? f.Name}: {f.Message}").ToList();
+    }
+
+    /// <summary>
+    /// Gets the names of the loaded grammars, in name order.
+    /// </summary>
+    public IReadOnlyList<string> Names => _grammars.Keys.Order(StringComparer.Ordinal).ToList();
+
+    /// <summary>
+    /// Gets a message for each grammar that could not be loaded.
+    /// </summary>
+    public IReadOnlyList<string> Failures { get; }
+
+    internal GrammarContainer Container { get; }
+
+    /// <summary>
+    /// Loads every grammar file in a directory. Grammars that fail to load are reported in <see cref="Failures"/>.
+    /// </summary>
+    /// <param name="directory">The directory.</param>
+    /// <param name="cancellationToken">A token cancelling the load.</param>
+    /// <returns>The catalog.</returns>
+    public static async Task<GrammarCatalog> LoadDirectoryAsync(string directory, CancellationToken cancellationToken = default)
+    {
+        ArgumentNullException.ThrowIfNull(directory);
+
+        return new GrammarCatalog(await GrammarContainer.LoadDirectoryAsync(directory, cancellationToken: cancellationToken));
+    }
+
+    /// <summary>
+    /// Gets a grammar by name, ignoring case.
+    /// </summary>
+    /// <param name="name">The grammar name.</param>
+    /// <returns>The grammar, or null if the catalog has none by that name.</returns>
+    public LanguageGrammar? Get(string name)
+    {
+        return _grammars.GetValueOrDefault(name);
+    }
+
+    /// <summary>
+    /// Chooses the grammar for a source file from its extension, its content and the project's configuration.
+    /// </summary>
+    /// <param name="filePath">The source file.</param>
+    /// <param name="projectRoot">The root directory of the project the file belongs to.</param>
+    /// <returns>The grammar, or null if none was detected or the detected grammar is not in the catalog.</returns>
+    public async Task<LanguageGrammar?> RouteAsync(string filePath, string projectRoot)
+    {
+        ArgumentNullException.ThrowIfNull(filePath);
+        ArgumentNullException.ThrowIfNull(projectRoot);
+
+        var detection = await GrammarDetectionManager.CreateDefault().DetectGrammarAsync(filePath, projectRoot);
+        return detection.IsSuccessful && detection.GrammarName != null ? Get(detection.GrammarName) : null;
+    }
+
+    /// <summary>
+    /// Chooses the grammar for a source file like <see cref="RouteAsync"/>, but when detection cannot decide between
+    /// grammars in the catalog, such as C and C++ for a <c>.h</c> file, parses the start of the text with each of
+    /// them and chooses the one that parses it best. The decision is remembered for the file until its text changes.
+    /// </summary>
+    /// <param name="filePath">The source file.</param>
+    /// <param name="text">The file's text.</param>
+    /// <param name="projectRoot">The root directory of the project the file belongs to.</param>
+    /// <param name="cancellationToken">A token cancelling the routing and the parses.</param>
+    /// <returns>The grammar, or null if none was detected or the detected grammar is not in the catalog.</returns>
+    public async Task<LanguageGrammar?> ResolveOrRaceAsync(string filePath, string text, string projectRoot, CancellationToken cancellationToken = default)
+    {
+        ArgumentNullException.ThrowIfNull(filePath);
+        ArgumentNullException.ThrowIfNull(text);
+        ArgumentNullException.ThrowIfNull(projectRoot);
+
+        var trace = await _detection.ResolveOrRaceAsync(filePath, text, projectRoot, name => Find(name)?.Compiled, cancellationToken: cancellationToken);
+        return trace.Result.IsSuccessful && trace.Result.GrammarName != null ? Find(trace.Result.GrammarName) : null;
+    }
+
+    // Detection names grammars after their files, e.g. "C17.grammar".
+    private LanguageGrammar? Find(string name)
+    {
+        return Get(name) ?? Get(Path.GetFileNameWithoutExtension(name));
+    }
+}
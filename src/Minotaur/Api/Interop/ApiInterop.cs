@@ -0,0 +1,89 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Parser;
+
+namespace Minotaur.Api.Interop;
+
+/// <summary>
+/// Conversions between the <c>Minotaur.Api</c> handles and the engine types behind them, for embedders that need a
+/// feature the stable surface does not cover yet. The members are stable, but the engine types they expose are not
+/// covered by <see cref="MinotaurApi.Version"/> and may change in any release.
+/// </summary>
+public static class ApiInterop
+{
+    /// <summary>
+    /// Gets the compiled grammar behind a grammar handle.
+    /// </summary>
+    /// <param name="grammar">The grammar handle.</param>
+    /// <returns>The compiled grammar.</returns>
+    public static CompiledGrammar GetCompiledGrammar(LanguageGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        return grammar.Compiled;
+    }
+
+    /// <summary>
+    /// Wraps a compiled grammar in a grammar handle.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <returns>The grammar handle.</returns>
+    public static LanguageGrammar FromCompiled(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        return new LanguageGrammar(grammar);
+    }
+
+    /// <summary>
+    /// Gets the engine parser behind a parser handle.
+    /// </summary>
+    /// <param name="parser">The parser handle.</param>
+    /// <returns>The engine parser.</returns>
+    public static GeneralizedParser GetParser(SourceParser parser)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+
+        return parser.Inner;
+    }
+
+    /// <summary>
+    /// Gets the full parse result behind a parse outcome, including its forest and tokens.
+    /// </summary>
+    /// <param name="outcome">The parse outcome.</param>
+    /// <returns>The parse result.</returns>
+    public static ParseResult GetParseResult(ParseOutcome outcome)
+    {
+        ArgumentNullException.ThrowIfNull(outcome);
+
+        return outcome.Result;
+    }
+
+    /// <summary>
+    /// Gets the tree node behind a syntax node.
+    /// </summary>
+    /// <param name="node">The syntax node.</param>
+    /// <returns>The tree node.</returns>
+    public static CognitiveGraphNode GetNode(SyntaxNode node)
+    {
+        ArgumentNullException.ThrowIfNull(node);
+
+        return node.Node;
+    }
+}
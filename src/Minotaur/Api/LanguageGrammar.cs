@@ -0,0 +1,149 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Api;
+
+/// <summary>
+/// The stable embedding surface of Minotaur. Types in <c>Minotaur.Api</c> follow semantic versioning by
+/// <see cref="Version"/>: a minor version only adds types and members, so enums may gain values and switches over
+/// them need a default arm. Their shape is recorded in a public API listing that tests compare against, so a change
+/// to the surface is always deliberate. Everything else in the assembly may change in any release; code that still
+/// needs it goes through <see cref="Interop.ApiInterop"/>.
+/// </summary>
+public static class MinotaurApi
+{
+    /// <summary>
+    /// The semantic version of the <c>Minotaur.Api</c> surface.
+    /// </summary>
+    public const string Version = "1.1.0";
+}
+
+/// <summary>
+/// The error raised when a grammar cannot be read or compiled.
+/// </summary>
+public sealed class GrammarLoadException : Exception
+{
+    /// <summary>
+    /// Initializes a new instance of the GrammarLoadException class.
+    /// </summary>
+    /// <param name="message">Why the grammar could not be loaded.</param>
+    /// <param name="innerException">The exception that caused the failure, if any.</param>
+    public GrammarLoadException(string message, Exception? innerException = null)
+        : base(message, innerException)
+    {
+    }
+}
+
+/// <summary>
+/// A compiled grammar, ready to create parsers. The handle is immutable and may be shared between threads.
+/// </summary>
+public sealed class LanguageGrammar
+{
+    internal LanguageGrammar(CompiledGrammar compiled)
+    {
+        Compiled = compiled;
+    }
+
+    /// <summary>
+    /// Gets the grammar name.
+    /// </summary>
+    public string Name => Compiled.Name;
+
+    /// <summary>
+    /// Gets the rule parsers start from unless an entry point is chosen.
+    /// </summary>
+    public string StartRule => Compiled.StartRule;
+
+    /// <summary>
+    /// Gets the names of the grammar's rules in definition order.
+    /// </summary>
+    public IReadOnlyList<string> Rules => Compiled.Rules.Select(r => r.Name).ToList();
+
+    /// <summary>
+    /// Gets the names of the grammar's entry points, in name order.
+    /// </summary>
+    public IReadOnlyList<string> EntryPoints => Compiled.EntryPoints.Keys.Order(StringComparer.Ordinal).ToList();
+
+    internal CompiledGrammar Compiled { get; }
+
+    /// <summary>
+    /// Compiles a grammar from the text of a grammar file.
+    /// </summary>
+    /// <param name="text">The grammar file content.</param>
+    /// <param name="startRule">The start rule or entry point, or null for the grammar's own.</param>
+    /// <returns>The grammar.</returns>
+    /// <exception cref="GrammarLoadException">The grammar is malformed.</exception>
+    public static LanguageGrammar FromText(string text, string? startRule = null)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        try
+        {
+            return new LanguageGrammar(CompiledGrammar.Compile(new GrammarFileReader().Read(text), startRule));
+        }
+        catch (Exception ex) when (ex is ArgumentException or FormatException)
+        {
+            throw new GrammarLoadException(ex.Message, ex);
+        }
+    }
+
+    /// <summary>
+    /// Reads and compiles a grammar file.
+    /// </summary>
+    /// <param name="path">The grammar file.</param>
+    /// <param name="startRule">The start rule or entry point, or null for the grammar's own.</param>
+    /// <param name="cancellationToken">A token cancelling the read.</param>
+    /// <returns>The grammar.</returns>
+    /// <exception cref="GrammarLoadException">The file cannot be read or the grammar is malformed.</exception>
+    public static async Task<LanguageGrammar> LoadAsync(string path, string? startRule = null, CancellationToken cancellationToken = default)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        string text;
+        try
+        {
+            text = await File.ReadAllTextAsync(path, cancellationToken);
+        }
+        catch (IOException ex)
+        {
+            throw new GrammarLoadException($"Grammar file {path} cannot be read: {ex.Message}", ex);
+        }
+
+        return FromText(text, startRule);
+    }
+
+    /// <summary>
+    /// Creates a parser for the grammar.
+    /// </summary>
+    /// <param name="entryPoint">The entry point to parse, or null for the start rule.</param>
+    /// <returns>The parser.</returns>
+    /// <exception cref="ArgumentException">The grammar has no such entry point.</exception>
+    public SourceParser CreateParser(string? entryPoint = null)
+    {
+        var parser = new GeneralizedParser(Compiled);
+        return new SourceParser(this, entryPoint != null ? parser.ForEntry(entryPoint) : parser);
+    }
+
+    /// <inheritdoc />
+    public override string ToString()
+    {
+        return Name;
+    }
+}
@@ -0,0 +1,88 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Parser;
+
+namespace Minotaur.Api;
+
+/// <summary>
+/// Parses source text with a <see cref="LanguageGrammar"/>. A parser may be shared between threads.
+/// </summary>
+public sealed class SourceParser
+{
+    internal SourceParser(LanguageGrammar grammar, GeneralizedParser parser)
+    {
+        Grammar = grammar;
+        Inner = parser;
+    }
+
+    /// <summary>
+    /// Gets the grammar.
+    /// </summary>
+    public LanguageGrammar Grammar { get; }
+
+    internal GeneralizedParser Inner { get; }
+
+    /// <summary>
+    /// Parses source text.
+    /// </summary>
+    /// <param name="text">The source text.</param>
+    /// <param name="sourceFile">The file name used in diagnostic locations, or null.</param>
+    /// <returns>The outcome. Syntax errors are reported as diagnostics rather than thrown.</returns>
+    public ParseOutcome Parse(string text, string? sourceFile = null)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        return new ParseOutcome(Inner.Parse(text, new ParseOptions { SourceFile = sourceFile }));
+    }
+}
+
+/// <summary>
+/// The outcome of parsing source text: its tree if it parsed, and its diagnostics.
+/// </summary>
+public sealed class ParseOutcome
+{
+    private SyntaxNode? _root;
+
+    internal ParseOutcome(ParseResult result)
+    {
+        Result = result;
+        Diagnostics = result.Diagnostics.Select(d => new DiagnosticInfo(d)).ToList();
+    }
+
+    /// <summary>
+    /// Gets a value indicating whether the text parsed without errors.
+    /// </summary>
+    public bool IsSuccess => Result.IsSuccess;
+
+    /// <summary>
+    /// Gets a value indicating whether some span of the text has more than one derivation.
+    /// </summary>
+    public bool IsAmbiguous => Result.IsAmbiguous;
+
+    /// <summary>
+    /// Gets the root of the syntax tree, or null if the text did not parse.
+    /// </summary>
+    public SyntaxNode? Root => _root ??= Result.Tree != null ? new SyntaxNode(Result.Tree, null, Result.Input) : null;
+
+    /// <summary>
+    /// Gets the diagnostics, in the order they were reported.
+    /// </summary>
+    public IReadOnlyList<DiagnosticInfo> Diagnostics { get; }
+
+    internal ParseResult Result { get; }
+}
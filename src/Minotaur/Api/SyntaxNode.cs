@@ -0,0 +1,152 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Api;
+
+/// <summary>
+/// A location in source text.
+/// </summary>
+public sealed class SourceSpan
+{
+    internal SourceSpan(SourcePosition position)
+    {
+        File = position.SourceFile;
+        Line = position.Line;
+        Column = position.Column;
+        Offset = position.Offset;
+        Length = position.Length;
+    }
+
+    /// <summary>
+    /// Gets the source file, or null if none was given.
+    /// </summary>
+    public string? File { get; }
+
+    /// <summary>
+    /// Gets the 1-based line of the start.
+    /// </summary>
+    public int Line { get; }
+
+    /// <summary>
+    /// Gets the 1-based column of the start.
+    /// </summary>
+    public int Column { get; }
+
+    /// <summary>
+    /// Gets the 0-based character offset of the start.
+    /// </summary>
+    public int Offset { get; }
+
+    /// <summary>
+    /// Gets the length in characters.
+    /// </summary>
+    public int Length { get; }
+
+    /// <summary>
+    /// Returns the span as "file:line:column", or "line:column" without a file.
+    /// </summary>
+    /// <returns>The start of the span.</returns>
+    public override string ToString()
+    {
+        return File != null ? $"{File}:{Line}:{Column}" : $"{Line}:{Column}";
+    }
+}
+
+/// <summary>
+/// A node of a syntax tree: a rule with its children, or a token.
+/// </summary>
+public sealed class SyntaxNode
+{
+    private readonly string _input;
+    private IReadOnlyList<SyntaxNode>? _children;
+
+    internal SyntaxNode(CognitiveGraphNode node, SyntaxNode? parent, string input)
+    {
+        Node = node;
+        Parent = parent;
+        _input = input;
+        Span = node.SourcePosition is { } position ? new SourceSpan(position) : null;
+    }
+
+    /// <summary>
+    /// Gets the rule name of a rule node, or the token kind of a token.
+    /// </summary>
+    public string Name => Node switch
+    {
+        NonTerminalNode rule => rule.RuleName,
+        TerminalNode token => token.TokenType,
+        _ => Node.NodeType
+    };
+
+    /// <summary>
+    /// Gets a value indicating whether the node is a token.
+    /// </summary>
+    public bool IsToken => Node is TerminalNode;
+
+    /// <summary>
+    /// Gets the source text the node covers.
+    /// </summary>
+    public string Text => Node is TerminalNode token
+        ? token.Text
+        : Span != null && Span.Offset + Span.Length <= _input.Length ? _input.Substring(Span.Offset, Span.Length) : string.Empty;
+
+    /// <summary>
+    /// Gets where the node is in the source text, or null if it has no location.
+    /// </summary>
+    public SourceSpan? Span { get; }
+
+    /// <summary>
+    /// Gets the parent node, or null for the root.
+    /// </summary>
+    public SyntaxNode? Parent { get; }
+
+    /// <summary>
+    /// Gets the child nodes in source order.
+    /// </summary>
+    public IReadOnlyList<SyntaxNode> Children => _children ??= Node.Children.Select(c => new SyntaxNode(c, this, _input)).ToList();
+
+    internal CognitiveGraphNode Node { get; }
+
+    /// <summary>
+    /// Enumerates the node's descendants in document order, not including the node itself.
+    /// </summary>
+    /// <returns>The descendants.</returns>
+    public IEnumerable<SyntaxNode> Descendants()
+    {
+        var pending = new Stack<SyntaxNode>(Children.Reverse());
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            yield return node;
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Returns the node as "name 'text'".
+    /// </summary>
+    /// <returns>The node's name and text.</returns>
+    public override string ToString()
+    {
+        return $"{Name} '{Text}'";
+    }
+}
@@ -69,7 +69,14 @@ public abstract class CognitiveGraphNode
     /// <summary>
     /// Gets the collection of child nodes.
     /// </summary>
-    public IReadOnlyList<CognitiveGraphNode> Children => _children.AsReadOnly();
+    public IReadOnlyList<CognitiveGraphNode> Children
+    {
+        get
+        {
+            EnsureChildren();
+            return _children.AsReadOnly();
+        }
+    }
 
     private readonly List<CognitiveGraphNode> _children = new();
 
@@ -110,6 +117,13 @@ public abstract class CognitiveGraphNode
         HasUnderlyingNode = false;
     }
 
+    /// <summary>
+    /// Called before the children are read, so a node whose children are built on first access can build them.
+    /// </summary>
+    protected virtual void EnsureChildren()
+    {
+    }
+
     /// <summary>
     /// Adds a child node to this node.
     /// </summary>
@@ -127,6 +141,24 @@ public abstract class CognitiveGraphNode
         child.Parent = this;
     }
 
+    /// <summary>
+    /// Inserts a child node at a position among this node's children.
+    /// </summary>
+    /// <param name="index">The position of the child.</param>
+    /// <param name="child">The child node to insert.</param>
+    public virtual void InsertChild(int index, CognitiveGraphNode child)
+    {
+        ArgumentNullException.ThrowIfNull(child);
+
+        if (child.Parent != null)
+        {
+            throw new InvalidOperationException("Node already has a parent");
+        }
+
+        _children.Insert(index, child);
+        child.Parent = this;
+    }
+
     /// <summary>
     /// Removes a child node from this node.
     /// </summary>
@@ -156,24 +188,19 @@ public abstract class CognitiveGraphNode
     /// </summary>
     public CognitiveGraphNode? FindNodeAt(SourcePosition position)
     {
-        // Check if this node contains the position
-        if (SourcePosition?.Contains(position) == true)
+        if (SourcePosition?.Contains(position) != true)
         {
-            // Check children first (more specific)
-            foreach (var child in Children)
-            {
-                var result = child.FindNodeAt(position);
-                if (result != null)
-                {
-                    return result;
-                }
-            }
+            return null;
+        }
 
-            // If no child contains it, this node is the best match
-            return this;
+        // Descend into the first child containing the position (more specific) until none does.
+        var current = this;
+        while (current.Children.FirstOrDefault(node => node.SourcePosition?.Contains(position) == true) is { } child)
+        {
+            current = child;
         }
 
-        return null;
+        return current;
     }
 
     /// <summary>
@@ -256,6 +283,43 @@ public record SourcePosition(int Line, int Column, int Offset, int Length)
         };
     }
 
+    /// <summary>
+    /// Gets the start column with tabs expanded to the next multiple of <paramref name="tabWidth"/>, as an editor
+    /// or terminal would display it.
+    /// </summary>
+    /// <param name="sourceText">The source text this position refers to.</param>
+    /// <param name="tabWidth">The number of columns between tab stops.</param>
+    /// <returns>The 1-based visual start column.</returns>
+    public int GetVisualColumn(string sourceText, int tabWidth = LineIndex.DefaultTabWidth)
+    {
+        ArgumentNullException.ThrowIfNull(sourceText);
+        return VisualColumnAt(sourceText, Offset, tabWidth);
+    }
+
+    /// <summary>
+    /// Gets the end column with tabs expanded to the next multiple of <paramref name="tabWidth"/>.
+    /// </summary>
+    /// <param name="sourceText">The source text this position refers to.</param>
+    /// <param name="tabWidth">The number of columns between tab stops.</param>
+    /// <returns>The 1-based visual end column.</returns>
+    public int GetVisualEndColumn(string sourceText, int tabWidth = LineIndex.DefaultTabWidth)
+    {
+        ArgumentNullException.ThrowIfNull(sourceText);
+        return VisualColumnAt(sourceText, Offset + Length, tabWidth);
+    }
+
+    private static int VisualColumnAt(string sourceText, int offset, int tabWidth)
+    {
+        offset = Math.Clamp(offset, 0, sourceText.Length);
+        var lineStart = offset;
+        while (lineStart > 0 && sourceText[lineStart - 1] is not ('\n' or '\r'))
+        {
+            lineStart--;
+        }
+
+        return LineIndex.ComputeVisualColumn(sourceText, lineStart, offset, tabWidth);
+    }
+
     private static (int Line, int Column) CalculateLineColumn(string sourceText, int offset)
     {
         var line = 1;
@@ -263,7 +327,8 @@ public record SourcePosition(int Line, int Column, int Offset, int Length)
 
         for (var i = 0; i < Math.Min(offset, sourceText.Length); i++)
         {
-            if (sourceText[i] == '\n')
+            var ending = LineIndex.GetTerminator(sourceText, i);
+            if (ending is LineEnding.Lf or LineEnding.Cr)
             {
                 line++;
                 column = 1;
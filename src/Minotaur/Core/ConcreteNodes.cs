@@ -162,22 +162,46 @@ public class NonTerminalNode : CognitiveGraphNode
     /// <returns>A deep copy of this node.</returns>
     public override CognitiveGraphNode Clone()
     {
-        var clone = new NonTerminalNode(RuleName, ProductionIndex)
+        // Nested non-terminals are copied from a heap stack rather than by recursion, so the depth of the
+        // tree is not limited by the thread's stack. Other nodes clone themselves.
+        var clone = CopyNode();
+        var pending = new Stack<(CognitiveGraphNode Source, CognitiveGraphNode Copy)>();
+        pending.Push((this, clone));
+
+        while (pending.Count > 0)
+        {
+            var (source, copy) = pending.Pop();
+            foreach (var child in source.Children)
+            {
+                if (child.GetType() == typeof(NonTerminalNode))
+                {
+                    var nested = ((NonTerminalNode)child).CopyNode();
+                    copy.AddChild(nested);
+                    pending.Push((child, nested));
+                }
+                else
+                {
+                    copy.AddChild(child.Clone());
+                }
+            }
+        }
+
+        return clone;
+    }
+
+    private NonTerminalNode CopyNode()
+    {
+        var copy = new NonTerminalNode(RuleName, ProductionIndex)
         {
             SourcePosition = SourcePosition
         };
 
         foreach (var metadata in Metadata)
         {
-            clone.Metadata[metadata.Key] = metadata.Value;
-        }
-
-        foreach (var child in Children)
-        {
-            clone.AddChild(child.Clone());
+            copy.Metadata[metadata.Key] = metadata.Value;
         }
 
-        return clone;
+        return copy;
     }
 
     /// <summary>
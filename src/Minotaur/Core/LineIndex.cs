@@ -0,0 +1,241 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Core;
+
+/// <summary>
+/// The terminator that ends a line of source text.
+/// </summary>
+public enum LineEnding
+{
+    /// <summary>
+    /// The line is the last line and has no terminator.
+    /// </summary>
+    None,
+
+    /// <summary>
+    /// A line feed ("\n").
+    /// </summary>
+    Lf,
+
+    /// <summary>
+    /// A carriage return followed by a line feed ("\r\n").
+    /// </summary>
+    CrLf,
+
+    /// <summary>
+    /// A lone carriage return ("\r").
+    /// </summary>
+    Cr
+}
+
+/// <summary>
+/// Precomputed line start offsets for a source text, giving O(log n) offset to line/column conversion.
+/// Uses the same 1-based line and column convention as <see cref="SourcePosition.FromOffset"/>. Lone CR, CRLF and
+/// LF all end a line, and the terminator of every line is recorded so mixed files can be detected.
+/// </summary>
+public sealed class LineIndex
+{
+    /// <summary>
+    /// The tab width used for visual columns when none is specified.
+    /// </summary>
+    public const int DefaultTabWidth = 4;
+
+    private readonly List<int> _lineStarts = new() { 0 };
+    private readonly List<LineEnding> _lineEndings = new();
+
+    /// <summary>
+    /// Initializes a new instance of the LineIndex class.
+    /// </summary>
+    /// <param name="text">The source text to index.</param>
+    public LineIndex(string text)
+    {
+        Text = text ?? throw new ArgumentNullException(nameof(text));
+
+        for (var i = 0; i < text.Length; i++)
+        {
+            var ending = GetTerminator(text, i);
+            if (ending == LineEnding.None)
+            {
+                continue;
+            }
+
+            if (ending == LineEnding.CrLf)
+            {
+                i++;
+            }
+
+            _lineEndings.Add(ending);
+            _lineStarts.Add(i + 1);
+        }
+
+        _lineEndings.Add(LineEnding.None);
+    }
+
+    /// <summary>
+    /// Gets the indexed source text.
+    /// </summary>
+    public string Text { get; }
+
+    /// <summary>
+    /// Gets the number of lines in the text.
+    /// </summary>
+    public int LineCount => _lineStarts.Count;
+
+    /// <summary>
+    /// Gets the terminator of every line, in line order. The last line always reports <see cref="LineEnding.None"/>.
+    /// </summary>
+    public IReadOnlyList<LineEnding> LineEndings => _lineEndings;
+
+    /// <summary>
+    /// Gets a value indicating whether the text terminates lines in more than one style.
+    /// </summary>
+    public bool HasMixedLineEndings => _lineEndings.Where(e => e != LineEnding.None).Distinct().Skip(1).Any();
+
+    /// <summary>
+    /// Gets the most frequent line terminator, preferring LF, then CRLF, then CR on ties, or
+    /// <see cref="LineEnding.None"/> for single-line text. Useful when inserting new lines into a mixed file.
+    /// </summary>
+    public LineEnding DominantLineEnding => _lineEndings
+        .Where(e => e != LineEnding.None)
+        .GroupBy(e => e)
+        .OrderByDescending(g => g.Count())
+        .ThenBy(g => g.Key)
+        .Select(g => g.Key)
+        .FirstOrDefault();
+
+    /// <summary>
+    /// Gets the terminator of the specified 1-based line.
+    /// </summary>
+    /// <param name="line">The 1-based line number.</param>
+    /// <returns>The line's terminator.</returns>
+    public LineEnding GetLineEnding(int line)
+    {
+        return _lineEndings[Math.Clamp(line, 1, _lineEndings.Count) - 1];
+    }
+
+    /// <summary>
+    /// Gets the offset just past the last content character of the specified 1-based line, before its terminator.
+    /// </summary>
+    /// <param name="line">The 1-based line number.</param>
+    /// <returns>The offset at which the line's terminator, or the text, ends the line.</returns>
+    public int GetLineContentEnd(int line)
+    {
+        line = Math.Clamp(line, 1, _lineStarts.Count);
+        if (line == _lineStarts.Count)
+        {
+            return Text.Length;
+        }
+
+        return _lineStarts[line] - (GetLineEnding(line) == LineEnding.CrLf ? 2 : 1);
+    }
+
+    /// <summary>
+    /// Gets the text of the specified 1-based line without its terminator.
+    /// </summary>
+    /// <param name="line">The 1-based line number.</param>
+    /// <returns>The line's content.</returns>
+    public string GetLineText(int line)
+    {
+        var start = GetLineStart(line);
+        return Text[start..GetLineContentEnd(line)];
+    }
+
+    /// <summary>
+    /// Converts an offset to a 1-based visual column, expanding each tab to the next multiple of
+    /// <paramref name="tabWidth"/>.
+    /// </summary>
+    /// <param name="offset">The character offset.</param>
+    /// <param name="tabWidth">The number of columns between tab stops.</param>
+    /// <returns>The visual column of the offset.</returns>
+    public int GetVisualColumn(int offset, int tabWidth = DefaultTabWidth)
+    {
+        offset = Math.Clamp(offset, 0, Text.Length);
+        var (line, _) = GetLineColumn(offset);
+        return ComputeVisualColumn(Text, GetLineStart(line), offset, tabWidth);
+    }
+
+    /// <summary>
+    /// Gets the offset at which the specified 1-based line starts.
+    /// </summary>
+    /// <param name="line">The 1-based line number.</param>
+    /// <returns>The offset of the first character of the line.</returns>
+    public int GetLineStart(int line)
+    {
+        return _lineStarts[Math.Clamp(line, 1, _lineStarts.Count) - 1];
+    }
+
+    /// <summary>
+    /// Converts an offset to a 1-based line and column.
+    /// </summary>
+    /// <param name="offset">The character offset.</param>
+    /// <returns>The line and column of the offset.</returns>
+    public (int Line, int Column) GetLineColumn(int offset)
+    {
+        offset = Math.Clamp(offset, 0, Text.Length);
+        var index = _lineStarts.BinarySearch(offset);
+        var line = index >= 0 ? index : ~index - 1;
+        return (line + 1, offset - _lineStarts[line] + 1);
+    }
+
+    /// <summary>
+    /// Creates a source position for the specified span.
+    /// </summary>
+    /// <param name="offset">The start offset of the span.</param>
+    /// <param name="length">The length of the span.</param>
+    /// <param name="sourceFile">The optional source file name.</param>
+    /// <returns>A source position with start and end line/column information.</returns>
+    public SourcePosition GetPosition(int offset, int length, string? sourceFile = null)
+    {
+        var (line, column) = GetLineColumn(offset);
+        var (endLine, endColumn) = GetLineColumn(offset + length);
+
+        return new SourcePosition(line, column, offset, length)
+        {
+            EndLine = endLine,
+            EndColumn = endColumn,
+            SourceFile = sourceFile
+        };
+    }
+
+    internal static LineEnding GetTerminator(string text, int index)
+    {
+        return text[index] switch
+        {
+            '\n' => LineEnding.Lf,
+            '\r' when index + 1 < text.Length && text[index + 1] == '\n' => LineEnding.CrLf,
+            '\r' => LineEnding.Cr,
+            _ => LineEnding.None
+        };
+    }
+
+    internal static int ComputeVisualColumn(string text, int lineStart, int offset, int tabWidth)
+    {
+        if (tabWidth < 1)
+        {
+            throw new ArgumentOutOfRangeException(nameof(tabWidth), tabWidth, "Tab width must be at least 1.");
+        }
+
+        var column = 0;
+        for (var i = lineStart; i < offset && i < text.Length; i++)
+        {
+            column = text[i] == '\t' ? (column / tabWidth + 1) * tabWidth : column + 1;
+        }
+
+        return column + 1;
+    }
+}
@@ -0,0 +1,329 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+
+namespace Minotaur.Core;
+
+/// <summary>
+/// An immutable text stored as a balanced tree of string chunks. Replacing a range builds a new rope that shares
+/// every untouched chunk with the old one, so keeping earlier versions of a large document costs little, and
+/// line/character positions are found through the line break counts kept on each node instead of a scan.
+/// Lines end at '\n', as in <see cref="LineIndex"/>; positions are 0-based.
+/// </summary>
+public sealed class Rope
+{
+    private const int MaxLeafLength = 512;
+    private const int MaxDepth = 48;
+
+    private readonly string? _leaf;
+    private readonly Rope? _left;
+    private readonly Rope? _right;
+    private readonly int _depth;
+
+    private Rope(string leaf)
+    {
+        _leaf = leaf;
+        Length = leaf.Length;
+        LineBreakCount = leaf.AsSpan().Count('\n');
+    }
+
+    private Rope(Rope left, Rope right)
+    {
+        _left = left;
+        _right = right;
+        Length = left.Length + right.Length;
+        LineBreakCount = left.LineBreakCount + right.LineBreakCount;
+        _depth = Math.Max(left._depth, right._depth) + 1;
+    }
+
+    /// <summary>
+    /// Gets the empty rope.
+    /// </summary>
+    public static Rope Empty { get; } = new(string.Empty);
+
+    /// <summary>
+    /// Gets the number of characters in the text.
+    /// </summary>
+    public int Length { get; }
+
+    /// <summary>
+    /// Gets the number of '\n' characters in the text; the text has one more line than this.
+    /// </summary>
+    public int LineBreakCount { get; }
+
+    /// <summary>
+    /// Gets the character at an offset.
+    /// </summary>
+    /// <param name="index">The zero-based offset.</param>
+    /// <returns>The character.</returns>
+    public char this[int index]
+    {
+        get
+        {
+            if ((uint)index >= (uint)Length)
+            {
+                throw new ArgumentOutOfRangeException(nameof(index));
+            }
+
+            var node = this;
+            while (node._leaf == null)
+            {
+                if (index < node._left!.Length)
+                {
+                    node = node._left;
+                }
+                else
+                {
+                    index -= node._left.Length;
+                    node = node._right!;
+                }
+            }
+
+            return node._leaf[index];
+        }
+    }
+
+    /// <summary>
+    /// Creates a rope holding a text.
+    /// </summary>
+    /// <param name="text">The text.</param>
+    /// <returns>The rope.</returns>
+    public static Rope FromString(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+        return Build(text, 0, text.Length);
+    }
+
+    /// <summary>
+    /// Replaces a range of the text.
+    /// </summary>
+    /// <param name="offset">The start of the replaced range.</param>
+    /// <param name="length">The number of characters replaced.</param>
+    /// <param name="text">The inserted text.</param>
+    /// <returns>The new rope; this rope is unchanged.</returns>
+    public Rope Replace(int offset, int length, string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+        if (offset < 0 || length < 0 || offset + length > Length)
+        {
+            throw new ArgumentOutOfRangeException(nameof(offset), $"Range [{offset}, {offset + length}) is outside the text of length {Length}");
+        }
+
+        return Concat(Concat(Slice(0, offset), FromString(text)), Slice(offset + length, Length - offset - length));
+    }
+
+    /// <summary>
+    /// Gets part of the text as a rope.
+    /// </summary>
+    /// <param name="offset">The start offset.</param>
+    /// <param name="length">The number of characters.</param>
+    /// <returns>The rope of the range, sharing chunks with this one.</returns>
+    public Rope Slice(int offset, int length)
+    {
+        if (offset < 0 || length < 0 || offset + length > Length)
+        {
+            throw new ArgumentOutOfRangeException(nameof(offset), $"Range [{offset}, {offset + length}) is outside the text of length {Length}");
+        }
+
+        if (length == Length)
+        {
+            return this;
+        }
+
+        if (length == 0)
+        {
+            return Empty;
+        }
+
+        if (_leaf != null)
+        {
+            return new Rope(_leaf.Substring(offset, length));
+        }
+
+        var leftLength = _left!.Length;
+        if (offset + length <= leftLength)
+        {
+            return _left.Slice(offset, length);
+        }
+
+        if (offset >= leftLength)
+        {
+            return _right!.Slice(offset - leftLength, length);
+        }
+
+        return Concat(_left.Slice(offset, leftLength - offset), _right!.Slice(0, offset + length - leftLength));
+    }
+
+    /// <summary>
+    /// Gets the offset of a 0-based line and character.
+    /// </summary>
+    /// <param name="line">The 0-based line.</param>
+    /// <param name="character">The 0-based character within the line.</param>
+    /// <returns>The offset, or -1 if the line does not exist or the character is past the end of the line.</returns>
+    public int GetOffset(int line, int character)
+    {
+        if (line < 0 || line > LineBreakCount || character < 0)
+        {
+            return -1;
+        }
+
+        var start = GetLineStart(line);
+        var end = line == LineBreakCount ? Length : GetLineStart(line + 1) - 1;
+        return start + character <= end ? start + character : -1;
+    }
+
+    /// <summary>
+    /// Gets the 0-based line and character of an offset.
+    /// </summary>
+    /// <param name="offset">The offset, from 0 to <see cref="Length"/>.</param>
+    /// <returns>The line and character.</returns>
+    public (int Line, int Character) GetPosition(int offset)
+    {
+        if (offset < 0 || offset > Length)
+        {
+            throw new ArgumentOutOfRangeException(nameof(offset));
+        }
+
+        var line = CountLineBreaks(offset);
+        return (line, offset - GetLineStart(line));
+    }
+
+    /// <summary>
+    /// Returns the text.
+    /// </summary>
+    /// <returns>The text.</returns>
+    public override string ToString()
+    {
+        if (_leaf != null)
+        {
+            return _leaf;
+        }
+
+        var builder = new StringBuilder(Length);
+        foreach (var leaf in Leaves())
+        {
+            builder.Append(leaf);
+        }
+
+        return builder.ToString();
+    }
+
+    private static Rope Build(string text, int start, int length)
+    {
+        if (length <= MaxLeafLength)
+        {
+            return length == 0 ? Empty : new Rope(text.Substring(start, length));
+        }
+
+        var half = length / 2;
+        return new Rope(Build(text, start, half), Build(text, start + half, length - half));
+    }
+
+    private static Rope Concat(Rope left, Rope right)
+    {
+        if (left.Length == 0)
+        {
+            return right;
+        }
+
+        if (right.Length == 0)
+        {
+            return left;
+        }
+
+        // Small neighbours merge into one chunk so single-character edits do not leave a trail of tiny leaves.
+        if (left.Length + right.Length <= MaxLeafLength && left._leaf != null && right._leaf != null)
+        {
+            return new Rope(left._leaf + right._leaf);
+        }
+
+        var rope = new Rope(left, right);
+        return rope._depth > MaxDepth ? FromString(rope.ToString()) : rope;
+    }
+
+    private int GetLineStart(int line)
+    {
+        // The start of a line is just past the line break that ends the previous one.
+        var offset = 0;
+        var node = this;
+        while (line > 0 && node._leaf == null)
+        {
+            if (line <= node._left!.LineBreakCount)
+            {
+                node = node._left;
+            }
+            else
+            {
+                line -= node._left.LineBreakCount;
+                offset += node._left.Length;
+                node = node._right!;
+            }
+        }
+
+        var leaf = node._leaf!;
+        for (var i = 0; line > 0; i++)
+        {
+            if (leaf[i] == '\n' && --line == 0)
+            {
+                return offset + i + 1;
+            }
+        }
+
+        return offset;
+    }
+
+    private int CountLineBreaks(int offset)
+    {
+        var count = 0;
+        var node = this;
+        while (node._leaf == null)
+        {
+            if (offset <= node._left!.Length)
+            {
+                node = node._left;
+            }
+            else
+            {
+                count += node._left.LineBreakCount;
+                offset -= node._left.Length;
+                node = node._right!;
+            }
+        }
+
+        return count + node._leaf.AsSpan(0, offset).Count('\n');
+    }
+
+    private IEnumerable<string> Leaves()
+    {
+        var stack = new Stack<Rope>();
+        stack.Push(this);
+        while (stack.Count > 0)
+        {
+            var node = stack.Pop();
+            if (node._leaf != null)
+            {
+                yield return node._leaf;
+            }
+            else
+            {
+                stack.Push(node._right!);
+                stack.Push(node._left!);
+            }
+        }
+    }
+}
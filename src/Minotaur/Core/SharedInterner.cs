@@ -0,0 +1,291 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Numerics;
+using Minotaur.Monitoring;
+
+namespace Minotaur.Core;
+
+/// <summary>
+/// Interns strings that many documents repeat, such as identifier text, so that every parse sharing the interner
+/// holds one copy of each. Meant to live as long as a process that parses many files, like
+/// <see cref="Daemon.WorkspaceDaemon"/>, and to be trimmed with <see cref="Collect"/> when the documents using its
+/// strings go away. Rule names need no interning: every tree refers to the names of its compiled grammar.
+/// </summary>
+/// <remarks>
+/// <para>
+/// Strings are spread over shards by hash, each with its own lock, so parses on different threads rarely contend.
+/// Looking up text already interned allocates nothing, which is what makes lexing through the interner cheap.
+/// </para>
+/// <para>
+/// Every entry is stamped with the interner's epoch when it is added or looked up. A collection starts a new epoch,
+/// stamps the strings it is told are live, and drops the entries stamped earlier. Strings interned by parses that
+/// run during a collection are stamped with the new epoch and kept. A string a parse looked up just before the
+/// collection started may be dropped while the parse still holds it; that costs sharing, not correctness, since the
+/// string stays valid and the next lookup adds a new copy.
+/// </para>
+/// </remarks>
+public sealed class SharedInterner : IMemoryReporting
+{
+    /// <summary>
+    /// The number of shards when none is given.
+    /// </summary>
+    public const int DefaultShardCount = 16;
+
+    private readonly Shard[] _shards;
+    private readonly object _collectLock = new();
+    private long _epoch;
+    private int _count;
+    private long _bytes;
+    private int _collections;
+
+    /// <summary>
+    /// Initializes a new instance of the SharedInterner class.
+    /// </summary>
+    /// <param name="shardCount">The number of shards, rounded up to a power of two.</param>
+    public SharedInterner(int shardCount = DefaultShardCount)
+    {
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(shardCount);
+
+        _shards = new Shard[(int)BitOperations.RoundUpToPowerOf2((uint)shardCount)];
+        for (var i = 0; i < _shards.Length; i++)
+        {
+            _shards[i] = new Shard();
+        }
+    }
+
+    /// <summary>
+    /// Gets the number of interned strings.
+    /// </summary>
+    public int Count => Volatile.Read(ref _count);
+
+    /// <summary>
+    /// Gets the number of collections run so far.
+    /// </summary>
+    public int Collections => Volatile.Read(ref _collections);
+
+    /// <summary>
+    /// Gets the shared copy of a string, adding the string if it is not interned.
+    /// </summary>
+    /// <param name="text">The string.</param>
+    /// <returns>The interned string equal to the text.</returns>
+    public string Intern(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+        return Intern(text.AsSpan(), text);
+    }
+
+    /// <summary>
+    /// Gets the shared copy of a span of text, allocating a string only if the text is not interned.
+    /// </summary>
+    /// <param name="text">The text, such as a token's span of the source.</param>
+    /// <returns>The interned string equal to the text.</returns>
+    public string Intern(ReadOnlySpan<char> text)
+    {
+        return Intern(text, null);
+    }
+
+    /// <summary>
+    /// Gets the shared copy of a string without adding it.
+    /// </summary>
+    /// <param name="text">The string.</param>
+    /// <param name="interned">The interned string, if any.</param>
+    /// <returns>True if the string is interned.</returns>
+    public bool TryGet(string text, out string? interned)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var hash = string.GetHashCode(text.AsSpan());
+        var shard = GetShard(hash);
+        lock (shard)
+        {
+            interned = shard.Find(hash, text)?.Text;
+            return interned != null;
+        }
+    }
+
+    /// <summary>
+    /// Drops the interned strings that are not live. Collections run one at a time, while parses go on interning.
+    /// </summary>
+    /// <param name="live">The strings still in use, such as the identifier text of every open document; strings
+    /// that are not interned are ignored.</param>
+    /// <returns>The number of strings dropped.</returns>
+    public int Collect(IEnumerable<string> live)
+    {
+        ArgumentNullException.ThrowIfNull(live);
+
+        lock (_collectLock)
+        {
+            var epoch = Interlocked.Increment(ref _epoch);
+            foreach (var text in live)
+            {
+                var hash = string.GetHashCode(text.AsSpan());
+                var shard = GetShard(hash);
+                lock (shard)
+                {
+                    shard.Find(hash, text)?.Stamp(epoch);
+                }
+            }
+
+            var dropped = 0;
+            foreach (var shard in _shards)
+            {
+                lock (shard)
+                {
+                    foreach (var entry in shard.RemoveOlderThan(epoch))
+                    {
+                        dropped++;
+                        Interlocked.Decrement(ref _count);
+                        Interlocked.Add(ref _bytes, -GetSize(entry.Text));
+                    }
+                }
+            }
+
+            Interlocked.Increment(ref _collections);
+            return dropped;
+        }
+    }
+
+    /// <summary>
+    /// Gets the number of interned strings and, with memory accounting, the bytes of their string objects.
+    /// </summary>
+    /// <returns>The memory report.</returns>
+    public MemoryReport GetMemoryReport()
+    {
+        var bytes = MemoryReport.IsAccountingEnabled ? Interlocked.Read(ref _bytes) : 0;
+        return new MemoryReport("shared interner", new[] { new MemoryReportEntry(MemoryCategories.InternedStrings, Count, bytes) });
+    }
+
+    private string Intern(ReadOnlySpan<char> text, string? instance)
+    {
+        var hash = string.GetHashCode(text);
+        var shard = GetShard(hash);
+        lock (shard)
+        {
+            // The epoch is read under the shard lock, which a collection's sweep of the shard also takes.
+            var epoch = Interlocked.Read(ref _epoch);
+            if (shard.Find(hash, text) is { } entry)
+            {
+                entry.Stamp(epoch);
+                return entry.Text;
+            }
+
+            var added = new Entry(instance ?? text.ToString(), epoch);
+            shard.Add(hash, added);
+            Interlocked.Increment(ref _count);
+            Interlocked.Add(ref _bytes, GetSize(added.Text));
+            return added.Text;
+        }
+    }
+
+    private Shard GetShard(int hash)
+    {
+        // The low bits pick the bucket within the shard's dictionary, so the shard is picked by the high ones.
+        return _shards[(int)((uint)hash >> 16) & (_shards.Length - 1)];
+    }
+
+    private static long GetSize(string text)
+    {
+        // An object header, a method table pointer, the length and the characters with their terminator.
+        return IntPtr.Size * 2 + sizeof(int) + ((long)text.Length + 1) * sizeof(char);
+    }
+
+    private sealed class Entry
+    {
+        private long _epoch;
+
+        public Entry(string text, long epoch)
+        {
+            Text = text;
+            _epoch = epoch;
+        }
+
+        public string Text { get; }
+
+        public Entry? Next { get; set; }
+
+        public long Epoch => _epoch;
+
+        public void Stamp(long epoch)
+        {
+            if (_epoch < epoch)
+            {
+                _epoch = epoch;
+            }
+        }
+    }
+
+    // Entries are chained by hash rather than kept in a dictionary keyed by string, so spans can be looked up.
+    private sealed class Shard
+    {
+        private readonly Dictionary<int, Entry> _buckets = new();
+
+        public Entry? Find(int hash, ReadOnlySpan<char> text)
+        {
+            for (var entry = _buckets.GetValueOrDefault(hash); entry != null; entry = entry.Next)
+            {
+                if (text.SequenceEqual(entry.Text.AsSpan()))
+                {
+                    return entry;
+                }
+            }
+
+            return null;
+        }
+
+        public void Add(int hash, Entry entry)
+        {
+            entry.Next = _buckets.GetValueOrDefault(hash);
+            _buckets[hash] = entry;
+        }
+
+        public List<Entry> RemoveOlderThan(long epoch)
+        {
+            var removed = new List<Entry>();
+            foreach (var hash in _buckets.Keys.ToList())
+            {
+                Entry? kept = null;
+                for (var entry = _buckets[hash]; entry != null;)
+                {
+                    var next = entry.Next;
+                    if (entry.Epoch < epoch)
+                    {
+                        removed.Add(entry);
+                    }
+                    else
+                    {
+                        entry.Next = kept;
+                        kept = entry;
+                    }
+
+                    entry = next;
+                }
+
+                if (kept == null)
+                {
+                    _buckets.Remove(hash);
+                }
+                else
+                {
+                    _buckets[hash] = kept;
+                }
+            }
+
+            return removed;
+        }
+    }
+}
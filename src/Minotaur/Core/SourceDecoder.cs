@@ -0,0 +1,468 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Buffers;
+using System.Text;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Core;
+
+/// <summary>
+/// How bytes that are not valid in the input encoding are handled.
+/// </summary>
+public enum InvalidBytePolicy
+{
+    /// <summary>
+    /// Stop decoding with a <see cref="SourceDecodingException"/> reporting the byte offset.
+    /// </summary>
+    Error,
+
+    /// <summary>
+    /// Replace the bytes with U+FFFD and report a warning.
+    /// </summary>
+    Substitute
+}
+
+/// <summary>
+/// Options controlling how source bytes are decoded.
+/// </summary>
+public class SourceDecodeOptions
+{
+    /// <summary>
+    /// Gets or sets the encoding to use instead of detecting one. A byte order mark that matches the
+    /// encoding is still skipped.
+    /// </summary>
+    public Encoding? Encoding { get; set; }
+
+    /// <summary>
+    /// Gets or sets the policy for bytes that are not valid in the encoding.
+    /// </summary>
+    public InvalidBytePolicy InvalidBytes { get; set; } = InvalidBytePolicy.Error;
+
+    /// <summary>
+    /// Gets or sets the source file name used in diagnostics.
+    /// </summary>
+    public string? SourceFile { get; set; }
+}
+
+/// <summary>
+/// Decodes source files into text for parsing. The encoding is taken from an explicit override, a byte order
+/// mark (UTF-8, UTF-16LE, UTF-16BE), or defaults to UTF-8. The decoded text keeps a map from character offsets
+/// back to byte offsets in the original file, so positions can be reported to tools that work with bytes.
+/// </summary>
+public static class SourceDecoder
+{
+    private static readonly Lazy<bool> CodePagesRegistered = new(() =>
+    {
+        Encoding.RegisterProvider(CodePagesEncodingProvider.Instance);
+        return true;
+    });
+
+    /// <summary>
+    /// Gets an encoding by name, including Windows code pages such as "windows-1252".
+    /// </summary>
+    /// <param name="name">The encoding name or code page number.</param>
+    /// <returns>The encoding.</returns>
+    /// <exception cref="ArgumentException">The encoding is unknown.</exception>
+    public static Encoding GetEncoding(string name)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(name);
+        _ = CodePagesRegistered.Value;
+
+        return int.TryParse(name, out var codePage) ? Encoding.GetEncoding(codePage) : Encoding.GetEncoding(name);
+    }
+
+    /// <summary>
+    /// Detects a byte order mark.
+    /// </summary>
+    /// <param name="bytes">The file content.</param>
+    /// <param name="bomLength">The length of the byte order mark, or 0 if there is none.</param>
+    /// <returns>The encoding indicated by the byte order mark, or null if there is none.</returns>
+    public static Encoding? DetectByteOrderMark(ReadOnlySpan<byte> bytes, out int bomLength)
+    {
+        if (bytes.StartsWith(new byte[] { 0xEF, 0xBB, 0xBF }))
+        {
+            bomLength = 3;
+            return new UTF8Encoding(true);
+        }
+
+        if (bytes.StartsWith(new byte[] { 0xFF, 0xFE }))
+        {
+            bomLength = 2;
+            return new UnicodeEncoding(false, true);
+        }
+
+        if (bytes.StartsWith(new byte[] { 0xFE, 0xFF }))
+        {
+            bomLength = 2;
+            return new UnicodeEncoding(true, true);
+        }
+
+        bomLength = 0;
+        return null;
+    }
+
+    /// <summary>
+    /// Reads and decodes a source file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <param name="options">Optional decode options; the source file defaults to the path.</param>
+    /// <returns>The decoded source.</returns>
+    public static async Task<DecodedSource> DecodeFileAsync(string path, SourceDecodeOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        var bytes = await File.ReadAllBytesAsync(path);
+        return Decode(bytes, new SourceDecodeOptions
+        {
+            Encoding = options?.Encoding,
+            InvalidBytes = options?.InvalidBytes ?? InvalidBytePolicy.Error,
+            SourceFile = options?.SourceFile ?? path
+        });
+    }
+
+    /// <summary>
+    /// Decodes source bytes.
+    /// </summary>
+    /// <param name="bytes">The file content.</param>
+    /// <param name="options">Optional decode options.</param>
+    /// <returns>The decoded source.</returns>
+    /// <exception cref="SourceDecodingException">The bytes are not valid in the encoding and the policy is <see cref="InvalidBytePolicy.Error"/>.</exception>
+    public static DecodedSource Decode(byte[] bytes, SourceDecodeOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(bytes);
+        options ??= new SourceDecodeOptions();
+
+        var detected = DetectByteOrderMark(bytes, out var bomLength);
+        var encoding = options.Encoding ?? detected ?? new UTF8Encoding(false);
+        if (detected == null || detected.CodePage != encoding.CodePage)
+        {
+            bomLength = 0;
+        }
+
+        var decoder = new Decoding(bytes, bomLength, encoding, options);
+        switch (encoding.CodePage)
+        {
+            case 65001:
+                decoder.DecodeUtf8();
+                break;
+            case 1200:
+                decoder.DecodeUtf16(bigEndian: false);
+                break;
+            case 1201:
+                decoder.DecodeUtf16(bigEndian: true);
+                break;
+            default:
+                decoder.DecodeWithEncoding();
+                break;
+        }
+
+        return decoder.ToResult(bomLength > 0);
+    }
+
+    private sealed class Decoding
+    {
+        private readonly byte[] _bytes;
+        private readonly int _start;
+        private readonly Encoding _encoding;
+        private readonly SourceDecodeOptions _options;
+        private readonly StringBuilder _text = new();
+        private readonly List<int> _offsets = new();
+        private readonly List<(int CharOffset, int ByteOffset, int Length)> _substitutions = new();
+
+        public Decoding(byte[] bytes, int start, Encoding encoding, SourceDecodeOptions options)
+        {
+            _bytes = bytes;
+            _start = start;
+            _encoding = encoding;
+            _options = options;
+        }
+
+        public void DecodeUtf8()
+        {
+            var position = _start;
+            while (position < _bytes.Length)
+            {
+                var status = Rune.DecodeFromUtf8(_bytes.AsSpan(position), out var rune, out var consumed);
+                if (status == OperationStatus.Done)
+                {
+                    Append(rune, position);
+                }
+                else
+                {
+                    Invalid(position, consumed);
+                }
+
+                position += consumed;
+            }
+        }
+
+        public void DecodeUtf16(bool bigEndian)
+        {
+            var units = new char[(_bytes.Length - _start) / 2];
+            for (var i = 0; i < units.Length; i++)
+            {
+                var b0 = _bytes[_start + 2 * i];
+                var b1 = _bytes[_start + 2 * i + 1];
+                units[i] = (char)(bigEndian ? (b0 << 8) | b1 : (b1 << 8) | b0);
+            }
+
+            var unit = 0;
+            while (unit < units.Length)
+            {
+                var status = Rune.DecodeFromUtf16(units.AsSpan(unit), out var rune, out var consumed);
+                if (status == OperationStatus.Done)
+                {
+                    Append(rune, _start + 2 * unit);
+                }
+                else
+                {
+                    Invalid(_start + 2 * unit, 2 * consumed);
+                }
+
+                unit += consumed;
+            }
+
+            // A trailing odd byte cannot form a code unit.
+            if ((_bytes.Length - _start) % 2 != 0)
+            {
+                Invalid(_bytes.Length - 1, 1);
+            }
+        }
+
+        public void DecodeWithEncoding()
+        {
+            // Decoding byte by byte keeps the start offset of every character, for any encoding.
+            var strict = (Encoding)_encoding.Clone();
+            strict.DecoderFallback = DecoderFallback.ExceptionFallback;
+            var decoder = strict.GetDecoder();
+            var chars = new char[strict.GetMaxCharCount(1) + 2];
+            var sequenceStart = _start;
+
+            for (var i = _start; i < _bytes.Length; i++)
+            {
+                int count;
+                try
+                {
+                    count = decoder.GetChars(_bytes, i, 1, chars, 0, flush: i == _bytes.Length - 1);
+                }
+                catch (DecoderFallbackException)
+                {
+                    decoder.Reset();
+                    Invalid(sequenceStart, i + 1 - sequenceStart);
+                    sequenceStart = i + 1;
+                    continue;
+                }
+
+                if (count > 0)
+                {
+                    for (var c = 0; c < count; c++)
+                    {
+                        _text.Append(chars[c]);
+                        _offsets.Add(sequenceStart);
+                    }
+
+                    sequenceStart = i + 1;
+                }
+            }
+
+            if (sequenceStart < _bytes.Length)
+            {
+                Invalid(sequenceStart, _bytes.Length - sequenceStart);
+            }
+        }
+
+        public DecodedSource ToResult(bool hadByteOrderMark)
+        {
+            _offsets.Add(_bytes.Length);
+            var text = _text.ToString();
+            var lineIndex = new LineIndex(text);
+
+            var diagnostics = _substitutions.Select(s => new Diagnostic
+            {
+                Code = DiagnosticCodes.SubstitutedBytes,
+                Severity = DiagnosticSeverity.Warning,
+                Location = lineIndex.GetPosition(s.CharOffset, 1, _options.SourceFile),
+                Data = { ["byteOffset"] = s.ByteOffset, ["byteLength"] = s.Length }
+            }.WithMessage(DiagnosticCodes.SubstitutedBytes, ("bytes", Hex(s.ByteOffset, s.Length)), ("offset", s.ByteOffset), ("encoding", _encoding.WebName))).ToList();
+
+            return new DecodedSource(text, _encoding, hadByteOrderMark, _offsets.ToArray(), diagnostics);
+        }
+
+        private void Append(Rune rune, int byteOffset)
+        {
+            var length = rune.Utf16SequenceLength;
+            _text.Append(rune.ToString());
+            for (var i = 0; i < length; i++)
+            {
+                _offsets.Add(byteOffset);
+            }
+        }
+
+        private void Invalid(int byteOffset, int length)
+        {
+            if (_options.InvalidBytes == InvalidBytePolicy.Error)
+            {
+                var bytes = Hex(byteOffset, length);
+                throw new SourceDecodingException(
+                    $"{_options.SourceFile ?? "input"}: {bytes} at byte {byteOffset} is not valid {_encoding.WebName}",
+                    _options.SourceFile,
+                    byteOffset,
+                    _encoding)
+                {
+                    InvalidBytes = bytes
+                };
+            }
+
+            _substitutions.Add((_text.Length, byteOffset, length));
+            _text.Append('�');
+            _offsets.Add(byteOffset);
+        }
+
+        private string Hex(int offset, int length)
+        {
+            return string.Join(" ", _bytes.Skip(offset).Take(length).Select(b => $"0x{b:X2}"));
+        }
+    }
+}
+
+/// <summary>
+/// Decoded source text with a map back to byte offsets in the original file.
+/// </summary>
+public sealed class DecodedSource
+{
+    private readonly int[] _byteOffsets;
+
+    internal DecodedSource(string text, Encoding encoding, bool hadByteOrderMark, int[] byteOffsets, IReadOnlyList<Diagnostic> diagnostics)
+    {
+        Text = text;
+        Encoding = encoding;
+        HadByteOrderMark = hadByteOrderMark;
+        _byteOffsets = byteOffsets;
+        Diagnostics = diagnostics;
+    }
+
+    /// <summary>
+    /// Gets the decoded text.
+    /// </summary>
+    public string Text { get; }
+
+    /// <summary>
+    /// Gets the encoding the source was decoded with.
+    /// </summary>
+    public Encoding Encoding { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the source started with a byte order mark.
+    /// </summary>
+    public bool HadByteOrderMark { get; }
+
+    /// <summary>
+    /// Gets the warnings for substituted bytes.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics { get; }
+
+    /// <summary>
+    /// Gets the length of the original file in bytes.
+    /// </summary>
+    public int ByteLength => _byteOffsets[^1];
+
+    /// <summary>
+    /// Gets the byte offset in the original file of a character offset in the decoded text.
+    /// </summary>
+    /// <param name="charOffset">The character offset; the text length maps to the end of the file.</param>
+    /// <returns>The byte offset of the first byte of the character.</returns>
+    public int GetByteOffset(int charOffset)
+    {
+        ArgumentOutOfRangeException.ThrowIfNegative(charOffset);
+        ArgumentOutOfRangeException.ThrowIfGreaterThan(charOffset, Text.Length);
+        return _byteOffsets[charOffset];
+    }
+
+    /// <summary>
+    /// Gets the byte range in the original file of a span of the decoded text.
+    /// </summary>
+    /// <param name="position">The span.</param>
+    /// <returns>The byte offset and byte length.</returns>
+    public (int Offset, int Length) GetByteSpan(SourcePosition position)
+    {
+        ArgumentNullException.ThrowIfNull(position);
+
+        var start = GetByteOffset(position.Offset);
+        var end = GetByteOffset(position.Offset + position.Length);
+        return (start, end - start);
+    }
+}
+
+/// <summary>
+/// Thrown when source bytes are not valid in their encoding and substitution is not allowed.
+/// </summary>
+public class SourceDecodingException : Exception
+{
+    /// <summary>
+    /// Initializes a new instance of the SourceDecodingException class.
+    /// </summary>
+    /// <param name="message">The message.</param>
+    /// <param name="sourceFile">The source file, if known.</param>
+    /// <param name="byteOffset">The offset of the first invalid byte.</param>
+    /// <param name="encoding">The encoding the bytes were decoded with.</param>
+    public SourceDecodingException(string message, string? sourceFile, int byteOffset, Encoding encoding)
+        : base(message)
+    {
+        SourceFile = sourceFile;
+        ByteOffset = byteOffset;
+        Encoding = encoding;
+    }
+
+    /// <summary>
+    /// Gets the source file, if known.
+    /// </summary>
+    public string? SourceFile { get; }
+
+    /// <summary>
+    /// Gets the offset of the first invalid byte.
+    /// </summary>
+    public int ByteOffset { get; }
+
+    /// <summary>
+    /// Gets the encoding the bytes were decoded with.
+    /// </summary>
+    public Encoding Encoding { get; }
+
+    /// <summary>
+    /// Gets the invalid bytes in hexadecimal, e.g. "0xC3 0x28", if known.
+    /// </summary>
+    public string? InvalidBytes { get; init; }
+
+    /// <summary>
+    /// Gets the diagnostic describing the invalid bytes.
+    /// </summary>
+    public Diagnostic ToDiagnostic()
+    {
+        var diagnostic = new Diagnostic
+        {
+            Code = DiagnosticCodes.InvalidEncoding,
+            Severity = DiagnosticSeverity.Error,
+            Message = Message,
+            Data = { ["byteOffset"] = ByteOffset, ["encoding"] = Encoding.WebName }
+        };
+
+        return InvalidBytes == null
+            ? diagnostic
+            : diagnostic.WithMessage(DiagnosticCodes.InvalidEncoding, ("file", SourceFile ?? "input"), ("bytes", InvalidBytes), ("offset", ByteOffset), ("encoding", Encoding.WebName));
+    }
+}
@@ -0,0 +1,198 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+
+namespace Minotaur.Core;
+
+/// <summary>
+/// Selects nodes of a parse tree with a CSS-like selector. A selector is a list of steps separated by
+/// whitespace (descendant) or <c>&gt;</c> (child). A step matches a non-terminal by rule name, a terminal by
+/// token kind (quoted literals such as <c>"use"</c> match literal tokens), or any node with <c>*</c>.
+/// </summary>
+/// <example>
+/// <c>import_statement &gt; IDENTIFIER</c> selects identifiers directly under an import statement.
+/// </example>
+public sealed class TreeQuery
+{
+    private readonly IReadOnlyList<QueryStep> _steps;
+
+    private TreeQuery(string text, IReadOnlyList<QueryStep> steps)
+    {
+        Text = text;
+        _steps = steps;
+    }
+
+    /// <summary>
+    /// Gets the selector text.
+    /// </summary>
+    public string Text { get; }
+
+    /// <summary>
+    /// Parses a selector.
+    /// </summary>
+    /// <param name="selector">The selector text.</param>
+    /// <returns>The query.</returns>
+    /// <exception cref="FormatException">The selector is empty or malformed.</exception>
+    public static TreeQuery Parse(string selector)
+    {
+        ArgumentNullException.ThrowIfNull(selector);
+
+        var steps = new List<QueryStep>();
+        var childOfPrevious = false;
+        var i = 0;
+
+        while (i < selector.Length)
+        {
+            var c = selector[i];
+            if (char.IsWhiteSpace(c))
+            {
+                i++;
+                continue;
+            }
+
+            if (c == '>')
+            {
+                if (steps.Count == 0 || childOfPrevious)
+                {
+                    throw new FormatException($"Unexpected '>' at position {i} in tree query '{selector}'");
+                }
+
+                childOfPrevious = true;
+                i++;
+                continue;
+            }
+
+            var name = new StringBuilder();
+            if (c is '"' or '\'')
+            {
+                var close = selector.IndexOf(c, i + 1);
+                if (close < 0)
+                {
+                    throw new FormatException($"Unterminated literal at position {i} in tree query '{selector}'");
+                }
+
+                // Literal tokens are kinded by their double-quoted text.
+                name.Append('"').Append(selector, i + 1, close - i - 1).Append('"');
+                i = close + 1;
+            }
+            else
+            {
+                while (i < selector.Length && !char.IsWhiteSpace(selector[i]) && selector[i] != '>')
+                {
+                    name.Append(selector[i++]);
+                }
+            }
+
+            steps.Add(new QueryStep(name.ToString(), childOfPrevious));
+            childOfPrevious = false;
+        }
+
+        if (steps.Count == 0 || childOfPrevious)
+        {
+            throw new FormatException($"Tree query '{selector}' is incomplete");
+        }
+
+        return new TreeQuery(selector, steps);
+    }
+
+    /// <summary>
+    /// Selects the matching nodes of a tree in document order.
+    /// </summary>
+    /// <param name="root">The root of the tree.</param>
+    /// <returns>The matching nodes.</returns>
+    public IEnumerable<CognitiveGraphNode> Select(CognitiveGraphNode root)
+    {
+        ArgumentNullException.ThrowIfNull(root);
+
+        var stack = new Stack<CognitiveGraphNode>();
+        stack.Push(root);
+        while (stack.Count > 0)
+        {
+            var node = stack.Pop();
+            if (Matches(node, _steps.Count - 1, root))
+            {
+                yield return node;
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                stack.Push(node.Children[i]);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Determines whether a node is selected by the query within the tree rooted at <paramref name="root"/>.
+    /// </summary>
+    /// <param name="node">The node to test.</param>
+    /// <param name="root">The root the ancestors are searched up to.</param>
+    /// <returns>True if the node matches.</returns>
+    public bool IsMatch(CognitiveGraphNode node, CognitiveGraphNode root)
+    {
+        ArgumentNullException.ThrowIfNull(node);
+        ArgumentNullException.ThrowIfNull(root);
+        return Matches(node, _steps.Count - 1, root);
+    }
+
+    /// <inheritdoc />
+    public override string ToString()
+    {
+        return Text;
+    }
+
+    private bool Matches(CognitiveGraphNode node, int step, CognitiveGraphNode root)
+    {
+        if (!_steps[step].Matches(node))
+        {
+            return false;
+        }
+
+        if (step == 0)
+        {
+            return true;
+        }
+
+        if (_steps[step].IsChild)
+        {
+            return node != root && node.Parent != null && Matches(node.Parent, step - 1, root);
+        }
+
+        for (var ancestor = node != root ? node.Parent : null; ancestor != null; ancestor = ancestor != root ? ancestor.Parent : null)
+        {
+            if (Matches(ancestor, step - 1, root))
+            {
+                return true;
+            }
+        }
+
+        return false;
+    }
+
+    private sealed record QueryStep(string Name, bool IsChild)
+    {
+        public bool Matches(CognitiveGraphNode node)
+        {
+            return Name == "*" || node switch
+            {
+                NonTerminalNode nonTerminal => nonTerminal.RuleName == Name,
+                TerminalNode terminal => terminal.TokenType == Name,
+                _ => false
+            };
+        }
+    }
+}
@@ -0,0 +1,417 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Collections.Concurrent;
+using System.Net.Sockets;
+using System.Text;
+using System.Text.Json;
+using Minotaur.Analysis.Passes;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Daemon;
+
+/// <summary>
+/// Connects to a <see cref="WorkspaceDaemon"/> and sends it JSON-RPC requests. Requests may be sent concurrently;
+/// each completes when its own response arrives.
+/// </summary>
+public sealed class DaemonClient : IAsyncDisposable
+{
+    private static readonly JsonSerializerOptions SerializerOptions = new(JsonSerializerDefaults.Web);
+
+    private readonly NetworkStream _stream;
+    private readonly SemaphoreSlim _writeLock = new(1, 1);
+    private readonly ConcurrentDictionary<long, TaskCompletionSource<JsonElement>> _pending = new();
+    private readonly Task _reader;
+    private long _nextId;
+
+    private DaemonClient(Socket socket)
+    {
+        _stream = new NetworkStream(socket, ownsSocket: true);
+        _reader = ReadResponsesAsync();
+    }
+
+    /// <summary>
+    /// Connects to a daemon.
+    /// </summary>
+    /// <param name="socketPath">The daemon's socket path.</param>
+    /// <param name="cancellationToken">Cancels connecting.</param>
+    /// <returns>The connected client.</returns>
+    public static async Task<DaemonClient> ConnectAsync(string socketPath, CancellationToken cancellationToken = default)
+    {
+        var socket = new Socket(AddressFamily.Unix, SocketType.Stream, ProtocolType.Unspecified);
+        try
+        {
+            await socket.ConnectAsync(new UnixDomainSocketEndPoint(socketPath), cancellationToken);
+        }
+        catch
+        {
+            socket.Dispose();
+            throw;
+        }
+
+        return new DaemonClient(socket);
+    }
+
+    /// <summary>
+    /// Sends a request and waits for its result.
+    /// </summary>
+    /// <param name="method">The method name.</param>
+    /// <param name="parameters">The parameters, serialized with camel-case property names, or null.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>The result.</returns>
+    /// <exception cref="DaemonException">The daemon answered with an error.</exception>
+    /// <exception cref="IOException">The connection was closed before the response arrived.</exception>
+    public async Task<JsonElement> InvokeAsync(string method, object? parameters = null, CancellationToken cancellationToken = default)
+    {
+        var id = Interlocked.Increment(ref _nextId);
+        var response = new TaskCompletionSource<JsonElement>(TaskCreationOptions.RunContinuationsAsynchronously);
+        _pending[id] = response;
+
+        var request = JsonSerializer.SerializeToUtf8Bytes(new { jsonrpc = "2.0", id, method, @params = parameters }, SerializerOptions);
+        await _writeLock.WaitAsync(cancellationToken);
+        try
+        {
+            await _stream.WriteAsync(request, cancellationToken);
+            await _stream.WriteAsync("\n"u8.ToArray(), cancellationToken);
+            await _stream.FlushAsync(cancellationToken);
+        }
+        catch
+        {
+            _pending.TryRemove(id, out _);
+            throw;
+        }
+        finally
+        {
+            _writeLock.Release();
+        }
+
+        using var registration = cancellationToken.Register(() =>
+        {
+            if (_pending.TryRemove(id, out var cancelled))
+            {
+                cancelled.TrySetCanceled(cancellationToken);
+            }
+        });
+
+        return await response.Task;
+    }
+
+    /// <summary>
+    /// Parses a file, from the daemon's workspace unless unsaved text is given.
+    /// </summary>
+    /// <param name="path">The file path relative to the daemon's root.</param>
+    /// <param name="text">Unsaved text to parse instead of the file, if any.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>Whether the parse succeeded, and its diagnostics.</returns>
+    public async Task<(bool IsSuccess, IReadOnlyList<Diagnostic> Diagnostics)> ParseFileAsync(string path, string? text = null, CancellationToken cancellationToken = default)
+    {
+        var result = await InvokeAsync("parseFile", new { path, text }, cancellationToken);
+        return (result.GetProperty("success").GetBoolean(), ReadDiagnostics(result.GetProperty("diagnostics"), path));
+    }
+
+    /// <summary>
+    /// Selects nodes of a file's tree with a <see cref="TreeQuery"/>.
+    /// </summary>
+    /// <param name="path">The file path relative to the daemon's root.</param>
+    /// <param name="selector">The tree query.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>The source text of each selected node.</returns>
+    public async Task<IReadOnlyList<string>> QueryAsync(string path, string selector, CancellationToken cancellationToken = default)
+    {
+        var result = await InvokeAsync("query", new { path, selector }, cancellationToken);
+        return result.EnumerateArray().Select(m => m.TryGetProperty("text", out var text) ? text.GetString()! : string.Empty).ToList();
+    }
+
+    /// <summary>
+    /// Gets the current diagnostics of a file in the daemon's workspace.
+    /// </summary>
+    /// <param name="path">The file path relative to the daemon's root.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>The diagnostics, or an empty list if the file is not in the workspace.</returns>
+    public async Task<IReadOnlyList<Diagnostic>> GetDiagnosticsAsync(string path, CancellationToken cancellationToken = default)
+    {
+        var result = await InvokeAsync("diagnostics", new { path }, cancellationToken);
+        var file = result.GetProperty("files").EnumerateArray().FirstOrDefault();
+        return file.ValueKind == JsonValueKind.Object ? ReadDiagnostics(file.GetProperty("diagnostics"), path) : Array.Empty<Diagnostic>();
+    }
+
+    /// <summary>
+    /// Gets the files exporting a symbol.
+    /// </summary>
+    /// <param name="name">The symbol name.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>The paths of the exporting files.</returns>
+    public async Task<IReadOnlyList<string>> FindExportsAsync(string name, CancellationToken cancellationToken = default)
+    {
+        var result = await InvokeAsync("symbols", new { name }, cancellationToken);
+        return result.EnumerateArray().Select(s => s.GetProperty("path").GetString()!).ToList();
+    }
+
+    /// <summary>
+    /// Finds the declarations of the workspace whose names fuzzily match a query.
+    /// </summary>
+    /// <param name="query">The query; see <see cref="WorkspaceSymbolIndex"/>.</param>
+    /// <param name="limit">The maximum number of results.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>The matching declarations, best first.</returns>
+    public async Task<IReadOnlyList<SymbolIndexEntry>> SearchSymbolsAsync(string query, int limit = 100, CancellationToken cancellationToken = default)
+    {
+        var result = await InvokeAsync("workspaceSymbol", new { query, limit }, cancellationToken);
+        return result.EnumerateArray().Select(s =>
+        {
+            var path = s.GetProperty("path").GetString()!;
+            return new SymbolIndexEntry(
+                s.GetProperty("name").GetString()!,
+                s.GetProperty("kind").GetString()!,
+                s.TryGetProperty("container", out var container) ? container.GetString() : null,
+                path,
+                s.TryGetProperty("span", out var span) ? ReadSpan(span, path) : null)
+            {
+                IsExported = s.GetProperty("exported").GetBoolean()
+            };
+        }).ToList();
+    }
+
+    /// <summary>
+    /// Gets the occurrences in a file of the symbol at a position.
+    /// </summary>
+    /// <param name="path">The file path relative to the daemon's root.</param>
+    /// <param name="line">The 1-based line.</param>
+    /// <param name="column">The 1-based column.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>The occurrences in source order.</returns>
+    public async Task<IReadOnlyList<DocumentHighlight>> GetHighlightsAsync(string path, int line, int column, CancellationToken cancellationToken = default)
+    {
+        var result = await InvokeAsync("documentHighlight", new { path, line, column }, cancellationToken);
+        return result.EnumerateArray()
+            .Select(h => new DocumentHighlight(ReadSpan(h.GetProperty("span"), path), Enum.Parse<DocumentHighlightKind>(h.GetProperty("kind").GetString()!, ignoreCase: true)))
+            .ToList();
+    }
+
+    /// <summary>
+    /// Gets the description of the construct at a position.
+    /// </summary>
+    /// <param name="path">The file path relative to the daemon's root.</param>
+    /// <param name="line">The 1-based line.</param>
+    /// <param name="column">The 1-based column.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>The hover, or null if nothing at the position is documented or deprecated.</returns>
+    public async Task<Hover?> GetHoverAsync(string path, int line, int column, CancellationToken cancellationToken = default)
+    {
+        var result = await InvokeAsync("hover", new { path, line, column }, cancellationToken);
+        return result.ValueKind == JsonValueKind.Null
+            ? null
+            : new Hover(ReadSpan(result.GetProperty("span"), path), result.GetProperty("contents").GetString()!);
+    }
+
+    /// <summary>
+    /// Gets the inline hints of a file, or of a range of it.
+    /// </summary>
+    /// <param name="path">The file path relative to the daemon's root.</param>
+    /// <param name="start">The zero-based start of the range, or null for the whole file.</param>
+    /// <param name="end">The zero-based end of the range, or null for the whole file.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>The hints in source order.</returns>
+    public async Task<IReadOnlyList<InlayHint>> GetInlayHintsAsync(string path, DocumentPosition? start = null, DocumentPosition? end = null, CancellationToken cancellationToken = default)
+    {
+        object parameters = start is { } from && end is { } to
+            ? new
+            {
+                path,
+                range = new
+                {
+                    start = new { line = from.Line, character = from.Character },
+                    end = new { line = to.Line, character = to.Character }
+                }
+            }
+            : new { path };
+        var result = await InvokeAsync("inlayHint", parameters, cancellationToken);
+        return result.EnumerateArray()
+            .Select(h => new InlayHint(
+                ReadSpan(h.GetProperty("span"), path),
+                h.GetProperty("label").GetString()!,
+                Enum.Parse<InlayHintKind>(h.GetProperty("kind").GetString()!, ignoreCase: true),
+                h.TryGetProperty("tooltip", out var tooltip) ? tooltip.GetString() : null))
+            .ToList();
+    }
+
+    /// <summary>
+    /// Tells the daemon that the editor opened a document, or sends its whole text after a resync was requested.
+    /// </summary>
+    /// <param name="path">The file path relative to the daemon's root.</param>
+    /// <param name="version">The editor's version number.</param>
+    /// <param name="text">The document text.</param>
+    /// <param name="resync">True to resynchronize a document the daemon already has open.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>The document's version in the daemon.</returns>
+    public async Task<DocumentChangeResult> OpenDocumentAsync(string path, int version, string text, bool resync = false, CancellationToken cancellationToken = default)
+    {
+        return ReadChange(await InvokeAsync(resync ? "resync" : "didOpen", new { path, version, text }, cancellationToken));
+    }
+
+    /// <summary>
+    /// Sends the changes of a document version.
+    /// </summary>
+    /// <param name="path">The file path relative to the daemon's root.</param>
+    /// <param name="version">The editor's version number after the changes.</param>
+    /// <param name="changes">The content changes in application order.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>What the daemon did with the version.</returns>
+    public async Task<DocumentChangeResult> ChangeDocumentAsync(string path, int version, IReadOnlyList<DocumentChange> changes, CancellationToken cancellationToken = default)
+    {
+        var contentChanges = changes.Select(c => c.IsFullText
+            ? (object)new { text = c.Text }
+            : new
+            {
+                text = c.Text,
+                range = new
+                {
+                    start = new { line = c.Start!.Value.Line, character = c.Start.Value.Character },
+                    end = new { line = c.End!.Value.Line, character = c.End.Value.Character }
+                }
+            });
+        return ReadChange(await InvokeAsync("didChange", new { path, version, changes = contentChanges }, cancellationToken));
+    }
+
+    /// <summary>
+    /// Tells the daemon that the editor closed a document.
+    /// </summary>
+    /// <param name="path">The file path relative to the daemon's root.</param>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>True if the document was open.</returns>
+    public async Task<bool> CloseDocumentAsync(string path, CancellationToken cancellationToken = default)
+    {
+        return (await InvokeAsync("didClose", new { path }, cancellationToken)).GetBoolean();
+    }
+
+    /// <summary>
+    /// Asks the daemon to stop.
+    /// </summary>
+    /// <param name="cancellationToken">Stops waiting for the response.</param>
+    /// <returns>A task that completes when the daemon has acknowledged the request.</returns>
+    public async Task ShutdownAsync(CancellationToken cancellationToken = default)
+    {
+        await InvokeAsync("shutdown", null, cancellationToken);
+    }
+
+    /// <inheritdoc />
+    public async ValueTask DisposeAsync()
+    {
+        try
+        {
+            _stream.Socket.Shutdown(SocketShutdown.Both);
+        }
+        catch (SocketException)
+        {
+            // The daemon already closed the connection.
+        }
+
+        await _stream.DisposeAsync();
+        await _reader;
+        _writeLock.Dispose();
+    }
+
+    private async Task ReadResponsesAsync()
+    {
+        try
+        {
+            using var reader = new StreamReader(_stream, new UTF8Encoding(false), leaveOpen: true);
+            while (await reader.ReadLineAsync() is { } line)
+            {
+                using var response = JsonDocument.Parse(line);
+                var root = response.RootElement;
+                if (!root.TryGetProperty("id", out var id) || id.ValueKind != JsonValueKind.Number || !_pending.TryRemove(id.GetInt64(), out var pending))
+                {
+                    continue;
+                }
+
+                if (root.TryGetProperty("error", out var error))
+                {
+                    pending.TrySetException(new DaemonException(error.GetProperty("code").GetInt32(), error.GetProperty("message").GetString()!));
+                }
+                else
+                {
+                    pending.TrySetResult(root.GetProperty("result").Clone());
+                }
+            }
+        }
+        catch (Exception ex) when (ex is IOException or ObjectDisposedException or JsonException)
+        {
+        }
+
+        foreach (var id in _pending.Keys)
+        {
+            if (_pending.TryRemove(id, out var pending))
+            {
+                pending.TrySetException(new IOException("The daemon closed the connection"));
+            }
+        }
+    }
+
+    private static DocumentChangeResult ReadChange(JsonElement result)
+    {
+        return new DocumentChangeResult(
+            Enum.Parse<DocumentChangeStatus>(result.GetProperty("status").GetString()!, ignoreCase: true),
+            result.GetProperty("version").GetInt32(),
+            result.TryGetProperty("reason", out var reason) ? reason.GetString() : null);
+    }
+
+    private static IReadOnlyList<Diagnostic> ReadDiagnostics(JsonElement diagnostics, string path)
+    {
+        return diagnostics.EnumerateArray().Select(d => new Diagnostic
+        {
+            Code = d.GetProperty("code").GetString()!,
+            Severity = Enum.Parse<DiagnosticSeverity>(d.GetProperty("severity").GetString()!),
+            Message = d.GetProperty("message").GetString()!,
+            Location = d.TryGetProperty("span", out var span) ? ReadSpan(span, path) : null
+        }).ToList();
+    }
+
+    private static SourcePosition ReadSpan(JsonElement span, string path)
+    {
+        return new SourcePosition(span.GetProperty("line").GetInt32(), span.GetProperty("column").GetInt32(), span.GetProperty("offset").GetInt32(), span.GetProperty("length").GetInt32())
+        {
+            EndLine = span.GetProperty("endLine").GetInt32(),
+            EndColumn = span.GetProperty("endColumn").GetInt32(),
+            SourceFile = path
+        };
+    }
+}
+
+/// <summary>
+/// An error response from a <see cref="WorkspaceDaemon"/>.
+/// </summary>
+public sealed class DaemonException : Exception
+{
+    /// <summary>
+    /// Initializes a new instance of the DaemonException class.
+    /// </summary>
+    /// <param name="code">The JSON-RPC error code.</param>
+    /// <param name="message">The error message.</param>
+    public DaemonException(int code, string message)
+        : base(message)
+    {
+        Code = code;
+    }
+
+    /// <summary>
+    /// Gets the JSON-RPC error code: -32601 for an unknown method, -32602 for invalid parameters.
+    /// </summary>
+    public int Code { get; }
+}
@@ -0,0 +1,578 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Collections.Concurrent;
+using Minotaur.Core;
+using Minotaur.Parser;
+
+namespace Minotaur.Daemon;
+
+/// <summary>
+/// A 0-based line and character in a document, as in the Language Server Protocol.
+/// </summary>
+/// <param name="Line">The 0-based line.</param>
+/// <param name="Character">The 0-based character within the line.</param>
+public readonly record struct DocumentPosition(int Line, int Character);
+
+/// <summary>
+/// One content change of a document version: a range replaced by text, or the whole text when the range is null.
+/// </summary>
+/// <param name="Text">The inserted text, or the whole new text.</param>
+/// <param name="Start">The start of the replaced range, or null to replace the whole text.</param>
+/// <param name="End">The end of the replaced range, or null to replace the whole text.</param>
+public sealed record DocumentChange(string Text, DocumentPosition? Start = null, DocumentPosition? End = null)
+{
+    /// <summary>
+    /// Gets a value indicating whether the change replaces the whole text.
+    /// </summary>
+    public bool IsFullText => Start == null || End == null;
+
+    /// <summary>
+    /// Creates a change replacing the whole text.
+    /// </summary>
+    /// <param name="text">The new text.</param>
+    /// <returns>The change.</returns>
+    public static DocumentChange FullText(string text) => new(text);
+
+    /// <summary>
+    /// Creates a change replacing a range.
+    /// </summary>
+    /// <param name="start">The start of the range.</param>
+    /// <param name="end">The end of the range.</param>
+    /// <param name="text">The inserted text.</param>
+    /// <returns>The change.</returns>
+    public static DocumentChange Replace(DocumentPosition start, DocumentPosition end, string text) => new(text, start, end);
+}
+
+/// <summary>
+/// What a <see cref="DocumentStore"/> did with a document version.
+/// </summary>
+public enum DocumentChangeStatus
+{
+    /// <summary>
+    /// The version, and any buffered versions it made consecutive, were applied.
+    /// </summary>
+    Applied,
+
+    /// <summary>
+    /// The version arrived before a version it builds on and is held until the gap is filled.
+    /// </summary>
+    Buffered,
+
+    /// <summary>
+    /// The version is not newer than the current one and was ignored.
+    /// </summary>
+    Stale,
+
+    /// <summary>
+    /// The store cannot follow the client: the document is not open, a change range does not fit the text, or too
+    /// many versions are waiting for a missing one. The client should send the whole text with
+    /// <see cref="DocumentStore.Resync"/>.
+    /// </summary>
+    ResyncRequired
+}
+
+/// <summary>
+/// The outcome of a document change.
+/// </summary>
+/// <param name="Status">What the store did with the version.</param>
+/// <param name="Version">The document's current version afterwards, or -1 if it is not open.</param>
+/// <param name="Reason">Why a resync is required, if it is.</param>
+public sealed record DocumentChangeResult(DocumentChangeStatus Status, int Version, string? Reason = null);
+
+/// <summary>
+/// A version of a document kept by a <see cref="DocumentStore"/>.
+/// </summary>
+public sealed class DocumentVersion
+{
+    internal DocumentVersion(int version, Rope text, IReadOnlyList<TextEdit> edits)
+    {
+        Version = version;
+        Text = text;
+        Edits = edits;
+    }
+
+    /// <summary>
+    /// Gets the client's version number.
+    /// </summary>
+    public int Version { get; }
+
+    /// <summary>
+    /// Gets the text of the version.
+    /// </summary>
+    public Rope Text { get; }
+
+    /// <summary>
+    /// Gets the edits that turned the previous version into this one, in application order; empty for the version
+    /// a document was opened or resynchronized with.
+    /// </summary>
+    public IReadOnlyList<TextEdit> Edits { get; }
+}
+
+/// <summary>
+/// Options for a <see cref="DocumentStore"/>.
+/// </summary>
+public class DocumentStoreOptions
+{
+    /// <summary>
+    /// Gets or sets how many versions of each document are kept, including the current one.
+    /// </summary>
+    public int RetainedVersions { get; set; } = 16;
+
+    /// <summary>
+    /// Gets or sets how many out-of-order versions of a document may wait for a missing one before the store asks
+    /// for a resync.
+    /// </summary>
+    public int MaxPendingVersions { get; set; } = 64;
+
+    /// <summary>
+    /// Gets or sets the watchdog applied to each reparse, if any.
+    /// </summary>
+    public ParseWatchdogOptions? Watchdog { get; set; }
+
+    /// <summary>
+    /// Gets or sets the interner the identifier text of every document's parse is shared through, if any.
+    /// </summary>
+    public SharedInterner? Interner { get; set; }
+}
+
+/// <summary>
+/// Holds the text of documents open in an editor as the client's change notifications describe it. Versions are
+/// applied in order: one that arrives early waits for the versions before it, one that replaces the whole text is
+/// applied at once, and a change whose range does not fit the text rejects its version and asks for a resync.
+/// The last few versions stay available so results computed for an older version can be mapped onto the current
+/// text. With a parser, each applied version is reparsed incrementally with the version's edits composed into one.
+/// </summary>
+/// <remarks>
+/// Changes, resyncs and reparses of a document are serialized on that document, while different documents are
+/// updated in parallel.
+/// </remarks>
+public sealed class DocumentStore
+{
+    private readonly GeneralizedParser? _parser;
+    private readonly DocumentStoreOptions _options;
+    private readonly ConcurrentDictionary<string, DocumentState> _documents = new(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Initializes a new instance of the DocumentStore class.
+    /// </summary>
+    /// <param name="parser">The parser that keeps each document parsed, or null to only track text.</param>
+    /// <param name="options">The store options.</param>
+    public DocumentStore(GeneralizedParser? parser = null, DocumentStoreOptions? options = null)
+    {
+        _parser = parser;
+        _options = options ?? new DocumentStoreOptions();
+    }
+
+    /// <summary>
+    /// Gets the paths of the open documents, in ordinal order.
+    /// </summary>
+    public IReadOnlyList<string> Paths => _documents.Keys.OrderBy(path => path, StringComparer.Ordinal).ToList();
+
+    /// <summary>
+    /// Gets the number of open documents.
+    /// </summary>
+    public int Count => _documents.Count;
+
+    /// <summary>
+    /// Opens a document, replacing it if it is already open.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <param name="version">The client's version number.</param>
+    /// <param name="text">The document text.</param>
+    /// <returns>The opened version.</returns>
+    public DocumentVersion Open(string path, int version, string text)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        ArgumentNullException.ThrowIfNull(text);
+
+        var state = new DocumentState(path);
+        lock (state)
+        {
+            Reset(state, version, text);
+        }
+
+        _documents.AddOrUpdate(path, state, (_, previous) =>
+        {
+            Close(previous);
+            return state;
+        });
+        return state.Current;
+    }
+
+    /// <summary>
+    /// Closes a document.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <returns>True if the document was open.</returns>
+    public bool Close(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        if (!_documents.TryRemove(path, out var state))
+        {
+            return false;
+        }
+
+        Close(state);
+        return true;
+    }
+
+    /// <summary>
+    /// Applies a version of a document described by content changes, which apply one after another.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <param name="version">The client's version number after the changes.</param>
+    /// <param name="changes">The content changes in application order.</param>
+    /// <returns>What the store did with the version.</returns>
+    public DocumentChangeResult Change(string path, int version, IReadOnlyList<DocumentChange> changes)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        ArgumentNullException.ThrowIfNull(changes);
+
+        return Update(path, state =>
+        {
+            var current = state.Current.Version;
+            if (version <= current)
+            {
+                return new DocumentChangeResult(DocumentChangeStatus.Stale, current);
+            }
+
+            // A version that replaces the whole text does not depend on the versions before it.
+            if (version > current + 1 && !changes.Any(c => c.IsFullText))
+            {
+                if (state.Pending.Count >= _options.MaxPendingVersions)
+                {
+                    state.Pending.Clear();
+                    return new DocumentChangeResult(
+                        DocumentChangeStatus.ResyncRequired,
+                        current,
+                        $"{_options.MaxPendingVersions} versions are waiting for version {current + 1}");
+                }
+
+                state.Pending[version] = changes;
+                return new DocumentChangeResult(DocumentChangeStatus.Buffered, current);
+            }
+
+            return ApplyWithPending(state, version, changes);
+        });
+    }
+
+    /// <summary>
+    /// Replaces a document's text with the client's, whatever the store's version, when the two have diverged.
+    /// Retained versions are dropped, and buffered versions after the given one are applied on top of it.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <param name="version">The client's version number.</param>
+    /// <param name="text">The client's text.</param>
+    /// <returns>The outcome; a document that is not open is opened.</returns>
+    public DocumentChangeResult Resync(string path, int version, string text)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        ArgumentNullException.ThrowIfNull(text);
+
+        var result = Update(path, state =>
+        {
+            Reset(state, version, text);
+            return Drain(state);
+        });
+
+        if (result.Version < 0)
+        {
+            return new DocumentChangeResult(DocumentChangeStatus.Applied, Open(path, version, text).Version);
+        }
+
+        return result;
+    }
+
+    /// <summary>
+    /// Gets the current version of a document.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <returns>The current version, or null if the document is not open.</returns>
+    public DocumentVersion? GetCurrent(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        if (!_documents.TryGetValue(path, out var state))
+        {
+            return null;
+        }
+
+        lock (state)
+        {
+            return state.Current;
+        }
+    }
+
+    /// <summary>
+    /// Gets a retained version of a document.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <param name="version">The client's version number.</param>
+    /// <returns>The version, or null if the document is not open or the version is not retained.</returns>
+    public DocumentVersion? GetVersion(string path, int version)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        if (!_documents.TryGetValue(path, out var state))
+        {
+            return null;
+        }
+
+        lock (state)
+        {
+            return state.Versions.FirstOrDefault(v => v.Version == version);
+        }
+    }
+
+    /// <summary>
+    /// Gets the parse of a document's current version, waiting for a reparse in progress.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <returns>The parse, or null if the document is not open or the store has no parser.</returns>
+    public ParseResult? GetParse(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        if (!_documents.TryGetValue(path, out var state))
+        {
+            return null;
+        }
+
+        lock (state)
+        {
+            return state.Parser?.Current;
+        }
+    }
+
+    /// <summary>
+    /// Maps a range of a retained version onto the current text, for results that arrive after newer versions.
+    /// </summary>
+    /// <param name="path">The document path.</param>
+    /// <param name="version">The version the range refers to.</param>
+    /// <param name="range">The range in that version's text.</param>
+    /// <returns>The range in the current text, or null if the version is not retained or a later edit changed
+    /// text inside the range.</returns>
+    public TextRange? MapRange(string path, int version, TextRange range)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        if (!_documents.TryGetValue(path, out var state))
+        {
+            return null;
+        }
+
+        lock (state)
+        {
+            var index = state.Versions.FindIndex(v => v.Version == version);
+            if (index < 0)
+            {
+                return null;
+            }
+
+            var start = range.Start;
+            var end = range.End;
+            foreach (var edit in state.Versions.Skip(index + 1).SelectMany(v => v.Edits))
+            {
+                var editEnd = edit.Offset + edit.Length;
+                var overlaps = edit.Length > 0
+                    ? edit.Offset < end && editEnd > start
+                    : start < edit.Offset && edit.Offset < end;
+                if (overlaps)
+                {
+                    return null;
+                }
+
+                // Edits before the range shift it; edits after it leave it alone.
+                if (editEnd <= start)
+                {
+                    start += edit.Delta;
+                    end += edit.Delta;
+                }
+            }
+
+            return new TextRange(start, end - start);
+        }
+    }
+
+    /// <summary>
+    /// Gets the parses of the current versions of the open documents, for finding what they still use.
+    /// </summary>
+    internal List<ParseResult> GetParses()
+    {
+        var parses = new List<ParseResult>();
+        foreach (var state in _documents.Values)
+        {
+            lock (state)
+            {
+                if (state.Parser?.Current is { } parse)
+                {
+                    parses.Add(parse);
+                }
+            }
+        }
+
+        return parses;
+    }
+
+    private DocumentChangeResult Update(string path, Func<DocumentState, DocumentChangeResult> update)
+    {
+        if (!_documents.TryGetValue(path, out var state))
+        {
+            return new DocumentChangeResult(DocumentChangeStatus.ResyncRequired, -1, $"Document '{path}' is not open");
+        }
+
+        lock (state)
+        {
+            return state.IsClosed
+                ? new DocumentChangeResult(DocumentChangeStatus.ResyncRequired, -1, $"Document '{path}' is not open")
+                : update(state);
+        }
+    }
+
+    private DocumentChangeResult ApplyWithPending(DocumentState state, int version, IReadOnlyList<DocumentChange> changes)
+    {
+        var result = Apply(state, version, changes);
+        return result.Status == DocumentChangeStatus.Applied ? Drain(state) : result;
+    }
+
+    private DocumentChangeResult Drain(DocumentState state)
+    {
+        // Buffered versions at or below the current one are superseded; the next one in line can now be applied.
+        while (state.Pending.Count > 0)
+        {
+            var (version, changes) = state.Pending.First();
+            state.Pending.Remove(version);
+            if (version <= state.Current.Version)
+            {
+                continue;
+            }
+
+            if (version > state.Current.Version + 1)
+            {
+                state.Pending[version] = changes;
+                break;
+            }
+
+            var result = Apply(state, version, changes);
+            if (result.Status != DocumentChangeStatus.Applied)
+            {
+                return result;
+            }
+        }
+
+        return new DocumentChangeResult(DocumentChangeStatus.Applied, state.Current.Version);
+    }
+
+    private DocumentChangeResult Apply(DocumentState state, int version, IReadOnlyList<DocumentChange> changes)
+    {
+        // Changes before the last whole-text change are overwritten by it, so their ranges are never checked.
+        var first = 0;
+        for (var i = 0; i < changes.Count; i++)
+        {
+            first = changes[i].IsFullText ? i : first;
+        }
+
+        var text = state.Current.Text;
+        var edits = new List<TextEdit>(changes.Count);
+        for (var i = first; i < changes.Count; i++)
+        {
+            var change = changes[i];
+            TextEdit edit;
+            if (change.IsFullText)
+            {
+                edit = new TextEdit(0, text.Length, change.Text);
+            }
+            else
+            {
+                var start = text.GetOffset(change.Start!.Value.Line, change.Start.Value.Character);
+                var end = text.GetOffset(change.End!.Value.Line, change.End.Value.Character);
+                if (start < 0 || end < start)
+                {
+                    // The client's text is not the store's; nothing waiting on this version can be applied either.
+                    state.Pending.Clear();
+                    return new DocumentChangeResult(
+                        DocumentChangeStatus.ResyncRequired,
+                        state.Current.Version,
+                        $"Change {i + 1} of version {version} of '{state.Path}' has range {Format(change.Start.Value)}-{Format(change.End.Value)}, " +
+                        $"which is not in the text of version {state.Current.Version}");
+                }
+
+                edit = new TextEdit(start, end - start, change.Text);
+            }
+
+            text = text.Replace(edit.Offset, edit.Length, edit.NewText);
+            edits.Add(edit);
+        }
+
+        state.Versions.Add(new DocumentVersion(version, text, edits));
+        if (state.Versions.Count > Math.Max(1, _options.RetainedVersions))
+        {
+            state.Versions.RemoveAt(0);
+        }
+
+        if (edits.Count > 0)
+        {
+            state.Parser?.ApplyEdit(TextEdit.Compose(edits, text.ToString()));
+        }
+
+        return new DocumentChangeResult(DocumentChangeStatus.Applied, version);
+    }
+
+    private void Reset(DocumentState state, int version, string text)
+    {
+        state.Versions.Clear();
+        state.Versions.Add(new DocumentVersion(version, Rope.FromString(text), Array.Empty<TextEdit>()));
+        state.Parser = _parser == null
+            ? null
+            : new IncrementalParser(_parser, text, new ParseOptions { SourceFile = state.Path, Watchdog = _options.Watchdog, Interner = _options.Interner });
+    }
+
+    private static void Close(DocumentState state)
+    {
+        lock (state)
+        {
+            state.IsClosed = true;
+        }
+    }
+
+    private static string Format(DocumentPosition position)
+    {
+        return $"{position.Line}:{position.Character}";
+    }
+
+    private sealed class DocumentState
+    {
+        public DocumentState(string path)
+        {
+            Path = path;
+        }
+
+        public string Path { get; }
+
+        public List<DocumentVersion> Versions { get; } = new();
+
+        public SortedDictionary<int, IReadOnlyList<DocumentChange>> Pending { get; } = new();
+
+        public IncrementalParser? Parser { get; set; }
+
+        public bool IsClosed { get; set; }
+
+        public DocumentVersion Current => Versions[^1];
+    }
+}
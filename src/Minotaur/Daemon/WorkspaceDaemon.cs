@@ -0,0 +1,907 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Net.Sockets;
+using System.Text;
+using System.Text.Json;
+using System.Threading.Channels;
+using Minotaur.Analysis.Passes;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Monitoring;
+using Minotaur.Parser;
+
+namespace Minotaur.Daemon;
+
+/// <summary>
+/// Options of a <see cref="WorkspaceDaemon"/>.
+/// </summary>
+public class WorkspaceDaemonOptions
+{
+    /// <summary>
+    /// Gets or sets the pattern of the files under the root directory that belong to the workspace.
+    /// </summary>
+    public string SearchPattern { get; set; } = "*";
+
+    /// <summary>
+    /// Gets or sets how long file change notifications are collected before the workspace is updated, so that
+    /// the several notifications of one save cause one update.
+    /// </summary>
+    public TimeSpan Debounce { get; set; } = TimeSpan.FromMilliseconds(50);
+
+    /// <summary>
+    /// Gets or sets a checkpoint the workspace is restored from on start and saved to on shutdown, if any.
+    /// </summary>
+    public string? CheckpointPath { get; set; }
+
+    /// <summary>
+    /// Gets or sets the watchdog of <c>parseFile</c> requests with unsaved text, if any.
+    /// </summary>
+    public ParseWatchdogOptions? Watchdog { get; set; }
+
+    /// <summary>
+    /// Gets or sets how many interned strings, as the daemon's memory report counts them, start a garbage
+    /// collection after a workspace update or a closed document; see <see cref="WorkspaceDaemon.CollectGarbage"/>.
+    /// Zero collects only when asked.
+    /// </summary>
+    public int CollectionThreshold { get; set; } = 100_000;
+}
+
+/// <summary>
+/// What a garbage collection of a <see cref="WorkspaceDaemon"/> dropped.
+/// </summary>
+/// <param name="DroppedStrings">The interned strings no live document used any more.</param>
+/// <param name="ForgottenQueries">The cached analysis values of files no longer in the workspace.</param>
+public sealed record DaemonCollectionResult(int DroppedStrings, int ForgottenQueries);
+
+/// <summary>
+/// Keeps an <see cref="AnalysisWorkspace"/> of the files under a directory up to date and answers JSON-RPC 2.0
+/// requests about it over a Unix domain socket, for tools that want to query a long-lived process.
+/// </summary>
+/// <remarks>
+/// <para>
+/// Messages are JSON objects, one per line. The methods are <c>parseFile</c> (<c>path</c>, optional unsaved
+/// <c>text</c> and <c>tree</c> flag), <c>query</c> (<c>path</c> and a <see cref="TreeQuery"/> <c>selector</c>),
+/// <c>diagnostics</c> (optional <c>path</c>), <c>symbols</c> (a <c>path</c> for its declarations or a <c>name</c>
+/// for its exports), <c>workspaceSymbol</c> (a fuzzy <c>query</c> and optional <c>limit</c>; see
+/// <see cref="WorkspaceSymbolIndex"/>), <c>documentHighlight</c> (<c>path</c> and 1-based <c>line</c> and <c>column</c>; see
+/// <see cref="DocumentHighlightProvider"/>), <c>hover</c> (the same; see <see cref="HoverProvider"/>),
+/// <c>inlayHint</c> (<c>path</c> and an optional Language Server Protocol <c>range</c>; see
+/// <see cref="InlayHintProvider"/>), <c>onTypeFormatting</c> (the same as <c>hover</c>, just after a typed
+/// delimiter; answers the closing delimiter to insert there, see <see cref="GrammarEditorInfo.GetClosingText"/>)
+/// and <c>shutdown</c>. Paths are relative to the root directory with <c>/</c> separators.
+/// </para>
+/// <para>
+/// Editors report the documents they have open with <c>didOpen</c> (<c>path</c>, <c>version</c>, <c>text</c>),
+/// <c>didChange</c> (<c>path</c>, <c>version</c> and Language Server Protocol <c>changes</c>), <c>resync</c> (like
+/// <c>didOpen</c>, after a change was answered with status <c>resyncRequired</c>) and <c>didClose</c>; see
+/// <see cref="DocumentStore"/>. <c>parseFile</c> without <c>text</c> answers from an open document's latest version.
+/// </para>
+/// <para>
+/// A file watcher queues changed paths, and a single update loop applies them to the workspace and then publishes
+/// an immutable snapshot of every document. Requests are answered from the latest snapshot, each on its own task,
+/// so they never wait for an update or for one another; parses of unsaved text do not touch the workspace and are
+/// bounded by the <see cref="WorkspaceDaemonOptions.Watchdog"/>.
+/// </para>
+/// <para>
+/// Every parse the daemon makes shares identifier text through one <see cref="SharedInterner"/> for the daemon's
+/// lifetime, so a name used across many files is held once. What the interner and the workspace's query cache hold
+/// for documents that were closed or deleted is dropped by <see cref="CollectGarbage"/>, which runs on its own once
+/// the interner grows past <see cref="WorkspaceDaemonOptions.CollectionThreshold"/>.
+/// </para>
+/// </remarks>
+public sealed class WorkspaceDaemon : IMemoryReporting
+{
+    internal const int ParseError = -32700;
+    internal const int InvalidRequest = -32600;
+    internal const int MethodNotFound = -32601;
+    internal const int InvalidParams = -32602;
+    internal const int InternalError = -32603;
+
+    private readonly GeneralizedParser _parser;
+    private readonly string _root;
+    private readonly WorkspaceDaemonOptions _options;
+    private readonly AnalysisWorkspace _workspace;
+    private readonly DocumentStore _documents;
+    private readonly SharedInterner _interner = new();
+    private readonly object _updateLock = new();
+    private readonly Channel<string> _changes = Channel.CreateUnbounded<string>(new UnboundedChannelOptions { SingleReader = true });
+    private readonly CancellationTokenSource _shutdown = new();
+    private readonly TaskCompletionSource _started = new(TaskCreationOptions.RunContinuationsAsynchronously);
+    private volatile WorkspaceSnapshot _snapshot = new(0, new Dictionary<string, WorkspaceDocument>(), new Dictionary<string, IReadOnlyList<Diagnostic>>());
+    private int _nextCollection;
+
+    /// <summary>
+    /// Initializes a new instance of the WorkspaceDaemon class.
+    /// </summary>
+    /// <param name="parser">The parser used for every file.</param>
+    /// <param name="rootDirectory">The directory whose files form the workspace.</param>
+    /// <param name="options">The daemon options.</param>
+    public WorkspaceDaemon(GeneralizedParser parser, string rootDirectory, WorkspaceDaemonOptions? options = null)
+    {
+        _parser = parser ?? throw new ArgumentNullException(nameof(parser));
+        _root = Path.GetFullPath(rootDirectory ?? throw new ArgumentNullException(nameof(rootDirectory)));
+        _options = options ?? new WorkspaceDaemonOptions();
+        _workspace = new AnalysisWorkspace(parser) { Interner = _interner };
+        _documents = new DocumentStore(parser, new DocumentStoreOptions { Watchdog = _options.Watchdog, Interner = _interner });
+        _nextCollection = _options.CollectionThreshold;
+        DiagnosticCatalog.Default.RegisterGrammar(parser.Grammar);
+    }
+
+    /// <summary>
+    /// Gets a task that completes once the workspace is loaded and the socket accepts connections.
+    /// </summary>
+    public Task Started => _started.Task;
+
+    /// <summary>
+    /// Gets the number of workspace updates published so far, starting with the initial load.
+    /// </summary>
+    public long Generation => _snapshot.Generation;
+
+    /// <summary>
+    /// Gets the number of garbage collections run so far.
+    /// </summary>
+    public int Collections => _interner.Collections;
+
+    /// <summary>
+    /// Gets what the daemon holds besides the documents themselves: the interned strings, the open documents and the
+    /// workspace's cached query values.
+    /// </summary>
+    /// <returns>The memory report.</returns>
+    public MemoryReport GetMemoryReport()
+    {
+        int cached;
+        lock (_updateLock)
+        {
+            cached = _workspace.CachedQueryCount;
+        }
+
+        var interned = _interner.GetMemoryReport().Get(MemoryCategories.InternedStrings)!;
+        return new MemoryReport("workspace daemon", new[]
+        {
+            interned,
+            new MemoryReportEntry(MemoryCategories.OpenDocuments, _documents.Count, 0),
+            new MemoryReportEntry(MemoryCategories.CachedQueries, cached, 0)
+        });
+    }
+
+    /// <summary>
+    /// Drops the interned strings that no document in the workspace or open in an editor uses, and the cached
+    /// analysis of files no longer in the workspace. Parses and requests go on while it runs; a string a parse
+    /// interned just before may be dropped, after which the parse's text is simply not shared.
+    /// </summary>
+    /// <returns>What was dropped.</returns>
+    public DaemonCollectionResult CollectGarbage()
+    {
+        var live = new List<string>();
+        int forgotten;
+        lock (_updateLock)
+        {
+            forgotten = _workspace.Trim();
+
+            // Requests may still read a snapshot published before the last update.
+            foreach (var document in _workspace.Documents.Concat(_snapshot.Documents.Values).Distinct())
+            {
+                live.AddRange(document.Parse.Tokens.Select(t => t.Text));
+                live.AddRange(document.Symbols.Occurrences.Select(o => o.Name));
+            }
+        }
+
+        foreach (var parse in _documents.GetParses())
+        {
+            live.AddRange(parse.Tokens.Select(t => t.Text));
+        }
+
+        var dropped = _interner.Collect(live);
+
+        // A workspace whose live names exceed the threshold would otherwise collect after every update.
+        Volatile.Write(ref _nextCollection, Math.Max(_options.CollectionThreshold, _interner.Count * 2));
+        return new DaemonCollectionResult(dropped, forgotten);
+    }
+
+    /// <summary>
+    /// Loads the workspace, then serves connections on a socket until a <c>shutdown</c> request or cancellation.
+    /// </summary>
+    /// <param name="socketPath">The socket path; an existing file there is replaced.</param>
+    /// <param name="cancellationToken">Stops the daemon.</param>
+    /// <returns>A task that completes when the daemon has stopped.</returns>
+    public async Task RunAsync(string socketPath, CancellationToken cancellationToken = default)
+    {
+        using var stop = CancellationTokenSource.CreateLinkedTokenSource(cancellationToken, _shutdown.Token);
+        var token = stop.Token;
+
+        try
+        {
+            Load();
+            using var watcher = CreateWatcher();
+
+            File.Delete(socketPath);
+            using var listener = new Socket(AddressFamily.Unix, SocketType.Stream, ProtocolType.Unspecified);
+            listener.Bind(new UnixDomainSocketEndPoint(socketPath));
+            listener.Listen();
+            _started.TrySetResult();
+
+            var tasks = new List<Task> { ApplyChangesAsync(token) };
+            try
+            {
+                while (true)
+                {
+                    var connection = await listener.AcceptAsync(token);
+                    tasks.Add(ServeAsync(connection, token));
+                }
+            }
+            catch (OperationCanceledException) when (token.IsCancellationRequested)
+            {
+            }
+
+            await Task.WhenAll(tasks);
+        }
+        catch (Exception ex)
+        {
+            _started.TrySetException(ex);
+            throw;
+        }
+        finally
+        {
+            File.Delete(socketPath);
+        }
+
+        if (_options.CheckpointPath != null)
+        {
+            lock (_updateLock)
+            {
+                _workspace.SaveCheckpoint(_options.CheckpointPath);
+            }
+        }
+    }
+
+    private void Load()
+    {
+        var files = Directory.EnumerateFiles(_root, _options.SearchPattern, SearchOption.AllDirectories)
+            .ToDictionary(ToWorkspacePath, File.ReadAllText, StringComparer.Ordinal);
+
+        lock (_updateLock)
+        {
+            if (_options.CheckpointPath != null)
+            {
+                _workspace.RestoreCheckpoint(_options.CheckpointPath, files);
+            }
+            else
+            {
+                foreach (var (path, text) in files.OrderBy(f => f.Key, StringComparer.Ordinal))
+                {
+                    _workspace.SetDocument(path, text);
+                }
+            }
+
+            Publish();
+        }
+    }
+
+    private FileSystemWatcher CreateWatcher()
+    {
+        var watcher = new FileSystemWatcher(_root, _options.SearchPattern)
+        {
+            IncludeSubdirectories = true,
+            NotifyFilter = NotifyFilters.FileName | NotifyFilters.DirectoryName | NotifyFilters.LastWrite | NotifyFilters.Size
+        };
+
+        watcher.Changed += (_, e) => _changes.Writer.TryWrite(e.FullPath);
+        watcher.Created += (_, e) => _changes.Writer.TryWrite(e.FullPath);
+        watcher.Deleted += (_, e) => _changes.Writer.TryWrite(e.FullPath);
+        watcher.Renamed += (_, e) =>
+        {
+            _changes.Writer.TryWrite(e.OldFullPath);
+            _changes.Writer.TryWrite(e.FullPath);
+        };
+        watcher.EnableRaisingEvents = true;
+        return watcher;
+    }
+
+    private async Task ApplyChangesAsync(CancellationToken token)
+    {
+        try
+        {
+            while (await _changes.Reader.WaitToReadAsync(token))
+            {
+                await Task.Delay(_options.Debounce, token);
+
+                var paths = new SortedSet<string>(StringComparer.Ordinal);
+                while (_changes.Reader.TryRead(out var path))
+                {
+                    paths.Add(path);
+                }
+
+                lock (_updateLock)
+                {
+                    foreach (var path in paths)
+                    {
+                        Apply(path);
+                    }
+
+                    Publish();
+                }
+
+                CollectIfDue();
+            }
+        }
+        catch (OperationCanceledException) when (token.IsCancellationRequested)
+        {
+        }
+    }
+
+    private void Apply(string fullPath)
+    {
+        var path = ToWorkspacePath(fullPath);
+        if (Directory.Exists(fullPath))
+        {
+            return;
+        }
+
+        if (!File.Exists(fullPath))
+        {
+            // A deleted directory reports only itself.
+            var removed = _workspace.Documents.Select(d => d.Path).Where(p => p == path || p.StartsWith(path + "/", StringComparison.Ordinal)).ToList();
+            foreach (var document in removed)
+            {
+                _workspace.RemoveDocument(document);
+            }
+
+            return;
+        }
+
+        string text;
+        try
+        {
+            text = File.ReadAllText(fullPath);
+        }
+        catch (IOException)
+        {
+            // The file is still being written; the writer's next notification brings it back.
+            _changes.Writer.TryWrite(fullPath);
+            return;
+        }
+
+        if (_workspace.GetDocument(path)?.Parse.Input != text)
+        {
+            _workspace.SetDocument(path, text);
+        }
+    }
+
+    private void Publish()
+    {
+        var documents = _workspace.Documents.ToDictionary(d => d.Path, StringComparer.Ordinal);
+        _snapshot = new WorkspaceSnapshot(_snapshot.Generation + 1, documents, documents.ToDictionary(d => d.Key, d => d.Value.Diagnostics, StringComparer.Ordinal));
+    }
+
+    private string ToWorkspacePath(string fullPath)
+    {
+        return Path.GetRelativePath(_root, fullPath).Replace(Path.DirectorySeparatorChar, '/');
+    }
+
+    private async Task ServeAsync(Socket connection, CancellationToken token)
+    {
+        using var stream = new NetworkStream(connection, ownsSocket: true);
+        using var reader = new StreamReader(stream, new UTF8Encoding(false));
+        var writeLock = new SemaphoreSlim(1, 1);
+        var pending = new List<Task>();
+
+        try
+        {
+            while (await reader.ReadLineAsync(token) is { } line)
+            {
+                if (line.Length > 0)
+                {
+                    pending.Add(Task.Run(() => HandleAsync(line, stream, writeLock, token), token));
+                }
+            }
+        }
+        catch (Exception ex) when (ex is OperationCanceledException or IOException)
+        {
+        }
+
+        try
+        {
+            await Task.WhenAll(pending);
+        }
+        catch (Exception ex) when (ex is OperationCanceledException or IOException)
+        {
+        }
+    }
+
+    private async Task HandleAsync(string line, Stream stream, SemaphoreSlim writeLock, CancellationToken token)
+    {
+        JsonElement? id = null;
+        byte[] response;
+        var respond = true;
+        var shutdown = false;
+        try
+        {
+            using var request = JsonDocument.Parse(line);
+            var root = request.RootElement;
+            if (root.ValueKind == JsonValueKind.Object && root.TryGetProperty("id", out var requestId))
+            {
+                id = requestId.Clone();
+            }
+
+            if (root.ValueKind != JsonValueKind.Object || !root.TryGetProperty("method", out var method) || method.ValueKind != JsonValueKind.String)
+            {
+                response = Error(id, InvalidRequest, "A request must be an object with a method");
+            }
+            else
+            {
+                var parameters = root.TryGetProperty("params", out var p) ? p : default;
+                shutdown = method.GetString() == "shutdown";
+                respond = id != null;
+                response = Respond(id, method.GetString()!, parameters);
+            }
+        }
+        catch (JsonException ex)
+        {
+            response = Error(null, ParseError, ex.Message);
+        }
+
+        // Notifications get no response.
+        if (respond)
+        {
+            await writeLock.WaitAsync(token);
+            try
+            {
+                await stream.WriteAsync(response, token);
+                await stream.FlushAsync(token);
+            }
+            finally
+            {
+                writeLock.Release();
+            }
+        }
+
+        if (shutdown)
+        {
+            _shutdown.Cancel();
+        }
+    }
+
+    private byte[] Respond(JsonElement? id, string method, JsonElement parameters)
+    {
+        var snapshot = _snapshot;
+        try
+        {
+            return method switch
+            {
+                "parseFile" => Result(id, writer => WriteParse(writer, snapshot, parameters)),
+                "query" => Result(id, writer => WriteQuery(writer, snapshot, parameters)),
+                "diagnostics" => Result(id, writer => WriteDiagnostics(writer, snapshot, parameters)),
+                "symbols" => Result(id, writer => WriteSymbols(writer, snapshot, parameters)),
+                "workspaceSymbol" => Result(id, writer => WriteSymbolMatches(writer, parameters)),
+                "documentHighlight" => Result(id, writer => WriteHighlights(writer, snapshot, parameters)),
+                "hover" => Result(id, writer => WriteHover(writer, snapshot, parameters)),
+                "inlayHint" => Result(id, writer => WriteInlayHints(writer, snapshot, parameters)),
+                "onTypeFormatting" => Result(id, writer => WriteTypingEdits(writer, snapshot, parameters)),
+                "didOpen" => Result(id, writer => WriteChange(writer, OpenDocument(parameters))),
+                "didChange" => Result(id, writer => WriteChange(writer, ChangeDocument(parameters))),
+                "resync" => Result(id, writer => WriteChange(writer, _documents.Resync(
+                    GetString(parameters, "path", required: true)!,
+                    GetInt32(parameters, "version"),
+                    GetString(parameters, "text", required: true)!))),
+                "didClose" => Result(id, writer => writer.WriteBooleanValue(CloseDocument(parameters))),
+                "shutdown" => Result(id, writer => writer.WriteNullValue()),
+                _ => Error(id, MethodNotFound, $"Unknown method '{method}'")
+            };
+        }
+        catch (Exception ex) when (ex is ArgumentException or FormatException or InvalidOperationException or IOException)
+        {
+            return Error(id, InvalidParams, ex.Message);
+        }
+        catch (Exception ex)
+        {
+            return Error(id, InternalError, ex.Message);
+        }
+    }
+
+    private void WriteParse(Utf8JsonWriter writer, WorkspaceSnapshot snapshot, JsonElement parameters)
+    {
+        var path = GetString(parameters, "path", required: true)!;
+        var text = GetString(parameters, "text", required: false);
+
+        // Open documents come from the document store and saved files from the warm workspace; unsaved text is parsed
+        // on the side.
+        var result = text == null && _documents.GetParse(path) is { } open
+            ? open
+            : text == null && snapshot.Documents.TryGetValue(path, out var document)
+            ? document.Parse
+            : _parser.Parse(text ?? File.ReadAllText(Path.Combine(_root, path)), new ParseOptions { SourceFile = path, Watchdog = _options.Watchdog, Interner = _interner });
+
+        writer.WriteStartObject();
+        writer.WriteString("path", path);
+        writer.WriteBoolean("success", result.IsSuccess);
+        writer.WriteNumber("tokens", result.Tokens.Count);
+        writer.WritePropertyName("diagnostics");
+        WriteDiagnosticList(writer, result.Diagnostics);
+        if (result.IsSuccess && parameters.ValueKind == JsonValueKind.Object && parameters.TryGetProperty("tree", out var tree) && tree.ValueKind == JsonValueKind.True)
+        {
+            using var export = JsonDocument.Parse(ParseTreeExport.ToJson(result));
+            writer.WritePropertyName("tree");
+            export.RootElement.GetProperty("tree").WriteTo(writer);
+        }
+
+        writer.WriteEndObject();
+    }
+
+    private static void WriteQuery(Utf8JsonWriter writer, WorkspaceSnapshot snapshot, JsonElement parameters)
+    {
+        var document = GetDocument(snapshot, GetString(parameters, "path", required: true)!);
+        var query = TreeQuery.Parse(GetString(parameters, "selector", required: true)!);
+
+        writer.WriteStartArray();
+        if (document.Parse.Tree is { } tree)
+        {
+            foreach (var node in query.Select(tree))
+            {
+                writer.WriteStartObject();
+                if (node is TerminalNode terminal)
+                {
+                    writer.WriteString("token", terminal.TokenType);
+                    writer.WriteString("text", terminal.Text);
+                }
+                else
+                {
+                    writer.WriteString("rule", ((NonTerminalNode)node).RuleName);
+                    if (node.SourcePosition is { } span)
+                    {
+                        writer.WriteString("text", document.Parse.Input.Substring(span.Offset, span.Length));
+                    }
+                }
+
+                ParseTreeExport.WriteJsonSpan(writer, node.SourcePosition);
+                writer.WriteEndObject();
+            }
+        }
+
+        writer.WriteEndArray();
+    }
+
+    private static void WriteDiagnostics(Utf8JsonWriter writer, WorkspaceSnapshot snapshot, JsonElement parameters)
+    {
+        var path = GetString(parameters, "path", required: false);
+
+        writer.WriteStartObject();
+        writer.WriteNumber("generation", snapshot.Generation);
+        writer.WriteStartArray("files");
+        foreach (var (file, diagnostics) in snapshot.Diagnostics.OrderBy(d => d.Key, StringComparer.Ordinal))
+        {
+            if (path != null && file != path)
+            {
+                continue;
+            }
+
+            writer.WriteStartObject();
+            writer.WriteString("path", file);
+            writer.WritePropertyName("diagnostics");
+            WriteDiagnosticList(writer, diagnostics);
+            writer.WriteEndObject();
+        }
+
+        writer.WriteEndArray();
+        writer.WriteEndObject();
+    }
+
+    private static void WriteSymbols(Utf8JsonWriter writer, WorkspaceSnapshot snapshot, JsonElement parameters)
+    {
+        var path = GetString(parameters, "path", required: false);
+        var name = GetString(parameters, "name", required: false);
+        if ((path == null) == (name == null))
+        {
+            throw new ArgumentException("Either a path or a symbol name is required");
+        }
+
+        var occurrences = path != null
+            ? GetDocument(snapshot, path).Symbols.Occurrences
+                .Where(o => o.Kind == SymbolOccurrenceKind.Declaration)
+                .Select(o => (Path: path, Occurrence: o))
+            : snapshot.Documents.Values
+                .OrderBy(d => d.Path, StringComparer.Ordinal)
+                .SelectMany(d => d.Symbols.Exports.Where(o => o.Name == name).Select(o => (d.Path, Occurrence: o)));
+
+        writer.WriteStartArray();
+        foreach (var (file, occurrence) in occurrences)
+        {
+            writer.WriteStartObject();
+            writer.WriteString("name", occurrence.Name);
+            writer.WriteString("path", file);
+            writer.WriteString("rule", occurrence.Rule);
+            writer.WriteBoolean("exported", occurrence.IsExported);
+            ParseTreeExport.WriteJsonSpan(writer, occurrence.Location);
+            writer.WriteEndObject();
+        }
+
+        writer.WriteEndArray();
+    }
+
+    private void WriteSymbolMatches(Utf8JsonWriter writer, JsonElement parameters)
+    {
+        // The index follows the workspace, which may be ahead of the snapshot.
+        var query = GetString(parameters, "query", required: true)!;
+        var limit = parameters.TryGetProperty("limit", out _) ? GetInt32(parameters, "limit") : 100;
+
+        writer.WriteStartArray();
+        foreach (var match in _workspace.SymbolIndex.Search(query, limit))
+        {
+            var symbol = match.Symbol;
+            writer.WriteStartObject();
+            writer.WriteString("name", symbol.Name);
+            writer.WriteString("kind", symbol.Kind);
+            if (symbol.Container != null)
+            {
+                writer.WriteString("container", symbol.Container);
+            }
+
+            writer.WriteString("path", symbol.Path);
+            writer.WriteBoolean("exported", symbol.IsExported);
+            writer.WriteString("match", JsonNamingPolicy.CamelCase.ConvertName(match.Match.ToString()));
+            ParseTreeExport.WriteJsonSpan(writer, symbol.Location);
+            writer.WriteEndObject();
+        }
+
+        writer.WriteEndArray();
+    }
+
+    private static void WriteHighlights(Utf8JsonWriter writer, WorkspaceSnapshot snapshot, JsonElement parameters)
+    {
+        var document = GetDocument(snapshot, GetString(parameters, "path", required: true)!);
+        var lines = new LineIndex(document.Parse.Input);
+        var offset = lines.GetLineStart(GetInt32(parameters, "line")) + GetInt32(parameters, "column") - 1;
+
+        writer.WriteStartArray();
+        foreach (var highlight in DocumentHighlightProvider.GetHighlights(document.Parse, offset, document.Symbols))
+        {
+            writer.WriteStartObject();
+            writer.WriteString("kind", highlight.Kind.ToString().ToLowerInvariant());
+            ParseTreeExport.WriteJsonSpan(writer, highlight.Location);
+            writer.WriteEndObject();
+        }
+
+        writer.WriteEndArray();
+    }
+
+    private static void WriteHover(Utf8JsonWriter writer, WorkspaceSnapshot snapshot, JsonElement parameters)
+    {
+        var document = GetDocument(snapshot, GetString(parameters, "path", required: true)!);
+        var lines = new LineIndex(document.Parse.Input);
+        var offset = lines.GetLineStart(GetInt32(parameters, "line")) + GetInt32(parameters, "column") - 1;
+
+        var hover = HoverProvider.GetHover(document.Parse, offset);
+        if (hover == null)
+        {
+            writer.WriteNullValue();
+            return;
+        }
+
+        writer.WriteStartObject();
+        writer.WriteString("contents", hover.Contents);
+        ParseTreeExport.WriteJsonSpan(writer, hover.Location);
+        writer.WriteEndObject();
+    }
+
+    private void WriteInlayHints(Utf8JsonWriter writer, WorkspaceSnapshot snapshot, JsonElement parameters)
+    {
+        var document = GetDocument(snapshot, GetString(parameters, "path", required: true)!);
+        TextRange? range = null;
+        if (parameters.TryGetProperty("range", out var span) && span.ValueKind == JsonValueKind.Object)
+        {
+            var lines = new LineIndex(document.Parse.Input);
+            var start = ReadPosition(span, "start");
+            var end = ReadPosition(span, "end");
+            var startOffset = lines.GetLineStart(start.Line + 1) + start.Character;
+            range = new TextRange(startOffset, lines.GetLineStart(end.Line + 1) + end.Character - startOffset);
+        }
+
+        writer.WriteStartArray();
+        foreach (var hint in InlayHintProvider.GetHints(document.Run.Context, _workspace.InlayHintProducers, range))
+        {
+            writer.WriteStartObject();
+            writer.WriteString("label", hint.Label);
+            writer.WriteString("kind", hint.Kind.ToString().ToLowerInvariant());
+            if (hint.Tooltip != null)
+            {
+                writer.WriteString("tooltip", hint.Tooltip);
+            }
+
+            ParseTreeExport.WriteJsonSpan(writer, hint.Position);
+            writer.WriteEndObject();
+        }
+
+        writer.WriteEndArray();
+    }
+
+    private void WriteTypingEdits(Utf8JsonWriter writer, WorkspaceSnapshot snapshot, JsonElement parameters)
+    {
+        // The delimiter was typed into an open document, which is ahead of the saved file.
+        var path = GetString(parameters, "path", required: true)!;
+        var text = _documents.GetCurrent(path) is { } open ? open.Text.ToString() : GetDocument(snapshot, path).Parse.Input;
+        var line = GetInt32(parameters, "line");
+        var column = GetInt32(parameters, "column");
+        var offset = new LineIndex(text).GetLineStart(line) + column - 1;
+
+        writer.WriteStartArray();
+        if (_parser.Grammar.EditorInfo.GetClosingText(text, offset) is { } close)
+        {
+            writer.WriteStartObject();
+            writer.WriteString("newText", close);
+            writer.WriteNumber("line", line);
+            writer.WriteNumber("column", column);
+            writer.WriteEndObject();
+        }
+
+        writer.WriteEndArray();
+    }
+
+    private DocumentChangeResult OpenDocument(JsonElement parameters)
+    {
+        var version = _documents.Open(GetString(parameters, "path", required: true)!, GetInt32(parameters, "version"), GetString(parameters, "text", required: true)!);
+        return new DocumentChangeResult(DocumentChangeStatus.Applied, version.Version);
+    }
+
+    private bool CloseDocument(JsonElement parameters)
+    {
+        var closed = _documents.Close(GetString(parameters, "path", required: true)!);
+        CollectIfDue();
+        return closed;
+    }
+
+    private void CollectIfDue()
+    {
+        var interned = _interner.GetMemoryReport().Get(MemoryCategories.InternedStrings)!.Count;
+        if (_options.CollectionThreshold > 0 && interned >= Volatile.Read(ref _nextCollection))
+        {
+            CollectGarbage();
+        }
+    }
+
+    private DocumentChangeResult ChangeDocument(JsonElement parameters)
+    {
+        if (!parameters.TryGetProperty("changes", out var changes) || changes.ValueKind != JsonValueKind.Array)
+        {
+            throw new ArgumentException("The 'changes' parameter is required");
+        }
+
+        var batch = changes.EnumerateArray()
+            .Select(change => change.TryGetProperty("range", out var range) && range.ValueKind == JsonValueKind.Object
+                ? DocumentChange.Replace(ReadPosition(range, "start"), ReadPosition(range, "end"), GetString(change, "text", required: true)!)
+                : DocumentChange.FullText(GetString(change, "text", required: true)!))
+            .ToList();
+        return _documents.Change(GetString(parameters, "path", required: true)!, GetInt32(parameters, "version"), batch);
+    }
+
+    private static DocumentPosition ReadPosition(JsonElement range, string name)
+    {
+        return range.TryGetProperty(name, out var position)
+            ? new DocumentPosition(GetInt32(position, "line"), GetInt32(position, "character"))
+            : throw new ArgumentException($"A change range needs a '{name}' position");
+    }
+
+    private static void WriteChange(Utf8JsonWriter writer, DocumentChangeResult result)
+    {
+        writer.WriteStartObject();
+        writer.WriteString("status", JsonNamingPolicy.CamelCase.ConvertName(result.Status.ToString()));
+        writer.WriteNumber("version", result.Version);
+        if (result.Reason != null)
+        {
+            writer.WriteString("reason", result.Reason);
+        }
+
+        writer.WriteEndObject();
+    }
+
+    private static void WriteDiagnosticList(Utf8JsonWriter writer, IReadOnlyList<Diagnostic> diagnostics)
+    {
+        writer.WriteStartArray();
+        foreach (var diagnostic in diagnostics)
+        {
+            writer.WriteStartObject();
+            writer.WriteString("code", diagnostic.Code);
+            writer.WriteString("severity", diagnostic.Severity.ToString());
+            writer.WriteString("message", diagnostic.Message);
+            ParseTreeExport.WriteJsonSpan(writer, diagnostic.Location);
+            if (DiagnosticCatalog.Default.Get(diagnostic.Code) is { } descriptor)
+            {
+                writer.WriteStartObject("documentation");
+                writer.WriteString("explanation", descriptor.Explanation);
+                writer.WriteString("command", $"minotaur explain {descriptor.Code}");
+                writer.WriteEndObject();
+            }
+
+            writer.WriteEndObject();
+        }
+
+        writer.WriteEndArray();
+    }
+
+    private static WorkspaceDocument GetDocument(WorkspaceSnapshot snapshot, string path)
+    {
+        return snapshot.Documents.TryGetValue(path, out var document)
+            ? document
+            : throw new ArgumentException($"'{path}' is not in the workspace");
+    }
+
+    private static string? GetString(JsonElement parameters, string name, bool required)
+    {
+        if (parameters.ValueKind == JsonValueKind.Object && parameters.TryGetProperty(name, out var value) && value.ValueKind == JsonValueKind.String)
+        {
+            return value.GetString();
+        }
+
+        return required ? throw new ArgumentException($"The '{name}' parameter is required") : null;
+    }
+
+    private static int GetInt32(JsonElement parameters, string name)
+    {
+        return parameters.ValueKind == JsonValueKind.Object && parameters.TryGetProperty(name, out var value) && value.TryGetInt32(out var number)
+            ? number
+            : throw new ArgumentException($"The '{name}' parameter is required");
+    }
+
+    private static byte[] Result(JsonElement? id, Action<Utf8JsonWriter> writeResult)
+    {
+        return Message(id, writer =>
+        {
+            writer.WritePropertyName("result");
+            writeResult(writer);
+        });
+    }
+
+    private static byte[] Error(JsonElement? id, int code, string message)
+    {
+        return Message(id, writer =>
+        {
+            writer.WriteStartObject("error");
+            writer.WriteNumber("code", code);
+            writer.WriteString("message", message);
+            writer.WriteEndObject();
+        });
+    }
+
+    private static byte[] Message(JsonElement? id, Action<Utf8JsonWriter> writeBody)
+    {
+        using var stream = new MemoryStream();
+        using (var writer = new Utf8JsonWriter(stream))
+        {
+            writer.WriteStartObject();
+            writer.WriteString("jsonrpc", "2.0");
+            writer.WritePropertyName("id");
+            if (id is { } value)
+            {
+                value.WriteTo(writer);
+            }
+            else
+            {
+                writer.WriteNullValue();
+            }
+
+            writeBody(writer);
+            writer.WriteEndObject();
+        }
+
+        stream.WriteByte((byte)'\n');
+        return stream.ToArray();
+    }
+
+    private sealed record WorkspaceSnapshot(
+        long Generation,
+        IReadOnlyDictionary<string, WorkspaceDocument> Documents,
+        IReadOnlyDictionary<string, IReadOnlyList<Diagnostic>> Diagnostics);
+}
@@ -0,0 +1,117 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Testing;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// A run of lines, 1-based. An empty range marks a deletion just before its start line.
+/// </summary>
+/// <param name="Start">The first line.</param>
+/// <param name="Count">The number of lines.</param>
+public readonly record struct LineRange(int Start, int Count);
+
+/// <summary>
+/// The lines of a file's new version that a change added or replaced.
+/// </summary>
+public sealed class ChangedLines
+{
+    /// <summary>
+    /// Initializes a new instance of the ChangedLines class.
+    /// </summary>
+    /// <param name="ranges">The changed ranges, in file order.</param>
+    public ChangedLines(IReadOnlyList<LineRange> ranges)
+    {
+        ArgumentNullException.ThrowIfNull(ranges);
+
+        Ranges = ranges;
+    }
+
+    /// <summary>
+    /// Gets the changed ranges, in file order.
+    /// </summary>
+    public IReadOnlyList<LineRange> Ranges { get; }
+
+    /// <summary>
+    /// Compares two versions of a file line by line.
+    /// </summary>
+    /// <param name="oldText">The old version.</param>
+    /// <param name="newText">The new version.</param>
+    /// <returns>The lines of the new version that are not in the old one.</returns>
+    public static ChangedLines Between(string oldText, string newText)
+    {
+        ArgumentNullException.ThrowIfNull(oldText);
+        ArgumentNullException.ThrowIfNull(newText);
+
+        var a = Snapshot.SplitLines(oldText);
+        var b = Snapshot.SplitLines(newText);
+
+        // Edits are usually small next to the file, so only the lines between the common ends are compared.
+        var prefix = 0;
+        while (prefix < a.Length && prefix < b.Length && a[prefix] == b[prefix])
+        {
+            prefix++;
+        }
+
+        var suffix = 0;
+        while (suffix < a.Length - prefix && suffix < b.Length - prefix && a[^(suffix + 1)] == b[^(suffix + 1)])
+        {
+            suffix++;
+        }
+
+        var edits = Snapshot.LineEdits(a[prefix..^suffix], b[prefix..^suffix]);
+        var ranges = new List<LineRange>();
+        var i = 0;
+        while (i < edits.Count)
+        {
+            if (edits[i].Op == ' ')
+            {
+                i++;
+                continue;
+            }
+
+            var start = edits[i].NewLine;
+            var added = 0;
+            for (; i < edits.Count && edits[i].Op != ' '; i++)
+            {
+                if (edits[i].Op == '+')
+                {
+                    added++;
+                }
+            }
+
+            ranges.Add(new LineRange(prefix + start + 1, added));
+        }
+
+        return new ChangedLines(ranges);
+    }
+
+    /// <summary>
+    /// Determines whether a span touches a changed line or comes within some lines of one.
+    /// </summary>
+    /// <param name="position">The span.</param>
+    /// <param name="contextLines">How many lines around each change count as changed.</param>
+    /// <returns>True if any line of the span is changed.</returns>
+    public bool Touches(SourcePosition position, int contextLines = 0)
+    {
+        ArgumentNullException.ThrowIfNull(position);
+
+        return Ranges.Any(r => position.Line < r.Start + r.Count + contextLines && Math.Max(position.EndLine, position.Line) >= r.Start - contextLines);
+    }
+}
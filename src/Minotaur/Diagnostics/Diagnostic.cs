@@ -0,0 +1,346 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// A diagnostic produced while lexing, parsing or analyzing source code.
+/// </summary>
+public class Diagnostic
+{
+    /// <summary>
+    /// Gets or sets the diagnostic code (e.g., "E0001").
+    /// </summary>
+    public string Code { get; set; } = string.Empty;
+
+    /// <summary>
+    /// Gets or sets the severity of the diagnostic.
+    /// </summary>
+    public DiagnosticSeverity Severity { get; set; } = DiagnosticSeverity.Error;
+
+    /// <summary>
+    /// Gets or sets the human-readable message.
+    /// </summary>
+    public string Message { get; set; } = string.Empty;
+
+    /// <summary>
+    /// Gets or sets the key of the message in the <see cref="MessageCatalog"/>s, usually the code, or null when the
+    /// message is not localizable.
+    /// </summary>
+    public string? MessageKey { get; set; }
+
+    /// <summary>
+    /// Gets or sets the values of the message's placeholders, by name.
+    /// </summary>
+    public Dictionary<string, object> MessageArguments { get; set; } = new();
+
+    /// <summary>
+    /// Gets or sets the source location the diagnostic refers to.
+    /// </summary>
+    public SourcePosition? Location { get; set; }
+
+    /// <summary>
+    /// Gets or sets structured data attached to the diagnostic for tools (e.g., expected terminals).
+    /// </summary>
+    public Dictionary<string, object> Data { get; set; } = new();
+
+    /// <summary>
+    /// Sets the message from the built-in English catalog, keeping the key and arguments so a
+    /// <see cref="DiagnosticLocalizer"/> can render it in another locale.
+    /// </summary>
+    /// <param name="key">The message key, e.g. "W0002" or "E0001.character".</param>
+    /// <param name="arguments">The values of the message's placeholders.</param>
+    /// <returns>This diagnostic, for chaining.</returns>
+    /// <exception cref="KeyNotFoundException">The English catalog has no message for the key.</exception>
+    public Diagnostic WithMessage(string key, params (string Name, object Value)[] arguments)
+    {
+        return WithMessage(MessageCatalog.English, key, arguments);
+    }
+
+    /// <summary>
+    /// Sets the message from a catalog, usually a grammar's English one, keeping the key and arguments so a
+    /// <see cref="DiagnosticLocalizer"/> can render it in another locale.
+    /// </summary>
+    /// <param name="catalog">The catalog the message is formatted with.</param>
+    /// <param name="key">The message key.</param>
+    /// <param name="arguments">The values of the message's placeholders.</param>
+    /// <returns>This diagnostic, for chaining.</returns>
+    /// <exception cref="KeyNotFoundException">The catalog has no message for the key.</exception>
+    public Diagnostic WithMessage(MessageCatalog catalog, string key, params (string Name, object Value)[] arguments)
+    {
+        ArgumentNullException.ThrowIfNull(catalog);
+        ArgumentNullException.ThrowIfNull(key);
+
+        MessageKey = key;
+        MessageArguments = arguments.ToDictionary(a => a.Name, a => a.Value, StringComparer.Ordinal);
+        Message = catalog.Format(key, MessageArguments);
+        return this;
+    }
+
+    /// <summary>
+    /// Returns the diagnostic in "line:column: severity code: message" form.
+    /// </summary>
+    /// <returns>A single-line rendering of the diagnostic.</returns>
+    public override string ToString()
+    {
+        var severity = Severity.ToString().ToLowerInvariant();
+        return Location != null
+            ? $"{Location.Line}:{Location.Column}: {severity} {Code}: {Message}"
+            : $"{severity} {Code}: {Message}";
+    }
+}
+
+/// <summary>
+/// Severity levels for diagnostics, ordered from least to most severe.
+/// </summary>
+public enum DiagnosticSeverity
+{
+    /// <summary>
+    /// A hint for the user that does not indicate a problem.
+    /// </summary>
+    Hint,
+
+    /// <summary>
+    /// Informational message.
+    /// </summary>
+    Information,
+
+    /// <summary>
+    /// A potential problem that does not prevent processing.
+    /// </summary>
+    Warning,
+
+    /// <summary>
+    /// An error that prevents successful processing.
+    /// </summary>
+    Error
+}
+
+/// <summary>
+/// Diagnostic codes emitted by the built-in input decoder, lexer, parser, semantic actions and analysis passes.
+/// </summary>
+public static class DiagnosticCodes
+{
+    /// <summary>
+    /// The parser encountered a token that no alternative can accept.
+    /// </summary>
+    public const string UnexpectedToken = "E0001";
+
+    /// <summary>
+    /// The input ended before the start rule was complete.
+    /// </summary>
+    public const string UnexpectedEndOfInput = "E0002";
+
+    /// <summary>
+    /// The lexer found a character that no terminal matches.
+    /// </summary>
+    public const string UnrecognizedCharacter = "E0003";
+
+    /// <summary>
+    /// An analysis pass threw an exception; passes depending on it were skipped.
+    /// </summary>
+    public const string AnalysisPassFailed = "E0004";
+
+    /// <summary>
+    /// A semantic action threw an exception while evaluating a parse.
+    /// </summary>
+    public const string SemanticActionFailed = "E0005";
+
+    /// <summary>
+    /// A symbol is neither declared in its file nor exported by any imported file.
+    /// </summary>
+    public const string UnresolvedReference = "E0006";
+
+    /// <summary>
+    /// An imported module is not part of the analysis workspace, or its specifier does not resolve to a path.
+    /// </summary>
+    public const string UnresolvedImport = "E0007";
+
+    /// <summary>
+    /// The input contains bytes that are not valid in its encoding.
+    /// </summary>
+    public const string InvalidEncoding = "E0008";
+
+    /// <summary>
+    /// The parser consumed tokens too slowly and was stopped by the progress watchdog.
+    /// </summary>
+    public const string ParseStalled = "E0009";
+
+    /// <summary>
+    /// An operator expression could not be structured with the operator table in effect at its position.
+    /// </summary>
+    public const string UnresolvedOperator = "E0010";
+
+    /// <summary>
+    /// The source contains a bidirectional control character, which can make code display differently from
+    /// how it is parsed.
+    /// </summary>
+    public const string BidiControlCharacter = "E0011";
+
+    /// <summary>
+    /// An identifier appeared where only keywords are acceptable and was read as the keyword it is closest to.
+    /// The diagnostic's "fix" data holds the <see cref="Parser.TextEdit"/> that corrects the spelling.
+    /// </summary>
+    public const string MisspelledKeyword = "E0012";
+
+    /// <summary>
+    /// A closing delimiter differs from the one captured by its opening token, such as <c>&lt;/b&gt;</c> closing
+    /// <c>&lt;a&gt;</c>, or a heredoc is not terminated. The "related" data holds the opening location.
+    /// </summary>
+    public const string MismatchedDelimiter = "E0013";
+
+    /// <summary>
+    /// A grammar migration maps a rule to one that the newer grammar version does not define.
+    /// </summary>
+    public const string InvalidMigration = "E0014";
+
+    /// <summary>
+    /// The input uses syntax guarded by a grammar feature that is not enabled.
+    /// </summary>
+    public const string FeatureDisabled = "E0015";
+
+    /// <summary>
+    /// A file or run reached its error budget; the errors past it are counted in one diagnostic whose "suppressed"
+    /// data holds their number and "codes" data their most frequent codes.
+    /// </summary>
+    public const string TooManyErrors = "E0016";
+
+    /// <summary>
+    /// The start of a file has so many errors that it is probably written in another language than the grammar's.
+    /// </summary>
+    public const string WrongGrammar = "E0017";
+
+    /// <summary>
+    /// The <see cref="Parser.Expander"/> could not find the file an include directive names.
+    /// </summary>
+    public const string UnresolvedInclude = "E0018";
+
+    /// <summary>
+    /// A file includes itself, directly or through other files; the "backtrace" data holds the chain of includes.
+    /// </summary>
+    public const string RecursiveInclude = "E0019";
+
+    /// <summary>
+    /// Includes or macro expansions are nested deeper than the <see cref="Parser.SourceExpander"/> allows.
+    /// </summary>
+    public const string ExpansionTooDeep = "E0020";
+
+    /// <summary>
+    /// A bracket is not closed before its block outdents, and the <see cref="Parser.DelimiterRecoveryPolicy"/>
+    /// closed it there. The "related" data holds the opening location and the "region" data the recovered text.
+    /// </summary>
+    public const string UnclosedDelimiter = "E0021";
+
+    /// <summary>
+    /// A member of a container whose grammar rule is annotated with <c>// @unique_by</c> repeats an earlier member's
+    /// key. The "related" data holds the earlier key's location and the "fix" data removes the later member.
+    /// </summary>
+    public const string DuplicateKey = "E0022";
+
+    /// <summary>
+    /// A closing bracket does not match the innermost open bracket, and the <see cref="Parser.DelimiterRecoveryPolicy"/>
+    /// took it for the matching one. The "related" data holds the opening location and the "fix" data the replacement.
+    /// </summary>
+    public const string MismatchedDelimiter = "E0023";
+
+    /// <summary>
+    /// A closing bracket closes nothing the parser can accept, and the <see cref="Parser.DelimiterRecoveryPolicy"/>
+    /// dropped it. The "fix" data removes it.
+    /// </summary>
+    public const string UnexpectedDelimiter = "E0024";
+
+    /// <summary>
+    /// An escape sequence in a string token is malformed under the <see cref="Parser.EscapeScheme"/> its terminal
+    /// declares. The token is kept; the "escape" data holds the escape as written.
+    /// </summary>
+    public const string InvalidEscape = "E0025";
+
+    /// <summary>
+    /// The input has more than one derivation.
+    /// </summary>
+    public const string AmbiguousParse = "W0001";
+
+    /// <summary>
+    /// A symbol is declared but never referenced.
+    /// </summary>
+    public const string UnusedSymbol = "W0002";
+
+    /// <summary>
+    /// A symbol is referenced but never declared.
+    /// </summary>
+    public const string UndeclaredSymbol = "W0003";
+
+    /// <summary>
+    /// Bytes that are not valid in the input encoding were replaced with U+FFFD.
+    /// </summary>
+    public const string SubstitutedBytes = "W0004";
+
+    /// <summary>
+    /// Two identifiers are spelled with different code points but are equal after Unicode normalization.
+    /// </summary>
+    public const string UnnormalizedIdentifier = "W0005";
+
+    /// <summary>
+    /// Two different identifiers look alike.
+    /// </summary>
+    public const string ConfusableIdentifier = "W0006";
+
+    /// <summary>
+    /// A rule disappeared between grammar versions without a migration mapping, or a tree node's rule was removed
+    /// and has no counterpart in the newer version.
+    /// </summary>
+    public const string UnmappedRule = "W0007";
+
+    /// <summary>
+    /// A tree node's rule was split between grammar versions and no condition or more than one chose its new rule.
+    /// </summary>
+    public const string AmbiguousMigration = "W0008";
+
+    /// <summary>
+    /// A comment in a declared directive syntax could not be read as a directive.
+    /// </summary>
+    public const string MalformedDirective = "W0009";
+
+    /// <summary>
+    /// A region directive has no partner: an end without a begin, or a begin that is never ended.
+    /// </summary>
+    public const string UnmatchedDirective = "W0010";
+
+    /// <summary>
+    /// The input uses a rule or alternative that the grammar marks as deprecated.
+    /// </summary>
+    public const string DeprecatedSyntax = "W0011";
+
+    /// <summary>
+    /// A bracket in a file read without a grammar has no partner: a closing bracket that closes nothing, or an
+    /// opening bracket that is never closed.
+    /// </summary>
+    public const string UnbalancedBracket = "W0012";
+
+    /// <summary>
+    /// Files of an analysis workspace import each other in a cycle.
+    /// </summary>
+    public const string ImportCycle = "W0013";
+
+    /// <summary>
+    /// An escape sequence in a string token is accepted by the <see cref="Parser.EscapeScheme"/> its terminal
+    /// declares but does not stand for a character, like a lone surrogate in JSON, or is kept as written.
+    /// </summary>
+    public const string SuspiciousEscape = "W0014";
+}
@@ -0,0 +1,378 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Buffers.Binary;
+using System.Security.Cryptography;
+using System.Text;
+using Minotaur.Core;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// The diagnostics of one code in an aggregated run.
+/// </summary>
+/// <param name="Code">The diagnostic code.</param>
+/// <param name="Total">The number of diagnostics with the code.</param>
+/// <param name="Reported">The number reported individually; the rest are collapsed.</param>
+/// <param name="Files">The number of files with the code.</param>
+public sealed record DiagnosticCodeTotal(string Code, int Total, int Reported, int Files)
+{
+    /// <summary>
+    /// Gets the number of diagnostics collapsed into the code's aggregate entry.
+    /// </summary>
+    public int Collapsed => Total - Reported;
+}
+
+/// <summary>
+/// The outcome of <see cref="DiagnosticAggregator.Complete"/>.
+/// </summary>
+public sealed class DiagnosticAggregation
+{
+    internal DiagnosticAggregation(IReadOnlyList<Diagnostic> reported, IReadOnlyList<Diagnostic>? all, IReadOnlyList<DiagnosticCodeTotal> totals)
+    {
+        Reported = reported;
+        All = all;
+        Totals = totals;
+    }
+
+    /// <summary>
+    /// Gets the diagnostics to show, ordered by file and position, followed by one aggregate entry per code
+    /// whose diagnostics were collapsed.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Reported { get; }
+
+    /// <summary>
+    /// Gets every diagnostic, ordered by file and position, if the aggregator was asked to keep them.
+    /// </summary>
+    public IReadOnlyList<Diagnostic>? All { get; }
+
+    /// <summary>
+    /// Gets the totals per code, ordered by code.
+    /// </summary>
+    public IReadOnlyList<DiagnosticCodeTotal> Totals { get; }
+}
+
+/// <summary>
+/// Collapses diagnostics repeated across a run, like a lint violated everywhere, into one aggregate entry per code.
+/// The first <see cref="DiagnosticLimits.MaxPerCodePerFile"/> diagnostics of a code in each file are reported, up
+/// to <see cref="DiagnosticLimits.MaxPerCodePerRun"/> in the run, taking files in path order; the rest are counted
+/// in an aggregate entry with the code's severity, whose "count" data holds their number and "locations" data a
+/// sample of their positions.
+/// </summary>
+/// <remarks>
+/// The outcome depends only on the diagnostics, not on the order files are added in or on how they were split
+/// between aggregators later merged, so parallel runs print the same as sequential ones. The sample is the
+/// collapsed diagnostics with the smallest hashes of their file, position and message. Memory is bounded by the
+/// limits rather than the number of diagnostics unless every diagnostic is kept.
+/// </remarks>
+public sealed class DiagnosticAggregator
+{
+    private readonly object _lock = new();
+    private readonly Dictionary<string, CodeState> _codes = new(StringComparer.Ordinal);
+    private readonly List<Entry>? _all;
+
+    /// <summary>
+    /// Initializes a new instance of the DiagnosticAggregator class.
+    /// </summary>
+    /// <param name="limits">The limits, or null for the defaults.</param>
+    /// <param name="keepAll">Whether to keep every diagnostic for <see cref="DiagnosticAggregation.All"/>.</param>
+    public DiagnosticAggregator(DiagnosticLimits? limits = null, bool keepAll = false)
+    {
+        Limits = limits ?? new DiagnosticLimits();
+        _all = keepAll ? new List<Entry>() : null;
+    }
+
+    /// <summary>
+    /// Gets the limits.
+    /// </summary>
+    public DiagnosticLimits Limits { get; }
+
+    /// <summary>
+    /// Adds the diagnostics of a file. Each file is added once; this may be called from any thread.
+    /// </summary>
+    /// <param name="file">The file's path.</param>
+    /// <param name="diagnostics">The file's diagnostics.</param>
+    public void Add(string file, IEnumerable<Diagnostic> diagnostics)
+    {
+        ArgumentNullException.ThrowIfNull(file);
+        ArgumentNullException.ThrowIfNull(diagnostics);
+
+        var entries = diagnostics.Select(d => new Entry(file, d)).OrderBy(e => e, EntryComparer.Instance).ToList();
+        lock (_lock)
+        {
+            _all?.AddRange(entries);
+            foreach (var group in entries.GroupBy(e => e.Diagnostic.Code, StringComparer.Ordinal))
+            {
+                var state = GetState(group.Key, group.First().Diagnostic.Severity);
+                state.Total += group.Count();
+                state.Files++;
+
+                var index = 0;
+                foreach (var entry in group)
+                {
+                    if (Limits.MaxPerCodePerFile > 0 && index++ >= Limits.MaxPerCodePerFile)
+                    {
+                        state.Sample(entry, Limits.SampledLocations);
+                    }
+                    else
+                    {
+                        state.Keep(entry, Limits.MaxPerCodePerRun, Limits.SampledLocations);
+                    }
+                }
+            }
+        }
+    }
+
+    /// <summary>
+    /// Adds the diagnostics of another aggregator with the same limits, such as one a worker thread filled with
+    /// files of its own.
+    /// </summary>
+    /// <param name="other">The other aggregator, which is no longer changing.</param>
+    public void Merge(DiagnosticAggregator other)
+    {
+        ArgumentNullException.ThrowIfNull(other);
+
+        lock (_lock)
+        {
+            if (_all != null && other._all != null)
+            {
+                _all.AddRange(other._all);
+            }
+
+            foreach (var (code, theirs) in other._codes)
+            {
+                var state = GetState(code, theirs.Severity);
+                state.Total += theirs.Total;
+                state.Files += theirs.Files;
+                foreach (var entry in theirs.Kept)
+                {
+                    state.Keep(entry, Limits.MaxPerCodePerRun, Limits.SampledLocations);
+                }
+
+                foreach (var entry in theirs.Sampled)
+                {
+                    state.Sample(entry, Limits.SampledLocations);
+                }
+            }
+        }
+    }
+
+    /// <summary>
+    /// Checks items in parallel, each worker adding the files it checks to an aggregator of its own that is merged
+    /// into this one when the worker finishes.
+    /// </summary>
+    /// <typeparam name="T">The type of the items.</typeparam>
+    /// <param name="items">The items, such as file paths.</param>
+    /// <param name="check">Checks an item, returning its file's path and diagnostics.</param>
+    /// <param name="maxDegreeOfParallelism">The largest number of items checked at once.</param>
+    public void AddParallel<T>(IEnumerable<T> items, Func<T, (string File, IEnumerable<Diagnostic> Diagnostics)> check, int maxDegreeOfParallelism)
+    {
+        ArgumentNullException.ThrowIfNull(items);
+        ArgumentNullException.ThrowIfNull(check);
+
+        Parallel.ForEach(
+            items,
+            new ParallelOptions { MaxDegreeOfParallelism = Math.Max(1, maxDegreeOfParallelism) },
+            () => new DiagnosticAggregator(Limits, _all != null),
+            (item, _, local) =>
+            {
+                var (file, diagnostics) = check(item);
+                local.Add(file, diagnostics);
+                return local;
+            },
+            Merge);
+    }
+
+    /// <summary>
+    /// Completes the aggregation.
+    /// </summary>
+    /// <returns>The diagnostics to report, the totals per code and, if kept, every diagnostic.</returns>
+    public DiagnosticAggregation Complete()
+    {
+        lock (_lock)
+        {
+            var aggregates = new List<Diagnostic>();
+            var totals = new List<DiagnosticCodeTotal>();
+            foreach (var (code, state) in _codes.OrderBy(c => c.Key, StringComparer.Ordinal))
+            {
+                state.Trim(Limits.MaxPerCodePerRun, Limits.SampledLocations);
+                totals.Add(new DiagnosticCodeTotal(code, state.Total, state.Kept.Count, state.Files));
+                if (state.Total > state.Kept.Count)
+                {
+                    aggregates.Add(CreateAggregate(code, state));
+                }
+            }
+
+            var reported = _codes.Values
+                .SelectMany(s => s.Kept)
+                .OrderBy(e => e, EntryComparer.Instance)
+                .Select(e => e.Diagnostic)
+                .Concat(aggregates)
+                .ToList();
+            var all = _all?.OrderBy(e => e, EntryComparer.Instance).Select(e => e.Diagnostic).ToList();
+            return new DiagnosticAggregation(reported, all, totals);
+        }
+    }
+
+    private CodeState GetState(string code, DiagnosticSeverity severity)
+    {
+        if (!_codes.TryGetValue(code, out var state))
+        {
+            state = new CodeState(severity);
+            _codes[code] = state;
+        }
+
+        return state;
+    }
+
+    private static Diagnostic CreateAggregate(string code, CodeState state)
+    {
+        var collapsed = state.Total - state.Kept.Count;
+        var locations = state.Sampled
+            .OrderBy(e => e, EntryComparer.Instance)
+            .Select(e => e.Diagnostic.Location is { } location ? location with { SourceFile = e.File } : null)
+            .OfType<SourcePosition>()
+            .ToList();
+        var examples = locations.Select(l => $"{l.SourceFile}:{l.Line}:{l.Column}").ToList();
+
+        return new Diagnostic
+        {
+            Code = code,
+            Severity = state.Severity,
+            Location = locations.FirstOrDefault(),
+            Data =
+            {
+                ["aggregate"] = true,
+                ["count"] = collapsed,
+                ["locations"] = locations
+            }
+        }.WithMessage("aggregate",
+            ("count", collapsed),
+            ("code", code),
+            ("total", state.Total),
+            ("files", state.Files),
+            ("examples", examples));
+    }
+
+    private sealed class CodeState
+    {
+        public CodeState(DiagnosticSeverity severity)
+        {
+            Severity = severity;
+        }
+
+        public DiagnosticSeverity Severity { get; }
+
+        public int Total { get; set; }
+
+        public int Files { get; set; }
+
+        public List<Entry> Kept { get; } = new();
+
+        public List<Entry> Sampled { get; } = new();
+
+        // Keeps the entries first in file order; the others are offered to the sample as they are pushed out.
+        public void Keep(Entry entry, int maxKept, int maxSampled)
+        {
+            Kept.Add(entry);
+            if (maxKept > 0 && Kept.Count > 2 * maxKept)
+            {
+                Trim(maxKept, maxSampled);
+            }
+        }
+
+        public void Sample(Entry entry, int maxSampled)
+        {
+            Sampled.Add(entry);
+            if (Sampled.Count > 2 * Math.Max(1, maxSampled))
+            {
+                TrimSample(maxSampled);
+            }
+        }
+
+        public void Trim(int maxKept, int maxSampled)
+        {
+            if (maxKept > 0 && Kept.Count > maxKept)
+            {
+                Kept.Sort(EntryComparer.Instance);
+                Sampled.AddRange(Kept.Skip(maxKept));
+                Kept.RemoveRange(maxKept, Kept.Count - maxKept);
+            }
+
+            TrimSample(maxSampled);
+        }
+
+        private void TrimSample(int maxSampled)
+        {
+            Sampled.Sort((x, y) => x.Hash != y.Hash ? x.Hash.CompareTo(y.Hash) : EntryComparer.Instance.Compare(x, y));
+            if (Sampled.Count > maxSampled)
+            {
+                Sampled.RemoveRange(Math.Max(0, maxSampled), Sampled.Count - Math.Max(0, maxSampled));
+            }
+        }
+    }
+
+    private sealed class Entry
+    {
+        public Entry(string file, Diagnostic diagnostic)
+        {
+            File = file;
+            Diagnostic = diagnostic;
+
+            var location = diagnostic.Location;
+            var key = $"{file}\n{location?.Offset}\n{diagnostic.Code}\n{diagnostic.Message}";
+            Hash = BinaryPrimitives.ReadUInt64BigEndian(SHA256.HashData(Encoding.UTF8.GetBytes(key)));
+        }
+
+        public string File { get; }
+
+        public Diagnostic Diagnostic { get; }
+
+        public ulong Hash { get; }
+    }
+
+    private sealed class EntryComparer : IComparer<Entry>
+    {
+        public static readonly EntryComparer Instance = new();
+
+        public int Compare(Entry? x, Entry? y)
+        {
+            if (ReferenceEquals(x, y))
+            {
+                return 0;
+            }
+
+            if (x == null || y == null)
+            {
+                return x == null ? -1 : 1;
+            }
+
+            var result = string.CompareOrdinal(x.File, y.File);
+            if (result == 0)
+            {
+                result = (x.Diagnostic.Location?.Offset ?? -1).CompareTo(y.Diagnostic.Location?.Offset ?? -1);
+            }
+
+            if (result == 0)
+            {
+                result = string.CompareOrdinal(x.Diagnostic.Code, y.Diagnostic.Code);
+            }
+
+            return result != 0 ? result : string.CompareOrdinal(x.Diagnostic.Message, y.Diagnostic.Message);
+        }
+    }
+}
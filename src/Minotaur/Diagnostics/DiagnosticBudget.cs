@@ -0,0 +1,275 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.Json.Serialization;
+using Minotaur.Parser;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// Limits on the errors reported for a file and for a run, and on the diagnostics of one code, read from the
+/// "diagnosticLimits" section of the project configuration.
+/// </summary>
+public sealed class DiagnosticLimits
+{
+    /// <summary>
+    /// Gets or sets the number of errors reported for one file before the rest are summarized. Zero for no limit.
+    /// </summary>
+    [JsonPropertyName("maxErrorsPerFile")]
+    public int MaxErrorsPerFile { get; set; } = 100;
+
+    /// <summary>
+    /// Gets or sets the number of errors reported for all files of a run before the rest are summarized. Zero for
+    /// no limit.
+    /// </summary>
+    [JsonPropertyName("maxErrorsPerRun")]
+    public int MaxErrorsPerRun { get; set; } = 1000;
+
+    /// <summary>
+    /// Gets or sets the number of leading tokens of a file checked for a wrong grammar. Zero turns the check off.
+    /// </summary>
+    [JsonPropertyName("wrongGrammarWindow")]
+    public int WrongGrammarWindow { get; set; } = 100;
+
+    /// <summary>
+    /// Gets or sets the number of errors per token within <see cref="WrongGrammarWindow"/> at which a file is
+    /// reported as not written in the grammar's language.
+    /// </summary>
+    [JsonPropertyName("wrongGrammarErrorDensity")]
+    public double WrongGrammarErrorDensity { get; set; } = 0.25;
+
+    /// <summary>
+    /// Gets or sets the number of diagnostics of one code reported for one file before the rest are collapsed
+    /// into an aggregate entry. Zero for no limit.
+    /// </summary>
+    [JsonPropertyName("maxPerCodePerFile")]
+    public int MaxPerCodePerFile { get; set; } = 10;
+
+    /// <summary>
+    /// Gets or sets the number of diagnostics of one code reported for all files of a run before the rest are
+    /// collapsed into an aggregate entry. Zero for no limit.
+    /// </summary>
+    [JsonPropertyName("maxPerCodePerRun")]
+    public int MaxPerCodePerRun { get; set; } = 100;
+
+    /// <summary>
+    /// Gets or sets the number of locations an aggregate entry lists as a sample of those it collapsed.
+    /// </summary>
+    [JsonPropertyName("sampledLocations")]
+    public int SampledLocations { get; set; } = 5;
+}
+
+/// <summary>
+/// Keeps cascades of errors, such as those of a file parsed with the wrong grammar, from drowning the output. Each
+/// file reports its first errors up to the file limit, and the run as a whole up to the run limit; the errors past
+/// either are replaced by one <see cref="DiagnosticCodes.TooManyErrors"/> error naming their count and most frequent
+/// codes. Only diagnostics are dropped: the tree is built as it would be without a budget.
+/// </summary>
+/// <remarks>
+/// A file whose first <see cref="DiagnosticLimits.WrongGrammarWindow"/> tokens have at least three errors and at
+/// least <see cref="DiagnosticLimits.WrongGrammarErrorDensity"/> errors per token reports a single
+/// <see cref="DiagnosticCodes.WrongGrammar"/> error instead of its own, which are all summarized. Errors are counted
+/// by location, so characters the lexer skipped count although they are not tokens. A budget is shared by the
+/// parses of a run, from any thread.
+/// </remarks>
+public sealed class DiagnosticBudget
+{
+    private const int MinimumWrongGrammarErrors = 3;
+    private const int SummarizedCodes = 3;
+
+    private readonly object _lock = new();
+    private readonly Dictionary<string, int> _suppressedCodes = new(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Initializes a new instance of the DiagnosticBudget class.
+    /// </summary>
+    /// <param name="limits">The limits, or null for the defaults.</param>
+    public DiagnosticBudget(DiagnosticLimits? limits = null)
+    {
+        Limits = limits ?? new DiagnosticLimits();
+    }
+
+    /// <summary>
+    /// Gets the limits.
+    /// </summary>
+    public DiagnosticLimits Limits { get; }
+
+    /// <summary>
+    /// Gets the number of errors reported so far in the run, not counting summaries.
+    /// </summary>
+    public int ReportedErrors { get; private set; }
+
+    /// <summary>
+    /// Gets the number of errors suppressed so far in the run.
+    /// </summary>
+    public int SuppressedErrors { get; private set; }
+
+    /// <summary>
+    /// Gets the number of files reported as written in another language than their grammar's.
+    /// </summary>
+    public int WrongGrammarFiles { get; private set; }
+
+    /// <summary>
+    /// Applies the budget to the diagnostics of a parse, in place.
+    /// </summary>
+    /// <param name="diagnostics">The diagnostics of the parse.</param>
+    /// <param name="tokens">The tokens of the parse.</param>
+    /// <param name="grammarName">The name of the grammar the file was parsed with.</param>
+    public void Apply(List<Diagnostic> diagnostics, IReadOnlyList<Token> tokens, string grammarName)
+    {
+        ArgumentNullException.ThrowIfNull(diagnostics);
+        ArgumentNullException.ThrowIfNull(tokens);
+        ArgumentNullException.ThrowIfNull(grammarName);
+
+        var errors = diagnostics
+            .Where(d => d.Severity == DiagnosticSeverity.Error)
+            .OrderBy(d => d.Location?.Offset ?? int.MaxValue)
+            .ToList();
+        if (errors.Count == 0)
+        {
+            return;
+        }
+
+        lock (_lock)
+        {
+            if (CheckGrammar(errors, tokens, grammarName) is { } wrongGrammar)
+            {
+                WrongGrammarFiles++;
+                ReportedErrors++;
+                diagnostics.RemoveAll(d => d.Severity == DiagnosticSeverity.Error);
+                diagnostics.Insert(0, wrongGrammar);
+                diagnostics.Add(Suppress(errors));
+                return;
+            }
+
+            var allowed = errors.Count;
+            if (Limits.MaxErrorsPerFile > 0)
+            {
+                allowed = Math.Min(allowed, Limits.MaxErrorsPerFile);
+            }
+
+            if (Limits.MaxErrorsPerRun > 0)
+            {
+                allowed = Math.Min(allowed, Math.Max(0, Limits.MaxErrorsPerRun - ReportedErrors));
+            }
+
+            ReportedErrors += allowed;
+            if (allowed == errors.Count)
+            {
+                return;
+            }
+
+            var suppressed = errors.Skip(allowed).ToList();
+            var dropped = new HashSet<Diagnostic>(suppressed, ReferenceEqualityComparer.Instance);
+            diagnostics.RemoveAll(dropped.Contains);
+            diagnostics.Add(Suppress(suppressed));
+        }
+    }
+
+    /// <summary>
+    /// Creates the diagnostic summarizing what the run suppressed, for the end of its output.
+    /// </summary>
+    /// <returns>The summary, or null if nothing was suppressed.</returns>
+    public Diagnostic? CreateRunSummary()
+    {
+        lock (_lock)
+        {
+            if (SuppressedErrors == 0)
+            {
+                return null;
+            }
+
+            return new Diagnostic
+            {
+                Code = DiagnosticCodes.TooManyErrors,
+                Data =
+                {
+                    ["suppressed"] = SuppressedErrors,
+                    ["codes"] = TopCodes(_suppressedCodes)
+                }
+            }.WithMessage("E0016.run",
+                ("count", SuppressedErrors),
+                ("reported", ReportedErrors),
+                ("codes", FormatCodes(_suppressedCodes)),
+                ("files", WrongGrammarFiles));
+        }
+    }
+
+    private Diagnostic? CheckGrammar(List<Diagnostic> errors, IReadOnlyList<Token> tokens, string grammarName)
+    {
+        if (Limits.WrongGrammarWindow <= 0)
+        {
+            return null;
+        }
+
+        var window = Math.Min(Limits.WrongGrammarWindow, tokens.Count);
+        var windowEnd = tokens.Count > Limits.WrongGrammarWindow ? tokens[window - 1].End : int.MaxValue;
+        var count = errors.Count(e => e.Location != null && e.Location.Offset < windowEnd);
+        var density = (double)count / Math.Max(1, window);
+        if (count < MinimumWrongGrammarErrors || density < Limits.WrongGrammarErrorDensity)
+        {
+            return null;
+        }
+
+        return new Diagnostic
+        {
+            Code = DiagnosticCodes.WrongGrammar,
+            Location = errors[0].Location,
+            Data =
+            {
+                ["grammar"] = grammarName,
+                ["density"] = density
+            }
+        }.WithMessage(DiagnosticCodes.WrongGrammar, ("grammar", grammarName), ("count", count), ("window", window));
+    }
+
+    private Diagnostic Suppress(List<Diagnostic> suppressed)
+    {
+        var codes = new Dictionary<string, int>(StringComparer.Ordinal);
+        foreach (var error in suppressed)
+        {
+            codes[error.Code] = codes.GetValueOrDefault(error.Code) + 1;
+            _suppressedCodes[error.Code] = _suppressedCodes.GetValueOrDefault(error.Code) + 1;
+        }
+
+        SuppressedErrors += suppressed.Count;
+        return new Diagnostic
+        {
+            Code = DiagnosticCodes.TooManyErrors,
+            Location = suppressed[0].Location,
+            Data =
+            {
+                ["suppressed"] = suppressed.Count,
+                ["codes"] = TopCodes(codes)
+            }
+        }.WithMessage(DiagnosticCodes.TooManyErrors, ("count", suppressed.Count), ("codes", FormatCodes(codes)));
+    }
+
+    private static Dictionary<string, int> TopCodes(Dictionary<string, int> codes)
+    {
+        return codes
+            .OrderByDescending(c => c.Value)
+            .ThenBy(c => c.Key, StringComparer.Ordinal)
+            .Take(SummarizedCodes)
+            .ToDictionary(c => c.Key, c => c.Value, StringComparer.Ordinal);
+    }
+
+    private static string FormatCodes(Dictionary<string, int> codes)
+    {
+        return string.Join(", ", TopCodes(codes).Select(c => $"{c.Key} ({c.Value})"));
+    }
+}
@@ -0,0 +1,350 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Parser;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// A pair of inputs showing a diagnostic: one that produces it and the same input corrected. Both are parsed with
+/// <see cref="Grammar"/>, so the catalog's tests can check that the examples still behave as they claim.
+/// </summary>
+/// <param name="Grammar">The grammar text the inputs are parsed with.</param>
+/// <param name="Failing">An input that produces the diagnostic.</param>
+/// <param name="Fixed">The input corrected, which produces no error.</param>
+public sealed record DiagnosticExample(string Grammar, string Failing, string Fixed);
+
+/// <summary>
+/// The documentation of a diagnostic code: its usual severity, the shape of its message and a long-form
+/// explanation of what causes it and how to fix it.
+/// </summary>
+/// <param name="Code">The diagnostic code, e.g. "E0001".</param>
+/// <param name="Severity">The severity the code is reported with.</param>
+/// <param name="Message">The message template, with the parts that vary in braces.</param>
+/// <param name="Explanation">What the diagnostic means and how to fix it, in one or more paragraphs.</param>
+public sealed record DiagnosticDescriptor(string Code, DiagnosticSeverity Severity, string Message, string Explanation)
+{
+    /// <summary>
+    /// Gets an example of input producing the diagnostic and its fix, if any.
+    /// </summary>
+    public DiagnosticExample? Example { get; init; }
+
+    /// <summary>
+    /// Gets the name of the grammar that registered the code, or null for built-in codes.
+    /// </summary>
+    public string? Grammar { get; init; }
+}
+
+/// <summary>
+/// The registry of diagnostic codes and their explanations, behind <c>minotaur explain &lt;code&gt;</c> and the
+/// documentation the daemon and SARIF logs attach to diagnostics. Every code in <see cref="DiagnosticCodes"/> is
+/// registered in <see cref="Default"/>; grammars register their own codes with "Diagnostic_&lt;code&gt;" metadata.
+/// </summary>
+/// <remarks>
+/// A grammar's metadata line <c>Diagnostic_LANG001: Message template | Explanation</c> registers LANG001 with the
+/// grammar's name. Codes starting with W are warnings and all others errors. A code can be registered again only
+/// with the same descriptor, so two grammars cannot give one code different meanings.
+/// </remarks>
+public sealed class DiagnosticCatalog
+{
+    /// <summary>
+    /// The prefix of the grammar metadata keys that register diagnostic codes.
+    /// </summary>
+    public const string MetadataPrefix = "Diagnostic_";
+
+    private const string ExampleGrammar = """
+        Grammar: Example
+
+        <program> ::= <statements>
+        <statements> ::= <statement> | <statements> <statement>
+        <statement> ::= "let" <IDENTIFIER> "=" <expr> ";" | "return" <expr> ";"
+        <expr> ::= <IDENTIFIER> | <NUMBER>
+        """;
+
+    private readonly object _lock = new();
+    private readonly Dictionary<string, DiagnosticDescriptor> _descriptors = new(StringComparer.OrdinalIgnoreCase);
+
+    /// <summary>
+    /// Gets the catalog shared by the parser, the analysis passes and the command line, holding the built-in codes.
+    /// </summary>
+    public static DiagnosticCatalog Default { get; } = CreateBuiltIn();
+
+    /// <summary>
+    /// Gets the registered descriptors ordered by code.
+    /// </summary>
+    public IReadOnlyList<DiagnosticDescriptor> Descriptors
+    {
+        get
+        {
+            lock (_lock)
+            {
+                return _descriptors.Values.OrderBy(d => d.Code, StringComparer.Ordinal).ToList();
+            }
+        }
+    }
+
+    /// <summary>
+    /// Registers a diagnostic code.
+    /// </summary>
+    /// <param name="descriptor">The descriptor of the code.</param>
+    /// <exception cref="InvalidOperationException">The code is registered with a different descriptor.</exception>
+    public void Register(DiagnosticDescriptor descriptor)
+    {
+        ArgumentNullException.ThrowIfNull(descriptor);
+        ArgumentException.ThrowIfNullOrWhiteSpace(descriptor.Code, nameof(descriptor));
+
+        lock (_lock)
+        {
+            if (_descriptors.TryGetValue(descriptor.Code, out var existing) && existing != descriptor)
+            {
+                var owner = existing.Grammar != null ? $"grammar '{existing.Grammar}'" : "Minotaur";
+                throw new InvalidOperationException($"Diagnostic code {existing.Code} is already registered by {owner}");
+            }
+
+            _descriptors[descriptor.Code] = descriptor;
+        }
+    }
+
+    /// <summary>
+    /// Registers the diagnostic codes a grammar declares with "Diagnostic_&lt;code&gt;" metadata.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <returns>The descriptors registered.</returns>
+    public IReadOnlyList<DiagnosticDescriptor> RegisterGrammar(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var registered = new List<DiagnosticDescriptor>();
+        foreach (var (key, value) in grammar.Source.Metadata.OrderBy(m => m.Key, StringComparer.Ordinal))
+        {
+            if (!key.StartsWith(MetadataPrefix, StringComparison.Ordinal) || key.Length == MetadataPrefix.Length)
+            {
+                continue;
+            }
+
+            var code = key[MetadataPrefix.Length..];
+            var separator = value.IndexOf('|');
+            var message = (separator < 0 ? value : value[..separator]).Trim();
+            var explanation = separator < 0 ? string.Empty : value[(separator + 1)..].Trim();
+            var severity = code.StartsWith('W') ? DiagnosticSeverity.Warning : DiagnosticSeverity.Error;
+
+            var descriptor = new DiagnosticDescriptor(code, severity, message, explanation) { Grammar = grammar.Name };
+            Register(descriptor);
+            registered.Add(descriptor);
+        }
+
+        return registered;
+    }
+
+    /// <summary>
+    /// Gets the descriptor of a code, ignoring case.
+    /// </summary>
+    /// <param name="code">The diagnostic code.</param>
+    /// <returns>The descriptor, or null if the code is not registered.</returns>
+    public DiagnosticDescriptor? Get(string code)
+    {
+        ArgumentNullException.ThrowIfNull(code);
+
+        lock (_lock)
+        {
+            return _descriptors.GetValueOrDefault(code.Trim());
+        }
+    }
+
+    private static DiagnosticCatalog CreateBuiltIn()
+    {
+        var catalog = new DiagnosticCatalog();
+
+        catalog.Add(DiagnosticCodes.UnexpectedToken, "Unexpected {kind} '{text}'; expected {terminals}",
+            "The parser reached a token that no alternative of the grammar accepts at that position. The message " +
+            "lists the terminals, or the grammar's expected phrases, that would have been accepted instead.\n\n" +
+            "Fix the input at the location reported; often a token before it is missing or misplaced. If the input " +
+            "is correct, the grammar lacks an alternative for it.",
+            new DiagnosticExample(ExampleGrammar, "let x = ;", "let x = 1;"));
+        catalog.Add(DiagnosticCodes.UnexpectedEndOfInput, "Unexpected end of input; expected {terminals}",
+            "The input ended while a rule was still incomplete, so the start rule could not be finished. The message " +
+            "lists what the parser was waiting for.\n\n" +
+            "Complete the construct at the end of the input, typically a missing terminator or closing bracket.",
+            new DiagnosticExample(ExampleGrammar, "let x = 1", "let x = 1;"));
+        catalog.Add(DiagnosticCodes.UnrecognizedCharacter, "Unrecognized character '{character}'",
+            "The lexer found a character that no terminal of the grammar matches. It skips the character and goes " +
+            "on, so the parse may report further errors around it.\n\n" +
+            "Remove the character, or add a terminal that matches it to the grammar.",
+            new DiagnosticExample(ExampleGrammar, "let x = 1; $", "let x = 1;"));
+        catalog.Add(DiagnosticCodes.AnalysisPassFailed, "Analysis pass '{pass}' failed: {exception}",
+            "An analysis pass threw an exception. The passes depending on its result were skipped, so their " +
+            "diagnostics are missing for this file.\n\n" +
+            "This points at a bug in the pass or in the plugin that provides it rather than in the input.");
+        catalog.Add(DiagnosticCodes.SemanticActionFailed, "Action '{action}' failed for rule '{rule}': {exception}",
+            "A semantic action of the grammar threw an exception while the parse was evaluated, so the value of the " +
+            "node it was attached to is missing.\n\n" +
+            "Fix the action, or make it handle the input that triggered the exception.");
+        catalog.Add(DiagnosticCodes.UnresolvedReference, "'{name}' is not declared in {file} or exported by an imported file",
+            "A symbol is used but neither declared in its own file nor exported by any file it imports. The message " +
+            "lists the files searched.\n\n" +
+            "Declare the symbol, import the file that declares it, or export it from that file.");
+        catalog.Add(DiagnosticCodes.UnresolvedImport, "Module '{module}' imported by {file} is not in the workspace",
+            "A file imports a module that is not part of the analysis workspace, so the symbols it exports cannot be " +
+            "resolved.\n\n" +
+            "Add the module to the workspace, for example with --include, or correct the import. In grammars with " +
+            "@import rules the module resolver of the grammar maps each specifier to a path, and the message ends " +
+            "with the resolver's reason when it cannot.");
+        catalog.Add(DiagnosticCodes.InvalidEncoding, "{file}: {bytes} at byte {offset} is not valid {encoding}",
+            "The input contains bytes that cannot be decoded in its encoding, which is taken from the byte order " +
+            "mark, then the configured encoding, then UTF-8.\n\n" +
+            "Pass the right encoding with --encoding, or use --substitute-invalid to replace the bytes with U+FFFD " +
+            "and report them as warnings instead.");
+        catalog.Add(DiagnosticCodes.ParseStalled, "Parsing stalled at token {position} in {rules}",
+            "The parser consumed tokens more slowly than the watchdog allows and was stopped. The message names the " +
+            "rules active at the time, which are usually highly ambiguous or deeply recursive.\n\n" +
+            "Make those rules less ambiguous, or raise the watchdog's limits with --stall-timeout.");
+        catalog.Add(DiagnosticCodes.UnresolvedOperator, "Operator expression cannot be structured: '{text}' {reason}",
+            "An operator expression could not be arranged with the precedence and associativity table in effect at " +
+            "its position, for example because two non-associative operators are chained.\n\n" +
+            "Add parentheses to make the grouping explicit, or declare the operator in the table.");
+        catalog.Add(DiagnosticCodes.BidiControlCharacter, "Bidirectional control character U+{code point} can make the code display differently from how it is parsed",
+            "The source contains a Unicode bidirectional control character. Editors reorder the text around it, so " +
+            "code can look different from what the parser reads, which has been used to hide malicious code.\n\n" +
+            "Remove the character, or escape it where it is intended, for example in a string literal.");
+        catalog.Add(DiagnosticCodes.MisspelledKeyword, "Unexpected {kind} '{text}'; did you mean '{keyword}'?",
+            "A word appeared where only keywords are accepted and is close to exactly one of them. The parser reads " +
+            "it as that keyword and goes on, and the diagnostic carries a quick fix correcting the spelling.\n\n" +
+            "Apply the quick fix or correct the word by hand.",
+            new DiagnosticExample(ExampleGrammar, "retrun x;", "return x;"));
+        catalog.Add(DiagnosticCodes.MismatchedDelimiter, "Closing delimiter '{closing}' does not match '{opening}' opened at line {line}, column {column}",
+            "A closing delimiter differs from the one its opening token captured, such as a closing tag naming " +
+            "another element, or a heredoc is not terminated before the end of the input.\n\n" +
+            "Make the closing delimiter repeat the opening one, or terminate the heredoc.");
+        catalog.Add(DiagnosticCodes.InvalidMigration, "Rule <{rule}> of version {from} migrates to <{target}>, which is not defined in version {to}",
+            "A grammar migration maps a rule to one that the newer grammar version does not define, so trees cannot " +
+            "be migrated.\n\n" +
+            "Correct the mapping in the migration manifest, or define the rule in the newer version.");
+        catalog.Add(DiagnosticCodes.FeatureDisabled, "This syntax requires feature '{feature}' (<{rule}> ::= {alternative})",
+            "The input uses an alternative that the grammar guards with a feature, and the feature is not enabled " +
+            "for this parse.\n\n" +
+            "Enable the feature in the parse options or project configuration, or avoid the syntax.");
+        catalog.Add(DiagnosticCodes.TooManyErrors, "Suppressed {count} additional errors; most frequent: {codes}",
+            "A file or run reached its error budget, and the errors past it were replaced by this one, which counts " +
+            "them and names their most frequent codes.\n\n" +
+            "Fix the reported errors first, since later ones are often caused by them, or raise the limits in the " +
+            "\"diagnosticLimits\" section of minotaur.grammar.json.");
+        catalog.Add(DiagnosticCodes.WrongGrammar, "This file does not appear to be {grammar}: {count} errors in its first {window} tokens",
+            "The start of the file has so many errors that it is probably written in another language than the " +
+            "grammar's, so its errors are summarized instead of reported.\n\n" +
+            "Check which grammar the file is parsed with, for example with 'minotaur config <file>'.");
+        catalog.Add(DiagnosticCodes.UnresolvedInclude, "Cannot find '{target}' included from {file}",
+            "An include directive names a file that the host's expander cannot find, so the tokens it would " +
+            "contribute are missing and later errors may follow from that.\n\n" +
+            "Correct the name in the directive, or make the file available where the expander looks for includes.");
+        catalog.Add(DiagnosticCodes.RecursiveInclude, "'{file}' includes itself through {chain}",
+            "A file includes itself, directly or through the files it includes. The include is skipped, since " +
+            "following it would never end.\n\n" +
+            "Remove the include that closes the cycle, or guard the file so its contents are only expanded once.");
+        catalog.Add(DiagnosticCodes.ExpansionTooDeep, "Expansion of '{name}' is nested deeper than {limit} levels",
+            "Includes or macro expansions are nested deeper than the expansion stage allows, which usually means a " +
+            "macro expands to itself through other macros. The expansion stops at the limit.\n\n" +
+            "Break the chain of macros, or raise the limit where the nesting is intended.");
+        catalog.Add(DiagnosticCodes.UnclosedDelimiter, "'{opening}' opened at line {line}, column {column} is never closed; assuming a missing '{closing}' before this line",
+            "A bracket is still open at a line indented no deeper than the line that opened it. The grammar enables " +
+            "delimiter recovery, so the parser assumes the closing bracket is missing there and goes on, which keeps " +
+            "the rest of the file parsed as before.\n\n" +
+            "Add the closing bracket where the block ends.");
+        catalog.Add(DiagnosticCodes.DuplicateKey, "Duplicate key {key}; {earlier} is already defined at line {line}, column {column}",
+            "A member repeats the key of an earlier member of the same container. The grammar requires the keys to be " +
+            "distinct, possibly ignoring case or Unicode normalization differences, and consumers would keep only " +
+            "one of the values.\n\n" +
+            "Remove the later member, which the quick fix does, or rename its key.");
+        catalog.Add(DiagnosticCodes.MismatchedDelimiter, "'{found}' does not close '{opening}' opened at line {line}, column {column}; assuming '{closing}' instead",
+            "A closing bracket does not match the innermost open bracket. The grammar enables stack delimiter " +
+            "recovery, and taking it for the matching closer leaves the fewest bracket errors in the lines after it.\n\n" +
+            "Replace the closer, which the quick fix does, or add the brackets that are missing before it.");
+        catalog.Add(DiagnosticCodes.UnexpectedDelimiter, "Unexpected '{found}'; assuming it is extra and ignoring it",
+            "A closing bracket has no bracket to close here. The grammar enables stack delimiter recovery, and " +
+            "dropping the closer leaves the fewest bracket errors in the lines after it.\n\n" +
+            "Remove the closer, which the quick fix does, or add the opening bracket it was meant for.");
+        catalog.Add(DiagnosticCodes.InvalidEscape, "Invalid escape sequence '{escape}': {reason}",
+            "A string token holds an escape sequence its terminal's escape scheme, declared with a // @escapes " +
+            "annotation, does not allow: an unknown escape, too few hexadecimal digits, or a code point that is not " +
+            "a character. The token is still read as one string, so the parse goes on, and the escape is kept as " +
+            "written in its decoded value.\n\n" +
+            "Write the character the escape was meant for, or double the backslash to write a backslash.");
+        catalog.Add(DiagnosticCodes.AmbiguousParse, "Ambiguous parse of <{rule}>: {count} derivations",
+            "The input has more than one derivation under the rule named, so its tree depends on which one is " +
+            "chosen.\n\n" +
+            "Make the grammar unambiguous, for example with a 'Precedence:' header or by factoring the rule, accept " +
+            "the ambiguity for the whole rule with a // @ambiguous annotation, or silence the warning with an allow " +
+            "directive where it is harmless. 'minotaur grammar conflicts --interactive' walks through the choices.");
+        catalog.Add(DiagnosticCodes.UnusedSymbol, "'{name}' is declared but never used",
+            "A symbol is declared but never referenced, and it is not exported.\n\n" +
+            "Remove the declaration, use the symbol, or silence the warning with an allow directive.");
+        catalog.Add(DiagnosticCodes.UndeclaredSymbol, "'{name}' is used but never declared",
+            "A symbol is referenced but never declared in the file.\n\n" +
+            "Declare the symbol or correct the reference's spelling.");
+        catalog.Add(DiagnosticCodes.SubstitutedBytes, "Replaced {bytes} at byte {offset}, which is not valid {encoding}, with U+FFFD",
+            "Bytes that are not valid in the input encoding were replaced with U+FFFD because invalid input was " +
+            "allowed. The text parsed differs from the file there.\n\n" +
+            "Convert the file to its declared encoding, or pass the encoding it is written in.");
+        catalog.Add(DiagnosticCodes.UnnormalizedIdentifier, "'{name}' and '{other}' are equal after normalization but spelled with different code points; {consequence}",
+            "Two identifiers are spelled with different code points but are equal after Unicode normalization, so " +
+            "they look the same and are treated as one symbol.\n\n" +
+            "Spell the identifier the same way everywhere, normally in NFC.");
+        catalog.Add(DiagnosticCodes.ConfusableIdentifier, "'{name}' ({code points}) looks like '{other}' ({code points})",
+            "Two different identifiers look alike, for example because one uses a Cyrillic letter where the other " +
+            "uses a Latin one.\n\n" +
+            "Rename one of them, or spell both with the same script.");
+        catalog.Add(DiagnosticCodes.UnmappedRule, "Rule <{rule}> of version {from} is not in version {to} and no migration maps or removes it",
+            "A rule disappeared between grammar versions without a migration mapping, or a tree node's rule was " +
+            "removed and has no counterpart in the newer version.\n\n" +
+            "Map or remove the rule in the migration manifest.");
+        catalog.Add(DiagnosticCodes.AmbiguousMigration, "Rule <{rule}> was split in version {to} and the node could be any of {rules}",
+            "A tree node's rule was split between grammar versions, and no condition or more than one chose the new " +
+            "rule for it.\n\n" +
+            "Make the conditions of the split in the migration manifest exclusive and complete.");
+        catalog.Add(DiagnosticCodes.MalformedDirective, "Directive '{text}' {problem}",
+            "A comment written in a declared directive syntax could not be read as a directive, so it has no " +
+            "effect.\n\n" +
+            "Correct the directive, or write the comment so it does not look like one.");
+        catalog.Add(DiagnosticCodes.UnmatchedDirective, "Directive 'end {name}' has no matching 'begin {name}'",
+            "A region directive has no partner: an end without a begin, or a begin that is never ended.\n\n" +
+            "Add the missing directive or remove the one left over.");
+        catalog.Add(DiagnosticCodes.DeprecatedSyntax, "{syntax} is deprecated since {version}: {grammar message}",
+            "The input uses a rule or alternative that the grammar marks as deprecated. The message is the " +
+            "grammar's, usually naming the syntax to use instead.\n\n" +
+            "Rewrite the input with the replacement syntax.");
+        catalog.Add(DiagnosticCodes.UnbalancedBracket, "'{bracket}' is never closed",
+            "A bracket in a file read without a grammar has no partner: a closing bracket that closes nothing, or an " +
+            "opening bracket that is never closed.\n\n" +
+            "Add or remove the bracket.");
+        catalog.Add(DiagnosticCodes.ImportCycle, "Modules import each other in a cycle: {cycle}",
+            "Files of the workspace import each other, directly or through other files, so there is no order in " +
+            "which each can be processed after the files it imports.\n\n" +
+            "Move the declarations both files need into a third file, or remove one of the imports.");
+        catalog.Add(DiagnosticCodes.SuspiciousEscape, "Suspicious escape sequence '{escape}': {reason}",
+            "A string token holds an escape sequence its terminal's escape scheme accepts but that is unlikely to be " +
+            "meant: a JSON \\u escape of a lone surrogate, which no UTF-8 text can hold, or a Python escape that is " +
+            "not one and keeps its backslash.\n\n" +
+            "Escape both halves of a surrogate pair, or double the backslash if it is meant literally.");
+
+        return catalog;
+    }
+
+    private void Add(string code, string message, string explanation, DiagnosticExample? example = null)
+    {
+        var severity = code.StartsWith('W') ? DiagnosticSeverity.Warning : DiagnosticSeverity.Error;
+        Register(new DiagnosticDescriptor(code, severity, message, explanation) { Example = example });
+    }
+}
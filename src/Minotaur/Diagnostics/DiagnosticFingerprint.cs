@@ -0,0 +1,92 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Security.Cryptography;
+using System.Text;
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// Computes fingerprints that identify a diagnostic across versions of its file, in the manner of SARIF
+/// <c>partialFingerprints</c>. A fingerprint hashes the code, the message and the text of the reported span and
+/// its line, with whitespace collapsed and positions in the message left out, so code that moves or is reindented
+/// keeps its fingerprints. Diagnostics that hash alike are told apart by their order in the file.
+/// </summary>
+public static class DiagnosticFingerprint
+{
+    /// <summary>
+    /// The key of the fingerprint in a SARIF result's <c>partialFingerprints</c>.
+    /// </summary>
+    public const string SarifKey = "minotaurLineHash/v1";
+
+    private static readonly Regex Whitespace = new(@"\s+", RegexOptions.CultureInvariant);
+    private static readonly Regex MessagePosition = new(@"\b(?:line \d+, column \d+|\d+:\d+)\b", RegexOptions.CultureInvariant);
+
+    /// <summary>
+    /// Computes the fingerprints of the diagnostics of one file.
+    /// </summary>
+    /// <param name="diagnostics">The diagnostics.</param>
+    /// <param name="source">The text of the file the diagnostics refer to.</param>
+    /// <returns>The fingerprints, in the order of the diagnostics, each "hash:ordinal".</returns>
+    public static IReadOnlyList<string> Compute(IReadOnlyList<Diagnostic> diagnostics, string source)
+    {
+        ArgumentNullException.ThrowIfNull(diagnostics);
+        ArgumentNullException.ThrowIfNull(source);
+
+        var lineIndex = new LineIndex(source);
+        var hashes = diagnostics.Select(d => Hash(d, source, lineIndex)).ToList();
+
+        // Ordinals follow the file, not the reporting order, so passes that report in another order agree.
+        var fingerprints = new string[diagnostics.Count];
+        var seen = new Dictionary<string, int>(StringComparer.Ordinal);
+        foreach (var i in Enumerable.Range(0, diagnostics.Count).OrderBy(i => diagnostics[i].Location?.Offset ?? -1))
+        {
+            var ordinal = seen.GetValueOrDefault(hashes[i]) + 1;
+            seen[hashes[i]] = ordinal;
+            fingerprints[i] = $"{hashes[i]}:{ordinal}";
+        }
+
+        return fingerprints;
+    }
+
+    private static string Hash(Diagnostic diagnostic, string source, LineIndex lineIndex)
+    {
+        var key = new StringBuilder()
+            .Append(diagnostic.Code).Append('\0')
+            .Append(MessagePosition.Replace(diagnostic.Message, "#")).Append('\0');
+
+        if (diagnostic.Location is { } location && location.Offset <= source.Length)
+        {
+            var end = Math.Min(source.Length, location.Offset + location.Length);
+            var line = lineIndex.GetLineColumn(location.Offset).Line;
+            var lineStart = lineIndex.GetLineStart(line);
+            var lineEnd = source.IndexOf('\n', lineStart);
+            key.Append(Collapse(source[location.Offset..end])).Append('\0')
+                .Append(Collapse(source[lineStart..(lineEnd < 0 ? source.Length : lineEnd)]));
+        }
+
+        var hash = SHA256.HashData(Encoding.UTF8.GetBytes(key.ToString()));
+        return Convert.ToHexString(hash, 0, 8).ToLowerInvariant();
+    }
+
+    private static string Collapse(string text)
+    {
+        return Whitespace.Replace(text, " ").Trim();
+    }
+}
@@ -0,0 +1,306 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Minotaur.Core;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// Renders diagnostics as text, hover content and SARIF. When a <see cref="SourceMapper"/> is supplied, every
+/// rendering offers the original location next to the generated one.
+/// </summary>
+public class DiagnosticFormatter
+{
+    /// <summary>
+    /// Initializes a new instance of the DiagnosticFormatter class.
+    /// </summary>
+    /// <param name="mapper">The mapper translating generated positions, or null to render generated positions only.</param>
+    public DiagnosticFormatter(SourceMapper? mapper = null)
+    {
+        Mapper = mapper;
+    }
+
+    /// <summary>
+    /// Gets the mapper translating generated positions.
+    /// </summary>
+    public SourceMapper? Mapper { get; }
+
+    /// <summary>
+    /// Gets or sets the decoded source the diagnostics refer to. When set, SARIF regions also carry byte offsets
+    /// into the original file and results carry <see cref="DiagnosticFingerprint"/> partial fingerprints.
+    /// </summary>
+    public DecodedSource? Source { get; set; }
+
+    /// <summary>
+    /// Gets or sets the number of columns between tab stops used to align carets under source lines.
+    /// </summary>
+    public int TabWidth { get; set; } = LineIndex.DefaultTabWidth;
+
+    /// <summary>
+    /// Gets or sets the localizer messages are rendered with, or null to render them as reported, in English.
+    /// </summary>
+    public DiagnosticLocalizer? Localizer { get; set; }
+
+    /// <summary>
+    /// Renders a diagnostic on one line: "file:line:column: severity code: message (in path) (original: file:line:column)",
+    /// with the path of diagnostics parsed with <see cref="Parser.ParseOptions.PathContext"/>.
+    /// </summary>
+    /// <param name="diagnostic">The diagnostic.</param>
+    /// <returns>The rendered diagnostic.</returns>
+    public string Format(Diagnostic diagnostic)
+    {
+        ArgumentNullException.ThrowIfNull(diagnostic);
+
+        var text = new StringBuilder();
+        if (diagnostic.Location is { } location)
+        {
+            text.Append(Describe(location)).Append(": ");
+        }
+
+        text.Append(diagnostic.Severity.ToString().ToLowerInvariant()).Append(' ').Append(diagnostic.Code).Append(": ").Append(Localizer?.Render(diagnostic) ?? diagnostic.Message);
+        if (diagnostic.Data.GetValueOrDefault(Parser.QualifiedPath.DataKey) is { } path)
+        {
+            text.Append(" (in ").Append(path).Append(')');
+        }
+
+        if (diagnostic.Location != null && Mapper?.Map(diagnostic.Location) is { } original)
+        {
+            text.Append(" (original: ").Append(original).Append(')');
+        }
+
+        return text.ToString();
+    }
+
+    /// <summary>
+    /// Renders a diagnostic followed by the offending source line and a caret line underlining its span. Tabs in the
+    /// source line are expanded to <see cref="TabWidth"/> so the carets sit under the right characters regardless of
+    /// indentation, and lines ending in CR, CRLF or LF are all recognized.
+    /// </summary>
+    /// <param name="diagnostic">The diagnostic.</param>
+    /// <param name="sourceText">The source text the diagnostic's location refers to.</param>
+    /// <returns>The rendered diagnostic, one line per row, separated by "\n".</returns>
+    public string FormatSnippet(Diagnostic diagnostic, string sourceText)
+    {
+        ArgumentNullException.ThrowIfNull(diagnostic);
+        ArgumentNullException.ThrowIfNull(sourceText);
+
+        var text = new StringBuilder(Format(diagnostic));
+        if (diagnostic.Location is not { } location)
+        {
+            return text.ToString();
+        }
+
+        var index = new LineIndex(sourceText);
+        var (line, _) = index.GetLineColumn(location.Offset);
+        var lineStart = index.GetLineStart(line);
+        var contentEnd = index.GetLineContentEnd(line);
+        var lineText = index.GetLineText(line);
+
+        var start = location.GetVisualColumn(sourceText, TabWidth);
+        var end = LineIndex.ComputeVisualColumn(sourceText, lineStart, Math.Min(location.Offset + location.Length, contentEnd), TabWidth);
+
+        var gutter = line.ToString(CultureInfo.InvariantCulture);
+        text.Append('\n').Append(gutter).Append(" | ").Append(ExpandTabs(lineText));
+        text.Append('\n').Append(' ', gutter.Length).Append(" | ").Append(' ', start - 1).Append('^', Math.Max(1, end - start));
+        return text.ToString();
+    }
+
+    /// <summary>
+    /// Renders hover content for a position as Markdown, listing the generated and original locations.
+    /// </summary>
+    /// <param name="position">The generated position.</param>
+    /// <param name="content">Optional content shown above the locations, such as a node description.</param>
+    /// <returns>The hover Markdown.</returns>
+    public string FormatHover(SourcePosition position, string? content = null)
+    {
+        ArgumentNullException.ThrowIfNull(position);
+
+        var text = new StringBuilder();
+        if (!string.IsNullOrEmpty(content))
+        {
+            text.Append(content).Append("\n\n");
+        }
+
+        text.Append("Generated: `").Append(Describe(position)).Append('`');
+        if (Mapper?.Map(position) is { } original)
+        {
+            text.Append("  \nOriginal: `").Append(original).Append('`');
+            if (original.Name != null)
+            {
+                text.Append(" (").Append(original.Name).Append(')');
+            }
+        }
+
+        return text.ToString();
+    }
+
+    /// <summary>
+    /// Renders diagnostics as a SARIF 2.1.0 log. Each result is located in the generated file; mapped results
+    /// carry the original location as a related location.
+    /// </summary>
+    /// <param name="diagnostics">The diagnostics.</param>
+    /// <param name="toolName">The tool name reported in the log.</param>
+    /// <returns>The SARIF JSON.</returns>
+    public string ToSarif(IEnumerable<Diagnostic> diagnostics, string toolName = "minotaur")
+    {
+        ArgumentNullException.ThrowIfNull(diagnostics);
+
+        var list = diagnostics.ToList();
+        var fingerprints = Source != null ? DiagnosticFingerprint.Compute(list, Source.Text) : null;
+        var results = new JsonArray();
+        for (var i = 0; i < list.Count; i++)
+        {
+            var diagnostic = list[i];
+            var result = new JsonObject
+            {
+                ["ruleId"] = diagnostic.Code,
+                ["level"] = diagnostic.Severity switch
+                {
+                    DiagnosticSeverity.Error => "error",
+                    DiagnosticSeverity.Warning => "warning",
+                    _ => "note"
+                },
+                ["message"] = new JsonObject { ["text"] = Localizer?.Render(diagnostic) ?? diagnostic.Message }
+            };
+
+            if (diagnostic.Location is { } location)
+            {
+                result["locations"] = new JsonArray(new JsonObject
+                {
+                    ["physicalLocation"] = PhysicalLocation(location.SourceFile ?? Mapper?.GeneratedFile, Region(location))
+                });
+
+                if (Mapper?.Map(location) is { } original)
+                {
+                    result["relatedLocations"] = new JsonArray(new JsonObject
+                    {
+                        ["id"] = 1,
+                        ["message"] = new JsonObject { ["text"] = "Original source location" },
+                        ["physicalLocation"] = PhysicalLocation(original.SourceFile, new JsonObject
+                        {
+                            ["startLine"] = original.Line,
+                            ["startColumn"] = original.Column
+                        })
+                    });
+                }
+            }
+
+            if (fingerprints != null)
+            {
+                result["partialFingerprints"] = new JsonObject { [DiagnosticFingerprint.SarifKey] = fingerprints[i] };
+            }
+
+            results.Add(result);
+        }
+
+        var log = new JsonObject
+        {
+            ["$schema"] = "https://json.schemastore.org/sarif-2.1.0.json",
+            ["version"] = "2.1.0",
+            ["runs"] = new JsonArray(new JsonObject
+            {
+                ["tool"] = new JsonObject { ["driver"] = new JsonObject { ["name"] = toolName, ["rules"] = Rules(list) } },
+                ["results"] = results
+            })
+        };
+
+        return log.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+    }
+
+    // Describes the registered codes among the diagnostics, so SARIF viewers can show their explanations.
+    private static JsonArray Rules(List<Diagnostic> diagnostics)
+    {
+        var rules = new JsonArray();
+        foreach (var code in diagnostics.Select(d => d.Code).Distinct(StringComparer.Ordinal).Order(StringComparer.Ordinal))
+        {
+            if (DiagnosticCatalog.Default.Get(code) is { } descriptor)
+            {
+                rules.Add(new JsonObject
+                {
+                    ["id"] = descriptor.Code,
+                    ["shortDescription"] = new JsonObject { ["text"] = descriptor.Message },
+                    ["fullDescription"] = new JsonObject { ["text"] = descriptor.Explanation }
+                });
+            }
+        }
+
+        return rules;
+    }
+
+    private string ExpandTabs(string line)
+    {
+        var text = new StringBuilder();
+        foreach (var c in line)
+        {
+            if (c == '\t')
+            {
+                text.Append(' ', TabWidth - text.Length % TabWidth);
+            }
+            else
+            {
+                text.Append(c);
+            }
+        }
+
+        return text.ToString();
+    }
+
+    private static string Describe(SourcePosition position)
+    {
+        return position.SourceFile != null
+            ? $"{position.SourceFile}:{position.Line}:{position.Column}"
+            : $"{position.Line}:{position.Column}";
+    }
+
+    private JsonObject Region(SourcePosition location)
+    {
+        var region = new JsonObject
+        {
+            ["startLine"] = location.Line,
+            ["startColumn"] = location.Column,
+            ["endLine"] = location.EndLine,
+            ["endColumn"] = location.EndColumn,
+            ["charOffset"] = location.Offset,
+            ["charLength"] = location.Length
+        };
+
+        if (Source != null && location.Offset + location.Length <= Source.Text.Length)
+        {
+            var (byteOffset, byteLength) = Source.GetByteSpan(location);
+            region["byteOffset"] = byteOffset;
+            region["byteLength"] = byteLength;
+        }
+
+        return region;
+    }
+
+    private static JsonObject PhysicalLocation(string? file, JsonObject region)
+    {
+        var location = new JsonObject();
+        if (file != null)
+        {
+            location["artifactLocation"] = new JsonObject { ["uri"] = file.Replace('\\', '/') };
+        }
+
+        location["region"] = region;
+        return location;
+    }
+}
@@ -0,0 +1,317 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// Renders diagnostic messages in a locale from <see cref="MessageCatalog"/>s, falling back along a chain of locales
+/// that ends in English. A message that is missing or cannot be formatted in any locale before English is rendered
+/// in English and recorded in <see cref="Warnings"/>; rendering never throws.
+/// </summary>
+/// <remarks>
+/// The locale comes from the constructor, then the <c>MINOTAUR_LOCALE</c> environment variable, then the
+/// configured locale (the "locale" of minotaur.grammar.json), then <c>LC_ALL</c>, <c>LC_MESSAGES</c>, <c>LANG</c>
+/// and the current UI culture. The chain of "de-AT" is "de-AT", "de", the <see cref="Fallbacks"/>, then "en".
+/// Catalogs added with <see cref="AddCatalog"/>, usually a grammar's, are searched before the built-in ones.
+/// </remarks>
+public sealed class DiagnosticLocalizer
+{
+    /// <summary>
+    /// The environment variable that selects the locale of diagnostic messages.
+    /// </summary>
+    public const string LocaleVariable = "MINOTAUR_LOCALE";
+
+    private const string English = "en";
+
+    private readonly object _lock = new();
+    private readonly List<MessageCatalog> _catalogs = new();
+    private readonly List<string> _warnings = new();
+    private readonly HashSet<string> _warned = new(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Initializes a new instance of the DiagnosticLocalizer class.
+    /// </summary>
+    /// <param name="locale">The locale, or null to resolve it with <see cref="ResolveLocale"/>.</param>
+    /// <param name="configuredLocale">The locale of the project configuration, used when neither
+    /// <paramref name="locale"/> nor <c>MINOTAUR_LOCALE</c> is set.</param>
+    public DiagnosticLocalizer(string? locale = null, string? configuredLocale = null)
+    {
+        Locale = NormalizeLocale(string.IsNullOrWhiteSpace(locale) ? ResolveLocale(configuredLocale) : locale);
+    }
+
+    /// <summary>
+    /// Gets the locale messages are rendered in.
+    /// </summary>
+    public string Locale { get; }
+
+    /// <summary>
+    /// Gets the locales tried after the locale and its parents and before English.
+    /// </summary>
+    public IList<string> Fallbacks { get; } = new List<string>();
+
+    /// <summary>
+    /// Gets the locales tried in order, ending with English.
+    /// </summary>
+    public IReadOnlyList<string> FallbackChain
+    {
+        get
+        {
+            var chain = new List<string>();
+            foreach (var locale in Fallbacks.Select(NormalizeLocale).Prepend(Locale))
+            {
+                for (var current = locale; current.Length > 0; current = Parent(current))
+                {
+                    if (!chain.Contains(current, StringComparer.OrdinalIgnoreCase))
+                    {
+                        chain.Add(current);
+                    }
+                }
+            }
+
+            chain.RemoveAll(l => l.Equals(English, StringComparison.OrdinalIgnoreCase));
+            chain.Add(English);
+            return chain;
+        }
+    }
+
+    /// <summary>
+    /// Gets the messages that fell back to English, one line per key and locale.
+    /// </summary>
+    public IReadOnlyList<string> Warnings
+    {
+        get
+        {
+            lock (_lock)
+            {
+                return _warnings.ToList();
+            }
+        }
+    }
+
+    /// <summary>
+    /// Adds a catalog, searched before the built-in catalogs and those added earlier.
+    /// </summary>
+    /// <param name="catalog">The catalog.</param>
+    /// <returns>This localizer, for chaining.</returns>
+    public DiagnosticLocalizer AddCatalog(MessageCatalog catalog)
+    {
+        ArgumentNullException.ThrowIfNull(catalog);
+
+        lock (_lock)
+        {
+            _catalogs.Insert(0, catalog);
+        }
+
+        return this;
+    }
+
+    /// <summary>
+    /// Adds the catalogs shipped next to a grammar file: "toy.de.messages" and "toy.pt-BR.messages" beside
+    /// "toy.grammar". Catalogs that cannot be read are recorded in <see cref="Warnings"/> and skipped.
+    /// </summary>
+    /// <param name="grammarPath">The path of the grammar file.</param>
+    /// <returns>The number of catalogs added.</returns>
+    public int LoadGrammarCatalogs(string grammarPath)
+    {
+        ArgumentNullException.ThrowIfNull(grammarPath);
+
+        var directory = Path.GetDirectoryName(Path.GetFullPath(grammarPath))!;
+        if (!Directory.Exists(directory))
+        {
+            return 0;
+        }
+
+        var added = 0;
+        var pattern = Path.GetFileNameWithoutExtension(grammarPath) + ".*" + MessageCatalog.FileExtension;
+        foreach (var path in Directory.EnumerateFiles(directory, pattern).OrderBy(p => p, StringComparer.Ordinal))
+        {
+            try
+            {
+                AddCatalog(MessageCatalog.Load(path));
+                added++;
+            }
+            catch (Exception ex) when (ex is FormatException or IOException or UnauthorizedAccessException or ArgumentException)
+            {
+                Warn(path, $"Message catalog {path} was skipped: {ex.Message}");
+            }
+        }
+
+        return added;
+    }
+
+    /// <summary>
+    /// Renders the message of a diagnostic in the first locale of <see cref="FallbackChain"/> with a message for
+    /// its <see cref="Diagnostic.MessageKey"/>. Diagnostics without a key keep their message.
+    /// </summary>
+    /// <param name="diagnostic">The diagnostic.</param>
+    /// <returns>The message.</returns>
+    public string Render(Diagnostic diagnostic)
+    {
+        ArgumentNullException.ThrowIfNull(diagnostic);
+
+        if (diagnostic.MessageKey is not { } key)
+        {
+            return diagnostic.Message;
+        }
+
+        foreach (var locale in FallbackChain)
+        {
+            if (locale.Equals(English, StringComparison.OrdinalIgnoreCase))
+            {
+                break;
+            }
+
+            foreach (var catalog in GetCatalogs(locale))
+            {
+                if (!catalog.TryGetMessage(key, out var template))
+                {
+                    continue;
+                }
+
+                try
+                {
+                    return MessageFormat.Format(template, diagnostic.MessageArguments, catalog.Culture);
+                }
+                catch (FormatException ex)
+                {
+                    Warn($"{locale}:{key}:format", $"The '{locale}' message {key} cannot be formatted: {ex.Message}");
+                }
+            }
+        }
+
+        if (!Locale.Equals(English, StringComparison.OrdinalIgnoreCase))
+        {
+            Warn($"{Locale}:{key}", $"No '{Locale}' message for {key}; using English");
+        }
+
+        return diagnostic.Message;
+    }
+
+    /// <summary>
+    /// Returns a copy of a diagnostic with its message rendered by <see cref="Render"/>.
+    /// </summary>
+    /// <param name="diagnostic">The diagnostic.</param>
+    /// <returns>The localized copy.</returns>
+    public Diagnostic Localize(Diagnostic diagnostic)
+    {
+        ArgumentNullException.ThrowIfNull(diagnostic);
+
+        return new Diagnostic
+        {
+            Code = diagnostic.Code,
+            Severity = diagnostic.Severity,
+            Message = Render(diagnostic),
+            MessageKey = diagnostic.MessageKey,
+            MessageArguments = new Dictionary<string, object>(diagnostic.MessageArguments),
+            Location = diagnostic.Location,
+            Data = new Dictionary<string, object>(diagnostic.Data)
+        };
+    }
+
+    /// <summary>
+    /// Resolves the locale from the environment: <c>MINOTAUR_LOCALE</c>, then the configured locale, then
+    /// <c>LC_ALL</c>, <c>LC_MESSAGES</c>, <c>LANG</c> and the current UI culture, then English.
+    /// </summary>
+    /// <param name="configuredLocale">The locale of the project configuration, if any.</param>
+    /// <returns>The locale, normalized.</returns>
+    public static string ResolveLocale(string? configuredLocale = null)
+    {
+        var candidates = new[]
+        {
+            Environment.GetEnvironmentVariable(LocaleVariable),
+            configuredLocale,
+            Environment.GetEnvironmentVariable("LC_ALL"),
+            Environment.GetEnvironmentVariable("LC_MESSAGES"),
+            Environment.GetEnvironmentVariable("LANG"),
+            CultureInfo.CurrentUICulture.Name
+        };
+
+        return NormalizeLocale(candidates.FirstOrDefault(c => !string.IsNullOrWhiteSpace(c)) ?? English);
+    }
+
+    /// <summary>
+    /// Normalizes a locale name: POSIX names such as "de_DE.UTF-8@euro" become "de-DE", "C" and "POSIX" become
+    /// "en", and the language is lower-cased and a two-letter region upper-cased.
+    /// </summary>
+    /// <param name="locale">The locale name.</param>
+    /// <returns>The normalized name.</returns>
+    public static string NormalizeLocale(string locale)
+    {
+        ArgumentNullException.ThrowIfNull(locale);
+
+        var name = locale.Trim();
+        var end = name.IndexOfAny(new[] { '.', '@' });
+        if (end >= 0)
+        {
+            name = name[..end];
+        }
+
+        if (name.Length == 0 || name is "C" or "POSIX" || name.Equals(CultureInfo.InvariantCulture.Name, StringComparison.Ordinal))
+        {
+            return English;
+        }
+
+        var parts = name.Replace('_', '-').Split('-', StringSplitOptions.RemoveEmptyEntries);
+        if (parts.Length == 0)
+        {
+            return English;
+        }
+
+        parts[0] = parts[0].ToLowerInvariant();
+        for (var i = 1; i < parts.Length; i++)
+        {
+            parts[i] = parts[i].Length == 2 ? parts[i].ToUpperInvariant() : parts[i];
+        }
+
+        return string.Join('-', parts);
+    }
+
+    private static string Parent(string locale)
+    {
+        var separator = locale.LastIndexOf('-');
+        return separator < 0 ? string.Empty : locale[..separator];
+    }
+
+    private List<MessageCatalog> GetCatalogs(string locale)
+    {
+        List<MessageCatalog> catalogs;
+        lock (_lock)
+        {
+            catalogs = _catalogs.Where(c => c.Locale.Equals(locale, StringComparison.OrdinalIgnoreCase)).ToList();
+        }
+
+        if (MessageCatalog.GetBuiltIn(locale) is { } builtIn)
+        {
+            catalogs.Add(builtIn);
+        }
+
+        return catalogs;
+    }
+
+    private void Warn(string key, string warning)
+    {
+        lock (_lock)
+        {
+            if (_warned.Add(key))
+            {
+                _warnings.Add(warning);
+            }
+        }
+    }
+}
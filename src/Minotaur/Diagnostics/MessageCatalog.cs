@@ -0,0 +1,211 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Collections.Concurrent;
+using System.Globalization;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// The diagnostic messages of one locale, keyed by diagnostic code. Minotaur's own catalogs are embedded in the
+/// assembly; a grammar ships catalogs for its codes as "&lt;grammar file name&gt;.&lt;locale&gt;.messages" next to
+/// the grammar, which <see cref="DiagnosticLocalizer.LoadGrammarCatalogs"/> picks up.
+/// </summary>
+/// <remarks>
+/// A catalog file holds one <c>KEY = template</c> line per message, with <c>#</c> starting a comment line. Keys are
+/// diagnostic codes, followed by a dot and a variant name where a code has messages of different shapes, e.g.
+/// "E0001.character". Templates use the syntax of <see cref="MessageFormat"/>.
+/// </remarks>
+public sealed class MessageCatalog
+{
+    /// <summary>
+    /// The extension of catalog files.
+    /// </summary>
+    public const string FileExtension = ".messages";
+
+    private const string ResourcePrefix = "Minotaur.Diagnostics.Messages.";
+
+    private static readonly ConcurrentDictionary<string, MessageCatalog?> BuiltIn = new(StringComparer.OrdinalIgnoreCase);
+
+    private readonly Dictionary<string, string> _messages;
+
+    /// <summary>
+    /// Initializes a new instance of the MessageCatalog class.
+    /// </summary>
+    /// <param name="locale">The locale of the messages, e.g. "de" or "pt-BR".</param>
+    /// <param name="messages">The message templates by key.</param>
+    public MessageCatalog(string locale, IReadOnlyDictionary<string, string> messages)
+    {
+        ArgumentException.ThrowIfNullOrWhiteSpace(locale);
+        ArgumentNullException.ThrowIfNull(messages);
+
+        Locale = DiagnosticLocalizer.NormalizeLocale(locale);
+        _messages = new Dictionary<string, string>(messages, StringComparer.OrdinalIgnoreCase);
+    }
+
+    /// <summary>
+    /// Gets the built-in English catalog, which every built-in diagnostic message is formatted with.
+    /// </summary>
+    public static MessageCatalog English => GetBuiltIn("en")
+        ?? throw new InvalidOperationException("The English message catalog is missing from the assembly");
+
+    /// <summary>
+    /// Gets the locales Minotaur ships messages for.
+    /// </summary>
+    public static IReadOnlyList<string> BuiltInLocales { get; } = typeof(MessageCatalog).Assembly
+        .GetManifestResourceNames()
+        .Where(n => n.StartsWith(ResourcePrefix, StringComparison.Ordinal) && n.EndsWith(FileExtension, StringComparison.Ordinal))
+        .Select(n => n[ResourcePrefix.Length..^FileExtension.Length])
+        .OrderBy(n => n, StringComparer.Ordinal)
+        .ToList();
+
+    /// <summary>
+    /// Gets the locale of the messages.
+    /// </summary>
+    public string Locale { get; }
+
+    /// <summary>
+    /// Gets the message templates by key.
+    /// </summary>
+    public IReadOnlyDictionary<string, string> Messages => _messages;
+
+    /// <summary>
+    /// Gets the culture numbers in the messages are formatted with, or the invariant culture when the locale is not
+    /// known to the runtime.
+    /// </summary>
+    public CultureInfo Culture
+    {
+        get
+        {
+            try
+            {
+                return CultureInfo.GetCultureInfo(Locale);
+            }
+            catch (CultureNotFoundException)
+            {
+                return CultureInfo.InvariantCulture;
+            }
+        }
+    }
+
+    /// <summary>
+    /// Gets the built-in catalog of a locale.
+    /// </summary>
+    /// <param name="locale">The locale; matched exactly after normalization, so "de-AT" does not find "de".</param>
+    /// <returns>The catalog, or null if Minotaur ships no messages for the locale.</returns>
+    public static MessageCatalog? GetBuiltIn(string locale)
+    {
+        ArgumentNullException.ThrowIfNull(locale);
+
+        return BuiltIn.GetOrAdd(DiagnosticLocalizer.NormalizeLocale(locale), static name =>
+        {
+            using var stream = typeof(MessageCatalog).Assembly.GetManifestResourceStream(ResourcePrefix + name + FileExtension);
+            if (stream == null)
+            {
+                return null;
+            }
+
+            using var reader = new StreamReader(stream);
+            return Parse(reader.ReadToEnd(), name);
+        });
+    }
+
+    /// <summary>
+    /// Parses catalog text.
+    /// </summary>
+    /// <param name="text">The catalog text.</param>
+    /// <param name="locale">The locale of the messages.</param>
+    /// <returns>The catalog.</returns>
+    /// <exception cref="FormatException">A line is not a <c>KEY = template</c> pair or repeats a key.</exception>
+    public static MessageCatalog Parse(string text, string locale)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var messages = new Dictionary<string, string>(StringComparer.OrdinalIgnoreCase);
+        var lineNumber = 0;
+        foreach (var rawLine in text.Split('\n'))
+        {
+            lineNumber++;
+            var line = rawLine.Trim();
+            if (line.Length == 0 || line.StartsWith('#'))
+            {
+                continue;
+            }
+
+            var separator = line.IndexOf('=');
+            var key = separator < 0 ? string.Empty : line[..separator].Trim();
+            if (key.Length == 0 || !key.All(c => char.IsLetterOrDigit(c) || c is '.' or '_' or '-'))
+            {
+                throw new FormatException($"Message catalog line {lineNumber}: expected 'KEY = message' but found '{line}'");
+            }
+
+            if (!messages.TryAdd(key, line[(separator + 1)..].Trim()))
+            {
+                throw new FormatException($"Message catalog line {lineNumber}: '{key}' is defined twice");
+            }
+        }
+
+        return new MessageCatalog(locale, messages);
+    }
+
+    /// <summary>
+    /// Loads a catalog file, taking the locale from the last dotted segment of its name, so "toy.de.messages" and
+    /// "de.messages" both hold German messages.
+    /// </summary>
+    /// <param name="path">The path of the catalog file.</param>
+    /// <returns>The catalog.</returns>
+    /// <exception cref="FormatException">A line is not a <c>KEY = template</c> pair or repeats a key.</exception>
+    public static MessageCatalog Load(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        var name = Path.GetFileNameWithoutExtension(path);
+        var locale = name[(name.LastIndexOf('.') + 1)..];
+        return Parse(File.ReadAllText(path), locale);
+    }
+
+    /// <summary>
+    /// Gets the template of a key.
+    /// </summary>
+    /// <param name="key">The message key.</param>
+    /// <param name="template">The template, if the catalog has the key.</param>
+    /// <returns>True if the catalog has the key.</returns>
+    public bool TryGetMessage(string key, out string template)
+    {
+        ArgumentNullException.ThrowIfNull(key);
+
+        return _messages.TryGetValue(key, out template!);
+    }
+
+    /// <summary>
+    /// Formats the message of a key in the catalog's culture.
+    /// </summary>
+    /// <param name="key">The message key.</param>
+    /// <param name="arguments">The arguments by placeholder name.</param>
+    /// <returns>The formatted message.</returns>
+    /// <exception cref="KeyNotFoundException">The catalog has no message for the key.</exception>
+    /// <exception cref="FormatException">The template is malformed or needs an argument that is missing.</exception>
+    public string Format(string key, IReadOnlyDictionary<string, object> arguments)
+    {
+        if (!TryGetMessage(key, out var template))
+        {
+            throw new KeyNotFoundException($"The '{Locale}' message catalog has no message '{key}'");
+        }
+
+        return MessageFormat.Format(template, arguments, Culture);
+    }
+}
@@ -0,0 +1,320 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Collections;
+using System.Globalization;
+using System.Text;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// Formats the message templates of <see cref="MessageCatalog"/>s: a small subset of ICU MessageFormat with named
+/// placeholders, plural branches chosen by the locale's plural rules and select branches.
+/// </summary>
+/// <remarks>
+/// <c>{name}</c> is replaced by the argument's value: numbers and other formattable values in the locale's culture,
+/// collections joined with ", ". <c>{name, plural, =0 {...} one {...} other {...}}</c> chooses a branch by the
+/// argument's count, an exact <c>=n</c> branch before the plural category of <see cref="PluralRules"/>, and
+/// <c>#</c> in the branch stands for the count; the count of a collection is its number of items.
+/// <c>{name, select, a {...} other {...}}</c> chooses the branch named by the argument's text. Every plural and
+/// select needs an <c>other</c> branch. <c>\{</c>, <c>\}</c>, <c>\#</c> and <c>\\</c> escape the characters.
+/// </remarks>
+public static class MessageFormat
+{
+    /// <summary>
+    /// Formats a template.
+    /// </summary>
+    /// <param name="template">The message template.</param>
+    /// <param name="arguments">The arguments by placeholder name.</param>
+    /// <param name="culture">The culture numbers are formatted and plural categories chosen with; invariant by default.</param>
+    /// <returns>The formatted message.</returns>
+    /// <exception cref="FormatException">The template is malformed or names an argument that is missing.</exception>
+    public static string Format(string template, IReadOnlyDictionary<string, object> arguments, CultureInfo? culture = null)
+    {
+        ArgumentNullException.ThrowIfNull(template);
+        ArgumentNullException.ThrowIfNull(arguments);
+
+        var output = new StringBuilder();
+        var index = 0;
+        Render(template, ref index, false, arguments, culture ?? CultureInfo.InvariantCulture, null, output);
+        return output.ToString();
+    }
+
+    /// <summary>
+    /// Gets the names of the arguments a template refers to, in order of first appearance.
+    /// </summary>
+    /// <param name="template">The message template.</param>
+    /// <returns>The argument names.</returns>
+    public static IReadOnlyList<string> GetArgumentNames(string template)
+    {
+        ArgumentNullException.ThrowIfNull(template);
+
+        var names = new List<string>();
+        for (var i = 0; i < template.Length; i++)
+        {
+            if (template[i] == '\\')
+            {
+                i++;
+            }
+            else if (template[i] == '{')
+            {
+                var end = template.IndexOfAny(new[] { ',', '}', '{' }, i + 1);
+                var name = end < 0 ? string.Empty : template[(i + 1)..end].Trim();
+                if (IsName(name) && !names.Contains(name, StringComparer.Ordinal))
+                {
+                    names.Add(name);
+                }
+            }
+        }
+
+        return names;
+    }
+
+    private static void Render(string template, ref int index, bool nested, IReadOnlyDictionary<string, object> arguments, CultureInfo culture, string? count, StringBuilder output)
+    {
+        while (index < template.Length)
+        {
+            var c = template[index];
+            if (c == '\\' && index + 1 < template.Length)
+            {
+                output.Append(template[index + 1]);
+                index += 2;
+            }
+            else if (c == '}')
+            {
+                if (nested)
+                {
+                    return;
+                }
+
+                throw new FormatException($"Unmatched '}}' at position {index} of message '{template}'");
+            }
+            else if (c == '#' && count != null)
+            {
+                output.Append(count);
+                index++;
+            }
+            else if (c == '{')
+            {
+                index++;
+                RenderArgument(template, ref index, arguments, culture, output);
+            }
+            else
+            {
+                output.Append(c);
+                index++;
+            }
+        }
+
+        if (nested)
+        {
+            throw new FormatException($"Unterminated branch in message '{template}'");
+        }
+    }
+
+    private static void RenderArgument(string template, ref int index, IReadOnlyDictionary<string, object> arguments, CultureInfo culture, StringBuilder output)
+    {
+        var name = ReadWord(template, ref index, ",}");
+        if (!IsName(name))
+        {
+            throw new FormatException($"Invalid placeholder '{{{name}' in message '{template}'");
+        }
+
+        if (!arguments.TryGetValue(name, out var value))
+        {
+            throw new FormatException($"Message '{template}' needs argument '{name}'");
+        }
+
+        if (template[index] == '}')
+        {
+            index++;
+            output.Append(Describe(value, culture));
+            return;
+        }
+
+        index++;
+        var type = ReadWord(template, ref index, ",}");
+        if (template[index] != ',' || type is not ("plural" or "select"))
+        {
+            throw new FormatException($"Unknown format '{type}' for argument '{name}' in message '{template}'");
+        }
+
+        index++;
+        var branches = ReadBranches(template, ref index);
+        string? pound = null;
+        string selector;
+        int start;
+        if (type == "plural")
+        {
+            var n = Count(value, name);
+            pound = n.ToString(culture);
+            selector = PluralRules.Select(culture.Name, n);
+            start = branches.TryGetValue($"={n}", out var exact) ? exact : branches.GetValueOrDefault(selector, -1);
+        }
+        else
+        {
+            selector = Describe(value, culture);
+            start = branches.GetValueOrDefault(selector, -1);
+        }
+
+        if (start < 0 && !branches.TryGetValue("other", out start))
+        {
+            throw new FormatException($"The {type} of argument '{name}' has no 'other' branch in message '{template}'");
+        }
+
+        var branch = new StringBuilder();
+        Render(template, ref start, true, arguments, culture, pound, branch);
+        output.Append(branch);
+    }
+
+    // Reads the branches up to the argument's closing brace, returning where each branch's text starts.
+    private static Dictionary<string, int> ReadBranches(string template, ref int index)
+    {
+        var branches = new Dictionary<string, int>(StringComparer.Ordinal);
+        while (true)
+        {
+            while (index < template.Length && char.IsWhiteSpace(template[index]))
+            {
+                index++;
+            }
+
+            if (index >= template.Length)
+            {
+                throw new FormatException($"Unterminated argument in message '{template}'");
+            }
+
+            if (template[index] == '}')
+            {
+                index++;
+                return branches;
+            }
+
+            var selector = ReadWord(template, ref index, "{}");
+            if (selector.Length == 0 || template[index] != '{')
+            {
+                throw new FormatException($"Expected a branch selector followed by '{{' in message '{template}'");
+            }
+
+            index++;
+            branches.TryAdd(selector, index);
+            SkipBranch(template, ref index);
+        }
+    }
+
+    private static void SkipBranch(string template, ref int index)
+    {
+        var depth = 1;
+        for (; index < template.Length; index++)
+        {
+            switch (template[index])
+            {
+                case '\\':
+                    index++;
+                    break;
+                case '{':
+                    depth++;
+                    break;
+                case '}' when --depth == 0:
+                    index++;
+                    return;
+            }
+        }
+
+        throw new FormatException($"Unterminated branch in message '{template}'");
+    }
+
+    private static string ReadWord(string template, ref int index, string stops)
+    {
+        var start = index;
+        while (index < template.Length && stops.IndexOf(template[index]) < 0)
+        {
+            index++;
+        }
+
+        if (index >= template.Length)
+        {
+            throw new FormatException($"Unterminated argument in message '{template}'");
+        }
+
+        return template[start..index].Trim();
+    }
+
+    private static bool IsName(string name)
+    {
+        return name.Length > 0 && name.All(c => char.IsLetterOrDigit(c) || c == '_');
+    }
+
+    private static long Count(object value, string name)
+    {
+        return value switch
+        {
+            ICollection collection => collection.Count,
+            string text when long.TryParse(text, NumberStyles.Integer, CultureInfo.InvariantCulture, out var parsed) => parsed,
+            IEnumerable items and not string => items.Cast<object>().LongCount(),
+            IConvertible number when number is not string => Convert.ToInt64(number, CultureInfo.InvariantCulture),
+            _ => throw new FormatException($"Argument '{name}' is not a number or collection")
+        };
+    }
+
+    private static string Describe(object? value, CultureInfo culture)
+    {
+        return value switch
+        {
+            null => string.Empty,
+            string text => text,
+            IFormattable formattable => formattable.ToString(null, culture),
+            IEnumerable items => string.Join(", ", items.Cast<object?>().Select(item => Describe(item, culture))),
+            _ => value.ToString() ?? string.Empty
+        };
+    }
+}
+
+/// <summary>
+/// The plural categories of CLDR for the languages Minotaur ships messages in and those most often added, reduced
+/// to the integer counts diagnostics use.
+/// </summary>
+public static class PluralRules
+{
+    /// <summary>
+    /// Gets the plural category of a count: "one", "few", "many" or "other".
+    /// </summary>
+    /// <param name="locale">The locale, e.g. "de-DE"; only its language is used.</param>
+    /// <param name="count">The count.</param>
+    /// <returns>The category.</returns>
+    public static string Select(string locale, long count)
+    {
+        ArgumentNullException.ThrowIfNull(locale);
+
+        var n = Math.Abs(count);
+        var separator = locale.IndexOfAny(new[] { '-', '_' });
+        var language = (separator < 0 ? locale : locale[..separator]).ToLowerInvariant();
+        var (mod10, mod100) = (n % 10, n % 100);
+        return language switch
+        {
+            "ja" or "zh" or "ko" or "vi" or "th" or "id" or "ms" => "other",
+            "fr" or "pt" => n <= 1 ? "one" : "other",
+            "ru" or "uk" or "be" => mod10 == 1 && mod100 != 11 ? "one"
+                : mod10 is >= 2 and <= 4 && mod100 is < 12 or > 14 ? "few"
+                : "many",
+            "pl" => n == 1 ? "one"
+                : mod10 is >= 2 and <= 4 && mod100 is < 12 or > 14 ? "few"
+                : "many",
+            "cs" or "sk" => n == 1 ? "one" : n is >= 2 and <= 4 ? "few" : "other",
+            _ => n == 1 ? "one" : "other"
+        };
+    }
+}
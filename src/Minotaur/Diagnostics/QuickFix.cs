@@ -0,0 +1,77 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Parser;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// Applies the fixes that diagnostics carry as a <see cref="TextEdit"/> in their "fix" data, such as the
+/// corrections of misspelled keywords and deprecated syntax.
+/// </summary>
+public static class QuickFix
+{
+    /// <summary>
+    /// The key of a diagnostic's data entry holding its fix.
+    /// </summary>
+    public const string DataKey = "fix";
+
+    /// <summary>
+    /// Applies the fixes of the diagnostics to a text. Fixes are taken in diagnostic order, and a fix that
+    /// overlaps one already taken is skipped.
+    /// </summary>
+    /// <param name="text">The text the diagnostics were reported on.</param>
+    /// <param name="diagnostics">The diagnostics; those without a fix are ignored.</param>
+    /// <param name="lexer">The lexer used to map positions inside fixes token by token, or null to map characters.</param>
+    /// <returns>The fixed text, the diagnostics whose fixes were applied or skipped, and the map from the text to the fixed one.</returns>
+    public static QuickFixResult Apply(string text, IEnumerable<Diagnostic> diagnostics, GrammarLexer? lexer = null)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+        ArgumentNullException.ThrowIfNull(diagnostics);
+
+        var applied = new List<Diagnostic>();
+        var skipped = new List<Diagnostic>();
+        var edits = new List<TextEdit>();
+        foreach (var diagnostic in diagnostics)
+        {
+            if (diagnostic.Data.GetValueOrDefault(DataKey) is not TextEdit fix)
+            {
+                continue;
+            }
+
+            var overlaps = fix.Offset < 0 || fix.Offset + fix.Length > text.Length || edits.Any(e =>
+                (e.Offset < fix.Offset + fix.Length && fix.Offset < e.Offset + e.Length) || e.Offset == fix.Offset);
+            (overlaps ? skipped : applied).Add(diagnostic);
+            if (!overlaps)
+            {
+                edits.Add(fix);
+            }
+        }
+
+        var map = PositionMap.FromEdits(text, edits, lexer);
+        return new QuickFixResult(map.Output, applied, skipped, map);
+    }
+}
+
+/// <summary>
+/// The result of <see cref="QuickFix.Apply"/>.
+/// </summary>
+/// <param name="Text">The fixed text.</param>
+/// <param name="Applied">The diagnostics whose fixes were applied.</param>
+/// <param name="Skipped">The diagnostics whose fixes overlapped an applied one or fell outside the text.</param>
+/// <param name="Map">The map from the original text to the fixed one.</param>
+public sealed record QuickFixResult(string Text, IReadOnlyList<Diagnostic> Applied, IReadOnlyList<Diagnostic> Skipped, PositionMap Map);
@@ -0,0 +1,559 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Parser;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// How a line of a semantic diff changed.
+/// </summary>
+public enum SemanticDiffLineKind
+{
+    /// <summary>
+    /// The line has the same tokens in both versions.
+    /// </summary>
+    Unchanged,
+
+    /// <summary>
+    /// The line is only in the old version.
+    /// </summary>
+    Deleted,
+
+    /// <summary>
+    /// The line is only in the new version.
+    /// </summary>
+    Inserted,
+
+    /// <summary>
+    /// The line is part of a subtree that moved elsewhere, shown where it was.
+    /// </summary>
+    MovedFrom,
+
+    /// <summary>
+    /// The line is part of a subtree that moved here, shown where it is.
+    /// </summary>
+    MovedTo
+}
+
+/// <summary>
+/// A line of a semantic diff. Lines joined by a token that spans them, such as a string with an embedded newline,
+/// are one diff line.
+/// </summary>
+/// <param name="Kind">How the line changed.</param>
+/// <param name="OldLine">The 1-based line in the old version, or null for inserted lines.</param>
+/// <param name="NewLine">The 1-based line in the new version, or null for deleted lines.</param>
+/// <param name="Text">The line's text, from the new version for unchanged lines.</param>
+/// <param name="Highlights">The ranges of <paramref name="Text"/> covered by changed tokens.</param>
+/// <param name="Move">The index of the move in <see cref="SemanticDiff.Moves"/>, or null if the line did not move.</param>
+public sealed record SemanticDiffLine(
+    SemanticDiffLineKind Kind,
+    int? OldLine,
+    int? NewLine,
+    string Text,
+    IReadOnlyList<TextRange> Highlights,
+    int? Move = null);
+
+/// <summary>
+/// A subtree that moved between the versions, possibly with edits inside.
+/// </summary>
+/// <param name="Rule">The rule of the moved subtree.</param>
+/// <param name="OldLocation">The span of the subtree in the old version.</param>
+/// <param name="NewLocation">The span of the subtree in the new version.</param>
+/// <param name="Similarity">The share of tokens the two subtrees have in common, from 0.5 to 1.</param>
+public sealed record SemanticDiffMove(string Rule, SourcePosition OldLocation, SourcePosition NewLocation, double Similarity);
+
+/// <summary>
+/// A diff of two versions of a file for people to read, aligned on their token streams rather than on characters.
+/// Changed lines carry the ranges of the tokens that changed, and subtrees that moved are reported as moves, with
+/// their own edits, instead of as deleted and inserted lines.
+/// </summary>
+/// <remarks>
+/// Tokens are compared by text, so whitespace and line breaks between tokens never count as changes while a string
+/// token is one unit however many lines it spans. A subtree is moved when it is of the same rule as a subtree of the
+/// other version, at least half of the tokens of both are missing from the alignment of the whole files, at least
+/// half of their tokens are the same, and the tokens around one are aligned across the other. The files are then
+/// aligned again without the moved subtrees. Comments and other text between tokens only decide whether lines with
+/// the same tokens are unchanged.
+/// </remarks>
+public sealed class SemanticDiff
+{
+    /// <summary>
+    /// The default number of tokens a subtree needs to be reported as moved.
+    /// </summary>
+    public const int DefaultMinimumMoveTokens = 4;
+
+    // Myers' algorithm keeps O(d²) diagonals for d edits; beyond this many the changed middle is not aligned.
+    private const int MaxAlignmentEdits = 4096;
+
+    private SemanticDiff(IReadOnlyList<SemanticDiffLine> lines, IReadOnlyList<SemanticDiffMove> moves)
+    {
+        Lines = lines;
+        Moves = moves;
+    }
+
+    /// <summary>
+    /// Gets the lines of both versions in diff order: unchanged lines in their place, and the deleted and moved-away
+    /// lines of each change before its inserted and moved-in lines.
+    /// </summary>
+    public IReadOnlyList<SemanticDiffLine> Lines { get; }
+
+    /// <summary>
+    /// Gets the moved subtrees.
+    /// </summary>
+    public IReadOnlyList<SemanticDiffMove> Moves { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether any line changed.
+    /// </summary>
+    public bool HasChanges => Lines.Any(l => l.Kind != SemanticDiffLineKind.Unchanged);
+
+    /// <summary>
+    /// Compares two parses of a file.
+    /// </summary>
+    /// <param name="oldParse">The parse of the old version.</param>
+    /// <param name="newParse">The parse of the new version.</param>
+    /// <param name="minimumMoveTokens">The number of tokens a subtree needs to be reported as moved.</param>
+    /// <returns>The diff.</returns>
+    /// <exception cref="ArgumentException">A parse has no tree.</exception>
+    public static SemanticDiff Create(ParseResult oldParse, ParseResult newParse, int minimumMoveTokens = DefaultMinimumMoveTokens)
+    {
+        ArgumentNullException.ThrowIfNull(oldParse);
+        ArgumentNullException.ThrowIfNull(newParse);
+        ArgumentOutOfRangeException.ThrowIfLessThan(minimumMoveTokens, 1);
+
+        if (oldParse.Tree == null || newParse.Tree == null)
+        {
+            throw new ArgumentException("Only parse results with trees can be compared");
+        }
+
+        var older = new Version(oldParse.Input, oldParse.Tree, minimumMoveTokens);
+        var newer = new Version(newParse.Input, newParse.Tree, minimumMoveTokens);
+        var ids = new Dictionary<string, int>(StringComparer.Ordinal);
+        var a = older.Tokens.Select(t => Intern(ids, t.Text)).ToArray();
+        var b = newer.Tokens.Select(t => Intern(ids, t.Text)).ToArray();
+
+        var first = Align(a, b);
+        var moves = FindMoves(older, newer, a, b, first);
+
+        // Aligning without the moved tokens keeps them from pinning the rest of the file to stray matches.
+        var keptOld = Enumerable.Range(0, a.Length).Where(i => older.Move[i] < 0).ToArray();
+        var keptNew = Enumerable.Range(0, b.Length).Where(j => newer.Move[j] < 0).ToArray();
+        foreach (var (i, j) in Align(keptOld.Select(i => a[i]).ToArray(), keptNew.Select(j => b[j]).ToArray()))
+        {
+            older.Match[keptOld[i]] = keptNew[j];
+            newer.Match[keptNew[j]] = keptOld[i];
+        }
+
+        foreach (var move in moves)
+        {
+            foreach (var (i, j) in Align(a[move.Old.Start..move.Old.End], b[move.New.Start..move.New.End]))
+            {
+                older.Match[move.Old.Start + i] = move.New.Start + j;
+                newer.Match[move.New.Start + j] = move.Old.Start + i;
+            }
+        }
+
+        var lines = Render(older, newer, moves);
+        return new SemanticDiff(lines, moves.Select(m => new SemanticDiffMove(m.Old.Node.RuleName, m.Old.Node.SourcePosition!, m.New.Node.SourcePosition!, m.Similarity)).ToList());
+    }
+
+    private static List<MovePair> FindMoves(Version older, Version newer, int[] a, int[] b, List<(int Old, int New)> first)
+    {
+        var oldMatched = new bool[a.Length];
+        var newMatched = new bool[b.Length];
+        foreach (var (i, j) in first)
+        {
+            oldMatched[i] = true;
+            newMatched[j] = true;
+        }
+
+        // Subtrees are listed children first; reversed, ancestors win ties below.
+        var oldCandidates = older.Subtrees.Where(s => IsMostlyUnmatched(s, oldMatched)).Reverse().ToList();
+        var newCandidates = newer.Subtrees.Where(s => IsMostlyUnmatched(s, newMatched)).Reverse().ToList();
+        var pairs = new List<MovePair>();
+        foreach (var oldSubtree in oldCandidates)
+        {
+            foreach (var newSubtree in newCandidates.Where(s => s.Node.RuleName == oldSubtree.Node.RuleName))
+            {
+                var total = oldSubtree.Length + newSubtree.Length;
+                if (2.0 * Math.Min(oldSubtree.Length, newSubtree.Length) / total < 0.5 || !Crosses(oldSubtree, newSubtree, first))
+                {
+                    continue;
+                }
+
+                var common = Align(a[oldSubtree.Start..oldSubtree.End], b[newSubtree.Start..newSubtree.End]).Count;
+                if (2.0 * common / total >= 0.5)
+                {
+                    pairs.Add(new MovePair(oldSubtree, newSubtree, common));
+                }
+            }
+        }
+
+        // The pairs sharing the most tokens win, so a moved function is not split up by a statement of it that
+        // happens to be unchanged.
+        var moves = new List<MovePair>();
+        foreach (var pair in pairs.OrderByDescending(p => p.Common))
+        {
+            if (moves.Any(m => m.Old.Overlaps(pair.Old) || m.New.Overlaps(pair.New)))
+            {
+                continue;
+            }
+
+            moves.Add(pair);
+        }
+
+        moves.Sort((x, y) => x.New.Start.CompareTo(y.New.Start));
+        for (var index = 0; index < moves.Count; index++)
+        {
+            Array.Fill(older.Move, index, moves[index].Old.Start, moves[index].Old.Length);
+            Array.Fill(newer.Move, index, moves[index].New.Start, moves[index].New.Length);
+        }
+
+        return moves;
+    }
+
+    private static bool IsMostlyUnmatched(Subtree subtree, bool[] matched)
+    {
+        var unmatched = 0;
+        for (var i = subtree.Start; i < subtree.End; i++)
+        {
+            unmatched += matched[i] ? 0 : 1;
+        }
+
+        return 2 * unmatched >= subtree.Length;
+    }
+
+    // A subtree moved if tokens aligned before it in one version are aligned after it in the other.
+    private static bool Crosses(Subtree oldSubtree, Subtree newSubtree, List<(int Old, int New)> alignment)
+    {
+        foreach (var (i, j) in alignment)
+        {
+            if ((i < oldSubtree.Start && j >= newSubtree.End) || (i >= oldSubtree.End && j < newSubtree.Start))
+            {
+                return true;
+            }
+        }
+
+        return false;
+    }
+
+    private static List<SemanticDiffLine> Render(Version older, Version newer, List<MovePair> moves)
+    {
+        // Lines are aligned on keys: a line of the old version gets the key of the new line holding exactly the
+        // tokens its own tokens are aligned to, and lines without tokens are keyed by their trimmed text.
+        var keys = new Dictionary<string, int>(StringComparer.Ordinal);
+        var newKeys = newer.Lines.Select((line, n) => Intern(keys, line.Tokens.Count == 0 ? "\u0001" + line.Text.Trim() : $"\u0002{n}\u0000{line.Trivia}")).ToArray();
+        var oldKeys = older.Lines.Select((line, o) =>
+        {
+            if (line.Tokens.Count == 0)
+            {
+                return Intern(keys, "\u0001" + line.Text.Trim());
+            }
+
+            var target = older.Match[line.Tokens[0]] is var j && j >= 0 ? newer.LineOf[j] : -1;
+            var same = target >= 0
+                && newer.Lines[target].Tokens.Count == line.Tokens.Count
+                && line.Tokens.Select(i => older.Match[i]).SequenceEqual(newer.Lines[target].Tokens)
+                && older.Move[line.Tokens[0]] < 0;
+            return Intern(keys, same ? $"\u0002{target}\u0000{line.Trivia}" : $"\u0003{o}");
+        }).ToArray();
+
+        var result = new List<SemanticDiffLine>();
+        int x = 0, y = 0;
+        foreach (var (o, n) in Align(oldKeys, newKeys).Append((older.Lines.Count, newer.Lines.Count)))
+        {
+            for (; x < o; x++)
+            {
+                var line = older.Lines[x];
+                var move = MoveOf(line, older, moves, m => m.Old);
+                result.Add(new SemanticDiffLine(
+                    move == null ? SemanticDiffLineKind.Deleted : SemanticDiffLineKind.MovedFrom,
+                    line.Number,
+                    null,
+                    line.Text,
+                    Highlights(line, older, move),
+                    move));
+            }
+
+            for (; y < n; y++)
+            {
+                var line = newer.Lines[y];
+                var move = MoveOf(line, newer, moves, m => m.New);
+                result.Add(new SemanticDiffLine(
+                    move == null ? SemanticDiffLineKind.Inserted : SemanticDiffLineKind.MovedTo,
+                    null,
+                    line.Number,
+                    line.Text,
+                    Highlights(line, newer, move),
+                    move));
+            }
+
+            if (o < older.Lines.Count)
+            {
+                result.Add(new SemanticDiffLine(SemanticDiffLineKind.Unchanged, older.Lines[o].Number, newer.Lines[n].Number, newer.Lines[n].Text, Array.Empty<TextRange>()));
+                x = o + 1;
+                y = n + 1;
+            }
+        }
+
+        return result;
+    }
+
+    private static int? MoveOf(Line line, Version version, List<MovePair> moves, Func<MovePair, Subtree> side)
+    {
+        if (line.Tokens.Count > 0)
+        {
+            var move = version.Move[line.Tokens[0]];
+            return move >= 0 && line.Tokens.All(t => version.Move[t] == move) ? move : null;
+        }
+
+        // Blank and comment lines move with the subtree they are inside.
+        var index = moves.FindIndex(m => side(m).FirstLine < line.Number && line.Number < side(m).LastLine);
+        return index >= 0 ? index : null;
+    }
+
+    private static IReadOnlyList<TextRange> Highlights(Line line, Version version, int? move)
+    {
+        return line.Tokens
+            .Where(t => version.Match[t] < 0 || (move == null && version.Move[t] >= 0))
+            .Select(t => new TextRange(version.Tokens[t].Offset - line.Start, version.Tokens[t].Length))
+            .ToList();
+    }
+
+    private static int Intern(Dictionary<string, int> ids, string key)
+    {
+        if (!ids.TryGetValue(key, out var id))
+        {
+            ids[key] = id = ids.Count;
+        }
+
+        return id;
+    }
+
+    /// <summary>
+    /// Aligns two sequences with Myers' algorithm after removing their common prefix and suffix.
+    /// </summary>
+    /// <param name="a">The old sequence.</param>
+    /// <param name="b">The new sequence.</param>
+    /// <returns>The aligned index pairs, in order.</returns>
+    internal static List<(int Old, int New)> Align(int[] a, int[] b)
+    {
+        var matches = new List<(int, int)>();
+        var prefix = 0;
+        while (prefix < a.Length && prefix < b.Length && a[prefix] == b[prefix])
+        {
+            matches.Add((prefix, prefix));
+            prefix++;
+        }
+
+        var suffix = 0;
+        while (suffix < a.Length - prefix && suffix < b.Length - prefix && a[a.Length - 1 - suffix] == b[b.Length - 1 - suffix])
+        {
+            suffix++;
+        }
+
+        int n = a.Length - prefix - suffix, m = b.Length - prefix - suffix;
+        var offset = n + m + 1;
+        var v = new int[2 * offset + 1];
+
+        // Each step keeps the diagonals it started from, [-d - 1, d + 1], for the backtrack.
+        var trace = new List<int[]>();
+        var done = n == 0 && m == 0;
+        for (var d = 0; d <= n + m && !done; d++)
+        {
+            if (d > MaxAlignmentEdits)
+            {
+                // Too different to align affordably: the middle counts as replaced.
+                trace.Clear();
+                break;
+            }
+
+            trace.Add(v[(offset - d - 1)..(offset + d + 2)]);
+            for (var k = -d; k <= d && !done; k += 2)
+            {
+                var x = k == -d || (k != d && v[offset + k - 1] < v[offset + k + 1]) ? v[offset + k + 1] : v[offset + k - 1] + 1;
+                var y = x - k;
+                while (x < n && y < m && a[prefix + x] == b[prefix + y])
+                {
+                    x++;
+                    y++;
+                }
+
+                v[offset + k] = x;
+                done = x >= n && y >= m;
+            }
+        }
+
+        var middle = new List<(int, int)>();
+        if (done && trace.Count > 0)
+        {
+            int x = n, y = m;
+            for (var d = trace.Count - 1; d >= 0; d--)
+            {
+                var previous = trace[d];
+                var k = x - y;
+                var previousK = k == -d || (k != d && previous[d + k] < previous[d + k + 2]) ? k + 1 : k - 1;
+                var previousX = previous[d + 1 + previousK];
+                var previousY = previousX - previousK;
+                while (x > previousX && y > previousY)
+                {
+                    middle.Add((prefix + x - 1, prefix + y - 1));
+                    x--;
+                    y--;
+                }
+
+                x = previousX;
+                y = previousY;
+            }
+        }
+
+        middle.Reverse();
+        matches.AddRange(middle);
+        for (var s = suffix; s > 0; s--)
+        {
+            matches.Add((a.Length - s, b.Length - s));
+        }
+
+        return matches;
+    }
+
+    private sealed record TokenSpan(string Text, int Offset, int Length);
+
+    private sealed record Subtree(NonTerminalNode Node, int Start, int End, int FirstLine, int LastLine)
+    {
+        public int Length => End - Start;
+
+        public bool Overlaps(Subtree other) => Start < other.End && other.Start < End;
+    }
+
+    private sealed record MovePair(Subtree Old, Subtree New, int Common)
+    {
+        public double Similarity => 2.0 * Common / (Old.Length + New.Length);
+    }
+
+    private sealed record Line(int Number, int Start, string Text, List<int> Tokens, string Trivia);
+
+    private sealed class Version
+    {
+        public Version(string input, CognitiveGraphNode tree, int minimumMoveTokens)
+        {
+            var index = new LineIndex(input);
+            var pending = new Stack<(CognitiveGraphNode Node, int Start, bool Exit)>();
+            pending.Push((tree, 0, false));
+            while (pending.Count > 0)
+            {
+                var (node, start, exit) = pending.Pop();
+                if (exit)
+                {
+                    if (node is NonTerminalNode nonTerminal && Tokens.Count - start >= minimumMoveTokens)
+                    {
+                        var firstLine = index.GetLineColumn(Tokens[start].Offset).Line;
+                        var lastLine = index.GetLineColumn(Tokens[^1].Offset + Tokens[^1].Length - 1).Line;
+                        Subtrees.Add(new Subtree(nonTerminal, start, Tokens.Count, firstLine, lastLine));
+                    }
+
+                    continue;
+                }
+
+                if (node is TerminalNode && node.SourcePosition is { Length: > 0 } position)
+                {
+                    Tokens.Add(new TokenSpan(input.Substring(position.Offset, position.Length), position.Offset, position.Length));
+                    continue;
+                }
+
+                pending.Push((node, Tokens.Count, true));
+                for (var i = node.Children.Count - 1; i >= 0; i--)
+                {
+                    pending.Push((node.Children[i], 0, false));
+                }
+            }
+
+            Match = Enumerable.Repeat(-1, Tokens.Count).ToArray();
+            Move = Enumerable.Repeat(-1, Tokens.Count).ToArray();
+            LineOf = new int[Tokens.Count];
+
+            // A token spanning line breaks joins its lines into one diff line.
+            var joined = new bool[index.LineCount + 1];
+            var tokenLines = Tokens.Select(t => (First: index.GetLineColumn(t.Offset).Line, Last: index.GetLineColumn(t.Offset + t.Length - 1).Line)).ToList();
+            foreach (var (firstLine, lastLine) in tokenLines)
+            {
+                for (var line = firstLine; line < lastLine; line++)
+                {
+                    joined[line] = true;
+                }
+            }
+
+            var token = 0;
+            for (var line = 1; line <= index.LineCount; line++)
+            {
+                var first = line;
+                while (joined[line])
+                {
+                    line++;
+                }
+
+                var start = index.GetLineStart(first);
+                var end = index.GetLineContentEnd(line);
+                var tokens = new List<int>();
+                while (token < Tokens.Count && tokenLines[token].First <= line)
+                {
+                    LineOf[token] = Lines.Count;
+                    tokens.Add(token++);
+                }
+
+                Lines.Add(new Line(first, start, input[start..end], tokens, Trivia(input, start, end, tokens)));
+            }
+        }
+
+        public List<TokenSpan> Tokens { get; } = new();
+
+        public List<Subtree> Subtrees { get; } = new();
+
+        public List<Line> Lines { get; } = new();
+
+        public int[] Match { get; }
+
+        public int[] Move { get; }
+
+        public int[] LineOf { get; }
+
+        // The text between the tokens of a line without whitespace, so comment edits change a line but reindenting
+        // does not.
+        private string Trivia(string input, int start, int end, List<int> tokens)
+        {
+            var trivia = new StringBuilder();
+            var position = start;
+            foreach (var span in tokens.Select(t => Tokens[t]).Append(new TokenSpan(string.Empty, end, 0)))
+            {
+                for (var i = position; i < Math.Min(span.Offset, end); i++)
+                {
+                    if (!char.IsWhiteSpace(input[i]))
+                    {
+                        trivia.Append(input[i]);
+                    }
+                }
+
+                position = Math.Max(position, span.Offset + span.Length);
+            }
+
+            return trivia.ToString();
+        }
+    }
+}
@@ -0,0 +1,327 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text.Json;
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// Translates positions in a generated or preprocessed file to positions in the files it was produced from.
+/// Mappers are built from a JSON source map (revision 3) or from <c>#line</c>-style directives in the generated
+/// text, and are applied when diagnostics are rendered; the parser itself only ever sees generated positions.
+/// </summary>
+public class SourceMapper
+{
+    /// <summary>
+    /// The directive recognized when a grammar does not declare one: C/C# style <c>#line 12 "file"</c>,
+    /// where <c>#line default</c> and <c>#line hidden</c> end the mapped region.
+    /// </summary>
+    public const string DefaultLineDirective = @"^[ \t]*#[ \t]*line[ \t]+(?:(?<line>\d+)(?:[ \t]+""(?<file>[^""]*)"")?|default|hidden)[ \t]*\r?$";
+
+    private const string Base64Digits = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    // Segments per generated line (1-based), sorted by generated column.
+    private readonly Dictionary<int, List<Segment>> _lines;
+
+    private SourceMapper(Dictionary<int, List<Segment>> lines, IReadOnlyList<string> sources, string? generatedFile)
+    {
+        _lines = lines;
+        Sources = sources;
+        GeneratedFile = generatedFile;
+    }
+
+    /// <summary>
+    /// Gets the generated file the mapper applies to, if known.
+    /// </summary>
+    public string? GeneratedFile { get; }
+
+    /// <summary>
+    /// Gets the original source files referenced by the mapper.
+    /// </summary>
+    public IReadOnlyList<string> Sources { get; }
+
+    /// <summary>
+    /// Reads a JSON source map (revision 3).
+    /// </summary>
+    /// <param name="json">The source map JSON.</param>
+    /// <returns>The mapper.</returns>
+    /// <exception cref="FormatException">The source map is malformed or uses an unsupported feature.</exception>
+    public static SourceMapper FromJson(string json)
+    {
+        ArgumentNullException.ThrowIfNull(json);
+
+        using var document = JsonDocument.Parse(json);
+        var root = document.RootElement;
+
+        if (root.TryGetProperty("sections", out _))
+        {
+            throw new FormatException("Indexed source maps (\"sections\") are not supported");
+        }
+
+        if (!root.TryGetProperty("version", out var version) || version.GetInt32() != 3)
+        {
+            throw new FormatException("Only version 3 source maps are supported");
+        }
+
+        var sourceRoot = root.TryGetProperty("sourceRoot", out var rootElement) ? rootElement.GetString() ?? string.Empty : string.Empty;
+        if (sourceRoot.Length > 0 && !sourceRoot.EndsWith('/'))
+        {
+            sourceRoot += "/";
+        }
+
+        var sources = root.GetProperty("sources").EnumerateArray().Select(s => sourceRoot + s.GetString()).ToList();
+        var names = root.TryGetProperty("names", out var namesElement)
+            ? namesElement.EnumerateArray().Select(n => n.GetString() ?? string.Empty).ToList()
+            : new List<string>();
+        var file = root.TryGetProperty("file", out var fileElement) ? fileElement.GetString() : null;
+
+        var lines = DecodeMappings(root.GetProperty("mappings").GetString() ?? string.Empty, sources, names);
+        return new SourceMapper(lines, sources, file);
+    }
+
+    /// <summary>
+    /// Builds a mapper from line directives in generated text. A directive maps the line after it to the given
+    /// line of the given file (or of the previous file), and following lines continue from there.
+    /// </summary>
+    /// <param name="generatedText">The generated text.</param>
+    /// <param name="generatedFile">The generated file name, used when a directive names no file.</param>
+    /// <param name="directivePattern">A regex matching a whole directive line, with a "line" group and an optional "file" group.
+    /// A match without a "line" group ends the mapped region. Defaults to <see cref="DefaultLineDirective"/>.</param>
+    /// <returns>The mapper.</returns>
+    public static SourceMapper FromLineDirectives(string generatedText, string? generatedFile = null, string? directivePattern = null)
+    {
+        ArgumentNullException.ThrowIfNull(generatedText);
+
+        var directive = new Regex(directivePattern ?? DefaultLineDirective, RegexOptions.CultureInvariant);
+        var lines = new Dictionary<int, List<Segment>>();
+        var sources = new List<string>();
+        string? currentFile = generatedFile;
+        int? nextLine = null;
+
+        var generatedLines = generatedText.Split('\n');
+        for (var i = 0; i < generatedLines.Length; i++)
+        {
+            var match = directive.Match(generatedLines[i]);
+            if (match.Success)
+            {
+                if (match.Groups["line"].Success)
+                {
+                    nextLine = int.Parse(match.Groups["line"].Value, CultureInfo.InvariantCulture);
+                    currentFile = match.Groups["file"].Success ? match.Groups["file"].Value : currentFile;
+                }
+                else
+                {
+                    nextLine = null;
+                }
+
+                continue;
+            }
+
+            if (nextLine is { } originalLine && currentFile != null)
+            {
+                if (!sources.Contains(currentFile))
+                {
+                    sources.Add(currentFile);
+                }
+
+                lines[i + 1] = new List<Segment> { new(1, currentFile, originalLine, 1, null) };
+                nextLine = originalLine + 1;
+            }
+        }
+
+        return new SourceMapper(lines, sources, generatedFile);
+    }
+
+    /// <summary>
+    /// Builds a mapper from the line directives a grammar declares in its "LineDirective" metadata,
+    /// or <see cref="DefaultLineDirective"/> if it declares none.
+    /// </summary>
+    /// <param name="generatedText">The generated text.</param>
+    /// <param name="grammar">The grammar of the generated text.</param>
+    /// <param name="generatedFile">The generated file name.</param>
+    /// <returns>The mapper.</returns>
+    public static SourceMapper FromLineDirectives(string generatedText, Grammar grammar, string? generatedFile = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        return FromLineDirectives(generatedText, generatedFile, grammar.Metadata.GetValueOrDefault("LineDirective"));
+    }
+
+    /// <summary>
+    /// Maps a 1-based generated line and column to the original location.
+    /// </summary>
+    /// <param name="line">The 1-based generated line.</param>
+    /// <param name="column">The 1-based generated column.</param>
+    /// <returns>The original location, or null if the position is not mapped.</returns>
+    public OriginalLocation? Map(int line, int column)
+    {
+        if (!_lines.TryGetValue(line, out var segments))
+        {
+            return null;
+        }
+
+        // The mapping of a position is the last segment starting at or before it.
+        Segment? segment = null;
+        foreach (var candidate in segments)
+        {
+            if (candidate.GeneratedColumn > column)
+            {
+                break;
+            }
+
+            segment = candidate;
+        }
+
+        if (segment?.Source == null)
+        {
+            return null;
+        }
+
+        return new OriginalLocation(segment.Source, segment.OriginalLine, segment.OriginalColumn + column - segment.GeneratedColumn)
+        {
+            Name = segment.Name
+        };
+    }
+
+    /// <summary>
+    /// Maps the start of a generated position to the original location.
+    /// </summary>
+    /// <param name="position">The generated position.</param>
+    /// <returns>The original location, or null if the position is not mapped.</returns>
+    public OriginalLocation? Map(SourcePosition position)
+    {
+        ArgumentNullException.ThrowIfNull(position);
+        return Map(position.Line, position.Column);
+    }
+
+    private static Dictionary<int, List<Segment>> DecodeMappings(string mappings, List<string> sources, List<string> names)
+    {
+        var lines = new Dictionary<int, List<Segment>>();
+        int source = 0, originalLine = 0, originalColumn = 0, name = 0;
+        var generatedLine = 1;
+        var fields = new List<int>(5);
+
+        foreach (var lineText in mappings.Split(';'))
+        {
+            var generatedColumn = 0;
+            var segments = new List<Segment>();
+
+            foreach (var segmentText in lineText.Split(',', StringSplitOptions.RemoveEmptyEntries))
+            {
+                fields.Clear();
+                DecodeVlq(segmentText, fields);
+                if (fields.Count is not (1 or 4 or 5))
+                {
+                    throw new FormatException($"Source map segment '{segmentText}' has {fields.Count} fields");
+                }
+
+                generatedColumn += fields[0];
+                if (fields.Count == 1)
+                {
+                    segments.Add(new Segment(generatedColumn + 1, null, 0, 0, null));
+                    continue;
+                }
+
+                source += fields[1];
+                originalLine += fields[2];
+                originalColumn += fields[3];
+                if (source < 0 || source >= sources.Count)
+                {
+                    throw new FormatException($"Source map segment '{segmentText}' refers to unknown source {source}");
+                }
+
+                string? segmentName = null;
+                if (fields.Count == 5)
+                {
+                    name += fields[4];
+                    segmentName = name >= 0 && name < names.Count ? names[name] : null;
+                }
+
+                segments.Add(new Segment(generatedColumn + 1, sources[source], originalLine + 1, originalColumn + 1, segmentName));
+            }
+
+            if (segments.Count > 0)
+            {
+                segments.Sort((a, b) => a.GeneratedColumn.CompareTo(b.GeneratedColumn));
+                lines[generatedLine] = segments;
+            }
+
+            generatedLine++;
+        }
+
+        return lines;
+    }
+
+    private static void DecodeVlq(string text, List<int> values)
+    {
+        var value = 0;
+        var shift = 0;
+
+        foreach (var c in text)
+        {
+            var digit = Base64Digits.IndexOf(c);
+            if (digit < 0)
+            {
+                throw new FormatException($"Invalid base64 VLQ character '{c}' in source map");
+            }
+
+            value += (digit & 31) << shift;
+            if ((digit & 32) != 0)
+            {
+                shift += 5;
+                continue;
+            }
+
+            values.Add((value & 1) != 0 ? -(value >> 1) : value >> 1);
+            value = 0;
+            shift = 0;
+        }
+
+        if (shift != 0)
+        {
+            throw new FormatException($"Truncated base64 VLQ value '{text}' in source map");
+        }
+    }
+
+    private sealed record Segment(int GeneratedColumn, string? Source, int OriginalLine, int OriginalColumn, string? Name);
+}
+
+/// <summary>
+/// A position in an original source file.
+/// </summary>
+/// <param name="SourceFile">The original file.</param>
+/// <param name="Line">The 1-based line.</param>
+/// <param name="Column">The 1-based column.</param>
+public sealed record OriginalLocation(string SourceFile, int Line, int Column)
+{
+    /// <summary>
+    /// Gets the original name of the mapped symbol, if the source map records one.
+    /// </summary>
+    public string? Name { get; init; }
+
+    /// <summary>
+    /// Returns the location in "file:line:column" form.
+    /// </summary>
+    /// <returns>The location description.</returns>
+    public override string ToString()
+    {
+        return $"{SourceFile}:{Line}:{Column}";
+    }
+}
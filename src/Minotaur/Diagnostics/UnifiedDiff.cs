@@ -0,0 +1,369 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using System.Text.RegularExpressions;
+
+namespace Minotaur.Diagnostics;
+
+/// <summary>
+/// A patch in unified diff format, as written by <c>diff -u</c> and <c>git diff</c>.
+/// </summary>
+public sealed class UnifiedDiff
+{
+    private static readonly Regex HunkHeader = new(@"^@@ -(?<oldStart>\d+)(?:,(?<oldCount>\d+))? \+(?<newStart>\d+)(?:,(?<newCount>\d+))? @@", RegexOptions.CultureInvariant);
+    private static readonly Regex AnyHunkHeader = new(@"^@@ -\d+(?:,\d+)? \+\d+(?:,\d+)? @@", RegexOptions.CultureInvariant | RegexOptions.Multiline);
+
+    private UnifiedDiff(List<FileDiff> files)
+    {
+        Files = files;
+    }
+
+    /// <summary>
+    /// Gets the changed files, in patch order.
+    /// </summary>
+    public IReadOnlyList<FileDiff> Files { get; }
+
+    /// <summary>
+    /// Determines whether text looks like a unified diff rather than a source file.
+    /// </summary>
+    /// <param name="text">The text.</param>
+    /// <returns>True if the text has a hunk header.</returns>
+    public static bool IsPatch(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        return AnyHunkHeader.IsMatch(text);
+    }
+
+    /// <summary>
+    /// Parses a unified diff. Text outside file headers and hunks, such as commit messages, is ignored.
+    /// </summary>
+    /// <param name="text">The patch text.</param>
+    /// <returns>The parsed patch.</returns>
+    /// <exception cref="FormatException">A hunk has fewer lines than its header says.</exception>
+    public static UnifiedDiff Parse(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var lines = text.ReplaceLineEndings("\n").Split('\n');
+        var files = new List<FileDiff>();
+        string? oldPath = null;
+        string? newPath = null;
+        List<DiffHunk>? hunks = null;
+
+        for (var i = 0; i < lines.Length; i++)
+        {
+            var line = lines[i];
+            if (line.StartsWith("--- ", StringComparison.Ordinal) && i + 1 < lines.Length && lines[i + 1].StartsWith("+++ ", StringComparison.Ordinal))
+            {
+                oldPath = ReadPath(line[4..], "a/");
+                newPath = ReadPath(lines[++i][4..], "b/");
+                hunks = new List<DiffHunk>();
+                files.Add(new FileDiff(oldPath, newPath, hunks));
+                continue;
+            }
+
+            var header = HunkHeader.Match(line);
+            if (!header.Success)
+            {
+                continue;
+            }
+
+            if (hunks == null)
+            {
+                // A bare hunk without file headers belongs to an unnamed file.
+                hunks = new List<DiffHunk>();
+                files.Add(new FileDiff(null, null, hunks));
+            }
+
+            var oldCount = header.Groups["oldCount"].Success ? int.Parse(header.Groups["oldCount"].Value) : 1;
+            var newCount = header.Groups["newCount"].Success ? int.Parse(header.Groups["newCount"].Value) : 1;
+            var body = new List<string>();
+            int oldSeen = 0, newSeen = 0;
+            while (oldSeen < oldCount || newSeen < newCount)
+            {
+                if (++i >= lines.Length)
+                {
+                    throw new FormatException($"Hunk '{line}' ends after {oldSeen} of {oldCount} old and {newSeen} of {newCount} new lines");
+                }
+
+                // Some tools strip the space that marks an empty context line.
+                var hunkLine = lines[i].Length == 0 ? " " : lines[i];
+                switch (hunkLine[0])
+                {
+                    case ' ':
+                        oldSeen++;
+                        newSeen++;
+                        break;
+                    case '-':
+                        oldSeen++;
+                        break;
+                    case '+':
+                        newSeen++;
+                        break;
+                    case '\\':
+                        continue;
+                    default:
+                        throw new FormatException($"Hunk '{line}' has a line starting with neither ' ', '-' nor '+': {hunkLine}");
+                }
+
+                body.Add(hunkLine);
+            }
+
+            hunks.Add(new DiffHunk(int.Parse(header.Groups["oldStart"].Value), oldCount, int.Parse(header.Groups["newStart"].Value), newCount, body));
+        }
+
+        return new UnifiedDiff(files);
+    }
+
+    /// <summary>
+    /// Writes the line changes between two versions of a file as a unified diff with <c>a/</c> and <c>b/</c>
+    /// path prefixes, which <see cref="Parse"/> reads back.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <param name="oldText">The text before the change.</param>
+    /// <param name="newText">The text after the change.</param>
+    /// <param name="context">The number of unchanged lines shown around each change.</param>
+    /// <returns>The diff, or an empty string if the texts have the same lines.</returns>
+    public static string Create(string path, string oldText, string newText, int context = 3)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        ArgumentNullException.ThrowIfNull(oldText);
+        ArgumentNullException.ThrowIfNull(newText);
+
+        var edits = Testing.Snapshot.LineEdits(Testing.Snapshot.SplitLines(oldText), Testing.Snapshot.SplitLines(newText));
+        if (edits.All(e => e.Op == ' '))
+        {
+            return string.Empty;
+        }
+
+        // Absolute paths lose their leading slash to the prefixes; Find still matches them as a suffix.
+        var name = path.Replace('\\', '/').TrimStart('/');
+        var text = new StringBuilder();
+        text.Append("--- a/").Append(name).Append('\n');
+        text.Append("+++ b/").Append(name).Append('\n');
+        var i = 0;
+        while (i < edits.Count)
+        {
+            if (edits[i].Op == ' ')
+            {
+                i++;
+                continue;
+            }
+
+            // Changes closer than twice the context share a hunk.
+            var start = Math.Max(0, i - context);
+            var end = i;
+            while (edits.FindIndex(end + 1, e => e.Op != ' ') is var next && next >= 0 && next - end <= 2 * context)
+            {
+                end = next;
+            }
+
+            end = Math.Min(edits.Count - 1, end + context);
+            var hunk = edits.GetRange(start, end - start + 1);
+            var oldCount = hunk.Count(e => e.Op != '+');
+            var newCount = hunk.Count(e => e.Op != '-');
+            text.Append("@@ -").Append(oldCount == 0 ? edits[start].OldLine : edits[start].OldLine + 1).Append(',').Append(oldCount)
+                .Append(" +").Append(newCount == 0 ? edits[start].NewLine : edits[start].NewLine + 1).Append(',').Append(newCount).Append(" @@\n");
+            foreach (var edit in hunk)
+            {
+                text.Append(edit.Op).Append(edit.Text).Append('\n');
+            }
+
+            i = end + 1;
+        }
+
+        return text.ToString();
+    }
+
+    /// <summary>
+    /// Finds the changes of a file by its new path, or its old path if it was deleted. Paths match when they are
+    /// equal or one ends with the other as a path suffix, so a patch relative to the repository root matches
+    /// absolute paths.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <returns>The file's changes, or null if the patch does not touch it.</returns>
+    public FileDiff? Find(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        var normalized = path.Replace('\\', '/');
+        return Files.FirstOrDefault(f => PathMatches(f.NewPath ?? f.OldPath, normalized))
+            ?? (Files.Count == 1 && Files[0].NewPath == null && Files[0].OldPath == null ? Files[0] : null);
+    }
+
+    private static string? ReadPath(string text, string prefix)
+    {
+        // Timestamps follow the path after a tab.
+        var path = text.Split('\t')[0].Trim();
+        if (path == "/dev/null")
+        {
+            return null;
+        }
+
+        return path.StartsWith(prefix, StringComparison.Ordinal) ? path[prefix.Length..] : path;
+    }
+
+    private static bool PathMatches(string? patchPath, string path)
+    {
+        if (patchPath == null)
+        {
+            return false;
+        }
+
+        return patchPath == path ||
+            path.EndsWith("/" + patchPath, StringComparison.Ordinal) ||
+            patchPath.EndsWith("/" + path, StringComparison.Ordinal);
+    }
+}
+
+/// <summary>
+/// The changes a patch makes to one file.
+/// </summary>
+public sealed class FileDiff
+{
+    internal FileDiff(string? oldPath, string? newPath, IReadOnlyList<DiffHunk> hunks)
+    {
+        OldPath = oldPath;
+        NewPath = newPath;
+        Hunks = hunks;
+    }
+
+    /// <summary>
+    /// Gets the path before the change, or null for a new file.
+    /// </summary>
+    public string? OldPath { get; }
+
+    /// <summary>
+    /// Gets the path after the change, or null for a deleted file.
+    /// </summary>
+    public string? NewPath { get; }
+
+    /// <summary>
+    /// Gets the hunks, in file order.
+    /// </summary>
+    public IReadOnlyList<DiffHunk> Hunks { get; }
+
+    /// <summary>
+    /// Gets the lines of the new version the hunks add or replace. A pure deletion is an empty range before the
+    /// line that follows it.
+    /// </summary>
+    /// <returns>The changed lines.</returns>
+    public ChangedLines GetChangedLines()
+    {
+        var ranges = new List<LineRange>();
+        foreach (var hunk in Hunks)
+        {
+            // A hunk with no new lines names the line before it.
+            var line = hunk.NewCount == 0 ? hunk.NewStart + 1 : hunk.NewStart;
+            int? start = null;
+            var changed = false;
+            foreach (var text in hunk.Lines.Append(" "))
+            {
+                if (text[0] == ' ')
+                {
+                    if (changed)
+                    {
+                        var first = start ?? line;
+                        ranges.Add(new LineRange(first, line - first));
+                    }
+
+                    start = null;
+                    changed = false;
+                    line++;
+                    continue;
+                }
+
+                changed = true;
+                if (text[0] == '+')
+                {
+                    start ??= line;
+                    line++;
+                }
+            }
+        }
+
+        return new ChangedLines(ranges);
+    }
+
+    /// <summary>
+    /// Recovers the old version of the file from its new version by undoing the hunks.
+    /// </summary>
+    /// <param name="newText">The new version.</param>
+    /// <returns>The old version, with "\n" line endings.</returns>
+    /// <exception cref="InvalidOperationException">The new version does not have the lines the patch added or kept.</exception>
+    public string ReverseApply(string newText)
+    {
+        ArgumentNullException.ThrowIfNull(newText);
+
+        var lines = newText.ReplaceLineEndings("\n").Split('\n');
+        var old = new StringBuilder();
+        var next = 0;
+        foreach (var hunk in Hunks)
+        {
+            // A hunk with no new lines names the line before it rather than its first.
+            var start = hunk.NewCount == 0 ? hunk.NewStart : hunk.NewStart - 1;
+            for (; next < start && next < lines.Length; next++)
+            {
+                old.Append(lines[next]).Append('\n');
+            }
+
+            foreach (var text in hunk.Lines)
+            {
+                if (text[0] == '-')
+                {
+                    old.Append(text, 1, text.Length - 1).Append('\n');
+                    continue;
+                }
+
+                if (next >= lines.Length || lines[next] != text[1..])
+                {
+                    throw new InvalidOperationException($"The patch does not match {NewPath ?? "the file"} at line {next + 1}");
+                }
+
+                if (text[0] == ' ')
+                {
+                    old.Append(lines[next]).Append('\n');
+                }
+
+                next++;
+            }
+        }
+
+        for (; next < lines.Length; next++)
+        {
+            old.Append(lines[next]);
+            if (next < lines.Length - 1)
+            {
+                old.Append('\n');
+            }
+        }
+
+        return old.ToString();
+    }
+}
+
+/// <summary>
+/// A hunk of a unified diff.
+/// </summary>
+/// <param name="OldStart">The first old line, 1-based.</param>
+/// <param name="OldCount">The number of old lines.</param>
+/// <param name="NewStart">The first new line, 1-based.</param>
+/// <param name="NewCount">The number of new lines.</param>
+/// <param name="Lines">The hunk lines, each starting with ' ', '-' or '+'.</param>
+public sealed record DiffHunk(int OldStart, int OldCount, int NewStart, int NewCount, IReadOnlyList<string> Lines);
@@ -0,0 +1,329 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.GrammarGeneration.Analysis;
+
+/// <summary>
+/// Directed graph of rule references in a grammar, with strongly connected components
+/// computed so recursive rule groups can be identified.
+/// </summary>
+public class RuleDependencyGraph
+{
+    private static readonly Regex ReferencePattern = new(@"<([A-Za-z_][A-Za-z0-9_\-]*)>", RegexOptions.Compiled);
+    private static readonly string[] StartRuleNames = { "program", "start", "compilation_unit", "file_input" };
+
+    private readonly Dictionary<string, RuleGraphNode> _nodes = new();
+    private readonly List<RuleGraphNode> _orderedNodes = new();
+    private readonly List<RuleGraphEdge> _edges = new();
+    private readonly List<IReadOnlyList<string>> _components = new();
+
+    private RuleDependencyGraph(string grammarName)
+    {
+        GrammarName = grammarName;
+    }
+
+    /// <summary>
+    /// Gets the name of the grammar this graph was built from.
+    /// </summary>
+    public string GrammarName { get; }
+
+    /// <summary>
+    /// Gets the nodes of the graph in grammar definition order (productions first, then tokens).
+    /// </summary>
+    public IReadOnlyList<RuleGraphNode> Nodes => _orderedNodes;
+
+    /// <summary>
+    /// Gets the edges of the graph; each edge aggregates all references from source to target.
+    /// </summary>
+    public IReadOnlyList<RuleGraphEdge> Edges => _edges;
+
+    /// <summary>
+    /// Gets the strongly connected components of the graph, indexed by <see cref="RuleGraphNode.ComponentId"/>.
+    /// </summary>
+    public IReadOnlyList<IReadOnlyList<string>> StronglyConnectedComponents => _components;
+
+    /// <summary>
+    /// Builds the rule dependency graph for a grammar.
+    /// </summary>
+    /// <param name="grammar">The grammar to analyze.</param>
+    /// <returns>The dependency graph with components and recursion membership computed.</returns>
+    public static RuleDependencyGraph Build(Grammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var graph = new RuleDependencyGraph(grammar.Name);
+        var tokenNames = new HashSet<string>(grammar.TokenRules.Patterns.Select(p => p.Name));
+        var firstRule = grammar.ProductionRules.Rules.FirstOrDefault()?.Name;
+
+        foreach (var rule in grammar.ProductionRules.Rules)
+        {
+            if (graph._nodes.ContainsKey(rule.Name))
+            {
+                continue;
+            }
+
+            var isStart = rule.Name == firstRule || StartRuleNames.Contains(rule.Name);
+            graph.AddNode(new RuleGraphNode(rule.Name, isStart ? RuleKind.Start : RuleKind.Production, rule.Alternatives.Count));
+        }
+
+        var multiplicities = new Dictionary<(string Source, string Target), int>();
+        var referencedSymbols = new List<string>();
+
+        foreach (var rule in grammar.ProductionRules.Rules)
+        {
+            foreach (var alternative in rule.Alternatives)
+            {
+                foreach (Match match in ReferencePattern.Matches(alternative))
+                {
+                    var target = match.Groups[1].Value;
+                    var key = (rule.Name, target);
+                    multiplicities[key] = multiplicities.GetValueOrDefault(key, 0) + 1;
+
+                    if (!referencedSymbols.Contains(target))
+                    {
+                        referencedSymbols.Add(target);
+                    }
+                }
+            }
+        }
+
+        foreach (var symbol in referencedSymbols.Where(s => !graph._nodes.ContainsKey(s)))
+        {
+            var kind = tokenNames.Contains(symbol) || IsTokenName(symbol) ? RuleKind.Token : RuleKind.Undefined;
+            graph.AddNode(new RuleGraphNode(symbol, kind, 0));
+        }
+
+        foreach (var ((source, target), count) in multiplicities)
+        {
+            graph._edges.Add(new RuleGraphEdge(source, target, count));
+            graph._nodes[target].ReferenceCount += count;
+        }
+
+        graph.ComputeComponents();
+        return graph;
+    }
+
+    /// <summary>
+    /// Gets the node with the specified name.
+    /// </summary>
+    /// <param name="name">The rule or token name.</param>
+    /// <returns>The node, or null if the symbol does not appear in the grammar.</returns>
+    public RuleGraphNode? GetNode(string name)
+    {
+        return _nodes.TryGetValue(name, out var node) ? node : null;
+    }
+
+    /// <summary>
+    /// Gets the edges leaving the specified node.
+    /// </summary>
+    /// <param name="name">The source rule name.</param>
+    /// <returns>The outgoing edges in discovery order.</returns>
+    public IEnumerable<RuleGraphEdge> GetOutgoingEdges(string name)
+    {
+        return _edges.Where(e => e.Source == name);
+    }
+
+    private void AddNode(RuleGraphNode node)
+    {
+        _nodes[node.Name] = node;
+        _orderedNodes.Add(node);
+    }
+
+    private static bool IsTokenName(string name)
+    {
+        return name.All(c => char.IsUpper(c) || char.IsDigit(c) || c == '_');
+    }
+
+    private void ComputeComponents()
+    {
+        // Tarjan's algorithm with an explicit stack so deep grammars cannot overflow the call stack.
+        var index = 0;
+        var indices = new Dictionary<string, int>();
+        var lowLinks = new Dictionary<string, int>();
+        var onStack = new HashSet<string>();
+        var stack = new Stack<string>();
+        var successors = _orderedNodes.ToDictionary(
+            n => n.Name,
+            n => _edges.Where(e => e.Source == n.Name).Select(e => e.Target).ToList());
+        var found = new List<List<string>>();
+
+        foreach (var root in _orderedNodes.Select(n => n.Name))
+        {
+            if (indices.ContainsKey(root))
+            {
+                continue;
+            }
+
+            var work = new Stack<(string Node, int NextSuccessor)>();
+            work.Push((root, 0));
+            indices[root] = lowLinks[root] = index++;
+            stack.Push(root);
+            onStack.Add(root);
+
+            while (work.Count > 0)
+            {
+                var (node, next) = work.Pop();
+                var nodeSuccessors = successors[node];
+
+                if (next < nodeSuccessors.Count)
+                {
+                    work.Push((node, next + 1));
+                    var successor = nodeSuccessors[next];
+
+                    if (!indices.ContainsKey(successor))
+                    {
+                        indices[successor] = lowLinks[successor] = index++;
+                        stack.Push(successor);
+                        onStack.Add(successor);
+                        work.Push((successor, 0));
+                    }
+                    else if (onStack.Contains(successor))
+                    {
+                        lowLinks[node] = Math.Min(lowLinks[node], indices[successor]);
+                    }
+
+                    continue;
+                }
+
+                if (work.Count > 0)
+                {
+                    var parent = work.Peek().Node;
+                    lowLinks[parent] = Math.Min(lowLinks[parent], lowLinks[node]);
+                }
+
+                if (lowLinks[node] == indices[node])
+                {
+                    var component = new List<string>();
+                    string member;
+                    do
+                    {
+                        member = stack.Pop();
+                        onStack.Remove(member);
+                        component.Add(member);
+                    }
+                    while (member != node);
+
+                    found.Add(component);
+                }
+            }
+        }
+
+        // Number components by the definition order of their earliest member so output is stable.
+        var order = _orderedNodes.Select((n, i) => (n.Name, i)).ToDictionary(p => p.Name, p => p.i);
+        foreach (var component in found.OrderBy(c => c.Min(m => order[m])))
+        {
+            var members = component.OrderBy(m => order[m]).ToList();
+            var componentId = _components.Count;
+            _components.Add(members);
+
+            var isRecursive = members.Count > 1 ||
+                _edges.Any(e => e.Source == members[0] && e.Target == members[0]);
+
+            foreach (var member in members)
+            {
+                _nodes[member].ComponentId = componentId;
+                _nodes[member].IsRecursive = isRecursive;
+            }
+        }
+    }
+}
+
+/// <summary>
+/// A rule or token in a <see cref="RuleDependencyGraph"/>.
+/// </summary>
+public class RuleGraphNode
+{
+    /// <summary>
+    /// Initializes a new instance of the RuleGraphNode class.
+    /// </summary>
+    /// <param name="name">The rule or token name.</param>
+    /// <param name="kind">The kind of symbol.</param>
+    /// <param name="alternativeCount">The number of alternatives defined for the rule.</param>
+    public RuleGraphNode(string name, RuleKind kind, int alternativeCount)
+    {
+        Name = name;
+        Kind = kind;
+        AlternativeCount = alternativeCount;
+    }
+
+    /// <summary>
+    /// Gets the rule or token name.
+    /// </summary>
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets the kind of symbol this node represents.
+    /// </summary>
+    public RuleKind Kind { get; }
+
+    /// <summary>
+    /// Gets the number of alternatives defined for the rule (zero for tokens).
+    /// </summary>
+    public int AlternativeCount { get; }
+
+    /// <summary>
+    /// Gets the total number of references to this symbol across the grammar.
+    /// </summary>
+    public int ReferenceCount { get; internal set; }
+
+    /// <summary>
+    /// Gets the index of the strongly connected component this node belongs to.
+    /// </summary>
+    public int ComponentId { get; internal set; } = -1;
+
+    /// <summary>
+    /// Gets a value indicating whether the node takes part in a reference cycle.
+    /// </summary>
+    public bool IsRecursive { get; internal set; }
+}
+
+/// <summary>
+/// A reference from one rule to another symbol, aggregated over all alternatives.
+/// </summary>
+/// <param name="Source">The referencing rule.</param>
+/// <param name="Target">The referenced rule or token.</param>
+/// <param name="Multiplicity">The number of times the target is referenced by the source.</param>
+public record RuleGraphEdge(string Source, string Target, int Multiplicity);
+
+/// <summary>
+/// Kinds of symbols in a rule dependency graph.
+/// </summary>
+public enum RuleKind
+{
+    /// <summary>
+    /// A production rule used as a grammar entry point.
+    /// </summary>
+    Start,
+
+    /// <summary>
+    /// An ordinary production rule.
+    /// </summary>
+    Production,
+
+    /// <summary>
+    /// A token (terminal) definition.
+    /// </summary>
+    Token,
+
+    /// <summary>
+    /// A referenced symbol that the grammar never defines.
+    /// </summary>
+    Undefined
+}
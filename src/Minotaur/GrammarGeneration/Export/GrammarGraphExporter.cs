@@ -0,0 +1,203 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using System.Xml.Linq;
+using Minotaur.GrammarGeneration.Analysis;
+
+namespace Minotaur.GrammarGeneration.Export;
+
+/// <summary>
+/// Serializes a <see cref="RuleDependencyGraph"/> to GraphML and to a JSON node-link format
+/// for import into graph tooling and architecture documentation.
+/// </summary>
+public class GrammarGraphExporter
+{
+    /// <summary>
+    /// The GraphML XML namespace.
+    /// </summary>
+    public static readonly XNamespace GraphMLNamespace = "http://graphml.graphdrawing.org/xmlns";
+
+    private readonly IReadOnlyDictionary<string, long>? _usageCounts;
+
+    /// <summary>
+    /// Initializes a new instance of the GrammarGraphExporter class.
+    /// </summary>
+    /// <param name="usageCounts">Optional per-rule usage counts (e.g. from a corpus run) emitted as a node attribute.</param>
+    public GrammarGraphExporter(IReadOnlyDictionary<string, long>? usageCounts = null)
+    {
+        _usageCounts = usageCounts;
+    }
+
+    /// <summary>
+    /// Exports the graph as a GraphML document.
+    /// </summary>
+    /// <param name="graph">The dependency graph to export.</param>
+    /// <returns>The GraphML document text.</returns>
+    public string ExportGraphML(RuleDependencyGraph graph)
+    {
+        ArgumentNullException.ThrowIfNull(graph);
+
+        var ns = GraphMLNamespace;
+        var root = new XElement(ns + "graphml",
+            Key("kind", "node", "kind", "string"),
+            Key("alternatives", "node", "alternatives", "int"),
+            Key("recursive", "node", "recursive", "boolean"),
+            Key("component", "node", "component", "int"),
+            Key("references", "node", "references", "int"));
+
+        if (_usageCounts != null)
+        {
+            root.Add(Key("usage", "node", "usage", "long"));
+        }
+
+        root.Add(Key("multiplicity", "edge", "multiplicity", "int"));
+
+        var graphElement = new XElement(ns + "graph",
+            new XAttribute("id", string.IsNullOrEmpty(graph.GrammarName) ? "grammar" : graph.GrammarName),
+            new XAttribute("edgedefault", "directed"));
+
+        foreach (var node in graph.Nodes)
+        {
+            var nodeElement = new XElement(ns + "node",
+                new XAttribute("id", node.Name),
+                Data("kind", FormatKind(node.Kind)),
+                Data("alternatives", node.AlternativeCount.ToString()),
+                Data("recursive", node.IsRecursive ? "true" : "false"),
+                Data("component", node.ComponentId.ToString()),
+                Data("references", node.ReferenceCount.ToString()));
+
+            if (_usageCounts != null)
+            {
+                nodeElement.Add(Data("usage", _usageCounts.GetValueOrDefault(node.Name, 0).ToString()));
+            }
+
+            graphElement.Add(nodeElement);
+        }
+
+        var edgeIndex = 0;
+        foreach (var edge in graph.Edges)
+        {
+            graphElement.Add(new XElement(ns + "edge",
+                new XAttribute("id", $"e{edgeIndex++}"),
+                new XAttribute("source", edge.Source),
+                new XAttribute("target", edge.Target),
+                Data("multiplicity", edge.Multiplicity.ToString())));
+        }
+
+        root.Add(graphElement);
+        var document = new XDocument(new XDeclaration("1.0", "utf-8", null), root);
+        return document.Declaration + Environment.NewLine + document.ToString();
+    }
+
+    /// <summary>
+    /// Exports the graph in the JSON node-link format (compatible with NetworkX <c>node_link_graph</c>).
+    /// </summary>
+    /// <param name="graph">The dependency graph to export.</param>
+    /// <returns>The indented JSON text.</returns>
+    public string ExportJson(RuleDependencyGraph graph)
+    {
+        return ToJsonObject(graph).ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+    }
+
+    /// <summary>
+    /// Builds the JSON node-link representation of the graph.
+    /// </summary>
+    /// <param name="graph">The dependency graph to export.</param>
+    /// <returns>The node-link JSON object.</returns>
+    public JsonObject ToJsonObject(RuleDependencyGraph graph)
+    {
+        ArgumentNullException.ThrowIfNull(graph);
+
+        var nodes = new JsonArray();
+        foreach (var node in graph.Nodes)
+        {
+            var nodeObject = new JsonObject
+            {
+                ["id"] = node.Name,
+                ["kind"] = FormatKind(node.Kind),
+                ["alternatives"] = node.AlternativeCount,
+                ["recursive"] = node.IsRecursive,
+                ["component"] = node.ComponentId,
+                ["references"] = node.ReferenceCount
+            };
+
+            if (_usageCounts != null)
+            {
+                nodeObject["usage"] = _usageCounts.GetValueOrDefault(node.Name, 0);
+            }
+
+            nodes.Add(nodeObject);
+        }
+
+        var links = new JsonArray();
+        foreach (var edge in graph.Edges)
+        {
+            links.Add(new JsonObject
+            {
+                ["source"] = edge.Source,
+                ["target"] = edge.Target,
+                ["multiplicity"] = edge.Multiplicity
+            });
+        }
+
+        var components = new JsonArray();
+        for (var i = 0; i < graph.StronglyConnectedComponents.Count; i++)
+        {
+            var members = new JsonArray();
+            foreach (var member in graph.StronglyConnectedComponents[i])
+            {
+                members.Add(member);
+            }
+
+            components.Add(new JsonObject { ["id"] = i, ["members"] = members });
+        }
+
+        return new JsonObject
+        {
+            ["directed"] = true,
+            ["multigraph"] = false,
+            ["graph"] = new JsonObject
+            {
+                ["name"] = graph.GrammarName,
+                ["components"] = components
+            },
+            ["nodes"] = nodes,
+            ["links"] = links
+        };
+    }
+
+    private static XElement Key(string id, string target, string name, string type)
+    {
+        return new XElement(GraphMLNamespace + "key",
+            new XAttribute("id", id),
+            new XAttribute("for", target),
+            new XAttribute("attr.name", name),
+            new XAttribute("attr.type", type));
+    }
+
+    private static XElement Data(string key, string value)
+    {
+        return new XElement(GraphMLNamespace + "data", new XAttribute("key", key), value);
+    }
+
+    private static string FormatKind(RuleKind kind)
+    {
+        return kind.ToString().ToLowerInvariant();
+    }
+}
@@ -0,0 +1,438 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using System.Text.RegularExpressions;
+using Minotaur.GrammarGeneration.Models;
+using Minotaur.Monitoring;
+using Minotaur.Parser;
+
+namespace Minotaur.GrammarGeneration;
+
+/// <summary>
+/// Reads grammar files in the format written by <see cref="GrammarGenerator.GenerateGrammarFile"/>
+/// back into a <see cref="Grammar"/> model. The terminal libraries a grammar imports with the "Terminals" entry are
+/// resolved into token patterns as it is read; see <see cref="TerminalLibraryRegistry"/>.
+/// </summary>
+public class GrammarFileReader
+{
+    private static readonly Regex RuleStart = new(@"^<(?<name>[A-Za-z_][A-Za-z0-9_\-]*)>\s*::=(?<body>.*)$", RegexOptions.CultureInvariant);
+    private static readonly Regex HeaderLine = new(@"^(?<key>[A-Za-z][A-Za-z0-9_]*)\s*:\s*(?<value>.*)$", RegexOptions.CultureInvariant);
+    private static readonly Regex ActionSuffix = new(@"=>\s*\{(?<action>[^}]*)\}\s*$", RegexOptions.CultureInvariant);
+    private static readonly Regex AnnotationLine = new(@"^//\s*@(?<kind>example|snippet|description|deprecated|expected|unpaired|ambiguous|import|unique_by|escapes|breadcrumb)(?:\s+|(?=\())(?<text>.*)$", RegexOptions.CultureInvariant);
+
+    private readonly TerminalLibraryRegistry _terminals;
+
+    /// <summary>
+    /// Initializes a new instance of the GrammarFileReader class.
+    /// </summary>
+    /// <param name="terminals">The terminal libraries grammars may import, or null for the standard ones.</param>
+    public GrammarFileReader(TerminalLibraryRegistry? terminals = null)
+    {
+        _terminals = terminals ?? new TerminalLibraryRegistry();
+    }
+
+    /// <summary>
+    /// Reads a grammar file from disk.
+    /// </summary>
+    /// <param name="path">The path of the grammar file.</param>
+    /// <returns>The grammar model.</returns>
+    public async Task<Grammar> ReadFileAsync(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        var content = await File.ReadAllTextAsync(path);
+        var grammar = Read(content);
+
+        if (string.IsNullOrEmpty(grammar.Name))
+        {
+            grammar.Name = Path.GetFileNameWithoutExtension(path);
+        }
+
+        return grammar;
+    }
+
+    /// <summary>
+    /// Reads a grammar from grammar file content.
+    /// </summary>
+    /// <param name="content">The grammar file content.</param>
+    /// <returns>The grammar model.</returns>
+    /// <exception cref="ArgumentException">The grammar imports a terminal library that cannot be resolved.</exception>
+    public Grammar Read(string content)
+    {
+        ArgumentNullException.ThrowIfNull(content);
+
+        var grammar = new Grammar();
+        string? currentName = null;
+        var currentBody = new StringBuilder();
+        var lineAlternatives = new List<int>();
+        var inComment = false;
+        var lineNumber = 0;
+
+        foreach (var rawLine in content.Split('\n'))
+        {
+            var line = rawLine.Trim();
+            lineNumber++;
+
+            if (inComment)
+            {
+                inComment = !line.Contains("*/");
+                continue;
+            }
+
+            if (line.StartsWith("/*"))
+            {
+                inComment = !line.Contains("*/");
+                continue;
+            }
+
+            if (line.Length == 0 || line.StartsWith("//"))
+            {
+                // Annotation comments document the definition they follow.
+                var annotationMatch = AnnotationLine.Match(line);
+                if (currentName != null && annotationMatch.Success)
+                {
+                    long mark = 0;
+                    MemoryLedger.Mark(ref mark);
+                    grammar.Annotations.Add(new GrammarAnnotation
+                    {
+                        Kind = Enum.Parse<GrammarAnnotationKind>(annotationMatch.Groups["kind"].Value.Replace("_", string.Empty), ignoreCase: true),
+                        Target = currentName,
+                        Text = annotationMatch.Groups["text"].Value.Trim(),
+                        Line = lineNumber,
+                        Alternatives = new List<int>(lineAlternatives)
+                    });
+                    MemoryLedger.Record(ref grammar.Memory, MemoryCategories.Annotations, mark);
+                }
+
+                continue;
+            }
+
+            var ruleMatch = RuleStart.Match(line);
+            if (ruleMatch.Success)
+            {
+                AddDefinition(grammar, currentName, currentBody.ToString());
+                currentName = ruleMatch.Groups["name"].Value;
+                currentBody.Clear().Append(ruleMatch.Groups["body"].Value);
+                lineAlternatives.Clear();
+                continue;
+            }
+
+            if (currentName != null)
+            {
+                // A continuation line holds the alternatives it starts with '|' and the rest of the one before it;
+                // indices count the alternatives of earlier definitions of the same rule.
+                var before = SplitAlternatives(currentBody.ToString()).Count;
+                currentBody.Append(' ').Append(line);
+                var after = SplitAlternatives(currentBody.ToString()).Count;
+                var first = line.StartsWith('|') ? before : before - 1;
+                var existing = grammar.ProductionRules.GetRule(currentName)?.Alternatives.Count ?? 0;
+                lineAlternatives = Enumerable.Range(existing + first, after - first).ToList();
+                continue;
+            }
+
+            var headerMatch = HeaderLine.Match(line);
+            if (headerMatch.Success)
+            {
+                long mark = 0;
+                MemoryLedger.Mark(ref mark);
+                ApplyHeader(grammar, headerMatch.Groups["key"].Value, headerMatch.Groups["value"].Value.Trim());
+                MemoryLedger.Record(ref grammar.Memory, MemoryCategories.Metadata, mark);
+            }
+        }
+
+        AddDefinition(grammar, currentName, currentBody.ToString());
+        _terminals.Resolve(grammar);
+        return grammar;
+    }
+
+    /// <summary>
+    /// Finds where the alternatives of each definition are written in grammar file content. Alternatives are listed
+    /// per name in the order <see cref="Read"/> adds them, so the index of a range matches the index of the
+    /// alternative in the rule; an alternative continued over several lines spans all of them, and action suffixes
+    /// are not part of the range.
+    /// </summary>
+    /// <param name="content">The grammar file content.</param>
+    /// <returns>The ranges of the alternatives of each rule and token definition, as offsets into the content.</returns>
+    public static IReadOnlyDictionary<string, IReadOnlyList<TextRange>> FindAlternativeRanges(string content)
+    {
+        ArgumentNullException.ThrowIfNull(content);
+
+        var ranges = new Dictionary<string, List<TextRange>>(StringComparer.Ordinal);
+        string? currentName = null;
+        var currentBody = new StringBuilder();
+        var segments = new List<(int BodyOffset, int FileOffset)>();
+        var inComment = false;
+        var lineStart = 0;
+
+        foreach (var rawLine in content.Split('\n'))
+        {
+            var line = rawLine.Trim();
+            var lineOffset = lineStart + rawLine.Length - rawLine.TrimStart().Length;
+            lineStart += rawLine.Length + 1;
+
+            if (inComment)
+            {
+                inComment = !line.Contains("*/");
+                continue;
+            }
+
+            if (line.StartsWith("/*"))
+            {
+                inComment = !line.Contains("*/");
+                continue;
+            }
+
+            if (line.Length == 0 || line.StartsWith("//"))
+            {
+                continue;
+            }
+
+            var ruleMatch = RuleStart.Match(line);
+            if (ruleMatch.Success)
+            {
+                AddRanges(ranges, currentName, currentBody.ToString(), segments);
+                currentName = ruleMatch.Groups["name"].Value;
+                currentBody.Clear().Append(ruleMatch.Groups["body"].Value);
+                segments.Clear();
+                segments.Add((0, lineOffset + ruleMatch.Groups["body"].Index));
+                continue;
+            }
+
+            if (currentName != null)
+            {
+                currentBody.Append(' ');
+                segments.Add((currentBody.Length, lineOffset));
+                currentBody.Append(line);
+            }
+        }
+
+        AddRanges(ranges, currentName, currentBody.ToString(), segments);
+        return ranges.ToDictionary(r => r.Key, r => (IReadOnlyList<TextRange>)r.Value, StringComparer.Ordinal);
+    }
+
+    private static void AddRanges(Dictionary<string, List<TextRange>> ranges, string? name, string body, List<(int BodyOffset, int FileOffset)> segments)
+    {
+        if (name == null)
+        {
+            return;
+        }
+
+        if (!ranges.TryGetValue(name, out var list))
+        {
+            list = new List<TextRange>();
+            ranges[name] = list;
+        }
+
+        // Alternatives are trimmed substrings of the joined body, found in order; body offsets map back to the line
+        // segment they were joined from.
+        int ToFile(int bodyOffset)
+        {
+            var segment = segments.Last(s => s.BodyOffset <= bodyOffset);
+            return segment.FileOffset + bodyOffset - segment.BodyOffset;
+        }
+
+        var cursor = 0;
+        foreach (var alternative in SplitAlternatives(body))
+        {
+            var actionMatch = ActionSuffix.Match(alternative);
+            var text = actionMatch.Success ? alternative[..actionMatch.Index].TrimEnd() : alternative;
+            var start = body.IndexOf(text, cursor, StringComparison.Ordinal);
+            if (start < 0)
+            {
+                start = cursor;
+            }
+
+            var fileStart = ToFile(start);
+            list.Add(new TextRange(fileStart, text.Length == 0 ? 0 : ToFile(start + text.Length - 1) + 1 - fileStart));
+            cursor = start + alternative.Length;
+        }
+    }
+
+    private static void ApplyHeader(Grammar grammar, string key, string value)
+    {
+        switch (key)
+        {
+            case "Grammar":
+                grammar.Name = value;
+                break;
+
+            case "Version":
+                grammar.Version = value;
+                break;
+
+            case "Keywords":
+                foreach (var keyword in value.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+                {
+                    grammar.TokenRules.AddPattern(new TokenPattern
+                    {
+                        Name = keyword.ToUpperInvariant(),
+                        Pattern = Regex.Escape(keyword),
+                        Type = TokenType.Keyword,
+                        IsKeyword = true
+                    });
+                }
+                break;
+
+            default:
+                grammar.Metadata[key] = value;
+                break;
+        }
+    }
+
+    private static void AddDefinition(Grammar grammar, string? name, string body)
+    {
+        if (name == null)
+        {
+            return;
+        }
+
+        long mark = 0;
+        MemoryLedger.Mark(ref mark);
+        var alternatives = SplitAlternatives(body);
+        var actions = new Dictionary<int, string>();
+
+        for (var i = 0; i < alternatives.Count; i++)
+        {
+            var actionMatch = ActionSuffix.Match(alternatives[i]);
+            if (actionMatch.Success)
+            {
+                actions[i] = actionMatch.Groups["action"].Value.Trim();
+                alternatives[i] = alternatives[i][..actionMatch.Index].TrimEnd();
+            }
+        }
+
+        if (IsTokenName(name) && alternatives.Count == 1 && IsRegex(alternatives[0]))
+        {
+            var skip = actions.TryGetValue(0, out var action) && action.Contains("skip");
+            grammar.TokenRules.AddPattern(new TokenPattern
+            {
+                Name = name,
+                Pattern = alternatives[0][1..^1],
+                Type = skip ? TokenType.Whitespace : InferTokenType(name)
+            });
+            MemoryLedger.Record(ref grammar.Memory, MemoryCategories.TokenPatterns, mark);
+            return;
+        }
+
+        var rule = grammar.ProductionRules.GetRule(name);
+        if (rule == null)
+        {
+            rule = new ProductionRule { Name = name };
+            grammar.ProductionRules.AddRule(rule);
+        }
+
+        foreach (var (index, action) in actions)
+        {
+            rule.Actions[rule.Alternatives.Count + index] = action;
+        }
+
+        rule.Alternatives.AddRange(alternatives);
+        MemoryLedger.Record(ref grammar.Memory, MemoryCategories.ProductionRules, mark);
+    }
+
+    /// <summary>
+    /// Splits a rule body on top-level '|' separators, ignoring separators inside quotes and regexes.
+    /// </summary>
+    /// <param name="body">The rule body.</param>
+    /// <returns>The trimmed alternatives.</returns>
+    public static List<string> SplitAlternatives(string body)
+    {
+        ArgumentNullException.ThrowIfNull(body);
+
+        var alternatives = new List<string>();
+        var current = new StringBuilder();
+        char? quote = null;
+        var inRegex = false;
+        var inClass = false;
+
+        for (var i = 0; i < body.Length; i++)
+        {
+            var c = body[i];
+
+            if ((quote != null || inRegex) && c == '\\' && i + 1 < body.Length)
+            {
+                current.Append(c).Append(body[++i]);
+                continue;
+            }
+
+            if (quote != null)
+            {
+                quote = c == quote ? null : quote;
+            }
+            else if (inRegex)
+            {
+                if (c == '[')
+                {
+                    inClass = true;
+                }
+                else if (c == ']')
+                {
+                    inClass = false;
+                }
+                else if (c == '/' && !inClass)
+                {
+                    inRegex = false;
+                }
+            }
+            else if (c is '"' or '\'')
+            {
+                quote = c;
+            }
+            else if (c == '/' && (current.Length == 0 || char.IsWhiteSpace(current[^1])))
+            {
+                inRegex = true;
+            }
+            else if (c == '|')
+            {
+                alternatives.Add(current.ToString().Trim());
+                current.Clear();
+                continue;
+            }
+
+            current.Append(c);
+        }
+
+        alternatives.Add(current.ToString().Trim());
+        return alternatives;
+    }
+
+    private static bool IsTokenName(string name)
+    {
+        return name.Any(char.IsLetter) && name.All(c => char.IsUpper(c) || char.IsDigit(c) || c == '_');
+    }
+
+    private static bool IsRegex(string alternative)
+    {
+        return alternative.Length >= 2 && alternative[0] == '/' && alternative[^1] == '/';
+    }
+
+    private static TokenType InferTokenType(string name)
+    {
+        if (name.Contains("COMMENT"))
+        {
+            return TokenType.Comment;
+        }
+
+        if (name.Contains("WHITESPACE") || name == "WS")
+        {
+            return TokenType.Whitespace;
+        }
+
+        return name.Contains("IDENTIFIER") ? TokenType.Identifier : TokenType.Literal;
+    }
+}
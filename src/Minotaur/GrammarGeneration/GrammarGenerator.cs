@@ -146,7 +146,9 @@ public class GrammarGenerator
         // Production rules
         foreach (var rule in grammar.ProductionRules.Rules.OrderByDescending(r => r.Priority))
         {
-            sb.AppendLine($"<{rule.Name}> ::= {string.Join(" | ", rule.Alternatives)}");
+            var alternatives = rule.Alternatives.Select((alternative, index) =>
+                rule.Actions.TryGetValue(index, out var action) ? $"{alternative} => {{ {action} }}" : alternative);
+            sb.AppendLine($"<{rule.Name}> ::= {string.Join(" | ", alternatives)}");
 
             if (rule.Examples.Any())
             {
@@ -156,9 +158,10 @@ public class GrammarGenerator
             sb.AppendLine();
         }
 
-        // Token patterns (excluding keywords which are listed separately)
+        // Token patterns (excluding keywords which are listed separately, and terminal library patterns, which the
+        // "Terminals" header brings back)
         var nonKeywordTokens = grammar.TokenRules.Patterns
-            .Where(p => p.Type != TokenType.Keyword)
+            .Where(p => p.Type != TokenType.Keyword && p.Library == null)
             .OrderByDescending(p => p.Priority);
 
         foreach (var token in nonKeywordTokens)
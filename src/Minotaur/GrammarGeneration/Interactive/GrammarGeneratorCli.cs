@@ -15,7 +15,20 @@
  * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
  */
 
+using System.Diagnostics;
+using System.Text;
+using System.Text.RegularExpressions;
+using Minotaur.Analysis;
+using Minotaur.Analysis.Passes;
+using Minotaur.Core;
+using Minotaur.Daemon;
+using Minotaur.Diagnostics;
 using Minotaur.GrammarGeneration.Models;
+using Minotaur.Monitoring;
+using Minotaur.Parser;
+using Minotaur.Projects.Grammar;
+using Minotaur.Testing;
+using Minotaur.Visualization;
 
 namespace Minotaur.GrammarGeneration.Interactive;
 
@@ -53,6 +66,25 @@ public class GrammarGeneratorCli
             {
                 "generate" => await HandleGenerateCommand(args.Skip(1).ToArray()),
                 "validate" => await HandleValidateCommand(args.Skip(1).ToArray()),
+                "parse" => await HandleParseCommand(args.Skip(1).ToArray()),
+                "reparse" => await HandleReparseCommand(args.Skip(1).ToArray()),
+                "reduce" => await HandleReduceCommand(args.Skip(1).ToArray()),
+                "xtest" => await HandleXtestCommand(args.Skip(1).ToArray()),
+                "selftest" => await HandleSelftestCommand(args.Skip(1).ToArray()),
+                "corpus" => await HandleCorpusCommand(args.Skip(1).ToArray()),
+                "grammar" => await HandleGrammarCommand(args.Skip(1).ToArray()),
+                "daemon" => await HandleDaemonCommand(args.Skip(1).ToArray()),
+                "sgrep" => await HandleSgrepCommand(args.Skip(1).ToArray()),
+                "symbols" => await HandleSymbolsCommand(args.Skip(1).ToArray()),
+                "skeleton" => await HandleSkeletonCommand(args.Skip(1).ToArray()),
+                "modules" => await HandleModulesCommand(args.Skip(1).ToArray()),
+                "index" => await HandleIndexCommand(args.Skip(1).ToArray()),
+                "mutate" => await HandleMutateCommand(args.Skip(1).ToArray()),
+                "diff" => await HandleDiffCommand(args.Skip(1).ToArray()),
+                "explain" => await HandleExplainCommand(args.Skip(1).ToArray()),
+                "check" => await HandleCheckCommand(args.Skip(1).ToArray()),
+                "test" => await HandleTestCommand(args.Skip(1).ToArray()),
+                "config" => await HandleConfigCommand(args.Skip(1).ToArray()),
                 "help" => HandleHelpCommand(args.Skip(1).ToArray()),
                 _ => HandleUnknownCommand(command)
             };
@@ -143,141 +175,3585 @@ public class GrammarGeneratorCli
         return await Task.FromResult(0);
     }
 
+    private async Task<int> HandleParseCommand(string[] args)
+    {
+        var options = ParseParseOptions(args);
+
+        if (options == null)
+        {
+            PrintParseUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var parser = CreateParser(grammar, options.StartRule);
+
+        DecodedSource source;
+        try
+        {
+            source = await SourceDecoder.DecodeFileAsync(options.InputFile, new SourceDecodeOptions
+            {
+                Encoding = options.Encoding != null ? SourceDecoder.GetEncoding(options.Encoding) : null,
+                InvalidBytes = options.SubstituteInvalidBytes ? InvalidBytePolicy.Substitute : InvalidBytePolicy.Error
+            });
+        }
+        catch (SourceDecodingException ex)
+        {
+            Console.WriteLine($"❌ {ex.ToDiagnostic()}");
+            return 1;
+        }
+        catch (ArgumentException ex)
+        {
+            Console.WriteLine($"❌ Unknown encoding '{options.Encoding}': {ex.Message}");
+            return 1;
+        }
+
+        var input = source.Text;
+
+        Console.WriteLine($"🔍 Parsing {options.InputFile} ({source.Encoding.WebName}) with grammar: {grammar.Name}");
+
+        var parseOptions = new ParseOptions
+        {
+            SourceFile = options.InputFile,
+            DiagnosticBudget = await LoadDiagnosticBudgetAsync(),
+            Watchdog = options.StallTimeout is { } timeout ? new ParseWatchdogOptions { Interval = TimeSpan.FromMilliseconds(timeout) } : null,
+            PathContext = options.PathContext
+        };
+
+        // Source maps only affect how diagnostics are rendered, never the parse itself.
+        SourceMapper? mapper = null;
+        if (!string.IsNullOrEmpty(options.SourceMapFile))
+        {
+            mapper = SourceMapper.FromJson(await File.ReadAllTextAsync(options.SourceMapFile));
+        }
+        else if (options.LineDirectives)
+        {
+            mapper = SourceMapper.FromLineDirectives(input, grammar, options.InputFile);
+        }
+
+        var formatter = new DiagnosticFormatter(mapper) { Source = source };
+        foreach (var diagnostic in source.Diagnostics)
+        {
+            Console.WriteLine($"⚠️ {formatter.Format(diagnostic)}");
+        }
+
+        if (options.Documents)
+        {
+            return await ParseDocumentsAsync(parser, source, parseOptions, formatter, options);
+        }
+
+        var result = parser.Parse(input, parseOptions);
+
+        foreach (var diagnostic in result.Diagnostics)
+        {
+            Console.WriteLine($"❌ {formatter.Format(diagnostic)}");
+            if (diagnostic.Data.GetValueOrDefault("hints") is IEnumerable<GrammarHint> hints)
+            {
+                foreach (var hint in hints)
+                {
+                    Console.WriteLine($"   💡 {hint}");
+                }
+            }
+        }
+
+        if (!string.IsNullOrEmpty(options.SarifFile))
+        {
+            await File.WriteAllTextAsync(options.SarifFile, formatter.ToSarif(source.Diagnostics.Concat(result.Diagnostics)));
+            Console.WriteLine($"💾 SARIF log saved to: {options.SarifFile}");
+        }
+
+        if (options.MemoryReport)
+        {
+            PrintMemoryReports(grammar, parser.Grammar, result);
+        }
+
+        if (!result.IsSuccess)
+        {
+            if (options.CaptureCorpus)
+            {
+                CaptureCorpusCase(result.Grammar!, input, RegressionCorpus.GetDirectory(options.GrammarFile), new CorpusCaptureOptions());
+            }
+
+            return 1;
+        }
+
+        Console.WriteLine($"✅ Parsed {result.Tokens.Count} tokens from <{result.StartRule}>");
+        Console.WriteLine($"🌳 Forest nodes: {result.Forest!.Nodes.Count}, ambiguous: {result.Forest.AmbiguityCount}");
+
+        if (!string.IsNullOrEmpty(options.ForestHtmlFile))
+        {
+            await File.WriteAllTextAsync(options.ForestHtmlFile, result.ForestHtml());
+            Console.WriteLine($"💾 Forest visualization saved to: {options.ForestHtmlFile}");
+        }
+
+        return 0;
+    }
+
+    private static async Task<int> ParseDocumentsAsync(GeneralizedParser parser, DecodedSource source, ParseOptions parseOptions, DiagnosticFormatter formatter, ParseCommandOptions options)
+    {
+        if (parser.Grammar.Documents == null)
+        {
+            Console.WriteLine($"❌ Grammar '{parser.Grammar.Name}' declares no {CompiledGrammar.DocumentsKey}; add e.g. \"{CompiledGrammar.DocumentsKey}: <statement> \";\"\" to its header");
+            return 1;
+        }
+
+        var diagnostics = new List<Diagnostic>(source.Diagnostics);
+        var count = 0;
+        var failed = 0;
+        foreach (var document in parser.ParseDocuments(source.Text, parseOptions))
+        {
+            count++;
+            var result = document.Parse;
+            if (result.IsSuccess)
+            {
+                Console.WriteLine($"✅ Document {document.Index + 1} (line {document.Location.Line}): parsed {result.Tokens.Count} tokens from <{result.StartRule}>");
+                continue;
+            }
+
+            failed++;
+            Console.WriteLine($"❌ Document {document.Index + 1} (line {document.Location.Line}):");
+            foreach (var diagnostic in result.Diagnostics)
+            {
+                Console.WriteLine($"   {formatter.Format(diagnostic)}");
+            }
+
+            diagnostics.AddRange(result.Diagnostics);
+        }
+
+        Console.WriteLine($"📄 {count} documents, {count - failed} parsed, {failed} with errors");
+
+        if (!string.IsNullOrEmpty(options.SarifFile))
+        {
+            await File.WriteAllTextAsync(options.SarifFile, formatter.ToSarif(diagnostics));
+            Console.WriteLine($"💾 SARIF log saved to: {options.SarifFile}");
+        }
+
+        return failed > 0 ? 1 : 0;
+    }
+
+    private static void PrintMemoryReports(params IMemoryReporting[] subjects)
+    {
+        Console.WriteLine("📊 Memory" + (MemoryReport.IsAccountingEnabled ? "" : " (bytes are not available: built without memory accounting)"));
+        foreach (var subject in subjects)
+        {
+            Console.WriteLine(subject.GetMemoryReport());
+        }
+    }
+
+    // --rule names a start rule or an entry point; an entry point also brings its end-of-input handling.
+    private static GeneralizedParser CreateParser(Grammar grammar, string? rule)
+    {
+        var parser = new GeneralizedParser(CompiledGrammar.Compile(grammar, rule));
+        foreach (var warning in parser.Grammar.Warnings)
+        {
+            Console.Error.WriteLine($"⚠️ {warning}");
+        }
+
+        return rule != null && parser.Grammar.EntryPoints.ContainsKey(rule) ? parser.ForEntry(rule) : parser;
+    }
+
+    private async Task<int> HandleReparseCommand(string[] args)
+    {
+        var options = ParseParseOptions(args);
+
+        if (options == null || string.IsNullOrEmpty(options.EditScriptFile))
+        {
+            if (options != null)
+            {
+                Console.WriteLine("Error: An edit script is required (--edits)");
+            }
+
+            PrintReparseUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var parser = CreateParser(grammar, options.StartRule);
+        var input = await File.ReadAllTextAsync(options.InputFile);
+        var edits = TextEdit.ParseScript(await File.ReadAllTextAsync(options.EditScriptFile));
+
+        Console.WriteLine($"🔁 Replaying {edits.Count} edits on {options.InputFile} with grammar: {grammar.Name}");
+
+        var incremental = new IncrementalParser(parser, input, new ParseOptions { SourceFile = options.InputFile });
+        foreach (var edit in edits)
+        {
+            incremental.ApplyEdit(edit);
+            var stats = incremental.LastStats!.Value;
+            var status = stats.IsSuccess ? "✅" : "❌";
+            Console.WriteLine($"{status} #{stats.Version} {edit}: re-lexed {stats.RelexedRange} ({stats.RelexedTokenCount} tokens), " +
+                $"reused {stats.ReusedNodeCount}, rebuilt {stats.RebuiltNodeCount}, {stats.TotalTime.TotalMilliseconds:F3} ms");
+        }
+
+        if (!string.IsNullOrEmpty(options.TimelineHtmlFile))
+        {
+            var html = new ReparseTimelineHtmlRenderer().Render(incremental.History, Path.GetFileName(options.InputFile));
+            await File.WriteAllTextAsync(options.TimelineHtmlFile, html);
+            Console.WriteLine($"💾 Reparse timeline saved to: {options.TimelineHtmlFile}");
+        }
+
+        return incremental.Current.IsSuccess ? 0 : 1;
+    }
+
+    private async Task<int> HandleReduceCommand(string[] args)
+    {
+        var options = ParseParseOptions(args);
+
+        if (options == null || string.IsNullOrEmpty(options.PredicateCommand))
+        {
+            if (options != null)
+            {
+                Console.WriteLine("Error: A predicate command is required (--predicate)");
+            }
+
+            PrintReduceUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var compiled = CompiledGrammar.Compile(grammar, options.StartRule);
+        var input = await File.ReadAllTextAsync(options.InputFile);
+        var outputPattern = options.PredicateOutputPattern != null ? new Regex(options.PredicateOutputPattern) : null;
+        var candidateFile = Path.Combine(Path.GetTempPath(), $"minotaur-reduce-{Guid.NewGuid():N}{Path.GetExtension(options.InputFile)}");
+
+        Console.WriteLine($"✂️ Reducing {options.InputFile} ({input.Length} chars) with grammar: {grammar.Name}");
+
+        bool IsFailing(string candidate)
+        {
+            File.WriteAllText(candidateFile, candidate);
+            return RunPredicate(options.PredicateCommand, candidateFile, options.PredicateExitCode, outputPattern);
+        }
+
+        try
+        {
+            var result = InputReducer.Reduce(input, compiled, IsFailing, new ReductionOptions
+            {
+                MaxTests = options.MaxTests,
+                Progress = new Progress<ReductionProgress>(p =>
+                    Console.Write($"\r🔄 {p.Phase}: {p.Length} chars after {p.Tests} tests   "))
+            });
+
+            Console.WriteLine();
+            Console.WriteLine($"✅ Reduced {result.OriginalLength} chars to {result.Input.Length} in {result.Tests} tests" +
+                (result.UsedTree ? "" : " (tokens only: the input does not parse)"));
+
+            if (result.ReachedTestLimit)
+            {
+                Console.WriteLine($"⚠️ Stopped after {options.MaxTests} tests; the result may not be minimal");
+            }
+
+            var outputFile = options.OutputFile ?? options.InputFile + ".reduced" + Path.GetExtension(options.InputFile);
+            await File.WriteAllTextAsync(outputFile, result.Input);
+            Console.WriteLine($"💾 Reproducer saved to: {outputFile}");
+            return 0;
+        }
+        catch (ArgumentException ex)
+        {
+            Console.WriteLine($"❌ {ex.Message}");
+            return 1;
+        }
+        finally
+        {
+            File.Delete(candidateFile);
+        }
+    }
+
+    private async Task<int> HandleXtestCommand(string[] args)
+    {
+        var options = ParseXtestOptions(args);
+
+        if (options == null)
+        {
+            PrintXtestUsage();
+            return 1;
+        }
+
+        var language = ReferenceLanguages.Get(options.Language);
+        if (language == null)
+        {
+            Console.WriteLine($"❌ No reference frontend for '{options.Language}'. Available: {string.Join(", ", ReferenceLanguages.Names)}");
+            return 1;
+        }
+
+        var root = Path.GetFullPath(options.Root ?? Directory.GetCurrentDirectory());
+        var grammarFile = options.GrammarFile ?? Path.Combine(root, language.GrammarFile);
+        var allowlistFile = options.AllowlistFile ?? (language.AllowlistFile != null ? Path.Combine(root, language.AllowlistFile) : null);
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(grammarFile);
+        var candidate = new GrammarFrontend(CompiledGrammar.Compile(grammar));
+        var reference = options.ReferenceCommand != null
+            ? new CommandFrontend("reference", options.ReferenceCommand, root)
+            : language.CreateReference(root);
+        var allowlist = allowlistFile != null && File.Exists(allowlistFile) ? DifferenceAllowlist.Load(allowlistFile) : null;
+
+        Console.WriteLine($"🔍 Comparing {candidate.Name} ({grammar.Name}) with {reference.Name} on {options.CorpusDirectory}");
+
+        var harness = new DifferentialHarness(reference, candidate, allowlist);
+        var report = harness.Run(options.CorpusDirectory, language.SearchPattern);
+
+        foreach (var comparison in report.Files)
+        {
+            Console.WriteLine($"{(comparison.IsMatch ? "✅" : "❌")} {comparison.Path}");
+        }
+
+        Console.WriteLine();
+        Console.Write(report);
+
+        if (options.DumpDirectory != null && report.Mismatches.Count > 0)
+        {
+            Directory.CreateDirectory(options.DumpDirectory);
+            foreach (var mismatch in report.Mismatches)
+            {
+                var name = Path.GetRelativePath(options.CorpusDirectory, mismatch.Path).Replace('/', '_').Replace('\\', '_');
+                await File.WriteAllTextAsync(Path.Combine(options.DumpDirectory, name + ".diff.txt"), mismatch.Dump());
+            }
+
+            Console.WriteLine($"💾 Mismatch dumps saved to: {options.DumpDirectory}");
+        }
+        else
+        {
+            foreach (var mismatch in report.Mismatches)
+            {
+                Console.WriteLine(mismatch.Dump());
+            }
+        }
+
+        return report.Mismatches.Count == 0 ? 0 : 1;
+    }
+
+    private async Task<int> HandleCorpusCommand(string[] args)
+    {
+        var options = ParseCorpusOptions(args);
+
+        if (options == null)
+        {
+            PrintCorpusUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var compiled = CompiledGrammar.Compile(grammar, options.StartRule);
+        var corpusDirectory = options.CorpusDirectory ?? RegressionCorpus.GetDirectory(options.GrammarFile);
+
+        if (options.Action == "add")
+        {
+            var input = await File.ReadAllTextAsync(options.InputFile!);
+            return CaptureCorpusCase(compiled, input, corpusDirectory, new CorpusCaptureOptions
+            {
+                Reduce = options.Reduce,
+                Anonymize = options.Anonymize,
+                MaxTests = options.MaxTests
+            }) ? 0 : 1;
+        }
+
+        if (options.Action == "reorder")
+        {
+            return await ReorderAlternativesAsync(options, corpusDirectory);
+        }
+
+        Console.WriteLine($"🔍 Checking corpus {corpusDirectory} with grammar: {grammar.Name}");
+
+        try
+        {
+            var count = RegressionCorpus.Verify(compiled, corpusDirectory);
+            Console.WriteLine($"✅ {count} corpus cases match");
+            return 0;
+        }
+        catch (SnapshotMismatchException ex)
+        {
+            Console.WriteLine($"❌ {ex.Message}");
+            return 1;
+        }
+    }
+
+    private static async Task<int> ReorderAlternativesAsync(CorpusCommandOptions options, string corpusDirectory)
+    {
+        Console.WriteLine($"🔍 Replaying corpus {corpusDirectory} to order the alternatives of {options.GrammarFile}");
+
+        var plan = AlternativeOrderOptimizer.Optimize(options.GrammarFile, corpusDirectory, options.StartRule);
+        if (plan.Refusal != null)
+        {
+            Console.WriteLine($"⚠️ {plan.Refusal}");
+            return 0;
+        }
+
+        foreach (var rule in plan.Rules)
+        {
+            if (rule.Refusal != null)
+            {
+                Console.WriteLine($"⏭️ <{rule.Rule.Name}> kept: {rule.Refusal}");
+            }
+            else if (rule.IsChanged)
+            {
+                Console.WriteLine($"🔀 <{rule.Rule.Name}> ::= {string.Join(" | ", rule.Order.Select(a => a.Text))}");
+            }
+
+            foreach (var overlap in rule.Overlaps)
+            {
+                Console.WriteLine($"   kept in order: {overlap.Reason}");
+            }
+        }
+
+        Console.WriteLine($"📊 Attempted alternatives: {plan.AttemptsBefore} -> {plan.AttemptsAfter} ({plan.Improvement:P1} fewer)");
+        if (!plan.Changes.Any())
+        {
+            Console.WriteLine("✅ The alternatives are already in the best order the corpus supports");
+            return 0;
+        }
+
+        if (options.Apply)
+        {
+            var content = await File.ReadAllTextAsync(options.GrammarFile);
+            await File.WriteAllTextAsync(options.GrammarFile, plan.Apply(content));
+            Console.WriteLine($"💾 Reordered {plan.Changes.Count()} rules in {options.GrammarFile}");
+        }
+
+        return 0;
+    }
+
+    private static bool CaptureCorpusCase(CompiledGrammar grammar, string input, string corpusDirectory, CorpusCaptureOptions options)
+    {
+        try
+        {
+            var corpusCase = RegressionCorpus.Add(grammar, input, corpusDirectory, options);
+            var expectation = corpusCase.ExpectsDiagnostics ? "diagnostics" : "tree";
+            Console.WriteLine($"💾 Corpus case {corpusCase.Name} saved to: {corpusCase.InputPath} (expected {expectation}: {corpusCase.SnapshotPath})");
+            return true;
+        }
+        catch (InvalidOperationException ex)
+        {
+            Console.WriteLine($"❌ Corpus case not saved: {ex.Message}");
+            return false;
+        }
+    }
+
+    private async Task<int> HandleGrammarCommand(string[] args)
+    {
+        var options = ParseGrammarOptions(args);
+
+        if (options == null)
+        {
+            PrintGrammarUsage();
+            return 1;
+        }
+
+        if (options.Action == "language-configuration")
+        {
+            return await WriteLanguageConfigurationAsync(options);
+        }
+
+        if (options.Action == "conflicts")
+        {
+            return await ResolveConflictsAsync(options);
+        }
+
+        if (options.Action == "changelog")
+        {
+            return await WriteChangelogAsync(options);
+        }
+
+        if (options.Action == "bundle")
+        {
+            return WriteBundle(options);
+        }
+
+        var reader = new GrammarFileReader();
+        var from = await reader.ReadFileAsync(options.FromGrammarFile);
+        var to = await reader.ReadFileAsync(options.ToGrammarFile);
+        var manifestFile = options.ManifestFile ?? MigrationManifest.GetPath(options.ToGrammarFile);
+
+        Console.WriteLine($"🔍 Checking migrations {manifestFile} from {from.Name} {from.Version} to {to.Name} {to.Version}");
+
+        try
+        {
+            var diagnostics = MigrationManifest.Load(manifestFile).Check(from, to);
+            foreach (var diagnostic in diagnostics)
+            {
+                Console.WriteLine($"{(diagnostic.Severity == DiagnosticSeverity.Error ? "❌" : "⚠️")} {diagnostic}");
+            }
+
+            if (diagnostics.Count > 0)
+            {
+                return 1;
+            }
+
+            Console.WriteLine($"✅ Every rule removed since {from.Version} is migrated");
+            return 0;
+        }
+        catch (Exception ex) when (ex is FormatException or ArgumentException)
+        {
+            Console.WriteLine($"❌ {ex.Message}");
+            return 1;
+        }
+    }
+
+    private static async Task<int> WriteLanguageConfigurationAsync(GrammarCommandOptions options)
+    {
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var configuration = CompiledGrammar.Compile(grammar).EditorInfo.ToLanguageConfiguration();
+        if (options.OutputFile == null)
+        {
+            Console.WriteLine(configuration);
+            return 0;
+        }
+
+        await File.WriteAllTextAsync(options.OutputFile, configuration);
+        Console.WriteLine($"✅ Wrote the language configuration of {grammar.Name} to {options.OutputFile}");
+        return 0;
+    }
+
+    private static int WriteBundle(GrammarCommandOptions options)
+    {
+        var output = options.OutputFile ?? Path.GetFileName(Path.TrimEndingDirectorySeparator(Path.GetFullPath(options.BundleDirectory))) + GrammarBundle.Extension;
+        try
+        {
+            var manifest = GrammarBundle.Create(options.BundleDirectory, output);
+            foreach (var kind in manifest.Artifacts.GroupBy(a => a.Kind).OrderBy(g => g.Key))
+            {
+                Console.WriteLine($"  {kind.Key}: {string.Join(", ", kind.Select(a => a.Path))}");
+            }
+
+            Console.WriteLine($"✅ Bundled {manifest.Name} {manifest.Version} (Minotaur {manifest.Compatibility}) into {output}");
+            return 0;
+        }
+        catch (GrammarBundleException ex)
+        {
+            Console.WriteLine($"❌ {ex.Message}");
+            return 1;
+        }
+    }
+
+    private static async Task<int> WriteChangelogAsync(GrammarCommandOptions options)
+    {
+        CompiledGrammar from, to;
+        try
+        {
+            from = CompiledGrammar.Compile(await ReadVersionAsync(options.FromGrammarFile));
+            to = CompiledGrammar.Compile(await ReadVersionAsync(options.ToGrammarFile));
+        }
+        catch (ArgumentException ex)
+        {
+            Console.WriteLine($"❌ {ex.Message}");
+            return 1;
+        }
+
+        var changelog = new GrammarChangelog(from, to).ToMarkdown();
+        if (options.OutputFile == null)
+        {
+            Console.Write(changelog);
+            return 0;
+        }
+
+        await File.WriteAllTextAsync(options.OutputFile, changelog);
+        Console.WriteLine($"✅ Wrote the changelog of {to.Name} from {from.Source.Version} to {to.Source.Version} to {options.OutputFile}");
+        return 0;
+    }
+
+    // A version written after the file, as in calc.grammar@1.3, must match the grammar's Version header.
+    private static async Task<Grammar> ReadVersionAsync(string reference)
+    {
+        var separator = reference.LastIndexOf('@');
+        var file = separator > 0 && !File.Exists(reference) ? reference[..separator] : reference;
+        var grammar = await new GrammarFileReader().ReadFileAsync(file);
+        if (file != reference && grammar.Version != reference[(separator + 1)..])
+        {
+            throw new ArgumentException($"{file} is version {grammar.Version} of {grammar.Name}, not {reference[(separator + 1)..]}");
+        }
+
+        return grammar;
+    }
+
+    private static async Task<int> ResolveConflictsAsync(GrammarCommandOptions options)
+    {
+        var content = await File.ReadAllTextAsync(options.GrammarFile);
+        CompiledGrammar grammar;
+        try
+        {
+            grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(content), options.StartRule);
+        }
+        catch (ArgumentException ex)
+        {
+            Console.WriteLine($"❌ {ex.Message}");
+            return 1;
+        }
+
+        var assistant = new GrammarConflictAssistant(grammar);
+        var all = assistant.FindConflicts();
+        var conflicts = all.Where(c => !c.IsAccepted).ToList();
+        Console.WriteLine($"🔍 {grammar.Name}: {conflicts.Count} conflict(s), {all.Count - conflicts.Count} accepted with // @ambiguous");
+
+        var resolved = 0;
+        for (var n = 0; n < conflicts.Count; n++)
+        {
+            var conflict = conflicts[n];
+            Console.WriteLine();
+            Console.WriteLine($"Conflict {n + 1} of {conflicts.Count} in <{conflict.Rule.Name}>: '{conflict.Text}'");
+            Console.WriteLine($"  1st (kept): {conflict.FirstTree}    via {conflict.First.Text}");
+            Console.WriteLine($"  2nd:        {conflict.SecondTree}    via {conflict.Second.Text}");
+            if (!options.Suggest && !options.Interactive)
+            {
+                continue;
+            }
+
+            var resolutions = assistant.Propose(conflict);
+            for (var r = 0; r < resolutions.Count; r++)
+            {
+                var verdict = string.Empty;
+                if (options.Suggest)
+                {
+                    var outcome = TryResolve(assistant, content, conflict, resolutions[r]);
+                    verdict = outcome == null ? " ❌ does not compile"
+                        : outcome.IsSuccess ? " ✅"
+                        : !outcome.Resolved ? " ❌ leaves the conflict"
+                        : $" ⚠️  adds {outcome.NewConflicts.Count} conflict(s)";
+                }
+
+                Console.WriteLine($"  [{r + 1}] {resolutions[r].Description}{verdict}");
+            }
+
+            if (!options.Interactive)
+            {
+                continue;
+            }
+
+            Console.Write($"Resolution (1-{resolutions.Count}, Enter to skip, q to quit): ");
+            var answer = Console.ReadLine()?.Trim();
+            if (answer is null or "q")
+            {
+                break;
+            }
+
+            if (!int.TryParse(answer, out var choice) || choice < 1 || choice > resolutions.Count)
+            {
+                continue;
+            }
+
+            var applied = TryResolve(assistant, content, conflict, resolutions[choice - 1]);
+            if (applied is not { IsSuccess: true })
+            {
+                Console.WriteLine(applied == null ? "❌ The edited grammar does not compile; nothing was written"
+                    : !applied.Resolved ? "❌ The edit leaves the conflict; nothing was written"
+                    : $"❌ The edit adds conflicts; nothing was written: {string.Join("; ", applied.NewConflicts)}");
+                continue;
+            }
+
+            content = applied.Content;
+            await File.WriteAllTextAsync(options.GrammarFile, content);
+            Console.WriteLine($"✅ Resolved; wrote {options.GrammarFile}");
+            resolved++;
+
+            // Later conflicts are found again in the edited grammar, since the edit may have resolved them too.
+            grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(content), options.StartRule);
+            assistant = new GrammarConflictAssistant(grammar);
+            var remaining = assistant.FindConflicts().Where(c => !c.IsAccepted).ToList();
+            conflicts = conflicts.Take(n + 1).Concat(remaining).ToList();
+        }
+
+        if (options.Interactive)
+        {
+            Console.WriteLine();
+            Console.WriteLine($"Resolved {resolved} conflict(s)");
+        }
+
+        return !options.Interactive && conflicts.Count > 0 ? 1 : 0;
+    }
+
+    private static ConflictResolutionOutcome? TryResolve(GrammarConflictAssistant assistant, string content, GrammarConflict conflict, ConflictResolution resolution)
+    {
+        try
+        {
+            return assistant.Resolve(content, conflict, resolution);
+        }
+        catch (ArgumentException)
+        {
+            return null;
+        }
+    }
+
+    private async Task<int> HandleDaemonCommand(string[] args)
+    {
+        var options = ParseDaemonOptions(args);
+
+        if (options == null)
+        {
+            PrintDaemonUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var daemon = new WorkspaceDaemon(CreateParser(grammar, options.StartRule), options.Root, new WorkspaceDaemonOptions
+        {
+            SearchPattern = options.SearchPattern,
+            CheckpointPath = options.CheckpointFile,
+            Watchdog = options.StallTimeout is { } timeout ? new ParseWatchdogOptions { Interval = TimeSpan.FromMilliseconds(timeout) } : null
+        });
+
+        using var stop = new CancellationTokenSource();
+        Console.CancelKeyPress += (_, e) =>
+        {
+            e.Cancel = true;
+            stop.Cancel();
+        };
+
+        var run = daemon.RunAsync(options.SocketPath, stop.Token);
+        await Task.WhenAny(daemon.Started, run);
+        if (daemon.Started.IsCompletedSuccessfully)
+        {
+            Console.WriteLine($"👂 Serving {Path.GetFullPath(options.Root)} with grammar {grammar.Name} on {options.SocketPath}");
+        }
+
+        await run;
+        Console.WriteLine("👋 Daemon stopped");
+        return 0;
+    }
+
+    private async Task<int> HandleSgrepCommand(string[] args)
+    {
+        var options = ParseSgrepOptions(args);
+
+        if (options == null)
+        {
+            PrintSgrepUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var parser = CreateParser(grammar, options.StartRule);
+
+        try
+        {
+            var pattern = options.PatternRule != null
+                ? StructuralPattern.Compile(parser.Grammar, options.PatternRule, options.Pattern)
+                : StructuralPattern.Compile(parser.Grammar, options.Pattern);
+
+            var found = 0;
+            foreach (var file in options.InputFiles)
+            {
+                var parse = parser.Parse(await File.ReadAllTextAsync(file), new ParseOptions { SourceFile = file });
+                if (!parse.IsSuccess)
+                {
+                    Console.WriteLine($"⚠️  Skipping {file}: {parse.Diagnostics.First(d => d.Severity == DiagnosticSeverity.Error)}");
+                    continue;
+                }
+
+                var matches = pattern.FindAll(parse);
+                found += matches.Count;
+                if (options.Rewrite == null)
+                {
+                    foreach (var match in matches)
+                    {
+                        var position = match.Node.SourcePosition!;
+                        Console.WriteLine($"{file}:{position.Line}:{position.Column}: {match.Text.Split('\n')[0].TrimEnd()}");
+                    }
+                }
+                else if (matches.Count > 0)
+                {
+                    var result = pattern.RewriteAll(parser, parse, options.Rewrite);
+                    if (options.InPlace)
+                    {
+                        await File.WriteAllTextAsync(file, result.Text);
+                        Console.WriteLine($"✏️  Rewrote {matches.Count} match(es) in {file}");
+                    }
+                    else
+                    {
+                        Console.Write(result.Text);
+                    }
+                }
+            }
+
+            return found > 0 ? 0 : 1;
+        }
+        catch (ArgumentException ex)
+        {
+            Console.WriteLine($"❌ {ex.Message}");
+            return 1;
+        }
+    }
+
+    private async Task<int> HandleSymbolsCommand(string[] args)
+    {
+        var options = ParseSymbolsOptions(args);
+
+        if (options == null)
+        {
+            PrintSymbolsUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var workspace = new AnalysisWorkspace(CreateParser(grammar, options.StartRule));
+        var root = Path.GetFullPath(options.Root);
+        var files = Directory.EnumerateFiles(root, options.SearchPattern, SearchOption.AllDirectories)
+            .ToDictionary(f => Path.GetRelativePath(root, f).Replace(Path.DirectorySeparatorChar, '/'), File.ReadAllText, StringComparer.Ordinal);
+
+        if (options.CheckpointFile != null)
+        {
+            workspace.RestoreCheckpoint(options.CheckpointFile, files);
+            workspace.SaveCheckpoint(options.CheckpointFile);
+        }
+        else
+        {
+            foreach (var (path, text) in files.OrderBy(f => f.Key, StringComparer.Ordinal))
+            {
+                workspace.SetDocument(path, text);
+            }
+        }
+
+        var matches = workspace.SymbolIndex.Search(options.Query, options.Limit);
+        foreach (var match in matches)
+        {
+            var symbol = match.Symbol;
+            var location = symbol.Location is { } position ? $"{symbol.Path}:{position.Line}:{position.Column}" : symbol.Path;
+            var container = symbol.Container != null ? $" in {symbol.Container}" : string.Empty;
+            Console.WriteLine($"{location}: {symbol.Kind} {symbol.Name}{container}");
+        }
+
+        return matches.Count > 0 ? 0 : 1;
+    }
+
+    private async Task<int> HandleSkeletonCommand(string[] args)
+    {
+        var options = ParseSkeletonOptions(args);
+
+        if (options == null)
+        {
+            PrintSkeletonUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var parser = CreateParser(grammar, options.StartRule);
+        foreach (var file in options.InputFiles)
+        {
+            var entries = SkeletonExtractor.Extract(parser, await File.ReadAllTextAsync(file), file);
+            if (options.Format == "jsonl")
+            {
+                SkeletonExtractor.WriteJsonLines(Console.Out, entries, file);
+                continue;
+            }
+
+            foreach (var entry in entries)
+            {
+                Console.WriteLine($"{file}:{entry.Location.Line}:{entry.Location.Column}: {entry.Kind} {entry.GetQualifiedName()}: {entry.Signature}");
+            }
+        }
+
+        return 0;
+    }
+
+    private async Task<int> HandleModulesCommand(string[] args)
+    {
+        var options = ParseModulesOptions(args);
+
+        if (options == null)
+        {
+            PrintModulesUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var workspace = new AnalysisWorkspace(CreateParser(grammar, options.StartRule));
+        var root = Path.GetFullPath(options.Root);
+        foreach (var file in Directory.EnumerateFiles(root, options.SearchPattern, SearchOption.AllDirectories).Order(StringComparer.Ordinal))
+        {
+            workspace.SetDocument(Path.GetRelativePath(root, file).Replace(Path.DirectorySeparatorChar, '/'), await File.ReadAllTextAsync(file));
+        }
+
+        var graph = workspace.GetModuleGraph();
+        string output;
+        switch (options.Format)
+        {
+            case "json":
+                output = graph.ToJson();
+                break;
+
+            case "dot":
+                output = graph.ToDot();
+                break;
+
+            default:
+                var text = new StringBuilder();
+                foreach (var module in graph.TopologicalOrder())
+                {
+                    var dependencies = graph.DependenciesOf(module);
+                    text.AppendLine(dependencies.Count > 0 ? $"{module} -> {string.Join(", ", dependencies)}" : module);
+                }
+
+                foreach (var diagnostic in graph.Diagnostics)
+                {
+                    text.AppendLine($"⚠️ {diagnostic.Message}");
+                }
+
+                output = text.ToString();
+                break;
+        }
+
+        if (options.OutputFile != null)
+        {
+            await File.WriteAllTextAsync(options.OutputFile, output);
+            Console.WriteLine($"✅ Wrote the graph of {graph.Modules.Count} modules to {options.OutputFile}");
+        }
+        else
+        {
+            Console.Write(output);
+        }
+
+        foreach (var unresolved in graph.Unresolved)
+        {
+            var location = unresolved.Location is { } position ? $"{unresolved.From}:{position.Line}:{position.Column}" : unresolved.From;
+            Console.Error.WriteLine($"❌ {location}: {unresolved.Failure}");
+        }
+
+        return graph.Unresolved.Count > 0 ? 1 : 0;
+    }
+
+    private async Task<int> HandleIndexCommand(string[] args)
+    {
+        var options = ParseIndexOptions(args);
+
+        if (options == null)
+        {
+            PrintIndexUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var workspace = new AnalysisWorkspace(CreateParser(grammar, options.StartRule));
+        var root = Path.GetFullPath(options.Root);
+        var output = Path.GetFullPath(options.OutputDirectory);
+        foreach (var file in Directory.EnumerateFiles(root, options.SearchPattern, SearchOption.AllDirectories).Order(StringComparer.Ordinal))
+        {
+            if (!file.StartsWith(output + Path.DirectorySeparatorChar, StringComparison.Ordinal))
+            {
+                workspace.SetDocument(Path.GetRelativePath(root, file).Replace(Path.DirectorySeparatorChar, '/'), await File.ReadAllTextAsync(file));
+            }
+        }
+
+        var result = new SymbolIndexExporter(workspace).Export(output, options.Incremental);
+        if (options.Incremental && result.FallbackReason != null)
+        {
+            Console.WriteLine($"⚠️ Exporting every file: {result.FallbackReason}");
+        }
+
+        foreach (var path in result.Emitted)
+        {
+            Console.WriteLine($"  wrote   {path}");
+        }
+
+        foreach (var path in result.Removed)
+        {
+            Console.WriteLine($"  removed {path}");
+        }
+
+        Console.WriteLine($"✅ Indexed {result.Emitted.Count} files into {options.OutputDirectory} ({result.Skipped.Count} unchanged, {result.Removed.Count} removed)");
+        return 0;
+    }
+
+    private async Task<int> HandleMutateCommand(string[] args)
+    {
+        var options = ParseMutateOptions(args);
+
+        if (options == null)
+        {
+            PrintMutateUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var parser = CreateParser(grammar, options.StartRule);
+        var text = await File.ReadAllTextAsync(options.InputFile);
+        var parse = parser.Parse(text, new ParseOptions { SourceFile = options.InputFile });
+        if (!parse.IsSuccess)
+        {
+            Console.WriteLine($"❌ {options.InputFile} does not parse: {parse.Diagnostics.First(d => d.Severity == DiagnosticSeverity.Error)}");
+            return 1;
+        }
+
+        var mutants = new Mutator(parser).Generate(parse, options.Count, options.Seed);
+        if (options.OutputDirectory != null)
+        {
+            Directory.CreateDirectory(options.OutputDirectory);
+        }
+
+        for (var i = 0; i < mutants.Count; i++)
+        {
+            var mutant = mutants[i];
+            var position = mutant.Mutation.Location;
+            if (options.OutputDirectory != null)
+            {
+                var name = $"{Path.GetFileNameWithoutExtension(options.InputFile)}.mutant{i + 1}{Path.GetExtension(options.InputFile)}";
+                await File.WriteAllTextAsync(Path.Combine(options.OutputDirectory, name), mutant.Text);
+                Console.WriteLine($"🧬 {name}: {mutant.Mutation.Description} at {position.Line}:{position.Column}");
+            }
+            else
+            {
+                Console.WriteLine($"🧬 #{i + 1} {mutant.Mutation.Description} at {position.Line}:{position.Column}");
+                Console.Write(UnifiedDiff.Create(options.InputFile, text, mutant.Text));
+            }
+        }
+
+        Console.WriteLine($"✅ {mutants.Count} mutant(s) of {options.InputFile} with seed {options.Seed}");
+        return mutants.Count > 0 ? 0 : 1;
+    }
+
+    private async Task<int> HandleDiffCommand(string[] args)
+    {
+        var options = ParseDiffOptions(args);
+
+        if (options == null)
+        {
+            PrintDiffUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var parser = CreateParser(grammar, options.StartRule);
+        var parses = new List<ParseResult>();
+        foreach (var file in new[] { options.OldFile, options.NewFile })
+        {
+            var parse = parser.Parse(await File.ReadAllTextAsync(file), new ParseOptions { SourceFile = file });
+            if (parse.Tree == null)
+            {
+                Console.WriteLine($"❌ {file} does not parse: {parse.Diagnostics.First(d => d.Severity == DiagnosticSeverity.Error)}");
+                return 2;
+            }
+
+            parses.Add(parse);
+        }
+
+        var diff = SemanticDiff.Create(parses[0], parses[1]);
+        var renderer = new SemanticDiffRenderer();
+        if (options.HtmlFile != null)
+        {
+            await File.WriteAllTextAsync(options.HtmlFile, renderer.RenderHtml(diff, $"{options.OldFile} → {options.NewFile}"));
+            Console.WriteLine($"📄 Diff written to {options.HtmlFile}");
+        }
+        else
+        {
+            Console.Write(renderer.RenderTerminal(diff, options.ContextLines, options.Color && !Console.IsOutputRedirected));
+        }
+
+        return diff.HasChanges ? 1 : 0;
+    }
+
+    private async Task<int> HandleExplainCommand(string[] args)
+    {
+        var options = ParseExplainOptions(args);
+
+        if (options == null)
+        {
+            PrintExplainUsage();
+            return 1;
+        }
+
+        var catalog = DiagnosticCatalog.Default;
+        if (options.GrammarFile != null)
+        {
+            var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+            catalog.RegisterGrammar(CompiledGrammar.Compile(grammar));
+        }
+
+        if (options.List)
+        {
+            foreach (var entry in catalog.Descriptors)
+            {
+                Console.WriteLine($"{entry.Code,-10} {entry.Severity.ToString().ToLowerInvariant(),-8} {entry.Message}");
+            }
+
+            return 0;
+        }
+
+        if (catalog.Get(options.Code!) is not { } descriptor)
+        {
+            Console.WriteLine($"❌ Unknown diagnostic code '{options.Code}'. Use 'explain --list' to see all codes.");
+            return 1;
+        }
+
+        var origin = descriptor.Grammar != null ? $" (grammar {descriptor.Grammar})" : string.Empty;
+        Console.WriteLine($"{descriptor.Code}: {descriptor.Severity.ToString().ToLowerInvariant()}{origin}");
+        Console.WriteLine($"Message: {descriptor.Message}");
+        Console.WriteLine();
+        Console.WriteLine(descriptor.Explanation);
+
+        if (descriptor.Example is { } example)
+        {
+            Console.WriteLine();
+            Console.WriteLine("Example of input producing it:");
+            Console.WriteLine();
+            Console.WriteLine($"    {example.Failing.ReplaceLineEndings("\n    ")}");
+            Console.WriteLine();
+            Console.WriteLine("Corrected:");
+            Console.WriteLine();
+            Console.WriteLine($"    {example.Fixed.ReplaceLineEndings("\n    ")}");
+        }
+
+        return 0;
+    }
+
+    private async Task<int> HandleCheckCommand(string[] args)
+    {
+        var options = ParseCheckOptions(args);
+
+        if (options == null)
+        {
+            PrintCheckUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var parser = CreateParser(grammar, options.StartRule);
+        var formatter = new DiagnosticFormatter { Localizer = await CreateLocalizerAsync(options.Locale, options.GrammarFile) };
+
+        UnifiedDiff? patch = null;
+        string? oldRevision = null;
+        if (options.DiffFrom != null)
+        {
+            var baseline = await File.ReadAllTextAsync(options.DiffFrom);
+            if (UnifiedDiff.IsPatch(baseline))
+            {
+                patch = UnifiedDiff.Parse(baseline);
+            }
+            else if (options.InputFiles.Length > 1)
+            {
+                Console.WriteLine("❌ --diff-from names a file revision, which can only be compared with one file; pass a patch to check several");
+                return 1;
+            }
+            else
+            {
+                oldRevision = baseline;
+            }
+        }
+
+        if (!options.ChangedOnly)
+        {
+            var result = await CheckAllDiagnosticsAsync(options, parser, formatter);
+            PrintLocalizerWarnings(formatter);
+            return result;
+        }
+
+        var reported = 0;
+        foreach (var file in options.InputFiles)
+        {
+            var text = await File.ReadAllTextAsync(file);
+            var parseOptions = new ParseOptions { SourceFile = file };
+            var fileDiff = patch?.Find(file);
+            if (patch != null && fileDiff == null)
+            {
+                // The patch leaves the file alone, so it cannot have introduced anything there.
+                continue;
+            }
+
+            var diffOptions = new DiffAnalysisOptions { ContextLines = options.ContextLines, ParseOptions = parseOptions };
+            DiffAnalysis analysis;
+            try
+            {
+                analysis = fileDiff != null
+                    ? DiffAnalysis.Analyze(parser, fileDiff, text, diffOptions)
+                    : DiffAnalysis.Analyze(parser, oldRevision!, text, diffOptions);
+            }
+            catch (InvalidOperationException ex)
+            {
+                Console.WriteLine($"❌ {ex.Message}");
+                return 1;
+            }
+
+            foreach (var diagnostic in analysis.Reported)
+            {
+                Console.WriteLine(formatter.Format(diagnostic));
+            }
+
+            reported += analysis.Reported.Count;
+            Console.WriteLine($"{file}: {analysis.Get(DiagnosticChange.New).Count()} new ({analysis.Reported.Count} on changed lines), " +
+                $"{analysis.Get(DiagnosticChange.PreExisting).Count()} pre-existing, {analysis.Get(DiagnosticChange.Fixed).Count()} fixed");
+        }
+
+        PrintLocalizerWarnings(formatter);
+        return reported > 0 ? 1 : 0;
+    }
+
+    private static async Task<int> CheckAllDiagnosticsAsync(CheckCommandOptions options, GeneralizedParser parser, DiagnosticFormatter formatter)
+    {
+        // Full checks share the run's error budget; a diff needs every error of both revisions.
+        var budget = await LoadDiagnosticBudgetAsync();
+        var aggregator = new DiagnosticAggregator(budget.Limits, options.AllDiagnostics);
+        aggregator.AddParallel(options.InputFiles, file =>
+        {
+            var parse = parser.Parse(File.ReadAllText(file), new ParseOptions { SourceFile = file, DiagnosticBudget = budget });
+            var passes = new PassManager();
+            passes.RegisterBuiltInPasses();
+            return (file, parse.Diagnostics.Concat(passes.Run(parse, file).Diagnostics).ToList());
+        }, options.Jobs);
+
+        var aggregation = aggregator.Complete();
+        foreach (var diagnostic in aggregation.Reported)
+        {
+            Console.WriteLine(formatter.Format(diagnostic));
+        }
+
+        if (options.SarifFile != null)
+        {
+            await File.WriteAllTextAsync(options.SarifFile, formatter.ToSarif(aggregation.All ?? aggregation.Reported));
+        }
+
+        if (options.InputFiles.Length > 1 && budget.CreateRunSummary() is { } summary)
+        {
+            Console.WriteLine(formatter.Format(summary));
+        }
+
+        if (aggregation.Totals.Count > 0)
+        {
+            Console.WriteLine();
+            Console.WriteLine("Summary:");
+            foreach (var total in aggregation.Totals)
+            {
+                var collapsed = total.Collapsed > 0 ? $", {total.Collapsed} collapsed" : string.Empty;
+                Console.WriteLine($"  {total.Code,-8} {total.Total,7} in {total.Files} file(s){collapsed}");
+            }
+        }
+
+        return aggregation.Totals.Count > 0 ? 1 : 0;
+    }
+
+    private static async Task<DiagnosticBudget> LoadDiagnosticBudgetAsync()
+    {
+        using var manager = GrammarDetectionManager.CreateDefault();
+        var configuration = await manager.GetConfigurationAsync(Directory.GetCurrentDirectory());
+        return new DiagnosticBudget(configuration?.DiagnosticLimits);
+    }
+
+    private static async Task<DiagnosticLocalizer> CreateLocalizerAsync(string? locale, string grammarFile)
+    {
+        using var manager = GrammarDetectionManager.CreateDefault();
+        var configuration = await manager.GetConfigurationAsync(Directory.GetCurrentDirectory());
+        var localizer = new DiagnosticLocalizer(locale, configuration?.Locale);
+        localizer.LoadGrammarCatalogs(grammarFile);
+        return localizer;
+    }
+
+    private static void PrintLocalizerWarnings(DiagnosticFormatter formatter)
+    {
+        foreach (var warning in formatter.Localizer?.Warnings ?? Array.Empty<string>())
+        {
+            Console.WriteLine($"⚠️ {warning}");
+        }
+    }
+
+    private async Task<int> HandleTestCommand(string[] args)
+    {
+        var options = ParseTestOptions(args);
+
+        if (options == null)
+        {
+            PrintTestUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var compiled = CompiledGrammar.Compile(grammar, options.StartRule);
+        var corpusDirectory = options.CorpusDirectory ?? RegressionCorpus.GetDirectory(options.GrammarFile);
+
+        Console.WriteLine($"🔍 Testing corpus {corpusDirectory} with grammar: {grammar.Name}");
+
+        var failed = false;
+        try
+        {
+            var count = RegressionCorpus.Verify(compiled, corpusDirectory);
+            Console.WriteLine($"✅ {count} corpus cases match");
+        }
+        catch (SnapshotMismatchException ex)
+        {
+            Console.WriteLine($"❌ {ex.Message}");
+            failed = true;
+        }
+
+        var manifest = options.ManifestFile != null
+            ? CoverageManifest.Load(options.ManifestFile)
+            : CoverageManifest.ForGrammar(options.GrammarFile);
+        var report = GrammarCoverage.Measure(compiled, corpusDirectory).Check(manifest, options.GrammarFile);
+
+        Console.WriteLine($"📊 Coverage: {report.Covered}/{report.Total} alternatives ({report.Percentage:0.#}%), minimum {report.MinimumPercentage:0.#}%");
+        foreach (var uncovered in report.Uncovered)
+        {
+            var location = uncovered.Span is { } span ? $"{span.SourceFile}:{span.Line}:{span.Column}: " : string.Empty;
+            Console.WriteLine($"  {location}<{uncovered.Alternative.Rule.Name}> alternative {uncovered.Alternative.Index + 1} is not covered: {uncovered.Alternative.Text}");
+            Console.WriteLine(uncovered.SuggestedInput != null
+                ? $"    suggested input: {uncovered.SuggestedInput}"
+                : "    no input could be generated for it");
+        }
+
+        if (options.EnforceCoverage && !report.IsSatisfied)
+        {
+            Console.WriteLine($"❌ Coverage {report.Percentage:0.#}% is below the required {report.MinimumPercentage:0.#}%");
+            failed = true;
+        }
+
+        if (options.Engines.Count > 0)
+        {
+            var matrix = EngineMatrix.Run(compiled, corpusDirectory, options.Engines);
+            foreach (var skipped in matrix.Skipped)
+            {
+                Console.WriteLine($"⚠️  Skipped engine {skipped.Engine}: {skipped.Reason}");
+            }
+
+            foreach (var timing in matrix.Timings)
+            {
+                Console.WriteLine($"📊 {timing.Engine}: {timing.Elapsed.TotalMilliseconds:0.#} ms for {timing.Cases} cases ({timing.PerCase.TotalMilliseconds:0.##} ms per case)");
+            }
+
+            foreach (var divergence in matrix.Divergences)
+            {
+                Console.WriteLine($"❌ {divergence.Case}: {divergence.Engine} diverges from {divergence.Reference}");
+                Console.Write(divergence.Diff);
+            }
+
+            if (matrix.IsConsistent && matrix.Engines.Count > 1)
+            {
+                Console.WriteLine($"✅ {string.Join(", ", matrix.Engines)} agree on {matrix.CaseCount} corpus cases");
+            }
+
+            failed |= !matrix.IsConsistent;
+        }
+
+        return failed ? 1 : 0;
+    }
+
+    private async Task<int> HandleConfigCommand(string[] args)
+    {
+        var options = ParseConfigOptions(args);
+
+        if (options == null)
+        {
+            PrintConfigUsage();
+            return 1;
+        }
+
+        var filePath = Path.GetFullPath(options.File);
+        var root = Path.GetFullPath(options.Root ?? Path.GetDirectoryName(filePath) ?? ".");
+
+        using var manager = GrammarDetectionManager.CreateDefault();
+        var trace = await manager.ExplainAsync(filePath, root, options.ProjectType);
+
+        Console.WriteLine(options.Json ? trace.ToJson() : trace.ToNarrative());
+        return trace.Result.IsSuccessful ? 0 : 1;
+    }
+
+    private async Task<int> HandleSelftestCommand(string[] args)
+    {
+        var options = ParseSelftestOptions(args);
+
+        if (options == null)
+        {
+            PrintSelftestUsage();
+            return 1;
+        }
+
+        var grammar = await new GrammarFileReader().ReadFileAsync(options.GrammarFile);
+        var compiled = CompiledGrammar.Compile(grammar, options.StartRule);
+        var seed = options.Seed ?? Environment.TickCount;
+
+        Console.WriteLine($"🔍 Round-tripping {options.Iterations} sentences of {grammar.Name} (seed {seed})");
+
+        var result = RoundTripProperty.Check(compiled, options.Iterations, seed, new RoundTripOptions
+        {
+            ExcludedRules = options.ExcludedRules,
+            MaxDepth = options.MaxDepth
+        });
+
+        if (result.Failure != null)
+        {
+            Console.WriteLine($"❌ {result.Failure}");
+            return 1;
+        }
+
+        Console.WriteLine($"✅ {result.Iterations} sentences round-tripped");
+        return 0;
+    }
+
+    /// <summary>
+    /// Runs a predicate command on a candidate file. "{}" in the command is replaced by the file path; otherwise
+    /// the path is appended. The candidate fails when the exit code matches (any non-zero code if none is given)
+    /// and, if a pattern is given, the combined output matches it.
+    /// </summary>
+    private static bool RunPredicate(string command, string path, int? exitCode, Regex? outputPattern)
+    {
+        var quoted = $"\"{path}\"";
+        var commandLine = command.Contains("{}") ? command.Replace("{}", quoted) : $"{command} {quoted}";
+        var startInfo = OperatingSystem.IsWindows()
+            ? new ProcessStartInfo("cmd.exe") { ArgumentList = { "/c", commandLine } }
+            : new ProcessStartInfo("/bin/sh") { ArgumentList = { "-c", commandLine } };
+        startInfo.RedirectStandardOutput = true;
+        startInfo.RedirectStandardError = true;
+        startInfo.UseShellExecute = false;
+
+        using var process = Process.Start(startInfo)!;
+        var stdout = process.StandardOutput.ReadToEndAsync();
+        var stderr = process.StandardError.ReadToEndAsync();
+        process.WaitForExit();
+
+        var exitMatches = exitCode is { } expected ? process.ExitCode == expected : process.ExitCode != 0;
+        return exitMatches && (outputPattern == null || outputPattern.IsMatch(stdout.Result + stderr.Result));
+    }
+
     private int HandleHelpCommand(string[] args)
     {
-        if (args.Length > 0)
+        if (args.Length > 0)
+        {
+            var topic = args[0].ToLower();
+            return topic switch
+            {
+                "generate" => PrintGenerateHelp(),
+                "validate" => PrintValidateHelp(),
+                "parse" => PrintParseHelp(),
+                "reparse" => PrintReparseHelp(),
+                "reduce" => PrintReduceHelp(),
+                "xtest" => PrintXtestHelp(),
+                "selftest" => PrintSelftestHelp(),
+                "corpus" => PrintCorpusHelp(),
+                "grammar" => PrintGrammarHelp(),
+                "daemon" => PrintDaemonHelp(),
+                "sgrep" => PrintSgrepHelp(),
+                "symbols" => PrintSymbolsHelp(),
+                "skeleton" => PrintSkeletonHelp(),
+                "modules" => PrintModulesHelp(),
+                "index" => PrintIndexHelp(),
+                "mutate" => PrintMutateHelp(),
+                "diff" => PrintDiffHelp(),
+                "explain" => PrintExplainHelp(),
+                "check" => PrintCheckHelp(),
+                "test" => PrintTestHelp(),
+                "config" => PrintConfigHelp(),
+                _ => PrintGeneralHelp()
+            };
+        }
+
+        return PrintGeneralHelp();
+    }
+
+    private int HandleUnknownCommand(string command)
+    {
+        Console.WriteLine($"Unknown command: {command}");
+        Console.WriteLine("Use 'help' to see available commands.");
+        return 1;
+    }
+
+    private GenerateOptions? ParseGenerateOptions(string[] args)
+    {
+        var options = new GenerateOptions();
+        var sourceFiles = new List<string>();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--language" or "-l":
+                    if (i + 1 < args.Length)
+                    {
+                        options.LanguageName = args[++i];
+                    }
+                    break;
+
+                case "--output" or "-o":
+                    if (i + 1 < args.Length)
+                    {
+                        options.OutputFile = args[++i];
+                    }
+                    break;
+
+                case "--source" or "-s":
+                    if (i + 1 < args.Length)
+                    {
+                        sourceFiles.AddRange(args[++i].Split(','));
+                    }
+                    break;
+
+                case "--context":
+                    // Parse context options (simplified)
+                    options.Context = new LanguageContext
+                    {
+                        HasNestedScopes = true,
+                        HasTypeSystem = true,
+                        HasComments = true,
+                        HasStringLiterals = true,
+                        HasNumericLiterals = true
+                    };
+                    break;
+
+                default:
+                    if (!args[i].StartsWith('-'))
+                    {
+                        sourceFiles.Add(args[i]);
+                    }
+                    break;
+            }
+        }
+
+        if (string.IsNullOrEmpty(options.LanguageName))
+        {
+            Console.WriteLine("Error: Language name is required (--language)");
+            return null;
+        }
+
+        if (!sourceFiles.Any())
+        {
+            Console.WriteLine("Error: At least one source file is required");
+            return null;
+        }
+
+        options.SourceFiles = sourceFiles.ToArray();
+        return options;
+    }
+
+    private ParseCommandOptions? ParseParseOptions(string[] args)
+    {
+        var options = new ParseCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                case "--forest-html":
+                    if (i + 1 < args.Length)
+                    {
+                        options.ForestHtmlFile = args[++i];
+                    }
+                    break;
+
+                case "--source-map":
+                    if (i + 1 < args.Length)
+                    {
+                        options.SourceMapFile = args[++i];
+                    }
+                    break;
+
+                case "--line-directives":
+                    options.LineDirectives = true;
+                    break;
+
+                case "--sarif":
+                    if (i + 1 < args.Length)
+                    {
+                        options.SarifFile = args[++i];
+                    }
+                    break;
+
+                case "--encoding":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Encoding = args[++i];
+                    }
+                    break;
+
+                case "--substitute-invalid":
+                    options.SubstituteInvalidBytes = true;
+                    break;
+
+                case "--stall-timeout":
+                    if (i + 1 < args.Length && int.TryParse(args[++i], out var stallTimeout))
+                    {
+                        options.StallTimeout = stallTimeout;
+                    }
+                    break;
+
+                case "--capture-corpus":
+                    options.CaptureCorpus = true;
+                    break;
+
+                case "--mem-report":
+                    options.MemoryReport = true;
+                    break;
+
+                case "--documents":
+                    options.Documents = true;
+                    break;
+
+                case "--path-context":
+                    options.PathContext = true;
+                    break;
+
+                case "--edits" or "-e":
+                    if (i + 1 < args.Length)
+                    {
+                        options.EditScriptFile = args[++i];
+                    }
+                    break;
+
+                case "--timeline-html":
+                    if (i + 1 < args.Length)
+                    {
+                        options.TimelineHtmlFile = args[++i];
+                    }
+                    break;
+
+                case "--predicate" or "-p":
+                    if (i + 1 < args.Length)
+                    {
+                        options.PredicateCommand = args[++i];
+                    }
+                    break;
+
+                case "--exit-code":
+                    if (i + 1 < args.Length)
+                    {
+                        options.PredicateExitCode = int.Parse(args[++i]);
+                    }
+                    break;
+
+                case "--output-regex":
+                    if (i + 1 < args.Length)
+                    {
+                        options.PredicateOutputPattern = args[++i];
+                    }
+                    break;
+
+                case "--max-tests":
+                    if (i + 1 < args.Length)
+                    {
+                        options.MaxTests = int.Parse(args[++i]);
+                    }
+                    break;
+
+                case "--output" or "-o":
+                    if (i + 1 < args.Length)
+                    {
+                        options.OutputFile = args[++i];
+                    }
+                    break;
+
+                default:
+                    if (!args[i].StartsWith('-'))
+                    {
+                        options.InputFile = args[i];
+                    }
+                    break;
+            }
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: Grammar file is required (--grammar)");
+            return null;
+        }
+
+        if (string.IsNullOrEmpty(options.InputFile))
+        {
+            Console.WriteLine("Error: An input file is required");
+            return null;
+        }
+
+        return options;
+    }
+
+    private void PrintUsage()
+    {
+        Console.WriteLine("Minotaur Grammar Generator");
+        Console.WriteLine("=========================");
+        Console.WriteLine();
+        Console.WriteLine("Usage: minotaur-grammar <command> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Commands:");
+        Console.WriteLine("  generate    Generate a grammar from source files");
+        Console.WriteLine("  validate    Validate a grammar against source files");
+        Console.WriteLine("  parse       Parse a file with a grammar");
+        Console.WriteLine("  reparse     Replay an edit script with incremental reparsing");
+        Console.WriteLine("  reduce      Shrink an input while a predicate command still fails");
+        Console.WriteLine("  xtest       Compare a grammar with a reference parser over a corpus");
+        Console.WriteLine("  selftest    Check that generated sentences survive parse, print and reparse");
+        Console.WriteLine("  corpus      Record inputs as regression cases and check them");
+        Console.WriteLine("  grammar     Check the migrations between grammar versions");
+        Console.WriteLine("  daemon      Keep a workspace warm and answer JSON-RPC requests on a socket");
+        Console.WriteLine("  sgrep       Search and rewrite code with structural patterns");
+        Console.WriteLine("  symbols     Find the declarations of a workspace by fuzzy name");
+        Console.WriteLine("  skeleton    List the declarations of files without parsing their bodies");
+        Console.WriteLine("  modules     Export the module dependency graph of a workspace");
+        Console.WriteLine("  index       Export the symbols of a workspace as code-search index files");
+        Console.WriteLine("  mutate      Generate valid mutants of an input for mutation testing");
+        Console.WriteLine("  diff        Compare two versions of a file token by token, showing moves");
+        Console.WriteLine("  explain     Explain a diagnostic code, with an example and its fix");
+        Console.WriteLine("  check       Report diagnostics, optionally only those a change introduced");
+        Console.WriteLine("  test        Check a grammar's corpus and how much of the grammar it covers");
+        Console.WriteLine("  config      Explain which grammar and version a file resolves to, and why");
+        Console.WriteLine("  help        Show help information");
+        Console.WriteLine();
+        Console.WriteLine("Use 'help <command>' for more information about a command.");
+    }
+
+    private void PrintGenerateUsage()
+    {
+        Console.WriteLine("Usage: generate --language <name> [options] <source-files...>");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --language, -l <name>     Name of the language");
+        Console.WriteLine("  --output, -o <file>       Output grammar file");
+        Console.WriteLine("  --source, -s <files>      Comma-separated source files");
+        Console.WriteLine("  --context                 Enable context-aware features");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  generate --language MyLang --output mylang.grammar src/*.mylang");
+        Console.WriteLine("  generate -l Python -o python.grammar *.py");
+    }
+
+    private int PrintGenerateHelp()
+    {
+        Console.WriteLine("Generate Command");
+        Console.WriteLine("================");
+        Console.WriteLine();
+        Console.WriteLine("Generates a grammar file from source code examples.");
+        Console.WriteLine();
+        PrintGenerateUsage();
+        Console.WriteLine();
+        Console.WriteLine("The generator analyzes source files to identify:");
+        Console.WriteLine("• Token patterns (keywords, operators, literals)");
+        Console.WriteLine("• Syntax structures (expressions, statements, declarations)");
+        Console.WriteLine("• Language constructs (functions, classes, control flow)");
+        Console.WriteLine();
+        Console.WriteLine("The output grammar follows the Grammar File Creation Guide format.");
+        return 0;
+    }
+
+    private void PrintParseUsage()
+    {
+        Console.WriteLine("Usage: parse <input-file> --grammar <grammar-file> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse with");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine("  --forest-html <file>      Write the parse forest as an HTML page");
+        Console.WriteLine("  --source-map <file>       Report diagnostics at original locations from a JSON source map");
+        Console.WriteLine("  --line-directives         Report diagnostics at original locations from #line directives");
+        Console.WriteLine("  --sarif <file>            Write diagnostics as a SARIF log");
+        Console.WriteLine("  --encoding <name>         Input encoding, e.g. windows-1252 (defaults to the BOM, then UTF-8)");
+        Console.WriteLine("  --substitute-invalid      Replace invalid bytes with U+FFFD and warn instead of failing");
+        Console.WriteLine("  --capture-corpus          On errors, add the reduced, anonymized input to the grammar's corpus");
+        Console.WriteLine("  --stall-timeout <ms>      Stop a parse that consumes no token for this long, with grammar hints");
+        Console.WriteLine("  --mem-report              Print the memory held by the grammar, its compiled tables and the parse");
+        Console.WriteLine("  --documents               Parse each document of the grammar's Documents policy on its own");
+        Console.WriteLine("  --path-context            Show the declarations enclosing each diagnostic, e.g. (in mod a > fn b)");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  parse input.txt --grammar dangling_else.grammar --forest-html forest.html");
+        Console.WriteLine("  parse input.txt --grammar dangling_else.grammar --mem-report");
+        Console.WriteLine("  parse script.sql --grammar sql.grammar --documents");
+    }
+
+    private int PrintParseHelp()
+    {
+        Console.WriteLine("Parse Command");
+        Console.WriteLine("=============");
+        Console.WriteLine();
+        Console.WriteLine("Parses a file with the generalized parser, which accepts ambiguous grammars.");
+        Console.WriteLine();
+        PrintParseUsage();
+        Console.WriteLine();
+        Console.WriteLine("The forest visualization shows:");
+        Console.WriteLine("• Each derivation of an ambiguous node in its own tab");
+        Console.WriteLine("• The source region where the derivations differ");
+        Console.WriteLine("• Shared subtrees once, linked from every use");
+        return 0;
+    }
+
+    private void PrintReparseUsage()
+    {
+        Console.WriteLine("Usage: reparse <input-file> --grammar <grammar-file> --edits <edit-script> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse with");
+        Console.WriteLine("  --edits, -e <file>        JSON array of edits: [{ \"offset\": 4, \"length\": 1, \"text\": \"x\" }]");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine("  --timeline-html <file>    Write the reparse timeline as an HTML page");
+    }
+
+    private int PrintReparseHelp()
+    {
+        Console.WriteLine("Reparse Command");
+        Console.WriteLine("===============");
+        Console.WriteLine();
+        Console.WriteLine("Parses a file, then applies each edit of a script and reparses incrementally.");
+        Console.WriteLine();
+        PrintReparseUsage();
+        Console.WriteLine();
+        Console.WriteLine("For every edit the timeline shows:");
+        Console.WriteLine("• The damaged and re-lexed ranges over a map of the file");
+        Console.WriteLine("• Reused and rebuilt tree node counts");
+        Console.WriteLine("• Lexing and parsing time");
+        return 0;
+    }
+
+    private XtestCommandOptions? ParseXtestOptions(string[] args)
+    {
+        var options = new XtestCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--corpus" or "-c":
+                    if (i + 1 < args.Length)
+                    {
+                        options.CorpusDirectory = args[++i];
+                    }
+                    break;
+
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--reference-command":
+                    if (i + 1 < args.Length)
+                    {
+                        options.ReferenceCommand = args[++i];
+                    }
+                    break;
+
+                case "--allowlist":
+                    if (i + 1 < args.Length)
+                    {
+                        options.AllowlistFile = args[++i];
+                    }
+                    break;
+
+                case "--root":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Root = args[++i];
+                    }
+                    break;
+
+                case "--dump":
+                    if (i + 1 < args.Length)
+                    {
+                        options.DumpDirectory = args[++i];
+                    }
+                    break;
+
+                default:
+                    if (!args[i].StartsWith('-'))
+                    {
+                        options.Language = args[i];
+                    }
+                    break;
+            }
+        }
+
+        if (string.IsNullOrEmpty(options.Language))
+        {
+            Console.WriteLine("Error: A language is required");
+            return null;
+        }
+
+        if (string.IsNullOrEmpty(options.CorpusDirectory))
+        {
+            Console.WriteLine("Error: A corpus directory is required (--corpus)");
+            return null;
+        }
+
+        return options;
+    }
+
+    private CorpusCommandOptions? ParseCorpusOptions(string[] args)
+    {
+        var options = new CorpusCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                case "--corpus" or "-c":
+                    if (i + 1 < args.Length)
+                    {
+                        options.CorpusDirectory = args[++i];
+                    }
+                    break;
+
+                case "--no-reduce":
+                    options.Reduce = false;
+                    break;
+
+                case "--no-anonymize":
+                    options.Anonymize = false;
+                    break;
+
+                case "--apply":
+                    options.Apply = true;
+                    break;
+
+                case "--max-tests":
+                    if (i + 1 < args.Length)
+                    {
+                        options.MaxTests = int.Parse(args[++i]);
+                    }
+                    break;
+
+                default:
+                    if (args[i].StartsWith('-'))
+                    {
+                        break;
+                    }
+
+                    if (options.Action == null)
+                    {
+                        options.Action = args[i];
+                    }
+                    else
+                    {
+                        options.InputFile = args[i];
+                    }
+                    break;
+            }
+        }
+
+        if (options.Action is not ("add" or "run" or "reorder"))
+        {
+            Console.WriteLine("Error: An action is required (add, run or reorder)");
+            return null;
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: Grammar file is required (--grammar)");
+            return null;
+        }
+
+        if (options.Action == "add" && string.IsNullOrEmpty(options.InputFile))
+        {
+            Console.WriteLine("Error: An input file is required");
+            return null;
+        }
+
+        return options;
+    }
+
+    private void PrintCorpusUsage()
+    {
+        Console.WriteLine("Usage: corpus add <input-file> --grammar <grammar-file> [options]");
+        Console.WriteLine("       corpus run --grammar <grammar-file> [options]");
+        Console.WriteLine("       corpus reorder --grammar <grammar-file> [--apply] [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse with");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine("  --corpus, -c <dir>        Corpus directory (defaults to corpus/<grammar> next to the grammar)");
+        Console.WriteLine("  --no-reduce               Store a failing input as it is instead of reducing it");
+        Console.WriteLine("  --no-anonymize            Keep identifiers, strings and comments");
+        Console.WriteLine("  --max-tests <count>       Stop reducing after this many parses (default 10000)");
+        Console.WriteLine("  --apply                   Rewrite the grammar file with the proposed order of alternatives");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  corpus add crash.src --grammar lang.grammar");
+        Console.WriteLine("  corpus run --grammar lang.grammar");
+        Console.WriteLine("  corpus reorder --grammar lang.grammar --apply");
+    }
+
+    private int PrintCorpusHelp()
+    {
+        Console.WriteLine("Corpus Command");
+        Console.WriteLine("==============");
+        Console.WriteLine();
+        Console.WriteLine("Records inputs as regression cases next to the grammar, checks them, and orders the grammar's alternatives by how often they are used.");
+        Console.WriteLine();
+        PrintCorpusUsage();
+        Console.WriteLine();
+        Console.WriteLine("Adding an input:");
+        Console.WriteLine("• Reduces it to the smallest input failing with the same error code");
+        Console.WriteLine("• Renames identifiers, scrubs string literals and drops comments, keeping how it parses");
+        Console.WriteLine("• Stores it with its diagnostics, or its tree if it parses, as the expected result");
+        return 0;
+    }
+
+    private GrammarCommandOptions? ParseGrammarOptions(string[] args)
+    {
+        var options = new GrammarCommandOptions();
+        var actions = new List<string>();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--from" or "-f":
+                    if (i + 1 < args.Length)
+                    {
+                        options.FromGrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--to" or "-t":
+                    if (i + 1 < args.Length)
+                    {
+                        options.ToGrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--manifest" or "-m":
+                    if (i + 1 < args.Length)
+                    {
+                        options.ManifestFile = args[++i];
+                    }
+                    break;
+
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--output" or "-o":
+                    if (i + 1 < args.Length)
+                    {
+                        options.OutputFile = args[++i];
+                    }
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                case "--suggest":
+                    options.Suggest = true;
+                    break;
+
+                case "--interactive" or "-i":
+                    options.Interactive = true;
+                    break;
+
+                default:
+                    if (!args[i].StartsWith('-'))
+                    {
+                        actions.Add(args[i]);
+                    }
+                    break;
+            }
+        }
+
+        if (actions is ["language-configuration"])
+        {
+            options.Action = "language-configuration";
+            if (string.IsNullOrEmpty(options.GrammarFile))
+            {
+                Console.WriteLine("Error: The grammar file is required (--grammar)");
+                return null;
+            }
+
+            return options;
+        }
+
+        if (actions is ["conflicts"])
+        {
+            options.Action = "conflicts";
+            if (string.IsNullOrEmpty(options.GrammarFile))
+            {
+                Console.WriteLine("Error: The grammar file is required (--grammar)");
+                return null;
+            }
+
+            return options;
+        }
+
+        if (actions is ["bundle", var directory])
+        {
+            options.Action = "bundle";
+            options.BundleDirectory = directory;
+            return options;
+        }
+
+        if (actions is not (["migrations", "check"] or ["changelog"]))
+        {
+            Console.WriteLine("Error: An action is required (migrations check, changelog, language-configuration, conflicts, bundle)");
+            return null;
+        }
+
+        if (string.IsNullOrEmpty(options.FromGrammarFile) || string.IsNullOrEmpty(options.ToGrammarFile))
+        {
+            Console.WriteLine("Error: The old and new grammar files are required (--from, --to)");
+            return null;
+        }
+
+        if (actions is ["changelog"])
+        {
+            options.Action = "changelog";
+        }
+
+        return options;
+    }
+
+    private void PrintGrammarUsage()
+    {
+        Console.WriteLine("Usage: grammar migrations check --from <old-grammar> --to <new-grammar> [options]");
+        Console.WriteLine("       grammar changelog --from <old-grammar>[@version] --to <new-grammar>[@version] [--output <file>]");
+        Console.WriteLine("       grammar language-configuration --grammar <grammar> [--output <file>]");
+        Console.WriteLine("       grammar conflicts --grammar <grammar> [--suggest | --interactive] [--rule <rule>]");
+        Console.WriteLine("       grammar bundle <directory> [--output <file.mgb>]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --from, -f <file>         Grammar file of the old version");
+        Console.WriteLine("  --to, -t <file>           Grammar file of the new version");
+        Console.WriteLine("  --manifest, -m <file>     Migration manifest (defaults to the new grammar with a .migrations extension)");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to derive an editor language configuration from");
+        Console.WriteLine("  --output, -o <file>       Where to write the changelog or language configuration (defaults to standard output),");
+        Console.WriteLine("                            or the bundle (defaults to the directory name with an .mgb extension)");
+        Console.WriteLine("  --rule, -r <rule>         Start rule or entry point to search for conflicts from");
+        Console.WriteLine("  --suggest                 Print the proposed resolutions of every conflict and whether each works");
+        Console.WriteLine("  --interactive, -i         Walk through the conflicts, applying the chosen resolutions to the grammar file");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  grammar migrations check --from lang-1.grammar --to lang-2.grammar");
+        Console.WriteLine("  grammar changelog --from calc-1.2.grammar@1.2 --to calc-1.3.grammar@1.3 --output CHANGELOG.md");
+        Console.WriteLine("  grammar language-configuration --grammar rust.grammar --output language-configuration.json");
+        Console.WriteLine("  grammar conflicts --grammar calc.grammar --interactive");
+        Console.WriteLine("  grammar bundle grammars/rust --output rust.mgb");
+    }
+
+    private int PrintGrammarHelp()
+    {
+        Console.WriteLine("Grammar Command");
+        Console.WriteLine("===============");
+        Console.WriteLine();
+        Console.WriteLine("Checks the migration manifest between two versions of a grammar, writes their changelog, writes the");
+        Console.WriteLine("VS Code language configuration (brackets, auto-closing pairs, comments) derived from a grammar, or");
+        Console.WriteLine("finds and resolves a grammar's ambiguities, or packs a grammar's files into one bundle.");
+        Console.WriteLine();
+        PrintGrammarUsage();
+        Console.WriteLine();
+        Console.WriteLine("Checks:");
+        Console.WriteLine("• Every rule of the old version that the new version lacks is renamed, split, merged or removed");
+        Console.WriteLine("• Every rule a migration maps to is defined in the new version");
+        Console.WriteLine("• Versions come from the grammars' 'Version:' headers");
+        Console.WriteLine();
+        Console.WriteLine("Changelog:");
+        Console.WriteLine("• New and removed syntax: alternatives that are in only one version, each with an example sentence");
+        Console.WriteLine("  generated through it, preferring one the other version rejects");
+        Console.WriteLine("• Deprecations: // @deprecated annotations added or removed");
+        Console.WriteLine("• Behavior changes: precedence and associativity, accepted ambiguities and feature guards");
+        Console.WriteLine();
+        Console.WriteLine("Conflicts:");
+        Console.WriteLine("• Sentences generated through every alternative are parsed; every span with two derivations is a conflict");
+        Console.WriteLine("• Each conflict shows its shortest example and both derivations, the one the parser keeps first");
+        Console.WriteLine("• Resolutions: a 'Precedence:' declaration for operator alternatives, left-factoring shared prefixes,");
+        Console.WriteLine("  or accepting the kept derivation with a // @ambiguous annotation");
+        Console.WriteLine("• An edit is only written once the conflict is gone and no new one appeared");
+        Console.WriteLine();
+        Console.WriteLine("Bundle:");
+        Console.WriteLine("• Packs every file of the directory: *.grammar sources, *.messages catalogs, detector.json,");
+        Console.WriteLine("  queries/ and docs/, indexed by a manifest with each file's SHA-256");
+        Console.WriteLine("• bundle.json in the directory may set \"name\", \"version\", \"grammar\" and the \"minotaur\" range");
+        Console.WriteLine("• The grammars must compile and catalogs and the detector profile must parse before anything is written");
+        Console.WriteLine("• Grammar containers load *.mgb files beside *.grammar files, checking each artifact when it is read");
+        return 0;
+    }
+
+    private DaemonCommandOptions? ParseDaemonOptions(string[] args)
+    {
+        var options = new DaemonCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--socket" or "-s":
+                    if (i + 1 < args.Length)
+                    {
+                        options.SocketPath = args[++i];
+                    }
+                    break;
+
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--root":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Root = args[++i];
+                    }
+                    break;
+
+                case "--include":
+                    if (i + 1 < args.Length)
+                    {
+                        options.SearchPattern = args[++i];
+                    }
+                    break;
+
+                case "--checkpoint":
+                    if (i + 1 < args.Length)
+                    {
+                        options.CheckpointFile = args[++i];
+                    }
+                    break;
+
+                case "--stall-timeout":
+                    if (i + 1 < args.Length && int.TryParse(args[++i], out var timeout))
+                    {
+                        options.StallTimeout = timeout;
+                    }
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+            }
+        }
+
+        if (string.IsNullOrEmpty(options.SocketPath))
+        {
+            Console.WriteLine("Error: A socket path is required (--socket)");
+            return null;
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: A grammar file is required (--grammar)");
+            return null;
+        }
+
+        return options;
+    }
+
+    private void PrintDaemonUsage()
+    {
+        Console.WriteLine("Usage: daemon --socket <path> --grammar <grammar-file> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --socket, -s <path>       Unix domain socket to listen on");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse the workspace with");
+        Console.WriteLine("  --root <dir>              Workspace directory, watched recursively (defaults to the current directory)");
+        Console.WriteLine("  --include <pattern>       Pattern of workspace files (default *)");
+        Console.WriteLine("  --checkpoint <file>       Restore the workspace from this checkpoint and save it on shutdown");
+        Console.WriteLine("  --stall-timeout <ms>      Stop parses of unsaved text that make no progress for this long");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  daemon --socket /tmp/minotaur.sock --grammar lang.grammar --root src --include \"*.lang\"");
+    }
+
+    private int PrintDaemonHelp()
+    {
+        Console.WriteLine("Daemon Command");
+        Console.WriteLine("==============");
+        Console.WriteLine();
+        Console.WriteLine("Keeps the parse and analysis of a directory warm and answers JSON-RPC 2.0 requests, one per line.");
+        Console.WriteLine();
+        PrintDaemonUsage();
+        Console.WriteLine();
+        Console.WriteLine("Methods:");
+        Console.WriteLine("• parseFile {path, text?, tree?}: parse a workspace file or unsaved text");
+        Console.WriteLine("• query {path, selector}: select nodes of a file's tree with a tree query");
+        Console.WriteLine("• diagnostics {path?}: current diagnostics of one or every file");
+        Console.WriteLine("• symbols {path} or {name}: declarations of a file or exports of a symbol");
+        Console.WriteLine("• workspaceSymbol {query, limit?}: declarations of every file matching a fuzzy query");
+        Console.WriteLine("• documentHighlight {path, line, column}: occurrences of the symbol at a position");
+        Console.WriteLine("• shutdown: stop the daemon");
+        return 0;
+    }
+
+    private SgrepCommandOptions? ParseSgrepOptions(string[] args)
+    {
+        var options = new SgrepCommandOptions();
+        var files = new List<string>();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--as":
+                    if (i + 1 < args.Length)
+                    {
+                        options.PatternRule = args[++i];
+                    }
+                    break;
+
+                case "--rewrite":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Rewrite = args[++i];
+                    }
+                    break;
+
+                case "--in-place":
+                    options.InPlace = true;
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                default:
+                    if (options.Pattern.Length == 0)
+                    {
+                        options.Pattern = args[i];
+                    }
+                    else
+                    {
+                        files.Add(args[i]);
+                    }
+                    break;
+            }
+        }
+
+        options.InputFiles = files.ToArray();
+
+        if (string.IsNullOrEmpty(options.Pattern))
+        {
+            Console.WriteLine("Error: A pattern is required");
+            return null;
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: A grammar file is required (--grammar)");
+            return null;
+        }
+
+        if (options.InputFiles.Length == 0)
+        {
+            Console.WriteLine("Error: At least one input file is required");
+            return null;
+        }
+
+        return options;
+    }
+
+    private void PrintSgrepUsage()
+    {
+        Console.WriteLine("Usage: sgrep <pattern> --grammar <grammar-file> [options] <files...>");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file of the searched language");
+        Console.WriteLine("  --as <rule>               Rule the pattern stands for (inferred from the pattern if omitted)");
+        Console.WriteLine("  --rewrite <template>      Replace every match with the template and print the result");
+        Console.WriteLine("  --in-place                Write rewrites back to the files instead of printing them");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point of the files (defaults to the grammar's start rule)");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  sgrep 'fn $name($$params) { $$body }' --grammar rust.grammar src/main.rs");
+        Console.WriteLine("  sgrep 'unwrap_or($x, $y)' --rewrite 'or_else($y, $x)' --grammar rust.grammar --in-place src/main.rs");
+    }
+
+    private int PrintSgrepHelp()
+    {
+        Console.WriteLine("Sgrep Command");
+        Console.WriteLine("=============");
+        Console.WriteLine();
+        Console.WriteLine("Finds code by its structure rather than its text, and optionally rewrites it.");
+        Console.WriteLine();
+        PrintSgrepUsage();
+        Console.WriteLine();
+        Console.WriteLine("Patterns:");
+        Console.WriteLine("• Patterns are written in the target language and must parse as the rule they stand for");
+        Console.WriteLine("• $name binds one subtree; $$name binds a possibly empty run of siblings such as statements");
+        Console.WriteLine("• A metavariable used twice must bind the same tokens both times");
+        Console.WriteLine("• Whitespace and comments are ignored, and strings and comments are never searched inside");
+        Console.WriteLine("• Rewrites keep bound code and everything outside the matches as written");
+        Console.WriteLine("• Exits with 0 if anything matched and 1 otherwise");
+        return 0;
+    }
+
+    private SymbolsCommandOptions? ParseSymbolsOptions(string[] args)
+    {
+        var options = new SymbolsCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--root":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Root = args[++i];
+                    }
+                    break;
+
+                case "--include":
+                    if (i + 1 < args.Length)
+                    {
+                        options.SearchPattern = args[++i];
+                    }
+                    break;
+
+                case "--checkpoint":
+                    if (i + 1 < args.Length)
+                    {
+                        options.CheckpointFile = args[++i];
+                    }
+                    break;
+
+                case "--limit" or "-n":
+                    if (i + 1 < args.Length && int.TryParse(args[++i], out var limit) && limit >= 0)
+                    {
+                        options.Limit = limit;
+                    }
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                default:
+                    options.Query ??= args[i];
+                    break;
+            }
+        }
+
+        if (options.Query == null)
+        {
+            Console.WriteLine("Error: A query is required");
+            return null;
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: A grammar file is required (--grammar)");
+            return null;
+        }
+
+        return options;
+    }
+
+    private ModulesCommandOptions? ParseModulesOptions(string[] args)
+    {
+        var options = new ModulesCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--root":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Root = args[++i];
+                    }
+                    break;
+
+                case "--include":
+                    if (i + 1 < args.Length)
+                    {
+                        options.SearchPattern = args[++i];
+                    }
+                    break;
+
+                case "--format" or "-f":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Format = args[++i].ToLowerInvariant();
+                    }
+                    break;
+
+                case "--output" or "-o":
+                    if (i + 1 < args.Length)
+                    {
+                        options.OutputFile = args[++i];
+                    }
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+            }
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: A grammar file is required (--grammar)");
+            return null;
+        }
+
+        if (options.Format is not ("text" or "json" or "dot"))
+        {
+            Console.WriteLine($"Error: Unknown format '{options.Format}' (expected text, json or dot)");
+            return null;
+        }
+
+        return options;
+    }
+
+    private IndexCommandOptions? ParseIndexOptions(string[] args)
+    {
+        var options = new IndexCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--root":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Root = args[++i];
+                    }
+                    break;
+
+                case "--include":
+                    if (i + 1 < args.Length)
+                    {
+                        options.SearchPattern = args[++i];
+                    }
+                    break;
+
+                case "--out" or "-o":
+                    if (i + 1 < args.Length)
+                    {
+                        options.OutputDirectory = args[++i];
+                    }
+                    break;
+
+                case "--incremental":
+                    options.Incremental = true;
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+            }
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: A grammar file is required (--grammar)");
+            return null;
+        }
+
+        return options;
+    }
+
+    private void PrintIndexUsage()
+    {
+        Console.WriteLine("Usage: index --grammar <grammar-file> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse the workspace with");
+        Console.WriteLine("  --root <dir>              Workspace directory, searched recursively (defaults to the current directory)");
+        Console.WriteLine("  --include <pattern>       Pattern of workspace files (default *)");
+        Console.WriteLine("  --out, -o <dir>           Directory to write the index files and manifest to (default index)");
+        Console.WriteLine("  --incremental             Write only the files that changed since the last export");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  index --grammar lang.grammar --root src --include \"*.lang\" --out index/ --incremental");
+    }
+
+    private int PrintIndexHelp()
+    {
+        Console.WriteLine("Index Command");
+        Console.WriteLine("=============");
+        Console.WriteLine();
+        Console.WriteLine("Exports the declarations, definitions and references of every file in a directory for code-search backends.");
+        Console.WriteLine();
+        PrintIndexUsage();
+        Console.WriteLine();
+        Console.WriteLine("Index:");
+        Console.WriteLine("• Each file gets <path>.index.json with its symbols, their documentation comments and its occurrences");
+        Console.WriteLine("• Symbols are named by SCIP-style monikers built from the declaring file's path and the enclosing declarations,");
+        Console.WriteLine("  so edits to other files never change them");
+        Console.WriteLine("• manifest.json records the hash, exports and dependencies of every file");
+        Console.WriteLine("• With --incremental, only files whose text, dependencies or dependencies' exports changed are written again,");
+        Console.WriteLine("  and the index of deleted files is removed");
+        return 0;
+    }
+
+    private void PrintModulesUsage()
+    {
+        Console.WriteLine("Usage: modules --grammar <grammar-file> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse the workspace with");
+        Console.WriteLine("  --root <dir>              Workspace directory, searched recursively (defaults to the current directory)");
+        Console.WriteLine("  --include <pattern>       Pattern of workspace files (default *)");
+        Console.WriteLine("  --format, -f <format>     text, json or dot (default text)");
+        Console.WriteLine("  --output, -o <file>       Where to write the graph (defaults to standard output)");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  modules --grammar lang.grammar --root src --include \"*.lang\" --format dot --output modules.dot");
+    }
+
+    private int PrintModulesHelp()
+    {
+        Console.WriteLine("Modules Command");
+        Console.WriteLine("===============");
+        Console.WriteLine();
+        Console.WriteLine("Builds the module dependency graph of every file in a directory from its import declarations.");
+        Console.WriteLine();
+        PrintModulesUsage();
+        Console.WriteLine();
+        Console.WriteLine("Graph:");
+        Console.WriteLine("• Import declarations are the rules the grammar annotates with // @import(<STRING>)");
+        Console.WriteLine("• Specifiers starting with ./ or ../ resolve against the importing file's directory");
+        Console.WriteLine("• The text format lists modules after the modules they import, with import cycles as warnings");
+        Console.WriteLine("• Imports that do not resolve are reported with the reason on standard error");
+        Console.WriteLine("• Exits with 0 if every import resolved and 1 otherwise");
+        return 0;
+    }
+
+    private void PrintSymbolsUsage()
+    {
+        Console.WriteLine("Usage: symbols <query> --grammar <grammar-file> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse the workspace with");
+        Console.WriteLine("  --root <dir>              Workspace directory, searched recursively (defaults to the current directory)");
+        Console.WriteLine("  --include <pattern>       Pattern of workspace files (default *)");
+        Console.WriteLine("  --checkpoint <file>       Restore the workspace from this checkpoint and save it afterwards");
+        Console.WriteLine("  --limit, -n <count>       Maximum number of results (default 100)");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  symbols prsexp --grammar lang.grammar --root src --include \"*.lang\" --checkpoint .minotaur/workspace.checkpoint");
+    }
+
+    private int PrintSymbolsHelp()
+    {
+        Console.WriteLine("Symbols Command");
+        Console.WriteLine("===============");
+        Console.WriteLine();
+        Console.WriteLine("Finds the declarations of every file in a directory whose names match a fuzzy query.");
+        Console.WriteLine();
+        PrintSymbolsUsage();
+        Console.WriteLine();
+        Console.WriteLine("Matching:");
+        Console.WriteLine("• A query matches a name containing its characters in order, ignoring case");
+        Console.WriteLine("• Exact names rank first, then prefixes, substrings and scattered matches");
+        Console.WriteLine("• Matches at word starts such as camelCase humps rank before matches inside words");
+        Console.WriteLine("• Exported declarations rank before local ones");
+        Console.WriteLine("• With --checkpoint, only files changed since the last run are parsed again");
+        Console.WriteLine("• Exits with 0 if anything matched and 1 otherwise");
+        return 0;
+    }
+
+    private SkeletonCommandOptions? ParseSkeletonOptions(string[] args)
+    {
+        var options = new SkeletonCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--format" or "-f":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Format = args[++i];
+                    }
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                default:
+                    options.InputFiles.Add(args[i]);
+                    break;
+            }
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: A grammar file is required (--grammar)");
+            return null;
+        }
+
+        if (options.InputFiles.Count == 0)
+        {
+            Console.WriteLine("Error: At least one input file is required");
+            return null;
+        }
+
+        if (options.Format is not ("text" or "jsonl"))
+        {
+            Console.WriteLine($"Error: Unknown format '{options.Format}'; expected text or jsonl");
+            return null;
+        }
+
+        return options;
+    }
+
+    private void PrintSkeletonUsage()
+    {
+        Console.WriteLine("Usage: skeleton --grammar <grammar-file> [options] <input-files...>");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse the inputs with");
+        Console.WriteLine("  --format, -f <format>     Output format: text or jsonl (default text)");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  skeleton --grammar rust.grammar --format jsonl src/main.rs src/lib.rs");
+    }
+
+    private int PrintSkeletonHelp()
+    {
+        Console.WriteLine("Skeleton Command");
+        Console.WriteLine("================");
+        Console.WriteLine();
+        Console.WriteLine("Lists the declarations of files with their signatures, qualified names and spans, for code-search indexers.");
+        Console.WriteLine();
+        PrintSkeletonUsage();
+        Console.WriteLine();
+        Console.WriteLine("Extraction:");
+        Console.WriteLine("• Files are parsed lazily: bodies of the grammar's Deferred rules are skipped, not parsed");
+        Console.WriteLine("• Declarations are the nodes of the grammar's Outline rules, or its outline entries if it lists none");
+        Console.WriteLine("• A signature is the declaration's text up to its body or the end of its first line");
+        Console.WriteLine("• Declarations nested inside deferred bodies are not listed");
+        Console.WriteLine("• jsonl writes one object per declaration with its file, name, kind, signature, path and span");
+        return 0;
+    }
+
+    private MutateCommandOptions? ParseMutateOptions(string[] args)
+    {
+        var options = new MutateCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--count" or "-n":
+                    if (i + 1 < args.Length && int.TryParse(args[++i], out var count) && count >= 0)
+                    {
+                        options.Count = count;
+                    }
+                    break;
+
+                case "--seed":
+                    if (i + 1 < args.Length && int.TryParse(args[++i], out var seed))
+                    {
+                        options.Seed = seed;
+                    }
+                    break;
+
+                case "--output" or "-o":
+                    if (i + 1 < args.Length)
+                    {
+                        options.OutputDirectory = args[++i];
+                    }
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                default:
+                    options.InputFile ??= args[i];
+                    break;
+            }
+        }
+
+        if (options.InputFile == null)
+        {
+            Console.WriteLine("Error: An input file is required");
+            return null;
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: A grammar file is required (--grammar)");
+            return null;
+        }
+
+        return options;
+    }
+
+    private void PrintMutateUsage()
+    {
+        Console.WriteLine("Usage: mutate <file> --grammar <grammar-file> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse the input with");
+        Console.WriteLine("  --count, -n <count>       Maximum number of mutants (default 50)");
+        Console.WriteLine("  --seed <number>           Seed of the choice of mutations (default 0)");
+        Console.WriteLine("  --output, -o <dir>        Write each mutant to a file in this directory instead of printing diffs");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  mutate src/main.rs --grammar rust.grammar -n 50 --seed 7");
+    }
+
+    private int PrintMutateHelp()
+    {
+        Console.WriteLine("Mutate Command");
+        Console.WriteLine("==============");
+        Console.WriteLine();
+        Console.WriteLine("Generates mutants of a valid input that the grammar still accepts, to test linters and analyzers.");
+        Console.WriteLine();
+        PrintMutateUsage();
+        Console.WriteLine();
+        Console.WriteLine("Mutations:");
+        Console.WriteLine("• Operands of binary alternatives such as <sum> \"+\" <term> are swapped");
+        Console.WriteLine("• Operators are replaced within their group: the literals that alternatives differ in only,");
+        Console.WriteLine("  and the groups of the OperatorGroups metadata");
+        Console.WriteLine("• The last element of a list rule is duplicated");
+        Console.WriteLine("• Symbols that another alternative of the rule goes without are removed");
+        Console.WriteLine("• Every mutant reparses without errors into a different tree");
+        Console.WriteLine("• The same seed always gives the same mutants");
+        return 0;
+    }
+
+    private DiffCommandOptions? ParseDiffOptions(string[] args)
+    {
+        var options = new DiffCommandOptions();
+        var files = new List<string>();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--html":
+                    if (i + 1 < args.Length)
+                    {
+                        options.HtmlFile = args[++i];
+                    }
+                    break;
+
+                case "--context" or "-U":
+                    if (i + 1 < args.Length && int.TryParse(args[++i], out var context) && context >= 0)
+                    {
+                        options.ContextLines = context;
+                    }
+                    break;
+
+                case "--no-color":
+                    options.Color = false;
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                default:
+                    files.Add(args[i]);
+                    break;
+            }
+        }
+
+        if (files.Count != 2)
+        {
+            Console.WriteLine("Error: An old and a new file are required");
+            return null;
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: A grammar file is required (--grammar)");
+            return null;
+        }
+
+        options.OldFile = files[0];
+        options.NewFile = files[1];
+        return options;
+    }
+
+    private void PrintDiffUsage()
+    {
+        Console.WriteLine("Usage: diff <old-file> <new-file> --grammar <grammar-file> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse both versions with");
+        Console.WriteLine("  --html <file>             Write the whole diff as an HTML page instead of printing it");
+        Console.WriteLine("  --context, -U <lines>     Unchanged lines shown around each change (default 3)");
+        Console.WriteLine("  --no-color                Print without ANSI colors");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  diff old.rs new.rs --grammar rust.grammar");
+        Console.WriteLine("  diff old.rs new.rs --grammar rust.grammar --html diff.html");
+    }
+
+    private int PrintDiffHelp()
+    {
+        Console.WriteLine("Diff Command");
+        Console.WriteLine("============");
+        Console.WriteLine();
+        Console.WriteLine("Compares two versions of a file by their tokens rather than their characters.");
+        Console.WriteLine();
+        PrintDiffUsage();
+        Console.WriteLine();
+        Console.WriteLine("Output:");
+        Console.WriteLine("• - and + mark deleted and inserted lines, with the changed tokens highlighted");
+        Console.WriteLine("• < and > mark the lines of a subtree that moved, where it was and where it is,");
+        Console.WriteLine("  with the edits made to it highlighted");
+        Console.WriteLine("• Changes to whitespace between tokens are not shown");
+        Console.WriteLine("• A token spanning lines, such as a multi-line string, is compared as one unit");
+        Console.WriteLine("• Exits with 0 if nothing but whitespace changed, 1 otherwise and 2 if a file does not parse");
+        return 0;
+    }
+
+    private ExplainCommandOptions? ParseExplainOptions(string[] args)
+    {
+        var options = new ExplainCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--list":
+                    options.List = true;
+                    break;
+
+                default:
+                    options.Code ??= args[i];
+                    break;
+            }
+        }
+
+        if (options.Code == null && !options.List)
+        {
+            Console.WriteLine("Error: A diagnostic code is required");
+            return null;
+        }
+
+        return options;
+    }
+
+    private void PrintExplainUsage()
+    {
+        Console.WriteLine("Usage: explain <code> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Also know the diagnostic codes this grammar declares");
+        Console.WriteLine("  --list                    List every known code with its message instead");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  explain E0001");
+        Console.WriteLine("  explain LANG001 --grammar mylang.grammar");
+    }
+
+    private int PrintExplainHelp()
+    {
+        Console.WriteLine("Explain Command");
+        Console.WriteLine("===============");
+        Console.WriteLine();
+        Console.WriteLine("Explains what a diagnostic code means and how to fix it.");
+        Console.WriteLine();
+        PrintExplainUsage();
+        Console.WriteLine();
+        Console.WriteLine("Codes:");
+        Console.WriteLine("• Codes are matched ignoring case, so e0001 finds E0001");
+        Console.WriteLine("• Many built-in codes come with an input producing them and its corrected version");
+        Console.WriteLine("• A grammar declares its own codes with metadata lines such as");
+        Console.WriteLine("  Diagnostic_LANG001: Message template | Explanation");
+        Console.WriteLine("• Exits with 1 if the code is unknown");
+        return 0;
+    }
+
+    private TestCommandOptions? ParseTestOptions(string[] args)
+    {
+        var options = new TestCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--grammar" or "-g":
+                    if (i + 1 < args.Length)
+                    {
+                        options.GrammarFile = args[++i];
+                    }
+                    break;
+
+                case "--corpus" or "-c":
+                    if (i + 1 < args.Length)
+                    {
+                        options.CorpusDirectory = args[++i];
+                    }
+                    break;
+
+                case "--manifest" or "-m":
+                    if (i + 1 < args.Length)
+                    {
+                        options.ManifestFile = args[++i];
+                    }
+                    break;
+
+                case "--enforce-coverage":
+                    options.EnforceCoverage = true;
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                case "--engines":
+                    if (i + 1 < args.Length)
+                    {
+                        var names = args[++i].Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries);
+                        if (names is ["all"])
+                        {
+                            options.Engines = ParseEngine.All.ToList();
+                            break;
+                        }
+
+                        foreach (var name in names)
+                        {
+                            if (ParseEngine.Get(name) is not { } engine)
+                            {
+                                Console.WriteLine($"Error: Unknown parse engine '{name}' (expected all or {string.Join(", ", ParseEngine.All)})");
+                                return null;
+                            }
+
+                            options.Engines.Add(engine);
+                        }
+                    }
+                    break;
+            }
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: A grammar file is required (--grammar)");
+            return null;
+        }
+
+        return options;
+    }
+
+    private void PrintTestUsage()
+    {
+        Console.WriteLine("Usage: test --grammar <grammar-file> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to test");
+        Console.WriteLine("  --corpus, -c <dir>        Corpus directory (defaults to corpus/<grammar name> next to the grammar)");
+        Console.WriteLine("  --manifest, -m <file>     Coverage manifest (defaults to <grammar>.coverage next to the grammar)");
+        Console.WriteLine("  --enforce-coverage        Fail when the corpus covers less than the manifest's minimum");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine("  --engines <list>          Also parse the corpus with these engines and compare: all, or any of");
+        Console.WriteLine("                            sequential, parallel, lazy, incremental");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  test --grammar json.grammar");
+        Console.WriteLine("  test --grammar json.grammar --enforce-coverage");
+        Console.WriteLine("  test --grammar json.grammar --engines all");
+    }
+
+    private int PrintTestHelp()
+    {
+        Console.WriteLine("Test Command");
+        Console.WriteLine("============");
+        Console.WriteLine();
+        Console.WriteLine("Checks every corpus case against its snapshot and reports which grammar alternatives no case uses.");
+        Console.WriteLine();
+        PrintTestUsage();
+        Console.WriteLine();
+        Console.WriteLine("Coverage manifest:");
+        Console.WriteLine("• 'minimum <percent>' sets the required share of alternatives (default: 100)");
+        Console.WriteLine("• 'allow <rule>...' lists rules that are intentionally uncovered and do not count");
+        Console.WriteLine("• Each uncovered alternative is listed with its place in the grammar and, if one can be generated, an input covering it");
+        Console.WriteLine("• Exits with 1 if a corpus case fails or, with --enforce-coverage, the minimum is not reached");
+        Console.WriteLine();
+        Console.WriteLine("Engines:");
+        Console.WriteLine("• With --engines, every corpus case is parsed by each engine and compared with the first engine listed");
+        Console.WriteLine("• Trees are compared as in snapshots, so engines may pack ambiguous forests differently");
+        Console.WriteLine("• Engines that do not support the grammar are skipped with the reason, e.g. lazy without deferred rules");
+        Console.WriteLine("• Each engine's parse time is reported, and any divergence is shown as a diff and fails the command");
+        return 0;
+    }
+
+    private ConfigCommandOptions? ParseConfigOptions(string[] args)
+    {
+        var options = new ConfigCommandOptions();
+        var actions = new List<string>();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--root":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Root = args[++i];
+                    }
+                    break;
+
+                case "--project-type":
+                    if (i + 1 < args.Length)
+                    {
+                        if (!Enum.TryParse<Minotaur.Projects.ProjectType>(args[++i], ignoreCase: true, out var projectType))
+                        {
+                            Console.WriteLine($"Error: Unknown project type '{args[i]}'");
+                            return null;
+                        }
+
+                        options.ProjectType = projectType;
+                    }
+                    break;
+
+                case "--json":
+                    options.Json = true;
+                    break;
+
+                default:
+                    if (!args[i].StartsWith('-'))
+                    {
+                        actions.Add(args[i]);
+                    }
+                    break;
+            }
+        }
+
+        if (actions is not ["explain", var file])
         {
-            var topic = args[0].ToLower();
-            return topic switch
-            {
-                "generate" => PrintGenerateHelp(),
-                "validate" => PrintValidateHelp(),
-                _ => PrintGeneralHelp()
-            };
+            Console.WriteLine("Error: An action and a file are required (explain <file>)");
+            return null;
         }
 
-        return PrintGeneralHelp();
+        options.File = file;
+        return options;
     }
 
-    private int HandleUnknownCommand(string command)
+    private void PrintConfigUsage()
     {
-        Console.WriteLine($"Unknown command: {command}");
-        Console.WriteLine("Use 'help' to see available commands.");
-        return 1;
+        Console.WriteLine("Usage: config explain <file> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --root <dir>              Project root holding the grammar configuration (defaults to the file's directory)");
+        Console.WriteLine("  --project-type <type>     Project type of the file (defaults to GenericFolder)");
+        Console.WriteLine("  --json                    Write the resolution trace as JSON");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  config explain src/Program.cs --root .");
+        Console.WriteLine("  config explain scripts/build.js --json");
     }
 
-    private GenerateOptions? ParseGenerateOptions(string[] args)
+    private int PrintConfigHelp()
     {
-        var options = new GenerateOptions();
-        var sourceFiles = new List<string>();
+        Console.WriteLine("Config Command");
+        Console.WriteLine("==============");
+        Console.WriteLine();
+        Console.WriteLine("Explains how a file's grammar and version are resolved.");
+        Console.WriteLine();
+        PrintConfigUsage();
+        Console.WriteLine();
+        Console.WriteLine("Explanation:");
+        Console.WriteLine("• Names the grammar configuration file in effect (minotaur.grammar.json and its alternatives)");
+        Console.WriteLine("• Lists every detector in the order it was consulted, with its verdict and evidence");
+        Console.WriteLine("• Evidence names the configuration entry, built-in mapping or content line that matched");
+        Console.WriteLine("• Ends with the chosen grammar and why it won, such as higher confidence or detector priority");
+        Console.WriteLine("• Exits with 1 if no grammar was resolved");
+        return 0;
+    }
+
+    private CheckCommandOptions? ParseCheckOptions(string[] args)
+    {
+        var options = new CheckCommandOptions();
+        var files = new List<string>();
 
         for (int i = 0; i < args.Length; i++)
         {
             switch (args[i])
             {
-                case "--language" or "-l":
+                case "--grammar" or "-g":
                     if (i + 1 < args.Length)
                     {
-                        options.LanguageName = args[++i];
+                        options.GrammarFile = args[++i];
                     }
                     break;
 
-                case "--output" or "-o":
+                case "--changed-only":
+                    options.ChangedOnly = true;
+                    break;
+
+                case "--sarif":
                     if (i + 1 < args.Length)
                     {
-                        options.OutputFile = args[++i];
+                        options.SarifFile = args[++i];
                     }
                     break;
 
-                case "--source" or "-s":
+                case "--all-diagnostics":
+                    options.AllDiagnostics = true;
+                    break;
+
+                case "--jobs" or "-j":
+                    if (i + 1 < args.Length && int.TryParse(args[++i], out var jobs) && jobs > 0)
+                    {
+                        options.Jobs = jobs;
+                    }
+                    break;
+
+                case "--diff-from":
                     if (i + 1 < args.Length)
                     {
-                        sourceFiles.AddRange(args[++i].Split(','));
+                        options.DiffFrom = args[++i];
                     }
                     break;
 
                 case "--context":
-                    // Parse context options (simplified)
-                    options.Context = new LanguageContext
+                    if (i + 1 < args.Length)
                     {
-                        HasNestedScopes = true,
-                        HasTypeSystem = true,
-                        HasComments = true,
-                        HasStringLiterals = true,
-                        HasNumericLiterals = true
-                    };
+                        options.ContextLines = int.Parse(args[++i]);
+                    }
                     break;
 
-                default:
-                    if (!args[i].StartsWith('-'))
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
                     {
-                        sourceFiles.Add(args[i]);
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                case "--locale":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Locale = args[++i];
                     }
                     break;
+
+                default:
+                    files.Add(args[i]);
+                    break;
             }
         }
 
-        if (string.IsNullOrEmpty(options.LanguageName))
+        options.InputFiles = files.ToArray();
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
         {
-            Console.WriteLine("Error: Language name is required (--language)");
+            Console.WriteLine("Error: A grammar file is required (--grammar)");
             return null;
         }
 
-        if (!sourceFiles.Any())
+        if (options.InputFiles.Length == 0)
         {
-            Console.WriteLine("Error: At least one source file is required");
+            Console.WriteLine("Error: At least one input file is required");
+            return null;
+        }
+
+        if (options.ChangedOnly != (options.DiffFrom != null))
+        {
+            Console.WriteLine("Error: --changed-only and --diff-from are used together");
             return null;
         }
 
-        options.SourceFiles = sourceFiles.ToArray();
         return options;
     }
 
-    private void PrintUsage()
+    private void PrintCheckUsage()
     {
-        Console.WriteLine("Minotaur Grammar Generator");
-        Console.WriteLine("=========================");
+        Console.WriteLine("Usage: check --grammar <grammar-file> [options] <files...>");
         Console.WriteLine();
-        Console.WriteLine("Usage: minotaur-grammar <command> [options]");
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file of the checked language");
+        Console.WriteLine("  --changed-only            Report only new diagnostics on changed lines");
+        Console.WriteLine("  --diff-from <file>        The files' previous revision, or a unified diff leading to them");
+        Console.WriteLine("  --context <lines>         Lines around each change that count as changed (default: 0)");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point of the files (defaults to the grammar's start rule)");
+        Console.WriteLine("  --sarif <file>            Write the reported diagnostics as a SARIF log");
+        Console.WriteLine("  --all-diagnostics         Write every diagnostic to the SARIF log, including collapsed ones");
+        Console.WriteLine("  --jobs, -j <n>            Check up to n files at once (default 1)");
+        Console.WriteLine("  --locale <name>           Language of the messages, e.g. de (defaults to MINOTAUR_LOCALE, the configuration, then LANG)");
         Console.WriteLine();
-        Console.WriteLine("Commands:");
-        Console.WriteLine("  generate    Generate a grammar from source files");
-        Console.WriteLine("  validate    Validate a grammar against source files");
-        Console.WriteLine("  help        Show help information");
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  check --grammar rust.grammar src/main.rs");
+        Console.WriteLine("  check --grammar rust.grammar --jobs 8 --sarif check.sarif --all-diagnostics src/*.rs");
+        Console.WriteLine("  git diff main | check --grammar rust.grammar --changed-only --diff-from /dev/stdin src/*.rs");
+    }
+
+    private int PrintCheckHelp()
+    {
+        Console.WriteLine("Check Command");
+        Console.WriteLine("=============");
         Console.WriteLine();
-        Console.WriteLine("Use 'help <command>' for more information about a command.");
+        Console.WriteLine("Parses files, runs the built-in analysis passes and reports their diagnostics.");
+        Console.WriteLine();
+        PrintCheckUsage();
+        Console.WriteLine();
+        Console.WriteLine("Error limits:");
+        Console.WriteLine("• After 100 errors in a file or 1000 in the run, further errors are summarized by count and most frequent codes");
+        Console.WriteLine("• A file with many errors in its first 100 tokens is reported once as not written in the grammar's language");
+        Console.WriteLine("• Set maxErrorsPerFile, maxErrorsPerRun, wrongGrammarWindow and wrongGrammarErrorDensity in the");
+        Console.WriteLine("  diagnosticLimits section of minotaur.grammar.json in the current directory");
+        Console.WriteLine();
+        Console.WriteLine("Repeated diagnostics:");
+        Console.WriteLine("• After 10 diagnostics of one code in a file or 100 in the run, the rest are collapsed into one entry");
+        Console.WriteLine("  per code with their count and a sample of 5 locations (maxPerCodePerFile, maxPerCodePerRun and");
+        Console.WriteLine("  sampledLocations in diagnosticLimits)");
+        Console.WriteLine("• Files are taken in path order and the sample is chosen by hash, so the output is the same on every run");
+        Console.WriteLine("• The summary at the end lists the total of each code");
+        Console.WriteLine("• With --jobs, the per-run error limit counts files in the order they finish");
+        Console.WriteLine();
+        Console.WriteLine("Message language:");
+        Console.WriteLine("• Messages are in English unless --locale, MINOTAUR_LOCALE, \"locale\" in minotaur.grammar.json or LANG names");
+        Console.WriteLine("  another language; de-AT falls back to de, then to English");
+        Console.WriteLine("• Catalogs named <grammar>.<locale>.messages next to the grammar file translate the grammar's own codes");
+        Console.WriteLine("• Messages missing from the chosen language are printed in English, with a warning after the report");
+        Console.WriteLine();
+        Console.WriteLine("Changed-only mode:");
+        Console.WriteLine("• Both revisions are checked and their diagnostics matched by fingerprint, which ignores line moves");
+        Console.WriteLine("• Diagnostics are new, pre-existing or fixed; only new ones on changed lines are reported");
+        Console.WriteLine("• With a patch, each file's previous revision is recovered by undoing its hunks; untouched files are skipped");
+        Console.WriteLine("• Exits with 1 if any diagnostic was reported and 0 otherwise");
+        return 0;
     }
 
-    private void PrintGenerateUsage()
+    private SelftestCommandOptions? ParseSelftestOptions(string[] args)
     {
-        Console.WriteLine("Usage: generate --language <name> [options] <source-files...>");
+        var options = new SelftestCommandOptions();
+
+        for (int i = 0; i < args.Length; i++)
+        {
+            switch (args[i])
+            {
+                case "--iterations" or "-n":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Iterations = int.Parse(args[++i]);
+                    }
+                    break;
+
+                case "--seed" or "-s":
+                    if (i + 1 < args.Length)
+                    {
+                        options.Seed = int.Parse(args[++i]);
+                    }
+                    break;
+
+                case "--exclude" or "-x":
+                    if (i + 1 < args.Length)
+                    {
+                        options.ExcludedRules.UnionWith(args[++i].Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries));
+                    }
+                    break;
+
+                case "--max-depth":
+                    if (i + 1 < args.Length)
+                    {
+                        options.MaxDepth = int.Parse(args[++i]);
+                    }
+                    break;
+
+                case "--rule" or "-r":
+                    if (i + 1 < args.Length)
+                    {
+                        options.StartRule = args[++i];
+                    }
+                    break;
+
+                default:
+                    if (!args[i].StartsWith('-'))
+                    {
+                        options.GrammarFile = args[i];
+                    }
+                    break;
+            }
+        }
+
+        if (string.IsNullOrEmpty(options.GrammarFile))
+        {
+            Console.WriteLine("Error: A grammar file is required");
+            return null;
+        }
+
+        return options;
+    }
+
+    private void PrintSelftestUsage()
+    {
+        Console.WriteLine("Usage: selftest <grammar-file> [options]");
         Console.WriteLine();
         Console.WriteLine("Options:");
-        Console.WriteLine("  --language, -l <name>     Name of the language");
-        Console.WriteLine("  --output, -o <file>       Output grammar file");
-        Console.WriteLine("  --source, -s <files>      Comma-separated source files");
-        Console.WriteLine("  --context                 Enable context-aware features");
+        Console.WriteLine("  --iterations, -n <count>  Number of generated sentences (default 100)");
+        Console.WriteLine("  --seed, -s <seed>         Seed of the first sentence (defaults to a random seed)");
+        Console.WriteLine("  --exclude, -x <rules>     Comma-separated rules never to generate");
+        Console.WriteLine("  --max-depth <depth>       Rule depth after which sentences are finished quickly (default 12)");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
         Console.WriteLine();
         Console.WriteLine("Examples:");
-        Console.WriteLine("  generate --language MyLang --output mylang.grammar src/*.mylang");
-        Console.WriteLine("  generate -l Python -o python.grammar *.py");
+        Console.WriteLine("  selftest calculator.grammar --iterations 1000 --exclude comment");
     }
 
-    private int PrintGenerateHelp()
+    private int PrintSelftestHelp()
     {
-        Console.WriteLine("Generate Command");
+        Console.WriteLine("Selftest Command");
         Console.WriteLine("================");
         Console.WriteLine();
-        Console.WriteLine("Generates a grammar file from source code examples.");
+        Console.WriteLine("Checks that the grammar, the parser and the pretty-printer agree.");
         Console.WriteLine();
-        PrintGenerateUsage();
+        PrintSelftestUsage();
         Console.WriteLine();
-        Console.WriteLine("The generator analyzes source files to identify:");
-        Console.WriteLine("• Token patterns (keywords, operators, literals)");
-        Console.WriteLine("• Syntax structures (expressions, statements, declarations)");
-        Console.WriteLine("• Language constructs (functions, classes, control flow)");
+        Console.WriteLine("Each generated sentence is:");
+        Console.WriteLine("• Parsed, printed from its tree and parsed again");
+        Console.WriteLine("• Compared structurally with the tree of the printed form");
+        Console.WriteLine("• Shrunk to a minimal input if it fails; the seed reproduces the failure");
+        return 0;
+    }
+
+    private void PrintXtestUsage()
+    {
+        Console.WriteLine("Usage: xtest <language> --corpus <dir> [options]");
         Console.WriteLine();
-        Console.WriteLine("The output grammar follows the Grammar File Creation Guide format.");
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --corpus, -c <dir>        Directory of source files to compare, searched recursively");
+        Console.WriteLine("  --grammar, -g <file>      Grammar under test (defaults to the language's grammar)");
+        Console.WriteLine("  --reference-command <cmd> Command printing a JSON summary for a file ({} is the file)");
+        Console.WriteLine("  --allowlist <file>        Tolerated differences (defaults to the language's allowlist)");
+        Console.WriteLine("  --root <dir>              Repository root for default paths (defaults to the current directory)");
+        Console.WriteLine("  --dump <dir>              Write both representations of each mismatching file here");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  xtest rust --corpus ~/src/ripgrep --dump mismatches");
+    }
+
+    private int PrintXtestHelp()
+    {
+        Console.WriteLine("Xtest Command");
+        Console.WriteLine("=============");
+        Console.WriteLine();
+        Console.WriteLine("Parses a corpus with a grammar and with a reference frontend and compares the results.");
+        Console.WriteLine();
+        PrintXtestUsage();
+        Console.WriteLine();
+        Console.WriteLine("For each file the comparison checks:");
+        Console.WriteLine("• Item counts and item kinds");
+        Console.WriteLine("• The set of identifiers");
+        Console.WriteLine("• The first item where the two frontends diverge");
+        Console.WriteLine();
+        Console.WriteLine($"Languages: {string.Join(", ", ReferenceLanguages.Names)}");
+        return 0;
+    }
+
+    private void PrintReduceUsage()
+    {
+        Console.WriteLine("Usage: reduce <input-file> --grammar <grammar-file> --predicate <command> [options]");
+        Console.WriteLine();
+        Console.WriteLine("Options:");
+        Console.WriteLine("  --grammar, -g <file>      Grammar file to parse candidates with");
+        Console.WriteLine("  --predicate, -p <cmd>     Command run on each candidate; {} is replaced by the candidate file");
+        Console.WriteLine("  --exit-code <code>        Exit code meaning \"still fails\" (defaults to any non-zero code)");
+        Console.WriteLine("  --output-regex <regex>    Also require the command output to match");
+        Console.WriteLine("  --max-tests <count>       Stop after this many predicate runs (default 10000)");
+        Console.WriteLine("  --output, -o <file>       Reproducer file (defaults to <input-file>.reduced)");
+        Console.WriteLine("  --rule, -r <name>         Start rule or entry point (defaults to the grammar's start rule)");
+        Console.WriteLine();
+        Console.WriteLine("Examples:");
+        Console.WriteLine("  reduce big.src --grammar lang.grammar --predicate \"./crash.sh {}\" --output-regex \"IndexOutOfRange\"");
+    }
+
+    private int PrintReduceHelp()
+    {
+        Console.WriteLine("Reduce Command");
+        Console.WriteLine("==============");
+        Console.WriteLine();
+        Console.WriteLine("Shrinks a failing input to a minimal reproducer by hierarchical delta debugging.");
+        Console.WriteLine();
+        PrintReduceUsage();
+        Console.WriteLine();
+        Console.WriteLine("Reduction removes, while the predicate still fails:");
+        Console.WriteLine("• Subtrees of the parse tree, level by level from the root");
+        Console.WriteLine("• Then single tokens, or only tokens if the input does not parse");
         return 0;
     }
 
@@ -322,6 +3798,200 @@ public class GrammarGeneratorCli
         public string? OutputFile { get; set; }
         public LanguageContext? Context { get; set; }
     }
+
+    private class XtestCommandOptions
+    {
+        public string Language { get; set; } = string.Empty;
+        public string CorpusDirectory { get; set; } = string.Empty;
+        public string? GrammarFile { get; set; }
+        public string? ReferenceCommand { get; set; }
+        public string? AllowlistFile { get; set; }
+        public string? Root { get; set; }
+        public string? DumpDirectory { get; set; }
+    }
+
+    private class CorpusCommandOptions
+    {
+        public string? Action { get; set; }
+        public string? InputFile { get; set; }
+        public string GrammarFile { get; set; } = string.Empty;
+        public string? StartRule { get; set; }
+        public string? CorpusDirectory { get; set; }
+        public bool Reduce { get; set; } = true;
+        public bool Anonymize { get; set; } = true;
+        public int MaxTests { get; set; } = 10_000;
+        public bool Apply { get; set; }
+    }
+
+    private class GrammarCommandOptions
+    {
+        public string Action { get; set; } = "migrations";
+        public string FromGrammarFile { get; set; } = string.Empty;
+        public string ToGrammarFile { get; set; } = string.Empty;
+        public string? ManifestFile { get; set; }
+        public string GrammarFile { get; set; } = string.Empty;
+        public string? OutputFile { get; set; }
+        public string? StartRule { get; set; }
+        public bool Suggest { get; set; }
+        public bool Interactive { get; set; }
+        public string BundleDirectory { get; set; } = string.Empty;
+    }
+
+    private class DaemonCommandOptions
+    {
+        public string SocketPath { get; set; } = string.Empty;
+        public string GrammarFile { get; set; } = string.Empty;
+        public string Root { get; set; } = ".";
+        public string SearchPattern { get; set; } = "*";
+        public string? CheckpointFile { get; set; }
+        public int? StallTimeout { get; set; }
+        public string? StartRule { get; set; }
+    }
+
+    private class SgrepCommandOptions
+    {
+        public string Pattern { get; set; } = string.Empty;
+        public string GrammarFile { get; set; } = string.Empty;
+        public string? PatternRule { get; set; }
+        public string? Rewrite { get; set; }
+        public bool InPlace { get; set; }
+        public string? StartRule { get; set; }
+        public string[] InputFiles { get; set; } = Array.Empty<string>();
+    }
+
+    private class SymbolsCommandOptions
+    {
+        public string? Query { get; set; }
+        public string GrammarFile { get; set; } = string.Empty;
+        public string Root { get; set; } = ".";
+        public string SearchPattern { get; set; } = "*";
+        public string? CheckpointFile { get; set; }
+        public int Limit { get; set; } = 100;
+        public string? StartRule { get; set; }
+    }
+
+    private class SkeletonCommandOptions
+    {
+        public string GrammarFile { get; set; } = string.Empty;
+        public string Format { get; set; } = "text";
+        public string? StartRule { get; set; }
+        public List<string> InputFiles { get; set; } = new();
+    }
+
+    private class ModulesCommandOptions
+    {
+        public string GrammarFile { get; set; } = string.Empty;
+        public string Root { get; set; } = ".";
+        public string SearchPattern { get; set; } = "*";
+        public string Format { get; set; } = "text";
+        public string? OutputFile { get; set; }
+        public string? StartRule { get; set; }
+    }
+
+    private class IndexCommandOptions
+    {
+        public string GrammarFile { get; set; } = string.Empty;
+        public string Root { get; set; } = ".";
+        public string SearchPattern { get; set; } = "*";
+        public string OutputDirectory { get; set; } = "index";
+        public bool Incremental { get; set; }
+        public string? StartRule { get; set; }
+    }
+
+    private class MutateCommandOptions
+    {
+        public string? InputFile { get; set; }
+        public string GrammarFile { get; set; } = string.Empty;
+        public int Count { get; set; } = 50;
+        public int Seed { get; set; }
+        public string? OutputDirectory { get; set; }
+        public string? StartRule { get; set; }
+    }
+
+    private class DiffCommandOptions
+    {
+        public string OldFile { get; set; } = string.Empty;
+        public string NewFile { get; set; } = string.Empty;
+        public string GrammarFile { get; set; } = string.Empty;
+        public string? HtmlFile { get; set; }
+        public int ContextLines { get; set; } = 3;
+        public bool Color { get; set; } = true;
+        public string? StartRule { get; set; }
+    }
+
+    private class ExplainCommandOptions
+    {
+        public string? Code { get; set; }
+        public string? GrammarFile { get; set; }
+        public bool List { get; set; }
+    }
+
+    private class CheckCommandOptions
+    {
+        public string GrammarFile { get; set; } = string.Empty;
+        public bool ChangedOnly { get; set; }
+        public string? DiffFrom { get; set; }
+        public string? SarifFile { get; set; }
+        public bool AllDiagnostics { get; set; }
+        public int Jobs { get; set; } = 1;
+        public int ContextLines { get; set; }
+        public string? StartRule { get; set; }
+        public string? Locale { get; set; }
+        public string[] InputFiles { get; set; } = Array.Empty<string>();
+    }
+
+    private class TestCommandOptions
+    {
+        public string GrammarFile { get; set; } = string.Empty;
+        public string? CorpusDirectory { get; set; }
+        public string? ManifestFile { get; set; }
+        public bool EnforceCoverage { get; set; }
+        public string? StartRule { get; set; }
+        public List<ParseEngine> Engines { get; set; } = new();
+    }
+
+    private class ConfigCommandOptions
+    {
+        public string File { get; set; } = string.Empty;
+        public string? Root { get; set; }
+        public Minotaur.Projects.ProjectType ProjectType { get; set; } = Minotaur.Projects.ProjectType.GenericFolder;
+        public bool Json { get; set; }
+    }
+
+    private class SelftestCommandOptions
+    {
+        public string GrammarFile { get; set; } = string.Empty;
+        public int Iterations { get; set; } = 100;
+        public int? Seed { get; set; }
+        public HashSet<string> ExcludedRules { get; set; } = new();
+        public int MaxDepth { get; set; } = 12;
+        public string? StartRule { get; set; }
+    }
+
+    private class ParseCommandOptions
+    {
+        public string GrammarFile { get; set; } = string.Empty;
+        public string InputFile { get; set; } = string.Empty;
+        public string? StartRule { get; set; }
+        public string? ForestHtmlFile { get; set; }
+        public string? EditScriptFile { get; set; }
+        public string? TimelineHtmlFile { get; set; }
+        public string? SourceMapFile { get; set; }
+        public bool LineDirectives { get; set; }
+        public string? SarifFile { get; set; }
+        public string? Encoding { get; set; }
+        public bool SubstituteInvalidBytes { get; set; }
+        public bool CaptureCorpus { get; set; }
+        public bool MemoryReport { get; set; }
+        public bool Documents { get; set; }
+        public bool PathContext { get; set; }
+        public int? StallTimeout { get; set; }
+        public string? PredicateCommand { get; set; }
+        public int? PredicateExitCode { get; set; }
+        public string? PredicateOutputPattern { get; set; }
+        public int MaxTests { get; set; } = 10_000;
+        public string? OutputFile { get; set; }
+    }
 }
 
 /// <summary>
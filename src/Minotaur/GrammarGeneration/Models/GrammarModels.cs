@@ -15,6 +15,8 @@
  * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
  */
 
+using Minotaur.Monitoring;
+
 namespace Minotaur.GrammarGeneration.Models;
 
 /// <summary>
@@ -56,6 +58,12 @@ public class TokenPattern
     /// Gets or sets the confidence level of this pattern's accuracy (0.0 to 1.0).
     /// </summary>
     public double Confidence { get; set; }
+
+    /// <summary>
+    /// Gets or sets the terminal library reference the pattern was resolved from, such as
+    /// <c>std::dq_string(escapes = json)</c>, or null if the grammar defines the pattern itself.
+    /// </summary>
+    public string? Library { get; set; }
 }
 
 /// <summary>
@@ -158,6 +166,11 @@ public class ProductionRule
     /// </summary>
     public List<string> Alternatives { get; set; } = new();
 
+    /// <summary>
+    /// Gets or sets the names of semantic actions bound to alternatives, keyed by alternative index.
+    /// </summary>
+    public Dictionary<int, string> Actions { get; set; } = new();
+
     /// <summary>
     /// Gets or sets the priority of this production rule for conflict resolution.
     /// </summary>
@@ -231,8 +244,13 @@ public class ProductionRules
 /// <summary>
 /// Complete grammar definition
 /// </summary>
-public class Grammar
+public class Grammar : IMemoryReporting
 {
+    /// <summary>
+    /// The bytes allocated reading the grammar, recorded by <see cref="GrammarFileReader"/>.
+    /// </summary>
+    internal MemoryLedger? Memory;
+
     /// <summary>
     /// Gets or sets the name of the grammar.
     /// </summary>
@@ -267,6 +285,125 @@ public class Grammar
     /// Gets or sets the version of the grammar.
     /// </summary>
     public string Version { get; set; } = "1.0.0";
+
+    /// <summary>
+    /// Gets or sets the documentation annotations attached to rules and token patterns.
+    /// </summary>
+    public List<GrammarAnnotation> Annotations { get; set; } = new();
+
+    /// <inheritdoc />
+    public MemoryReport GetMemoryReport()
+    {
+        return new MemoryReport($"Grammar '{Name}'", new[]
+        {
+            new MemoryReportEntry(MemoryCategories.ProductionRules, ProductionRules.Rules.Count, MemoryLedger.Get(Memory, MemoryCategories.ProductionRules)),
+            new MemoryReportEntry(MemoryCategories.TokenPatterns, TokenRules.Patterns.Count, MemoryLedger.Get(Memory, MemoryCategories.TokenPatterns)),
+            new MemoryReportEntry(MemoryCategories.Annotations, Annotations.Count, MemoryLedger.Get(Memory, MemoryCategories.Annotations)),
+            new MemoryReportEntry(MemoryCategories.Metadata, Metadata.Count, MemoryLedger.Get(Memory, MemoryCategories.Metadata))
+        });
+    }
+}
+
+/// <summary>
+/// Kinds of documentation annotation
+/// </summary>
+public enum GrammarAnnotationKind
+{
+    /// <summary>
+    /// Source text the annotated rule or token must accept.
+    /// </summary>
+    Example,
+
+    /// <summary>
+    /// An editor snippet inserting the construct, with placeholders such as <c>${1:condition}</c>.
+    /// </summary>
+    Snippet,
+
+    /// <summary>
+    /// A one-line description of the construct.
+    /// </summary>
+    Description,
+
+    /// <summary>
+    /// A deprecation of the construct, written as <c>// @deprecated("message", since = "2.0", replace_with = "...")</c>;
+    /// see <see cref="Minotaur.Parser.GrammarDeprecation"/>.
+    /// </summary>
+    Deprecated,
+
+    /// <summary>
+    /// How syntax errors name the construct where it is expected, written as <c>// @expected("a type")</c>.
+    /// </summary>
+    Expected,
+
+    /// <summary>
+    /// A delimiter the construct uses alone, written as <c>// @unpaired("'")</c>, which editors must not pair or
+    /// auto-close; see <see cref="Minotaur.Parser.GrammarEditorInfo"/>.
+    /// </summary>
+    Unpaired,
+
+    /// <summary>
+    /// A parse ambiguity of the rule accepted as intended, written as <c>// @ambiguous("reason")</c>; see
+    /// <see cref="Minotaur.Parser.CompiledRule.AcceptedAmbiguity"/>.
+    /// </summary>
+    Ambiguous,
+
+    /// <summary>
+    /// An import declaration, written as <c>// @import(&lt;STRING&gt;)</c> naming the symbol whose text is the
+    /// imported module specifier; see <see cref="Minotaur.Parser.CompiledRule.ImportSpecifier"/>.
+    /// </summary>
+    Import,
+
+    /// <summary>
+    /// A requirement that the members of a container have distinct keys, written as
+    /// <c>// @unique_by(&lt;member&gt;, &lt;STRING&gt;)</c>; see <see cref="Minotaur.Parser.UniqueKeyConstraint"/>.
+    /// </summary>
+    UniqueBy,
+
+    /// <summary>
+    /// The escape sequences of a string token pattern, written as <c>// @escapes(json)</c>; see
+    /// <see cref="Minotaur.Parser.EscapeScheme"/>.
+    /// </summary>
+    Escapes,
+
+    /// <summary>
+    /// How qualified paths name the outline rule's nodes, written as <c>// @breadcrumb("impl $path for $type")</c>;
+    /// see <see cref="Minotaur.Parser.QualifiedPath"/>.
+    /// </summary>
+    Breadcrumb
+}
+
+/// <summary>
+/// A documentation annotation written as a <c>// @example</c>, <c>// @snippet</c>, <c>// @description</c>,
+/// <c>// @deprecated</c>, <c>// @expected</c>, <c>// @unpaired</c> or <c>// @ambiguous</c> comment line after a rule or token pattern
+/// </summary>
+public class GrammarAnnotation
+{
+    /// <summary>
+    /// Gets or sets the kind of annotation.
+    /// </summary>
+    public GrammarAnnotationKind Kind { get; set; }
+
+    /// <summary>
+    /// Gets or sets the name of the annotated rule or token pattern.
+    /// </summary>
+    public string Target { get; set; } = string.Empty;
+
+    /// <summary>
+    /// Gets or sets the annotation text.
+    /// </summary>
+    public string Text { get; set; } = string.Empty;
+
+    /// <summary>
+    /// Gets or sets the 1-based line of the annotation in the grammar file.
+    /// </summary>
+    public int Line { get; set; }
+
+    /// <summary>
+    /// Gets or sets the indices of the alternatives of the annotated rule written on the line the annotation
+    /// follows, or an empty list when it follows the line the rule starts on and so annotates the whole rule.
+    /// Only deprecations distinguish the two.
+    /// </summary>
+    public List<int> Alternatives { get; set; } = new();
 }
 
 /// <summary>
@@ -0,0 +1,119 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.GrammarGeneration;
+
+/// <summary>
+/// The standard terminal libraries, for C-style and JSON-style numbers, strings, identifiers and comments:
+/// <list type="bullet">
+/// <item><c>std::c_identifier</c>: IDENTIFIER, ASCII letters, digits and underscores not starting with a digit.</item>
+/// <item><c>std::c_numeric(separators = none | underscore)</c>: NUMBER, decimal, hexadecimal and binary integers
+/// with C suffixes, decimal floats and hexadecimal floats with their required binary exponent; with
+/// <c>separators = underscore</c> single underscores may separate digits.</item>
+/// <item><c>std::json_number</c>: NUMBER as RFC 8259 defines it, without leading zeros, a leading plus or a bare
+/// decimal point.</item>
+/// <item><c>std::dq_string(escapes = c | json)</c>: STRING in double quotes, with C's simple, octal, hexadecimal
+/// and universal character escapes and line continuations, or JSON's escapes, where raw control characters are not
+/// allowed. The pattern takes any escape and the lexer checks them with <see cref="Parser.EscapeScheme.C"/> or
+/// <see cref="Parser.EscapeScheme.Json"/>, so a string with a bad escape is still one token.</item>
+/// <item><c>std::c_comments</c>: LINE_COMMENT and BLOCK_COMMENT, skipped; block comments do not nest.</item>
+/// </list>
+/// </summary>
+internal static class StandardTerminalLibraries
+{
+    private const string Hex = "[0-9A-Fa-f]";
+
+    // The escapes themselves are checked by the lexer against the terminal's escape scheme.
+    private const string CString = @"""(?:[^""\\\r\n]|\\(?:\r?\n|[^\r\n]))*""";
+
+    private const string JsonString = @"""(?:[^""\\\x00-\x1F]|\\[^\x00-\x1F])*""";
+
+    public static IEnumerable<TerminalLibrary> Create()
+    {
+        yield return new TerminalLibrary("std::c_identifier", Array.Empty<TerminalLibraryParameter>(), _ => new[]
+        {
+            new LibraryTerminal("IDENTIFIER", "[A-Za-z_][A-Za-z0-9_]*", TokenType.Identifier)
+            {
+                Description = "A C identifier.",
+                Expected = "an identifier",
+                Examples = new[] { "_tmp", "x1" }
+            }
+        });
+
+        yield return new TerminalLibrary("std::c_numeric", new[] { new TerminalLibraryParameter("separators", new[] { "none", "underscore" }, "none") }, values => new[]
+        {
+            new LibraryTerminal("NUMBER", CNumeric(values["separators"] == "underscore"), TokenType.Literal)
+            {
+                Description = "A C integer or floating-point constant.",
+                Expected = "a number",
+                Examples = new[] { "42ull", "0x1F", "0b1010", "1.5e-3f", "0x1.8p3" }
+            }
+        });
+
+        yield return new TerminalLibrary("std::json_number", Array.Empty<TerminalLibraryParameter>(), _ => new[]
+        {
+            new LibraryTerminal("NUMBER", @"-?(?:0|[1-9][0-9]*)(?:\.[0-9]+)?(?:[eE][+-]?[0-9]+)?", TokenType.Literal)
+            {
+                Description = "A JSON number.",
+                Expected = "a number",
+                Examples = new[] { "-0", "12.5e+3" }
+            }
+        });
+
+        yield return new TerminalLibrary("std::dq_string", new[] { new TerminalLibraryParameter("escapes", new[] { "c", "json" }, "c") }, values => new[]
+        {
+            new LibraryTerminal("STRING", values["escapes"] == "json" ? JsonString : CString, TokenType.Literal)
+            {
+                Description = values["escapes"] == "json" ? "A JSON string." : "A C string literal.",
+                Expected = "a string",
+                Escapes = values["escapes"],
+                Examples = values["escapes"] == "json" ? new[] { @"""tab\tquote\""""", @"""😀""" } : new[] { @"""tab\tquote\""""", @"""\x41\101""" }
+            }
+        });
+
+        yield return new TerminalLibrary("std::c_comments", Array.Empty<TerminalLibraryParameter>(), _ => new[]
+        {
+            new LibraryTerminal("LINE_COMMENT", @"//[^\r\n]*", TokenType.Comment) { Description = "A comment to the end of the line." },
+            new LibraryTerminal("BLOCK_COMMENT", @"/\*[\s\S]*?\*/", TokenType.Comment) { Description = "A comment up to the first */." }
+        });
+    }
+
+    private static string CNumeric(bool underscores)
+    {
+        var dec = Digits("[0-9]", underscores);
+        var hex = Digits(Hex, underscores);
+        var bin = Digits("[01]", underscores);
+        const string integerSuffix = "(?:[uU](?:ll|LL|[lL])?|(?:ll|LL|[lL])[uU]?)?";
+        const string floatSuffix = "[fFlL]?";
+
+        // Alternatives are tried in order, so each float form comes before the integer it starts with.
+        return "(?:" + string.Join("|",
+            $@"0[xX](?:{hex}\.(?:{hex})?|\.{hex}|{hex})[pP][+-]?{dec}{floatSuffix}",
+            $"0[xX]{hex}{integerSuffix}",
+            $"0[bB]{bin}{integerSuffix}",
+            $@"(?:{dec}\.(?:{dec})?|\.{dec})(?:[eE][+-]?{dec})?{floatSuffix}",
+            $"{dec}[eE][+-]?{dec}{floatSuffix}",
+            $"{dec}{integerSuffix}") + ")";
+    }
+
+    private static string Digits(string digit, bool underscores)
+    {
+        return underscores ? $"{digit}(?:_?{digit})*" : $"{digit}+";
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.GrammarGeneration;
+
+/// <summary>
+/// A terminal a <see cref="TerminalLibrary"/> defines.
+/// </summary>
+/// <param name="Name">The token name, e.g. <c>NUMBER</c>.</param>
+/// <param name="Pattern">The regular expression, without slashes.</param>
+/// <param name="Type">The token type; comments and whitespace are skipped by the lexer.</param>
+public sealed record LibraryTerminal(string Name, string Pattern, TokenType Type)
+{
+    /// <summary>
+    /// Gets the description documenting the terminal, if any.
+    /// </summary>
+    public string? Description { get; init; }
+
+    /// <summary>
+    /// Gets how syntax errors name the terminal, if any; see <see cref="GrammarAnnotationKind.Expected"/>.
+    /// </summary>
+    public string? Expected { get; init; }
+
+    /// <summary>
+    /// Gets the escape scheme of a string terminal, like <c>json</c>, if any; see <see cref="GrammarAnnotationKind.Escapes"/>.
+    /// </summary>
+    public string? Escapes { get; init; }
+
+    /// <summary>
+    /// Gets example texts the terminal matches whole.
+    /// </summary>
+    public IReadOnlyList<string> Examples { get; init; } = Array.Empty<string>();
+}
+
+/// <summary>
+/// A parameter of a <see cref="TerminalLibrary"/>, selecting between dialects.
+/// </summary>
+/// <param name="Name">The parameter name, e.g. <c>escapes</c>.</param>
+/// <param name="Values">The values the parameter accepts.</param>
+/// <param name="Default">The value used when a grammar does not set the parameter.</param>
+public sealed record TerminalLibraryParameter(string Name, IReadOnlyList<string> Values, string Default);
+
+/// <summary>
+/// A named, reusable set of terminals that grammars import with the "Terminals" metadata entry instead of writing
+/// their own patterns for numbers, strings, identifiers and comments.
+/// </summary>
+public sealed class TerminalLibrary
+{
+    private readonly Func<IReadOnlyDictionary<string, string>, IEnumerable<LibraryTerminal>> _create;
+
+    /// <summary>
+    /// Initializes a new instance of the TerminalLibrary class.
+    /// </summary>
+    /// <param name="name">The name grammars import the library by, e.g. <c>std::dq_string</c>.</param>
+    /// <param name="parameters">The library's parameters.</param>
+    /// <param name="create">Creates the terminals for the parameter values, keyed by parameter name.</param>
+    public TerminalLibrary(string name, IEnumerable<TerminalLibraryParameter> parameters, Func<IReadOnlyDictionary<string, string>, IEnumerable<LibraryTerminal>> create)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(name);
+        ArgumentNullException.ThrowIfNull(parameters);
+        ArgumentNullException.ThrowIfNull(create);
+
+        Name = name;
+        Parameters = parameters.ToList();
+        _create = create;
+    }
+
+    /// <summary>
+    /// Gets the name grammars import the library by.
+    /// </summary>
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets the library's parameters.
+    /// </summary>
+    public IReadOnlyList<TerminalLibraryParameter> Parameters { get; }
+
+    /// <summary>
+    /// Creates a library without parameters from the token patterns of a grammar, named after the grammar. The
+    /// descriptions, expected phrases, escape schemes and examples annotating the patterns go with them.
+    /// </summary>
+    /// <param name="grammar">The grammar holding the patterns, usually a file of token definitions only.</param>
+    /// <returns>The library.</returns>
+    public static TerminalLibrary FromGrammar(Grammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        string? Annotation(string target, GrammarAnnotationKind kind)
+        {
+            var texts = grammar.Annotations.Where(a => a.Target == target && a.Kind == kind).Select(a => a.Text).ToList();
+            return texts.Count > 0 ? string.Join(" ", texts) : null;
+        }
+
+        var terminals = grammar.TokenRules.Patterns
+            .Select(p => new LibraryTerminal(p.Name, p.Pattern, p.Type)
+            {
+                Description = Annotation(p.Name, GrammarAnnotationKind.Description),
+                Expected = Annotation(p.Name, GrammarAnnotationKind.Expected),
+                Escapes = Annotation(p.Name, GrammarAnnotationKind.Escapes),
+                Examples = grammar.Annotations.Where(a => a.Target == p.Name && a.Kind == GrammarAnnotationKind.Example).Select(a => a.Text).ToList()
+            })
+            .ToList();
+        return new TerminalLibrary(grammar.Name, Array.Empty<TerminalLibraryParameter>(), _ => terminals);
+    }
+
+    /// <summary>
+    /// Creates the library's terminals.
+    /// </summary>
+    /// <param name="arguments">The parameter values a grammar sets, keyed by parameter name.</param>
+    /// <param name="reference">The reference with every parameter value spelled out, e.g.
+    /// <c>std::dq_string(escapes = json)</c>, which the terminals record as their provenance.</param>
+    /// <returns>The terminals.</returns>
+    /// <exception cref="ArgumentException">An argument names no parameter or has a value the parameter does not
+    /// accept.</exception>
+    public IReadOnlyList<LibraryTerminal> Create(IReadOnlyDictionary<string, string> arguments, out string reference)
+    {
+        ArgumentNullException.ThrowIfNull(arguments);
+
+        foreach (var name in arguments.Keys.Where(k => Parameters.All(p => p.Name != k)))
+        {
+            throw new ArgumentException($"Terminal library '{Name}' has no parameter '{name}'", nameof(arguments));
+        }
+
+        var values = new Dictionary<string, string>(StringComparer.Ordinal);
+        foreach (var parameter in Parameters)
+        {
+            var value = arguments.GetValueOrDefault(parameter.Name) ?? parameter.Default;
+            if (!parameter.Values.Contains(value))
+            {
+                throw new ArgumentException(
+                    $"Parameter '{parameter.Name}' of terminal library '{Name}' must be one of {string.Join(", ", parameter.Values)}, not '{value}'",
+                    nameof(arguments));
+            }
+
+            values[parameter.Name] = value;
+        }
+
+        var text = new StringBuilder(Name);
+        if (Parameters.Count > 0)
+        {
+            text.Append('(').AppendJoin(", ", Parameters.Select(p => $"{p.Name} = {values[p.Name]}")).Append(')');
+        }
+
+        reference = text.ToString();
+        return _create(values).ToList();
+    }
+}
@@ -0,0 +1,200 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.GrammarGeneration;
+
+/// <summary>
+/// The terminal libraries grammars can import: the standard ones under <c>std::</c> and those registered by the host.
+/// </summary>
+/// <remarks>
+/// <para>
+/// A grammar imports libraries with the "Terminals" metadata entry, a comma-separated list of library names, each
+/// optionally followed by parameters in parentheses, e.g.
+/// <c>Terminals: std::c_numeric, std::dq_string(escapes = json)</c>. The <see cref="GrammarFileReader"/> resolves the
+/// list when it reads the grammar, into ordinary token patterns that record the library in
+/// <see cref="TokenPattern.Library"/>. A token the grammar defines itself takes precedence over a library's, and of
+/// two libraries defining a token the first listed wins.
+/// </para>
+/// <para>
+/// The libraries' descriptions, expected phrases and examples become annotations of their tokens, so they show in
+/// <see cref="Parser.GrammarDocs"/> with the library they came from and name the tokens in syntax errors. Examples
+/// of the tokens the grammar's rules use are checked by <see cref="Parser.GrammarDocs.Validate"/> like the grammar's
+/// own.
+/// </para>
+/// </remarks>
+public sealed class TerminalLibraryRegistry
+{
+    /// <summary>
+    /// The metadata key listing the terminal libraries a grammar imports.
+    /// </summary>
+    public const string TerminalsKey = "Terminals";
+
+    private readonly Dictionary<string, TerminalLibrary> _libraries = new(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Initializes a new instance of the TerminalLibraryRegistry class with the standard libraries.
+    /// </summary>
+    public TerminalLibraryRegistry()
+    {
+        foreach (var library in StandardTerminalLibraries.Create())
+        {
+            Register(library);
+        }
+    }
+
+    /// <summary>
+    /// Gets the names of the registered libraries, in ordinal order.
+    /// </summary>
+    public IEnumerable<string> LibraryNames => _libraries.Keys.OrderBy(n => n, StringComparer.Ordinal);
+
+    /// <summary>
+    /// Registers a library, replacing any library of the same name.
+    /// </summary>
+    /// <param name="library">The library.</param>
+    /// <returns>This registry, for chaining.</returns>
+    public TerminalLibraryRegistry Register(TerminalLibrary library)
+    {
+        ArgumentNullException.ThrowIfNull(library);
+
+        _libraries[library.Name] = library;
+        return this;
+    }
+
+    /// <summary>
+    /// Gets a library by name.
+    /// </summary>
+    /// <param name="name">The library name.</param>
+    /// <returns>The library, or null if none is registered under the name.</returns>
+    public TerminalLibrary? Get(string name)
+    {
+        return _libraries.GetValueOrDefault(name);
+    }
+
+    /// <summary>
+    /// Adds the token patterns of the libraries a grammar imports to the grammar.
+    /// </summary>
+    /// <param name="grammar">The grammar; unchanged if it has no "Terminals" entry.</param>
+    /// <exception cref="ArgumentException">The grammar imports a library that is not registered, or sets a
+    /// parameter the library does not have or to a value it does not accept.</exception>
+    public void Resolve(Grammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        if (!grammar.Metadata.TryGetValue(TerminalsKey, out var declaration))
+        {
+            return;
+        }
+
+        var defined = grammar.TokenRules.Patterns.Select(p => p.Name).ToHashSet(StringComparer.Ordinal);
+        foreach (var (name, arguments) in ParseReferences(grammar, declaration))
+        {
+            var library = Get(name)
+                ?? throw new ArgumentException($"Terminal library '{name}' imported by grammar '{grammar.Name}' is not defined", nameof(grammar));
+
+            var terminals = library.Create(arguments, out var reference);
+            foreach (var terminal in terminals.Where(t => defined.Add(t.Name)))
+            {
+                grammar.TokenRules.AddPattern(new TokenPattern
+                {
+                    Name = terminal.Name,
+                    Pattern = terminal.Pattern,
+                    Type = terminal.Type,
+                    Library = reference
+                });
+                AddAnnotations(grammar, terminal, reference);
+            }
+        }
+    }
+
+    private static void AddAnnotations(Grammar grammar, LibraryTerminal terminal, string reference)
+    {
+        var description = terminal.Description != null ? $"{terminal.Description} (from {reference})" : $"From {reference}.";
+        grammar.Annotations.Add(new GrammarAnnotation { Kind = GrammarAnnotationKind.Description, Target = terminal.Name, Text = description });
+        if (terminal.Expected != null)
+        {
+            grammar.Annotations.Add(new GrammarAnnotation { Kind = GrammarAnnotationKind.Expected, Target = terminal.Name, Text = terminal.Expected });
+        }
+
+        if (terminal.Escapes != null)
+        {
+            grammar.Annotations.Add(new GrammarAnnotation { Kind = GrammarAnnotationKind.Escapes, Target = terminal.Name, Text = terminal.Escapes });
+        }
+
+        // Examples are checked by lexing them, which only works for tokens the grammar uses and does not skip.
+        var lexed = terminal.Type is not (TokenType.Comment or TokenType.Whitespace)
+            && grammar.ProductionRules.Rules.Any(r => r.Alternatives.Any(a => a.Contains($"<{terminal.Name}>", StringComparison.Ordinal)));
+        foreach (var example in lexed ? terminal.Examples : Array.Empty<string>())
+        {
+            grammar.Annotations.Add(new GrammarAnnotation { Kind = GrammarAnnotationKind.Example, Target = terminal.Name, Text = example });
+        }
+    }
+
+    // "a, b(x = 1, y = 2)": commas inside parentheses separate parameters, not libraries.
+    private static IEnumerable<(string Name, Dictionary<string, string> Arguments)> ParseReferences(Grammar grammar, string declaration)
+    {
+        var depth = 0;
+        var start = 0;
+        var references = new List<string>();
+        for (var i = 0; i <= declaration.Length; i++)
+        {
+            if (i == declaration.Length || declaration[i] == ',' && depth == 0)
+            {
+                references.Add(declaration[start..i].Trim());
+                start = i + 1;
+            }
+            else if (declaration[i] == '(')
+            {
+                depth++;
+            }
+            else if (declaration[i] == ')')
+            {
+                depth--;
+            }
+        }
+
+        foreach (var reference in references.Where(r => r.Length > 0))
+        {
+            var open = reference.IndexOf('(');
+            if (open < 0)
+            {
+                yield return (reference, new Dictionary<string, string>(StringComparer.Ordinal));
+                continue;
+            }
+
+            if (!reference.EndsWith(')'))
+            {
+                throw new ArgumentException($"Terminal library reference '{reference}' in grammar '{grammar.Name}' must end with ')'", nameof(grammar));
+            }
+
+            var arguments = new Dictionary<string, string>(StringComparer.Ordinal);
+            foreach (var argument in reference[(open + 1)..^1].Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+            {
+                var separator = argument.IndexOf('=');
+                if (separator <= 0)
+                {
+                    throw new ArgumentException($"Parameter '{argument}' of terminal library reference '{reference}' in grammar '{grammar.Name}' must be written as name = value", nameof(grammar));
+                }
+
+                arguments[argument[..separator].Trim()] = argument[(separator + 1)..].Trim();
+            }
+
+            yield return (reference[..open].Trim(), arguments);
+        }
+    }
+}
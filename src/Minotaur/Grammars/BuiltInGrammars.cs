@@ -0,0 +1,184 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Grammars;
+
+/// <summary>
+/// The grammars bundled with Minotaur as embedded resources: JSON and YAML. They are complete grammars rather than
+/// examples, each with a typed accessor over its trees (<see cref="JsonValue"/> and <see cref="YamlStream"/>),
+/// and the reference for packaging a grammar: annotated with validated examples, documented limits, and a
+/// conformance suite in the tests.
+/// </summary>
+/// <remarks>
+/// The grammars are built into the assembly unless the build sets the MinotaurBuiltInGrammars property to false,
+/// which leaves out this namespace together with the resources.
+/// </remarks>
+public static class BuiltInGrammars
+{
+    /// <summary>
+    /// The name of the JSON grammar.
+    /// </summary>
+    public const string JsonName = "Json";
+
+    /// <summary>
+    /// The name of the YAML grammar.
+    /// </summary>
+    public const string YamlName = "Yaml";
+
+    /// <summary>
+    /// The feature enabling strict JSON parsing, in which an object repeating a member name is an error.
+    /// </summary>
+    public const string JsonStrictFeature = "strict";
+
+    private static readonly Lazy<CompiledGrammar> _json = new(() => Compile(JsonName));
+    private static readonly Lazy<CompiledGrammar> _yaml = new(() => Compile(YamlName));
+
+    /// <summary>
+    /// Gets the names of the built-in grammars.
+    /// </summary>
+    public static IReadOnlyList<string> Names { get; } = new[] { JsonName, YamlName };
+
+    /// <summary>
+    /// Gets the compiled JSON grammar (RFC 8259).
+    /// </summary>
+    public static CompiledGrammar Json => _json.Value;
+
+    /// <summary>
+    /// Gets the compiled YAML grammar (YAML 1.2 without complex keys, directives and multi-line plain scalars).
+    /// </summary>
+    public static CompiledGrammar Yaml => _yaml.Value;
+
+    /// <summary>
+    /// Reads the grammar file of a built-in grammar.
+    /// </summary>
+    /// <param name="name">The grammar name, e.g. <see cref="JsonName"/>.</param>
+    /// <returns>The grammar file content.</returns>
+    /// <exception cref="ArgumentException">There is no built-in grammar of the name.</exception>
+    public static string ReadSource(string name)
+    {
+        ArgumentNullException.ThrowIfNull(name);
+
+        using var stream = typeof(BuiltInGrammars).Assembly.GetManifestResourceStream($"Minotaur.Grammars.{name}.grammar")
+            ?? throw new ArgumentException($"There is no built-in grammar '{name}'", nameof(name));
+        using var reader = new StreamReader(stream);
+        return reader.ReadToEnd();
+    }
+
+    /// <summary>
+    /// Reads a built-in grammar into a grammar model, for hosts that extend or import it.
+    /// </summary>
+    /// <param name="name">The grammar name.</param>
+    /// <returns>The grammar model.</returns>
+    /// <exception cref="ArgumentException">There is no built-in grammar of the name.</exception>
+    public static Grammar Read(string name)
+    {
+        return new GrammarFileReader().Read(ReadSource(name));
+    }
+
+    /// <summary>
+    /// Compiles a built-in grammar.
+    /// </summary>
+    /// <param name="name">The grammar name.</param>
+    /// <returns>The compiled grammar.</returns>
+    /// <exception cref="ArgumentException">There is no built-in grammar of the name.</exception>
+    public static CompiledGrammar Compile(string name)
+    {
+        return CompiledGrammar.Compile(Read(name));
+    }
+
+    /// <summary>
+    /// Parses text strictly: without keyword correction, so malformed input is rejected rather than repaired.
+    /// </summary>
+    internal static ParseResult Parse(CompiledGrammar grammar, string text, string? sourceFile)
+    {
+        return new GeneralizedParser(grammar).Parse(text, new ParseOptions
+        {
+            SourceFile = sourceFile,
+            KeywordCorrectionDistance = 0
+        });
+    }
+
+    /// <summary>
+    /// Throws the first error of a failed parse as a <see cref="FormatException"/>.
+    /// </summary>
+    internal static CognitiveGraphNode RequireTree(ParseResult result, string language)
+    {
+        if (result.IsSuccess)
+        {
+            return result.Tree!;
+        }
+
+        var error = result.Diagnostics.First(d => d.Severity == DiagnosticSeverity.Error);
+        var location = error.Location != null ? $" at line {error.Location.Line}, column {error.Location.Column}" : string.Empty;
+        throw new FormatException($"Invalid {language}{location}: {error.Message}");
+    }
+
+    /// <summary>
+    /// Lists the items of a left-recursive list rule, <c>&lt;list&gt; ::= &lt;item&gt; | &lt;list&gt; sep &lt;item&gt;</c>,
+    /// in source order without recursing down the list.
+    /// </summary>
+    internal static List<NonTerminalNode> Flatten(NonTerminalNode list, string itemRule)
+    {
+        var items = new List<NonTerminalNode>();
+        NonTerminalNode? current = list;
+        while (current != null)
+        {
+            items.Add(Child(current, itemRule)!);
+            current = Child(current, list.RuleName);
+        }
+
+        items.Reverse();
+        return items;
+    }
+
+    /// <summary>
+    /// Finds the first child produced by a rule.
+    /// </summary>
+    internal static NonTerminalNode? Child(CognitiveGraphNode node, string rule)
+    {
+        foreach (var child in node.Children)
+        {
+            if (child is NonTerminalNode nonTerminal && nonTerminal.RuleName == rule)
+            {
+                return nonTerminal;
+            }
+        }
+
+        return null;
+    }
+
+    /// <summary>
+    /// Finds the first child that is a token of a kind.
+    /// </summary>
+    internal static TerminalNode? Token(CognitiveGraphNode node, string kind)
+    {
+        foreach (var child in node.Children)
+        {
+            if (child is TerminalNode terminal && terminal.TokenType == kind)
+            {
+                return terminal;
+            }
+        }
+
+        return null;
+    }
+}
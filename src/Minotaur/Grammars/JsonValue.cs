@@ -0,0 +1,435 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Parser;
+
+namespace Minotaur.Grammars;
+
+/// <summary>
+/// The kinds of JSON values.
+/// </summary>
+public enum JsonKind
+{
+    /// <summary>
+    /// An object of name/value pairs.
+    /// </summary>
+    Object,
+
+    /// <summary>
+    /// An array of values.
+    /// </summary>
+    Array,
+
+    /// <summary>
+    /// A string.
+    /// </summary>
+    String,
+
+    /// <summary>
+    /// A number.
+    /// </summary>
+    Number,
+
+    /// <summary>
+    /// The literal <c>true</c>.
+    /// </summary>
+    True,
+
+    /// <summary>
+    /// The literal <c>false</c>.
+    /// </summary>
+    False,
+
+    /// <summary>
+    /// The literal <c>null</c>.
+    /// </summary>
+    Null
+}
+
+/// <summary>
+/// A name/value pair of a JSON object.
+/// </summary>
+/// <param name="Name">The decoded name.</param>
+/// <param name="Value">The value.</param>
+/// <param name="NamePosition">The location of the name's string, or null if the tree has no positions.</param>
+public sealed record JsonProperty(string Name, JsonValue Value, SourcePosition? NamePosition);
+
+/// <summary>
+/// A JSON value read from a tree of the built-in JSON grammar: strings are decoded, surrogate pairs included,
+/// numbers keep their text so no precision is lost before the caller picks a type, and objects keep their
+/// properties in source order, duplicates included.
+/// </summary>
+/// <remarks>
+/// Every value keeps its location, and, when the tree is read with its source text, the exact text it was
+/// written as, so a tool can rewrite one value and splice the rest of the document back unchanged. Trees of any
+/// depth are read and written without recursion.
+/// </remarks>
+public sealed class JsonValue
+{
+    private readonly string? _scalar;
+    private string? _sourceText;
+
+    private JsonValue(JsonKind kind, string? scalar, IReadOnlyList<JsonValue> items, IReadOnlyList<JsonProperty> properties, SourcePosition? position, string? sourceText)
+    {
+        Kind = kind;
+        _scalar = scalar;
+        Items = items;
+        Properties = properties;
+        Position = position;
+        _sourceText = sourceText;
+    }
+
+    /// <summary>
+    /// Gets the kind of value.
+    /// </summary>
+    public JsonKind Kind { get; }
+
+    /// <summary>
+    /// Gets the elements of an array; empty for other kinds.
+    /// </summary>
+    public IReadOnlyList<JsonValue> Items { get; }
+
+    /// <summary>
+    /// Gets the properties of an object in source order; empty for other kinds.
+    /// </summary>
+    public IReadOnlyList<JsonProperty> Properties { get; }
+
+    /// <summary>
+    /// Gets the location of the value, or null if the tree has no positions.
+    /// </summary>
+    public SourcePosition? Position { get; }
+
+    /// <summary>
+    /// Gets the value as written: the source text of its span when the tree was read with its source, otherwise
+    /// the token text of a scalar or the compact JSON of an object or array.
+    /// </summary>
+    public string SourceText => _sourceText ??= ToString();
+
+    /// <summary>
+    /// Gets the element at an index of an array.
+    /// </summary>
+    /// <param name="index">The index.</param>
+    /// <returns>The element.</returns>
+    /// <exception cref="InvalidOperationException">The value is not an array.</exception>
+    public JsonValue this[int index] => Kind == JsonKind.Array
+        ? Items[index]
+        : throw new InvalidOperationException($"A JSON {Kind.ToString().ToLowerInvariant()} has no elements");
+
+    /// <summary>
+    /// Gets the value of a property of an object.
+    /// </summary>
+    /// <param name="name">The property name.</param>
+    /// <returns>The value of the last property with the name.</returns>
+    /// <exception cref="KeyNotFoundException">The object has no property with the name.</exception>
+    /// <exception cref="InvalidOperationException">The value is not an object.</exception>
+    public JsonValue this[string name] => TryGetProperty(name, out var value)
+        ? value
+        : throw new KeyNotFoundException($"The JSON object has no property '{name}'");
+
+    /// <summary>
+    /// Finds the value of a property of an object. Of duplicate names the last wins, as in JavaScript.
+    /// </summary>
+    /// <param name="name">The property name.</param>
+    /// <param name="value">The value, if found.</param>
+    /// <returns>True if the object has a property with the name.</returns>
+    /// <exception cref="InvalidOperationException">The value is not an object.</exception>
+    public bool TryGetProperty(string name, out JsonValue value)
+    {
+        ArgumentNullException.ThrowIfNull(name);
+
+        if (Kind != JsonKind.Object)
+        {
+            throw new InvalidOperationException($"A JSON {Kind.ToString().ToLowerInvariant()} has no properties");
+        }
+
+        for (var i = Properties.Count - 1; i >= 0; i--)
+        {
+            if (Properties[i].Name == name)
+            {
+                value = Properties[i].Value;
+                return true;
+            }
+        }
+
+        value = null!;
+        return false;
+    }
+
+    /// <summary>
+    /// Gets the decoded text of a string. Escaped lone surrogates, which JSON allows, are kept as they are.
+    /// </summary>
+    /// <returns>The string.</returns>
+    /// <exception cref="InvalidOperationException">The value is not a string.</exception>
+    public string GetString()
+    {
+        return Kind == JsonKind.String ? _scalar! : throw Mismatch(JsonKind.String);
+    }
+
+    /// <summary>
+    /// Gets a number as a double. Numbers beyond its range become infinities, as in JavaScript.
+    /// </summary>
+    /// <returns>The number.</returns>
+    /// <exception cref="InvalidOperationException">The value is not a number.</exception>
+    public double GetDouble()
+    {
+        return Kind == JsonKind.Number
+            ? double.Parse(_scalar!, NumberStyles.Float, CultureInfo.InvariantCulture)
+            : throw Mismatch(JsonKind.Number);
+    }
+
+    /// <summary>
+    /// Gets a number as a 64-bit integer, if it is written as one and fits.
+    /// </summary>
+    /// <param name="value">The integer.</param>
+    /// <returns>True if the number is an integer in range.</returns>
+    /// <exception cref="InvalidOperationException">The value is not a number.</exception>
+    public bool TryGetInt64(out long value)
+    {
+        if (Kind != JsonKind.Number)
+        {
+            throw Mismatch(JsonKind.Number);
+        }
+
+        return long.TryParse(_scalar, NumberStyles.AllowLeadingSign, CultureInfo.InvariantCulture, out value);
+    }
+
+    /// <summary>
+    /// Gets the value of <c>true</c> or <c>false</c>.
+    /// </summary>
+    /// <returns>The boolean.</returns>
+    /// <exception cref="InvalidOperationException">The value is not a boolean.</exception>
+    public bool GetBoolean()
+    {
+        return Kind switch
+        {
+            JsonKind.True => true,
+            JsonKind.False => false,
+            _ => throw Mismatch(JsonKind.True)
+        };
+    }
+
+    /// <summary>
+    /// Writes the value as compact JSON, escaping only quotes, backslashes and control characters.
+    /// </summary>
+    /// <returns>The JSON text.</returns>
+    public override string ToString()
+    {
+        var text = new StringBuilder();
+        var pending = new Stack<object>();
+        pending.Push(this);
+
+        while (pending.Count > 0)
+        {
+            var next = pending.Pop();
+            if (next is string literal)
+            {
+                text.Append(literal);
+                continue;
+            }
+
+            var value = (JsonValue)next;
+            switch (value.Kind)
+            {
+                case JsonKind.Object:
+                    text.Append('{');
+                    pending.Push("}");
+                    for (var i = value.Properties.Count - 1; i >= 0; i--)
+                    {
+                        pending.Push(value.Properties[i].Value);
+                        pending.Push(Quote(value.Properties[i].Name) + ":");
+                        if (i > 0)
+                        {
+                            pending.Push(",");
+                        }
+                    }
+                    break;
+
+                case JsonKind.Array:
+                    text.Append('[');
+                    pending.Push("]");
+                    for (var i = value.Items.Count - 1; i >= 0; i--)
+                    {
+                        pending.Push(value.Items[i]);
+                        if (i > 0)
+                        {
+                            pending.Push(",");
+                        }
+                    }
+                    break;
+
+                case JsonKind.String:
+                    text.Append(Quote(value._scalar!));
+                    break;
+
+                case JsonKind.Number:
+                    text.Append(value._scalar);
+                    break;
+
+                default:
+                    text.Append(value.Kind.ToString().ToLowerInvariant());
+                    break;
+            }
+        }
+
+        return text.ToString();
+    }
+
+    /// <summary>
+    /// Reads a JSON value from a tree of the built-in JSON grammar.
+    /// </summary>
+    /// <param name="tree">The tree of a <c>&lt;json&gt;</c> or <c>&lt;value&gt;</c> rule.</param>
+    /// <param name="source">The text the tree was parsed from, to keep the text of every value; optional.</param>
+    /// <returns>The value.</returns>
+    /// <exception cref="ArgumentException">The tree is not a JSON text or value.</exception>
+    public static JsonValue FromTree(CognitiveGraphNode tree, string? source = null)
+    {
+        ArgumentNullException.ThrowIfNull(tree);
+
+        var root = tree is NonTerminalNode { RuleName: "json" } ? BuiltInGrammars.Child(tree, "value") : tree as NonTerminalNode;
+        if (root?.RuleName != "value")
+        {
+            throw new ArgumentException($"The tree is not a JSON text or value: {tree}", nameof(tree));
+        }
+
+        // Composites are expanded into their values, which are read before the composite is built from them.
+        var results = new Stack<JsonValue>();
+        var pending = new Stack<(NonTerminalNode Value, NonTerminalNode? Composite, List<TerminalNode>? Names, int Count)>();
+        pending.Push((root, null, null, 0));
+
+        while (pending.Count > 0)
+        {
+            var (value, composite, names, count) = pending.Pop();
+            if (composite != null)
+            {
+                results.Push(Build(composite, names, count, results, source));
+                continue;
+            }
+
+            if (value.Children[0] is TerminalNode terminal)
+            {
+                results.Push(Scalar(terminal, source));
+                continue;
+            }
+
+            var content = (NonTerminalNode)value.Children[0];
+            var isObject = content.RuleName == "object";
+            var list = content.Children.OfType<NonTerminalNode>().FirstOrDefault();
+            var entries = list != null ? BuiltInGrammars.Flatten(list, isObject ? "member" : "value") : new List<NonTerminalNode>();
+            var values = isObject ? entries.Select(m => BuiltInGrammars.Child(m, "value")!).ToList() : entries;
+
+            pending.Push((value, content, isObject ? entries.Select(m => BuiltInGrammars.Token(m, "STRING")!).ToList() : null, values.Count));
+            for (var i = values.Count - 1; i >= 0; i--)
+            {
+                pending.Push((values[i], null, null, 0));
+            }
+        }
+
+        return results.Pop();
+    }
+
+    /// <summary>
+    /// Parses JSON text with the built-in grammar.
+    /// </summary>
+    /// <param name="json">The JSON text.</param>
+    /// <param name="sourceFile">The file name for positions, if any.</param>
+    /// <returns>The value.</returns>
+    /// <exception cref="FormatException">The text is not valid JSON.</exception>
+    public static JsonValue Parse(string json, string? sourceFile = null)
+    {
+        ArgumentNullException.ThrowIfNull(json);
+
+        var result = BuiltInGrammars.Parse(BuiltInGrammars.Json, json, sourceFile);
+        return FromTree(BuiltInGrammars.RequireTree(result, "JSON"), json);
+    }
+
+    private static JsonValue Scalar(TerminalNode terminal, string? source)
+    {
+        var (kind, scalar) = terminal.TokenType switch
+        {
+            "STRING" => (JsonKind.String, EscapeScheme.Json.Decode(terminal.Text).Value),
+            "NUMBER" => (JsonKind.Number, terminal.Text),
+            _ => (Enum.Parse<JsonKind>(terminal.Text, ignoreCase: true), (string?)null)
+        };
+
+        return new JsonValue(kind, scalar, Array.Empty<JsonValue>(), Array.Empty<JsonProperty>(), terminal.SourcePosition, Slice(source, terminal.SourcePosition) ?? terminal.Text);
+    }
+
+    private static JsonValue Build(NonTerminalNode composite, List<TerminalNode>? names, int count, Stack<JsonValue> results, string? source)
+    {
+        var values = new JsonValue[count];
+        for (var i = count - 1; i >= 0; i--)
+        {
+            values[i] = results.Pop();
+        }
+
+        var position = composite.SourcePosition;
+        if (names == null)
+        {
+            return new JsonValue(JsonKind.Array, null, values, Array.Empty<JsonProperty>(), position, Slice(source, position));
+        }
+
+        var properties = names.Select((name, i) => new JsonProperty(EscapeScheme.Json.Decode(name.Text).Value, values[i], name.SourcePosition)).ToList();
+        return new JsonValue(JsonKind.Object, null, Array.Empty<JsonValue>(), properties, position, Slice(source, position));
+    }
+
+    private static string? Slice(string? source, SourcePosition? position)
+    {
+        return source != null && position != null && position.Offset + position.Length <= source.Length
+            ? source.Substring(position.Offset, position.Length)
+            : null;
+    }
+
+    private static string Quote(string value)
+    {
+        var text = new StringBuilder(value.Length + 2).Append('"');
+        foreach (var c in value)
+        {
+            var escaped = c switch
+            {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\b' => "\\b",
+                '\f' => "\\f",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                < ' ' => $"\\u{(int)c:x4}",
+                _ => null
+            };
+
+            if (escaped != null)
+            {
+                text.Append(escaped);
+            }
+            else
+            {
+                text.Append(c);
+            }
+        }
+
+        return text.Append('"').ToString();
+    }
+
+    private InvalidOperationException Mismatch(JsonKind expected)
+    {
+        var name = expected == JsonKind.True ? "boolean" : expected.ToString().ToLowerInvariant();
+        return new InvalidOperationException($"A JSON {Kind.ToString().ToLowerInvariant()} is not a {name}");
+    }
+}
@@ -0,0 +1,440 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+
+namespace Minotaur.Grammars;
+
+/// <summary>
+/// The kinds of YAML nodes.
+/// </summary>
+public enum YamlNodeKind
+{
+    /// <summary>
+    /// A scalar: a plain, quoted or block string.
+    /// </summary>
+    Scalar,
+
+    /// <summary>
+    /// A sequence of nodes.
+    /// </summary>
+    Sequence,
+
+    /// <summary>
+    /// A mapping of key nodes to value nodes.
+    /// </summary>
+    Mapping,
+
+    /// <summary>
+    /// An alias of an anchored node.
+    /// </summary>
+    Alias
+}
+
+/// <summary>
+/// How a scalar is written.
+/// </summary>
+public enum YamlScalarStyle
+{
+    /// <summary>
+    /// Unquoted, the only style whose value is resolved as null, boolean or number.
+    /// </summary>
+    Plain,
+
+    /// <summary>
+    /// In single quotes.
+    /// </summary>
+    SingleQuoted,
+
+    /// <summary>
+    /// In double quotes, with escapes.
+    /// </summary>
+    DoubleQuoted,
+
+    /// <summary>
+    /// A literal block scalar (<c>|</c>), keeping line breaks.
+    /// </summary>
+    Literal,
+
+    /// <summary>
+    /// A folded block scalar (<c>&gt;</c>), joining lines with spaces.
+    /// </summary>
+    Folded
+}
+
+/// <summary>
+/// A key/value pair of a YAML mapping.
+/// </summary>
+/// <param name="Key">The key node.</param>
+/// <param name="Value">The value node; an empty plain scalar if the entry has no value.</param>
+public sealed record YamlEntry(YamlNode Key, YamlNode Value);
+
+/// <summary>
+/// A YAML node read from a tree of the built-in YAML grammar, with its scalar decoded, its alias resolved and
+/// the comments around it attached. Plain scalars resolve to null, booleans and numbers by the YAML 1.2 core
+/// schema, through <see cref="IsNull"/>, <see cref="TryGetBoolean"/>, <see cref="TryGetInt64"/> and
+/// <see cref="TryGetDouble"/>; every scalar's <see cref="Value"/> is its text.
+/// </summary>
+public sealed class YamlNode
+{
+    private static readonly Regex DecimalInteger = new(@"^[-+]?[0-9]+$", RegexOptions.CultureInvariant);
+    private static readonly Regex Float = new(@"^[-+]?(\.[0-9]+|[0-9]+(\.[0-9]*)?)([eE][-+]?[0-9]+)?$", RegexOptions.CultureInvariant);
+
+    private readonly List<string> _leadingComments = new();
+
+    internal YamlNode(YamlNodeKind kind, string value, YamlScalarStyle style, IReadOnlyList<YamlNode> items, IReadOnlyList<YamlEntry> entries, YamlNode? target, string? anchor, string? tag, SourcePosition? position, string? sourceText)
+    {
+        Kind = kind;
+        Value = value;
+        Style = style;
+        Items = items;
+        Entries = entries;
+        Target = target;
+        Anchor = anchor;
+        Tag = tag;
+        Position = position;
+        SourceText = sourceText ?? value;
+    }
+
+    /// <summary>
+    /// Gets the kind of node.
+    /// </summary>
+    public YamlNodeKind Kind { get; }
+
+    /// <summary>
+    /// Gets the decoded text of a scalar, or the anchor name of an alias; empty for collections.
+    /// </summary>
+    public string Value { get; }
+
+    /// <summary>
+    /// Gets how a scalar is written.
+    /// </summary>
+    public YamlScalarStyle Style { get; }
+
+    /// <summary>
+    /// Gets the items of a sequence; empty for other kinds.
+    /// </summary>
+    public IReadOnlyList<YamlNode> Items { get; }
+
+    /// <summary>
+    /// Gets the entries of a mapping in source order; empty for other kinds.
+    /// </summary>
+    public IReadOnlyList<YamlEntry> Entries { get; }
+
+    /// <summary>
+    /// Gets the node an alias refers to, or null for other kinds.
+    /// </summary>
+    public YamlNode? Target { get; }
+
+    /// <summary>
+    /// Gets the anchor name the node is labeled with, without the <c>&amp;</c>, or null.
+    /// </summary>
+    public string? Anchor { get; }
+
+    /// <summary>
+    /// Gets the tag as written, e.g. <c>!!str</c>, or null.
+    /// </summary>
+    public string? Tag { get; }
+
+    /// <summary>
+    /// Gets the location of the node, properties included, or null for the empty value of an entry.
+    /// </summary>
+    public SourcePosition? Position { get; }
+
+    /// <summary>
+    /// Gets the node as written when the tree was read with its source text, otherwise the value of a scalar.
+    /// </summary>
+    public string SourceText { get; }
+
+    /// <summary>
+    /// Gets the comments on the lines before the node, without the <c>#</c>.
+    /// </summary>
+    public IReadOnlyList<string> LeadingComments => _leadingComments;
+
+    /// <summary>
+    /// Gets the comment after the node on its last line, without the <c>#</c>, or null.
+    /// </summary>
+    public string? TrailingComment { get; internal set; }
+
+    /// <summary>
+    /// Gets the item at an index of a sequence, following aliases.
+    /// </summary>
+    /// <param name="index">The index.</param>
+    /// <returns>The item.</returns>
+    /// <exception cref="InvalidOperationException">The node is not a sequence.</exception>
+    public YamlNode this[int index]
+    {
+        get
+        {
+            var node = Resolve();
+            return node.Kind == YamlNodeKind.Sequence
+                ? node.Items[index]
+                : throw new InvalidOperationException($"A YAML {node.Kind.ToString().ToLowerInvariant()} has no items");
+        }
+    }
+
+    /// <summary>
+    /// Gets the value of the entry whose scalar key has a text, following aliases.
+    /// </summary>
+    /// <param name="key">The key text.</param>
+    /// <returns>The value.</returns>
+    /// <exception cref="KeyNotFoundException">The mapping has no such key.</exception>
+    /// <exception cref="InvalidOperationException">The node is not a mapping.</exception>
+    public YamlNode this[string key] => TryGetValue(key, out var value)
+        ? value
+        : throw new KeyNotFoundException($"The YAML mapping has no key '{key}'");
+
+    /// <summary>
+    /// Finds the value of the entry whose scalar key has a text, following aliases.
+    /// </summary>
+    /// <param name="key">The key text.</param>
+    /// <param name="value">The value, if found.</param>
+    /// <returns>True if the mapping has the key.</returns>
+    /// <exception cref="InvalidOperationException">The node is not a mapping.</exception>
+    public bool TryGetValue(string key, out YamlNode value)
+    {
+        ArgumentNullException.ThrowIfNull(key);
+
+        var node = Resolve();
+        if (node.Kind != YamlNodeKind.Mapping)
+        {
+            throw new InvalidOperationException($"A YAML {node.Kind.ToString().ToLowerInvariant()} has no entries");
+        }
+
+        foreach (var entry in node.Entries)
+        {
+            var candidate = entry.Key.Resolve();
+            if (candidate.Kind == YamlNodeKind.Scalar && candidate.Value == key)
+            {
+                value = entry.Value;
+                return true;
+            }
+        }
+
+        value = null!;
+        return false;
+    }
+
+    /// <summary>
+    /// Follows an alias to the node it refers to.
+    /// </summary>
+    /// <returns>The target of an alias, or this node.</returns>
+    public YamlNode Resolve()
+    {
+        return Target ?? this;
+    }
+
+    /// <summary>
+    /// Gets a value indicating whether the node is null: an empty or <c>~</c> or <c>null</c> plain scalar, or
+    /// tagged <c>!!null</c>.
+    /// </summary>
+    public bool IsNull
+    {
+        get
+        {
+            var node = Resolve();
+            return node.Kind == YamlNodeKind.Scalar && (node.HasTag("null")
+                || node.IsUntaggedPlain && node.Value is "" or "~" or "null" or "Null" or "NULL");
+        }
+    }
+
+    /// <summary>
+    /// Reads a boolean: <c>true</c> or <c>false</c> in lower, title or upper case.
+    /// </summary>
+    /// <param name="value">The boolean.</param>
+    /// <returns>True if the node is a plain or <c>!!bool</c> scalar holding a boolean.</returns>
+    public bool TryGetBoolean(out bool value)
+    {
+        var node = Resolve();
+        value = node.Value is "true" or "True" or "TRUE";
+        if (node.Resolves("bool") && (value || node.Value is "false" or "False" or "FALSE"))
+        {
+            return true;
+        }
+
+        value = false;
+        return false;
+    }
+
+    /// <summary>
+    /// Reads an integer: decimal, <c>0o</c> octal or <c>0x</c> hexadecimal.
+    /// </summary>
+    /// <param name="value">The integer.</param>
+    /// <returns>True if the node is a plain or <c>!!int</c> scalar holding an integer in range.</returns>
+    public bool TryGetInt64(out long value)
+    {
+        var node = Resolve();
+        value = 0;
+        if (!node.Resolves("int"))
+        {
+            return false;
+        }
+
+        var text = node.Value;
+        if (text.StartsWith("0o", StringComparison.Ordinal))
+        {
+            return TryParseDigits(text[2..], 8, out value);
+        }
+
+        if (text.StartsWith("0x", StringComparison.Ordinal))
+        {
+            return TryParseDigits(text[2..], 16, out value);
+        }
+
+        return DecimalInteger.IsMatch(text) && long.TryParse(text, NumberStyles.AllowLeadingSign, CultureInfo.InvariantCulture, out value);
+    }
+
+    /// <summary>
+    /// Reads a number: an integer, a decimal with an optional exponent, <c>.inf</c> or <c>.nan</c>.
+    /// </summary>
+    /// <param name="value">The number.</param>
+    /// <returns>True if the node is a plain, <c>!!float</c> or <c>!!int</c> scalar holding a number.</returns>
+    public bool TryGetDouble(out double value)
+    {
+        var node = Resolve();
+        value = 0;
+        if (!node.Resolves("float") && !node.Resolves("int"))
+        {
+            return false;
+        }
+
+        var text = node.Value;
+        if (Float.IsMatch(text))
+        {
+            value = double.Parse(text, NumberStyles.Float, CultureInfo.InvariantCulture);
+            return true;
+        }
+
+        switch (text.TrimStart('+', '-'))
+        {
+            case ".inf" or ".Inf" or ".INF":
+                value = text[0] == '-' ? double.NegativeInfinity : double.PositiveInfinity;
+                return true;
+
+            case ".nan" or ".NaN" or ".NAN" when text[0] == '.':
+                value = double.NaN;
+                return true;
+        }
+
+        if (TryGetInt64(out var integer))
+        {
+            value = integer;
+            return true;
+        }
+
+        return false;
+    }
+
+    /// <summary>
+    /// Returns a short description of the node.
+    /// </summary>
+    /// <returns>The kind and value or size of the node.</returns>
+    public override string ToString()
+    {
+        return Kind switch
+        {
+            YamlNodeKind.Sequence => $"sequence of {Items.Count}",
+            YamlNodeKind.Mapping => $"mapping of {Entries.Count}",
+            YamlNodeKind.Alias => $"*{Value}",
+            _ => Value
+        };
+    }
+
+    /// <summary>
+    /// Reads the single document of a YAML stream from a tree of the built-in YAML grammar.
+    /// </summary>
+    /// <param name="tree">The tree of a <c>&lt;yaml&gt;</c> rule.</param>
+    /// <param name="source">The text the tree was parsed from, for the source text and comments of nodes; optional.</param>
+    /// <returns>The root node of the document, or an empty plain scalar if the stream has no document.</returns>
+    /// <exception cref="ArgumentException">The tree is not a YAML stream.</exception>
+    /// <exception cref="FormatException">The stream holds several documents, or an alias refers to no anchor.</exception>
+    public static YamlNode FromTree(CognitiveGraphNode tree, string? source = null)
+    {
+        return Single(YamlStream.FromTree(tree, source));
+    }
+
+    /// <summary>
+    /// Parses a single-document YAML stream with the built-in grammar.
+    /// </summary>
+    /// <param name="yaml">The YAML text.</param>
+    /// <param name="sourceFile">The file name for positions, if any.</param>
+    /// <returns>The root node of the document, or an empty plain scalar if the stream has no document.</returns>
+    /// <exception cref="FormatException">The text is not valid YAML, holds several documents, or an alias
+    /// refers to no anchor.</exception>
+    public static YamlNode Parse(string yaml, string? sourceFile = null)
+    {
+        return Single(YamlStream.Parse(yaml, sourceFile));
+    }
+
+    internal static YamlNode Scalar(string value, YamlScalarStyle style, string? anchor, string? tag, SourcePosition? position, string? sourceText)
+    {
+        return new YamlNode(YamlNodeKind.Scalar, value, style, Array.Empty<YamlNode>(), Array.Empty<YamlEntry>(), null, anchor, tag, position, sourceText);
+    }
+
+    internal void AddLeadingComment(string comment)
+    {
+        _leadingComments.Add(comment);
+    }
+
+    private bool IsUntaggedPlain => Tag == null && Style == YamlScalarStyle.Plain;
+
+    private bool Resolves(string type)
+    {
+        return Kind == YamlNodeKind.Scalar && (IsUntaggedPlain || HasTag(type));
+    }
+
+    private bool HasTag(string type)
+    {
+        return Tag == "!!" + type || Tag == "tag:yaml.org,2002:" + type || Tag == "!<tag:yaml.org,2002:" + type + ">";
+    }
+
+    private static bool TryParseDigits(string digits, int radix, out long value)
+    {
+        value = 0;
+        if (digits.Length == 0)
+        {
+            return false;
+        }
+
+        foreach (var c in digits)
+        {
+            var lower = char.ToLowerInvariant(c);
+            var digit = c is >= '0' and <= '9' ? c - '0' : lower is >= 'a' and <= 'f' ? lower - 'a' + 10 : radix;
+            if (digit >= radix || value > (long.MaxValue - digit) / radix)
+            {
+                value = 0;
+                return false;
+            }
+
+            value = value * radix + digit;
+        }
+
+        return true;
+    }
+
+    private static YamlNode Single(YamlStream stream)
+    {
+        return stream.Documents.Count switch
+        {
+            0 => Scalar(string.Empty, YamlScalarStyle.Plain, null, null, null, null),
+            1 => stream.Documents[0],
+            var count => throw new FormatException($"The YAML stream holds {count} documents; read it as a YamlStream")
+        };
+    }
+}
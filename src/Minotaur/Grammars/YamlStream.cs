@@ -0,0 +1,610 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text;
+using Minotaur.Core;
+
+namespace Minotaur.Grammars;
+
+/// <summary>
+/// The documents of a YAML stream read from a tree of the built-in YAML grammar. Scalars are decoded, aliases
+/// resolved to the node last anchored with their name in the same document, and, when the tree is read with
+/// its source text, comments attached to nodes: a comment after a node on its last line trails that node, and
+/// other comments lead the innermost node that starts after them.
+/// </summary>
+/// <remarks>
+/// Collections of any depth and length are read without recursion. Anchors must precede their aliases, and an
+/// anchored collection cannot contain an alias of itself.
+/// </remarks>
+public sealed class YamlStream
+{
+    private YamlStream(IReadOnlyList<YamlNode> documents, IReadOnlyList<string> comments)
+    {
+        Documents = documents;
+        Comments = comments;
+    }
+
+    /// <summary>
+    /// Gets the root nodes of the documents; a document with no content has an empty plain scalar.
+    /// </summary>
+    public IReadOnlyList<YamlNode> Documents { get; }
+
+    /// <summary>
+    /// Gets the comments that belong to no node, like those after the last node of the stream.
+    /// </summary>
+    public IReadOnlyList<string> Comments { get; }
+
+    /// <summary>
+    /// Reads a YAML stream from a tree of the built-in YAML grammar.
+    /// </summary>
+    /// <param name="tree">The tree of a <c>&lt;yaml&gt;</c> rule.</param>
+    /// <param name="source">The text the tree was parsed from, for the source text and comments of nodes; optional.</param>
+    /// <returns>The stream.</returns>
+    /// <exception cref="ArgumentException">The tree is not a YAML stream.</exception>
+    /// <exception cref="FormatException">An alias refers to no anchor, or an escape is invalid.</exception>
+    public static YamlStream FromTree(CognitiveGraphNode tree, string? source = null)
+    {
+        ArgumentNullException.ThrowIfNull(tree);
+
+        if (tree is not NonTerminalNode { RuleName: "yaml" } root)
+        {
+            throw new ArgumentException($"The tree is not a YAML stream: {tree}", nameof(tree));
+        }
+
+        var nodes = new List<YamlNode>();
+        var documents = ReadDocuments(root).Select(document => new Reader(source, nodes).Read(document)).ToList();
+        var comments = source != null ? AttachComments(root, source, nodes) : new List<string>();
+        return new YamlStream(documents, comments);
+    }
+
+    /// <summary>
+    /// Parses a YAML stream with the built-in grammar.
+    /// </summary>
+    /// <param name="yaml">The YAML text.</param>
+    /// <param name="sourceFile">The file name for positions, if any.</param>
+    /// <returns>The stream.</returns>
+    /// <exception cref="FormatException">The text is not valid YAML, or an alias refers to no anchor.</exception>
+    public static YamlStream Parse(string yaml, string? sourceFile = null)
+    {
+        ArgumentNullException.ThrowIfNull(yaml);
+
+        var result = BuiltInGrammars.Parse(BuiltInGrammars.Yaml, yaml, sourceFile);
+        return FromTree(BuiltInGrammars.RequireTree(result, "YAML"), yaml);
+    }
+
+    private static IEnumerable<NonTerminalNode?> ReadDocuments(NonTerminalNode root)
+    {
+        foreach (var child in root.Children.OfType<NonTerminalNode>())
+        {
+            if (child.RuleName == "document")
+            {
+                yield return BuiltInGrammars.Child(child, "block_node");
+                continue;
+            }
+
+            foreach (var document in BuiltInGrammars.Flatten(child, "explicit_document"))
+            {
+                // A document ended by "..." wraps the document it ends.
+                var content = document;
+                while (BuiltInGrammars.Child(content, "explicit_document") is { } inner)
+                {
+                    content = inner;
+                }
+
+                yield return BuiltInGrammars.Child(content, "block_node");
+            }
+        }
+    }
+
+    private static List<string> AttachComments(NonTerminalNode root, string source, List<YamlNode> nodes)
+    {
+        var tokens = TokenPositions(root);
+        var starts = nodes.OrderBy(n => n.Position!.Offset).ThenBy(n => n.Position!.Length).ToList();
+        var ends = new Dictionary<int, YamlNode>();
+        foreach (var node in nodes.OrderByDescending(n => n.Position!.Length))
+        {
+            ends[node.Position!.Offset + node.Position.Length] = node;
+        }
+
+        var orphans = new List<string>();
+        var previousEnd = -1;
+        for (var k = 0; k <= tokens.Count; k++)
+        {
+            var gapStart = Math.Max(previousEnd, 0);
+            var gapEnd = k < tokens.Count ? tokens[k].Offset : source.Length;
+            var hash = gapEnd > gapStart ? source.IndexOf('#', gapStart, gapEnd - gapStart) : -1;
+            while (hash >= 0)
+            {
+                var lineEnd = source.IndexOf('\n', hash, gapEnd - hash);
+                lineEnd = lineEnd < 0 ? gapEnd : lineEnd;
+                var comment = source[(hash + 1)..lineEnd].Trim();
+
+                var trailing = previousEnd >= 0 && source.IndexOf('\n', previousEnd, hash - previousEnd) < 0;
+                if (trailing && ends.TryGetValue(previousEnd, out var owner) && owner.TrailingComment == null)
+                {
+                    owner.TrailingComment = comment;
+                }
+                else if (FirstStartingAt(starts, gapEnd) is { } next)
+                {
+                    next.AddLeadingComment(comment);
+                }
+                else
+                {
+                    orphans.Add(comment);
+                }
+
+                hash = lineEnd < gapEnd ? source.IndexOf('#', lineEnd, gapEnd - lineEnd) : -1;
+            }
+
+            if (k < tokens.Count)
+            {
+                previousEnd = tokens[k].Offset + tokens[k].Length;
+            }
+        }
+
+        return orphans;
+    }
+
+    private static List<SourcePosition> TokenPositions(CognitiveGraphNode root)
+    {
+        var positions = new List<SourcePosition>();
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(root);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node is TerminalNode { SourcePosition: { Length: > 0 } position })
+            {
+                positions.Add(position);
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+
+        return positions;
+    }
+
+    private static YamlNode? FirstStartingAt(List<YamlNode> starts, int offset)
+    {
+        int low = 0, high = starts.Count;
+        while (low < high)
+        {
+            var middle = (low + high) / 2;
+            if (starts[middle].Position!.Offset < offset)
+            {
+                low = middle + 1;
+            }
+            else
+            {
+                high = middle;
+            }
+        }
+
+        return low < starts.Count ? starts[low] : null;
+    }
+
+    private sealed record Collection(YamlNodeKind Kind, string? Anchor, string? Tag, SourcePosition? Position, int Count);
+
+    private sealed record Frame(NonTerminalNode? Syntax, Collection? Collection);
+
+    /// <summary>
+    /// Reads the nodes of one document, whose anchors are its own.
+    /// </summary>
+    private sealed class Reader
+    {
+        private readonly string? _source;
+        private readonly List<YamlNode> _nodes;
+        private readonly Dictionary<string, YamlNode> _anchors = new(StringComparer.Ordinal);
+
+        public Reader(string? source, List<YamlNode> nodes)
+        {
+            _source = source;
+            _nodes = nodes;
+        }
+
+        public YamlNode Read(NonTerminalNode? document)
+        {
+            // Collections are expanded into their children, which are read before the collection is built from them.
+            var results = new Stack<YamlNode>();
+            var pending = new Stack<Frame>();
+            pending.Push(new Frame(document, null));
+
+            while (pending.Count > 0)
+            {
+                var frame = pending.Pop();
+                if (frame.Collection is { } collection)
+                {
+                    results.Push(Build(collection, results));
+                    continue;
+                }
+
+                var (anchor, tag, properties, content) = Unwrap(frame.Syntax);
+                var position = properties != null && content?.SourcePosition != null
+                    ? properties.SpanTo(content.SourcePosition)
+                    : properties ?? content?.SourcePosition;
+
+                switch (content)
+                {
+                    case TerminalNode terminal:
+                        results.Push(Add(ReadScalar(terminal, anchor, tag, position)));
+                        break;
+
+                    case NonTerminalNode node:
+                        var children = ReadChildren(node, out var kind);
+                        pending.Push(new Frame(null, new Collection(kind, anchor, tag, position, children.Count)));
+                        for (var i = children.Count - 1; i >= 0; i--)
+                        {
+                            pending.Push(new Frame(children[i], null));
+                        }
+                        break;
+
+                    default:
+                        results.Push(Add(YamlNode.Scalar(string.Empty, YamlScalarStyle.Plain, anchor, tag, position, Slice(position))));
+                        break;
+                }
+            }
+
+            return results.Pop();
+        }
+
+        private YamlNode Build(Collection collection, Stack<YamlNode> results)
+        {
+            var children = new YamlNode[collection.Count];
+            for (var i = collection.Count - 1; i >= 0; i--)
+            {
+                children[i] = results.Pop();
+            }
+
+            var entries = new List<YamlEntry>();
+            if (collection.Kind == YamlNodeKind.Mapping)
+            {
+                for (var i = 0; i < children.Length; i += 2)
+                {
+                    entries.Add(new YamlEntry(children[i], children[i + 1]));
+                }
+            }
+
+            var items = collection.Kind == YamlNodeKind.Sequence ? children : Array.Empty<YamlNode>();
+            return Add(new YamlNode(collection.Kind, string.Empty, YamlScalarStyle.Plain, items, entries, null,
+                collection.Anchor, collection.Tag, collection.Position, Slice(collection.Position)));
+        }
+
+        private YamlNode ReadScalar(TerminalNode terminal, string? anchor, string? tag, SourcePosition? position)
+        {
+            var text = terminal.Text;
+            var sourceText = Slice(position);
+            switch (terminal.TokenType)
+            {
+                case "ALIAS":
+                    if (!_anchors.TryGetValue(text[1..], out var target))
+                    {
+                        throw new FormatException($"Alias '{text}' at line {position?.Line} refers to no anchor");
+                    }
+
+                    return new YamlNode(YamlNodeKind.Alias, text[1..], YamlScalarStyle.Plain, Array.Empty<YamlNode>(), Array.Empty<YamlEntry>(),
+                        target, null, null, position, sourceText ?? text);
+
+                case "DOUBLE_QUOTED":
+                    return YamlNode.Scalar(Unescape(Fold(text[1..^1])), YamlScalarStyle.DoubleQuoted, anchor, tag, position, sourceText);
+
+                case "SINGLE_QUOTED":
+                    return YamlNode.Scalar(Fold(text[1..^1]).Replace("''", "'"), YamlScalarStyle.SingleQuoted, anchor, tag, position, sourceText);
+
+                case "BLOCK_SCALAR":
+                    var value = ReadBlock(text, out var style);
+                    return YamlNode.Scalar(value, style, anchor, tag, position, sourceText);
+
+                default:
+                    return YamlNode.Scalar(text, YamlScalarStyle.Plain, anchor, tag, position, sourceText);
+            }
+        }
+
+        private YamlNode Add(YamlNode node)
+        {
+            if (node.Anchor != null)
+            {
+                _anchors[node.Anchor] = node;
+            }
+
+            if (node.Position != null)
+            {
+                _nodes.Add(node);
+            }
+
+            return node;
+        }
+
+        private string? Slice(SourcePosition? position)
+        {
+            return _source != null && position != null && position.Offset + position.Length <= _source.Length
+                ? _source.Substring(position.Offset, position.Length)
+                : null;
+        }
+
+        /// <summary>
+        /// Strips the wrappers around a node's content, collecting the anchor and tag on the way.
+        /// </summary>
+        private static (string? Anchor, string? Tag, SourcePosition? Properties, CognitiveGraphNode? Content) Unwrap(NonTerminalNode? syntax)
+        {
+            string? anchor = null;
+            string? tag = null;
+            SourcePosition? properties = null;
+            CognitiveGraphNode? node = syntax;
+
+            while (node is NonTerminalNode current)
+            {
+                if (BuiltInGrammars.Child(current, "properties") is { } declared)
+                {
+                    anchor = BuiltInGrammars.Token(declared, "ANCHOR")?.Text[1..] ?? anchor;
+                    tag = BuiltInGrammars.Token(declared, "TAG")?.Text ?? tag;
+                    properties ??= declared.SourcePosition;
+                }
+
+                switch (current.RuleName)
+                {
+                    case "block_mapping" or "block_sequence" or "flow_sequence" or "flow_mapping" or "flow_pair":
+                        return (anchor, tag, properties, current);
+
+                    case "block_scalar":
+                        node = BuiltInGrammars.Token(current, "BLOCK_SCALAR");
+                        break;
+
+                    case "scalar":
+                        node = current.Children[0];
+                        break;
+
+                    case "flow_node":
+                        node = (CognitiveGraphNode?)BuiltInGrammars.Token(current, "ALIAS") ?? BuiltInGrammars.Child(current, "flow_content");
+                        break;
+
+                    default:
+                        node = current.Children.OfType<NonTerminalNode>().FirstOrDefault(c => c.RuleName != "properties");
+                        break;
+                }
+            }
+
+            return (anchor, tag, properties, node);
+        }
+
+        /// <summary>
+        /// Lists the syntax of a collection's children: items of a sequence, or keys and values of a mapping in
+        /// turn, with null for an entry without a value.
+        /// </summary>
+        private static List<NonTerminalNode?> ReadChildren(NonTerminalNode collection, out YamlNodeKind kind)
+        {
+            var children = new List<NonTerminalNode?>();
+            kind = collection.RuleName is "block_sequence" or "flow_sequence" ? YamlNodeKind.Sequence : YamlNodeKind.Mapping;
+
+            switch (collection.RuleName)
+            {
+                case "block_mapping":
+                    foreach (var entry in BuiltInGrammars.Flatten(collection, "mapping_entry"))
+                    {
+                        children.Add(BuiltInGrammars.Child(entry, "mapping_key"));
+                        children.Add(BuiltInGrammars.Child(entry, "mapping_value"));
+                    }
+                    break;
+
+                case "block_sequence":
+                    foreach (var entry in BuiltInGrammars.Flatten(collection, "sequence_entry"))
+                    {
+                        children.Add(BuiltInGrammars.Child(entry, "indented_node"));
+                    }
+                    break;
+
+                case "flow_sequence":
+                    // A pair in a flow sequence is a mapping of one entry.
+                    foreach (var entry in FlowEntries(collection))
+                    {
+                        children.Add(BuiltInGrammars.Child(entry, "flow_pair") ?? BuiltInGrammars.Child(entry, "flow_node"));
+                    }
+                    break;
+
+                case "flow_mapping":
+                    foreach (var entry in FlowEntries(collection))
+                    {
+                        var pair = BuiltInGrammars.Child(entry, "flow_pair");
+                        children.AddRange(pair != null ? Pair(pair) : new[] { BuiltInGrammars.Child(entry, "flow_node"), null });
+                    }
+                    break;
+
+                default:
+                    children.AddRange(Pair(collection));
+                    break;
+            }
+
+            return children;
+        }
+
+        private static IEnumerable<NonTerminalNode> FlowEntries(NonTerminalNode collection)
+        {
+            return BuiltInGrammars.Child(collection, "flow_entries") is { } entries
+                ? BuiltInGrammars.Flatten(entries, "flow_entry")
+                : Enumerable.Empty<NonTerminalNode>();
+        }
+
+        private static NonTerminalNode?[] Pair(NonTerminalNode pair)
+        {
+            var nodes = pair.Children.OfType<NonTerminalNode>().ToList();
+            return new[] { nodes[0], nodes.Count > 1 ? nodes[1] : null };
+        }
+
+        /// <summary>
+        /// Folds the lines of a quoted scalar: a line break becomes a space, and each empty line a line break.
+        /// </summary>
+        private static string Fold(string value)
+        {
+            if (!value.Contains('\n'))
+            {
+                return value;
+            }
+
+            var lines = value.Split('\n');
+            var text = new StringBuilder(lines[0].TrimEnd(' ', '\t', '\r'));
+            var breaks = 0;
+            for (var i = 1; i < lines.Length; i++)
+            {
+                var last = i == lines.Length - 1;
+                var line = last ? lines[i].TrimStart(' ', '\t') : lines[i].Trim(' ', '\t', '\r');
+                if (line.Length == 0 && !last)
+                {
+                    breaks++;
+                    continue;
+                }
+
+                text.Append(breaks > 0 ? new string('\n', breaks) : " ").Append(line);
+                breaks = 0;
+            }
+
+            return text.ToString();
+        }
+
+        private static string Unescape(string value)
+        {
+            if (!value.Contains('\\'))
+            {
+                return value;
+            }
+
+            var text = new StringBuilder(value.Length);
+            for (var i = 0; i < value.Length; i++)
+            {
+                if (value[i] != '\\' || i + 1 == value.Length)
+                {
+                    text.Append(value[i]);
+                    continue;
+                }
+
+                var escape = value[++i];
+                var digits = escape switch
+                {
+                    'x' => 2,
+                    'u' => 4,
+                    'U' => 8,
+                    _ => 0
+                };
+
+                if (digits > 0)
+                {
+                    if (i + digits >= value.Length || !int.TryParse(value.AsSpan(i + 1, digits), NumberStyles.AllowHexSpecifier, CultureInfo.InvariantCulture, out var code)
+                        || code > 0x10FFFF || digits == 8 && code is >= 0xD800 and <= 0xDFFF)
+                    {
+                        throw new FormatException($"Invalid escape '\\{value.Substring(i, Math.Min(digits + 1, value.Length - i))}' in a double-quoted scalar");
+                    }
+
+                    text.Append(digits == 8 ? char.ConvertFromUtf32(code) : ((char)code).ToString());
+                    i += digits;
+                    continue;
+                }
+
+                text.Append(escape switch
+                {
+                    '0' => '\0',
+                    'a' => '\a',
+                    'b' => '\b',
+                    't' or '\t' => '\t',
+                    'n' => '\n',
+                    'v' => '\v',
+                    'f' => '\f',
+                    'r' => '\r',
+                    'e' => '\u001B',
+                    'N' => '\u0085',
+                    '_' => '\u00A0',
+                    'L' => '\u2028',
+                    'P' => '\u2029',
+                    ' ' or '"' or '/' or '\\' => escape,
+                    _ => throw new FormatException($"Invalid escape '\\{escape}' in a double-quoted scalar")
+                });
+            }
+
+            return text.ToString();
+        }
+
+        /// <summary>
+        /// Reads a block scalar: the lines after its header without their common indentation, kept (<c>|</c>) or
+        /// folded (<c>&gt;</c>), with the final line breaks stripped (<c>-</c>), kept (<c>+</c>) or clipped to one.
+        /// </summary>
+        private static string ReadBlock(string text, out YamlScalarStyle style)
+        {
+            style = text[0] == '>' ? YamlScalarStyle.Folded : YamlScalarStyle.Literal;
+
+            var newline = text.IndexOf('\n');
+            var header = newline < 0 ? text : text[..newline];
+            var chomping = header.Skip(1).TakeWhile(c => c is '-' or '+' or (>= '0' and <= '9')).FirstOrDefault(c => c is '-' or '+');
+            var lines = newline < 0 ? Array.Empty<string>() : text[(newline + 1)..].Split('\n').Select(l => l.TrimEnd('\r')).ToArray();
+
+            var last = Array.FindLastIndex(lines, l => l.Trim().Length > 0);
+            var indent = last < 0 ? 0 : lines.First(l => l.Trim().Length > 0).TakeWhile(c => c == ' ').Count();
+            var content = lines.Take(last + 1)
+                .Select(l => l.Trim().Length == 0
+                    ? (l.Length > indent ? l[indent..] : string.Empty)
+                    : l[Math.Min(indent, l.TakeWhile(c => c == ' ').Count())..])
+                .ToList();
+
+            var body = style == YamlScalarStyle.Folded ? FoldBlock(content) : string.Join("\n", content);
+            return chomping switch
+            {
+                '-' => body,
+                '+' => body + new string('\n', lines.Length - last),
+                _ => last < 0 ? body : body + "\n"
+            };
+        }
+
+        /// <summary>
+        /// Folds the lines of a folded block scalar: a line break between two lines that are not more indented
+        /// becomes a space unless empty lines follow it, and more indented lines keep their line breaks.
+        /// </summary>
+        private static string FoldBlock(List<string> lines)
+        {
+            var text = new StringBuilder();
+            var breaks = 0;
+            bool? previousNormal = null;
+            foreach (var line in lines)
+            {
+                if (line.Length == 0)
+                {
+                    breaks++;
+                    continue;
+                }
+
+                var normal = line[0] is not (' ' or '\t');
+                if (previousNormal == null)
+                {
+                    text.Append('\n', breaks);
+                }
+                else if (previousNormal.Value && normal)
+                {
+                    text.Append(breaks > 0 ? new string('\n', breaks) : " ");
+                }
+                else
+                {
+                    text.Append('\n', breaks + 1);
+                }
+
+                text.Append(line);
+                breaks = 0;
+                previousNormal = normal;
+            }
+
+            return text.ToString();
+        }
+    }
+}
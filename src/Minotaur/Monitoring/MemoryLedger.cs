@@ -0,0 +1,97 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+
+namespace Minotaur.Monitoring;
+
+/// <summary>
+/// The bytes allocated per category while building an object, for its <see cref="MemoryReport"/>. Allocating code
+/// paths call <see cref="Mark"/> before and <see cref="Record"/> after the allocations they account for; both are
+/// conditional on <see cref="Symbol"/>, so without memory accounting the calls, and the counter reads in them, are
+/// compiled out of the callers.
+/// </summary>
+internal sealed class MemoryLedger
+{
+    /// <summary>
+    /// The compilation symbol that enables memory accounting.
+    /// </summary>
+    public const string Symbol = "MINOTAUR_MEMORY_ACCOUNTING";
+
+    private readonly Dictionary<string, long> _bytes = new();
+
+    /// <summary>
+    /// Gets a value indicating whether the library was built with memory accounting.
+    /// </summary>
+#if MINOTAUR_MEMORY_ACCOUNTING
+    public static bool IsEnabled => true;
+#else
+    public static bool IsEnabled => false;
+#endif
+
+    /// <summary>
+    /// Reads the allocation counter of the current thread.
+    /// </summary>
+    /// <param name="mark">Receives the bytes the thread has allocated so far.</param>
+    [Conditional(Symbol)]
+    public static void Mark(ref long mark)
+    {
+        mark = GC.GetAllocatedBytesForCurrentThread();
+    }
+
+    /// <summary>
+    /// Adds the bytes the current thread allocated since a mark to a category, creating the ledger if needed.
+    /// </summary>
+    /// <param name="ledger">The ledger.</param>
+    /// <param name="category">The category.</param>
+    /// <param name="mark">The mark taken before the allocations.</param>
+    [Conditional(Symbol)]
+    public static void Record(ref MemoryLedger? ledger, string category, long mark)
+    {
+        // The counter is read before the ledger allocates anything itself.
+        var bytes = GC.GetAllocatedBytesForCurrentThread() - mark;
+        (ledger ??= new MemoryLedger()).Add(category, bytes);
+    }
+
+    /// <summary>
+    /// Gets the bytes recorded for a category of a ledger.
+    /// </summary>
+    /// <param name="ledger">The ledger, or null if nothing was recorded.</param>
+    /// <param name="category">The category.</param>
+    /// <returns>The bytes, or zero.</returns>
+    public static long Get(MemoryLedger? ledger, string category)
+    {
+        if (ledger == null)
+        {
+            return 0;
+        }
+
+        lock (ledger._bytes)
+        {
+            return ledger._bytes.GetValueOrDefault(category);
+        }
+    }
+
+    private void Add(string category, long bytes)
+    {
+        // A compiled grammar's lexer can be built by whichever thread first parses with it.
+        lock (_bytes)
+        {
+            _bytes[category] = _bytes.GetValueOrDefault(category) + bytes;
+        }
+    }
+}
@@ -0,0 +1,194 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text;
+
+namespace Minotaur.Monitoring;
+
+/// <summary>
+/// An object that can report the memory it holds.
+/// </summary>
+public interface IMemoryReporting
+{
+    /// <summary>
+    /// Gets a breakdown of the memory the object holds.
+    /// </summary>
+    /// <returns>The memory report.</returns>
+    MemoryReport GetMemoryReport();
+}
+
+/// <summary>
+/// One part of a <see cref="MemoryReport"/>.
+/// </summary>
+/// <param name="Category">The part, one of the <see cref="MemoryCategories"/>.</param>
+/// <param name="Count">The number of items in the part, such as rules, nodes or tokens.</param>
+/// <param name="Bytes">The bytes the code that built the part allocated, or zero if it was not accounted.</param>
+public sealed record MemoryReportEntry(string Category, long Count, long Bytes);
+
+/// <summary>
+/// The categories of memory reports.
+/// </summary>
+public static class MemoryCategories
+{
+    /// <summary>
+    /// The production rules of a grammar model and their alternatives.
+    /// </summary>
+    public const string ProductionRules = "production rules";
+
+    /// <summary>
+    /// The token patterns of a grammar model.
+    /// </summary>
+    public const string TokenPatterns = "token patterns";
+
+    /// <summary>
+    /// The documentation and deprecation annotations of a grammar model.
+    /// </summary>
+    public const string Annotations = "annotations";
+
+    /// <summary>
+    /// The header entries of a grammar model.
+    /// </summary>
+    public const string Metadata = "metadata";
+
+    /// <summary>
+    /// The rules of a compiled grammar.
+    /// </summary>
+    public const string Rules = "rules";
+
+    /// <summary>
+    /// The alternatives of a compiled grammar with their symbols; the count is of parser item states, one per
+    /// position in each alternative.
+    /// </summary>
+    public const string Alternatives = "alternatives";
+
+    /// <summary>
+    /// The compiled patterns of a grammar's lexer, built when the grammar is first used to tokenize.
+    /// </summary>
+    public const string Lexer = "lexer";
+
+    /// <summary>
+    /// The tokens of a parse, including the lexer's scratch allocations while reading them.
+    /// </summary>
+    public const string Tokens = "tokens";
+
+    /// <summary>
+    /// The shared packed parse forest of a parse.
+    /// </summary>
+    public const string Forest = "forest";
+
+    /// <summary>
+    /// The parse tree's nodes with their metadata and spans.
+    /// </summary>
+    public const string TreeNodes = "tree nodes";
+
+    /// <summary>
+    /// The child lists of the parse tree's nodes.
+    /// </summary>
+    public const string ChildLists = "child lists";
+
+    /// <summary>
+    /// The strings of a <see cref="Core.SharedInterner"/>; bytes are the size of the string objects it holds.
+    /// </summary>
+    public const string InternedStrings = "interned strings";
+
+    /// <summary>
+    /// The documents a daemon keeps open for an editor.
+    /// </summary>
+    public const string OpenDocuments = "open documents";
+
+    /// <summary>
+    /// The computed values a workspace's query database keeps for reuse.
+    /// </summary>
+    public const string CachedQueries = "cached queries";
+}
+
+/// <summary>
+/// A breakdown of the memory held by a grammar, a compiled grammar or a parse result. Counts are always given;
+/// bytes are measured with the runtime's allocation counter around the code paths that build each part, and are
+/// only available when the library is built with memory accounting (the <c>MinotaurMemoryAccounting</c> build
+/// property, on by default). Parts built before accounting could see them, such as a grammar model assembled in
+/// code or the nodes an incremental parse reused, report no bytes.
+/// </summary>
+public sealed class MemoryReport
+{
+    /// <summary>
+    /// Initializes a new instance of the MemoryReport class.
+    /// </summary>
+    /// <param name="subject">What the report describes.</param>
+    /// <param name="entries">The parts of the report.</param>
+    public MemoryReport(string subject, IReadOnlyList<MemoryReportEntry> entries)
+    {
+        ArgumentNullException.ThrowIfNull(subject);
+        ArgumentNullException.ThrowIfNull(entries);
+
+        Subject = subject;
+        Entries = entries;
+    }
+
+    /// <summary>
+    /// Gets a value indicating whether the library was built with memory accounting, so reports give bytes.
+    /// </summary>
+    public static bool IsAccountingEnabled => MemoryLedger.IsEnabled;
+
+    /// <summary>
+    /// Gets what the report describes.
+    /// </summary>
+    public string Subject { get; }
+
+    /// <summary>
+    /// Gets the parts of the report.
+    /// </summary>
+    public IReadOnlyList<MemoryReportEntry> Entries { get; }
+
+    /// <summary>
+    /// Gets the bytes of all parts.
+    /// </summary>
+    public long TotalBytes => Entries.Sum(e => e.Bytes);
+
+    /// <summary>
+    /// Gets a part of the report.
+    /// </summary>
+    /// <param name="category">The category of the part.</param>
+    /// <returns>The part, or null if the report has none of the category.</returns>
+    public MemoryReportEntry? Get(string category)
+    {
+        return Entries.FirstOrDefault(e => e.Category == category);
+    }
+
+    /// <summary>
+    /// Returns the report as a table with a line per part and a total.
+    /// </summary>
+    /// <returns>The formatted report.</returns>
+    public override string ToString()
+    {
+        var text = new StringBuilder();
+        text.AppendLine(CultureInfo.InvariantCulture, $"{Subject}:");
+        foreach (var entry in Entries)
+        {
+            text.AppendLine(CultureInfo.InvariantCulture, $"  {entry.Category,-18} {entry.Count,10:N0} {FormatBytes(entry.Bytes),12}");
+        }
+
+        text.Append(CultureInfo.InvariantCulture, $"  {"total",-18} {"",10} {FormatBytes(TotalBytes),12}");
+        return text.ToString();
+    }
+
+    private static string FormatBytes(long bytes)
+    {
+        return IsAccountingEnabled ? bytes.ToString("N0", CultureInfo.InvariantCulture) + " B" : "n/a";
+    }
+}
@@ -0,0 +1,78 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Information passed to a <see cref="SemanticAction"/> about the reduction being evaluated.
+/// One context is reused for every reduction of an evaluation; actions must not keep it.
+/// </summary>
+public sealed class ActionContext
+{
+    private readonly LineIndex _lineIndex;
+    private readonly string? _sourceFile;
+
+    internal ActionContext(LineIndex lineIndex, string? sourceFile, IDictionary<string, object?> state)
+    {
+        _lineIndex = lineIndex;
+        _sourceFile = sourceFile;
+        State = state;
+    }
+
+    /// <summary>
+    /// Gets the source text being evaluated.
+    /// </summary>
+    public string Input => _lineIndex.Text;
+
+    /// <summary>
+    /// Gets the alternative being reduced.
+    /// </summary>
+    public CompiledAlternative Alternative { get; internal set; } = null!;
+
+    /// <summary>
+    /// Gets the rule being reduced.
+    /// </summary>
+    public CompiledRule Rule => Alternative.Rule;
+
+    /// <summary>
+    /// Gets the range of source text covered by the reduction.
+    /// </summary>
+    public TextRange Span { get; internal set; }
+
+    /// <summary>
+    /// Gets the source text covered by the reduction.
+    /// </summary>
+    public string Text => Input.Substring(Span.Start, Span.Length);
+
+    /// <summary>
+    /// Gets the source position of the reduction.
+    /// </summary>
+    public SourcePosition Location => _lineIndex.GetPosition(Span.Start, Span.Length, _sourceFile);
+
+    /// <summary>
+    /// Gets a value indicating whether the reduction belongs to one of several derivations of an ambiguous region,
+    /// whose value may be discarded.
+    /// </summary>
+    public bool IsSpeculative { get; internal set; }
+
+    /// <summary>
+    /// Gets host state shared by all actions of an evaluation, such as variable bindings of an interpreter.
+    /// </summary>
+    public IDictionary<string, object?> State { get; }
+}
@@ -0,0 +1,57 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The result of parsing with semantic actions: the value computed by the actions and the underlying parse.
+/// </summary>
+public class ActionParseResult
+{
+    /// <summary>
+    /// Gets the value computed for the start rule, or null if parsing or evaluation failed.
+    /// </summary>
+    public object? Value { get; init; }
+
+    /// <summary>
+    /// Gets the underlying parse result, including the forest and tree.
+    /// </summary>
+    public ParseResult Parse { get; init; } = new();
+
+    /// <summary>
+    /// Gets the parse tree.
+    /// </summary>
+    public CognitiveGraphNode? Tree => Parse.Tree;
+
+    /// <summary>
+    /// Gets the host state shared by the actions of the evaluation.
+    /// </summary>
+    public IDictionary<string, object?> State { get; init; } = new Dictionary<string, object?>();
+
+    /// <summary>
+    /// Gets the parse diagnostics followed by any semantic action failure.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics { get; init; } = Array.Empty<Diagnostic>();
+
+    /// <summary>
+    /// Gets a value indicating whether parsing and every semantic action succeeded.
+    /// </summary>
+    public bool IsSuccess => Parse.Tree != null && !Diagnostics.Any(d => d.Severity == DiagnosticSeverity.Error);
+}
@@ -0,0 +1,137 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A semantic action computing the value of a reduction from the values of its symbols.
+/// </summary>
+/// <param name="context">Information about the reduction being evaluated.</param>
+/// <param name="values">One value per symbol of the alternative: token text for terminals, the computed value for rules.</param>
+/// <returns>The value of the reduction.</returns>
+public delegate object? SemanticAction(ActionContext context, IReadOnlyList<object?> values);
+
+/// <summary>
+/// Host-registered semantic actions and their bindings to grammar alternatives.
+/// </summary>
+/// <remarks>
+/// Alternatives are bound either in the grammar file with an <c>=&gt; { name }</c> suffix or in code with
+/// <see cref="Bind"/>; code bindings take precedence. Alternatives without a binding pass through the value
+/// of their only symbol, or the list of all symbol values.
+/// <para>
+/// The generalized parser evaluates every derivation of an ambiguous region before <see cref="ResolveAmbiguity"/>
+/// picks one, so side-effecting actions may run for derivations that are later discarded. Actions registered as
+/// pure must compute their value from their arguments only; their results are shared between derivations, and
+/// impure actions can check <see cref="ActionContext.IsSpeculative"/> to defer side effects.
+/// </para>
+/// </remarks>
+public class ActionRegistry
+{
+    private readonly Dictionary<string, RegisteredAction> _actions = new();
+    private readonly Dictionary<(string Rule, int Alternative), string> _bindings = new();
+
+    /// <summary>
+    /// Gets or sets the function choosing the value of an ambiguous region from the values of its derivations,
+    /// in forest order. If null, the first derivation's value is used.
+    /// </summary>
+    public Func<ActionContext, IReadOnlyList<object?>, object?>? ResolveAmbiguity { get; set; }
+
+    /// <summary>
+    /// Gets the names of the registered actions.
+    /// </summary>
+    public IEnumerable<string> ActionNames => _actions.Keys;
+
+    /// <summary>
+    /// Registers a semantic action.
+    /// </summary>
+    /// <param name="name">The action name referenced by bindings.</param>
+    /// <param name="action">The action.</param>
+    /// <param name="pure">True if the action has no side effects and its value depends only on its arguments.</param>
+    /// <returns>This registry, for chaining.</returns>
+    public ActionRegistry Register(string name, SemanticAction action, bool pure = false)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(name);
+        ArgumentNullException.ThrowIfNull(action);
+
+        _actions[name] = new RegisteredAction(name, action, pure);
+        return this;
+    }
+
+    /// <summary>
+    /// Binds an alternative to a registered action, overriding any binding in the grammar.
+    /// </summary>
+    /// <param name="rule">The rule name.</param>
+    /// <param name="alternative">The zero-based alternative index within the rule.</param>
+    /// <param name="actionName">The action name.</param>
+    /// <returns>This registry, for chaining.</returns>
+    public ActionRegistry Bind(string rule, int alternative, string actionName)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(rule);
+        ArgumentException.ThrowIfNullOrEmpty(actionName);
+        ArgumentOutOfRangeException.ThrowIfNegative(alternative);
+
+        _bindings[(rule, alternative)] = actionName;
+        return this;
+    }
+
+    /// <summary>
+    /// Gets the name of the action bound to an alternative.
+    /// </summary>
+    /// <param name="alternative">The alternative.</param>
+    /// <returns>The action name, or null if the alternative has no binding.</returns>
+    public string? GetBinding(CompiledAlternative alternative)
+    {
+        ArgumentNullException.ThrowIfNull(alternative);
+        return _bindings.GetValueOrDefault((alternative.Rule.Name, alternative.Index)) ?? alternative.Action;
+    }
+
+    /// <summary>
+    /// Checks that every binding in the grammar and registry refers to a registered action and an existing alternative.
+    /// </summary>
+    /// <param name="grammar">The grammar to check against.</param>
+    /// <exception cref="InvalidOperationException">A binding cannot be resolved.</exception>
+    public void Validate(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        foreach (var ((rule, index), _) in _bindings)
+        {
+            if (grammar.GetRule(rule) is not { } compiled || index >= compiled.Alternatives.Count)
+            {
+                throw new InvalidOperationException($"Action binding refers to unknown alternative {index} of rule '{rule}'");
+            }
+        }
+
+        foreach (var alternative in grammar.Rules.SelectMany(r => r.Alternatives))
+        {
+            var name = GetBinding(alternative);
+            if (name != null && !_actions.ContainsKey(name))
+            {
+                throw new InvalidOperationException(
+                    $"Action '{name}' bound to alternative {alternative.Index} of rule '{alternative.Rule.Name}' is not registered");
+            }
+        }
+    }
+
+    internal RegisteredAction? Resolve(CompiledAlternative alternative)
+    {
+        var name = GetBinding(alternative);
+        return name != null ? _actions[name] : null;
+    }
+
+    internal sealed record RegisteredAction(string Name, SemanticAction Action, bool IsPure);
+}
@@ -0,0 +1,591 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Unparser;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Builds parse trees programmatically, for code generators that construct programs rather than splice text, and
+/// prints them with synthesized whitespace.
+/// </summary>
+/// <remarks>
+/// <para>
+/// Nodes are built with <see cref="Node"/>: children are added in order with <see cref="AstNodeBuilder.Child(CognitiveGraphNode)"/>
+/// or by the name of the symbol they stand for with <see cref="AstNodeBuilder.Field(string, CognitiveGraphNode)"/>,
+/// and nothing is checked until <see cref="AstNodeBuilder.Finish"/>, which picks the first alternative of the rule
+/// the children fit. Literal tokens of the alternative, like <c>"fn"</c> or <c>"("</c>, are added unless given, and a
+/// child of a rule reached from the expected symbol through alternatives of a single symbol, such as a
+/// <c>&lt;path&gt;</c> where an <c>&lt;expr&gt;</c> is expected, is wrapped in the nodes of those rules. When no
+/// alternative fits, an <see cref="AstValidationException"/> names the closest one.
+/// </para>
+/// <para>
+/// The result is the concrete tree the parser builds for the printed text, so <see cref="Print"/> followed by a
+/// parse gives an equal tree as long as the grammar does not parse the text another way.
+/// </para>
+/// </remarks>
+public sealed class AstBuilder
+{
+    private readonly CompiledGrammar _grammar;
+    private readonly GrammarLexer _lexer;
+    private readonly HashSet<string> _terminals;
+
+    /// <summary>
+    /// Initializes a new instance of the AstBuilder class.
+    /// </summary>
+    /// <param name="grammar">The grammar the built trees follow.</param>
+    public AstBuilder(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        _grammar = grammar;
+        _lexer = new GrammarLexer(grammar);
+        _terminals = grammar.GetTerminals().Select(t => t.Key).ToHashSet(StringComparer.Ordinal);
+    }
+
+    /// <summary>
+    /// Gets the grammar the built trees follow.
+    /// </summary>
+    public CompiledGrammar Grammar => _grammar;
+
+    /// <summary>
+    /// Starts a node of a rule.
+    /// </summary>
+    /// <param name="rule">The rule name.</param>
+    /// <returns>The builder of the node.</returns>
+    /// <exception cref="ArgumentException">The grammar has no such rule.</exception>
+    public AstNodeBuilder Node(string rule)
+    {
+        ArgumentNullException.ThrowIfNull(rule);
+
+        return new AstNodeBuilder(this, _grammar.GetRule(rule)
+            ?? throw new ArgumentException($"Grammar '{_grammar.Name}' has no rule <{rule}>", nameof(rule)));
+    }
+
+    /// <summary>
+    /// Creates a token of a named terminal, such as <c>Token("NUMBER", "42")</c>.
+    /// </summary>
+    /// <param name="kind">The token name, or the key of a literal or inline pattern.</param>
+    /// <param name="text">The token text, which must lex as exactly one token of the kind.</param>
+    /// <returns>The terminal node.</returns>
+    /// <exception cref="ArgumentException">The grammar has no such terminal, or the text does not lex as it.</exception>
+    public TerminalNode Token(string kind, string text)
+    {
+        ArgumentNullException.ThrowIfNull(kind);
+        ArgumentNullException.ThrowIfNull(text);
+
+        if (!_terminals.Contains(kind))
+        {
+            throw new ArgumentException($"Grammar '{_grammar.Name}' has no terminal {kind}", nameof(kind));
+        }
+
+        var lexed = _lexer.Tokenize(text);
+        if (lexed.Diagnostics.Count > 0 || lexed.Tokens.Count != 1 || lexed.Tokens[0].Kind != kind)
+        {
+            var actual = string.Join(" ", lexed.Tokens.Select(t => t.Kind));
+            throw new ArgumentException($"'{text}' does not lex as one {kind} token but as {(actual.Length > 0 ? actual : "nothing")}", nameof(text));
+        }
+
+        return new TerminalNode(text, kind);
+    }
+
+    /// <summary>
+    /// Creates an <c>IDENTIFIER</c> token.
+    /// </summary>
+    /// <param name="name">The identifier.</param>
+    /// <returns>The terminal node.</returns>
+    public TerminalNode Identifier(string name)
+    {
+        return Token("IDENTIFIER", name);
+    }
+
+    /// <summary>
+    /// Builds a list rule, such as <c>&lt;statements&gt; ::= &lt;statement&gt; | &lt;statements&gt; &lt;statement&gt;</c>,
+    /// from its items by nesting nodes of its left- or right-recursive alternative; separators are added.
+    /// </summary>
+    /// <param name="rule">The list rule.</param>
+    /// <param name="items">The items, at least one unless the rule has an empty alternative.</param>
+    /// <returns>The outermost node of the list.</returns>
+    /// <exception cref="ArgumentException">The rule is not recursive at either end.</exception>
+    /// <exception cref="AstValidationException">An item fits no alternative.</exception>
+    public NonTerminalNode List(string rule, IEnumerable<CognitiveGraphNode> items)
+    {
+        ArgumentNullException.ThrowIfNull(items);
+
+        var compiled = Node(rule).Rule;
+        var list = items.ToList();
+        bool Recursive(CompiledAlternative a, int at) => a.Symbols.Count > 1 && a.Symbols[at] is { Kind: GrammarSymbolKind.Rule } s && s.Name == rule;
+        var left = compiled.Alternatives.Any(a => Recursive(a, 0));
+        if (!left && !compiled.Alternatives.Any(a => Recursive(a, a.Symbols.Count - 1)))
+        {
+            throw new ArgumentException($"<{rule}> is not a left- or right-recursive list", nameof(rule));
+        }
+
+        if (list.Count == 0)
+        {
+            return Node(rule).Finish();
+        }
+
+        if (left)
+        {
+            var node = Node(rule).Child(list[0]).Finish();
+            foreach (var item in list.Skip(1))
+            {
+                node = Node(rule).Child(node).Child(item).Finish();
+            }
+
+            return node;
+        }
+
+        var tail = Node(rule).Child(list[^1]).Finish();
+        for (var i = list.Count - 2; i >= 0; i--)
+        {
+            tail = Node(rule).Child(list[i]).Child(tail).Finish();
+        }
+
+        return tail;
+    }
+
+    /// <summary>
+    /// Prints a tree with synthesized whitespace: one space between tokens except next to brackets and before
+    /// separators, a space wherever the tokens would otherwise lex as others, and line breaks with indentation
+    /// after <c>;</c> and inside <c>{ }</c> blocks.
+    /// </summary>
+    /// <param name="tree">The tree.</param>
+    /// <returns>The printed source.</returns>
+    public string Print(CognitiveGraphNode tree)
+    {
+        ArgumentNullException.ThrowIfNull(tree);
+
+        using var unparser = new GraphUnparser(new UnparseConfiguration { LineEnding = "\n" });
+        unparser.RegisterStrategy("terminal", new SynthesizedTriviaUnparseStrategy(_lexer, _grammar.EditorInfo.Brackets));
+        return unparser.Unparse(tree).TrimEnd('\n') + "\n";
+    }
+
+    // A chain of alternatives of a single symbol leading from a rule to a node's rule or token, outermost first.
+    internal IReadOnlyList<CompiledAlternative>? FindChain(CompiledRule from, CognitiveGraphNode node)
+    {
+        var target = node switch
+        {
+            NonTerminalNode rule => rule.RuleName,
+            TerminalNode terminal => terminal.TokenType,
+            _ => null
+        };
+
+        if (target == null)
+        {
+            return null;
+        }
+
+        var previous = new Dictionary<int, CompiledAlternative?> { [from.Index] = null };
+        var pending = new Queue<CompiledRule>();
+        pending.Enqueue(from);
+        while (pending.Count > 0)
+        {
+            var rule = pending.Dequeue();
+            foreach (var alternative in rule.Alternatives.Where(a => a.Symbols.Count == 1))
+            {
+                var symbol = alternative.Symbols[0];
+                var name = symbol.Kind == GrammarSymbolKind.Rule ? symbol.Name : symbol.Key;
+                if (name == target)
+                {
+                    var chain = new List<CompiledAlternative> { alternative };
+                    for (var step = previous[rule.Index]; step != null; step = previous[step.Rule.Index])
+                    {
+                        chain.Insert(0, step);
+                    }
+
+                    return chain;
+                }
+
+                if (symbol.Kind == GrammarSymbolKind.Rule && alternative.RuleIndices[0] is var index && !previous.ContainsKey(index))
+                {
+                    previous[index] = alternative;
+                    pending.Enqueue(_grammar.Rules[index]);
+                }
+            }
+        }
+
+        return null;
+    }
+
+    private sealed class SynthesizedTriviaUnparseStrategy : UnparseStrategyBase
+    {
+        private readonly TokenSpacing _spacing;
+        private string? _previous;
+        private bool _atLineStart = true;
+
+        public SynthesizedTriviaUnparseStrategy(GrammarLexer lexer, IReadOnlyList<EditorPair> brackets)
+        {
+            _spacing = new TokenSpacing(lexer, brackets);
+        }
+
+        public override void UnparseNode(CognitiveGraphNode node, UnparseContext context)
+        {
+            var text = GetNodeText(node);
+            if (string.IsNullOrEmpty(text))
+            {
+                return;
+            }
+
+            if (text == "}")
+            {
+                if (!_atLineStart)
+                {
+                    context.WriteLine();
+                }
+
+                context.DecreaseIndent();
+            }
+            else if (_previous != null && !_atLineStart && _spacing.NeedsSpace(_previous, text))
+            {
+                context.Write(" ");
+            }
+
+            context.Write(text);
+            _previous = text;
+            _atLineStart = false;
+
+            if (text == "{")
+            {
+                context.IncreaseIndent();
+            }
+
+            if (text is "{" or "}" or ";")
+            {
+                context.WriteLine();
+                _atLineStart = true;
+            }
+        }
+    }
+}
+
+/// <summary>
+/// Builds one node of an <see cref="AstBuilder"/>. Children are collected as given and only checked against the
+/// rule's alternatives by <see cref="Finish"/>, so a node can be built up in any order.
+/// </summary>
+public sealed class AstNodeBuilder
+{
+    private readonly AstBuilder _builder;
+    private readonly List<CognitiveGraphNode> _children = new();
+    private readonly List<(string Name, CognitiveGraphNode Node)> _fields = new();
+
+    internal AstNodeBuilder(AstBuilder builder, CompiledRule rule)
+    {
+        _builder = builder;
+        Rule = rule;
+    }
+
+    /// <summary>
+    /// Gets the rule of the node.
+    /// </summary>
+    public CompiledRule Rule { get; }
+
+    /// <summary>
+    /// Adds the next child. Literal tokens may be left out; they are added by <see cref="Finish"/>.
+    /// </summary>
+    /// <param name="child">The child node.</param>
+    /// <returns>This builder.</returns>
+    public AstNodeBuilder Child(CognitiveGraphNode child)
+    {
+        ArgumentNullException.ThrowIfNull(child);
+
+        _children.Add(child);
+        return this;
+    }
+
+    /// <summary>
+    /// Finishes a node and adds it as the next child.
+    /// </summary>
+    /// <param name="child">The builder of the child node.</param>
+    /// <returns>This builder.</returns>
+    public AstNodeBuilder Child(AstNodeBuilder child)
+    {
+        ArgumentNullException.ThrowIfNull(child);
+
+        return Child(child.Finish());
+    }
+
+    /// <summary>
+    /// Adds a child for the next symbol of the alternative named <paramref name="name"/>, such as
+    /// <c>Field("IDENTIFIER", name)</c> or <c>Field("block", body)</c>, wherever it is in the alternative.
+    /// </summary>
+    /// <param name="name">The name of a rule or token of the alternative.</param>
+    /// <param name="child">The child node.</param>
+    /// <returns>This builder.</returns>
+    public AstNodeBuilder Field(string name, CognitiveGraphNode child)
+    {
+        ArgumentNullException.ThrowIfNull(name);
+        ArgumentNullException.ThrowIfNull(child);
+
+        _fields.Add((name, child));
+        return this;
+    }
+
+    /// <summary>
+    /// Finishes a node and adds it as a child for the next symbol named <paramref name="name"/>.
+    /// </summary>
+    /// <param name="name">The name of a rule or token of the alternative.</param>
+    /// <param name="child">The builder of the child node.</param>
+    /// <returns>This builder.</returns>
+    public AstNodeBuilder Field(string name, AstNodeBuilder child)
+    {
+        ArgumentNullException.ThrowIfNull(child);
+
+        return Field(name, child.Finish());
+    }
+
+    /// <summary>
+    /// Validates the children against the rule's alternatives and builds the node of the first alternative they
+    /// fit.
+    /// </summary>
+    /// <returns>The node.</returns>
+    /// <exception cref="AstValidationException">The children fit no alternative.</exception>
+    public NonTerminalNode Finish()
+    {
+        foreach (var alternative in Rule.Alternatives)
+        {
+            var children = Match(alternative);
+            if (children != null)
+            {
+                var node = new NonTerminalNode(Rule.Name, alternative.Index);
+                foreach (var child in children)
+                {
+                    node.AddChild(child);
+                }
+
+                return node;
+            }
+        }
+
+        throw Closest();
+    }
+
+    private List<CognitiveGraphNode>? Match(CompiledAlternative alternative)
+    {
+        var symbols = alternative.Symbols;
+        var assigned = new CognitiveGraphNode?[symbols.Count];
+        foreach (var (name, node) in _fields)
+        {
+            var at = Enumerable.Range(0, symbols.Count).FirstOrDefault(i => assigned[i] == null && symbols[i].Kind != GrammarSymbolKind.Literal && symbols[i].Name == name, -1);
+            if (at < 0)
+            {
+                return null;
+            }
+
+            assigned[at] = node;
+        }
+
+        var children = new List<CognitiveGraphNode>();
+        var next = 0;
+        for (var i = 0; i < symbols.Count; i++)
+        {
+            var symbol = symbols[i];
+            if (assigned[i] != null)
+            {
+                var fitted = Fit(symbol, assigned[i]!);
+                if (fitted == null)
+                {
+                    return null;
+                }
+
+                children.Add(fitted);
+            }
+            else if (symbol.Kind == GrammarSymbolKind.Literal)
+            {
+                if (next < _children.Count && _children[next] is TerminalNode literal && literal.TokenType == symbol.Key)
+                {
+                    children.Add(literal);
+                    next++;
+                }
+                else
+                {
+                    children.Add(new TerminalNode(symbol.Name, symbol.Key));
+                }
+            }
+            else
+            {
+                var fitted = next < _children.Count ? Fit(symbol, _children[next]) : null;
+                if (fitted == null)
+                {
+                    return null;
+                }
+
+                children.Add(fitted);
+                next++;
+            }
+        }
+
+        return next == _children.Count ? children : null;
+    }
+
+    private CognitiveGraphNode? Fit(GrammarSymbol symbol, CognitiveGraphNode node)
+    {
+        switch (node)
+        {
+            case NonTerminalNode rule when symbol.Kind == GrammarSymbolKind.Rule && rule.RuleName == symbol.Name:
+            case TerminalNode terminal when symbol.IsTerminal && terminal.TokenType == symbol.Key:
+                return node;
+        }
+
+        var expected = symbol.Kind == GrammarSymbolKind.Rule ? _builder.Grammar.GetRule(symbol.Name) : null;
+        var chain = expected != null ? _builder.FindChain(expected, node) : null;
+        if (chain == null)
+        {
+            return null;
+        }
+
+        var wrapped = node;
+        for (var i = chain.Count - 1; i >= 0; i--)
+        {
+            var wrapper = new NonTerminalNode(chain[i].Rule.Name, chain[i].Index);
+            wrapper.AddChild(wrapped);
+            wrapped = wrapper;
+        }
+
+        return wrapped;
+    }
+
+    // The alternative sharing the longest common subsequence with the given children, counting children that fit
+    // a symbol, with what is missing from the children and what the alternative has no place for.
+    private AstValidationException Closest()
+    {
+        var given = _children.Select(c => (Name: (string?)null, Node: c)).Concat(_fields.Select(f => ((string?)f.Name, f.Node))).ToList();
+        var described = string.Join(" ", given.Select(g => Describe(g.Node)));
+
+        (CompiledAlternative Alternative, List<string> Missing, List<string> Unexpected)? best = null;
+        foreach (var alternative in Rule.Alternatives)
+        {
+            var symbols = alternative.Symbols.Where(s => s.Kind != GrammarSymbolKind.Literal).ToList();
+            var items = given.Where(g => !(g.Name == null && g.Node is TerminalNode t && t.TokenType.StartsWith('"'))).ToList();
+            var lengths = new int[symbols.Count + 1, items.Count + 1];
+            for (var i = symbols.Count - 1; i >= 0; i--)
+            {
+                for (var j = items.Count - 1; j >= 0; j--)
+                {
+                    lengths[i, j] = Fits(symbols[i], items[j])
+                        ? lengths[i + 1, j + 1] + 1
+                        : Math.Max(lengths[i + 1, j], lengths[i, j + 1]);
+                }
+            }
+
+            var missing = new List<string>();
+            var unexpected = new List<string>();
+            int s = 0, k = 0;
+            while (s < symbols.Count || k < items.Count)
+            {
+                if (s < symbols.Count && k < items.Count && Fits(symbols[s], items[k]))
+                {
+                    s++;
+                    k++;
+                }
+                else if (k < items.Count && (s == symbols.Count || lengths[s, k + 1] >= lengths[s + 1, k]))
+                {
+                    unexpected.Add(Describe(items[k++].Node));
+                }
+                else
+                {
+                    missing.Add(symbols[s++].ToString());
+                }
+            }
+
+            if (best == null || missing.Count + unexpected.Count < best.Value.Missing.Count + best.Value.Unexpected.Count)
+            {
+                best = (alternative, missing, unexpected);
+            }
+        }
+
+        return new AstValidationException(Rule.Name, described, best?.Alternative, best?.Missing ?? new List<string>(), best?.Unexpected ?? new List<string>());
+    }
+
+    private bool Fits(GrammarSymbol symbol, (string? Name, CognitiveGraphNode Node) item)
+    {
+        return (item.Name == null || item.Name == symbol.Name) && Fit(symbol, item.Node) != null;
+    }
+
+    private static string Describe(CognitiveGraphNode node)
+    {
+        return node switch
+        {
+            NonTerminalNode rule => $"<{rule.RuleName}>",
+            TerminalNode { TokenType: var kind } when kind.StartsWith('"') => kind,
+            TerminalNode terminal => $"<{terminal.TokenType}>",
+            _ => node.NodeType
+        };
+    }
+}
+
+/// <summary>
+/// Thrown when the children of a node built with an <see cref="AstBuilder"/> fit no alternative of its rule.
+/// </summary>
+public class AstValidationException : ArgumentException
+{
+    /// <summary>
+    /// Initializes a new instance of the AstValidationException class.
+    /// </summary>
+    /// <param name="rule">The rule of the node.</param>
+    /// <param name="children">The children as written in the grammar notation.</param>
+    /// <param name="closest">The alternative closest to the children, if the rule has any.</param>
+    /// <param name="missing">The symbols of the closest alternative the children lack.</param>
+    /// <param name="unexpected">The children the closest alternative has no place for.</param>
+    public AstValidationException(string rule, string children, CompiledAlternative? closest, IReadOnlyList<string> missing, IReadOnlyList<string> unexpected)
+        : base(Describe(rule, children, closest, missing, unexpected))
+    {
+        Rule = rule;
+        ClosestAlternative = closest;
+        Missing = missing;
+        Unexpected = unexpected;
+    }
+
+    /// <summary>
+    /// Gets the rule of the node.
+    /// </summary>
+    public string Rule { get; }
+
+    /// <summary>
+    /// Gets the alternative closest to the children, if the rule has any.
+    /// </summary>
+    public CompiledAlternative? ClosestAlternative { get; }
+
+    /// <summary>
+    /// Gets the symbols of the closest alternative the children lack.
+    /// </summary>
+    public IReadOnlyList<string> Missing { get; }
+
+    /// <summary>
+    /// Gets the children the closest alternative has no place for.
+    /// </summary>
+    public IReadOnlyList<string> Unexpected { get; }
+
+    private static string Describe(string rule, string children, CompiledAlternative? closest, IReadOnlyList<string> missing, IReadOnlyList<string> unexpected)
+    {
+        var text = new StringBuilder($"Children ({children}) fit no alternative of <{rule}>");
+        if (closest != null)
+        {
+            text.Append($"; closest is <{rule}> ::= {closest.Text}");
+            var problems = missing.Select(m => $"missing {m}").Concat(unexpected.Select(u => $"unexpected {u}")).ToList();
+            if (problems.Count > 0)
+            {
+                text.Append($" ({string.Join(", ", problems)})");
+            }
+        }
+
+        return text.ToString();
+    }
+}
@@ -0,0 +1,1753 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Collections.Concurrent;
+using System.Security.Cryptography;
+using System.Text;
+using System.Text.RegularExpressions;
+using Minotaur.GrammarGeneration.Models;
+using Minotaur.Monitoring;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A grammar prepared for parsing: alternatives are resolved into symbols, rules are indexed
+/// and nullable rules are precomputed.
+/// </summary>
+public sealed class CompiledGrammar : IMemoryReporting
+{
+    /// <summary>
+    /// The metadata key that switches a grammar to scannerless parsing when set to "true".
+    /// </summary>
+    public const string ScannerlessKey = "Scannerless";
+
+    /// <summary>
+    /// The metadata key naming the layout rule inserted between symbols of scannerless grammars.
+    /// </summary>
+    public const string LayoutKey = "Layout";
+
+    /// <summary>
+    /// The metadata key listing, comma-separated, the rules of a scannerless grammar that get no layout inserted.
+    /// </summary>
+    public const string LexicalRulesKey = "LexicalRules";
+
+    /// <summary>
+    /// The metadata key grouping terminals into categories, written as
+    /// <c>name = terminal terminal ...; name = ...</c> with terminals in the grammar notation.
+    /// </summary>
+    public const string CategoriesKey = "Categories";
+
+    /// <summary>
+    /// The metadata key declaring contextual keywords, written as <c>keyword = rule rule ...; keyword = ...</c>.
+    /// A contextual keyword is lexed as whatever token its text would otherwise be, usually an identifier, and is
+    /// only taken as the keyword literal by the alternatives of the listed rules.
+    /// </summary>
+    public const string ContextualKeywordsKey = "ContextualKeywords";
+
+    /// <summary>
+    /// The metadata key guarding terminals by the category of the previous token, written as
+    /// <c>terminal = categories; terminal = !categories; ...</c>. A guarded terminal is only lexed after a token of
+    /// one of the categories, or, with <c>!</c>, at the start of the input or after a token of none of them, so
+    /// <c>&lt;REGEX&gt; = !operand</c> reads <c>/</c> after an operand as division.
+    /// </summary>
+    public const string TokenGuardsKey = "TokenGuards";
+
+    /// <summary>
+    /// The metadata key restricting rules to declared names, written as <c>rule = kind; rule = !kind; ...</c>. Every
+    /// alternative of a listed rule is a single terminal, and when a parse is given <see cref="ParseOptions.Symbols"/>
+    /// the rule only derives a token whose text is declared with the kind, or, with <c>!</c>, one that is not. The
+    /// kind is the rule whose nodes declare the names, so <c>type_name = typedef; variable = !typedef</c> reads
+    /// <c>(T)*x</c> as a cast exactly when a <c>&lt;typedef&gt;</c> declares <c>T</c>. See <see cref="TwoPhaseParser"/>.
+    /// </summary>
+    public const string SymbolPredicatesKey = "SymbolPredicates";
+
+    /// <summary>
+    /// The metadata key declaring entry points, written as <c>name = rule [complete|prefix]; name = ...</c>.
+    /// See <see cref="EntryPoint"/>.
+    /// </summary>
+    public const string EntryPointsKey = "EntryPoints";
+
+    /// <summary>
+    /// The metadata key declaring features that guard alternatives, e.g.
+    /// <c>optional-chaining = &lt;member&gt; ::= &lt;member&gt; "?." &lt;IDENTIFIER&gt;; async = &lt;async_block&gt;</c>.
+    /// A feature names either one alternative of a rule, written as in the rule, or the whole rule. Guarded
+    /// alternatives are recognized as usual but only reduced when their feature is enabled, so using them
+    /// without it is reported as a <see cref="Diagnostics.DiagnosticCodes.FeatureDisabled"/> error.
+    /// </summary>
+    public const string FeaturesKey = "Features";
+
+    /// <summary>
+    /// The metadata key of a regular expression finding feature pragmas in the comments of the input, e.g.
+    /// <c>/@enable ([\w, -]+)/</c>. The first group of each match lists features, separated by commas or spaces,
+    /// that the file enables; each match is read as a file-level <see cref="DirectiveSyntax.FeaturePragmaName"/>
+    /// directive.
+    /// </summary>
+    public const string FeaturePragmaKey = "FeaturePragma";
+
+    /// <summary>
+    /// The metadata key declaring directive syntaxes, e.g. <c>"// minotaur:"; "/* minotaur:" /allow .*/</c>.
+    /// Comments starting with a declared prefix are read as directives; see <see cref="DirectiveSyntax"/>.
+    /// </summary>
+    public const string DirectivesKey = "Directives";
+
+    /// <summary>
+    /// The metadata key listing symbols that are structural noise, like <c>"," "{" "}"</c>, in the grammar
+    /// notation. The AST view of a parse leaves out their nodes; a listed rule is left out with its subtree.
+    /// </summary>
+    public const string HideKey = "Hide";
+
+    /// <summary>
+    /// The metadata key listing rules, like <c>&lt;item_kind&gt; &lt;term&gt;</c>, whose nodes the AST view
+    /// replaces with their children, so trivial wrapper rules do not add a level to it.
+    /// </summary>
+    public const string InlineKey = "Inline";
+
+    /// <summary>
+    /// The metadata key declaring operator precedence, written as <c>left "+" "-"; left "*" "/"; right "^"</c> from
+    /// the loosest level to the tightest. See <see cref="GrammarPrecedence"/>.
+    /// </summary>
+    public const string PrecedenceKey = "Precedence";
+
+    /// <summary>
+    /// The metadata key listing rules, like <c>&lt;block&gt; &lt;class_body&gt;</c>, whose bodies a lazy parse
+    /// skips as balanced bracket groups and parses when their nodes are first read. Every alternative of a listed
+    /// rule must start with the same literal opening bracket and end with the same literal closing bracket. See
+    /// <see cref="ParseOptions.Lazy"/>.
+    /// </summary>
+    public const string DeferredKey = "Deferred";
+
+    /// <summary>
+    /// The metadata key listing rules, like <c>&lt;function&gt; &lt;struct&gt; &lt;impl&gt;</c>, whose nodes are the
+    /// entries of a file's outline and skeleton. Without it, the outline is guessed from the layout of the tree. See
+    /// <see cref="OutlineProvider"/> and <see cref="SkeletonExtractor"/>.
+    /// </summary>
+    public const string OutlineKey = "Outline";
+
+    /// <summary>
+    /// The metadata key declaring that an input holds several independent documents, written as
+    /// <c>&lt;statement&gt; ";"</c> or <c>&lt;record&gt; newline</c>. See <see cref="DocumentPolicy"/>.
+    /// </summary>
+    public const string DocumentsKey = "Documents";
+
+    private static readonly string[] StartRuleNames = { "program", "start", "compilation_unit", "file_input" };
+
+    private readonly Dictionary<string, CompiledRule> _rulesByName;
+    private readonly bool[] _nullable;
+    private readonly Dictionary<string, string> _categories;
+    private readonly Dictionary<string, HashSet<string>> _contextualKeywords;
+    private readonly Dictionary<string, string> _expectedPhrases;
+    private readonly Dictionary<string, TokenGuard> _tokenGuards;
+    private readonly ConcurrentDictionary<string, EntryPoint> _ruleEntries = new(StringComparer.Ordinal);
+    private Dictionary<string, EscapeScheme> _escapeSchemes = new();
+    private List<Regex>? _commentPatterns;
+    private GrammarLexer? _lexer;
+    private string? _fingerprint;
+    private GrammarDocs? _docs;
+    private GrammarEditorInfo? _editorInfo;
+    private MemoryLedger? _memory;
+
+    private CompiledGrammar(
+        Grammar source,
+        List<CompiledRule> rules,
+        string startRule,
+        Dictionary<string, string> categories,
+        Dictionary<string, HashSet<string>> contextualKeywords,
+        Dictionary<string, EntryPoint> entryPoints,
+        List<string> features,
+        List<DirectiveSyntax> directives,
+        List<GrammarSymbol> hiddenSymbols,
+        HashSet<string> inlinedRules,
+        Dictionary<string, string> expectedPhrases,
+        Dictionary<string, TokenGuard> tokenGuards)
+    {
+        Source = source;
+        Rules = rules;
+        StartRule = startRule;
+        _rulesByName = rules.ToDictionary(r => r.Name);
+        _nullable = ComputeNullable(rules);
+        _categories = categories;
+        _contextualKeywords = contextualKeywords;
+        EntryPoints = entryPoints;
+        Features = features;
+        Directives = directives;
+        HiddenSymbols = hiddenSymbols;
+        InlinedRules = inlinedRules;
+        HasDeprecations = rules.Any(r => r.Alternatives.Any(a => a.Deprecation != null));
+        HasUniqueKeys = rules.Any(r => r.UniqueKey != null);
+        HasDeferredRules = rules.Any(r => r.IsDeferred);
+        HasOutlineRules = rules.Any(r => r.IsOutlined);
+        _expectedPhrases = expectedPhrases;
+        HasExpectedPhrases = expectedPhrases.Count > 0 || rules.Any(r => r.ExpectedPhrase != null);
+        _tokenGuards = tokenGuards;
+        EntryPointStateCount = entryPoints.Values
+            .SelectMany(e => e.Rules)
+            .Distinct()
+            .SelectMany(r => r.Alternatives)
+            .Sum(a => a.Symbols.Count + 1);
+    }
+
+    /// <summary>
+    /// Gets the grammar this instance was compiled from.
+    /// </summary>
+    public Grammar Source { get; }
+
+    /// <summary>
+    /// Gets the grammar name.
+    /// </summary>
+    public string Name => Source.Name;
+
+    /// <summary>
+    /// Gets the compiled production rules in definition order.
+    /// </summary>
+    public IReadOnlyList<CompiledRule> Rules { get; }
+
+    /// <summary>
+    /// Gets the default start rule.
+    /// </summary>
+    public string StartRule { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the grammar is parsed without a lexer. Scannerless grammars match their
+    /// literals, inline patterns and token patterns directly against the characters of the input.
+    /// </summary>
+    public bool IsScannerless => string.Equals(Source.Metadata.GetValueOrDefault(ScannerlessKey), "true", StringComparison.OrdinalIgnoreCase);
+
+    /// <summary>
+    /// Gets a SHA-256 hash of everything that affects parsing: the start rule, rules, alternatives, actions,
+    /// token patterns, escape schemes and metadata. Equal grammars have equal fingerprints regardless of how they were loaded,
+    /// so the fingerprint can key caches of compiled grammars.
+    /// </summary>
+    public string Fingerprint => _fingerprint ??= ComputeFingerprint();
+
+    /// <summary>
+    /// Gets the entry points declared by the "EntryPoints" metadata entry, by name.
+    /// </summary>
+    public IReadOnlyDictionary<string, EntryPoint> EntryPoints { get; }
+
+    /// <summary>
+    /// Gets the number of parser states in the tables of all entry points together, counting states shared by
+    /// several entry points once.
+    /// </summary>
+    public int EntryPointStateCount { get; }
+
+    /// <summary>
+    /// Gets the documentation collected from the grammar's <c>// @description</c>, <c>// @snippet</c> and
+    /// <c>// @example</c> annotations.
+    /// </summary>
+    public GrammarDocs Docs => _docs ??= GrammarDocs.Create(this);
+
+    /// <summary>
+    /// Gets the bracket pairs, quotes and comment syntax editors use, derived from the grammar's rules and token
+    /// patterns.
+    /// </summary>
+    public GrammarEditorInfo EditorInfo => _editorInfo ??= GrammarEditorInfo.Create(this);
+
+    /// <summary>
+    /// Gets the features declared by the "Features" metadata entry, in declaration order.
+    /// </summary>
+    public IReadOnlyList<string> Features { get; }
+
+    /// <summary>
+    /// Gets the directive syntaxes declared by the "Directives" metadata entry, followed by the feature pragma's
+    /// if the grammar has one.
+    /// </summary>
+    public IReadOnlyList<DirectiveSyntax> Directives { get; }
+
+    /// <summary>
+    /// Gets the grammar's lexer, built when first needed and shared by its parsers and the operations that need
+    /// one without a parser.
+    /// </summary>
+    internal GrammarLexer Lexer
+    {
+        get
+        {
+            if (_lexer == null)
+            {
+                long mark = 0;
+                MemoryLedger.Mark(ref mark);
+                var lexer = new GrammarLexer(this);
+                MemoryLedger.Record(ref _memory, MemoryCategories.Lexer, mark);
+                _lexer = lexer;
+            }
+
+            return _lexer;
+        }
+    }
+
+    /// <summary>
+    /// Gets the anchored patterns of the grammar's comment tokens.
+    /// </summary>
+    internal IReadOnlyList<Regex> CommentPatterns => _commentPatterns ??= Source.TokenRules.Patterns
+        .Where(p => p.Type == TokenType.Comment)
+        .Select(p => new Regex(@"\G(?:" + p.Pattern + ")", RegexOptions.CultureInvariant))
+        .ToList();
+
+    /// <summary>
+    /// Gets the symbols declared by the "Hide" metadata entry, which the AST view leaves out.
+    /// </summary>
+    public IReadOnlyList<GrammarSymbol> HiddenSymbols { get; }
+
+    /// <summary>
+    /// Gets the rules declared by the "Inline" metadata entry, which the AST view splices into their parents.
+    /// </summary>
+    public IReadOnlySet<string> InlinedRules { get; }
+
+    /// <summary>
+    /// Gets the operator precedence declared by the "Precedence" metadata entry, or null if there is none.
+    /// </summary>
+    public GrammarPrecedence? Precedence { get; private set; }
+
+    /// <summary>
+    /// Gets how an input is split into documents, declared by the "Documents" metadata entry, or null if inputs are
+    /// parsed whole.
+    /// </summary>
+    public DocumentPolicy? Documents { get; private set; }
+
+    /// <summary>
+    /// Gets problems found while loading the grammar that do not stop it from loading, such as lookahead
+    /// constraints that can never apply.
+    /// </summary>
+    public IReadOnlyList<string> Warnings { get; private set; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets or sets the token kind accepted wherever a token is expected, or null. Only the copies of a grammar that
+    /// structural patterns are parsed with have one.
+    /// </summary>
+    internal string? HoleKind { get; set; }
+
+    /// <summary>
+    /// Gets a value indicating whether any rule or alternative is deprecated, so parses must be checked for them.
+    /// </summary>
+    internal bool HasDeprecations { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether any rule requires distinct member keys, so parses must be checked for
+    /// duplicates.
+    /// </summary>
+    internal bool HasUniqueKeys { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the "Deferred" metadata entry lists any rules, so lazy parses skip bodies.
+    /// </summary>
+    internal bool HasDeferredRules { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the "Outline" metadata entry lists any rules, so outlines are made of their
+    /// nodes rather than guessed from the layout.
+    /// </summary>
+    public bool HasOutlineRules { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the "SymbolPredicates" metadata entry restricts any rules, so parses given
+    /// declared names must check them.
+    /// </summary>
+    public bool HasSymbolPredicates { get; private set; }
+
+    /// <summary>
+    /// Gets a value indicating whether any rule or token pattern has an <c>// @expected</c> annotation, so syntax
+    /// errors are worth describing with them.
+    /// </summary>
+    internal bool HasExpectedPhrases { get; }
+
+    /// <summary>
+    /// Compiles a grammar for parsing.
+    /// </summary>
+    /// <param name="grammar">The grammar to compile.</param>
+    /// <param name="startRule">The start rule or the name of an entry point. If null, uses the "StartRule" metadata entry,
+    /// the first entry point, a conventional start rule name, or the first rule.</param>
+    /// <param name="limits">Limits on what compilation may build, or null for none.</param>
+    /// <returns>The compiled grammar.</returns>
+    /// <exception cref="GrammarLimitExceededException">Compilation exceeded one of the limits.</exception>
+    public static CompiledGrammar Compile(Grammar grammar, string? startRule = null, GrammarLoadLimits? limits = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        if (!grammar.ProductionRules.Rules.Any())
+        {
+            throw new ArgumentException("Grammar must have at least one production rule", nameof(grammar));
+        }
+
+        // Rules defined more than once contribute all of their alternatives to a single rule.
+        var ruleNames = new HashSet<string>(grammar.ProductionRules.Rules.Select(r => r.Name));
+        var layout = LayoutRule(grammar, ruleNames);
+        var lexicalRules = grammar.Metadata.GetValueOrDefault(LexicalRulesKey)?
+            .Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries)
+            .ToHashSet() ?? new HashSet<string>();
+        var rules = new List<CompiledRule>();
+        var byName = new Dictionary<string, CompiledRule>();
+        var budget = new GrammarLoadBudget(grammar.Name, limits);
+        MemoryLedger? memory = null;
+        budget.CheckRisk(grammar);
+
+        foreach (var pattern in grammar.TokenRules.Patterns)
+        {
+            budget.AddPattern(pattern.Pattern, pattern.Name);
+        }
+
+        foreach (var rule in grammar.ProductionRules.Rules)
+        {
+            long mark = 0;
+            if (!byName.TryGetValue(rule.Name, out var compiled))
+            {
+                budget.AddRule(rule.Name);
+                MemoryLedger.Mark(ref mark);
+                compiled = new CompiledRule(rule.Name, rules.Count);
+                byName[rule.Name] = compiled;
+                rules.Add(compiled);
+                MemoryLedger.Record(ref memory, MemoryCategories.Rules, mark);
+            }
+
+            for (var i = 0; i < rule.Alternatives.Count; i++)
+            {
+                var alternative = rule.Alternatives[i];
+                MemoryLedger.Mark(ref mark);
+                var symbols = GrammarSymbol.ParseAlternative(alternative, ruleNames);
+                if (layout != null && rule.Name != layout && !lexicalRules.Contains(rule.Name))
+                {
+                    symbols = InsertLayout(symbols, layout);
+                }
+
+                compiled.AddAlternative(alternative, symbols, rule.Actions.GetValueOrDefault(i));
+                MemoryLedger.Record(ref memory, MemoryCategories.Alternatives, mark);
+                budget.AddAlternative(symbols, rule.Name);
+            }
+        }
+
+        foreach (var alternative in rules.SelectMany(r => r.Alternatives))
+        {
+            alternative.ResolveRuleIndices(byName);
+        }
+
+        var entryPoints = ParseEntryPoints(grammar, byName);
+        var start = (startRule != null && entryPoints.TryGetValue(startRule, out var entry) ? entry.Rule.Name : startRule)
+            ?? grammar.Metadata.GetValueOrDefault("StartRule")
+            ?? entryPoints.Values.FirstOrDefault()?.Rule.Name
+            ?? StartRuleNames.FirstOrDefault(byName.ContainsKey)
+            ?? rules[0].Name;
+
+        if (!byName.ContainsKey(start))
+        {
+            throw new ArgumentException($"Start rule '{start}' is not defined in grammar '{grammar.Name}'", nameof(startRule));
+        }
+
+        ParseDeprecations(grammar, byName, layout);
+        ParseAcceptedAmbiguities(grammar, byName);
+        ParseImportRules(grammar, byName, ruleNames);
+        ParseUniqueKeys(grammar, byName, ruleNames);
+        ParseDeferredRules(grammar, byName, ruleNames);
+        ParseOutlineRules(grammar, byName, ruleNames);
+        ParseBreadcrumbs(grammar, byName, layout);
+        var expectedPhrases = ParseExpectedPhrases(grammar, byName);
+        var categories = ParseCategories(grammar, ruleNames);
+        var compiled = new CompiledGrammar(
+            grammar,
+            rules,
+            start,
+            categories,
+            ParseContextualKeywords(grammar, ruleNames),
+            entryPoints,
+            ParseFeatures(grammar, byName, ruleNames),
+            ParseDirectives(grammar),
+            ParseHiddenSymbols(grammar, ruleNames),
+            ParseInlinedRules(grammar, ruleNames),
+            expectedPhrases,
+            ParseTokenGuards(grammar, rules, ruleNames, categories));
+        compiled._memory = memory;
+        compiled._escapeSchemes = ParseEscapeSchemes(grammar, byName);
+        compiled.Precedence = ParsePrecedence(grammar, ruleNames);
+        compiled.Documents = DocumentPolicy.Parse(grammar, byName, ruleNames);
+        compiled.Warnings = compiled.ParseLookaheadConstraints(ruleNames);
+        compiled.ParseSymbolPredicates(byName, ruleNames);
+
+        // Examples are part of the grammar's contract, so a grammar whose examples do not parse fails to load.
+        budget.CheckTime(null);
+        var failures = GrammarDocs.Validate(compiled);
+        budget.CheckTime(null);
+        if (failures.Count > 0)
+        {
+            throw new ArgumentException($"Grammar '{grammar.Name}' has examples that do not parse:\n{string.Join("\n", failures)}", nameof(grammar));
+        }
+
+        return compiled;
+    }
+
+    /// <summary>
+    /// Gets the rule with the specified name.
+    /// </summary>
+    /// <param name="name">The rule name.</param>
+    /// <returns>The compiled rule, or null if not defined.</returns>
+    public CompiledRule? GetRule(string name)
+    {
+        return _rulesByName.TryGetValue(name, out var rule) ? rule : null;
+    }
+
+    /// <summary>
+    /// Gets an entry point that parses any rule as a complete input, for testing a rule on its own; see
+    /// <see cref="GeneralizedParser.ForRule"/>. A declared complete entry point of the rule is reused; otherwise one
+    /// named after the rule is built on first use and cached, so parsing the same rule again builds no table.
+    /// </summary>
+    /// <param name="name">The rule name.</param>
+    /// <returns>The entry point.</returns>
+    /// <exception cref="ArgumentException">The grammar does not define the rule.</exception>
+    public EntryPoint GetRuleEntry(string name)
+    {
+        ArgumentNullException.ThrowIfNull(name);
+
+        var rule = GetRule(name) ?? throw new ArgumentException($"Rule '{name}' is not defined in grammar '{Name}'", nameof(name));
+        return _ruleEntries.GetOrAdd(name, _ =>
+            EntryPoints.Values.FirstOrDefault(e => e.Rule == rule && e.End == EntryPointEnd.Complete)
+            ?? new EntryPoint(name, rule, EntryPointEnd.Complete, ReachableRules(rule, Rules.Count, Rules)));
+    }
+
+    /// <inheritdoc />
+    public MemoryReport GetMemoryReport()
+    {
+        var alternatives = Rules.SelectMany(r => r.Alternatives).ToList();
+        return new MemoryReport($"Compiled grammar '{Name}'", new[]
+        {
+            new MemoryReportEntry(MemoryCategories.Rules, Rules.Count, MemoryLedger.Get(_memory, MemoryCategories.Rules)),
+            new MemoryReportEntry(MemoryCategories.Alternatives, alternatives.Sum(a => a.Symbols.Count + 1), MemoryLedger.Get(_memory, MemoryCategories.Alternatives)),
+            new MemoryReportEntry(MemoryCategories.Lexer, Source.TokenRules.Patterns.Count, MemoryLedger.Get(_memory, MemoryCategories.Lexer))
+        });
+    }
+
+    /// <summary>
+    /// Determines whether the rule can derive the empty string.
+    /// </summary>
+    /// <param name="rule">The rule to check.</param>
+    /// <returns>True if the rule is nullable.</returns>
+    public bool IsNullable(CompiledRule rule)
+    {
+        return _nullable[rule.Index];
+    }
+
+    /// <summary>
+    /// Gets the category of a terminal: the one declared in the "Categories" metadata entry, otherwise the
+    /// lower-cased type of its token pattern, otherwise "other".
+    /// </summary>
+    /// <param name="key">The terminal's key.</param>
+    /// <returns>The category name.</returns>
+    public string GetTerminalCategory(string key)
+    {
+        if (_categories.TryGetValue(key, out var category))
+        {
+            return category;
+        }
+
+        var pattern = Source.TokenRules.Patterns.FirstOrDefault(p => p.Name == key);
+        return pattern != null ? pattern.Type.ToString().ToLowerInvariant() : "other";
+    }
+
+    /// <summary>
+    /// Gets how syntax errors name a terminal, from the <c>// @expected</c> annotation of its token pattern.
+    /// </summary>
+    /// <param name="key">The terminal's key.</param>
+    /// <returns>The phrase, or null if the terminal's token pattern has none.</returns>
+    public string? GetExpectedPhrase(string key)
+    {
+        return _expectedPhrases.GetValueOrDefault(key);
+    }
+
+    /// <summary>
+    /// Gets the built-in engines that can parse the grammar other than the way <see cref="ParseEngine.Sequential"/>
+    /// does, which is always first. An engine left out would only repeat the sequential parse.
+    /// </summary>
+    /// <returns>The supported engines, in the order of <see cref="ParseEngine.All"/>.</returns>
+    public IReadOnlyList<ParseEngine> GetSupportedEngines()
+    {
+        return ParseEngine.All.Where(e => e.GetUnsupportedReason(this) == null).ToList();
+    }
+
+    /// <summary>
+    /// Gets a value indicating whether any token pattern declares an escape scheme with a <c>// @escapes</c>
+    /// annotation.
+    /// </summary>
+    public bool HasEscapeSchemes => _escapeSchemes.Count > 0;
+
+    /// <summary>
+    /// Gets the escape scheme the <c>// @escapes</c> annotation of a terminal's token pattern declares.
+    /// </summary>
+    /// <param name="key">The terminal's key.</param>
+    /// <returns>The scheme, or null if the terminal's escapes are not checked.</returns>
+    public EscapeScheme? GetEscapeScheme(string key)
+    {
+        return _escapeSchemes.GetValueOrDefault(key);
+    }
+
+    /// <summary>
+    /// Gets the guard the "TokenGuards" metadata entry declares for a terminal.
+    /// </summary>
+    /// <param name="key">The terminal's key.</param>
+    /// <returns>The guard, or null if the terminal is lexed wherever it matches.</returns>
+    internal TokenGuard? GetTokenGuard(string key)
+    {
+        return _tokenGuards.GetValueOrDefault(key);
+    }
+
+    /// <summary>
+    /// Determines whether a literal is a contextual keyword, lexed as an ordinary token rather than reserved.
+    /// </summary>
+    /// <param name="literal">The literal text.</param>
+    /// <returns>True if the "ContextualKeywords" metadata entry declares the literal.</returns>
+    public bool IsContextualKeyword(string literal)
+    {
+        return _contextualKeywords.ContainsKey(literal);
+    }
+
+    /// <summary>
+    /// Determines whether a token can stand for the symbol at the specified position of an alternative: its kind
+    /// is the symbol's key, or the symbol is a contextual keyword of the alternative's rule spelled as the token.
+    /// </summary>
+    /// <param name="token">The token.</param>
+    /// <param name="alternative">The alternative.</param>
+    /// <param name="index">The position of the terminal in the alternative.</param>
+    /// <returns>True if the token matches the terminal.</returns>
+    internal bool Accepts(Token token, CompiledAlternative alternative, int index)
+    {
+        var symbol = alternative.Symbols[index];
+        if (token.Kind == symbol.Key || (HoleKind != null && token.Kind == HoleKind && symbol.Kind != GrammarSymbolKind.Literal))
+        {
+            return true;
+        }
+
+        return symbol.Kind == GrammarSymbolKind.Literal && token.Text == symbol.Name &&
+            _contextualKeywords.TryGetValue(symbol.Name, out var rules) && rules.Contains(alternative.Rule.Name);
+    }
+
+    /// <summary>
+    /// Determines whether the token after an alternative's match is one its <c>!FOLLOWED_BY(...)</c> constraint
+    /// forbids. Nothing follows the end of input, so no constraint forbids it.
+    /// </summary>
+    /// <param name="alternative">The completed alternative.</param>
+    /// <param name="tokens">The tokens of the input.</param>
+    /// <param name="position">The index of the token after the alternative's match.</param>
+    /// <returns>True if the alternative must not be reduced there.</returns>
+    internal bool IsFollowedByForbidden(CompiledAlternative alternative, IReadOnlyList<Token> tokens, int position)
+    {
+        if (alternative.NotFollowedBy.Count == 0 || position >= tokens.Count)
+        {
+            return false;
+        }
+
+        var token = tokens[position];
+        return alternative.NotFollowedBy.Any(symbol => token.Kind == symbol.Key ||
+            (symbol.Kind == GrammarSymbolKind.Literal && token.Text == symbol.Name && _contextualKeywords.ContainsKey(symbol.Name)));
+    }
+
+    /// <summary>
+    /// Reads the feature pragmas of an input.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <returns>The declared features the input enables, in first-mention order; empty if the grammar has no
+    /// "FeaturePragma" metadata entry. Undeclared names are ignored.</returns>
+    public IReadOnlyList<string> ReadFeaturePragmas(string input)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+
+        return Directives.Any(d => d.Name == DirectiveSyntax.FeaturePragmaName)
+            ? ReadFeaturePragmas(input, Lexer.Tokenize(input).Tokens)
+            : Array.Empty<string>();
+    }
+
+    /// <summary>
+    /// Reads the feature pragmas in the comments between already produced tokens.
+    /// </summary>
+    internal IReadOnlyList<string> ReadFeaturePragmas(string input, IReadOnlyList<Token> tokens)
+    {
+        var syntax = Directives.FirstOrDefault(d => d.Name == DirectiveSyntax.FeaturePragmaName);
+        if (syntax == null)
+        {
+            return Array.Empty<string>();
+        }
+
+        var features = new List<string>();
+        foreach (var (_, comment) in DirectiveSet.ReadComments(this, input, tokens))
+        {
+            foreach (var body in syntax.Match(comment))
+            {
+                if (syntax.TryRead(body, out _, out _, out var values, out _) == null)
+                {
+                    features.AddRange(values);
+                }
+            }
+        }
+
+        return features.Where(Features.Contains).Distinct().ToList();
+    }
+
+    /// <summary>
+    /// Gets all distinct terminals referenced by the grammar's alternatives.
+    /// </summary>
+    /// <returns>The terminal symbols in first-reference order.</returns>
+    public IReadOnlyList<GrammarSymbol> GetTerminals()
+    {
+        return Rules
+            .SelectMany(r => r.Alternatives)
+            .SelectMany(a => a.Symbols)
+            .Where(s => s.IsTerminal)
+            .Distinct()
+            .ToList();
+    }
+
+    /// <summary>
+    /// Specializes the grammar to some entry points and features: keeps the rules they reach through alternatives
+    /// the features allow and compiles the result, which accepts exactly what this grammar accepts from the first
+    /// entry point with those features enabled. See <see cref="GrammarSpecialization"/>.
+    /// </summary>
+    /// <param name="entryPoints">Names of entry points or rules; the first is the start rule of the result.</param>
+    /// <param name="enabledFeatures">The declared features to keep; alternatives guarded by others are removed.</param>
+    /// <param name="options">Options, or null for the defaults.</param>
+    /// <returns>The specialized grammar and a report of what was removed.</returns>
+    public GrammarSpecialization Specialize(IEnumerable<string> entryPoints, IEnumerable<string> enabledFeatures, SpecializationOptions? options = null)
+    {
+        return GrammarSpecialization.Create(this, entryPoints, enabledFeatures, options);
+    }
+
+    private static Dictionary<string, string> ParseCategories(Grammar grammar, ISet<string> ruleNames)
+    {
+        var categories = new Dictionary<string, string>();
+        var declaration = grammar.Metadata.GetValueOrDefault(CategoriesKey);
+        if (declaration == null)
+        {
+            return categories;
+        }
+
+        foreach (var entry in declaration.Split(';', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var separator = entry.IndexOf('=');
+            if (separator <= 0)
+            {
+                throw new ArgumentException($"Category '{entry}' in grammar '{grammar.Name}' must be written as name = terminals", nameof(grammar));
+            }
+
+            var name = entry[..separator].Trim();
+            foreach (var symbol in GrammarSymbol.ParseAlternative(entry[(separator + 1)..], ruleNames))
+            {
+                if (!symbol.IsTerminal)
+                {
+                    throw new ArgumentException($"Category '{name}' in grammar '{grammar.Name}' lists rule <{symbol.Name}>; only terminals have categories", nameof(grammar));
+                }
+
+                categories[symbol.Key] = name;
+            }
+        }
+
+        return categories;
+    }
+
+    private static Dictionary<string, TokenGuard> ParseTokenGuards(Grammar grammar, List<CompiledRule> rules, ISet<string> ruleNames, Dictionary<string, string> categories)
+    {
+        var guards = new Dictionary<string, TokenGuard>();
+        var declaration = grammar.Metadata.GetValueOrDefault(TokenGuardsKey);
+        if (declaration == null)
+        {
+            return guards;
+        }
+
+        // Categories are the declared ones and those terminals get from their token pattern's type.
+        var known = new HashSet<string>(categories.Values) { "other" };
+        known.UnionWith(Enum.GetNames<TokenType>().Select(n => n.ToLowerInvariant()));
+        var terminals = rules.SelectMany(r => r.Alternatives).SelectMany(a => a.Symbols).Where(s => s.IsTerminal).Select(s => s.Key).ToHashSet();
+
+        foreach (var entry in declaration.Split(';', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var separator = entry.IndexOf('=');
+            var symbols = separator > 0 ? GrammarSymbol.ParseAlternative(entry[..separator], ruleNames) : new List<GrammarSymbol>();
+            var list = separator > 0 ? entry[(separator + 1)..].Trim() : string.Empty;
+            var negated = list.StartsWith('!');
+            var names = list.TrimStart('!').Split(new[] { ',', ' ' }, StringSplitOptions.RemoveEmptyEntries);
+            if (symbols.Count != 1 || !symbols[0].IsTerminal || names.Length == 0)
+            {
+                throw new ArgumentException($"Token guard '{entry}' in grammar '{grammar.Name}' must be written as terminal = categories or terminal = !categories", nameof(grammar));
+            }
+
+            var key = symbols[0].Key;
+            if (!terminals.Contains(key))
+            {
+                throw new ArgumentException($"Token guard '{entry}' in grammar '{grammar.Name}' guards {key}, which no rule uses", nameof(grammar));
+            }
+
+            foreach (var name in names.Where(n => !known.Contains(n)))
+            {
+                throw new ArgumentException($"Token guard '{entry}' in grammar '{grammar.Name}' names undeclared category '{name}'", nameof(grammar));
+            }
+
+            if (!guards.TryAdd(key, new TokenGuard(names.ToHashSet(), negated)))
+            {
+                throw new ArgumentException($"Token {key} in grammar '{grammar.Name}' is guarded more than once", nameof(grammar));
+            }
+        }
+
+        return guards;
+    }
+
+    private static Dictionary<string, HashSet<string>> ParseContextualKeywords(Grammar grammar, ISet<string> ruleNames)
+    {
+        var keywords = new Dictionary<string, HashSet<string>>();
+        var declaration = grammar.Metadata.GetValueOrDefault(ContextualKeywordsKey);
+        if (declaration == null)
+        {
+            return keywords;
+        }
+
+        foreach (var entry in declaration.Split(';', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var separator = entry.IndexOf('=');
+            var keyword = separator > 0 ? entry[..separator].Trim().Trim('"', '\'') : string.Empty;
+            var rules = separator > 0
+                ? entry[(separator + 1)..].Split(new[] { ',', ' ' }, StringSplitOptions.RemoveEmptyEntries).Select(r => r.Trim('<', '>')).ToList()
+                : new List<string>();
+
+            if (keyword.Length == 0 || rules.Count == 0)
+            {
+                throw new ArgumentException($"Contextual keyword '{entry}' in grammar '{grammar.Name}' must be written as keyword = rules", nameof(grammar));
+            }
+
+            foreach (var rule in rules.Where(r => !ruleNames.Contains(r)))
+            {
+                throw new ArgumentException($"Contextual keyword '{keyword}' in grammar '{grammar.Name}' names undefined rule <{rule}>", nameof(grammar));
+            }
+
+            if (!keywords.TryGetValue(keyword, out var set))
+            {
+                keywords[keyword] = set = new HashSet<string>();
+            }
+
+            set.UnionWith(rules);
+        }
+
+        return keywords;
+    }
+
+    private static Dictionary<string, EntryPoint> ParseEntryPoints(Grammar grammar, Dictionary<string, CompiledRule> rules)
+    {
+        var entryPoints = new Dictionary<string, EntryPoint>();
+        var declaration = grammar.Metadata.GetValueOrDefault(EntryPointsKey);
+        if (declaration == null)
+        {
+            return entryPoints;
+        }
+
+        foreach (var entry in declaration.Split(';', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var separator = entry.IndexOf('=');
+            var name = separator > 0 ? entry[..separator].Trim() : string.Empty;
+            var parts = separator > 0 ? entry[(separator + 1)..].Split(' ', StringSplitOptions.RemoveEmptyEntries) : Array.Empty<string>();
+            if (name.Length == 0 || parts.Length is 0 or > 2)
+            {
+                throw new ArgumentException($"Entry point '{entry}' in grammar '{grammar.Name}' must be written as name = rule [complete|prefix]", nameof(grammar));
+            }
+
+            var ruleName = parts[0].Trim('<', '>');
+            if (!rules.TryGetValue(ruleName, out var rule))
+            {
+                throw new ArgumentException($"Entry point '{name}' in grammar '{grammar.Name}' names undefined rule <{ruleName}>", nameof(grammar));
+            }
+
+            var end = parts.Length == 1 ? EntryPointEnd.Complete : parts[1].ToLowerInvariant() switch
+            {
+                "complete" => EntryPointEnd.Complete,
+                "prefix" => EntryPointEnd.Prefix,
+                var other => throw new ArgumentException($"Entry point '{name}' in grammar '{grammar.Name}' has end '{other}'; expected complete or prefix", nameof(grammar))
+            };
+
+            if (!entryPoints.TryAdd(name, new EntryPoint(name, rule, end, ReachableRules(rule, rules.Count, rules.Values))))
+            {
+                throw new ArgumentException($"Entry point '{name}' is declared more than once in grammar '{grammar.Name}'", nameof(grammar));
+            }
+        }
+
+        return entryPoints;
+    }
+
+    private static List<GrammarSymbol> ParseHiddenSymbols(Grammar grammar, ISet<string> ruleNames)
+    {
+        var declaration = grammar.Metadata.GetValueOrDefault(HideKey);
+        return declaration != null ? GrammarSymbol.ParseAlternative(declaration, ruleNames).Distinct().ToList() : new List<GrammarSymbol>();
+    }
+
+    private static HashSet<string> ParseInlinedRules(Grammar grammar, ISet<string> ruleNames)
+    {
+        var rules = new HashSet<string>();
+        var declaration = grammar.Metadata.GetValueOrDefault(InlineKey);
+        if (declaration == null)
+        {
+            return rules;
+        }
+
+        foreach (var symbol in GrammarSymbol.ParseAlternative(declaration, ruleNames))
+        {
+            if (symbol.IsTerminal)
+            {
+                throw new ArgumentException($"Inline in grammar '{grammar.Name}' lists {symbol}, which is not a rule; only rules can be inlined", nameof(grammar));
+            }
+
+            rules.Add(symbol.Name);
+        }
+
+        return rules;
+    }
+
+    private static List<string> ParseFeatures(Grammar grammar, Dictionary<string, CompiledRule> rules, ISet<string> ruleNames)
+    {
+        var features = new List<string>();
+        var declaration = grammar.Metadata.GetValueOrDefault(FeaturesKey);
+        if (declaration == null)
+        {
+            return features;
+        }
+
+        foreach (var entry in declaration.Split(';', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var separator = entry.IndexOf('=');
+            var name = separator > 0 ? entry[..separator].Trim() : string.Empty;
+            var target = separator > 0 ? entry[(separator + 1)..].Trim() : string.Empty;
+            if (name.Length == 0 || target.Length == 0)
+            {
+                throw new ArgumentException($"Feature '{entry}' in grammar '{grammar.Name}' must be written as name = <rule> [::= alternative]", nameof(grammar));
+            }
+
+            var definition = target.IndexOf("::=", StringComparison.Ordinal);
+            var ruleName = (definition >= 0 ? target[..definition] : target).Trim().Trim('<', '>');
+            if (!rules.TryGetValue(ruleName, out var rule))
+            {
+                throw new ArgumentException($"Feature '{name}' in grammar '{grammar.Name}' names undefined rule <{ruleName}>", nameof(grammar));
+            }
+
+            var guarded = rule.Alternatives.ToList();
+            if (definition >= 0)
+            {
+                var written = target[(definition + 3)..];
+                var symbols = GrammarSymbol.ParseAlternative(written, ruleNames);
+                guarded = guarded.Where(a => GrammarSymbol.ParseAlternative(a.Text, ruleNames).SequenceEqual(symbols)).ToList();
+                if (guarded.Count == 0)
+                {
+                    throw new ArgumentException($"Feature '{name}' in grammar '{grammar.Name}' names no alternative of <{ruleName}> written as {written.Trim()}", nameof(grammar));
+                }
+            }
+
+            foreach (var alternative in guarded)
+            {
+                if (alternative.Feature != null && alternative.Feature != name)
+                {
+                    throw new ArgumentException($"Alternative {alternative.Text} of <{ruleName}> in grammar '{grammar.Name}' is guarded by both '{alternative.Feature}' and '{name}'", nameof(grammar));
+                }
+
+                alternative.Feature = name;
+            }
+
+            if (!features.Contains(name))
+            {
+                features.Add(name);
+            }
+        }
+
+        return features;
+    }
+
+    // Sets the lookahead constraints of the alternatives and warns about those no terminal they list can ever
+    // follow, which refuse nothing.
+    private List<string> ParseLookaheadConstraints(ISet<string> ruleNames)
+    {
+        var warnings = new List<string>();
+        List<HashSet<string>>? follow = null;
+        foreach (var alternative in Rules.SelectMany(r => r.Alternatives))
+        {
+            var where = $"Alternative {alternative.Text.Trim()} of <{alternative.Rule.Name}> in grammar '{Name}'";
+            IReadOnlyList<GrammarSymbol> constraint;
+            try
+            {
+                constraint = GrammarSymbol.ParseNotFollowedBy(alternative.Text, ruleNames);
+            }
+            catch (FormatException ex)
+            {
+                throw new ArgumentException($"{where} has a malformed constraint: {ex.Message}", "grammar");
+            }
+
+            if (constraint.Count == 0)
+            {
+                continue;
+            }
+
+            if (IsScannerless)
+            {
+                throw new ArgumentException($"{where} has a {GrammarSymbol.NotFollowedByKeyword}...) constraint, which needs a token stream; scannerless grammars cannot use it", "grammar");
+            }
+
+            if (constraint.FirstOrDefault(s => !s.IsTerminal) is { } nonTerminal)
+            {
+                throw new ArgumentException($"{where} lists {nonTerminal} in its constraint, which is not a terminal", "grammar");
+            }
+
+            alternative.NotFollowedBy = constraint;
+
+            // Contextual keywords are lexed as ordinary tokens, so which ones follow cannot be told from the terminals.
+            follow ??= ComputeFollow();
+            if (!constraint.Any(s => follow[alternative.Rule.Index].Contains(s.Key) || (s.Kind == GrammarSymbolKind.Literal && _contextualKeywords.ContainsKey(s.Name))))
+            {
+                warnings.Add($"{where} is constrained not to be followed by {string.Join(" ", constraint)}, which can never follow <{alternative.Rule.Name}>; the constraint is redundant");
+            }
+        }
+
+        return warnings;
+    }
+
+    private void ParseSymbolPredicates(Dictionary<string, CompiledRule> rules, ISet<string> ruleNames)
+    {
+        var declaration = Source.Metadata.GetValueOrDefault(SymbolPredicatesKey);
+        if (declaration == null)
+        {
+            return;
+        }
+
+        if (IsScannerless)
+        {
+            throw new ArgumentException($"Grammar '{Name}' declares symbol predicates, which check token text; scannerless grammars cannot use them", "grammar");
+        }
+
+        foreach (var entry in declaration.Split(';', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var separator = entry.IndexOf('=');
+            var name = separator > 0 ? entry[..separator].Trim().Trim('<', '>') : string.Empty;
+            var kind = separator > 0 ? entry[(separator + 1)..].Trim() : string.Empty;
+            var negated = kind.StartsWith('!');
+            kind = kind.TrimStart('!').Trim().Trim('<', '>');
+            if (name.Length == 0 || kind.Length == 0)
+            {
+                throw new ArgumentException($"Symbol predicate '{entry}' in grammar '{Name}' must be written as rule = kind or rule = !kind", "grammar");
+            }
+
+            if (!rules.TryGetValue(name, out var rule))
+            {
+                throw new ArgumentException($"Symbol predicate '{entry}' in grammar '{Name}' names {name}, which is not a production rule", "grammar");
+            }
+
+            if (!ruleNames.Contains(kind))
+            {
+                throw new ArgumentException($"Symbol predicate '{entry}' in grammar '{Name}' checks names declared by <{kind}>, which is not a production rule", "grammar");
+            }
+
+            if (rule.Alternatives.FirstOrDefault(a => a.Symbols.Count != 1 || !a.Symbols[0].IsTerminal) is { } alternative)
+            {
+                throw new ArgumentException($"Symbol predicate '{entry}' in grammar '{Name}' restricts <{name}>, whose alternative {alternative.Text.Trim()} is not a single terminal", "grammar");
+            }
+
+            if (rule.SymbolPredicate != null)
+            {
+                throw new ArgumentException($"Rule <{name}> in grammar '{Name}' has more than one symbol predicate", "grammar");
+            }
+
+            rule.SymbolPredicate = new SymbolPredicate(kind, negated);
+            HasSymbolPredicates = true;
+        }
+    }
+
+    // The terminals that can come right after each rule wherever the grammar uses it.
+    private List<HashSet<string>> ComputeFollow()
+    {
+        var first = Rules.Select(_ => new HashSet<string>(StringComparer.Ordinal)).ToList();
+        var follow = Rules.Select(_ => new HashSet<string>(StringComparer.Ordinal)).ToList();
+        bool changed;
+        do
+        {
+            changed = false;
+            foreach (var alternative in Rules.SelectMany(r => r.Alternatives))
+            {
+                var rule = alternative.Rule.Index;
+                var nullablePrefix = true;
+                for (var i = 0; i < alternative.Symbols.Count && nullablePrefix; i++)
+                {
+                    var symbolRule = alternative.RuleIndices[i];
+                    changed |= symbolRule < 0 ? first[rule].Add(alternative.Symbols[i].Key) : symbolRule != rule && AddAll(first[rule], first[symbolRule]);
+                    nullablePrefix = symbolRule >= 0 && _nullable[symbolRule];
+                }
+
+                // What can follow a symbol: the first terminals of what comes after it, and the rule's own follow
+                // where everything after it can be empty.
+                var after = new HashSet<string>(follow[rule], StringComparer.Ordinal);
+                for (var i = alternative.Symbols.Count - 1; i >= 0; i--)
+                {
+                    var symbolRule = alternative.RuleIndices[i];
+                    if (symbolRule < 0)
+                    {
+                        after = new HashSet<string>(StringComparer.Ordinal) { alternative.Symbols[i].Key };
+                        continue;
+                    }
+
+                    changed |= AddAll(follow[symbolRule], after);
+                    after = _nullable[symbolRule] ? new HashSet<string>(after.Concat(first[symbolRule]), StringComparer.Ordinal) : new HashSet<string>(first[symbolRule], StringComparer.Ordinal);
+                }
+            }
+        }
+        while (changed);
+
+        return follow;
+    }
+
+    private static bool AddAll(HashSet<string> target, IEnumerable<string> keys)
+    {
+        var changed = false;
+        foreach (var key in keys)
+        {
+            changed |= target.Add(key);
+        }
+
+        return changed;
+    }
+
+    private static void ParseDeprecations(Grammar grammar, Dictionary<string, CompiledRule> rules, string? layout)
+    {
+        // A deprecation of some alternatives takes precedence over one of their whole rule, wherever it is written.
+        foreach (var annotation in grammar.Annotations.Where(a => a.Kind == GrammarAnnotationKind.Deprecated).OrderBy(a => a.Alternatives.Count > 0))
+        {
+            var where = $"Deprecation on line {annotation.Line} of grammar '{grammar.Name}'";
+            if (!rules.TryGetValue(annotation.Target, out var rule))
+            {
+                throw new ArgumentException($"{where} annotates {annotation.Target}, which is not a production rule", nameof(grammar));
+            }
+
+            GrammarDeprecation deprecation;
+            try
+            {
+                deprecation = GrammarDeprecation.Parse(annotation.Text);
+            }
+            catch (FormatException ex)
+            {
+                throw new ArgumentException($"{where} is malformed: {ex.Message}", nameof(grammar));
+            }
+
+            var deprecated = annotation.Alternatives.Count == 0 ? rule.Alternatives.ToList() : new List<CompiledAlternative>();
+            foreach (var index in annotation.Alternatives)
+            {
+                deprecated.Add(index >= 0 && index < rule.Alternatives.Count
+                    ? rule.Alternatives[index]
+                    : throw new ArgumentException($"{where} names alternative {index} of <{rule.Name}>, which has {rule.Alternatives.Count}", nameof(grammar)));
+            }
+
+            if (deprecation.ReplaceWith != null && !deprecated.Any(a => deprecation.CanRewrite(a, layout)))
+            {
+                throw new ArgumentException($"{where} replaces <{rule.Name}> with '{deprecation.ReplaceWith}', which uses labels the deprecated alternatives do not have", nameof(grammar));
+            }
+
+            if (annotation.Alternatives.Count == 0)
+            {
+                deprecation = deprecation.ForRule();
+            }
+
+            foreach (var alternative in deprecated)
+            {
+                alternative.Deprecation = deprecation;
+            }
+        }
+    }
+
+    private static void ParseAcceptedAmbiguities(Grammar grammar, Dictionary<string, CompiledRule> rules)
+    {
+        foreach (var annotation in grammar.Annotations.Where(a => a.Kind == GrammarAnnotationKind.Ambiguous))
+        {
+            if (!rules.TryGetValue(annotation.Target, out var rule))
+            {
+                throw new ArgumentException($"Ambiguity annotation on line {annotation.Line} of grammar '{grammar.Name}' annotates {annotation.Target}, which is not a production rule", nameof(grammar));
+            }
+
+            // The reason is optional, quoted and in parentheses like the other annotations' arguments.
+            var reason = annotation.Text.Trim();
+            if (reason.StartsWith('(') && reason.EndsWith(')'))
+            {
+                reason = reason[1..^1].Trim();
+            }
+
+            if (reason.Length >= 2 && reason[0] == '"' && reason[^1] == '"')
+            {
+                reason = reason[1..^1].Replace("\\\"", "\"").Trim();
+            }
+
+            rule.AcceptedAmbiguity = reason;
+        }
+    }
+
+    private static void ParseImportRules(Grammar grammar, Dictionary<string, CompiledRule> rules, ISet<string> ruleNames)
+    {
+        foreach (var annotation in grammar.Annotations.Where(a => a.Kind == GrammarAnnotationKind.Import))
+        {
+            var where = $"Import annotation on line {annotation.Line} of grammar '{grammar.Name}'";
+            if (!rules.TryGetValue(annotation.Target, out var rule))
+            {
+                throw new ArgumentException($"{where} annotates {annotation.Target}, which is not a production rule", nameof(grammar));
+            }
+
+            var argument = annotation.Text.Trim();
+            if (argument.StartsWith('(') && argument.EndsWith(')'))
+            {
+                argument = argument[1..^1].Trim();
+            }
+
+            var symbols = GrammarSymbol.ParseAlternative(argument, ruleNames);
+            if (symbols.Count != 1 || symbols[0].Kind == GrammarSymbolKind.Literal)
+            {
+                throw new ArgumentException($"{where} must name the one rule or token holding the module specifier, like @import(<STRING>)", nameof(grammar));
+            }
+
+            var specifier = symbols[0];
+            var missing = rule.Alternatives.FirstOrDefault(a => !a.Symbols.Contains(specifier));
+            if (missing != null)
+            {
+                throw new ArgumentException($"{where} names {specifier}, which alternative {missing.Index + 1} of <{rule.Name}> does not contain", nameof(grammar));
+            }
+
+            rule.ImportSpecifier = specifier;
+        }
+    }
+
+    private static void ParseUniqueKeys(Grammar grammar, Dictionary<string, CompiledRule> rules, ISet<string> ruleNames)
+    {
+        foreach (var annotation in grammar.Annotations.Where(a => a.Kind == GrammarAnnotationKind.UniqueBy))
+        {
+            var where = $"Unique key annotation on line {annotation.Line} of grammar '{grammar.Name}'";
+            if (!rules.TryGetValue(annotation.Target, out var rule))
+            {
+                throw new ArgumentException($"{where} annotates {annotation.Target}, which is not a production rule", nameof(grammar));
+            }
+
+            UniqueKeyConstraint constraint;
+            try
+            {
+                constraint = UniqueKeyConstraint.Parse(annotation.Text, ruleNames);
+            }
+            catch (FormatException ex)
+            {
+                throw new ArgumentException($"{where} is malformed: {ex.Message}", nameof(grammar));
+            }
+
+            if (!rules.TryGetValue(constraint.Member, out var member))
+            {
+                throw new ArgumentException($"{where} names member <{constraint.Member}>, which is not a production rule", nameof(grammar));
+            }
+
+            var missing = member.Alternatives.FirstOrDefault(a => !a.Symbols.Contains(constraint.Key));
+            if (missing != null)
+            {
+                throw new ArgumentException($"{where} names key {constraint.Key}, which alternative {missing.Index + 1} of <{member.Name}> does not contain", nameof(grammar));
+            }
+
+            if (rule.UniqueKey != null)
+            {
+                throw new ArgumentException($"{where} gives <{rule.Name}> a second unique key", nameof(grammar));
+            }
+
+            rule.UniqueKey = constraint;
+        }
+    }
+
+    private static void ParseDeferredRules(Grammar grammar, Dictionary<string, CompiledRule> rules, ISet<string> ruleNames)
+    {
+        var declaration = grammar.Metadata.GetValueOrDefault(DeferredKey);
+        if (declaration == null)
+        {
+            return;
+        }
+
+        foreach (var symbol in GrammarSymbol.ParseAlternative(declaration, ruleNames))
+        {
+            if (symbol.IsTerminal)
+            {
+                throw new ArgumentException($"Deferred in grammar '{grammar.Name}' lists {symbol}, which is not a rule; only rules can be deferred", nameof(grammar));
+            }
+
+            // The skipped group is found by bracket matching alone, so every alternative must be one such group.
+            var rule = rules[symbol.Name];
+            var brackets = rule.Alternatives
+                .Select(a => a.Symbols.Count >= 2 && a.Symbols[0].Kind == GrammarSymbolKind.Literal && a.Symbols[^1].Kind == GrammarSymbolKind.Literal
+                    ? (Open: a.Symbols[0], Close: a.Symbols[^1])
+                    : ((GrammarSymbol Open, GrammarSymbol Close)?)null)
+                .Distinct()
+                .ToList();
+            if (brackets.Count != 1 || brackets[0] is not { } pair || pair.Open == pair.Close)
+            {
+                throw new ArgumentException($"Deferred rule <{rule.Name}> in grammar '{grammar.Name}' must start every alternative with the same opening literal and end it with the same closing literal", nameof(grammar));
+            }
+
+            rule.Placeholder = new CompiledAlternative(rule, -1, $"{pair.Open} ... {pair.Close}", new[] { pair.Open, pair.Close }, null);
+            rule.Placeholder.ResolveRuleIndices(rules);
+        }
+    }
+
+    private static void ParseOutlineRules(Grammar grammar, Dictionary<string, CompiledRule> rules, ISet<string> ruleNames)
+    {
+        var declaration = grammar.Metadata.GetValueOrDefault(OutlineKey);
+        if (declaration == null)
+        {
+            return;
+        }
+
+        foreach (var symbol in GrammarSymbol.ParseAlternative(declaration, ruleNames))
+        {
+            if (symbol.IsTerminal)
+            {
+                throw new ArgumentException($"Outline in grammar '{grammar.Name}' lists {symbol}, which is not a rule; only rule nodes can be outline entries", nameof(grammar));
+            }
+
+            rules[symbol.Name].IsOutlined = true;
+        }
+    }
+
+    private static void ParseBreadcrumbs(Grammar grammar, Dictionary<string, CompiledRule> rules, string? layout)
+    {
+        foreach (var annotation in grammar.Annotations.Where(a => a.Kind == GrammarAnnotationKind.Breadcrumb))
+        {
+            var where = $"Breadcrumb annotation on line {annotation.Line} of grammar '{grammar.Name}'";
+            if (!rules.TryGetValue(annotation.Target, out var rule))
+            {
+                throw new ArgumentException($"{where} annotates {annotation.Target}, which is not a production rule", nameof(grammar));
+            }
+
+            if (!rule.IsOutlined)
+            {
+                throw new ArgumentException($"{where} annotates <{rule.Name}>, which is not listed under {OutlineKey}; only outline entries appear in paths", nameof(grammar));
+            }
+
+            var template = annotation.Text.Trim();
+            if (template.StartsWith('(') && template.EndsWith(')'))
+            {
+                template = template[1..^1].Trim();
+            }
+
+            if (template.Length < 2 || template[0] != '"' || template[^1] != '"')
+            {
+                throw new ArgumentException($"{where} must be a quoted template, like @breadcrumb(\"impl $path for $type\")", nameof(grammar));
+            }
+
+            template = template[1..^1].Replace("\\\"", "\"");
+            var labels = QualifiedPath.GetTemplateLabels(template);
+            if (!rule.Alternatives.Any(a => labels.All(GrammarDeprecation.GetLabels(a, layout).Contains)))
+            {
+                throw new ArgumentException($"{where} uses labels {string.Join(", ", labels.Select(l => "$" + l))}, which no alternative of <{rule.Name}> has all of", nameof(grammar));
+            }
+
+            rule.Breadcrumbs = rule.Breadcrumbs.Append(template).ToList();
+        }
+    }
+
+    private static GrammarPrecedence? ParsePrecedence(Grammar grammar, ISet<string> ruleNames)
+    {
+        var declaration = grammar.Metadata.GetValueOrDefault(PrecedenceKey);
+        if (declaration == null)
+        {
+            return null;
+        }
+
+        try
+        {
+            return GrammarPrecedence.Parse(declaration, ruleNames);
+        }
+        catch (FormatException ex)
+        {
+            throw new ArgumentException($"Precedence in grammar '{grammar.Name}' is malformed: {ex.Message}", nameof(grammar));
+        }
+    }
+
+    private static Dictionary<string, string> ParseExpectedPhrases(Grammar grammar, Dictionary<string, CompiledRule> rules)
+    {
+        // Rules keep their phrase; token patterns are looked up by terminal key when an error lists them.
+        var tokens = new Dictionary<string, string>();
+        foreach (var annotation in grammar.Annotations.Where(a => a.Kind == GrammarAnnotationKind.Expected))
+        {
+            var where = $"Expected annotation on line {annotation.Line} of grammar '{grammar.Name}'";
+            string phrase;
+            try
+            {
+                phrase = ExpectedPhrases.Parse(annotation.Text);
+            }
+            catch (FormatException ex)
+            {
+                throw new ArgumentException($"{where} is malformed: {ex.Message}", nameof(grammar));
+            }
+
+            var previous = rules.TryGetValue(annotation.Target, out var rule) ? rule.ExpectedPhrase : tokens.GetValueOrDefault(annotation.Target);
+            if (previous != null && previous != phrase)
+            {
+                throw new ArgumentException($"{where} names <{annotation.Target}> '{phrase}', which is already named '{previous}'", nameof(grammar));
+            }
+
+            if (rule != null)
+            {
+                rule.ExpectedPhrase = phrase;
+            }
+            else
+            {
+                tokens[annotation.Target] = phrase;
+            }
+        }
+
+        return tokens;
+    }
+
+    private static Dictionary<string, EscapeScheme> ParseEscapeSchemes(Grammar grammar, Dictionary<string, CompiledRule> rules)
+    {
+        var schemes = new Dictionary<string, EscapeScheme>();
+        foreach (var annotation in grammar.Annotations.Where(a => a.Kind == GrammarAnnotationKind.Escapes))
+        {
+            var where = $"Escapes annotation on line {annotation.Line} of grammar '{grammar.Name}'";
+            if (rules.ContainsKey(annotation.Target))
+            {
+                throw new ArgumentException($"{where} annotates <{annotation.Target}>, which is not a token pattern", nameof(grammar));
+            }
+
+            EscapeScheme scheme;
+            try
+            {
+                scheme = EscapeScheme.Parse(annotation.Text);
+            }
+            catch (FormatException ex)
+            {
+                throw new ArgumentException($"{where} is malformed: {ex.Message}", nameof(grammar));
+            }
+
+            if (schemes.TryGetValue(annotation.Target, out var previous) && previous.Declaration != scheme.Declaration)
+            {
+                throw new ArgumentException($"{where} declares '{scheme}' for {annotation.Target}, which already has '{previous}'", nameof(grammar));
+            }
+
+            schemes[annotation.Target] = scheme;
+        }
+
+        return schemes;
+    }
+
+    private static List<DirectiveSyntax> ParseDirectives(Grammar grammar)
+    {
+        var header = grammar.Metadata.GetValueOrDefault(DirectivesKey);
+        var directives = string.IsNullOrWhiteSpace(header)
+            ? new List<DirectiveSyntax>()
+            : DirectiveSyntax.ParseHeader(header, grammar.Name);
+        if (ParseFeaturePragma(grammar) is { } pragma)
+        {
+            directives.Add(DirectiveSyntax.ForFeaturePragma(pragma));
+        }
+
+        return directives;
+    }
+
+    private static Regex? ParseFeaturePragma(Grammar grammar)
+    {
+        var pattern = grammar.Metadata.GetValueOrDefault(FeaturePragmaKey)?.Trim();
+        if (string.IsNullOrEmpty(pattern))
+        {
+            return null;
+        }
+
+        if (pattern.Length >= 2 && pattern[0] == '/' && pattern[^1] == '/')
+        {
+            pattern = pattern[1..^1];
+        }
+
+        try
+        {
+            return new Regex(pattern, RegexOptions.CultureInvariant);
+        }
+        catch (ArgumentException ex)
+        {
+            throw new ArgumentException($"Feature pragma in grammar '{grammar.Name}' is not a valid regular expression: {ex.Message}", nameof(grammar));
+        }
+    }
+
+    private static List<CompiledRule> ReachableRules(CompiledRule start, int ruleCount, IEnumerable<CompiledRule> rules)
+    {
+        var reached = new bool[ruleCount];
+        var pending = new Stack<CompiledRule>();
+        reached[start.Index] = true;
+        pending.Push(start);
+
+        var byIndex = rules.OrderBy(r => r.Index).ToList();
+        while (pending.Count > 0)
+        {
+            foreach (var index in pending.Pop().Alternatives.SelectMany(a => a.RuleIndices).Where(i => i >= 0 && !reached[i]))
+            {
+                reached[index] = true;
+                pending.Push(byIndex[index]);
+            }
+        }
+
+        return byIndex.Where(r => reached[r.Index]).ToList();
+    }
+
+    private static string? LayoutRule(Grammar grammar, ISet<string> ruleNames)
+    {
+        var layout = grammar.Metadata.GetValueOrDefault(LayoutKey);
+        if (layout == null || !string.Equals(grammar.Metadata.GetValueOrDefault(ScannerlessKey), "true", StringComparison.OrdinalIgnoreCase))
+        {
+            return null;
+        }
+
+        return ruleNames.Contains(layout)
+            ? layout
+            : throw new ArgumentException($"Layout rule '{layout}' is not defined in grammar '{grammar.Name}'", nameof(grammar));
+    }
+
+    // Automatic layout insertion: the layout rule goes between every two symbols of an alternative, so rules can be
+    // written as if a lexer had removed the whitespace. Leading and trailing layout is written explicitly in the
+    // start rule; nothing is inserted next to an explicit layout symbol, which would make the layout ambiguous.
+    private static IReadOnlyList<GrammarSymbol> InsertLayout(IReadOnlyList<GrammarSymbol> symbols, string layout)
+    {
+        var result = new List<GrammarSymbol>(symbols.Count * 2);
+        foreach (var symbol in symbols)
+        {
+            var explicitLayout = symbol.Kind == GrammarSymbolKind.Rule && symbol.Name == layout;
+            if (result.Count > 0 && !explicitLayout && !(result[^1].Kind == GrammarSymbolKind.Rule && result[^1].Name == layout))
+            {
+                result.Add(new GrammarSymbol(GrammarSymbolKind.Rule, layout));
+            }
+
+            result.Add(symbol);
+        }
+
+        return result;
+    }
+
+    private string ComputeFingerprint()
+    {
+        var text = new StringBuilder();
+        text.Append("start ").Append(StartRule).Append('\n');
+
+        foreach (var rule in Rules)
+        {
+            foreach (var alternative in rule.Alternatives)
+            {
+                text.Append('<').Append(rule.Name).Append("> ::= ").Append(alternative.Text);
+                if (alternative.Action != null)
+                {
+                    text.Append(" => { ").Append(alternative.Action).Append(" }");
+                }
+
+                text.Append('\n');
+            }
+        }
+
+        foreach (var pattern in Source.TokenRules.Patterns)
+        {
+            text.Append("token ").Append(pattern.Name).Append(' ').Append(pattern.Type).Append(' ')
+                .Append(pattern.Priority).Append(" /").Append(pattern.Pattern).Append("/\n");
+        }
+
+        foreach (var (key, scheme) in _escapeSchemes.OrderBy(s => s.Key, StringComparer.Ordinal))
+        {
+            text.Append("escapes ").Append(key).Append(' ').Append(scheme.Declaration).Append('\n');
+        }
+
+        foreach (var (key, value) in Source.Metadata.OrderBy(m => m.Key, StringComparer.Ordinal))
+        {
+            text.Append("meta ").Append(key).Append(": ").Append(value).Append('\n');
+        }
+
+        return Convert.ToHexString(SHA256.HashData(Encoding.UTF8.GetBytes(text.ToString()))).ToLowerInvariant();
+    }
+
+    private static bool[] ComputeNullable(List<CompiledRule> rules)
+    {
+        var nullable = new bool[rules.Count];
+        bool changed;
+
+        do
+        {
+            changed = false;
+            foreach (var rule in rules)
+            {
+                if (nullable[rule.Index])
+                {
+                    continue;
+                }
+
+                if (rule.Alternatives.Any(a => a.RuleIndices.All(index => index >= 0 && nullable[index])))
+                {
+                    nullable[rule.Index] = true;
+                    changed = true;
+                }
+            }
+        }
+        while (changed);
+
+        return nullable;
+    }
+}
+
+/// <summary>
+/// A production rule prepared for parsing.
+/// </summary>
+public sealed class CompiledRule
+{
+    private readonly List<CompiledAlternative> _alternatives = new();
+
+    internal CompiledRule(string name, int index)
+    {
+        Name = name;
+        Index = index;
+    }
+
+    /// <summary>
+    /// Gets the rule name.
+    /// </summary>
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets the index of the rule within its compiled grammar.
+    /// </summary>
+    public int Index { get; }
+
+    /// <summary>
+    /// Gets the alternatives of the rule in definition order.
+    /// </summary>
+    public IReadOnlyList<CompiledAlternative> Alternatives => _alternatives;
+
+    /// <summary>
+    /// Gets how syntax errors name the rule where it is expected, like "a type", from its <c>// @expected</c>
+    /// annotation, or null if it has none.
+    /// </summary>
+    public string? ExpectedPhrase { get; internal set; }
+
+    /// <summary>
+    /// Gets why the rule's parse ambiguities are intended, from its <c>// @ambiguous</c> annotation, or null if it has
+    /// none. Accepted ambiguities keep the derivation using the earliest alternatives and are not reported.
+    /// </summary>
+    public string? AcceptedAmbiguity { get; internal set; }
+
+    /// <summary>
+    /// Gets the symbol whose text is the imported module specifier if the rule is an import declaration, from its
+    /// <c>// @import(&lt;STRING&gt;)</c> annotation, or null if it has none; see
+    /// <see cref="Minotaur.Analysis.Passes.ImportPass"/>.
+    /// </summary>
+    public GrammarSymbol? ImportSpecifier { get; internal set; }
+
+    /// <summary>
+    /// Gets the requirement that the rule's members have distinct keys, from its <c>// @unique_by</c> annotation, or
+    /// null if it has none.
+    /// </summary>
+    public UniqueKeyConstraint? UniqueKey { get; internal set; }
+
+    /// <summary>
+    /// Gets the predicate the "SymbolPredicates" metadata entry declares for the rule, or null if it derives any
+    /// name.
+    /// </summary>
+    public SymbolPredicate? SymbolPredicate { get; internal set; }
+
+    /// <summary>
+    /// Gets a value indicating whether the rule is listed by the "Deferred" metadata entry, so a lazy parse skips
+    /// its bodies until their nodes are read.
+    /// </summary>
+    public bool IsDeferred => Placeholder != null;
+
+    /// <summary>
+    /// Gets a value indicating whether the rule is listed by the "Outline" metadata entry, so its nodes are outline
+    /// and skeleton entries.
+    /// </summary>
+    public bool IsOutlined { get; internal set; }
+
+    /// <summary>
+    /// Gets the templates naming the rule's nodes in qualified paths, like <c>impl $path for $type</c>, from its
+    /// <c>// @breadcrumb</c> annotations in order; see <see cref="QualifiedPath"/>.
+    /// </summary>
+    public IReadOnlyList<string> Breadcrumbs { get; internal set; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the alternative a lazy parse derives the rule with when it skips a body: its opening and closing
+    /// brackets, with index -1. Null unless the rule is deferred.
+    /// </summary>
+    internal CompiledAlternative? Placeholder { get; set; }
+
+    internal void AddAlternative(string text, IReadOnlyList<GrammarSymbol> symbols, string? action)
+    {
+        _alternatives.Add(new CompiledAlternative(this, _alternatives.Count, text, symbols, action));
+    }
+}
+
+/// <summary>
+/// A single alternative of a production rule.
+/// </summary>
+public sealed class CompiledAlternative
+{
+    internal CompiledAlternative(CompiledRule rule, int index, string text, IReadOnlyList<GrammarSymbol> symbols, string? action)
+    {
+        Rule = rule;
+        Index = index;
+        Text = text;
+        Symbols = symbols;
+        Action = action;
+        RuleIndices = new int[symbols.Count];
+    }
+
+    /// <summary>
+    /// Gets the rule this alternative belongs to.
+    /// </summary>
+    public CompiledRule Rule { get; }
+
+    /// <summary>
+    /// Gets the index of the alternative within its rule.
+    /// </summary>
+    public int Index { get; }
+
+    /// <summary>
+    /// Gets the alternative as written in the grammar.
+    /// </summary>
+    public string Text { get; }
+
+    /// <summary>
+    /// Gets the symbols of the alternative.
+    /// </summary>
+    public IReadOnlyList<GrammarSymbol> Symbols { get; }
+
+    /// <summary>
+    /// Gets the name of the semantic action bound to the alternative in the grammar, if any.
+    /// </summary>
+    public string? Action { get; }
+
+    /// <summary>
+    /// Gets the feature that guards the alternative, or null if it is always enabled.
+    /// </summary>
+    public string? Feature { get; internal set; }
+
+    /// <summary>
+    /// Gets the deprecation of the alternative or of its whole rule, or null if it is not deprecated.
+    /// </summary>
+    public GrammarDeprecation? Deprecation { get; internal set; }
+
+    /// <summary>
+    /// Gets the terminals none of which may follow the alternative, from its <c>!FOLLOWED_BY(...)</c> constraint;
+    /// empty if it has none. The alternative is not reduced where the next token is one of them.
+    /// </summary>
+    public IReadOnlyList<GrammarSymbol> NotFollowedBy { get; internal set; } = Array.Empty<GrammarSymbol>();
+
+    /// <summary>
+    /// Gets, for each symbol, the index of the referenced rule or -1 for terminals.
+    /// </summary>
+    internal int[] RuleIndices { get; }
+
+    internal void ResolveRuleIndices(Dictionary<string, CompiledRule> rules)
+    {
+        for (var i = 0; i < Symbols.Count; i++)
+        {
+            RuleIndices[i] = Symbols[i].Kind == GrammarSymbolKind.Rule ? rules[Symbols[i].Name].Index : -1;
+        }
+    }
+
+    /// <summary>
+    /// Returns the alternative in "rule ::= symbols" form.
+    /// </summary>
+    /// <returns>The alternative description.</returns>
+    public override string ToString()
+    {
+        return $"<{Rule.Name}> ::= {Text}";
+    }
+}
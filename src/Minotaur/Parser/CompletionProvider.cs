@@ -0,0 +1,101 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A completion offered at a position of the input.
+/// </summary>
+/// <param name="Label">The text shown in the list: a literal's text or a token name.</param>
+/// <param name="Terminal">The expected terminal's key.</param>
+/// <param name="Category">The terminal's category, see <see cref="CompiledGrammar.GetTerminalCategory"/>.</param>
+/// <param name="Description">The one-line description of the terminal or the construct it introduces, if documented.</param>
+/// <param name="Snippet">The snippet to insert instead of the label, if documented.</param>
+public sealed record CompletionItem(string Label, string Terminal, string Category, string? Description, string? Snippet);
+
+/// <summary>
+/// Offers the terminals that may follow a prefix of the input, described by the grammar's
+/// <see cref="CompiledGrammar.Docs"/>. A literal that introduces a documented construct gets one item per
+/// snippet of that construct; token patterns are offered only where they have snippets, since their names
+/// cannot be inserted as they are.
+/// </summary>
+public sealed class CompletionProvider
+{
+    private readonly GeneralizedParser _parser;
+
+    /// <summary>
+    /// Initializes a new instance of the <see cref="CompletionProvider"/> class.
+    /// </summary>
+    /// <param name="parser">The parser for the language.</param>
+    public CompletionProvider(GeneralizedParser parser)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+        _parser = parser;
+    }
+
+    /// <summary>
+    /// Gets the completions at the end of a text. A partly typed word at the end is completed: the prefix is
+    /// parsed without it and only literals starting with it are offered.
+    /// </summary>
+    /// <param name="textBeforeCursor">The input up to the cursor.</param>
+    /// <returns>The completions, ordered by label.</returns>
+    public IReadOnlyList<CompletionItem> Complete(string textBeforeCursor)
+    {
+        ArgumentNullException.ThrowIfNull(textBeforeCursor);
+
+        var wordStart = textBeforeCursor.Length;
+        while (wordStart > 0 && (char.IsLetterOrDigit(textBeforeCursor[wordStart - 1]) || textBeforeCursor[wordStart - 1] == '_'))
+        {
+            wordStart--;
+        }
+
+        var word = textBeforeCursor[wordStart..];
+        var prefix = _parser.ParsePrefix(textBeforeCursor[..wordStart]);
+        if (prefix.Status == PrefixStatus.Invalid)
+        {
+            return Array.Empty<CompletionItem>();
+        }
+
+        var grammar = _parser.Grammar;
+        var items = new List<CompletionItem>();
+        foreach (var terminal in prefix.Expected)
+        {
+            var isLiteral = terminal.StartsWith('"');
+            var label = isLiteral ? terminal[1..^1] : terminal;
+            if (isLiteral ? !label.StartsWith(word, StringComparison.Ordinal) : word.Length > 0)
+            {
+                continue;
+            }
+
+            var docs = grammar.Docs.GetForTerminal(terminal);
+            var category = grammar.GetTerminalCategory(terminal);
+            if (docs == null || docs.Snippets.Count == 0)
+            {
+                if (isLiteral)
+                {
+                    items.Add(new CompletionItem(label, terminal, category, docs?.Description, null));
+                }
+
+                continue;
+            }
+
+            items.AddRange(docs.Snippets.Select(snippet => new CompletionItem(label, terminal, category, docs.Description, snippet)));
+        }
+
+        return items.OrderBy(i => i.Label, StringComparer.Ordinal).ToList();
+    }
+}
@@ -0,0 +1,276 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The bracket groups a <see cref="ParseOptions.Lazy"/> parse skipped. Each is a <see cref="DeferredNode"/> in the
+/// tree whose children are parsed when first read, so a file's declarations are available before its bodies are.
+/// Regions found inside a region when it is parsed are deferred in turn and join the same set.
+/// </summary>
+public sealed class DeferredRegions
+{
+    private readonly GeneralizedParser _parser;
+    private readonly string _input;
+    private readonly LineIndex _lineIndex;
+    private readonly ParseOptions _options;
+    private readonly object _gate = new();
+    private readonly List<DeferredNode> _nodes = new();
+    private readonly List<Diagnostic> _diagnostics = new();
+    private IReadOnlyList<Diagnostic>? _combined;
+    private (int Parsed, int Deferred) _combinedCounts;
+    private int _materializedCount;
+
+    internal DeferredRegions(GeneralizedParser parser, string input, LineIndex lineIndex, ParseOptions options)
+    {
+        _parser = parser;
+        _input = input;
+        _lineIndex = lineIndex;
+        _options = options;
+    }
+
+    /// <summary>
+    /// Gets the number of regions deferred so far, including those found inside parsed regions.
+    /// </summary>
+    public int Count
+    {
+        get
+        {
+            lock (_gate)
+            {
+                return _nodes.Count;
+            }
+        }
+    }
+
+    /// <summary>
+    /// Gets the number of regions parsed so far.
+    /// </summary>
+    public int MaterializedCount => Volatile.Read(ref _materializedCount);
+
+    /// <summary>
+    /// Gets the diagnostics of the regions parsed so far, in the order they were parsed.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics
+    {
+        get
+        {
+            lock (_gate)
+            {
+                return _diagnostics.ToList();
+            }
+        }
+    }
+
+    /// <summary>
+    /// Parses every region not parsed yet, including the regions found inside them.
+    /// </summary>
+    /// <returns>The diagnostics of all regions.</returns>
+    public IReadOnlyList<Diagnostic> MaterializeAll()
+    {
+        for (var i = 0; ; i++)
+        {
+            DeferredNode node;
+            lock (_gate)
+            {
+                if (i >= _nodes.Count)
+                {
+                    break;
+                }
+
+                node = _nodes[i];
+            }
+
+            node.Materialize();
+        }
+
+        return Diagnostics;
+    }
+
+    /// <summary>
+    /// Finds, for every opening bracket of a deferred rule, the index of the token closing it; -1 for other tokens
+    /// and for unclosed brackets.
+    /// </summary>
+    internal static int[] MatchBrackets(CompiledGrammar grammar, IReadOnlyList<Token> tokens)
+    {
+        var ends = new int[tokens.Count];
+        Array.Fill(ends, -1);
+
+        var pairs = grammar.Rules
+            .Where(r => r.Placeholder != null)
+            .Select(r => (Open: r.Placeholder!.Symbols[0].Key, Close: r.Placeholder.Symbols[1].Key))
+            .Distinct();
+        var open = new Stack<int>();
+        foreach (var (openKind, closeKind) in pairs)
+        {
+            open.Clear();
+            for (var i = 0; i < tokens.Count; i++)
+            {
+                if (tokens[i].Kind == openKind)
+                {
+                    open.Push(i);
+                }
+                else if (tokens[i].Kind == closeKind && open.Count > 0)
+                {
+                    ends[open.Pop()] = i;
+                }
+            }
+        }
+
+        return ends;
+    }
+
+    internal DeferredNode Add(CompiledRule rule, SourcePosition position, IReadOnlyList<Token> tokens, int start, int end)
+    {
+        var node = new DeferredNode(this, rule.Name, tokens, start, end) { SourcePosition = position };
+        lock (_gate)
+        {
+            _nodes.Add(node);
+        }
+
+        return node;
+    }
+
+    /// <summary>
+    /// Gets a parse's own diagnostics followed by those of the regions parsed so far.
+    /// </summary>
+    internal IReadOnlyList<Diagnostic> AppendDiagnostics(IReadOnlyList<Diagnostic> diagnostics)
+    {
+        lock (_gate)
+        {
+            if (_diagnostics.Count == 0)
+            {
+                return diagnostics;
+            }
+
+            if (_combined == null || _combinedCounts != (diagnostics.Count, _diagnostics.Count))
+            {
+                _combined = diagnostics.Concat(_diagnostics).ToList();
+                _combinedCounts = (diagnostics.Count, _diagnostics.Count);
+            }
+
+            return _combined;
+        }
+    }
+
+    /// <summary>
+    /// Parses a region against its rule and moves the resulting children to its node.
+    /// </summary>
+    internal void Parse(DeferredNode node, IReadOnlyList<Token> tokens, int start, int end)
+    {
+        var region = new List<Token>(end - start);
+        for (var i = start; i < end; i++)
+        {
+            region.Add(tokens[i]);
+        }
+
+        var options = new ParseOptions
+        {
+            StartRule = node.RuleName,
+            End = EntryPointEnd.Complete,
+            MaxAmbiguitiesPerNode = _options.MaxAmbiguitiesPerNode,
+            SourceFile = _options.SourceFile,
+            KeywordCorrectionDistance = _options.KeywordCorrectionDistance,
+            Features = _options.Features,
+            Lazy = true
+        };
+        var result = _parser.Parse(_input, region, Array.Empty<Diagnostic>(), options, new ParseTreeBuilder(region, _lineIndex, _options.SourceFile), this);
+        if (result.Tree is NonTerminalNode root)
+        {
+            node.ProductionIndex = root.ProductionIndex;
+            node.Metadata["productionIndex"] = root.ProductionIndex;
+            foreach (var child in root.Children.ToList())
+            {
+                root.RemoveChild(child);
+                node.AddChild(child);
+            }
+        }
+
+        lock (_gate)
+        {
+            _diagnostics.AddRange(result.Diagnostics);
+        }
+
+        Interlocked.Increment(ref _materializedCount);
+    }
+}
+
+/// <summary>
+/// A rule node whose bracket group a lazy parse skipped. It has the span of the whole group, so outlines and
+/// folding ranges can use it as it is; its children are parsed the first time they are read, once, even when
+/// several threads read them at the same time. A region that does not parse keeps no children and reports its
+/// errors through <see cref="ParseResult.Diagnostics"/>.
+/// </summary>
+public sealed class DeferredNode : NonTerminalNode
+{
+    private readonly object _gate = new();
+    private readonly int _start;
+    private readonly int _end;
+    private DeferredRegions? _regions;
+    private IReadOnlyList<Token>? _tokens;
+    private volatile bool _materialized;
+
+    internal DeferredNode(DeferredRegions regions, string ruleName, IReadOnlyList<Token> tokens, int start, int end)
+        : base(ruleName)
+    {
+        _regions = regions;
+        _tokens = tokens;
+        _start = start;
+        _end = end;
+        Metadata["deferred"] = true;
+    }
+
+    /// <summary>
+    /// Gets a value indicating whether the region has been parsed.
+    /// </summary>
+    public bool IsMaterialized => _materialized;
+
+    /// <summary>
+    /// Parses the region now if it has not been parsed yet.
+    /// </summary>
+    public void Materialize()
+    {
+        if (_materialized)
+        {
+            return;
+        }
+
+        lock (_gate)
+        {
+            if (_materialized)
+            {
+                return;
+            }
+
+            _regions!.Parse(this, _tokens!, _start, _end);
+
+            // The tokens are only needed until the region is parsed.
+            _regions = null;
+            _tokens = null;
+            _materialized = true;
+        }
+    }
+
+    /// <inheritdoc />
+    protected override void EnsureChildren()
+    {
+        Materialize();
+    }
+}
@@ -0,0 +1,331 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// What a <see cref="DelimiterRule"/> does with its slot.
+/// </summary>
+public enum DelimiterAction
+{
+    /// <summary>
+    /// The token's delimiter is captured into the slot on a new frame of the delimiter stack.
+    /// </summary>
+    Capture,
+
+    /// <summary>
+    /// The token's delimiter must equal the slot of the innermost frame that holds it; the frame is popped.
+    /// </summary>
+    Match
+}
+
+/// <summary>
+/// A terminal that captures or matches a delimiter. The delimiter is the text of the first group of the
+/// terminal's pattern, or the whole token if the pattern has no group.
+/// </summary>
+/// <param name="Kind">The terminal key of the token, e.g. <c>OPEN_TAG</c>.</param>
+/// <param name="Action">Whether the token captures or matches the slot.</param>
+/// <param name="Slot">The name of the slot, e.g. <c>name</c>.</param>
+public sealed record DelimiterRule(string Kind, DelimiterAction Action, string Slot)
+{
+    /// <summary>
+    /// Gets the terminal key of the heredoc body token produced for a capture, or null if the capture has no
+    /// body. A heredoc body runs from the line after the capturing token to the line holding only the
+    /// delimiter, and is read as one token whatever it contains.
+    /// </summary>
+    public string? BodyKind { get; init; }
+
+    /// <summary>
+    /// Gets the terminal key of the token produced for the line that ends a heredoc body, or null.
+    /// </summary>
+    public string? EndKind { get; init; }
+}
+
+/// <summary>
+/// Delimiters chosen by the source rather than the grammar, like shell heredoc tags and XML element names.
+/// A capturing token stores its delimiter in a named slot on a frame of the lexer's delimiter stack, and a
+/// later matching token must repeat it; a mismatch is reported with both delimiters and both locations.
+/// </summary>
+/// <remarks>
+/// A policy is described by the "Delimiters" grammar metadata entry as <c>;</c>-separated clauses
+/// <c>terminal captures slot</c>, <c>terminal matches slot</c> or, for heredocs,
+/// <c>terminal captures slot lines body end</c>, with terminals in the grammar notation, e.g.
+/// <c>&lt;OPEN_TAG&gt; captures name; &lt;CLOSE_TAG&gt; matches name</c>. The body and end terminals of a heredoc
+/// have no patterns; the lexer produces them when it reaches the line after the capturing token, after the
+/// tokens on the rest of that line. A body ends at the first line that holds only the delimiter, ignoring
+/// leading tabs as <c>&lt;&lt;-</c> does, and several heredocs opened on one line are read in order.
+/// </remarks>
+public sealed class DelimiterPolicy
+{
+    /// <summary>
+    /// The metadata key declaring the delimiter rules.
+    /// </summary>
+    public const string DelimitersKey = "Delimiters";
+
+    private readonly Dictionary<string, DelimiterRule> _rules = new(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Initializes a new instance of the DelimiterPolicy class.
+    /// </summary>
+    /// <param name="rules">The delimiter rules, at most one per terminal.</param>
+    /// <exception cref="ArgumentException">A terminal has more than one rule.</exception>
+    public DelimiterPolicy(IEnumerable<DelimiterRule> rules)
+    {
+        ArgumentNullException.ThrowIfNull(rules);
+
+        foreach (var rule in rules)
+        {
+            if (!_rules.TryAdd(rule.Kind, rule))
+            {
+                throw new ArgumentException($"Terminal {rule.Kind} has more than one delimiter rule", nameof(rules));
+            }
+        }
+    }
+
+    /// <summary>
+    /// Gets the delimiter rules by terminal key.
+    /// </summary>
+    public IReadOnlyDictionary<string, DelimiterRule> Rules => _rules;
+
+    /// <summary>
+    /// Creates the policy described by a grammar's "Delimiters" metadata.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <returns>The policy, or null if the grammar declares no delimiters.</returns>
+    /// <exception cref="ArgumentException">A clause is malformed, names something that is not a terminal of the
+    /// grammar, or matches a slot that no terminal captures.</exception>
+    public static DelimiterPolicy? FromGrammar(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var declaration = grammar.Source.Metadata.GetValueOrDefault(DelimitersKey);
+        if (declaration == null)
+        {
+            return null;
+        }
+
+        var ruleNames = grammar.Rules.Select(r => r.Name).ToHashSet();
+        var terminals = grammar.GetTerminals().Select(t => t.Key).ToHashSet();
+        var rules = new List<DelimiterRule>();
+
+        foreach (var clause in declaration.Split(';', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var words = clause.Split(' ', StringSplitOptions.RemoveEmptyEntries);
+            DelimiterAction? action = words.Length > 1 ? words[1] switch
+            {
+                "captures" => DelimiterAction.Capture,
+                "matches" => DelimiterAction.Match,
+                _ => null
+            } : null;
+            var lines = action == DelimiterAction.Capture && words.Length == 6 && words[3] == "lines";
+            if (action == null || (words.Length != 3 && !lines))
+            {
+                throw new ArgumentException(
+                    $"Delimiter '{clause}' in grammar '{grammar.Name}' must be written as 'terminal captures slot [lines body end]' or 'terminal matches slot'",
+                    nameof(grammar));
+            }
+
+            rules.Add(new DelimiterRule(Terminal(words[0]), action.Value, words[2])
+            {
+                BodyKind = lines ? Terminal(words[4]) : null,
+                EndKind = lines ? Terminal(words[5]) : null
+            });
+        }
+
+        var captured = rules.Where(r => r.Action == DelimiterAction.Capture && r.BodyKind == null).Select(r => r.Slot).ToHashSet();
+        var unmatched = rules.FirstOrDefault(r => r.Action == DelimiterAction.Match && !captured.Contains(r.Slot));
+        if (unmatched != null)
+        {
+            throw new ArgumentException($"Delimiter slot '{unmatched.Slot}' in grammar '{grammar.Name}' is matched by {unmatched.Kind} but never captured", nameof(grammar));
+        }
+
+        return new DelimiterPolicy(rules);
+
+        string Terminal(string text)
+        {
+            var symbols = GrammarSymbol.ParseAlternative(text, ruleNames);
+            if (symbols.Count != 1 || !symbols[0].IsTerminal || !terminals.Contains(symbols[0].Key))
+            {
+                throw new ArgumentException($"Delimiter terminal '{text}' is not a terminal of grammar '{grammar.Name}'", nameof(grammar));
+            }
+
+            return symbols[0].Key;
+        }
+    }
+}
+
+/// <summary>
+/// The delimiter stack of one lexer run, with the heredocs whose bodies start on the next line.
+/// </summary>
+internal sealed class DelimiterState
+{
+    private readonly DelimiterPolicy _policy;
+    private readonly List<CapturedDelimiter> _frames = new();
+    private readonly List<CapturedDelimiter> _heredocs = new();
+    private readonly Queue<Token> _queued = new();
+    private int _bodyStart;
+
+    internal DelimiterState(DelimiterPolicy policy)
+    {
+        _policy = policy;
+    }
+
+    /// <summary>
+    /// Returns the next token read ahead with heredoc bodies, if any.
+    /// </summary>
+    public Token? Dequeue()
+    {
+        return _queued.Count > 0 ? _queued.Dequeue() : null;
+    }
+
+    /// <summary>
+    /// Determines whether the lexer has reached the bodies of pending heredocs.
+    /// </summary>
+    public bool IsBodyDue(int position, int length)
+    {
+        return _heredocs.Count > 0 && (position >= _bodyStart || position >= length);
+    }
+
+    /// <summary>
+    /// Shortens skipped layout so that it stops where pending heredoc bodies start.
+    /// </summary>
+    public int ClampSkip(int position, int skipped)
+    {
+        return _heredocs.Count > 0 && position < _bodyStart && position + skipped > _bodyStart ? _bodyStart - position : skipped;
+    }
+
+    /// <summary>
+    /// Captures or matches the delimiter of a token the lexer has just read.
+    /// </summary>
+    public void Accept(Token token, Match match, string input, LineIndex lineIndex, ICollection<Diagnostic> diagnostics)
+    {
+        if (!_policy.Rules.TryGetValue(token.Kind, out var rule))
+        {
+            return;
+        }
+
+        var group = match.Groups.Count > 1 && match.Groups[1].Success ? match.Groups[1] : match.Groups[0];
+        var delimiter = new CapturedDelimiter(rule, group.Value, lineIndex.GetPosition(group.Index, group.Length));
+
+        if (rule.Action == DelimiterAction.Capture)
+        {
+            if (rule.BodyKind == null)
+            {
+                _frames.Add(delimiter);
+                return;
+            }
+
+            if (_heredocs.Count == 0)
+            {
+                var lineEnd = input.IndexOf('\n', token.End);
+                _bodyStart = lineEnd < 0 ? input.Length : lineEnd + 1;
+            }
+
+            _heredocs.Add(delimiter);
+            return;
+        }
+
+        // Frames above the innermost one holding the slot were never closed; the parser reports them.
+        var open = _frames.FindLastIndex(f => f.Rule.Slot == rule.Slot);
+        if (open < 0)
+        {
+            return;
+        }
+
+        var opening = _frames[open];
+        _frames.RemoveRange(open, _frames.Count - open);
+        if (opening.Text != delimiter.Text)
+        {
+            var diagnostic = new Diagnostic
+            {
+                Code = DiagnosticCodes.MismatchedDelimiter,
+                Location = delimiter.Location
+            }.WithMessage(DiagnosticCodes.MismatchedDelimiter,
+                ("closing", delimiter.Text),
+                ("opening", opening.Text),
+                ("line", opening.Location.Line),
+                ("column", opening.Location.Column));
+            diagnostic.Data["expected"] = opening.Text;
+            diagnostic.Data["found"] = delimiter.Text;
+            diagnostic.Data["related"] = opening.Location;
+            diagnostics.Add(diagnostic);
+        }
+    }
+
+    /// <summary>
+    /// Reads the bodies of the pending heredocs from the position on, queueing a body token and an end token
+    /// for each, and leaves the position after the last end delimiter.
+    /// </summary>
+    public void ReadBodies(string input, ref int position, LineIndex lineIndex, ICollection<Diagnostic> diagnostics)
+    {
+        var start = position;
+        foreach (var heredoc in _heredocs)
+        {
+            var lineStart = start;
+            var end = -1;
+            while (lineStart < input.Length)
+            {
+                var lineEnd = input.IndexOf('\n', lineStart);
+                lineEnd = lineEnd < 0 ? input.Length : lineEnd;
+
+                var indent = lineStart;
+                while (indent < lineEnd && input[indent] == '\t')
+                {
+                    indent++;
+                }
+
+                if (input.AsSpan(indent, lineEnd - indent).TrimEnd().Equals(heredoc.Text, StringComparison.Ordinal))
+                {
+                    end = indent;
+                    break;
+                }
+
+                lineStart = lineEnd + 1;
+            }
+
+            if (end < 0)
+            {
+                _queued.Enqueue(new Token(heredoc.Rule.BodyKind!, input[start..], start));
+                var diagnostic = new Diagnostic
+                {
+                    Code = DiagnosticCodes.MismatchedDelimiter,
+                    Location = lineIndex.GetPosition(input.Length, 0)
+                }.WithMessage("E0013.heredoc", ("delimiter", heredoc.Text), ("line", heredoc.Location.Line), ("column", heredoc.Location.Column));
+                diagnostic.Data["expected"] = heredoc.Text;
+                diagnostic.Data["related"] = heredoc.Location;
+                diagnostics.Add(diagnostic);
+                position = start = input.Length;
+                continue;
+            }
+
+            _queued.Enqueue(new Token(heredoc.Rule.BodyKind!, input[start..lineStart], start));
+            _queued.Enqueue(new Token(heredoc.Rule.EndKind!, heredoc.Text, end));
+            position = end + heredoc.Text.Length;
+
+            var next = input.IndexOf('\n', position);
+            start = next < 0 ? input.Length : next + 1;
+        }
+
+        _heredocs.Clear();
+    }
+
+    private sealed record CapturedDelimiter(DelimiterRule Rule, string Text, SourcePosition Location);
+}
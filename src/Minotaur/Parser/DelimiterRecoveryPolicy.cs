@@ -0,0 +1,390 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Recovery from unclosed brackets that keeps the damage inside the block where the bracket was left open. When
+/// the parser gets stuck, the innermost bracket still open at the first outdent after it is closed there with a
+/// synthetic closing token, and parsing resumes from that point. An outdent is a line whose first token is indented
+/// no deeper than the line that opened the bracket, other than the matching closing bracket at the same indentation.
+/// Typing an unclosed <c>{</c> in a function thus closes it before the next line at its own indentation, and the
+/// functions after it parse as before.
+/// </summary>
+/// <remarks>
+/// The policy is enabled with the "DelimiterRecovery" grammar metadata entry set to <c>outdent</c>; <c>none</c> or
+/// no entry disables it. The brackets are the pairs the grammar's <see cref="GrammarEditorInfo"/> finds. A closing
+/// token is only inserted where the parser can accept it, and each inserted closer is reported as an
+/// <see cref="Diagnostics.DiagnosticCodes.UnclosedDelimiter"/> error at the outdent, whose "region" data is the text
+/// from the opening bracket to the inserted closer. Scannerless grammars have no token stream and ignore the policy.
+/// <para>
+/// The <c>stack</c> mode also repairs closing brackets the parser gets stuck at: it may take a closer that does not
+/// match the innermost open bracket for the matching one, drop a closer as extra, or close the innermost bracket
+/// before it. Each repair is weighed by the bracket errors left in the next "DelimiterRecoveryLookahead" tokens
+/// (256 by default): closers that match no open bracket or skip open ones, lines outdented past an open bracket,
+/// and brackets left open at the end of the input. The cheapest repair the parser can accept is made, and reported
+/// as <see cref="Diagnostics.DiagnosticCodes.UnclosedDelimiter"/>,
+/// <see cref="Diagnostics.DiagnosticCodes.MismatchedDelimiter"/> or
+/// <see cref="Diagnostics.DiagnosticCodes.UnexpectedDelimiter"/>.
+/// </para>
+/// </remarks>
+public sealed class DelimiterRecoveryPolicy
+{
+    /// <summary>
+    /// The metadata key enabling the policy: <c>outdent</c> or <c>none</c>.
+    /// </summary>
+    public const string RecoveryKey = "DelimiterRecovery";
+
+    /// <summary>
+    /// The metadata key setting how many tokens after a repair the <c>stack</c> mode weighs it over.
+    /// </summary>
+    public const string LookaheadKey = "DelimiterRecoveryLookahead";
+
+    /// <summary>
+    /// The number of tokens a repair is weighed over when the grammar does not set one.
+    /// </summary>
+    public const int DefaultLookahead = 256;
+
+    private readonly Dictionary<string, EditorPair> _pairs;
+    private readonly Dictionary<string, string> _closers;
+    private readonly HashSet<string> _closing;
+
+    /// <summary>
+    /// Initializes a new instance of the DelimiterRecoveryPolicy class.
+    /// </summary>
+    /// <param name="brackets">The bracket pairs, as the texts of their opening and closing literals.</param>
+    /// <param name="mode">Which repairs the policy makes.</param>
+    /// <param name="lookahead">The number of tokens the <c>stack</c> mode weighs a repair over.</param>
+    public DelimiterRecoveryPolicy(IEnumerable<EditorPair> brackets, DelimiterRecoveryMode mode = DelimiterRecoveryMode.Outdent, int lookahead = DefaultLookahead)
+    {
+        ArgumentNullException.ThrowIfNull(brackets);
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(lookahead);
+
+        Brackets = brackets.Where(p => !p.IsQuote).ToList();
+        Mode = mode;
+        Lookahead = lookahead;
+        _pairs = new Dictionary<string, EditorPair>(StringComparer.Ordinal);
+        _closers = new Dictionary<string, string>(StringComparer.Ordinal);
+        foreach (var pair in Brackets)
+        {
+            if (_pairs.TryAdd(Key(pair.Open), pair))
+            {
+                _closers.Add(Key(pair.Open), Key(pair.Close));
+            }
+        }
+
+        _closing = _closers.Values.ToHashSet(StringComparer.Ordinal);
+    }
+
+    /// <summary>
+    /// Gets the bracket pairs the policy closes.
+    /// </summary>
+    public IReadOnlyList<EditorPair> Brackets { get; }
+
+    /// <summary>
+    /// Gets which repairs the policy makes.
+    /// </summary>
+    public DelimiterRecoveryMode Mode { get; }
+
+    /// <summary>
+    /// Gets the number of tokens after a repair the <c>stack</c> mode counts bracket errors in.
+    /// </summary>
+    public int Lookahead { get; }
+
+    /// <summary>
+    /// Creates the policy described by a grammar's "DelimiterRecovery" metadata.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <returns>The policy, or null if the grammar does not enable it.</returns>
+    /// <exception cref="ArgumentException">The recovery mode or lookahead is invalid.</exception>
+    public static DelimiterRecoveryPolicy? FromGrammar(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var mode = grammar.Source.Metadata.GetValueOrDefault(RecoveryKey)?.Trim().ToLowerInvariant() switch
+        {
+            null or "none" => (DelimiterRecoveryMode?)null,
+            "outdent" => DelimiterRecoveryMode.Outdent,
+            "stack" => DelimiterRecoveryMode.Stack,
+            var other => throw new ArgumentException($"Delimiter recovery '{other}' in grammar '{grammar.Name}' must be 'outdent', 'stack' or 'none'", nameof(grammar))
+        };
+        if (mode == null)
+        {
+            return null;
+        }
+
+        var lookahead = DefaultLookahead;
+        if (grammar.Source.Metadata.GetValueOrDefault(LookaheadKey) is { } text
+            && (!int.TryParse(text.Trim(), NumberStyles.None, CultureInfo.InvariantCulture, out lookahead) || lookahead == 0))
+        {
+            throw new ArgumentException($"Delimiter recovery lookahead '{text}' in grammar '{grammar.Name}' must be a positive number of tokens", nameof(grammar));
+        }
+
+        var terminals = grammar.GetTerminals().Select(t => t.Key).ToHashSet();
+        return new DelimiterRecoveryPolicy(
+            GrammarEditorInfo.Create(grammar).Brackets.Where(p => terminals.Contains(Key(p.Open)) && terminals.Contains(Key(p.Close))),
+            mode.Value,
+            lookahead);
+    }
+
+    /// <summary>
+    /// Finds where to close the innermost bracket left open before a token the parser cannot accept.
+    /// </summary>
+    /// <param name="tokens">The token stream.</param>
+    /// <param name="position">The index of the token the parser is stuck at, or the token count at the end.</param>
+    /// <param name="input">The input text.</param>
+    /// <param name="opener">The index of the opening bracket to close.</param>
+    /// <returns>The index to insert the closing token at, no greater than <paramref name="position"/>, or -1.</returns>
+    internal int FindInsertion(IReadOnlyList<Token> tokens, int position, string input, out int opener)
+    {
+        var open = new Stack<(int Index, int Indent)>();
+        for (var i = 0; i <= position && i < tokens.Count; i++)
+        {
+            var token = tokens[i];
+            if (Indentation(input, token.Offset) is { } indent && open.Count > 0)
+            {
+                var (index, openIndent) = open.Peek();
+                if (indent < openIndent || indent == openIndent && token.Kind != _closers[tokens[index].Kind])
+                {
+                    opener = index;
+                    return i;
+                }
+            }
+
+            if (_closers.ContainsKey(token.Kind))
+            {
+                open.Push((i, LineIndentation(input, token.Offset)));
+            }
+            else if (open.Count > 0 && token.Kind == _closers[tokens[open.Peek().Index].Kind])
+            {
+                open.Pop();
+            }
+        }
+
+        // Brackets still open at the end of the input are closed there.
+        if (position == tokens.Count && open.Count > 0)
+        {
+            opener = open.Peek().Index;
+            return position;
+        }
+
+        opener = -1;
+        return -1;
+    }
+
+    /// <summary>
+    /// Lists the repairs of the brackets around a token the parser cannot accept, cheapest first.
+    /// </summary>
+    /// <param name="tokens">The token stream.</param>
+    /// <param name="position">The index of the token the parser is stuck at, or the token count at the end.</param>
+    /// <param name="input">The input text.</param>
+    /// <returns>The closer inserted at the outdent in the <c>outdent</c> mode; all candidates in the <c>stack</c> mode.</returns>
+    internal IReadOnlyList<DelimiterRepair> FindRepairs(IReadOnlyList<Token> tokens, int position, string input)
+    {
+        var repairs = new List<DelimiterRepair>();
+        var insertion = FindInsertion(tokens, position, input, out var opener);
+        if (insertion >= 0)
+        {
+            repairs.Add(new DelimiterRepair(DelimiterRepairKind.Insert, insertion, opener));
+        }
+
+        if (Mode != DelimiterRecoveryMode.Stack || position >= tokens.Count || !_closing.Contains(tokens[position].Kind))
+        {
+            return repairs;
+        }
+
+        var open = OpenBefore(tokens, position, input);
+        var kind = tokens[position].Kind;
+        if (open.Count > 0 && _closers[tokens[open[^1].Index].Kind] != kind)
+        {
+            var top = open[^1].Index;
+            repairs.Add(new DelimiterRepair(DelimiterRepairKind.Replace, position, top));
+
+            // A closer matching a bracket further out may just be missing the closers of the brackets inside it.
+            if (insertion != position && open.Exists(o => _closers[tokens[o.Index].Kind] == kind))
+            {
+                repairs.Add(new DelimiterRepair(DelimiterRepairKind.Insert, position, top));
+            }
+        }
+
+        // Dropping the last token would leave nothing to resume recognition at.
+        if (position + 1 < tokens.Count)
+        {
+            repairs.Add(new DelimiterRepair(DelimiterRepairKind.Delete, position, open.Count > 0 ? open[^1].Index : -1));
+        }
+
+        // OrderBy is stable, so ties keep the order above: insertions at the outdent first, drops last.
+        return repairs
+            .Select(r => r with { Cost = Weigh(tokens, r, input) })
+            .OrderBy(r => r.Cost)
+            .ToList();
+    }
+
+    internal EditorPair GetPair(string openerKind)
+    {
+        return _pairs[openerKind];
+    }
+
+    internal static Token CreateToken(EditorPair pair, int offset)
+    {
+        return new Token(Key(pair.Close), string.Empty, offset) { IsSynthetic = true };
+    }
+
+    internal static Token CreateReplacement(EditorPair pair, Token token)
+    {
+        return token with { Kind = Key(pair.Close) };
+    }
+
+    // One for the repair itself, plus the bracket errors left in the lookahead window after it. An outdent past an
+    // open bracket closes it, and a closer matching a bracket further out closes the ones inside it, each counting
+    // one error, so a single missing bracket is not counted again at every later line.
+    private int Weigh(IReadOnlyList<Token> tokens, DelimiterRepair repair, string input)
+    {
+        var open = OpenBefore(tokens, repair.Index, input);
+        var i = repair.Index;
+        switch (repair.Kind)
+        {
+            case DelimiterRepairKind.Insert:
+                open.RemoveAt(open.Count - 1);
+                break;
+            case DelimiterRepairKind.Replace:
+                open.RemoveAt(open.Count - 1);
+                i++;
+                break;
+            case DelimiterRepairKind.Delete:
+                i++;
+                break;
+        }
+
+        var errors = 1;
+        var end = Math.Min(tokens.Count, repair.Index + Lookahead);
+        for (; i < end; i++)
+        {
+            var token = tokens[i];
+            if (Indentation(input, token.Offset) is { } indent)
+            {
+                while (open.Count > 0 && (indent < open[^1].Indent || indent == open[^1].Indent && token.Kind != _closers[tokens[open[^1].Index].Kind]))
+                {
+                    open.RemoveAt(open.Count - 1);
+                    errors++;
+                }
+            }
+
+            if (_closers.ContainsKey(token.Kind))
+            {
+                open.Add((i, LineIndentation(input, token.Offset)));
+            }
+            else if (_closing.Contains(token.Kind))
+            {
+                var match = open.FindLastIndex(o => _closers[tokens[o.Index].Kind] == token.Kind);
+                errors += match < 0 ? 1 : open.Count - 1 - match;
+                if (match >= 0)
+                {
+                    open.RemoveRange(match, open.Count - match);
+                }
+            }
+        }
+
+        return end == tokens.Count ? errors + open.Count : errors;
+    }
+
+    // The brackets open before a token, innermost last, with the indentation of the lines that open them. A closer
+    // that does not match the innermost bracket is passed over.
+    private List<(int Index, int Indent)> OpenBefore(IReadOnlyList<Token> tokens, int position, string input)
+    {
+        var open = new List<(int Index, int Indent)>();
+        for (var i = 0; i < position; i++)
+        {
+            var kind = tokens[i].Kind;
+            if (_closers.ContainsKey(kind))
+            {
+                open.Add((i, LineIndentation(input, tokens[i].Offset)));
+            }
+            else if (open.Count > 0 && kind == _closers[tokens[open[^1].Index].Kind])
+            {
+                open.RemoveAt(open.Count - 1);
+            }
+        }
+
+        return open;
+    }
+
+    private static string Key(string literal)
+    {
+        return new GrammarSymbol(GrammarSymbolKind.Literal, literal).Key;
+    }
+
+    private static int LineStart(string input, int offset)
+    {
+        return offset == 0 ? 0 : input.LastIndexOf('\n', offset - 1) + 1;
+    }
+
+    private static int LineIndentation(string input, int offset)
+    {
+        var start = LineStart(input, offset);
+        var end = start;
+        while (end < offset && input[end] is ' ' or '\t')
+        {
+            end++;
+        }
+
+        return end - start;
+    }
+
+    // The indentation of a token that starts its line, or null if text precedes it on the line.
+    private static int? Indentation(string input, int offset)
+    {
+        var start = LineStart(input, offset);
+        var prefix = input.AsSpan(start, offset - start);
+        return prefix.IsWhiteSpace() ? prefix.Length : null;
+    }
+}
+
+/// <summary>
+/// Which repairs a <see cref="DelimiterRecoveryPolicy"/> makes.
+/// </summary>
+public enum DelimiterRecoveryMode
+{
+    /// <summary>
+    /// Closes a bracket left open at the next line indented no deeper than the line that opened it.
+    /// </summary>
+    Outdent,
+
+    /// <summary>
+    /// Also replaces or drops closing brackets that do not match the open ones, weighing each repair by the bracket
+    /// errors left after it.
+    /// </summary>
+    Stack
+}
+
+internal enum DelimiterRepairKind
+{
+    Insert,
+    Replace,
+    Delete
+}
+
+/// <summary>
+/// A repair of the token stream's brackets.
+/// </summary>
+/// <param name="Kind">Whether a closer is inserted, or the token at the index is replaced by the closer or dropped.</param>
+/// <param name="Index">The index of the token the repair is made at.</param>
+/// <param name="Opener">The index of the innermost bracket open there, or -1 if there is none.</param>
+/// <param name="Cost">The repair plus the bracket errors left after it.</param>
+internal readonly record struct DelimiterRepair(DelimiterRepairKind Kind, int Index, int Opener, int Cost = 0);
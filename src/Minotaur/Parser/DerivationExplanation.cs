@@ -0,0 +1,210 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The kind of decision a <see cref="DerivationStep"/> records.
+/// </summary>
+public enum DerivationDecisionKind
+{
+    /// <summary>
+    /// The node's tokens had a single derivation, so the alternative was the one the lookahead token led to.
+    /// </summary>
+    Alternative,
+
+    /// <summary>
+    /// The node's tokens had several derivations and the one using the earliest alternatives was kept.
+    /// </summary>
+    Ordering,
+
+    /// <summary>
+    /// The operator layer built the node for an operator declaration.
+    /// </summary>
+    Operator,
+
+    /// <summary>
+    /// An operator node became an operand of another because its operator binds tighter.
+    /// </summary>
+    Precedence,
+
+    /// <summary>
+    /// An operator node became an operand of another with the same precedence because of their associativity.
+    /// </summary>
+    Associativity
+}
+
+/// <summary>
+/// One decision that shaped a node of a parse tree.
+/// </summary>
+/// <param name="NodeId">The id of the node the decision shaped.</param>
+/// <param name="Rule">The rule of the node.</param>
+/// <param name="Kind">The kind of decision.</param>
+/// <param name="Reason">The decision in words.</param>
+public sealed record DerivationStep(Guid NodeId, string Rule, DerivationDecisionKind Kind, string Reason)
+{
+    /// <summary>
+    /// Gets the depth of the node below the explained node.
+    /// </summary>
+    public int Depth { get; init; }
+
+    /// <summary>
+    /// Gets the token that drove the decision: the token at which the alternative started, or the operator of the
+    /// operand grouped by precedence or associativity; null at the end of input.
+    /// </summary>
+    public string? Lookahead { get; init; }
+
+    /// <summary>
+    /// Gets where the lookahead token is.
+    /// </summary>
+    public SourcePosition? Location { get; init; }
+
+    /// <summary>
+    /// Gets the chosen alternative for <see cref="DerivationDecisionKind.Alternative"/> and
+    /// <see cref="DerivationDecisionKind.Ordering"/> decisions.
+    /// </summary>
+    public CompiledAlternative? Alternative { get; init; }
+
+    /// <summary>
+    /// Gets the number of derivations the node's tokens had.
+    /// </summary>
+    public int Derivations { get; init; } = 1;
+
+    /// <summary>
+    /// Gets the operator declarations responsible for operator decisions, the operand's first.
+    /// </summary>
+    public IReadOnlyList<OperatorEntry> Declarations { get; init; } = Array.Empty<OperatorEntry>();
+
+    /// <summary>
+    /// Returns the step as an indented line.
+    /// </summary>
+    /// <returns>The line.</returns>
+    public override string ToString()
+    {
+        return $"{new string(' ', Depth * 2)}<{Rule}> {Reason}";
+    }
+}
+
+/// <summary>
+/// Explains why a subtree of a parse tree has its shape: the alternative chosen for every node, the token that
+/// selected it, and the operator declarations that grouped its operator expressions.
+/// </summary>
+public sealed class DerivationExplanation
+{
+    internal DerivationExplanation(Guid nodeId, IReadOnlyList<DerivationStep> steps)
+    {
+        NodeId = nodeId;
+        Steps = steps;
+    }
+
+    /// <summary>
+    /// Gets the id of the explained node.
+    /// </summary>
+    public Guid NodeId { get; }
+
+    /// <summary>
+    /// Gets the decisions in preorder of the nodes they shaped.
+    /// </summary>
+    public IReadOnlyList<DerivationStep> Steps { get; }
+
+    /// <summary>
+    /// Returns the explanation as text, one indented line per step.
+    /// </summary>
+    /// <returns>The text.</returns>
+    public override string ToString()
+    {
+        var builder = new StringBuilder();
+        foreach (var step in Steps)
+        {
+            builder.AppendLine(step.ToString());
+        }
+
+        return builder.ToString();
+    }
+}
+
+/// <summary>
+/// The decisions recorded for the nodes of a parse tree when <see cref="ParseOptions.RecordDerivations"/> is set.
+/// </summary>
+internal sealed class DerivationLog
+{
+    private readonly Dictionary<Guid, List<DerivationStep>> _steps = new();
+
+    public void RecordAlternative(NonTerminalNode tree, SymbolForestNode node, IReadOnlyList<Token> tokens, LineIndex lineIndex, string? sourceFile)
+    {
+        var alternative = node.Packed[0].Alternative;
+        var token = node.Start < tokens.Count ? tokens[node.Start] : null;
+        var lookahead = token != null ? $"on '{token.Text}'" : "at the end of input";
+        var reason = node.IsAmbiguous
+            ? $"chose {alternative.Text} {lookahead}, the earliest of {node.Packed.Count} derivations"
+            : $"chose {alternative.Text} {lookahead}";
+
+        Add(tree, new DerivationStep(tree.Id, node.RuleName, node.IsAmbiguous ? DerivationDecisionKind.Ordering : DerivationDecisionKind.Alternative, reason)
+        {
+            Lookahead = token?.Text,
+            Location = token != null ? lineIndex.GetPosition(token.Offset, token.Length, sourceFile) : null,
+            Alternative = alternative,
+            Derivations = node.Packed.Count
+        });
+    }
+
+    public void Add(NonTerminalNode node, DerivationStep step)
+    {
+        if (!_steps.TryGetValue(node.Id, out var steps))
+        {
+            steps = new List<DerivationStep>();
+            _steps[node.Id] = steps;
+        }
+
+        steps.Add(step);
+    }
+
+    // A node that takes over the structure built for it replaces its own decisions with those of the structure.
+    public void Move(NonTerminalNode from, NonTerminalNode to)
+    {
+        _steps.Remove(to.Id);
+        if (_steps.Remove(from.Id, out var steps))
+        {
+            _steps[to.Id] = steps.Select(s => s with { NodeId = to.Id }).ToList();
+        }
+    }
+
+    public DerivationExplanation Explain(CognitiveGraphNode node)
+    {
+        var steps = new List<DerivationStep>();
+        var pending = new Stack<(CognitiveGraphNode Node, int Depth)>();
+        pending.Push((node, 0));
+        while (pending.Count > 0)
+        {
+            var (current, depth) = pending.Pop();
+            if (_steps.TryGetValue(current.Id, out var recorded))
+            {
+                steps.AddRange(recorded.Select(s => s with { Depth = depth }));
+            }
+
+            for (var i = current.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push((current.Children[i], depth + 1));
+            }
+        }
+
+        return new DerivationExplanation(node.Id, steps);
+    }
+}
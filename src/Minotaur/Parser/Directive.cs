@@ -0,0 +1,141 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// What a directive applies to.
+/// </summary>
+public enum DirectiveScope
+{
+    /// <summary>
+    /// The code node the directive is attached to: the code before it on its line, or else the code after it.
+    /// </summary>
+    NextNode,
+
+    /// <summary>
+    /// The whole file.
+    /// </summary>
+    File,
+
+    /// <summary>
+    /// The code from the directive up to the matching <see cref="RegionEnd"/> directive of the same name.
+    /// </summary>
+    RegionStart,
+
+    /// <summary>
+    /// The end of a region.
+    /// </summary>
+    RegionEnd
+}
+
+/// <summary>
+/// A directive read from a comment, such as <c>// minotaur: allow W0002</c>.
+/// </summary>
+public sealed class Directive
+{
+    internal Directive(DirectiveSyntax syntax, string name, DirectiveScope scope, IReadOnlyList<string> values, IReadOnlyDictionary<string, string> arguments, string comment, SourcePosition location)
+    {
+        Syntax = syntax;
+        Name = name;
+        Scope = scope;
+        Values = values;
+        Arguments = arguments;
+        Comment = comment;
+        Location = location;
+    }
+
+    /// <summary>
+    /// Gets the syntax the directive was written in.
+    /// </summary>
+    public DirectiveSyntax Syntax { get; }
+
+    /// <summary>
+    /// Gets the directive name.
+    /// </summary>
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets what the directive applies to.
+    /// </summary>
+    public DirectiveScope Scope { get; }
+
+    /// <summary>
+    /// Gets the bare arguments, in order.
+    /// </summary>
+    public IReadOnlyList<string> Values { get; }
+
+    /// <summary>
+    /// Gets the <c>key=value</c> arguments.
+    /// </summary>
+    public IReadOnlyDictionary<string, string> Arguments { get; }
+
+    /// <summary>
+    /// Gets the text of the comment the directive was read from.
+    /// </summary>
+    public string Comment { get; }
+
+    /// <summary>
+    /// Gets the location of the comment.
+    /// </summary>
+    public SourcePosition Location { get; }
+
+    /// <summary>
+    /// Gets the code node the directive is attached to: for <see cref="DirectiveScope.NextNode"/> directives the
+    /// node it applies to, for file directives the root, and for region boundaries the node the boundary precedes.
+    /// Null when the parse has no tree or no code follows.
+    /// </summary>
+    public CognitiveGraphNode? Target { get; internal set; }
+
+    /// <summary>
+    /// Returns the directive as it would be written after its prefix.
+    /// </summary>
+    /// <returns>The scope, name and arguments.</returns>
+    public override string ToString()
+    {
+        var scope = Scope switch
+        {
+            DirectiveScope.File when Syntax.Name == null => "file ",
+            DirectiveScope.RegionStart => "begin ",
+            DirectiveScope.RegionEnd => "end ",
+            _ => string.Empty
+        };
+
+        return scope + string.Join(" ", new[] { Name }.Concat(Values).Concat(Arguments.Select(a => $"{a.Key}={a.Value}")));
+    }
+}
+
+/// <summary>
+/// A matched pair of region directives.
+/// </summary>
+/// <param name="Name">The directive name.</param>
+/// <param name="Start">The directive that begins the region.</param>
+/// <param name="End">The directive that ends the region.</param>
+public sealed record DirectiveRegion(string Name, Directive Start, Directive End)
+{
+    /// <summary>
+    /// Determines whether an offset lies between the two directives.
+    /// </summary>
+    /// <param name="offset">The offset.</param>
+    /// <returns>True if the offset is after the beginning comment and before the ending one.</returns>
+    public bool Contains(int offset)
+    {
+        return offset >= Start.Location.Offset + Start.Location.Length && offset < End.Location.Offset;
+    }
+}
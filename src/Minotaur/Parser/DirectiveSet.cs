@@ -0,0 +1,335 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The directives of a parse, read from the comments between its tokens with the syntaxes its grammar declares.
+/// A directive applies to the node it is attached to: a comment after code on the same line attaches to the
+/// largest node ending there that starts on that line, and any other comment to the largest node starting at the
+/// next token that does not run on past that line's statement. Region directives are paired by name, innermost
+/// first. Malformed directives and unpaired region directives are reported as warnings and otherwise ignored.
+/// </summary>
+public sealed class DirectiveSet
+{
+    private DirectiveSet(List<Directive> directives, List<DirectiveRegion> regions, List<Diagnostic> diagnostics)
+    {
+        Directives = directives;
+        Regions = regions;
+        Diagnostics = diagnostics;
+    }
+
+    /// <summary>
+    /// Gets a set without directives.
+    /// </summary>
+    public static DirectiveSet Empty { get; } = new(new List<Directive>(), new List<DirectiveRegion>(), new List<Diagnostic>());
+
+    /// <summary>
+    /// Gets the well-formed directives in source order.
+    /// </summary>
+    public IReadOnlyList<Directive> Directives { get; }
+
+    /// <summary>
+    /// Gets the paired region directives, ordered by where they begin.
+    /// </summary>
+    public IReadOnlyList<DirectiveRegion> Regions { get; }
+
+    /// <summary>
+    /// Gets the warnings about malformed and unpaired directives.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics { get; }
+
+    /// <summary>
+    /// Reads the directives of a parse.
+    /// </summary>
+    /// <param name="parse">The parse result.</param>
+    /// <returns>The directives; empty if the grammar declares no directive syntax.</returns>
+    public static DirectiveSet Read(ParseResult parse)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+
+        if (parse.Grammar == null || parse.Grammar.Directives.Count == 0)
+        {
+            return Empty;
+        }
+
+        var lineIndex = new LineIndex(parse.Input);
+        var sourceFile = parse.Tree?.SourcePosition?.SourceFile;
+        var tokens = parse.Tokens.Where(t => !t.IsSynthetic).ToList();
+        var terminals = new Dictionary<int, CognitiveGraphNode>();
+        if (parse.Tree != null)
+        {
+            IndexTerminals(parse.Tree, terminals);
+        }
+
+        var directives = new List<Directive>();
+        var diagnostics = new List<Diagnostic>();
+        foreach (var (offset, text) in ReadComments(parse.Grammar, parse.Input, tokens))
+        {
+            var location = lineIndex.GetPosition(offset, text.Length, sourceFile);
+            foreach (var syntax in parse.Grammar.Directives)
+            {
+                foreach (var body in syntax.Match(text))
+                {
+                    var error = syntax.TryRead(body, out var scope, out var name, out var values, out var arguments);
+                    if (error != null)
+                    {
+                        diagnostics.Add(new Diagnostic
+                        {
+                            Code = DiagnosticCodes.MalformedDirective,
+                            Severity = DiagnosticSeverity.Warning,
+                            Location = location
+                        }.WithMessage(DiagnosticCodes.MalformedDirective, ("text", text.Trim()), ("problem", error)));
+                        continue;
+                    }
+
+                    var directive = new Directive(syntax, name, scope, values, arguments, text, location);
+                    directive.Target = scope == DirectiveScope.File
+                        ? parse.Tree
+                        : Attach(tokens, terminals, lineIndex, offset + text.Length, lineIndex.GetLineColumn(offset).Line, scope == DirectiveScope.NextNode);
+                    directives.Add(directive);
+                }
+            }
+        }
+
+        return new DirectiveSet(directives, PairRegions(directives, diagnostics), diagnostics);
+    }
+
+    /// <summary>
+    /// Gets the directives with a name.
+    /// </summary>
+    /// <param name="name">The directive name.</param>
+    /// <returns>The directives of every scope, in source order.</returns>
+    public IEnumerable<Directive> Get(string name)
+    {
+        return Directives.Where(d => d.Name == name);
+    }
+
+    /// <summary>
+    /// Gets the file-level directives with a name.
+    /// </summary>
+    /// <param name="name">The directive name.</param>
+    /// <returns>The directives, in source order.</returns>
+    public IEnumerable<Directive> GetFileDirectives(string name)
+    {
+        return Get(name).Where(d => d.Scope == DirectiveScope.File);
+    }
+
+    /// <summary>
+    /// Gets the directives with a name that apply at an offset: file directives, directives attached to a node
+    /// spanning the offset, and the beginning directives of regions containing it.
+    /// </summary>
+    /// <param name="name">The directive name.</param>
+    /// <param name="offset">The offset.</param>
+    /// <returns>The applying directives, outermost region first.</returns>
+    public IEnumerable<Directive> GetApplying(string name, int offset)
+    {
+        foreach (var directive in Get(name))
+        {
+            if (directive.Scope == DirectiveScope.File ||
+                (directive.Scope == DirectiveScope.NextNode && directive.Target?.SourcePosition is { } target &&
+                 offset >= target.Offset && offset < target.Offset + Math.Max(target.Length, 1)))
+            {
+                yield return directive;
+            }
+        }
+
+        foreach (var region in Regions.Where(r => r.Name == name && r.Contains(offset)))
+        {
+            yield return region.Start;
+        }
+    }
+
+    /// <summary>
+    /// Gets the directives with a name that apply to a node, which are those applying where it starts.
+    /// </summary>
+    /// <param name="name">The directive name.</param>
+    /// <param name="node">The node.</param>
+    /// <returns>The applying directives; only file directives if the node has no position.</returns>
+    public IEnumerable<Directive> GetApplying(string name, CognitiveGraphNode node)
+    {
+        ArgumentNullException.ThrowIfNull(node);
+
+        return node.SourcePosition != null
+            ? GetApplying(name, node.SourcePosition.Offset)
+            : GetFileDirectives(name);
+    }
+
+    /// <summary>
+    /// Finds the comments in the gaps between tokens.
+    /// </summary>
+    /// <param name="grammar">The grammar whose comment tokens are looked for.</param>
+    /// <param name="input">The source text.</param>
+    /// <param name="tokens">The tokens of the input, in order.</param>
+    /// <returns>The offset and text of each comment.</returns>
+    internal static IEnumerable<(int Offset, string Text)> ReadComments(CompiledGrammar grammar, string input, IReadOnlyList<Token> tokens)
+    {
+        var patterns = grammar.CommentPatterns;
+        if (patterns.Count == 0)
+        {
+            yield break;
+        }
+
+        var position = 0;
+        for (var i = 0; i <= tokens.Count; i++)
+        {
+            if (i < tokens.Count && tokens[i].IsSynthetic)
+            {
+                continue;
+            }
+
+            var end = i < tokens.Count ? tokens[i].Offset : input.Length;
+            while (position < end)
+            {
+                var length = patterns.Select(p => p.Match(input, position)).Max(m => m.Success ? m.Length : 0);
+                if (length > 0)
+                {
+                    yield return (position, input.Substring(position, length));
+                    position += length;
+                }
+                else
+                {
+                    position++;
+                }
+            }
+
+            if (i < tokens.Count)
+            {
+                position = Math.Max(position, tokens[i].End);
+            }
+        }
+    }
+
+    private static CognitiveGraphNode? Attach(List<Token> tokens, Dictionary<int, CognitiveGraphNode> terminals, LineIndex lineIndex, int end, int line, bool trailing)
+    {
+        var next = tokens.FindIndex(t => t.Offset >= end);
+        if (next < 0)
+        {
+            next = tokens.Count;
+        }
+
+        // A comment after code on its own line is about that code.
+        if (trailing && next > 0 && lineIndex.GetLineColumn(tokens[next - 1].Offset).Line == line &&
+            terminals.TryGetValue(tokens[next - 1].Offset, out var previous))
+        {
+            var node = previous;
+            while (node.Parent?.SourcePosition is { } parent &&
+                   parent.Offset + parent.Length == node.SourcePosition!.Offset + node.SourcePosition.Length &&
+                   lineIndex.GetLineColumn(parent.Offset).Line == line)
+            {
+                node = node.Parent;
+            }
+
+            return node;
+        }
+
+        if (next == tokens.Count || !terminals.TryGetValue(tokens[next].Offset, out var following))
+        {
+            return null;
+        }
+
+        // Otherwise it is about the largest construct the next token starts, short of one that goes on to
+        // further lines' siblings, so a comment before a statement does not cover the rest of the block.
+        var current = following;
+        while (current.Parent is { SourcePosition: { } start } parent && start.Offset == current.SourcePosition!.Offset &&
+               !HasSiblingOnLaterLine(parent, current))
+        {
+            current = parent;
+        }
+
+        return current;
+    }
+
+    private static bool HasSiblingOnLaterLine(CognitiveGraphNode parent, CognitiveGraphNode node)
+    {
+        var sibling = parent.Children
+            .SkipWhile(c => !ReferenceEquals(c, node))
+            .Skip(1)
+            .FirstOrDefault(c => c.SourcePosition is { Length: > 0 });
+        return sibling != null && sibling.SourcePosition!.Line > node.SourcePosition!.EndLine;
+    }
+
+    private static List<DirectiveRegion> PairRegions(List<Directive> directives, List<Diagnostic> diagnostics)
+    {
+        var regions = new List<DirectiveRegion>();
+        var open = new Dictionary<string, Stack<Directive>>(StringComparer.Ordinal);
+        foreach (var directive in directives)
+        {
+            if (directive.Scope == DirectiveScope.RegionStart)
+            {
+                if (!open.TryGetValue(directive.Name, out var stack))
+                {
+                    open[directive.Name] = stack = new Stack<Directive>();
+                }
+
+                stack.Push(directive);
+            }
+            else if (directive.Scope == DirectiveScope.RegionEnd)
+            {
+                if (open.TryGetValue(directive.Name, out var stack) && stack.Count > 0)
+                {
+                    regions.Add(new DirectiveRegion(directive.Name, stack.Pop(), directive));
+                }
+                else
+                {
+                    diagnostics.Add(Unmatched(directive, DiagnosticCodes.UnmatchedDirective));
+                }
+            }
+        }
+
+        foreach (var start in open.Values.SelectMany(s => s).OrderBy(d => d.Location.Offset))
+        {
+            diagnostics.Add(Unmatched(start, "W0010.begin"));
+        }
+
+        regions.Sort((a, b) => a.Start.Location.Offset.CompareTo(b.Start.Location.Offset));
+        return regions;
+    }
+
+    private static Diagnostic Unmatched(Directive directive, string key)
+    {
+        return new Diagnostic
+        {
+            Code = DiagnosticCodes.UnmatchedDirective,
+            Severity = DiagnosticSeverity.Warning,
+            Location = directive.Location
+        }.WithMessage(key, ("name", directive.Name));
+    }
+
+    private static void IndexTerminals(CognitiveGraphNode root, Dictionary<int, CognitiveGraphNode> terminals)
+    {
+        // Long lists nest as deeply as they are long, so the walk keeps its own stack.
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(root);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node is TerminalNode { SourcePosition: { Length: > 0 } position })
+            {
+                terminals.TryAdd(position.Offset, node);
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+    }
+}
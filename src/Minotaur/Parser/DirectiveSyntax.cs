@@ -0,0 +1,237 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A way of writing directives in comments, declared by a grammar. A syntax declared in the "Directives" header is a
+/// comment prefix such as <c>// minotaur:</c>, optionally followed by a regular expression the rest of the comment
+/// must match; that rest reads as an optional scope (<c>file</c>, <c>begin</c> or <c>end</c>), the directive name
+/// and its arguments, each either <c>key=value</c> or a bare value, with double quotes around values that contain
+/// spaces. The "FeaturePragma" header declares a syntax of its own, whose first group lists the features to enable.
+/// </summary>
+public sealed class DirectiveSyntax
+{
+    /// <summary>
+    /// The name of the file-level directives read by the feature pragma.
+    /// </summary>
+    public const string FeaturePragmaName = "enable-features";
+
+    /// <summary>
+    /// The name of the directive that suppresses warnings, e.g. <c>// minotaur: allow W0002</c>; without values it
+    /// suppresses every warning it applies to.
+    /// </summary>
+    public const string AllowName = "allow";
+
+    private static readonly Regex NamePattern = new(@"^[A-Za-z_][\w.-]*$", RegexOptions.CultureInvariant);
+    private static readonly Regex WordPattern = new(@"\G[\s,]*(?:(?<word>[^\s,=""]+)(?:=(?<value>""(?:[^""\\]|\\.)*""|[^\s,""]*))?|(?<word>""(?:[^""\\]|\\.)*""))", RegexOptions.CultureInvariant);
+
+    private readonly Regex _pattern;
+
+    private DirectiveSyntax(string? prefix, Regex pattern, Regex? arguments, string? name)
+    {
+        Prefix = prefix;
+        _pattern = pattern;
+        Arguments = arguments;
+        Name = name;
+    }
+
+    /// <summary>
+    /// Gets the comment prefix that introduces a directive, or null for the feature pragma.
+    /// </summary>
+    public string? Prefix { get; }
+
+    /// <summary>
+    /// Gets the pattern the text after the prefix must match, or null if any text in the directive form is accepted.
+    /// </summary>
+    public Regex? Arguments { get; }
+
+    /// <summary>
+    /// Gets the name of every directive of this syntax when it has a fixed one; such directives are file-level and
+    /// all of their arguments are bare values.
+    /// </summary>
+    public string? Name { get; }
+
+    /// <summary>
+    /// Parses the "Directives" header: entries separated by semicolons, each a quoted prefix optionally followed by
+    /// a slash-delimited pattern, e.g. <c>"// minotaur:" /(allow|begin|end) .*/</c>.
+    /// </summary>
+    /// <param name="header">The header value.</param>
+    /// <param name="grammarName">The grammar name, used in error messages.</param>
+    /// <returns>The declared syntaxes.</returns>
+    /// <exception cref="ArgumentException">An entry is malformed or its pattern is not a valid regular expression.</exception>
+    internal static List<DirectiveSyntax> ParseHeader(string header, string grammarName)
+    {
+        var syntaxes = new List<DirectiveSyntax>();
+        foreach (var entry in header.Split(';', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var close = entry.Length > 1 && entry[0] == '"' ? entry.IndexOf('"', 1) : -1;
+            if (close < 2)
+            {
+                throw new ArgumentException($"Directive syntax '{entry}' in grammar '{grammarName}' must start with a quoted comment prefix");
+            }
+
+            var prefix = entry[1..close];
+            var rest = entry[(close + 1)..].Trim();
+            Regex? arguments = null;
+            if (rest.Length > 0)
+            {
+                if (rest.Length < 2 || rest[0] != '/' || rest[^1] != '/')
+                {
+                    throw new ArgumentException($"Directive syntax '{entry}' in grammar '{grammarName}' must follow its prefix with a /pattern/ or nothing");
+                }
+
+                try
+                {
+                    arguments = new Regex($"^(?:{rest[1..^1]})$", RegexOptions.CultureInvariant);
+                }
+                catch (ArgumentException ex)
+                {
+                    throw new ArgumentException($"Directive syntax '{entry}' in grammar '{grammarName}' has an invalid pattern: {ex.Message}");
+                }
+            }
+
+            syntaxes.Add(ForPrefix(prefix, arguments));
+        }
+
+        return syntaxes;
+    }
+
+    /// <summary>
+    /// Creates the syntax of directives introduced by a comment prefix.
+    /// </summary>
+    internal static DirectiveSyntax ForPrefix(string prefix, Regex? arguments = null)
+    {
+        // Block comments end with a closer that is not part of the directive.
+        var pattern = new Regex($@"^{Regex.Escape(prefix)}(?<body>.*?)\s*(?:\*/)?\s*$", RegexOptions.CultureInvariant | RegexOptions.Singleline);
+        return new DirectiveSyntax(prefix, pattern, arguments, null);
+    }
+
+    /// <summary>
+    /// Creates the syntax of a feature pragma from its pattern.
+    /// </summary>
+    internal static DirectiveSyntax ForFeaturePragma(Regex pattern)
+    {
+        return new DirectiveSyntax(null, pattern, null, FeaturePragmaName);
+    }
+
+    /// <summary>
+    /// Finds the directive bodies in a comment: at most one for a prefix, which must start the comment, and one per
+    /// match for the feature pragma.
+    /// </summary>
+    internal IEnumerable<string> Match(string comment)
+    {
+        if (Prefix != null)
+        {
+            var match = _pattern.Match(comment);
+            return match.Success ? new[] { match.Groups["body"].Value.Trim() } : Array.Empty<string>();
+        }
+
+        return _pattern.Matches(comment).Select(m => m.Groups.Count > 1 ? m.Groups[1].Value : string.Empty);
+    }
+
+    /// <summary>
+    /// Reads a directive body into its scope, name and arguments.
+    /// </summary>
+    /// <returns>Null if the body is well formed; otherwise why it is not.</returns>
+    internal string? TryRead(string body, out DirectiveScope scope, out string name, out List<string> values, out Dictionary<string, string> arguments)
+    {
+        scope = Name != null ? DirectiveScope.File : DirectiveScope.NextNode;
+        name = Name ?? string.Empty;
+        values = new List<string>();
+        arguments = new Dictionary<string, string>(StringComparer.Ordinal);
+
+        if (Arguments != null && !Arguments.IsMatch(body))
+        {
+            return $"does not match the syntax /{Arguments.ToString()[4..^2]}/";
+        }
+
+        var words = new List<(string Word, string? Value)>();
+        var position = 0;
+        while (position < body.Length)
+        {
+            var match = WordPattern.Match(body, position);
+            if (!match.Success || match.Length == 0)
+            {
+                if (body[position..].All(c => char.IsWhiteSpace(c) || c == ','))
+                {
+                    break;
+                }
+
+                return $"has an unterminated quote or stray '=' at column {position + 1}";
+            }
+
+            words.Add((Unquote(match.Groups["word"].Value), match.Groups["value"].Success ? Unquote(match.Groups["value"].Value) : null));
+            position += match.Length;
+        }
+
+        if (Name != null)
+        {
+            values.AddRange(words.Select(w => w.Value == null ? w.Word : $"{w.Word}={w.Value}"));
+            return null;
+        }
+
+        if (words.Count > 1 && words[0].Value == null && words[0].Word is "file" or "begin" or "end")
+        {
+            scope = words[0].Word switch
+            {
+                "file" => DirectiveScope.File,
+                "begin" => DirectiveScope.RegionStart,
+                _ => DirectiveScope.RegionEnd
+            };
+            words.RemoveAt(0);
+        }
+
+        if (words.Count == 0)
+        {
+            return "has no name";
+        }
+
+        if (words[0].Value != null || !NamePattern.IsMatch(words[0].Word))
+        {
+            return $"has an invalid name '{words[0].Word}'";
+        }
+
+        name = words[0].Word;
+        foreach (var (word, value) in words.Skip(1))
+        {
+            if (value == null)
+            {
+                values.Add(word);
+            }
+            else if (value.Length == 0)
+            {
+                return $"gives no value for '{word}'";
+            }
+            else
+            {
+                arguments[word] = value;
+            }
+        }
+
+        return null;
+    }
+
+    private static string Unquote(string text)
+    {
+        return text.Length >= 2 && text[0] == '"' && text[^1] == '"'
+            ? Regex.Unescape(text[1..^1])
+            : text;
+    }
+}
@@ -0,0 +1,138 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A document of a multi-document input, parsed on its own.
+/// </summary>
+/// <param name="Index">The 0-based position of the document in the input.</param>
+/// <param name="Location">The span of the document's tokens in the whole input.</param>
+/// <param name="Parse">The parse of the document, with positions in the whole input.</param>
+public sealed record ParsedDocument(int Index, SourcePosition Location, ParseResult Parse);
+
+/// <summary>
+/// How an input holds several independent documents, from the grammar's "Documents" metadata entry, written as
+/// <c>&lt;statement&gt; ";"</c> for documents separated by a terminal or <c>&lt;record&gt; newline</c> for one
+/// document per line. Separators are found in the token stream without parsing, so an error in one document never
+/// stops the next from parsing; a separator inside brackets still separates, so documents cannot nest them.
+/// </summary>
+public sealed class DocumentPolicy
+{
+    /// <summary>
+    /// The separator that puts every document on a line of its own.
+    /// </summary>
+    public const string NewlineSeparator = "newline";
+
+    private DocumentPolicy(CompiledRule rule, GrammarSymbol? separator)
+    {
+        Rule = rule;
+        Separator = separator;
+    }
+
+    /// <summary>
+    /// Gets the rule each document is parsed against.
+    /// </summary>
+    public CompiledRule Rule { get; }
+
+    /// <summary>
+    /// Gets the terminal separating documents, or null if documents are separated by line breaks.
+    /// </summary>
+    public GrammarSymbol? Separator { get; }
+
+    /// <summary>
+    /// Returns the policy as written in the grammar.
+    /// </summary>
+    /// <returns>The "Documents" metadata entry.</returns>
+    public override string ToString()
+    {
+        return $"<{Rule.Name}> {Separator?.ToString() ?? NewlineSeparator}";
+    }
+
+    internal static DocumentPolicy? Parse(Grammar grammar, IReadOnlyDictionary<string, CompiledRule> rules, ISet<string> ruleNames)
+    {
+        var declaration = grammar.Metadata.GetValueOrDefault(CompiledGrammar.DocumentsKey);
+        if (declaration == null)
+        {
+            return null;
+        }
+
+        var parts = declaration.Trim().Split((char[]?)null, 2, StringSplitOptions.RemoveEmptyEntries);
+        var rule = parts.Length == 2 ? GrammarSymbol.ParseAlternative(parts[0], ruleNames) : Array.Empty<GrammarSymbol>();
+        var separator = parts.Length == 2 && !parts[1].Equals(NewlineSeparator, StringComparison.OrdinalIgnoreCase)
+            ? GrammarSymbol.ParseAlternative(parts[1], ruleNames)
+            : null;
+        if (rule.Count != 1 || rule[0].IsTerminal || separator is { Count: not 1 } || separator?[0].Kind is GrammarSymbolKind.Rule or GrammarSymbolKind.Pattern)
+        {
+            throw new ArgumentException($"Documents in grammar '{grammar.Name}' must be written as <rule> followed by a separator literal or token, or {NewlineSeparator}", nameof(grammar));
+        }
+
+        if (string.Equals(grammar.Metadata.GetValueOrDefault(CompiledGrammar.ScannerlessKey), "true", StringComparison.OrdinalIgnoreCase))
+        {
+            throw new ArgumentException($"Documents in grammar '{grammar.Name}' need a lexer to find separators; scannerless grammars cannot declare them", nameof(grammar));
+        }
+
+        return new DocumentPolicy(rules[rule[0].Name], separator?[0]);
+    }
+
+    /// <summary>
+    /// Splits a token stream into the token ranges of its documents, leaving out separators and empty documents.
+    /// </summary>
+    internal List<(int Start, int End)> Split(string input, IReadOnlyList<Token> tokens)
+    {
+        var ranges = new List<(int Start, int End)>();
+        var start = 0;
+        for (var i = 0; i <= tokens.Count; i++)
+        {
+            int next;
+            if (i == tokens.Count)
+            {
+                next = i;
+            }
+            else if (Separator != null)
+            {
+                if (tokens[i].Kind != Separator.Key)
+                {
+                    continue;
+                }
+
+                next = i + 1;
+            }
+            else
+            {
+                if (i == start || !input.AsSpan(tokens[i - 1].End, tokens[i].Offset - tokens[i - 1].End).Contains('\n'))
+                {
+                    continue;
+                }
+
+                next = i;
+            }
+
+            if (i > start)
+            {
+                ranges.Add((start, i));
+            }
+
+            start = next;
+        }
+
+        return ranges;
+    }
+}
@@ -0,0 +1,101 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// How much of the input an entry point must consume.
+/// </summary>
+public enum EntryPointEnd
+{
+    /// <summary>
+    /// The whole input must be a sentence of the entry rule.
+    /// </summary>
+    Complete,
+
+    /// <summary>
+    /// The longest prefix of the input that is a sentence of the entry rule is parsed and the rest is ignored,
+    /// e.g. for an expression typed into a watch window.
+    /// </summary>
+    Prefix
+}
+
+/// <summary>
+/// A named start symbol of a grammar, declared with the "EntryPoints" header as
+/// <c>name = rule [complete|prefix]; name = ...</c>. Each entry point has its own table: the rules reachable
+/// from its rule and the parser states of their alternatives. Tables refer to the grammar's compiled rules
+/// rather than copying them, so entry points that reach the same rules share their states.
+/// </summary>
+public sealed class EntryPoint
+{
+    internal EntryPoint(string name, CompiledRule rule, EntryPointEnd end, IReadOnlyList<CompiledRule> rules)
+    {
+        Name = name;
+        Rule = rule;
+        End = end;
+        Rules = rules;
+        StateCount = rules.SelectMany(r => r.Alternatives).Sum(a => a.Symbols.Count + 1);
+        Terminals = rules
+            .SelectMany(r => r.Alternatives)
+            .SelectMany(a => a.Symbols)
+            .Where(s => s.IsTerminal)
+            .Select(s => s.Key)
+            .Distinct()
+            .OrderBy(k => k, StringComparer.Ordinal)
+            .ToList();
+    }
+
+    /// <summary>
+    /// Gets the entry point name.
+    /// </summary>
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets the start rule of the entry point.
+    /// </summary>
+    public CompiledRule Rule { get; }
+
+    /// <summary>
+    /// Gets how much of the input the entry point must consume.
+    /// </summary>
+    public EntryPointEnd End { get; }
+
+    /// <summary>
+    /// Gets the rules reachable from the start rule, in grammar order.
+    /// </summary>
+    public IReadOnlyList<CompiledRule> Rules { get; }
+
+    /// <summary>
+    /// Gets the keys of the terminals that can occur in a sentence of the entry point, ordered by key.
+    /// </summary>
+    public IReadOnlyList<string> Terminals { get; }
+
+    /// <summary>
+    /// Gets the number of parser states in the entry point's table: one for every position of the dot in
+    /// every alternative of <see cref="Rules"/>.
+    /// </summary>
+    public int StateCount { get; }
+
+    /// <summary>
+    /// Returns the entry point as it is declared, e.g. <c>expression = expr prefix</c>.
+    /// </summary>
+    /// <returns>The declaration text.</returns>
+    public override string ToString()
+    {
+        return End == EntryPointEnd.Prefix ? $"{Name} = {Rule.Name} prefix" : $"{Name} = {Rule.Name}";
+    }
+}
@@ -0,0 +1,689 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The escape sequences of a string terminal, declared with a <c>// @escapes(json)</c> annotation after its token
+/// pattern: one of the built-in schemes <c>json</c>, <c>rust</c>, <c>c</c> and <c>python</c>, a custom table like
+/// <c>n = 0A, t = 09, \ = 5C</c> mapping the character after a backslash to the code point it stands for, or a
+/// built-in scheme extended by such a table, like <c>json, e = 1B</c>. The lexer checks every token of the terminal
+/// against its scheme and reports each malformed escape at its exact span without rejecting the token, and
+/// <see cref="Token.DecodedText"/> holds the value with the escapes replaced.
+/// </summary>
+/// <remarks>
+/// The body of a token is the text between its quotes, after any prefix such as <c>b</c>, <c>r</c> or <c>u8</c>; a
+/// token without quotes is all body. In the rust and python schemes a prefix holding an <c>r</c> makes a raw string
+/// without escapes, and rust raw strings may put <c>#</c>s around their quotes. Python strings may be triple-quoted.
+/// A <c>b</c> prefix makes a byte string, where rust allows <c>\x</c> up to FF instead of 7F and has no <c>\u</c>,
+/// and python has no <c>\u</c>, <c>\U</c> or <c>\N</c>. Escapes that are malformed are kept as written.
+/// </remarks>
+public sealed class EscapeScheme
+{
+    /// <summary>
+    /// The JSON scheme: <c>\" \\ \/ \b \f \n \r \t</c> and <c>\uXXXX</c>, where a surrogate pair written as two
+    /// escapes decodes to one character. A lone surrogate is kept and reported as a warning.
+    /// </summary>
+    public static readonly EscapeScheme Json = new("json", Dialect.Json, new Dictionary<char, string>());
+
+    /// <summary>
+    /// The Rust scheme: <c>\n \r \t \\ \0 \' \"</c>, <c>\xHH</c> up to 7F, <c>\u{...}</c> with one to six hexadecimal
+    /// digits naming a Unicode scalar value, and a backslash before a line break, which skips the break and the
+    /// whitespace after it.
+    /// </summary>
+    public static readonly EscapeScheme Rust = new("rust", Dialect.Rust, new Dictionary<char, string>());
+
+    /// <summary>
+    /// The C scheme: <c>\a \b \f \n \r \t \v \' \" \? \\</c>, one to three octal digits up to 377, <c>\x</c> with any
+    /// number of hexadecimal digits, <c>\uXXXX</c>, <c>\UXXXXXXXX</c> and a backslash before a line break.
+    /// </summary>
+    public static readonly EscapeScheme C = new("c", Dialect.C, new Dictionary<char, string>());
+
+    /// <summary>
+    /// The Python scheme: the escapes of C with exactly two digits after <c>\x</c>, plus <c>\N{name}</c>, which is kept
+    /// as written because decoding it needs the Unicode name table. Unknown escapes are kept as written and reported
+    /// as warnings, as Python does.
+    /// </summary>
+    public static readonly EscapeScheme Python = new("python", Dialect.Python, new Dictionary<char, string>());
+
+    private static readonly EscapeScheme[] BuiltIn = { Json, Rust, C, Python };
+
+    private readonly Dialect _dialect;
+    private readonly Dictionary<char, string> _table;
+
+    private EscapeScheme(string declaration, Dialect dialect, Dictionary<char, string> table)
+    {
+        Declaration = declaration;
+        _dialect = dialect;
+        _table = table;
+    }
+
+    private enum Dialect
+    {
+        None,
+        Json,
+        Rust,
+        C,
+        Python
+    }
+
+    /// <summary>
+    /// Gets the names of the built-in schemes.
+    /// </summary>
+    public static IReadOnlyList<string> Names { get; } = BuiltIn.Select(s => s.Name).ToArray();
+
+    /// <summary>
+    /// Gets the name of the built-in scheme the scheme extends, or "custom" for a table alone.
+    /// </summary>
+    public string Name => _dialect == Dialect.None ? "custom" : _dialect.ToString().ToLowerInvariant();
+
+    /// <summary>
+    /// Gets the scheme as written in an annotation, normalized, e.g. <c>json, e = 1B</c>.
+    /// </summary>
+    public string Declaration { get; }
+
+    /// <summary>
+    /// Gets a built-in scheme by name.
+    /// </summary>
+    /// <param name="name">The name, e.g. <c>json</c>; case is ignored.</param>
+    /// <returns>The scheme, or null if no built-in scheme has the name.</returns>
+    public static EscapeScheme? Get(string name)
+    {
+        ArgumentNullException.ThrowIfNull(name);
+
+        return BuiltIn.FirstOrDefault(s => string.Equals(s.Name, name.Trim(), StringComparison.OrdinalIgnoreCase));
+    }
+
+    /// <summary>
+    /// Reads the text of an escapes annotation: a built-in scheme's name, a table of <c>character = code point</c>
+    /// entries, or both separated by commas with the name first, optionally in parentheses. The character may be
+    /// quoted, like <c>'=' = 3D</c>, and the code point is hexadecimal.
+    /// </summary>
+    /// <param name="declaration">The annotation text after <c>@escapes</c>.</param>
+    /// <returns>The scheme.</returns>
+    /// <exception cref="FormatException">The text names no scheme or holds a malformed entry.</exception>
+    public static EscapeScheme Parse(string declaration)
+    {
+        ArgumentNullException.ThrowIfNull(declaration);
+
+        var text = declaration.Trim();
+        if (text.StartsWith('('))
+        {
+            text = text.EndsWith(')')
+                ? text[1..^1].Trim()
+                : throw new FormatException($"'{declaration}' has no closing parenthesis");
+        }
+
+        if (text.Length == 0)
+        {
+            throw new FormatException("the annotation names no escape scheme");
+        }
+
+        var dialect = Dialect.None;
+        var table = new Dictionary<char, string>();
+        var entries = new List<string>();
+        var parts = text.Split(',', StringSplitOptions.TrimEntries);
+        for (var i = 0; i < parts.Length; i++)
+        {
+            var part = parts[i];
+            var equals = part.Length > 1 ? part.IndexOf('=', 1) : -1;
+            if (equals < 0)
+            {
+                var scheme = Get(part) ?? throw new FormatException(
+                    $"'{part}' is not an escape scheme; expected {string.Join(", ", Names)} or entries like n = 0A");
+                if (i > 0)
+                {
+                    throw new FormatException($"the scheme '{part}' must come before the table entries");
+                }
+
+                dialect = scheme._dialect;
+                continue;
+            }
+
+            var key = part[..equals].Trim();
+            if (key.Length == 3 && key[0] == key[2] && key[0] is '\'' or '"')
+            {
+                key = key[1..2];
+            }
+
+            if (key.Length != 1)
+            {
+                throw new FormatException($"'{key}' in '{part}' is not a single character");
+            }
+
+            var value = part[(equals + 1)..].Trim();
+            if (!int.TryParse(value, NumberStyles.AllowHexSpecifier, CultureInfo.InvariantCulture, out var codePoint) ||
+                codePoint > 0x10FFFF || (codePoint >= 0xD800 && codePoint <= 0xDFFF))
+            {
+                throw new FormatException($"'{value}' in '{part}' is not the hexadecimal code point of a character");
+            }
+
+            if (!table.TryAdd(key[0], char.ConvertFromUtf32(codePoint)))
+            {
+                throw new FormatException($"'{key}' is mapped twice");
+            }
+
+            entries.Add($"{key} = {codePoint:X2}");
+        }
+
+        if (dialect != Dialect.None)
+        {
+            entries.Insert(0, dialect.ToString().ToLowerInvariant());
+        }
+
+        return new EscapeScheme(string.Join(", ", entries), dialect, table);
+    }
+
+    /// <summary>
+    /// Decodes the text of a token: the body between its quotes with the escapes replaced by what they stand for.
+    /// </summary>
+    /// <param name="text">The token text, including its quotes and prefix.</param>
+    /// <returns>The value and the problems with its escapes, at offsets in the text.</returns>
+    public DecodedString Decode(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var errors = new List<EscapeError>();
+        var (start, end, raw, bytes) = FindBody(text);
+        if (raw || text.IndexOf('\\', start, end - start) < 0)
+        {
+            return new DecodedString(text[start..end], errors);
+        }
+
+        var output = new StringBuilder(end - start);
+        var position = start;
+        while (position < end)
+        {
+            var backslash = text.IndexOf('\\', position, end - position);
+            if (backslash < 0)
+            {
+                output.Append(text, position, end - position);
+                break;
+            }
+
+            output.Append(text, position, backslash - position);
+            position = ReadEscape(text, backslash, end, bytes, output, errors);
+        }
+
+        return new DecodedString(output.ToString(), errors);
+    }
+
+    /// <summary>
+    /// Returns the declaration of the scheme.
+    /// </summary>
+    /// <returns>The normalized declaration.</returns>
+    public override string ToString()
+    {
+        return Declaration;
+    }
+
+    private (int Start, int End, bool Raw, bool Bytes) FindBody(string text)
+    {
+        var prefix = 0;
+        while (prefix < text.Length && (char.IsAsciiLetter(text[prefix]) || (prefix > 0 && char.IsAsciiDigit(text[prefix]))))
+        {
+            prefix++;
+        }
+
+        var letters = text[..prefix].ToLowerInvariant();
+        var raw = _dialect is Dialect.Rust or Dialect.Python && letters.Contains('r');
+        var hashes = 0;
+        while (raw && _dialect == Dialect.Rust && prefix + hashes < text.Length && text[prefix + hashes] == '#')
+        {
+            hashes++;
+        }
+
+        var open = prefix + hashes;
+        if (open >= text.Length || text[open] is not ('"' or '\''))
+        {
+            return (0, text.Length, false, false);
+        }
+
+        var quote = text[open];
+        var quotes = _dialect == Dialect.Python && open + 5 < text.Length && text[open + 1] == quote && text[open + 2] == quote ? 3 : 1;
+        var start = open + quotes;
+        var closing = new string(quote, quotes) + new string('#', hashes);
+        var end = text.Length - closing.Length >= start && text.EndsWith(closing, StringComparison.Ordinal)
+            ? text.Length - closing.Length
+            : text.Length;
+        return (start, end, raw, letters.Contains('b'));
+    }
+
+    private int ReadEscape(string text, int at, int end, bool bytes, StringBuilder output, List<EscapeError> errors)
+    {
+        if (at + 1 >= end)
+        {
+            return Keep(text, at, end - at, "incomplete", output, errors);
+        }
+
+        if (_table.TryGetValue(text[at + 1], out var replacement))
+        {
+            output.Append(replacement);
+            return at + 2;
+        }
+
+        return _dialect switch
+        {
+            Dialect.Json => ReadJsonEscape(text, at, end, output, errors),
+            Dialect.Rust => ReadRustEscape(text, at, end, bytes, output, errors),
+            Dialect.C => ReadCEscape(text, at, end, output, errors),
+            Dialect.Python => ReadPythonEscape(text, at, end, bytes, output, errors),
+            _ => Unknown(text, at, end, DiagnosticSeverity.Error, output, errors)
+        };
+    }
+
+    private static int ReadJsonEscape(string text, int at, int end, StringBuilder output, List<EscapeError> errors)
+    {
+        switch (text[at + 1])
+        {
+            case '"' or '\\' or '/':
+                output.Append(text[at + 1]);
+                return at + 2;
+            case 'b':
+                output.Append('\b');
+                return at + 2;
+            case 'f':
+                output.Append('\f');
+                return at + 2;
+            case 'n':
+                output.Append('\n');
+                return at + 2;
+            case 'r':
+                output.Append('\r');
+                return at + 2;
+            case 't':
+                output.Append('\t');
+                return at + 2;
+            case 'u':
+                var digits = HexDigits(text, at + 2, end, 4);
+                if (digits < 4)
+                {
+                    return Keep(text, at, 2 + digits, "digits", output, errors, count: 4);
+                }
+
+                var unit = (char)ReadHex(text, at + 2, 4);
+                if (char.IsHighSurrogate(unit) && at + 12 <= end && text[at + 6] == '\\' && text[at + 7] == 'u' &&
+                    HexDigits(text, at + 8, end, 4) == 4 && char.IsLowSurrogate((char)ReadHex(text, at + 8, 4)))
+                {
+                    output.Append(unit).Append((char)ReadHex(text, at + 8, 4));
+                    return at + 12;
+                }
+
+                if (char.IsSurrogate(unit))
+                {
+                    errors.Add(new EscapeError(at, 6, "lone", DiagnosticSeverity.Warning) { Value = ((int)unit).ToString("X4", CultureInfo.InvariantCulture) });
+                }
+
+                output.Append(unit);
+                return at + 6;
+            default:
+                return Unknown(text, at, end, DiagnosticSeverity.Error, output, errors);
+        }
+    }
+
+    private static int ReadRustEscape(string text, int at, int end, bool bytes, StringBuilder output, List<EscapeError> errors)
+    {
+        var next = text[at + 1];
+        switch (next)
+        {
+            case '\\' or '\'' or '"':
+                output.Append(next);
+                return at + 2;
+            case 'n':
+                output.Append('\n');
+                return at + 2;
+            case 'r':
+                output.Append('\r');
+                return at + 2;
+            case 't':
+                output.Append('\t');
+                return at + 2;
+            case '0':
+                output.Append('\0');
+                return at + 2;
+            case '\r' or '\n':
+                var position = at + 1;
+                while (position < end && text[position] is ' ' or '\t' or '\r' or '\n')
+                {
+                    position++;
+                }
+
+                return position;
+            case 'x':
+                var digits = HexDigits(text, at + 2, end, 2);
+                if (digits < 2)
+                {
+                    return Keep(text, at, 2 + digits, "digits", output, errors, count: 2);
+                }
+
+                var value = ReadHex(text, at + 2, 2);
+                if (value > 0x7F && !bytes)
+                {
+                    errors.Add(new EscapeError(at, 4, "byte", DiagnosticSeverity.Error) { Value = text.Substring(at + 2, 2).ToUpperInvariant() });
+                    output.Append(text, at, 4);
+                }
+                else
+                {
+                    output.Append((char)value);
+                }
+
+                return at + 4;
+            case 'u' when !bytes:
+                return ReadBracedEscape(text, at, end, output, errors);
+            default:
+                return Unknown(text, at, end, DiagnosticSeverity.Error, output, errors);
+        }
+    }
+
+    private static int ReadBracedEscape(string text, int at, int end, StringBuilder output, List<EscapeError> errors)
+    {
+        if (at + 2 >= end || text[at + 2] != '{')
+        {
+            return Keep(text, at, 2, "braces", output, errors);
+        }
+
+        var position = at + 3;
+        var digits = 0;
+        var value = 0;
+        while (position < end && (char.IsAsciiHexDigit(text[position]) || text[position] == '_'))
+        {
+            if (text[position] != '_')
+            {
+                digits++;
+                value = Math.Min(value * 16 + Convert.ToInt32(text[position].ToString(), 16), 0x110000);
+            }
+
+            position++;
+        }
+
+        var closed = position < end && text[position] == '}';
+        if (!closed || digits == 0 || digits > 6 || text[at + 3] == '_')
+        {
+            return Keep(text, at, (closed ? position + 1 : position) - at, "braces", output, errors);
+        }
+
+        return AppendScalar(text, at, position + 1 - at, value, output, errors);
+    }
+
+    private static int ReadCEscape(string text, int at, int end, StringBuilder output, List<EscapeError> errors)
+    {
+        var next = text[at + 1];
+        switch (next)
+        {
+            case '\'' or '"' or '?' or '\\':
+                output.Append(next);
+                return at + 2;
+            case '\r' or '\n':
+                return at + 1 + LineBreakLength(text, at + 1, end);
+            case >= '0' and <= '7':
+                return ReadOctalEscape(text, at, end, DiagnosticSeverity.Error, output, errors);
+            case 'x':
+                var digits = HexDigits(text, at + 2, end, int.MaxValue);
+                if (digits == 0)
+                {
+                    return Keep(text, at, 2, "digits", output, errors, count: 1);
+                }
+
+                return AppendCodePoint(text, at, 2 + digits, ReadHex(text, at + 2, digits), output, errors);
+            case 'u' or 'U':
+                var length = next == 'u' ? 4 : 8;
+                var found = HexDigits(text, at + 2, end, length);
+                if (found < length)
+                {
+                    return Keep(text, at, 2 + found, "digits", output, errors, count: length);
+                }
+
+                return AppendScalar(text, at, 2 + length, ReadHex(text, at + 2, length), output, errors);
+            default:
+                if (SimpleEscape(next) is { } simple)
+                {
+                    output.Append(simple);
+                    return at + 2;
+                }
+
+                return Unknown(text, at, end, DiagnosticSeverity.Error, output, errors);
+        }
+    }
+
+    private static int ReadPythonEscape(string text, int at, int end, bool bytes, StringBuilder output, List<EscapeError> errors)
+    {
+        var next = text[at + 1];
+        switch (next)
+        {
+            case '\'' or '"' or '\\':
+                output.Append(next);
+                return at + 2;
+            case '\r' or '\n':
+                return at + 1 + LineBreakLength(text, at + 1, end);
+            case >= '0' and <= '7':
+                return ReadOctalEscape(text, at, end, bytes ? DiagnosticSeverity.Error : DiagnosticSeverity.Warning, output, errors);
+            case 'x':
+                var digits = HexDigits(text, at + 2, end, 2);
+                if (digits < 2)
+                {
+                    return Keep(text, at, 2 + digits, "digits", output, errors, count: 2);
+                }
+
+                output.Append((char)ReadHex(text, at + 2, 2));
+                return at + 4;
+            case 'u' or 'U' when !bytes:
+                var length = next == 'u' ? 4 : 8;
+                var found = HexDigits(text, at + 2, end, length);
+                if (found < length)
+                {
+                    return Keep(text, at, 2 + found, "digits", output, errors, count: length);
+                }
+
+                return AppendCodePoint(text, at, 2 + length, ReadHex(text, at + 2, length), output, errors);
+            case 'N' when !bytes:
+                var close = at + 2 < end && text[at + 2] == '{' ? text.IndexOf('}', at + 3, end - at - 3) : -1;
+                if (close <= at + 3)
+                {
+                    var known = close < 0 ? (at + 2 < end && text[at + 2] == '{' ? end : at + 2) : close + 1;
+                    return Keep(text, at, known - at, "name", output, errors);
+                }
+
+                output.Append(text, at, close + 1 - at);
+                return close + 1;
+            default:
+                if (SimpleEscape(next) is { } simple)
+                {
+                    output.Append(simple);
+                    return at + 2;
+                }
+
+                return Unknown(text, at, end, DiagnosticSeverity.Warning, output, errors);
+        }
+    }
+
+    private static int ReadOctalEscape(string text, int at, int end, DiagnosticSeverity severity, StringBuilder output, List<EscapeError> errors)
+    {
+        var digits = 0;
+        var value = 0;
+        while (digits < 3 && at + 1 + digits < end && text[at + 1 + digits] is >= '0' and <= '7')
+        {
+            value = value * 8 + (text[at + 1 + digits] - '0');
+            digits++;
+        }
+
+        if (value > 0xFF)
+        {
+            errors.Add(new EscapeError(at, 1 + digits, "octal", severity) { Value = text.Substring(at + 1, digits) });
+        }
+
+        if (value > 0xFF && severity == DiagnosticSeverity.Error)
+        {
+            output.Append(text, at, 1 + digits);
+        }
+        else
+        {
+            output.Append((char)value);
+        }
+
+        return at + 1 + digits;
+    }
+
+    private static char? SimpleEscape(char next)
+    {
+        return next switch
+        {
+            'a' => '\a',
+            'b' => '\b',
+            'f' => '\f',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\v',
+            _ => null
+        };
+    }
+
+    // A code point that must be a Unicode scalar value, as \u{...} in Rust and \u and \U in C require.
+    private static int AppendScalar(string text, int at, int length, int value, StringBuilder output, List<EscapeError> errors)
+    {
+        if (value >= 0xD800 && value <= 0xDFFF)
+        {
+            errors.Add(new EscapeError(at, length, "surrogate", DiagnosticSeverity.Error) { Value = value.ToString("X4", CultureInfo.InvariantCulture) });
+            output.Append(text, at, length);
+            return at + length;
+        }
+
+        return AppendCodePoint(text, at, length, value, output, errors);
+    }
+
+    private static int AppendCodePoint(string text, int at, int length, int value, StringBuilder output, List<EscapeError> errors)
+    {
+        if (value > 0x10FFFF)
+        {
+            var digits = text.Substring(at + 2, length - 2).Trim('{', '}').Replace("_", string.Empty).TrimStart('0');
+            errors.Add(new EscapeError(at, length, "range", DiagnosticSeverity.Error) { Value = digits.ToUpperInvariant() });
+            output.Append(text, at, length);
+        }
+        else if (value < 0x10000)
+        {
+            output.Append((char)value);
+        }
+        else
+        {
+            output.Append(char.ConvertFromUtf32(value));
+        }
+
+        return at + length;
+    }
+
+    private static int Unknown(string text, int at, int end, DiagnosticSeverity severity, StringBuilder output, List<EscapeError> errors)
+    {
+        var length = char.IsHighSurrogate(text[at + 1]) && at + 2 < end && char.IsLowSurrogate(text[at + 2]) ? 3 : 2;
+        errors.Add(new EscapeError(at, length, "unknown", severity));
+        output.Append(text, at, length);
+        return at + length;
+    }
+
+    private static int Keep(string text, int at, int length, string reason, StringBuilder output, List<EscapeError> errors, int count = 0)
+    {
+        errors.Add(new EscapeError(at, length, reason, DiagnosticSeverity.Error) { Count = count });
+        output.Append(text, at, length);
+        return at + length;
+    }
+
+    private static int LineBreakLength(string text, int position, int end)
+    {
+        return text[position] == '\r' && position + 1 < end && text[position + 1] == '\n' ? 2 : 1;
+    }
+
+    private static int HexDigits(string text, int start, int end, int limit)
+    {
+        var count = 0;
+        while (count < limit && start + count < end && char.IsAsciiHexDigit(text[start + count]))
+        {
+            count++;
+        }
+
+        return count;
+    }
+
+    // Values beyond U+10FFFF are capped just past it, which is all callers need to report them.
+    private static int ReadHex(string text, int start, int length)
+    {
+        var value = 0;
+        for (var i = start; i < start + length; i++)
+        {
+            value = Math.Min(value * 16 + Convert.ToInt32(text[i].ToString(), 16), 0x110000);
+        }
+
+        return value;
+    }
+}
+
+/// <summary>
+/// The value of a string token decoded by an <see cref="EscapeScheme"/>.
+/// </summary>
+/// <param name="Value">The body of the token with its escapes replaced; malformed escapes are kept as written.</param>
+/// <param name="Errors">The problems with the escapes, in order.</param>
+public sealed record DecodedString(string Value, IReadOnlyList<EscapeError> Errors)
+{
+    /// <summary>
+    /// Gets a value indicating whether every escape is valid, allowing those that only warrant a warning.
+    /// </summary>
+    public bool IsValid => Errors.All(e => e.Severity != DiagnosticSeverity.Error);
+}
+
+/// <summary>
+/// A problem with one escape sequence of a string token.
+/// </summary>
+/// <param name="Offset">The offset of the escape's backslash in the token text.</param>
+/// <param name="Length">The length of the escape as far as it was read.</param>
+/// <param name="Reason">What is wrong: "unknown", "digits", "braces", "name", "range", "surrogate", "lone", "byte",
+/// "octal" or "incomplete".</param>
+/// <param name="Severity">Error, or Warning for escapes the scheme accepts but that are suspicious, like a lone
+/// surrogate in JSON.</param>
+public sealed record EscapeError(int Offset, int Length, string Reason, DiagnosticSeverity Severity)
+{
+    /// <summary>
+    /// Gets the number of hexadecimal digits expected, for the "digits" reason.
+    /// </summary>
+    public int Count { get; init; }
+
+    /// <summary>
+    /// Gets the value the reason refers to: the hexadecimal code point or byte, or the octal digits.
+    /// </summary>
+    public string Value { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Creates the diagnostic reporting the problem in a token.
+    /// </summary>
+    /// <param name="token">The token.</param>
+    /// <param name="lineIndex">The line index of the input.</param>
+    /// <returns>An <see cref="DiagnosticCodes.InvalidEscape"/> error or <see cref="DiagnosticCodes.SuspiciousEscape"/>
+    /// warning spanning the escape.</returns>
+    internal Diagnostic ToDiagnostic(Token token, LineIndex lineIndex)
+    {
+        var code = Severity == DiagnosticSeverity.Error ? DiagnosticCodes.InvalidEscape : DiagnosticCodes.SuspiciousEscape;
+        return new Diagnostic
+        {
+            Code = code,
+            Severity = Severity,
+            Location = lineIndex.GetPosition(token.Offset + Offset, Length),
+            Data = { ["escape"] = token.Text.Substring(Offset, Length), ["scheme"] = token.Escapes?.Name ?? string.Empty }
+        }.WithMessage(code, ("escape", token.Text.Substring(Offset, Length)), ("reason", Reason), ("count", Count), ("value", Value));
+    }
+}
@@ -0,0 +1,209 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Describes what a failed parse expected in the words of the grammar's <c>// @expected("a type")</c>
+/// annotations. A terminal expected by an item is described by an annotated rule when the rule dominates the item
+/// at the error position: the rule was predicted there, and every chain of rules the item was predicted through,
+/// up to the first one that had already read input, passes through it. Of several such rules the outermost one
+/// is used, so an error at the start of a type inside a statement names the type unless the statement itself
+/// starts there. Terminals some item expects without a dominating rule are listed as they are, using their token
+/// pattern's annotation if it has one.
+/// </summary>
+internal static class ExpectedPhrases
+{
+    /// <summary>
+    /// Reads the text of an expected annotation: a phrase, optionally quoted and optionally in parentheses.
+    /// </summary>
+    /// <param name="text">The annotation text after <c>@expected</c>.</param>
+    /// <returns>The phrase.</returns>
+    /// <exception cref="FormatException">The text holds no phrase.</exception>
+    public static string Parse(string text)
+    {
+        var phrase = text.Trim();
+        if (phrase.StartsWith('('))
+        {
+            phrase = phrase.EndsWith(')')
+                ? phrase[1..^1].Trim()
+                : throw new FormatException($"'{text}' has no closing parenthesis");
+        }
+
+        if (phrase.Length >= 2 && phrase[0] == '"' && phrase[^1] == '"')
+        {
+            phrase = phrase[1..^1].Replace("\\\"", "\"").Trim();
+        }
+
+        return phrase.Length > 0 ? phrase : throw new FormatException("the annotation names nothing that is expected");
+    }
+
+    /// <summary>
+    /// Describes the terminals expected at the set where a parse failed.
+    /// </summary>
+    /// <param name="chart">The chart of the failed parse.</param>
+    /// <param name="position">The index of the set.</param>
+    /// <param name="grammar">The grammar.</param>
+    /// <returns>The phrases of the dominating rules in order of their first expected terminal, followed by the
+    /// remaining terminals, both as they appear in messages; empty when the grammar annotates nothing that
+    /// applies, in which case messages list the terminals.</returns>
+    public static List<string> Describe(GeneralizedParser.EarleySet?[] chart, int position, CompiledGrammar grammar)
+    {
+        var described = new List<string>();
+        if (!grammar.HasExpectedPhrases)
+        {
+            return described;
+        }
+
+        var set = chart[position]!;
+        var dominators = new Dictionary<int, HashSet<int>>();
+        var phrases = new Dictionary<string, List<string>>(StringComparer.Ordinal);
+        var undominated = new HashSet<string>(StringComparer.Ordinal);
+
+        foreach (var item in set.Items.Where(it => it.Dot < it.Alternative.Symbols.Count && it.Alternative.RuleIndices[it.Dot] < 0))
+        {
+            var key = item.Alternative.Symbols[item.Dot].Key;
+            var rule = item.Origin == position ? Outermost(Dominators(set, position, item.Alternative.Rule.Index, grammar, dominators), dominators) : -1;
+            if (rule < 0)
+            {
+                undominated.Add(key);
+                continue;
+            }
+
+            if (!phrases.TryGetValue(key, out var list))
+            {
+                list = new List<string>();
+                phrases[key] = list;
+            }
+
+            list.Add(grammar.Rules[rule].ExpectedPhrase!);
+        }
+
+        if (phrases.Count == 0 && !undominated.Any(k => grammar.GetExpectedPhrase(k) != null))
+        {
+            return described;
+        }
+
+        var remaining = new List<string>();
+        foreach (var key in phrases.Keys.Concat(undominated).Distinct().OrderBy(k => k, StringComparer.Ordinal))
+        {
+            if (undominated.Contains(key))
+            {
+                remaining.Add(grammar.GetExpectedPhrase(key) ?? key);
+            }
+            else
+            {
+                described.AddRange(phrases[key]);
+            }
+        }
+
+        return described.Concat(remaining).Distinct().ToList();
+    }
+
+    // The annotated rules every chain from a rule predicted at the position passes through before reaching an item
+    // that had already read input, including the rule itself. The rules predicted at one position and the items
+    // waiting for them form a graph, possibly cyclic, so the sets are computed as a fixed point: each rule's set is
+    // the intersection of its callers' sets plus itself, starting from every rule and shrinking.
+    private static HashSet<int> Dominators(GeneralizedParser.EarleySet set, int position, int rule, CompiledGrammar grammar, Dictionary<int, HashSet<int>> known)
+    {
+        if (known.TryGetValue(rule, out var found))
+        {
+            return found;
+        }
+
+        var callers = new Dictionary<int, List<int>>();
+        var exits = new HashSet<int>();
+        var pending = new Stack<int>();
+        pending.Push(rule);
+        callers[rule] = new List<int>();
+        while (pending.Count > 0)
+        {
+            var current = pending.Pop();
+            if (!set.WaitingFor.TryGetValue(current, out var waiting) || waiting.Count == 0)
+            {
+                exits.Add(current);
+                continue;
+            }
+
+            foreach (var caller in waiting)
+            {
+                if (caller.Origin != position)
+                {
+                    exits.Add(current);
+                    continue;
+                }
+
+                var callerRule = caller.Alternative.Rule.Index;
+                callers[current].Add(callerRule);
+                if (!callers.ContainsKey(callerRule))
+                {
+                    callers[callerRule] = new List<int>();
+                    pending.Push(callerRule);
+                }
+            }
+        }
+
+        var annotated = callers.Keys.Where(r => grammar.Rules[r].ExpectedPhrase != null).ToHashSet();
+        var sets = callers.Keys.ToDictionary(r => r, _ => new HashSet<int>(annotated));
+        bool changed;
+        do
+        {
+            changed = false;
+            foreach (var (current, next) in callers)
+            {
+                var result = exits.Contains(current) ? new HashSet<int>() : null;
+                foreach (var caller in next)
+                {
+                    if (result == null)
+                    {
+                        result = new HashSet<int>(sets[caller]);
+                    }
+                    else
+                    {
+                        result.IntersectWith(sets[caller]);
+                    }
+                }
+
+                result ??= new HashSet<int>();
+                if (annotated.Contains(current))
+                {
+                    result.Add(current);
+                }
+
+                if (!result.SetEquals(sets[current]))
+                {
+                    sets[current] = result;
+                    changed = true;
+                }
+            }
+        }
+        while (changed);
+
+        foreach (var (current, dominating) in sets)
+        {
+            known.TryAdd(current, dominating);
+        }
+
+        return sets[rule];
+    }
+
+    // Dominators lie on every chain, so they are ordered; the outermost is dominated by none of the others.
+    private static int Outermost(HashSet<int> dominating, Dictionary<int, HashSet<int>> known)
+    {
+        return dominating.Count == 0 ? -1 : dominating.MinBy(r => known[r].Count);
+    }
+}
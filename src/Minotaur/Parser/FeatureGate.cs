@@ -0,0 +1,97 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Decides which feature-guarded alternatives a parse may reduce and remembers the first one it refused, so
+/// a failed parse can name the feature its input needed.
+/// </summary>
+internal sealed class FeatureGate
+{
+    private readonly HashSet<string> _enabled;
+
+    private FeatureGate(HashSet<string> enabled)
+    {
+        _enabled = enabled;
+    }
+
+    /// <summary>
+    /// Gets the refused reduction with the earliest origin, or null if none was refused.
+    /// </summary>
+    public FeatureRefusal? Refusal { get; private set; }
+
+    /// <summary>
+    /// Creates the gate for a parse, or null if the grammar declares no features.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <param name="enabled">The features enabled by the caller.</param>
+    /// <param name="input">The input, whose pragmas enable further features.</param>
+    /// <param name="tokens">The tokens of the input, between which its comments are.</param>
+    /// <returns>The gate, or null.</returns>
+    public static FeatureGate? Create(CompiledGrammar grammar, IEnumerable<string>? enabled, string input, IReadOnlyList<Token> tokens)
+    {
+        if (grammar.Features.Count == 0)
+        {
+            return null;
+        }
+
+        var features = new HashSet<string>(enabled ?? Array.Empty<string>());
+        features.UnionWith(grammar.ReadFeaturePragmas(input, tokens));
+        return new FeatureGate(features);
+    }
+
+    /// <summary>
+    /// Determines whether an alternative may be reduced.
+    /// </summary>
+    /// <param name="alternative">The alternative.</param>
+    /// <returns>True if the alternative is unguarded or its feature is enabled.</returns>
+    public bool IsEnabled(CompiledAlternative alternative)
+    {
+        return alternative.Feature == null || _enabled.Contains(alternative.Feature);
+    }
+
+    /// <summary>
+    /// Determines whether a completed alternative must not be reduced, remembering it if so.
+    /// </summary>
+    /// <param name="alternative">The completed alternative.</param>
+    /// <param name="origin">The position the alternative started at.</param>
+    /// <param name="end">The position it ended at.</param>
+    /// <returns>True if the alternative's feature is disabled.</returns>
+    public bool Refuses(CompiledAlternative alternative, int origin, int end)
+    {
+        if (IsEnabled(alternative))
+        {
+            return false;
+        }
+
+        if (Refusal == null || origin < Refusal.Value.Origin)
+        {
+            Refusal = new FeatureRefusal(alternative, origin, end);
+        }
+
+        return true;
+    }
+}
+
+/// <summary>
+/// A reduction a <see cref="FeatureGate"/> refused.
+/// </summary>
+/// <param name="Alternative">The guarded alternative.</param>
+/// <param name="Origin">The position the alternative started at.</param>
+/// <param name="End">The position it ended at.</param>
+internal readonly record struct FeatureRefusal(CompiledAlternative Alternative, int Origin, int End);
@@ -0,0 +1,96 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A range of lines an editor can fold.
+/// </summary>
+/// <param name="StartLine">The 1-based line the range starts on, which stays visible when folded.</param>
+/// <param name="EndLine">The 1-based line the range ends on.</param>
+/// <param name="Kind">What the range holds, <see cref="FoldingProvider.RegionKind"/> or <see cref="FoldingProvider.CommentKind"/>.</param>
+public sealed record FoldingRange(int StartLine, int EndLine, string Kind);
+
+/// <summary>
+/// Finds the foldable ranges of a parse: every rule node and every comment token that spans more than one line,
+/// except the inner nodes of left-recursive lists. Where several ranges start on the same line, only the outermost
+/// is kept. It needs nothing but the tree, so it
+/// works alike for grammar parses and for the regions of the <see cref="UniversalFallbackAnalyzer"/>.
+/// </summary>
+public static class FoldingProvider
+{
+    /// <summary>
+    /// The kind of a range folding a construct.
+    /// </summary>
+    public const string RegionKind = "region";
+
+    /// <summary>
+    /// The kind of a range folding a comment.
+    /// </summary>
+    public const string CommentKind = "comment";
+
+    /// <summary>
+    /// Gets the foldable ranges of a parse.
+    /// </summary>
+    /// <param name="parse">The parse of the file.</param>
+    /// <returns>The ranges in order of their start lines, or none if the parse has no tree.</returns>
+    public static IReadOnlyList<FoldingRange> GetFoldingRanges(ParseResult parse)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+
+        var ranges = new List<FoldingRange>();
+        if (parse.Tree == null)
+        {
+            return ranges;
+        }
+
+        var startLines = new HashSet<int>();
+        var stack = new Stack<CognitiveGraphNode>();
+        stack.Push(parse.Tree);
+        while (stack.Count > 0)
+        {
+            // A region a lazy parse deferred is folded by its span without being parsed.
+            var node = stack.Pop();
+            for (var i = node is DeferredNode { IsMaterialized: false } ? -1 : node.Children.Count - 1; i >= 0; i--)
+            {
+                stack.Push(node.Children[i]);
+            }
+
+            // The root spans the whole file and is not folded, nor is a list node that a node of its rule continues.
+            if (node == parse.Tree || node.SourcePosition is not { } position || position.EndLine <= position.Line ||
+                (node is NonTerminalNode { Parent: NonTerminalNode parent } list && parent.RuleName == list.RuleName && parent.SourcePosition?.Offset == position.Offset))
+            {
+                continue;
+            }
+
+            var kind = node switch
+            {
+                NonTerminalNode => RegionKind,
+                TerminalNode terminal when terminal.TokenType.Contains("COMMENT", StringComparison.OrdinalIgnoreCase) => CommentKind,
+                _ => null
+            };
+            if (kind != null && startLines.Add(position.Line))
+            {
+                ranges.Add(new FoldingRange(position.Line, position.EndLine, kind));
+            }
+        }
+
+        return ranges.OrderBy(r => r.StartLine).ToList();
+    }
+}
@@ -0,0 +1,1287 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Collections.Concurrent;
+using System.Runtime.InteropServices;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Monitoring;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A generalized (Earley) parser over a compiled grammar. Handles any context-free grammar,
+/// including left-recursive and ambiguous ones, and produces a shared packed parse forest.
+/// Scannerless grammars are parsed over characters, with terminals matched against the input as they are expected.
+/// </summary>
+public class GeneralizedParser
+{
+    private readonly CompiledGrammar _grammar;
+    private readonly GrammarLexer _lexer;
+    private readonly OperatorLayer? _operators;
+    private readonly IReadOnlyList<ITokenFilter> _filters;
+    private readonly TerminatorPolicy? _terminators;
+    private readonly DelimiterRecoveryPolicy? _delimiters;
+
+    /// <summary>
+    /// Initializes a new instance of the GeneralizedParser class.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar to parse with.</param>
+    /// <param name="filters">The token filters the grammar's pipeline may name, or null for the built-in ones.</param>
+    public GeneralizedParser(CompiledGrammar grammar, TokenFilterRegistry? filters = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        _grammar = grammar;
+        _lexer = grammar.Lexer;
+        _operators = OperatorLayer.FromGrammar(grammar);
+        _filters = (filters ?? new TokenFilterRegistry()).CreatePipeline(grammar);
+        _terminators = _filters.OfType<TerminatorPolicy>().FirstOrDefault();
+        _delimiters = DelimiterRecoveryPolicy.FromGrammar(grammar);
+    }
+
+    private GeneralizedParser(GeneralizedParser parser, EntryPoint entry)
+    {
+        _grammar = parser._grammar;
+        _lexer = parser._lexer;
+        _operators = parser._operators;
+        _filters = parser._filters;
+        _terminators = parser._terminators;
+        _delimiters = parser._delimiters;
+        Entry = entry;
+    }
+
+    /// <summary>
+    /// Gets the compiled grammar used by this parser.
+    /// </summary>
+    public CompiledGrammar Grammar => _grammar;
+
+    /// <summary>
+    /// Gets the entry point this parser was selected for with <see cref="ForEntry"/>, or null.
+    /// </summary>
+    public EntryPoint? Entry { get; }
+
+    /// <summary>
+    /// Gets the token filters applied between the lexer and the parser, in order.
+    /// </summary>
+    public IReadOnlyList<ITokenFilter> TokenFilters => _filters;
+
+    /// <summary>
+    /// Gets the lexer used to tokenize input for this parser.
+    /// </summary>
+    internal GrammarLexer Lexer => _lexer;
+
+    /// <summary>
+    /// Gets a parser for one of the grammar's entry points. It shares this parser's lexer and grammar tables and
+    /// parses from the entry point's rule with its end-of-input handling unless the parse options say otherwise.
+    /// </summary>
+    /// <param name="name">The entry point name.</param>
+    /// <returns>The parser for the entry point.</returns>
+    /// <exception cref="ArgumentException">The grammar declares no entry point with the name.</exception>
+    public GeneralizedParser ForEntry(string name)
+    {
+        ArgumentNullException.ThrowIfNull(name);
+
+        return _grammar.EntryPoints.TryGetValue(name, out var entry)
+            ? new GeneralizedParser(this, entry)
+            : throw new ArgumentException($"Entry point '{name}' is not declared in grammar '{_grammar.Name}'", nameof(name));
+    }
+
+    /// <summary>
+    /// Gets a parser for a single rule of the grammar, which must consume the whole input. Like <see cref="ForEntry"/>
+    /// it shares this parser's lexer and grammar tables; the rule's entry table is cached by the grammar.
+    /// </summary>
+    /// <param name="rule">The rule name.</param>
+    /// <returns>The parser for the rule.</returns>
+    /// <exception cref="ArgumentException">The grammar does not define the rule.</exception>
+    public GeneralizedParser ForRule(string rule)
+    {
+        ArgumentNullException.ThrowIfNull(rule);
+
+        return new GeneralizedParser(this, _grammar.GetRuleEntry(rule));
+    }
+
+    /// <summary>
+    /// Parses the specified input.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <param name="options">Optional parse options.</param>
+    /// <returns>The parse result.</returns>
+    public ParseResult Parse(string input, ParseOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+        options ??= new ParseOptions();
+
+        // The lexer starts after the preamble, so every position stays an offset in the whole input.
+        var preamble = options.Preamble != null && !_grammar.IsScannerless ? Preamble.Detect(input, options.Preamble) : Preamble.Empty;
+
+        long mark = 0;
+        MemoryLedger.Mark(ref mark);
+        var lexResult = _lexer.Tokenize(input, preamble.Length, options.Interner);
+        var treeBuilder = new ParseTreeBuilder(lexResult.Tokens, new LineIndex(input), options.SourceFile);
+        MemoryLedger.Record(ref treeBuilder.Memory, MemoryCategories.Tokens, mark);
+        var result = Parse(input, lexResult.Tokens, lexResult.Diagnostics, options, treeBuilder);
+        result.Preamble = preamble;
+        return result;
+    }
+
+    /// <summary>
+    /// Parses text held in memory, such as a cached file. Memory over a whole string is parsed in place and the
+    /// result shares that string, so callers that keep text in strings pay no copy; memory over part of a string or
+    /// over an array is copied once into the string the result keeps as its <see cref="ParseResult.Input"/>.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <param name="options">Optional parse options.</param>
+    /// <returns>The parse result.</returns>
+    public ParseResult Parse(ReadOnlyMemory<char> input, ParseOptions? options = null)
+    {
+        return Parse(ShareOrCopy(input), options);
+    }
+
+    /// <summary>
+    /// Parses an already tokenized input.
+    /// </summary>
+    /// <param name="input">The source text the tokens were produced from.</param>
+    /// <param name="tokens">The tokens to parse.</param>
+    /// <param name="options">Optional parse options.</param>
+    /// <returns>The parse result.</returns>
+    public ParseResult Parse(string input, IReadOnlyList<Token> tokens, ParseOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+        ArgumentNullException.ThrowIfNull(tokens);
+
+        options ??= new ParseOptions();
+        var treeBuilder = new ParseTreeBuilder(tokens, new LineIndex(input), options.SourceFile);
+        return Parse(input, tokens, Array.Empty<Diagnostic>(), options, treeBuilder);
+    }
+
+    /// <summary>
+    /// Parses each document of a multi-document input on its own, as split by the grammar's
+    /// <see cref="CompiledGrammar.Documents"/> policy. Every document is parsed against the policy's rule, so a syntax
+    /// error in one leaves the others unaffected; positions and diagnostics stay in terms of the whole input.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <param name="options">Optional parse options; the start rule and end are taken from the policy.</param>
+    /// <returns>The documents in input order, parsed as they are enumerated.</returns>
+    /// <exception cref="InvalidOperationException">The grammar declares no documents.</exception>
+    public IEnumerable<ParsedDocument> ParseDocuments(string input, ParseOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+
+        var policy = _grammar.Documents
+            ?? throw new InvalidOperationException($"Grammar '{_grammar.Name}' declares no {CompiledGrammar.DocumentsKey}");
+        return ParseDocuments(input, policy, options ?? new ParseOptions());
+    }
+
+    /// <summary>
+    /// Parses as much of the input as forms a valid beginning of a sentence and reports where that prefix ends,
+    /// whether the input is a complete sentence and which terminals may come next. Unlike <see cref="Parse(string, ParseOptions?)"/>
+    /// it builds no tree and reports no syntax errors, which suits shell completion and REPLs deciding whether
+    /// to read another line. Input after the first lexical error is not considered.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <param name="startRule">The rule to parse against, or null for the entry point's or grammar's start rule.</param>
+    /// <returns>The prefix parse result.</returns>
+    public PrefixParseResult ParsePrefix(string input, string? startRule = null)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+
+        var startName = startRule ?? Entry?.Rule.Name ?? _grammar.StartRule;
+        var rule = _grammar.GetRule(startName)
+            ?? throw new ArgumentException($"Start rule '{startName}' is not defined in grammar '{_grammar.Name}'", nameof(startRule));
+
+        var lexResult = _lexer.Tokenize(input);
+        var tokens = _filters.Count > 0
+            ? TokenFilterRegistry.Apply(_filters, lexResult.Tokens, new TokenFilterContext(input, new LineIndex(input), null, new List<Diagnostic>()))
+            : lexResult.Tokens;
+        var lexErrorOffset = lexResult.Diagnostics
+            .Where(d => d.Severity == DiagnosticSeverity.Error)
+            .Select(d => d.Location?.Offset ?? 0)
+            .DefaultIfEmpty(input.Length)
+            .Min();
+        var parsed = tokens.TakeWhile(t => t.Offset < lexErrorOffset).ToList();
+
+        var matcher = _grammar.IsScannerless ? new ScannerlessMatcher(_lexer, input) : null;
+        var features = FeatureGate.Create(_grammar, null, input, parsed);
+        var chart = Recognize(parsed, rule, matcher, null, null, null, features, null, null, null, CancellationToken.None, out var lastSet, out _);
+        var set = chart[lastSet]!;
+
+        var status = lastSet < parsed.Count || lexErrorOffset < input.Length ? PrefixStatus.Invalid
+            : set.Completed.Contains((rule.Index, 0)) ? PrefixStatus.Complete
+            : PrefixStatus.Incomplete;
+        var expected = ExpectedTerminals(chart, lastSet, _grammar, rule, features);
+
+        return new PrefixParseResult
+        {
+            Input = input,
+            StartRule = startName,
+            Status = status,
+            Tokens = tokens,
+            ValidTokenCount = lastSet,
+            ValidLength = lastSet < parsed.Count ? parsed[lastSet].Offset : Math.Min(lexErrorOffset, input.Length),
+            Expected = expected,
+            ExpectedByCategory = expected
+                .GroupBy(_grammar.GetTerminalCategory)
+                .OrderBy(g => g.Key, StringComparer.Ordinal)
+                .ToDictionary(g => g.Key, g => (IReadOnlyList<string>)g.ToList())
+        };
+    }
+
+    /// <summary>
+    /// Parses the specified input and evaluates the semantic actions bound to its alternatives.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <param name="actions">The registered actions and bindings.</param>
+    /// <param name="options">Optional parse options.</param>
+    /// <param name="state">Optional host state shared by all actions.</param>
+    /// <returns>The computed value together with the parse result.</returns>
+    /// <exception cref="InvalidOperationException">A binding refers to an unregistered action.</exception>
+    public ActionParseResult ParseWithActions(string input, ActionRegistry actions, ParseOptions? options = null, IDictionary<string, object?>? state = null)
+    {
+        ArgumentNullException.ThrowIfNull(actions);
+        actions.Validate(_grammar);
+
+        state ??= new Dictionary<string, object?>();
+        var parse = Parse(input, options);
+        if (parse.Forest == null)
+        {
+            return new ActionParseResult { Parse = parse, State = state, Diagnostics = parse.Diagnostics };
+        }
+
+        var context = new ActionContext(new LineIndex(input), options?.SourceFile, state);
+        var evaluator = new SemanticEvaluator(actions, parse.Tokens, context);
+        if (!evaluator.TryEvaluate(parse.Forest.Root, out var value, out var error))
+        {
+            return new ActionParseResult { Parse = parse, State = state, Diagnostics = parse.Diagnostics.Append(error!).ToList() };
+        }
+
+        return new ActionParseResult { Value = value, Parse = parse, State = state, Diagnostics = parse.Diagnostics };
+    }
+
+    private IEnumerable<ParsedDocument> ParseDocuments(string input, DocumentPolicy policy, ParseOptions options)
+    {
+        var preamble = options.Preamble != null ? Preamble.Detect(input, options.Preamble) : Preamble.Empty;
+        var lexResult = _lexer.Tokenize(input, preamble.Length, options.Interner);
+        var tokens = lexResult.Tokens;
+        var lineIndex = new LineIndex(input);
+        var ranges = policy.Split(input, tokens);
+
+        var documentOptions = options.Clone();
+        documentOptions.StartRule = policy.Rule.Name;
+        documentOptions.End = EntryPointEnd.Complete;
+        documentOptions.Preamble = null;
+
+        for (var d = 0; d < ranges.Count; d++)
+        {
+            var (start, end) = ranges[d];
+            var slice = new List<Token>(end - start);
+            for (var i = start; i < end; i++)
+            {
+                slice.Add(tokens[i]);
+            }
+
+            // A lexical error belongs to the document it falls in, or before, so none is lost between documents.
+            var from = d == 0 ? 0 : tokens[start].Offset;
+            var to = d + 1 < ranges.Count ? tokens[ranges[d + 1].Start].Offset : int.MaxValue;
+            var lexDiagnostics = lexResult.Diagnostics
+                .Where(diagnostic => (diagnostic.Location?.Offset ?? 0) >= from && (diagnostic.Location?.Offset ?? 0) < to)
+                .ToList();
+
+            // A document that ends too early is reported where it ends, not at the end of the input.
+            var treeBuilder = new ParseTreeBuilder(slice, lineIndex, options.SourceFile) { EndOffset = slice[^1].End };
+            var result = Parse(input, slice, lexDiagnostics, documentOptions, treeBuilder);
+            if (d == 0)
+            {
+                result.Preamble = preamble;
+            }
+
+            var location = lineIndex.GetPosition(slice[0].Offset, slice[^1].End - slice[0].Offset, options.SourceFile);
+            yield return new ParsedDocument(d, location, result);
+        }
+    }
+
+    internal ParseResult Parse(string input, IReadOnlyList<Token> tokens, IReadOnlyList<Diagnostic> lexDiagnostics, ParseOptions options, ParseTreeBuilder treeBuilder, DeferredRegions? materializing = null)
+    {
+        var startName = options.StartRule ?? Entry?.Rule.Name ?? _grammar.StartRule;
+        var startRule = _grammar.GetRule(startName)
+            ?? throw new ArgumentException($"Start rule '{startName}' is not defined in grammar '{_grammar.Name}'", nameof(options));
+        var end = options.End ?? Entry?.End ?? EntryPointEnd.Complete;
+
+        var lineIndex = treeBuilder.LineIndex;
+        var diagnostics = new List<Diagnostic>(lexDiagnostics);
+        var watchdog = options.Watchdog != null ? new ParseWatchdog(options.Watchdog) : null;
+        var matcher = _grammar.IsScannerless ? new ScannerlessMatcher(_lexer, input) : null;
+        var recorder = options.RecordEvents ? new ParseRecorder() : null;
+
+        // A deferred region's tokens were filtered with the rest of the input before they were set aside.
+        if (_filters.Count > 0 && materializing == null)
+        {
+            tokens = TokenFilterRegistry.Apply(_filters, tokens, new TokenFilterContext(input, lineIndex, options.SourceFile, diagnostics));
+            treeBuilder.Tokens = tokens;
+        }
+
+        var repair = matcher == null && (_terminators != null || _delimiters != null || options.KeywordCorrectionDistance > 0)
+            ? new TokenRepair(_grammar, _terminators, _delimiters, options.KeywordCorrectionDistance, tokens, lineIndex, options.SourceFile)
+            : null;
+        if (repair != null)
+        {
+            tokens = repair.Tokens;
+            treeBuilder.Tokens = tokens;
+        }
+
+        var lazy = options.Lazy && matcher == null && _grammar.HasDeferredRules && (options.Operators ?? _operators) == null;
+        var deferred = lazy ? materializing ?? new DeferredRegions(this, input, lineIndex, options) : null;
+        var deferredEnds = deferred != null ? DeferredRegions.MatchBrackets(_grammar, tokens) : null;
+        treeBuilder.Deferred = deferred;
+
+        var features = FeatureGate.Create(_grammar, options.Features, input, tokens);
+        var symbols = _grammar.HasSymbolPredicates ? options.Symbols : null;
+        var chart = Recognize(tokens, startRule, matcher, watchdog, recorder, repair, features, symbols, matcher == null ? options.Parallelism : null, deferredEnds, options.CancellationToken, out var lastSet, out var stall);
+        if (repair != null)
+        {
+            diagnostics.AddRange(repair.Diagnostics);
+        }
+
+        if (stall != null)
+        {
+            diagnostics.Add(CreateStallError(chart, stall.Value, tokens, lineIndex, options, _grammar));
+            return Finish(new ParseResult
+            {
+                Input = input,
+                Grammar = _grammar,
+                StartRule = startName,
+                Tokens = tokens,
+                Diagnostics = diagnostics
+            }, diagnostics, recorder, options);
+        }
+
+        // A prefix entry point takes the longest prefix the start rule completed over.
+        var parsed = tokens.Count;
+        if (end == EntryPointEnd.Prefix)
+        {
+            while (parsed >= 0 && chart[parsed]?.Completed.Contains((startRule.Index, 0)) != true)
+            {
+                parsed--;
+            }
+        }
+
+        var accepted = parsed >= 0 && chart[parsed]?.Completed.Contains((startRule.Index, 0)) == true;
+        if (!accepted)
+        {
+            // Input that only a disabled feature's syntax would accept is reported as needing that feature.
+            diagnostics.Add(features?.Refusal is { } refusal
+                ? CreateFeatureError(refusal, tokens, matcher != null, lineIndex, options.SourceFile)
+                : CreateSyntaxError(chart, tokens, lastSet, lineIndex, treeBuilder.EndOffset ?? input.Length, options.SourceFile, _grammar, startRule, features));
+            return Finish(new ParseResult
+            {
+                Input = input,
+                Grammar = _grammar,
+                StartRule = startName,
+                Tokens = tokens,
+                Diagnostics = diagnostics
+            }, diagnostics, recorder, options);
+        }
+
+        long mark = 0;
+        MemoryLedger.Mark(ref mark);
+        var builder = new ForestBuilder(_grammar, chart!, tokens, matcher, features, symbols, options.MaxAmbiguitiesPerNode);
+        var root = builder.BuildSymbol(startRule, 0, parsed)
+            ?? throw new InvalidOperationException($"Failed to build a parse forest for rule '{startName}'");
+        _grammar.Precedence?.Filter(root);
+        var forest = new ParseForest(root);
+        MemoryLedger.Record(ref treeBuilder.Memory, MemoryCategories.Forest, mark);
+        treeBuilder.Derivations = options.RecordDerivations ? new DerivationLog() : null;
+        var tree = treeBuilder.Build(root);
+        var operators = (options.Operators ?? _operators)?.Resolve(tree, diagnostics, options.SourceFile, treeBuilder.Derivations);
+
+        var result = new ParseResult
+        {
+            Input = input,
+            Grammar = _grammar,
+            StartRule = startName,
+            Tokens = tokens,
+            Forest = forest,
+            Tree = tree,
+            Operators = operators,
+            Diagnostics = diagnostics,
+            ParsedLength = parsed == tokens.Count ? input.Length : parsed > 0 ? tokens[parsed - 1].End : 0,
+            Memory = treeBuilder.Memory,
+            Derivations = treeBuilder.Derivations,
+            Deferred = materializing == null && deferred?.Count > 0 ? deferred : null
+        };
+
+        // Deprecation warnings need the finished parse to read allow directives and build quick fixes.
+        if (_grammar.HasDeprecations)
+        {
+            diagnostics.AddRange(GrammarDeprecation.Check(result));
+        }
+
+        if (_grammar.HasUniqueKeys)
+        {
+            var enabled = new HashSet<string>(options.Features ?? Array.Empty<string>());
+            enabled.UnionWith(_grammar.ReadFeaturePragmas(input, tokens));
+            diagnostics.AddRange(UniqueKeyConstraint.Check(result, enabled));
+        }
+
+        return Finish(result, diagnostics, recorder, options);
+    }
+
+    // Tokens and positions are resolved against a string, so only memory spanning a whole string avoids the copy.
+    internal static string ShareOrCopy(ReadOnlyMemory<char> input)
+    {
+        return MemoryMarshal.TryGetString(input, out var whole, out var start, out var length) && start == 0 && length == whole.Length
+            ? whole
+            : input.ToString();
+    }
+
+    private static ParseResult Finish(ParseResult result, List<Diagnostic> diagnostics, ParseRecorder? recorder, ParseOptions options)
+    {
+        if (options.PathContext && result.Tree != null)
+        {
+            foreach (var diagnostic in diagnostics.Where(d => d.Location != null))
+            {
+                if (result.GetQualifiedPath(diagnostic.Location!) is { IsEmpty: false } path)
+                {
+                    diagnostic.Data[QualifiedPath.DataKey] = path;
+                }
+            }
+        }
+
+        options.DiagnosticBudget?.Apply(diagnostics, result.Tokens, result.Grammar?.Name ?? string.Empty);
+        if (recorder != null)
+        {
+            result.EventLog = recorder.Finish(result, options.SourceFile);
+        }
+
+        return result;
+    }
+
+    private EarleySet?[] Recognize(
+        IReadOnlyList<Token> tokens,
+        CompiledRule startRule,
+        ScannerlessMatcher? matcher,
+        ParseWatchdog? watchdog,
+        ParseRecorder? recorder,
+        TokenRepair? repair,
+        FeatureGate? features,
+        SymbolSet? symbols,
+        ParallelParseOptions? parallel,
+        int[]? deferredEnds,
+        CancellationToken cancellationToken,
+        out int lastSet,
+        out Stall? stall)
+    {
+        stall = null;
+        var furthest = 0;
+        var chart = new EarleySet?[tokens.Count + 1];
+        chart[0] = new EarleySet(0, recorder);
+        foreach (var alternative in startRule.Alternatives)
+        {
+            chart[0]!.Add(new EarleyItem(alternative, 0, 0), ParseEventKind.Start);
+        }
+
+        lastSet = 0;
+        for (var i = 0; i <= tokens.Count; i++)
+        {
+            // Scannerless terminals can skip positions, so a missing set only ends recognition past the furthest scan.
+            var set = chart[i];
+            if (set == null)
+            {
+                if (i > furthest)
+                {
+                    break;
+                }
+
+                continue;
+            }
+
+            cancellationToken.ThrowIfCancellationRequested();
+            lastSet = i;
+            if (recorder != null)
+            {
+                recorder.Position = i;
+            }
+
+            EarleyItem[]?[]? advanced = null;
+            var advancedFrom = 0;
+            var skipped = false;
+            for (var k = 0; k < set.Items.Count; k++)
+            {
+                if (parallel != null && k >= advancedFrom + (advanced?.Length ?? 0) && set.Items.Count - k >= parallel.Threshold)
+                {
+                    advanced = AdvanceInParallel(chart, set, i, k, parallel);
+                    advancedFrom = k;
+                }
+
+                var item = set.Items[k];
+                var alternative = item.Alternative;
+
+                if (watchdog?.Step(i) is { } consumed)
+                {
+                    // The item at hand may belong to a rule that just happens to be processed at the check; the
+                    // rule with the most items in the set is where the parser is spending its time.
+                    var busiest = set.Items.GroupBy(it => it.Alternative.Rule.Index).MaxBy(g => g.Count())!;
+                    stall = new Stall(i, alternative.Rule.Index == busiest.Key ? item : busiest.First(), consumed);
+                    return chart;
+                }
+
+                if (item.Dot == alternative.Symbols.Count)
+                {
+                    // An alternative guarded by a disabled feature is recognized but never reduced, one constrained
+                    // not to be followed by the next token is not reduced before it, and one whose symbol predicate
+                    // rejects its token is not reduced at all.
+                    if (features?.Refuses(alternative, item.Origin, i) != true && !_grammar.IsFollowedByForbidden(alternative, tokens, i) &&
+                        symbols?.Refuses(alternative, tokens, item.Origin) != true)
+                    {
+                        Complete(chart, set, i, item, advanced != null && k - advancedFrom < advanced.Length ? advanced[k - advancedFrom] : null);
+                    }
+
+                    continue;
+                }
+
+                var ruleIndex = alternative.RuleIndices[item.Dot];
+                if (ruleIndex >= 0)
+                {
+                    var rule = _grammar.Rules[ruleIndex];
+                    set.AddWaiting(ruleIndex, item);
+
+                    // A deferred rule at an opening bracket is recognized as its whole bracket group, unread.
+                    if (deferredEnds != null && rule.Placeholder is { } placeholder && i < tokens.Count &&
+                        deferredEnds[i] > i && tokens[i].Kind == placeholder.Symbols[0].Key)
+                    {
+                        var groupEnd = deferredEnds[i] + 1;
+                        chart[groupEnd] ??= new EarleySet(groupEnd, recorder);
+                        chart[groupEnd]!.Add(new EarleyItem(placeholder, placeholder.Symbols.Count, i), ParseEventKind.Scan);
+                        furthest = Math.Max(furthest, groupEnd);
+                        skipped = true;
+                        continue;
+                    }
+
+                    foreach (var predicted in rule.Alternatives)
+                    {
+                        set.Add(new EarleyItem(predicted, 0, i), ParseEventKind.Predict);
+                    }
+
+                    // Aycock-Horspool: a nullable rule can be stepped over immediately.
+                    if (_grammar.IsNullable(rule) || set.Completed.Contains((ruleIndex, i)))
+                    {
+                        set.Add(item with { Dot = item.Dot + 1 }, ParseEventKind.Advance);
+                    }
+                }
+                else if (matcher != null)
+                {
+                    var length = matcher.Match(alternative.Symbols[item.Dot].Key, i);
+                    if (length >= 0 && i + length <= tokens.Count)
+                    {
+                        chart[i + length] ??= new EarleySet(i + length, recorder);
+                        chart[i + length]!.Add(item with { Dot = item.Dot + 1 }, ParseEventKind.Scan);
+                        furthest = Math.Max(furthest, i + length);
+                    }
+                }
+                else if (i < tokens.Count && _grammar.Accepts(tokens[i], alternative, item.Dot))
+                {
+                    chart[i + 1] ??= new EarleySet(i + 1, recorder);
+                    chart[i + 1]!.Add(item with { Dot = item.Dot + 1 }, ParseEventKind.Scan);
+                    furthest = Math.Max(furthest, i + 1);
+                }
+            }
+
+            // When no item could scan the token, or the input ends unaccepted, the token stream may be repaired
+            // with an inserted terminator, a corrected keyword, or a closing bracket inserted, replaced or dropped;
+            // the repaired token is then scanned as usual. A bracket may be closed at an earlier token, and the sets
+            // after it are recognized again; deferred bracket groups already placed in later sets rule that out for
+            // lazy parses.
+            var stuck = i < tokens.Count ? chart[i + 1] == null && !skipped : !set.Completed.Contains((startRule.Index, 0));
+            if (repair != null && stuck && repair.TryRepair(chart, i, deferredEnds == null) is var repaired and >= 0)
+            {
+                Array.Resize(ref chart, tokens.Count + 1);
+                if (repaired < i)
+                {
+                    Array.Clear(chart, repaired + 1, chart.Length - repaired - 1);
+                    set = chart[repaired]!;
+                    i = repaired;
+                }
+
+                chart[i + 1] = new EarleySet(i + 1, recorder);
+                if (deferredEnds != null)
+                {
+                    deferredEnds = DeferredRegions.MatchBrackets(_grammar, tokens);
+                }
+                foreach (var item in set.Items.Where(it => it.Dot < it.Alternative.Symbols.Count && _grammar.Accepts(tokens[i], it.Alternative, it.Dot)))
+                {
+                    chart[i + 1]!.Add(item with { Dot = item.Dot + 1 }, ParseEventKind.Scan);
+                }
+
+                furthest = i + 1;
+            }
+        }
+
+        return chart;
+    }
+
+    private static void Complete(EarleySet?[] chart, EarleySet set, int position, EarleyItem item, EarleyItem[]? advanced)
+    {
+        var ruleIndex = item.Alternative.Rule.Index;
+        if (!set.AddCompleted(ruleIndex, item.Origin))
+        {
+            return;
+        }
+
+        if (advanced != null)
+        {
+            foreach (var next in advanced)
+            {
+                set.Add(next, ParseEventKind.Complete);
+            }
+
+            return;
+        }
+
+        var origin = chart[item.Origin]!;
+        if (!origin.WaitingFor.TryGetValue(ruleIndex, out var waiting))
+        {
+            return;
+        }
+
+        // Items waiting in the current set may grow while iterating; later ones are handled at prediction.
+        for (var w = 0; w < waiting.Count; w++)
+        {
+            set.Add(waiting[w] with { Dot = waiting[w].Dot + 1 }, ParseEventKind.Complete);
+        }
+    }
+
+    // Advances the completed items among those from the start index on over the items waiting for their rules in
+    // earlier sets. Only those sets are read, and they no longer change; the ordered pass adds the results to this
+    // set, dropping the ones it already holds, in the order the sequential parser would. Completions of rules that
+    // started in this set are left to it entirely because the items waiting for them are still being added.
+    private static EarleyItem[]?[] AdvanceInParallel(EarleySet?[] chart, EarleySet set, int position, int start, ParallelParseOptions parallel)
+    {
+        var items = set.Items;
+        var results = new EarleyItem[]?[items.Count - start];
+        var degree = Math.Max(1, parallel.MaxDegreeOfParallelism);
+        var partitions = Partitioner.Create(0, results.Length, Math.Max(1, results.Length / (degree * 4)));
+
+        Parallel.ForEach(partitions, new ParallelOptions { MaxDegreeOfParallelism = degree }, range =>
+        {
+            for (var j = range.Item1; j < range.Item2; j++)
+            {
+                var item = items[start + j];
+                if (item.Dot < item.Alternative.Symbols.Count || item.Origin == position)
+                {
+                    continue;
+                }
+
+                results[j] = chart[item.Origin]!.WaitingFor.TryGetValue(item.Alternative.Rule.Index, out var waiting)
+                    ? waiting.Select(w => w with { Dot = w.Dot + 1 }).ToArray()
+                    : Array.Empty<EarleyItem>();
+            }
+        });
+
+        return results;
+    }
+
+    private static List<string> ExpectedTerminals(EarleySet set)
+    {
+        return set.Items
+            .Where(item => item.Dot < item.Alternative.Symbols.Count && item.Alternative.RuleIndices[item.Dot] < 0)
+            .Select(item => item.Alternative.Symbols[item.Dot].Key)
+            .Distinct()
+            .OrderBy(key => key, StringComparer.Ordinal)
+            .ToList();
+    }
+
+    // A terminal that the constraint of an alternative completed at the set forbids is only expected if some item
+    // still expects it once the completions it forbids are refused, as they would be were it the next token.
+    private static List<string> ExpectedTerminals(EarleySet?[] chart, int position, CompiledGrammar grammar, CompiledRule startRule, FeatureGate? features)
+    {
+        var set = chart[position]!;
+        var expected = ExpectedTerminals(set);
+        var forbidden = set.Items
+            .Where(item => item.Dot == item.Alternative.Symbols.Count)
+            .SelectMany(item => item.Alternative.NotFollowedBy)
+            .Select(symbol => symbol.Key)
+            .ToHashSet();
+
+        return forbidden.Count == 0
+            ? expected
+            : expected.Where(key => !forbidden.Contains(key) || ExpectsNext(chart, position, key, grammar, startRule, features)).ToList();
+    }
+
+    // Recognizes a set again from the items scanned into it, refusing the completions whose constraint forbids the
+    // terminal, and tells whether any item then expects the terminal.
+    private static bool ExpectsNext(EarleySet?[] chart, int position, string key, CompiledGrammar grammar, CompiledRule startRule, FeatureGate? features)
+    {
+        var items = chart[position]!.Items
+            .Where(item => item.Dot > 0
+                ? item.Alternative.Index < 0 || item.Alternative.RuleIndices[item.Dot - 1] < 0
+                : position == 0 && item.Alternative.Rule == startRule)
+            .ToList();
+        var seen = new HashSet<EarleyItem>(items);
+        var waitingFor = new Dictionary<int, List<EarleyItem>>();
+        var completed = new HashSet<(int Rule, int Origin)>();
+
+        void Add(EarleyItem item)
+        {
+            if (seen.Add(item))
+            {
+                items.Add(item);
+            }
+        }
+
+        for (var k = 0; k < items.Count; k++)
+        {
+            var item = items[k];
+            var alternative = item.Alternative;
+            var ruleIndex = alternative.Rule.Index;
+            if (item.Dot == alternative.Symbols.Count)
+            {
+                if (features?.IsEnabled(alternative) == false || alternative.NotFollowedBy.Any(s => s.Key == key) || !completed.Add((ruleIndex, item.Origin)))
+                {
+                    continue;
+                }
+
+                var waiting = item.Origin == position ? waitingFor.GetValueOrDefault(ruleIndex) : chart[item.Origin]!.WaitingFor.GetValueOrDefault(ruleIndex);
+                foreach (var parent in waiting ?? new List<EarleyItem>())
+                {
+                    Add(parent with { Dot = parent.Dot + 1 });
+                }
+
+                continue;
+            }
+
+            var next = alternative.RuleIndices[item.Dot];
+            if (next < 0)
+            {
+                if (alternative.Symbols[item.Dot].Key == key)
+                {
+                    return true;
+                }
+
+                continue;
+            }
+
+            if (!waitingFor.TryGetValue(next, out var list))
+            {
+                list = new List<EarleyItem>();
+                waitingFor[next] = list;
+            }
+
+            list.Add(item);
+            foreach (var predicted in grammar.Rules[next].Alternatives)
+            {
+                Add(new EarleyItem(predicted, 0, position));
+            }
+
+            if (grammar.IsNullable(grammar.Rules[next]) || completed.Contains((next, position)))
+            {
+                Add(item with { Dot = item.Dot + 1 });
+            }
+        }
+
+        return false;
+    }
+
+    private static Diagnostic CreateSyntaxError(EarleySet?[] chart, IReadOnlyList<Token> tokens, int position, LineIndex lineIndex, int end, string? sourceFile, CompiledGrammar grammar, CompiledRule startRule, FeatureGate? features)
+    {
+        // Grammar authors' phrases replace the terminals they cover in the message; tools keep the terminals.
+        var expected = ExpectedTerminals(chart, position, grammar, startRule, features);
+        var phrases = ExpectedPhrases.Describe(chart, position, grammar);
+        var shown = (phrases.Count > 0 ? phrases : expected).ToList();
+
+        if (position < tokens.Count)
+        {
+            var token = tokens[position];
+            return new Diagnostic
+            {
+                Code = DiagnosticCodes.UnexpectedToken,
+                Location = lineIndex.GetPosition(token.Offset, token.Length, sourceFile),
+                Data = { ["expected"] = expected, ["expectedPhrases"] = phrases, ["tokenIndex"] = position }
+            }.WithMessage(token.Kind == GrammarLexer.CharacterKind ? "E0001.character" : DiagnosticCodes.UnexpectedToken,
+                ("kind", token.Kind),
+                ("text", token.Text),
+                ("expected", shown));
+        }
+
+        return new Diagnostic
+        {
+            Code = DiagnosticCodes.UnexpectedEndOfInput,
+            Location = lineIndex.GetPosition(end, 0, sourceFile),
+            Data = { ["expected"] = expected, ["expectedPhrases"] = phrases, ["tokenIndex"] = position }
+        }.WithMessage(DiagnosticCodes.UnexpectedEndOfInput, ("expected", shown));
+    }
+
+    private static Diagnostic CreateFeatureError(FeatureRefusal refusal, IReadOnlyList<Token> tokens, bool scannerless, LineIndex lineIndex, string? sourceFile)
+    {
+        var alternative = refusal.Alternative;
+        int start, end;
+        if (scannerless)
+        {
+            (start, end) = (refusal.Origin, refusal.End);
+        }
+        else
+        {
+            start = refusal.Origin < tokens.Count ? tokens[refusal.Origin].Offset : lineIndex.Text.Length;
+            end = refusal.End > refusal.Origin ? tokens[refusal.End - 1].End : start;
+        }
+
+        return new Diagnostic
+        {
+            Code = DiagnosticCodes.FeatureDisabled,
+            Location = lineIndex.GetPosition(start, end - start, sourceFile),
+            Data = { ["feature"] = alternative.Feature!, ["rule"] = alternative.Rule.Name }
+        }.WithMessage(DiagnosticCodes.FeatureDisabled, ("feature", alternative.Feature!), ("rule", alternative.Rule.Name), ("alternative", alternative.Text));
+    }
+
+    private static Diagnostic CreateStallError(EarleySet?[] chart, Stall stall, IReadOnlyList<Token> tokens, LineIndex lineIndex, ParseOptions options, CompiledGrammar grammar)
+    {
+        var ruleStack = RuleStack(chart, stall.Item);
+        var shown = ruleStack.Count > 10 ? new[] { "..." }.Concat(ruleStack.TakeLast(10)) : ruleStack;
+        var offset = stall.Position < tokens.Count ? tokens[stall.Position].Offset : lineIndex.Text.Length;
+        var interval = (long)Math.Round(options.Watchdog!.Interval.TotalMilliseconds, MidpointRounding.AwayFromZero);
+
+        return new Diagnostic
+        {
+            Code = DiagnosticCodes.ParseStalled,
+            Location = lineIndex.GetPosition(offset, 0, options.SourceFile),
+            Data =
+            {
+                ["ruleStack"] = ruleStack,
+                ["tokenIndex"] = stall.Position,
+                ["consumedTokens"] = stall.Consumed,
+                ["hints"] = StallAnalyzer.Analyze(chart, stall.Position, grammar)
+            }
+        }.WithMessage(DiagnosticCodes.ParseStalled,
+            ("position", stall.Position),
+            ("consumed", stall.Consumed),
+            ("interval", interval),
+            ("rules", string.Join(" > ", shown)));
+    }
+
+    // Earley items have no call stack; the chain of items waiting for each rule, back to the start rule at
+    // position 0, stands in for it. Each (rule, origin) pair is used once so recursion cannot loop.
+    private static List<string> RuleStack(EarleySet?[] chart, EarleyItem item)
+    {
+        var stack = new List<string> { item.Alternative.Rule.Name };
+        var visited = new HashSet<(int Rule, int Origin)> { (item.Alternative.Rule.Index, item.Origin) };
+        var current = item;
+
+        while (chart[current.Origin]?.WaitingFor.TryGetValue(current.Alternative.Rule.Index, out var waiting) == true)
+        {
+            var parents = waiting.Where(w => !visited.Contains((w.Alternative.Rule.Index, w.Origin))).ToList();
+            if (parents.Count == 0)
+            {
+                break;
+            }
+
+            current = parents.MinBy(w => w.Origin);
+            visited.Add((current.Alternative.Rule.Index, current.Origin));
+            stack.Add(current.Alternative.Rule.Name);
+        }
+
+        stack.Reverse();
+        return stack;
+    }
+
+    private readonly record struct Stall(int Position, EarleyItem Item, int Consumed);
+
+    internal readonly record struct EarleyItem(CompiledAlternative Alternative, int Dot, int Origin);
+
+    internal sealed class EarleySet
+    {
+        private readonly int _position;
+        private readonly ParseRecorder? _recorder;
+
+        public EarleySet(int position, ParseRecorder? recorder)
+        {
+            _position = position;
+            _recorder = recorder;
+        }
+
+        public List<EarleyItem> Items { get; } = new();
+
+        public HashSet<EarleyItem> Seen { get; } = new();
+
+        public Dictionary<int, List<EarleyItem>> WaitingFor { get; } = new();
+
+        public HashSet<(int Rule, int Origin)> Completed { get; } = new();
+
+        public Dictionary<int, List<int>> CompletedOrigins { get; } = new();
+
+        public bool AddCompleted(int ruleIndex, int origin)
+        {
+            if (!Completed.Add((ruleIndex, origin)))
+            {
+                return false;
+            }
+
+            if (!CompletedOrigins.TryGetValue(ruleIndex, out var origins))
+            {
+                origins = new List<int>();
+                CompletedOrigins[ruleIndex] = origins;
+            }
+
+            origins.Add(origin);
+            return true;
+        }
+
+        public void Add(EarleyItem item, ParseEventKind cause)
+        {
+            if (Seen.Add(item))
+            {
+                Items.Add(item);
+                _recorder?.ItemAdded(cause, _position, item);
+            }
+        }
+
+        public void AddWaiting(int ruleIndex, EarleyItem item)
+        {
+            if (!WaitingFor.TryGetValue(ruleIndex, out var list))
+            {
+                list = new List<EarleyItem>();
+                WaitingFor[ruleIndex] = list;
+            }
+
+            list.Add(item);
+        }
+    }
+
+    private sealed class ForestBuilder
+    {
+        private readonly CompiledGrammar _grammar;
+        private readonly EarleySet?[] _chart;
+        private readonly IReadOnlyList<Token> _tokens;
+        private readonly ScannerlessMatcher? _matcher;
+        private readonly FeatureGate? _features;
+        private readonly SymbolSet? _declared;
+        private readonly int _maxDerivations;
+        private readonly Dictionary<(int Rule, int Start, int End), SymbolForestNode?> _symbols = new();
+        private readonly HashSet<(int Rule, int Start, int End)> _inProgress = new();
+        private readonly Dictionary<(CompiledAlternative Alternative, int Dot, int Start, int End), List<ForestNode[]>> _derivations = new();
+        private readonly Dictionary<int, TokenForestNode> _leaves = new();
+        private readonly Dictionary<int, TokenForestNode> _promoted = new();
+        private readonly Dictionary<(string Kind, int Start, int End), TokenForestNode> _matches = new();
+        private readonly Stack<Frame> _frames = new();
+
+        public ForestBuilder(CompiledGrammar grammar, EarleySet?[] chart, IReadOnlyList<Token> tokens, ScannerlessMatcher? matcher, FeatureGate? features, SymbolSet? symbols, int maxDerivations)
+        {
+            _grammar = grammar;
+            _chart = chart;
+            _tokens = tokens;
+            _matcher = matcher;
+            _features = features;
+            _declared = symbols;
+            _maxDerivations = Math.Max(1, maxDerivations);
+        }
+
+        public SymbolForestNode? BuildSymbol(CompiledRule rule, int start, int end)
+        {
+            if (RequestSymbol(rule, start, end, out var node))
+            {
+                return node;
+            }
+
+            // Frames live on the heap, so nesting depth is bounded by memory rather than the thread's stack.
+            while (_frames.Count > 0)
+            {
+                if (_frames.Peek().Resume(this))
+                {
+                    _frames.Pop();
+                }
+            }
+
+            return _symbols[(rule.Index, start, end)];
+        }
+
+        // Returns true with the symbol when it is known; otherwise pushes a frame that builds it.
+        private bool RequestSymbol(CompiledRule rule, int start, int end, out SymbolForestNode? node)
+        {
+            var key = (rule.Index, start, end);
+            if (_symbols.TryGetValue(key, out node))
+            {
+                return true;
+            }
+
+            // A cyclic derivation (A =>+ A over the same span) contributes nothing new.
+            if (!_inProgress.Add(key))
+            {
+                node = null;
+                return true;
+            }
+
+            _frames.Push(new SymbolFrame(rule, start, end));
+            return false;
+        }
+
+        // Returns true with the derivations when they are known; otherwise pushes a frame that builds them.
+        private bool RequestDerivations(CompiledAlternative alternative, int dot, int start, int end, out List<ForestNode[]> derivations)
+        {
+            if (_derivations.TryGetValue((alternative, dot, start, end), out var cached))
+            {
+                derivations = cached;
+                return true;
+            }
+
+            derivations = null!;
+            _frames.Push(new DerivationFrame(alternative, dot, start, end));
+            return false;
+        }
+
+        // The origins at which a rule completed at a set, in ascending order, limited to [start, end].
+        private List<int> CompletedOrigins(int ruleIndex, int start, int end)
+        {
+            var origins = new List<int>();
+            if (_chart[end]!.CompletedOrigins.TryGetValue(ruleIndex, out var completed))
+            {
+                foreach (var origin in completed)
+                {
+                    if (origin >= start && origin <= end)
+                    {
+                        origins.Add(origin);
+                    }
+                }
+
+                origins.Sort();
+            }
+
+            return origins;
+        }
+
+        private abstract class Frame
+        {
+            // Advances the frame; returns false when it pushed a frame whose result it needs first.
+            public abstract bool Resume(ForestBuilder builder);
+        }
+
+        private sealed class SymbolFrame : Frame
+        {
+            private readonly SymbolForestNode _node;
+            private int _alternative;
+
+            public SymbolFrame(CompiledRule rule, int start, int end)
+            {
+                _node = new SymbolForestNode(rule, start, end);
+            }
+
+            public override bool Resume(ForestBuilder builder)
+            {
+                var rule = _node.Rule;
+
+                // A skipped bracket group is derived by the rule's placeholder, which has no children to build.
+                if (_alternative == 0 && _node.Packed.Count == 0 && rule.Placeholder is { } placeholder &&
+                    builder.HasItem(_node.End, placeholder, placeholder.Symbols.Count, _node.Start))
+                {
+                    _node.Packed.Add(new PackedForestNode(placeholder, Array.Empty<ForestNode>()));
+                }
+
+                for (; _alternative < rule.Alternatives.Count; _alternative++)
+                {
+                    var alternative = rule.Alternatives[_alternative];
+                    if (!builder.HasItem(_node.End, alternative, alternative.Symbols.Count, _node.Start) ||
+                        builder._features?.IsEnabled(alternative) == false ||
+                        builder._grammar.IsFollowedByForbidden(alternative, builder._tokens, _node.End) ||
+                        builder._declared?.Refuses(alternative, builder._tokens, _node.Start) == true)
+                    {
+                        continue;
+                    }
+
+                    if (!builder.RequestDerivations(alternative, alternative.Symbols.Count, _node.Start, _node.End, out var derivations))
+                    {
+                        return false;
+                    }
+
+                    foreach (var children in derivations)
+                    {
+                        if (_node.Packed.Count >= builder._maxDerivations)
+                        {
+                            break;
+                        }
+
+                        _node.Packed.Add(new PackedForestNode(alternative, children));
+                    }
+                }
+
+                var key = (rule.Index, _node.Start, _node.End);
+                builder._inProgress.Remove(key);
+                builder._symbols[key] = _node.Packed.Count > 0 ? _node : null;
+                return true;
+            }
+        }
+
+        private sealed class DerivationFrame : Frame
+        {
+            private readonly CompiledAlternative _alternative;
+            private readonly int _dot;
+            private readonly int _start;
+            private readonly int _end;
+            private readonly List<ForestNode[]> _results = new();
+            private List<int>? _splits;
+            private int _next;
+
+            public DerivationFrame(CompiledAlternative alternative, int dot, int start, int end)
+            {
+                _alternative = alternative;
+                _dot = dot;
+                _start = start;
+                _end = end;
+            }
+
+            public override bool Resume(ForestBuilder builder)
+            {
+                if (_dot == 0)
+                {
+                    if (_start == _end)
+                    {
+                        _results.Add(Array.Empty<ForestNode>());
+                    }
+
+                    return Finish(builder);
+                }
+
+                var symbolIndex = _dot - 1;
+                var ruleIndex = _alternative.RuleIndices[symbolIndex];
+
+                if (ruleIndex < 0 && builder._matcher != null)
+                {
+                    var kind = _alternative.Symbols[symbolIndex].Key;
+                    _splits ??= builder._matcher.StartsEndingAt(kind, _end)
+                        .Where(matchStart => matchStart >= _start && builder.HasItem(matchStart, _alternative, symbolIndex, _start))
+                        .ToList();
+
+                    for (; _next < _splits.Count; _next++)
+                    {
+                        var matchStart = _splits[_next];
+                        if (!builder.RequestDerivations(_alternative, symbolIndex, _start, matchStart, out var prefixes))
+                        {
+                            return false;
+                        }
+
+                        builder.Extend(_results, prefixes, builder.GetMatchLeaf(kind, matchStart, _end), symbolIndex);
+                    }
+                }
+                else if (ruleIndex < 0)
+                {
+                    var tokenIndex = _end - 1;
+                    if (tokenIndex >= _start && builder._grammar.Accepts(builder._tokens[tokenIndex], _alternative, symbolIndex) &&
+                        builder.HasItem(tokenIndex, _alternative, symbolIndex, _start))
+                    {
+                        if (!builder.RequestDerivations(_alternative, symbolIndex, _start, tokenIndex, out var prefixes))
+                        {
+                            return false;
+                        }
+
+                        var leaf = builder.GetLeaf(tokenIndex, _alternative.Symbols[symbolIndex].Key);
+                        builder.Extend(_results, prefixes, leaf, symbolIndex);
+                    }
+                }
+                else
+                {
+                    var rule = builder._grammar.Rules[ruleIndex];
+                    _splits ??= builder.CompletedOrigins(ruleIndex, _start, _end);
+                    for (; _next < _splits.Count && _results.Count < builder._maxDerivations; _next++)
+                    {
+                        var middle = _splits[_next];
+                        if (!builder.HasItem(middle, _alternative, symbolIndex, _start))
+                        {
+                            continue;
+                        }
+
+                        // Both requests are repeated on resumption; the first is then answered from the memo.
+                        if (!builder.RequestSymbol(rule, middle, _end, out var child))
+                        {
+                            return false;
+                        }
+
+                        if (child == null)
+                        {
+                            continue;
+                        }
+
+                        if (!builder.RequestDerivations(_alternative, symbolIndex, _start, middle, out var prefixes))
+                        {
+                            return false;
+                        }
+
+                        builder.Extend(_results, prefixes, child, symbolIndex);
+                    }
+                }
+
+                return Finish(builder);
+            }
+
+            private bool Finish(ForestBuilder builder)
+            {
+                builder._derivations[(_alternative, _dot, _start, _end)] = _results;
+                return true;
+            }
+        }
+
+        private void Extend(List<ForestNode[]> results, List<ForestNode[]> prefixes, ForestNode child, int index)
+        {
+            foreach (var prefix in prefixes)
+            {
+                if (results.Count >= _maxDerivations)
+                {
+                    return;
+                }
+
+                var children = new ForestNode[index + 1];
+                Array.Copy(prefix, children, index);
+                children[index] = child;
+                results.Add(children);
+            }
+        }
+
+        private bool HasItem(int set, CompiledAlternative alternative, int dot, int origin)
+        {
+            return _chart[set]?.Seen.Contains(new EarleyItem(alternative, dot, origin)) == true;
+        }
+
+        // A contextual keyword matched as its literal gets a leaf of the literal's kind.
+        private TokenForestNode GetLeaf(int index, string kind)
+        {
+            if (_tokens[index].Kind != kind)
+            {
+                if (!_promoted.TryGetValue(index, out var promoted))
+                {
+                    promoted = new TokenForestNode(_tokens[index] with { Kind = kind }, index);
+                    _promoted[index] = promoted;
+                }
+
+                return promoted;
+            }
+
+            if (!_leaves.TryGetValue(index, out var leaf))
+            {
+                leaf = new TokenForestNode(_tokens[index], index);
+                _leaves[index] = leaf;
+            }
+
+            return leaf;
+        }
+
+        // A scannerless leaf covers the characters its terminal matched, so a pattern yields one leaf per run.
+        private TokenForestNode GetMatchLeaf(string kind, int start, int end)
+        {
+            if (!_matches.TryGetValue((kind, start, end), out var leaf))
+            {
+                leaf = new TokenForestNode(_matcher!.CreateToken(kind, start, end), start, end);
+                _matches[(kind, start, end)] = leaf;
+            }
+
+            return leaf;
+        }
+    }
+}
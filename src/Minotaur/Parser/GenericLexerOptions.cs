@@ -0,0 +1,50 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The syntax the <see cref="UniversalFallbackAnalyzer"/> recognizes in files that no grammar matches. The defaults
+/// cover the comment styles, string quotes and brackets most languages share.
+/// </summary>
+public class GenericLexerOptions
+{
+    /// <summary>
+    /// Gets or sets the markers that start a comment running to the end of the line.
+    /// </summary>
+    public IList<string> LineComments { get; set; } = new List<string> { "//", "#" };
+
+    /// <summary>
+    /// Gets or sets the markers that start and end a comment spanning any number of lines.
+    /// </summary>
+    public IList<(string Open, string Close)> BlockComments { get; set; } = new List<(string, string)> { ("/*", "*/"), ("<!--", "-->") };
+
+    /// <summary>
+    /// Gets or sets the characters that start and end a string. A string that is not closed ends with its line.
+    /// </summary>
+    public IList<char> StringQuotes { get; set; } = new List<char> { '"', '\'', '`' };
+
+    /// <summary>
+    /// Gets or sets the character that escapes the next character in a string, or null if strings have no escapes.
+    /// </summary>
+    public char? EscapeCharacter { get; set; } = '\\';
+
+    /// <summary>
+    /// Gets or sets the bracket pairs whose contents form nested regions.
+    /// </summary>
+    public IList<(string Open, string Close)> Brackets { get; set; } = new List<(string, string)> { ("{", "}"), ("[", "]"), ("(", ")") };
+}
@@ -0,0 +1,673 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.IO.Compression;
+using System.Security.Cryptography;
+using System.Text;
+using System.Text.Json;
+using Minotaur.Api;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.GrammarGeneration.Models;
+using Minotaur.Projects.Grammar;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The kind of an artifact in a <see cref="GrammarBundle"/>, decided by its path in the bundled directory.
+/// </summary>
+public enum GrammarBundleArtifactKind
+{
+    /// <summary>
+    /// A grammar file (<c>*.grammar</c>): the bundle's grammar and the grammars it inherits or imports.
+    /// </summary>
+    Grammar,
+
+    /// <summary>
+    /// A message catalog (<c>*.messages</c>) localizing the grammar's diagnostics.
+    /// </summary>
+    Messages,
+
+    /// <summary>
+    /// The detector profile (<c>detector.json</c>): content detection rules selecting the grammar for files.
+    /// </summary>
+    Detector,
+
+    /// <summary>
+    /// A query under <c>queries/</c>, such as highlight scopes or injections.
+    /// </summary>
+    Query,
+
+    /// <summary>
+    /// Documentation under <c>docs/</c> or a Markdown file.
+    /// </summary>
+    Doc,
+
+    /// <summary>
+    /// Any other file, carried along unread.
+    /// </summary>
+    Other
+}
+
+/// <summary>
+/// An artifact listed in a bundle's manifest.
+/// </summary>
+/// <param name="Path">The artifact's path in the bundle, relative to the bundled directory with '/' separators.</param>
+/// <param name="Kind">The artifact's kind.</param>
+/// <param name="Size">The artifact's length in bytes.</param>
+/// <param name="Sha256">The lowercase hexadecimal SHA-256 of the artifact's content.</param>
+public sealed record GrammarBundleArtifact(string Path, GrammarBundleArtifactKind Kind, long Size, string Sha256);
+
+/// <summary>
+/// The manifest of a <see cref="GrammarBundle"/>, stored first in the bundle so that opening one reads nothing else.
+/// It indexes every artifact with its content hash.
+/// </summary>
+/// <param name="Name">The name the bundle is distributed under.</param>
+/// <param name="Version">The bundle's version.</param>
+/// <param name="Grammar">The name of the grammar the bundle provides; other bundled grammars are its dependencies.</param>
+/// <param name="Compatibility">The versions of <see cref="MinotaurApi"/> the bundle can be loaded by.</param>
+/// <param name="Fingerprint">The <see cref="CompiledGrammar.Fingerprint"/> of the grammar when it was bundled, so caches
+/// can key on it without compiling.</param>
+/// <param name="Artifacts">The artifacts in path order.</param>
+public sealed record GrammarBundleManifest(
+    string Name,
+    string Version,
+    string Grammar,
+    VersionRange Compatibility,
+    string Fingerprint,
+    IReadOnlyList<GrammarBundleArtifact> Artifacts)
+{
+    /// <summary>
+    /// The version of the bundle format this build writes and reads.
+    /// </summary>
+    public const int FormatVersion = 1;
+
+    /// <summary>
+    /// Writes the manifest as JSON.
+    /// </summary>
+    /// <returns>The indented JSON.</returns>
+    public string ToJson()
+    {
+        using var stream = new MemoryStream();
+        using (var writer = new Utf8JsonWriter(stream, new JsonWriterOptions { Indented = true }))
+        {
+            writer.WriteStartObject();
+            writer.WriteNumber("format", FormatVersion);
+            writer.WriteString("name", Name);
+            writer.WriteString("version", Version);
+            writer.WriteString("grammar", Grammar);
+            writer.WriteString("minotaur", Compatibility.Text);
+            writer.WriteString("fingerprint", Fingerprint);
+            writer.WriteStartArray("artifacts");
+            foreach (var artifact in Artifacts)
+            {
+                writer.WriteStartObject();
+                writer.WriteString("path", artifact.Path);
+                writer.WriteString("kind", artifact.Kind.ToString().ToLowerInvariant());
+                writer.WriteNumber("size", artifact.Size);
+                writer.WriteString("sha256", artifact.Sha256);
+                writer.WriteEndObject();
+            }
+
+            writer.WriteEndArray();
+            writer.WriteEndObject();
+        }
+
+        return Encoding.UTF8.GetString(stream.ToArray());
+    }
+
+    /// <summary>
+    /// Reads a manifest from JSON.
+    /// </summary>
+    /// <param name="json">The JSON.</param>
+    /// <returns>The manifest.</returns>
+    /// <exception cref="FormatException">The JSON is not a manifest of a supported format version.</exception>
+    public static GrammarBundleManifest Parse(string json)
+    {
+        ArgumentNullException.ThrowIfNull(json);
+
+        try
+        {
+            using var document = JsonDocument.Parse(json);
+            var root = document.RootElement;
+            var format = root.GetProperty("format").GetInt32();
+            if (format != FormatVersion)
+            {
+                throw new FormatException($"Bundle format {format} is not supported; this build reads format {FormatVersion}");
+            }
+
+            var artifacts = root.GetProperty("artifacts").EnumerateArray()
+                .Select(a => new GrammarBundleArtifact(
+                    a.GetProperty("path").GetString()!,
+                    Enum.Parse<GrammarBundleArtifactKind>(a.GetProperty("kind").GetString()!, ignoreCase: true),
+                    a.GetProperty("size").GetInt64(),
+                    a.GetProperty("sha256").GetString()!))
+                .ToList();
+
+            return new GrammarBundleManifest(
+                root.GetProperty("name").GetString()!,
+                root.GetProperty("version").GetString()!,
+                root.GetProperty("grammar").GetString()!,
+                VersionRange.Parse(root.GetProperty("minotaur").GetString()!),
+                root.GetProperty("fingerprint").GetString()!,
+                artifacts);
+        }
+        catch (Exception ex) when (ex is JsonException or KeyNotFoundException or InvalidOperationException or ArgumentException)
+        {
+            throw new FormatException($"The bundle manifest is malformed: {ex.Message}", ex);
+        }
+    }
+}
+
+/// <summary>
+/// The error raised when a bundle cannot be created or opened, is incompatible with this build, or fails its
+/// integrity checks.
+/// </summary>
+public sealed class GrammarBundleException : Exception
+{
+    /// <summary>
+    /// Initializes a new instance of the GrammarBundleException class.
+    /// </summary>
+    /// <param name="message">Why the bundle cannot be used.</param>
+    /// <param name="innerException">The exception that caused the failure, if any.</param>
+    public GrammarBundleException(string message, Exception? innerException = null)
+        : base(message, innerException)
+    {
+    }
+}
+
+/// <summary>
+/// A grammar distributed as one file: a zip archive (<c>.mgb</c>) holding the grammar source and the grammars it
+/// builds on, its message catalogs, detector profile, queries and documentation, indexed by a
+/// <see cref="GrammarBundleManifest"/> stored as its first entry.
+/// </summary>
+/// <remarks>
+/// Opening a bundle reads only the manifest and the archive's directory; it checks that this build is in the
+/// manifest's compatibility range and that the archive holds exactly the indexed artifacts. Artifacts are read on
+/// demand, each checked against its size and hash when read, so a parser loads the grammar sources alone and
+/// documentation is only read when it is shown. Bundles are written to a temporary file and moved into place, and
+/// their entries are sorted and carry a fixed timestamp, so bundling the same directory twice gives the same bytes.
+/// A <see cref="GrammarContainer"/> loads the grammars of every bundle among its files.
+/// </remarks>
+public sealed class GrammarBundle : IDisposable
+{
+    /// <summary>
+    /// The file extension of bundles.
+    /// </summary>
+    public const string Extension = ".mgb";
+
+    /// <summary>
+    /// The path of the manifest in a bundle.
+    /// </summary>
+    public const string ManifestPath = "manifest.json";
+
+    /// <summary>
+    /// The optional file of a bundled directory that sets the bundle's name, version, grammar and compatibility
+    /// range, as the "name", "version", "grammar" and "minotaur" properties of a JSON object.
+    /// </summary>
+    public const string SettingsFile = "bundle.json";
+
+    /// <summary>
+    /// The file name of the detector profile in a bundled directory.
+    /// </summary>
+    public const string DetectorFile = "detector.json";
+
+    private static readonly DateTimeOffset EntryTimestamp = new(1980, 1, 1, 0, 0, 0, TimeSpan.Zero);
+
+    private static readonly JsonSerializerOptions DetectorOptions = new()
+    {
+        PropertyNameCaseInsensitive = true,
+        ReadCommentHandling = JsonCommentHandling.Skip,
+        AllowTrailingCommas = true
+    };
+
+    private readonly ZipArchive _archive;
+    private readonly Dictionary<string, GrammarBundleArtifact> _artifacts;
+    private readonly HashSet<string> _loaded = new(StringComparer.Ordinal);
+    private readonly object _lock = new();
+
+    private GrammarBundle(ZipArchive archive, GrammarBundleManifest manifest, string? path)
+    {
+        _archive = archive;
+        Manifest = manifest;
+        Path = path;
+        _artifacts = manifest.Artifacts.ToDictionary(a => a.Path, StringComparer.Ordinal);
+    }
+
+    /// <summary>
+    /// Gets the version of <see cref="MinotaurApi"/> bundles are checked against.
+    /// </summary>
+    public static GrammarVersion RuntimeVersion { get; } = GrammarVersion.Parse(MinotaurApi.Version);
+
+    /// <summary>
+    /// Gets the bundle's manifest.
+    /// </summary>
+    public GrammarBundleManifest Manifest { get; }
+
+    /// <summary>
+    /// Gets the bundle file, if the bundle was opened from one.
+    /// </summary>
+    public string? Path { get; }
+
+    /// <summary>
+    /// Gets the paths of the artifacts read so far, in path order.
+    /// </summary>
+    public IReadOnlyList<string> LoadedArtifacts
+    {
+        get
+        {
+            lock (_lock)
+            {
+                return _loaded.Order(StringComparer.Ordinal).ToList();
+            }
+        }
+    }
+
+    /// <summary>
+    /// Bundles every file of a directory, recursively. Grammar files must compile together, and catalogs and the
+    /// detector profile must parse, so a bundle that was written loads.
+    /// </summary>
+    /// <param name="directory">The directory.</param>
+    /// <param name="outputPath">The bundle file to write; an existing file is replaced.</param>
+    /// <returns>The manifest of the written bundle.</returns>
+    /// <exception cref="GrammarBundleException">The directory has no grammar, its grammars do not compile, the main
+    /// grammar is ambiguous, or an artifact or <see cref="SettingsFile"/> is malformed.</exception>
+    public static GrammarBundleManifest Create(string directory, string outputPath)
+    {
+        ArgumentNullException.ThrowIfNull(directory);
+        ArgumentNullException.ThrowIfNull(outputPath);
+
+        var root = System.IO.Path.GetFullPath(directory);
+        var output = System.IO.Path.GetFullPath(outputPath);
+        var files = Directory.EnumerateFiles(root, "*", SearchOption.AllDirectories)
+            .Where(f => f != output && !f.EndsWith(Extension, StringComparison.OrdinalIgnoreCase))
+            .Select(f => (Full: f, Relative: System.IO.Path.GetRelativePath(root, f).Replace(System.IO.Path.DirectorySeparatorChar, '/')))
+            .Where(f => f.Relative != SettingsFile)
+            .OrderBy(f => f.Relative, StringComparer.Ordinal)
+            .ToList();
+
+        var contents = files.ToDictionary(f => f.Relative, f => File.ReadAllBytes(f.Full), StringComparer.Ordinal);
+        var artifacts = contents
+            .Select(c => new GrammarBundleArtifact(c.Key, GetKind(c.Key), c.Value.LongLength, Hash(c.Value)))
+            .ToList();
+
+        var settings = ReadSettings(System.IO.Path.Combine(root, SettingsFile));
+        var grammars = artifacts
+            .Where(a => a.Kind == GrammarBundleArtifactKind.Grammar)
+            .Select(a => new GrammarFileReader().Read(Encoding.UTF8.GetString(contents[a.Path])))
+            .ToList();
+        var main = CompileMain(grammars, settings.GetValueOrDefault("grammar"), root);
+
+        foreach (var artifact in artifacts)
+        {
+            var text = Encoding.UTF8.GetString(contents[artifact.Path]);
+            try
+            {
+                if (artifact.Kind == GrammarBundleArtifactKind.Messages)
+                {
+                    MessageCatalog.Parse(text, GetLocale(artifact.Path));
+                }
+                else if (artifact.Kind == GrammarBundleArtifactKind.Detector)
+                {
+                    JsonSerializer.Deserialize<List<ContentDetectionRule>>(text, DetectorOptions);
+                }
+            }
+            catch (Exception ex) when (ex is FormatException or JsonException)
+            {
+                throw new GrammarBundleException($"{artifact.Path} is malformed: {ex.Message}", ex);
+            }
+        }
+
+        var compatibility = settings.TryGetValue("minotaur", out var range)
+            ? ParseRange(range)
+            : VersionRange.Parse($">={RuntimeVersion.Major}.{RuntimeVersion.Minor}.0 <{RuntimeVersion.Major + 1}.0.0");
+        var manifest = new GrammarBundleManifest(
+            settings.GetValueOrDefault("name") ?? main.Name,
+            settings.GetValueOrDefault("version") ?? main.Source.Version,
+            main.Name,
+            compatibility,
+            main.Fingerprint,
+            artifacts);
+
+        var temporary = output + ".tmp";
+        using (var stream = File.Create(temporary))
+        using (var archive = new ZipArchive(stream, ZipArchiveMode.Create))
+        {
+            WriteEntry(archive, ManifestPath, Encoding.UTF8.GetBytes(manifest.ToJson()));
+            foreach (var artifact in artifacts)
+            {
+                WriteEntry(archive, artifact.Path, contents[artifact.Path]);
+            }
+        }
+
+        File.Move(temporary, output, overwrite: true);
+        return manifest;
+    }
+
+    /// <summary>
+    /// Opens a bundle file, reading only its manifest.
+    /// </summary>
+    /// <param name="path">The bundle file.</param>
+    /// <param name="runtimeVersion">The version to check the compatibility range against, by default
+    /// <see cref="RuntimeVersion"/>.</param>
+    /// <returns>The open bundle, which must be disposed.</returns>
+    /// <exception cref="GrammarBundleException">The file is not a bundle, is incompatible with the runtime version, or
+    /// its archive does not hold exactly the artifacts its manifest lists.</exception>
+    public static GrammarBundle Open(string path, GrammarVersion? runtimeVersion = null)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        return Open(File.OpenRead(path), runtimeVersion, path);
+    }
+
+    /// <summary>
+    /// Opens a bundle from a stream, reading only its manifest. The bundle owns the stream.
+    /// </summary>
+    /// <param name="stream">A readable, seekable stream of the bundle.</param>
+    /// <param name="runtimeVersion">The version to check the compatibility range against, by default
+    /// <see cref="RuntimeVersion"/>.</param>
+    /// <returns>The open bundle, which must be disposed.</returns>
+    /// <exception cref="GrammarBundleException">The stream is not a bundle, is incompatible with the runtime version,
+    /// or its archive does not hold exactly the artifacts its manifest lists.</exception>
+    public static GrammarBundle Open(Stream stream, GrammarVersion? runtimeVersion = null)
+    {
+        ArgumentNullException.ThrowIfNull(stream);
+
+        return Open(stream, runtimeVersion, null);
+    }
+
+    /// <summary>
+    /// Gets the artifacts of a kind.
+    /// </summary>
+    /// <param name="kind">The kind.</param>
+    /// <returns>The artifacts in path order.</returns>
+    public IReadOnlyList<GrammarBundleArtifact> GetArtifacts(GrammarBundleArtifactKind kind)
+    {
+        return Manifest.Artifacts.Where(a => a.Kind == kind).ToList();
+    }
+
+    /// <summary>
+    /// Reads an artifact, checking it against its manifest entry.
+    /// </summary>
+    /// <param name="path">The artifact's path in the bundle.</param>
+    /// <returns>The artifact's content.</returns>
+    /// <exception cref="KeyNotFoundException">The manifest lists no such artifact.</exception>
+    /// <exception cref="GrammarBundleException">The content does not match the manifest's size or hash.</exception>
+    public byte[] ReadArtifact(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        if (!_artifacts.TryGetValue(path, out var artifact))
+        {
+            throw new KeyNotFoundException($"Bundle {Manifest.Name} has no artifact {path}");
+        }
+
+        byte[] content;
+        lock (_lock)
+        {
+            using var stream = _archive.GetEntry(path)!.Open();
+            using var buffer = new MemoryStream();
+            stream.CopyTo(buffer);
+            content = buffer.ToArray();
+            _loaded.Add(path);
+        }
+
+        if (content.LongLength != artifact.Size || Hash(content) != artifact.Sha256)
+        {
+            throw new GrammarBundleException($"Artifact {path} of bundle {Manifest.Name} does not match its manifest hash");
+        }
+
+        return content;
+    }
+
+    /// <summary>
+    /// Reads a text artifact, such as documentation or a query, checking it against its manifest entry.
+    /// </summary>
+    /// <param name="path">The artifact's path in the bundle.</param>
+    /// <returns>The artifact's text, decoded as UTF-8.</returns>
+    public string ReadText(string path)
+    {
+        return Encoding.UTF8.GetString(ReadArtifact(path));
+    }
+
+    /// <summary>
+    /// Reads the bundled grammar sources, reading no other artifact.
+    /// </summary>
+    /// <returns>The grammars in path order.</returns>
+    public IReadOnlyList<Grammar> ReadGrammars()
+    {
+        var reader = new GrammarFileReader();
+        return GetArtifacts(GrammarBundleArtifactKind.Grammar).Select(a => reader.Read(ReadText(a.Path))).ToList();
+    }
+
+    /// <summary>
+    /// Compiles the bundle's grammar from the bundled sources, reading no other artifact.
+    /// </summary>
+    /// <returns>The compiled grammar.</returns>
+    /// <exception cref="GrammarBundleException">A grammar source fails its integrity check or does not compile.</exception>
+    public CompiledGrammar LoadGrammar()
+    {
+        var container = GrammarContainer.Load(ReadGrammars());
+        return container.GetGrammar(Manifest.Grammar) ?? throw new GrammarBundleException(
+            $"Grammar {Manifest.Grammar} of bundle {Manifest.Name} does not load: {string.Join("; ", container.Failures.Select(f => f.Message))}");
+    }
+
+    /// <summary>
+    /// Reads the detector profile.
+    /// </summary>
+    /// <returns>The content detection rules, or none if the bundle has no profile.</returns>
+    public IReadOnlyList<ContentDetectionRule> LoadDetectorRules()
+    {
+        return GetArtifacts(GrammarBundleArtifactKind.Detector)
+            .SelectMany(a => JsonSerializer.Deserialize<List<ContentDetectionRule>>(ReadText(a.Path), DetectorOptions) ?? new List<ContentDetectionRule>())
+            .ToList();
+    }
+
+    /// <summary>
+    /// Reads the message catalogs, taking each locale from the last dotted segment of the file name as
+    /// <see cref="MessageCatalog.Load"/> does.
+    /// </summary>
+    /// <returns>The catalogs in path order.</returns>
+    public IReadOnlyList<MessageCatalog> LoadMessageCatalogs()
+    {
+        return GetArtifacts(GrammarBundleArtifactKind.Messages).Select(a => MessageCatalog.Parse(ReadText(a.Path), GetLocale(a.Path))).ToList();
+    }
+
+    /// <summary>
+    /// Reads every artifact, checking each against the manifest.
+    /// </summary>
+    /// <exception cref="GrammarBundleException">An artifact does not match its manifest entry.</exception>
+    public void Verify()
+    {
+        foreach (var artifact in Manifest.Artifacts)
+        {
+            ReadArtifact(artifact.Path);
+        }
+    }
+
+    /// <inheritdoc />
+    public void Dispose()
+    {
+        _archive.Dispose();
+    }
+
+    private static GrammarBundle Open(Stream stream, GrammarVersion? runtimeVersion, string? path)
+    {
+        var name = path ?? "stream";
+        ZipArchive archive;
+        try
+        {
+            archive = new ZipArchive(stream, ZipArchiveMode.Read);
+        }
+        catch (InvalidDataException ex)
+        {
+            stream.Dispose();
+            throw new GrammarBundleException($"{name} is not a grammar bundle: {ex.Message}", ex);
+        }
+
+        try
+        {
+            var entry = archive.GetEntry(ManifestPath) ?? throw new GrammarBundleException($"{name} is not a grammar bundle: it has no {ManifestPath}");
+            string json;
+            using (var reader = new StreamReader(entry.Open(), Encoding.UTF8))
+            {
+                json = reader.ReadToEnd();
+            }
+
+            GrammarBundleManifest manifest;
+            try
+            {
+                manifest = GrammarBundleManifest.Parse(json);
+            }
+            catch (FormatException ex)
+            {
+                throw new GrammarBundleException($"{name}: {ex.Message}", ex);
+            }
+
+            var version = runtimeVersion ?? RuntimeVersion;
+            if (!manifest.Compatibility.Contains(version))
+            {
+                throw new GrammarBundleException(
+                    $"Bundle {manifest.Name} {manifest.Version} requires Minotaur {manifest.Compatibility}, which excludes this version {version}");
+            }
+
+            var entries = archive.Entries.Select(e => e.FullName).Where(e => e != ManifestPath).ToHashSet(StringComparer.Ordinal);
+            var indexed = manifest.Artifacts.Select(a => a.Path).ToHashSet(StringComparer.Ordinal);
+            if (indexed.Except(entries).Order(StringComparer.Ordinal).FirstOrDefault() is { } missing)
+            {
+                throw new GrammarBundleException($"Bundle {manifest.Name} lists {missing}, which the archive does not hold");
+            }
+
+            if (entries.Except(indexed).Order(StringComparer.Ordinal).FirstOrDefault() is { } extra)
+            {
+                throw new GrammarBundleException($"Bundle {manifest.Name} holds {extra}, which its manifest does not list");
+            }
+
+            return new GrammarBundle(archive, manifest, path);
+        }
+        catch
+        {
+            archive.Dispose();
+            throw;
+        }
+    }
+
+    private static CompiledGrammar CompileMain(List<Grammar> grammars, string? declared, string directory)
+    {
+        if (grammars.Count == 0)
+        {
+            throw new GrammarBundleException($"{directory} has no grammar file to bundle");
+        }
+
+        var container = GrammarContainer.Load(grammars);
+        if (container.Failures.Count > 0)
+        {
+            throw new GrammarBundleException($"Bundled grammars do not compile: {string.Join("; ", container.Failures.Select(f => $"{f.Name}: {f.Message}"))}");
+        }
+
+        // Without a declared grammar, the main grammar is the one no other bundled grammar builds on.
+        var dependencies = grammars.SelectMany(GrammarContainer.GetDependencies).ToHashSet(StringComparer.Ordinal);
+        var roots = grammars.Select(g => g.Name).Where(n => !dependencies.Contains(n)).ToList();
+        var name = declared ?? (roots.Count == 1
+            ? roots[0]
+            : throw new GrammarBundleException($"{directory} bundles grammars {string.Join(", ", roots)}; name the main one with \"grammar\" in {SettingsFile}"));
+
+        return container.GetGrammar(name) ?? throw new GrammarBundleException($"{directory} has no grammar named {name}");
+    }
+
+    private static Dictionary<string, string> ReadSettings(string path)
+    {
+        var settings = new Dictionary<string, string>(StringComparer.Ordinal);
+        if (!File.Exists(path))
+        {
+            return settings;
+        }
+
+        try
+        {
+            using var document = JsonDocument.Parse(File.ReadAllText(path), new JsonDocumentOptions { CommentHandling = JsonCommentHandling.Skip, AllowTrailingCommas = true });
+            foreach (var property in document.RootElement.EnumerateObject())
+            {
+                settings[property.Name] = property.Value.GetString() ?? string.Empty;
+            }
+        }
+        catch (Exception ex) when (ex is JsonException or InvalidOperationException)
+        {
+            throw new GrammarBundleException($"{SettingsFile} is malformed: {ex.Message}", ex);
+        }
+
+        return settings;
+    }
+
+    private static VersionRange ParseRange(string range)
+    {
+        try
+        {
+            return VersionRange.Parse(range);
+        }
+        catch (FormatException ex)
+        {
+            throw new GrammarBundleException($"{SettingsFile} is malformed: {ex.Message}", ex);
+        }
+    }
+
+    private static GrammarBundleArtifactKind GetKind(string path)
+    {
+        if (path.EndsWith(".grammar", StringComparison.OrdinalIgnoreCase))
+        {
+            return GrammarBundleArtifactKind.Grammar;
+        }
+
+        if (path.EndsWith(MessageCatalog.FileExtension, StringComparison.OrdinalIgnoreCase))
+        {
+            return GrammarBundleArtifactKind.Messages;
+        }
+
+        if (path == DetectorFile)
+        {
+            return GrammarBundleArtifactKind.Detector;
+        }
+
+        if (path.StartsWith("queries/", StringComparison.Ordinal))
+        {
+            return GrammarBundleArtifactKind.Query;
+        }
+
+        return path.StartsWith("docs/", StringComparison.Ordinal) || path.EndsWith(".md", StringComparison.OrdinalIgnoreCase)
+            ? GrammarBundleArtifactKind.Doc
+            : GrammarBundleArtifactKind.Other;
+    }
+
+    private static string GetLocale(string path)
+    {
+        var name = System.IO.Path.GetFileNameWithoutExtension(path);
+        return name[(name.LastIndexOf('.') + 1)..];
+    }
+
+    private static string Hash(byte[] content)
+    {
+        return Convert.ToHexString(SHA256.HashData(content)).ToLowerInvariant();
+    }
+
+    private static void WriteEntry(ZipArchive archive, string path, byte[] content)
+    {
+        var entry = archive.CreateEntry(path, CompressionLevel.Optimal);
+        entry.LastWriteTime = EntryTimestamp;
+        using var stream = entry.Open();
+        stream.Write(content);
+    }
+}
@@ -0,0 +1,415 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Collections.Concurrent;
+using Minotaur.GrammarGeneration;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Options for loading a <see cref="GrammarContainer"/>.
+/// </summary>
+public class GrammarContainerOptions
+{
+    /// <summary>
+    /// Gets or sets the maximum number of grammars read or compiled at the same time. 1 loads sequentially.
+    /// </summary>
+    public int MaxDegreeOfParallelism { get; set; } = Environment.ProcessorCount;
+
+    /// <summary>
+    /// Gets or sets the pattern selecting grammar files when loading a directory.
+    /// </summary>
+    public string SearchPattern { get; set; } = "*.grammar";
+
+    /// <summary>
+    /// Gets or sets the pattern selecting grammar bundles when loading a directory, or null to ignore bundles.
+    /// </summary>
+    public string? BundlePattern { get; set; } = "*" + GrammarBundle.Extension;
+}
+
+/// <summary>
+/// A grammar that could not be loaded, with the reason.
+/// </summary>
+/// <param name="Name">The grammar name, or the file name if the file could not be read.</param>
+/// <param name="Path">The grammar file, if the grammar was loaded from disk.</param>
+/// <param name="Message">Why the grammar could not be loaded.</param>
+/// <param name="Exception">The exception that caused the failure, if any.</param>
+public sealed record GrammarLoadFailure(string Name, string? Path, string Message, Exception? Exception = null);
+
+/// <summary>
+/// A set of grammars that may build on each other. A grammar names its dependencies with the
+/// "Inherits: base" header, which takes the base grammar's rules, token patterns and metadata, and the
+/// "Imports: a, b" header, which takes rules and token patterns only. Definitions in the grammar itself
+/// always win over inherited or imported ones. A <see cref="GrammarBundle"/> among the files contributes the
+/// grammars it holds, reading no other artifact of it.
+/// </summary>
+/// <remarks>
+/// Loading reads all grammar files concurrently, orders the grammars by their dependencies, then resolves and
+/// compiles each dependency level in parallel. Every step is keyed by grammar name rather than completion
+/// order, so the result - and each grammar's <see cref="CompiledGrammar.Fingerprint"/> - is the same as a
+/// sequential load. A grammar that fails to read, has a missing or cyclic dependency, depends on a failed
+/// grammar or fails to compile is reported in <see cref="Failures"/>; unrelated grammars still load.
+/// </remarks>
+public sealed class GrammarContainer
+{
+    /// <summary>
+    /// The metadata key naming the grammar a grammar inherits from.
+    /// </summary>
+    public const string InheritsKey = "Inherits";
+
+    /// <summary>
+    /// The metadata key listing the grammars a grammar imports.
+    /// </summary>
+    public const string ImportsKey = "Imports";
+
+    private readonly SortedDictionary<string, CompiledGrammar> _grammars;
+
+    private GrammarContainer(SortedDictionary<string, CompiledGrammar> grammars, List<GrammarLoadFailure> failures, List<IReadOnlyList<string>> levels)
+    {
+        _grammars = grammars;
+        Failures = failures;
+        BuildLevels = levels;
+    }
+
+    /// <summary>
+    /// Gets the loaded grammars by name, in ordinal name order.
+    /// </summary>
+    public IReadOnlyDictionary<string, CompiledGrammar> Grammars => _grammars;
+
+    /// <summary>
+    /// Gets the grammars that could not be loaded, in ordinal name order.
+    /// </summary>
+    public IReadOnlyList<GrammarLoadFailure> Failures { get; }
+
+    /// <summary>
+    /// Gets the dependency levels the grammars were built in. Grammars in a level depend only on grammars in
+    /// earlier levels and were built in parallel.
+    /// </summary>
+    public IReadOnlyList<IReadOnlyList<string>> BuildLevels { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether every grammar loaded.
+    /// </summary>
+    public bool IsSuccess => Failures.Count == 0;
+
+    /// <summary>
+    /// Gets a loaded grammar by name.
+    /// </summary>
+    /// <param name="name">The grammar name.</param>
+    /// <returns>The compiled grammar, or null if it is not in the container or failed to load.</returns>
+    public CompiledGrammar? GetGrammar(string name)
+    {
+        return _grammars.TryGetValue(name, out var grammar) ? grammar : null;
+    }
+
+    /// <summary>
+    /// Loads every grammar file in a directory.
+    /// </summary>
+    /// <param name="directory">The directory containing the grammar files.</param>
+    /// <param name="options">Optional load options.</param>
+    /// <param name="cancellationToken">A token to cancel loading.</param>
+    /// <returns>The loaded container.</returns>
+    public static Task<GrammarContainer> LoadDirectoryAsync(string directory, GrammarContainerOptions? options = null, CancellationToken cancellationToken = default)
+    {
+        ArgumentNullException.ThrowIfNull(directory);
+
+        options ??= new GrammarContainerOptions();
+        var bundles = options.BundlePattern != null ? Directory.GetFiles(directory, options.BundlePattern) : Array.Empty<string>();
+        return LoadAsync(Directory.GetFiles(directory, options.SearchPattern).Concat(bundles), options, cancellationToken);
+    }
+
+    /// <summary>
+    /// Loads grammar files and bundles. A bundle that cannot be opened, is incompatible or fails its integrity
+    /// checks is reported as a failure named after its file.
+    /// </summary>
+    /// <param name="paths">The grammar files and bundles.</param>
+    /// <param name="options">Optional load options.</param>
+    /// <param name="cancellationToken">A token to cancel loading.</param>
+    /// <returns>The loaded container.</returns>
+    public static async Task<GrammarContainer> LoadAsync(IEnumerable<string> paths, GrammarContainerOptions? options = null, CancellationToken cancellationToken = default)
+    {
+        ArgumentNullException.ThrowIfNull(paths);
+
+        options ??= new GrammarContainerOptions();
+        var parallel = new ParallelOptions
+        {
+            MaxDegreeOfParallelism = Math.Max(1, options.MaxDegreeOfParallelism),
+            CancellationToken = cancellationToken
+        };
+
+        var sorted = paths.Distinct().OrderBy(p => p, StringComparer.Ordinal).ToList();
+        var read = new (IReadOnlyList<Grammar>? Grammars, Exception? Error)[sorted.Count];
+        var reader = new GrammarFileReader();
+
+        await Parallel.ForEachAsync(Enumerable.Range(0, sorted.Count), parallel, async (i, _) =>
+        {
+            try
+            {
+                read[i] = sorted[i].EndsWith(GrammarBundle.Extension, StringComparison.OrdinalIgnoreCase)
+                    ? (ReadBundle(sorted[i]), null)
+                    : (new[] { await reader.ReadFileAsync(sorted[i]) }, null);
+            }
+            catch (Exception ex) when (ex is IOException or UnauthorizedAccessException or GrammarBundleException)
+            {
+                read[i] = (null, ex);
+            }
+        });
+
+        var failures = new List<GrammarLoadFailure>();
+        var sources = new List<(Grammar Grammar, string? Path)>();
+        for (var i = 0; i < sorted.Count; i++)
+        {
+            if (read[i].Grammars is { } grammars)
+            {
+                sources.AddRange(grammars.Select(g => (g, (string?)sorted[i])));
+            }
+            else
+            {
+                var name = Path.GetFileNameWithoutExtension(sorted[i]);
+                var kind = read[i].Error is GrammarBundleException ? "grammar bundle" : "grammar file";
+                failures.Add(new GrammarLoadFailure(name, sorted[i], $"Could not read {kind}: {read[i].Error!.Message}", read[i].Error));
+            }
+        }
+
+        return Build(sources, parallel, failures);
+    }
+
+    /// <summary>
+    /// Builds a container from grammars that are already in memory.
+    /// </summary>
+    /// <param name="grammars">The grammars.</param>
+    /// <param name="options">Optional load options.</param>
+    /// <returns>The loaded container.</returns>
+    public static GrammarContainer Load(IEnumerable<Grammar> grammars, GrammarContainerOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammars);
+
+        options ??= new GrammarContainerOptions();
+        var parallel = new ParallelOptions { MaxDegreeOfParallelism = Math.Max(1, options.MaxDegreeOfParallelism) };
+        return Build(grammars.Select(g => (g, (string?)null)).ToList(), parallel, new List<GrammarLoadFailure>());
+    }
+
+    /// <summary>
+    /// Gets the names of the grammars a grammar depends on: the inherited grammar first, then imports in
+    /// declaration order.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <returns>The dependency names.</returns>
+    public static IReadOnlyList<string> GetDependencies(Grammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var dependencies = new List<string>();
+        if (grammar.Metadata.TryGetValue(InheritsKey, out var inherits) && !string.IsNullOrWhiteSpace(inherits))
+        {
+            dependencies.Add(inherits.Trim());
+        }
+
+        if (grammar.Metadata.TryGetValue(ImportsKey, out var imports))
+        {
+            dependencies.AddRange(imports.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries));
+        }
+
+        return dependencies.Distinct().ToList();
+    }
+
+    private static IReadOnlyList<Grammar> ReadBundle(string path)
+    {
+        using var bundle = GrammarBundle.Open(path);
+        return bundle.ReadGrammars();
+    }
+
+    private static GrammarContainer Build(List<(Grammar Grammar, string? Path)> sources, ParallelOptions parallel, List<GrammarLoadFailure> failures)
+    {
+        // Sources arrive in a fixed order, so the first definition of a duplicated name is always the same one.
+        var byName = new Dictionary<string, (Grammar Grammar, string? Path)>(StringComparer.Ordinal);
+        foreach (var source in sources)
+        {
+            if (!byName.TryAdd(source.Grammar.Name, source))
+            {
+                failures.Add(new GrammarLoadFailure(source.Grammar.Name, source.Path,
+                    $"Grammar '{source.Grammar.Name}' is already defined in {byName[source.Grammar.Name].Path ?? "another grammar"}"));
+            }
+        }
+
+        var failed = new HashSet<string>(failures.Select(f => f.Name).Where(n => !byName.ContainsKey(n)), StringComparer.Ordinal);
+        var levels = ComputeLevels(byName, failed, failures);
+        var grammars = new SortedDictionary<string, CompiledGrammar>(StringComparer.Ordinal);
+
+        foreach (var level in levels)
+        {
+            var built = new ConcurrentDictionary<string, CompiledGrammar>(StringComparer.Ordinal);
+            var errors = new ConcurrentDictionary<string, Exception>(StringComparer.Ordinal);
+
+            Parallel.ForEach(level, parallel, name =>
+            {
+                var grammar = byName[name].Grammar;
+                var dependencies = GetDependencies(grammar);
+                if (dependencies.FirstOrDefault(d => !grammars.ContainsKey(d)) is { } missing)
+                {
+                    errors[name] = new InvalidOperationException($"Dependency '{missing}' failed to load");
+                    return;
+                }
+
+                try
+                {
+                    built[name] = CompiledGrammar.Compile(Resolve(grammar, dependencies.Select(d => grammars[d].Source).ToList()));
+                }
+                catch (ArgumentException ex)
+                {
+                    errors[name] = ex;
+                }
+            });
+
+            foreach (var name in level)
+            {
+                if (built.TryGetValue(name, out var compiled))
+                {
+                    grammars[name] = compiled;
+                }
+                else
+                {
+                    failures.Add(new GrammarLoadFailure(name, byName[name].Path, errors[name].Message, errors[name]));
+                }
+            }
+        }
+
+        var ordered = failures
+            .OrderBy(f => f.Name, StringComparer.Ordinal)
+            .ThenBy(f => f.Path, StringComparer.Ordinal)
+            .ToList();
+
+        return new GrammarContainer(grammars, ordered, levels);
+    }
+
+    private static List<IReadOnlyList<string>> ComputeLevels(
+        Dictionary<string, (Grammar Grammar, string? Path)> byName,
+        HashSet<string> failed,
+        List<GrammarLoadFailure> failures)
+    {
+        var remaining = new SortedDictionary<string, IReadOnlyList<string>>(StringComparer.Ordinal);
+        foreach (var (name, source) in byName)
+        {
+            var dependencies = GetDependencies(source.Grammar);
+            if (dependencies.FirstOrDefault(d => !byName.ContainsKey(d) && !failed.Contains(d)) is { } missing)
+            {
+                failures.Add(new GrammarLoadFailure(name, source.Path, $"Dependency '{missing}' is not in the container"));
+                failed.Add(name);
+            }
+            else
+            {
+                remaining[name] = dependencies;
+            }
+        }
+
+        var levels = new List<IReadOnlyList<string>>();
+        var placed = new HashSet<string>(StringComparer.Ordinal);
+
+        while (remaining.Count > 0)
+        {
+            // A grammar whose dependency already failed fails too, without waiting for a level.
+            var doomed = remaining.Where(r => r.Value.Any(failed.Contains)).Select(r => r.Key).ToList();
+            foreach (var name in doomed)
+            {
+                var dependency = remaining[name].First(failed.Contains);
+                failures.Add(new GrammarLoadFailure(name, byName[name].Path, $"Dependency '{dependency}' failed to load"));
+                failed.Add(name);
+                remaining.Remove(name);
+            }
+
+            if (doomed.Count > 0)
+            {
+                continue;
+            }
+
+            var level = remaining.Where(r => r.Value.All(placed.Contains)).Select(r => r.Key).ToList();
+            if (level.Count == 0)
+            {
+                foreach (var name in remaining.Keys)
+                {
+                    failures.Add(new GrammarLoadFailure(name, byName[name].Path,
+                        $"Dependency cycle between {string.Join(", ", remaining.Keys)}"));
+                }
+
+                break;
+            }
+
+            foreach (var name in level)
+            {
+                remaining.Remove(name);
+                placed.Add(name);
+            }
+
+            levels.Add(level);
+        }
+
+        return levels;
+    }
+
+    private static Grammar Resolve(Grammar grammar, IReadOnlyList<Grammar> dependencies)
+    {
+        if (dependencies.Count == 0)
+        {
+            return grammar;
+        }
+
+        var resolved = new Grammar
+        {
+            Name = grammar.Name,
+            Language = grammar.Language,
+            Created = grammar.Created,
+            Version = grammar.Version,
+            Metadata = new Dictionary<string, string>(grammar.Metadata)
+        };
+
+        var ruleNames = new HashSet<string>(grammar.ProductionRules.Rules.Select(r => r.Name), StringComparer.Ordinal);
+        var tokenNames = new HashSet<string>(grammar.TokenRules.Patterns.Select(p => p.Name), StringComparer.Ordinal);
+        resolved.ProductionRules.Rules.AddRange(grammar.ProductionRules.Rules);
+        resolved.TokenRules.Patterns.AddRange(grammar.TokenRules.Patterns);
+        resolved.Annotations.AddRange(grammar.Annotations);
+
+        foreach (var dependency in dependencies)
+        {
+            // Inherited and imported grammars are already resolved, so one pass over each brings in its own
+            // dependencies as well.
+            var newRules = dependency.ProductionRules.Rules.Where(r => !ruleNames.Contains(r.Name)).ToList();
+            var newPatterns = dependency.TokenRules.Patterns.Where(p => tokenNames.Add(p.Name)).ToList();
+            resolved.ProductionRules.Rules.AddRange(newRules);
+            resolved.TokenRules.Patterns.AddRange(newPatterns);
+
+            // Documentation travels with the definitions it describes.
+            var taken = newRules.Select(r => r.Name).Concat(newPatterns.Select(p => p.Name)).ToHashSet();
+            resolved.Annotations.AddRange(dependency.Annotations.Where(a => taken.Contains(a.Target)));
+            ruleNames.UnionWith(newRules.Select(r => r.Name));
+        }
+
+        if (grammar.Metadata.TryGetValue(InheritsKey, out var inherits))
+        {
+            var baseGrammar = dependencies.First(d => d.Name == inherits.Trim());
+            foreach (var (key, value) in baseGrammar.Metadata)
+            {
+                if (key is not (InheritsKey or ImportsKey))
+                {
+                    resolved.Metadata.TryAdd(key, value);
+                }
+            }
+        }
+
+        return resolved;
+    }
+}
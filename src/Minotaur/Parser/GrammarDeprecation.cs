@@ -0,0 +1,270 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A deprecation of a rule or of some of its alternatives, written as a
+/// <c>// @deprecated("message", since = "2.0", replace_with = "...")</c> annotation after the rule's first line or
+/// after the line holding the alternatives. Every argument is optional. The replacement is a rewrite template over
+/// the deprecated alternative's children: each child that is not a literal is labeled <c>$name</c> after its rule or
+/// token, <c>$pattern</c> for an inline pattern, with a number appended when the name occurs more than once, so
+/// <c>"echo" &lt;expr&gt; ";"</c> can be rewritten as <c>print $expr ;</c>.
+/// </summary>
+public sealed class GrammarDeprecation
+{
+    private static readonly Regex Argument = new(@"\G\s*(?:(?<name>[A-Za-z_]+)\s*=\s*)?""(?<value>(?:[^""\\]|\\.)*)""\s*(?:,|$)", RegexOptions.CultureInvariant);
+    private static readonly Regex Escape = new(@"\\(.)", RegexOptions.CultureInvariant);
+    private static readonly Regex Label = new(@"\$[A-Za-z_][A-Za-z0-9_]*", RegexOptions.CultureInvariant);
+    private static readonly ConditionalWeakTable<CompiledAlternative, StrongBox<StructuralPattern?>> Patterns = new();
+
+    private GrammarDeprecation(string message, string? since, string? replaceWith)
+    {
+        Message = message;
+        Since = since;
+        ReplaceWith = replaceWith;
+    }
+
+    /// <summary>
+    /// Gets the message explaining the deprecation; empty if none was given.
+    /// </summary>
+    public string Message { get; }
+
+    /// <summary>
+    /// Gets the version the construct was deprecated in, or null if not given.
+    /// </summary>
+    public string? Since { get; }
+
+    /// <summary>
+    /// Gets the rewrite template of the construct's replacement, or null if there is none.
+    /// </summary>
+    public string? ReplaceWith { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the deprecation annotates the whole rule rather than some of its alternatives.
+    /// </summary>
+    public bool AppliesToRule { get; private set; }
+
+    /// <summary>
+    /// Reads the text of a deprecation annotation: an optional quoted message followed by optional
+    /// <c>since</c> and <c>replace_with</c> arguments, optionally in parentheses.
+    /// </summary>
+    /// <param name="text">The annotation text after <c>@deprecated</c>.</param>
+    /// <returns>The deprecation.</returns>
+    /// <exception cref="FormatException">The text is not a valid argument list.</exception>
+    public static GrammarDeprecation Parse(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var arguments = text.Trim();
+        if (arguments.StartsWith('('))
+        {
+            arguments = arguments.EndsWith(')')
+                ? arguments[1..^1].Trim()
+                : throw new FormatException($"'{text}' has no closing parenthesis");
+        }
+
+        var values = new Dictionary<string, string>();
+        var position = 0;
+        while (position < arguments.Length)
+        {
+            var match = Argument.Match(arguments, position);
+            if (!match.Success)
+            {
+                throw new FormatException($"'{text}' is not a list of quoted values at '{arguments[position..].Trim()}'");
+            }
+
+            var name = match.Groups["name"].Success ? match.Groups["name"].Value : "message";
+            if (name is not ("message" or "since" or "replace_with"))
+            {
+                throw new FormatException($"'{text}' has unknown argument '{name}'; expected since or replace_with");
+            }
+
+            if (!values.TryAdd(name, Escape.Replace(match.Groups["value"].Value, m => m.Groups[1].Value == "n" ? "\n" : m.Groups[1].Value)))
+            {
+                throw new FormatException($"'{text}' gives {name} more than once");
+            }
+
+            position = match.Index + match.Length;
+        }
+
+        return new GrammarDeprecation(values.GetValueOrDefault("message", string.Empty), values.GetValueOrDefault("since"), values.GetValueOrDefault("replace_with"));
+    }
+
+    /// <summary>
+    /// Gets the label of each symbol of an alternative in rewrite templates, or null for literals and layout.
+    /// </summary>
+    /// <param name="alternative">The alternative.</param>
+    /// <param name="layout">The grammar's layout rule, or null if it has none.</param>
+    /// <returns>The labels without their dollar signs, one per symbol.</returns>
+    public static IReadOnlyList<string?> GetLabels(CompiledAlternative alternative, string? layout)
+    {
+        ArgumentNullException.ThrowIfNull(alternative);
+
+        var names = alternative.Symbols
+            .Select(s => s.Kind switch
+            {
+                GrammarSymbolKind.Literal => null,
+                GrammarSymbolKind.Rule when s.Name == layout => null,
+                GrammarSymbolKind.Pattern => "pattern",
+                _ => s.Name.Replace('-', '_')
+            })
+            .ToList();
+
+        var counts = names.OfType<string>().GroupBy(n => n).ToDictionary(g => g.Key, g => g.Count());
+        var numbers = new Dictionary<string, int>();
+        return names.Select(n => n == null || counts[n] == 1 ? n : n + (numbers[n] = numbers.GetValueOrDefault(n) + 1)).ToList();
+    }
+
+    /// <summary>
+    /// Returns the deprecation as shown in documentation and hovers, without the replacement.
+    /// </summary>
+    /// <returns>The description.</returns>
+    public override string ToString()
+    {
+        var since = Since != null ? $"Deprecated since {Since}" : "Deprecated";
+        return Message.Length > 0 ? $"{since}: {Message}" : since;
+    }
+
+    /// <summary>
+    /// Determines whether the replacement only uses labels of an alternative.
+    /// </summary>
+    internal bool CanRewrite(CompiledAlternative alternative, string? layout)
+    {
+        var labels = GetLabels(alternative, layout);
+        return ReplaceWith != null && Label.Matches(ReplaceWith).All(m => labels.Contains(m.Value[1..]));
+    }
+
+    internal GrammarDeprecation ForRule()
+    {
+        return new GrammarDeprecation(Message, Since, ReplaceWith) { AppliesToRule = true };
+    }
+
+    /// <summary>
+    /// Reports a warning for every node of a parse that reduced a deprecated rule or alternative, unless an
+    /// <c>allow</c> directive applies to it. A warning carries a quick fix when the deprecation has a replacement
+    /// that the structural rewrite machinery can apply to the node.
+    /// </summary>
+    /// <param name="parse">A successful parse.</param>
+    /// <returns>The warnings in document order.</returns>
+    internal static List<Diagnostic> Check(ParseResult parse)
+    {
+        var warnings = new List<Diagnostic>();
+        if (parse.Tree == null || parse.Grammar == null)
+        {
+            return warnings;
+        }
+
+        var grammar = parse.Grammar;
+        var layout = grammar.IsScannerless ? grammar.Source.Metadata.GetValueOrDefault(CompiledGrammar.LayoutKey) : null;
+        var matches = new Dictionary<StructuralPattern, IReadOnlyList<StructuralMatch>>();
+        DirectiveSet? directives = null;
+
+        var stack = new Stack<CognitiveGraphNode>();
+        stack.Push(parse.Tree);
+        while (stack.Count > 0)
+        {
+            // A deferred region is checked by its own parse when it is materialized.
+            var node = stack.Pop();
+            if (node is DeferredNode { IsMaterialized: false })
+            {
+                continue;
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                stack.Push(node.Children[i]);
+            }
+
+            if (node is not NonTerminalNode { SourcePosition: not null } nonTerminal ||
+                grammar.GetRule(nonTerminal.RuleName) is not { } rule ||
+                nonTerminal.ProductionIndex < 0 || nonTerminal.ProductionIndex >= rule.Alternatives.Count ||
+                rule.Alternatives[nonTerminal.ProductionIndex] is not { Deprecation: { } deprecation } alternative)
+            {
+                continue;
+            }
+
+            directives ??= DirectiveSet.Read(parse);
+            if (directives.GetApplying(DirectiveSyntax.AllowName, node).Any(d => d.Values.Count == 0 || d.Values.Contains(DiagnosticCodes.DeprecatedSyntax, StringComparer.OrdinalIgnoreCase)))
+            {
+                continue;
+            }
+
+            var subject = deprecation.AppliesToRule ? $"<{rule.Name}>" : alternative.ToString();
+            var key = DiagnosticCodes.DeprecatedSyntax + (deprecation.Since != null ? ".since" : string.Empty) + (deprecation.Message.Length > 0 ? ".message" : string.Empty);
+            var warning = new Diagnostic
+            {
+                Code = DiagnosticCodes.DeprecatedSyntax,
+                Severity = DiagnosticSeverity.Warning,
+                Location = node.SourcePosition,
+                Data = { ["rule"] = rule.Name }
+            }.WithMessage(key, ("syntax", subject), ("since", deprecation.Since ?? string.Empty), ("message", deprecation.Message));
+
+            if (deprecation.Since != null)
+            {
+                warning.Data["since"] = deprecation.Since;
+            }
+
+            if (deprecation.ReplaceWith != null && deprecation.CanRewrite(alternative, layout) &&
+                Patterns.GetValue(alternative, a => new StrongBox<StructuralPattern?>(CompilePattern(grammar, a, layout))).Value is { } pattern)
+            {
+                if (!matches.TryGetValue(pattern, out var found))
+                {
+                    found = pattern.FindAll(parse);
+                    matches[pattern] = found;
+                }
+
+                if (found.FirstOrDefault(m => ReferenceEquals(m.Node, node)) is { } match)
+                {
+                    var replacement = pattern.Rewrite(parse, match, deprecation.ReplaceWith);
+                    warning.Data["replacement"] = replacement;
+                    warning.Data["fix"] = new TextEdit(match.Start, match.End - match.Start, replacement);
+                }
+            }
+
+            warnings.Add(warning);
+        }
+
+        return warnings;
+    }
+
+    /// <summary>
+    /// Compiles the structural pattern of an alternative, its literals as written and its other children as their
+    /// labels, or returns null if the grammar does not support structural patterns.
+    /// </summary>
+    private static StructuralPattern? CompilePattern(CompiledGrammar grammar, CompiledAlternative alternative, string? layout)
+    {
+        var labels = GetLabels(alternative, layout);
+        var text = string.Join(" ", alternative.Symbols
+            .Select((symbol, i) => symbol.Kind == GrammarSymbolKind.Literal ? symbol.Name : labels[i] != null ? "$" + labels[i] : null)
+            .OfType<string>());
+
+        try
+        {
+            return StructuralPattern.Compile(grammar, alternative.Rule.Name, text);
+        }
+        catch (ArgumentException)
+        {
+            return null;
+        }
+    }
+}
@@ -0,0 +1,215 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The documentation of a rule or token pattern, collected from its annotations.
+/// </summary>
+/// <param name="Name">The rule or token name.</param>
+/// <param name="IsRule">True for a production rule, false for a token pattern.</param>
+/// <param name="Description">The description, with several description lines joined by spaces; null if none.</param>
+/// <param name="Snippets">The editor snippets in annotation order.</param>
+/// <param name="Examples">The examples in annotation order.</param>
+public sealed record SymbolDocs(string Name, bool IsRule, string? Description, IReadOnlyList<string> Snippets, IReadOnlyList<string> Examples);
+
+/// <summary>
+/// An example annotation that its rule or token does not accept.
+/// </summary>
+/// <param name="Annotation">The example annotation.</param>
+/// <param name="Diagnostics">The errors reported for the example.</param>
+public sealed record ExampleFailure(GrammarAnnotation Annotation, IReadOnlyList<Diagnostic> Diagnostics)
+{
+    /// <summary>
+    /// Describes the failure with the annotation's line and the example's errors, one per line.
+    /// </summary>
+    /// <returns>The description.</returns>
+    public override string ToString()
+    {
+        var text = new StringBuilder($"Example of <{Annotation.Target}> at line {Annotation.Line} does not parse: {Annotation.Text}");
+        foreach (var diagnostic in Diagnostics)
+        {
+            text.Append("\n  ").Append(diagnostic);
+        }
+
+        return text.ToString();
+    }
+}
+
+/// <summary>
+/// The documentation of a grammar's rules and token patterns, read from <c>// @description</c>,
+/// <c>// @snippet</c> and <c>// @example</c> comment lines after their definitions. Completion and
+/// documentation generators read it from <see cref="CompiledGrammar.Docs"/>.
+/// </summary>
+/// <remarks>
+/// A literal has the documentation of the first documented rule with an alternative beginning with it, so
+/// <c>"if"</c> is described by the <c>&lt;if_statement&gt;</c> construct it introduces.
+/// </remarks>
+public sealed class GrammarDocs
+{
+    private readonly Dictionary<string, SymbolDocs> _symbols;
+    private readonly Dictionary<string, SymbolDocs> _literals;
+
+    private GrammarDocs(List<SymbolDocs> symbols, Dictionary<string, SymbolDocs> literals)
+    {
+        Symbols = symbols;
+        _symbols = symbols.ToDictionary(s => s.Name);
+        _literals = literals;
+    }
+
+    /// <summary>
+    /// Gets the documented rules and token patterns, in the order they were first annotated.
+    /// </summary>
+    public IReadOnlyList<SymbolDocs> Symbols { get; }
+
+    /// <summary>
+    /// Gets the documentation of a rule or token pattern.
+    /// </summary>
+    /// <param name="name">The rule or token name.</param>
+    /// <returns>The documentation, or null if the symbol has no annotations.</returns>
+    public SymbolDocs? Get(string name)
+    {
+        return _symbols.GetValueOrDefault(name);
+    }
+
+    /// <summary>
+    /// Gets the documentation of a terminal as it appears in expected-terminal lists.
+    /// </summary>
+    /// <param name="key">The terminal key: a token name or a quoted literal.</param>
+    /// <returns>The token's documentation or the construct the literal introduces, or null.</returns>
+    public SymbolDocs? GetForTerminal(string key)
+    {
+        return _literals.GetValueOrDefault(key) ?? Get(key);
+    }
+
+    /// <summary>
+    /// Collects the documentation of a grammar.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <returns>The documentation.</returns>
+    public static GrammarDocs Create(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var symbols = new List<SymbolDocs>();
+        foreach (var group in grammar.Source.Annotations.GroupBy(a => a.Target))
+        {
+            var descriptions = group.Where(a => a.Kind == GrammarAnnotationKind.Description).Select(a => a.Text).ToList();
+            symbols.Add(new SymbolDocs(
+                group.Key,
+                grammar.GetRule(group.Key) != null,
+                descriptions.Count > 0 ? string.Join(' ', descriptions) : null,
+                group.Where(a => a.Kind == GrammarAnnotationKind.Snippet).Select(a => a.Text).ToList(),
+                group.Where(a => a.Kind == GrammarAnnotationKind.Example).Select(a => a.Text).ToList()));
+        }
+
+        var byName = symbols.ToDictionary(s => s.Name);
+        var literals = new Dictionary<string, SymbolDocs>();
+        var noRules = new HashSet<string>();
+        foreach (var rule in grammar.Rules)
+        {
+            if (!byName.TryGetValue(rule.Name, out var docs) || (docs.Description == null && docs.Snippets.Count == 0))
+            {
+                continue;
+            }
+
+            // The alternative as written, so an inserted layout rule does not hide the leading literal.
+            foreach (var alternative in rule.Alternatives)
+            {
+                var first = GrammarSymbol.ParseAlternative(alternative.Text, noRules).FirstOrDefault();
+                if (first?.Kind == GrammarSymbolKind.Literal)
+                {
+                    literals.TryAdd(first.Key, docs);
+                }
+            }
+        }
+
+        return new GrammarDocs(symbols, literals);
+    }
+
+    /// <summary>
+    /// Checks that every example is accepted by the rule or token it documents. Rule examples are parsed
+    /// completely from that rule with all features enabled; token examples must lex as one token of the kind.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <returns>The examples that failed, in annotation order.</returns>
+    public static IReadOnlyList<ExampleFailure> Validate(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var examples = grammar.Source.Annotations.Where(a => a.Kind == GrammarAnnotationKind.Example).ToList();
+        if (examples.Count == 0)
+        {
+            return Array.Empty<ExampleFailure>();
+        }
+
+        var parser = new GeneralizedParser(grammar);
+        var failures = new List<ExampleFailure>();
+        foreach (var example in examples)
+        {
+            var errors = grammar.GetRule(example.Target) != null
+                ? ParseExample(parser, example)
+                : LexExample(parser.Lexer, example);
+            if (errors.Count > 0)
+            {
+                failures.Add(new ExampleFailure(example, errors));
+            }
+        }
+
+        return failures;
+    }
+
+    private static List<Diagnostic> ParseExample(GeneralizedParser parser, GrammarAnnotation example)
+    {
+        var result = parser.Parse(example.Text, new ParseOptions
+        {
+            StartRule = example.Target,
+            End = EntryPointEnd.Complete,
+            Features = parser.Grammar.Features,
+            KeywordCorrectionDistance = 0
+        });
+
+        return result.IsSuccess
+            ? new List<Diagnostic>()
+            : result.Diagnostics.Where(d => d.Severity == DiagnosticSeverity.Error).ToList();
+    }
+
+    private static List<Diagnostic> LexExample(GrammarLexer lexer, GrammarAnnotation example)
+    {
+        var result = lexer.Tokenize(example.Text);
+        var errors = result.Diagnostics.Where(d => d.Severity == DiagnosticSeverity.Error).ToList();
+        if (errors.Count == 0 && (result.Tokens.Count != 1 || result.Tokens[0].Kind != example.Target))
+        {
+            errors.Add(new Diagnostic
+            {
+                Code = DiagnosticCodes.UnexpectedToken,
+                Location = new LineIndex(example.Text).GetPosition(0, example.Text.Length, null),
+                Data = { ["expected"] = new List<string> { example.Target } }
+            }.WithMessage("E0001.example",
+                ("kind", example.Target),
+                ("count", result.Tokens.Count),
+                ("found", string.Join(" ", result.Tokens.Select(t => t.Kind)))));
+        }
+
+        return errors;
+    }
+}
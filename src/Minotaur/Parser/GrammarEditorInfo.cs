@@ -0,0 +1,409 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using System.Text.Encodings.Web;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A pair of delimiters an editor matches, closes or surrounds a selection with.
+/// </summary>
+/// <param name="Open">The opening delimiter.</param>
+/// <param name="Close">The closing delimiter.</param>
+/// <param name="NotIn">The token scopes (<c>string</c>, <c>comment</c>) in which typing the opening delimiter does
+/// not insert the closing one; empty for brackets.</param>
+public sealed record EditorPair(string Open, string Close, IReadOnlyList<string> NotIn)
+{
+    /// <summary>
+    /// Gets a value indicating whether the pair is a quote, which opens and closes with the same text.
+    /// </summary>
+    public bool IsQuote => Open == Close;
+}
+
+/// <summary>
+/// The delimiters of a block comment.
+/// </summary>
+/// <param name="Start">The text that starts the comment.</param>
+/// <param name="End">The text that ends the comment.</param>
+public sealed record BlockCommentSyntax(string Start, string End);
+
+/// <summary>
+/// The language configuration an editor needs for a grammar, derived from the grammar itself: bracket pairs from
+/// rules of the shape <c>open ... close</c>, quotes from string-like token patterns and comment syntax from
+/// comment token patterns. <see cref="ToLanguageConfiguration"/> writes it as a VS Code language-configuration
+/// file, and <see cref="GetClosingText"/> answers the closing text to insert as a delimiter is typed.
+/// </summary>
+/// <remarks>
+/// <para>
+/// A punctuation literal pairs with the nearest punctuation literal after it when every alternative using the first
+/// has the second after it and every alternative using the second has the first before it, so <c>"(" ")"</c> pairs
+/// while <c>"&lt;"</c> does not when it is also a comparison operator. A quote literal pairs with itself when every
+/// alternative using it uses it an even number of times, and a token pattern that starts and ends with the same
+/// quote makes that quote a pair.
+/// </para>
+/// <para>
+/// Delimiters that also appear alone, such as the quote of a Rust lifetime <c>'a</c>, are vetoed with a
+/// <c>// @unpaired("'")</c> annotation after the rule or token pattern that uses them alone.
+/// </para>
+/// </remarks>
+public sealed class GrammarEditorInfo
+{
+    /// <summary>
+    /// The characters a closing delimiter is inserted before when its opening delimiter is typed; at other
+    /// characters the opening delimiter most likely starts an expression that should not be closed yet.
+    /// </summary>
+    public const string AutoCloseBefore = ";:.,=}])> \t\r\n";
+
+    private const string Quotes = "\"'`";
+    private const string Metacharacters = @"\^$.|?*+()[]{}";
+    private const string Quantifiers = "*+?{";
+
+    private GrammarEditorInfo(string? lineComment, BlockCommentSyntax? blockComment, List<EditorPair> brackets, List<EditorPair> autoClosingPairs, List<string> unpaired)
+    {
+        LineComment = lineComment;
+        BlockComment = blockComment;
+        Brackets = brackets;
+        AutoClosingPairs = autoClosingPairs;
+        Unpaired = unpaired;
+    }
+
+    /// <summary>
+    /// Gets the text that starts a line comment, or null if the grammar has none.
+    /// </summary>
+    public string? LineComment { get; }
+
+    /// <summary>
+    /// Gets the delimiters of a block comment, or null if the grammar has none.
+    /// </summary>
+    public BlockCommentSyntax? BlockComment { get; }
+
+    /// <summary>
+    /// Gets the bracket pairs, in the order their opening delimiters first appear in the grammar.
+    /// </summary>
+    public IReadOnlyList<EditorPair> Brackets { get; }
+
+    /// <summary>
+    /// Gets the pairs closed as they are typed: the brackets followed by the quotes.
+    /// </summary>
+    public IReadOnlyList<EditorPair> AutoClosingPairs { get; }
+
+    /// <summary>
+    /// Gets the delimiters vetoed by <c>// @unpaired</c> annotations.
+    /// </summary>
+    public IReadOnlyList<string> Unpaired { get; }
+
+    /// <summary>
+    /// Gets the closing text to insert after a delimiter has been typed, as a language server answers
+    /// <c>onTypeFormatting</c> requests.
+    /// </summary>
+    /// <param name="text">The document text, including the typed delimiter.</param>
+    /// <param name="offset">The offset just after the typed delimiter.</param>
+    /// <returns>The closing delimiter, or null if nothing should be inserted.</returns>
+    /// <remarks>
+    /// Nothing is inserted before a character outside <see cref="AutoCloseBefore"/>. A quote is not closed after
+    /// a letter or digit, as in <c>it's</c>, or when it ends a string opened earlier on the line.
+    /// </remarks>
+    public string? GetClosingText(string text, int offset)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+        ArgumentOutOfRangeException.ThrowIfNegative(offset);
+        ArgumentOutOfRangeException.ThrowIfGreaterThan(offset, text.Length);
+
+        if (offset < text.Length && !AutoCloseBefore.Contains(text[offset]))
+        {
+            return null;
+        }
+
+        foreach (var pair in AutoClosingPairs.OrderByDescending(p => p.Open.Length))
+        {
+            if (!text.AsSpan(0, offset).EndsWith(pair.Open, StringComparison.Ordinal))
+            {
+                continue;
+            }
+
+            var start = offset - pair.Open.Length;
+            if (pair.IsQuote)
+            {
+                var lineStart = text.LastIndexOf('\n', Math.Max(start - 1, 0)) + 1;
+                var before = text[Math.Min(lineStart, start)..start];
+                if ((start > 0 && char.IsLetterOrDigit(text[start - 1])) || CountOccurrences(before, pair.Open) % 2 == 1)
+                {
+                    return null;
+                }
+            }
+
+            return pair.Close;
+        }
+
+        return null;
+    }
+
+    /// <summary>
+    /// Writes the information as a VS Code language-configuration file.
+    /// </summary>
+    /// <returns>The JSON text.</returns>
+    public string ToLanguageConfiguration()
+    {
+        var root = new JsonObject();
+        if (LineComment != null || BlockComment != null)
+        {
+            var comments = new JsonObject();
+            if (LineComment != null)
+            {
+                comments["lineComment"] = LineComment;
+            }
+
+            if (BlockComment != null)
+            {
+                comments["blockComment"] = new JsonArray(BlockComment.Start, BlockComment.End);
+            }
+
+            root["comments"] = comments;
+        }
+
+        root["brackets"] = new JsonArray(Brackets.Select(p => (JsonNode)new JsonArray(p.Open, p.Close)).ToArray());
+        root["autoClosingPairs"] = new JsonArray(AutoClosingPairs.Select(p =>
+        {
+            var pair = new JsonObject { ["open"] = p.Open, ["close"] = p.Close };
+            if (p.NotIn.Count > 0)
+            {
+                pair["notIn"] = new JsonArray(p.NotIn.Select(scope => (JsonNode)scope).ToArray());
+            }
+
+            return (JsonNode)pair;
+        }).ToArray());
+        root["surroundingPairs"] = new JsonArray(AutoClosingPairs.Select(p => (JsonNode)new JsonArray(p.Open, p.Close)).ToArray());
+
+        return root.ToJsonString(new JsonSerializerOptions { WriteIndented = true, Encoder = JavaScriptEncoder.UnsafeRelaxedJsonEscaping });
+    }
+
+    /// <summary>
+    /// Derives the editor information of a grammar.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <returns>The editor information.</returns>
+    public static GrammarEditorInfo Create(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var unpaired = grammar.Source.Annotations
+            .Where(a => a.Kind == GrammarAnnotationKind.Unpaired)
+            .Select(a => ParseDelimiter(a.Text))
+            .Where(d => d.Length > 0)
+            .Distinct()
+            .ToList();
+
+        string? lineComment = null;
+        BlockCommentSyntax? blockComment = null;
+        var quotes = new List<string>();
+        foreach (var pattern in grammar.Source.TokenRules.Patterns)
+        {
+            var prefix = LiteralPrefix(pattern.Pattern);
+            var suffix = LiteralSuffix(pattern.Pattern);
+            if (pattern.Type == TokenType.Comment || pattern.Name.Contains("COMMENT"))
+            {
+                if (prefix.Length > 0 && suffix.Length == 0)
+                {
+                    lineComment ??= prefix;
+                }
+                else if (prefix.Length > 0 && suffix.Length > 0)
+                {
+                    blockComment ??= new BlockCommentSyntax(prefix, suffix);
+                }
+            }
+            else if (pattern.Type != TokenType.Whitespace && prefix.Length > 0 && prefix == suffix && prefix.All(Quotes.Contains) && pattern.Pattern.Length > 2 * prefix.Length)
+            {
+                quotes.Add(prefix);
+            }
+        }
+
+        // The punctuation literals of every alternative as written, so inserted layout rules do not come between them.
+        var noRules = new HashSet<string>();
+        var alternatives = grammar.Rules
+            .SelectMany(r => r.Alternatives)
+            .Select(a => GrammarSymbol.ParseAlternative(a.Text, noRules)
+                .Where(s => s.Kind == GrammarSymbolKind.Literal && IsPunctuation(s.Name))
+                .Select(s => s.Name)
+                .ToList())
+            .Where(literals => literals.Count > 0)
+            .ToList();
+
+        var brackets = new List<EditorPair>();
+        var paired = new HashSet<string>(unpaired);
+        foreach (var open in alternatives.SelectMany(literals => literals).Distinct().ToList())
+        {
+            if (Quotes.Contains(open))
+            {
+                if (alternatives.All(literals => literals.Count(l => l == open) % 2 == 0))
+                {
+                    quotes.Add(open);
+                }
+
+                continue;
+            }
+
+            if (paired.Contains(open))
+            {
+                continue;
+            }
+
+            var close = FindClose(alternatives, open, paired);
+            if (close != null)
+            {
+                brackets.Add(new EditorPair(open, close, Array.Empty<string>()));
+                paired.Add(open);
+                paired.Add(close);
+            }
+        }
+
+        var notIn = lineComment != null || blockComment != null ? new[] { "string", "comment" } : new[] { "string" };
+        var autoClosingPairs = brackets
+            .Concat(quotes.Distinct().Where(q => !unpaired.Contains(q)).Select(q => new EditorPair(q, q, notIn)))
+            .ToList();
+
+        return new GrammarEditorInfo(lineComment, blockComment, brackets, autoClosingPairs, unpaired);
+    }
+
+    // The nearest literal after the first use of the opening delimiter that follows every use of it and is preceded
+    // by it everywhere it is used.
+    private static string? FindClose(List<List<string>> alternatives, string open, HashSet<string> paired)
+    {
+        List<string>? followers = null;
+        foreach (var literals in alternatives)
+        {
+            for (var i = 0; i < literals.Count; i++)
+            {
+                if (literals[i] != open)
+                {
+                    continue;
+                }
+
+                var after = literals.Skip(i + 1).Where(l => l != open).Distinct().ToList();
+                followers = followers == null ? after : followers.Where(after.Contains).ToList();
+            }
+        }
+
+        return followers?.FirstOrDefault(close =>
+            !paired.Contains(close) &&
+            !Quotes.Contains(close) &&
+            alternatives.All(literals => Enumerable.Range(0, literals.Count).All(j => literals[j] != close || literals.Take(j).Contains(open))));
+    }
+
+    private static bool IsPunctuation(string literal)
+    {
+        return literal.Length > 0 && literal.All(c => !char.IsLetterOrDigit(c) && c != '_' && !char.IsWhiteSpace(c));
+    }
+
+    // The text of an annotation such as @unpaired("'"): a delimiter, optionally quoted and optionally in parentheses.
+    private static string ParseDelimiter(string text)
+    {
+        var delimiter = text.Trim();
+        if (delimiter.Length >= 2 && delimiter[0] == '(' && delimiter[^1] == ')')
+        {
+            delimiter = delimiter[1..^1].Trim();
+        }
+
+        if (delimiter.Length >= 3 && delimiter[0] is '"' or '\'' && delimiter[^1] == delimiter[0])
+        {
+            delimiter = delimiter[1..^1];
+        }
+
+        return delimiter;
+    }
+
+    // The characters every match of a pattern starts with.
+    private static string LiteralPrefix(string pattern)
+    {
+        var prefix = new StringBuilder();
+        for (var i = 0; i < pattern.Length; i++)
+        {
+            char literal;
+            if (pattern[i] == '\\' && i + 1 < pattern.Length && !char.IsLetterOrDigit(pattern[i + 1]))
+            {
+                literal = pattern[++i];
+            }
+            else if (Metacharacters.Contains(pattern[i]))
+            {
+                break;
+            }
+            else
+            {
+                literal = pattern[i];
+            }
+
+            // A quantified character may be missing or repeated.
+            if (i + 1 < pattern.Length && Quantifiers.Contains(pattern[i + 1]))
+            {
+                break;
+            }
+
+            prefix.Append(literal);
+        }
+
+        return prefix.ToString();
+    }
+
+    // The characters every match of a pattern ends with.
+    private static string LiteralSuffix(string pattern)
+    {
+        var suffix = new StringBuilder();
+        for (var i = pattern.Length - 1; i >= 0; i--)
+        {
+            var backslashes = 0;
+            while (i - backslashes - 1 >= 0 && pattern[i - backslashes - 1] == '\\')
+            {
+                backslashes++;
+            }
+
+            if (backslashes % 2 == 1)
+            {
+                if (char.IsLetterOrDigit(pattern[i]))
+                {
+                    break;
+                }
+
+                suffix.Insert(0, pattern[i]);
+                i--;
+                continue;
+            }
+
+            if (Metacharacters.Contains(pattern[i]))
+            {
+                break;
+            }
+
+            suffix.Insert(0, pattern[i]);
+        }
+
+        return suffix.ToString();
+    }
+
+    private static int CountOccurrences(string text, string value)
+    {
+        var count = 0;
+        for (var index = text.IndexOf(value, StringComparison.Ordinal); index >= 0; index = text.IndexOf(value, index + value.Length, StringComparison.Ordinal))
+        {
+            count++;
+        }
+
+        return count;
+    }
+}
@@ -0,0 +1,163 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The kind of grammar change a <see cref="GrammarHint"/> suggests.
+/// </summary>
+public enum GrammarHintKind
+{
+    /// <summary>
+    /// Alternatives share a prefix, so the parser carries one item per alternative across it; factoring the
+    /// prefix out leaves one.
+    /// </summary>
+    LeftFactor,
+
+    /// <summary>
+    /// An alternative recurses at both ends, like <c>&lt;e&gt; ::= &lt;e&gt; "+" &lt;e&gt;</c>, so every split of
+    /// the input is a derivation; recursing on one side only leaves one.
+    /// </summary>
+    OneSidedRecursion
+}
+
+/// <summary>
+/// A suggested grammar change for the rule where a parse stalled, attached as the "hints" data of a
+/// <see cref="Diagnostics.DiagnosticCodes.ParseStalled"/> error.
+/// </summary>
+/// <param name="Kind">The kind of change.</param>
+/// <param name="Rule">The rule to change.</param>
+/// <param name="Alternative">The alternative responsible, as written in the grammar.</param>
+/// <param name="Reentries">The number of positions the rule was re-entered from around the stall point.</param>
+/// <param name="CommonPrefix">The symbols to factor out, for <see cref="GrammarHintKind.LeftFactor"/>; otherwise empty.</param>
+/// <param name="Suggestion">The change, with the rewritten rules.</param>
+public sealed record GrammarHint(GrammarHintKind Kind, string Rule, string Alternative, int Reentries, IReadOnlyList<string> CommonPrefix, string Suggestion)
+{
+    /// <summary>
+    /// Returns the hint as one line, as the CLI prints it.
+    /// </summary>
+    /// <returns>The hint description.</returns>
+    public override string ToString()
+    {
+        return $"{Kind} <{Rule}> ::= {Alternative} (re-entered from {Reentries} positions): {Suggestion}";
+    }
+}
+
+/// <summary>
+/// Derives <see cref="GrammarHint"/>s from the chart around a stall. The recognizer keeps every (rule, origin)
+/// pair once and is indifferent to the order of alternatives, so the work that can still blow up is the number
+/// of items per rule: alternatives tracked in parallel across a shared prefix, and alternatives recursing at
+/// both ends, which re-enter their rule from every earlier position. Rules are ranked by their items in the sets
+/// before the stall point, busiest first.
+/// </summary>
+internal static class StallAnalyzer
+{
+    private const int Window = 16;
+
+    public static List<GrammarHint> Analyze(GeneralizedParser.EarleySet?[] chart, int position, CompiledGrammar grammar)
+    {
+        var items = new List<GeneralizedParser.EarleyItem>();
+        for (var i = Math.Max(0, position - Window); i <= Math.Min(position + 1, chart.Length - 1); i++)
+        {
+            if (chart[i] is { } set)
+            {
+                items.AddRange(set.Items);
+            }
+        }
+
+        var hints = new List<GrammarHint>();
+        foreach (var rule in items.GroupBy(it => it.Alternative.Rule).OrderByDescending(g => g.Count()))
+        {
+            var reentries = rule.Select(it => it.Origin).Distinct().Count();
+            if (LeftFactor(rule.Key, rule.ToList(), reentries, grammar) is { } factor)
+            {
+                hints.Add(factor);
+            }
+
+            hints.AddRange(rule.Select(it => it.Alternative).Distinct().Where(IsRecursiveAtBothEnds)
+                .Select(alternative => OneSidedRecursion(alternative, reentries, grammar)));
+        }
+
+        return hints;
+    }
+
+    private static GrammarHint? LeftFactor(CompiledRule rule, List<GeneralizedParser.EarleyItem> items, int reentries, CompiledGrammar grammar)
+    {
+        // Alternatives starting with the same symbol and tracked from the same origin share work.
+        var group = rule.Alternatives
+            .Where(a => a.Symbols.Count > 0)
+            .GroupBy(a => a.Symbols[0])
+            .Where(g => g.Count() > 1)
+            .Select(g => g.ToList())
+            .FirstOrDefault(alternatives => items
+                .Where(it => alternatives.Contains(it.Alternative))
+                .GroupBy(it => it.Origin)
+                .Any(g => g.Select(it => it.Alternative).Distinct().Count() > 1));
+        if (group == null)
+        {
+            return null;
+        }
+
+        var length = 1;
+        while (group.All(a => a.Symbols.Count > length && a.Symbols[length] == group[0].Symbols[length]))
+        {
+            length++;
+        }
+
+        var prefix = group[0].Symbols.Take(length).Select(s => s.ToString()).ToList();
+        var tail = UnusedName(grammar, rule.Name + "_tail");
+        var remainders = group
+            .Select(a => string.Join(" ", a.Symbols.Skip(length)))
+            .OrderBy(r => r.Length == 0)
+            .ToList();
+
+        return new GrammarHint(GrammarHintKind.LeftFactor, rule.Name, group[0].Text, reentries, prefix,
+            $"left-factor the common prefix {string.Join(" ", prefix)}: <{rule.Name}> ::= {string.Join(" ", prefix)} <{tail}> " +
+            $"with <{tail}> ::= {string.Join(" | ", remainders)}");
+    }
+
+    private static bool IsRecursiveAtBothEnds(CompiledAlternative alternative)
+    {
+        return alternative.Symbols.Count > 1
+            && alternative.RuleIndices[0] == alternative.Rule.Index
+            && alternative.RuleIndices[^1] == alternative.Rule.Index;
+    }
+
+    private static GrammarHint OneSidedRecursion(CompiledAlternative alternative, int reentries, CompiledGrammar grammar)
+    {
+        var rule = alternative.Rule;
+        var operand = UnusedName(grammar, rule.Name + "_operand");
+        var middle = alternative.Symbols.Skip(1).SkipLast(1).Select(s => s.ToString());
+        var operands = rule.Alternatives.Where(a => !a.RuleIndices.Contains(rule.Index)).Select(a => a.Text).ToList();
+
+        return new GrammarHint(GrammarHintKind.OneSidedRecursion, rule.Name, alternative.Text, reentries, Array.Empty<string>(),
+            $"recurse on one side only so each input has one derivation: <{rule.Name}> ::= " +
+            $"{string.Join(" ", middle.Prepend($"<{rule.Name}>").Append($"<{operand}>"))} | <{operand}> " +
+            $"with <{operand}> ::= {(operands.Count > 0 ? string.Join(" | ", operands) : "...")}");
+    }
+
+    private static string UnusedName(CompiledGrammar grammar, string name)
+    {
+        var candidate = name;
+        for (var n = 2; grammar.GetRule(candidate) != null; n++)
+        {
+            candidate = name + n;
+        }
+
+        return candidate;
+    }
+}
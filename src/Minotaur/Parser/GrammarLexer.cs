@@ -0,0 +1,422 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Tokenizes source text using the terminals of a compiled grammar.
+/// Matching is longest-match; ties prefer literals, then higher token priority, then declaration order.
+/// Contextual keywords get no literal rule of their own. For scannerless grammars every character is a token of kind <see cref="CharacterKind"/> and nothing is skipped;
+/// the parser matches terminals against the characters itself. Grammars with a <see cref="DelimiterPolicy"/> keep a
+/// delimiter stack while tokenizing, so heredoc bodies and matching end tags are read against what the source chose.
+/// Terminals guarded by the grammar's "TokenGuards" metadata are only tried after a previous token of the right
+/// category, which is how <c>/</c> is told apart as division or the start of a regex literal.
+/// </summary>
+public sealed class GrammarLexer
+{
+    /// <summary>
+    /// The token kind of the single-character tokens produced for scannerless grammars.
+    /// </summary>
+    public const string CharacterKind = "CHARACTER";
+
+    private static readonly Dictionary<string, string> BuiltInPatterns = new()
+    {
+        ["IDENTIFIER"] = "[A-Za-z_][A-Za-z0-9_]*",
+        ["NUMBER"] = @"[0-9]+(\.[0-9]+)?",
+        ["STRING"] = "\"(\\\\.|[^\"\\\\])*\"",
+        ["REGEX"] = @"/(?![*/])(?:\\.|\[(?:\\.|[^\]\\\r\n])*\]|[^/\\\[\r\n])+/[A-Za-z]*"
+    };
+
+    private readonly List<LexerRule> _rules = new();
+    private readonly List<Regex> _skipPatterns = new();
+    private readonly Dictionary<string, Regex> _patternsByKind = new();
+    private readonly Dictionary<string, LexerRule> _capturingRules = new();
+    private readonly bool _scannerless;
+    private readonly DelimiterPolicy? _delimiters;
+    private readonly CompiledGrammar _grammar;
+
+    /// <summary>
+    /// Initializes a new instance of the GrammarLexer class.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar whose terminals are recognized.</param>
+    public GrammarLexer(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var patterns = grammar.Source.TokenRules.Patterns;
+        var order = 0;
+        _scannerless = grammar.IsScannerless;
+        _delimiters = _scannerless ? null : DelimiterPolicy.FromGrammar(grammar);
+        _grammar = grammar;
+
+        foreach (var terminal in grammar.GetTerminals())
+        {
+            switch (terminal.Kind)
+            {
+                // Contextual keywords are lexed as what their text otherwise is; the parser promotes them where expected.
+                case GrammarSymbolKind.Literal when _scannerless || !grammar.IsContextualKeyword(terminal.Name):
+                    _rules.Add(new LexerRule(terminal.Key, new Regex(@"\G" + Regex.Escape(terminal.Name), RegexOptions.CultureInvariant), true, int.MaxValue, order++));
+                    break;
+
+                case GrammarSymbolKind.Pattern:
+                    _rules.Add(new LexerRule(terminal.Key, CreatePattern(terminal.Name), false, 0, order++));
+                    break;
+
+                case GrammarSymbolKind.Token:
+                    var definition = patterns.FirstOrDefault(p => p.Name == terminal.Name);
+                    if (definition != null)
+                    {
+                        _rules.Add(new LexerRule(terminal.Name, CreatePattern(definition.Pattern), false, definition.Priority, order++)
+                        {
+                            IsIdentifier = definition.Type == TokenType.Identifier
+                        });
+                    }
+                    else if (BuiltInPatterns.TryGetValue(terminal.Name, out var builtIn))
+                    {
+                        _rules.Add(new LexerRule(terminal.Name, CreatePattern(builtIn), false, 0, order++) { IsIdentifier = terminal.Name == "IDENTIFIER" });
+                    }
+                    break;
+            }
+        }
+
+        for (var i = 0; i < _rules.Count; i++)
+        {
+            _rules[i] = _rules[i] with { Guard = grammar.GetTokenGuard(_rules[i].Kind), Escapes = grammar.GetEscapeScheme(_rules[i].Kind) };
+        }
+
+        foreach (var rule in _rules)
+        {
+            _patternsByKind.TryAdd(rule.Kind, rule.Pattern);
+            if (rule.CaptureNames != null)
+            {
+                _capturingRules.TryAdd(rule.Kind, rule);
+            }
+        }
+
+        foreach (var pattern in patterns.Where(p => p.Type is TokenType.Whitespace or TokenType.Comment))
+        {
+            _skipPatterns.Add(CreatePattern(pattern.Pattern));
+        }
+
+        if (!patterns.Any(p => p.Type == TokenType.Whitespace))
+        {
+            _skipPatterns.Add(CreatePattern(@"\s+"));
+        }
+    }
+
+    /// <summary>
+    /// Gets the delimiter policy declared by the grammar, or null.
+    /// </summary>
+    public DelimiterPolicy? Delimiters => _delimiters;
+
+    /// <summary>
+    /// Tokenizes the specified input.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <returns>The tokens and any lexical diagnostics.</returns>
+    public LexResult Tokenize(string input)
+    {
+        return Tokenize(input, 0);
+    }
+
+    /// <summary>
+    /// Tokenizes the specified input from an offset on, such as the end of a <see cref="Preamble"/>. Offsets and
+    /// diagnostic locations remain those of the whole input.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <param name="start">The offset to start at.</param>
+    /// <returns>The tokens and any lexical diagnostics.</returns>
+    public LexResult Tokenize(string input, int start)
+    {
+        return Tokenize(input, start, null);
+    }
+
+    /// <summary>
+    /// Tokenizes the specified input from an offset on, sharing the text of identifier tokens through an interner.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <param name="start">The offset to start at.</param>
+    /// <param name="interner">The interner identifier text is looked up in, or null to give every token its own.</param>
+    /// <returns>The tokens and any lexical diagnostics.</returns>
+    public LexResult Tokenize(string input, int start, SharedInterner? interner)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+        ArgumentOutOfRangeException.ThrowIfNegative(start);
+        ArgumentOutOfRangeException.ThrowIfGreaterThan(start, input.Length);
+
+        var tokens = new List<Token>();
+        var diagnostics = new List<Diagnostic>();
+        var lineIndex = new LineIndex(input);
+        var position = start;
+        var delimiters = CreateDelimiterState();
+
+        while (NextToken(input, ref position, diagnostics, lineIndex, delimiters, tokens.Count > 0 ? tokens[^1] : null, interner) is { } token)
+        {
+            tokens.Add(token);
+        }
+
+        return new LexResult(tokens, diagnostics);
+    }
+
+    /// <summary>
+    /// Reads the next token starting at the specified position, skipping whitespace and comments.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <param name="position">The position to read from; advanced past the token.</param>
+    /// <param name="diagnostics">Receives diagnostics for unrecognized characters.</param>
+    /// <param name="lineIndex">The line index of the input, used for diagnostic locations.</param>
+    /// <param name="delimiters">The delimiter stack of the run, if the grammar declares delimiters.</param>
+    /// <param name="previous">The last token read before the position, which guarded terminals are checked against.</param>
+    /// <param name="interner">The interner the text of identifier tokens is shared through, if any.</param>
+    /// <returns>The token, or null at the end of the input.</returns>
+    internal Token? NextToken(string input, ref int position, ICollection<Diagnostic> diagnostics, LineIndex lineIndex, DelimiterState? delimiters = null, Token? previous = null, SharedInterner? interner = null)
+    {
+        if (_scannerless)
+        {
+            return position < input.Length ? new Token(CharacterKind, input[position].ToString(), position++) : null;
+        }
+
+        if (delimiters?.Dequeue() is { } queued)
+        {
+            return queued;
+        }
+
+        while (position < input.Length)
+        {
+            if (delimiters?.IsBodyDue(position, input.Length) == true)
+            {
+                delimiters.ReadBodies(input, ref position, lineIndex, diagnostics);
+                return delimiters.Dequeue();
+            }
+
+            var skipped = SkipLength(input, position);
+            if (skipped > 0)
+            {
+                position += delimiters?.ClampSkip(position, skipped) ?? skipped;
+                continue;
+            }
+
+            LexerRule? best = null;
+            Match? bestMatch = null;
+            var bestLength = 0;
+
+            var previousCategory = previous != null ? _grammar.GetTerminalCategory(previous.Kind) : null;
+            foreach (var rule in _rules)
+            {
+                if (rule.Guard?.Permits(previousCategory) == false)
+                {
+                    continue;
+                }
+
+                var match = rule.Pattern.Match(input, position);
+                if (!match.Success || match.Length == 0)
+                {
+                    continue;
+                }
+
+                if (best == null || match.Length > bestLength ||
+                    (match.Length == bestLength && rule.Ranks(best)))
+                {
+                    best = rule;
+                    bestMatch = match;
+                    bestLength = match.Length;
+                }
+            }
+
+            if (best == null)
+            {
+                diagnostics.Add(new Diagnostic
+                {
+                    Code = DiagnosticCodes.UnrecognizedCharacter,
+                    Location = lineIndex.GetPosition(position, 1)
+                }.WithMessage(DiagnosticCodes.UnrecognizedCharacter, ("character", input[position].ToString())));
+                position++;
+                continue;
+            }
+
+            var text = interner != null && best.IsIdentifier ? interner.Intern(input.AsSpan(position, bestLength)) : input.Substring(position, bestLength);
+            var token = new Token(best.Kind, text, position)
+            {
+                Captures = best.CaptureNames != null ? TokenCaptures.Read(bestMatch!, best.CaptureNames, position) : null,
+                Escapes = best.Escapes
+            };
+            position += bestLength;
+            if (best.Escapes != null && token.Text.Contains('\\'))
+            {
+                // Malformed escapes are reported but keep the token, so the parse goes on with the string in place.
+                foreach (var error in best.Escapes.Decode(token.Text).Errors)
+                {
+                    diagnostics.Add(error.ToDiagnostic(token, lineIndex));
+                }
+            }
+
+            delimiters?.Accept(token, bestMatch!, input, lineIndex, diagnostics);
+            return token;
+        }
+
+        if (delimiters?.IsBodyDue(position, input.Length) == true)
+        {
+            delimiters.ReadBodies(input, ref position, lineIndex, diagnostics);
+            return delimiters.Dequeue();
+        }
+
+        return null;
+    }
+
+    /// <summary>
+    /// Records the captures and escape schemes of tokens read back from a form that keeps only their kinds and
+    /// spans, by matching their terminals again where they start.
+    /// </summary>
+    /// <param name="tokens">The tokens, updated in place.</param>
+    /// <param name="input">The source text the tokens were read from.</param>
+    internal void RestoreCaptures(Token[] tokens, string input)
+    {
+        if (_capturingRules.Count == 0 && !_grammar.HasEscapeSchemes)
+        {
+            return;
+        }
+
+        for (var i = 0; i < tokens.Length; i++)
+        {
+            var token = tokens[i];
+            if (token.IsSynthetic)
+            {
+                continue;
+            }
+
+            if (_grammar.GetEscapeScheme(token.Kind) is { } escapes)
+            {
+                token = tokens[i] = token with { Escapes = escapes };
+            }
+
+            if (token.End > input.Length || !_capturingRules.TryGetValue(token.Kind, out var rule))
+            {
+                continue;
+            }
+
+            var match = rule.Pattern.Match(input, token.Offset);
+            if (match.Success && match.Length == token.Length)
+            {
+                tokens[i] = token with { Captures = TokenCaptures.Read(match, rule.CaptureNames!, token.Offset) };
+            }
+        }
+    }
+
+    /// <summary>
+    /// Creates the delimiter stack for a lexer run.
+    /// </summary>
+    /// <returns>The delimiter state, or null if the grammar declares no delimiters.</returns>
+    internal DelimiterState? CreateDelimiterState()
+    {
+        return _delimiters != null ? new DelimiterState(_delimiters) : null;
+    }
+
+    /// <summary>
+    /// Matches a terminal directly against the input, as scannerless parsing does. Patterns match greedily.
+    /// </summary>
+    /// <param name="input">The source text.</param>
+    /// <param name="kind">The terminal's key.</param>
+    /// <param name="position">The position to match at.</param>
+    /// <returns>The length of the match, which may be zero for patterns, or -1 if the terminal does not match.</returns>
+    internal int MatchTerminal(string input, string kind, int position)
+    {
+        if (!_patternsByKind.TryGetValue(kind, out var pattern))
+        {
+            return -1;
+        }
+
+        var match = pattern.Match(input, position);
+        return match.Success ? match.Length : -1;
+    }
+
+    private int SkipLength(string input, int position)
+    {
+        var longest = 0;
+        foreach (var pattern in _skipPatterns)
+        {
+            var match = pattern.Match(input, position);
+            if (match.Success && match.Length > longest)
+            {
+                longest = match.Length;
+            }
+        }
+
+        return longest;
+    }
+
+    private static Regex CreatePattern(string pattern)
+    {
+        return new Regex(@"\G(?:" + pattern + ")", RegexOptions.CultureInvariant);
+    }
+
+    private sealed record LexerRule(string Kind, Regex Pattern, bool IsLiteral, int Priority, int Order)
+    {
+        public string[]? CaptureNames { get; } = IsLiteral ? null : TokenCaptures.GetNames(Pattern);
+
+        public TokenGuard? Guard { get; init; }
+
+        public EscapeScheme? Escapes { get; init; }
+
+        public bool IsIdentifier { get; init; }
+
+        public bool Ranks(LexerRule other)
+        {
+            if (IsLiteral != other.IsLiteral)
+            {
+                return IsLiteral;
+            }
+
+            if (Priority != other.Priority)
+            {
+                return Priority > other.Priority;
+            }
+
+            return Order < other.Order;
+        }
+    }
+}
+
+/// <summary>
+/// The result of tokenizing source text.
+/// </summary>
+/// <param name="Tokens">The tokens in source order.</param>
+/// <param name="Diagnostics">Lexical diagnostics such as unrecognized characters.</param>
+public sealed record LexResult(IReadOnlyList<Token> Tokens, IReadOnlyList<Diagnostic> Diagnostics);
+
+/// <summary>
+/// The categories of previous token a guarded terminal may be lexed after.
+/// </summary>
+/// <param name="Categories">The categories the guard names.</param>
+/// <param name="Negated">Whether the terminal is lexed after tokens of none of the categories instead.</param>
+internal sealed record TokenGuard(IReadOnlySet<string> Categories, bool Negated)
+{
+    /// <summary>
+    /// Determines whether the terminal may be lexed after a token of a category.
+    /// </summary>
+    /// <param name="previousCategory">The category of the previous token, or null at the start of the input.</param>
+    /// <returns>true if the terminal may be lexed.</returns>
+    public bool Permits(string? previousCategory)
+    {
+        var listed = previousCategory != null && Categories.Contains(previousCategory);
+        return Negated ? !listed : listed;
+    }
+}
@@ -0,0 +1,487 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Limits on the structures built while a grammar is compiled, for services that load grammars they do not
+/// trust. Each limit is checked as the structure grows, so a pathological grammar fails before it is built
+/// rather than after. A null limit is not enforced; by default nothing is.
+/// </summary>
+public class GrammarLoadLimits
+{
+    /// <summary>
+    /// Gets or sets the maximum number of distinct production rules.
+    /// </summary>
+    public int? MaxRules { get; set; }
+
+    /// <summary>
+    /// Gets or sets the maximum number of states of the lexer's automata together: the states the token
+    /// patterns, inline patterns and literals would need with every counted repetition expanded, so that
+    /// <c>(a{1,100}){1,100}</c> counts ten thousand.
+    /// </summary>
+    public long? MaxAutomatonStates { get; set; }
+
+    /// <summary>
+    /// Gets or sets the maximum number of parser table entries: one per position of the dot in every alternative.
+    /// </summary>
+    public long? MaxTableEntries { get; set; }
+
+    /// <summary>
+    /// Gets or sets the maximum time compilation may take, including the validation of the grammar's examples.
+    /// </summary>
+    public TimeSpan? MaxBuildTime { get; set; }
+
+    /// <summary>
+    /// Gets or sets the highest <see cref="GrammarRiskAnalyzer"/> level a grammar may reach. The analysis runs
+    /// before anything is built, so a grammar with catastrophic token patterns is refused without being compiled.
+    /// </summary>
+    public GrammarRiskLevel? MaxRisk { get; set; }
+}
+
+/// <summary>
+/// The limits of <see cref="GrammarLoadLimits"/>.
+/// </summary>
+public enum GrammarLoadLimit
+{
+    /// <summary>
+    /// <see cref="GrammarLoadLimits.MaxRules"/>.
+    /// </summary>
+    Rules,
+
+    /// <summary>
+    /// <see cref="GrammarLoadLimits.MaxAutomatonStates"/>.
+    /// </summary>
+    AutomatonStates,
+
+    /// <summary>
+    /// <see cref="GrammarLoadLimits.MaxTableEntries"/>.
+    /// </summary>
+    TableEntries,
+
+    /// <summary>
+    /// <see cref="GrammarLoadLimits.MaxBuildTime"/>.
+    /// </summary>
+    BuildTime,
+
+    /// <summary>
+    /// <see cref="GrammarLoadLimits.MaxRisk"/>.
+    /// </summary>
+    Risk
+}
+
+/// <summary>
+/// Thrown when compiling a grammar exceeds one of its <see cref="GrammarLoadLimits"/>.
+/// </summary>
+public class GrammarLimitExceededException : ArgumentException
+{
+    /// <summary>
+    /// Initializes a new instance of the GrammarLimitExceededException class.
+    /// </summary>
+    /// <param name="grammar">The grammar name.</param>
+    /// <param name="limit">The limit that was exceeded.</param>
+    /// <param name="maximum">The value of the limit; milliseconds for the build time, a <see cref="GrammarRiskLevel"/> for the risk.</param>
+    /// <param name="rule">The rule or token being compiled when the limit was exceeded, if any.</param>
+    public GrammarLimitExceededException(string grammar, GrammarLoadLimit limit, long maximum, string? rule)
+        : base($"Grammar '{grammar}' exceeds its limit of {Describe(limit, maximum)}{(rule != null ? $" while compiling '{rule}'" : string.Empty)}")
+    {
+        Limit = limit;
+        Maximum = maximum;
+        Rule = rule;
+    }
+
+    /// <summary>
+    /// Gets the limit that was exceeded.
+    /// </summary>
+    public GrammarLoadLimit Limit { get; }
+
+    /// <summary>
+    /// Gets the value of the limit; milliseconds for the build time, a <see cref="GrammarRiskLevel"/> for the risk.
+    /// </summary>
+    public long Maximum { get; }
+
+    /// <summary>
+    /// Gets the rule or token being compiled when the limit was exceeded, if any.
+    /// </summary>
+    public string? Rule { get; }
+
+    /// <summary>
+    /// Gets the number of rules, automaton states or table entries counted when the limit was exceeded, or null
+    /// for the build time and risk limits. Pattern sizes are measured only just past the limit, so this stays
+    /// close to <see cref="Maximum"/> however large the automaton would have been.
+    /// </summary>
+    public long? Count { get; init; }
+
+    /// <summary>
+    /// Gets the risk report of a grammar refused for <see cref="GrammarLoadLimit.Risk"/>.
+    /// </summary>
+    public GrammarRiskReport? RiskReport { get; init; }
+
+    private static string Describe(GrammarLoadLimit limit, long maximum) => limit switch
+    {
+        GrammarLoadLimit.Rules => $"{maximum} rules",
+        GrammarLoadLimit.AutomatonStates => $"{maximum} automaton states",
+        GrammarLoadLimit.TableEntries => $"{maximum} table entries",
+        GrammarLoadLimit.Risk => $"{((GrammarRiskLevel)maximum).ToString().ToLowerInvariant()} risk",
+        _ => $"{maximum} ms of build time"
+    };
+}
+
+/// <summary>
+/// Counts what a compilation has built so far against its <see cref="GrammarLoadLimits"/>.
+/// </summary>
+internal sealed class GrammarLoadBudget
+{
+    private readonly string _grammar;
+    private readonly GrammarLoadLimits _limits;
+    private readonly Stopwatch _watch = Stopwatch.StartNew();
+    private readonly HashSet<string> _terminals = new(StringComparer.Ordinal);
+    private int _rules;
+    private long _states;
+    private long _entries;
+
+    public GrammarLoadBudget(string grammar, GrammarLoadLimits? limits)
+    {
+        _grammar = grammar;
+        _limits = limits ?? new GrammarLoadLimits();
+    }
+
+    public void CheckRisk(Grammar grammar)
+    {
+        if (_limits.MaxRisk is not { } max)
+        {
+            return;
+        }
+
+        var report = GrammarRiskAnalyzer.Analyze(grammar);
+        if (report.Level > max)
+        {
+            throw new GrammarLimitExceededException(_grammar, GrammarLoadLimit.Risk, (long)max, report.Findings[0].Subject)
+            {
+                RiskReport = report
+            };
+        }
+    }
+
+    public void AddRule(string rule)
+    {
+        _rules++;
+        if (_rules > _limits.MaxRules)
+        {
+            throw new GrammarLimitExceededException(_grammar, GrammarLoadLimit.Rules, _limits.MaxRules.Value, rule)
+            {
+                Count = _rules
+            };
+        }
+
+        CheckTime(rule);
+    }
+
+    public void AddAlternative(IReadOnlyList<GrammarSymbol> symbols, string rule)
+    {
+        _entries += symbols.Count + 1;
+        if (_entries > _limits.MaxTableEntries)
+        {
+            throw new GrammarLimitExceededException(_grammar, GrammarLoadLimit.TableEntries, _limits.MaxTableEntries.Value, rule)
+            {
+                Count = _entries
+            };
+        }
+
+        foreach (var symbol in symbols)
+        {
+            switch (symbol.Kind)
+            {
+                case GrammarSymbolKind.Literal when _terminals.Add(symbol.Key):
+                    AddStates(symbol.Name.Length, rule);
+                    break;
+
+                case GrammarSymbolKind.Pattern when _terminals.Add(symbol.Key):
+                    AddPattern(symbol.Name, rule);
+                    break;
+            }
+        }
+
+        CheckTime(rule);
+    }
+
+    public void AddPattern(string pattern, string rule)
+    {
+        // The size is computed, not built, and saturates just past what is left of the limit.
+        var remaining = _limits.MaxAutomatonStates is { } max ? Math.Clamp(max - _states, 0, int.MaxValue) : int.MaxValue;
+        AddStates(new PatternSize(pattern, remaining).Measure(), rule);
+        CheckTime(rule);
+    }
+
+    public void CheckTime(string? rule)
+    {
+        if (_limits.MaxBuildTime is { } max && _watch.Elapsed > max)
+        {
+            throw new GrammarLimitExceededException(_grammar, GrammarLoadLimit.BuildTime, (long)max.TotalMilliseconds, rule);
+        }
+    }
+
+    private void AddStates(long states, string rule)
+    {
+        _states += states;
+        if (_states > _limits.MaxAutomatonStates)
+        {
+            throw new GrammarLimitExceededException(_grammar, GrammarLoadLimit.AutomatonStates, _limits.MaxAutomatonStates.Value, rule)
+            {
+                Count = _states
+            };
+        }
+    }
+
+    /// <summary>
+    /// Measures the states of a regular expression's automaton with counted repetitions expanded: a character,
+    /// class or escape is one state, a sequence or alternation the sum of its parts, <c>x*</c>, <c>x+</c> and
+    /// <c>x?</c> one more than <c>x</c>, and <c>x{n,m}</c> <c>m</c> times <c>x</c>.
+    /// </summary>
+    private sealed class PatternSize
+    {
+        // Deeper nesting than any real token needs is measured as too large rather than walked.
+        private const int MaxDepth = 256;
+
+        private readonly string _pattern;
+        private readonly long _cap;
+        private int _position;
+        private int _depth;
+
+        public PatternSize(string pattern, long remaining)
+        {
+            _pattern = pattern;
+            _cap = remaining + 1;
+        }
+
+        public long Measure()
+        {
+            var size = 0L;
+            while (_position < _pattern.Length)
+            {
+                // A stray ')' ends nothing at the top level.
+                size = Add(size, Alternation());
+                _position++;
+            }
+
+            return size;
+        }
+
+        private long Alternation()
+        {
+            var size = Sequence();
+            while (_position < _pattern.Length && _pattern[_position] == '|')
+            {
+                _position++;
+                size = Add(size, Sequence());
+            }
+
+            return size;
+        }
+
+        private long Sequence()
+        {
+            var size = 0L;
+            while (_position < _pattern.Length && _pattern[_position] is not ('|' or ')'))
+            {
+                size = Add(size, Quantified(Atom()));
+            }
+
+            return size;
+        }
+
+        private long Atom()
+        {
+            var c = _pattern[_position++];
+            switch (c)
+            {
+                case '(':
+                    if (++_depth > MaxDepth)
+                    {
+                        _position = _pattern.Length;
+                        return _cap;
+                    }
+
+                    SkipGroupPrefix();
+                    var size = Alternation();
+                    if (_position < _pattern.Length)
+                    {
+                        _position++;
+                    }
+
+                    _depth--;
+                    return size;
+
+                case '[':
+                    SkipClass();
+                    return 1;
+
+                case '\\':
+                    SkipEscape();
+                    return 1;
+
+                case '^' or '$':
+                    return 0;
+
+                default:
+                    return 1;
+            }
+        }
+
+        private long Quantified(long size)
+        {
+            while (_position < _pattern.Length)
+            {
+                var c = _pattern[_position];
+                if (c is '*' or '+' or '?')
+                {
+                    _position++;
+                    size = Add(size, 1);
+                }
+                else if (c == '{' && TryReadCount(out var count))
+                {
+                    size = Multiply(size, count);
+                }
+                else
+                {
+                    return size;
+                }
+
+                // Lazy and possessive markers change matching, not size.
+                if (_position < _pattern.Length && _pattern[_position] is '?' or '+')
+                {
+                    _position++;
+                }
+            }
+
+            return size;
+        }
+
+        private bool TryReadCount(out long count)
+        {
+            var close = _pattern.IndexOf('}', _position);
+            count = 0;
+            if (close < 0)
+            {
+                return false;
+            }
+
+            var bounds = _pattern.Substring(_position + 1, close - _position - 1).Split(',');
+            if (bounds.Length > 2 || !bounds.All(b => b.Length == 0 || b.All(char.IsAsciiDigit)) || bounds[0].Length == 0)
+            {
+                return false;
+            }
+
+            _position = close + 1;
+            var min = Parse(bounds[0]);
+            count = bounds.Length == 1 ? min : bounds[1].Length == 0 ? Add(min, 1) : Math.Max(min, Parse(bounds[1]));
+            return true;
+        }
+
+        private long Parse(string digits)
+        {
+            return long.TryParse(digits, out var value) ? Math.Min(value, _cap) : _cap;
+        }
+
+        private void SkipGroupPrefix()
+        {
+            if (_position >= _pattern.Length || _pattern[_position] != '?')
+            {
+                return;
+            }
+
+            _position++;
+            if (_position < _pattern.Length && _pattern[_position] is '<' or '\'' && _position + 1 < _pattern.Length && _pattern[_position + 1] is not ('=' or '!'))
+            {
+                var close = _pattern.IndexOfAny(new[] { '>', '\'' }, _position + 1);
+                _position = close < 0 ? _pattern.Length : close + 1;
+                return;
+            }
+
+            // Lookarounds, atomic groups and inline options up to ':' or, for (?i), the closing parenthesis.
+            while (_position < _pattern.Length && _pattern[_position] is not (':' or ')'))
+            {
+                _position++;
+            }
+
+            if (_position < _pattern.Length && _pattern[_position] == ':')
+            {
+                _position++;
+            }
+        }
+
+        private void SkipClass()
+        {
+            var depth = 1;
+            if (_position < _pattern.Length && _pattern[_position] == '^')
+            {
+                _position++;
+            }
+
+            // A ']' right after the opening bracket is literal.
+            if (_position < _pattern.Length && _pattern[_position] == ']')
+            {
+                _position++;
+            }
+
+            while (_position < _pattern.Length && depth > 0)
+            {
+                switch (_pattern[_position++])
+                {
+                    case '\\':
+                        _position++;
+                        break;
+
+                    case '[':
+                        depth++;
+                        break;
+
+                    case ']':
+                        depth--;
+                        break;
+                }
+            }
+        }
+
+        private void SkipEscape()
+        {
+            if (_position >= _pattern.Length)
+            {
+                return;
+            }
+
+            var c = _pattern[_position++];
+            if (c is 'p' or 'P' && _position < _pattern.Length && _pattern[_position] == '{')
+            {
+                var close = _pattern.IndexOf('}', _position);
+                _position = close < 0 ? _pattern.Length : close + 1;
+            }
+            else if (c == 'k' && _position < _pattern.Length && _pattern[_position] == '<')
+            {
+                var close = _pattern.IndexOf('>', _position);
+                _position = close < 0 ? _pattern.Length : close + 1;
+            }
+        }
+
+        private long Add(long a, long b) => Math.Min(a + b, _cap);
+
+        private long Multiply(long a, long b) => b != 0 && a > _cap / b ? _cap : Math.Min(a * b, _cap);
+    }
+}
@@ -0,0 +1,267 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics.CodeAnalysis;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// One level of a <see cref="GrammarPrecedence"/>: operators that bind equally tightly.
+/// </summary>
+/// <param name="Associativity">How chains of the level's operators group: <see cref="OperatorFixity.InfixLeft"/>,
+/// <see cref="OperatorFixity.InfixRight"/>, or <see cref="OperatorFixity.Infix"/> for operators that do not chain.</param>
+/// <param name="Operators">The operator terminals.</param>
+public sealed record PrecedenceLevel(OperatorFixity Associativity, IReadOnlyList<GrammarSymbol> Operators)
+{
+    /// <summary>
+    /// Returns the level as it is written in the "Precedence" metadata entry, e.g. <c>left "+" "-"</c>.
+    /// </summary>
+    /// <returns>The level's declaration.</returns>
+    public override string ToString()
+    {
+        var keyword = Associativity switch
+        {
+            OperatorFixity.InfixLeft => "left",
+            OperatorFixity.InfixRight => "right",
+            _ => "nonassoc"
+        };
+
+        return $"{keyword} {string.Join(" ", Operators)}";
+    }
+}
+
+/// <summary>
+/// The operator precedence declared by a grammar's "Precedence" metadata entry, written as
+/// <c>left "+" "-"; left "*" "/"; right "^"</c> from the loosest level to the tightest, each level starting with
+/// <c>left</c>, <c>right</c> or <c>nonassoc</c>.
+/// </summary>
+/// <remarks>
+/// The declaration resolves the ambiguities of operator alternatives, those written <c>&lt;e&gt; op &lt;e&gt;</c>,
+/// <c>op &lt;e&gt;</c> or <c>&lt;e&gt; op</c> where <c>&lt;e&gt;</c> is their own rule. A derivation is dropped from the
+/// forest when an operand next to its operator can only be an operator alternative of the same rule whose operator
+/// faces it and binds less tightly, or equally tightly on the side the level's associativity does not group. A span
+/// none of whose derivations survive, as with chained non-associative operators, keeps them all and stays ambiguous.
+/// Operators without a level are never filtered.
+/// </remarks>
+public sealed class GrammarPrecedence
+{
+    private readonly Dictionary<string, int> _levels = new(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Initializes a new instance of the GrammarPrecedence class.
+    /// </summary>
+    /// <param name="levels">The levels from the loosest to the tightest.</param>
+    /// <exception cref="ArgumentException">An operator is on more than one level.</exception>
+    public GrammarPrecedence(IReadOnlyList<PrecedenceLevel> levels)
+    {
+        ArgumentNullException.ThrowIfNull(levels);
+
+        Levels = levels;
+        for (var level = 0; level < levels.Count; level++)
+        {
+            foreach (var symbol in levels[level].Operators)
+            {
+                if (!_levels.TryAdd(symbol.Key, level))
+                {
+                    throw new ArgumentException($"Operator {symbol} is declared on more than one precedence level", nameof(levels));
+                }
+            }
+        }
+    }
+
+    /// <summary>
+    /// Gets the levels from the loosest to the tightest.
+    /// </summary>
+    public IReadOnlyList<PrecedenceLevel> Levels { get; }
+
+    /// <summary>
+    /// Gets the level of an operator.
+    /// </summary>
+    /// <param name="terminalKey">The operator's terminal key.</param>
+    /// <returns>The index of its level in <see cref="Levels"/>, or null if it has none.</returns>
+    public int? GetLevel(string terminalKey)
+    {
+        ArgumentNullException.ThrowIfNull(terminalKey);
+
+        return _levels.TryGetValue(terminalKey, out var level) ? level : null;
+    }
+
+    /// <summary>
+    /// Reads a "Precedence" metadata entry.
+    /// </summary>
+    /// <param name="declaration">The entry's value.</param>
+    /// <param name="ruleNames">The names of production rules; other angle-bracket references are tokens.</param>
+    /// <returns>The precedence.</returns>
+    /// <exception cref="FormatException">A level has no associativity keyword or lists no terminals, or an operator is
+    /// on more than one level.</exception>
+    public static GrammarPrecedence Parse(string declaration, ISet<string> ruleNames)
+    {
+        ArgumentNullException.ThrowIfNull(declaration);
+        ArgumentNullException.ThrowIfNull(ruleNames);
+
+        var levels = new List<PrecedenceLevel>();
+        foreach (var entry in declaration.Split(';', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var separator = entry.IndexOfAny(new[] { ' ', '\t' });
+            var keyword = separator > 0 ? entry[..separator] : entry;
+            var associativity = keyword switch
+            {
+                "left" => OperatorFixity.InfixLeft,
+                "right" => OperatorFixity.InfixRight,
+                "nonassoc" => OperatorFixity.Infix,
+                _ => throw new FormatException($"Precedence level '{entry}' must start with left, right or nonassoc")
+            };
+
+            var operators = separator > 0 ? GrammarSymbol.ParseAlternative(entry[separator..], ruleNames) : Array.Empty<GrammarSymbol>();
+            if (operators.Count == 0)
+            {
+                throw new FormatException($"Precedence level '{entry}' lists no operators");
+            }
+
+            if (operators.FirstOrDefault(s => !s.IsTerminal) is { } rule)
+            {
+                throw new FormatException($"Precedence level '{entry}' lists rule <{rule.Name}>; only terminals have a precedence");
+            }
+
+            levels.Add(new PrecedenceLevel(associativity, operators));
+        }
+
+        try
+        {
+            return new GrammarPrecedence(levels);
+        }
+        catch (ArgumentException ex)
+        {
+            throw new FormatException(ex.Message.Split(" (Parameter")[0], ex);
+        }
+    }
+
+    /// <summary>
+    /// Returns the precedence as it is written in the "Precedence" metadata entry.
+    /// </summary>
+    /// <returns>The levels, separated by semicolons.</returns>
+    public override string ToString()
+    {
+        return string.Join("; ", Levels);
+    }
+
+    /// <summary>
+    /// Determines whether an alternative is an operator alternative: <c>&lt;e&gt; op &lt;e&gt;</c>,
+    /// <c>op &lt;e&gt;</c> or <c>&lt;e&gt; op</c> with <c>&lt;e&gt;</c> its own rule.
+    /// </summary>
+    /// <param name="alternative">The alternative.</param>
+    /// <param name="op">The operator terminal.</param>
+    /// <param name="position">Where the operator is written: <see cref="OperatorFixity.Infix"/>,
+    /// <see cref="OperatorFixity.Prefix"/> or <see cref="OperatorFixity.Postfix"/>.</param>
+    /// <returns>True if the alternative is an operator alternative.</returns>
+    public static bool TryGetOperator(CompiledAlternative alternative, [NotNullWhen(true)] out GrammarSymbol? op, out OperatorFixity position)
+    {
+        ArgumentNullException.ThrowIfNull(alternative);
+
+        var symbols = alternative.Symbols;
+        bool Self(int i) => symbols[i].Kind == GrammarSymbolKind.Rule && symbols[i].Name == alternative.Rule.Name;
+
+        op = null;
+        position = default;
+        if (symbols.Count == 3 && Self(0) && symbols[1].IsTerminal && Self(2))
+        {
+            (op, position) = (symbols[1], OperatorFixity.Infix);
+        }
+        else if (symbols.Count == 2 && symbols[0].IsTerminal && Self(1))
+        {
+            (op, position) = (symbols[0], OperatorFixity.Prefix);
+        }
+        else if (symbols.Count == 2 && Self(0) && symbols[1].IsTerminal)
+        {
+            (op, position) = (symbols[1], OperatorFixity.Postfix);
+        }
+
+        return op != null;
+    }
+
+    /// <summary>
+    /// Drops the derivations of a forest that the precedence rules out.
+    /// </summary>
+    /// <param name="root">The forest's root.</param>
+    internal void Filter(SymbolForestNode root)
+    {
+        // Operands are filtered before the derivations using them, so only their surviving derivations count.
+        var done = new HashSet<SymbolForestNode>();
+        var pending = new Stack<(SymbolForestNode Node, bool Expanded)>();
+        pending.Push((root, false));
+        while (pending.Count > 0)
+        {
+            var (node, expanded) = pending.Pop();
+            if (expanded)
+            {
+                if (node.IsAmbiguous)
+                {
+                    var kept = node.Packed.Where(p => !IsRuledOut(p)).ToList();
+                    if (kept.Count > 0 && kept.Count < node.Packed.Count)
+                    {
+                        node.Packed.Clear();
+                        node.Packed.AddRange(kept);
+                    }
+                }
+
+                continue;
+            }
+
+            if (!done.Add(node))
+            {
+                continue;
+            }
+
+            pending.Push((node, true));
+            foreach (var child in node.Packed.SelectMany(p => p.Children).OfType<SymbolForestNode>())
+            {
+                if (!done.Contains(child))
+                {
+                    pending.Push((child, false));
+                }
+            }
+        }
+    }
+
+    private bool IsRuledOut(PackedForestNode packed)
+    {
+        if (!TryGetOperator(packed.Alternative, out var op, out var position) || GetLevel(op.Key) is not { } level)
+        {
+            return false;
+        }
+
+        // The operand left of the operator must not be an operator open to the right that binds less tightly, and
+        // the operand right of it not one open to the left.
+        var children = packed.Children;
+        return (position != OperatorFixity.Prefix && Captures(children[0], packed.Alternative.Rule, level, OperatorFixity.InfixLeft))
+            || (position != OperatorFixity.Postfix && Captures(children[children.Count - 1], packed.Alternative.Rule, level, OperatorFixity.InfixRight));
+    }
+
+    private bool Captures(ForestNode operand, CompiledRule rule, int level, OperatorFixity side)
+    {
+        if (operand is not SymbolForestNode symbol || symbol.Rule != rule)
+        {
+            return false;
+        }
+
+        var facing = side == OperatorFixity.InfixLeft ? OperatorFixity.Postfix : OperatorFixity.Prefix;
+        return symbol.Packed.All(p => TryGetOperator(p.Alternative, out var op, out var position)
+            && position != facing
+            && GetLevel(op.Key) is { } inner
+            && (inner < level || (inner == level && Levels[level].Associativity != side)));
+    }
+}
@@ -0,0 +1,1066 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// How much a grammar construct risks slow lexing or parsing on hostile input, from least to most.
+/// </summary>
+public enum GrammarRiskLevel
+{
+    /// <summary>
+    /// Nothing was found.
+    /// </summary>
+    None,
+
+    /// <summary>
+    /// Worth knowing about, but every engine handles it in polynomial time.
+    /// </summary>
+    Low,
+
+    /// <summary>
+    /// Polynomial blowup, or an automaton that grows large but stays buildable.
+    /// </summary>
+    Medium,
+
+    /// <summary>
+    /// Exponential work for engines without memoization, or an automaton too large to build.
+    /// </summary>
+    High,
+
+    /// <summary>
+    /// Exponential work for the engine that will actually run it, such as catastrophic regex backtracking.
+    /// </summary>
+    Critical
+}
+
+/// <summary>
+/// The kinds of construct <see cref="GrammarRiskAnalyzer"/> flags.
+/// </summary>
+public enum GrammarRiskKind
+{
+    /// <summary>
+    /// An unbounded quantifier repeated by another, as in <c>(a+)+</c>, so a near match is tried in exponentially many splits.
+    /// </summary>
+    NestedQuantifier,
+
+    /// <summary>
+    /// A repeated alternation whose branches match the same characters, as in <c>(\w|\d)+</c>.
+    /// </summary>
+    OverlappingAlternation,
+
+    /// <summary>
+    /// Unbounded quantifiers over overlapping characters with nothing required between them, as in <c>\d+\d+</c>.
+    /// </summary>
+    AdjacentQuantifiers,
+
+    /// <summary>
+    /// A pattern whose deterministic automaton needs exponentially many states, as in <c>[ab]*a[ab]{20}</c>.
+    /// </summary>
+    DfaBlowup,
+
+    /// <summary>
+    /// A rule that derives itself without consuming input, so a span has infinitely many parses.
+    /// </summary>
+    NullableCycle,
+
+    /// <summary>
+    /// Alternatives sharing a prefix that recurses into the rule, which a backtracking parser without
+    /// memoization reparses once per alternative at every level of nesting.
+    /// </summary>
+    SharedPrefixRecursion,
+
+    /// <summary>
+    /// An alternative that refers to its own rule more than once, as in <c>&lt;e&gt; &lt;e&gt;</c>, giving
+    /// exponentially many parse trees for a span.
+    /// </summary>
+    AmbiguousRecursion
+}
+
+/// <summary>
+/// One construct flagged by <see cref="GrammarRiskAnalyzer"/>.
+/// </summary>
+/// <param name="Kind">The kind of construct.</param>
+/// <param name="Level">How risky the construct is.</param>
+/// <param name="Subject">The rule or terminal the construct is in: a token name, an inline pattern's key or a rule name.</param>
+/// <param name="IsTerminal">Whether <paramref name="Subject"/> is a terminal rather than a rule.</param>
+/// <param name="Fragment">The problematic sub-pattern or symbols, as written in the grammar.</param>
+/// <param name="Message">A description of the problem.</param>
+public sealed record GrammarRiskFinding(GrammarRiskKind Kind, GrammarRiskLevel Level, string Subject, bool IsTerminal, string Fragment, string Message)
+{
+    /// <summary>
+    /// Returns the finding as "level kind subject: fragment: message".
+    /// </summary>
+    /// <returns>The finding on one line.</returns>
+    public override string ToString()
+    {
+        return $"{Level.ToString().ToLowerInvariant()} {Kind} {Subject}: {Fragment}: {Message}";
+    }
+}
+
+/// <summary>
+/// The result of <see cref="GrammarRiskAnalyzer.Analyze"/>: every flagged construct and the estimated
+/// deterministic automaton size of every terminal pattern.
+/// </summary>
+public sealed class GrammarRiskReport
+{
+    internal GrammarRiskReport(string grammar, IReadOnlyList<GrammarRiskFinding> findings, IReadOnlyDictionary<string, long> estimatedDfaStates)
+    {
+        Grammar = grammar;
+        Findings = findings;
+        EstimatedDfaStates = estimatedDfaStates;
+    }
+
+    /// <summary>
+    /// Gets the grammar name.
+    /// </summary>
+    public string Grammar { get; }
+
+    /// <summary>
+    /// Gets the flagged constructs, most risky first.
+    /// </summary>
+    public IReadOnlyList<GrammarRiskFinding> Findings { get; }
+
+    /// <summary>
+    /// Gets the estimated number of deterministic automaton states per terminal pattern, keyed like
+    /// <see cref="GrammarRiskFinding.Subject"/>. Estimates saturate at <see cref="long.MaxValue"/>.
+    /// </summary>
+    public IReadOnlyDictionary<string, long> EstimatedDfaStates { get; }
+
+    /// <summary>
+    /// Gets the level of the riskiest finding, or <see cref="GrammarRiskLevel.None"/>.
+    /// </summary>
+    public GrammarRiskLevel Level => Findings.Count > 0 ? Findings[0].Level : GrammarRiskLevel.None;
+
+    /// <summary>
+    /// Renders the report as JSON: the grammar, its level, the findings and the automaton estimates.
+    /// </summary>
+    /// <returns>The report JSON.</returns>
+    public string ToJson()
+    {
+        var findings = new JsonArray();
+        foreach (var finding in Findings)
+        {
+            findings.Add(new JsonObject
+            {
+                ["kind"] = finding.Kind.ToString(),
+                ["level"] = finding.Level.ToString(),
+                ["subject"] = finding.Subject,
+                ["terminal"] = finding.IsTerminal,
+                ["fragment"] = finding.Fragment,
+                ["message"] = finding.Message
+            });
+        }
+
+        var estimates = new JsonObject();
+        foreach (var (terminal, states) in EstimatedDfaStates)
+        {
+            estimates[terminal] = states;
+        }
+
+        var report = new JsonObject
+        {
+            ["grammar"] = Grammar,
+            ["level"] = Level.ToString(),
+            ["findings"] = findings,
+            ["estimatedDfaStates"] = estimates
+        };
+
+        return report.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+    }
+
+    /// <summary>
+    /// Returns the findings one per line.
+    /// </summary>
+    /// <returns>The report as text.</returns>
+    public override string ToString()
+    {
+        return Findings.Count == 0
+            ? $"Grammar '{Grammar}': no risks found"
+            : $"Grammar '{Grammar}': {Level.ToString().ToLowerInvariant()} risk\n{string.Join("\n", Findings)}";
+    }
+}
+
+/// <summary>
+/// Statically analyzes a grammar for constructs that make lexing or parsing slow on hostile input, before the
+/// grammar is compiled. Terminal patterns are checked for catastrophic regex backtracking, since the lexer runs
+/// them on the backtracking <see cref="System.Text.RegularExpressions.Regex"/> engine, and their deterministic
+/// automaton size is estimated; rules are checked for shapes that are exponential without memoization.
+/// </summary>
+public static class GrammarRiskAnalyzer
+{
+    // Findings about automata are reported once the estimate reaches these sizes.
+    private const long MediumDfaStates = 1 << 10;
+    private const long HighDfaStates = 1 << 16;
+
+    // Counted repetitions this large are treated as unbounded when looking for nested quantifiers.
+    private const int UnboundedCount = 16;
+
+    /// <summary>
+    /// Analyzes a grammar's token patterns, inline patterns and rules.
+    /// </summary>
+    /// <param name="grammar">The grammar to analyze.</param>
+    /// <returns>The risk report.</returns>
+    public static GrammarRiskReport Analyze(Grammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var findings = new List<GrammarRiskFinding>();
+        var estimates = new Dictionary<string, long>(StringComparer.Ordinal);
+        foreach (var pattern in grammar.TokenRules.Patterns)
+        {
+            if (!estimates.ContainsKey(pattern.Name))
+            {
+                estimates[pattern.Name] = new PatternAnalysis(pattern.Pattern, pattern.Name, findings).Run();
+            }
+        }
+
+        var ruleNames = new HashSet<string>(grammar.ProductionRules.Rules.Select(r => r.Name));
+        var rules = new Dictionary<string, List<IReadOnlyList<GrammarSymbol>>>(StringComparer.Ordinal);
+        foreach (var rule in grammar.ProductionRules.Rules)
+        {
+            if (!rules.TryGetValue(rule.Name, out var alternatives))
+            {
+                rules[rule.Name] = alternatives = new List<IReadOnlyList<GrammarSymbol>>();
+            }
+
+            foreach (var alternative in rule.Alternatives)
+            {
+                var symbols = GrammarSymbol.ParseAlternative(alternative, ruleNames);
+                alternatives.Add(symbols);
+                foreach (var symbol in symbols.Where(s => s.Kind == GrammarSymbolKind.Pattern && !estimates.ContainsKey(s.Key)))
+                {
+                    estimates[symbol.Key] = new PatternAnalysis(symbol.Name, symbol.Key, findings).Run();
+                }
+            }
+        }
+
+        AnalyzeRules(rules, findings);
+        return new GrammarRiskReport(grammar.Name, Order(findings), estimates);
+    }
+
+    /// <summary>
+    /// Analyzes a single regular expression as a terminal pattern.
+    /// </summary>
+    /// <param name="pattern">The regular expression.</param>
+    /// <param name="name">The name findings are reported under.</param>
+    /// <returns>The risk report, named after the pattern.</returns>
+    public static GrammarRiskReport AnalyzePattern(string pattern, string name = "pattern")
+    {
+        ArgumentNullException.ThrowIfNull(pattern);
+
+        var findings = new List<GrammarRiskFinding>();
+        var states = new PatternAnalysis(pattern, name, findings).Run();
+        return new GrammarRiskReport(name, Order(findings), new Dictionary<string, long> { [name] = states });
+    }
+
+    private static IReadOnlyList<GrammarRiskFinding> Order(List<GrammarRiskFinding> findings)
+    {
+        return findings.Distinct().OrderByDescending(f => f.Level).ThenBy(f => f.Kind).ToList();
+    }
+
+    private static void AnalyzeRules(Dictionary<string, List<IReadOnlyList<GrammarSymbol>>> rules, List<GrammarRiskFinding> findings)
+    {
+        var nullable = new HashSet<string>(StringComparer.Ordinal);
+        bool IsNullable(GrammarSymbol symbol) => symbol.Kind == GrammarSymbolKind.Rule && nullable.Contains(symbol.Name);
+        for (var changed = true; changed;)
+        {
+            changed = false;
+            foreach (var (name, alternatives) in rules)
+            {
+                if (!nullable.Contains(name) && alternatives.Any(a => a.All(IsNullable)))
+                {
+                    nullable.Add(name);
+                    changed = true;
+                }
+            }
+        }
+
+        // Rules each rule refers to anywhere, and those it can become without consuming input.
+        var references = rules.ToDictionary(r => r.Key, r => r.Value.SelectMany(a => a).Where(s => s.Kind == GrammarSymbolKind.Rule && rules.ContainsKey(s.Name)).Select(s => s.Name).ToHashSet());
+        var units = rules.ToDictionary(r => r.Key, r => r.Value
+            .SelectMany(a => a.Where((s, i) => s.Kind == GrammarSymbolKind.Rule && rules.ContainsKey(s.Name) && a.Where((_, j) => j != i).All(IsNullable)))
+            .Select(s => s.Name)
+            .ToHashSet());
+
+        foreach (var (name, alternatives) in rules)
+        {
+            var cycle = alternatives.FirstOrDefault(a => a.Any(s => s.Kind == GrammarSymbolKind.Rule && units[name].Contains(s.Name) && Reaches(units, s.Name, name)));
+            if (cycle != null)
+            {
+                findings.Add(new GrammarRiskFinding(GrammarRiskKind.NullableCycle, GrammarRiskLevel.Critical, name, false, Describe(cycle),
+                    $"'{name}' derives itself without consuming input, so every span it matches has infinitely many parses"));
+            }
+
+            var sharedPrefix = false;
+            for (var i = 0; i < alternatives.Count; i++)
+            {
+                for (var j = i + 1; j < alternatives.Count && !sharedPrefix; j++)
+                {
+                    var prefix = alternatives[i].Zip(alternatives[j]).TakeWhile(p => p.First == p.Second).Select(p => p.First).ToList();
+                    sharedPrefix = prefix.Any(s => s.Kind == GrammarSymbolKind.Rule && rules.ContainsKey(s.Name) && Reaches(references, s.Name, name));
+                    if (sharedPrefix)
+                    {
+                        findings.Add(new GrammarRiskFinding(GrammarRiskKind.SharedPrefixRecursion, GrammarRiskLevel.High, name, false, Describe(prefix),
+                            $"alternatives {i + 1} and {j + 1} of '{name}' share a prefix that recurses into '{name}'; without memoization it is reparsed per alternative at every level of nesting"));
+                    }
+                }
+
+                var self = alternatives[i].Select((s, k) => (Symbol: s, Index: k)).Where(p => p.Symbol.Kind == GrammarSymbolKind.Rule && p.Symbol.Name == name).Select(p => p.Index).ToList();
+                if (self.Count >= 2)
+                {
+                    var adjacent = self.Zip(self.Skip(1)).Any(p => alternatives[i].Skip(p.First + 1).Take(p.Second - p.First - 1).All(IsNullable));
+                    findings.Add(new GrammarRiskFinding(GrammarRiskKind.AmbiguousRecursion, adjacent ? GrammarRiskLevel.Medium : GrammarRiskLevel.Low, name, false, Describe(alternatives[i]),
+                        adjacent
+                            ? $"'{name}' follows itself with nothing required in between, so a span of n items has exponentially many parses"
+                            : $"'{name}' refers to itself more than once; without precedence a chain has exponentially many parses"));
+                }
+            }
+        }
+    }
+
+    private static bool Reaches(Dictionary<string, HashSet<string>> edges, string from, string to)
+    {
+        var seen = new HashSet<string>(StringComparer.Ordinal) { from };
+        var pending = new Stack<string>();
+        pending.Push(from);
+        while (pending.Count > 0)
+        {
+            var rule = pending.Pop();
+            if (rule == to)
+            {
+                return true;
+            }
+
+            foreach (var next in edges.GetValueOrDefault(rule) ?? new HashSet<string>())
+            {
+                if (seen.Add(next))
+                {
+                    pending.Push(next);
+                }
+            }
+        }
+
+        return false;
+    }
+
+    private static string Describe(IEnumerable<GrammarSymbol> symbols)
+    {
+        return string.Join(" ", symbols);
+    }
+
+    /// <summary>
+    /// Parses one regular expression into a small syntax tree and walks it for backtracking hazards, returning
+    /// the estimated size of its deterministic automaton.
+    /// </summary>
+    private sealed class PatternAnalysis
+    {
+        private const int MaxDepth = 256;
+
+        private readonly string _pattern;
+        private readonly string _name;
+        private readonly List<GrammarRiskFinding> _findings;
+        private int _position;
+        private int _depth;
+        private int _exponent;
+        private int _exponentStart;
+        private int _exponentEnd;
+
+        public PatternAnalysis(string pattern, string name, List<GrammarRiskFinding> findings)
+        {
+            _pattern = pattern;
+            _name = name;
+            _findings = findings;
+        }
+
+        public long Run()
+        {
+            var root = ParseAlternation();
+            while (_position < _pattern.Length)
+            {
+                // A stray ')' ends nothing at the top level.
+                _position++;
+                root = new SequenceNode(0, _pattern.Length, new List<Node> { root, ParseAlternation() });
+            }
+
+            Walk(root, Enumerable.Empty<Node>());
+
+            var linear = Positions(root).Aggregate(1L, (sum, p) => Saturate((double)sum + p.Count));
+            var states = _exponent >= 62 ? long.MaxValue : Saturate(linear + (1L << _exponent));
+            if (_exponent > 0 && states >= MediumDfaStates)
+            {
+                Report(GrammarRiskKind.DfaBlowup, states >= HighDfaStates ? GrammarRiskLevel.High : GrammarRiskLevel.Medium, _exponentStart, _exponentEnd,
+                    $"a deterministic automaton needs about 2^{_exponent} states, because characters the unbounded repetition matches reach only some of the {_exponent} positions after it");
+            }
+
+            return states;
+        }
+
+        private void Walk(Node node, IEnumerable<Node> following)
+        {
+            switch (node)
+            {
+                case SequenceNode sequence:
+                    for (var i = 0; i < sequence.Items.Count; i++)
+                    {
+                        Walk(sequence.Items[i], sequence.Items.Skip(i + 1).Concat(following));
+                    }
+
+                    CheckAdjacent(sequence.Items);
+                    break;
+
+                case AlternationNode alternation:
+                    foreach (var branch in alternation.Branches)
+                    {
+                        Walk(branch, following);
+                    }
+
+                    break;
+
+                case RepeatNode repeat:
+                    Walk(repeat.Body, following);
+                    if (repeat.Max is null or >= UnboundedCount)
+                    {
+                        CheckNested(repeat);
+                    }
+
+                    if (repeat.Max == null)
+                    {
+                        CheckOverlappingBranches(repeat);
+                        EstimateBlowup(repeat, following);
+                    }
+
+                    break;
+
+                case EmptyNode { Inner: { } inner }:
+                    Walk(inner, Enumerable.Empty<Node>());
+                    break;
+            }
+        }
+
+        private void CheckNested(RepeatNode outer)
+        {
+            var body = Unwrap(outer.Body);
+            var branches = body is AlternationNode alternation ? alternation.Branches : new List<Node> { body };
+            foreach (var branch in branches)
+            {
+                var items = Unwrap(branch) is SequenceNode sequence ? sequence.Items : new List<Node> { branch };
+                for (var i = 0; i < items.Count; i++)
+                {
+                    if (Unwrap(items[i]) is not RepeatNode { Max: null or >= UnboundedCount } inner)
+                    {
+                        continue;
+                    }
+
+                    var chars = Chars(inner.Body);
+                    if (items.Where((_, j) => j != i).All(x => Nullable(x) || Chars(x).IsSubsetOf(chars)))
+                    {
+                        Report(GrammarRiskKind.NestedQuantifier, GrammarRiskLevel.Critical, outer.Start, outer.End,
+                            $"the repetition {Text(inner)} is itself repeated, so input that almost matches is split between the repetitions in exponentially many ways");
+                        return;
+                    }
+                }
+            }
+        }
+
+        private void CheckOverlappingBranches(RepeatNode repeat)
+        {
+            if (Unwrap(repeat.Body) is not AlternationNode alternation)
+            {
+                return;
+            }
+
+            var branches = alternation.Branches.Select(Unwrap).ToList();
+            for (var i = 0; i < branches.Count; i++)
+            {
+                for (var j = i + 1; j < branches.Count; j++)
+                {
+                    var same = Text(branches[i]) == Text(branches[j]);
+                    var overlapping = IsSingleCharacter(branches[i]) && IsSingleCharacter(branches[j]) && Chars(branches[i]).Overlaps(Chars(branches[j]));
+                    if (same || overlapping)
+                    {
+                        Report(GrammarRiskKind.OverlappingAlternation, GrammarRiskLevel.Critical, repeat.Start, repeat.End,
+                            $"the branches {Text(branches[i])} and {Text(branches[j])} match the same characters, so every repetition can take either and a failing match tries exponentially many combinations");
+                        return;
+                    }
+                }
+            }
+        }
+
+        private void CheckAdjacent(List<Node> items)
+        {
+            for (var i = 0; i < items.Count; i++)
+            {
+                if (Unwrap(items[i]) is not RepeatNode { Max: null } first)
+                {
+                    continue;
+                }
+
+                for (var j = i + 1; j < items.Count; j++)
+                {
+                    if (Unwrap(items[j]) is RepeatNode { Max: null } second && Chars(first.Body).Overlaps(First(second.Body)))
+                    {
+                        Report(GrammarRiskKind.AdjacentQuantifiers, GrammarRiskLevel.Medium, first.Start, second.End,
+                            $"{Text(first)} and {Text(second)} match the same characters with nothing required between them, so a failing match tries every way to divide the input");
+                        break;
+                    }
+
+                    if (!Nullable(items[j]))
+                    {
+                        break;
+                    }
+                }
+            }
+        }
+
+        private void EstimateBlowup(RepeatNode repeat, IEnumerable<Node> following)
+        {
+            // After x*, a deterministic automaton tracks which of the positions that follow have matched the
+            // characters read so far. When some character x matches advances only part of them, every subset
+            // of those positions is a distinct state, as in [ab]*a[ab]{n}.
+            var chars = Chars(repeat.Body);
+            var exponent = 0L;
+            var end = repeat.End;
+            var intersections = new HashSet<CharSet>();
+            foreach (var node in following.TakeWhile(_ => exponent < 62))
+            {
+                foreach (var position in Positions(node).Where(p => p.Set.Overlaps(chars)))
+                {
+                    exponent = Saturate((double)exponent + position.Count);
+                    intersections.Add(position.Set.Intersect(chars));
+                    end = Math.Max(end, node.End);
+                }
+            }
+
+            if (intersections.Count > 1 && exponent > _exponent)
+            {
+                _exponent = (int)Math.Min(exponent, 62);
+                _exponentStart = repeat.Start;
+                _exponentEnd = end;
+            }
+        }
+
+        private void Report(GrammarRiskKind kind, GrammarRiskLevel level, int start, int end, string message)
+        {
+            _findings.Add(new GrammarRiskFinding(kind, level, _name, true, _pattern[start..end], message));
+        }
+
+        private string Text(Node node) => _pattern[node.Start..node.End];
+
+        private static Node Unwrap(Node node)
+        {
+            while (node is SequenceNode { Items.Count: 1 } sequence)
+            {
+                node = sequence.Items[0];
+            }
+
+            return node;
+        }
+
+        private static bool IsSingleCharacter(Node node)
+        {
+            return Unwrap(node) switch
+            {
+                AtomNode => true,
+                RepeatNode repeat => IsSingleCharacter(repeat.Body),
+                AlternationNode alternation => alternation.Branches.All(IsSingleCharacter),
+                _ => false
+            };
+        }
+
+        private static bool Nullable(Node node) => node switch
+        {
+            AtomNode => false,
+            SequenceNode sequence => sequence.Items.All(Nullable),
+            AlternationNode alternation => alternation.Branches.Any(Nullable),
+            RepeatNode repeat => repeat.Min == 0 || Nullable(repeat.Body),
+            _ => true
+        };
+
+        private static CharSet Chars(Node node) => node switch
+        {
+            AtomNode atom => atom.Set,
+            SequenceNode sequence => sequence.Items.Aggregate(default(CharSet), (set, item) => set.Union(Chars(item))),
+            AlternationNode alternation => alternation.Branches.Aggregate(default(CharSet), (set, branch) => set.Union(Chars(branch))),
+            RepeatNode repeat => Chars(repeat.Body),
+            _ => default
+        };
+
+        private static CharSet First(Node node)
+        {
+            switch (node)
+            {
+                case AtomNode atom:
+                    return atom.Set;
+
+                case SequenceNode sequence:
+                    var set = default(CharSet);
+                    foreach (var item in sequence.Items)
+                    {
+                        set = set.Union(First(item));
+                        if (!Nullable(item))
+                        {
+                            break;
+                        }
+                    }
+
+                    return set;
+
+                case AlternationNode alternation:
+                    return alternation.Branches.Aggregate(default(CharSet), (union, branch) => union.Union(First(branch)));
+
+                case RepeatNode repeat:
+                    return First(repeat.Body);
+
+                default:
+                    return default;
+            }
+        }
+
+        // The positions of a pattern's automaton with counted repetitions expanded; an alternation of single
+        // characters is one position.
+        private static IEnumerable<(CharSet Set, long Count)> Positions(Node node)
+        {
+            switch (node)
+            {
+                case AtomNode atom:
+                    return new[] { (atom.Set, 1L) };
+
+                case SequenceNode sequence:
+                    return sequence.Items.SelectMany(Positions).ToList();
+
+                case AlternationNode alternation when alternation.Branches.All(b => Unwrap(b) is AtomNode):
+                    return new[] { (Chars(alternation), 1L) };
+
+                case AlternationNode alternation:
+                    return alternation.Branches.SelectMany(Positions).ToList();
+
+                case RepeatNode repeat:
+                    var times = repeat.Max ?? 1;
+                    return Positions(repeat.Body).Select(p => (p.Set, Saturate(p.Count * (double)times))).ToList();
+
+                default:
+                    return Array.Empty<(CharSet, long)>();
+            }
+        }
+
+        private static long Saturate(double value) => value >= long.MaxValue ? long.MaxValue : (long)value;
+
+        private Node ParseAlternation()
+        {
+            var start = _position;
+            var branches = new List<Node> { ParseSequence() };
+            while (_position < _pattern.Length && _pattern[_position] == '|')
+            {
+                _position++;
+                branches.Add(ParseSequence());
+            }
+
+            return branches.Count == 1 ? branches[0] : new AlternationNode(start, _position, branches);
+        }
+
+        private Node ParseSequence()
+        {
+            var start = _position;
+            var items = new List<Node>();
+            while (_position < _pattern.Length && _pattern[_position] is not ('|' or ')'))
+            {
+                items.Add(ParseQuantified(ParseAtom()));
+            }
+
+            return new SequenceNode(start, _position, items);
+        }
+
+        private Node ParseQuantified(Node node)
+        {
+            for (var chain = 0; _position < _pattern.Length && chain < MaxDepth; chain++)
+            {
+                var c = _pattern[_position];
+                int min;
+                int? max;
+                if (c is '*' or '+' or '?')
+                {
+                    _position++;
+                    min = c == '+' ? 1 : 0;
+                    max = c == '?' ? 1 : null;
+                }
+                else if (c != '{' || !TryReadCount(out min, out max))
+                {
+                    return node;
+                }
+
+                // A lazy marker changes which match is found first, not how many are tried.
+                if (_position < _pattern.Length && _pattern[_position] == '?')
+                {
+                    _position++;
+                }
+
+                node = new RepeatNode(node.Start, _position, node, min, max);
+            }
+
+            return node;
+        }
+
+        private bool TryReadCount(out int min, out int? max)
+        {
+            var close = _pattern.IndexOf('}', _position);
+            min = 0;
+            max = null;
+            if (close < 0)
+            {
+                return false;
+            }
+
+            var bounds = _pattern.Substring(_position + 1, close - _position - 1).Split(',');
+            if (bounds.Length > 2 || bounds[0].Length == 0 || !bounds.All(b => b.All(char.IsAsciiDigit)))
+            {
+                return false;
+            }
+
+            _position = close + 1;
+            min = int.TryParse(bounds[0], out var low) ? low : int.MaxValue;
+            max = bounds.Length == 1 ? min : bounds[1].Length == 0 ? null : int.TryParse(bounds[1], out var high) ? high : int.MaxValue;
+            return true;
+        }
+
+        private Node ParseAtom()
+        {
+            var start = _position;
+            var c = _pattern[_position++];
+            switch (c)
+            {
+                case '(':
+                    return ParseGroup(start);
+
+                case '[':
+                    return new AtomNode(start, _position, ParseClass());
+
+                case '\\':
+                    return ParseEscape(start);
+
+                case '^' or '$':
+                    return new EmptyNode(start, _position, null);
+
+                case '.':
+                    return new AtomNode(start, _position, CharSet.Single('\n').Negate());
+
+                default:
+                    return new AtomNode(start, _position, CharSet.Single(c));
+            }
+        }
+
+        private Node ParseGroup(int start)
+        {
+            if (++_depth > MaxDepth)
+            {
+                _position = _pattern.Length;
+                return new EmptyNode(start, _position, null);
+            }
+
+            var zeroWidth = false;
+            if (_position < _pattern.Length && _pattern[_position] == '?')
+            {
+                _position++;
+                var rest = _pattern.AsSpan(_position);
+                zeroWidth = rest is ['=' or '!', ..] or ['<', '=' or '!', ..];
+                if (!zeroWidth && rest is ['<' or '\'', ..])
+                {
+                    var close = _pattern.IndexOfAny(new[] { '>', '\'' }, _position + 1);
+                    _position = close < 0 ? _pattern.Length : close + 1;
+                }
+                else
+                {
+                    // Lookarounds, atomic groups and inline options up to ':' or, for (?i), the closing parenthesis.
+                    while (_position < _pattern.Length && _pattern[_position] is not (':' or ')' or '=' or '!' or '>'))
+                    {
+                        _position++;
+                    }
+
+                    if (_position < _pattern.Length && _pattern[_position] is ':' or '=' or '!' or '>')
+                    {
+                        _position++;
+                    }
+                }
+            }
+
+            var inner = ParseAlternation();
+            if (_position < _pattern.Length)
+            {
+                _position++;
+            }
+
+            _depth--;
+            if (zeroWidth)
+            {
+                return new EmptyNode(start, _position, inner);
+            }
+
+            inner.Start = start;
+            inner.End = _position;
+            return inner;
+        }
+
+        private Node ParseEscape(int start)
+        {
+            if (_position >= _pattern.Length)
+            {
+                return new AtomNode(start, _position, CharSet.Single('\\'));
+            }
+
+            var c = _pattern[_position++];
+            if (c is 'b' or 'B' or 'A' or 'z' or 'Z' or 'G')
+            {
+                return new EmptyNode(start, _position, null);
+            }
+
+            if (c == 'k' || char.IsAsciiDigit(c) && c != '0')
+            {
+                // A backreference matches whatever its group did.
+                SkipName();
+                return new AtomNode(start, _position, CharSet.Any);
+            }
+
+            return new AtomNode(start, _position, EscapeSet(c));
+        }
+
+        private CharSet ParseClass()
+        {
+            var negated = _position < _pattern.Length && _pattern[_position] == '^';
+            if (negated)
+            {
+                _position++;
+            }
+
+            var set = default(CharSet);
+            var first = true;
+            while (_position < _pattern.Length && (_pattern[_position] != ']' || first))
+            {
+                first = false;
+                var c = _pattern[_position++];
+                if (c == '-' && _position < _pattern.Length && _pattern[_position] == '[')
+                {
+                    // Subtraction only narrows the class; keeping the wider class errs towards reporting.
+                    _position++;
+                    ParseClass();
+                    continue;
+                }
+
+                if (c == '\\' && _position < _pattern.Length && _pattern[_position] is 'd' or 'D' or 'w' or 'W' or 's' or 'S' or 'p' or 'P')
+                {
+                    set = set.Union(EscapeSet(_pattern[_position++]));
+                    continue;
+                }
+
+                var low = c == '\\' && _position < _pattern.Length ? EscapeChar(_pattern[_position++]) : c;
+                var high = low;
+                if (_position + 1 < _pattern.Length && _pattern[_position] == '-' && _pattern[_position + 1] != ']')
+                {
+                    _position++;
+                    high = _pattern[_position++];
+                    if (high == '\\' && _position < _pattern.Length)
+                    {
+                        high = EscapeChar(_pattern[_position++]);
+                    }
+                }
+
+                set = set.Union(CharSet.Range(low, high));
+            }
+
+            if (_position < _pattern.Length)
+            {
+                _position++;
+            }
+
+            return negated ? set.Negate() : set;
+        }
+
+        private CharSet EscapeSet(char c)
+        {
+            switch (c)
+            {
+                case 'd': return CharSet.Digit;
+                case 'D': return CharSet.Digit.Negate();
+                case 'w': return CharSet.Word;
+                case 'W': return CharSet.Word.Negate();
+                case 's': return CharSet.Space;
+                case 'S': return CharSet.Space.Negate();
+                case 'p' or 'P':
+                    SkipName();
+                    return CharSet.Other;
+                default:
+                    return CharSet.Single(EscapeChar(c));
+            }
+        }
+
+        private char EscapeChar(char c)
+        {
+            switch (c)
+            {
+                case 'n': return '\n';
+                case 'r': return '\r';
+                case 't': return '\t';
+                case 'f': return '\f';
+                case 'v': return '\v';
+                case 'e': return '\u001b';
+                case 'a': return '\a';
+                case 'x' or 'u':
+                    var end = Math.Min(_position + (c == 'x' ? 2 : 4), _pattern.Length);
+                    var code = int.TryParse(_pattern.AsSpan(_position, end - _position), NumberStyles.HexNumber, null, out var value) ? value : 0;
+                    _position = end;
+                    return (char)code;
+                case 'c' when _position < _pattern.Length:
+                    return (char)(_pattern[_position++] % 32);
+                default:
+                    return c;
+            }
+        }
+
+        private void SkipName()
+        {
+            if (_position < _pattern.Length && _pattern[_position] is '{' or '<')
+            {
+                var close = _pattern.IndexOf(_pattern[_position] == '{' ? '}' : '>', _position);
+                _position = close < 0 ? _pattern.Length : close + 1;
+            }
+
+            while (_position < _pattern.Length && char.IsAsciiDigit(_pattern[_position]))
+            {
+                _position++;
+            }
+        }
+    }
+
+    private abstract class Node
+    {
+        protected Node(int start, int end)
+        {
+            Start = start;
+            End = end;
+        }
+
+        public int Start { get; set; }
+
+        public int End { get; set; }
+    }
+
+    private sealed class AtomNode : Node
+    {
+        public AtomNode(int start, int end, CharSet set) : base(start, end) => Set = set;
+
+        public CharSet Set { get; }
+    }
+
+    private sealed class SequenceNode : Node
+    {
+        public SequenceNode(int start, int end, List<Node> items) : base(start, end) => Items = items;
+
+        public List<Node> Items { get; }
+    }
+
+    private sealed class AlternationNode : Node
+    {
+        public AlternationNode(int start, int end, List<Node> branches) : base(start, end) => Branches = branches;
+
+        public List<Node> Branches { get; }
+    }
+
+    private sealed class RepeatNode : Node
+    {
+        public RepeatNode(int start, int end, Node body, int min, int? max) : base(start, end)
+        {
+            Body = body;
+            Min = min;
+            Max = max;
+        }
+
+        public Node Body { get; }
+
+        public int Min { get; }
+
+        public int? Max { get; }
+    }
+
+    // Anchors, word boundaries and lookarounds, which match no characters; a lookaround keeps its content.
+    private sealed class EmptyNode : Node
+    {
+        public EmptyNode(int start, int end, Node? inner) : base(start, end) => Inner = inner;
+
+        public Node? Inner { get; }
+    }
+
+    /// <summary>
+    /// A set of characters: ASCII exactly, everything beyond it as three buckets (letters and digits, white space,
+    /// the rest), which is precise enough to tell which repetitions compete for the same input.
+    /// </summary>
+    private readonly record struct CharSet(ulong Low, ulong High, int Beyond)
+    {
+        private const int BeyondWord = 1;
+        private const int BeyondSpace = 2;
+        private const int BeyondAll = 7;
+
+        public static readonly CharSet Other = new(0, 0, BeyondAll);
+        public static readonly CharSet Any = new(ulong.MaxValue, ulong.MaxValue, BeyondAll);
+        public static readonly CharSet Digit = Range('0', '9') with { Beyond = BeyondWord };
+        public static readonly CharSet Word = Range('a', 'z').Union(Range('A', 'Z')).Union(Digit).Union(Single('_'));
+        public static readonly CharSet Space = Single(' ').Union(Range('\t', '\r')) with { Beyond = BeyondSpace };
+
+        public bool IsEmpty => Low == 0 && High == 0 && Beyond == 0;
+
+        public static CharSet Single(char c)
+        {
+            if (c < 128)
+            {
+                return Range(c, c);
+            }
+
+            return new CharSet(0, 0, char.IsLetterOrDigit(c) ? BeyondWord : char.IsWhiteSpace(c) ? BeyondSpace : BeyondAll & ~(BeyondWord | BeyondSpace));
+        }
+
+        public static CharSet Range(char low, char high)
+        {
+            ulong lowBits = 0, highBits = 0;
+            for (int c = low; c <= Math.Min((int)high, 127); c++)
+            {
+                if (c < 64)
+                {
+                    lowBits |= 1UL << c;
+                }
+                else
+                {
+                    highBits |= 1UL << (c - 64);
+                }
+            }
+
+            return new CharSet(lowBits, highBits, high >= 128 ? BeyondAll : 0);
+        }
+
+        public CharSet Union(CharSet other) => new(Low | other.Low, High | other.High, Beyond | other.Beyond);
+
+        public CharSet Intersect(CharSet other) => new(Low & other.Low, High & other.High, Beyond & other.Beyond);
+
+        public CharSet Negate() => new(~Low, ~High, BeyondAll & ~Beyond);
+
+        public bool Overlaps(CharSet other) => !Intersect(other).IsEmpty;
+
+        public bool IsSubsetOf(CharSet other) => Intersect(other) == this;
+    }
+}
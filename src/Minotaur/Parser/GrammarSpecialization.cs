@@ -0,0 +1,496 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Options of <see cref="CompiledGrammar.Specialize"/>.
+/// </summary>
+public sealed class SpecializationOptions
+{
+    /// <summary>
+    /// Gets or sets whether rules with a <c>// @expected</c> annotation are kept, with the rules they reach, when no
+    /// entry point reaches them under the enabled features, so tools naming them in messages still find them.
+    /// </summary>
+    public bool KeepExpectedRules { get; set; }
+}
+
+/// <summary>
+/// What <see cref="CompiledGrammar.Specialize"/> removed from a grammar.
+/// </summary>
+public sealed class SpecializationReport
+{
+    internal SpecializationReport()
+    {
+    }
+
+    /// <summary>
+    /// Gets the rules the specialized grammar starts from.
+    /// </summary>
+    public IReadOnlyList<string> EntryRules { get; internal init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the enabled features.
+    /// </summary>
+    public IReadOnlyList<string> EnabledFeatures { get; internal init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the rules no entry point reaches, in grammar order.
+    /// </summary>
+    public IReadOnlyList<string> RemovedRules { get; internal init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the alternatives of kept rules guarded by features that are not enabled, as <c>&lt;rule&gt; ::= alternative</c>.
+    /// </summary>
+    public IReadOnlyList<string> RemovedAlternatives { get; internal init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the token patterns no kept terminal uses.
+    /// </summary>
+    public IReadOnlyList<string> RemovedTokenPatterns { get; internal init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the keys of the terminals only removed rules used, which <see cref="RetainedTerminalsRule"/> keeps so
+    /// the lexer reads every input exactly as the original grammar's lexer does.
+    /// </summary>
+    public IReadOnlyList<string> RetainedTerminals { get; internal init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the name of the rule no entry point reaches that lists the original grammar's terminals in their original
+    /// order, or null if the kept rules already reference all of them in that order.
+    /// </summary>
+    public string? RetainedTerminalsRule { get; internal init; }
+
+    /// <summary>
+    /// Gets the example annotations of kept rules that use removed alternatives and so were left out.
+    /// </summary>
+    public IReadOnlyList<GrammarAnnotation> DroppedExamples { get; internal init; } = Array.Empty<GrammarAnnotation>();
+
+    /// <summary>
+    /// Gets the number of rules before specialization.
+    /// </summary>
+    public int RulesBefore { get; internal init; }
+
+    /// <summary>
+    /// Gets the number of rules after specialization, including <see cref="RetainedTerminalsRule"/>.
+    /// </summary>
+    public int RulesAfter { get; internal init; }
+
+    /// <summary>
+    /// Gets the number of parser states before specialization: one per position of the dot in every alternative.
+    /// </summary>
+    public int StatesBefore { get; internal init; }
+
+    /// <summary>
+    /// Gets the number of parser states after specialization.
+    /// </summary>
+    public int StatesAfter { get; internal init; }
+
+    /// <summary>
+    /// Summarizes the report, one removed item per line.
+    /// </summary>
+    /// <returns>The summary.</returns>
+    public override string ToString()
+    {
+        var text = new StringBuilder();
+        text.AppendLine($"Specialized for {string.Join(", ", EntryRules.Select(r => $"<{r}>"))} with features [{string.Join(", ", EnabledFeatures)}]");
+        text.AppendLine($"Rules: {RulesBefore} -> {RulesAfter}; states: {StatesBefore} -> {StatesAfter}");
+        foreach (var rule in RemovedRules)
+        {
+            text.AppendLine($"  removed rule <{rule}>");
+        }
+
+        foreach (var alternative in RemovedAlternatives)
+        {
+            text.AppendLine($"  removed alternative {alternative}");
+        }
+
+        foreach (var pattern in RemovedTokenPatterns)
+        {
+            text.AppendLine($"  removed token <{pattern}>");
+        }
+
+        if (RetainedTerminals.Count > 0)
+        {
+            text.AppendLine($"  kept {RetainedTerminals.Count} terminal(s) of removed rules in <{RetainedTerminalsRule}> for the lexer");
+        }
+
+        foreach (var example in DroppedExamples)
+        {
+            text.AppendLine($"  dropped example of <{example.Target}> at line {example.Line}");
+        }
+
+        return text.ToString();
+    }
+}
+
+/// <summary>
+/// A grammar specialized to some entry points and features: the rules they reach with the alternatives the features
+/// allow, compiled. For those entry points and features it accepts exactly the inputs the original grammar accepts.
+/// </summary>
+/// <remarks>
+/// <para>
+/// Alternatives guarded by a feature that is not enabled are removed; the original grammar recognizes them only
+/// to report them as disabled. Rules no entry point reaches through the remaining alternatives are removed with
+/// their annotations, and metadata naming them (entry points, features, hidden and inlined rules, contextual
+/// keywords, lexical rules) is rewritten to leave them out.
+/// </para>
+/// <para>
+/// Terminals that only removed rules use still decide how the input is split into tokens, and their order breaks
+/// ties between matches, so all terminals are kept in their original order in one rule that nothing reaches;
+/// without it <c>&lt;=</c> might lex as <c>&lt;</c> <c>=</c> and be accepted.
+/// </para>
+/// </remarks>
+public sealed class GrammarSpecialization
+{
+    private const string RetainedTerminalsName = "retained_terminals";
+
+    private GrammarSpecialization(Grammar grammar, CompiledGrammar compiled, SpecializationReport report)
+    {
+        Grammar = grammar;
+        Compiled = compiled;
+        Report = report;
+    }
+
+    /// <summary>
+    /// Gets the specialized grammar model.
+    /// </summary>
+    public Grammar Grammar { get; }
+
+    /// <summary>
+    /// Gets the specialized grammar, compiled.
+    /// </summary>
+    public CompiledGrammar Compiled { get; }
+
+    /// <summary>
+    /// Gets what was removed.
+    /// </summary>
+    public SpecializationReport Report { get; }
+
+    internal static GrammarSpecialization Create(CompiledGrammar grammar, IEnumerable<string> entryPoints, IEnumerable<string> enabledFeatures, SpecializationOptions? options)
+    {
+        ArgumentNullException.ThrowIfNull(entryPoints);
+        ArgumentNullException.ThrowIfNull(enabledFeatures);
+        options ??= new SpecializationOptions();
+
+        var names = entryPoints.Distinct().ToList();
+        if (names.Count == 0)
+        {
+            throw new ArgumentException("At least one entry point is required", nameof(entryPoints));
+        }
+
+        var entryRules = names
+            .Select(name => grammar.EntryPoints.GetValueOrDefault(name)?.Rule ?? grammar.GetRule(name)
+                ?? throw new ArgumentException($"'{name}' is neither an entry point nor a rule of grammar '{grammar.Name}'", nameof(entryPoints)))
+            .Distinct()
+            .ToList();
+
+        var features = enabledFeatures.Distinct().ToList();
+        var unknown = features.FirstOrDefault(f => !grammar.Features.Contains(f));
+        if (unknown != null)
+        {
+            throw new ArgumentException($"Grammar '{grammar.Name}' declares no feature '{unknown}'", nameof(enabledFeatures));
+        }
+
+        var allowed = AllowedAlternatives(grammar, features);
+        bool Allowed(CompiledAlternative alternative) => allowed.Contains(alternative);
+
+        // The rules reachable through allowed alternatives, including layout rules inserted between their symbols.
+        var reached = new bool[grammar.Rules.Count];
+        var pending = new Stack<CompiledRule>();
+        var roots = options.KeepExpectedRules ? entryRules.Concat(grammar.Rules.Where(r => r.ExpectedPhrase != null)) : entryRules;
+        foreach (var root in roots.Where(r => !reached[r.Index]))
+        {
+            reached[root.Index] = true;
+            pending.Push(root);
+        }
+
+        while (pending.Count > 0)
+        {
+            foreach (var index in pending.Pop().Alternatives.Where(Allowed).SelectMany(a => a.RuleIndices).Where(i => i >= 0 && !reached[i]))
+            {
+                reached[index] = true;
+                pending.Push(grammar.Rules[index]);
+            }
+        }
+
+        var kept = grammar.Rules.Where(r => reached[r.Index]).ToList();
+        var model = new Grammar
+        {
+            Name = grammar.Source.Name,
+            Language = grammar.Source.Language,
+            Created = grammar.Source.Created,
+            Version = grammar.Source.Version,
+            Metadata = new Dictionary<string, string>(grammar.Source.Metadata)
+        };
+
+        var removedAlternatives = new List<string>();
+        var alternativeIndices = new Dictionary<string, Dictionary<int, int>>();
+        foreach (var rule in kept)
+        {
+            var production = new ProductionRule { Name = rule.Name };
+            var indices = new Dictionary<int, int>();
+            foreach (var alternative in rule.Alternatives)
+            {
+                if (!Allowed(alternative))
+                {
+                    removedAlternatives.Add($"<{rule.Name}> ::= {alternative.Text}");
+                    continue;
+                }
+
+                indices[alternative.Index] = production.Alternatives.Count;
+                if (alternative.Action != null)
+                {
+                    production.Actions[production.Alternatives.Count] = alternative.Action;
+                }
+
+                production.Alternatives.Add(alternative.Text);
+            }
+
+            alternativeIndices[rule.Name] = indices;
+            model.ProductionRules.AddRule(production);
+        }
+
+        // The lexer tries terminals in first-reference order, so when the kept rules drop terminals or reference them
+        // in another order, a first rule nothing reaches lists all of them as the original grammar references them.
+        var lexed = grammar.IsScannerless
+            ? new List<GrammarSymbol>()
+            : grammar.GetTerminals().Where(t => t.Kind != GrammarSymbolKind.Literal || !grammar.IsContextualKeyword(t.Name)).ToList();
+        var keptTerminals = kept
+            .SelectMany(r => r.Alternatives)
+            .Where(Allowed)
+            .SelectMany(a => a.Symbols)
+            .Where(s => s.IsTerminal)
+            .Distinct()
+            .ToList();
+        var retained = lexed.Except(keptTerminals).ToList();
+        string? retainedRule = null;
+        if (!lexed.SequenceEqual(keptTerminals.Where(lexed.Contains)))
+        {
+            retainedRule = RetainedTerminalsName;
+            for (var i = 2; grammar.GetRule(retainedRule) != null; i++)
+            {
+                retainedRule = $"{RetainedTerminalsName}_{i}";
+            }
+
+            model.ProductionRules.Rules.Insert(0, new ProductionRule { Name = retainedRule, Alternatives = lexed.Select(Write).ToList() });
+        }
+
+        var usedTokens = keptTerminals.Concat(lexed).Select(t => t.Key).ToHashSet();
+        var removedPatterns = new List<string>();
+        foreach (var pattern in grammar.Source.TokenRules.Patterns)
+        {
+            if (pattern.Type is TokenType.Whitespace or TokenType.Comment || usedTokens.Contains(pattern.Name))
+            {
+                model.TokenRules.AddPattern(pattern);
+            }
+            else
+            {
+                removedPatterns.Add(pattern.Name);
+            }
+        }
+
+        var keptPatterns = model.TokenRules.Patterns.Select(p => p.Name).ToHashSet();
+        var droppedExamples = new List<GrammarAnnotation>();
+        var examples = new GeneralizedParser(grammar);
+        foreach (var annotation in grammar.Source.Annotations)
+        {
+            if (alternativeIndices.TryGetValue(annotation.Target, out var indices))
+            {
+                // Annotations of some alternatives follow them to their new indices and go when they all go.
+                var moved = annotation.Alternatives.Where(indices.ContainsKey).Select(i => indices[i]).ToList();
+                if (annotation.Alternatives.Count > 0 && moved.Count == 0)
+                {
+                    continue;
+                }
+
+                if (annotation.Kind == GrammarAnnotationKind.Example && !examples.Parse(annotation.Text, new ParseOptions
+                {
+                    StartRule = annotation.Target,
+                    End = EntryPointEnd.Complete,
+                    Features = features,
+                    KeywordCorrectionDistance = 0
+                }).IsSuccess)
+                {
+                    droppedExamples.Add(annotation);
+                    continue;
+                }
+
+                model.Annotations.Add(new GrammarAnnotation
+                {
+                    Kind = annotation.Kind,
+                    Target = annotation.Target,
+                    Text = annotation.Text,
+                    Line = annotation.Line,
+                    Alternatives = moved
+                });
+            }
+            else if (keptPatterns.Contains(annotation.Target))
+            {
+                model.Annotations.Add(annotation);
+            }
+        }
+
+        RewriteMetadata(model, grammar, names, entryRules[0].Name, features);
+        var compiled = CompiledGrammar.Compile(model, entryRules[0].Name);
+
+        var report = new SpecializationReport
+        {
+            EntryRules = entryRules.Select(r => r.Name).ToList(),
+            EnabledFeatures = features,
+            RemovedRules = grammar.Rules.Where(r => !reached[r.Index]).Select(r => r.Name).ToList(),
+            RemovedAlternatives = removedAlternatives,
+            RemovedTokenPatterns = removedPatterns,
+            RetainedTerminals = retained.Select(t => t.Key).ToList(),
+            RetainedTerminalsRule = retainedRule,
+            DroppedExamples = droppedExamples,
+            RulesBefore = grammar.Rules.Count,
+            RulesAfter = compiled.Rules.Count,
+            StatesBefore = CountStates(grammar),
+            StatesAfter = CountStates(compiled)
+        };
+
+        return new GrammarSpecialization(model, compiled, report);
+    }
+
+    // The alternatives whose feature is enabled, less those using a rule left with none, which derive nothing.
+    private static HashSet<CompiledAlternative> AllowedAlternatives(CompiledGrammar grammar, List<string> features)
+    {
+        var allowed = grammar.Rules
+            .SelectMany(r => r.Alternatives)
+            .Where(a => a.Feature == null || features.Contains(a.Feature))
+            .ToHashSet();
+
+        var empty = new bool[grammar.Rules.Count];
+        var changed = true;
+        while (changed)
+        {
+            changed = false;
+            foreach (var rule in grammar.Rules.Where(r => !empty[r.Index] && !r.Alternatives.Any(allowed.Contains)))
+            {
+                empty[rule.Index] = true;
+                changed = true;
+            }
+
+            if (changed)
+            {
+                allowed.RemoveWhere(a => a.RuleIndices.Any(i => i >= 0 && empty[i]));
+            }
+        }
+
+        return allowed;
+    }
+
+    private static void RewriteMetadata(Grammar model, CompiledGrammar grammar, List<string> names, string startRule, List<string> features)
+    {
+        var metadata = model.Metadata;
+        var ruleNames = grammar.Rules.Select(r => r.Name).ToHashSet();
+        var alternatives = model.ProductionRules.Rules.ToDictionary(r => r.Name, r => r.Alternatives);
+        var kept = alternatives.Keys.ToHashSet();
+        metadata["StartRule"] = startRule;
+
+        Rewrite(metadata, CompiledGrammar.EntryPointsKey, "; ", grammar.EntryPoints.Values
+            .Where(e => names.Contains(e.Name))
+            .Select(e => e.ToString()));
+
+        Rewrite(metadata, CompiledGrammar.FeaturesKey, "; ", Entries(metadata, CompiledGrammar.FeaturesKey).Where(entry =>
+        {
+            var separator = entry.IndexOf('=');
+            var target = entry[(separator + 1)..];
+            var definition = target.IndexOf("::=", StringComparison.Ordinal);
+            var rule = (definition >= 0 ? target[..definition] : target).Trim().Trim('<', '>');
+            if (!features.Contains(entry[..separator].Trim()) || !alternatives.TryGetValue(rule, out var texts))
+            {
+                return false;
+            }
+
+            // An alternative using a rule left with no alternatives is removed even when its feature is enabled.
+            var symbols = definition >= 0 ? GrammarSymbol.ParseAlternative(target[(definition + 3)..], ruleNames) : null;
+            return symbols == null || texts.Any(t => GrammarSymbol.ParseAlternative(t, ruleNames).SequenceEqual(symbols));
+        }));
+
+        Rewrite(metadata, CompiledGrammar.ContextualKeywordsKey, "; ", Entries(metadata, CompiledGrammar.ContextualKeywordsKey).Select(entry =>
+        {
+            var separator = entry.IndexOf('=');
+            var rules = entry[(separator + 1)..].Split(new[] { ',', ' ' }, StringSplitOptions.RemoveEmptyEntries).Where(r => kept.Contains(r.Trim('<', '>'))).ToList();
+            return rules.Count > 0 ? $"{entry[..separator].Trim()} = {string.Join(' ', rules)}" : null;
+        }).OfType<string>());
+
+        Rewrite(metadata, CompiledGrammar.SymbolPredicatesKey, "; ", Entries(metadata, CompiledGrammar.SymbolPredicatesKey).Where(entry =>
+        {
+            var separator = entry.IndexOf('=');
+            return separator > 0 && kept.Contains(entry[..separator].Trim().Trim('<', '>')) &&
+                kept.Contains(entry[(separator + 1)..].Trim().TrimStart('!').Trim().Trim('<', '>'));
+        }));
+
+        foreach (var key in new[] { CompiledGrammar.HideKey, CompiledGrammar.InlineKey, CompiledGrammar.DeferredKey, CompiledGrammar.OutlineKey })
+        {
+            if (metadata.TryGetValue(key, out var declaration))
+            {
+                Rewrite(metadata, key, " ", GrammarSymbol.ParseAlternative(declaration, ruleNames)
+                    .Where(s => s.IsTerminal || kept.Contains(s.Name))
+                    .Select(Write));
+            }
+        }
+
+        if (grammar.Documents is { } documents && !kept.Contains(documents.Rule.Name))
+        {
+            metadata.Remove(CompiledGrammar.DocumentsKey);
+        }
+
+        if (metadata.TryGetValue(CompiledGrammar.LexicalRulesKey, out var lexical))
+        {
+            Rewrite(metadata, CompiledGrammar.LexicalRulesKey, ", ", lexical
+                .Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries)
+                .Where(kept.Contains));
+        }
+    }
+
+    private static IEnumerable<string> Entries(Dictionary<string, string> metadata, string key)
+    {
+        return metadata.TryGetValue(key, out var declaration)
+            ? declaration.Split(';', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries)
+            : Enumerable.Empty<string>();
+    }
+
+    private static void Rewrite(Dictionary<string, string> metadata, string key, string separator, IEnumerable<string> entries)
+    {
+        var list = entries.ToList();
+        if (list.Count > 0)
+        {
+            metadata[key] = string.Join(separator, list);
+        }
+        else
+        {
+            metadata.Remove(key);
+        }
+    }
+
+    // A symbol in the grammar notation; literals holding a double quote are written in single quotes.
+    private static string Write(GrammarSymbol symbol)
+    {
+        return symbol.Kind == GrammarSymbolKind.Literal && symbol.Name.Contains('"') ? $"'{symbol.Name}'" : symbol.ToString();
+    }
+
+    private static int CountStates(CompiledGrammar grammar)
+    {
+        return grammar.Rules.SelectMany(r => r.Alternatives).Sum(a => a.Symbols.Count + 1);
+    }
+}
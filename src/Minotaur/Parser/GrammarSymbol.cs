@@ -0,0 +1,309 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A symbol appearing in a production alternative.
+/// </summary>
+/// <param name="Kind">The kind of symbol.</param>
+/// <param name="Name">The rule or token name, the literal text, or the regular expression.</param>
+public sealed record GrammarSymbol(GrammarSymbolKind Kind, string Name)
+{
+    /// <summary>
+    /// How a negative lookahead constraint starts in an alternative, e.g. <c>&lt;IDENTIFIER&gt; !FOLLOWED_BY(":")</c>.
+    /// </summary>
+    public const string NotFollowedByKeyword = "!FOLLOWED_BY(";
+
+    /// <summary>
+    /// Gets a value indicating whether the symbol is a terminal.
+    /// </summary>
+    public bool IsTerminal => Kind != GrammarSymbolKind.Rule;
+
+    /// <summary>
+    /// Gets the key used to match this terminal against token kinds produced by the lexer.
+    /// Literals are quoted and inline patterns are slash-delimited so they never collide with token names.
+    /// </summary>
+    public string Key => Kind switch
+    {
+        GrammarSymbolKind.Literal => $"\"{Name}\"",
+        GrammarSymbolKind.Pattern => $"/{Name}/",
+        _ => Name
+    };
+
+    /// <summary>
+    /// Returns the symbol as it is written in grammar files.
+    /// </summary>
+    /// <returns>The grammar notation of the symbol.</returns>
+    public override string ToString()
+    {
+        return Kind is GrammarSymbolKind.Rule or GrammarSymbolKind.Token ? $"<{Name}>" : Key;
+    }
+
+    /// <summary>
+    /// Parses a production alternative written in the grammar file notation into symbols.
+    /// </summary>
+    /// <param name="alternative">The alternative text, e.g. <c>"if" &lt;expr&gt; &lt;block&gt;</c>.</param>
+    /// <param name="ruleNames">The names of production rules; other angle-bracket references are tokens.</param>
+    /// <returns>The symbols of the alternative; empty for an epsilon alternative.</returns>
+    public static IReadOnlyList<GrammarSymbol> ParseAlternative(string alternative, ISet<string> ruleNames)
+    {
+        return ParseSymbols(alternative, ruleNames, constraints: null);
+    }
+
+    /// <summary>
+    /// Parses the negative lookahead constraint of a production alternative, written anywhere in it as
+    /// <c>!FOLLOWED_BY(":" "=")</c>: the alternative only applies where none of the listed terminals follows it.
+    /// </summary>
+    /// <param name="alternative">The alternative text.</param>
+    /// <param name="ruleNames">The names of production rules; other angle-bracket references are tokens.</param>
+    /// <returns>The listed symbols in order; empty if the alternative has no constraint.</returns>
+    /// <exception cref="FormatException">The constraint is not closed or is written more than once.</exception>
+    public static IReadOnlyList<GrammarSymbol> ParseNotFollowedBy(string alternative, ISet<string> ruleNames)
+    {
+        var constraints = new List<(int Start, int End)>();
+        ParseSymbols(alternative, ruleNames, constraints);
+        if (constraints.Count == 0)
+        {
+            return Array.Empty<GrammarSymbol>();
+        }
+
+        if (constraints.Count > 1)
+        {
+            throw new FormatException($"{NotFollowedByKeyword} is written more than once");
+        }
+
+        var (start, end) = constraints[0];
+        return end >= 0
+            ? ParseSymbols(alternative[start..end], ruleNames, constraints: null)
+            : throw new FormatException($"{NotFollowedByKeyword} is not closed with ')'");
+    }
+
+    private static IReadOnlyList<GrammarSymbol> ParseSymbols(string alternative, ISet<string> ruleNames, List<(int Start, int End)>? constraints)
+    {
+        var symbols = new List<GrammarSymbol>();
+        var i = 0;
+
+        while (i < alternative.Length)
+        {
+            var c = alternative[i];
+
+            if (char.IsWhiteSpace(c))
+            {
+                i++;
+                continue;
+            }
+
+            // Constraints are not symbols; their terminals are read by ParseNotFollowedBy.
+            if (c == '!' && alternative.AsSpan(i).StartsWith(NotFollowedByKeyword))
+            {
+                var start = i + NotFollowedByKeyword.Length;
+                var close = FindConstraintEnd(alternative, start);
+                constraints?.Add((start, close));
+                i = close >= 0 ? close + 1 : alternative.Length;
+                continue;
+            }
+
+            if (c == '<')
+            {
+                var close = alternative.IndexOf('>', i + 1);
+                if (close > i + 1 && IsReferenceName(alternative.AsSpan(i + 1, close - i - 1)))
+                {
+                    var name = alternative.Substring(i + 1, close - i - 1);
+                    symbols.Add(new GrammarSymbol(ruleNames.Contains(name) ? GrammarSymbolKind.Rule : GrammarSymbolKind.Token, name));
+                    i = close + 1;
+                    continue;
+                }
+            }
+
+            if (c == '"' || c == '\'')
+            {
+                var (text, next) = ReadQuoted(alternative, i);
+                if (text.Length > 0)
+                {
+                    symbols.Add(new GrammarSymbol(GrammarSymbolKind.Literal, text));
+                }
+
+                i = next;
+                continue;
+            }
+
+            if (c == '/' && i + 1 < alternative.Length && !char.IsWhiteSpace(alternative[i + 1]))
+            {
+                var (pattern, next) = ReadPattern(alternative, i);
+                if (pattern != null)
+                {
+                    symbols.Add(new GrammarSymbol(GrammarSymbolKind.Pattern, pattern));
+                    i = next;
+                    continue;
+                }
+            }
+
+            // Bare words (e.g. ';' or 'return' written without quotes) are treated as literals.
+            var wordStart = i;
+            while (i < alternative.Length && !char.IsWhiteSpace(alternative[i]))
+            {
+                i++;
+            }
+
+            var word = alternative.Substring(wordStart, i - wordStart);
+            if (word != "ε")
+            {
+                symbols.Add(new GrammarSymbol(GrammarSymbolKind.Literal, word));
+            }
+        }
+
+        return symbols;
+    }
+
+    // Returns the index of the ')' closing a constraint whose terminals start at the specified index, or -1.
+    private static int FindConstraintEnd(string text, int start)
+    {
+        var i = start;
+        while (i < text.Length)
+        {
+            var c = text[i];
+            if (c == ')')
+            {
+                return i;
+            }
+
+            if (c == '"' || c == '\'')
+            {
+                i = ReadQuoted(text, i).Next;
+            }
+            else if (c == '/' && i + 1 < text.Length && ReadPattern(text, i) is { Pattern: not null } pattern)
+            {
+                i = pattern.Next;
+            }
+            else
+            {
+                i++;
+            }
+        }
+
+        return -1;
+    }
+
+    private static bool IsReferenceName(ReadOnlySpan<char> name)
+    {
+        if (!(char.IsLetter(name[0]) || name[0] == '_'))
+        {
+            return false;
+        }
+
+        foreach (var c in name)
+        {
+            if (!(char.IsLetterOrDigit(c) || c == '_' || c == '-'))
+            {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    private static (string Text, int Next) ReadQuoted(string text, int start)
+    {
+        var quote = text[start];
+        var sb = new StringBuilder();
+        var i = start + 1;
+
+        while (i < text.Length && text[i] != quote)
+        {
+            if (text[i] == '\\' && i + 1 < text.Length)
+            {
+                var escaped = text[i + 1];
+                sb.Append(escaped switch
+                {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    _ => escaped
+                });
+                i += 2;
+                continue;
+            }
+
+            sb.Append(text[i]);
+            i++;
+        }
+
+        return (sb.ToString(), Math.Min(i + 1, text.Length));
+    }
+
+    private static (string? Pattern, int Next) ReadPattern(string text, int start)
+    {
+        var i = start + 1;
+        var inClass = false;
+
+        while (i < text.Length)
+        {
+            var c = text[i];
+            if (c == '\\')
+            {
+                i += 2;
+                continue;
+            }
+
+            if (c == '[')
+            {
+                inClass = true;
+            }
+            else if (c == ']')
+            {
+                inClass = false;
+            }
+            else if (c == '/' && !inClass)
+            {
+                return (text.Substring(start + 1, i - start - 1), i + 1);
+            }
+
+            i++;
+        }
+
+        return (null, start);
+    }
+}
+
+/// <summary>
+/// Kinds of symbols in a production alternative.
+/// </summary>
+public enum GrammarSymbolKind
+{
+    /// <summary>
+    /// A reference to another production rule.
+    /// </summary>
+    Rule,
+
+    /// <summary>
+    /// A reference to a named token pattern.
+    /// </summary>
+    Token,
+
+    /// <summary>
+    /// A literal string such as a keyword or punctuation.
+    /// </summary>
+    Literal,
+
+    /// <summary>
+    /// An inline regular expression terminal.
+    /// </summary>
+    Pattern
+}
@@ -0,0 +1,104 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The information shown when the cursor rests on a construct.
+/// </summary>
+/// <param name="Location">The span of the construct described.</param>
+/// <param name="Contents">The description as Markdown.</param>
+public sealed record Hover(SourcePosition Location, string Contents);
+
+/// <summary>
+/// Describes the construct under the cursor from its grammar: the innermost node at the cursor whose rule has a
+/// <c>// @description</c> annotation or whose rule or alternative is deprecated is shown with its description and
+/// its deprecation, including the replacement the deprecation suggests.
+/// </summary>
+public static class HoverProvider
+{
+    /// <summary>
+    /// Gets the hover at an offset.
+    /// </summary>
+    /// <param name="parse">The parse of the file.</param>
+    /// <param name="offset">The cursor offset.</param>
+    /// <returns>The hover, or null if no construct at the offset is documented or deprecated.</returns>
+    public static Hover? GetHover(ParseResult parse, int offset)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+
+        if (parse.Tree == null || parse.Grammar == null)
+        {
+            return null;
+        }
+
+        var path = new List<CognitiveGraphNode>();
+        for (CognitiveGraphNode? node = parse.Tree; node != null; node = node.Children.FirstOrDefault(c => Spans(c, offset)))
+        {
+            path.Add(node);
+        }
+
+        for (var i = path.Count - 1; i >= 0; i--)
+        {
+            if (path[i] is not NonTerminalNode { SourcePosition: { } location } nonTerminal || parse.Grammar.GetRule(nonTerminal.RuleName) is not { } rule)
+            {
+                continue;
+            }
+
+            var description = parse.Grammar.Docs.Get(rule.Name)?.Description;
+            var alternative = nonTerminal.ProductionIndex >= 0 && nonTerminal.ProductionIndex < rule.Alternatives.Count
+                ? rule.Alternatives[nonTerminal.ProductionIndex]
+                : null;
+            if (description == null && alternative?.Deprecation == null)
+            {
+                continue;
+            }
+
+            var contents = new StringBuilder($"`<{rule.Name}>`");
+            if (description != null)
+            {
+                contents.Append("\n\n").Append(description);
+            }
+
+            if (alternative?.Deprecation is { } deprecation)
+            {
+                contents.Append("\n\n**").Append(deprecation).Append("**");
+                if (!deprecation.AppliesToRule)
+                {
+                    contents.Append(" (`").Append(alternative.Text).Append("`)");
+                }
+
+                if (deprecation.ReplaceWith != null)
+                {
+                    contents.Append("\n\nReplace with `").Append(deprecation.ReplaceWith).Append('`');
+                }
+            }
+
+            return new Hover(location, contents.ToString());
+        }
+
+        return null;
+    }
+
+    private static bool Spans(CognitiveGraphNode node, int offset)
+    {
+        return node.SourcePosition is { } position && offset >= position.Offset && offset < position.Offset + Math.Max(position.Length, 1);
+    }
+}
@@ -0,0 +1,598 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Keeps a document parsed across edits. Each edit re-lexes only the damaged region until the
+/// token stream resynchronizes, and tree nodes whose tokens lie outside that region are carried
+/// over from the previous parse instead of being rebuilt. Reused nodes keep their ids, and each
+/// result's <see cref="ParseResult.NodeIdMap"/> maps the ids of rebuilt nodes to their replacements.
+/// Reused nodes are detached from the previous tree, so trees of earlier results should not be used
+/// after an edit.
+/// </summary>
+public class IncrementalParser
+{
+    private readonly GeneralizedParser _parser;
+    private readonly ParseOptions _options;
+    private static readonly (string Open, string Close)[] Brackets = { ("\"(\"", "\")\""), ("\"[\"", "\"]\""), ("\"{\"", "\"}\"") };
+
+    private readonly List<ReparseStats> _history = new();
+    private List<Token> _tokens = new();
+    private List<Diagnostic> _lexDiagnostics = new();
+
+    /// <summary>
+    /// Initializes a new instance of the IncrementalParser class.
+    /// </summary>
+    /// <param name="parser">The parser used for each reparse.</param>
+    /// <param name="text">The initial document text.</param>
+    /// <param name="options">Optional parse options.</param>
+    public IncrementalParser(GeneralizedParser parser, string text, ParseOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+        ArgumentNullException.ThrowIfNull(text);
+
+        _parser = parser;
+        _options = options ?? new ParseOptions();
+
+        var lexResult = parser.Lexer.Tokenize(text, 0, _options.Interner);
+        _tokens = lexResult.Tokens.ToList();
+        _lexDiagnostics = lexResult.Diagnostics.ToList();
+        Current = Reparse(text, new ParseTreeBuilder(_tokens, new LineIndex(text), _options.SourceFile));
+    }
+
+    /// <summary>
+    /// Initializes a new instance of the IncrementalParser class from text held in memory. Memory over a whole
+    /// string becomes the document text without a copy; other memory is copied once.
+    /// </summary>
+    /// <param name="parser">The parser used for each reparse.</param>
+    /// <param name="text">The initial document text.</param>
+    /// <param name="options">Optional parse options.</param>
+    public IncrementalParser(GeneralizedParser parser, ReadOnlyMemory<char> text, ParseOptions? options = null)
+        : this(parser, GeneralizedParser.ShareOrCopy(text), options)
+    {
+    }
+
+    /// <summary>
+    /// Gets the current document text.
+    /// </summary>
+    public string Text => Current.Input;
+
+    /// <summary>
+    /// Gets the result of the most recent parse.
+    /// </summary>
+    public ParseResult Current { get; private set; }
+
+    /// <summary>
+    /// Gets the statistics of every reparse in edit order.
+    /// </summary>
+    public IReadOnlyList<ReparseStats> History => _history;
+
+    /// <summary>
+    /// Gets the statistics of the most recent reparse, or null before the first edit.
+    /// </summary>
+    public ReparseStats? LastStats => _history.Count > 0 ? _history[^1] : null;
+
+    /// <summary>
+    /// Applies an edit and reparses the document.
+    /// </summary>
+    /// <param name="edit">The edit to apply.</param>
+    /// <returns>The result of the reparse.</returns>
+    public ParseResult ApplyEdit(TextEdit edit)
+    {
+        ArgumentNullException.ThrowIfNull(edit);
+
+        var oldText = Text;
+        var newText = edit.Apply(oldText);
+        var lineIndex = new LineIndex(newText);
+
+        var lexWatch = Stopwatch.StartNew();
+        var relex = Relex(newText, edit, lineIndex);
+        lexWatch.Stop();
+
+        var previous = NodeIdMap.Snapshot(Current.Tree);
+        var parseWatch = Stopwatch.StartNew();
+        var reusable = CollectReusableNodes(Current, relex);
+        // Token filters merge and insert tokens, shifting token indices between parses, so subtrees cannot be matched up.
+        var treeBuilder = _parser.TokenFilters.Count == 0
+            ? new ParseTreeBuilder(_tokens, lineIndex, _options.SourceFile, node => TryReuse(node, relex, reusable, lineIndex, edit.Delta))
+            : new ParseTreeBuilder(_tokens, lineIndex, _options.SourceFile);
+        Current = Reparse(newText, treeBuilder);
+        parseWatch.Stop();
+
+        Current.NodeIdMap = NodeIdMap.Build(previous, Current.Tree, edit);
+
+        _history.Add(new ReparseStats
+        {
+            Version = _history.Count + 1,
+            Edit = edit,
+            DamagedRange = new TextRange(edit.Offset, edit.NewText.Length),
+            RelexedRange = new TextRange(relex.StartOffset, relex.EndOffset - relex.StartOffset),
+            RelexedTokenCount = relex.NewTokenCount,
+            TokenCount = _tokens.Count,
+            ReusedNodeCount = treeBuilder.ReusedNodeCount,
+            RebuiltNodeCount = treeBuilder.CreatedNodeCount,
+            DocumentLength = newText.Length,
+            IsSuccess = Current.IsSuccess,
+            LexTime = lexWatch.Elapsed,
+            ParseTime = parseWatch.Elapsed
+        });
+
+        return Current;
+    }
+
+    /// <summary>
+    /// Replaces the text of one node and reparses only that node against its rule, for editors that know an edit
+    /// is confined to it, such as a change inside one function body. The new text is lexed in place and must
+    /// begin and end with a token, leave the tokens around it as they were and parse as exactly one node of the
+    /// node's rule; the new node then takes the old one's place and the nodes after it move with the text. When
+    /// the edit could reach beyond the node, such as with unbalanced brackets, or the new text does not parse on
+    /// its own, the edit is applied with <see cref="ApplyEdit"/> instead and the result says why. A local reparse
+    /// updates the current tree in place and gives a result without a parse forest.
+    /// </summary>
+    /// <param name="nodeId">The id of a rule node of the current tree.</param>
+    /// <param name="newText">The replacement for the node's text.</param>
+    /// <returns>The result of the reparse and whether only the node was reparsed.</returns>
+    /// <exception cref="ArgumentException">The current tree has no rule node with the id.</exception>
+    public NodeReparseResult ReparseNode(Guid nodeId, string newText)
+    {
+        ArgumentNullException.ThrowIfNull(newText);
+
+        if (Find(Current.Tree, nodeId) is not NonTerminalNode { SourcePosition: { } position } node)
+        {
+            throw new ArgumentException($"Node {nodeId} is not a rule node of the current tree", nameof(nodeId));
+        }
+
+        var edit = new TextEdit(position.Offset, position.Length, newText);
+        var local = TryReparseNode(node, edit, out var reason);
+        return local != null
+            ? new NodeReparseResult(local, true, null)
+            : new NodeReparseResult(ApplyEdit(edit), false, reason);
+    }
+
+    private ParseResult? TryReparseNode(NonTerminalNode node, TextEdit edit, out string? reason)
+    {
+        reason = !Current.IsSuccess ? "the document has errors"
+            : node.Parent == null ? "the node is the root of the tree"
+            : edit.Length == 0 ? "the node is empty"
+            : _parser.TokenFilters.Count > 0 ? "token filters rewrite the token stream"
+            : _parser.Grammar.IsScannerless ? "the grammar is scannerless"
+            : _parser.Lexer.CreateDelimiterState() != null ? "the grammar captures delimiters"
+            : Current.Operators != null ? "operator layers are resolved over the whole tree"
+            : null;
+        if (reason != null)
+        {
+            return null;
+        }
+
+        var newText = edit.Apply(Text);
+        var lineIndex = new LineIndex(newText);
+        var nodeEnd = edit.Offset + edit.NewText.Length;
+
+        // Lexing starts at the token before the node, which must come out the same, and stops at the first token
+        // after it, which must be the old one moved with the text.
+        var lexWatch = Stopwatch.StartNew();
+        var first = FirstTokenAtOrAfter(_tokens, edit.Offset);
+        var end = FirstTokenAtOrAfter(_tokens, edit.Offset + edit.Length);
+        var startOffset = first > 0 ? _tokens[first - 1].Offset : 0;
+        var position = startOffset;
+        var lexDiagnostics = new List<Diagnostic>();
+        var relexed = new List<Token>();
+        Token? next = null;
+        var previous = first > 1 ? _tokens[first - 2] : null;
+        while (_parser.Lexer.NextToken(newText, ref position, lexDiagnostics, lineIndex, previous: previous, interner: _options.Interner) is { } token)
+        {
+            if (token.Offset >= nodeEnd)
+            {
+                next = token;
+                break;
+            }
+
+            relexed.Add(token);
+            previous = token;
+        }
+
+        lexWatch.Stop();
+
+        var expectedNext = end < _tokens.Count ? _tokens[end] with { Offset = _tokens[end].Offset + edit.Delta } : null;
+        var tokens = first > 0 && relexed.Count > 0 && relexed[0] == _tokens[first - 1] ? relexed.Skip(1).ToList() : relexed;
+        reason = lexDiagnostics.Count > 0 ? "the new text does not lex"
+            : first > 0 && tokens == relexed ? "the new text runs into the token before the node"
+            : tokens.Count == 0 || tokens[0].Offset != edit.Offset || tokens[^1].End != nodeEnd ? "the new text does not begin and end with a token"
+            : next != expectedNext ? "the new text runs into the tokens after the node"
+            : !IsBalanced(tokens) ? "the new text has unbalanced brackets"
+            : null;
+        if (reason != null)
+        {
+            return null;
+        }
+
+        var parseWatch = Stopwatch.StartNew();
+        var options = new ParseOptions
+        {
+            StartRule = node.RuleName,
+            End = EntryPointEnd.Complete,
+            MaxAmbiguitiesPerNode = _options.MaxAmbiguitiesPerNode,
+            SourceFile = _options.SourceFile,
+            Watchdog = _options.Watchdog,
+            KeywordCorrectionDistance = _options.KeywordCorrectionDistance,
+            Features = _options.Features
+        };
+        var treeBuilder = new ParseTreeBuilder(tokens, lineIndex, _options.SourceFile);
+        var local = _parser.Parse(newText, tokens, Array.Empty<Diagnostic>(), options, treeBuilder);
+        if (!local.IsSuccess || local.Tokens.Count != tokens.Count)
+        {
+            parseWatch.Stop();
+            reason = $"the new text is not a valid <{node.RuleName}>";
+            return null;
+        }
+
+        var previous = NodeIdMap.Snapshot(Current.Tree);
+        var replacement = local.Tree!;
+        var parent = node.Parent!;
+        var index = 0;
+        while (parent.Children[index] != node)
+        {
+            index++;
+        }
+
+        parent.RemoveChild(node);
+        parent.InsertChild(index, replacement);
+
+        // Every node after the replacement is a later sibling of it or of one of its ancestors, and every
+        // ancestor grows or shrinks with it.
+        var child = replacement;
+        for (CognitiveGraphNode? ancestor = parent; ancestor != null; child = ancestor, ancestor = ancestor.Parent)
+        {
+            var after = false;
+            foreach (var sibling in ancestor.Children)
+            {
+                if (after)
+                {
+                    Shift(sibling, lineIndex, edit.Delta);
+                }
+
+                after |= sibling == child;
+            }
+
+            var span = ancestor.SourcePosition!;
+            ancestor.SourcePosition = lineIndex.GetPosition(span.Offset, span.Length + edit.Delta, span.SourceFile);
+        }
+
+        parseWatch.Stop();
+
+        var spliced = new List<Token>(_tokens.Count - (end - first) + tokens.Count);
+        spliced.AddRange(_tokens.Take(first));
+        spliced.AddRange(tokens);
+        spliced.AddRange(_tokens.Skip(end).Select(t => t with { Offset = t.Offset + edit.Delta }));
+        _tokens = spliced;
+        _lexDiagnostics = Move(_lexDiagnostics, edit, lineIndex);
+
+        var tree = Current.Tree!;
+        Current = new ParseResult
+        {
+            Input = newText,
+            Grammar = Current.Grammar,
+            StartRule = Current.StartRule,
+            Tokens = _tokens,
+            Tree = tree,
+            Diagnostics = Move(Current.Diagnostics, edit, lineIndex).Concat(local.Diagnostics).OrderBy(d => d.Location?.Offset ?? 0).ToList(),
+            ParsedLength = Current.ParsedLength + edit.Delta
+        };
+        Current.NodeIdMap = NodeIdMap.Build(previous, tree, edit);
+
+        _history.Add(new ReparseStats
+        {
+            Version = _history.Count + 1,
+            Edit = edit,
+            DamagedRange = new TextRange(edit.Offset, edit.NewText.Length),
+            RelexedRange = new TextRange(startOffset, (next?.End ?? newText.Length) - startOffset),
+            RelexedTokenCount = tokens.Count,
+            TokenCount = _tokens.Count,
+            ReusedNodeCount = previous.Count - Count(node),
+            RebuiltNodeCount = treeBuilder.CreatedNodeCount,
+            DocumentLength = newText.Length,
+            IsSuccess = true,
+            ReparsedRule = node.RuleName,
+            LexTime = lexWatch.Elapsed,
+            ParseTime = parseWatch.Elapsed
+        });
+
+        return Current;
+    }
+
+    private ParseResult Reparse(string text, ParseTreeBuilder treeBuilder)
+    {
+        return _parser.Parse(text, _tokens, _lexDiagnostics, _options, treeBuilder);
+    }
+
+    /// <summary>
+    /// Re-lexes from the end of the last token before the edit until a new token lines up with an
+    /// old token after the edit, then splices the new tokens into the token list.
+    /// </summary>
+    private RelexRegion Relex(string newText, TextEdit edit, LineIndex lineIndex)
+    {
+        var oldTokens = _tokens;
+        var editEnd = edit.Offset + edit.Length;
+
+        // Captured delimiters depend on everything before them, so grammars that declare them are re-lexed
+        // from the start to the end. Otherwise tokens that end before the edit are unaffected; a token ending
+        // exactly at the edit may merge with it.
+        var delimiters = _parser.Lexer.CreateDelimiterState();
+        var first = 0;
+        while (delimiters == null && first < oldTokens.Count && oldTokens[first].End < edit.Offset)
+        {
+            first++;
+        }
+
+        var startOffset = first > 0 ? oldTokens[first - 1].End : 0;
+        var position = startOffset;
+        var relexed = new List<Token>();
+        var diagnostics = new List<Diagnostic>();
+        var resync = oldTokens.Count;
+        var candidate = first;
+        var previous = first > 0 ? oldTokens[first - 1] : null;
+
+        while (_parser.Lexer.NextToken(newText, ref position, diagnostics, lineIndex, delimiters, previous, _options.Interner) is { } token)
+        {
+            previous = token;
+            if (delimiters == null && token.Offset >= edit.Offset + edit.NewText.Length)
+            {
+                var oldOffset = token.Offset - edit.Delta;
+                while (candidate < oldTokens.Count && oldTokens[candidate].Offset < oldOffset)
+                {
+                    candidate++;
+                }
+
+                if (candidate < oldTokens.Count && oldTokens[candidate].Offset >= editEnd &&
+                    oldTokens[candidate].Offset == oldOffset && oldTokens[candidate] with { Offset = token.Offset } == token)
+                {
+                    resync = candidate;
+                    position = token.Offset;
+                    break;
+                }
+            }
+
+            relexed.Add(token);
+        }
+
+        var endOffset = resync < oldTokens.Count ? position : newText.Length;
+
+        var tokens = new List<Token>(oldTokens.Count - (resync - first) + relexed.Count);
+        tokens.AddRange(oldTokens.Take(first));
+        tokens.AddRange(relexed);
+        tokens.AddRange(oldTokens.Skip(resync).Select(t => t with { Offset = t.Offset + edit.Delta }));
+        _tokens = tokens;
+
+        // Lexical diagnostics outside the re-lexed region are kept and moved with the text.
+        var lexDiagnostics = new List<Diagnostic>();
+        foreach (var diagnostic in _lexDiagnostics)
+        {
+            var location = diagnostic.Location!;
+            if (location.Offset < startOffset)
+            {
+                lexDiagnostics.Add(diagnostic);
+            }
+            else if (location.Offset >= endOffset - edit.Delta)
+            {
+                lexDiagnostics.Add(Shift(diagnostic, lineIndex, edit.Delta));
+            }
+        }
+
+        lexDiagnostics.AddRange(diagnostics);
+        _lexDiagnostics = lexDiagnostics.OrderBy(d => d.Location?.Offset ?? 0).ToList();
+
+        return new RelexRegion(first, relexed.Count, resync - first, startOffset, endOffset);
+    }
+
+    private Dictionary<(string Rule, int Production, int Start, int End), NonTerminalNode> CollectReusableNodes(ParseResult previous, RelexRegion relex)
+    {
+        var reusable = new Dictionary<(string Rule, int Production, int Start, int End), NonTerminalNode>();
+        if (previous.Tree == null)
+        {
+            return reusable;
+        }
+
+        var oldTokens = previous.Tokens;
+        var oldResync = relex.FirstToken + relex.RemovedTokenCount;
+        var stack = new Stack<CognitiveGraphNode>();
+        stack.Push(previous.Tree);
+
+        while (stack.Count > 0)
+        {
+            var node = stack.Pop();
+            if (node is not NonTerminalNode nonTerminal || nonTerminal.SourcePosition == null)
+            {
+                continue;
+            }
+
+            var start = FirstTokenAtOrAfter(oldTokens, nonTerminal.SourcePosition.Offset);
+            var end = FirstTokenAtOrAfter(oldTokens, nonTerminal.SourcePosition.Offset + nonTerminal.SourcePosition.Length);
+
+            // Empty nodes at the start of the re-lexed region take their position from a re-lexed token.
+            var before = end < relex.FirstToken || (end == relex.FirstToken && start < end);
+            if (before || start >= oldResync)
+            {
+                // Keyed by the token span the node will have after the edit.
+                var shift = start >= oldResync ? relex.NewTokenCount - relex.RemovedTokenCount : 0;
+                reusable.TryAdd((nonTerminal.RuleName, nonTerminal.ProductionIndex, start + shift, end + shift), nonTerminal);
+                continue;
+            }
+
+            foreach (var child in node.Children)
+            {
+                stack.Push(child);
+            }
+        }
+
+        return reusable;
+    }
+
+    private static CognitiveGraphNode? TryReuse(
+        SymbolForestNode node,
+        RelexRegion relex,
+        Dictionary<(string Rule, int Production, int Start, int End), NonTerminalNode> reusable,
+        LineIndex lineIndex,
+        int delta)
+    {
+        var key = (node.RuleName, node.Packed[0].Alternative.Index, node.Start, node.End);
+        if (!reusable.Remove(key, out var existing))
+        {
+            return null;
+        }
+
+        existing.Parent?.RemoveChild(existing);
+
+        // Nodes after the edit move with the text; their lines may change even when the length does not.
+        if (node.Start >= relex.FirstToken + relex.NewTokenCount)
+        {
+            Shift(existing, lineIndex, delta);
+        }
+
+        return existing;
+    }
+
+    private static void Shift(CognitiveGraphNode node, LineIndex lineIndex, int delta)
+    {
+        if (node.SourcePosition is { } position)
+        {
+            node.SourcePosition = lineIndex.GetPosition(position.Offset + delta, position.Length, position.SourceFile);
+        }
+
+        foreach (var child in node.Children)
+        {
+            Shift(child, lineIndex, delta);
+        }
+    }
+
+    /// <summary>
+    /// Keeps the diagnostics before a replaced span and moves those after it with the text; those inside are dropped.
+    /// </summary>
+    private static List<Diagnostic> Move(IEnumerable<Diagnostic> diagnostics, TextEdit edit, LineIndex lineIndex)
+    {
+        var moved = new List<Diagnostic>();
+        foreach (var diagnostic in diagnostics)
+        {
+            if (diagnostic.Location is not { } location || location.Offset + location.Length <= edit.Offset)
+            {
+                moved.Add(diagnostic);
+            }
+            else if (location.Offset >= edit.Offset + edit.Length)
+            {
+                moved.Add(Shift(diagnostic, lineIndex, edit.Delta));
+            }
+        }
+
+        return moved;
+    }
+
+    private static Diagnostic Shift(Diagnostic diagnostic, LineIndex lineIndex, int delta)
+    {
+        var location = diagnostic.Location!;
+        return new Diagnostic
+        {
+            Code = diagnostic.Code,
+            Severity = diagnostic.Severity,
+            Message = diagnostic.Message,
+            MessageKey = diagnostic.MessageKey,
+            MessageArguments = diagnostic.MessageArguments,
+            Location = lineIndex.GetPosition(location.Offset + delta, location.Length, location.SourceFile),
+            Data = diagnostic.Data
+        };
+    }
+
+    private static bool IsBalanced(IReadOnlyList<Token> tokens)
+    {
+        var open = new Stack<string>();
+        foreach (var token in tokens)
+        {
+            var pair = Array.FindIndex(Brackets, b => b.Open == token.Kind || b.Close == token.Kind);
+            if (pair < 0)
+            {
+                continue;
+            }
+
+            if (Brackets[pair].Open == token.Kind)
+            {
+                open.Push(Brackets[pair].Close);
+            }
+            else if (!open.TryPop(out var close) || close != token.Kind)
+            {
+                return false;
+            }
+        }
+
+        return open.Count == 0;
+    }
+
+    private static CognitiveGraphNode? Find(CognitiveGraphNode? node, Guid id)
+    {
+        if (node == null || node.Id == id)
+        {
+            return node;
+        }
+
+        foreach (var child in node.Children)
+        {
+            if (Find(child, id) is { } found)
+            {
+                return found;
+            }
+        }
+
+        return null;
+    }
+
+    private static int Count(CognitiveGraphNode node)
+    {
+        return 1 + node.Children.Sum(Count);
+    }
+
+    private static int FirstTokenAtOrAfter(IReadOnlyList<Token> tokens, int offset)
+    {
+        var low = 0;
+        var high = tokens.Count;
+        while (low < high)
+        {
+            var mid = (low + high) / 2;
+            if (tokens[mid].Offset < offset)
+            {
+                low = mid + 1;
+            }
+            else
+            {
+                high = mid;
+            }
+        }
+
+        return low;
+    }
+
+    private readonly record struct RelexRegion(int FirstToken, int NewTokenCount, int RemovedTokenCount, int StartOffset, int EndOffset);
+}
+
+/// <summary>
+/// The result of reparsing one node.
+/// </summary>
+/// <param name="Parse">The parse of the document after the edit.</param>
+/// <param name="IsLocal">Whether only the node was reparsed; otherwise the edit was applied as a document edit.</param>
+/// <param name="FallbackReason">Why the node could not be reparsed on its own, or null if it was.</param>
+public sealed record NodeReparseResult(ParseResult Parse, bool IsLocal, string? FallbackReason);
@@ -0,0 +1,281 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Options for <see cref="InputReducer"/>.
+/// </summary>
+public class ReductionOptions
+{
+    /// <summary>
+    /// Gets or sets the maximum number of predicate evaluations. When reached, the smallest failing input found
+    /// so far is returned.
+    /// </summary>
+    public int MaxTests { get; set; } = 10_000;
+
+    /// <summary>
+    /// Gets or sets the receiver of progress reports, sent whenever a smaller failing input is found and when a
+    /// phase starts.
+    /// </summary>
+    public IProgress<ReductionProgress>? Progress { get; set; }
+
+    /// <summary>
+    /// Gets or sets the parse options used when parsing candidates into trees.
+    /// </summary>
+    public ParseOptions? ParseOptions { get; set; }
+}
+
+/// <summary>
+/// Progress of an input reduction.
+/// </summary>
+public class ReductionProgress
+{
+    /// <summary>
+    /// Gets or sets the current phase, such as "tree depth 3" or "tokens".
+    /// </summary>
+    public string Phase { get; set; } = string.Empty;
+
+    /// <summary>
+    /// Gets or sets the number of predicate evaluations so far.
+    /// </summary>
+    public int Tests { get; set; }
+
+    /// <summary>
+    /// Gets or sets the length of the smallest failing input so far.
+    /// </summary>
+    public int Length { get; set; }
+}
+
+/// <summary>
+/// The outcome of an input reduction.
+/// </summary>
+public class ReductionResult
+{
+    /// <summary>
+    /// Gets the smallest failing input found.
+    /// </summary>
+    public string Input { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Gets the length of the original input.
+    /// </summary>
+    public int OriginalLength { get; init; }
+
+    /// <summary>
+    /// Gets the number of predicate evaluations.
+    /// </summary>
+    public int Tests { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether reduction stopped at <see cref="ReductionOptions.MaxTests"/> rather than
+    /// at a minimum.
+    /// </summary>
+    public bool ReachedTestLimit { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether the input parsed, so subtrees could be removed before single tokens.
+    /// </summary>
+    public bool UsedTree { get; init; }
+}
+
+/// <summary>
+/// Shrinks an input while a predicate keeps failing on it, to produce a minimal reproducer for a parser bug or
+/// slowdown. Reduction is hierarchical delta debugging: the input is parsed, and at each depth of the tree
+/// the subtrees are removed in ever smaller groups while the predicate still fails. Afterwards, and for inputs
+/// that do not parse, the same is done over the token stream and finally over runs of whitespace.
+/// </summary>
+public static class InputReducer
+{
+    private static readonly Regex WhitespaceRun = new(@"\s+", RegexOptions.CultureInvariant);
+
+    /// <summary>
+    /// Reduces an input.
+    /// </summary>
+    /// <param name="input">The failing input.</param>
+    /// <param name="grammar">The grammar used to parse and tokenize candidates.</param>
+    /// <param name="isFailing">Returns true when a candidate still shows the failure.</param>
+    /// <param name="options">Optional reduction options.</param>
+    /// <returns>The reduction result.</returns>
+    /// <exception cref="ArgumentException">The predicate does not fail on the original input.</exception>
+    public static ReductionResult Reduce(string input, CompiledGrammar grammar, Func<string, bool> isFailing, ReductionOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+        ArgumentNullException.ThrowIfNull(grammar);
+        ArgumentNullException.ThrowIfNull(isFailing);
+
+        if (!isFailing(input))
+        {
+            throw new ArgumentException("The predicate does not fail on the original input", nameof(isFailing));
+        }
+
+        var run = new Run(input, isFailing, options ?? new ReductionOptions());
+        var parser = new GeneralizedParser(grammar);
+        var usedTree = false;
+
+        for (var depth = 1; !run.LimitReached; depth++)
+        {
+            var tree = parser.Parse(run.Current, run.Options.ParseOptions).Tree;
+            if (tree == null)
+            {
+                break;
+            }
+
+            usedTree = true;
+            var nodes = NodesAtDepth(tree, depth);
+            if (nodes.Count == 0)
+            {
+                break;
+            }
+
+            var spans = nodes
+                .Select(n => n.SourcePosition)
+                .OfType<SourcePosition>()
+                .Where(p => p.Length > 0)
+                .Select(p => new TextRange(p.Offset, p.Length))
+                .Distinct()
+                .ToList();
+
+            run.Minimize($"tree depth {depth}", spans);
+        }
+
+        var tokens = new GrammarLexer(grammar).Tokenize(run.Current).Tokens;
+        run.Minimize("tokens", tokens.Where(t => t.Length > 0).Select(t => new TextRange(t.Offset, t.Length)).ToList());
+
+        // Removing spans leaves the whitespace around them behind; drop whatever whitespace the failure doesn't need.
+        var whitespace = WhitespaceRun.Matches(run.Current).Select(m => new TextRange(m.Index, m.Length)).ToList();
+        run.Minimize("whitespace", whitespace);
+
+        return new ReductionResult
+        {
+            Input = run.Current,
+            OriginalLength = input.Length,
+            Tests = run.Tests,
+            ReachedTestLimit = run.LimitReached,
+            UsedTree = usedTree
+        };
+    }
+
+    private static List<CognitiveGraphNode> NodesAtDepth(CognitiveGraphNode root, int depth)
+    {
+        var level = new List<CognitiveGraphNode> { root };
+        for (var i = 0; i < depth && level.Count > 0; i++)
+        {
+            level = level.SelectMany(n => n.Children).ToList();
+        }
+
+        return level;
+    }
+
+    private sealed class Run
+    {
+        private readonly Func<string, bool> _isFailing;
+
+        public Run(string input, Func<string, bool> isFailing, ReductionOptions options)
+        {
+            Current = input;
+            _isFailing = isFailing;
+            Options = options;
+        }
+
+        public string Current { get; private set; }
+
+        public ReductionOptions Options { get; }
+
+        public int Tests { get; private set; }
+
+        public bool LimitReached => Tests >= Options.MaxTests;
+
+        /// <summary>
+        /// Runs ddmin over removable, non-overlapping spans of the current input: try removing each of n
+        /// groups, keep any removal that still fails, and split into finer groups when none does.
+        /// </summary>
+        public void Minimize(string phase, List<TextRange> spans)
+        {
+            Report(phase);
+
+            var baseline = Current;
+            var kept = spans.OrderBy(s => s.Start).ToList();
+            var groups = 2;
+
+            while (kept.Count > 0 && !LimitReached)
+            {
+                groups = Math.Min(groups, kept.Count);
+                var size = (kept.Count + groups - 1) / groups;
+                var reduced = false;
+
+                for (var start = 0; start < kept.Count && !LimitReached; start += size)
+                {
+                    var remaining = kept.Take(start).Concat(kept.Skip(start + size)).ToList();
+                    var candidate = Remove(baseline, spans, remaining);
+                    Tests++;
+
+                    if (_isFailing(candidate))
+                    {
+                        kept = remaining;
+                        Current = candidate;
+                        groups = Math.Max(groups - 1, 2);
+                        reduced = true;
+                        Report(phase);
+                        break;
+                    }
+                }
+
+                if (!reduced)
+                {
+                    if (groups >= kept.Count)
+                    {
+                        break;
+                    }
+
+                    groups = Math.Min(groups * 2, kept.Count);
+                }
+            }
+        }
+
+        private static string Remove(string baseline, List<TextRange> all, List<TextRange> kept)
+        {
+            // Spans are relative to the baseline; everything not kept has been or is being removed.
+            var keep = new HashSet<TextRange>(kept);
+            var text = new StringBuilder(baseline.Length);
+            var position = 0;
+
+            foreach (var span in all.Where(s => !keep.Contains(s)).OrderBy(s => s.Start))
+            {
+                if (span.Start < position)
+                {
+                    continue;
+                }
+
+                text.Append(baseline, position, span.Start - position);
+                position = span.Start + span.Length;
+            }
+
+            text.Append(baseline, position, baseline.Length - position);
+            return text.ToString();
+        }
+
+        private void Report(string phase)
+        {
+            Options.Progress?.Report(new ReductionProgress { Phase = phase, Tests = Tests, Length = Current.Length });
+        }
+    }
+}
@@ -0,0 +1,334 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration.Models;
+using Minotaur.Projects.Grammar;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// How a rule of one grammar version maps onto the next.
+/// </summary>
+public enum RuleMigrationKind
+{
+    /// <summary>
+    /// The rule is renamed.
+    /// </summary>
+    Rename,
+
+    /// <summary>
+    /// The rule is split into several rules; conditions choose the new rule for each node.
+    /// </summary>
+    Split,
+
+    /// <summary>
+    /// The rule is merged with others into one rule.
+    /// </summary>
+    Merge,
+
+    /// <summary>
+    /// The rule is removed without a counterpart.
+    /// </summary>
+    Remove
+}
+
+/// <summary>
+/// A rule an old rule maps to.
+/// </summary>
+/// <param name="Rule">The new rule name.</param>
+/// <param name="When">For splits, the condition choosing this rule: a literal is the text of the node's first
+/// terminal and a rule reference is the rule of one of its children, by its name before the step. Null if the
+/// target applies unconditionally.</param>
+public sealed record MigrationTarget(string Rule, GrammarSymbol? When);
+
+/// <summary>
+/// The mapping of one old rule in a <see cref="MigrationStep"/>.
+/// </summary>
+/// <param name="Kind">The kind of mapping.</param>
+/// <param name="Rule">The old rule name.</param>
+/// <param name="Targets">The new rules; empty for <see cref="RuleMigrationKind.Remove"/>.</param>
+public sealed record RuleMigration(RuleMigrationKind Kind, string Rule, IReadOnlyList<MigrationTarget> Targets);
+
+/// <summary>
+/// The rule mappings from one grammar version to the next.
+/// </summary>
+public sealed class MigrationStep
+{
+    internal MigrationStep(GrammarVersion from, GrammarVersion to)
+    {
+        From = from;
+        To = to;
+    }
+
+    /// <summary>
+    /// Gets the version the step migrates from.
+    /// </summary>
+    public GrammarVersion From { get; }
+
+    /// <summary>
+    /// Gets the version the step migrates to.
+    /// </summary>
+    public GrammarVersion To { get; }
+
+    /// <summary>
+    /// Gets the mappings by old rule name. Rules without a mapping keep their name.
+    /// </summary>
+    public IReadOnlyDictionary<string, RuleMigration> Rules => RuleMap;
+
+    internal Dictionary<string, RuleMigration> RuleMap { get; } = new(StringComparer.Ordinal);
+}
+
+/// <summary>
+/// Declares how the rules of a grammar change between versions, so trees parsed with an old version can be
+/// given the node kinds of a new one. See <see cref="TreeMigrator"/>.
+/// </summary>
+/// <remarks>
+/// A manifest is a text file, by convention next to the grammar with the <see cref="FileExtension"/> extension,
+/// holding one <c>migration &lt;from&gt; -&gt; &lt;to&gt;</c> section per version step, each followed by clauses:
+/// <code>
+/// migration 1.0 -> 2.0
+/// rename &lt;expr_stmt&gt; -&gt; &lt;expression_statement&gt;
+/// split &lt;decl&gt; -&gt; &lt;var_decl&gt; when "var" | &lt;func_decl&gt; when "fn"
+/// merge &lt;if_stmt&gt; &lt;unless_stmt&gt; -&gt; &lt;conditional&gt;
+/// remove &lt;pragma&gt;
+/// </code>
+/// Lines starting with <c>//</c> are comments.
+/// </remarks>
+public sealed class MigrationManifest
+{
+    /// <summary>
+    /// The file extension of migration manifests.
+    /// </summary>
+    public const string FileExtension = ".migrations";
+
+    private readonly List<MigrationStep> _steps;
+
+    private MigrationManifest(List<MigrationStep> steps)
+    {
+        _steps = steps;
+    }
+
+    /// <summary>
+    /// Gets the version steps in declaration order.
+    /// </summary>
+    public IReadOnlyList<MigrationStep> Steps => _steps;
+
+    /// <summary>
+    /// Gets the conventional manifest path for a grammar file.
+    /// </summary>
+    /// <param name="grammarFile">The grammar file path.</param>
+    /// <returns>The grammar path with the <see cref="FileExtension"/> extension.</returns>
+    public static string GetPath(string grammarFile)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(grammarFile);
+
+        return Path.ChangeExtension(grammarFile, FileExtension);
+    }
+
+    /// <summary>
+    /// Loads a manifest file.
+    /// </summary>
+    /// <param name="path">The manifest path.</param>
+    /// <returns>The manifest.</returns>
+    /// <exception cref="FormatException">The manifest is malformed.</exception>
+    public static MigrationManifest Load(string path)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(path);
+
+        return Parse(File.ReadAllText(path));
+    }
+
+    /// <summary>
+    /// Parses manifest text.
+    /// </summary>
+    /// <param name="text">The manifest text.</param>
+    /// <returns>The manifest.</returns>
+    /// <exception cref="FormatException">A line is not a section or clause, a clause comes before the first
+    /// section, or a rule is mapped twice in one step.</exception>
+    public static MigrationManifest Parse(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var steps = new List<MigrationStep>();
+        var lines = text.Split('\n');
+        for (var i = 0; i < lines.Length; i++)
+        {
+            var line = lines[i].Trim();
+            if (line.Length == 0 || line.StartsWith("//"))
+            {
+                continue;
+            }
+
+            var keyword = line.Split(' ', 2)[0];
+            var rest = line[keyword.Length..].Trim();
+            var arrow = rest.IndexOf("->", StringComparison.Ordinal);
+            var left = arrow >= 0 ? rest[..arrow].Trim() : rest;
+            var right = arrow >= 0 ? rest[(arrow + 2)..].Trim() : string.Empty;
+
+            if (keyword == "migration")
+            {
+                if (arrow < 0 || !GrammarVersion.TryParse(left, out var from) || !GrammarVersion.TryParse(right, out var to))
+                {
+                    throw Error(i, "a migration section is written as 'migration <from> -> <to>'");
+                }
+
+                steps.Add(new MigrationStep(from!, to!));
+                continue;
+            }
+
+            if (steps.Count == 0)
+            {
+                throw Error(i, $"'{keyword}' comes before the first migration section");
+            }
+
+            var migrations = keyword switch
+            {
+                "rename" when arrow >= 0 => new[] { new RuleMigration(RuleMigrationKind.Rename, RuleName(left, i), new[] { new MigrationTarget(RuleName(right, i), null) }) },
+                "merge" when arrow >= 0 => RuleNames(left, i)
+                    .Select(rule => new RuleMigration(RuleMigrationKind.Merge, rule, new[] { new MigrationTarget(RuleName(right, i), null) }))
+                    .ToArray(),
+                "split" when arrow >= 0 => new[] { new RuleMigration(RuleMigrationKind.Split, RuleName(left, i), right.Split('|').Select(t => Target(t, i)).ToList()) },
+                "remove" when arrow < 0 => new[] { new RuleMigration(RuleMigrationKind.Remove, RuleName(left, i), Array.Empty<MigrationTarget>()) },
+                _ => throw Error(i, $"'{line}' is not a rename, split, merge or remove clause")
+            };
+
+            foreach (var migration in migrations)
+            {
+                if (!steps[^1].RuleMap.TryAdd(migration.Rule, migration))
+                {
+                    throw Error(i, $"rule <{migration.Rule}> is mapped more than once from version {steps[^1].From}");
+                }
+            }
+        }
+
+        return new MigrationManifest(steps);
+    }
+
+    /// <summary>
+    /// Gets the chain of steps leading from one version to another.
+    /// </summary>
+    /// <param name="from">The version to migrate from.</param>
+    /// <param name="to">The version to migrate to.</param>
+    /// <returns>The steps in the order they apply; empty if the versions are equal.</returns>
+    /// <exception cref="ArgumentException">No chain of steps leads from the version to the other.</exception>
+    public IReadOnlyList<MigrationStep> GetSteps(GrammarVersion from, GrammarVersion to)
+    {
+        ArgumentNullException.ThrowIfNull(from);
+        ArgumentNullException.ThrowIfNull(to);
+
+        var chain = new List<MigrationStep>();
+        var version = from;
+        while (version != to)
+        {
+            var step = _steps.FirstOrDefault(s => s.From == version && !chain.Contains(s))
+                ?? throw new ArgumentException($"No migration leads from version {version} towards {to}", nameof(to));
+            chain.Add(step);
+            version = step.To;
+        }
+
+        return chain;
+    }
+
+    /// <summary>
+    /// Checks that the manifest covers every rule of the old grammar that the new grammar no longer has, and that
+    /// every rule it maps to exists in the new grammar.
+    /// </summary>
+    /// <param name="from">The old grammar; its <see cref="Grammar.Version"/> is the version migrated from.</param>
+    /// <param name="to">The new grammar; its <see cref="Grammar.Version"/> is the version migrated to.</param>
+    /// <returns>An <see cref="DiagnosticCodes.UnmappedRule"/> warning for every rule that disappeared without a
+    /// mapping and an <see cref="DiagnosticCodes.InvalidMigration"/> error for every mapping to an undefined rule.</returns>
+    /// <exception cref="ArgumentException">No chain of steps leads from the old version to the new one.</exception>
+    public IReadOnlyList<Diagnostic> Check(Grammar from, Grammar to)
+    {
+        ArgumentNullException.ThrowIfNull(from);
+        ArgumentNullException.ThrowIfNull(to);
+
+        var fromVersion = GrammarVersion.Parse(from.Version);
+        var toVersion = GrammarVersion.Parse(to.Version);
+        var steps = GetSteps(fromVersion, toVersion);
+        var newRules = to.ProductionRules.Rules.Select(r => r.Name).ToHashSet(StringComparer.Ordinal);
+        var diagnostics = new List<Diagnostic>();
+
+        foreach (var rule in from.ProductionRules.Rules.Select(r => r.Name))
+        {
+            IEnumerable<string> names = new[] { rule };
+            foreach (var step in steps)
+            {
+                names = names
+                    .SelectMany(name => step.Rules.TryGetValue(name, out var migration) ? migration.Targets.Select(t => t.Rule) : new[] { name })
+                    .ToList();
+            }
+
+            foreach (var name in names.Distinct().Where(name => !newRules.Contains(name)))
+            {
+                var diagnostic = name == rule
+                    ? new Diagnostic
+                    {
+                        Code = DiagnosticCodes.UnmappedRule,
+                        Severity = DiagnosticSeverity.Warning
+                    }.WithMessage(DiagnosticCodes.UnmappedRule, ("rule", rule), ("from", fromVersion.ToString()), ("to", toVersion.ToString()))
+                    : new Diagnostic
+                    {
+                        Code = DiagnosticCodes.InvalidMigration
+                    }.WithMessage(DiagnosticCodes.InvalidMigration, ("rule", rule), ("from", fromVersion.ToString()), ("target", name), ("to", toVersion.ToString()));
+                diagnostic.Data["rule"] = rule;
+                diagnostics.Add(diagnostic);
+            }
+        }
+
+        return diagnostics;
+    }
+
+    private static MigrationTarget Target(string text, int line)
+    {
+        var parts = text.Split(" when ", 2, StringSplitOptions.TrimEntries);
+        if (parts.Length == 1)
+        {
+            return new MigrationTarget(RuleName(parts[0], line), null);
+        }
+
+        var condition = GrammarSymbol.ParseAlternative(parts[1], new HashSet<string>());
+        if (condition.Count != 1 || condition[0].Kind == GrammarSymbolKind.Pattern)
+        {
+            throw Error(line, $"split condition '{parts[1]}' must be a literal or a rule reference");
+        }
+
+        return new MigrationTarget(RuleName(parts[0], line), condition[0]);
+    }
+
+    private static IEnumerable<string> RuleNames(string text, int line)
+    {
+        return text.Split(' ', StringSplitOptions.RemoveEmptyEntries).Select(name => RuleName(name, line));
+    }
+
+    private static string RuleName(string text, int line)
+    {
+        var name = text.Trim().TrimStart('<').TrimEnd('>');
+        if (name.Length == 0 || name.Any(c => !(char.IsLetterOrDigit(c) || c == '_' || c == '-')))
+        {
+            throw Error(line, $"'{text.Trim()}' is not a rule name");
+        }
+
+        return name;
+    }
+
+    private static FormatException Error(int line, string message)
+    {
+        return new FormatException($"Migration manifest line {line + 1}: {message}");
+    }
+}
@@ -0,0 +1,226 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Relates the node ids of a parse tree to the ids of the tree produced by the following incremental reparse.
+/// Nodes reused by the reparse keep their id. Nodes that were rebuilt are mapped to the new node of the same
+/// rule (or token kind) whose span overlaps theirs, so annotations keyed by id can follow the text.
+/// </summary>
+/// <remarks>
+/// Node ids are <see cref="Guid"/> values assigned once per node, so an id never names two different nodes
+/// within a document session; a replaced node's id is retired rather than handed to its replacement.
+/// </remarks>
+public sealed class NodeIdMap
+{
+    private readonly Dictionary<Guid, Guid> _replaced;
+    private readonly HashSet<Guid> _retained;
+    private readonly HashSet<Guid> _removed;
+
+    private NodeIdMap(Dictionary<Guid, Guid> replaced, HashSet<Guid> retained, HashSet<Guid> removed)
+    {
+        _replaced = replaced;
+        _retained = retained;
+        _removed = removed;
+    }
+
+    /// <summary>
+    /// Gets a map for a parse without a predecessor.
+    /// </summary>
+    public static NodeIdMap Empty { get; } = new(new(), new(), new());
+
+    /// <summary>
+    /// Gets the ids of rebuilt nodes mapped to the ids of their replacements.
+    /// </summary>
+    public IReadOnlyDictionary<Guid, Guid> Replaced => _replaced;
+
+    /// <summary>
+    /// Gets the ids of nodes carried over unchanged into the new tree.
+    /// </summary>
+    public IReadOnlySet<Guid> Retained => _retained;
+
+    /// <summary>
+    /// Gets the ids of nodes that no longer exist and have no replacement.
+    /// </summary>
+    public IReadOnlySet<Guid> Removed => _removed;
+
+    /// <summary>
+    /// Maps the id of a node of the previous tree to the id of the corresponding node of the new tree.
+    /// </summary>
+    /// <param name="oldId">The id in the previous tree.</param>
+    /// <returns>The same id if the node was reused, the replacement's id, or null if the node was removed or unknown.</returns>
+    public Guid? Map(Guid oldId)
+    {
+        if (_retained.Contains(oldId))
+        {
+            return oldId;
+        }
+
+        return _replaced.TryGetValue(oldId, out var newId) ? newId : null;
+    }
+
+    /// <summary>
+    /// Builds the map between a previous tree and the tree reparsed after an edit.
+    /// </summary>
+    /// <param name="previous">The nodes of the previous tree with their spans before the edit, in preorder.</param>
+    /// <param name="current">The new tree, or null if the reparse failed.</param>
+    /// <param name="edit">The edit between the two trees.</param>
+    /// <returns>The node id map.</returns>
+    internal static NodeIdMap Build(IReadOnlyList<(CognitiveGraphNode Node, TextRange Span)> previous, CognitiveGraphNode? current, TextEdit edit)
+    {
+        var replaced = new Dictionary<Guid, Guid>();
+        var retained = new HashSet<Guid>();
+        var removed = new HashSet<Guid>();
+
+        var currentNodes = current != null ? Preorder(current).ToList() : new List<CognitiveGraphNode>();
+        var currentIds = currentNodes.Select(n => n.Id).ToHashSet();
+        var previousIds = previous.Select(p => p.Node.Id).ToHashSet();
+
+        // Only nodes built by the reparse can stand in for nodes that were not reused.
+        var created = new Dictionary<string, List<CognitiveGraphNode>>();
+        foreach (var node in currentNodes.Where(n => !previousIds.Contains(n.Id)))
+        {
+            var kind = Kind(node);
+            if (!created.TryGetValue(kind, out var candidates))
+            {
+                created[kind] = candidates = new List<CognitiveGraphNode>();
+            }
+
+            candidates.Add(node);
+        }
+
+        var claimed = new HashSet<Guid>();
+        foreach (var (node, span) in previous)
+        {
+            if (currentIds.Contains(node.Id))
+            {
+                retained.Add(node.Id);
+                continue;
+            }
+
+            var match = created.TryGetValue(Kind(node), out var candidates)
+                ? BestOverlap(candidates, MapSpan(span, edit), claimed)
+                : null;
+
+            if (match != null)
+            {
+                claimed.Add(match.Id);
+                replaced[node.Id] = match.Id;
+            }
+            else
+            {
+                removed.Add(node.Id);
+            }
+        }
+
+        return new NodeIdMap(replaced, retained, removed);
+    }
+
+    /// <summary>
+    /// Collects the nodes of a tree in preorder with their current spans.
+    /// </summary>
+    /// <param name="tree">The tree, or null.</param>
+    /// <returns>The nodes and spans.</returns>
+    internal static List<(CognitiveGraphNode Node, TextRange Span)> Snapshot(CognitiveGraphNode? tree)
+    {
+        return tree == null
+            ? new List<(CognitiveGraphNode, TextRange)>()
+            : Preorder(tree).Select(n => (n, new TextRange(n.SourcePosition?.Offset ?? 0, n.SourcePosition?.Length ?? 0))).ToList();
+    }
+
+    private static CognitiveGraphNode? BestOverlap(List<CognitiveGraphNode> candidates, TextRange span, HashSet<Guid> claimed)
+    {
+        CognitiveGraphNode? best = null;
+        var bestOverlap = -1;
+        var bestDistance = int.MaxValue;
+
+        foreach (var candidate in candidates)
+        {
+            if (claimed.Contains(candidate.Id) || candidate.SourcePosition is not { } position)
+            {
+                continue;
+            }
+
+            var start = Math.Max(span.Start, position.Offset);
+            var end = Math.Min(span.End, position.Offset + position.Length);
+
+            // Empty spans correspond when they touch; non-empty spans must share at least one character.
+            var overlapping = span.Length == 0 || position.Length == 0 ? start <= end : start < end;
+            if (!overlapping)
+            {
+                continue;
+            }
+
+            var overlap = end - start;
+            var distance = Math.Abs(position.Offset - span.Start);
+            if (overlap > bestOverlap || (overlap == bestOverlap && distance < bestDistance))
+            {
+                best = candidate;
+                bestOverlap = overlap;
+                bestDistance = distance;
+            }
+        }
+
+        return best;
+    }
+
+    private static TextRange MapSpan(TextRange span, TextEdit edit)
+    {
+        var start = MapOffset(span.Start, edit, edit.Offset);
+        var end = MapOffset(span.End, edit, edit.Offset + edit.NewText.Length);
+        return new TextRange(start, Math.Max(0, end - start));
+    }
+
+    // Offsets inside the replaced text collapse onto the inserted text.
+    private static int MapOffset(int offset, TextEdit edit, int inside)
+    {
+        if (offset <= edit.Offset)
+        {
+            return offset;
+        }
+
+        return offset >= edit.Offset + edit.Length ? offset + edit.Delta : inside;
+    }
+
+    private static string Kind(CognitiveGraphNode node)
+    {
+        return node switch
+        {
+            NonTerminalNode nonTerminal => "<" + nonTerminal.RuleName + ">",
+            TerminalNode terminal => terminal.TokenType,
+            _ => node.NodeType
+        };
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Preorder(CognitiveGraphNode root)
+    {
+        var stack = new Stack<CognitiveGraphNode>();
+        stack.Push(root);
+        while (stack.Count > 0)
+        {
+            var node = stack.Pop();
+            yield return node;
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                stack.Push(node.Children[i]);
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The built-in "indentation" <see cref="ITokenFilter"/>: turns indentation into tokens, as Python's and YAML's
+/// offside rule reads it. Before the first token of a line it inserts <see cref="IndentKind"/> when the line is
+/// indented deeper than the enclosing block, one <see cref="DedentKind"/> for each block the line closes, and
+/// <see cref="NewlineKind"/> when the line continues the block it lands in; the end of the input closes every
+/// open block. Grammars reference the inserted tokens as <c>&lt;INDENT&gt;</c>, <c>&lt;DEDENT&gt;</c> and
+/// <c>&lt;NEWLINE&gt;</c>.
+/// </summary>
+/// <remarks>
+/// Line breaks inside brackets do not count, so bracketed constructs can span lines freely. A token listed in
+/// the "IndentAfter" metadata entry opens a block at the column of the token following it on the same line,
+/// which is how YAML's compact <c>- key: value</c> sequence entries line up with the lines after them.
+/// Indentation tokens are synthetic, with zero-width spans: indents at the start of the line they open and
+/// dedents and newlines at the end of the token before them, so rules do not span the layout that follows them.
+/// </remarks>
+public sealed class OffsideRuleFilter : ITokenFilter
+{
+    /// <summary>
+    /// The metadata key listing the terminals that open a block at the token following them.
+    /// </summary>
+    public const string IndentAfterKey = "IndentAfter";
+
+    /// <summary>
+    /// The kind of the token opening an indented block.
+    /// </summary>
+    public const string IndentKind = "INDENT";
+
+    /// <summary>
+    /// The kind of the token closing an indented block.
+    /// </summary>
+    public const string DedentKind = "DEDENT";
+
+    /// <summary>
+    /// The kind of the token separating lines of the same block.
+    /// </summary>
+    public const string NewlineKind = "NEWLINE";
+
+    private static readonly string[] DefaultOpening = { "\"(\"", "\"[\"", "\"{\"" };
+    private static readonly string[] DefaultClosing = { "\")\"", "\"]\"", "\"}\"" };
+
+    /// <summary>
+    /// Initializes a new instance of the OffsideRuleFilter class.
+    /// </summary>
+    /// <param name="indentAfter">The terminal keys that open a block at the token following them on their line.</param>
+    /// <param name="opening">The terminal keys of opening brackets; if null, <c>"("</c>, <c>"["</c> and <c>"{"</c>.</param>
+    /// <param name="closing">The terminal keys of closing brackets; if null, <c>")"</c>, <c>"]"</c> and <c>"}"</c>.</param>
+    public OffsideRuleFilter(IEnumerable<string>? indentAfter = null, IEnumerable<string>? opening = null, IEnumerable<string>? closing = null)
+    {
+        IndentAfter = new HashSet<string>(indentAfter ?? Enumerable.Empty<string>(), StringComparer.Ordinal);
+        Opening = new HashSet<string>(opening ?? DefaultOpening, StringComparer.Ordinal);
+        Closing = new HashSet<string>(closing ?? DefaultClosing, StringComparer.Ordinal);
+    }
+
+    /// <summary>
+    /// Gets the terminal keys that open a block at the token following them on their line.
+    /// </summary>
+    public IReadOnlySet<string> IndentAfter { get; }
+
+    /// <summary>
+    /// Gets the terminal keys of opening brackets.
+    /// </summary>
+    public IReadOnlySet<string> Opening { get; }
+
+    /// <summary>
+    /// Gets the terminal keys of closing brackets.
+    /// </summary>
+    public IReadOnlySet<string> Closing { get; }
+
+    /// <summary>
+    /// Creates the filter described by a grammar's "IndentAfter" metadata.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <returns>The filter.</returns>
+    /// <exception cref="ArgumentException">"IndentAfter" lists a rule.</exception>
+    public static OffsideRuleFilter FromGrammar(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var declaration = grammar.Source.Metadata.GetValueOrDefault(IndentAfterKey) ?? string.Empty;
+        var ruleNames = grammar.Rules.Select(r => r.Name).ToHashSet();
+        var indentAfter = new List<string>();
+        foreach (var symbol in GrammarSymbol.ParseAlternative(declaration, ruleNames))
+        {
+            if (!symbol.IsTerminal)
+            {
+                throw new ArgumentException($"'{IndentAfterKey}' in grammar '{grammar.Name}' lists rule <{symbol.Name}>; only terminals can be listed", nameof(grammar));
+            }
+
+            indentAfter.Add(symbol.Key);
+        }
+
+        return new OffsideRuleFilter(indentAfter);
+    }
+
+    /// <inheritdoc />
+    public IEnumerable<Token> Filter(IEnumerable<Token> tokens, TokenFilterContext context)
+    {
+        ArgumentNullException.ThrowIfNull(tokens);
+        ArgumentNullException.ThrowIfNull(context);
+
+        return Layout(tokens, context);
+    }
+
+    private IEnumerable<Token> Layout(IEnumerable<Token> tokens, TokenFilterContext context)
+    {
+        var levels = new Stack<int>();
+        var depth = 0;
+        var indentNext = false;
+        Token? previous = null;
+
+        foreach (var token in tokens)
+        {
+            if (token.IsSynthetic)
+            {
+                yield return token;
+                continue;
+            }
+
+            var (line, column) = context.LineIndex.GetLineColumn(token.Offset);
+            if (previous == null)
+            {
+                levels.Push(column);
+            }
+            else if (depth == 0 && line > context.LineIndex.GetLineColumn(previous.End).Line)
+            {
+                while (levels.Count > 1 && column < levels.Peek())
+                {
+                    levels.Pop();
+                    yield return Create(DedentKind, previous.End);
+                }
+
+                if (column > levels.Peek())
+                {
+                    levels.Push(column);
+                    yield return Create(IndentKind, token.Offset);
+                }
+                else
+                {
+                    // A line at the block's column continues it; one left of the first line's becomes the outermost level.
+                    levels.Pop();
+                    levels.Push(column);
+                    yield return Create(NewlineKind, previous.End);
+                }
+            }
+            else if (indentNext)
+            {
+                levels.Push(column);
+                yield return Create(IndentKind, token.Offset);
+            }
+
+            yield return token;
+
+            if (Opening.Contains(token.Kind))
+            {
+                depth++;
+            }
+            else if (Closing.Contains(token.Kind) && depth > 0)
+            {
+                depth--;
+            }
+
+            indentNext = depth == 0 && IndentAfter.Contains(token.Kind);
+            previous = token;
+        }
+
+        while (levels.Count > 1)
+        {
+            levels.Pop();
+            yield return Create(DedentKind, previous!.End);
+        }
+    }
+
+    private static Token Create(string kind, int offset)
+    {
+        return new Token(kind, string.Empty, offset) { IsSynthetic = true };
+    }
+}
@@ -0,0 +1,577 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Handles an operator declaration found in the parse tree.
+/// </summary>
+/// <param name="context">The declaration and the table to define operators in.</param>
+public delegate void OperatorDeclarationHandler(OperatorDeclarationContext context);
+
+/// <summary>
+/// Structures operator expressions with an operator table that the parsed file itself can extend, for languages
+/// whose programs declare operators (Haskell's <c>infixl 6 &lt;+&gt;</c>, Prolog's <c>op/3</c>).
+/// </summary>
+/// <remarks>
+/// The grammar describes the operator rule flatly, as any sequence of operands and operator tokens, for example
+/// <c>&lt;expr&gt; ::= &lt;operand&gt; | &lt;OPERATOR&gt; &lt;expr&gt; | &lt;operand&gt; &lt;OPERATOR&gt; &lt;expr&gt; | &lt;operand&gt; &lt;OPERATOR&gt;</c>.
+/// After the tree is built, declaration nodes are handed to their handlers in document order; each defined entry
+/// applies from the end of its declaration. Every node of the operator rule is then flattened into its operands
+/// and operators and rebuilt by precedence climbing, looking operators up in the table as it stood at their
+/// position. Rebuilt operator nodes are nodes of the operator rule with production index -1 and the
+/// <see cref="OperatorEntry"/> that produced them in their "operator" metadata. Parenthesized expressions
+/// belong in the operand rule, so that they are not flattened into the enclosing expression.
+/// </remarks>
+public sealed class OperatorLayer
+{
+    /// <summary>
+    /// The metadata key naming the operator rule.
+    /// </summary>
+    public const string RuleKey = "OperatorRule";
+
+    /// <summary>
+    /// The metadata key naming the token kind of operators, "OPERATOR" if not set.
+    /// </summary>
+    public const string TokenKey = "OperatorToken";
+
+    /// <summary>
+    /// The metadata key listing, comma-separated, the rules handled by <see cref="DeclareFixity"/>.
+    /// </summary>
+    public const string DeclarationRulesKey = "OperatorDeclarationRules";
+
+    /// <summary>
+    /// The metadata key of rebuilt operator nodes holding their <see cref="OperatorEntry"/>.
+    /// </summary>
+    public const string EntryMetadataKey = "operator";
+
+    private readonly Dictionary<string, OperatorDeclarationHandler> _handlers;
+
+    /// <summary>
+    /// Initializes a new instance of the OperatorLayer class.
+    /// </summary>
+    /// <param name="rule">The operator rule.</param>
+    /// <param name="operatorKind">The token kind of operators.</param>
+    /// <param name="table">The operators known before the file declares any. The table is copied for every parse.</param>
+    public OperatorLayer(string rule, string operatorKind = "OPERATOR", OperatorTable? table = null)
+        : this(rule, operatorKind, table ?? new OperatorTable(), new Dictionary<string, OperatorDeclarationHandler>())
+    {
+    }
+
+    private OperatorLayer(string rule, string operatorKind, OperatorTable table, Dictionary<string, OperatorDeclarationHandler> handlers)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(rule);
+        ArgumentException.ThrowIfNullOrEmpty(operatorKind);
+
+        Rule = rule;
+        OperatorKind = operatorKind;
+        Table = table;
+        _handlers = handlers;
+    }
+
+    /// <summary>
+    /// Gets the operator rule.
+    /// </summary>
+    public string Rule { get; }
+
+    /// <summary>
+    /// Gets the token kind of operators.
+    /// </summary>
+    public string OperatorKind { get; }
+
+    /// <summary>
+    /// Gets the operators known before the file declares any.
+    /// </summary>
+    public OperatorTable Table { get; }
+
+    /// <summary>
+    /// Creates the layer described by a grammar's "OperatorRule", "OperatorToken" and
+    /// "OperatorDeclarationRules" metadata.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <returns>The layer, or null if the grammar has no operator rule.</returns>
+    public static OperatorLayer? FromGrammar(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var metadata = grammar.Source.Metadata;
+        var rule = metadata.GetValueOrDefault(RuleKey);
+        if (rule == null)
+        {
+            return null;
+        }
+
+        if (grammar.GetRule(rule) == null)
+        {
+            throw new ArgumentException($"Operator rule '{rule}' is not defined in grammar '{grammar.Name}'", nameof(grammar));
+        }
+
+        var layer = new OperatorLayer(rule, metadata.GetValueOrDefault(TokenKey) ?? "OPERATOR");
+        foreach (var declaration in (metadata.GetValueOrDefault(DeclarationRulesKey) ?? string.Empty)
+            .Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            layer.OnDeclaration(declaration, DeclareFixity);
+        }
+
+        return layer;
+    }
+
+    /// <summary>
+    /// Registers the handler for declarations of a rule.
+    /// </summary>
+    /// <param name="rule">The declaration rule.</param>
+    /// <param name="handler">The handler.</param>
+    /// <returns>This layer.</returns>
+    public OperatorLayer OnDeclaration(string rule, OperatorDeclarationHandler handler)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(rule);
+        ArgumentNullException.ThrowIfNull(handler);
+
+        _handlers[rule] = handler;
+        return this;
+    }
+
+    /// <summary>
+    /// Creates a layer with the same rule and handlers whose table also holds the operators of other files.
+    /// </summary>
+    /// <param name="imports">The operator tables of the imported files.</param>
+    /// <returns>The new layer.</returns>
+    public OperatorLayer WithImports(IEnumerable<OperatorTable> imports)
+    {
+        ArgumentNullException.ThrowIfNull(imports);
+
+        var table = Table.Clone();
+        foreach (var import in imports)
+        {
+            table.Import(import);
+        }
+
+        return new OperatorLayer(Rule, OperatorKind, table, _handlers);
+    }
+
+    /// <summary>
+    /// Handles a fixity declaration: its first fixity keyword terminal (see <see cref="OperatorTable.TryParseFixity"/>),
+    /// its first integer terminal as the precedence (9 if there is none) and every operator token in it.
+    /// </summary>
+    /// <param name="context">The declaration context.</param>
+    public static void DeclareFixity(OperatorDeclarationContext context)
+    {
+        ArgumentNullException.ThrowIfNull(context);
+
+        OperatorFixity? fixity = null;
+        int? precedence = null;
+        var symbols = new List<string>();
+
+        foreach (var terminal in context.Terminals)
+        {
+            if (terminal.TokenType == context.Layer.OperatorKind)
+            {
+                symbols.Add(terminal.Text);
+            }
+            else if (fixity == null && OperatorTable.TryParseFixity(terminal.Text, out var parsed))
+            {
+                fixity = parsed;
+            }
+            else if (precedence == null && int.TryParse(terminal.Text, out var number))
+            {
+                precedence = number;
+            }
+        }
+
+        foreach (var symbol in symbols)
+        {
+            context.Define(symbol, fixity ?? OperatorFixity.InfixLeft, precedence ?? 9);
+        }
+    }
+
+    /// <summary>
+    /// Runs the declaration handlers over a tree and rebuilds its operator expressions.
+    /// </summary>
+    /// <param name="tree">The parse tree, which is changed in place.</param>
+    /// <param name="diagnostics">Receives an error for every expression that cannot be structured.</param>
+    /// <param name="sourceFile">The file the tree was parsed from.</param>
+    /// <param name="derivations">Receives the declarations that grouped each rebuilt node, if recording.</param>
+    /// <returns>The table with the file's declarations.</returns>
+    internal OperatorTable Resolve(CognitiveGraphNode tree, ICollection<Diagnostic> diagnostics, string? sourceFile, DerivationLog? derivations = null)
+    {
+        var table = Table.Clone();
+        if (_handlers.Count > 0)
+        {
+            Declare(tree, table, sourceFile);
+        }
+
+        Rebuild(tree, table, diagnostics, derivations);
+        return table;
+    }
+
+    private void Declare(CognitiveGraphNode node, OperatorTable table, string? sourceFile)
+    {
+        if (node is NonTerminalNode nonTerminal && _handlers.TryGetValue(nonTerminal.RuleName, out var handler))
+        {
+            handler(new OperatorDeclarationContext(this, nonTerminal, table, sourceFile));
+        }
+
+        foreach (var child in node.Children)
+        {
+            Declare(child, table, sourceFile);
+        }
+    }
+
+    private void Rebuild(CognitiveGraphNode node, OperatorTable table, ICollection<Diagnostic> diagnostics, DerivationLog? derivations)
+    {
+        if (node is NonTerminalNode expression && expression.RuleName == Rule)
+        {
+            var elements = new List<CognitiveGraphNode>();
+            Flatten(expression, elements);
+            if (elements.Count == 0)
+            {
+                return;
+            }
+
+            foreach (var operand in elements.Where(e => !IsOperator(e)))
+            {
+                Rebuild(operand, table, diagnostics, derivations);
+            }
+
+            var climber = new PrecedenceClimber(this, table, elements, derivations);
+            if (climber.Climb() is { } structured)
+            {
+                Replace(expression, structured, derivations);
+            }
+            else
+            {
+                // The expression is left flat.
+                foreach (var element in elements)
+                {
+                    element.Parent?.RemoveChild(element);
+                    expression.AddChild(element);
+                }
+
+                diagnostics.Add(climber.Error!);
+            }
+
+            return;
+        }
+
+        foreach (var child in node.Children.ToList())
+        {
+            Rebuild(child, table, diagnostics, derivations);
+        }
+    }
+
+    // Operator nodes nested in the expression, whether from the grammar or from an earlier rebuild, are dissolved.
+    private void Flatten(NonTerminalNode expression, List<CognitiveGraphNode> elements)
+    {
+        foreach (var child in expression.Children.ToList())
+        {
+            expression.RemoveChild(child);
+            if (child is NonTerminalNode nested && nested.RuleName == Rule)
+            {
+                Flatten(nested, elements);
+            }
+            else
+            {
+                elements.Add(child);
+            }
+        }
+    }
+
+    // The expression node keeps its identity and becomes the root of the structure.
+    private static void Replace(NonTerminalNode expression, CognitiveGraphNode structured, DerivationLog? derivations)
+    {
+        expression.Metadata.Remove(EntryMetadataKey);
+        if (structured is NonTerminalNode root && root.Metadata.TryGetValue(EntryMetadataKey, out var entry))
+        {
+            foreach (var child in root.Children.ToList())
+            {
+                root.RemoveChild(child);
+                expression.AddChild(child);
+            }
+
+            expression.ProductionIndex = -1;
+            expression.Metadata["productionIndex"] = -1;
+            expression.Metadata[EntryMetadataKey] = entry;
+            derivations?.Move(root, expression);
+            return;
+        }
+
+        expression.AddChild(structured);
+    }
+
+    private bool IsOperator(CognitiveGraphNode node)
+    {
+        return node is TerminalNode terminal && terminal.TokenType == OperatorKind;
+    }
+
+    // Precedence climbing over the flattened elements of one operator expression.
+    private sealed class PrecedenceClimber
+    {
+        private readonly OperatorLayer _layer;
+        private readonly OperatorTable _table;
+        private readonly List<CognitiveGraphNode> _elements;
+        private readonly DerivationLog? _derivations;
+        private int _position;
+
+        public PrecedenceClimber(OperatorLayer layer, OperatorTable table, List<CognitiveGraphNode> elements, DerivationLog? derivations)
+        {
+            _layer = layer;
+            _table = table;
+            _elements = elements;
+            _derivations = derivations;
+        }
+
+        public Diagnostic? Error { get; private set; }
+
+        public CognitiveGraphNode? Climb()
+        {
+            var result = Expression(int.MinValue, null);
+            if (result != null && _position < _elements.Count)
+            {
+                return Fail(_elements[_position], "is not an operator; operands must be separated by operators");
+            }
+
+            return result;
+        }
+
+        private CognitiveGraphNode? Expression(int minPrecedence, OperatorEntry? parent)
+        {
+            var left = Operand();
+            OperatorEntry? previous = null;
+
+            while (left != null && _position < _elements.Count && _layer.IsOperator(_elements[_position]))
+            {
+                var op = (TerminalNode)_elements[_position];
+                var offset = op.SourcePosition?.Offset ?? 0;
+                var infix = _table.FindInfix(op.Text, offset);
+                var postfix = _table.FindPostfix(op.Text, offset);
+
+                // An operator that is both is postfix when no operand follows it.
+                var next = _position + 1 < _elements.Count ? _elements[_position + 1] : null;
+                if (postfix != null && (infix == null || next == null || _layer.IsOperator(next)))
+                {
+                    if (postfix.Precedence < minPrecedence)
+                    {
+                        break;
+                    }
+
+                    _position++;
+                    left = Node(postfix, left, op);
+                    continue;
+                }
+
+                if (infix == null)
+                {
+                    return Fail(op, "is not declared as an infix or postfix operator");
+                }
+
+                if (infix.Precedence < minPrecedence)
+                {
+                    break;
+                }
+
+                var prior = previous ?? parent;
+                if (prior != null && prior.Precedence == infix.Precedence &&
+                    (prior.Fixity != infix.Fixity || infix.Fixity == OperatorFixity.Infix))
+                {
+                    return Fail(op, $"({infix}) cannot be mixed with '{prior.Symbol}' ({prior}) without parentheses");
+                }
+
+                _position++;
+                var right = Expression(infix.Fixity == OperatorFixity.InfixRight ? infix.Precedence : infix.Precedence + 1, infix);
+                if (right == null)
+                {
+                    return null;
+                }
+
+                left = Node(infix, left, op, right);
+                previous = infix;
+            }
+
+            return left;
+        }
+
+        private CognitiveGraphNode? Operand()
+        {
+            if (_position >= _elements.Count)
+            {
+                return Fail(_elements[^1], "is missing its operand");
+            }
+
+            var element = _elements[_position];
+            if (!_layer.IsOperator(element))
+            {
+                _position++;
+                return element;
+            }
+
+            var op = (TerminalNode)element;
+            var prefix = _table.FindPrefix(op.Text, op.SourcePosition?.Offset ?? 0);
+            if (prefix == null)
+            {
+                return Fail(op, "is not declared as a prefix operator");
+            }
+
+            _position++;
+            var operand = Expression(prefix.Precedence + 1, null);
+            return operand != null ? Node(prefix, op, operand) : null;
+        }
+
+        private NonTerminalNode Node(OperatorEntry entry, params CognitiveGraphNode[] children)
+        {
+            var node = new NonTerminalNode(_layer.Rule);
+            node.Metadata[EntryMetadataKey] = entry;
+            foreach (var child in children)
+            {
+                child.Parent?.RemoveChild(child);
+                node.AddChild(child);
+            }
+
+            var first = children[0].SourcePosition;
+            var last = children[^1].SourcePosition;
+            node.SourcePosition = first != null && last != null ? first.SpanTo(last) : first ?? last;
+            if (_derivations != null)
+            {
+                Record(node, entry, children);
+            }
+
+            return node;
+        }
+
+        // An operand that is itself an operator node was grouped into this one because its operator binds tighter
+        // or, at the same precedence, because of associativity. A prefix operator's operand is grouped by the
+        // prefix operator's precedence alone, and a prefix node on the right of an operator always belongs to it.
+        private void Record(NonTerminalNode node, OperatorEntry entry, CognitiveGraphNode[] children)
+        {
+            var op = (TerminalNode)children[entry.Fixity == OperatorFixity.Prefix ? 0 : 1];
+            _derivations!.Add(node, new DerivationStep(node.Id, node.RuleName, DerivationDecisionKind.Operator, $"applies '{op.Text}' as {entry}")
+            {
+                Lookahead = op.Text,
+                Location = op.SourcePosition,
+                Declarations = new[] { entry }
+            });
+
+            if (entry.Fixity != OperatorFixity.Prefix)
+            {
+                RecordOperand(node, entry, children[0], "left");
+            }
+
+            if (entry.Fixity != OperatorFixity.Postfix && children[^1] is NonTerminalNode right &&
+                right.Metadata.GetValueOrDefault(EntryMetadataKey) is OperatorEntry { Fixity: not OperatorFixity.Prefix })
+            {
+                RecordOperand(node, entry, right, "right");
+            }
+        }
+
+        private void RecordOperand(NonTerminalNode node, OperatorEntry entry, CognitiveGraphNode operand, string side)
+        {
+            if (operand.Metadata.GetValueOrDefault(EntryMetadataKey) is not OperatorEntry inner || inner.Precedence < entry.Precedence)
+            {
+                return;
+            }
+
+            var op = operand.Children.OfType<TerminalNode>().First(t => t.TokenType == _layer.OperatorKind);
+            var grouped = inner.Precedence > entry.Precedence;
+            var reason = grouped
+                ? $"groups '{inner.Symbol}' ({inner}) into its {side} operand because it binds tighter than '{entry.Symbol}' ({entry})"
+                : $"groups '{inner.Symbol}' ({inner}) into its {side} operand because operators of precedence {entry.Precedence} associate to the {side}";
+            _derivations!.Add(node, new DerivationStep(node.Id, node.RuleName, grouped ? DerivationDecisionKind.Precedence : DerivationDecisionKind.Associativity, reason)
+            {
+                Lookahead = op.Text,
+                Location = op.SourcePosition,
+                Declarations = new[] { inner, entry }
+            });
+        }
+
+        private CognitiveGraphNode? Fail(CognitiveGraphNode element, string reason)
+        {
+            var text = element is TerminalNode terminal ? terminal.Text : element is NonTerminalNode rule ? rule.RuleName : element.NodeType;
+            Error ??= new Diagnostic
+            {
+                Code = DiagnosticCodes.UnresolvedOperator,
+                Location = element.SourcePosition,
+                Data = { ["symbol"] = text }
+            }.WithMessage(DiagnosticCodes.UnresolvedOperator, ("text", text), ("reason", reason));
+
+            return null;
+        }
+    }
+}
+
+/// <summary>
+/// An operator declaration handed to an <see cref="OperatorDeclarationHandler"/>.
+/// </summary>
+public sealed class OperatorDeclarationContext
+{
+    private readonly OperatorTable _table;
+    private readonly string? _sourceFile;
+
+    internal OperatorDeclarationContext(OperatorLayer layer, NonTerminalNode declaration, OperatorTable table, string? sourceFile)
+    {
+        Layer = layer;
+        Declaration = declaration;
+        _table = table;
+        _sourceFile = sourceFile;
+    }
+
+    /// <summary>
+    /// Gets the operator layer.
+    /// </summary>
+    public OperatorLayer Layer { get; }
+
+    /// <summary>
+    /// Gets the declaration node.
+    /// </summary>
+    public NonTerminalNode Declaration { get; }
+
+    /// <summary>
+    /// Gets the operators defined so far, including those of earlier declarations.
+    /// </summary>
+    public IReadOnlyList<OperatorEntry> Entries => _table.Entries;
+
+    /// <summary>
+    /// Gets the terminals of the declaration in source order.
+    /// </summary>
+    public IEnumerable<TerminalNode> Terminals => Collect(Declaration);
+
+    /// <summary>
+    /// Defines an operator that applies from the end of the declaration on.
+    /// </summary>
+    /// <param name="symbol">The operator symbol.</param>
+    /// <param name="fixity">The position and associativity.</param>
+    /// <param name="precedence">The precedence; higher binds tighter.</param>
+    /// <returns>The new entry.</returns>
+    public OperatorEntry Define(string symbol, OperatorFixity fixity, int precedence)
+    {
+        var position = Declaration.SourcePosition;
+        var end = position != null ? position.Offset + position.Length : 0;
+        return _table.Define(symbol, fixity, precedence, end, _sourceFile);
+    }
+
+    private static IEnumerable<TerminalNode> Collect(CognitiveGraphNode node)
+    {
+        if (node is TerminalNode terminal)
+        {
+            return new[] { terminal };
+        }
+
+        return node.Children.SelectMany(Collect);
+    }
+}
@@ -0,0 +1,230 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The position and associativity of an operator.
+/// </summary>
+public enum OperatorFixity
+{
+    /// <summary>
+    /// A prefix operator, written before its operand.
+    /// </summary>
+    Prefix,
+
+    /// <summary>
+    /// A postfix operator, written after its operand.
+    /// </summary>
+    Postfix,
+
+    /// <summary>
+    /// A left-associative infix operator: <c>a op b op c</c> is <c>(a op b) op c</c>.
+    /// </summary>
+    InfixLeft,
+
+    /// <summary>
+    /// A right-associative infix operator: <c>a op b op c</c> is <c>a op (b op c)</c>.
+    /// </summary>
+    InfixRight,
+
+    /// <summary>
+    /// A non-associative infix operator that cannot be chained with operators of the same precedence.
+    /// </summary>
+    Infix
+}
+
+/// <summary>
+/// An entry of an <see cref="OperatorTable"/>.
+/// </summary>
+/// <param name="Id">The entry's index in its table.</param>
+/// <param name="Symbol">The operator symbol.</param>
+/// <param name="Fixity">The position and associativity.</param>
+/// <param name="Precedence">The precedence; higher binds tighter.</param>
+/// <param name="EffectiveOffset">The offset from which the entry applies; operators before it do not see it.</param>
+/// <param name="SourceFile">The file that declared the entry, or null for entries defined by the host.</param>
+public sealed record OperatorEntry(int Id, string Symbol, OperatorFixity Fixity, int Precedence, int EffectiveOffset, string? SourceFile)
+{
+    /// <summary>
+    /// Gets a value indicating whether the operator is an infix operator.
+    /// </summary>
+    public bool IsInfix => Fixity is OperatorFixity.InfixLeft or OperatorFixity.InfixRight or OperatorFixity.Infix;
+
+    /// <summary>
+    /// Returns the entry as a fixity declaration, e.g. <c>infixl 6 &lt;+&gt;</c>.
+    /// </summary>
+    /// <returns>The declaration text.</returns>
+    public override string ToString()
+    {
+        return $"{OperatorTable.FormatFixity(Fixity)} {Precedence} {Symbol}";
+    }
+}
+
+/// <summary>
+/// A table of operators that grows while a file is parsed. Entries are never removed; a later entry for the
+/// same symbol and position shadows earlier ones from its effective offset on, so a redeclaration only
+/// changes how operators after it are read.
+/// </summary>
+public sealed class OperatorTable
+{
+    private readonly List<OperatorEntry> _entries = new();
+
+    /// <summary>
+    /// Gets the entries in definition order.
+    /// </summary>
+    public IReadOnlyList<OperatorEntry> Entries => _entries;
+
+    /// <summary>
+    /// Defines an operator.
+    /// </summary>
+    /// <param name="symbol">The operator symbol.</param>
+    /// <param name="fixity">The position and associativity.</param>
+    /// <param name="precedence">The precedence; higher binds tighter.</param>
+    /// <param name="effectiveOffset">The offset from which the entry applies.</param>
+    /// <param name="sourceFile">The declaring file, if any.</param>
+    /// <returns>The new entry.</returns>
+    public OperatorEntry Define(string symbol, OperatorFixity fixity, int precedence, int effectiveOffset = 0, string? sourceFile = null)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(symbol);
+
+        var entry = new OperatorEntry(_entries.Count, symbol, fixity, precedence, effectiveOffset, sourceFile);
+        _entries.Add(entry);
+        return entry;
+    }
+
+    /// <summary>
+    /// Finds the infix entry in effect for an operator.
+    /// </summary>
+    /// <param name="symbol">The operator symbol.</param>
+    /// <param name="offset">The offset of the operator.</param>
+    /// <returns>The latest infix entry effective at the offset, or null.</returns>
+    public OperatorEntry? FindInfix(string symbol, int offset)
+    {
+        return Find(symbol, offset, entry => entry.IsInfix);
+    }
+
+    /// <summary>
+    /// Finds the prefix entry in effect for an operator.
+    /// </summary>
+    /// <param name="symbol">The operator symbol.</param>
+    /// <param name="offset">The offset of the operator.</param>
+    /// <returns>The latest prefix entry effective at the offset, or null.</returns>
+    public OperatorEntry? FindPrefix(string symbol, int offset)
+    {
+        return Find(symbol, offset, entry => entry.Fixity == OperatorFixity.Prefix);
+    }
+
+    /// <summary>
+    /// Finds the postfix entry in effect for an operator.
+    /// </summary>
+    /// <param name="symbol">The operator symbol.</param>
+    /// <param name="offset">The offset of the operator.</param>
+    /// <returns>The latest postfix entry effective at the offset, or null.</returns>
+    public OperatorEntry? FindPostfix(string symbol, int offset)
+    {
+        return Find(symbol, offset, entry => entry.Fixity == OperatorFixity.Postfix);
+    }
+
+    /// <summary>
+    /// Copies the table.
+    /// </summary>
+    /// <returns>A table with the same entries.</returns>
+    public OperatorTable Clone()
+    {
+        var clone = new OperatorTable();
+        clone._entries.AddRange(_entries);
+        return clone;
+    }
+
+    /// <summary>
+    /// Adds the entries of another table, in effect from the start of the file. Used for operators declared
+    /// in imported files.
+    /// </summary>
+    /// <param name="other">The table to import.</param>
+    public void Import(OperatorTable other)
+    {
+        ArgumentNullException.ThrowIfNull(other);
+
+        foreach (var entry in other.Entries)
+        {
+            Define(entry.Symbol, entry.Fixity, entry.Precedence, 0, entry.SourceFile);
+        }
+    }
+
+    private OperatorEntry? Find(string symbol, int offset, Func<OperatorEntry, bool> position)
+    {
+        for (var i = _entries.Count - 1; i >= 0; i--)
+        {
+            var entry = _entries[i];
+            if (entry.Symbol == symbol && entry.EffectiveOffset <= offset && position(entry))
+            {
+                return entry;
+            }
+        }
+
+        return null;
+    }
+
+    /// <summary>
+    /// Parses a fixity keyword: <c>infixl</c>, <c>infixr</c>, <c>infix</c>, <c>prefix</c> or <c>postfix</c>.
+    /// </summary>
+    /// <param name="keyword">The keyword.</param>
+    /// <param name="fixity">The fixity.</param>
+    /// <returns>True if the keyword names a fixity.</returns>
+    public static bool TryParseFixity(string keyword, out OperatorFixity fixity)
+    {
+        switch (keyword)
+        {
+            case "infixl":
+                fixity = OperatorFixity.InfixLeft;
+                return true;
+            case "infixr":
+                fixity = OperatorFixity.InfixRight;
+                return true;
+            case "infix":
+                fixity = OperatorFixity.Infix;
+                return true;
+            case "prefix":
+                fixity = OperatorFixity.Prefix;
+                return true;
+            case "postfix":
+                fixity = OperatorFixity.Postfix;
+                return true;
+            default:
+                fixity = default;
+                return false;
+        }
+    }
+
+    /// <summary>
+    /// Formats a fixity as its keyword.
+    /// </summary>
+    /// <param name="fixity">The fixity.</param>
+    /// <returns>The keyword.</returns>
+    public static string FormatFixity(OperatorFixity fixity)
+    {
+        return fixity switch
+        {
+            OperatorFixity.InfixLeft => "infixl",
+            OperatorFixity.InfixRight => "infixr",
+            OperatorFixity.Infix => "infix",
+            OperatorFixity.Prefix => "prefix",
+            _ => "postfix"
+        };
+    }
+}
@@ -0,0 +1,117 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// An entry of a file's outline.
+/// </summary>
+/// <param name="Name">The text that introduces the construct, such as <c>fn main()</c>.</param>
+/// <param name="Kind">The rule of the construct's node.</param>
+/// <param name="Location">The span of the construct.</param>
+/// <param name="Children">The entries nested in the construct.</param>
+public sealed record OutlineItem(string Name, string Kind, SourcePosition Location, IReadOnlyList<OutlineItem> Children);
+
+/// <summary>
+/// Builds the outline of a parse from the shape of its tree: a rule node that spans more than one line is an entry
+/// unless it starts on the line of the entry that encloses it or continues a list of its parent's rule. An entry is
+/// named by the text of its first line, or of the line before when its first line holds only an opening bracket,
+/// without the bracket. It needs nothing but the tree, so it works alike for grammar parses and for the regions of
+/// the <see cref="UniversalFallbackAnalyzer"/>. Grammars that list their declaration rules under
+/// <see cref="CompiledGrammar.OutlineKey"/> get exactly the nodes of those rules as entries instead.
+/// </summary>
+public static class OutlineProvider
+{
+    private static readonly char[] OpeningBrackets = { '{', '[', '(' };
+
+    /// <summary>
+    /// Gets the outline of a parse.
+    /// </summary>
+    /// <param name="parse">The parse of the file.</param>
+    /// <returns>The top-level entries, or none if the parse has no tree.</returns>
+    public static IReadOnlyList<OutlineItem> GetOutline(ParseResult parse)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+
+        return parse.Tree == null ? Array.Empty<OutlineItem>() : Entries(parse.Tree, new LineIndex(parse.Input), parse.Grammar, 0);
+    }
+
+    /// <summary>
+    /// Gets a value indicating whether a child node is an outline entry.
+    /// </summary>
+    internal static bool IsEntry(CognitiveGraphNode parent, CognitiveGraphNode child, CompiledGrammar? grammar, int enclosingLine)
+    {
+        if (child is not NonTerminalNode nonTerminal || child.SourcePosition is not { } position)
+        {
+            return false;
+        }
+
+        if (grammar is { HasOutlineRules: true })
+        {
+            return grammar.GetRule(nonTerminal.RuleName)?.IsOutlined == true;
+        }
+
+        return position.EndLine > position.Line
+            && position.Line != enclosingLine
+            && !(parent is NonTerminalNode list && list.RuleName == nonTerminal.RuleName && list.SourcePosition?.Offset == position.Offset);
+    }
+
+    private static List<OutlineItem> Entries(CognitiveGraphNode node, LineIndex lineIndex, CompiledGrammar? grammar, int enclosingLine)
+    {
+        // A region a lazy parse deferred has no entries until it is parsed.
+        var entries = new List<OutlineItem>();
+        if (node is DeferredNode { IsMaterialized: false })
+        {
+            return entries;
+        }
+
+        foreach (var child in node.Children)
+        {
+            if (IsEntry(node, child, grammar, enclosingLine))
+            {
+                var location = child.SourcePosition!;
+                entries.Add(new OutlineItem(Name(lineIndex, location.Line), ((NonTerminalNode)child).RuleName, location, Entries(child, lineIndex, grammar, location.Line)));
+            }
+            else
+            {
+                entries.AddRange(Entries(child, lineIndex, grammar, enclosingLine));
+            }
+        }
+
+        return entries;
+    }
+
+    private static string Name(LineIndex lineIndex, int line)
+    {
+        var name = Line(lineIndex, line);
+        for (var previous = line - 1; name.Length == 0 && previous >= 1; previous--)
+        {
+            name = Line(lineIndex, previous);
+        }
+
+        return name;
+    }
+
+    private static string Line(LineIndex lineIndex, int line)
+    {
+        var start = lineIndex.GetLineStart(line);
+        var end = line < lineIndex.LineCount ? lineIndex.GetLineStart(line + 1) : lineIndex.Text.Length;
+        return lineIndex.Text[start..end].Trim().TrimEnd(OpeningBrackets).TrimEnd();
+    }
+}
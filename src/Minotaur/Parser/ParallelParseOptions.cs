@@ -0,0 +1,43 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Options for parallel recognition. When the items still to be processed in an Earley set reach the threshold,
+/// the completions among them are advanced over the items waiting in earlier sets on several threads before the
+/// set is processed in order as usual. Earlier sets no longer change, so this work only reads the chart, and the
+/// ordered pass adds its results where the sequential parser would have: charts, forests and trees are identical
+/// to those of a sequential parse. It pays off on highly ambiguous input, where sets hold thousands of items.
+/// </summary>
+/// <remarks>
+/// The Earley recognizer keeps no GLR stacks to fork, so what runs in parallel is the preparation of completions
+/// within one set, not the exploration of separate parses. Items are still added to sets, and forest nodes still
+/// created, by a single thread, so no interning structure is shared between threads.
+/// </remarks>
+public class ParallelParseOptions
+{
+    /// <summary>
+    /// Gets or sets the number of unprocessed items in a set from which they are prepared in parallel.
+    /// </summary>
+    public int Threshold { get; set; } = 1024;
+
+    /// <summary>
+    /// Gets or sets the largest number of threads used at once.
+    /// </summary>
+    public int MaxDegreeOfParallelism { get; set; } = Environment.ProcessorCount;
+}
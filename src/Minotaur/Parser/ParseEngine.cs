@@ -0,0 +1,182 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A way of running the generalized parser over an input. Every engine must give the tree and diagnostics
+/// <see cref="Sequential"/> gives for the grammars it supports, so running a corpus under each of them, as
+/// <see cref="Testing.EngineMatrix"/> does, catches bugs that only one code path has. Hosts with a parsing path of
+/// their own derive from this class to have it checked the same way.
+/// </summary>
+public abstract class ParseEngine
+{
+    /// <summary>
+    /// Initializes a new instance of the ParseEngine class.
+    /// </summary>
+    /// <param name="name">The name the engine is selected by, e.g. <c>lazy</c>.</param>
+    protected ParseEngine(string name)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(name);
+
+        Name = name;
+    }
+
+    /// <summary>
+    /// Gets the plain parse: the whole input lexed and recognized in one pass on one thread.
+    /// </summary>
+    public static ParseEngine Sequential { get; } = new SequentialEngine();
+
+    /// <summary>
+    /// Gets the parse with <see cref="ParseOptions.Parallelism"/> set to prepare every Earley set on several threads,
+    /// however few items it holds. Scannerless grammars are always recognized sequentially.
+    /// </summary>
+    public static ParseEngine Parallel { get; } = new ParallelEngine();
+
+    /// <summary>
+    /// Gets the parse with <see cref="ParseOptions.Lazy"/> set, which skips the bodies of deferred rules and parses
+    /// them when they are read. Only grammars with deferred rules that are not scannerless parse differently.
+    /// </summary>
+    public static ParseEngine Lazy { get; } = new LazyEngine();
+
+    /// <summary>
+    /// Gets the parse of an <see cref="IncrementalParser"/> that starts from the first half of the input and then
+    /// has the second half inserted, so the result comes from relexing around the edit and reusing subtrees.
+    /// </summary>
+    public static ParseEngine Incremental { get; } = new IncrementalEngine();
+
+    /// <summary>
+    /// Gets the built-in engines, <see cref="Sequential"/> first.
+    /// </summary>
+    public static IReadOnlyList<ParseEngine> All { get; } = new[] { Sequential, Parallel, Lazy, Incremental };
+
+    /// <summary>
+    /// Gets the name the engine is selected by.
+    /// </summary>
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets a built-in engine by name.
+    /// </summary>
+    /// <param name="name">The name; case is ignored.</param>
+    /// <returns>The engine, or null if no built-in engine has the name.</returns>
+    public static ParseEngine? Get(string name)
+    {
+        ArgumentNullException.ThrowIfNull(name);
+
+        return All.FirstOrDefault(e => string.Equals(e.Name, name.Trim(), StringComparison.OrdinalIgnoreCase));
+    }
+
+    /// <summary>
+    /// Determines why the engine cannot run a grammar any differently from <see cref="Sequential"/>, if it cannot.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <returns>The reason, or null if the engine supports the grammar.</returns>
+    public virtual string? GetUnsupportedReason(CompiledGrammar grammar)
+    {
+        return null;
+    }
+
+    /// <summary>
+    /// Parses an input.
+    /// </summary>
+    /// <param name="parser">The parser of the grammar.</param>
+    /// <param name="input">The input text.</param>
+    /// <param name="options">The options of the parse, which the engine adds its own settings to.</param>
+    /// <returns>The result.</returns>
+    public abstract ParseResult Parse(GeneralizedParser parser, string input, ParseOptions options);
+
+    /// <summary>
+    /// Returns the engine's name.
+    /// </summary>
+    /// <returns>The name.</returns>
+    public override string ToString()
+    {
+        return Name;
+    }
+
+    private sealed class SequentialEngine : ParseEngine
+    {
+        public SequentialEngine()
+            : base("sequential")
+        {
+        }
+
+        public override ParseResult Parse(GeneralizedParser parser, string input, ParseOptions options)
+        {
+            return parser.Parse(input, options);
+        }
+    }
+
+    private sealed class ParallelEngine : ParseEngine
+    {
+        public ParallelEngine()
+            : base("parallel")
+        {
+        }
+
+        public override string? GetUnsupportedReason(CompiledGrammar grammar)
+        {
+            return grammar.IsScannerless ? "scannerless grammars are recognized sequentially" : null;
+        }
+
+        public override ParseResult Parse(GeneralizedParser parser, string input, ParseOptions options)
+        {
+            var parallel = options.Clone();
+            parallel.Parallelism = new ParallelParseOptions { Threshold = 1 };
+            return parser.Parse(input, parallel);
+        }
+    }
+
+    private sealed class LazyEngine : ParseEngine
+    {
+        public LazyEngine()
+            : base("lazy")
+        {
+        }
+
+        public override string? GetUnsupportedReason(CompiledGrammar grammar)
+        {
+            return grammar.IsScannerless ? "scannerless grammars are parsed whole"
+                : !grammar.HasDeferredRules ? "the grammar has no deferred rules"
+                : null;
+        }
+
+        public override ParseResult Parse(GeneralizedParser parser, string input, ParseOptions options)
+        {
+            var lazy = options.Clone();
+            lazy.Lazy = true;
+            return parser.Parse(input, lazy);
+        }
+    }
+
+    private sealed class IncrementalEngine : ParseEngine
+    {
+        public IncrementalEngine()
+            : base("incremental")
+        {
+        }
+
+        public override ParseResult Parse(GeneralizedParser parser, string input, ParseOptions options)
+        {
+            // The split usually falls inside a token, so the edit exercises relexing as well as subtree reuse.
+            var split = input.Length / 2;
+            var incremental = new IncrementalParser(parser, input[..split], options);
+            return incremental.ApplyEdit(TextEdit.Insert(split, input[split..]));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A shared packed parse forest. Every derivation of the input is represented; subtrees shared
+/// between derivations appear once and symbol nodes with more than one packed node are ambiguous.
+/// </summary>
+public sealed class ParseForest
+{
+    internal ParseForest(SymbolForestNode root)
+    {
+        Root = root;
+
+        var nodes = new List<SymbolForestNode>();
+        var visited = new HashSet<SymbolForestNode>();
+        var stack = new Stack<SymbolForestNode>();
+        stack.Push(root);
+
+        // Preorder numbering so that ids are stable for a given grammar and input.
+        while (stack.Count > 0)
+        {
+            var node = stack.Pop();
+            if (!visited.Add(node))
+            {
+                continue;
+            }
+
+            node.Id = nodes.Count;
+            nodes.Add(node);
+
+            for (var p = node.Packed.Count - 1; p >= 0; p--)
+            {
+                var children = node.Packed[p].Children;
+                for (var c = children.Count - 1; c >= 0; c--)
+                {
+                    if (children[c] is SymbolForestNode child && !visited.Contains(child))
+                    {
+                        stack.Push(child);
+                    }
+                }
+            }
+        }
+
+        Nodes = nodes;
+    }
+
+    /// <summary>
+    /// Gets the root node, spanning the whole input.
+    /// </summary>
+    public SymbolForestNode Root { get; }
+
+    /// <summary>
+    /// Gets all symbol nodes reachable from the root in preorder.
+    /// </summary>
+    public IReadOnlyList<SymbolForestNode> Nodes { get; }
+
+    /// <summary>
+    /// Gets the ambiguous symbol nodes.
+    /// </summary>
+    public IEnumerable<SymbolForestNode> AmbiguousNodes => Nodes.Where(n => n.IsAmbiguous);
+
+    /// <summary>
+    /// Gets the number of ambiguous symbol nodes.
+    /// </summary>
+    public int AmbiguityCount => Nodes.Count(n => n.IsAmbiguous);
+}
+
+/// <summary>
+/// Base class for parse forest nodes. Spans are expressed in token indices.
+/// </summary>
+public abstract class ForestNode
+{
+    /// <summary>
+    /// Initializes a new instance of the ForestNode class.
+    /// </summary>
+    /// <param name="start">The index of the first token covered.</param>
+    /// <param name="end">The index after the last token covered.</param>
+    protected ForestNode(int start, int end)
+    {
+        Start = start;
+        End = end;
+    }
+
+    /// <summary>
+    /// Gets the index of the first token covered by this node.
+    /// </summary>
+    public int Start { get; }
+
+    /// <summary>
+    /// Gets the index after the last token covered by this node.
+    /// </summary>
+    public int End { get; }
+}
+
+/// <summary>
+/// A forest node for a rule recognized over a token span.
+/// </summary>
+public sealed class SymbolForestNode : ForestNode
+{
+    internal SymbolForestNode(CompiledRule rule, int start, int end)
+        : base(start, end)
+    {
+        Rule = rule;
+    }
+
+    /// <summary>
+    /// Gets the preorder id of the node within its forest.
+    /// </summary>
+    public int Id { get; internal set; }
+
+    /// <summary>
+    /// Gets the recognized rule.
+    /// </summary>
+    public CompiledRule Rule { get; }
+
+    /// <summary>
+    /// Gets the rule name.
+    /// </summary>
+    public string RuleName => Rule.Name;
+
+    /// <summary>
+    /// Gets the packed nodes, one per distinct derivation of the span.
+    /// </summary>
+    public List<PackedForestNode> Packed { get; } = new();
+
+    /// <summary>
+    /// Gets a value indicating whether the span has more than one derivation.
+    /// </summary>
+    public bool IsAmbiguous => Packed.Count > 1;
+}
+
+/// <summary>
+/// One derivation of a symbol node: an alternative and the nodes for each of its symbols.
+/// </summary>
+public sealed class PackedForestNode
+{
+    internal PackedForestNode(CompiledAlternative alternative, IReadOnlyList<ForestNode> children)
+    {
+        Alternative = alternative;
+        Children = children;
+    }
+
+    /// <summary>
+    /// Gets the alternative used by this derivation.
+    /// </summary>
+    public CompiledAlternative Alternative { get; }
+
+    /// <summary>
+    /// Gets the child nodes, one per alternative symbol.
+    /// </summary>
+    public IReadOnlyList<ForestNode> Children { get; }
+}
+
+/// <summary>
+/// A forest leaf for a single token, or for the characters a terminal matched in scannerless parsing.
+/// </summary>
+public sealed class TokenForestNode : ForestNode
+{
+    internal TokenForestNode(Token token, int index)
+        : base(index, index + 1)
+    {
+        Token = token;
+    }
+
+    internal TokenForestNode(Token token, int start, int end)
+        : base(start, end)
+    {
+        Token = token;
+    }
+
+    /// <summary>
+    /// Gets the token.
+    /// </summary>
+    public Token Token { get; }
+}
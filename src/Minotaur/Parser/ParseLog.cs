@@ -0,0 +1,679 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The kind of a recorded parse event.
+/// </summary>
+public enum ParseEventKind
+{
+    /// <summary>
+    /// An item for the start rule was added to the first set.
+    /// </summary>
+    Start,
+
+    /// <summary>
+    /// An item was predicted for a rule expected at the position.
+    /// </summary>
+    Predict,
+
+    /// <summary>
+    /// An item stepped over a nullable or already completed rule.
+    /// </summary>
+    Advance,
+
+    /// <summary>
+    /// An item consumed a terminal.
+    /// </summary>
+    Scan,
+
+    /// <summary>
+    /// An item advanced because a rule it was waiting for completed.
+    /// </summary>
+    Complete,
+
+    /// <summary>
+    /// A rule node of the final tree was entered.
+    /// </summary>
+    Enter,
+
+    /// <summary>
+    /// A terminal node of the final tree was visited.
+    /// </summary>
+    Leaf,
+
+    /// <summary>
+    /// The most recently entered rule node of the final tree was left.
+    /// </summary>
+    Exit
+}
+
+/// <summary>
+/// A recorded parse event.
+/// </summary>
+/// <param name="Kind">The kind of event.</param>
+public abstract record ParseEvent(ParseEventKind Kind);
+
+/// <summary>
+/// A recognition event: an Earley item was added to a set of the chart.
+/// </summary>
+/// <param name="Kind">The kind of event.</param>
+/// <param name="Position">The index of the set being processed when the item was added.</param>
+/// <param name="Set">The index of the set the item was added to.</param>
+/// <param name="Rule">The index of the item's rule in the grammar.</param>
+/// <param name="Alternative">The index of the item's alternative in its rule.</param>
+/// <param name="Dot">The number of symbols of the alternative already recognized.</param>
+/// <param name="Origin">The index of the set the item started in.</param>
+public sealed record ChartEvent(ParseEventKind Kind, int Position, int Set, int Rule, int Alternative, int Dot, int Origin) : ParseEvent(Kind);
+
+/// <summary>
+/// A tree event, recorded in pre-order from the final tree after recognition succeeded.
+/// </summary>
+/// <param name="Kind"><see cref="ParseEventKind.Enter"/>, <see cref="ParseEventKind.Leaf"/> or <see cref="ParseEventKind.Exit"/>.</param>
+/// <param name="Name">The rule name of an entered node or the token type of a leaf; empty for an exit.</param>
+/// <param name="ProductionIndex">The production index of an entered node.</param>
+/// <param name="Offset">The offset of the node in the input, or -1 if it has no position.</param>
+/// <param name="Length">The length of the node in the input.</param>
+/// <param name="Ambiguity">The number of derivations of an ambiguous rule node; 0 otherwise.</param>
+public sealed record TreeEvent(ParseEventKind Kind, string Name, int ProductionIndex, int Offset, int Length, int Ambiguity) : ParseEvent(Kind);
+
+/// <summary>
+/// An Earley item reconstructed from a parse log.
+/// </summary>
+/// <param name="Alternative">The alternative being recognized.</param>
+/// <param name="Dot">The number of symbols already recognized.</param>
+/// <param name="Origin">The index of the set the item started in.</param>
+public sealed record ReplayItem(CompiledAlternative Alternative, int Dot, int Origin)
+{
+    /// <summary>
+    /// Gets a value indicating whether every symbol of the alternative has been recognized.
+    /// </summary>
+    public bool IsComplete => Dot == Alternative.Symbols.Count;
+
+    /// <summary>
+    /// Returns the item in dotted form, e.g. <c>&lt;sum&gt; ::= &lt;sum&gt; • "+" &lt;term&gt; @0</c>.
+    /// </summary>
+    /// <returns>The item description.</returns>
+    public override string ToString()
+    {
+        var symbols = Alternative.Symbols.Select(s => s.ToString()).ToList();
+        symbols.Insert(Dot, "•");
+        return $"<{Alternative.Rule.Name}> ::= {string.Join(' ', symbols)} @{Origin}";
+    }
+}
+
+/// <summary>
+/// The state of a recorded parse after a given event.
+/// </summary>
+public sealed class ParseReplayState
+{
+    /// <summary>
+    /// Gets the index of the last event applied.
+    /// </summary>
+    public int EventIndex { get; init; }
+
+    /// <summary>
+    /// Gets the last event applied.
+    /// </summary>
+    public ParseEvent Event { get; init; } = null!;
+
+    /// <summary>
+    /// Gets the index of the token being processed; during tree events, the last position recognition reached.
+    /// </summary>
+    public int Position { get; init; }
+
+    /// <summary>
+    /// Gets the token at <see cref="Position"/>, or null at the end of the input.
+    /// </summary>
+    public Token? Token { get; init; }
+
+    /// <summary>
+    /// Gets the item added by the last event, or null for a tree event.
+    /// </summary>
+    public ReplayItem? Item { get; init; }
+
+    /// <summary>
+    /// Gets the items of each set of the chart so far, in the order they were added.
+    /// </summary>
+    public IReadOnlyList<IReadOnlyList<ReplayItem>> Chart { get; init; } = Array.Empty<IReadOnlyList<ReplayItem>>();
+
+    /// <summary>
+    /// Gets the rules being recognized, outermost first: for a recognition event, the chain of items waiting
+    /// for the added item's rule back to the start rule; for a tree event, the rule nodes entered and not yet left.
+    /// </summary>
+    public IReadOnlyList<string> Stack { get; init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the part of the tree built so far, or null before the first tree event.
+    /// </summary>
+    public CognitiveGraphNode? Tree { get; init; }
+}
+
+/// <summary>
+/// A recorded parse: the input, its tokens, every item the recognizer added to the chart, the final tree as
+/// pre-order events, and the diagnostics. A log can be saved as a compact binary <c>.mparse</c> file and the
+/// parser state reconstructed at any event without running the parser again.
+/// </summary>
+/// <remarks>
+/// The file starts with a magic string and <see cref="FormatVersion"/>, followed by the fingerprint of the
+/// grammar the parse was recorded with; a log is only loaded against a grammar with the same fingerprint.
+/// Names are stored once in a string table and numbers as 7-bit encoded integers.
+/// </remarks>
+public sealed class ParseLog
+{
+    /// <summary>
+    /// The version of the file format; logs of other versions are not loaded.
+    /// </summary>
+    public const int FormatVersion = 1;
+
+    private const string Magic = "MINOTAUR-PARSE";
+
+    private readonly HashSet<(string Kind, int Offset)> _synthetic;
+
+    internal ParseLog(CompiledGrammar grammar, string startRule, string? sourceFile, string input, IReadOnlyList<Token> tokens, IReadOnlyList<ParseEvent> events, IReadOnlyList<Diagnostic> diagnostics)
+    {
+        Grammar = grammar;
+        StartRule = startRule;
+        SourceFile = sourceFile;
+        Input = input;
+        Tokens = tokens;
+        Events = events;
+        Diagnostics = diagnostics;
+        _synthetic = tokens.Where(t => t.IsSynthetic).Select(t => (t.Kind, t.Offset)).ToHashSet();
+    }
+
+    /// <summary>
+    /// Gets the grammar the parse was recorded with.
+    /// </summary>
+    public CompiledGrammar Grammar { get; }
+
+    /// <summary>
+    /// Gets the rule the input was parsed against.
+    /// </summary>
+    public string StartRule { get; }
+
+    /// <summary>
+    /// Gets the source file name used in locations, if any.
+    /// </summary>
+    public string? SourceFile { get; }
+
+    /// <summary>
+    /// Gets the parsed input.
+    /// </summary>
+    public string Input { get; }
+
+    /// <summary>
+    /// Gets the tokens produced by the lexer.
+    /// </summary>
+    public IReadOnlyList<Token> Tokens { get; }
+
+    /// <summary>
+    /// Gets the recorded events: the recognition events in order, followed by the tree events if parsing succeeded.
+    /// </summary>
+    public IReadOnlyList<ParseEvent> Events { get; }
+
+    /// <summary>
+    /// Gets the diagnostics of the parse.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics { get; }
+
+    /// <summary>
+    /// Reconstructs the parser state after an event.
+    /// </summary>
+    /// <param name="eventIndex">The index of the event.</param>
+    /// <returns>The state.</returns>
+    /// <exception cref="ArgumentOutOfRangeException">The index is not the index of an event.</exception>
+    public ParseReplayState StateAt(int eventIndex)
+    {
+        ArgumentOutOfRangeException.ThrowIfNegative(eventIndex);
+        ArgumentOutOfRangeException.ThrowIfGreaterThanOrEqual(eventIndex, Events.Count);
+
+        var chart = new List<List<ReplayItem>>();
+        var lines = new LineIndex(Input);
+        var open = new Stack<CognitiveGraphNode>();
+        CognitiveGraphNode? root = null;
+        ReplayItem? item = null;
+        var position = 0;
+
+        for (var i = 0; i <= eventIndex; i++)
+        {
+            switch (Events[i])
+            {
+                case ChartEvent chartEvent:
+                    while (chart.Count <= chartEvent.Set)
+                    {
+                        chart.Add(new List<ReplayItem>());
+                    }
+
+                    item = new ReplayItem(Grammar.Rules[chartEvent.Rule].Alternatives[chartEvent.Alternative], chartEvent.Dot, chartEvent.Origin);
+                    chart[chartEvent.Set].Add(item);
+                    position = chartEvent.Position;
+                    break;
+
+                case TreeEvent { Kind: ParseEventKind.Exit }:
+                    open.Pop();
+                    item = null;
+                    break;
+
+                case TreeEvent treeEvent:
+                    var node = CreateNode(treeEvent, lines);
+                    if (open.TryPeek(out var parent))
+                    {
+                        parent.AddChild(node);
+                    }
+                    else
+                    {
+                        root = node;
+                    }
+
+                    if (treeEvent.Kind == ParseEventKind.Enter)
+                    {
+                        open.Push(node);
+                    }
+
+                    item = null;
+                    break;
+            }
+        }
+
+        return new ParseReplayState
+        {
+            EventIndex = eventIndex,
+            Event = Events[eventIndex],
+            Position = position,
+            Token = position < Tokens.Count ? Tokens[position] : null,
+            Item = item,
+            Chart = chart,
+            Stack = item != null ? RuleStack(chart, item) : open.Reverse().Select(n => ((NonTerminalNode)n).RuleName).ToList(),
+            Tree = root
+        };
+    }
+
+    /// <summary>
+    /// Rebuilds the result of the recorded parse. The result has the input, tokens, tree and diagnostics of
+    /// the original parse but no parse forest or operator table, and its parsed length ends with the tree.
+    /// </summary>
+    /// <returns>The replayed parse result.</returns>
+    public ParseResult Replay()
+    {
+        var tree = Events.Count > 0 ? StateAt(Events.Count - 1).Tree : null;
+        return new ParseResult
+        {
+            Input = Input,
+            Grammar = Grammar,
+            StartRule = StartRule,
+            Tokens = Tokens,
+            Tree = tree,
+            Diagnostics = Diagnostics,
+            ParsedLength = tree?.SourcePosition is { } span ? span.Offset + span.Length : 0,
+            EventLog = this
+        };
+    }
+
+    /// <summary>
+    /// Saves the log to a file.
+    /// </summary>
+    /// <param name="path">The file path, conventionally with the <c>.mparse</c> extension.</param>
+    public void Save(string path)
+    {
+        using var stream = File.Create(path);
+        Save(stream);
+    }
+
+    /// <summary>
+    /// Writes the log to a stream.
+    /// </summary>
+    /// <param name="stream">The stream.</param>
+    public void Save(Stream stream)
+    {
+        ArgumentNullException.ThrowIfNull(stream);
+
+        var names = new List<string>();
+        var indices = new Dictionary<string, int>(StringComparer.Ordinal);
+        int Name(string name)
+        {
+            if (!indices.TryGetValue(name, out var index))
+            {
+                index = names.Count;
+                indices[name] = index;
+                names.Add(name);
+            }
+
+            return index;
+        }
+
+        foreach (var token in Tokens)
+        {
+            Name(token.Kind);
+        }
+
+        foreach (var treeEvent in Events.OfType<TreeEvent>())
+        {
+            Name(treeEvent.Name);
+        }
+
+        using var writer = new BinaryWriter(stream, Encoding.UTF8, leaveOpen: true);
+        writer.Write(Magic);
+        writer.Write(FormatVersion);
+        writer.Write(Grammar.Fingerprint);
+        writer.Write(StartRule);
+        writer.Write(SourceFile != null);
+        writer.Write(SourceFile ?? string.Empty);
+        writer.Write(Input);
+
+        writer.Write7BitEncodedInt(names.Count);
+        foreach (var name in names)
+        {
+            writer.Write(name);
+        }
+
+        writer.Write7BitEncodedInt(Tokens.Count);
+        foreach (var token in Tokens)
+        {
+            writer.Write7BitEncodedInt(Name(token.Kind));
+            writer.Write7BitEncodedInt(token.Offset);
+            // Synthetic terminators are zero-width; -1 stands for them.
+            writer.Write7BitEncodedInt(token.IsSynthetic ? -1 : token.Length);
+        }
+
+        writer.Write7BitEncodedInt(Events.Count);
+        foreach (var parseEvent in Events)
+        {
+            writer.Write((byte)parseEvent.Kind);
+            switch (parseEvent)
+            {
+                case ChartEvent e:
+                    writer.Write7BitEncodedInt(e.Position);
+                    writer.Write7BitEncodedInt(e.Set);
+                    writer.Write7BitEncodedInt(e.Rule);
+                    writer.Write7BitEncodedInt(e.Alternative);
+                    writer.Write7BitEncodedInt(e.Dot);
+                    writer.Write7BitEncodedInt(e.Origin);
+                    break;
+
+                case TreeEvent { Kind: ParseEventKind.Exit }:
+                    break;
+
+                case TreeEvent e:
+                    writer.Write7BitEncodedInt(Name(e.Name));
+                    writer.Write7BitEncodedInt(e.ProductionIndex);
+                    writer.Write7BitEncodedInt(e.Offset);
+                    writer.Write7BitEncodedInt(e.Length);
+                    writer.Write7BitEncodedInt(e.Ambiguity);
+                    break;
+            }
+        }
+
+        writer.Write7BitEncodedInt(Diagnostics.Count);
+        foreach (var diagnostic in Diagnostics)
+        {
+            writer.Write(diagnostic.Code);
+            writer.Write((byte)diagnostic.Severity);
+            writer.Write(diagnostic.Message);
+            writer.Write7BitEncodedInt(diagnostic.Location?.Offset ?? -1);
+            writer.Write7BitEncodedInt(diagnostic.Location?.Length ?? 0);
+        }
+    }
+
+    /// <summary>
+    /// Loads a log from a file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <param name="grammar">The grammar to replay the log against; it must be the grammar the log was recorded with.</param>
+    /// <returns>The log.</returns>
+    /// <exception cref="InvalidDataException">The file is not a parse log, is of another version, is corrupt,
+    /// or was recorded with a different grammar.</exception>
+    public static ParseLog Load(string path, CompiledGrammar grammar)
+    {
+        using var stream = File.OpenRead(path);
+        return Load(stream, grammar);
+    }
+
+    /// <summary>
+    /// Reads a log from a stream.
+    /// </summary>
+    /// <param name="stream">The stream.</param>
+    /// <param name="grammar">The grammar to replay the log against; it must be the grammar the log was recorded with.</param>
+    /// <returns>The log.</returns>
+    /// <exception cref="InvalidDataException">The stream does not hold a parse log, holds one of another
+    /// version, is corrupt, or the log was recorded with a different grammar.</exception>
+    public static ParseLog Load(Stream stream, CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(stream);
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        using var reader = new BinaryReader(stream, Encoding.UTF8, leaveOpen: true);
+        try
+        {
+            return Read(reader, grammar);
+        }
+        catch (Exception ex) when (ex is EndOfStreamException or ArgumentOutOfRangeException or FormatException)
+        {
+            throw new InvalidDataException("The parse log is truncated or corrupt", ex);
+        }
+    }
+
+    private static ParseLog Read(BinaryReader reader, CompiledGrammar grammar)
+    {
+        if (reader.ReadString() != Magic)
+        {
+            throw new InvalidDataException("The file is not a parse log");
+        }
+
+        var version = reader.ReadInt32();
+        if (version != FormatVersion)
+        {
+            throw new InvalidDataException($"Parse log format version {version} is not supported; expected {FormatVersion}");
+        }
+
+        if (reader.ReadString() != grammar.Fingerprint)
+        {
+            throw new InvalidDataException($"The parse log was recorded with a different grammar than '{grammar.Name}'");
+        }
+
+        var startRule = reader.ReadString();
+        var hasSourceFile = reader.ReadBoolean();
+        var sourceFile = reader.ReadString();
+        var input = reader.ReadString();
+        var lines = new LineIndex(input);
+
+        var names = new string[reader.Read7BitEncodedInt()];
+        for (var i = 0; i < names.Length; i++)
+        {
+            names[i] = reader.ReadString();
+        }
+
+        var tokens = new Token[reader.Read7BitEncodedInt()];
+        for (var i = 0; i < tokens.Length; i++)
+        {
+            var kind = names[reader.Read7BitEncodedInt()];
+            var offset = reader.Read7BitEncodedInt();
+            var length = reader.Read7BitEncodedInt();
+            tokens[i] = length < 0
+                ? new Token(kind, string.Empty, offset) { IsSynthetic = true }
+                : new Token(kind, input.Substring(offset, length), offset);
+        }
+
+        grammar.Lexer.RestoreCaptures(tokens, input);
+
+        var events = new ParseEvent[reader.Read7BitEncodedInt()];
+        for (var i = 0; i < events.Length; i++)
+        {
+            var kind = (ParseEventKind)reader.ReadByte();
+            events[i] = kind switch
+            {
+                <= ParseEventKind.Complete => ReadChartEvent(reader, kind, grammar),
+                ParseEventKind.Exit => new TreeEvent(kind, string.Empty, 0, -1, 0, 0),
+                ParseEventKind.Enter or ParseEventKind.Leaf => new TreeEvent(
+                    kind,
+                    names[reader.Read7BitEncodedInt()],
+                    reader.Read7BitEncodedInt(),
+                    reader.Read7BitEncodedInt(),
+                    reader.Read7BitEncodedInt(),
+                    reader.Read7BitEncodedInt()),
+                _ => throw new InvalidDataException($"The parse log holds an event of unknown kind {(int)kind}")
+            };
+        }
+
+        var diagnostics = new Diagnostic[reader.Read7BitEncodedInt()];
+        for (var i = 0; i < diagnostics.Length; i++)
+        {
+            diagnostics[i] = new Diagnostic
+            {
+                Code = reader.ReadString(),
+                Severity = (DiagnosticSeverity)reader.ReadByte(),
+                Message = reader.ReadString()
+            };
+
+            var offset = reader.Read7BitEncodedInt();
+            var length = reader.Read7BitEncodedInt();
+            if (offset >= 0)
+            {
+                diagnostics[i].Location = lines.GetPosition(offset, length, hasSourceFile ? sourceFile : null);
+            }
+        }
+
+        return new ParseLog(grammar, startRule, hasSourceFile ? sourceFile : null, input, tokens, events, diagnostics);
+    }
+
+    private static ChartEvent ReadChartEvent(BinaryReader reader, ParseEventKind kind, CompiledGrammar grammar)
+    {
+        var chartEvent = new ChartEvent(
+            kind,
+            reader.Read7BitEncodedInt(),
+            reader.Read7BitEncodedInt(),
+            reader.Read7BitEncodedInt(),
+            reader.Read7BitEncodedInt(),
+            reader.Read7BitEncodedInt(),
+            reader.Read7BitEncodedInt());
+
+        if (chartEvent.Rule < 0 || chartEvent.Rule >= grammar.Rules.Count
+            || chartEvent.Alternative < 0 || chartEvent.Alternative >= grammar.Rules[chartEvent.Rule].Alternatives.Count
+            || chartEvent.Dot < 0 || chartEvent.Dot > grammar.Rules[chartEvent.Rule].Alternatives[chartEvent.Alternative].Symbols.Count
+            || chartEvent.Set < 0)
+        {
+            throw new InvalidDataException("The parse log refers to an item the grammar does not have");
+        }
+
+        return chartEvent;
+    }
+
+    private CognitiveGraphNode CreateNode(TreeEvent treeEvent, LineIndex lines)
+    {
+        CognitiveGraphNode node = treeEvent.Kind == ParseEventKind.Leaf
+            ? new TerminalNode(treeEvent.Offset >= 0 ? Input.Substring(treeEvent.Offset, treeEvent.Length) : string.Empty, treeEvent.Name)
+            : new NonTerminalNode(treeEvent.Name, treeEvent.ProductionIndex);
+
+        if (treeEvent.Offset >= 0)
+        {
+            node.SourcePosition = lines.GetPosition(treeEvent.Offset, treeEvent.Length, SourceFile);
+        }
+
+        if (treeEvent.Kind == ParseEventKind.Leaf && _synthetic.Contains((treeEvent.Name, treeEvent.Offset)))
+        {
+            node.Metadata[TerminatorPolicy.SyntheticMetadataKey] = true;
+        }
+
+        if (treeEvent.Ambiguity > 0)
+        {
+            node.Metadata["ambiguous"] = treeEvent.Ambiguity;
+        }
+
+        return node;
+    }
+
+    // Mirrors the rule stack of stall diagnostics: the items in the origin set whose next symbol is the item's
+    // rule stand in for callers, back to the start rule. Each (rule, origin) pair is used once.
+    private static List<string> RuleStack(List<List<ReplayItem>> chart, ReplayItem item)
+    {
+        var stack = new List<string> { item.Alternative.Rule.Name };
+        var visited = new HashSet<(int Rule, int Origin)> { (item.Alternative.Rule.Index, item.Origin) };
+        var current = item;
+
+        while (current.Origin < chart.Count)
+        {
+            var rule = current.Alternative.Rule.Index;
+            var parents = chart[current.Origin]
+                .Where(w => !w.IsComplete && w.Alternative.RuleIndices[w.Dot] == rule)
+                .Where(w => !visited.Contains((w.Alternative.Rule.Index, w.Origin)))
+                .ToList();
+            if (parents.Count == 0)
+            {
+                break;
+            }
+
+            current = parents.MinBy(w => w.Origin)!;
+            visited.Add((current.Alternative.Rule.Index, current.Origin));
+            stack.Add(current.Alternative.Rule.Name);
+        }
+
+        stack.Reverse();
+        return stack;
+    }
+}
+
+/// <summary>
+/// Collects parse events while the generalized parser runs.
+/// </summary>
+internal sealed class ParseRecorder
+{
+    private readonly List<ParseEvent> _events = new();
+
+    public int Position { get; set; }
+
+    public void ItemAdded(ParseEventKind cause, int set, GeneralizedParser.EarleyItem item)
+    {
+        _events.Add(new ChartEvent(cause, Position, set, item.Alternative.Rule.Index, item.Alternative.Index, item.Dot, item.Origin));
+    }
+
+    public ParseLog Finish(ParseResult result, string? sourceFile)
+    {
+        if (result.Tree != null)
+        {
+            AddTree(result.Tree);
+        }
+
+        return new ParseLog(result.Grammar!, result.StartRule, sourceFile, result.Input, result.Tokens, _events, result.Diagnostics);
+    }
+
+    private void AddTree(CognitiveGraphNode node)
+    {
+        var offset = node.SourcePosition?.Offset ?? -1;
+        var length = node.SourcePosition?.Length ?? 0;
+
+        if (node is TerminalNode terminal)
+        {
+            _events.Add(new TreeEvent(ParseEventKind.Leaf, terminal.TokenType, 0, offset, length, 0));
+            return;
+        }
+
+        var rule = node as NonTerminalNode;
+        var ambiguity = node.Metadata.TryGetValue("ambiguous", out var count) && count is int n ? n : 0;
+        _events.Add(new TreeEvent(ParseEventKind.Enter, rule?.RuleName ?? node.NodeType, rule?.ProductionIndex ?? 0, offset, length, ambiguity));
+        foreach (var child in node.Children)
+        {
+            AddTree(child);
+        }
+
+        _events.Add(new TreeEvent(ParseEventKind.Exit, string.Empty, 0, -1, 0, 0));
+    }
+}
@@ -0,0 +1,143 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Options controlling the generalized parser.
+/// </summary>
+public class ParseOptions
+{
+    /// <summary>
+    /// Gets or sets the start rule. If null, the entry point's rule or the compiled grammar's start rule is used.
+    /// </summary>
+    public string? StartRule { get; set; }
+
+    /// <summary>
+    /// Gets or sets how much of the input must be consumed. If null, the parser's entry point decides, and
+    /// without one the whole input must be consumed.
+    /// </summary>
+    public EntryPointEnd? End { get; set; }
+
+    /// <summary>
+    /// Gets or sets the maximum number of derivations kept for a single forest node.
+    /// </summary>
+    public int MaxAmbiguitiesPerNode { get; set; } = 64;
+
+    /// <summary>
+    /// Gets or sets the source file name used in diagnostic locations.
+    /// </summary>
+    public string? SourceFile { get; set; }
+
+    /// <summary>
+    /// Gets or sets the progress watchdog. If set, a parse that consumes fewer tokens per interval than the
+    /// minimum is aborted with a <see cref="Diagnostics.DiagnosticCodes.ParseStalled"/> error.
+    /// </summary>
+    public ParseWatchdogOptions? Watchdog { get; set; }
+
+    /// <summary>
+    /// Gets or sets the operator layer that structures operator expressions. If null, the layer described by
+    /// the grammar's "OperatorRule" metadata is used, if any.
+    /// </summary>
+    public OperatorLayer? Operators { get; set; }
+
+    /// <summary>
+    /// Gets or sets the largest edit distance at which a word the parser cannot accept is read as the unique
+    /// closest keyword expected at its position, reported with a
+    /// <see cref="Diagnostics.DiagnosticCodes.MisspelledKeyword"/> error, so parsing continues past misspellings
+    /// like <c>retrun</c>. Zero disables the correction.
+    /// </summary>
+    public int KeywordCorrectionDistance { get; set; } = 2;
+
+    /// <summary>
+    /// Gets or sets a value indicating whether every step of the parse is recorded in
+    /// <see cref="ParseResult.EventLog"/> so it can be saved and replayed later.
+    /// </summary>
+    public bool RecordEvents { get; set; }
+
+    /// <summary>
+    /// Gets or sets a value indicating whether the decisions that shaped every node of the tree are recorded, so
+    /// <see cref="ParseResult.ExplainNode"/> can explain them. Off by default because the record grows with the tree.
+    /// </summary>
+    public bool RecordDerivations { get; set; }
+
+    /// <summary>
+    /// Gets or sets the preambles skipped before the input's content, such as a byte order mark or shebang line,
+    /// which are reported in <see cref="ParseResult.Preamble"/>. If null, the whole input is parsed. Scannerless
+    /// grammars always parse the whole input.
+    /// </summary>
+    public PreambleOptions? Preamble { get; set; }
+
+    /// <summary>
+    /// Gets or sets the grammar features enabled for the parse, e.g. from project configuration, in addition
+    /// to those the input enables with pragmas. Alternatives guarded by any other feature are not reduced.
+    /// </summary>
+    public IReadOnlyCollection<string>? Features { get; set; }
+
+    /// <summary>
+    /// Gets or sets the declared names the grammar's symbol predicates check, usually collected by a first parse; see
+    /// <see cref="TwoPhaseParser"/>. If null, the predicates are not checked and their rules derive any name.
+    /// </summary>
+    public SymbolSet? Symbols { get; set; }
+
+    /// <summary>
+    /// Gets or sets the parallel recognition options. If null, or for scannerless grammars, every set is processed
+    /// on the calling thread. The result does not depend on this setting.
+    /// </summary>
+    public ParallelParseOptions? Parallelism { get; set; }
+
+    /// <summary>
+    /// Gets or sets the error budget applied to the parse's diagnostics. If null, every diagnostic is reported. Share
+    /// one budget between the parses of a run to limit the errors of the whole run.
+    /// </summary>
+    public Diagnostics.DiagnosticBudget? DiagnosticBudget { get; set; }
+
+    /// <summary>
+    /// Gets or sets a value indicating whether the bodies of the grammar's deferred rules are skipped as balanced
+    /// bracket groups and left as <see cref="DeferredNode"/> placeholders, parsed when their children are first read.
+    /// Their diagnostics join the result's as they are parsed; see <see cref="ParseResult.Deferred"/>. Ignored for
+    /// scannerless grammars and when an operator layer is in effect, which need the whole tree at once.
+    /// </summary>
+    public bool Lazy { get; set; }
+
+    /// <summary>
+    /// Gets or sets a value indicating whether each diagnostic gets the <see cref="QualifiedPath"/> of the node it
+    /// points at, under <see cref="QualifiedPath.DataKey"/>, for grammars that list outline rules. Only parses that
+    /// build a tree have paths, so a syntax error the parser cannot recover from gets none, and neither do the
+    /// diagnostics of bodies a lazy parse deferred.
+    /// </summary>
+    public bool PathContext { get; set; }
+
+    /// <summary>
+    /// Gets or sets a token cancelling the parse. Recognition checks it before each token, so a cancelled parse
+    /// throws an <see cref="OperationCanceledException"/> promptly even on a large input.
+    /// </summary>
+    public CancellationToken CancellationToken { get; set; }
+
+    /// <summary>
+    /// Gets or sets the interner that the text of identifier tokens is shared through, so that parses of many files
+    /// hold one copy of each name. If null, every token has its own text.
+    /// </summary>
+    public SharedInterner? Interner { get; set; }
+
+    internal ParseOptions Clone()
+    {
+        return (ParseOptions)MemberwiseClone();
+    }
+}
@@ -0,0 +1,274 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Monitoring;
+using Minotaur.Visitors;
+using Minotaur.Visualization;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The result of parsing input with the generalized parser.
+/// </summary>
+public class ParseResult : IMemoryReporting
+{
+    private readonly IReadOnlyList<Diagnostic> _diagnostics = Array.Empty<Diagnostic>();
+    private CognitiveGraphNode? _ast;
+    private QualifiedPathResolver? _paths;
+
+    /// <summary>
+    /// Gets the parsed input. This is the string passed to the parser, or the string a
+    /// <see cref="GeneralizedParser.Parse(ReadOnlyMemory{char}, ParseOptions?)"/> memory spans whole, not a copy, so a
+    /// result keeps its input alive for as long as the result itself is reachable.
+    /// </summary>
+    public string Input { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Gets the grammar the input was parsed with.
+    /// </summary>
+    public CompiledGrammar? Grammar { get; init; }
+
+    /// <summary>
+    /// Gets the rule the input was parsed against.
+    /// </summary>
+    public string StartRule { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Gets the preamble skipped before parsing when <see cref="ParseOptions.Preamble"/> was set;
+    /// <see cref="Parser.Preamble.Empty"/> otherwise.
+    /// </summary>
+    public Preamble Preamble { get; internal set; } = Preamble.Empty;
+
+    /// <summary>
+    /// Gets the tokens produced by the lexer.
+    /// </summary>
+    public IReadOnlyList<Token> Tokens { get; init; } = Array.Empty<Token>();
+
+    /// <summary>
+    /// Gets the parse forest containing every derivation, or null if parsing failed.
+    /// </summary>
+    public ParseForest? Forest { get; init; }
+
+    /// <summary>
+    /// Gets the parse tree for the preferred derivation, or null if parsing failed.
+    /// </summary>
+    public CognitiveGraphNode? Tree { get; init; }
+
+    /// <summary>
+    /// Gets the AST view of <see cref="Tree"/>: the tree without the symbols the grammar hides and with the
+    /// rules it inlines spliced into their parents. Null if parsing failed; the same as a copy of the tree for
+    /// grammars that neither hide nor inline anything.
+    /// </summary>
+    public CognitiveGraphNode? Ast => _ast ??= Tree != null && Grammar != null ? SyntaxTreeView.CreateAst(Tree, Grammar) : null;
+
+    /// <summary>
+    /// Gets the mapping from node ids of the previous tree to node ids of this tree when the result was
+    /// produced by an incremental reparse; empty otherwise.
+    /// </summary>
+    public NodeIdMap NodeIdMap { get; internal set; } = NodeIdMap.Empty;
+
+    /// <summary>
+    /// Gets the operator table after the file's declarations when the grammar has an operator layer; null otherwise.
+    /// </summary>
+    public OperatorTable? Operators { get; init; }
+
+    /// <summary>
+    /// Gets the lexical and syntax diagnostics, followed by those of the deferred regions parsed so far.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics
+    {
+        get => Deferred?.AppendDiagnostics(_diagnostics) ?? _diagnostics;
+        init => _diagnostics = value;
+    }
+
+    /// <summary>
+    /// Gets the regions a <see cref="ParseOptions.Lazy"/> parse skipped, or null if it skipped none.
+    /// </summary>
+    public DeferredRegions? Deferred { get; internal set; }
+
+    /// <summary>
+    /// Gets the length of the input the tree covers: all of it, unless a <see cref="EntryPointEnd.Prefix"/> entry
+    /// point accepted a shorter prefix; 0 if parsing failed.
+    /// </summary>
+    public int ParsedLength { get; init; }
+
+    /// <summary>
+    /// Gets the recorded parse events when <see cref="ParseOptions.RecordEvents"/> was set, or the log a
+    /// replayed result came from; null otherwise.
+    /// </summary>
+    public ParseLog? EventLog { get; internal set; }
+
+    /// <summary>
+    /// Gets the bytes allocated building the result, or null if none were accounted.
+    /// </summary>
+    internal MemoryLedger? Memory { get; init; }
+
+    /// <summary>
+    /// Gets the decisions behind the tree when <see cref="ParseOptions.RecordDerivations"/> was set; null otherwise.
+    /// </summary>
+    internal DerivationLog? Derivations { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether the input parsed without errors.
+    /// </summary>
+    public bool IsSuccess => Tree != null && !Diagnostics.Any(d => d.Severity == DiagnosticSeverity.Error);
+
+    /// <summary>
+    /// Gets a value indicating whether the input has more than one derivation.
+    /// </summary>
+    public bool IsAmbiguous => Forest?.AmbiguityCount > 0;
+
+    /// <summary>
+    /// Gets a breakdown of the memory held by the result. The tree nodes and their child lists together are the
+    /// tree's arena: the allocations made building it and nothing else.
+    /// </summary>
+    /// <returns>The memory report.</returns>
+    public MemoryReport GetMemoryReport()
+    {
+        var nodes = 0;
+        var parents = 0;
+        if (Tree != null)
+        {
+            var pending = new Stack<CognitiveGraphNode>();
+            pending.Push(Tree);
+            while (pending.Count > 0)
+            {
+                var node = pending.Pop();
+                nodes++;
+                parents += node.Children.Count > 0 ? 1 : 0;
+                foreach (var child in node.Children)
+                {
+                    pending.Push(child);
+                }
+            }
+        }
+
+        return new MemoryReport($"Parse of <{StartRule}>", new[]
+        {
+            new MemoryReportEntry(MemoryCategories.Tokens, Tokens.Count, MemoryLedger.Get(Memory, MemoryCategories.Tokens)),
+            new MemoryReportEntry(MemoryCategories.Forest, Forest?.Nodes.Count ?? 0, MemoryLedger.Get(Memory, MemoryCategories.Forest)),
+            new MemoryReportEntry(MemoryCategories.TreeNodes, nodes, MemoryLedger.Get(Memory, MemoryCategories.TreeNodes)),
+            new MemoryReportEntry(MemoryCategories.ChildLists, parents, MemoryLedger.Get(Memory, MemoryCategories.ChildLists))
+        });
+    }
+
+    /// <summary>
+    /// Explains why a node of the parse tree has its subtree: for every node in it, the alternative chosen, the
+    /// lookahead token that selected it and whether it was preferred over other derivations, and for operator
+    /// expressions the declarations whose precedence or associativity grouped them.
+    /// </summary>
+    /// <param name="nodeId">The id of a node of <see cref="Tree"/>.</param>
+    /// <returns>The explanation.</returns>
+    /// <exception cref="InvalidOperationException">Parsing failed or the parse did not record derivations.</exception>
+    /// <exception cref="ArgumentException">The node is not in the tree.</exception>
+    public DerivationExplanation ExplainNode(Guid nodeId)
+    {
+        if (Tree == null)
+        {
+            throw new InvalidOperationException("No parse tree is available because parsing failed");
+        }
+
+        if (Derivations == null)
+        {
+            throw new InvalidOperationException("Derivations were not recorded; parse with ParseOptions.RecordDerivations set");
+        }
+
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(Tree);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node.Id == nodeId)
+            {
+                return Derivations.Explain(node);
+            }
+
+            foreach (var child in node.Children)
+            {
+                pending.Push(child);
+            }
+        }
+
+        throw new ArgumentException($"Node {nodeId} is not in the parse tree", nameof(nodeId));
+    }
+
+    /// <summary>
+    /// Gets where a node of <see cref="Tree"/> is: the nodes of the grammar's <see cref="CompiledGrammar.OutlineKey"/>
+    /// rules enclosing it, itself included, outermost first. Paths are cached along the ancestor chain, so the paths of
+    /// the nodes of one declaration share their computation.
+    /// </summary>
+    /// <param name="node">A node of <see cref="Tree"/>.</param>
+    /// <returns>The path; empty if the grammar lists no outline rules or no entry encloses the node.</returns>
+    public QualifiedPath GetQualifiedPath(CognitiveGraphNode node)
+    {
+        ArgumentNullException.ThrowIfNull(node);
+
+        return Grammar is { HasOutlineRules: true } ? (_paths ??= new QualifiedPathResolver(this, Grammar)).Get(node) : QualifiedPath.Empty;
+    }
+
+    /// <summary>
+    /// Gets the path of the innermost node of <see cref="Tree"/> spanning a position.
+    /// </summary>
+    /// <param name="position">The position, such as a diagnostic's location.</param>
+    /// <returns>The path; empty if no node spans the position.</returns>
+    public QualifiedPath GetQualifiedPath(SourcePosition position)
+    {
+        ArgumentNullException.ThrowIfNull(position);
+
+        return Tree?.FindNodeAt(position) is { } node ? GetQualifiedPath(node) : QualifiedPath.Empty;
+    }
+
+    /// <summary>
+    /// Gets a view of the parse tree.
+    /// </summary>
+    /// <param name="view">The view.</param>
+    /// <returns>The root of the view, or null if parsing failed.</returns>
+    public CognitiveGraphNode? GetTree(TreeView view)
+    {
+        return view == TreeView.Ast ? Ast : Tree;
+    }
+
+    /// <summary>
+    /// Walks a view of the parse tree with a visitor; visitors see the AST view unless asked otherwise.
+    /// </summary>
+    /// <param name="visitor">The visitor.</param>
+    /// <param name="view">The view to walk.</param>
+    /// <exception cref="InvalidOperationException">Parsing failed.</exception>
+    public void Accept(ICognitiveGraphVisitor visitor, TreeView view = TreeView.Ast)
+    {
+        ArgumentNullException.ThrowIfNull(visitor);
+
+        var root = GetTree(view) ?? throw new InvalidOperationException("No parse tree is available because parsing failed");
+        visitor.Visit(root);
+    }
+
+    /// <summary>
+    /// Renders the parse forest as a self-contained HTML page with ambiguities shown side by side.
+    /// </summary>
+    /// <returns>The HTML document.</returns>
+    public string ForestHtml()
+    {
+        if (Forest == null)
+        {
+            throw new InvalidOperationException("No parse forest is available because parsing failed");
+        }
+
+        return new ParseForestHtmlRenderer().Render(this);
+    }
+}
@@ -0,0 +1,374 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Buffers.Binary;
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Reads a parse tree buffer written by <see cref="ParseTreeExport.ToBytes(ParseResult)"/> in place: nodes, strings
+/// and spans are decoded from the buffer when they are asked for.
+/// </summary>
+public sealed class ParseTreeBuffer
+{
+    private readonly ReadOnlyMemory<byte> _buffer;
+    private readonly int _stringCount;
+    private readonly int _stringOffsetsOffset;
+    private readonly int _stringDataOffset;
+    private readonly int _nodesOffset;
+    private readonly int _nodeRecordSize;
+    private readonly int _spansOffset;
+    private readonly int _diagnosticsOffset;
+    private readonly int _diagnosticRecordSize;
+
+    private ParseTreeBuffer(ReadOnlyMemory<byte> buffer)
+    {
+        var span = buffer.Span;
+        if (span.Length < ParseTreeExport.HeaderSize || !span[..4].SequenceEqual(ParseTreeExport.Magic))
+        {
+            throw new FormatException("The buffer is not a parse tree buffer");
+        }
+
+        Version = BinaryPrimitives.ReadUInt16LittleEndian(span[4..]);
+        if (Version > ParseTreeExport.FormatVersion)
+        {
+            throw new FormatException($"Unsupported parse tree buffer version {Version}; this reader supports up to version {ParseTreeExport.FormatVersion}");
+        }
+
+        _buffer = buffer;
+        _nodeRecordSize = BinaryPrimitives.ReadUInt16LittleEndian(span[6..]);
+        _stringCount = ReadInt32(8);
+        _stringOffsetsOffset = ReadInt32(12);
+        _stringDataOffset = ReadInt32(16);
+        NodeCount = ReadInt32(20);
+        _nodesOffset = ReadInt32(24);
+        _spansOffset = ReadInt32(28);
+        DiagnosticCount = ReadInt32(32);
+        _diagnosticsOffset = ReadInt32(36);
+        _diagnosticRecordSize = ReadInt32(40);
+
+        if (_nodeRecordSize < ParseTreeExport.NodeRecordSize
+            || _diagnosticRecordSize < ParseTreeExport.DiagnosticRecordSize
+            || NodeCount < 1
+            || (long)_nodesOffset + (long)NodeCount * _nodeRecordSize > span.Length
+            || _spansOffset > span.Length
+            || (long)_diagnosticsOffset + (long)DiagnosticCount * _diagnosticRecordSize > span.Length
+            || (long)_stringOffsetsOffset + ((long)_stringCount + 1) * 4 > span.Length)
+        {
+            throw new FormatException("The parse tree buffer is truncated or corrupt");
+        }
+    }
+
+    /// <summary>
+    /// Gets the format version the buffer was written with.
+    /// </summary>
+    public int Version { get; }
+
+    /// <summary>
+    /// Gets the number of nodes in the tree.
+    /// </summary>
+    public int NodeCount { get; }
+
+    /// <summary>
+    /// Gets the number of diagnostics in the buffer.
+    /// </summary>
+    public int DiagnosticCount { get; }
+
+    /// <summary>
+    /// Gets the number of interned strings in the buffer.
+    /// </summary>
+    public int StringCount => _stringCount;
+
+    /// <summary>
+    /// Gets the root node of the tree.
+    /// </summary>
+    public BufferNode Root => GetNode(0);
+
+    /// <summary>
+    /// Opens a buffer without copying it.
+    /// </summary>
+    /// <param name="buffer">The buffer.</param>
+    /// <returns>The reader.</returns>
+    /// <exception cref="FormatException">The buffer is not a parse tree buffer, is newer than this reader or is truncated.</exception>
+    public static ParseTreeBuffer Open(ReadOnlyMemory<byte> buffer)
+    {
+        return new ParseTreeBuffer(buffer);
+    }
+
+    /// <summary>
+    /// Gets the node at an index; the root is node 0 and the children of a node have consecutive indices.
+    /// </summary>
+    /// <param name="index">The node index.</param>
+    /// <returns>The node.</returns>
+    public BufferNode GetNode(int index)
+    {
+        if ((uint)index >= (uint)NodeCount)
+        {
+            throw new ArgumentOutOfRangeException(nameof(index));
+        }
+
+        return new BufferNode(this, index);
+    }
+
+    /// <summary>
+    /// Gets the UTF-8 bytes of an interned string without decoding them.
+    /// </summary>
+    /// <param name="index">The string index.</param>
+    /// <returns>The bytes.</returns>
+    public ReadOnlySpan<byte> GetStringUtf8(int index)
+    {
+        if ((uint)index >= (uint)_stringCount)
+        {
+            throw new ArgumentOutOfRangeException(nameof(index));
+        }
+
+        var start = ReadInt32(_stringOffsetsOffset + index * 4);
+        var end = ReadInt32(_stringOffsetsOffset + (index + 1) * 4);
+        return _buffer.Span.Slice(_stringDataOffset + start, end - start);
+    }
+
+    /// <summary>
+    /// Gets an interned string.
+    /// </summary>
+    /// <param name="index">The string index.</param>
+    /// <returns>The string.</returns>
+    public string GetString(int index)
+    {
+        return Encoding.UTF8.GetString(GetStringUtf8(index));
+    }
+
+    /// <summary>
+    /// Gets a diagnostic from the buffer.
+    /// </summary>
+    /// <param name="index">The diagnostic index.</param>
+    /// <returns>The diagnostic.</returns>
+    public Diagnostic GetDiagnostic(int index)
+    {
+        if ((uint)index >= (uint)DiagnosticCount)
+        {
+            throw new ArgumentOutOfRangeException(nameof(index));
+        }
+
+        var record = _diagnosticsOffset + index * _diagnosticRecordSize;
+        return new Diagnostic
+        {
+            Code = GetString(ReadInt32(record)),
+            Message = GetString(ReadInt32(record + 4)),
+            Severity = (DiagnosticSeverity)_buffer.Span[record + 8],
+            Location = ReadSpan(ReadInt32(record + 12))
+        };
+    }
+
+    /// <summary>
+    /// Gets all diagnostics in the buffer.
+    /// </summary>
+    /// <returns>The diagnostics.</returns>
+    public IReadOnlyList<Diagnostic> GetDiagnostics()
+    {
+        var diagnostics = new List<Diagnostic>(DiagnosticCount);
+        for (var i = 0; i < DiagnosticCount; i++)
+        {
+            diagnostics.Add(GetDiagnostic(i));
+        }
+
+        return diagnostics;
+    }
+
+    /// <summary>
+    /// Decodes the whole buffer into a tree of <see cref="CognitiveGraphNode"/>s.
+    /// </summary>
+    /// <returns>The root of the tree.</returns>
+    public CognitiveGraphNode ToTree()
+    {
+        var nodes = new CognitiveGraphNode[NodeCount];
+        for (var i = 0; i < NodeCount; i++)
+        {
+            var source = GetNode(i);
+            CognitiveGraphNode node = source.IsTerminal
+                ? new TerminalNode(source.Text!, source.Name)
+                : new NonTerminalNode(source.Name, source.ProductionIndex);
+            node.SourcePosition = source.Span;
+            if (source.IsSynthetic)
+            {
+                node.Metadata[TerminatorPolicy.SyntheticMetadataKey] = true;
+            }
+
+            if (source.IsAmbiguous)
+            {
+                node.Metadata["ambiguous"] = source.Derivations;
+            }
+
+            nodes[i] = node;
+        }
+
+        // Breadth-first order creates every parent before its children.
+        for (var i = 1; i < NodeCount; i++)
+        {
+            nodes[ReadInt32(NodeRecord(i) + 12)].AddChild(nodes[i]);
+        }
+
+        return nodes[0];
+    }
+
+    internal int NodeRecord(int index) => _nodesOffset + index * _nodeRecordSize;
+
+    internal byte ReadByte(int offset) => _buffer.Span[offset];
+
+    internal ReadOnlySpan<byte> Slice(int offset, int length) => _buffer.Span.Slice(offset, length);
+
+    internal int ReadInt32(int offset) => BinaryPrimitives.ReadInt32LittleEndian(_buffer.Span[offset..]);
+
+    internal SourcePosition? ReadSpan(int offset)
+    {
+        if (offset < 0)
+        {
+            return null;
+        }
+
+        var position = _spansOffset + offset;
+        var start = ReadVarint(ref position);
+        var length = ReadVarint(ref position);
+        var line = ReadVarint(ref position);
+        var column = ReadVarint(ref position);
+        var endLine = line + ReadVarint(ref position);
+        var endColumn = ReadVarint(ref position);
+        var sourceFile = ReadVarint(ref position);
+        return new SourcePosition(line, column, start, length)
+        {
+            EndLine = endLine,
+            EndColumn = endColumn,
+            SourceFile = sourceFile > 0 ? GetString(sourceFile - 1) : null
+        };
+    }
+
+    private int ReadVarint(ref int position)
+    {
+        var span = _buffer.Span;
+        var value = 0;
+        for (var shift = 0; shift < 35; shift += 7)
+        {
+            var b = span[position++];
+            value |= (b & 0x7F) << shift;
+            if (b < 0x80)
+            {
+                return value;
+            }
+        }
+
+        throw new FormatException("The parse tree buffer contains a malformed varint");
+    }
+}
+
+/// <summary>
+/// A node of a <see cref="ParseTreeBuffer"/>, read from the buffer when its members are accessed.
+/// </summary>
+public readonly struct BufferNode
+{
+    private readonly ParseTreeBuffer _buffer;
+    private readonly int _record;
+
+    internal BufferNode(ParseTreeBuffer buffer, int index)
+    {
+        _buffer = buffer;
+        Index = index;
+        _record = buffer.NodeRecord(index);
+    }
+
+    /// <summary>
+    /// Gets the index of the node in the buffer.
+    /// </summary>
+    public int Index { get; }
+
+    /// <summary>
+    /// Gets whether the node is a token.
+    /// </summary>
+    public bool IsTerminal => _buffer.ReadByte(_record) == 1;
+
+    /// <summary>
+    /// Gets whether the node is a token inserted by error recovery or a token filter.
+    /// </summary>
+    public bool IsSynthetic => (_buffer.ReadByte(_record + 1) & 1) != 0;
+
+    /// <summary>
+    /// Gets whether the node had more than one derivation.
+    /// </summary>
+    public bool IsAmbiguous => (_buffer.ReadByte(_record + 1) & 2) != 0;
+
+    /// <summary>
+    /// Gets the number of derivations of an ambiguous node, or 0.
+    /// </summary>
+    public int Derivations => BinaryPrimitives.ReadUInt16LittleEndian(_buffer.Slice(_record + 2, 2));
+
+    /// <summary>
+    /// Gets the rule name of a rule node or the token kind of a token.
+    /// </summary>
+    public string Name => _buffer.GetString(_buffer.ReadInt32(_record + 4));
+
+    /// <summary>
+    /// Gets the UTF-8 bytes of <see cref="Name"/> without decoding them.
+    /// </summary>
+    public ReadOnlySpan<byte> NameUtf8 => _buffer.GetStringUtf8(_buffer.ReadInt32(_record + 4));
+
+    /// <summary>
+    /// Gets the text of a token, or null for a rule node.
+    /// </summary>
+    public string? Text => IsTerminal ? _buffer.GetString(_buffer.ReadInt32(_record + 8)) : null;
+
+    /// <summary>
+    /// Gets the production index of a rule node, or -1 for a token.
+    /// </summary>
+    public int ProductionIndex => IsTerminal ? -1 : _buffer.ReadInt32(_record + 8);
+
+    /// <summary>
+    /// Gets the index of the parent node, or -1 for the root.
+    /// </summary>
+    public int ParentIndex => _buffer.ReadInt32(_record + 12);
+
+    /// <summary>
+    /// Gets the number of children.
+    /// </summary>
+    public int ChildCount => _buffer.ReadInt32(_record + 20);
+
+    /// <summary>
+    /// Gets the source span of the node, or null when it has none.
+    /// </summary>
+    public SourcePosition? Span => _buffer.ReadSpan(_buffer.ReadInt32(_record + 24));
+
+    /// <summary>
+    /// Gets a child of the node.
+    /// </summary>
+    /// <param name="index">The child's position among the node's children.</param>
+    /// <returns>The child.</returns>
+    public BufferNode GetChild(int index)
+    {
+        if ((uint)index >= (uint)ChildCount)
+        {
+            throw new ArgumentOutOfRangeException(nameof(index));
+        }
+
+        return _buffer.GetNode(_buffer.ReadInt32(_record + 16) + index);
+    }
+
+    /// <inheritdoc />
+    public override string ToString()
+    {
+        return IsTerminal ? $"{Name} '{Text}'" : $"<{Name}>";
+    }
+}
@@ -0,0 +1,191 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Monitoring;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Builds a cognitive graph tree from the preferred derivation of a parse forest,
+/// optionally reusing subtrees from a previous parse.
+/// </summary>
+internal sealed class ParseTreeBuilder
+{
+    private readonly string? _sourceFile;
+    private readonly Func<SymbolForestNode, CognitiveGraphNode?>? _reuse;
+
+    public ParseTreeBuilder(IReadOnlyList<Token> tokens, LineIndex lineIndex, string? sourceFile, Func<SymbolForestNode, CognitiveGraphNode?>? reuse = null)
+    {
+        Tokens = tokens;
+        LineIndex = lineIndex;
+        _sourceFile = sourceFile;
+        _reuse = reuse;
+    }
+
+    public LineIndex LineIndex { get; }
+
+    // Replaced by the parser when a terminator policy inserts tokens.
+    public IReadOnlyList<Token> Tokens { get; set; }
+
+    public int CreatedNodeCount { get; private set; }
+
+    public int ReusedNodeCount { get; private set; }
+
+    // The bytes allocated for the parse, handed to its result; the parser records its tokens and forest here too.
+    public MemoryLedger? Memory;
+
+    // The decisions behind every built node, when the parse records them.
+    public DerivationLog? Derivations;
+
+    // The regions of a lazy parse, which its skipped bracket groups become placeholders of.
+    public DeferredRegions? Deferred;
+
+    // Where the tokens end when they are one document of a larger input; end-of-input errors are reported there.
+    public int? EndOffset;
+
+    public CognitiveGraphNode Build(SymbolForestNode node)
+    {
+        // Nodes are created in preorder from a heap stack, so nesting depth is not limited by the thread's stack.
+        // Siblings are pushed in reverse, so each parent receives its children left to right.
+        CognitiveGraphNode? root = null;
+        var pending = new Stack<(ForestNode Node, NonTerminalNode? Parent)>();
+        pending.Push((node, null));
+
+        while (pending.Count > 0)
+        {
+            var (current, parent) = pending.Pop();
+            CognitiveGraphNode built;
+            if (current is SymbolForestNode symbol)
+            {
+                built = BuildSymbol(symbol, pending);
+            }
+            else if (current is TokenForestNode leaf)
+            {
+                built = BuildLeaf(leaf);
+            }
+            else
+            {
+                continue;
+            }
+
+            if (parent == null)
+            {
+                root = built;
+            }
+            else
+            {
+                long mark = 0;
+                MemoryLedger.Mark(ref mark);
+                parent.AddChild(built);
+                MemoryLedger.Record(ref Memory, MemoryCategories.ChildLists, mark);
+            }
+        }
+
+        return root!;
+    }
+
+    private CognitiveGraphNode BuildSymbol(SymbolForestNode node, Stack<(ForestNode Node, NonTerminalNode? Parent)> pending)
+    {
+        if (_reuse?.Invoke(node) is { } reused)
+        {
+            ReusedNodeCount += CountNodes(reused);
+            return reused;
+        }
+
+        // The first packed node is the derivation using the earliest alternatives.
+        long mark = 0;
+        MemoryLedger.Mark(ref mark);
+        var packed = node.Packed[0];
+        if (Deferred != null && packed.Alternative == node.Rule.Placeholder)
+        {
+            CreatedNodeCount++;
+            MemoryLedger.Record(ref Memory, MemoryCategories.TreeNodes, mark);
+            return Deferred.Add(node.Rule, SpanPosition(node), Tokens, node.Start, node.End);
+        }
+
+        var tree = new NonTerminalNode(node.RuleName, packed.Alternative.Index)
+        {
+            SourcePosition = SpanPosition(node)
+        };
+        CreatedNodeCount++;
+
+        if (node.IsAmbiguous)
+        {
+            tree.Metadata["ambiguous"] = node.Packed.Count;
+        }
+
+        Derivations?.RecordAlternative(tree, node, Tokens, LineIndex, _sourceFile);
+
+        MemoryLedger.Record(ref Memory, MemoryCategories.TreeNodes, mark);
+
+        for (var i = packed.Children.Count - 1; i >= 0; i--)
+        {
+            pending.Push((packed.Children[i], tree));
+        }
+
+        return tree;
+    }
+
+    private TerminalNode BuildLeaf(TokenForestNode leaf)
+    {
+        long mark = 0;
+        MemoryLedger.Mark(ref mark);
+        var terminal = new TerminalNode(leaf.Token.Text, leaf.Token.Kind)
+        {
+            SourcePosition = LineIndex.GetPosition(leaf.Token.Offset, leaf.Token.Length, _sourceFile)
+        };
+        if (leaf.Token.IsSynthetic)
+        {
+            terminal.Metadata[TerminatorPolicy.SyntheticMetadataKey] = true;
+        }
+
+        CreatedNodeCount++;
+        MemoryLedger.Record(ref Memory, MemoryCategories.TreeNodes, mark);
+        return terminal;
+    }
+
+    private SourcePosition SpanPosition(ForestNode node)
+    {
+        if (node.Start == node.End)
+        {
+            var offset = node.Start < Tokens.Count ? Tokens[node.Start].Offset : LineIndex.Text.Length;
+            return LineIndex.GetPosition(offset, 0, _sourceFile);
+        }
+
+        var start = Tokens[node.Start].Offset;
+        return LineIndex.GetPosition(start, Tokens[node.End - 1].End - start, _sourceFile);
+    }
+
+    private static int CountNodes(CognitiveGraphNode node)
+    {
+        var count = 0;
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(node);
+        while (pending.Count > 0)
+        {
+            var current = pending.Pop();
+            count++;
+            foreach (var child in current.Children)
+            {
+                pending.Push(child);
+            }
+        }
+
+        return count;
+    }
+}
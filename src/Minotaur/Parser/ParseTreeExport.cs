@@ -0,0 +1,343 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Buffers.Binary;
+using System.Text;
+using System.Text.Json;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Exports parse trees and their diagnostics for other processes: as a compact binary buffer read in place by
+/// <see cref="ParseTreeBuffer"/>, or as JSON.
+/// </summary>
+/// <remarks>
+/// <para>
+/// The binary format is little-endian and starts with a header of <see cref="HeaderSize"/> bytes: the magic
+/// <c>MTRB</c>, the format version and node record size as 16-bit integers, then, as 32-bit integers, the string
+/// count, the offsets of the string offset table and the string data, the node count and the offset of the node
+/// records, the offset of the span data, and the diagnostic count, the offset of the diagnostic records and their
+/// record size.
+/// </para>
+/// <para>
+/// Strings are interned: rule names, token kinds and texts, source files and diagnostic codes and messages are
+/// stored once as UTF-8, found through a table of count + 1 offsets into the string data. Nodes are stored in
+/// breadth-first order, so the children of a node are consecutive records, as fixed-size records of a kind byte
+/// (0 for rules, 1 for tokens), a flags byte (1 synthetic, 2 ambiguous), the number of derivations of an ambiguous
+/// node as a 16-bit integer, the name string, the
+/// token's text string or the rule's production index, the parent, first child and child count, and the offset
+/// of the node's span in the span data. A span is a run of unsigned LEB128 varints: offset, length, line, column,
+/// end line minus line, end column and source file string + 1 (0 for none). Diagnostic records hold the code and
+/// message strings, the severity byte, three reserved bytes and the span offset. Missing strings, children and
+/// spans are written as -1.
+/// </para>
+/// <para>
+/// Readers accept buffers of their own version and older ones, and use the record size from the header, so a
+/// later version can append fields to records and sections to the buffer without breaking them.
+/// </para>
+/// </remarks>
+public static class ParseTreeExport
+{
+    /// <summary>
+    /// The version of the binary format written by <see cref="ToBytes(ParseResult)"/>.
+    /// </summary>
+    public const int FormatVersion = 1;
+
+    /// <summary>
+    /// The size of the binary header in bytes.
+    /// </summary>
+    public const int HeaderSize = 44;
+
+    /// <summary>
+    /// The size of a node record written by this version.
+    /// </summary>
+    public const int NodeRecordSize = 28;
+
+    /// <summary>
+    /// The size of a diagnostic record written by this version.
+    /// </summary>
+    public const int DiagnosticRecordSize = 16;
+
+    internal static ReadOnlySpan<byte> Magic => "MTRB"u8;
+
+    /// <summary>
+    /// Encodes the tree and diagnostics of a parse in the binary format.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    /// <returns>The encoded buffer.</returns>
+    /// <exception cref="InvalidOperationException">The parse has no tree.</exception>
+    public static byte[] ToBytes(ParseResult result)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+
+        var tree = result.Tree ?? throw new InvalidOperationException("No parse tree is available because parsing failed");
+        return ToBytes(tree, result.Diagnostics);
+    }
+
+    /// <summary>
+    /// Encodes a tree and diagnostics in the binary format.
+    /// </summary>
+    /// <param name="tree">The root of the tree.</param>
+    /// <param name="diagnostics">The diagnostics to include.</param>
+    /// <returns>The encoded buffer.</returns>
+    public static byte[] ToBytes(CognitiveGraphNode tree, IReadOnlyList<Diagnostic> diagnostics)
+    {
+        ArgumentNullException.ThrowIfNull(tree);
+        ArgumentNullException.ThrowIfNull(diagnostics);
+
+        var strings = new StringTable();
+        var spans = new MemoryStream();
+
+        // Breadth-first order keeps each node's children consecutive.
+        var order = new List<CognitiveGraphNode> { tree };
+        var parents = new List<int> { -1 };
+        var records = new List<byte[]>();
+        for (var i = 0; i < order.Count; i++)
+        {
+            var node = order[i];
+            var firstChild = node.Children.Count > 0 ? order.Count : -1;
+            foreach (var child in node.Children)
+            {
+                order.Add(child);
+                parents.Add(i);
+            }
+
+            var record = new byte[NodeRecordSize];
+            var terminal = node as TerminalNode;
+            record[0] = (byte)(terminal != null ? 1 : 0);
+            var derivations = node.Metadata.TryGetValue("ambiguous", out var count) && count is int n ? n : 0;
+            record[1] = (byte)((node.Metadata.ContainsKey(TerminatorPolicy.SyntheticMetadataKey) ? 1 : 0) | (derivations > 0 ? 2 : 0));
+            BinaryPrimitives.WriteUInt16LittleEndian(record.AsSpan(2), (ushort)Math.Min(derivations, ushort.MaxValue));
+            BinaryPrimitives.WriteInt32LittleEndian(record.AsSpan(4), strings.Add(terminal?.TokenType ?? ((NonTerminalNode)node).RuleName));
+            BinaryPrimitives.WriteInt32LittleEndian(record.AsSpan(8), terminal != null ? strings.Add(terminal.Text) : ((NonTerminalNode)node).ProductionIndex);
+            BinaryPrimitives.WriteInt32LittleEndian(record.AsSpan(12), parents[i]);
+            BinaryPrimitives.WriteInt32LittleEndian(record.AsSpan(16), firstChild);
+            BinaryPrimitives.WriteInt32LittleEndian(record.AsSpan(20), node.Children.Count);
+            BinaryPrimitives.WriteInt32LittleEndian(record.AsSpan(24), WriteSpan(spans, node.SourcePosition, strings));
+            records.Add(record);
+        }
+
+        var diagnosticRecords = new List<byte[]>();
+        foreach (var diagnostic in diagnostics)
+        {
+            var record = new byte[DiagnosticRecordSize];
+            BinaryPrimitives.WriteInt32LittleEndian(record, strings.Add(diagnostic.Code));
+            BinaryPrimitives.WriteInt32LittleEndian(record.AsSpan(4), strings.Add(diagnostic.Message));
+            record[8] = (byte)diagnostic.Severity;
+            BinaryPrimitives.WriteInt32LittleEndian(record.AsSpan(12), WriteSpan(spans, diagnostic.Location, strings));
+            diagnosticRecords.Add(record);
+        }
+
+        var (offsets, data) = strings.Encode();
+        var stringOffsetsOffset = HeaderSize;
+        var stringDataOffset = stringOffsetsOffset + offsets.Length;
+        var nodesOffset = stringDataOffset + data.Length;
+        var spansOffset = nodesOffset + records.Count * NodeRecordSize;
+        var diagnosticsOffset = spansOffset + (int)spans.Length;
+
+        var buffer = new byte[diagnosticsOffset + diagnosticRecords.Count * DiagnosticRecordSize];
+        Magic.CopyTo(buffer);
+        BinaryPrimitives.WriteUInt16LittleEndian(buffer.AsSpan(4), FormatVersion);
+        BinaryPrimitives.WriteUInt16LittleEndian(buffer.AsSpan(6), NodeRecordSize);
+        BinaryPrimitives.WriteInt32LittleEndian(buffer.AsSpan(8), strings.Count);
+        BinaryPrimitives.WriteInt32LittleEndian(buffer.AsSpan(12), stringOffsetsOffset);
+        BinaryPrimitives.WriteInt32LittleEndian(buffer.AsSpan(16), stringDataOffset);
+        BinaryPrimitives.WriteInt32LittleEndian(buffer.AsSpan(20), records.Count);
+        BinaryPrimitives.WriteInt32LittleEndian(buffer.AsSpan(24), nodesOffset);
+        BinaryPrimitives.WriteInt32LittleEndian(buffer.AsSpan(28), spansOffset);
+        BinaryPrimitives.WriteInt32LittleEndian(buffer.AsSpan(32), diagnosticRecords.Count);
+        BinaryPrimitives.WriteInt32LittleEndian(buffer.AsSpan(36), diagnosticsOffset);
+        BinaryPrimitives.WriteInt32LittleEndian(buffer.AsSpan(40), DiagnosticRecordSize);
+
+        offsets.CopyTo(buffer, stringOffsetsOffset);
+        data.CopyTo(buffer, stringDataOffset);
+        for (var i = 0; i < records.Count; i++)
+        {
+            records[i].CopyTo(buffer, nodesOffset + i * NodeRecordSize);
+        }
+
+        spans.ToArray().CopyTo(buffer, spansOffset);
+        for (var i = 0; i < diagnosticRecords.Count; i++)
+        {
+            diagnosticRecords[i].CopyTo(buffer, diagnosticsOffset + i * DiagnosticRecordSize);
+        }
+
+        return buffer;
+    }
+
+    /// <summary>
+    /// Writes the tree and diagnostics of a parse as JSON: <c>{"tree": node, "diagnostics": [...]}</c>, where a
+    /// rule node is <c>{"rule", "production", "span", "children"}</c> and a token node
+    /// <c>{"token", "text", "span"}</c>.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    /// <returns>The JSON text.</returns>
+    /// <exception cref="InvalidOperationException">The parse has no tree.</exception>
+    public static string ToJson(ParseResult result)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+
+        var tree = result.Tree ?? throw new InvalidOperationException("No parse tree is available because parsing failed");
+        using var stream = new MemoryStream();
+        using (var writer = new Utf8JsonWriter(stream))
+        {
+            writer.WriteStartObject();
+            writer.WritePropertyName("tree");
+
+            // A closing entry sits below a node's children and ends its children array and object.
+            var pending = new Stack<(CognitiveGraphNode Node, bool Closing)>();
+            pending.Push((tree, false));
+            while (pending.Count > 0)
+            {
+                var (node, closing) = pending.Pop();
+                if (closing)
+                {
+                    writer.WriteEndArray();
+                    writer.WriteEndObject();
+                    continue;
+                }
+
+                writer.WriteStartObject();
+                if (node is TerminalNode terminal)
+                {
+                    writer.WriteString("token", terminal.TokenType);
+                    writer.WriteString("text", terminal.Text);
+                }
+                else
+                {
+                    var nonTerminal = (NonTerminalNode)node;
+                    writer.WriteString("rule", nonTerminal.RuleName);
+                    writer.WriteNumber("production", nonTerminal.ProductionIndex);
+                }
+
+                WriteJsonSpan(writer, node.SourcePosition);
+                if (node.Children.Count == 0)
+                {
+                    writer.WriteEndObject();
+                    continue;
+                }
+
+                writer.WriteStartArray("children");
+                pending.Push((node, true));
+                for (var i = node.Children.Count - 1; i >= 0; i--)
+                {
+                    pending.Push((node.Children[i], false));
+                }
+            }
+
+            writer.WriteStartArray("diagnostics");
+            foreach (var diagnostic in result.Diagnostics)
+            {
+                writer.WriteStartObject();
+                writer.WriteString("code", diagnostic.Code);
+                writer.WriteString("severity", diagnostic.Severity.ToString());
+                writer.WriteString("message", diagnostic.Message);
+                WriteJsonSpan(writer, diagnostic.Location);
+                writer.WriteEndObject();
+            }
+
+            writer.WriteEndArray();
+            writer.WriteEndObject();
+        }
+
+        return Encoding.UTF8.GetString(stream.ToArray());
+    }
+
+    private static int WriteSpan(MemoryStream spans, SourcePosition? position, StringTable strings)
+    {
+        if (position == null)
+        {
+            return -1;
+        }
+
+        var offset = (int)spans.Length;
+        WriteVarint(spans, position.Offset);
+        WriteVarint(spans, position.Length);
+        WriteVarint(spans, position.Line);
+        WriteVarint(spans, position.Column);
+        WriteVarint(spans, position.EndLine - position.Line);
+        WriteVarint(spans, position.EndColumn);
+        WriteVarint(spans, position.SourceFile != null ? strings.Add(position.SourceFile) + 1 : 0);
+        return offset;
+    }
+
+    private static void WriteVarint(MemoryStream stream, int value)
+    {
+        var remaining = (uint)Math.Max(value, 0);
+        while (remaining >= 0x80)
+        {
+            stream.WriteByte((byte)(remaining | 0x80));
+            remaining >>= 7;
+        }
+
+        stream.WriteByte((byte)remaining);
+    }
+
+    internal static void WriteJsonSpan(Utf8JsonWriter writer, SourcePosition? position)
+    {
+        if (position == null)
+        {
+            return;
+        }
+
+        writer.WriteStartObject("span");
+        writer.WriteNumber("offset", position.Offset);
+        writer.WriteNumber("length", position.Length);
+        writer.WriteNumber("line", position.Line);
+        writer.WriteNumber("column", position.Column);
+        writer.WriteNumber("endLine", position.EndLine);
+        writer.WriteNumber("endColumn", position.EndColumn);
+        writer.WriteEndObject();
+    }
+
+    private sealed class StringTable
+    {
+        private readonly Dictionary<string, int> _indices = new(StringComparer.Ordinal);
+        private readonly List<string> _strings = new();
+
+        public int Count => _strings.Count;
+
+        public int Add(string value)
+        {
+            if (!_indices.TryGetValue(value, out var index))
+            {
+                index = _strings.Count;
+                _indices[value] = index;
+                _strings.Add(value);
+            }
+
+            return index;
+        }
+
+        public (byte[] Offsets, byte[] Data) Encode()
+        {
+            var offsets = new byte[(_strings.Count + 1) * 4];
+            var data = new MemoryStream();
+            for (var i = 0; i < _strings.Count; i++)
+            {
+                BinaryPrimitives.WriteInt32LittleEndian(offsets.AsSpan(i * 4), (int)data.Length);
+                var bytes = Encoding.UTF8.GetBytes(_strings[i]);
+                data.Write(bytes, 0, bytes.Length);
+            }
+
+            BinaryPrimitives.WriteInt32LittleEndian(offsets.AsSpan(_strings.Count * 4), (int)data.Length);
+            return (offsets, data.ToArray());
+        }
+    }
+}
@@ -0,0 +1,81 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Options for the parse progress watchdog. The watchdog measures how many tokens the parser consumes in each
+/// interval of wall-clock time and aborts the parse when that drops below a minimum, so inputs that make the
+/// parser spin are reported with where it was spinning instead of running for minutes.
+/// </summary>
+public class ParseWatchdogOptions
+{
+    /// <summary>
+    /// Gets or sets the length of a measurement interval.
+    /// </summary>
+    public TimeSpan Interval { get; set; } = TimeSpan.FromSeconds(1);
+
+    /// <summary>
+    /// Gets or sets the minimum number of tokens that must be consumed in each interval.
+    /// </summary>
+    public int MinTokensPerInterval { get; set; } = 1;
+}
+
+/// <summary>
+/// Tracks parse progress against <see cref="ParseWatchdogOptions"/>. The clock is read only every few hundred
+/// steps to keep the cost off the parser's inner loop.
+/// </summary>
+internal sealed class ParseWatchdog
+{
+    private const int StepsPerCheck = 256;
+
+    private readonly ParseWatchdogOptions _options;
+    private readonly Stopwatch _clock = Stopwatch.StartNew();
+    private TimeSpan _deadline;
+    private int _intervalStart;
+    private int _steps;
+
+    public ParseWatchdog(ParseWatchdogOptions options)
+    {
+        _options = options;
+        _deadline = options.Interval;
+    }
+
+    /// <summary>
+    /// Records a parser step at a token position and checks progress when an interval has elapsed.
+    /// </summary>
+    /// <returns>The number of tokens consumed in the interval that just ended, if it was below the minimum.</returns>
+    public int? Step(int position)
+    {
+        if (++_steps % StepsPerCheck != 0 || _clock.Elapsed < _deadline)
+        {
+            return null;
+        }
+
+        var consumed = position - _intervalStart;
+        if (consumed < _options.MinTokensPerInterval)
+        {
+            return consumed;
+        }
+
+        _intervalStart = position;
+        _deadline = _clock.Elapsed + _options.Interval;
+        return null;
+    }
+}
@@ -0,0 +1,445 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// How a span was carried across a <see cref="PositionMap"/>.
+/// </summary>
+public enum SpanMappingKind
+{
+    /// <summary>
+    /// Both ends of the span lie in text that was kept, so the mapped span covers the same tokens.
+    /// </summary>
+    Exact,
+
+    /// <summary>
+    /// An end of the span lies in text that was changed; the mapped span is widened to the whole of what that text
+    /// became, so it brackets the span's counterpart.
+    /// </summary>
+    Bracketing,
+
+    /// <summary>
+    /// The span lies entirely in text that the output no longer has; the mapped span is empty, where the text was.
+    /// </summary>
+    Deleted,
+
+    /// <summary>
+    /// The span lies entirely in output text that has no counterpart in the original; the mapped span is empty,
+    /// where the text was inserted.
+    /// </summary>
+    Synthesized
+}
+
+/// <summary>
+/// A span mapped through a <see cref="PositionMap"/>.
+/// </summary>
+/// <param name="Range">The mapped range.</param>
+/// <param name="Kind">How precisely the range corresponds to the span.</param>
+public readonly record struct MappedSpan(TextRange Range, SpanMappingKind Kind)
+{
+    /// <summary>
+    /// Gets a value indicating whether the range covers exactly the counterpart of the span.
+    /// </summary>
+    public bool IsExact => Kind == SpanMappingKind.Exact;
+}
+
+/// <summary>
+/// A piece of the correspondence between two texts: a range of the original and the range of the output it became.
+/// </summary>
+/// <param name="Original">The range of the original text.</param>
+/// <param name="Output">The range of the output text.</param>
+/// <param name="IsPreserved">Whether the two ranges hold the same text, character for character.</param>
+public sealed record PositionMapSegment(TextRange Original, TextRange Output, bool IsPreserved)
+{
+    /// <summary>
+    /// Gets a value indicating whether the original text was removed without replacement.
+    /// </summary>
+    public bool IsDeleted => !IsPreserved && Output.Length == 0;
+
+    /// <summary>
+    /// Gets a value indicating whether the output text was added with no original counterpart.
+    /// </summary>
+    public bool IsSynthesized => !IsPreserved && Original.Length == 0;
+}
+
+/// <summary>
+/// The correspondence between a text and the text a formatter, quick fix or rewrite made of it, at token
+/// granularity: tokens that survive are preserved segments, and whatever lies between them, whitespace included,
+/// is a changed segment. Spans map in both directions, so diagnostics computed on the output can be shown on the
+/// original buffer and diagnostics of the original can be shown in a preview of the output.
+/// </summary>
+public sealed class PositionMap
+{
+    private readonly List<PositionMapSegment> _segments;
+    private LineIndex? _originalLines;
+    private LineIndex? _outputLines;
+
+    private PositionMap(string original, string output, List<PositionMapSegment> segments)
+    {
+        Original = original;
+        Output = output;
+        _segments = segments;
+    }
+
+    /// <summary>
+    /// Gets the original text.
+    /// </summary>
+    public string Original { get; }
+
+    /// <summary>
+    /// Gets the output text.
+    /// </summary>
+    public string Output { get; }
+
+    /// <summary>
+    /// Gets the segments, which cover both texts in order.
+    /// </summary>
+    public IReadOnlyList<PositionMapSegment> Segments => _segments;
+
+    /// <summary>
+    /// Creates the map of a text that was left unchanged.
+    /// </summary>
+    /// <param name="text">The text.</param>
+    /// <returns>A map with a single preserved segment.</returns>
+    public static PositionMap Identity(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        return FromAnchors(text, text, new[] { (new TextRange(0, text.Length), new TextRange(0, text.Length)) });
+    }
+
+    /// <summary>
+    /// Creates the map of non-overlapping edits of a text. Text outside the edits is preserved; inside each edit,
+    /// the tokens the replaced and the replacing text have in common are preserved when a lexer is given, and
+    /// their common prefix and suffix otherwise.
+    /// </summary>
+    /// <param name="original">The text before the edits.</param>
+    /// <param name="edits">The edits, each relative to the original text, in any order; insertions at the same
+    /// offset appear in the order given.</param>
+    /// <param name="lexer">The lexer used to align the tokens inside edits, or null to align characters.</param>
+    /// <returns>The map from the original text to the edited one.</returns>
+    /// <exception cref="ArgumentException">Two edits overlap or an edit lies outside the text.</exception>
+    public static PositionMap FromEdits(string original, IEnumerable<TextEdit> edits, GrammarLexer? lexer = null)
+    {
+        ArgumentNullException.ThrowIfNull(original);
+        ArgumentNullException.ThrowIfNull(edits);
+
+        var output = new StringBuilder();
+        var anchors = new List<(TextRange Original, TextRange Output)>();
+        var position = 0;
+        foreach (var edit in edits.OrderBy(e => e.Offset).ThenBy(e => e.Length))
+        {
+            if (edit.Offset < position || edit.Offset + edit.Length > original.Length)
+            {
+                throw new ArgumentException($"Edit {edit} overlaps another edit or lies outside the text", nameof(edits));
+            }
+
+            anchors.Add((new TextRange(position, edit.Offset - position), new TextRange(output.Length, edit.Offset - position)));
+            output.Append(original, position, edit.Offset - position);
+
+            var replaced = original.Substring(edit.Offset, edit.Length);
+            foreach (var (from, to) in Align(replaced, edit.NewText, lexer))
+            {
+                anchors.Add((new TextRange(edit.Offset + from.Start, from.Length), new TextRange(output.Length + to.Start, to.Length)));
+            }
+
+            output.Append(edit.NewText);
+            position = edit.Offset + edit.Length;
+        }
+
+        anchors.Add((new TextRange(position, original.Length - position), new TextRange(output.Length, original.Length - position)));
+        output.Append(original, position, original.Length - position);
+        return FromAnchors(original, output.ToString(), anchors);
+    }
+
+    /// <summary>
+    /// Creates the map between two texts by aligning their tokens: tokens of the same kind and text, in the same
+    /// order, are preserved.
+    /// </summary>
+    /// <param name="original">The original text.</param>
+    /// <param name="originalTokens">The tokens of the original text.</param>
+    /// <param name="output">The output text.</param>
+    /// <param name="outputTokens">The tokens of the output text.</param>
+    /// <returns>The map from the original text to the output.</returns>
+    public static PositionMap FromTokens(string original, IReadOnlyList<Token> originalTokens, string output, IReadOnlyList<Token> outputTokens)
+    {
+        ArgumentNullException.ThrowIfNull(original);
+        ArgumentNullException.ThrowIfNull(originalTokens);
+        ArgumentNullException.ThrowIfNull(output);
+        ArgumentNullException.ThrowIfNull(outputTokens);
+
+        return FromAnchors(original, output, AlignTokens(originalTokens, outputTokens));
+    }
+
+    /// <summary>
+    /// Maps a span of the original text to the output.
+    /// </summary>
+    /// <param name="span">The span of the original text.</param>
+    /// <returns>The corresponding span of the output.</returns>
+    public MappedSpan MapForward(TextRange span)
+    {
+        return Map(span, true);
+    }
+
+    /// <summary>
+    /// Maps a span of the output to the original text.
+    /// </summary>
+    /// <param name="span">The span of the output.</param>
+    /// <returns>The corresponding span of the original text.</returns>
+    public MappedSpan MapBackward(TextRange span)
+    {
+        return Map(span, false);
+    }
+
+    /// <summary>
+    /// Maps a position in the original text to the output, recomputing its lines and columns.
+    /// </summary>
+    /// <param name="position">The position in the original text.</param>
+    /// <returns>The corresponding position in the output.</returns>
+    public SourcePosition MapForward(SourcePosition position)
+    {
+        ArgumentNullException.ThrowIfNull(position);
+
+        var range = MapForward(new TextRange(position.Offset, position.Length)).Range;
+        return (_outputLines ??= new LineIndex(Output)).GetPosition(range.Start, range.Length, position.SourceFile);
+    }
+
+    /// <summary>
+    /// Maps a position in the output to the original text, recomputing its lines and columns.
+    /// </summary>
+    /// <param name="position">The position in the output.</param>
+    /// <returns>The corresponding position in the original text.</returns>
+    public SourcePosition MapBackward(SourcePosition position)
+    {
+        ArgumentNullException.ThrowIfNull(position);
+
+        var range = MapBackward(new TextRange(position.Offset, position.Length)).Range;
+        return (_originalLines ??= new LineIndex(Original)).GetPosition(range.Start, range.Length, position.SourceFile);
+    }
+
+    /// <summary>
+    /// Builds the segments from preserved ranges in increasing order: each anchor is a preserved segment and the
+    /// text between consecutive anchors a changed one. Adjacent anchors are merged and empty ones dropped.
+    /// </summary>
+    internal static PositionMap FromAnchors(string original, string output, IEnumerable<(TextRange Original, TextRange Output)> anchors)
+    {
+        var segments = new List<PositionMapSegment>();
+        int from = 0, to = 0;
+        foreach (var (source, target) in anchors.Where(a => a.Original.Length > 0))
+        {
+            if (source.Start > from || target.Start > to)
+            {
+                segments.Add(new PositionMapSegment(new TextRange(from, source.Start - from), new TextRange(to, target.Start - to), false));
+            }
+
+            if (segments.Count > 0 && segments[^1] is { IsPreserved: true } previous)
+            {
+                segments[^1] = previous with
+                {
+                    Original = new TextRange(previous.Original.Start, source.End - previous.Original.Start),
+                    Output = new TextRange(previous.Output.Start, target.End - previous.Output.Start)
+                };
+            }
+            else
+            {
+                segments.Add(new PositionMapSegment(source, target, true));
+            }
+
+            from = source.End;
+            to = target.End;
+        }
+
+        if (from < original.Length || to < output.Length)
+        {
+            segments.Add(new PositionMapSegment(new TextRange(from, original.Length - from), new TextRange(to, output.Length - to), false));
+        }
+
+        return new PositionMap(original, output, segments);
+    }
+
+    private MappedSpan Map(TextRange span, bool forward)
+    {
+        if (_segments.Count == 0)
+        {
+            return new MappedSpan(new TextRange(0, 0), SpanMappingKind.Exact);
+        }
+
+        TextRange From(PositionMapSegment segment) => forward ? segment.Original : segment.Output;
+        TextRange To(PositionMapSegment segment) => forward ? segment.Output : segment.Original;
+
+        var first = FindStart(span.Start, From);
+        var last = span.Length == 0 ? first : FindEnd(span.End, From);
+
+        var startSegment = _segments[first];
+        var startExact = startSegment.IsPreserved;
+        var start = !startExact
+            ? (span.Start >= From(startSegment).End ? To(startSegment).End : To(startSegment).Start)
+            : To(startSegment).Start + Math.Min(span.Start - From(startSegment).Start, From(startSegment).Length);
+
+        var endSegment = _segments[last];
+        var endExact = endSegment.IsPreserved;
+        var end = span.Length == 0 && startExact
+            ? start
+            : endExact
+                ? To(endSegment).Start + Math.Min(span.End - From(endSegment).Start, From(endSegment).Length)
+                : To(endSegment).End;
+
+        var range = new TextRange(start, Math.Max(0, end - start));
+        if (_segments.Skip(first).Take(last - first + 1).All(s => !s.IsPreserved && To(s).Length == 0))
+        {
+            return new MappedSpan(range with { Length = 0 }, forward ? SpanMappingKind.Deleted : SpanMappingKind.Synthesized);
+        }
+
+        return new MappedSpan(range, startExact && endExact ? SpanMappingKind.Exact : SpanMappingKind.Bracketing);
+    }
+
+    // The first segment ending after the offset, which contains it; offsets at the end of the text fall in the last segment.
+    private int FindStart(int offset, Func<PositionMapSegment, TextRange> from)
+    {
+        int low = 0, high = _segments.Count - 1, found = _segments.Count - 1;
+        while (low <= high)
+        {
+            var middle = (low + high) / 2;
+            if (from(_segments[middle]).End > offset)
+            {
+                found = middle;
+                high = middle - 1;
+            }
+            else
+            {
+                low = middle + 1;
+            }
+        }
+
+        return found;
+    }
+
+    // The last segment starting before the offset, which contains the character before it.
+    private int FindEnd(int offset, Func<PositionMapSegment, TextRange> from)
+    {
+        int low = 0, high = _segments.Count - 1, found = 0;
+        while (low <= high)
+        {
+            var middle = (low + high) / 2;
+            if (from(_segments[middle]).Start < offset)
+            {
+                found = middle;
+                low = middle + 1;
+            }
+            else
+            {
+                high = middle - 1;
+            }
+        }
+
+        return found;
+    }
+
+    private static IEnumerable<(TextRange From, TextRange To)> Align(string replaced, string replacement, GrammarLexer? lexer)
+    {
+        if (lexer != null)
+        {
+            var before = lexer.Tokenize(replaced);
+            var after = lexer.Tokenize(replacement);
+            if (before.Diagnostics.Count == 0 && after.Diagnostics.Count == 0)
+            {
+                return AlignTokens(before.Tokens, after.Tokens);
+            }
+        }
+
+        var prefix = 0;
+        while (prefix < replaced.Length && prefix < replacement.Length && replaced[prefix] == replacement[prefix])
+        {
+            prefix++;
+        }
+
+        var suffix = 0;
+        while (suffix < replaced.Length - prefix && suffix < replacement.Length - prefix && replaced[^(suffix + 1)] == replacement[^(suffix + 1)])
+        {
+            suffix++;
+        }
+
+        return new[]
+        {
+            (new TextRange(0, prefix), new TextRange(0, prefix)),
+            (new TextRange(replaced.Length - suffix, suffix), new TextRange(replacement.Length - suffix, suffix))
+        };
+    }
+
+    /// <summary>
+    /// Pairs the tokens of two token lists that a longest common subsequence of kinds and texts matches, after
+    /// taking their common prefix and suffix; middles too large for the quadratic table stay unmatched.
+    /// </summary>
+    private static List<(TextRange From, TextRange To)> AlignTokens(IReadOnlyList<Token> before, IReadOnlyList<Token> after)
+    {
+        const long MaxTable = 4_000_000;
+
+        var a = before.Where(t => !t.IsSynthetic && t.Length > 0).ToList();
+        var b = after.Where(t => !t.IsSynthetic && t.Length > 0).ToList();
+        static bool Same(Token x, Token y) => x.Kind == y.Kind && x.Text == y.Text;
+        static (TextRange, TextRange) Pair(Token x, Token y) => (new TextRange(x.Offset, x.Length), new TextRange(y.Offset, y.Length));
+
+        var prefix = 0;
+        while (prefix < a.Count && prefix < b.Count && Same(a[prefix], b[prefix]))
+        {
+            prefix++;
+        }
+
+        var suffix = 0;
+        while (suffix < a.Count - prefix && suffix < b.Count - prefix && Same(a[^(suffix + 1)], b[^(suffix + 1)]))
+        {
+            suffix++;
+        }
+
+        var pairs = Enumerable.Range(0, prefix).Select(i => Pair(a[i], b[i])).ToList();
+        int n = a.Count - prefix - suffix, m = b.Count - prefix - suffix;
+        if (n > 0 && m > 0 && (long)n * m <= MaxTable)
+        {
+            var lcs = new int[n + 1, m + 1];
+            for (var i = n - 1; i >= 0; i--)
+            {
+                for (var j = m - 1; j >= 0; j--)
+                {
+                    lcs[i, j] = Same(a[prefix + i], b[prefix + j]) ? lcs[i + 1, j + 1] + 1 : Math.Max(lcs[i + 1, j], lcs[i, j + 1]);
+                }
+            }
+
+            for (int i = 0, j = 0; i < n && j < m;)
+            {
+                if (Same(a[prefix + i], b[prefix + j]))
+                {
+                    pairs.Add(Pair(a[prefix + i++], b[prefix + j++]));
+                }
+                else if (lcs[i + 1, j] >= lcs[i, j + 1])
+                {
+                    i++;
+                }
+                else
+                {
+                    j++;
+                }
+            }
+        }
+
+        pairs.AddRange(Enumerable.Range(0, suffix).Select(k => Pair(a[a.Count - suffix + k], b[b.Count - suffix + k])));
+        return pairs;
+    }
+}
@@ -0,0 +1,210 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The kind of a <see cref="PreamblePart"/>.
+/// </summary>
+public enum PreambleKind
+{
+    /// <summary>
+    /// A byte order mark, U+FEFF.
+    /// </summary>
+    ByteOrderMark,
+
+    /// <summary>
+    /// A <c>#!</c> interpreter line.
+    /// </summary>
+    Shebang,
+
+    /// <summary>
+    /// A prefix matched by a pattern of <see cref="PreambleOptions"/>, like a PHP opening tag or an editor mode line.
+    /// </summary>
+    Pattern
+}
+
+/// <summary>
+/// A recognized part of a file's preamble.
+/// </summary>
+/// <param name="Kind">The kind of part.</param>
+/// <param name="Name">The pattern name for <see cref="PreambleKind.Pattern"/> parts; otherwise the kind in lower case.</param>
+/// <param name="Offset">The offset of the part in the file.</param>
+/// <param name="Text">The text of the part, including the line break ending a shebang line.</param>
+public sealed record PreamblePart(PreambleKind Kind, string Name, int Offset, string Text)
+{
+    /// <summary>
+    /// Gets the length of the part.
+    /// </summary>
+    public int Length => Text.Length;
+
+    /// <summary>
+    /// Gets the offset immediately after the part.
+    /// </summary>
+    public int End => Offset + Text.Length;
+}
+
+/// <summary>
+/// Chooses which preambles the parser recognizes before the content of a file.
+/// </summary>
+public sealed class PreambleOptions
+{
+    private readonly List<(string Name, Regex Pattern)> _patterns = new();
+
+    /// <summary>
+    /// Gets or sets a value indicating whether a leading byte order mark is recognized.
+    /// </summary>
+    public bool ByteOrderMark { get; set; } = true;
+
+    /// <summary>
+    /// Gets or sets a value indicating whether a <c>#!</c> line after the byte order mark is recognized.
+    /// </summary>
+    public bool Shebang { get; set; } = true;
+
+    /// <summary>
+    /// Gets the configured prefix patterns in the order they are tried.
+    /// </summary>
+    public IReadOnlyList<(string Name, Regex Pattern)> Patterns => _patterns;
+
+    /// <summary>
+    /// Adds a prefix pattern, tried after the byte order mark and the shebang line and again after each prefix it
+    /// or another pattern matched, like <c>&lt;\?php\s*</c> or a mode line <c>#.*-\*-.*-\*-.*\r?\n</c>.
+    /// </summary>
+    /// <param name="name">The name recorded on matched parts.</param>
+    /// <param name="pattern">The regular expression, matched where the preamble so far ends.</param>
+    /// <returns>These options.</returns>
+    public PreambleOptions AddPattern(string name, string pattern)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(name);
+        ArgumentException.ThrowIfNullOrEmpty(pattern);
+
+        _patterns.Add((name, new Regex(@"\G(?:" + pattern + ")", RegexOptions.CultureInvariant)));
+        return this;
+    }
+}
+
+/// <summary>
+/// The byte order mark, shebang line and configured prefixes a file starts with, which the parser skips so that
+/// grammars need not model them. Offsets of the parts, and of everything parsed after them, are offsets in the
+/// whole file.
+/// </summary>
+public sealed class Preamble
+{
+    private Preamble(IReadOnlyList<PreamblePart> parts)
+    {
+        Parts = parts;
+    }
+
+    /// <summary>
+    /// Gets the preamble of a file that has none.
+    /// </summary>
+    public static Preamble Empty { get; } = new(Array.Empty<PreamblePart>());
+
+    /// <summary>
+    /// Gets the recognized parts in file order.
+    /// </summary>
+    public IReadOnlyList<PreamblePart> Parts { get; }
+
+    /// <summary>
+    /// Gets the length of the preamble, the offset where parsing starts.
+    /// </summary>
+    public int Length => Parts.Count > 0 ? Parts[^1].End : 0;
+
+    /// <summary>
+    /// Gets a value indicating whether the file starts with a byte order mark.
+    /// </summary>
+    public bool HasByteOrderMark => Parts.Any(p => p.Kind == PreambleKind.ByteOrderMark);
+
+    /// <summary>
+    /// Gets the command of the shebang line, without <c>#!</c> and the line break, or null if there is none.
+    /// </summary>
+    public string? Shebang => Parts.FirstOrDefault(p => p.Kind == PreambleKind.Shebang)?.Text[2..].TrimEnd('\r', '\n').Trim();
+
+    /// <summary>
+    /// Gets the program the shebang line runs, like <c>python3</c> for <c>#!/usr/bin/env -S python3 -u</c>, or null
+    /// if there is no shebang line.
+    /// </summary>
+    public string? Interpreter
+    {
+        get
+        {
+            var words = Shebang?.Split(new[] { ' ', '\t' }, StringSplitOptions.RemoveEmptyEntries);
+            if (words == null || words.Length == 0)
+            {
+                return null;
+            }
+
+            var program = Path.GetFileName(words[0]);
+            if (program == "env")
+            {
+                program = words.Skip(1).FirstOrDefault(w => !w.StartsWith('-') && !w.Contains('=')) is { } command ? Path.GetFileName(command) : program;
+            }
+
+            return program;
+        }
+    }
+
+    /// <summary>
+    /// Recognizes the preamble of a file.
+    /// </summary>
+    /// <param name="input">The file text.</param>
+    /// <param name="options">The preambles to recognize; the byte order mark and shebang line if null.</param>
+    /// <returns>The preamble, <see cref="Empty"/> if the file has none.</returns>
+    public static Preamble Detect(string input, PreambleOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+        options ??= new PreambleOptions();
+
+        var parts = new List<PreamblePart>();
+        var position = 0;
+        if (options.ByteOrderMark && input.StartsWith('\uFEFF'))
+        {
+            parts.Add(new PreamblePart(PreambleKind.ByteOrderMark, "bom", 0, "\uFEFF"));
+            position = 1;
+        }
+
+        if (options.Shebang && string.CompareOrdinal(input, position, "#!", 0, 2) == 0)
+        {
+            var end = input.IndexOf('\n', position);
+            end = end < 0 ? input.Length : end + 1;
+            parts.Add(new PreamblePart(PreambleKind.Shebang, "shebang", position, input[position..end]));
+            position = end;
+        }
+
+        // Patterns are retried after every match, so prefixes can follow each other in any order.
+        var matched = true;
+        while (matched && position < input.Length)
+        {
+            matched = false;
+            foreach (var (name, pattern) in options.Patterns)
+            {
+                var match = pattern.Match(input, position);
+                if (match.Success && match.Length > 0)
+                {
+                    parts.Add(new PreamblePart(PreambleKind.Pattern, name, position, match.Value));
+                    position += match.Length;
+                    matched = true;
+                    break;
+                }
+            }
+        }
+
+        return parts.Count > 0 ? new Preamble(parts) : Empty;
+    }
+}
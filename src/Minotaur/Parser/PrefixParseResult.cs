@@ -0,0 +1,98 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// How much of the input a prefix parse accepted.
+/// </summary>
+public enum PrefixStatus
+{
+    /// <summary>
+    /// The input is a complete sentence of the start rule. More input may still follow.
+    /// </summary>
+    Complete,
+
+    /// <summary>
+    /// The input is a valid beginning of a sentence but not a sentence yet.
+    /// </summary>
+    Incomplete,
+
+    /// <summary>
+    /// The input contains a token that no sentence can continue with.
+    /// </summary>
+    Invalid
+}
+
+/// <summary>
+/// The result of parsing the longest valid prefix of an input, with the terminals that may follow it.
+/// </summary>
+public class PrefixParseResult
+{
+    /// <summary>
+    /// Gets the parsed input.
+    /// </summary>
+    public string Input { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Gets the rule the input was parsed against.
+    /// </summary>
+    public string StartRule { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Gets whether the input is a complete sentence, a valid prefix of one, or neither.
+    /// </summary>
+    public PrefixStatus Status { get; init; }
+
+    /// <summary>
+    /// Gets the tokens produced by the lexer.
+    /// </summary>
+    public IReadOnlyList<Token> Tokens { get; init; } = Array.Empty<Token>();
+
+    /// <summary>
+    /// Gets the number of leading tokens that form a valid prefix.
+    /// </summary>
+    public int ValidTokenCount { get; init; }
+
+    /// <summary>
+    /// Gets the length of the longest valid prefix in characters: the offset of the first token that cannot
+    /// continue it, or the input length when the whole input is valid.
+    /// </summary>
+    public int ValidLength { get; init; }
+
+    /// <summary>
+    /// Gets the keys of the terminals that may follow the valid prefix, in ordinal order.
+    /// </summary>
+    public IReadOnlyList<string> Expected { get; init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the expected terminals grouped by their category, see <see cref="CompiledGrammar.GetTerminalCategory"/>.
+    /// </summary>
+    public IReadOnlyDictionary<string, IReadOnlyList<string>> ExpectedByCategory { get; init; } = new Dictionary<string, IReadOnlyList<string>>();
+
+    /// <summary>
+    /// Gets a value indicating whether the input is a complete sentence.
+    /// </summary>
+    public bool IsComplete => Status == PrefixStatus.Complete;
+
+    /// <summary>
+    /// Gets a value indicating whether the input is valid so far but needs more input, the signal for a
+    /// multi-line REPL to keep reading.
+    /// </summary>
+    public bool NeedsMoreInput => Status == PrefixStatus.Incomplete;
+}
@@ -0,0 +1,277 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A declaration enclosing a node, one step of a <see cref="QualifiedPath"/>.
+/// </summary>
+/// <param name="Kind">The rule of the declaration's node.</param>
+/// <param name="Name">The declared name: the identifier among the node's children, or else the node's breadcrumb, or
+/// else a placeholder numbering the unnamed declarations of the rule in the enclosing one, like <c>{closure#0}</c>.</param>
+/// <param name="Text">How breadcrumbs show the declaration: the first of the rule's breadcrumb templates the node
+/// fills in, like <c>impl Add for Point</c>, or else its name.</param>
+/// <param name="Location">The span of the declaration.</param>
+public sealed record PathSegment(string Kind, string Name, string Text, SourcePosition Location);
+
+/// <summary>
+/// Where a node is, as the declarations enclosing it: the nodes of the grammar's
+/// <see cref="CompiledGrammar.OutlineKey"/> rules among its ancestors and itself, outermost first, such as
+/// <c>mod shapes &gt; impl Add for Point &gt; fn add</c>. A rule's <c>// @breadcrumb("impl $path for $type")</c>
+/// annotations template how its nodes are shown, with each <c>$label</c> naming a child as in deprecation rewrites and
+/// replaced by the child's text; a node is shown with the first template whose labels it has children for. Get paths
+/// from <see cref="ParseResult.GetQualifiedPath(CognitiveGraphNode)"/>, and set <see cref="ParseOptions.PathContext"/>
+/// to have a parse's diagnostics carry the path of the node they point at.
+/// </summary>
+public sealed class QualifiedPath
+{
+    /// <summary>
+    /// The separator between the segments of a breadcrumb.
+    /// </summary>
+    public const string Separator = " > ";
+
+    /// <summary>
+    /// The key of a diagnostic's <see cref="Diagnostic.Data"/> holding its path with
+    /// <see cref="ParseOptions.PathContext"/>.
+    /// </summary>
+    public const string DataKey = "path";
+
+    private static readonly Regex Label = new(@"\$[A-Za-z_][A-Za-z0-9_]*", RegexOptions.CultureInvariant);
+
+    internal QualifiedPath(IReadOnlyList<PathSegment> segments)
+    {
+        Segments = segments;
+    }
+
+    /// <summary>
+    /// Gets the path of a node no declaration encloses.
+    /// </summary>
+    public static QualifiedPath Empty { get; } = new(Array.Empty<PathSegment>());
+
+    /// <summary>
+    /// Gets the enclosing declarations, outermost first.
+    /// </summary>
+    public IReadOnlyList<PathSegment> Segments { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether no declaration encloses the node.
+    /// </summary>
+    public bool IsEmpty => Segments.Count == 0;
+
+    /// <summary>
+    /// Gets the qualified name, the segments' names joined with a separator.
+    /// </summary>
+    /// <param name="separator">The separator, "::" by default.</param>
+    /// <returns>The qualified name, e.g. "shapes::impl Add for Point::add".</returns>
+    public string GetQualifiedName(string separator = "::")
+    {
+        return string.Join(separator, Segments.Select(s => s.Name));
+    }
+
+    /// <summary>
+    /// Returns the breadcrumb, the segments' texts joined with <see cref="Separator"/>.
+    /// </summary>
+    /// <returns>The breadcrumb, e.g. "mod shapes &gt; impl Add for Point &gt; fn add".</returns>
+    public override string ToString()
+    {
+        return string.Join(Separator, Segments.Select(s => s.Text));
+    }
+
+    internal static IReadOnlyList<string> GetTemplateLabels(string template)
+    {
+        return Label.Matches(template).Select(m => m.Value[1..]).Distinct().ToList();
+    }
+
+    internal static string? Fill(string template, CognitiveGraphNode node, string input)
+    {
+        var filled = true;
+        var text = Label.Replace(template, match =>
+        {
+            var child = FindLabeled(node.Children, match.Value[1..]);
+            filled &= child != null;
+            return child != null ? TextOf(child, input) : match.Value;
+        });
+        return filled ? text : null;
+    }
+
+    internal QualifiedPath Append(PathSegment segment)
+    {
+        return new QualifiedPath(Segments.Append(segment).ToList());
+    }
+
+    // Labels are named like GrammarDeprecation.GetLabels names them: after the child's rule or token, with a number
+    // counting from 1 when the alternative has more than one.
+    private static CognitiveGraphNode? FindLabeled(IReadOnlyList<CognitiveGraphNode> children, string label)
+    {
+        static string? LabelOf(CognitiveGraphNode node) => node switch
+        {
+            NonTerminalNode rule => rule.RuleName.Replace('-', '_'),
+            TerminalNode terminal when !terminal.TokenType.StartsWith('"') => terminal.TokenType.Replace('-', '_'),
+            _ => null
+        };
+
+        var exact = children.FirstOrDefault(c => LabelOf(c) == label);
+        if (exact != null)
+        {
+            return exact;
+        }
+
+        var digits = label.Length - label.Reverse().TakeWhile(char.IsAsciiDigit).Count();
+        if (digits == label.Length || digits == 0 || !int.TryParse(label[digits..], out var number) || number < 1)
+        {
+            return null;
+        }
+
+        return children.Where(c => LabelOf(c) == label[..digits]).Skip(number - 1).FirstOrDefault();
+    }
+
+    private static string TextOf(CognitiveGraphNode node, string input)
+    {
+        if (node is TerminalNode terminal)
+        {
+            return terminal.Text;
+        }
+
+        var span = node.SourcePosition;
+        return span == null || span.Offset + span.Length > input.Length
+            ? string.Empty
+            : string.Join(' ', input.Substring(span.Offset, span.Length).Split((char[]?)null, StringSplitOptions.RemoveEmptyEntries));
+    }
+}
+
+/// <summary>
+/// Computes and caches the qualified paths of the nodes of one parse.
+/// </summary>
+internal sealed class QualifiedPathResolver
+{
+    private readonly ParseResult _parse;
+    private readonly CompiledGrammar _grammar;
+    private readonly HashSet<string> _identifiers;
+    private readonly Dictionary<CognitiveGraphNode, QualifiedPath> _paths = new(ReferenceEqualityComparer.Instance);
+    private readonly Dictionary<CognitiveGraphNode, List<CognitiveGraphNode>> _unnamed = new(ReferenceEqualityComparer.Instance);
+
+    public QualifiedPathResolver(ParseResult parse, CompiledGrammar grammar)
+    {
+        _parse = parse;
+        _grammar = grammar;
+        _identifiers = SkeletonExtractor.GetIdentifierKinds(grammar.Source);
+    }
+
+    public QualifiedPath Get(CognitiveGraphNode node)
+    {
+        // The uncached ancestors are resolved outermost first, each extending its parent's path.
+        var uncached = new Stack<CognitiveGraphNode>();
+        var path = QualifiedPath.Empty;
+        for (var current = node; current != null; current = current.Parent)
+        {
+            if (_paths.TryGetValue(current, out var cached))
+            {
+                path = cached;
+                break;
+            }
+
+            uncached.Push(current);
+        }
+
+        while (uncached.Count > 0)
+        {
+            var current = uncached.Pop();
+            if (IsDeclaration(current))
+            {
+                path = path.Append(Segment((NonTerminalNode)current));
+            }
+
+            _paths[current] = path;
+        }
+
+        return path;
+    }
+
+    private bool IsDeclaration(CognitiveGraphNode node)
+    {
+        return node is NonTerminalNode { SourcePosition: not null } rule && _grammar.GetRule(rule.RuleName)?.IsOutlined == true;
+    }
+
+    private PathSegment Segment(NonTerminalNode node)
+    {
+        var (name, breadcrumb) = Describe(node);
+        name ??= breadcrumb ?? Placeholder(node);
+        return new PathSegment(node.RuleName, name, breadcrumb ?? name, node.SourcePosition!);
+    }
+
+    private (string? Name, string? Breadcrumb) Describe(NonTerminalNode node)
+    {
+        var name = node.Children.OfType<TerminalNode>().FirstOrDefault(t => _identifiers.Contains(t.TokenType))?.Text;
+        var breadcrumb = _grammar.GetRule(node.RuleName)!.Breadcrumbs
+            .Select(t => QualifiedPath.Fill(t, node, _parse.Input))
+            .FirstOrDefault(t => t != null);
+        return (name, breadcrumb);
+    }
+
+    // Unnamed declarations are numbered from 0 by rule within the declaration enclosing them, or the whole tree.
+    private string Placeholder(NonTerminalNode node)
+    {
+        var scope = node.Parent;
+        if (scope == null)
+        {
+            return $"{{{node.RuleName}#0}}";
+        }
+
+        while (!IsDeclaration(scope) && scope.Parent != null)
+        {
+            scope = scope.Parent;
+        }
+
+        if (!_unnamed.TryGetValue(scope, out var unnamed))
+        {
+            _unnamed[scope] = unnamed = FindUnnamed(scope);
+        }
+
+        var index = unnamed.Where(n => ((NonTerminalNode)n).RuleName == node.RuleName).ToList().IndexOf(node);
+        return $"{{{node.RuleName}#{index}}}";
+    }
+
+    private List<CognitiveGraphNode> FindUnnamed(CognitiveGraphNode scope)
+    {
+        var unnamed = new List<CognitiveGraphNode>();
+        var pending = new Stack<CognitiveGraphNode>(scope.Children.Reverse());
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (IsDeclaration(node))
+            {
+                if (Describe((NonTerminalNode)node) is (null, null))
+                {
+                    unnamed.Add(node);
+                }
+
+                continue;
+            }
+
+            foreach (var child in node.Children.Reverse())
+            {
+                pending.Push(child);
+            }
+        }
+
+        return unnamed;
+    }
+}
@@ -0,0 +1,96 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Measurements of a single incremental reparse.
+/// </summary>
+public readonly record struct ReparseStats
+{
+    /// <summary>
+    /// Gets the document version produced by the reparse, starting at 1 for the first edit.
+    /// </summary>
+    public int Version { get; init; }
+
+    /// <summary>
+    /// Gets the edit that triggered the reparse.
+    /// </summary>
+    public TextEdit Edit { get; init; }
+
+    /// <summary>
+    /// Gets the range of the new text written by the edit.
+    /// </summary>
+    public TextRange DamagedRange { get; init; }
+
+    /// <summary>
+    /// Gets the range of the new text that was lexed again, from the last unaffected token to the point
+    /// where the token stream resynchronized with the previous one.
+    /// </summary>
+    public TextRange RelexedRange { get; init; }
+
+    /// <summary>
+    /// Gets the number of tokens produced by re-lexing.
+    /// </summary>
+    public int RelexedTokenCount { get; init; }
+
+    /// <summary>
+    /// Gets the number of tokens in the document after the edit.
+    /// </summary>
+    public int TokenCount { get; init; }
+
+    /// <summary>
+    /// Gets the number of tree nodes carried over from the previous parse.
+    /// </summary>
+    public int ReusedNodeCount { get; init; }
+
+    /// <summary>
+    /// Gets the number of tree nodes created by the reparse.
+    /// </summary>
+    public int RebuiltNodeCount { get; init; }
+
+    /// <summary>
+    /// Gets the length of the document after the edit.
+    /// </summary>
+    public int DocumentLength { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether the document parsed without errors after the edit.
+    /// </summary>
+    public bool IsSuccess { get; init; }
+
+    /// <summary>
+    /// Gets the rule of the node reparsed on its own by <see cref="IncrementalParser.ReparseNode"/>, or null if
+    /// the document was reparsed.
+    /// </summary>
+    public string? ReparsedRule { get; init; }
+
+    /// <summary>
+    /// Gets the time spent re-lexing.
+    /// </summary>
+    public TimeSpan LexTime { get; init; }
+
+    /// <summary>
+    /// Gets the time spent parsing and building the tree.
+    /// </summary>
+    public TimeSpan ParseTime { get; init; }
+
+    /// <summary>
+    /// Gets the total reparse time.
+    /// </summary>
+    public TimeSpan TotalTime => LexTime + ParseTime;
+}
@@ -0,0 +1,82 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Matches terminals against the characters of the input for scannerless parsing. Every (terminal, position)
+/// pair is matched at most once; the recognizer asks for the same pair from many Earley items, and the forest
+/// builder later asks which matches end at a position, which is answered from the same record.
+/// </summary>
+internal sealed class ScannerlessMatcher
+{
+    private readonly GrammarLexer _lexer;
+    private readonly string _input;
+    private readonly Dictionary<(string Kind, int Position), int> _lengths = new();
+    private readonly Dictionary<(string Kind, int End), List<int>> _starts = new();
+
+    public ScannerlessMatcher(GrammarLexer lexer, string input)
+    {
+        _lexer = lexer;
+        _input = input;
+    }
+
+    /// <summary>
+    /// Matches a terminal at a position.
+    /// </summary>
+    /// <returns>The length of the match, or -1 if the terminal does not match.</returns>
+    public int Match(string kind, int position)
+    {
+        if (_lengths.TryGetValue((kind, position), out var length))
+        {
+            return length;
+        }
+
+        length = _lexer.MatchTerminal(_input, kind, position);
+        _lengths[(kind, position)] = length;
+
+        if (length >= 0)
+        {
+            var key = (kind, position + length);
+            if (!_starts.TryGetValue(key, out var starts))
+            {
+                starts = new List<int>();
+                _starts[key] = starts;
+            }
+
+            starts.Add(position);
+        }
+
+        return length;
+    }
+
+    /// <summary>
+    /// Gets the positions at which a terminal was matched so that the match ends at a position.
+    /// </summary>
+    public IReadOnlyList<int> StartsEndingAt(string kind, int end)
+    {
+        return _starts.TryGetValue((kind, end), out var starts) ? starts : Array.Empty<int>();
+    }
+
+    /// <summary>
+    /// Creates the token for a match.
+    /// </summary>
+    public Token CreateToken(string kind, int start, int end)
+    {
+        return new Token(kind, _input[start..end], start);
+    }
+}
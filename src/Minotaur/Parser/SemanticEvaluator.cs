@@ -0,0 +1,210 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Evaluates semantic actions over a parse forest. Reductions run bottom-up in input order, each popping the
+/// values of its symbols from a value stack and pushing the value of its action.
+/// </summary>
+internal sealed class SemanticEvaluator
+{
+    private readonly ActionRegistry _actions;
+    private readonly IReadOnlyList<Token> _tokens;
+    private readonly ActionContext _context;
+    private readonly Stack<object?> _values = new();
+    private readonly Dictionary<PackedForestNode, object?> _pureValues = new();
+    private readonly HashSet<SymbolForestNode> _active = new();
+    private int _speculationDepth;
+
+    public SemanticEvaluator(ActionRegistry actions, IReadOnlyList<Token> tokens, ActionContext context)
+    {
+        _actions = actions;
+        _tokens = tokens;
+        _context = context;
+    }
+
+    /// <summary>
+    /// Evaluates the forest below the specified node.
+    /// </summary>
+    /// <param name="root">The root node.</param>
+    /// <param name="value">The value of the root, if evaluation succeeded.</param>
+    /// <param name="error">The diagnostic describing a failed action, if evaluation failed.</param>
+    /// <returns>True if every action succeeded.</returns>
+    public bool TryEvaluate(SymbolForestNode root, out object? value, out Diagnostic? error)
+    {
+        try
+        {
+            EvaluateNode(root);
+            value = _values.Pop();
+            error = null;
+            return true;
+        }
+        catch (ActionFailedException ex)
+        {
+            _values.Clear();
+            value = null;
+            error = ex.Diagnostic;
+            return false;
+        }
+    }
+
+    // Pushes the value of the node and returns whether it was computed by pure actions only.
+    private bool EvaluateNode(ForestNode node)
+    {
+        if (node is TokenForestNode token)
+        {
+            _values.Push(token.Token.Text);
+            return true;
+        }
+
+        var symbol = (SymbolForestNode)node;
+        if (!_active.Add(symbol))
+        {
+            throw new InvalidOperationException($"Cyclic derivation of rule '{symbol.RuleName}' cannot be evaluated");
+        }
+
+        try
+        {
+            if (!symbol.IsAmbiguous)
+            {
+                return Reduce(symbol, symbol.Packed[0]);
+            }
+
+            // Every derivation is evaluated, like forks of a GLR parser, before one value is kept.
+            var pure = true;
+            var candidates = new List<object?>(symbol.Packed.Count);
+            _speculationDepth++;
+            foreach (var packed in symbol.Packed)
+            {
+                pure &= Reduce(symbol, packed);
+                candidates.Add(_values.Pop());
+            }
+
+            _speculationDepth--;
+
+            if (_actions.ResolveAmbiguity is { } resolve)
+            {
+                PrepareContext(symbol, symbol.Packed[0].Alternative);
+                _values.Push(Invoke("ResolveAmbiguity", () => resolve(_context, candidates)));
+            }
+            else
+            {
+                _values.Push(candidates[0]);
+            }
+
+            return pure;
+        }
+        finally
+        {
+            _active.Remove(symbol);
+        }
+    }
+
+    private bool Reduce(SymbolForestNode node, PackedForestNode packed)
+    {
+        if (_pureValues.TryGetValue(packed, out var cached))
+        {
+            _values.Push(cached);
+            return true;
+        }
+
+        var depth = _values.Count;
+        var pure = true;
+        foreach (var child in packed.Children)
+        {
+            pure &= EvaluateNode(child);
+        }
+
+        var values = new object?[_values.Count - depth];
+        for (var i = values.Length - 1; i >= 0; i--)
+        {
+            values[i] = _values.Pop();
+        }
+
+        object? value;
+        var action = _actions.Resolve(packed.Alternative);
+        if (action == null)
+        {
+            value = values.Length == 1 ? values[0] : values;
+        }
+        else
+        {
+            PrepareContext(node, packed.Alternative);
+            value = Invoke(action.Name, () => action.Action(_context, values));
+            pure &= action.IsPure;
+        }
+
+        if (pure)
+        {
+            _pureValues[packed] = value;
+        }
+
+        _values.Push(value);
+        return pure;
+    }
+
+    private void PrepareContext(SymbolForestNode node, CompiledAlternative alternative)
+    {
+        int start;
+        int end;
+        if (node.End > node.Start)
+        {
+            start = _tokens[node.Start].Offset;
+            end = _tokens[node.End - 1].End;
+        }
+        else
+        {
+            start = end = node.Start < _tokens.Count ? _tokens[node.Start].Offset : _tokens.Count > 0 ? _tokens[^1].End : 0;
+        }
+
+        _context.Alternative = alternative;
+        _context.Span = new TextRange(start, end - start);
+        _context.IsSpeculative = _speculationDepth > 0;
+    }
+
+    private object? Invoke(string name, Func<object?> action)
+    {
+        try
+        {
+            return action();
+        }
+        catch (Exception ex) when (ex is not ActionFailedException)
+        {
+            throw new ActionFailedException(new Diagnostic
+            {
+                Code = DiagnosticCodes.SemanticActionFailed,
+                Severity = DiagnosticSeverity.Error,
+                Location = _context.Location,
+                Data = { ["action"] = name, ["exception"] = ex }
+            }.WithMessage(DiagnosticCodes.SemanticActionFailed, ("action", name), ("rule", _context.Rule.Name), ("exception", ex.Message)));
+        }
+    }
+
+    private sealed class ActionFailedException : Exception
+    {
+        public ActionFailedException(Diagnostic diagnostic)
+            : base(diagnostic.Message)
+        {
+            Diagnostic = diagnostic;
+        }
+
+        public Diagnostic Diagnostic { get; }
+    }
+}
@@ -0,0 +1,152 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A token classified for highlighting.
+/// </summary>
+/// <param name="Location">The span of the token.</param>
+/// <param name="Type">The token's type: for identifiers the rule of the node holding them, such as <c>parameter</c>,
+/// and otherwise its lexical type.</param>
+/// <param name="IsLexical">Whether the token was classified from its kind alone, because the tree at it is missing or
+/// was guessed by error recovery.</param>
+public sealed record SemanticToken(SourcePosition Location, string Type, bool IsLexical);
+
+/// <summary>
+/// Classifies the tokens of a parse for semantic highlighting. An identifier takes the rule of the tree node holding
+/// it, so a function name and a parameter highlight differently; every other token takes its lexical type:
+/// <c>keyword</c> for word literals, <c>operator</c> for other literals and the category of the terminal (see
+/// <see cref="CompiledGrammar.GetTerminalCategory"/>) for named tokens.
+/// </summary>
+/// <remarks>
+/// Where the tree cannot be trusted, the tokens fall back to their lexical types instead of losing their highlighting:
+/// in a parse without a tree, in deferred regions that are not parsed yet, and in the regions of
+/// <see cref="DiagnosticCodes.UnclosedDelimiter"/> errors, whose blocks the <see cref="DelimiterRecoveryPolicy"/>
+/// closed by guessing. The recovery keeps the damage of an unclosed bracket inside its block, so the tokens after the
+/// block are classified as they were before the bracket was typed.
+/// </remarks>
+public static class SemanticTokenProvider
+{
+    /// <summary>
+    /// The lexical type of word literals.
+    /// </summary>
+    public const string KeywordType = "keyword";
+
+    /// <summary>
+    /// The lexical type of literals that are not words.
+    /// </summary>
+    public const string OperatorType = "operator";
+
+    /// <summary>
+    /// The lexical type of identifiers.
+    /// </summary>
+    public const string IdentifierType = "identifier";
+
+    /// <summary>
+    /// Gets the classified tokens of a parse.
+    /// </summary>
+    /// <param name="parse">The parse of the file.</param>
+    /// <returns>The tokens with text, in source order.</returns>
+    public static IReadOnlyList<SemanticToken> GetTokens(ParseResult parse)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+
+        var holders = parse.Tree != null ? GetHolders(parse.Tree) : new Dictionary<int, string>();
+        var recovered = GetRecoveredRegions(parse);
+        var lineIndex = new LineIndex(parse.Input);
+        var tokens = new List<SemanticToken>();
+        foreach (var token in parse.Tokens.Where(t => !t.IsSynthetic && t.Length > 0))
+        {
+            var type = GetLexicalType(parse.Grammar, token);
+            var trusted = holders.TryGetValue(token.Offset, out var rule) && !recovered.Any(r => r.Start <= token.Offset && token.Offset < r.End);
+            tokens.Add(new SemanticToken(
+                lineIndex.GetPosition(token.Offset, token.Length),
+                trusted && type == IdentifierType ? rule! : type,
+                !trusted));
+        }
+
+        return tokens;
+    }
+
+    /// <summary>
+    /// Gets the lexical type of a token: what it is classified as without a tree.
+    /// </summary>
+    /// <param name="grammar">The grammar the token was read with, or null.</param>
+    /// <param name="token">The token.</param>
+    /// <returns>The type.</returns>
+    public static string GetLexicalType(CompiledGrammar? grammar, Token token)
+    {
+        ArgumentNullException.ThrowIfNull(token);
+
+        if (token.Kind.StartsWith('"'))
+        {
+            return token.Text.Length > 0 && (char.IsLetter(token.Text[0]) || token.Text[0] == '_') ? KeywordType : OperatorType;
+        }
+
+        return token.Kind switch
+        {
+            "IDENTIFIER" => IdentifierType,
+            "NUMBER" => "number",
+            "STRING" => "string",
+            _ => grammar?.GetTerminalCategory(token.Kind) ?? "other"
+        };
+    }
+
+    private static List<TextRange> GetRecoveredRegions(ParseResult parse)
+    {
+        return parse.Diagnostics
+            .Where(d => d.Code == DiagnosticCodes.UnclosedDelimiter)
+            .Select(d => d.Data.GetValueOrDefault("region"))
+            .OfType<TextRange>()
+            .ToList();
+    }
+
+    // The rule of the node holding each terminal, by the terminal's offset. Deferred regions that are not parsed
+    // have no terminals, so their tokens get none.
+    private static Dictionary<int, string> GetHolders(CognitiveGraphNode tree)
+    {
+        var holders = new Dictionary<int, string>();
+        var pending = new Stack<(CognitiveGraphNode Node, string Rule)>();
+        pending.Push((tree, string.Empty));
+        while (pending.Count > 0)
+        {
+            var (node, rule) = pending.Pop();
+            if (node is DeferredNode { IsMaterialized: false })
+            {
+                continue;
+            }
+
+            if (node is TerminalNode { SourcePosition: { Length: > 0 } position })
+            {
+                holders.TryAdd(position.Offset, rule);
+                continue;
+            }
+
+            var holder = node is NonTerminalNode nonTerminal ? nonTerminal.RuleName : rule;
+            foreach (var child in node.Children)
+            {
+                pending.Push((child, holder));
+            }
+        }
+
+        return holders;
+    }
+}
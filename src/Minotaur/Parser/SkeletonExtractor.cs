@@ -0,0 +1,246 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using System.Text.Json;
+using Minotaur.Core;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A declaration of a file's skeleton.
+/// </summary>
+/// <param name="Name">The declared name: the first identifier of the declaration outside nested declarations and
+/// bodies, or the signature if it has none.</param>
+/// <param name="Kind">The rule of the declaration's node.</param>
+/// <param name="Signature">The declaration's text up to its body or the end of its first line, such as
+/// <c>fn area(self) -&gt; f64</c>.</param>
+/// <param name="Location">The span of the whole declaration, body included.</param>
+/// <param name="Path">The names of the enclosing declarations and of this one, outermost first.</param>
+public sealed record SkeletonEntry(string Name, string Kind, string Signature, SourcePosition Location, IReadOnlyList<string> Path)
+{
+    /// <summary>
+    /// Gets the number of declarations enclosing this one.
+    /// </summary>
+    public int Depth => Path.Count - 1;
+
+    /// <summary>
+    /// Gets the qualified name, the <see cref="Path"/> joined with a separator.
+    /// </summary>
+    /// <param name="separator">The separator, "::" by default.</param>
+    /// <returns>The qualified name, e.g. "geometry::Point::new".</returns>
+    public string GetQualifiedName(string separator = "::")
+    {
+        return string.Join(separator, Path);
+    }
+}
+
+/// <summary>
+/// Extracts the declarations of files for code-search indexers, without building or reading their bodies. The file
+/// is parsed lazily, so the bodies of the grammar's <see cref="CompiledGrammar.DeferredKey"/> rules are skipped as
+/// bracket groups and never parsed, and the outline entries of the rest of the tree become the skeleton: the nodes
+/// of the <see cref="CompiledGrammar.OutlineKey"/> rules, or the layout-based entries of
+/// <see cref="OutlineProvider"/>. Declarations nested inside deferred bodies are therefore not extracted.
+/// </summary>
+public static class SkeletonExtractor
+{
+    private static readonly char[] OpeningBrackets = { '{', '[', '(' };
+
+    /// <summary>
+    /// Parses a file lazily and extracts its skeleton.
+    /// </summary>
+    /// <param name="parser">The parser of the file's grammar.</param>
+    /// <param name="input">The file's text.</param>
+    /// <param name="sourceFile">The file's path, recorded in the entries' locations.</param>
+    /// <returns>The entries in source order, each after the entries enclosing it.</returns>
+    public static IReadOnlyList<SkeletonEntry> Extract(GeneralizedParser parser, string input, string? sourceFile = null)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+        ArgumentNullException.ThrowIfNull(input);
+
+        return Extract(parser.Parse(input, new ParseOptions { Lazy = true, SourceFile = sourceFile }));
+    }
+
+    /// <summary>
+    /// Extracts the skeleton of a parse. Deferred regions the parse has not parsed yet are left unparsed.
+    /// </summary>
+    /// <param name="parse">The parse, normally lazy.</param>
+    /// <returns>The entries in source order, each after the entries enclosing it.</returns>
+    public static IReadOnlyList<SkeletonEntry> Extract(ParseResult parse)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+
+        var entries = new List<SkeletonEntry>();
+        if (parse.Tree == null)
+        {
+            return entries;
+        }
+
+        var identifiers = GetIdentifierKinds(parse.Grammar?.Source);
+        var lineIndex = new LineIndex(parse.Input);
+
+        // Long lists nest as deeply as they are long, so the walk keeps its own stack. An entry is added when its node
+        // is popped, which keeps the entries in source order with each after the entries enclosing it.
+        var stack = new Stack<(CognitiveGraphNode Node, int EnclosingLine, IReadOnlyList<string> Path, SkeletonEntry? Entry)>();
+        stack.Push((parse.Tree, 0, Array.Empty<string>(), null));
+        while (stack.Count > 0)
+        {
+            var (node, enclosingLine, path, entry) = stack.Pop();
+            if (entry != null)
+            {
+                entries.Add(entry);
+            }
+
+            if (node is DeferredNode { IsMaterialized: false })
+            {
+                continue;
+            }
+
+            var children = node.Children.ToList();
+            for (var i = children.Count - 1; i >= 0; i--)
+            {
+                var child = children[i];
+                if (!OutlineProvider.IsEntry(node, child, parse.Grammar, enclosingLine))
+                {
+                    stack.Push((child, enclosingLine, path, null));
+                    continue;
+                }
+
+                var location = child.SourcePosition!;
+                var signature = Signature(child, lineIndex);
+                var name = FirstIdentifier(child, parse.Grammar, identifiers) ?? signature;
+                var nested = new SkeletonEntry(name, ((NonTerminalNode)child).RuleName, signature, location, path.Append(name).ToList());
+                stack.Push((child, location.Line, nested.Path, nested));
+            }
+        }
+
+        return entries;
+    }
+
+    /// <summary>
+    /// Writes entries as JSON lines, one object per entry with its file, name, kind, signature, qualified path and
+    /// span, for indexers that stream them.
+    /// </summary>
+    /// <param name="writer">The writer.</param>
+    /// <param name="entries">The entries.</param>
+    /// <param name="file">The file the entries belong to, if any.</param>
+    public static void WriteJsonLines(TextWriter writer, IEnumerable<SkeletonEntry> entries, string? file = null)
+    {
+        ArgumentNullException.ThrowIfNull(writer);
+        ArgumentNullException.ThrowIfNull(entries);
+
+        var buffer = new MemoryStream();
+        foreach (var entry in entries)
+        {
+            buffer.SetLength(0);
+            using (var json = new Utf8JsonWriter(buffer))
+            {
+                json.WriteStartObject();
+                if (file != null)
+                {
+                    json.WriteString("file", file);
+                }
+
+                json.WriteString("name", entry.Name);
+                json.WriteString("kind", entry.Kind);
+                json.WriteString("signature", entry.Signature);
+                json.WriteStartArray("path");
+                foreach (var name in entry.Path)
+                {
+                    json.WriteStringValue(name);
+                }
+
+                json.WriteEndArray();
+                ParseTreeExport.WriteJsonSpan(json, entry.Location);
+                json.WriteEndObject();
+            }
+
+            writer.WriteLine(Encoding.UTF8.GetString(buffer.GetBuffer(), 0, (int)buffer.Length));
+        }
+    }
+
+    internal static HashSet<string> GetIdentifierKinds(Grammar? grammar)
+    {
+        var kinds = new HashSet<string>(StringComparer.Ordinal) { "IDENTIFIER" };
+        if (grammar != null)
+        {
+            kinds.UnionWith(grammar.TokenRules.GetPatternsByType(TokenType.Identifier).Select(p => p.Name));
+        }
+
+        return kinds;
+    }
+
+    // The first identifier of the declaration itself: nested declarations and unparsed bodies are not searched.
+    private static string? FirstIdentifier(CognitiveGraphNode declaration, CompiledGrammar? grammar, HashSet<string> identifiers)
+    {
+        var stack = new Stack<CognitiveGraphNode>();
+        stack.Push(declaration);
+        while (stack.Count > 0)
+        {
+            var node = stack.Pop();
+            if (node is TerminalNode terminal && identifiers.Contains(terminal.TokenType))
+            {
+                return terminal.Text;
+            }
+
+            var nested = node != declaration && node is NonTerminalNode rule && grammar?.GetRule(rule.RuleName)?.IsOutlined == true;
+            if (nested || node is DeferredNode { IsMaterialized: false })
+            {
+                continue;
+            }
+
+            foreach (var child in node.Children.Reverse())
+            {
+                stack.Push(child);
+            }
+        }
+
+        return null;
+    }
+
+    // The text from the start of the declaration to its first unparsed body or the end of its first line.
+    private static string Signature(CognitiveGraphNode declaration, LineIndex lineIndex)
+    {
+        var location = declaration.SourcePosition!;
+        var end = Math.Min(location.Offset + location.Length, lineIndex.GetLineContentEnd(location.Line));
+
+        var stack = new Stack<CognitiveGraphNode>();
+        stack.Push(declaration);
+        while (stack.Count > 0)
+        {
+            var node = stack.Pop();
+            if (node.SourcePosition is { } position && position.Offset >= end)
+            {
+                continue;
+            }
+
+            if (node is DeferredNode { IsMaterialized: false } body)
+            {
+                end = Math.Min(end, body.SourcePosition?.Offset ?? end);
+                continue;
+            }
+
+            foreach (var child in node.Children.Reverse())
+            {
+                stack.Push(child);
+            }
+        }
+
+        return lineIndex.Text[location.Offset..end].Trim().TrimEnd(OpeningBrackets).TrimEnd();
+    }
+}
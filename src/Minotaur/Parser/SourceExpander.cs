@@ -0,0 +1,561 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The kinds of step in a token's expansion backtrace.
+/// </summary>
+public enum ExpansionSiteKind
+{
+    /// <summary>
+    /// An include directive brought in the file the token is read from.
+    /// </summary>
+    Include,
+
+    /// <summary>
+    /// The token was substituted for a macro use.
+    /// </summary>
+    Macro
+}
+
+/// <summary>
+/// A step of a token's expansion backtrace: the include directive that brought in its file, or the macro use it
+/// was substituted for.
+/// </summary>
+/// <param name="Kind">The kind of step.</param>
+/// <param name="Name">The include target as written in the directive, or the macro name.</param>
+/// <param name="Position">The position of the directive or macro use, with its file.</param>
+public sealed record ExpansionSite(ExpansionSiteKind Kind, string Name, SourcePosition Position)
+{
+    /// <summary>
+    /// Returns the step as a diagnostic note, e.g. "in expansion of 'LIMIT' at main.c:3:9".
+    /// </summary>
+    /// <returns>The note.</returns>
+    public override string ToString()
+    {
+        var at = $"{Position.SourceFile}:{Position.Line}:{Position.Column}";
+        return Kind == ExpansionSiteKind.Macro ? $"in expansion of '{Name}' at {at}" : $"included from {at}";
+    }
+}
+
+/// <summary>
+/// Where an expanded token comes from: the position it is spelled at and the expansions that brought it to the
+/// expanded stream, innermost first.
+/// </summary>
+/// <param name="Position">The position of the token's text, with its file; for a macro's tokens, in the macro's
+/// definition.</param>
+/// <param name="Backtrace">The expansion sites, innermost first; empty for a token of the main file.</param>
+public sealed record TokenOrigin(SourcePosition Position, IReadOnlyList<ExpansionSite> Backtrace)
+{
+    /// <summary>
+    /// Gets the position of the token before macro expansion: the outermost macro use it was substituted for, or
+    /// its own position if no macro was involved.
+    /// </summary>
+    public SourcePosition UnexpandedPosition
+    {
+        get
+        {
+            var position = Position;
+            foreach (var site in Backtrace.TakeWhile(s => s.Kind == ExpansionSiteKind.Macro))
+            {
+                position = site.Position;
+            }
+
+            return position;
+        }
+    }
+}
+
+/// <summary>
+/// A file an <see cref="Expander"/> resolved an include to.
+/// </summary>
+/// <param name="Path">The file's path, which identifies it in backtraces and cycle detection.</param>
+/// <param name="Text">The file's text.</param>
+public sealed record IncludedSource(string Path, string Text);
+
+/// <summary>
+/// The host's side of source expansion: reading directives, resolving includes and substituting macros. The
+/// <see cref="SourceExpander"/> finds the directives, follows the includes and keeps every token's backtrace.
+/// </summary>
+public abstract class Expander
+{
+    /// <summary>
+    /// Handles a directive line, for example by recording a macro definition.
+    /// </summary>
+    /// <param name="directive">The name of the entry point the line parsed as.</param>
+    /// <param name="node">The directive's parse tree.</param>
+    /// <param name="tokens">The directive's tokens; a macro's tokens returned later by <see cref="Substitute"/>
+    /// keep their position in the definition when they are taken from here.</param>
+    /// <returns>The include target if the directive includes a file, or null.</returns>
+    public abstract string? OnDirective(string directive, NonTerminalNode node, IReadOnlyList<Token> tokens);
+
+    /// <summary>
+    /// Resolves an include target.
+    /// </summary>
+    /// <param name="target">The target as returned by <see cref="OnDirective"/>.</param>
+    /// <param name="includingFile">The path of the file with the directive.</param>
+    /// <returns>The included file, or null if it cannot be found.</returns>
+    public abstract IncludedSource? ResolveInclude(string target, string includingFile);
+
+    /// <summary>
+    /// Gets the tokens a macro use expands to.
+    /// </summary>
+    /// <param name="token">A token of the source or of another macro's expansion.</param>
+    /// <returns>The replacement tokens, or null if the token is not a macro use.</returns>
+    public abstract IReadOnlyList<Token>? Substitute(Token token);
+}
+
+/// <summary>
+/// Expands includes and macros before parsing, for preprocessed languages like C or configuration formats with
+/// includes. Lines that parse as one of the entry points named in the grammar's "PreprocessorDirectives" metadata
+/// are directives: they are handed to the <see cref="Expander"/> and left out of the expanded tokens, and the
+/// files they include are expanded in their place. Every other token goes through
+/// <see cref="Expander.Substitute"/>, and the tokens substituted for it are expanded again.
+/// </summary>
+/// <remarks>
+/// A macro is not expanded again inside its own expansion. A file including itself is reported as a
+/// <see cref="DiagnosticCodes.RecursiveInclude"/> error and nesting past <see cref="MaxIncludeDepth"/> or
+/// <see cref="MaxMacroDepth"/> as <see cref="DiagnosticCodes.ExpansionTooDeep"/>.
+/// </remarks>
+public sealed class SourceExpander
+{
+    /// <summary>
+    /// The metadata key naming the entry points that directive lines parse as, e.g. <c>include, define</c>.
+    /// </summary>
+    public const string DirectivesKey = "PreprocessorDirectives";
+
+    private readonly GeneralizedParser _parser;
+    private readonly Expander _expander;
+    private readonly List<(string Name, GeneralizedParser Parser, HashSet<string>? Starts)> _directives = new();
+
+    /// <summary>
+    /// Initializes a new instance of the SourceExpander class.
+    /// </summary>
+    /// <param name="parser">The parser of the language, whose lexer tokenizes every file.</param>
+    /// <param name="expander">The host's expander.</param>
+    /// <exception cref="ArgumentException">A directive the grammar names is not one of its entry points.</exception>
+    public SourceExpander(GeneralizedParser parser, Expander expander)
+    {
+        _parser = parser ?? throw new ArgumentNullException(nameof(parser));
+        _expander = expander ?? throw new ArgumentNullException(nameof(expander));
+
+        var names = parser.Grammar.Source.Metadata.GetValueOrDefault(DirectivesKey) ?? string.Empty;
+        foreach (var name in names.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var directive = parser.ForEntry(name);
+            _directives.Add((name, directive, FirstTerminals(directive.Entry!.Rule)));
+        }
+    }
+
+    /// <summary>
+    /// Gets the names of the entry points that directive lines parse as.
+    /// </summary>
+    public IReadOnlyList<string> Directives => _directives.Select(d => d.Name).ToList();
+
+    /// <summary>
+    /// Gets or sets how deeply includes may nest.
+    /// </summary>
+    public int MaxIncludeDepth { get; set; } = 64;
+
+    /// <summary>
+    /// Gets or sets how deeply macro expansions may nest.
+    /// </summary>
+    public int MaxMacroDepth { get; set; } = 64;
+
+    /// <summary>
+    /// Expands a file.
+    /// </summary>
+    /// <param name="file">The path of the file, reported in backtraces.</param>
+    /// <param name="text">The file's text.</param>
+    /// <returns>The expanded tokens with their origins.</returns>
+    public ExpandedSource Expand(string file, string text)
+    {
+        ArgumentNullException.ThrowIfNull(file);
+        ArgumentNullException.ThrowIfNull(text);
+
+        var run = new ExpansionRun(this);
+        run.Active.Add(file);
+        run.ExpandFile(file, text, Array.Empty<ExpansionSite>(), 0);
+        return new ExpandedSource(run.Text.ToString(), run.Tokens, run.Origins, run.Files, run.Diagnostics);
+    }
+
+    // The terminals every alternative of the rule starts with, or null if one starts otherwise and every line
+    // has to be tried.
+    private static HashSet<string>? FirstTerminals(CompiledRule rule)
+    {
+        var starts = new HashSet<string>(StringComparer.Ordinal);
+        foreach (var alternative in rule.Alternatives)
+        {
+            if (alternative.Symbols.Count == 0 || !alternative.Symbols[0].IsTerminal)
+            {
+                return null;
+            }
+
+            starts.Add(alternative.Symbols[0].Key);
+        }
+
+        return starts;
+    }
+
+    private sealed class ExpansionRun
+    {
+        private readonly SourceExpander _owner;
+        private readonly Dictionary<Token, TokenOrigin> _spelled = new(ReferenceEqualityComparer.Instance);
+
+        public ExpansionRun(SourceExpander owner)
+        {
+            _owner = owner;
+        }
+
+        public StringBuilder Text { get; } = new();
+
+        public List<Token> Tokens { get; } = new();
+
+        public List<TokenOrigin> Origins { get; } = new();
+
+        public Dictionary<string, string> Files { get; } = new(StringComparer.Ordinal);
+
+        public List<Diagnostic> Diagnostics { get; } = new();
+
+        public List<string> Active { get; } = new();
+
+        public void ExpandFile(string path, string text, IReadOnlyList<ExpansionSite> backtrace, int depth)
+        {
+            Files[path] = text;
+            var lineIndex = new LineIndex(text);
+            var lexResult = _owner._parser.Lexer.Tokenize(text);
+            foreach (var diagnostic in lexResult.Diagnostics)
+            {
+                if (diagnostic.Location is { } location)
+                {
+                    diagnostic.Location = lineIndex.GetPosition(location.Offset, location.Length, path);
+                }
+
+                diagnostic.Data["backtrace"] = backtrace;
+                Diagnostics.Add(diagnostic);
+            }
+
+            var tokens = lexResult.Tokens;
+            for (var i = 0; i < tokens.Count;)
+            {
+                var line = lineIndex.GetLineColumn(tokens[i].Offset).Line;
+                var end = i + 1;
+                while (end < tokens.Count && lineIndex.GetLineColumn(tokens[end].Offset).Line == line)
+                {
+                    end++;
+                }
+
+                var lineTokens = new List<Token>(end - i);
+                for (var j = i; j < end; j++)
+                {
+                    lineTokens.Add(tokens[j]);
+                    _spelled[tokens[j]] = new TokenOrigin(lineIndex.GetPosition(tokens[j].Offset, tokens[j].Length, path), backtrace);
+                }
+
+                if (!TryDirective(path, text, lineIndex, lineTokens, backtrace, depth))
+                {
+                    foreach (var token in lineTokens)
+                    {
+                        Emit(token, _spelled[token], 0);
+                    }
+                }
+
+                i = end;
+            }
+        }
+
+        private bool TryDirective(string path, string text, LineIndex lineIndex, List<Token> lineTokens, IReadOnlyList<ExpansionSite> backtrace, int depth)
+        {
+            foreach (var (name, parser, starts) in _owner._directives)
+            {
+                if (starts != null && !starts.Contains(lineTokens[0].Kind))
+                {
+                    continue;
+                }
+
+                var result = parser.Parse(text, lineTokens);
+                if (!result.IsSuccess || result.Tree is not NonTerminalNode node)
+                {
+                    continue;
+                }
+
+                if (_owner._expander.OnDirective(name, node, lineTokens) is { } target)
+                {
+                    var start = lineTokens[0].Offset;
+                    Include(target, path, lineIndex.GetPosition(start, lineTokens[^1].End - start, path), backtrace, depth);
+                }
+
+                return true;
+            }
+
+            return false;
+        }
+
+        private void Include(string target, string path, SourcePosition position, IReadOnlyList<ExpansionSite> backtrace, int depth)
+        {
+            if (depth >= _owner.MaxIncludeDepth)
+            {
+                Report(DiagnosticCodes.ExpansionTooDeep, position, backtrace, ("name", target), ("limit", _owner.MaxIncludeDepth));
+                return;
+            }
+
+            if (_owner._expander.ResolveInclude(target, path) is not { } source)
+            {
+                Report(DiagnosticCodes.UnresolvedInclude, position, backtrace, ("target", target), ("file", path));
+                return;
+            }
+
+            if (Active.Contains(source.Path))
+            {
+                var chain = Active.SkipWhile(p => p != source.Path).Append(source.Path);
+                Report(DiagnosticCodes.RecursiveInclude, position, backtrace, ("file", source.Path), ("chain", string.Join(" -> ", chain)));
+                return;
+            }
+
+            Active.Add(source.Path);
+            ExpandFile(source.Path, source.Text, Prepend(new ExpansionSite(ExpansionSiteKind.Include, target, position), backtrace), depth + 1);
+            Active.RemoveAt(Active.Count - 1);
+        }
+
+        private void Emit(Token token, TokenOrigin origin, int depth)
+        {
+            var expanding = origin.Backtrace.Any(s => s.Kind == ExpansionSiteKind.Macro && s.Name == token.Text);
+            if (!expanding && _owner._expander.Substitute(token) is { } replacement)
+            {
+                if (depth >= _owner.MaxMacroDepth)
+                {
+                    Report(DiagnosticCodes.ExpansionTooDeep, origin.Position, origin.Backtrace, ("name", token.Text), ("limit", _owner.MaxMacroDepth));
+                    return;
+                }
+
+                var backtrace = Prepend(new ExpansionSite(ExpansionSiteKind.Macro, token.Text, origin.Position), origin.Backtrace);
+                foreach (var substituted in replacement)
+                {
+                    var position = _spelled.TryGetValue(substituted, out var spelled) ? spelled.Position : origin.Position;
+                    Emit(substituted, new TokenOrigin(position, backtrace), depth + 1);
+                }
+
+                return;
+            }
+
+            // Tokens from one unexpanded line stay on one expanded line, so the expanded text reads like the source.
+            if (Origins.Count > 0)
+            {
+                var previous = Origins[^1].UnexpandedPosition;
+                var current = origin.UnexpandedPosition;
+                Text.Append(previous.SourceFile == current.SourceFile && previous.Line == current.Line ? ' ' : '\n');
+            }
+
+            Tokens.Add(token with { Offset = Text.Length });
+            Origins.Add(origin);
+            Text.Append(token.Text);
+        }
+
+        private void Report(string code, SourcePosition position, IReadOnlyList<ExpansionSite> backtrace, params (string Name, object Value)[] arguments)
+        {
+            Diagnostics.Add(new Diagnostic
+            {
+                Code = code,
+                Location = position,
+                Data = { ["backtrace"] = backtrace }
+            }.WithMessage(code, arguments));
+        }
+
+        private static IReadOnlyList<ExpansionSite> Prepend(ExpansionSite site, IReadOnlyList<ExpansionSite> backtrace)
+        {
+            var list = new List<ExpansionSite>(backtrace.Count + 1) { site };
+            list.AddRange(backtrace);
+            return list;
+        }
+    }
+}
+
+/// <summary>
+/// The result of <see cref="SourceExpander.Expand"/>: the expanded tokens, laid out in <see cref="Text"/> one
+/// unexpanded line per line, and where each of them comes from. A parse of the expanded tokens is the expanded
+/// view of the source; <see cref="GetUnexpandedText"/> gives the unexpanded text of its nodes.
+/// </summary>
+public sealed class ExpandedSource
+{
+    internal ExpandedSource(string text, IReadOnlyList<Token> tokens, IReadOnlyList<TokenOrigin> origins, IReadOnlyDictionary<string, string> files, IReadOnlyList<Diagnostic> diagnostics)
+    {
+        Text = text;
+        Tokens = tokens;
+        Origins = origins;
+        Files = files;
+        Diagnostics = diagnostics;
+    }
+
+    /// <summary>
+    /// Gets the expanded text, which the tokens' offsets refer to.
+    /// </summary>
+    public string Text { get; }
+
+    /// <summary>
+    /// Gets the expanded tokens.
+    /// </summary>
+    public IReadOnlyList<Token> Tokens { get; }
+
+    /// <summary>
+    /// Gets the origin of each token, at the token's index.
+    /// </summary>
+    public IReadOnlyList<TokenOrigin> Origins { get; }
+
+    /// <summary>
+    /// Gets the text of every file expanded, by path.
+    /// </summary>
+    public IReadOnlyDictionary<string, string> Files { get; }
+
+    /// <summary>
+    /// Gets the errors of the expansion and of lexing the files, located in their files with a "backtrace" data
+    /// entry holding their <see cref="ExpansionSite"/>s.
+    /// </summary>
+    public IReadOnlyList<Diagnostic> Diagnostics { get; }
+
+    /// <summary>
+    /// Parses the expanded tokens. The tree is in expanded positions, and the diagnostics are moved to the
+    /// positions they come from, with their expanded location in an "expandedLocation" data entry and their
+    /// <see cref="ExpansionSite"/>s in a "backtrace" entry. The expansion's own diagnostics are not included.
+    /// </summary>
+    /// <param name="parser">The parser.</param>
+    /// <param name="options">Optional parse options.</param>
+    /// <returns>The parse result.</returns>
+    public ParseResult Parse(GeneralizedParser parser, ParseOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+
+        var result = parser.Parse(Text, Tokens, options);
+        foreach (var diagnostic in result.Diagnostics)
+        {
+            if (diagnostic.Location is { } location && GetOrigin(location.Offset) is { } origin)
+            {
+                diagnostic.Data["expandedLocation"] = location;
+                diagnostic.Data["backtrace"] = origin.Backtrace;
+                diagnostic.Location = origin.Position;
+            }
+        }
+
+        return result;
+    }
+
+    /// <summary>
+    /// Gets the origin of the expanded text at an offset: of the token there, or the end of the token before it.
+    /// </summary>
+    /// <param name="offset">The offset in <see cref="Text"/>.</param>
+    /// <returns>The origin, or null if there are no tokens.</returns>
+    public TokenOrigin? GetOrigin(int offset)
+    {
+        var index = FindToken(offset);
+        if (index < 0)
+        {
+            return Tokens.Count > 0 ? Origins[0] : null;
+        }
+
+        var origin = Origins[index];
+        return offset >= Tokens[index].End && offset > Tokens[index].Offset ? origin with { Position = origin.Position.End } : origin;
+    }
+
+    /// <summary>
+    /// Gets the text a node of a parse of the expanded tokens was written as, with its macro uses unexpanded.
+    /// </summary>
+    /// <param name="node">The node.</param>
+    /// <returns>The text, or null if the node has no tokens or they start and end in different files.</returns>
+    public string? GetUnexpandedText(CognitiveGraphNode node)
+    {
+        ArgumentNullException.ThrowIfNull(node);
+
+        if (node.SourcePosition is not { } span)
+        {
+            return null;
+        }
+
+        var first = FindToken(span.Offset);
+        if (first < 0 || Tokens[first].Offset < span.Offset)
+        {
+            first++;
+        }
+
+        var last = FindToken(span.Offset + span.Length - 1);
+        if (first >= Tokens.Count || last < first)
+        {
+            return null;
+        }
+
+        var start = Origins[first].UnexpandedPosition;
+        var end = Origins[last].UnexpandedPosition;
+        if (start.SourceFile is not { } file || end.SourceFile != file || !Files.TryGetValue(file, out var text) || end.Offset + end.Length < start.Offset)
+        {
+            return null;
+        }
+
+        return text[start.Offset..(end.Offset + end.Length)];
+    }
+
+    /// <summary>
+    /// Renders a diagnostic with its file and its expansion backtrace, one note per line, e.g.
+    /// "limits.h:2:15: error E0001: ..." followed by "  in expansion of 'LIMIT' at main.c:3:9".
+    /// </summary>
+    /// <param name="diagnostic">The diagnostic.</param>
+    /// <returns>The rendering.</returns>
+    public static string Format(Diagnostic diagnostic)
+    {
+        ArgumentNullException.ThrowIfNull(diagnostic);
+
+        var text = new StringBuilder();
+        if (diagnostic.Location?.SourceFile is { } file)
+        {
+            text.Append(file).Append(':');
+        }
+
+        text.Append(diagnostic);
+        if (diagnostic.Data.GetValueOrDefault("backtrace") is IReadOnlyList<ExpansionSite> backtrace)
+        {
+            foreach (var site in backtrace)
+            {
+                text.Append("\n  ").Append(site);
+            }
+        }
+
+        return text.ToString();
+    }
+
+    // The index of the last token starting at or before the offset, or -1.
+    private int FindToken(int offset)
+    {
+        int low = 0, high = Tokens.Count - 1, found = -1;
+        while (low <= high)
+        {
+            var middle = (low + high) / 2;
+            if (Tokens[middle].Offset <= offset)
+            {
+                found = middle;
+                low = middle + 1;
+            }
+            else
+            {
+                high = middle - 1;
+            }
+        }
+
+        return found;
+    }
+}
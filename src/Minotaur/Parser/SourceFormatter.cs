@@ -0,0 +1,305 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Formats source by reprinting its tokens with synthesized whitespace, laid out like <see cref="AstBuilder.Print"/>:
+/// one space between tokens except next to brackets and before separators, line breaks after <c>;</c> and around
+/// <c>{ }</c> blocks, and one indentation level per open block. Lines longer than <see cref="MaxLineLength"/> are
+/// wrapped after their last comma or opening bracket that fits, continuing one level deeper. Comments and other
+/// text between tokens are kept, and the result carries the <see cref="PositionMap"/> from the input to the output.
+/// </summary>
+public sealed class SourceFormatter
+{
+    private readonly CompiledGrammar _grammar;
+    private readonly TokenSpacing _spacing;
+
+    /// <summary>
+    /// Initializes a new instance of the SourceFormatter class.
+    /// </summary>
+    /// <param name="grammar">The grammar whose lexer reads the formatted source.</param>
+    public SourceFormatter(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        _grammar = grammar;
+        _spacing = new TokenSpacing(grammar.Lexer, grammar.EditorInfo.Brackets);
+    }
+
+    /// <summary>
+    /// Gets or sets the length past which lines are wrapped.
+    /// </summary>
+    public int MaxLineLength { get; set; } = 100;
+
+    /// <summary>
+    /// Gets or sets the text of one indentation level.
+    /// </summary>
+    public string IndentString { get; set; } = "    ";
+
+    /// <summary>
+    /// Formats source. Lines end the way most lines of the input do.
+    /// </summary>
+    /// <param name="text">The source.</param>
+    /// <returns>The formatted source and the map from the input to it.</returns>
+    /// <exception cref="ArgumentException">The grammar is scannerless or the source does not lex.</exception>
+    public FormatResult Format(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        if (_grammar.IsScannerless)
+        {
+            throw new ArgumentException($"Grammar '{_grammar.Name}' is scannerless; formatting needs tokens", nameof(text));
+        }
+
+        var lexed = _grammar.Lexer.Tokenize(text);
+        if (lexed.Diagnostics.FirstOrDefault(d => d.Severity == Diagnostics.DiagnosticSeverity.Error) is { } error)
+        {
+            throw new ArgumentException($"Source does not lex: {error.Message}", nameof(text));
+        }
+
+        var lineEnding = new LineIndex(text).DominantLineEnding == LineEnding.CrLf ? "\r\n" : "\n";
+        var output = new StringBuilder();
+        var anchors = new List<(TextRange Original, TextRange Output)>();
+        foreach (var line in Layout(text, lexed.Tokens.Where(t => !t.IsSynthetic).ToList()))
+        {
+            foreach (var row in Wrap(line))
+            {
+                for (var i = 0; i < row.Indent; i++)
+                {
+                    output.Append(IndentString);
+                }
+
+                for (var i = 0; i < row.Items.Count; i++)
+                {
+                    var item = row.Items[i];
+                    if (i > 0 && item.SpaceBefore)
+                    {
+                        output.Append(' ');
+                    }
+
+                    anchors.Add((item.Source, new TextRange(output.Length, item.Text.Length)));
+                    output.Append(item.Text);
+                }
+
+                output.Append(lineEnding);
+            }
+        }
+
+        return new FormatResult(output.ToString(), PositionMap.FromAnchors(text, output.ToString(), anchors));
+    }
+
+    /// <summary>
+    /// Splits the tokens and the comments between them into logical lines.
+    /// </summary>
+    private List<LayoutLine> Layout(string text, List<Token> tokens)
+    {
+        var lines = new List<LayoutLine>();
+        var current = new LayoutLine(0);
+        var indent = 0;
+        string? previous = null;
+
+        void Add(string itemText, TextRange source, bool trivia)
+        {
+            if (current.Items.Count == 0)
+            {
+                current = new LayoutLine(indent);
+            }
+
+            var space = previous != null && current.Items.Count > 0 && (trivia || current.Items[^1].IsTrivia || _spacing.NeedsSpace(previous, itemText));
+            current.Items.Add(new LayoutItem(itemText, source, space, trivia));
+            previous = itemText;
+        }
+
+        void EndLine()
+        {
+            if (current.Items.Count > 0)
+            {
+                lines.Add(current);
+                current = new LayoutLine(indent);
+            }
+        }
+
+        var position = 0;
+        for (var i = 0; i <= tokens.Count; i++)
+        {
+            var next = i < tokens.Count ? tokens[i].Offset : text.Length;
+            var gap = text[position..next];
+            if (gap.Trim().Length > 0)
+            {
+                var start = position + gap.Length - gap.TrimStart().Length;
+                var trimmed = gap.Trim();
+                if (current.Items.Count == 0 && lines.Count > 0 && !gap[..(start - position)].Contains('\n'))
+                {
+                    // A comment after the end of a line stays at its end.
+                    lines[^1].Items.Add(new LayoutItem(trimmed, new TextRange(start, trimmed.Length), true, true));
+                    previous = trimmed;
+                }
+                else
+                {
+                    Add(trimmed, new TextRange(start, trimmed.Length), true);
+                }
+
+                if (gap[(start - position + trimmed.Length)..].Contains('\n') || i == tokens.Count)
+                {
+                    EndLine();
+                }
+            }
+
+            if (i == tokens.Count)
+            {
+                break;
+            }
+
+            var token = tokens[i];
+            if (token.Text == "}")
+            {
+                EndLine();
+                indent = Math.Max(0, indent - 1);
+            }
+
+            Add(token.Text, new TextRange(token.Offset, token.Length), false);
+            position = token.End;
+
+            if (token.Text == "{")
+            {
+                indent++;
+            }
+
+            // A block closing inside an expression keeps the separator that follows it on its line.
+            var following = i + 1 < tokens.Count ? tokens[i + 1].Text : null;
+            if (token.Text is "{" or ";" || (token.Text == "}" && (following == null || !_spacing.AttachesLeft(following))))
+            {
+                EndLine();
+            }
+        }
+
+        EndLine();
+        return lines;
+    }
+
+    /// <summary>
+    /// Wraps a logical line into rows no longer than the maximum where it can, breaking after the last comma or
+    /// opening bracket that fits; continuation rows are indented one level deeper.
+    /// </summary>
+    private IEnumerable<LayoutLine> Wrap(LayoutLine line)
+    {
+        var row = new LayoutLine(line.Indent);
+        var width = line.Indent * IndentString.Length;
+        var lastBreak = -1;
+        foreach (var item in line.Items)
+        {
+            var added = (row.Items.Count > 0 && item.SpaceBefore ? 1 : 0) + item.Text.Length;
+            if (row.Items.Count > 0 && width + added > MaxLineLength && lastBreak > 0 && lastBreak < row.Items.Count)
+            {
+                var moved = row.Items.GetRange(lastBreak, row.Items.Count - lastBreak);
+                row.Items.RemoveRange(lastBreak, moved.Count);
+                yield return row;
+
+                row = new LayoutLine(line.Indent + 1);
+                row.Items.AddRange(moved);
+                width = row.Indent * IndentString.Length + moved.Select((m, i) => (i > 0 && m.SpaceBefore ? 1 : 0) + m.Text.Length).Sum();
+                lastBreak = moved.FindLastIndex(m => _spacing.IsBreakAfter(m.Text) && !m.IsTrivia) + 1;
+                added = (item.SpaceBefore ? 1 : 0) + item.Text.Length;
+            }
+
+            row.Items.Add(item);
+            width += added;
+            if (!item.IsTrivia && _spacing.IsBreakAfter(item.Text))
+            {
+                lastBreak = row.Items.Count;
+            }
+        }
+
+        yield return row;
+    }
+
+    private sealed record LayoutItem(string Text, TextRange Source, bool SpaceBefore, bool IsTrivia);
+
+    private sealed class LayoutLine
+    {
+        public LayoutLine(int indent) => Indent = indent;
+
+        public int Indent { get; }
+
+        public List<LayoutItem> Items { get; } = new();
+    }
+}
+
+/// <summary>
+/// The result of <see cref="SourceFormatter.Format"/>.
+/// </summary>
+/// <param name="Text">The formatted source.</param>
+/// <param name="Map">The map from the input to the formatted source.</param>
+public sealed record FormatResult(string Text, PositionMap Map);
+
+/// <summary>
+/// Decides the whitespace synthesized between two tokens printed next to each other.
+/// </summary>
+internal sealed class TokenSpacing
+{
+    private static readonly HashSet<string> NoSpaceBefore = new(StringComparer.Ordinal) { ",", ";", ".", ":", "::" };
+    private static readonly HashSet<string> NoSpaceAfter = new(StringComparer.Ordinal) { ".", "::" };
+
+    private readonly GrammarLexer _lexer;
+    private readonly HashSet<string> _opens;
+    private readonly HashSet<string> _closes;
+
+    public TokenSpacing(GrammarLexer lexer, IReadOnlyList<EditorPair> brackets)
+    {
+        _lexer = lexer;
+        _opens = brackets.Where(b => !b.IsQuote).Select(b => b.Open).ToHashSet(StringComparer.Ordinal);
+        _closes = brackets.Where(b => !b.IsQuote).Select(b => b.Close).ToHashSet(StringComparer.Ordinal);
+    }
+
+    /// <summary>
+    /// Determines whether a space goes between two tokens: one does unless the first opens a bracket or is a
+    /// member separator, the second closes a bracket or is a separator, or a word is followed by an opening
+    /// bracket other than a block's; and one always does where the tokens would otherwise lex as others.
+    /// </summary>
+    public bool NeedsSpace(string previous, string text)
+    {
+        var tokens = _lexer.Tokenize(previous + text).Tokens;
+        if (tokens.Count != 2 || tokens[0].Text != previous || tokens[1].Text != text)
+        {
+            return true;
+        }
+
+        var word = char.IsLetterOrDigit(previous[^1]) || previous[^1] == '_';
+        return !(_opens.Contains(previous) || NoSpaceAfter.Contains(previous) || AttachesLeft(text)
+            || (word && _opens.Contains(text) && text != "{"));
+    }
+
+    /// <summary>
+    /// Determines whether a token is written against the token before it: a closing bracket or a separator.
+    /// </summary>
+    public bool AttachesLeft(string text)
+    {
+        return _closes.Contains(text) || NoSpaceBefore.Contains(text);
+    }
+
+    /// <summary>
+    /// Determines whether a line may be wrapped after a token: a comma or an opening bracket.
+    /// </summary>
+    public bool IsBreakAfter(string text)
+    {
+        return text == "," || (_opens.Contains(text) && text != "{");
+    }
+}
@@ -0,0 +1,85 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The built-in "concatenate-strings" <see cref="ITokenFilter"/>: merges adjacent string literal tokens into one,
+/// as C concatenates <c>"a" "b"</c> into <c>"ab"</c>. The merged token spans the literals and the layout between
+/// them, so its text is the source text and semantic actions see every part.
+/// </summary>
+public sealed class StringConcatenationFilter : ITokenFilter
+{
+    /// <summary>
+    /// The token kind merged by default.
+    /// </summary>
+    public const string DefaultKind = "STRING";
+
+    /// <summary>
+    /// Initializes a new instance of the StringConcatenationFilter class.
+    /// </summary>
+    /// <param name="kinds">The token kinds to merge; adjacent tokens merge only with tokens of the same kind.
+    /// If null, <see cref="DefaultKind"/>.</param>
+    public StringConcatenationFilter(IEnumerable<string>? kinds = null)
+    {
+        Kinds = new HashSet<string>(kinds ?? new[] { DefaultKind }, StringComparer.Ordinal);
+    }
+
+    /// <summary>
+    /// Gets the token kinds that are merged.
+    /// </summary>
+    public IReadOnlySet<string> Kinds { get; }
+
+    /// <inheritdoc />
+    public IEnumerable<Token> Filter(IEnumerable<Token> tokens, TokenFilterContext context)
+    {
+        ArgumentNullException.ThrowIfNull(tokens);
+        ArgumentNullException.ThrowIfNull(context);
+
+        return Merge(tokens, context.Input);
+    }
+
+    private IEnumerable<Token> Merge(IEnumerable<Token> tokens, string input)
+    {
+        Token? run = null;
+        foreach (var token in tokens)
+        {
+            if (run != null && token.Kind == run.Kind && !token.IsSynthetic)
+            {
+                run = run with { Text = input[run.Offset..token.End] };
+                continue;
+            }
+
+            if (run != null)
+            {
+                yield return run;
+            }
+
+            run = Kinds.Contains(token.Kind) && !token.IsSynthetic ? token : null;
+            if (run == null)
+            {
+                yield return token;
+            }
+        }
+
+        if (run != null)
+        {
+            yield return run;
+        }
+    }
+}
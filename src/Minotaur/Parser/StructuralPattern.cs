@@ -0,0 +1,551 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+using System.Text;
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration.Models;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A structural search pattern: source of the target language in which a <c>$name</c> metavariable stands for a
+/// subtree and a <c>$$name</c> metavariable for a possibly empty run of sibling subtrees, such as the statements of
+/// a block. Patterns are parsed against the grammar with the metavariables as holes, so a pattern that could not
+/// occur in the language is rejected when it is compiled. Matching is token for token: whitespace, comments and
+/// line breaks are ignored, and text inside a string or comment token is never matched. A metavariable that
+/// appears more than once must bind the same tokens each time.
+/// </summary>
+public sealed class StructuralPattern
+{
+    private const string HoleKind = "__metavariable";
+
+    private static readonly Regex Metavariable = new(@"\$\$?[A-Za-z_][A-Za-z0-9_]*", RegexOptions.CultureInvariant);
+    private static readonly ConditionalWeakTable<CompiledGrammar, CompiledGrammar> HoleGrammars = new();
+
+    private readonly IReadOnlyList<PatternElement> _elements;
+
+    private StructuralPattern(CompiledGrammar grammar, string rule, string text, IReadOnlyList<Token> tokens)
+    {
+        Grammar = grammar;
+        Rule = rule;
+        Text = text;
+        _elements = tokens
+            .Select(t => t.Kind == HoleKind ? new PatternElement(t.Text, t.Text.TrimStart('$'), t.Text.StartsWith("$$", StringComparison.Ordinal)) : new PatternElement(t.Text, null, false))
+            .ToList();
+        Metavariables = _elements.Where(e => e.Hole != null).Select(e => e.Hole!).Distinct().ToList();
+    }
+
+    /// <summary>
+    /// Gets the grammar the pattern was compiled against.
+    /// </summary>
+    public CompiledGrammar Grammar { get; }
+
+    /// <summary>
+    /// Gets the rule the pattern parses as; only nodes of this rule are matched.
+    /// </summary>
+    public string Rule { get; }
+
+    /// <summary>
+    /// Gets the pattern source.
+    /// </summary>
+    public string Text { get; }
+
+    /// <summary>
+    /// Gets the names of the pattern's metavariables, without their dollar signs, in order of first appearance.
+    /// </summary>
+    public IReadOnlyList<string> Metavariables { get; }
+
+    /// <summary>
+    /// Compiles a pattern that parses as the specified rule.
+    /// </summary>
+    /// <param name="grammar">The grammar of the searched language.</param>
+    /// <param name="rule">The rule the pattern stands for.</param>
+    /// <param name="text">The pattern source.</param>
+    /// <returns>The compiled pattern.</returns>
+    /// <exception cref="ArgumentException">The rule is unknown, the grammar is scannerless, or the pattern does not parse as the rule.</exception>
+    public static StructuralPattern Compile(CompiledGrammar grammar, string rule, string text)
+    {
+        ArgumentNullException.ThrowIfNull(rule);
+
+        var (parser, tokens) = Prepare(grammar, text);
+        if (grammar.GetRule(rule) == null)
+        {
+            throw new ArgumentException($"Rule '{rule}' is not defined in grammar '{grammar.Name}'", nameof(rule));
+        }
+
+        var result = parser.Parse(text, tokens, new ParseOptions { StartRule = rule });
+        if (!result.IsSuccess)
+        {
+            throw new ArgumentException($"'{text}' is not a valid <{rule}> pattern: {string.Join("; ", result.Diagnostics.Select(d => d.Message))}", nameof(text));
+        }
+
+        return new StructuralPattern(grammar, rule, text, tokens);
+    }
+
+    /// <summary>
+    /// Compiles a pattern, taking the first rule of the grammar the pattern parses as by one of the rule's own
+    /// alternatives rather than through a single nested rule or metavariable. A pattern such as <c>$a + $b</c> thus
+    /// stands for the rule that has the <c>+</c>, not for every rule that can derive it.
+    /// </summary>
+    /// <param name="grammar">The grammar of the searched language.</param>
+    /// <param name="text">The pattern source.</param>
+    /// <returns>The compiled pattern.</returns>
+    /// <exception cref="ArgumentException">The grammar is scannerless or no rule parses the pattern that way.</exception>
+    public static StructuralPattern Compile(CompiledGrammar grammar, string text)
+    {
+        var (parser, tokens) = Prepare(grammar, text);
+        foreach (var rule in grammar.Rules)
+        {
+            var result = parser.Parse(text, tokens, new ParseOptions { StartRule = rule.Name });
+            if (result.IsSuccess && result.Tree!.Children is not [NonTerminalNode] and not [TerminalNode { TokenType: HoleKind }])
+            {
+                return new StructuralPattern(grammar, rule.Name, text, tokens);
+            }
+        }
+
+        throw new ArgumentException($"'{text}' is not a pattern for any rule of grammar '{grammar.Name}'; name the rule it stands for", nameof(text));
+    }
+
+    /// <summary>
+    /// Finds the matches of the pattern in a parse, outer matches before the matches nested in them.
+    /// </summary>
+    /// <param name="parse">The parse to search.</param>
+    /// <returns>The matches in document order; empty if the parse has no tree.</returns>
+    public IReadOnlyList<StructuralMatch> FindAll(ParseResult parse)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+
+        if (parse.Tree == null)
+        {
+            return Array.Empty<StructuralMatch>();
+        }
+
+        var target = new TargetIndex(parse.Tree);
+        var matched = new HashSet<(int, int)>();
+        var matches = new List<StructuralMatch>();
+        foreach (var node in target.Nodes)
+        {
+            if (node is not NonTerminalNode { RuleName: var name } || name != Rule)
+            {
+                continue;
+            }
+
+            var (start, end) = target.Range(node);
+            var bindings = new Dictionary<string, (int Start, int End)>();
+            if (start < end && !matched.Contains((start, end)) && Match(target, 0, start, end, bindings))
+            {
+                matched.Add((start, end));
+                matches.Add(CreateMatch(parse.Input, target, node, bindings));
+            }
+        }
+
+        return matches;
+    }
+
+    /// <summary>
+    /// Replaces every match with a rewrite of it and reparses the result. Metavariables in the template are replaced
+    /// by the source their matches bound, with any matches nested in that source rewritten in turn, so comments and
+    /// formatting inside bound code survive. Text outside the matches is kept as written, and continuation lines of
+    /// the template are indented to match the line each match starts on.
+    /// </summary>
+    /// <param name="parser">The parser that produced the parse, used to validate the rewrites.</param>
+    /// <param name="parse">The parse to rewrite.</param>
+    /// <param name="template">The replacement, written like the pattern with its metavariables.</param>
+    /// <returns>The rewritten text, the edits that produced it and its parse.</returns>
+    /// <exception cref="ArgumentException">The template uses a metavariable the pattern does not bind, the parse
+    /// has no tree, or a rewrite does not parse as the pattern's rule.</exception>
+    public TreeEditResult RewriteAll(GeneralizedParser parser, ParseResult parse, string template)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+        ArgumentNullException.ThrowIfNull(parse);
+        ArgumentNullException.ThrowIfNull(template);
+
+        CheckTemplate(template);
+
+        var editor = new TreeEditor(parser, parse);
+        var matches = FindAll(parse);
+        foreach (var match in Outermost(matches, null, 0, parse.Input.Length))
+        {
+            editor.ReplaceNode(match.Node.Id, Rewrite(parse.Input, matches, match, template), false);
+        }
+
+        return editor.Apply();
+    }
+
+    /// <summary>
+    /// Rewrites a single match without reparsing: metavariables in the template are replaced by the source their
+    /// match bound, as written, and continuation lines of the template are indented to match the line the match
+    /// starts on.
+    /// </summary>
+    /// <param name="parse">The parse the match was found in.</param>
+    /// <param name="match">The match to rewrite.</param>
+    /// <param name="template">The replacement, written like the pattern with its metavariables.</param>
+    /// <returns>The text that replaces the match.</returns>
+    /// <exception cref="ArgumentException">The template uses a metavariable the pattern does not bind.</exception>
+    public string Rewrite(ParseResult parse, StructuralMatch match, string template)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+        ArgumentNullException.ThrowIfNull(match);
+        ArgumentNullException.ThrowIfNull(template);
+
+        CheckTemplate(template);
+        return Rewrite(parse.Input, new[] { match }, match, template);
+    }
+
+    private void CheckTemplate(string template)
+    {
+        var unbound = Metavariable.Matches(template).Select(m => m.Value.TrimStart('$')).FirstOrDefault(n => !Metavariables.Contains(n));
+        if (unbound != null)
+        {
+            throw new ArgumentException($"Rewrite '{template}' uses ${unbound}, which the pattern does not bind", nameof(template));
+        }
+    }
+
+    /// <summary>
+    /// Rewrites a match, rewriting in turn the matches nested in the source its metavariables bound. Bound source
+    /// keeps the indentation it has in the input; only the template's own lines are indented to the line the match
+    /// starts on.
+    /// </summary>
+    private static string Rewrite(string input, IReadOnlyList<StructuralMatch> matches, StructuralMatch match, string template)
+    {
+        var lineStart = match.Start == 0 ? 0 : input.LastIndexOf('\n', match.Start - 1) + 1;
+        var indentation = input[lineStart..match.Start];
+        indentation = indentation[..(indentation.Length - indentation.TrimStart(' ', '\t').Length)];
+        return Metavariable.Replace(template.Replace("\n", "\n" + indentation), m =>
+        {
+            var binding = match.Bindings[m.Value.TrimStart('$')];
+            var text = new StringBuilder();
+            var position = binding.Start;
+            foreach (var inner in Outermost(matches, match, binding.Start, binding.End))
+            {
+                text.Append(input, position, inner.Start - position).Append(Rewrite(input, matches, inner, template));
+                position = inner.End;
+            }
+
+            return text.Append(input, position, binding.End - position).ToString();
+        });
+    }
+
+    private static (GeneralizedParser Parser, List<Token> Tokens) Prepare(CompiledGrammar grammar, string text)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        ArgumentNullException.ThrowIfNull(text);
+
+        if (grammar.IsScannerless)
+        {
+            throw new ArgumentException($"Grammar '{grammar.Name}' is scannerless; structural patterns need tokens to match", nameof(grammar));
+        }
+
+        return (new GeneralizedParser(HoleGrammars.GetValue(grammar, WithHoles)), Tokenize(grammar, text));
+    }
+
+    /// <summary>
+    /// Lexes the pattern with the grammar's own lexer, metavariables being read as hole tokens before anything else.
+    /// </summary>
+    private static List<Token> Tokenize(CompiledGrammar grammar, string text)
+    {
+        var lexer = new GrammarLexer(grammar);
+        var tokens = new List<Token>();
+        var position = 0;
+
+        foreach (Match hole in Metavariable.Matches(text))
+        {
+            Lex(position, hole.Index);
+            tokens.Add(new Token(HoleKind, hole.Value, hole.Index));
+            position = hole.Index + hole.Length;
+        }
+
+        Lex(position, text.Length);
+        return tokens;
+
+        void Lex(int start, int end)
+        {
+            var segment = lexer.Tokenize(text[start..end]);
+            var error = segment.Diagnostics.FirstOrDefault(d => d.Severity == DiagnosticSeverity.Error);
+            if (error != null)
+            {
+                throw new ArgumentException($"'{text}' is not a valid pattern: {error.Message}", nameof(text));
+            }
+
+            tokens.AddRange(segment.Tokens.Select(t => t with { Offset = t.Offset + start }));
+        }
+    }
+
+    /// <summary>
+    /// Copies a grammar with a hole alternative added to every rule; the copy also accepts a hole wherever a token
+    /// is expected, so a metavariable can stand for a rule or a token but not for a keyword or punctuation.
+    /// </summary>
+    private static CompiledGrammar WithHoles(CompiledGrammar grammar)
+    {
+        var source = grammar.Source;
+        var rules = new ProductionRules();
+        var extended = new HashSet<string>();
+        foreach (var rule in source.ProductionRules.Rules)
+        {
+            var alternatives = new List<string>(rule.Alternatives);
+            if (extended.Add(rule.Name))
+            {
+                alternatives.Add($"<{HoleKind}>");
+            }
+
+            rules.AddRule(new ProductionRule { Name = rule.Name, Alternatives = alternatives, Actions = rule.Actions });
+        }
+
+        // Annotations are left behind, so the grammar's examples are not validated again.
+        var copy = new Grammar
+        {
+            Name = source.Name,
+            Language = source.Language,
+            Version = source.Version,
+            TokenRules = source.TokenRules,
+            ProductionRules = rules,
+            Metadata = new Dictionary<string, string>(source.Metadata)
+        };
+
+        var compiled = CompiledGrammar.Compile(copy, grammar.StartRule);
+        compiled.HoleKind = HoleKind;
+        return compiled;
+    }
+
+    private bool Match(TargetIndex target, int element, int position, int end, Dictionary<string, (int Start, int End)> bindings)
+    {
+        if (element == _elements.Count)
+        {
+            return position == end;
+        }
+
+        var current = _elements[element];
+        if (current.Hole == null)
+        {
+            return position < end && target.Terminals[position].Text == current.Text && Match(target, element + 1, position + 1, end, bindings);
+        }
+
+        if (bindings.TryGetValue(current.Hole, out var bound))
+        {
+            var length = bound.End - bound.Start;
+            return position + length <= end && target.SameTokens(bound.Start, position, length) && Match(target, element + 1, position + length, end, bindings);
+        }
+
+        foreach (var stop in current.IsList ? target.SiblingRunEnds(position, end) : target.SubtreeEnds(position, end))
+        {
+            bindings[current.Hole] = (position, stop);
+            if (Match(target, element + 1, stop, end, bindings))
+            {
+                return true;
+            }
+        }
+
+        bindings.Remove(current.Hole);
+        return false;
+    }
+
+    private StructuralMatch CreateMatch(string input, TargetIndex target, CognitiveGraphNode node, Dictionary<string, (int Start, int End)> bindings)
+    {
+        var position = node.SourcePosition!;
+        var lists = _elements.Where(e => e.IsList).Select(e => e.Hole!).ToHashSet();
+        var bound = bindings.ToDictionary(b => b.Key, b =>
+        {
+            var (start, end) = (target.Start(b.Value.Start, b.Value.End), target.End(b.Value.End));
+            return new StructuralBinding(b.Key, lists.Contains(b.Key), target.Cover(b.Value.Start, b.Value.End, node), start, end, input[start..end]);
+        });
+
+        return new StructuralMatch(node, position.Offset, position.Offset + position.Length, input.Substring(position.Offset, position.Length), bound);
+    }
+
+    /// <summary>
+    /// Gets the matches within a range that no other match in it encloses; a match whose metavariable binds all of
+    /// it is not within its own binding.
+    /// </summary>
+    private static IEnumerable<StructuralMatch> Outermost(IReadOnlyList<StructuralMatch> matches, StructuralMatch? enclosing, int start, int end)
+    {
+        var position = start;
+        foreach (var match in matches)
+        {
+            if (!ReferenceEquals(match, enclosing) && match.Start >= position && match.End <= end)
+            {
+                yield return match;
+                position = match.End;
+            }
+        }
+    }
+
+    private sealed record PatternElement(string Text, string? Hole, bool IsList);
+
+    /// <summary>
+    /// The tokens of a searched tree and the token range of each of its nodes. Synthetic and empty terminals take no
+    /// part in matching.
+    /// </summary>
+    private sealed class TargetIndex
+    {
+        private readonly Dictionary<CognitiveGraphNode, (int Start, int End)> _ranges = new(ReferenceEqualityComparer.Instance);
+        private readonly Dictionary<int, List<CognitiveGraphNode>> _starts = new();
+
+        public TargetIndex(CognitiveGraphNode root)
+        {
+            Visit(root);
+        }
+
+        public List<TerminalNode> Terminals { get; } = new();
+
+        public List<CognitiveGraphNode> Nodes { get; } = new();
+
+        public (int Start, int End) Range(CognitiveGraphNode node) => _ranges[node];
+
+        /// <summary>
+        /// Gets the offset where a token range starts; an empty range starts where the token before it ends.
+        /// </summary>
+        public int Start(int start, int end)
+        {
+            return start < end ? Terminals[start].SourcePosition!.Offset : End(start);
+        }
+
+        /// <summary>
+        /// Gets the offset where the token before the specified one ends, or zero for the first token.
+        /// </summary>
+        public int End(int end)
+        {
+            var last = end > 0 ? Terminals[end - 1].SourcePosition! : null;
+            return last == null ? 0 : last.Offset + last.Length;
+        }
+
+        public bool SameTokens(int first, int second, int length)
+        {
+            for (var i = 0; i < length; i++)
+            {
+                if (Terminals[first + i].Text != Terminals[second + i].Text)
+                {
+                    return false;
+                }
+            }
+
+            return true;
+        }
+
+        public IEnumerable<int> SubtreeEnds(int position, int end)
+        {
+            return _starts.GetValueOrDefault(position, new List<CognitiveGraphNode>())
+                .Select(n => _ranges[n].End)
+                .Where(e => e <= end)
+                .Distinct()
+                .Order();
+        }
+
+        public IEnumerable<int> SiblingRunEnds(int position, int end)
+        {
+            var ends = new SortedSet<int> { position };
+            foreach (var node in _starts.GetValueOrDefault(position, new List<CognitiveGraphNode>()))
+            {
+                var siblings = node.Parent?.Children ?? new[] { node };
+                for (var i = IndexOf(siblings, node); i < siblings.Count && _ranges[siblings[i]].End <= end; i++)
+                {
+                    ends.Add(_ranges[siblings[i]].End);
+                }
+            }
+
+            return ends;
+        }
+
+        /// <summary>
+        /// Gets the largest subtrees that together cover a token range, none of them above the matched node.
+        /// </summary>
+        public IReadOnlyList<CognitiveGraphNode> Cover(int start, int end, CognitiveGraphNode match)
+        {
+            var nodes = new List<CognitiveGraphNode>();
+            while (start < end)
+            {
+                var node = _starts[start][0];
+                while (!ReferenceEquals(node, match) && node.Parent != null && _ranges[node.Parent].End <= end)
+                {
+                    node = node.Parent;
+                }
+
+                nodes.Add(node);
+                start = _ranges[node].End;
+            }
+
+            return nodes;
+        }
+
+        private static int IndexOf(IReadOnlyList<CognitiveGraphNode> nodes, CognitiveGraphNode node)
+        {
+            for (var i = 0; i < nodes.Count; i++)
+            {
+                if (ReferenceEquals(nodes[i], node))
+                {
+                    return i;
+                }
+            }
+
+            return -1;
+        }
+
+        private void Visit(CognitiveGraphNode node)
+        {
+            Nodes.Add(node);
+            var start = Terminals.Count;
+            if (node is TerminalNode terminal)
+            {
+                if (terminal.Text.Length > 0 && !terminal.Metadata.ContainsKey(TerminatorPolicy.SyntheticMetadataKey))
+                {
+                    Terminals.Add(terminal);
+                }
+            }
+            else
+            {
+                foreach (var child in node.Children)
+                {
+                    Visit(child);
+                }
+            }
+
+            _ranges[node] = (start, Terminals.Count);
+            if (Terminals.Count > start)
+            {
+                if (!_starts.TryGetValue(start, out var nodes))
+                {
+                    _starts[start] = nodes = new List<CognitiveGraphNode>();
+                }
+
+                nodes.Add(node);
+            }
+        }
+    }
+}
+
+/// <summary>
+/// A match of a <see cref="StructuralPattern"/>.
+/// </summary>
+/// <param name="Node">The matched node.</param>
+/// <param name="Start">The start offset of the match in the input.</param>
+/// <param name="End">The end offset of the match.</param>
+/// <param name="Text">The matched source text.</param>
+/// <param name="Bindings">The bindings of the pattern's metavariables by name, without dollar signs.</param>
+public sealed record StructuralMatch(CognitiveGraphNode Node, int Start, int End, string Text, IReadOnlyDictionary<string, StructuralBinding> Bindings);
+
+/// <summary>
+/// What a metavariable bound in a <see cref="StructuralMatch"/>.
+/// </summary>
+/// <param name="Name">The metavariable name, without dollar signs.</param>
+/// <param name="IsList">True for a <c>$$name</c> metavariable, which binds a run of sibling subtrees.</param>
+/// <param name="Nodes">The bound subtrees; exactly one for a <c>$name</c> metavariable, possibly none for a list.</param>
+/// <param name="Start">The start offset of the bound source.</param>
+/// <param name="End">The end offset of the bound source.</param>
+/// <param name="Text">The bound source, comments and formatting included.</param>
+public sealed record StructuralBinding(string Name, bool IsList, IReadOnlyList<CognitiveGraphNode> Nodes, int Start, int End, string Text);
@@ -0,0 +1,118 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A restriction of a rule to declared names, from the grammar's "SymbolPredicates" metadata entry.
+/// </summary>
+/// <param name="Kind">The rule whose nodes declare the names.</param>
+/// <param name="Negated">True if the rule derives only names that are not declared with the kind.</param>
+public sealed record SymbolPredicate(string Kind, bool Negated);
+
+/// <summary>
+/// Which declarations of a <see cref="SymbolSet"/> a use of a name sees.
+/// </summary>
+public enum SymbolVisibility
+{
+    /// <summary>
+    /// A declaration applies throughout the file, including before it.
+    /// </summary>
+    Forward,
+
+    /// <summary>
+    /// A declaration applies only after it, as C's typedefs do.
+    /// </summary>
+    DeclaredBefore
+}
+
+/// <summary>
+/// The names a file declares, by kind, that the grammar's symbol predicates check while parsing it; see
+/// <see cref="ParseOptions.Symbols"/>.
+/// </summary>
+public sealed class SymbolSet
+{
+    private readonly Dictionary<(string Name, string Kind), int> _declarations = new();
+
+    /// <summary>
+    /// Initializes a new instance of the SymbolSet class.
+    /// </summary>
+    /// <param name="visibility">Which declarations a use of a name sees.</param>
+    public SymbolSet(SymbolVisibility visibility = SymbolVisibility.Forward)
+    {
+        Visibility = visibility;
+    }
+
+    /// <summary>
+    /// Gets which declarations a use of a name sees.
+    /// </summary>
+    public SymbolVisibility Visibility { get; }
+
+    /// <summary>
+    /// Gets the number of declared names.
+    /// </summary>
+    public int Count => _declarations.Count;
+
+    /// <summary>
+    /// Declares a name. A name declared more than once with a kind is visible from its first declaration.
+    /// </summary>
+    /// <param name="name">The name.</param>
+    /// <param name="kind">The kind, the name of the rule declaring it.</param>
+    /// <param name="offset">The offset of the declaration in the input.</param>
+    /// <returns>This set, for chaining.</returns>
+    public SymbolSet Add(string name, string kind, int offset = 0)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(name);
+        ArgumentException.ThrowIfNullOrEmpty(kind);
+
+        var key = (name, kind);
+        _declarations[key] = _declarations.TryGetValue(key, out var first) ? Math.Min(first, offset) : offset;
+        return this;
+    }
+
+    /// <summary>
+    /// Determines whether a use of a name sees a declaration of it with a kind.
+    /// </summary>
+    /// <param name="name">The name.</param>
+    /// <param name="kind">The kind.</param>
+    /// <param name="offset">The offset of the use in the input.</param>
+    /// <returns>True if the name is declared with the kind and, unless declarations are visible forward, before the use.</returns>
+    public bool IsDeclared(string name, string kind, int offset)
+    {
+        return _declarations.TryGetValue((name, kind), out var declared)
+            && (Visibility == SymbolVisibility.Forward || declared < offset);
+    }
+
+    /// <summary>
+    /// Determines whether a completed alternative must not be reduced because its rule's predicate rejects the token
+    /// it derives.
+    /// </summary>
+    /// <param name="alternative">The completed alternative.</param>
+    /// <param name="tokens">The tokens of the input.</param>
+    /// <param name="origin">The position the alternative started at.</param>
+    /// <returns>True if the rule has a predicate the token does not satisfy.</returns>
+    internal bool Refuses(CompiledAlternative alternative, IReadOnlyList<Token> tokens, int origin)
+    {
+        if (alternative.Rule.SymbolPredicate is not { } predicate || origin >= tokens.Count)
+        {
+            return false;
+        }
+
+        var token = tokens[origin];
+        return IsDeclared(token.Text, predicate.Kind, token.Offset) == predicate.Negated;
+    }
+}
@@ -0,0 +1,145 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The views of a parse tree.
+/// </summary>
+public enum TreeView
+{
+    /// <summary>
+    /// The concrete syntax tree: every token and every rule of the derivation.
+    /// </summary>
+    Cst,
+
+    /// <summary>
+    /// The abstract syntax tree: the concrete tree without the symbols the grammar hides and with the rules it
+    /// inlines replaced by their children.
+    /// </summary>
+    Ast
+}
+
+/// <summary>
+/// Derives the AST view of a concrete syntax tree from the grammar's "Hide" and "Inline" metadata entries.
+/// </summary>
+/// <remarks>
+/// AST nodes are ordinary <see cref="NonTerminalNode"/>s and <see cref="TerminalNode"/>s with the spans of the
+/// concrete nodes they stand for, so both views are navigated with the same API and map back to the same text;
+/// each AST node holds its concrete node in its <see cref="CstNodeKey"/> metadata entry. The root is never
+/// inlined or hidden.
+/// </remarks>
+public static class SyntaxTreeView
+{
+    /// <summary>
+    /// The metadata key of an AST node holding the concrete node it stands for.
+    /// </summary>
+    public const string CstNodeKey = "cst";
+
+    /// <summary>
+    /// Creates the AST view of a concrete syntax tree.
+    /// </summary>
+    /// <param name="cst">The concrete syntax tree.</param>
+    /// <param name="grammar">The grammar the tree was parsed with.</param>
+    /// <returns>The root of the AST view.</returns>
+    public static CognitiveGraphNode CreateAst(CognitiveGraphNode cst, CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(cst);
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var hiddenTerminals = grammar.HiddenSymbols.Where(s => s.IsTerminal).Select(s => s.Key).ToHashSet(StringComparer.Ordinal);
+        var hiddenRules = grammar.HiddenSymbols.Where(s => !s.IsTerminal).Select(s => s.Name).ToHashSet(StringComparer.Ordinal);
+
+        // Children are pushed in reverse, and an inlined node's children take its place on the stack, so every
+        // parent receives its children left to right.
+        var root = Copy(cst);
+        var pending = new Stack<(CognitiveGraphNode Node, CognitiveGraphNode Parent)>();
+        PushChildren(pending, cst, root);
+
+        while (pending.Count > 0)
+        {
+            var (node, parent) = pending.Pop();
+            switch (node)
+            {
+                case TerminalNode terminal when hiddenTerminals.Contains(terminal.TokenType):
+                    break;
+
+                case NonTerminalNode hidden when hiddenRules.Contains(hidden.RuleName):
+                    break;
+
+                case NonTerminalNode inlined when grammar.InlinedRules.Contains(inlined.RuleName):
+                    PushChildren(pending, inlined, parent);
+                    break;
+
+                default:
+                    var copy = Copy(node);
+                    parent.AddChild(copy);
+                    PushChildren(pending, node, copy);
+                    break;
+            }
+        }
+
+        return root;
+    }
+
+    /// <summary>
+    /// Gets the concrete node an AST node stands for.
+    /// </summary>
+    /// <param name="node">A node of an AST view.</param>
+    /// <returns>The concrete node, or null if the node is not part of an AST view.</returns>
+    public static CognitiveGraphNode? GetCstNode(CognitiveGraphNode node)
+    {
+        ArgumentNullException.ThrowIfNull(node);
+
+        return node.Metadata.GetValueOrDefault(CstNodeKey) as CognitiveGraphNode;
+    }
+
+    private static void PushChildren(Stack<(CognitiveGraphNode Node, CognitiveGraphNode Parent)> pending, CognitiveGraphNode node, CognitiveGraphNode parent)
+    {
+        for (var i = node.Children.Count - 1; i >= 0; i--)
+        {
+            pending.Push((node.Children[i], parent));
+        }
+    }
+
+    private static CognitiveGraphNode Copy(CognitiveGraphNode node)
+    {
+        CognitiveGraphNode copy;
+        if (node is NonTerminalNode nonTerminal)
+        {
+            copy = new NonTerminalNode(nonTerminal.RuleName, nonTerminal.ProductionIndex)
+            {
+                NodeType = nonTerminal.NodeType,
+                SourcePosition = nonTerminal.SourcePosition
+            };
+
+            foreach (var metadata in nonTerminal.Metadata)
+            {
+                copy.Metadata[metadata.Key] = metadata.Value;
+            }
+        }
+        else
+        {
+            copy = node.Clone();
+        }
+
+        copy.Metadata[CstNodeKey] = node;
+        return copy;
+    }
+}
@@ -0,0 +1,268 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Where a <see cref="TerminatorPolicy"/> inserts terminators beyond those forced by newline-sensitive terminals.
+/// </summary>
+public enum TerminatorInsertion
+{
+    /// <summary>
+    /// JavaScript's rule: a terminator is inserted before a token that cannot continue the input when a line
+    /// break precedes it or it is one of <see cref="TerminatorPolicy.Before"/>, and at the end of the input.
+    /// </summary>
+    Offending,
+
+    /// <summary>
+    /// Go's rule: a terminator is inserted after every token of the kinds in <see cref="TerminatorPolicy.After"/>
+    /// that ends a line or the input, whether or not the next line could continue it.
+    /// </summary>
+    LineEnd
+}
+
+/// <summary>
+/// Automatic insertion of statement terminators for languages where a line break can end a statement. The
+/// policy is the built-in "terminators" <see cref="ITokenFilter"/>, inserting synthetic terminator tokens
+/// with zero-width spans at the end of the preceding token; in the tree they are terminal nodes with empty
+/// text and "synthetic" metadata.
+/// </summary>
+/// <remarks>
+/// A policy is described by grammar metadata: "Terminator" names the terminal to insert, in the grammar
+/// notation (e.g. <c>";"</c>); "TerminatorInsertion" is <c>offending</c> (the default) or <c>line-end</c>;
+/// "TerminatorAfter" and "TerminatorBefore" list terminals for the two modes; and "NewlineSensitive" lists
+/// terminals after which a line break always ends the statement, like JavaScript's <c>return</c>. Terminators
+/// are never inserted at the start of the input or after another terminator, so no empty statements are made.
+/// Scannerless grammars have no token stream and ignore the policy.
+/// </remarks>
+public sealed class TerminatorPolicy : ITokenFilter
+{
+    /// <summary>
+    /// The metadata key naming the terminator terminal.
+    /// </summary>
+    public const string TerminatorKey = "Terminator";
+
+    /// <summary>
+    /// The metadata key selecting the <see cref="TerminatorInsertion"/>: <c>offending</c> or <c>line-end</c>.
+    /// </summary>
+    public const string InsertionKey = "TerminatorInsertion";
+
+    /// <summary>
+    /// The metadata key listing the terminals after which <see cref="TerminatorInsertion.LineEnd"/> inserts terminators.
+    /// </summary>
+    public const string AfterKey = "TerminatorAfter";
+
+    /// <summary>
+    /// The metadata key listing the terminals before which <see cref="TerminatorInsertion.Offending"/> inserts
+    /// terminators without a line break, such as a closing brace.
+    /// </summary>
+    public const string BeforeKey = "TerminatorBefore";
+
+    /// <summary>
+    /// The metadata key listing the terminals after which a line break always inserts a terminator.
+    /// </summary>
+    public const string NewlineSensitiveKey = "NewlineSensitive";
+
+    /// <summary>
+    /// The metadata key set on synthetic terminator nodes.
+    /// </summary>
+    public const string SyntheticMetadataKey = "synthetic";
+
+    /// <summary>
+    /// Initializes a new instance of the TerminatorPolicy class.
+    /// </summary>
+    /// <param name="terminator">The terminal key of the terminator, e.g. <c>";"</c> with the quotes.</param>
+    /// <param name="insertion">Where terminators are inserted.</param>
+    /// <param name="after">The terminal keys after which <see cref="TerminatorInsertion.LineEnd"/> inserts terminators.</param>
+    /// <param name="before">The terminal keys before which <see cref="TerminatorInsertion.Offending"/> inserts terminators.</param>
+    /// <param name="newlineSensitive">The terminal keys after which a line break always inserts a terminator.</param>
+    public TerminatorPolicy(
+        string terminator,
+        TerminatorInsertion insertion = TerminatorInsertion.Offending,
+        IEnumerable<string>? after = null,
+        IEnumerable<string>? before = null,
+        IEnumerable<string>? newlineSensitive = null)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(terminator);
+
+        Terminator = terminator;
+        Insertion = insertion;
+        After = new HashSet<string>(after ?? Enumerable.Empty<string>(), StringComparer.Ordinal);
+        Before = new HashSet<string>(before ?? Enumerable.Empty<string>(), StringComparer.Ordinal);
+        NewlineSensitive = new HashSet<string>(newlineSensitive ?? Enumerable.Empty<string>(), StringComparer.Ordinal);
+    }
+
+    /// <summary>
+    /// Gets the terminal key of the terminator.
+    /// </summary>
+    public string Terminator { get; }
+
+    /// <summary>
+    /// Gets where terminators are inserted.
+    /// </summary>
+    public TerminatorInsertion Insertion { get; }
+
+    /// <summary>
+    /// Gets the terminal keys after which <see cref="TerminatorInsertion.LineEnd"/> inserts terminators.
+    /// </summary>
+    public IReadOnlySet<string> After { get; }
+
+    /// <summary>
+    /// Gets the terminal keys before which <see cref="TerminatorInsertion.Offending"/> inserts terminators.
+    /// </summary>
+    public IReadOnlySet<string> Before { get; }
+
+    /// <summary>
+    /// Gets the terminal keys after which a line break always inserts a terminator.
+    /// </summary>
+    public IReadOnlySet<string> NewlineSensitive { get; }
+
+    /// <summary>
+    /// Creates the policy described by a grammar's "Terminator" metadata and the keys that go with it.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <returns>The policy, or null if the grammar names no terminator.</returns>
+    /// <exception cref="ArgumentException">The terminator is not a terminal of the grammar, the insertion mode
+    /// is unknown, or a list names a rule.</exception>
+    public static TerminatorPolicy? FromGrammar(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var metadata = grammar.Source.Metadata;
+        var declaration = metadata.GetValueOrDefault(TerminatorKey);
+        if (declaration == null)
+        {
+            return null;
+        }
+
+        var ruleNames = grammar.Rules.Select(r => r.Name).ToHashSet();
+        var terminators = Terminals(grammar, TerminatorKey, declaration, ruleNames).ToList();
+        var terminator = terminators.Count == 1 ? terminators[0] : null;
+        if (terminator == null || !grammar.GetTerminals().Any(t => t.Key == terminator))
+        {
+            throw new ArgumentException($"Terminator '{declaration}' is not a terminal of grammar '{grammar.Name}'", nameof(grammar));
+        }
+
+        var insertion = metadata.GetValueOrDefault(InsertionKey)?.Trim().ToLowerInvariant() switch
+        {
+            null or "offending" => TerminatorInsertion.Offending,
+            "line-end" => TerminatorInsertion.LineEnd,
+            var other => throw new ArgumentException($"Terminator insertion '{other}' in grammar '{grammar.Name}' must be 'offending' or 'line-end'", nameof(grammar))
+        };
+
+        return new TerminatorPolicy(
+            terminator,
+            insertion,
+            Terminals(grammar, AfterKey, metadata.GetValueOrDefault(AfterKey), ruleNames),
+            Terminals(grammar, BeforeKey, metadata.GetValueOrDefault(BeforeKey), ruleNames),
+            Terminals(grammar, NewlineSensitiveKey, metadata.GetValueOrDefault(NewlineSensitiveKey), ruleNames));
+    }
+
+    /// <summary>
+    /// Inserts the terminators that do not depend on the parse: after newline-sensitive terminals that end a
+    /// line and, for <see cref="TerminatorInsertion.LineEnd"/>, after the listed terminals that end a line or the input.
+    /// Terminators the parser needs before a token it cannot accept are inserted while parsing.
+    /// </summary>
+    /// <param name="tokens">The token stream.</param>
+    /// <param name="context">The input being parsed.</param>
+    /// <returns>The tokens with terminators inserted, read one token ahead of the output.</returns>
+    public IEnumerable<Token> Filter(IEnumerable<Token> tokens, TokenFilterContext context)
+    {
+        ArgumentNullException.ThrowIfNull(tokens);
+        ArgumentNullException.ThrowIfNull(context);
+
+        return Insert(tokens, context.Input);
+    }
+
+    /// <summary>
+    /// Determines whether <see cref="TerminatorInsertion.Offending"/> may insert a terminator before a token
+    /// the parser cannot accept, or at the end of the input.
+    /// </summary>
+    internal bool CanInsertBefore(IReadOnlyList<Token> tokens, int index, string input)
+    {
+        if (Insertion != TerminatorInsertion.Offending || index == 0 || tokens[index - 1].Kind == Terminator)
+        {
+            return false;
+        }
+
+        return index == tokens.Count
+            || Before.Contains(tokens[index].Kind)
+            || HasLineBreak(input, tokens[index - 1].End, tokens[index].Offset);
+    }
+
+    internal Token CreateToken(int offset)
+    {
+        return new Token(Terminator, string.Empty, offset) { IsSynthetic = true };
+    }
+
+    private IEnumerable<Token> Insert(IEnumerable<Token> tokens, string input)
+    {
+        Token? previous = null;
+        foreach (var token in tokens)
+        {
+            if (previous != null)
+            {
+                yield return previous;
+                if (InsertsAfter(previous, token, input))
+                {
+                    yield return CreateToken(previous.End);
+                }
+            }
+
+            previous = token;
+        }
+
+        if (previous != null)
+        {
+            yield return previous;
+            if (InsertsAfter(previous, null, input))
+            {
+                yield return CreateToken(previous.End);
+            }
+        }
+    }
+
+    private bool InsertsAfter(Token token, Token? next, string input)
+    {
+        if (next?.Kind == Terminator || token.Kind == Terminator)
+        {
+            return false;
+        }
+
+        var lineEnd = next == null ? Insertion == TerminatorInsertion.LineEnd : HasLineBreak(input, token.End, next.Offset);
+        return NewlineSensitive.Contains(token.Kind) && next != null && lineEnd
+            || Insertion == TerminatorInsertion.LineEnd && After.Contains(token.Kind) && lineEnd;
+    }
+
+    private static bool HasLineBreak(string input, int start, int end)
+    {
+        return input.AsSpan(start, end - start).IndexOfAny('\n', '\r') >= 0;
+    }
+
+    private static IEnumerable<string> Terminals(CompiledGrammar grammar, string key, string? declaration, ISet<string> ruleNames)
+    {
+        foreach (var symbol in GrammarSymbol.ParseAlternative(declaration ?? string.Empty, ruleNames))
+        {
+            if (!symbol.IsTerminal)
+            {
+                throw new ArgumentException($"'{key}' in grammar '{grammar.Name}' lists rule <{symbol.Name}>; only terminals can be listed", nameof(grammar));
+            }
+
+            yield return symbol.Key;
+        }
+    }
+}
@@ -0,0 +1,157 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.Json;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A single text replacement: <see cref="Length"/> characters at <see cref="Offset"/> are replaced by <see cref="NewText"/>.
+/// </summary>
+/// <param name="Offset">The zero-based offset of the replaced range.</param>
+/// <param name="Length">The number of characters removed.</param>
+/// <param name="NewText">The inserted text.</param>
+public sealed record TextEdit(int Offset, int Length, string NewText)
+{
+    /// <summary>
+    /// Gets the change in document length caused by the edit.
+    /// </summary>
+    public int Delta => NewText.Length - Length;
+
+    /// <summary>
+    /// Creates an insertion.
+    /// </summary>
+    /// <param name="offset">The insertion offset.</param>
+    /// <param name="text">The inserted text.</param>
+    /// <returns>The edit.</returns>
+    public static TextEdit Insert(int offset, string text) => new(offset, 0, text);
+
+    /// <summary>
+    /// Creates a deletion.
+    /// </summary>
+    /// <param name="offset">The offset of the deleted range.</param>
+    /// <param name="length">The number of characters deleted.</param>
+    /// <returns>The edit.</returns>
+    public static TextEdit Delete(int offset, int length) => new(offset, length, string.Empty);
+
+    /// <summary>
+    /// Applies the edit to a text.
+    /// </summary>
+    /// <param name="text">The text before the edit.</param>
+    /// <returns>The text after the edit.</returns>
+    public string Apply(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        if (Offset < 0 || Length < 0 || Offset + Length > text.Length)
+        {
+            throw new ArgumentOutOfRangeException(nameof(text), $"Edit range [{Offset}, {Offset + Length}) is outside the text of length {text.Length}");
+        }
+
+        return string.Concat(text.AsSpan(0, Offset), NewText, text.AsSpan(Offset + Length));
+    }
+
+    /// <summary>
+    /// Composes edits applied one after another into a single edit with the same effect, replacing the smallest
+    /// range of the original text that any of them touched.
+    /// </summary>
+    /// <param name="edits">The edits in application order, each relative to the text left by the previous one.</param>
+    /// <param name="result">The text after all the edits.</param>
+    /// <returns>The composed edit, relative to the text before the first edit.</returns>
+    public static TextEdit Compose(IReadOnlyList<TextEdit> edits, string result)
+    {
+        ArgumentNullException.ThrowIfNull(edits);
+        ArgumentNullException.ThrowIfNull(result);
+
+        if (edits.Count == 0)
+        {
+            return Insert(0, string.Empty);
+        }
+
+        // [start, oldEnd) of the original text became [start, newEnd) of the text so far.
+        var start = edits[0].Offset;
+        var oldEnd = start + edits[0].Length;
+        var newEnd = start + edits[0].NewText.Length;
+        foreach (var edit in edits.Skip(1))
+        {
+            var editEnd = edit.Offset + edit.Length;
+            oldEnd += Math.Max(0, editEnd - newEnd);
+            newEnd = Math.Max(newEnd, editEnd) + edit.Delta;
+            start = Math.Min(start, edit.Offset);
+        }
+
+        return new TextEdit(start, oldEnd - start, result.Substring(start, newEnd - start));
+    }
+
+    /// <summary>
+    /// Reads an edit script: a JSON array of objects with "offset", "length" and "text" properties.
+    /// </summary>
+    /// <param name="json">The edit script.</param>
+    /// <returns>The edits in application order.</returns>
+    public static IReadOnlyList<TextEdit> ParseScript(string json)
+    {
+        ArgumentNullException.ThrowIfNull(json);
+
+        using var document = JsonDocument.Parse(json);
+        var edits = new List<TextEdit>();
+
+        foreach (var element in document.RootElement.EnumerateArray())
+        {
+            var offset = element.GetProperty("offset").GetInt32();
+            var length = element.TryGetProperty("length", out var lengthElement) ? lengthElement.GetInt32() : 0;
+            var text = element.TryGetProperty("text", out var textElement) ? textElement.GetString() ?? string.Empty : string.Empty;
+            edits.Add(new TextEdit(offset, length, text));
+        }
+
+        return edits;
+    }
+
+    /// <summary>
+    /// Returns a short description of the edit.
+    /// </summary>
+    /// <returns>The edit description.</returns>
+    public override string ToString()
+    {
+        return Length == 0
+            ? $"insert {JsonSerializer.Serialize(NewText)} at {Offset}"
+            : NewText.Length == 0
+                ? $"delete [{Offset}, {Offset + Length})"
+                : $"replace [{Offset}, {Offset + Length}) with {JsonSerializer.Serialize(NewText)}";
+    }
+}
+
+/// <summary>
+/// A range of characters in a text.
+/// </summary>
+/// <param name="Start">The zero-based start offset.</param>
+/// <param name="Length">The number of characters.</param>
+public readonly record struct TextRange(int Start, int Length)
+{
+    /// <summary>
+    /// Gets the offset immediately after the range.
+    /// </summary>
+    public int End => Start + Length;
+
+    /// <summary>
+    /// Returns the range in half-open interval notation.
+    /// </summary>
+    /// <returns>The range description.</returns>
+    public override string ToString()
+    {
+        return $"[{Start}, {End})";
+    }
+}
@@ -0,0 +1,106 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Runtime.CompilerServices;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A token produced by the grammar lexer.
+/// </summary>
+/// <param name="Kind">The terminal key the token matches, e.g. <c>IDENTIFIER</c> or <c>"if"</c>.</param>
+/// <param name="Text">The matched source text.</param>
+/// <param name="Offset">The zero-based offset of the token in the source.</param>
+public sealed record Token(string Kind, string Text, int Offset)
+{
+    // Records compare by value, so the decoded text is cached beside the token rather than in it.
+    private static readonly ConditionalWeakTable<Token, string> DecodedTexts = new();
+
+    /// <summary>
+    /// Gets the length of the token text.
+    /// </summary>
+    public int Length => Text.Length;
+
+    /// <summary>
+    /// Gets the offset immediately after the token.
+    /// </summary>
+    public int End => Offset + Text.Length;
+
+    /// <summary>
+    /// Gets a value indicating whether the token was inserted by a <see cref="TerminatorPolicy"/> rather than
+    /// read from the source.
+    /// </summary>
+    public bool IsSynthetic { get; init; }
+
+    /// <summary>
+    /// Gets the names of the capture groups the token's terminal pattern declares, like <c>exponent</c> in
+    /// <c>/[0-9]+(?:e(?&lt;exponent&gt;[0-9]+))?/</c>; empty if it declares none.
+    /// </summary>
+    public IReadOnlyList<string> CaptureNames => Captures?.Names ?? Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the recorded spans of the named capture groups, or null if the terminal declares none.
+    /// </summary>
+    internal TokenCaptures? Captures { get; init; }
+
+    /// <summary>
+    /// Gets the escape scheme the <c>// @escapes</c> annotation of the token's terminal declares, or null.
+    /// </summary>
+    public EscapeScheme? Escapes { get; init; }
+
+    /// <summary>
+    /// Gets the value of a string token: the text between its quotes with its escapes replaced, decoded by
+    /// <see cref="Escapes"/> when first asked for and cached; null if the terminal declares no escape scheme.
+    /// Malformed escapes, which the lexer reports, are kept as written.
+    /// </summary>
+    public string? DecodedText => Escapes != null ? DecodedTexts.GetValue(this, t => t.Escapes!.Decode(t.Text).Value) : null;
+
+    /// <summary>
+    /// Gets the span of a named capture group of the token's terminal pattern.
+    /// </summary>
+    /// <param name="name">The group name.</param>
+    /// <returns>The span in the source, or null if the pattern has no such group or it did not take part in
+    /// the match.</returns>
+    public TextRange? Capture(string name)
+    {
+        ArgumentNullException.ThrowIfNull(name);
+
+        return Captures?.Find(name, Offset);
+    }
+
+    /// <summary>
+    /// Gets the text of a named capture group of the token's terminal pattern.
+    /// </summary>
+    /// <param name="name">The group name.</param>
+    /// <returns>The captured text, or null if the group did not take part in the match or captured text
+    /// outside the token, as groups in lookarounds can.</returns>
+    public string? CaptureText(string name)
+    {
+        return Capture(name) is { } span && span.Start >= Offset && span.End <= End
+            ? Text.Substring(span.Start - Offset, span.Length)
+            : null;
+    }
+
+    /// <summary>
+    /// Returns a short description of the token.
+    /// </summary>
+    /// <returns>The token kind and text.</returns>
+    public override string ToString()
+    {
+        return $"{Kind} '{Text}' @{Offset}";
+    }
+}
@@ -0,0 +1,113 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// The spans of the named capture groups of the terminal pattern a token matched, relative to the token so they
+/// move with it. Only terminals whose patterns name groups record them.
+/// </summary>
+internal sealed class TokenCaptures : IEquatable<TokenCaptures>
+{
+    private readonly string[] _names;
+    private readonly int[] _starts;
+    private readonly int[] _lengths;
+
+    private TokenCaptures(string[] names, int[] starts, int[] lengths)
+    {
+        _names = names;
+        _starts = starts;
+        _lengths = lengths;
+    }
+
+    /// <summary>
+    /// Gets the group names, in pattern order.
+    /// </summary>
+    public IReadOnlyList<string> Names => _names;
+
+    /// <summary>
+    /// Gets the named groups of a pattern.
+    /// </summary>
+    /// <param name="pattern">The terminal pattern.</param>
+    /// <returns>The group names, or null if the pattern names none.</returns>
+    public static string[]? GetNames(Regex pattern)
+    {
+        var names = pattern.GetGroupNames().Where(n => !char.IsDigit(n[0])).ToArray();
+        return names.Length > 0 ? names : null;
+    }
+
+    /// <summary>
+    /// Records the named groups of a terminal match.
+    /// </summary>
+    /// <param name="match">The match.</param>
+    /// <param name="names">The pattern's group names.</param>
+    /// <param name="offset">The offset of the token.</param>
+    /// <returns>The captures.</returns>
+    public static TokenCaptures Read(Match match, string[] names, int offset)
+    {
+        var starts = new int[names.Length];
+        var lengths = new int[names.Length];
+        for (var i = 0; i < names.Length; i++)
+        {
+            var group = match.Groups[names[i]];
+            starts[i] = group.Index - offset;
+            lengths[i] = group.Success ? group.Length : -1;
+        }
+
+        return new TokenCaptures(names, starts, lengths);
+    }
+
+    /// <summary>
+    /// Finds the span of a group.
+    /// </summary>
+    /// <param name="name">The group name.</param>
+    /// <param name="offset">The offset of the token.</param>
+    /// <returns>The span, or null if the pattern has no such group or it did not take part in the match.</returns>
+    public TextRange? Find(string name, int offset)
+    {
+        var index = Array.IndexOf(_names, name);
+        return index >= 0 && _lengths[index] >= 0 ? new TextRange(offset + _starts[index], _lengths[index]) : null;
+    }
+
+    /// <inheritdoc />
+    public bool Equals(TokenCaptures? other)
+    {
+        return other != null && _names.SequenceEqual(other._names) && _starts.SequenceEqual(other._starts) && _lengths.SequenceEqual(other._lengths);
+    }
+
+    /// <inheritdoc />
+    public override bool Equals(object? obj)
+    {
+        return Equals(obj as TokenCaptures);
+    }
+
+    /// <inheritdoc />
+    public override int GetHashCode()
+    {
+        var hash = new HashCode();
+        for (var i = 0; i < _names.Length; i++)
+        {
+            hash.Add(_names[i]);
+            hash.Add(_starts[i]);
+            hash.Add(_lengths[i]);
+        }
+
+        return hash.ToHashCode();
+    }
+}
@@ -0,0 +1,177 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A stage of the token pipeline between the lexer and the parser, for language quirks that are easiest to
+/// describe as a rewrite of the token stream, like implicit line joining, inserted terminators or the
+/// concatenation of adjacent string literals.
+/// </summary>
+/// <remarks>
+/// Filters are lazy: they read input tokens only as their output is enumerated, and are enumerated once per
+/// parse. Whatever they produce keeps the spans meaningful for diagnostics and the tree: a token's text is the
+/// source text at its offset, so a token merged from several tokens spans from the start of the first to the
+/// end of the last, layout between them included, and a synthesized token has empty text, a zero-width span
+/// where it belongs and <see cref="Token.IsSynthetic"/> set.
+/// </remarks>
+public interface ITokenFilter
+{
+    /// <summary>
+    /// Rewrites a token stream.
+    /// </summary>
+    /// <param name="tokens">The tokens from the lexer or the previous filter.</param>
+    /// <param name="context">The input being parsed.</param>
+    /// <returns>The rewritten tokens, in source order.</returns>
+    IEnumerable<Token> Filter(IEnumerable<Token> tokens, TokenFilterContext context);
+}
+
+/// <summary>
+/// The input a token pipeline is applied to.
+/// </summary>
+/// <param name="Input">The source text.</param>
+/// <param name="LineIndex">The line index of the source text, for diagnostic locations.</param>
+/// <param name="SourceFile">The source file name, if any.</param>
+/// <param name="Diagnostics">Receives diagnostics reported by filters.</param>
+public sealed record TokenFilterContext(string Input, LineIndex LineIndex, string? SourceFile, ICollection<Diagnostic> Diagnostics);
+
+/// <summary>
+/// The token filters grammars can name in their pipeline: the built-in ones and those registered by the host.
+/// </summary>
+/// <remarks>
+/// A grammar declares its pipeline with the "TokenFilters" metadata entry, a comma-separated list of filter
+/// names applied in order, e.g. <c>TokenFilters: concatenate-strings, terminators</c>. Without the entry the
+/// pipeline holds the "terminators" filter if the grammar declares a "Terminator", and nothing otherwise.
+/// Scannerless grammars have no token stream and no pipeline.
+/// </remarks>
+public sealed class TokenFilterRegistry
+{
+    /// <summary>
+    /// The metadata key listing the grammar's token filters.
+    /// </summary>
+    public const string TokenFiltersKey = "TokenFilters";
+
+    /// <summary>
+    /// The name of the built-in filter inserting the grammar's <see cref="TerminatorPolicy"/> terminators.
+    /// </summary>
+    public const string TerminatorsFilter = "terminators";
+
+    /// <summary>
+    /// The name of the built-in <see cref="StringConcatenationFilter"/>.
+    /// </summary>
+    public const string ConcatenateStringsFilter = "concatenate-strings";
+
+    /// <summary>
+    /// The name of the built-in <see cref="OffsideRuleFilter"/>.
+    /// </summary>
+    public const string IndentationFilter = "indentation";
+
+    private readonly Dictionary<string, Func<CompiledGrammar, ITokenFilter>> _factories = new(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Initializes a new instance of the TokenFilterRegistry class with the built-in filters.
+    /// </summary>
+    public TokenFilterRegistry()
+    {
+        _factories[TerminatorsFilter] = grammar => TerminatorPolicy.FromGrammar(grammar)
+            ?? throw new ArgumentException($"Token filter '{TerminatorsFilter}' needs a '{TerminatorPolicy.TerminatorKey}' in grammar '{grammar.Name}'", nameof(grammar));
+        _factories[ConcatenateStringsFilter] = _ => new StringConcatenationFilter();
+        _factories[IndentationFilter] = OffsideRuleFilter.FromGrammar;
+    }
+
+    /// <summary>
+    /// Gets the names of the registered filters.
+    /// </summary>
+    public IEnumerable<string> FilterNames => _factories.Keys;
+
+    /// <summary>
+    /// Registers a filter, replacing any filter of the same name.
+    /// </summary>
+    /// <param name="name">The name grammars list the filter by.</param>
+    /// <param name="factory">Creates the filter for a grammar.</param>
+    /// <returns>This registry, for chaining.</returns>
+    public TokenFilterRegistry Register(string name, Func<CompiledGrammar, ITokenFilter> factory)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(name);
+        ArgumentNullException.ThrowIfNull(factory);
+
+        _factories[name] = factory;
+        return this;
+    }
+
+    /// <summary>
+    /// Creates the token pipeline a grammar declares.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <returns>The filters in the order they are applied.</returns>
+    /// <exception cref="ArgumentException">The grammar names a filter that is not registered, or a filter cannot
+    /// be created for the grammar.</exception>
+    public IReadOnlyList<ITokenFilter> CreatePipeline(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        if (grammar.IsScannerless)
+        {
+            return Array.Empty<ITokenFilter>();
+        }
+
+        var declaration = grammar.Source.Metadata.GetValueOrDefault(TokenFiltersKey);
+        if (declaration == null)
+        {
+            var terminators = TerminatorPolicy.FromGrammar(grammar);
+            return terminators != null ? new ITokenFilter[] { terminators } : Array.Empty<ITokenFilter>();
+        }
+
+        var pipeline = new List<ITokenFilter>();
+        foreach (var name in declaration.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            if (!_factories.TryGetValue(name, out var factory))
+            {
+                throw new ArgumentException($"Token filter '{name}' in grammar '{grammar.Name}' is not registered", nameof(grammar));
+            }
+
+            pipeline.Add(factory(grammar));
+        }
+
+        return pipeline;
+    }
+
+    /// <summary>
+    /// Applies a token pipeline.
+    /// </summary>
+    /// <param name="pipeline">The filters in the order they are applied.</param>
+    /// <param name="tokens">The tokens from the lexer.</param>
+    /// <param name="context">The input being parsed.</param>
+    /// <returns>The filtered tokens.</returns>
+    public static List<Token> Apply(IReadOnlyList<ITokenFilter> pipeline, IEnumerable<Token> tokens, TokenFilterContext context)
+    {
+        ArgumentNullException.ThrowIfNull(pipeline);
+        ArgumentNullException.ThrowIfNull(tokens);
+        ArgumentNullException.ThrowIfNull(context);
+
+        var stream = tokens;
+        foreach (var filter in pipeline)
+        {
+            stream = filter.Filter(stream, context);
+        }
+
+        return stream.ToList();
+    }
+}
@@ -0,0 +1,245 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Repairs the token stream where the recognizer gets stuck: inserts the terminators a
+/// <see cref="TerminatorPolicy"/> allows, takes misspelled keywords for the keyword they are closest to and repairs
+/// the brackets a <see cref="DelimiterRecoveryPolicy"/> finds left open or mismatched, possibly before the stuck token.
+/// Works on a copy of the tokens, so the caller's list is left as it was.
+/// </summary>
+internal sealed class TokenRepair
+{
+    private readonly CompiledGrammar _grammar;
+    private readonly TerminatorPolicy? _terminators;
+    private readonly DelimiterRecoveryPolicy? _delimiters;
+    private readonly int _keywordDistance;
+    private readonly List<Token> _tokens;
+    private readonly LineIndex _lineIndex;
+    private readonly string? _sourceFile;
+    private readonly List<Diagnostic> _diagnostics = new();
+
+    public TokenRepair(CompiledGrammar grammar, TerminatorPolicy? terminators, DelimiterRecoveryPolicy? delimiters, int keywordDistance, IReadOnlyList<Token> tokens, LineIndex lineIndex, string? sourceFile)
+    {
+        _grammar = grammar;
+        _terminators = terminators;
+        _delimiters = delimiters;
+        _keywordDistance = keywordDistance;
+        _tokens = tokens.ToList();
+        _lineIndex = lineIndex;
+        _sourceFile = sourceFile;
+    }
+
+    public IReadOnlyList<Token> Tokens => _tokens;
+
+    public IReadOnlyList<Diagnostic> Diagnostics => _diagnostics;
+
+    /// <summary>
+    /// Repairs the tokens around a position the recognizer is stuck at.
+    /// </summary>
+    /// <param name="chart">The Earley sets recognized so far.</param>
+    /// <param name="position">The index of the token no item could scan, or the token count at the end.</param>
+    /// <param name="rewind">Whether a repair may be made before <paramref name="position"/>.</param>
+    /// <returns>The index of the repaired token, from which recognition resumes, or -1 if nothing was repaired.</returns>
+    public int TryRepair(GeneralizedParser.EarleySet?[] chart, int position, bool rewind)
+    {
+        var expected = chart[position]!.Items
+            .Where(item => item.Dot < item.Alternative.Symbols.Count && item.Alternative.RuleIndices[item.Dot] < 0)
+            .Select(item => item.Alternative.Symbols[item.Dot])
+            .ToHashSet();
+
+        if (TryInsertTerminator(expected, position) || TryCorrectKeyword(expected, position))
+        {
+            return position;
+        }
+
+        return TryRepairDelimiter(chart, position, rewind);
+    }
+
+    private bool TryInsertTerminator(HashSet<GrammarSymbol> expected, int position)
+    {
+        if (_terminators == null
+            || !expected.Any(symbol => symbol.Key == _terminators.Terminator)
+            || !_terminators.CanInsertBefore(_tokens, position, _lineIndex.Text))
+        {
+            return false;
+        }
+
+        _tokens.Insert(position, _terminators.CreateToken(_tokens[position - 1].End));
+        return true;
+    }
+
+    // The closer goes where the bracket's block outdents, which may be lines before the token the parser got stuck
+    // at: the lines in between were read into the unclosed block. The policy lists the repairs cheapest first, and
+    // the first one the parser can accept is made.
+    private int TryRepairDelimiter(GeneralizedParser.EarleySet?[] chart, int position, bool rewind)
+    {
+        if (_delimiters == null)
+        {
+            return -1;
+        }
+
+        foreach (var repair in _delimiters.FindRepairs(_tokens, position, _lineIndex.Text))
+        {
+            if (repair.Index < position && !rewind || chart[repair.Index] is not { } set)
+            {
+                continue;
+            }
+
+            var pair = repair.Opener >= 0 ? _delimiters.GetPair(_tokens[repair.Opener].Kind) : null;
+            var next = repair.Kind switch
+            {
+                DelimiterRepairKind.Insert => DelimiterRecoveryPolicy.CreateToken(pair!, _tokens[repair.Index - 1].End),
+                DelimiterRepairKind.Replace => DelimiterRecoveryPolicy.CreateReplacement(pair!, _tokens[repair.Index]),
+                _ => _tokens[repair.Index + 1]
+            };
+            if (!set.Items.Any(item => item.Dot < item.Alternative.Symbols.Count && _grammar.Accepts(next, item.Alternative, item.Dot)))
+            {
+                continue;
+            }
+
+            var token = repair.Index < _tokens.Count ? _tokens[repair.Index] : next;
+            var opening = pair != null ? _lineIndex.GetPosition(_tokens[repair.Opener].Offset, _tokens[repair.Opener].Length, _sourceFile) : null;
+            var diagnostic = new Diagnostic
+            {
+                Location = _lineIndex.GetPosition(token.Offset, token.Length, _sourceFile),
+                Data = { ["tokenIndex"] = repair.Index }
+            };
+            if (opening != null)
+            {
+                diagnostic.Data["related"] = opening;
+            }
+
+            switch (repair.Kind)
+            {
+                case DelimiterRepairKind.Insert:
+                    var open = _tokens[repair.Opener];
+                    _tokens.Insert(repair.Index, next);
+                    diagnostic.Code = DiagnosticCodes.UnclosedDelimiter;
+                    diagnostic.Data["region"] = new TextRange(open.Offset, next.Offset - open.Offset);
+                    diagnostic.WithMessage(DiagnosticCodes.UnclosedDelimiter, ("opening", open.Text), ("closing", pair!.Close), ("line", opening!.Line), ("column", opening.Column));
+                    break;
+                case DelimiterRepairKind.Replace:
+                    _tokens[repair.Index] = next;
+                    diagnostic.Code = DiagnosticCodes.MismatchedDelimiter;
+                    diagnostic.Data["fix"] = new TextEdit(token.Offset, token.Length, pair!.Close);
+                    diagnostic.WithMessage(DiagnosticCodes.MismatchedDelimiter, ("found", token.Text), ("opening", pair.Open), ("closing", pair.Close), ("line", opening!.Line), ("column", opening.Column));
+                    break;
+                default:
+                    _tokens.RemoveAt(repair.Index);
+                    diagnostic.Code = DiagnosticCodes.UnexpectedDelimiter;
+                    diagnostic.Data["fix"] = new TextEdit(token.Offset, token.Length, string.Empty);
+                    diagnostic.WithMessage(DiagnosticCodes.UnexpectedDelimiter, ("found", token.Text));
+                    break;
+            }
+
+            _diagnostics.Add(diagnostic);
+            return repair.Index;
+        }
+
+        return -1;
+    }
+
+    // Only a word the parser cannot take at all is corrected: if an identifier were acceptable here, the token
+    // would have been scanned and the parser would not be stuck.
+    private bool TryCorrectKeyword(HashSet<GrammarSymbol> expected, int position)
+    {
+        if (_keywordDistance <= 0 || position >= _tokens.Count)
+        {
+            return false;
+        }
+
+        var token = _tokens[position];
+        if (token.Kind.StartsWith('"') || !IsWord(token.Text))
+        {
+            return false;
+        }
+
+        var candidates = expected
+            .Where(symbol => symbol.Kind == GrammarSymbolKind.Literal && IsWord(symbol.Name))
+            .Select(symbol => (Keyword: symbol, Distance: Distance(token.Text, symbol.Name)))
+            .Where(c => c.Distance <= _keywordDistance)
+            .ToList();
+        if (candidates.Count == 0)
+        {
+            return false;
+        }
+
+        var closest = candidates.Min(c => c.Distance);
+        var best = candidates.Where(c => c.Distance == closest).ToList();
+        if (best.Count != 1)
+        {
+            return false;
+        }
+
+        var keyword = best[0].Keyword;
+        _tokens[position] = token with { Kind = keyword.Key };
+        _diagnostics.Add(new Diagnostic
+        {
+            Code = DiagnosticCodes.MisspelledKeyword,
+            Location = _lineIndex.GetPosition(token.Offset, token.Length, _sourceFile),
+            Data =
+            {
+                ["suggestion"] = keyword.Name,
+                ["fix"] = new TextEdit(token.Offset, token.Length, keyword.Name),
+                ["tokenIndex"] = position
+            }
+        }.WithMessage(DiagnosticCodes.MisspelledKeyword, ("kind", token.Kind), ("text", token.Text), ("keyword", keyword.Name)));
+
+        return true;
+    }
+
+    private static bool IsWord(string text)
+    {
+        return text.Length > 0 && (char.IsLetter(text[0]) || text[0] == '_') && text.All(c => char.IsLetterOrDigit(c) || c == '_');
+    }
+
+    // Optimal string alignment distance: insertions, deletions, substitutions and swaps of adjacent characters
+    // each count one, so "retrun" is one edit from "return".
+    private static int Distance(string source, string target)
+    {
+        var d = new int[source.Length + 1, target.Length + 1];
+        for (var i = 0; i <= source.Length; i++)
+        {
+            d[i, 0] = i;
+        }
+
+        for (var j = 0; j <= target.Length; j++)
+        {
+            d[0, j] = j;
+        }
+
+        for (var i = 1; i <= source.Length; i++)
+        {
+            for (var j = 1; j <= target.Length; j++)
+            {
+                var cost = source[i - 1] == target[j - 1] ? 0 : 1;
+                d[i, j] = Math.Min(Math.Min(d[i - 1, j] + 1, d[i, j - 1] + 1), d[i - 1, j - 1] + cost);
+                if (i > 1 && j > 1 && source[i - 1] == target[j - 2] && source[i - 2] == target[j - 1])
+                {
+                    d[i, j] = Math.Min(d[i, j], d[i - 2, j - 2] + 1);
+                }
+            }
+        }
+
+        return d[source.Length, target.Length];
+    }
+}
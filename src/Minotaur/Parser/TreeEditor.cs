@@ -0,0 +1,411 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Edits a parsed document through its tree: nodes are replaced, inserted and deleted by id, and the edits are
+/// turned into text edits of the original input. Fragments are parsed against the rule of the node they stand
+/// for, so a fragment that could not appear there is rejected when the edit is made. Everything outside the
+/// edited nodes, comments and whitespace included, is kept as written; fragments are indented to match their
+/// surroundings, deleted nodes take their own lines with them, and the edited text is reparsed before it is
+/// returned. Edits refer to the original tree and may not overlap.
+/// </summary>
+public sealed class TreeEditor
+{
+    private readonly GeneralizedParser _parser;
+    private readonly ParseResult _original;
+    private readonly Dictionary<Guid, CognitiveGraphNode> _nodes = new();
+    private readonly List<TreeEditOperation> _operations = new();
+
+    /// <summary>
+    /// Initializes a new instance of the TreeEditor class.
+    /// </summary>
+    /// <param name="parser">The parser that produced the result, used for fragments and validation.</param>
+    /// <param name="original">The parse result to edit; it must have a tree.</param>
+    public TreeEditor(GeneralizedParser parser, ParseResult original)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+        ArgumentNullException.ThrowIfNull(original);
+
+        if (original.Tree == null)
+        {
+            throw new ArgumentException("Only a parse result with a tree can be edited", nameof(original));
+        }
+
+        _parser = parser;
+        _original = original;
+        Index(original.Tree);
+    }
+
+    /// <summary>
+    /// Gets the edits made so far, in the order they were made.
+    /// </summary>
+    public IReadOnlyList<TreeEditOperation> Operations => _operations;
+
+    /// <summary>
+    /// Replaces a node with a fragment of source.
+    /// </summary>
+    /// <param name="id">The id of the replaced node.</param>
+    /// <param name="source">The replacement, which must parse as the node's rule (or token kind, for a terminal).</param>
+    /// <returns>This editor.</returns>
+    /// <exception cref="ArgumentException">The node is unknown or the fragment does not parse.</exception>
+    /// <exception cref="InvalidOperationException">The edit overlaps an earlier edit.</exception>
+    public TreeEditor ReplaceNode(Guid id, string source)
+    {
+        return ReplaceNode(id, source, true);
+    }
+
+    /// <summary>
+    /// Replaces a node with a fragment of source, optionally taking the fragment's indentation as written. Without
+    /// reindentation the fragment's continuation lines must already be indented for the node's position.
+    /// </summary>
+    internal TreeEditor ReplaceNode(Guid id, string source, bool reindent)
+    {
+        ArgumentNullException.ThrowIfNull(source);
+
+        var node = GetNode(id);
+        var (start, end) = Span(node);
+        var fragment = reindent ? Reindent(source.Trim(), Indentation(start)) : source.Trim();
+        if (node is TerminalNode terminal)
+        {
+            ValidateToken(fragment, terminal.TokenType);
+        }
+        else
+        {
+            ValidateFragment(fragment, ((NonTerminalNode)node).RuleName);
+        }
+
+        return Add(new TreeEditOperation(TreeEditKind.Replace, node, start, end, fragment));
+    }
+
+    /// <summary>
+    /// Inserts a fragment of source as a child of a node, separated from its neighbours the way the node's
+    /// existing children are separated from each other. Children without text are not counted.
+    /// </summary>
+    /// <param name="parentId">The id of the parent node.</param>
+    /// <param name="index">The child index the fragment is inserted at.</param>
+    /// <param name="source">The inserted source.</param>
+    /// <param name="rule">The rule the fragment must parse as, or null for the rule of the neighbouring child.</param>
+    /// <returns>This editor.</returns>
+    /// <exception cref="ArgumentException">The node is unknown, the rule cannot be inferred, or the fragment does not parse.</exception>
+    /// <exception cref="InvalidOperationException">The edit overlaps an earlier edit.</exception>
+    public TreeEditor InsertChild(Guid parentId, int index, string source, string? rule = null)
+    {
+        ArgumentNullException.ThrowIfNull(source);
+
+        var parent = GetNode(parentId);
+        var children = parent.Children.Where(c => c.SourcePosition is { Length: > 0 }).ToList();
+        if (index < 0 || index > children.Count || children.Count == 0)
+        {
+            throw new ArgumentOutOfRangeException(nameof(index), $"Cannot insert at child {index} of a node with {children.Count} children");
+        }
+
+        rule ??= new[] { index, index - 1 }
+            .Where(i => i >= 0 && i < children.Count)
+            .Select(i => (children[i] as NonTerminalNode)?.RuleName)
+            .FirstOrDefault(r => r != null)
+            ?? throw new ArgumentException("No neighbouring child gives the rule of the fragment; pass the rule explicitly", nameof(rule));
+
+        // The separator is copied from before the child the fragment follows, or from after the child it precedes,
+        // so that a fragment added to a list is separated like the list's last or first element.
+        var separator = (index > 0 ? Gap(children, index - 1) ?? Gap(children, index) : Gap(children, 1)) ?? " ";
+
+        var offset = index > 0 ? Span(children[index - 1]).End : Span(children[0]).Start;
+        var indentation = separator.Contains('\n') ? separator[(separator.LastIndexOf('\n') + 1)..] : Indentation(offset);
+        var fragment = Reindent(source.Trim(), indentation);
+        ValidateFragment(fragment, rule);
+
+        var text = index > 0 ? separator + fragment : fragment + separator;
+        return Add(new TreeEditOperation(TreeEditKind.Insert, parent, offset, offset, text));
+    }
+
+    /// <summary>
+    /// Deletes a node. A node on lines of its own is deleted with those lines, along with a following blank line
+    /// that would otherwise be doubled or start the input; otherwise the whitespace following the node goes with it.
+    /// </summary>
+    /// <param name="id">The id of the deleted node.</param>
+    /// <returns>This editor.</returns>
+    /// <exception cref="ArgumentException">The node is unknown.</exception>
+    /// <exception cref="InvalidOperationException">The edit overlaps an earlier edit.</exception>
+    public TreeEditor DeleteNode(Guid id)
+    {
+        var node = GetNode(id);
+        var (start, end) = Span(node);
+        var input = _original.Input;
+
+        var lineStart = LineStart(start);
+        var lineEnd = end;
+        while (lineEnd < input.Length && input[lineEnd] is ' ' or '\t' or '\r')
+        {
+            lineEnd++;
+        }
+
+        if (IsBlank(lineStart, start) && (lineEnd == input.Length || input[lineEnd] == '\n'))
+        {
+            start = lineStart;
+            end = Math.Min(lineEnd + 1, input.Length);
+
+            var before = start > 0 ? LineStart(start - 1) : -1;
+            var afterEnd = input.IndexOf('\n', end);
+            if ((before < 0 || IsBlank(before, start)) && afterEnd >= 0 && IsBlank(end, afterEnd))
+            {
+                end = afterEnd + 1;
+            }
+        }
+        else
+        {
+            end = lineEnd;
+        }
+
+        return Add(new TreeEditOperation(TreeEditKind.Delete, node, start, end, string.Empty));
+    }
+
+    /// <summary>
+    /// Applies the edits to the original input and reparses the result.
+    /// </summary>
+    /// <returns>The edited text, the text edits that produced it, the map from the input to it and the reparse.</returns>
+    public TreeEditResult Apply()
+    {
+        var edits = _operations
+            .Select((operation, order) => (operation, order))
+            .OrderByDescending(e => e.operation.Start)
+            .ThenByDescending(e => e.order)
+            .Select(e => new TextEdit(e.operation.Start, e.operation.End - e.operation.Start, e.operation.Text))
+            .ToList();
+
+        var text = edits.Aggregate(_original.Input, (current, edit) => edit.Apply(current));
+        var parse = _parser.Parse(text, new ParseOptions { StartRule = _original.StartRule, SourceFile = _original.Tree!.SourcePosition?.SourceFile });
+        return new TreeEditResult(text, edits, parse, PositionMap.FromEdits(_original.Input, Enumerable.Reverse(edits), _parser.Lexer));
+    }
+
+    private TreeEditor Add(TreeEditOperation operation)
+    {
+        var conflict = _operations.FirstOrDefault(o => o.ConflictsWith(operation));
+        if (conflict != null)
+        {
+            throw new InvalidOperationException($"{operation} overlaps {conflict}");
+        }
+
+        _operations.Add(operation);
+        return this;
+    }
+
+    private CognitiveGraphNode GetNode(Guid id)
+    {
+        if (!_nodes.TryGetValue(id, out var node) || node.SourcePosition == null)
+        {
+            throw new ArgumentException($"Node {id} is not part of the edited tree", nameof(id));
+        }
+
+        return node;
+    }
+
+    private void ValidateFragment(string fragment, string rule)
+    {
+        var result = _parser.Parse(fragment, new ParseOptions { StartRule = rule });
+        if (!result.IsSuccess)
+        {
+            throw new ArgumentException($"'{fragment}' is not a valid <{rule}>: {string.Join("; ", result.Diagnostics.Select(d => d.Message))}");
+        }
+    }
+
+    private void ValidateToken(string fragment, string kind)
+    {
+        var tokens = _parser.Lexer.Tokenize(fragment);
+        if (tokens.Diagnostics.Count > 0 || tokens.Tokens.Count != 1 || tokens.Tokens[0].Kind != kind)
+        {
+            throw new ArgumentException($"'{fragment}' is not a single {kind} token");
+        }
+    }
+
+    private void Index(CognitiveGraphNode node)
+    {
+        _nodes[node.Id] = node;
+        foreach (var child in node.Children)
+        {
+            Index(child);
+        }
+    }
+
+    private static (int Start, int End) Span(CognitiveGraphNode node)
+    {
+        var position = node.SourcePosition!;
+        return (position.Offset, position.Offset + position.Length);
+    }
+
+    private string? Gap(IReadOnlyList<CognitiveGraphNode> children, int index)
+    {
+        if (index < 1 || index >= children.Count)
+        {
+            return null;
+        }
+
+        return _original.Input[Span(children[index - 1]).End..Span(children[index]).Start];
+    }
+
+    private int LineStart(int offset)
+    {
+        return offset == 0 ? 0 : _original.Input.LastIndexOf('\n', offset - 1) + 1;
+    }
+
+    private bool IsBlank(int start, int end)
+    {
+        return _original.Input.AsSpan(start, end - start).IsWhiteSpace();
+    }
+
+    private string Indentation(int offset)
+    {
+        var start = LineStart(offset);
+        var end = start;
+        while (end < offset && _original.Input[end] is ' ' or '\t')
+        {
+            end++;
+        }
+
+        return _original.Input[start..end];
+    }
+
+    /// <summary>
+    /// Removes the common indentation of a fragment's continuation lines and indents them by the given prefix.
+    /// </summary>
+    private static string Reindent(string fragment, string indentation)
+    {
+        var lines = fragment.Replace("\r\n", "\n").Split('\n');
+        if (lines.Length == 1)
+        {
+            return fragment;
+        }
+
+        var common = lines.Skip(1)
+            .Where(l => l.Trim().Length > 0)
+            .Select(l => l.Length - l.TrimStart(' ', '\t').Length)
+            .DefaultIfEmpty(0)
+            .Min();
+
+        var text = new StringBuilder(lines[0]);
+        foreach (var line in lines.Skip(1))
+        {
+            text.Append('\n');
+            if (line.Trim().Length > 0)
+            {
+                text.Append(indentation).Append(line[Math.Min(common, line.Length)..]);
+            }
+        }
+
+        return text.ToString();
+    }
+}
+
+/// <summary>
+/// Kinds of tree edits.
+/// </summary>
+public enum TreeEditKind
+{
+    /// <summary>
+    /// A node is replaced by a fragment.
+    /// </summary>
+    Replace,
+
+    /// <summary>
+    /// A fragment is inserted as a new child.
+    /// </summary>
+    Insert,
+
+    /// <summary>
+    /// A node is deleted.
+    /// </summary>
+    Delete
+}
+
+/// <summary>
+/// An edit made through a <see cref="TreeEditor"/>.
+/// </summary>
+/// <param name="Kind">The kind of edit.</param>
+/// <param name="Node">The replaced or deleted node, or the parent of an insertion.</param>
+/// <param name="Start">The start offset of the edited text in the original input.</param>
+/// <param name="End">The end offset of the edited text; equal to the start for insertions.</param>
+/// <param name="Text">The text written in place of the edited range.</param>
+public sealed record TreeEditOperation(TreeEditKind Kind, CognitiveGraphNode Node, int Start, int End, string Text)
+{
+    /// <summary>
+    /// Determines whether this edit and another edit touch the same part of the tree. Ranges conflict when they
+    /// overlap, an insertion conflicts with a range it falls inside or whose node is its parent or an ancestor of
+    /// it, and insertions never conflict with each other.
+    /// </summary>
+    /// <param name="other">The other edit.</param>
+    /// <returns>True if the edits conflict.</returns>
+    public bool ConflictsWith(TreeEditOperation other)
+    {
+        ArgumentNullException.ThrowIfNull(other);
+
+        return (Kind == TreeEditKind.Insert, other.Kind == TreeEditKind.Insert) switch
+        {
+            (true, true) => false,
+            (true, false) => other.Encloses(this),
+            (false, true) => Encloses(other),
+            _ => ReferenceEquals(Node, other.Node) || (Start < other.End && other.Start < End)
+        };
+    }
+
+    /// <summary>
+    /// Returns a description of the edit, such as "delete &lt;item&gt; at 14:1".
+    /// </summary>
+    /// <returns>The description.</returns>
+    public override string ToString()
+    {
+        var name = Node is NonTerminalNode nonTerminal ? $"<{nonTerminal.RuleName}>" : $"'{(Node as TerminalNode)?.Text}'";
+        var verb = Kind == TreeEditKind.Insert ? "insert into" : Kind.ToString().ToLowerInvariant();
+        return $"{verb} {name} at {Node.SourcePosition?.Line}:{Node.SourcePosition?.Column}";
+    }
+
+    private bool Encloses(TreeEditOperation insertion)
+    {
+        if (Start < insertion.Start && insertion.Start < End)
+        {
+            return true;
+        }
+
+        for (var node = insertion.Node; node != null; node = node.Parent)
+        {
+            if (ReferenceEquals(node, Node))
+            {
+                return true;
+            }
+        }
+
+        return false;
+    }
+}
+
+/// <summary>
+/// The result of applying tree edits.
+/// </summary>
+/// <param name="Text">The edited text.</param>
+/// <param name="Edits">The text edits applied to the original input, last offset first.</param>
+/// <param name="Parse">The reparse of the edited text.</param>
+/// <param name="Map">The map from the original input to the edited text.</param>
+public sealed record TreeEditResult(string Text, IReadOnlyList<TextEdit> Edits, ParseResult Parse, PositionMap Map)
+{
+    /// <summary>
+    /// Gets a value indicating whether the edited text parses without errors.
+    /// </summary>
+    public bool IsSuccess => Parse.IsSuccess;
+}
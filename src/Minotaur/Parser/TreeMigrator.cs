@@ -0,0 +1,197 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Projects.Grammar;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A node whose rule a <see cref="TreeMigrator"/> changed.
+/// </summary>
+/// <param name="Node">The node in the migrated tree.</param>
+/// <param name="From">The rule name before migration.</param>
+/// <param name="To">The rule name after migration.</param>
+public sealed record NodeMigration(NonTerminalNode Node, string From, string To);
+
+/// <summary>
+/// The result of migrating a tree between grammar versions.
+/// </summary>
+public sealed class TreeMigrationResult
+{
+    /// <summary>
+    /// Gets the migrated copy of the tree; the original is left as it was.
+    /// </summary>
+    public required CognitiveGraphNode Tree { get; init; }
+
+    /// <summary>
+    /// Gets the nodes whose rule changed, in tree order.
+    /// </summary>
+    public required IReadOnlyList<NodeMigration> Changes { get; init; }
+
+    /// <summary>
+    /// Gets warnings for nodes left with their old rule: <see cref="DiagnosticCodes.AmbiguousMigration"/> for
+    /// splits no single condition chose a rule for and <see cref="DiagnosticCodes.UnmappedRule"/> for removed rules.
+    /// </summary>
+    public required IReadOnlyList<Diagnostic> Diagnostics { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether every node has its rule in the target version.
+    /// </summary>
+    public bool IsComplete => Diagnostics.Count == 0;
+}
+
+/// <summary>
+/// Rewrites the rule names of trees parsed with an old grammar version into those of a newer one, as a
+/// <see cref="MigrationManifest"/> declares, so consumers matching on the new names can process old trees
+/// while a grammar upgrade is rolled out.
+/// </summary>
+/// <remarks>
+/// Steps of the manifest are applied one after another to a copy of the tree. A node of a split rule gets the
+/// target whose condition it meets, or the one unconditional target if it meets none; otherwise the mapping is
+/// ambiguous. Ambiguous nodes and nodes of removed rules keep their old rule, are flagged with a diagnostic and
+/// "migration" metadata, and are left alone by later steps.
+/// </remarks>
+public sealed class TreeMigrator
+{
+    /// <summary>
+    /// The metadata key set on nodes a migration could not map: the candidate rules of an ambiguous split, or an
+    /// empty list for a removed rule.
+    /// </summary>
+    public const string MigrationMetadataKey = "migration";
+
+    private readonly MigrationManifest _manifest;
+
+    /// <summary>
+    /// Initializes a new instance of the TreeMigrator class.
+    /// </summary>
+    /// <param name="manifest">The migration manifest.</param>
+    public TreeMigrator(MigrationManifest manifest)
+    {
+        ArgumentNullException.ThrowIfNull(manifest);
+        _manifest = manifest;
+    }
+
+    /// <summary>
+    /// Migrates a tree between grammar versions.
+    /// </summary>
+    /// <param name="tree">The tree parsed with the old version.</param>
+    /// <param name="from">The version the tree was parsed with.</param>
+    /// <param name="to">The version to migrate to.</param>
+    /// <returns>The migrated copy with the changes made and the nodes that could not be mapped.</returns>
+    /// <exception cref="ArgumentException">No chain of manifest steps leads from the version to the other.</exception>
+    public TreeMigrationResult Migrate(CognitiveGraphNode tree, GrammarVersion from, GrammarVersion to)
+    {
+        ArgumentNullException.ThrowIfNull(tree);
+
+        var steps = _manifest.GetSteps(from, to);
+        var copy = tree.Clone();
+        var nodes = NonTerminals(copy).ToList();
+        var original = nodes.ToDictionary(node => node, node => node.RuleName);
+        var flagged = new HashSet<NonTerminalNode>();
+        var diagnostics = new List<Diagnostic>();
+
+        foreach (var step in steps)
+        {
+            // Nodes are visited parents first, so split conditions see the children's rules before the step.
+            foreach (var node in nodes.Where(n => !flagged.Contains(n)))
+            {
+                if (!step.Rules.TryGetValue(node.RuleName, out var migration))
+                {
+                    continue;
+                }
+
+                var candidates = Candidates(node, migration);
+                if (candidates.Count == 1)
+                {
+                    Rename(node, candidates[0]);
+                    continue;
+                }
+
+                flagged.Add(node);
+                node.Metadata[MigrationMetadataKey] = candidates;
+                var diagnostic = migration.Kind == RuleMigrationKind.Remove
+                    ? new Diagnostic
+                    {
+                        Code = DiagnosticCodes.UnmappedRule,
+                        Severity = DiagnosticSeverity.Warning,
+                        Location = node.SourcePosition
+                    }.WithMessage("W0007.removed", ("rule", node.RuleName), ("to", step.To.ToString()))
+                    : new Diagnostic
+                    {
+                        Code = DiagnosticCodes.AmbiguousMigration,
+                        Severity = DiagnosticSeverity.Warning,
+                        Location = node.SourcePosition
+                    }.WithMessage(DiagnosticCodes.AmbiguousMigration, ("rule", node.RuleName), ("to", step.To.ToString()), ("rules", candidates.Select(c => $"<{c}>").ToList()));
+                diagnostic.Data["rule"] = node.RuleName;
+                diagnostic.Data["candidates"] = candidates;
+                diagnostics.Add(diagnostic);
+            }
+        }
+
+        return new TreeMigrationResult
+        {
+            Tree = copy,
+            Changes = nodes
+                .Where(node => node.RuleName != original[node])
+                .Select(node => new NodeMigration(node, original[node], node.RuleName))
+                .ToList(),
+            Diagnostics = diagnostics
+        };
+    }
+
+    private static List<string> Candidates(NonTerminalNode node, RuleMigration migration)
+    {
+        if (migration.Kind != RuleMigrationKind.Split)
+        {
+            return migration.Targets.Select(t => t.Rule).ToList();
+        }
+
+        var met = migration.Targets.Where(t => t.When != null && Meets(node, t.When)).ToList();
+        if (met.Count == 0)
+        {
+            met = migration.Targets.Where(t => t.When == null).ToList();
+        }
+
+        return (met.Count == 0 ? migration.Targets : met).Select(t => t.Rule).ToList();
+    }
+
+    private static bool Meets(NonTerminalNode node, GrammarSymbol condition)
+    {
+        return condition.Kind == GrammarSymbolKind.Literal
+            ? FirstTerminal(node)?.Text == condition.Name
+            : node.Children.OfType<NonTerminalNode>().Any(child => child.RuleName == condition.Name);
+    }
+
+    private static TerminalNode? FirstTerminal(CognitiveGraphNode node)
+    {
+        return node as TerminalNode ?? node.Children.Select(FirstTerminal).FirstOrDefault(t => t != null);
+    }
+
+    private static void Rename(NonTerminalNode node, string rule)
+    {
+        node.RuleName = rule;
+        node.Metadata["ruleName"] = rule;
+    }
+
+    private static IEnumerable<NonTerminalNode> NonTerminals(CognitiveGraphNode node)
+    {
+        var own = node is NonTerminalNode nonTerminal ? new[] { nonTerminal } : Array.Empty<NonTerminalNode>();
+        return own.Concat(node.Children.SelectMany(NonTerminals));
+    }
+}
@@ -0,0 +1,208 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Parses languages whose syntax depends on what names declare, like C's typedefs, where <c>(T)*x</c> is a cast
+/// if <c>T</c> names a type and a multiplication otherwise. The first phase parses without checking the grammar's
+/// "SymbolPredicates", so the generalized parser keeps both readings; the declarations of the result are collected
+/// into a <see cref="SymbolSet"/>; and the second phase parses again with the predicates checked against it.
+/// </summary>
+/// <remarks>
+/// The built-in extraction, <see cref="ExtractDeclarations"/>, reads declarations from the first phase's tree. A host
+/// with its own notion of declarations, such as names from included headers, supplies an <see cref="Extractor"/>.
+/// The second phase is skipped when the first is unambiguous and already satisfies every predicate.
+/// </remarks>
+public class TwoPhaseParser
+{
+    private readonly GeneralizedParser _parser;
+
+    /// <summary>
+    /// Initializes a new instance of the TwoPhaseParser class.
+    /// </summary>
+    /// <param name="parser">The parser used for both phases.</param>
+    /// <exception cref="ArgumentException">The grammar declares no symbol predicates.</exception>
+    public TwoPhaseParser(GeneralizedParser parser)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+
+        if (!parser.Grammar.HasSymbolPredicates)
+        {
+            throw new ArgumentException($"Grammar '{parser.Grammar.Name}' declares no '{CompiledGrammar.SymbolPredicatesKey}', so a second phase would parse the same way", nameof(parser));
+        }
+
+        _parser = parser;
+    }
+
+    /// <summary>
+    /// Gets which declarations a use of a name sees when the built-in extraction collects them. Defaults to
+    /// <see cref="SymbolVisibility.Forward"/>.
+    /// </summary>
+    public SymbolVisibility Visibility { get; init; } = SymbolVisibility.Forward;
+
+    /// <summary>
+    /// Gets the function collecting the declarations of a first-phase result. If null, <see cref="ExtractDeclarations"/>
+    /// is used with <see cref="Visibility"/>.
+    /// </summary>
+    public Func<ParseResult, SymbolSet>? Extractor { get; init; }
+
+    /// <summary>
+    /// Parses input in two phases.
+    /// </summary>
+    /// <param name="input">The input text.</param>
+    /// <param name="options">Optional parse options for both phases; their <see cref="ParseOptions.Symbols"/> is
+    /// replaced by the collected declarations.</param>
+    /// <returns>Both phases' results and the declarations between them.</returns>
+    public TwoPhaseParseResult Parse(string input, ParseOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+
+        var firstOptions = options?.Clone() ?? new ParseOptions();
+        firstOptions.Symbols = null;
+        var first = _parser.Parse(input, firstOptions);
+        if (first.Tree == null)
+        {
+            return new TwoPhaseParseResult(first, new SymbolSet(Visibility), first);
+        }
+
+        var symbols = Extractor?.Invoke(first) ?? ExtractDeclarations(first, Visibility);
+        if (!first.IsAmbiguous && Satisfies(first.Tree, first.Grammar!, symbols))
+        {
+            return new TwoPhaseParseResult(first, symbols, first);
+        }
+
+        var secondOptions = firstOptions.Clone();
+        secondOptions.Symbols = symbols;
+        return new TwoPhaseParseResult(first, symbols, _parser.Parse(input, secondOptions));
+    }
+
+    /// <summary>
+    /// Collects the names a parse declares for the grammar's symbol predicates. A node of a rule a predicate names as
+    /// its kind declares the first token in it that the predicate's rule could derive, not counting tokens under
+    /// predicate rules, which are uses. In ambiguous regions the tree's derivation is read.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    /// <param name="visibility">Which declarations a use of a name sees.</param>
+    /// <returns>The declared names.</returns>
+    public static SymbolSet ExtractDeclarations(ParseResult result, SymbolVisibility visibility = SymbolVisibility.Forward)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+
+        var symbols = new SymbolSet(visibility);
+        var grammar = result.Grammar;
+        if (result.Tree == null || grammar == null)
+        {
+            return symbols;
+        }
+
+        // The terminals each kind declares are those its predicate rules derive.
+        var declared = new Dictionary<string, HashSet<string>>(StringComparer.Ordinal);
+        foreach (var rule in grammar.Rules.Where(r => r.SymbolPredicate != null))
+        {
+            if (!declared.TryGetValue(rule.SymbolPredicate!.Kind, out var keys))
+            {
+                declared[rule.SymbolPredicate.Kind] = keys = new HashSet<string>(StringComparer.Ordinal);
+            }
+
+            keys.UnionWith(rule.Alternatives.Select(a => a.Symbols[0].Key));
+        }
+
+        foreach (var node in Preorder(result.Tree))
+        {
+            if (node is NonTerminalNode declaration && declared.TryGetValue(declaration.RuleName, out var keys) &&
+                FindDeclaredName(declaration, keys, grammar) is { } name)
+            {
+                symbols.Add(name.Text, declaration.RuleName, name.SourcePosition?.Offset ?? 0);
+            }
+        }
+
+        return symbols;
+    }
+
+    private static TerminalNode? FindDeclaredName(NonTerminalNode declaration, HashSet<string> keys, CompiledGrammar grammar)
+    {
+        var pending = new Stack<CognitiveGraphNode>(declaration.Children.Reverse());
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node is TerminalNode terminal && keys.Contains(terminal.TokenType))
+            {
+                return terminal;
+            }
+
+            if (node is NonTerminalNode rule && grammar.GetRule(rule.RuleName)?.SymbolPredicate != null)
+            {
+                continue;
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+
+        return null;
+    }
+
+    private static bool Satisfies(CognitiveGraphNode tree, CompiledGrammar grammar, SymbolSet symbols)
+    {
+        foreach (var node in Preorder(tree))
+        {
+            if (node is NonTerminalNode rule && grammar.GetRule(rule.RuleName)?.SymbolPredicate is { } predicate &&
+                rule.Children.Count > 0 && rule.Children[0] is TerminalNode token &&
+                symbols.IsDeclared(token.Text, predicate.Kind, token.SourcePosition?.Offset ?? 0) == predicate.Negated)
+            {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    private static IEnumerable<CognitiveGraphNode> Preorder(CognitiveGraphNode root)
+    {
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(root);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            yield return node;
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+    }
+}
+
+/// <summary>
+/// The result of a <see cref="TwoPhaseParser"/>.
+/// </summary>
+/// <param name="FirstPhase">The parse without symbol predicates, which may be ambiguous.</param>
+/// <param name="Symbols">The declarations collected from the first phase.</param>
+/// <param name="Result">The parse with the symbol predicates checked against <paramref name="Symbols"/>, or the first
+/// phase if it already satisfied them.</param>
+public sealed record TwoPhaseParseResult(ParseResult FirstPhase, SymbolSet Symbols, ParseResult Result)
+{
+    /// <summary>
+    /// Gets a value indicating whether the second phase parsed the input again.
+    /// </summary>
+    public bool Reparsed => !ReferenceEquals(FirstPhase, Result);
+}
@@ -0,0 +1,278 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// A requirement that the members of a container have distinct keys, written as a
+/// <c>// @unique_by(&lt;member&gt;, &lt;STRING&gt;, ignore_case, normalize, feature = "strict")</c> annotation after
+/// the container rule. The first argument names the member rule and the second the rule or token of each member whose
+/// text is its key. Keys are compared as written unless <c>ignore_case</c> folds their case or <c>normalize</c> puts
+/// them in Unicode normalization form C (or the form given, like <c>normalize = "NFKC"</c>). With a <c>feature</c>,
+/// keys are only checked in parses that enable it, so a grammar can leave duplicates to a strict mode.
+/// </summary>
+public sealed class UniqueKeyConstraint
+{
+    private UniqueKeyConstraint(string member, GrammarSymbol key, bool ignoreCase, NormalizationForm? normalization, string? feature)
+    {
+        Member = member;
+        Key = key;
+        IgnoreCase = ignoreCase;
+        Normalization = normalization;
+        Feature = feature;
+    }
+
+    /// <summary>
+    /// Gets the name of the rule whose nodes are the container's members.
+    /// </summary>
+    public string Member { get; }
+
+    /// <summary>
+    /// Gets the rule or token of each member whose text is its key.
+    /// </summary>
+    public GrammarSymbol Key { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether keys differing only in case collide.
+    /// </summary>
+    public bool IgnoreCase { get; }
+
+    /// <summary>
+    /// Gets the Unicode normalization form keys are compared in, or null if they are compared as written.
+    /// </summary>
+    public NormalizationForm? Normalization { get; }
+
+    /// <summary>
+    /// Gets the feature a parse must enable for keys to be checked, or null if they always are.
+    /// </summary>
+    public string? Feature { get; }
+
+    /// <summary>
+    /// Reads the text of a unique key annotation: the member rule and the key symbol followed by optional
+    /// <c>ignore_case</c>, <c>normalize</c> and <c>feature</c> arguments, in parentheses.
+    /// </summary>
+    /// <param name="text">The annotation text after <c>@unique_by</c>.</param>
+    /// <param name="ruleNames">The names of the grammar's rules, to tell rule references from tokens.</param>
+    /// <returns>The constraint.</returns>
+    /// <exception cref="FormatException">The text is not a valid argument list.</exception>
+    public static UniqueKeyConstraint Parse(string text, ISet<string> ruleNames)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+        ArgumentNullException.ThrowIfNull(ruleNames);
+
+        var arguments = text.Trim();
+        if (!arguments.StartsWith('(') || !arguments.EndsWith(')'))
+        {
+            throw new FormatException($"'{text}' must be a parenthesized argument list, like @unique_by(<member>, <STRING>)");
+        }
+
+        var parts = arguments[1..^1].Split(',', StringSplitOptions.TrimEntries);
+        if (parts.Length < 2 ||
+            GrammarSymbol.ParseAlternative(parts[0], ruleNames) is not [{ Kind: GrammarSymbolKind.Rule } member] ||
+            GrammarSymbol.ParseAlternative(parts[1], ruleNames) is not [{ Kind: not GrammarSymbolKind.Literal } key])
+        {
+            throw new FormatException($"'{text}' must name the member rule and the rule or token holding its key, like @unique_by(<member>, <STRING>)");
+        }
+
+        var ignoreCase = false;
+        NormalizationForm? normalization = null;
+        string? feature = null;
+        foreach (var part in parts.Skip(2))
+        {
+            var separator = part.IndexOf('=');
+            var name = separator < 0 ? part : part[..separator].Trim();
+            var value = separator < 0 ? null : part[(separator + 1)..].Trim().Trim('"');
+            switch (name)
+            {
+                case "ignore_case" when value == null:
+                    ignoreCase = true;
+                    break;
+                case "normalize":
+                    normalization = value?.ToUpperInvariant() switch
+                    {
+                        null or "NFC" => NormalizationForm.FormC,
+                        "NFD" => NormalizationForm.FormD,
+                        "NFKC" => NormalizationForm.FormKC,
+                        "NFKD" => NormalizationForm.FormKD,
+                        _ => throw new FormatException($"'{text}' has unknown normalization form '{value}'; expected NFC, NFD, NFKC or NFKD")
+                    };
+                    break;
+                case "feature" when !string.IsNullOrEmpty(value):
+                    feature = value;
+                    break;
+                default:
+                    throw new FormatException($"'{text}' has unknown argument '{part}'; expected ignore_case, normalize or feature");
+            }
+        }
+
+        return new UniqueKeyConstraint(member.Name, key, ignoreCase, normalization, feature);
+    }
+
+    /// <summary>
+    /// Returns the key a member is compared by.
+    /// </summary>
+    /// <param name="text">The text of the member's key.</param>
+    /// <returns>The text, normalized and case-folded as the constraint requires.</returns>
+    public string Normalize(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        if (Normalization is { } form)
+        {
+            text = text.Normalize(form);
+        }
+
+        return IgnoreCase ? text.ToLowerInvariant() : text;
+    }
+
+    /// <summary>
+    /// Reports an error for every member of a container whose key collides with an earlier member's. Members are
+    /// collected from anywhere under the container, so duplicates separated by other members are found in
+    /// left-recursive lists, but not from inside other members or nested containers. An error carries a quick fix
+    /// removing the later member with the separator before it.
+    /// </summary>
+    /// <param name="parse">A successful parse.</param>
+    /// <param name="features">The features the parse enables.</param>
+    /// <returns>The errors in document order.</returns>
+    internal static List<Diagnostic> Check(ParseResult parse, IReadOnlyCollection<string> features)
+    {
+        var errors = new List<Diagnostic>();
+        if (parse.Tree == null || parse.Grammar == null)
+        {
+            return errors;
+        }
+
+        var grammar = parse.Grammar;
+        var stack = new Stack<CognitiveGraphNode>();
+        stack.Push(parse.Tree);
+        while (stack.Count > 0)
+        {
+            // A deferred region is checked by its own parse when it is materialized.
+            var node = stack.Pop();
+            if (node is DeferredNode { IsMaterialized: false })
+            {
+                continue;
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                stack.Push(node.Children[i]);
+            }
+
+            if (node is NonTerminalNode container &&
+                grammar.GetRule(container.RuleName)?.UniqueKey is { } constraint &&
+                (constraint.Feature == null || features.Contains(constraint.Feature)))
+            {
+                constraint.CheckContainer(parse, container, errors);
+            }
+        }
+
+        return errors;
+    }
+
+    private void CheckContainer(ParseResult parse, NonTerminalNode container, List<Diagnostic> errors)
+    {
+        var first = new Dictionary<string, CognitiveGraphNode>(StringComparer.Ordinal);
+        foreach (var member in FindMembers(container))
+        {
+            var key = member.Children.FirstOrDefault(IsKey);
+            if (key?.SourcePosition == null)
+            {
+                continue;
+            }
+
+            var text = Text(key);
+            if (first.TryAdd(Normalize(text), key))
+            {
+                continue;
+            }
+
+            var earlier = first[Normalize(text)];
+            var location = earlier.SourcePosition!;
+            var error = new Diagnostic
+            {
+                Code = DiagnosticCodes.DuplicateKey,
+                Location = key.SourcePosition,
+                Data = { ["rule"] = container.RuleName, ["key"] = text, ["related"] = location }
+            }.WithMessage(DiagnosticCodes.DuplicateKey, ("key", text), ("earlier", Text(earlier)), ("line", location.Line), ("column", location.Column));
+
+            if (member.SourcePosition is { } span)
+            {
+                error.Data[QuickFix.DataKey] = Removal(parse.Tokens, span);
+            }
+
+            errors.Add(error);
+        }
+    }
+
+    private IEnumerable<NonTerminalNode> FindMembers(NonTerminalNode container)
+    {
+        // Depth-first in source order; members and nested containers hold no members of this container.
+        var stack = new Stack<CognitiveGraphNode>();
+        for (var i = container.Children.Count - 1; i >= 0; i--)
+        {
+            stack.Push(container.Children[i]);
+        }
+
+        while (stack.Count > 0)
+        {
+            var node = stack.Pop();
+            if (node is NonTerminalNode rule && rule.RuleName == Member)
+            {
+                yield return rule;
+                continue;
+            }
+
+            if (node is DeferredNode { IsMaterialized: false } || (node is NonTerminalNode nested && nested.RuleName == container.RuleName))
+            {
+                continue;
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                stack.Push(node.Children[i]);
+            }
+        }
+    }
+
+    private bool IsKey(CognitiveGraphNode node)
+    {
+        return node switch
+        {
+            NonTerminalNode rule => Key.Kind == GrammarSymbolKind.Rule && rule.RuleName == Key.Name,
+            TerminalNode terminal => Key.IsTerminal && terminal.TokenType == Key.Key,
+            _ => false
+        };
+    }
+
+    private static string Text(CognitiveGraphNode node)
+    {
+        return node is TerminalNode terminal ? terminal.Text : string.Concat(node.Children.Select(Text));
+    }
+
+    private static TextEdit Removal(IReadOnlyList<Token> tokens, SourcePosition member)
+    {
+        // A separator before the member goes with it, so the rest of the list stays well formed.
+        var before = tokens.LastOrDefault(t => t.End <= member.Offset);
+        var start = before is { Text: "," or ";" } ? before.Offset : member.Offset;
+        return new TextEdit(start, member.Offset + member.Length - start, string.Empty);
+    }
+}
@@ -0,0 +1,290 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+
+namespace Minotaur.Parser;
+
+/// <summary>
+/// Gives a file that no grammar matches an approximate structure, so that outline, folding and node lookup still
+/// work on it. The text is read with a generic lexer that knows common comment styles, string quotes and brackets
+/// (see <see cref="GenericLexerOptions"/>), and the tree has a <see cref="RegionRule"/> node for every bracketed
+/// region, nested as the brackets are, with comments, strings, brackets and the other text as leaves. A closing
+/// bracket that closes nothing and an opening bracket that is never closed are reported as warnings; a region left
+/// open ends with the last token before the bracket that closes an enclosing region, or with the file.
+/// </summary>
+public sealed class UniversalFallbackAnalyzer
+{
+    /// <summary>
+    /// The name reported for the analyzer where a grammar name is expected.
+    /// </summary>
+    public const string Name = "universal-fallback";
+
+    /// <summary>
+    /// The rule name of the tree's root, which spans the whole file.
+    /// </summary>
+    public const string DocumentRule = "document";
+
+    /// <summary>
+    /// The rule name of a bracketed region. The node's production index is the index of its bracket pair.
+    /// </summary>
+    public const string RegionRule = "region";
+
+    /// <summary>
+    /// The token kind of comments.
+    /// </summary>
+    public const string CommentKind = "COMMENT";
+
+    /// <summary>
+    /// The token kind of strings.
+    /// </summary>
+    public const string StringKind = "STRING";
+
+    /// <summary>
+    /// The token kind of text that is not a comment, string or bracket.
+    /// </summary>
+    public const string TextKind = "TEXT";
+
+    /// <summary>
+    /// Initializes a new instance of the UniversalFallbackAnalyzer class.
+    /// </summary>
+    /// <param name="options">The syntax to recognize; if null, the defaults of <see cref="GenericLexerOptions"/>.</param>
+    public UniversalFallbackAnalyzer(GenericLexerOptions? options = null)
+    {
+        Options = options ?? new GenericLexerOptions();
+    }
+
+    /// <summary>
+    /// Gets the syntax the analyzer recognizes.
+    /// </summary>
+    public GenericLexerOptions Options { get; }
+
+    /// <summary>
+    /// Analyzes a file. The result has no grammar and no parse forest; its start rule is <see cref="DocumentRule"/>.
+    /// </summary>
+    /// <param name="text">The file's text.</param>
+    /// <param name="sourceFile">The optional source file name for node positions and diagnostics.</param>
+    /// <returns>The tree of the file's regions and its tokens.</returns>
+    public ParseResult Analyze(string text, string? sourceFile = null)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var tokens = Tokenize(text);
+        var lineIndex = new LineIndex(text);
+        var diagnostics = new List<Diagnostic>();
+        var root = new NonTerminalNode(DocumentRule, 0) { SourcePosition = lineIndex.GetPosition(0, text.Length, sourceFile) };
+        var open = new Stack<(NonTerminalNode Region, Token Opening)>();
+        var previousEnd = 0;
+
+        void Close(NonTerminalNode region, Token opening, int end)
+        {
+            region.SourcePosition = lineIndex.GetPosition(opening.Offset, end - opening.Offset, sourceFile);
+        }
+
+        void Unclosed(Token opening)
+        {
+            diagnostics.Add(Warning(DiagnosticCodes.UnbalancedBracket, opening.Text, lineIndex.GetPosition(opening.Offset, opening.Length, sourceFile)));
+        }
+
+        foreach (var token in tokens)
+        {
+            var leaf = new TerminalNode(token.Text, token.Kind) { SourcePosition = lineIndex.GetPosition(token.Offset, token.Length, sourceFile) };
+            var pair = FindBracket(token);
+            if (pair >= 0 && Options.Brackets[pair].Open == token.Text)
+            {
+                var region = new NonTerminalNode(RegionRule, pair);
+                (open.Count > 0 ? open.Peek().Region : root).AddChild(region);
+                region.AddChild(leaf);
+                open.Push((region, token));
+            }
+            else if (pair >= 0 && open.Any(o => o.Region.ProductionIndex == pair))
+            {
+                // Regions opened inside the one this bracket closes end before it.
+                while (open.Peek().Region.ProductionIndex != pair)
+                {
+                    var (unclosed, opening) = open.Pop();
+                    Close(unclosed, opening, previousEnd);
+                    Unclosed(opening);
+                }
+
+                var (region, start) = open.Pop();
+                region.AddChild(leaf);
+                Close(region, start, token.End);
+            }
+            else
+            {
+                if (pair >= 0)
+                {
+                    diagnostics.Add(Warning("W0012.closing", token.Text, leaf.SourcePosition));
+                }
+
+                (open.Count > 0 ? open.Peek().Region : root).AddChild(leaf);
+            }
+
+            previousEnd = token.End;
+        }
+
+        while (open.Count > 0)
+        {
+            var (unclosed, opening) = open.Pop();
+            Close(unclosed, opening, previousEnd);
+            Unclosed(opening);
+        }
+
+        return new ParseResult
+        {
+            Input = text,
+            StartRule = DocumentRule,
+            Tokens = tokens,
+            Tree = root,
+            Diagnostics = diagnostics.OrderBy(d => d.Location!.Offset).ToList(),
+            ParsedLength = text.Length
+        };
+    }
+
+    /// <summary>
+    /// Reads a file's comments, strings, brackets and other text. Whitespace separates tokens and is not returned;
+    /// a bracket's kind is its quoted text, as for a grammar literal, e.g. <c>"{"</c>.
+    /// </summary>
+    /// <param name="text">The file's text.</param>
+    /// <returns>The tokens in order.</returns>
+    public IReadOnlyList<Token> Tokenize(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var tokens = new List<Token>();
+        var position = 0;
+        while (position < text.Length)
+        {
+            if (char.IsWhiteSpace(text[position]))
+            {
+                position++;
+                continue;
+            }
+
+            var start = position;
+            var kind = Scan(text, ref position);
+            tokens.Add(new Token(kind, text[start..position], start));
+        }
+
+        return tokens;
+    }
+
+    private string Scan(string text, ref int position)
+    {
+        foreach (var (open, close) in Options.BlockComments)
+        {
+            if (At(text, position, open))
+            {
+                var end = text.IndexOf(close, position + open.Length, StringComparison.Ordinal);
+                position = end >= 0 ? end + close.Length : text.Length;
+                return CommentKind;
+            }
+        }
+
+        foreach (var marker in Options.LineComments)
+        {
+            if (At(text, position, marker))
+            {
+                var end = text.IndexOf('\n', position);
+                position = end >= 0 ? end : text.Length;
+                if (text[position - 1] == '\r')
+                {
+                    position--;
+                }
+
+                return CommentKind;
+            }
+        }
+
+        if (Options.StringQuotes.Contains(text[position]))
+        {
+            var quote = text[position++];
+            while (position < text.Length && text[position] != quote && text[position] is not ('\n' or '\r'))
+            {
+                position += text[position] == Options.EscapeCharacter && position + 1 < text.Length ? 2 : 1;
+            }
+
+            if (position < text.Length && text[position] == quote)
+            {
+                position++;
+            }
+
+            return StringKind;
+        }
+
+        foreach (var (open, close) in Options.Brackets)
+        {
+            var bracket = At(text, position, open) ? open : At(text, position, close) ? close : null;
+            if (bracket != null)
+            {
+                position += bracket.Length;
+                return "\"" + bracket + "\"";
+            }
+        }
+
+        do
+        {
+            position++;
+        }
+        while (position < text.Length && !char.IsWhiteSpace(text[position]) && !StartsToken(text, position));
+
+        return TextKind;
+    }
+
+    private bool StartsToken(string text, int position)
+    {
+        return Options.StringQuotes.Contains(text[position])
+            || Options.BlockComments.Any(c => At(text, position, c.Open))
+            || Options.LineComments.Any(m => At(text, position, m))
+            || Options.Brackets.Any(b => At(text, position, b.Open) || At(text, position, b.Close));
+    }
+
+    private int FindBracket(Token token)
+    {
+        if (token.Kind is CommentKind or StringKind or TextKind)
+        {
+            return -1;
+        }
+
+        for (var i = 0; i < Options.Brackets.Count; i++)
+        {
+            if (Options.Brackets[i].Open == token.Text || Options.Brackets[i].Close == token.Text)
+            {
+                return i;
+            }
+        }
+
+        return -1;
+    }
+
+    private static bool At(string text, int position, string value)
+    {
+        return value.Length > 0 && string.CompareOrdinal(text, position, value, 0, value.Length) == 0;
+    }
+
+    private static Diagnostic Warning(string key, string bracket, SourcePosition location)
+    {
+        return new Diagnostic
+        {
+            Code = DiagnosticCodes.UnbalancedBracket,
+            Severity = DiagnosticSeverity.Warning,
+            Location = location
+        }.WithMessage(key, ("bracket", bracket));
+    }
+}
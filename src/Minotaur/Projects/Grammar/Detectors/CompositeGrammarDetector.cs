@@ -19,7 +19,8 @@ namespace Minotaur.Projects.Grammar.Detectors;
 
 /// <summary>
 /// Composite grammar detector that combines multiple detection strategies to provide the best possible grammar detection.
-/// This detector runs multiple child detectors and selects the result with the highest confidence.
+/// This detector runs multiple child detectors and selects the result with the highest confidence. When none of
+/// them finds a grammar, the result is a <see cref="GrammarDetectionResult.Fallback"/> to the universal fallback analyzer.
 /// </summary>
 public class CompositeGrammarDetector : IGrammarDetector
 {
@@ -60,11 +61,41 @@ public class CompositeGrammarDetector : IGrammarDetector
     /// <returns>A task that represents the asynchronous detection operation.</returns>
     public async Task<GrammarDetectionResult> DetectGrammarAsync(GrammarDetectionContext context)
     {
+        var trace = await ExplainAsync(context);
+        return trace.Result;
+    }
+
+    /// <summary>
+    /// Detects grammar as <see cref="DetectGrammarAsync"/> does and records how the result was reached: every child
+    /// detector in the order it was consulted, with its evidence and verdict, and why the winning result was chosen.
+    /// </summary>
+    /// <param name="context">The detection context.</param>
+    /// <returns>A task that represents the asynchronous operation. The task result contains the resolution trace.</returns>
+    public async Task<GrammarResolutionTrace> ExplainAsync(GrammarDetectionContext context)
+    {
+        var steps = new List<DetectorVerdict>();
+
+        GrammarResolutionTrace Trace(GrammarDetectionResult result, string reason, IGrammarDetector? winner = null)
+        {
+            return new GrammarResolutionTrace
+            {
+                FilePath = context.FilePath,
+                ConfigurationPath = context.Configuration?.SourcePath,
+                MinimumConfidence = _minimumConfidence,
+                RequireConsensus = _requireConsensus,
+                Steps = steps,
+                WinningDetectorId = winner?.DetectorId,
+                Reason = reason,
+                Result = result
+            };
+        }
+
         if (!CanDetect(context))
         {
-            return GrammarDetectionResult.Failure(
-                "No detectors available or context is invalid",
-                DetectorId);
+            steps.AddRange(_detectors.Select(DetectorVerdict.Skipped));
+            return Trace(
+                GrammarDetectionResult.Fallback("No detectors available or context is invalid", DetectorId),
+                "No detector can handle this file");
         }
 
         var results = new List<(IGrammarDetector detector, GrammarDetectionResult result)>();
@@ -76,12 +107,19 @@ public class CompositeGrammarDetector : IGrammarDetector
         };
 
         // Run all applicable detectors
-        foreach (var detector in _detectors.Where(d => d.CanDetect(context)))
+        foreach (var detector in _detectors)
         {
+            if (!detector.CanDetect(context))
+            {
+                steps.Add(DetectorVerdict.Skipped(detector));
+                continue;
+            }
+
             try
             {
                 var result = await detector.DetectGrammarAsync(context);
                 results.Add((detector, result));
+                steps.Add(DetectorVerdict.FromResult(detector, result, _minimumConfidence));
 
                 ((List<string>)metadata["detectorsUsed"]).Add(detector.DetectorId);
                 ((List<object>)metadata["allResults"]).Add(new
@@ -96,6 +134,9 @@ public class CompositeGrammarDetector : IGrammarDetector
             catch (Exception ex)
             {
                 // Log the error but continue with other detectors
+                steps.Add(new DetectorVerdict(
+                    detector.DetectorId, detector.Priority, DetectorOutcome.Error, null, null, 0.0,
+                    ex.Message, Array.Empty<DetectionEvidence>()));
                 ((List<object>)metadata["allResults"]).Add(new
                 {
                     DetectorId = detector.DetectorId,
@@ -107,10 +148,12 @@ public class CompositeGrammarDetector : IGrammarDetector
 
         if (!results.Any())
         {
-            return GrammarDetectionResult.Failure(
-                "No detectors could process the context",
-                DetectorId,
-                metadata);
+            return Trace(
+                GrammarDetectionResult.Fallback(
+                    "No detectors could process the context",
+                    DetectorId,
+                    metadata),
+                "No detector could process the file");
         }
 
         // Filter successful results
@@ -121,20 +164,18 @@ public class CompositeGrammarDetector : IGrammarDetector
         if (!successfulResults.Any())
         {
             metadata["reason"] = "No results met minimum confidence threshold";
-            return GrammarDetectionResult.Failure(
-                $"No results met minimum confidence threshold of {_minimumConfidence}",
-                DetectorId,
-                metadata);
+            return Trace(
+                GrammarDetectionResult.Fallback(
+                    $"No results met minimum confidence threshold of {_minimumConfidence}",
+                    DetectorId,
+                    metadata),
+                $"No detector found a grammar with at least the minimum confidence of {DetectorVerdict.Format(_minimumConfidence)}");
         }
 
-        if (_requireConsensus)
-        {
-            return await GetConsensusResultAsync(successfulResults, metadata);
-        }
-        else
-        {
-            return GetBestResultAsync(successfulResults, metadata);
-        }
+        var (bestResult, winner, reason) = _requireConsensus
+            ? GetConsensusResult(successfulResults, metadata)
+            : GetBestResult(successfulResults, metadata);
+        return Trace(bestResult, reason, winner);
     }
 
     /// <summary>
@@ -182,7 +223,7 @@ public class CompositeGrammarDetector : IGrammarDetector
         return _detectors.AsReadOnly();
     }
 
-    private async Task<GrammarDetectionResult> GetConsensusResultAsync(
+    private (GrammarDetectionResult Result, IGrammarDetector Winner, string Reason) GetConsensusResult(
         List<(IGrammarDetector detector, GrammarDetectionResult result)> results,
         Dictionary<string, object> metadata)
     {
@@ -200,7 +241,8 @@ public class CompositeGrammarDetector : IGrammarDetector
         if (consensusGroup.Count() < 2)
         {
             metadata["consensusReason"] = "No consensus reached - using best single result";
-            return GetBestResultAsync(results, metadata);
+            var best = GetBestResult(results, metadata);
+            return (best.Result, best.Winner, $"no two detectors agreed, so the best single result was used: {best.Reason}");
         }
 
         // Calculate weighted average confidence
@@ -223,19 +265,24 @@ public class CompositeGrammarDetector : IGrammarDetector
         metadata["consensusConfidence"] = weightedConfidence;
         metadata["agreementRatio"] = (double)consensusGroup.Count() / results.Count;
 
-        var bestResult = consensusGroup.OrderByDescending(r => r.result.Confidence).First().result;
+        var bestEntry = consensusGroup.OrderByDescending(r => r.result.Confidence).First();
+        var bestResult = bestEntry.result;
 
-        await Task.CompletedTask;
-        return GrammarDetectionResult.Success(
+        var result = GrammarDetectionResult.Success(
             bestResult.GrammarName!,
             versions.FirstOrDefault(),
             weightedConfidence,
             DetectorId,
             metadata,
-            allFallbacks);
+            allFallbacks,
+            consensusGroup.SelectMany(r => r.result.Evidence).ToList());
+        var reason = $"{consensusGroup.Count()} of {results.Count} matching detectors agreed on {bestResult.GrammarName} " +
+            $"({string.Join(", ", consensusGroup.Select(r => r.detector.DetectorId))}), giving a weighted confidence of " +
+            $"{DetectorVerdict.Format(weightedConfidence)}; {bestEntry.detector.DetectorId} had the highest confidence among them";
+        return (result, bestEntry.detector, reason);
     }
 
-    private GrammarDetectionResult GetBestResultAsync(
+    private (GrammarDetectionResult Result, IGrammarDetector Winner, string Reason) GetBestResult(
         List<(IGrammarDetector detector, GrammarDetectionResult result)> results,
         Dictionary<string, object> metadata)
     {
@@ -258,13 +305,26 @@ public class CompositeGrammarDetector : IGrammarDetector
             }
         }
 
-        return GrammarDetectionResult.Success(
+        var result = GrammarDetectionResult.Success(
             bestResult.result.GrammarName!,
             bestResult.result.Version,
             bestResult.result.Confidence,
             DetectorId,
             metadata,
-            bestResult.result.FallbackGrammars);
+            bestResult.result.FallbackGrammars,
+            bestResult.result.Evidence);
+
+        var confidence = DetectorVerdict.Format(bestResult.result.Confidence);
+        var rivals = results.Where(r => r.detector != bestResult.detector).ToList();
+        var tied = rivals.Where(r => r.result.Confidence == bestResult.result.Confidence).ToList();
+        var reason = rivals.Count == 0
+            ? $"it was the only detector to find a grammar with at least the minimum confidence (found {confidence})"
+            : tied.Count > 0
+                ? $"it tied at confidence {confidence} with {string.Join(", ", tied.Select(r => $"{r.detector.DetectorId} (priority {r.detector.Priority})"))} " +
+                  $"and won on its higher priority of {bestResult.detector.Priority}"
+                : $"it had the highest confidence ({confidence}), ahead of " +
+                  string.Join(", ", rivals.Select(r => $"{r.detector.DetectorId} ({DetectorVerdict.Format(r.result.Confidence)})"));
+        return (result, bestResult.detector, reason);
     }
 
     /// <summary>
@@ -222,8 +222,19 @@ public partial class ContentBasedGrammarDetector : IGrammarDetector
         }
     };
 
+    // Interpreters named by shebang lines, by the program name without its version suffix.
+    private static readonly Dictionary<string, GrammarMapping> InterpreterMappings = new(StringComparer.Ordinal)
+    {
+        ["python"] = new GrammarMapping { Grammar = "Python311.grammar", Version = "3.11", Confidence = 0.95 },
+        ["node"] = new GrammarMapping { Grammar = "JavaScriptES2022.grammar", Version = "ES2022", Confidence = 0.95 }
+    };
+
     private static readonly object _contentRulesLock = new();
 
+    private const string BuiltInSource = "built-in content rules";
+
+    private const int MaxEvidenceLineLength = 80;
+
     /// <summary>
     /// Gets the detector identifier.
     /// </summary>
@@ -262,6 +273,24 @@ public partial class ContentBasedGrammarDetector : IGrammarDetector
             ["detectionMethod"] = "content-analysis"
         };
 
+        // A shebang line names the interpreter, which settles the language before any pattern is tried.
+        var preamble = context.Preamble;
+        if (preamble.Interpreter is { } interpreter)
+        {
+            metadata["interpreter"] = interpreter;
+            if (InterpreterMappings.TryGetValue(interpreter.TrimEnd('0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '.'), out var mapping))
+            {
+                return GrammarDetectionResult.Success(
+                    mapping.Grammar,
+                    GrammarVersion.TryParse(mapping.Version, out var interpreterVersion) ? interpreterVersion : null,
+                    mapping.Confidence,
+                    DetectorId,
+                    metadata,
+                    mapping.Fallbacks,
+                    new[] { new DetectionEvidence(BuiltInSource, $"Shebang line '#!{preamble.Shebang}' runs {interpreter}, which maps to {mapping.Grammar} with confidence {mapping.Confidence:0.00}") });
+            }
+        }
+
         // Get rules from configuration first, then add default rules
         var rules = new List<ContentDetectionRule>();
         if (context.Configuration?.ContentRules != null)
@@ -277,7 +306,8 @@ public partial class ContentBasedGrammarDetector : IGrammarDetector
         // Sort rules by priority (highest first)
         rules.Sort((a, b) => b.Priority.CompareTo(a.Priority));
 
-        var lines = content.Split('\n', StringSplitOptions.None);
+        // A byte order mark would keep patterns anchored at the start of the first line from matching.
+        var lines = content[(preamble.HasByteOrderMark ? 1 : 0)..].Split('\n', StringSplitOptions.None);
         var bestMatch = await AnalyzeContentAsync(lines, rules);
 
         if (bestMatch.HasValue)
@@ -289,19 +319,44 @@ public partial class ContentBasedGrammarDetector : IGrammarDetector
 
             var version = GrammarVersion.TryParse(match.rule.Mapping.Version, out var parsedVersion) ? parsedVersion : null;
 
+            // Configured rules are named by their index in the configuration file so the entry can be found.
+            var configIndex = context.Configuration?.ContentRules.IndexOf(match.rule) ?? -1;
+            var source = configIndex >= 0
+                ? context.Configuration!.DescribeEntry($"contentRules[{configIndex}]")
+                : BuiltInSource;
+            var line = lines[match.lineNumber - 1].Trim();
+            if (line.Length > MaxEvidenceLineLength)
+            {
+                line = line[..MaxEvidenceLineLength] + "...";
+            }
+
+            var evidence = new[]
+            {
+                new DetectionEvidence(source, $"Rule '{match.rule.Name}' (priority {match.rule.Priority}) pattern /{match.rule.Pattern}/ matched line {match.lineNumber}: {line}"),
+                new DetectionEvidence(source, $"Rule '{match.rule.Name}' maps to {match.rule.Mapping.Grammar}{(match.rule.Mapping.Version != null ? " " + match.rule.Mapping.Version : string.Empty)} with confidence {match.rule.Mapping.Confidence:0.00}")
+            };
+
             return GrammarDetectionResult.Success(
                 match.rule.Mapping.Grammar,
                 version,
                 match.rule.Mapping.Confidence,
                 DetectorId,
                 metadata,
-                match.rule.Mapping.Fallbacks);
+                match.rule.Mapping.Fallbacks,
+                evidence);
         }
 
+        var configuredCount = context.Configuration?.ContentRules.Count ?? 0;
         return GrammarDetectionResult.Failure(
             "No content patterns matched",
             DetectorId,
-            metadata);
+            metadata,
+            new[]
+            {
+                new DetectionEvidence(
+                    configuredCount > 0 ? context.Configuration!.DescribeEntry("contentRules") : BuiltInSource,
+                    $"None of {rules.Count} content rules ({configuredCount} configured) matched within their line limits ({lines.Length} lines of content)")
+            });
     }
 
     /// <summary>
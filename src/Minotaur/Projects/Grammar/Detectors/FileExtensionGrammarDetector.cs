@@ -63,6 +63,8 @@ public class FileExtensionGrammarDetector : IGrammarDetector
 
     private static readonly object _extensionMappingsLock = new();
 
+    private const string BuiltInSource = "built-in extension mappings";
+
     /// <summary>
     /// Gets the detector identifier.
     /// </summary>
@@ -107,7 +109,8 @@ public class FileExtensionGrammarDetector : IGrammarDetector
                 configMapping.Confidence,
                 DetectorId,
                 metadata,
-                configMapping.Fallbacks);
+                configMapping.Fallbacks,
+                new[] { DescribeMatch(context.Configuration!.DescribeEntry($"extensionMappings[\"{extension}\"]"), extension, configMapping) });
         }
 
         // Use default extension mappings
@@ -128,14 +131,28 @@ public class FileExtensionGrammarDetector : IGrammarDetector
                 mapping.Confidence,
                 DetectorId,
                 metadata,
-                mapping.Fallbacks);
+                mapping.Fallbacks,
+                new[] { DescribeMatch(BuiltInSource, extension, mapping) });
         }
 
         await Task.CompletedTask;
         return GrammarDetectionResult.Failure(
             $"No grammar mapping found for extension '{extension}'",
             DetectorId,
-            metadata);
+            metadata,
+            new[]
+            {
+                new DetectionEvidence(
+                    context.Configuration != null ? context.Configuration.DescribeEntry("extensionMappings") : "project configuration",
+                    context.Configuration != null ? $"No mapping for '{extension}'" : "No configuration file found"),
+                new DetectionEvidence(BuiltInSource, $"No mapping for '{extension}'")
+            });
+    }
+
+    private static DetectionEvidence DescribeMatch(string source, string extension, GrammarMapping mapping)
+    {
+        var version = mapping.Version != null ? $" {mapping.Version}" : string.Empty;
+        return new DetectionEvidence(source, $"'{extension}' maps to {mapping.Grammar}{version} with confidence {mapping.Confidence:0.00}");
     }
 
     /// <summary>
@@ -17,6 +17,7 @@
 
 using System.Text.Json;
 using System.Text.Json.Serialization;
+using Minotaur.Diagnostics;
 
 namespace Minotaur.Projects.Grammar;
 
@@ -69,12 +70,30 @@ public class GrammarConfiguration
     [JsonPropertyName("grammarSearchPaths")]
     public List<string> GrammarSearchPaths { get; set; } = new();
 
+    /// <summary>
+    /// Gets or sets the limits on the errors reported per file and per run.
+    /// </summary>
+    [JsonPropertyName("diagnosticLimits")]
+    public DiagnosticLimits DiagnosticLimits { get; set; } = new();
+
+    /// <summary>
+    /// Gets or sets the locale diagnostic messages are rendered in, e.g. "de"; MINOTAUR_LOCALE takes precedence.
+    /// </summary>
+    [JsonPropertyName("locale")]
+    public string? Locale { get; set; }
+
     /// <summary>
     /// Gets or sets additional metadata for the configuration.
     /// </summary>
     [JsonPropertyName("metadata")]
     public Dictionary<string, object> Metadata { get; set; } = new();
 
+    /// <summary>
+    /// Gets or sets the path of the file the configuration was loaded from, or null if it was not loaded from a file.
+    /// </summary>
+    [JsonIgnore]
+    public string? SourcePath { get; set; }
+
     /// <summary>
     /// Gets the grammar mapping for a specific file extension.
     /// </summary>
@@ -120,6 +139,16 @@ public class GrammarConfiguration
         return ProjectTypeOverrides.TryGetValue(projectTypeName, out var mapping) ? mapping : null;
     }
 
+    /// <summary>
+    /// Describes an entry of the configuration as detection evidence, naming the file it came from.
+    /// </summary>
+    /// <param name="entry">The entry, such as <c>extensionMappings[".cs"]</c>.</param>
+    /// <returns>The entry prefixed with the configuration file.</returns>
+    internal string DescribeEntry(string entry)
+    {
+        return $"{SourcePath ?? "project configuration"}: {entry}";
+    }
+
     /// <summary>
     /// Loads grammar configuration from a JSON file.
     /// </summary>
@@ -138,8 +167,9 @@ public class GrammarConfiguration
             AllowTrailingCommas = true
         };
 
-        var config = JsonSerializer.Deserialize<GrammarConfiguration>(json, options);
-        return config ?? new GrammarConfiguration();
+        var config = JsonSerializer.Deserialize<GrammarConfiguration>(json, options) ?? new GrammarConfiguration();
+        config.SourcePath = configFilePath;
+        return config;
     }
 
     /// <summary>
@@ -232,6 +262,13 @@ public class GrammarMapping
     [JsonPropertyName("fallbacks")]
     public List<string> Fallbacks { get; set; } = new();
 
+    /// <summary>
+    /// Gets or sets the grammar features enabled for files mapped to this grammar, passed to the parser as
+    /// <see cref="Minotaur.Parser.ParseOptions.Features"/>.
+    /// </summary>
+    [JsonPropertyName("features")]
+    public List<string> Features { get; set; } = new();
+
     /// <summary>
     /// Gets or sets additional metadata for this mapping.
     /// </summary>
@@ -15,6 +15,8 @@
  * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
  */
 
+using Minotaur.Parser;
+
 namespace Minotaur.Projects.Grammar;
 
 /// <summary>
@@ -22,6 +24,8 @@ namespace Minotaur.Projects.Grammar;
 /// </summary>
 public class GrammarDetectionContext
 {
+    private Preamble? _preamble;
+
     /// <summary>
     /// Gets the absolute path to the file being analyzed.
     /// </summary>
@@ -67,6 +71,12 @@ public class GrammarDetectionContext
     /// </summary>
     public Lazy<string>? FileContent { get; init; }
 
+    /// <summary>
+    /// Gets the byte order mark and shebang line the file content starts with, for detectors that route on the
+    /// interpreter; <see cref="Preamble.Empty"/> without content.
+    /// </summary>
+    public Preamble Preamble => _preamble ??= FileContent != null ? Preamble.Detect(FileContent.Value) : Preamble.Empty;
+
     /// <summary>
     /// Gets additional context metadata.
     /// </summary>
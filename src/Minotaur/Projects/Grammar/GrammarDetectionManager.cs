@@ -15,6 +15,8 @@
  * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
  */
 
+using System.Collections.Concurrent;
+using Minotaur.Parser;
 using Minotaur.Projects.Grammar.Detectors;
 
 namespace Minotaur.Projects.Grammar;
@@ -26,6 +28,7 @@ public class GrammarDetectionManager : IDisposable
 {
     private readonly CompositeGrammarDetector _primaryDetector;
     private readonly Dictionary<string, GrammarConfiguration> _configurationCache;
+    private readonly ConcurrentDictionary<string, (string Key, GrammarRaceResult Race)> _detectionCache = new();
     private readonly string[] _configurationFileNames = { "minotaur.grammar.json", ".minotaur.grammar.json", "grammar.config.json" };
     private bool _disposed;
 
@@ -77,6 +80,122 @@ public class GrammarDetectionManager : IDisposable
         return await _primaryDetector.DetectGrammarAsync(context);
     }
 
+    /// <summary>
+    /// Explains how the grammar for a file is resolved: which configuration applies, what every detector found and
+    /// on what evidence, and why the winning result was chosen.
+    /// </summary>
+    /// <param name="filePath">The absolute path to the file.</param>
+    /// <param name="projectRootPath">The project root path.</param>
+    /// <param name="projectType">The detected project type.</param>
+    /// <returns>A task that represents the asynchronous operation. The task result contains the resolution trace.</returns>
+    public async Task<GrammarResolutionTrace> ExplainAsync(
+        string filePath,
+        string projectRootPath,
+        ProjectType projectType = ProjectType.GenericFolder)
+    {
+        var configuration = await GetConfigurationAsync(projectRootPath);
+        var context = GrammarDetectionContext.Create(filePath, projectRootPath, projectType, configuration);
+
+        return await _primaryDetector.ExplainAsync(context);
+    }
+
+    /// <summary>
+    /// Explains how the grammar for a file with pre-loaded content is resolved.
+    /// </summary>
+    /// <param name="filePath">The absolute path to the file.</param>
+    /// <param name="fileContent">The file content.</param>
+    /// <param name="projectRootPath">The project root path.</param>
+    /// <param name="projectType">The detected project type.</param>
+    /// <returns>A task that represents the asynchronous operation. The task result contains the resolution trace.</returns>
+    public async Task<GrammarResolutionTrace> ExplainAsync(
+        string filePath,
+        string fileContent,
+        string projectRootPath,
+        ProjectType projectType = ProjectType.GenericFolder)
+    {
+        var configuration = await GetConfigurationAsync(projectRootPath);
+        var context = GrammarDetectionContext.CreateWithContent(filePath, projectRootPath, fileContent, projectType, configuration);
+
+        return await _primaryDetector.ExplainAsync(context);
+    }
+
+    /// <summary>
+    /// Resolves the grammar for a file like <see cref="ExplainAsync(string, string, string, ProjectType)"/>, and when
+    /// the detection is inconclusive, parses the start of the file with each candidate grammar instead of guessing.
+    /// Detection is inconclusive when more than one candidate grammar is available and the detected grammar's
+    /// confidence is below <see cref="GrammarRaceOptions.DecisiveConfidence"/>. The candidates are parsed in parallel
+    /// within the budget, the one parsing the prefix with the fewest errors for the most coverage wins, and the decision
+    /// is kept in the detection cache until the prefix or the candidates change.
+    /// </summary>
+    /// <param name="filePath">The absolute path to the file.</param>
+    /// <param name="fileContent">The file content.</param>
+    /// <param name="projectRootPath">The project root path.</param>
+    /// <param name="grammars">Gets a compiled grammar by the name detection gives it, or null if it is not
+    /// available; unavailable grammars are not raced.</param>
+    /// <param name="options">Optional race options.</param>
+    /// <param name="projectType">The detected project type.</param>
+    /// <param name="cancellationToken">A token cancelling the resolution and every parse in the race.</param>
+    /// <returns>A task that represents the asynchronous operation. The task result contains the resolution trace,
+    /// with the candidates' scores in <see cref="GrammarResolutionTrace.Race"/> if they were raced.</returns>
+    public async Task<GrammarResolutionTrace> ResolveOrRaceAsync(
+        string filePath,
+        string fileContent,
+        string projectRootPath,
+        Func<string, CompiledGrammar?> grammars,
+        GrammarRaceOptions? options = null,
+        ProjectType projectType = ProjectType.GenericFolder,
+        CancellationToken cancellationToken = default)
+    {
+        ArgumentNullException.ThrowIfNull(grammars);
+
+        options ??= new GrammarRaceOptions();
+        var trace = await ExplainAsync(filePath, fileContent, projectRootPath, projectType);
+        var candidates = GrammarRace.GetCandidates(trace)
+            .Select(name => (Name: name, Grammar: grammars(name)))
+            .Where(c => c.Grammar != null)
+            .Take(options.MaxCandidates)
+            .ToList();
+        if (candidates.Count < 2 || trace.Result.IsSuccessful && trace.Result.Confidence >= options.DecisiveConfidence)
+        {
+            return trace;
+        }
+
+        var prefix = GrammarRace.GetPrefix(fileContent, options.PrefixLength);
+        var key = GrammarRace.GetCacheKey(prefix, candidates.Select(c => c.Name));
+        var race = _detectionCache.TryGetValue(filePath, out var cached) && cached.Key == key
+            ? cached.Race with { FromCache = true }
+            : await GrammarRace.RunAsync(prefix, candidates, options.Budget, cancellationToken);
+        if (race.Winner == null)
+        {
+            return new GrammarResolutionTrace
+            {
+                FilePath = trace.FilePath,
+                ConfigurationPath = trace.ConfigurationPath,
+                MinimumConfidence = trace.MinimumConfidence,
+                RequireConsensus = trace.RequireConsensus,
+                Steps = trace.Steps,
+                WinningDetectorId = trace.WinningDetectorId,
+                Reason = $"{trace.Reason}; no candidate finished parsing within the budget of {options.Budget.TotalMilliseconds:0} ms",
+                Result = trace.Result,
+                Race = race
+            };
+        }
+
+        _detectionCache[filePath] = (key, race);
+        return new GrammarResolutionTrace
+        {
+            FilePath = trace.FilePath,
+            ConfigurationPath = trace.ConfigurationPath,
+            MinimumConfidence = trace.MinimumConfidence,
+            RequireConsensus = trace.RequireConsensus,
+            Steps = trace.Steps,
+            WinningDetectorId = GrammarRace.DetectorId,
+            Reason = GrammarRace.Describe(race),
+            Result = GrammarRace.CreateResult(trace, race),
+            Race = race
+        };
+    }
+
     /// <summary>
     /// Detects grammars for multiple files in a project.
     /// </summary>
@@ -142,6 +261,15 @@ public class GrammarDetectionManager : IDisposable
         _configurationCache.Clear();
     }
 
+    /// <summary>
+    /// Clears the detection cache, so the next <see cref="ResolveOrRaceAsync"/> for each file parses its candidates
+    /// again.
+    /// </summary>
+    public void ClearDetectionCache()
+    {
+        _detectionCache.Clear();
+    }
+
     /// <summary>
     /// Adds a custom detector to the detection pipeline.
     /// </summary>
@@ -198,6 +326,7 @@ public class GrammarDetectionManager : IDisposable
         return new Dictionary<string, object>
         {
             ["configurationsCached"] = _configurationCache.Count,
+            ["decisionsCached"] = _detectionCache.Count,
             ["detectorsRegistered"] = _primaryDetector.GetDetectors().Count,
             ["detectorTypes"] = _primaryDetector.GetDetectors().Select(d => d.GetType().Name).ToArray()
         };
@@ -236,6 +365,7 @@ public class GrammarDetectionManager : IDisposable
         if (!_disposed)
         {
             _configurationCache.Clear();
+            _detectionCache.Clear();
             _disposed = true;
         }
     }
@@ -15,6 +15,8 @@
  * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
  */
 
+using Minotaur.Parser;
+
 namespace Minotaur.Projects.Grammar;
 
 /// <summary>
@@ -63,6 +65,19 @@ public class GrammarDetectionResult
     /// </summary>
     public string? FailureReason { get; init; }
 
+    /// <summary>
+    /// Gets a value indicating whether no grammar matched the file, so it should be read with the
+    /// <see cref="UniversalFallbackAnalyzer"/>. Such a result is not successful and names the analyzer as its
+    /// grammar; it is never returned when any grammar matched.
+    /// </summary>
+    public bool IsFallback { get; init; }
+
+    /// <summary>
+    /// Gets the evidence the detector based its verdict on, such as the configuration entry or content line that
+    /// matched, in the order it was considered.
+    /// </summary>
+    public IReadOnlyList<DetectionEvidence> Evidence { get; init; } = Array.Empty<DetectionEvidence>();
+
     /// <summary>
     /// Creates a successful grammar detection result.
     /// </summary>
@@ -72,6 +87,7 @@ public class GrammarDetectionResult
     /// <param name="detectorId">The ID of the detector that produced this result.</param>
     /// <param name="metadata">Additional metadata about the detection.</param>
     /// <param name="fallbackGrammars">Optional fallback grammar options.</param>
+    /// <param name="evidence">The evidence the detection was based on.</param>
     /// <returns>A successful detection result.</returns>
     public static GrammarDetectionResult Success(
         string grammarName,
@@ -79,7 +95,8 @@ public class GrammarDetectionResult
         double confidence = 1.0,
         string detectorId = "",
         IReadOnlyDictionary<string, object>? metadata = null,
-        IReadOnlyList<string>? fallbackGrammars = null)
+        IReadOnlyList<string>? fallbackGrammars = null,
+        IReadOnlyList<DetectionEvidence>? evidence = null)
     {
         return new GrammarDetectionResult
         {
@@ -89,7 +106,8 @@ public class GrammarDetectionResult
             Confidence = Math.Clamp(confidence, 0.0, 1.0),
             DetectorId = detectorId,
             Metadata = metadata ?? new Dictionary<string, object>(),
-            FallbackGrammars = fallbackGrammars ?? Array.Empty<string>()
+            FallbackGrammars = fallbackGrammars ?? Array.Empty<string>(),
+            Evidence = evidence ?? Array.Empty<DetectionEvidence>()
         };
     }
 
@@ -99,8 +117,33 @@ public class GrammarDetectionResult
     /// <param name="reason">The reason for the detection failure.</param>
     /// <param name="detectorId">The ID of the detector that produced this result.</param>
     /// <param name="metadata">Additional metadata about the detection attempt.</param>
+    /// <param name="evidence">The evidence that was considered without finding a grammar.</param>
     /// <returns>A failed detection result.</returns>
     public static GrammarDetectionResult Failure(
+        string reason,
+        string detectorId = "",
+        IReadOnlyDictionary<string, object>? metadata = null,
+        IReadOnlyList<DetectionEvidence>? evidence = null)
+    {
+        return new GrammarDetectionResult
+        {
+            IsSuccessful = false,
+            FailureReason = reason,
+            DetectorId = detectorId,
+            Confidence = 0.0,
+            Metadata = metadata ?? new Dictionary<string, object>(),
+            Evidence = evidence ?? Array.Empty<DetectionEvidence>()
+        };
+    }
+
+    /// <summary>
+    /// Creates the result of a resolution that found no grammar, pointing to the universal fallback analyzer.
+    /// </summary>
+    /// <param name="reason">Why no grammar was found.</param>
+    /// <param name="detectorId">The ID of the detector that produced this result.</param>
+    /// <param name="metadata">Additional metadata about the detection attempt.</param>
+    /// <returns>A fallback detection result.</returns>
+    public static GrammarDetectionResult Fallback(
         string reason,
         string detectorId = "",
         IReadOnlyDictionary<string, object>? metadata = null)
@@ -108,10 +151,19 @@ public class GrammarDetectionResult
         return new GrammarDetectionResult
         {
             IsSuccessful = false,
+            IsFallback = true,
+            GrammarName = UniversalFallbackAnalyzer.Name,
             FailureReason = reason,
             DetectorId = detectorId,
             Confidence = 0.0,
             Metadata = metadata ?? new Dictionary<string, object>()
         };
     }
-}
\ No newline at end of file
+}
+
+/// <summary>
+/// A piece of evidence a detector considered.
+/// </summary>
+/// <param name="Source">Where the evidence came from, such as a configuration file entry or the built-in rules.</param>
+/// <param name="Detail">What the evidence showed.</param>
+public sealed record DetectionEvidence(string Source, string Detail);
\ No newline at end of file
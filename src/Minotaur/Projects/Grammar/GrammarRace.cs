@@ -0,0 +1,301 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using System.Globalization;
+using System.Security.Cryptography;
+using System.Text;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Projects.Grammar;
+
+/// <summary>
+/// Options for resolving an ambiguous detection by parsing the file with each candidate grammar; see
+/// <see cref="GrammarDetectionManager.ResolveOrRaceAsync"/>.
+/// </summary>
+public class GrammarRaceOptions
+{
+    /// <summary>
+    /// Gets or sets how many characters of the file are parsed, 64 KiB by default. The prefix is cut back to the
+    /// last line break before the limit so no candidate fails on a line cut in half.
+    /// </summary>
+    public int PrefixLength { get; set; } = 64 * 1024;
+
+    /// <summary>
+    /// Gets or sets how long the candidates may parse in total. Candidates still parsing when it runs out are
+    /// cancelled and cannot win.
+    /// </summary>
+    public TimeSpan Budget { get; set; } = TimeSpan.FromSeconds(2);
+
+    /// <summary>
+    /// Gets or sets the most candidates parsed, taken in the order detection ranked them.
+    /// </summary>
+    public int MaxCandidates { get; set; } = 3;
+
+    /// <summary>
+    /// Gets or sets the confidence at which a detection result is taken as it is, without parsing.
+    /// </summary>
+    public double DecisiveConfidence { get; set; } = 0.9;
+}
+
+/// <summary>
+/// How a candidate's parse in a grammar race ended.
+/// </summary>
+public enum RaceOutcome
+{
+    /// <summary>
+    /// The candidate parsed the prefix and was scored.
+    /// </summary>
+    Completed,
+
+    /// <summary>
+    /// The candidate was stopped because a candidate ranked before it had already parsed the prefix cleanly.
+    /// </summary>
+    Cancelled,
+
+    /// <summary>
+    /// The candidate was still parsing when the budget ran out.
+    /// </summary>
+    TimedOut,
+
+    /// <summary>
+    /// The grammar is not available or the parser threw an exception.
+    /// </summary>
+    Failed
+}
+
+/// <summary>
+/// The score of one candidate grammar in a race. The error figures and coverage are meaningful only for
+/// <see cref="RaceOutcome.Completed"/> candidates; the others score zero.
+/// </summary>
+/// <param name="GrammarName">The candidate grammar.</param>
+/// <param name="Outcome">How the candidate's parse ended.</param>
+/// <param name="ErrorCount">The number of errors the parse reported.</param>
+/// <param name="ErrorDensity">The errors per 1,024 characters of the prefix.</param>
+/// <param name="Coverage">The fraction of the prefix parsed before the first error, between 0 and 1.</param>
+/// <param name="Score">The coverage divided by one plus the error density; the highest score wins.</param>
+/// <param name="Elapsed">How long the candidate parsed.</param>
+public sealed record RaceScore(
+    string GrammarName,
+    RaceOutcome Outcome,
+    int ErrorCount,
+    double ErrorDensity,
+    double Coverage,
+    double Score,
+    TimeSpan Elapsed)
+{
+    /// <summary>
+    /// Gets a value indicating whether the candidate parsed the whole prefix without errors.
+    /// </summary>
+    public bool IsClean => Outcome == RaceOutcome.Completed && ErrorCount == 0 && Coverage >= 1.0;
+
+    /// <summary>
+    /// Describes the score in a line, e.g. <c>2 errors (3.91 per KiB), coverage 41%, score 0.08</c>.
+    /// </summary>
+    /// <returns>The description.</returns>
+    public string Describe()
+    {
+        var elapsed = $"{Elapsed.TotalMilliseconds.ToString("0", CultureInfo.InvariantCulture)} ms";
+        return Outcome switch
+        {
+            RaceOutcome.Completed =>
+                $"{ErrorCount} error{(ErrorCount == 1 ? string.Empty : "s")} ({ErrorDensity.ToString("0.00", CultureInfo.InvariantCulture)} per KiB), " +
+                $"coverage {Coverage.ToString("0%", CultureInfo.InvariantCulture)}, score {DetectorVerdict.Format(Score)}, {elapsed}",
+            RaceOutcome.Cancelled => $"cancelled after {elapsed}, a higher-ranked candidate parsed cleanly",
+            RaceOutcome.TimedOut => $"timed out after {elapsed}",
+            _ => "failed"
+        };
+    }
+}
+
+/// <summary>
+/// The outcome of parsing a file's prefix with each candidate grammar.
+/// </summary>
+/// <param name="PrefixLength">The number of characters parsed.</param>
+/// <param name="Scores">The candidates' scores, in the order detection ranked them.</param>
+/// <param name="Winner">The winning grammar, or null if no candidate completed.</param>
+public sealed record GrammarRaceResult(int PrefixLength, IReadOnlyList<RaceScore> Scores, string? Winner)
+{
+    /// <summary>
+    /// Gets a value indicating whether the decision was taken from the detection cache instead of parsing again.
+    /// </summary>
+    public bool FromCache { get; init; }
+}
+
+/// <summary>
+/// Parses a file's prefix with each candidate grammar in parallel and picks the one that parses it best. The
+/// highest score wins, ties going to the candidate detection ranked first; since a later candidate cannot beat
+/// an earlier one that parsed cleanly, it is cancelled as soon as that happens.
+/// </summary>
+internal static class GrammarRace
+{
+    public const string DetectorId = "speculative-parse";
+
+    /// <summary>
+    /// Gets the grammars worth racing for a detection: the detected grammar, its fallbacks and every other grammar
+    /// a detector found, in that order.
+    /// </summary>
+    public static IReadOnlyList<string> GetCandidates(GrammarResolutionTrace trace)
+    {
+        var names = new List<string?> { trace.Result.GrammarName };
+        names.AddRange(trace.Result.FallbackGrammars);
+        names.AddRange(trace.Steps
+            .Where(s => s.Outcome is DetectorOutcome.Matched or DetectorOutcome.BelowThreshold)
+            .OrderByDescending(s => s.Confidence)
+            .Select(s => s.GrammarName));
+        return names.OfType<string>().Distinct(StringComparer.OrdinalIgnoreCase).ToList();
+    }
+
+    /// <summary>
+    /// Cuts the text to the prefix that is raced.
+    /// </summary>
+    public static string GetPrefix(string text, int length)
+    {
+        if (text.Length <= length)
+        {
+            return text;
+        }
+
+        var lineEnd = text.LastIndexOf('\n', Math.Max(0, length - 1));
+        return lineEnd > 0 ? text[..(lineEnd + 1)] : text[..length];
+    }
+
+    /// <summary>
+    /// Gets the key a race decision is cached under, which changes when the prefix or the candidates do.
+    /// </summary>
+    public static string GetCacheKey(string prefix, IEnumerable<string> candidates)
+    {
+        var key = string.Join("\n", candidates) + "\n\n" + prefix;
+        return Convert.ToHexString(SHA256.HashData(Encoding.UTF8.GetBytes(key))).ToLowerInvariant();
+    }
+
+    public static async Task<GrammarRaceResult> RunAsync(
+        string prefix,
+        IReadOnlyList<(string Name, CompiledGrammar? Grammar)> candidates,
+        TimeSpan budget,
+        CancellationToken cancellationToken)
+    {
+        using var deadline = CancellationTokenSource.CreateLinkedTokenSource(cancellationToken);
+        deadline.CancelAfter(budget);
+        var sources = candidates.Select(_ => CancellationTokenSource.CreateLinkedTokenSource(deadline.Token)).ToList();
+
+        RaceScore Parse(int index)
+        {
+            var (name, grammar) = candidates[index];
+            var clock = Stopwatch.StartNew();
+            if (grammar == null)
+            {
+                return new RaceScore(name, RaceOutcome.Failed, 0, 0.0, 0.0, 0.0, TimeSpan.Zero);
+            }
+
+            try
+            {
+                var result = new GeneralizedParser(grammar).Parse(prefix, new ParseOptions { CancellationToken = sources[index].Token });
+                var score = Score(name, result, prefix.Length, clock.Elapsed);
+                if (score.IsClean)
+                {
+                    foreach (var later in sources.Skip(index + 1))
+                    {
+                        later.Cancel();
+                    }
+                }
+
+                return score;
+            }
+            catch (OperationCanceledException)
+            {
+                var outcome = deadline.IsCancellationRequested ? RaceOutcome.TimedOut : RaceOutcome.Cancelled;
+                return new RaceScore(name, outcome, 0, 0.0, 0.0, 0.0, clock.Elapsed);
+            }
+            catch (Exception ex) when (ex is ArgumentException or InvalidOperationException)
+            {
+                return new RaceScore(name, RaceOutcome.Failed, 0, 0.0, 0.0, 0.0, clock.Elapsed);
+            }
+        }
+
+        try
+        {
+            var scores = await Task.WhenAll(candidates.Select((_, i) => Task.Run(() => Parse(i), CancellationToken.None)));
+            cancellationToken.ThrowIfCancellationRequested();
+
+            var winner = scores
+                .Select((score, index) => (score, index))
+                .Where(s => s.score.Outcome == RaceOutcome.Completed)
+                .OrderByDescending(s => s.score.Score)
+                .ThenBy(s => s.score.ErrorCount)
+                .ThenBy(s => s.index)
+                .Select(s => s.score.GrammarName)
+                .FirstOrDefault();
+            return new GrammarRaceResult(prefix.Length, scores, winner);
+        }
+        finally
+        {
+            foreach (var source in sources)
+            {
+                source.Dispose();
+            }
+        }
+    }
+
+    /// <summary>
+    /// Builds the resolution for a race's winner, keeping the version a detector found for it, if any.
+    /// </summary>
+    public static GrammarDetectionResult CreateResult(GrammarResolutionTrace trace, GrammarRaceResult race)
+    {
+        var winner = race.Scores.First(s => s.GrammarName == race.Winner);
+        var version = trace.Steps.FirstOrDefault(s => string.Equals(s.GrammarName, winner.GrammarName, StringComparison.OrdinalIgnoreCase))?.Version;
+        var metadata = new Dictionary<string, object>
+        {
+            ["detectionMethod"] = DetectorId,
+            ["prefixLength"] = race.PrefixLength,
+            ["fromCache"] = race.FromCache
+        };
+
+        return GrammarDetectionResult.Success(
+            winner.GrammarName,
+            GrammarVersion.TryParse(version, out var parsed) ? parsed : null,
+            winner.Score,
+            DetectorId,
+            metadata,
+            race.Scores.Select(s => s.GrammarName).Where(n => n != winner.GrammarName).ToList(),
+            race.Scores.Select(s => new DetectionEvidence($"parse with {s.GrammarName}", s.Describe())).ToList());
+    }
+
+    /// <summary>
+    /// Explains why the race's winner was chosen.
+    /// </summary>
+    public static string Describe(GrammarRaceResult race)
+    {
+        var cached = race.FromCache ? " (decision cached for this content)" : string.Empty;
+        var winner = race.Scores.First(s => s.GrammarName == race.Winner);
+        var rivals = race.Scores.Where(s => s != winner).ToList();
+        return $"detection was inconclusive, and parsing the first {race.PrefixLength} characters with each candidate favored " +
+            $"{winner.GrammarName} ({winner.Describe()})" +
+            (rivals.Count > 0 ? $" over {string.Join(", ", rivals.Select(r => $"{r.GrammarName} ({r.Describe()})"))}" : string.Empty) + cached;
+    }
+
+    private static RaceScore Score(string name, ParseResult result, int length, TimeSpan elapsed)
+    {
+        var errors = result.Diagnostics.Where(d => d.Severity == DiagnosticSeverity.Error).ToList();
+        var firstError = errors.Select(d => d.Location?.Offset ?? 0).DefaultIfEmpty(length).Min();
+        var covered = errors.Count > 0 ? firstError : result.ParsedLength;
+        var coverage = length == 0 ? 1.0 : Math.Clamp((double)covered / length, 0.0, 1.0);
+        var density = errors.Count * 1024.0 / Math.Max(1, length);
+        return new RaceScore(name, RaceOutcome.Completed, errors.Count, density, coverage, coverage / (1.0 + density), elapsed);
+    }
+}
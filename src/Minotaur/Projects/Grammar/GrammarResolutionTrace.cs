@@ -0,0 +1,285 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+
+namespace Minotaur.Projects.Grammar;
+
+/// <summary>
+/// What a detector concluded while a grammar was being resolved.
+/// </summary>
+public enum DetectorOutcome
+{
+    /// <summary>
+    /// The detector cannot handle the file and was not run.
+    /// </summary>
+    Skipped,
+
+    /// <summary>
+    /// The detector ran but found no grammar.
+    /// </summary>
+    NoMatch,
+
+    /// <summary>
+    /// The detector found a grammar, but with less than the minimum confidence.
+    /// </summary>
+    BelowThreshold,
+
+    /// <summary>
+    /// The detector found a grammar that was a candidate for the result.
+    /// </summary>
+    Matched,
+
+    /// <summary>
+    /// The detector threw an exception.
+    /// </summary>
+    Error
+}
+
+/// <summary>
+/// The verdict of one detector consulted while resolving a grammar.
+/// </summary>
+/// <param name="DetectorId">The detector identifier.</param>
+/// <param name="Priority">The detector priority.</param>
+/// <param name="Outcome">What the detector concluded.</param>
+/// <param name="GrammarName">The grammar the detector found, if any.</param>
+/// <param name="Version">The version the detector found, as written in its mapping.</param>
+/// <param name="Confidence">The confidence of the detector's result.</param>
+/// <param name="Reason">A short explanation of the outcome.</param>
+/// <param name="Evidence">The evidence the detector based its verdict on.</param>
+public sealed record DetectorVerdict(
+    string DetectorId,
+    int Priority,
+    DetectorOutcome Outcome,
+    string? GrammarName,
+    string? Version,
+    double Confidence,
+    string Reason,
+    IReadOnlyList<DetectionEvidence> Evidence)
+{
+    /// <summary>
+    /// Creates the verdict of a detector that cannot handle the file.
+    /// </summary>
+    /// <param name="detector">The detector.</param>
+    /// <returns>The verdict.</returns>
+    public static DetectorVerdict Skipped(IGrammarDetector detector)
+    {
+        return new DetectorVerdict(
+            detector.DetectorId, detector.Priority, DetectorOutcome.Skipped, null, null, 0.0,
+            "Not applicable to this file", Array.Empty<DetectionEvidence>());
+    }
+
+    /// <summary>
+    /// Creates the verdict of a detector from its result.
+    /// </summary>
+    /// <param name="detector">The detector.</param>
+    /// <param name="result">The detector's result.</param>
+    /// <param name="minimumConfidence">The confidence a result needs to be a candidate.</param>
+    /// <returns>The verdict.</returns>
+    public static DetectorVerdict FromResult(IGrammarDetector detector, GrammarDetectionResult result, double minimumConfidence)
+    {
+        var version = result.Version?.OriginalString;
+        if (!result.IsSuccessful)
+        {
+            return new DetectorVerdict(
+                detector.DetectorId, detector.Priority, DetectorOutcome.NoMatch, null, null, 0.0,
+                result.FailureReason ?? "No grammar found", result.Evidence);
+        }
+
+        return result.Confidence >= minimumConfidence
+            ? new DetectorVerdict(
+                detector.DetectorId, detector.Priority, DetectorOutcome.Matched, result.GrammarName, version, result.Confidence,
+                $"Found {Describe(result.GrammarName, version)} with confidence {Format(result.Confidence)}", result.Evidence)
+            : new DetectorVerdict(
+                detector.DetectorId, detector.Priority, DetectorOutcome.BelowThreshold, result.GrammarName, version, result.Confidence,
+                $"Found {Describe(result.GrammarName, version)} with confidence {Format(result.Confidence)}, below the minimum of {Format(minimumConfidence)}",
+                result.Evidence);
+    }
+
+    internal static string Describe(string? grammarName, string? version)
+    {
+        return string.IsNullOrEmpty(version) ? grammarName ?? string.Empty : $"{grammarName} {version}";
+    }
+
+    internal static string Format(double confidence)
+    {
+        return confidence.ToString("0.00", CultureInfo.InvariantCulture);
+    }
+}
+
+/// <summary>
+/// An ordered record of how a file's grammar was resolved: every detector consulted with its evidence and verdict,
+/// which one won, and why. Produced by <see cref="Detectors.CompositeGrammarDetector.ExplainAsync"/>.
+/// </summary>
+public sealed class GrammarResolutionTrace
+{
+    /// <summary>
+    /// Gets the file the grammar was resolved for.
+    /// </summary>
+    public string FilePath { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Gets the configuration file that was in effect, or null if there was none.
+    /// </summary>
+    public string? ConfigurationPath { get; init; }
+
+    /// <summary>
+    /// Gets the confidence a detector result needed to be a candidate.
+    /// </summary>
+    public double MinimumConfidence { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether detectors had to agree on the grammar.
+    /// </summary>
+    public bool RequireConsensus { get; init; }
+
+    /// <summary>
+    /// Gets the verdicts of the detectors in the order they were consulted.
+    /// </summary>
+    public IReadOnlyList<DetectorVerdict> Steps { get; init; } = Array.Empty<DetectorVerdict>();
+
+    /// <summary>
+    /// Gets the detector whose result was chosen, or null if no grammar was resolved.
+    /// </summary>
+    public string? WinningDetectorId { get; init; }
+
+    /// <summary>
+    /// Gets why the winning detector's result was chosen, or why no grammar was resolved.
+    /// </summary>
+    public string Reason { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Gets the resolution result.
+    /// </summary>
+    public GrammarDetectionResult Result { get; init; } = GrammarDetectionResult.Failure("Not resolved");
+
+    /// <summary>
+    /// Gets the scores of the candidate grammars when an inconclusive detection was resolved by parsing the file with
+    /// each of them, or null if it was not; see <see cref="GrammarDetectionManager.ResolveOrRaceAsync"/>.
+    /// </summary>
+    public GrammarRaceResult? Race { get; init; }
+
+    /// <summary>
+    /// Gets the verdict of the winning detector, or null if no grammar was resolved.
+    /// </summary>
+    public DetectorVerdict? Winner => Steps.FirstOrDefault(s => s.DetectorId == WinningDetectorId && s.Outcome == DetectorOutcome.Matched);
+
+    /// <summary>
+    /// Writes the trace as indented JSON.
+    /// </summary>
+    /// <returns>The JSON text.</returns>
+    public string ToJson()
+    {
+        var root = new JsonObject
+        {
+            ["filePath"] = FilePath,
+            ["configurationPath"] = ConfigurationPath,
+            ["resolved"] = Result.IsSuccessful,
+            ["fallback"] = Result.IsFallback,
+            ["grammar"] = Result.GrammarName,
+            ["version"] = Result.Version?.OriginalString,
+            ["confidence"] = Result.Confidence,
+            ["winner"] = WinningDetectorId,
+            ["reason"] = Reason,
+            ["minimumConfidence"] = MinimumConfidence,
+            ["requireConsensus"] = RequireConsensus,
+            ["steps"] = new JsonArray(Steps.Select(step => (JsonNode)new JsonObject
+            {
+                ["detector"] = step.DetectorId,
+                ["priority"] = step.Priority,
+                ["outcome"] = step.Outcome.ToString(),
+                ["grammar"] = step.GrammarName,
+                ["version"] = step.Version,
+                ["confidence"] = step.Confidence,
+                ["reason"] = step.Reason,
+                ["evidence"] = new JsonArray(step.Evidence.Select(evidence => (JsonNode)new JsonObject
+                {
+                    ["source"] = evidence.Source,
+                    ["detail"] = evidence.Detail
+                }).ToArray())
+            }).ToArray()),
+            ["race"] = Race == null ? null : new JsonObject
+            {
+                ["prefixLength"] = Race.PrefixLength,
+                ["fromCache"] = Race.FromCache,
+                ["winner"] = Race.Winner,
+                ["scores"] = new JsonArray(Race.Scores.Select(score => (JsonNode)new JsonObject
+                {
+                    ["grammar"] = score.GrammarName,
+                    ["outcome"] = score.Outcome.ToString(),
+                    ["errors"] = score.ErrorCount,
+                    ["errorDensity"] = score.ErrorDensity,
+                    ["coverage"] = score.Coverage,
+                    ["score"] = score.Score,
+                    ["elapsedMilliseconds"] = score.Elapsed.TotalMilliseconds
+                }).ToArray())
+            }
+        };
+
+        return root.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+    }
+
+    /// <summary>
+    /// Renders the trace as a readable narrative: the configuration in effect, each detector in the order it was
+    /// consulted with its verdict and evidence, and the conclusion.
+    /// </summary>
+    /// <returns>The narrative text.</returns>
+    public string ToNarrative()
+    {
+        var builder = new StringBuilder();
+        builder.AppendLine($"Grammar resolution for {FilePath}");
+        builder.AppendLine(ConfigurationPath != null ? $"Configuration: {ConfigurationPath}" : "Configuration: none found");
+        builder.AppendLine($"Minimum confidence: {DetectorVerdict.Format(MinimumConfidence)}{(RequireConsensus ? ", consensus required" : string.Empty)}");
+        builder.AppendLine();
+
+        builder.AppendLine("Detectors consulted, highest priority first:");
+        for (var i = 0; i < Steps.Count; i++)
+        {
+            var step = Steps[i];
+            var marker = step.DetectorId == WinningDetectorId && step.Outcome == DetectorOutcome.Matched ? " <- chosen" : string.Empty;
+            builder.AppendLine($"  {i + 1}. {step.DetectorId} (priority {step.Priority}): {step.Outcome}{marker}");
+            builder.AppendLine($"     {step.Reason}");
+            foreach (var evidence in step.Evidence)
+            {
+                builder.AppendLine($"     - {evidence.Source}");
+                builder.AppendLine($"       {evidence.Detail}");
+            }
+        }
+
+        if (Race != null)
+        {
+            builder.AppendLine();
+            builder.AppendLine($"Candidates parsed over the first {Race.PrefixLength} characters{(Race.FromCache ? " (cached)" : string.Empty)}:");
+            for (var i = 0; i < Race.Scores.Count; i++)
+            {
+                var score = Race.Scores[i];
+                var marker = score.GrammarName == Race.Winner ? " <- chosen" : string.Empty;
+                builder.AppendLine($"  {i + 1}. {score.GrammarName}: {score.Outcome}{marker}");
+                builder.AppendLine($"     {score.Describe()}");
+            }
+        }
+
+        builder.AppendLine();
+        builder.AppendLine(Result.IsSuccessful
+            ? $"Resolved to {DetectorVerdict.Describe(Result.GrammarName, Result.Version?.OriginalString)} by {WinningDetectorId}: {Reason}"
+            : $"Not resolved: {Reason}{(Result.IsFallback ? $"; falling back to {Result.GrammarName}" : string.Empty)}");
+        return builder.ToString();
+    }
+}
@@ -0,0 +1,102 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Projects.Grammar;
+
+/// <summary>
+/// A range of versions, written as space-separated comparisons that must all hold, such as
+/// <c>&gt;=1.2.0 &lt;2.0.0</c>. A bare version matches only itself and <c>*</c> matches every version.
+/// </summary>
+public sealed class VersionRange
+{
+    private static readonly string[] Operators = { ">=", "<=", ">", "<", "=" };
+
+    private readonly IReadOnlyList<(string Operator, GrammarVersion Version)> _comparisons;
+
+    private VersionRange(string text, IReadOnlyList<(string Operator, GrammarVersion Version)> comparisons)
+    {
+        Text = text;
+        _comparisons = comparisons;
+    }
+
+    /// <summary>
+    /// Gets the range that contains every version.
+    /// </summary>
+    public static VersionRange Any { get; } = new("*", Array.Empty<(string, GrammarVersion)>());
+
+    /// <summary>
+    /// Gets the text the range was parsed from.
+    /// </summary>
+    public string Text { get; }
+
+    /// <summary>
+    /// Parses a range.
+    /// </summary>
+    /// <param name="text">The range, such as <c>&gt;=1.2.0 &lt;2.0.0</c>.</param>
+    /// <returns>The range.</returns>
+    /// <exception cref="FormatException">A comparison has no valid version.</exception>
+    public static VersionRange Parse(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var trimmed = text.Trim();
+        if (trimmed is "" or "*")
+        {
+            return Any;
+        }
+
+        var comparisons = new List<(string, GrammarVersion)>();
+        foreach (var part in trimmed.Split(' ', StringSplitOptions.RemoveEmptyEntries))
+        {
+            var op = Operators.FirstOrDefault(o => part.StartsWith(o, StringComparison.Ordinal)) ?? "=";
+            var version = part[(part.StartsWith(op, StringComparison.Ordinal) ? op.Length : 0)..];
+            if (!GrammarVersion.TryParse(version, out var parsed))
+            {
+                throw new FormatException($"'{part}' in version range '{trimmed}' does not compare with a version");
+            }
+
+            comparisons.Add((op, parsed!));
+        }
+
+        return new VersionRange(trimmed, comparisons);
+    }
+
+    /// <summary>
+    /// Gets a value indicating whether a version is in the range.
+    /// </summary>
+    /// <param name="version">The version.</param>
+    /// <returns>True if every comparison of the range holds for the version.</returns>
+    public bool Contains(GrammarVersion version)
+    {
+        ArgumentNullException.ThrowIfNull(version);
+
+        return _comparisons.All(c => c.Operator switch
+        {
+            ">=" => version >= c.Version,
+            "<=" => version <= c.Version,
+            ">" => version > c.Version,
+            "<" => version < c.Version,
+            _ => version == c.Version
+        });
+    }
+
+    /// <inheritdoc />
+    public override string ToString()
+    {
+        return Text;
+    }
+}
@@ -0,0 +1,418 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Two alternatives of a rule whose relative order is significant, because an ordered-choice parser could match
+/// either of them at the same position.
+/// </summary>
+/// <param name="First">The earlier alternative.</param>
+/// <param name="Second">The later alternative.</param>
+/// <param name="SharedTerminals">The terminals both alternatives can start with.</param>
+/// <param name="Nullable">Whether one of them can match nothing, and so matches wherever the other could.</param>
+public sealed record AlternativeOverlap(CompiledAlternative First, CompiledAlternative Second, IReadOnlyList<string> SharedTerminals, bool Nullable)
+{
+    /// <summary>
+    /// Gets why the order of the two alternatives is kept.
+    /// </summary>
+    public string Reason => Nullable
+        ? $"'{First.Text}' and '{Second.Text}' overlap because one of them can match nothing"
+        : $"'{First.Text}' and '{Second.Text}' can both start with {string.Join(", ", SharedTerminals)}";
+}
+
+/// <summary>
+/// The proposed order of the alternatives of one rule.
+/// </summary>
+public sealed class RuleReordering
+{
+    internal RuleReordering(CompiledRule rule, IReadOnlyList<CompiledAlternative> order, IReadOnlyList<AlternativeOverlap> overlaps, string? refusal)
+    {
+        Rule = rule;
+        Order = order;
+        Overlaps = overlaps;
+        Refusal = refusal;
+    }
+
+    /// <summary>
+    /// Gets the rule.
+    /// </summary>
+    public CompiledRule Rule { get; }
+
+    /// <summary>
+    /// Gets the alternatives in the proposed order; their <see cref="CompiledAlternative.Index"/> is their
+    /// current position.
+    /// </summary>
+    public IReadOnlyList<CompiledAlternative> Order { get; }
+
+    /// <summary>
+    /// Gets the pairs of alternatives kept in their current relative order.
+    /// </summary>
+    public IReadOnlyList<AlternativeOverlap> Overlaps { get; }
+
+    /// <summary>
+    /// Gets why the rule is not reordered at all, or null if it may be.
+    /// </summary>
+    public string? Refusal { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the proposed order differs from the current one.
+    /// </summary>
+    public bool IsChanged => Order.Where((alternative, position) => alternative.Index != position).Any();
+}
+
+/// <summary>
+/// A proposed reordering of a grammar's alternatives and its expected effect on the corpus it was computed from.
+/// </summary>
+public sealed class AlternativeOrderPlan
+{
+    internal AlternativeOrderPlan(CompiledGrammar grammar, IReadOnlyList<RuleReordering> rules, string? refusal)
+    {
+        Grammar = grammar;
+        Rules = rules;
+        Refusal = refusal;
+    }
+
+    /// <summary>
+    /// Gets the grammar.
+    /// </summary>
+    public CompiledGrammar Grammar { get; }
+
+    /// <summary>
+    /// Gets the rules with more than one alternative, in grammar order.
+    /// </summary>
+    public IReadOnlyList<RuleReordering> Rules { get; }
+
+    /// <summary>
+    /// Gets why the grammar is not reordered at all, or null if it may be.
+    /// </summary>
+    public string? Refusal { get; }
+
+    /// <summary>
+    /// Gets the rules whose order changes.
+    /// </summary>
+    public IEnumerable<RuleReordering> Changes => Rules.Where(r => r.IsChanged);
+
+    /// <summary>
+    /// Gets the alternatives an ordered-choice parser attempts for the corpus with the current order, once
+    /// <see cref="AlternativeOrderOptimizer.Optimize"/> replayed it; 0 otherwise.
+    /// </summary>
+    public long AttemptsBefore { get; internal set; }
+
+    /// <summary>
+    /// Gets the alternatives an ordered-choice parser attempts for the corpus with the proposed order, once
+    /// <see cref="AlternativeOrderOptimizer.Optimize"/> replayed it; 0 otherwise.
+    /// </summary>
+    public long AttemptsAfter { get; internal set; }
+
+    /// <summary>
+    /// Gets the fraction of attempts the proposed order saves.
+    /// </summary>
+    public double Improvement => AttemptsBefore == 0 ? 0 : 1 - (double)AttemptsAfter / AttemptsBefore;
+
+    /// <summary>
+    /// Rewrites grammar file content with the proposed order, moving the text of each alternative as written.
+    /// </summary>
+    /// <param name="content">The content of the grammar file the plan's grammar was read from.</param>
+    /// <returns>The rewritten content.</returns>
+    /// <exception cref="ArgumentException">The content does not define a changed rule's alternatives.</exception>
+    public string Apply(string content)
+    {
+        ArgumentNullException.ThrowIfNull(content);
+
+        var ranges = GrammarFileReader.FindAlternativeRanges(content);
+        var edits = new List<(TextRange Range, string Text)>();
+        foreach (var change in Changes)
+        {
+            if (!ranges.TryGetValue(change.Rule.Name, out var written) || written.Count != change.Order.Count)
+            {
+                throw new ArgumentException($"The content does not define the {change.Order.Count} alternatives of <{change.Rule.Name}>", nameof(content));
+            }
+
+            for (var position = 0; position < change.Order.Count; position++)
+            {
+                var source = written[change.Order[position].Index];
+                edits.Add((written[position], content.Substring(source.Start, source.Length)));
+            }
+        }
+
+        var rewritten = content;
+        foreach (var (range, text) in edits.OrderByDescending(e => e.Range.Start))
+        {
+            rewritten = rewritten[..range.Start] + text + rewritten[range.End..];
+        }
+
+        return rewritten;
+    }
+}
+
+/// <summary>
+/// Proposes an order of each rule's alternatives that tries the alternatives a corpus uses most first, for parsers
+/// that try alternatives in order. Alternatives whose order is significant keep it: nothing is reordered in grammars
+/// imported from PEG, whose choice is ordered by definition; rules with actions, feature guards or deprecations, whose
+/// annotations refer to alternatives by position, are left alone; and two alternatives keep their relative order
+/// when their FIRST sets intersect or one of them is nullable, since either could then match where the other does.
+/// </summary>
+public sealed class AlternativeOrderOptimizer
+{
+    /// <summary>
+    /// The "FormatType" metadata value of grammars imported from PEG.
+    /// </summary>
+    public const string PegFormat = "PEG";
+
+    private readonly HashSet<string>[] _first;
+
+    /// <summary>
+    /// Initializes a new instance of the AlternativeOrderOptimizer class.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    public AlternativeOrderOptimizer(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        Grammar = grammar;
+        _first = ComputeFirst(grammar);
+    }
+
+    /// <summary>
+    /// Gets the grammar.
+    /// </summary>
+    public CompiledGrammar Grammar { get; }
+
+    /// <summary>
+    /// Gets why the grammar's alternatives may not be reordered at all, or null if they may.
+    /// </summary>
+    public string? Refusal => string.Equals(Grammar.Source.Metadata.GetValueOrDefault("FormatType"), PegFormat, StringComparison.OrdinalIgnoreCase)
+        ? $"Grammar '{Grammar.Name}' was imported from PEG, where the order of alternatives decides what they match"
+        : null;
+
+    /// <summary>
+    /// Finds the pairs of a rule's alternatives whose relative order is significant.
+    /// </summary>
+    /// <param name="rule">The rule.</param>
+    /// <returns>The overlapping pairs, the earlier alternative first.</returns>
+    public IReadOnlyList<AlternativeOverlap> FindOverlaps(CompiledRule rule)
+    {
+        ArgumentNullException.ThrowIfNull(rule);
+
+        var firsts = new List<(HashSet<string> Set, bool Nullable)>();
+        foreach (var alternative in rule.Alternatives)
+        {
+            var set = First(alternative, out var nullable);
+            firsts.Add((set, nullable));
+        }
+
+        var overlaps = new List<AlternativeOverlap>();
+        for (var i = 0; i < firsts.Count; i++)
+        {
+            for (var j = i + 1; j < firsts.Count; j++)
+            {
+                var shared = firsts[i].Set.Intersect(firsts[j].Set).OrderBy(k => k, StringComparer.Ordinal).ToList();
+                var nullable = firsts[i].Nullable || firsts[j].Nullable;
+                if (shared.Count > 0 || nullable)
+                {
+                    overlaps.Add(new AlternativeOverlap(rule.Alternatives[i], rule.Alternatives[j], shared, nullable));
+                }
+            }
+        }
+
+        return overlaps;
+    }
+
+    /// <summary>
+    /// Proposes an order from how often a corpus used each alternative: within each rule, the most used alternative
+    /// that no kept overlap requires to wait for another comes next, ties keeping the current order.
+    /// </summary>
+    /// <param name="coverage">The alternative counts of a corpus run with the grammar.</param>
+    /// <returns>The plan.</returns>
+    public AlternativeOrderPlan Propose(GrammarCoverage coverage)
+    {
+        ArgumentNullException.ThrowIfNull(coverage);
+
+        var refusal = Refusal;
+        var rules = new List<RuleReordering>();
+        foreach (var rule in Grammar.Rules.Where(r => r.Alternatives.Count > 1))
+        {
+            var positional = rule.Alternatives.FirstOrDefault(a => a.Action != null || a.Feature != null || a.Deprecation != null);
+            if (refusal != null || positional != null)
+            {
+                rules.Add(new RuleReordering(rule, rule.Alternatives, Array.Empty<AlternativeOverlap>(),
+                    refusal ?? $"'{positional!.Text}' has an action, feature guard or deprecation tied to its position"));
+                continue;
+            }
+
+            var overlaps = FindOverlaps(rule);
+            var pending = rule.Alternatives.ToList();
+            var order = new List<CompiledAlternative>();
+            while (pending.Count > 0)
+            {
+                var next = pending
+                    .Where(a => !overlaps.Any(o => o.Second == a && pending.Contains(o.First)))
+                    .OrderByDescending(coverage.GetCount)
+                    .ThenBy(a => a.Index)
+                    .First();
+                pending.Remove(next);
+                order.Add(next);
+            }
+
+            rules.Add(new RuleReordering(rule, order, overlaps, null));
+        }
+
+        return new AlternativeOrderPlan(Grammar, rules, refusal);
+    }
+
+    /// <summary>
+    /// Proposes an order for a grammar file from its corpus and replays the corpus with both orders. An ordered-choice
+    /// parser tries a rule's alternatives in order until one matches, so every node of a parse tree costs the position
+    /// of its alternative plus one; the plan reports the total over the corpus parsed with the current grammar and
+    /// with the rewritten one.
+    /// </summary>
+    /// <param name="grammarPath">The grammar file.</param>
+    /// <param name="corpusDirectory">The corpus directory.</param>
+    /// <param name="startRule">The start rule or entry point, or null for the grammar's.</param>
+    /// <returns>The plan, with its attempt counts.</returns>
+    public static AlternativeOrderPlan Optimize(string grammarPath, string corpusDirectory, string? startRule = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammarPath);
+        ArgumentNullException.ThrowIfNull(corpusDirectory);
+
+        var content = File.ReadAllText(grammarPath);
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(content), startRule);
+        var inputs = RegressionCorpus.GetCases(corpusDirectory).Select(c => File.ReadAllText(c.InputPath)).ToList();
+
+        var coverage = new GrammarCoverage(grammar);
+        var parser = new GeneralizedParser(grammar);
+        long before = 0;
+        foreach (var input in inputs)
+        {
+            var result = parser.Parse(input);
+            coverage.Record(result);
+            before += CountAttempts(result);
+        }
+
+        var plan = new AlternativeOrderOptimizer(grammar).Propose(coverage);
+        var reordered = new GeneralizedParser(CompiledGrammar.Compile(new GrammarFileReader().Read(plan.Apply(content)), startRule));
+        plan.AttemptsBefore = before;
+        plan.AttemptsAfter = inputs.Sum(input => CountAttempts(reordered.Parse(input)));
+        return plan;
+    }
+
+    /// <summary>
+    /// Counts the alternatives an ordered-choice parser attempts to build a parse tree: for every node, the
+    /// position of its alternative plus one.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    /// <returns>The number of attempts; 0 if parsing failed.</returns>
+    public static long CountAttempts(ParseResult result)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+
+        long attempts = 0;
+        var pending = new Stack<CognitiveGraphNode>();
+        if (result.Tree != null)
+        {
+            pending.Push(result.Tree);
+        }
+
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node is NonTerminalNode { ProductionIndex: >= 0 } nonTerminal)
+            {
+                attempts += nonTerminal.ProductionIndex + 1;
+            }
+
+            foreach (var child in node.Children)
+            {
+                pending.Push(child);
+            }
+        }
+
+        return attempts;
+    }
+
+    // The terminals each alternative can start with, through nullable rules.
+    private HashSet<string> First(CompiledAlternative alternative, out bool nullable)
+    {
+        var first = new HashSet<string>(StringComparer.Ordinal);
+        for (var i = 0; i < alternative.Symbols.Count; i++)
+        {
+            var ruleIndex = alternative.RuleIndices[i];
+            if (ruleIndex < 0)
+            {
+                first.Add(alternative.Symbols[i].Key);
+                nullable = false;
+                return first;
+            }
+
+            first.UnionWith(_first[ruleIndex]);
+            if (!Grammar.IsNullable(Grammar.Rules[ruleIndex]))
+            {
+                nullable = false;
+                return first;
+            }
+        }
+
+        nullable = true;
+        return first;
+    }
+
+    private static HashSet<string>[] ComputeFirst(CompiledGrammar grammar)
+    {
+        var first = grammar.Rules.Select(_ => new HashSet<string>(StringComparer.Ordinal)).ToArray();
+        bool changed;
+        do
+        {
+            changed = false;
+            foreach (var rule in grammar.Rules)
+            {
+                foreach (var alternative in rule.Alternatives)
+                {
+                    for (var i = 0; i < alternative.Symbols.Count; i++)
+                    {
+                        var ruleIndex = alternative.RuleIndices[i];
+                        if (ruleIndex < 0)
+                        {
+                            changed |= first[rule.Index].Add(alternative.Symbols[i].Key);
+                            break;
+                        }
+
+                        if (ruleIndex != rule.Index)
+                        {
+                            foreach (var key in first[ruleIndex])
+                            {
+                                changed |= first[rule.Index].Add(key);
+                            }
+                        }
+
+                        if (!grammar.IsNullable(grammar.Rules[ruleIndex]))
+                        {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        while (changed);
+
+        return first;
+    }
+}
@@ -0,0 +1,92 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// A reference frontend implemented by an external command. The command is run through the shell with the
+/// source file path appended (or substituted for "{}") and must print a <see cref="FrontendSummary"/> as JSON.
+/// </summary>
+public sealed class CommandFrontend : IDifferentialFrontend
+{
+    private readonly string _command;
+
+    /// <summary>
+    /// Initializes a new instance of the CommandFrontend class.
+    /// </summary>
+    /// <param name="name">The frontend name used in reports.</param>
+    /// <param name="command">The command line.</param>
+    /// <param name="workingDirectory">The directory to run the command in, or null for the current directory.</param>
+    public CommandFrontend(string name, string command, string? workingDirectory = null)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(name);
+        ArgumentException.ThrowIfNullOrEmpty(command);
+
+        Name = name;
+        _command = command;
+        WorkingDirectory = workingDirectory;
+    }
+
+    /// <inheritdoc />
+    public string Name { get; }
+
+    /// <summary>
+    /// Gets the directory the command runs in.
+    /// </summary>
+    public string? WorkingDirectory { get; }
+
+    /// <inheritdoc />
+    public FrontendSummary Summarize(string path, string source)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        var quoted = $"\"{Path.GetFullPath(path)}\"";
+        var commandLine = _command.Contains("{}") ? _command.Replace("{}", quoted) : $"{_command} {quoted}";
+        var startInfo = OperatingSystem.IsWindows()
+            ? new ProcessStartInfo("cmd.exe") { ArgumentList = { "/c", commandLine } }
+            : new ProcessStartInfo("/bin/sh") { ArgumentList = { "-c", commandLine } };
+        startInfo.RedirectStandardOutput = true;
+        startInfo.RedirectStandardError = true;
+        startInfo.UseShellExecute = false;
+        if (WorkingDirectory != null)
+        {
+            startInfo.WorkingDirectory = WorkingDirectory;
+        }
+
+        using var process = Process.Start(startInfo)
+            ?? throw new InvalidOperationException($"Could not start '{commandLine}'");
+        var stdout = process.StandardOutput.ReadToEndAsync();
+        var stderr = process.StandardError.ReadToEndAsync();
+        process.WaitForExit();
+
+        if (process.ExitCode != 0 && string.IsNullOrWhiteSpace(stdout.Result))
+        {
+            return FrontendSummary.Failed($"{Name} exited with code {process.ExitCode}: {stderr.Result.Trim()}");
+        }
+
+        try
+        {
+            return FrontendSummary.FromJson(stdout.Result);
+        }
+        catch (FormatException ex)
+        {
+            return FrontendSummary.Failed($"{Name} printed an invalid summary: {ex.Message}");
+        }
+    }
+}
@@ -0,0 +1,126 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// The coverage a grammar's corpus must reach, read from "&lt;grammar file name&gt;.coverage" next to the grammar.
+/// The text form has one directive per line, with '#' starting a comment:
+/// <code>
+/// minimum 90                   # percentage of alternatives the corpus must cover
+/// allow legacy_stmt debug_expr # rules intentionally left uncovered; they do not count towards the percentage
+/// </code>
+/// </summary>
+public sealed class CoverageManifest
+{
+    /// <summary>
+    /// The extension of coverage manifest files.
+    /// </summary>
+    public const string Extension = ".coverage";
+
+    /// <summary>
+    /// Gets a manifest that requires every alternative to be covered.
+    /// </summary>
+    public static CoverageManifest Default => new();
+
+    /// <summary>
+    /// Gets the percentage of alternatives the corpus must cover, from 0 to 100.
+    /// </summary>
+    public double MinimumPercentage { get; private set; } = 100;
+
+    /// <summary>
+    /// Gets the rules whose alternatives are intentionally uncovered.
+    /// </summary>
+    public ISet<string> AllowedRules { get; } = new HashSet<string>(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Gets the manifest path of a grammar file: the grammar path with the ".coverage" extension.
+    /// </summary>
+    /// <param name="grammarPath">The grammar file.</param>
+    /// <returns>The manifest path.</returns>
+    public static string GetPath(string grammarPath)
+    {
+        ArgumentNullException.ThrowIfNull(grammarPath);
+        return Path.ChangeExtension(Path.GetFullPath(grammarPath), Extension);
+    }
+
+    /// <summary>
+    /// Reads the manifest of a grammar file.
+    /// </summary>
+    /// <param name="grammarPath">The grammar file.</param>
+    /// <returns>The manifest, or <see cref="Default"/> if the grammar has none.</returns>
+    public static CoverageManifest ForGrammar(string grammarPath)
+    {
+        var path = GetPath(grammarPath);
+        return File.Exists(path) ? Load(path) : Default;
+    }
+
+    /// <summary>
+    /// Reads a manifest file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <returns>The manifest.</returns>
+    public static CoverageManifest Load(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        return Parse(File.ReadAllText(path));
+    }
+
+    /// <summary>
+    /// Parses manifest text.
+    /// </summary>
+    /// <param name="text">The manifest text.</param>
+    /// <returns>The manifest.</returns>
+    /// <exception cref="FormatException">A line is not a known directive.</exception>
+    public static CoverageManifest Parse(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var manifest = new CoverageManifest();
+        var lineNumber = 0;
+
+        foreach (var rawLine in text.Split('\n'))
+        {
+            lineNumber++;
+            var comment = rawLine.IndexOf('#', StringComparison.Ordinal);
+            var line = (comment >= 0 ? rawLine[..comment] : rawLine).Trim();
+            if (line.Length == 0)
+            {
+                continue;
+            }
+
+            var parts = line.Split((char[]?)null, StringSplitOptions.RemoveEmptyEntries);
+            switch (parts[0])
+            {
+                case "minimum" when parts.Length == 2
+                    && double.TryParse(parts[1].TrimEnd('%'), System.Globalization.NumberStyles.Float, System.Globalization.CultureInfo.InvariantCulture, out var minimum)
+                    && minimum is >= 0 and <= 100:
+                    manifest.MinimumPercentage = minimum;
+                    break;
+
+                case "allow" when parts.Length >= 2:
+                    manifest.AllowedRules.UnionWith(parts.Skip(1).Select(rule => rule.Trim('<', '>')));
+                    break;
+
+                default:
+                    throw new FormatException($"Coverage manifest line {lineNumber}: unknown directive '{line}'");
+            }
+        }
+
+        return manifest;
+    }
+}
@@ -0,0 +1,391 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// How diagnostics not listed in a <see cref="DiagnosticExpectations"/> block are treated.
+/// </summary>
+public enum ExpectationMode
+{
+    /// <summary>
+    /// Every diagnostic must be expected.
+    /// </summary>
+    Strict,
+
+    /// <summary>
+    /// Diagnostics that are not expected, such as errors following from an expected one, are allowed.
+    /// </summary>
+    Lenient
+}
+
+/// <summary>
+/// A diagnostic a parse is expected to report.
+/// </summary>
+/// <param name="Severity">The severity.</param>
+/// <param name="Code">The code, e.g. "E0001".</param>
+/// <param name="Line">The line the diagnostic is at, or null to accept any location.</param>
+/// <param name="Column">The column the diagnostic is at, or null to accept any location.</param>
+/// <param name="Message">Text the message must contain, or null to accept any message.</param>
+public sealed record ExpectedDiagnostic(DiagnosticSeverity Severity, string Code, int? Line = null, int? Column = null, string? Message = null)
+{
+    /// <summary>
+    /// Determines whether a diagnostic meets the expectation.
+    /// </summary>
+    /// <param name="diagnostic">The diagnostic.</param>
+    /// <returns>True if the severity and code are the same, and the location and message match where given.</returns>
+    public bool Matches(Diagnostic diagnostic)
+    {
+        ArgumentNullException.ThrowIfNull(diagnostic);
+
+        return diagnostic.Severity == Severity
+            && diagnostic.Code == Code
+            && (Line == null || diagnostic.Location?.Line == Line && diagnostic.Location.Column == Column)
+            && (Message == null || diagnostic.Message.Contains(Message, StringComparison.Ordinal));
+    }
+
+    /// <summary>
+    /// Returns the expectation as a line of an expectations block.
+    /// </summary>
+    /// <returns>"severity code [line:column] ["message"]".</returns>
+    public override string ToString()
+    {
+        var text = new StringBuilder(Severity.ToString().ToLowerInvariant()).Append(' ').Append(Code);
+        if (Line != null)
+        {
+            text.Append(' ').Append(Line).Append(':').Append(Column);
+        }
+
+        if (Message != null)
+        {
+            text.Append(' ').Append(SExpression.Quote(Message));
+        }
+
+        return text.ToString();
+    }
+}
+
+/// <summary>
+/// The diagnostics a failing input is expected to produce, stored next to the input as "&lt;input&gt;.expected".
+/// Unlike a diagnostics snapshot, an expectation can leave out the location or the message, and lenient blocks
+/// allow diagnostics they do not list. The text form has one entry per line, with '#' starting a comment:
+/// <code>
+/// mode lenient                              # strict (the default) or lenient
+/// error E0012 3:7 "did you mean 'return'"   # severity, code, optional line:column and message substring
+/// warning W0002                             # any location and message
+/// recovered 2                               # tokens error recovery inserted, replaced, dropped or corrected
+/// </code>
+/// Expectations are matched in order, each with the first diagnostic it matches that no earlier one took. The
+/// "recovered" count is the number of error nodes recovery put in the tree, counted from the repair diagnostics
+/// (<see cref="DiagnosticCodes.UnclosedDelimiter"/>, <see cref="DiagnosticCodes.MismatchedDelimiter"/>,
+/// <see cref="DiagnosticCodes.UnexpectedDelimiter"/> and <see cref="DiagnosticCodes.MisspelledKeyword"/>).
+/// </summary>
+public sealed class DiagnosticExpectations
+{
+    /// <summary>
+    /// The extension appended to an input file to name its expectations.
+    /// </summary>
+    public const string Extension = ".expected";
+
+    private static readonly HashSet<string> RepairCodes = new(StringComparer.Ordinal)
+    {
+        DiagnosticCodes.UnclosedDelimiter,
+        DiagnosticCodes.MismatchedDelimiter,
+        DiagnosticCodes.UnexpectedDelimiter,
+        DiagnosticCodes.MisspelledKeyword
+    };
+
+    /// <summary>
+    /// Gets or sets how diagnostics that are not expected are treated.
+    /// </summary>
+    public ExpectationMode Mode { get; set; } = ExpectationMode.Strict;
+
+    /// <summary>
+    /// Gets the expected diagnostics, in order.
+    /// </summary>
+    public List<ExpectedDiagnostic> Diagnostics { get; } = new();
+
+    /// <summary>
+    /// Gets or sets the number of error nodes recovery must produce, or null if it is not checked.
+    /// </summary>
+    public int? Recovered { get; set; }
+
+    /// <summary>
+    /// Reads an expectations file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <returns>The expectations.</returns>
+    public static DiagnosticExpectations Load(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        return Parse(File.ReadAllText(path));
+    }
+
+    /// <summary>
+    /// Parses an expectations block.
+    /// </summary>
+    /// <param name="text">The block text.</param>
+    /// <returns>The expectations.</returns>
+    /// <exception cref="FormatException">A line is not a mode, a diagnostic or a recovered count.</exception>
+    public static DiagnosticExpectations Parse(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var expectations = new DiagnosticExpectations();
+        var lineNumber = 0;
+        foreach (var rawLine in text.ReplaceLineEndings("\n").Split('\n'))
+        {
+            lineNumber++;
+            var line = StripComment(rawLine).Trim();
+            if (line.Length == 0)
+            {
+                continue;
+            }
+
+            var parts = line.Split((char[]?)null, 2, StringSplitOptions.RemoveEmptyEntries);
+            var rest = parts.Length > 1 ? parts[1].Trim() : string.Empty;
+            switch (parts[0])
+            {
+                case "mode" when Enum.TryParse<ExpectationMode>(rest, ignoreCase: true, out var mode) && !int.TryParse(rest, out _):
+                    expectations.Mode = mode;
+                    break;
+
+                case "recovered" when int.TryParse(rest, NumberStyles.None, CultureInfo.InvariantCulture, out var count):
+                    expectations.Recovered = count;
+                    break;
+
+                case var severity when Enum.TryParse<DiagnosticSeverity>(severity, ignoreCase: true, out var parsed) && !int.TryParse(severity, out _):
+                    expectations.Diagnostics.Add(ParseDiagnostic(parsed, rest)
+                        ?? throw new FormatException($"Expectations line {lineNumber}: expected 'code [line:column] [\"message\"]' after the severity in '{line}'"));
+                    break;
+
+                default:
+                    throw new FormatException($"Expectations line {lineNumber}: unknown entry '{line}'");
+            }
+        }
+
+        return expectations;
+    }
+
+    /// <summary>
+    /// Creates the expectations a parse meets exactly: each diagnostic with its location and whole message.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    /// <param name="mode">The mode of the expectations.</param>
+    /// <param name="recovered">Whether the number of error nodes is checked.</param>
+    /// <returns>The expectations.</returns>
+    public static DiagnosticExpectations FromResult(ParseResult result, ExpectationMode mode = ExpectationMode.Strict, bool recovered = false)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+
+        var expectations = new DiagnosticExpectations { Mode = mode, Recovered = recovered ? CountRecovered(result) : null };
+        expectations.Diagnostics.AddRange(result.Diagnostics.Select(d => new ExpectedDiagnostic(d.Severity, d.Code, d.Location?.Line, d.Location?.Column, d.Message)));
+        return expectations;
+    }
+
+    /// <summary>
+    /// Counts the error nodes recovery put in a parse's tree.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    /// <returns>The number of repairs recovery made to the token stream.</returns>
+    public static int CountRecovered(ParseResult result)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+        return result.Diagnostics.Count(d => RepairCodes.Contains(d.Code));
+    }
+
+    /// <summary>
+    /// Checks a parse against the expectations.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    /// <returns>Null if the parse meets the expectations; otherwise the expected and actual diagnostics side by side.</returns>
+    public string? Check(ParseResult result)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+
+        var actual = result.Diagnostics.ToList();
+        var taken = new bool[actual.Count];
+        var rows = new List<(string Expected, string Actual, bool Matched)>();
+        foreach (var expected in Diagnostics)
+        {
+            var index = Enumerable.Range(0, actual.Count).FirstOrDefault(i => !taken[i] && expected.Matches(actual[i]), -1);
+            if (index >= 0)
+            {
+                taken[index] = true;
+            }
+
+            rows.Add((expected.ToString(), index >= 0 ? ParseSnapshot.FormatDiagnostic(actual[index]) : "(missing)", index >= 0));
+        }
+
+        // Lenient expectations still show what they let through, so the report explains the whole parse.
+        for (var i = 0; i < actual.Count; i++)
+        {
+            if (!taken[i])
+            {
+                rows.Add(("(not expected)", ParseSnapshot.FormatDiagnostic(actual[i]), Mode == ExpectationMode.Lenient));
+            }
+        }
+
+        if (Recovered is { } count)
+        {
+            var recovered = CountRecovered(result);
+            rows.Add(($"recovered {count}", $"recovered {recovered}", recovered == count));
+        }
+
+        if (rows.All(r => r.Matched))
+        {
+            return null;
+        }
+
+        var width = Math.Max("expected".Length, rows.Max(r => r.Expected.Length));
+        var text = new StringBuilder($"mode {Mode.ToString().ToLowerInvariant()}; rows marked '!' do not match\n");
+        text.Append("expected".PadRight(width)).Append(" | actual\n");
+        foreach (var (expected, actualText, matched) in rows)
+        {
+            text.Append(expected.PadRight(width)).Append(matched ? " | " : " ! ").Append(actualText).Append('\n');
+        }
+
+        return text.ToString();
+    }
+
+    /// <summary>
+    /// Returns the expectations in their text form.
+    /// </summary>
+    /// <returns>The block, one entry per line, ending with a newline.</returns>
+    public string Format()
+    {
+        var text = new StringBuilder();
+        if (Mode != ExpectationMode.Strict)
+        {
+            text.Append("mode ").Append(Mode.ToString().ToLowerInvariant()).Append('\n');
+        }
+
+        foreach (var diagnostic in Diagnostics)
+        {
+            text.Append(diagnostic).Append('\n');
+        }
+
+        if (Recovered != null)
+        {
+            text.Append("recovered ").Append(Recovered).Append('\n');
+        }
+
+        return text.ToString();
+    }
+
+    private static ExpectedDiagnostic? ParseDiagnostic(DiagnosticSeverity severity, string text)
+    {
+        var space = text.IndexOf(' ');
+        var code = space < 0 ? text : text[..space];
+        text = space < 0 ? string.Empty : text[(space + 1)..].TrimStart();
+        if (code.Length == 0 || code.StartsWith('"'))
+        {
+            return null;
+        }
+
+        int? line = null, column = null;
+        if (text.Length > 0 && !text.StartsWith('"'))
+        {
+            space = text.IndexOf(' ');
+            var position = (space < 0 ? text : text[..space]).Split(':');
+            if (position.Length != 2
+                || !int.TryParse(position[0], NumberStyles.None, CultureInfo.InvariantCulture, out var parsedLine)
+                || !int.TryParse(position[1], NumberStyles.None, CultureInfo.InvariantCulture, out var parsedColumn))
+            {
+                return null;
+            }
+
+            (line, column) = (parsedLine, parsedColumn);
+            text = space < 0 ? string.Empty : text[(space + 1)..].TrimStart();
+        }
+
+        string? message = null;
+        if (text.Length > 0 && (message = Unquote(text)) == null)
+        {
+            return null;
+        }
+
+        return new ExpectedDiagnostic(severity, code, line, column, message);
+    }
+
+    // Reverses SExpression.Quote; null if the text is not exactly one quoted string.
+    private static string? Unquote(string text)
+    {
+        if (text.Length < 2 || text[0] != '"' || text[^1] != '"')
+        {
+            return null;
+        }
+
+        var value = new StringBuilder();
+        for (var i = 1; i < text.Length - 1; i++)
+        {
+            var c = text[i];
+            if (c == '"')
+            {
+                return null;
+            }
+
+            if (c == '\\')
+            {
+                if (++i == text.Length - 1)
+                {
+                    return null;
+                }
+
+                c = text[i] switch
+                {
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    var escaped => escaped
+                };
+            }
+
+            value.Append(c);
+        }
+
+        return value.ToString();
+    }
+
+    // A '#' inside the quoted message does not start a comment.
+    private static string StripComment(string line)
+    {
+        var quoted = false;
+        for (var i = 0; i < line.Length; i++)
+        {
+            if (line[i] == '\\' && quoted)
+            {
+                i++;
+            }
+            else if (line[i] == '"')
+            {
+                quoted = !quoted;
+            }
+            else if (line[i] == '#' && !quoted)
+            {
+                return line[..i];
+            }
+        }
+
+        return line;
+    }
+}
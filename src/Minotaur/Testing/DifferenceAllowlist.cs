@@ -0,0 +1,168 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Known, representable differences between two frontends that differential testing should tolerate. The text
+/// form has one directive per line, with '#' starting a comment:
+/// <code>
+/// ignore-kind macro            # items of this kind are not compared
+/// ignore-name impl             # items of this kind are compared without their names
+/// ignore-identifier r#type     # identifiers left out of the identifier comparison
+/// equivalent-kind fn method    # kinds treated as the same kind
+/// skip ui/*.rs                 # corpus files, relative to the corpus root, that are not compared
+/// ignore-positions             # items match on kind and name even if they start at different places
+/// </code>
+/// </summary>
+public sealed class DifferenceAllowlist
+{
+    private readonly Dictionary<string, string> _canonicalKinds = new(StringComparer.Ordinal);
+    private readonly List<Regex> _skipped = new();
+
+    /// <summary>
+    /// Gets a new allowlist that tolerates nothing.
+    /// </summary>
+    public static DifferenceAllowlist Empty => new();
+
+    /// <summary>
+    /// Gets the item kinds that are not compared.
+    /// </summary>
+    public ISet<string> IgnoredKinds { get; } = new HashSet<string>(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Gets the item kinds compared without their names.
+    /// </summary>
+    public ISet<string> UnnamedKinds { get; } = new HashSet<string>(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Gets the identifiers left out of the identifier comparison.
+    /// </summary>
+    public ISet<string> IgnoredIdentifiers { get; } = new HashSet<string>(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Gets a value indicating whether item positions are left out of the item-by-item comparison, for
+    /// frontends that disagree on where an item starts (for example before or after its attributes).
+    /// </summary>
+    public bool IgnorePositions { get; private set; }
+
+    /// <summary>
+    /// Reads an allowlist file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <returns>The allowlist.</returns>
+    public static DifferenceAllowlist Load(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        return Parse(File.ReadAllText(path));
+    }
+
+    /// <summary>
+    /// Parses allowlist text.
+    /// </summary>
+    /// <param name="text">The allowlist text.</param>
+    /// <returns>The allowlist.</returns>
+    /// <exception cref="FormatException">A line is not a known directive.</exception>
+    public static DifferenceAllowlist Parse(string text)
+    {
+        ArgumentNullException.ThrowIfNull(text);
+
+        var allowlist = new DifferenceAllowlist();
+        var lineNumber = 0;
+
+        foreach (var rawLine in text.Split('\n'))
+        {
+            lineNumber++;
+            var comment = rawLine.IndexOf('#', StringComparison.Ordinal);
+            var line = (comment >= 0 && (comment == 0 || char.IsWhiteSpace(rawLine[comment - 1])) ? rawLine[..comment] : rawLine).Trim();
+            if (line.Length == 0)
+            {
+                continue;
+            }
+
+            var parts = line.Split((char[]?)null, StringSplitOptions.RemoveEmptyEntries);
+            switch (parts[0])
+            {
+                case "ignore-kind" when parts.Length >= 2:
+                    allowlist.IgnoredKinds.UnionWith(parts.Skip(1));
+                    break;
+
+                case "ignore-name" when parts.Length >= 2:
+                    allowlist.UnnamedKinds.UnionWith(parts.Skip(1));
+                    break;
+
+                case "ignore-identifier" when parts.Length >= 2:
+                    allowlist.IgnoredIdentifiers.UnionWith(parts.Skip(1));
+                    break;
+
+                case "equivalent-kind" when parts.Length >= 3:
+                    foreach (var kind in parts.Skip(1))
+                    {
+                        allowlist._canonicalKinds[kind] = allowlist.CanonicalKind(parts[1]);
+                    }
+                    break;
+
+                case "ignore-positions" when parts.Length == 1:
+                    allowlist.IgnorePositions = true;
+                    break;
+
+                case "skip" when parts.Length == 2:
+                    allowlist._skipped.Add(GlobToRegex(parts[1]));
+                    break;
+
+                default:
+                    throw new FormatException($"Allowlist line {lineNumber}: unknown directive '{line}'");
+            }
+        }
+
+        return allowlist;
+    }
+
+    /// <summary>
+    /// Gets the kind all equivalent kinds are compared as.
+    /// </summary>
+    /// <param name="kind">The item kind.</param>
+    /// <returns>The canonical kind.</returns>
+    public string CanonicalKind(string kind)
+    {
+        return _canonicalKinds.GetValueOrDefault(kind, kind);
+    }
+
+    /// <summary>
+    /// Determines whether a corpus file is skipped.
+    /// </summary>
+    /// <param name="relativePath">The path relative to the corpus root.</param>
+    /// <returns>True if the file is not compared.</returns>
+    public bool IsSkipped(string relativePath)
+    {
+        ArgumentNullException.ThrowIfNull(relativePath);
+
+        var normalized = relativePath.Replace('\\', '/');
+        return _skipped.Any(pattern => pattern.IsMatch(normalized));
+    }
+
+    private static Regex GlobToRegex(string glob)
+    {
+        var pattern = Regex.Escape(glob.Replace('\\', '/'))
+            .Replace(@"\*\*/", "(.*/)?")
+            .Replace(@"\*", "[^/]*")
+            .Replace(@"\?", "[^/]");
+        return new Regex($"^{pattern}$", RegexOptions.CultureInvariant);
+    }
+}
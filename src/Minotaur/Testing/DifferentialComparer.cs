@@ -0,0 +1,215 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// The comparison of one file's summaries from a reference frontend and a candidate frontend.
+/// </summary>
+public sealed class FileComparison
+{
+    /// <summary>
+    /// Gets the compared file.
+    /// </summary>
+    public string Path { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Gets the name of the reference frontend.
+    /// </summary>
+    public string ReferenceName { get; init; } = "reference";
+
+    /// <summary>
+    /// Gets the name of the candidate frontend.
+    /// </summary>
+    public string CandidateName { get; init; } = "candidate";
+
+    /// <summary>
+    /// Gets the reference frontend's summary.
+    /// </summary>
+    public FrontendSummary Reference { get; init; } = new();
+
+    /// <summary>
+    /// Gets the candidate frontend's summary.
+    /// </summary>
+    public FrontendSummary Candidate { get; init; } = new();
+
+    /// <summary>
+    /// Gets the differences that the allowlist does not tolerate.
+    /// </summary>
+    public IReadOnlyList<string> Differences { get; init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the first item pair that differs, in source order. One side is null when the other has extra items.
+    /// </summary>
+    public (SummaryItem? Reference, SummaryItem? Candidate)? FirstDivergence { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether the frontends agree.
+    /// </summary>
+    public bool IsMatch => Differences.Count == 0;
+
+    /// <summary>
+    /// Renders the differences followed by both summaries, for debugging a mismatch.
+    /// </summary>
+    /// <returns>The dump text.</returns>
+    public string Dump()
+    {
+        var text = new StringBuilder();
+        text.AppendLine($"{Path}: {(IsMatch ? "match" : $"{Differences.Count} difference(s)")}");
+        foreach (var difference in Differences)
+        {
+            text.AppendLine($"  - {difference}");
+        }
+
+        text.AppendLine($"--- {ReferenceName}").AppendLine(Reference.ToJson());
+        text.AppendLine($"--- {CandidateName}").AppendLine(Candidate.ToJson());
+        return text.ToString();
+    }
+}
+
+/// <summary>
+/// Compares the summaries two frontends produce for a file: item counts, item kinds, identifier sets and the
+/// first item where the two diverge.
+/// </summary>
+public static class DifferentialComparer
+{
+    private const int MaxListedIdentifiers = 10;
+
+    /// <summary>
+    /// Compares two summaries of a file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <param name="reference">The reference frontend and its summary.</param>
+    /// <param name="candidate">The candidate frontend and its summary.</param>
+    /// <param name="allowlist">Tolerated differences, or null to tolerate none.</param>
+    /// <returns>The comparison.</returns>
+    public static FileComparison Compare(
+        string path,
+        (string Name, FrontendSummary Summary) reference,
+        (string Name, FrontendSummary Summary) candidate,
+        DifferenceAllowlist? allowlist = null)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+        ArgumentNullException.ThrowIfNull(reference.Summary);
+        ArgumentNullException.ThrowIfNull(candidate.Summary);
+        allowlist ??= DifferenceAllowlist.Empty;
+
+        var differences = new List<string>();
+        (SummaryItem?, SummaryItem?)? firstDivergence = null;
+
+        foreach (var (name, summary) in new[] { reference, candidate })
+        {
+            if (summary.Error != null)
+            {
+                differences.Add($"{name} failed: {summary.Error}");
+            }
+        }
+
+        if (differences.Count == 0)
+        {
+            var referenceItems = Normalize(reference.Summary.Items, allowlist);
+            var candidateItems = Normalize(candidate.Summary.Items, allowlist);
+
+            if (referenceItems.Count != candidateItems.Count)
+            {
+                differences.Add($"item count: {reference.Name} {referenceItems.Count}, {candidate.Name} {candidateItems.Count}");
+            }
+
+            var referenceKinds = referenceItems.GroupBy(i => i.Kind).ToDictionary(g => g.Key, g => g.Count());
+            var candidateKinds = candidateItems.GroupBy(i => i.Kind).ToDictionary(g => g.Key, g => g.Count());
+            foreach (var kind in referenceKinds.Keys.Union(candidateKinds.Keys).Order(StringComparer.Ordinal))
+            {
+                var expected = referenceKinds.GetValueOrDefault(kind);
+                var actual = candidateKinds.GetValueOrDefault(kind);
+                if (expected != actual)
+                {
+                    differences.Add($"kind '{kind}': {reference.Name} {expected}, {candidate.Name} {actual}");
+                }
+            }
+
+            var referenceIdentifiers = reference.Summary.Identifiers.Where(i => !allowlist.IgnoredIdentifiers.Contains(i)).ToHashSet();
+            var candidateIdentifiers = candidate.Summary.Identifiers.Where(i => !allowlist.IgnoredIdentifiers.Contains(i)).ToHashSet();
+            AddIdentifierDifference(differences, $"identifiers only in {reference.Name}", referenceIdentifiers.Except(candidateIdentifiers));
+            AddIdentifierDifference(differences, $"identifiers only in {candidate.Name}", candidateIdentifiers.Except(referenceIdentifiers));
+
+            firstDivergence = FindFirstDivergence(referenceItems, candidateItems, allowlist.IgnorePositions);
+            if (firstDivergence is (var expectedItem, var actualItem))
+            {
+                differences.Add($"first divergence: {reference.Name} {Describe(expectedItem)}, {candidate.Name} {Describe(actualItem)}");
+            }
+        }
+
+        return new FileComparison
+        {
+            Path = path,
+            ReferenceName = reference.Name,
+            CandidateName = candidate.Name,
+            Reference = reference.Summary,
+            Candidate = candidate.Summary,
+            Differences = differences,
+            FirstDivergence = firstDivergence
+        };
+    }
+
+    private static List<SummaryItem> Normalize(IEnumerable<SummaryItem> items, DifferenceAllowlist allowlist)
+    {
+        return items
+            .Where(i => !allowlist.IgnoredKinds.Contains(i.Kind))
+            .Select(i => i with { Kind = allowlist.CanonicalKind(i.Kind) })
+            .Select(i => allowlist.UnnamedKinds.Contains(i.Kind) ? i with { Name = null } : i)
+            .ToList();
+    }
+
+    private static (SummaryItem?, SummaryItem?)? FindFirstDivergence(List<SummaryItem> reference, List<SummaryItem> candidate, bool ignorePositions)
+    {
+        for (var i = 0; i < Math.Max(reference.Count, candidate.Count); i++)
+        {
+            var expected = i < reference.Count ? reference[i] : null;
+            var actual = i < candidate.Count ? candidate[i] : null;
+            var same = ignorePositions
+                ? expected?.Kind == actual?.Kind && expected?.Name == actual?.Name
+                : expected == actual;
+            if (!same)
+            {
+                return (expected, actual);
+            }
+        }
+
+        return null;
+    }
+
+    private static void AddIdentifierDifference(List<string> differences, string label, IEnumerable<string> identifiers)
+    {
+        var sorted = identifiers.Order(StringComparer.Ordinal).ToList();
+        if (sorted.Count == 0)
+        {
+            return;
+        }
+
+        var listed = string.Join(", ", sorted.Take(MaxListedIdentifiers));
+        differences.Add(sorted.Count > MaxListedIdentifiers
+            ? $"{label}: {listed} and {sorted.Count - MaxListedIdentifiers} more"
+            : $"{label}: {listed}");
+    }
+
+    private static string Describe(SummaryItem? item)
+    {
+        return item?.ToString() ?? "(none)";
+    }
+}
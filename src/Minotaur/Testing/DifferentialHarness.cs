@@ -0,0 +1,152 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// The outcome of running two frontends over a corpus.
+/// </summary>
+public sealed class DifferentialReport
+{
+    /// <summary>
+    /// Gets the comparisons of every compared file, in path order.
+    /// </summary>
+    public IReadOnlyList<FileComparison> Files { get; init; } = Array.Empty<FileComparison>();
+
+    /// <summary>
+    /// Gets the files skipped by the allowlist, relative to the corpus root.
+    /// </summary>
+    public IReadOnlyList<string> Skipped { get; init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the comparisons where the frontends disagree.
+    /// </summary>
+    public IReadOnlyList<FileComparison> Mismatches => Files.Where(f => !f.IsMatch).ToList();
+
+    /// <summary>
+    /// Renders a summary line and the differences of every mismatching file.
+    /// </summary>
+    /// <returns>The report text.</returns>
+    public override string ToString()
+    {
+        var mismatches = Mismatches;
+        var text = new StringBuilder();
+        text.AppendLine($"{Files.Count} files compared, {mismatches.Count} mismatches, {Skipped.Count} skipped");
+
+        foreach (var mismatch in mismatches)
+        {
+            text.AppendLine(mismatch.Path);
+            foreach (var difference in mismatch.Differences)
+            {
+                text.AppendLine($"  - {difference}");
+            }
+        }
+
+        return text.ToString();
+    }
+}
+
+/// <summary>
+/// Runs a reference frontend and a candidate frontend over a corpus of source files and compares their
+/// summaries file by file. The harness knows nothing about any language; a grammar takes part by providing a
+/// reference <see cref="IDifferentialFrontend"/> and, usually, "DifferentialItems" metadata for
+/// <see cref="GrammarFrontend"/>.
+/// </summary>
+public sealed class DifferentialHarness
+{
+    /// <summary>
+    /// Initializes a new instance of the DifferentialHarness class.
+    /// </summary>
+    /// <param name="reference">The frontend treated as correct.</param>
+    /// <param name="candidate">The frontend under test.</param>
+    /// <param name="allowlist">Tolerated differences, or null to tolerate none.</param>
+    public DifferentialHarness(IDifferentialFrontend reference, IDifferentialFrontend candidate, DifferenceAllowlist? allowlist = null)
+    {
+        ArgumentNullException.ThrowIfNull(reference);
+        ArgumentNullException.ThrowIfNull(candidate);
+
+        Reference = reference;
+        Candidate = candidate;
+        Allowlist = allowlist ?? DifferenceAllowlist.Empty;
+    }
+
+    /// <summary>
+    /// Gets the frontend treated as correct.
+    /// </summary>
+    public IDifferentialFrontend Reference { get; }
+
+    /// <summary>
+    /// Gets the frontend under test.
+    /// </summary>
+    public IDifferentialFrontend Candidate { get; }
+
+    /// <summary>
+    /// Gets the tolerated differences.
+    /// </summary>
+    public DifferenceAllowlist Allowlist { get; }
+
+    /// <summary>
+    /// Compares one file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <returns>The comparison.</returns>
+    public FileComparison CompareFile(string path)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        var source = File.ReadAllText(path);
+        return DifferentialComparer.Compare(
+            path,
+            (Reference.Name, Reference.Summarize(path, source)),
+            (Candidate.Name, Candidate.Summarize(path, source)),
+            Allowlist);
+    }
+
+    /// <summary>
+    /// Compares every matching file under a corpus directory, recursively.
+    /// </summary>
+    /// <param name="corpus">The corpus directory.</param>
+    /// <param name="searchPattern">The file pattern, such as "*.rs".</param>
+    /// <param name="progress">Optional receiver of each comparison as it completes.</param>
+    /// <returns>The report.</returns>
+    public DifferentialReport Run(string corpus, string searchPattern, IProgress<FileComparison>? progress = null)
+    {
+        ArgumentNullException.ThrowIfNull(corpus);
+        ArgumentNullException.ThrowIfNull(searchPattern);
+
+        var files = new List<FileComparison>();
+        var skipped = new List<string>();
+
+        foreach (var path in Directory.GetFiles(corpus, searchPattern, SearchOption.AllDirectories).Order(StringComparer.Ordinal))
+        {
+            var relative = Path.GetRelativePath(corpus, path).Replace('\\', '/');
+            if (Allowlist.IsSkipped(relative))
+            {
+                skipped.Add(relative);
+                continue;
+            }
+
+            var comparison = CompareFile(path);
+            files.Add(comparison);
+            progress?.Report(comparison);
+        }
+
+        return new DifferentialReport { Files = files, Skipped = skipped };
+    }
+}
@@ -0,0 +1,205 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Diagnostics;
+using System.Text;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// An engine left out of an <see cref="EngineMatrix"/> run because it does not support the grammar.
+/// </summary>
+/// <param name="Engine">The engine's name.</param>
+/// <param name="Reason">Why the engine would only repeat the sequential parse.</param>
+public sealed record SkippedEngine(string Engine, string Reason);
+
+/// <summary>
+/// The time an engine took over all the cases of an <see cref="EngineMatrix"/> run.
+/// </summary>
+/// <param name="Engine">The engine's name.</param>
+/// <param name="Cases">The number of cases parsed.</param>
+/// <param name="Elapsed">The total parse time.</param>
+public sealed record EngineTiming(string Engine, int Cases, TimeSpan Elapsed)
+{
+    /// <summary>
+    /// Gets the mean parse time per case.
+    /// </summary>
+    public TimeSpan PerCase => Cases > 0 ? Elapsed / Cases : TimeSpan.Zero;
+}
+
+/// <summary>
+/// A case an engine parsed differently from the reference engine.
+/// </summary>
+/// <param name="Case">The case name.</param>
+/// <param name="Reference">The reference engine's name.</param>
+/// <param name="Engine">The diverging engine's name.</param>
+/// <param name="Diff">A unified diff from the reference engine's diagnostics and tree to the engine's, or the
+/// exception the engine threw.</param>
+public sealed record EngineDivergence(string Case, string Reference, string Engine, string Diff);
+
+/// <summary>
+/// The result of running cases under several engines.
+/// </summary>
+public sealed class EngineMatrixReport
+{
+    /// <summary>
+    /// Gets the engines that ran, the reference engine first.
+    /// </summary>
+    public IReadOnlyList<string> Engines { get; init; } = Array.Empty<string>();
+
+    /// <summary>
+    /// Gets the requested engines that do not support the grammar.
+    /// </summary>
+    public IReadOnlyList<SkippedEngine> Skipped { get; init; } = Array.Empty<SkippedEngine>();
+
+    /// <summary>
+    /// Gets the time each engine took, in the order of <see cref="Engines"/>.
+    /// </summary>
+    public IReadOnlyList<EngineTiming> Timings { get; init; } = Array.Empty<EngineTiming>();
+
+    /// <summary>
+    /// Gets the cases that engines parsed differently from the reference engine, by case and then engine.
+    /// </summary>
+    public IReadOnlyList<EngineDivergence> Divergences { get; init; } = Array.Empty<EngineDivergence>();
+
+    /// <summary>
+    /// Gets the number of cases run.
+    /// </summary>
+    public int CaseCount { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether every engine agreed with the reference engine on every case.
+    /// </summary>
+    public bool IsConsistent => Divergences.Count == 0;
+}
+
+/// <summary>
+/// Parses test cases under each <see cref="ParseEngine"/> a grammar supports and compares the results with those of
+/// the first engine, catching bugs that only one engine has. Results are compared as the diagnostics, in source
+/// order, followed by the tree as <see cref="SExpression"/> writes it for snapshots. That form shows the chosen
+/// derivation and how many derivations an ambiguous node has rather than the shared nodes of the forest, so engines
+/// may pack a forest differently, and lazily parsed bodies may report their diagnostics last, without diverging.
+/// </summary>
+public static class EngineMatrix
+{
+    /// <summary>
+    /// Runs the cases of a corpus directory.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="corpusDirectory">The corpus directory; see <see cref="RegressionCorpus"/>.</param>
+    /// <param name="engines">The engines to run, the reference first; <see cref="ParseEngine.All"/> by default.</param>
+    /// <param name="options">Options for every parse, such as the start rule.</param>
+    /// <returns>The report.</returns>
+    public static EngineMatrixReport Run(CompiledGrammar grammar, string corpusDirectory, IEnumerable<ParseEngine>? engines = null, ParseOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(corpusDirectory);
+
+        // Results must not depend on how the corpus was checked out, as with its snapshots.
+        var cases = RegressionCorpus.GetCases(corpusDirectory)
+            .Select(c => (c.Name, File.ReadAllText(c.InputPath).ReplaceLineEndings("\n")));
+        return Run(grammar, cases, engines, options);
+    }
+
+    /// <summary>
+    /// Runs named inputs.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="cases">The case names and inputs.</param>
+    /// <param name="engines">The engines to run, the reference first; <see cref="ParseEngine.All"/> by default.</param>
+    /// <param name="options">Options for every parse, such as the start rule.</param>
+    /// <returns>The report.</returns>
+    public static EngineMatrixReport Run(CompiledGrammar grammar, IEnumerable<(string Name, string Input)> cases, IEnumerable<ParseEngine>? engines = null, ParseOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        ArgumentNullException.ThrowIfNull(cases);
+
+        var running = new List<ParseEngine>();
+        var skipped = new List<SkippedEngine>();
+        foreach (var engine in (engines ?? ParseEngine.All).Distinct())
+        {
+            if (engine.GetUnsupportedReason(grammar) is { } reason)
+            {
+                skipped.Add(new SkippedEngine(engine.Name, reason));
+            }
+            else
+            {
+                running.Add(engine);
+            }
+        }
+
+        var parser = new GeneralizedParser(grammar);
+        var elapsed = new TimeSpan[running.Count];
+        var divergences = new List<EngineDivergence>();
+        var count = 0;
+        foreach (var (name, input) in cases)
+        {
+            count++;
+            string? reference = null;
+            for (var i = 0; i < running.Count; i++)
+            {
+                var watch = Stopwatch.StartNew();
+                string rendered;
+                try
+                {
+                    rendered = Render(running[i].Parse(parser, input, options ?? new ParseOptions()));
+                }
+                catch (Exception ex) when (ex is not OutOfMemoryException)
+                {
+                    rendered = $"{running[i].Name} threw {ex.GetType().Name}: {ex.Message}\n";
+                }
+
+                elapsed[i] += watch.Elapsed;
+                if (reference == null)
+                {
+                    reference = rendered;
+                }
+                else if (rendered != reference)
+                {
+                    divergences.Add(new EngineDivergence(name, running[0].Name, running[i].Name, Snapshot.Diff(reference, rendered, running[0].Name, running[i].Name)));
+                }
+            }
+        }
+
+        return new EngineMatrixReport
+        {
+            Engines = running.Select(e => e.Name).ToList(),
+            Skipped = skipped,
+            Timings = running.Select((e, i) => new EngineTiming(e.Name, count, elapsed[i])).ToList(),
+            Divergences = divergences,
+            CaseCount = count
+        };
+    }
+
+    // Rendering reads every child, so the bodies a lazy parse deferred are parsed, and report their diagnostics,
+    // before the diagnostics are listed.
+    private static string Render(ParseResult result)
+    {
+        var tree = result.Tree != null ? SExpression.Format(result.Tree) : "(no tree)\n";
+        var diagnostics = result.Diagnostics
+            .OrderBy(d => d.Location?.Offset ?? -1)
+            .ThenBy(d => d.Code, StringComparer.Ordinal)
+            .ThenBy(d => d.Message, StringComparer.Ordinal);
+        var text = new StringBuilder();
+        foreach (var diagnostic in diagnostics)
+        {
+            text.Append(ParseSnapshot.FormatDiagnostic(diagnostic)).Append('\n');
+        }
+
+        return text.Append(tree).ToString();
+    }
+}
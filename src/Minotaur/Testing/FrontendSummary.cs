@@ -0,0 +1,136 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.Json;
+using System.Text.Json.Nodes;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// An item (function, struct, module...) found by a frontend.
+/// </summary>
+/// <param name="Kind">The item kind, in the reference frontend's vocabulary.</param>
+/// <param name="Name">The item name, or null for unnamed items such as impl blocks.</param>
+/// <param name="Line">The 1-based line the item starts on.</param>
+/// <param name="Column">The 1-based column the item starts on.</param>
+public sealed record SummaryItem(string Kind, string? Name, int Line, int Column)
+{
+    /// <summary>
+    /// Returns the item as "kind name @line:column".
+    /// </summary>
+    /// <returns>The item description.</returns>
+    public override string ToString()
+    {
+        return Name != null ? $"{Kind} {Name} @{Line}:{Column}" : $"{Kind} @{Line}:{Column}";
+    }
+}
+
+/// <summary>
+/// What a frontend extracted from one source file: its items in source order and the set of identifiers it
+/// contains. Summaries are the common representation compared by <see cref="DifferentialComparer"/>, so any
+/// parser that can produce one - in process or as an external command writing JSON - can serve as a reference.
+/// </summary>
+public sealed class FrontendSummary
+{
+    /// <summary>
+    /// Gets the items in source order.
+    /// </summary>
+    public IReadOnlyList<SummaryItem> Items { get; init; } = Array.Empty<SummaryItem>();
+
+    /// <summary>
+    /// Gets the distinct identifiers in the file.
+    /// </summary>
+    public IReadOnlySet<string> Identifiers { get; init; } = new HashSet<string>();
+
+    /// <summary>
+    /// Gets the error message if the frontend could not process the file.
+    /// </summary>
+    public string? Error { get; init; }
+
+    /// <summary>
+    /// Creates a summary for a file the frontend could not process.
+    /// </summary>
+    /// <param name="message">The error message.</param>
+    /// <returns>The failed summary.</returns>
+    public static FrontendSummary Failed(string message)
+    {
+        return new FrontendSummary { Error = message };
+    }
+
+    /// <summary>
+    /// Reads a summary from JSON of the form
+    /// <c>{ "items": [{ "kind": "fn", "name": "main", "line": 1, "column": 1 }], "identifiers": ["main"], "error": null }</c>.
+    /// </summary>
+    /// <param name="json">The JSON text.</param>
+    /// <returns>The summary.</returns>
+    /// <exception cref="FormatException">The JSON does not describe a summary.</exception>
+    public static FrontendSummary FromJson(string json)
+    {
+        ArgumentNullException.ThrowIfNull(json);
+
+        try
+        {
+            var root = JsonNode.Parse(json)?.AsObject() ?? throw new FormatException("Summary JSON is empty");
+            if (root["error"]?.GetValue<string>() is { } error)
+            {
+                return Failed(error);
+            }
+
+            var items = (root["items"]?.AsArray() ?? new JsonArray())
+                .Select(item => new SummaryItem(
+                    item?["kind"]?.GetValue<string>() ?? throw new FormatException("Summary item has no kind"),
+                    item["name"]?.GetValue<string>(),
+                    item["line"]?.GetValue<int>() ?? 0,
+                    item["column"]?.GetValue<int>() ?? 0))
+                .ToList();
+
+            var identifiers = (root["identifiers"]?.AsArray() ?? new JsonArray())
+                .Select(identifier => identifier?.GetValue<string>() ?? throw new FormatException("Identifier is null"))
+                .ToHashSet(StringComparer.Ordinal);
+
+            return new FrontendSummary { Items = items, Identifiers = identifiers };
+        }
+        catch (Exception ex) when (ex is JsonException or InvalidOperationException)
+        {
+            throw new FormatException($"Invalid summary JSON: {ex.Message}", ex);
+        }
+    }
+
+    /// <summary>
+    /// Writes the summary as indented JSON, with identifiers sorted so dumps of equal summaries are identical.
+    /// </summary>
+    /// <returns>The JSON text.</returns>
+    public string ToJson()
+    {
+        var root = new JsonObject();
+        if (Error != null)
+        {
+            root["error"] = Error;
+        }
+
+        root["items"] = new JsonArray(Items.Select(item => (JsonNode)new JsonObject
+        {
+            ["kind"] = item.Kind,
+            ["name"] = item.Name,
+            ["line"] = item.Line,
+            ["column"] = item.Column
+        }).ToArray());
+        root["identifiers"] = new JsonArray(Identifiers.Order(StringComparer.Ordinal).Select(i => (JsonNode)JsonValue.Create(i)!).ToArray());
+
+        return root.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+    }
+}
@@ -0,0 +1,390 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// The section of a changelog a change is listed in.
+/// </summary>
+public enum GrammarChangeKind
+{
+    /// <summary>
+    /// Syntax the new version accepts that the old one did not: an added rule or alternative.
+    /// </summary>
+    Added,
+
+    /// <summary>
+    /// Syntax the new version no longer accepts: a removed rule or alternative.
+    /// </summary>
+    Removed,
+
+    /// <summary>
+    /// Syntax deprecated in the new version, or no longer deprecated.
+    /// </summary>
+    Deprecated,
+
+    /// <summary>
+    /// Syntax accepted by both versions that is parsed differently: precedence, associativity, accepted
+    /// ambiguities or feature guards changed.
+    /// </summary>
+    Behavior
+}
+
+/// <summary>
+/// A change between two versions of a grammar.
+/// </summary>
+/// <param name="Kind">The section the change is listed in.</param>
+/// <param name="Description">The change in Markdown.</param>
+/// <param name="Example">For added and removed syntax, a sentence using it that the other version rejects if one
+/// was found, or null.</param>
+public sealed record GrammarChange(GrammarChangeKind Kind, string Description, string? Example = null);
+
+/// <summary>
+/// Generates the changelog between two versions of a grammar from their structural differences: alternatives are
+/// matched by rule and symbols, deprecations and feature guards by their annotations, and operator behavior by the
+/// "Precedence" entries. Added and removed alternatives come with an example sentence generated through them, the
+/// smallest one first, preferring one the other version rejects. The output only depends on the two grammars.
+/// </summary>
+public sealed class GrammarChangelog
+{
+    private static readonly string[] Headings = { "New syntax", "Removed syntax", "Deprecations", "Behavior changes" };
+
+    private IReadOnlyList<GrammarChange>? _changes;
+
+    /// <summary>
+    /// Initializes a new instance of the GrammarChangelog class.
+    /// </summary>
+    /// <param name="from">The old version.</param>
+    /// <param name="to">The new version.</param>
+    public GrammarChangelog(CompiledGrammar from, CompiledGrammar to)
+    {
+        ArgumentNullException.ThrowIfNull(from);
+        ArgumentNullException.ThrowIfNull(to);
+
+        From = from;
+        To = to;
+    }
+
+    /// <summary>
+    /// Gets the old version.
+    /// </summary>
+    public CompiledGrammar From { get; }
+
+    /// <summary>
+    /// Gets the new version.
+    /// </summary>
+    public CompiledGrammar To { get; }
+
+    /// <summary>
+    /// Gets or sets how many random sentences are tried per alternative when the smallest one is accepted by the
+    /// other version too.
+    /// </summary>
+    public int ExampleAttempts { get; init; } = 8;
+
+    /// <summary>
+    /// Gets or sets the seed of the random sentences, so the same grammars always yield the same changelog.
+    /// </summary>
+    public int Seed { get; init; } = 1;
+
+    /// <summary>
+    /// Finds the changes between the versions.
+    /// </summary>
+    /// <returns>The changes, by section and then in rule and alternative order of the version they are in.</returns>
+    public IReadOnlyList<GrammarChange> GetChanges()
+    {
+        if (_changes != null)
+        {
+            return _changes;
+        }
+
+        var changes = new List<GrammarChange>();
+        changes.AddRange(FindSyntax(To, From, GrammarChangeKind.Added));
+        changes.AddRange(FindSyntax(From, To, GrammarChangeKind.Removed));
+        changes.AddRange(FindDeprecations());
+        changes.AddRange(FindFeatureChanges());
+        changes.AddRange(FindAmbiguityChanges());
+        changes.AddRange(FindPrecedenceChanges());
+
+        _changes = changes.OrderBy(c => c.Kind).ToList();
+        return _changes;
+    }
+
+    /// <summary>
+    /// Writes the changelog as Markdown, with a section per <see cref="GrammarChangeKind"/> that has changes.
+    /// </summary>
+    /// <returns>The Markdown text.</returns>
+    public string ToMarkdown()
+    {
+        var sb = new StringBuilder();
+        sb.Append($"# {To.Name} {To.Source.Version}\n\n");
+        sb.Append($"Changes since {From.Source.Version}.\n");
+
+        var changes = GetChanges();
+        if (changes.Count == 0)
+        {
+            sb.Append("\nNo changes.\n");
+            return sb.ToString();
+        }
+
+        foreach (var section in changes.GroupBy(c => c.Kind))
+        {
+            sb.Append($"\n## {Headings[(int)section.Key]}\n\n");
+            var spaced = false;
+            foreach (var change in section)
+            {
+                // An example is a block of its item, so the next item is separated from it by a blank line.
+                sb.Append(spaced ? "\n" : string.Empty).Append($"- {change.Description}\n");
+                if (change.Example != null)
+                {
+                    sb.Append($"\n  ```\n  {change.Example}\n  ```\n");
+                }
+
+                spaced = change.Example != null;
+            }
+        }
+
+        return sb.ToString();
+    }
+
+    private IEnumerable<GrammarChange> FindSyntax(CompiledGrammar grammar, CompiledGrammar other, GrammarChangeKind kind)
+    {
+        var otherParser = new GeneralizedParser(other);
+        var smallest = new SentenceGenerator(grammar, new SentenceGeneratorOptions { MaxDepth = 0 });
+        var generator = new SentenceGenerator(grammar, new SentenceGeneratorOptions { MaxDepth = 3 });
+        foreach (var rule in grammar.Rules)
+        {
+            var counterpart = other.GetRule(rule.Name);
+            foreach (var alternative in rule.Alternatives.Where(a => counterpart == null || Find(counterpart, a) == null))
+            {
+                var notes = new List<string>();
+                if (counterpart == null)
+                {
+                    notes.Add(kind == GrammarChangeKind.Added ? "new rule" : "rule removed");
+                }
+
+                if (kind == GrammarChangeKind.Added && alternative.Feature != null)
+                {
+                    notes.Add($"behind feature `{alternative.Feature}`");
+                }
+
+                var description = notes.Count > 0 ? $"{Describe(alternative)} ({string.Join(", ", notes)})" : Describe(alternative);
+                yield return new GrammarChange(kind, description, FindExample(alternative, smallest, generator, otherParser));
+            }
+        }
+    }
+
+    private string? FindExample(CompiledAlternative alternative, SentenceGenerator smallest, SentenceGenerator generator, GeneralizedParser other)
+    {
+        // The smallest sentence takes the first choice everywhere, which keeps examples short and stable.
+        var example = smallest.GenerateThrough(alternative, new FirstChoiceRandom());
+        if (example == null || !other.Parse(example).IsSuccess)
+        {
+            return example;
+        }
+
+        var random = new Random(Seed);
+        for (var i = 0; i < ExampleAttempts; i++)
+        {
+            var sentence = generator.GenerateThrough(alternative, random);
+            if (sentence != null && !other.Parse(sentence).IsSuccess)
+            {
+                return sentence;
+            }
+        }
+
+        return example;
+    }
+
+    private IEnumerable<GrammarChange> FindDeprecations()
+    {
+        foreach (var rule in To.Rules)
+        {
+            // Added syntax that is deprecated from the start is only listed as added.
+            if (From.GetRule(rule.Name) is not { } old)
+            {
+                continue;
+            }
+
+            var deprecations = rule.Alternatives
+                .Select(a => (Alternative: a, Before: Find(old, a)))
+                .Where(d => d.Before != null)
+                .Select(d => (d.Alternative, Now: d.Alternative.Deprecation, Before: d.Before!.Deprecation))
+                .ToList();
+
+            // A deprecation of the whole rule is listed once, not for each of its alternatives.
+            if (deprecations.Count > 0 && deprecations.All(d => d.Now?.AppliesToRule == true) && !deprecations.All(d => d.Before?.AppliesToRule == true))
+            {
+                yield return new GrammarChange(GrammarChangeKind.Deprecated, $"`<{rule.Name}>`: {DescribeDeprecation(deprecations[0].Now!)}");
+                continue;
+            }
+
+            foreach (var (alternative, now, before) in deprecations)
+            {
+                if (now != null && before == null)
+                {
+                    yield return new GrammarChange(GrammarChangeKind.Deprecated, $"{Describe(alternative)}: {DescribeDeprecation(now)}");
+                }
+                else if (now == null && before != null)
+                {
+                    yield return new GrammarChange(GrammarChangeKind.Deprecated, $"{Describe(alternative)} is no longer deprecated");
+                }
+            }
+        }
+    }
+
+    private IEnumerable<GrammarChange> FindFeatureChanges()
+    {
+        foreach (var alternative in To.Rules.SelectMany(r => r.Alternatives))
+        {
+            if (From.GetRule(alternative.Rule.Name) is not { } old || Find(old, alternative) is not { } before || before.Feature == alternative.Feature)
+            {
+                continue;
+            }
+
+            var description = (before.Feature, alternative.Feature) switch
+            {
+                (null, { } now) => $"{Describe(alternative)} is now behind feature `{now}`",
+                ({ } was, null) => $"{Describe(alternative)} no longer needs feature `{was}`",
+                var (was, now) => $"{Describe(alternative)} moved from feature `{was}` to feature `{now}`"
+            };
+            yield return new GrammarChange(GrammarChangeKind.Behavior, description);
+        }
+    }
+
+    private IEnumerable<GrammarChange> FindAmbiguityChanges()
+    {
+        foreach (var rule in To.Rules)
+        {
+            if (From.GetRule(rule.Name) is not { } old)
+            {
+                continue;
+            }
+
+            if (old.AcceptedAmbiguity == null && rule.AcceptedAmbiguity != null)
+            {
+                yield return new GrammarChange(GrammarChangeKind.Behavior, $"Ambiguities of `<{rule.Name}>` are now accepted and resolved to the earliest alternative");
+            }
+            else if (old.AcceptedAmbiguity != null && rule.AcceptedAmbiguity == null)
+            {
+                yield return new GrammarChange(GrammarChangeKind.Behavior, $"Ambiguities of `<{rule.Name}>` are no longer accepted and are reported");
+            }
+            else if (rule.AcceptedAmbiguity != null)
+            {
+                // Accepted ambiguities resolve to the earliest alternative, so reordering the shared ones changes the result.
+                var order = rule.Alternatives.Select(a => Find(old, a)).OfType<CompiledAlternative>().Select(a => a.Index).ToList();
+                if (!order.SequenceEqual(order.Order()))
+                {
+                    yield return new GrammarChange(GrammarChangeKind.Behavior, $"Alternatives of `<{rule.Name}>` were reordered, which changes how its accepted ambiguities resolve");
+                }
+            }
+        }
+    }
+
+    private IEnumerable<GrammarChange> FindPrecedenceChanges()
+    {
+        var before = Operators(From.Precedence);
+        var after = Operators(To.Precedence);
+
+        foreach (var (symbol, level) in after)
+        {
+            if (!before.TryGetValue(symbol, out var old))
+            {
+                yield return new GrammarChange(GrammarChangeKind.Behavior, $"`{symbol}` now has a precedence level: `{level.Declaration}`");
+            }
+            else if (old.Declaration.Associativity != level.Declaration.Associativity)
+            {
+                yield return new GrammarChange(GrammarChangeKind.Behavior, $"`{symbol}` is now {Associativity(level.Declaration)} (was {Associativity(old.Declaration)})");
+            }
+        }
+
+        foreach (var symbol in before.Keys.Where(s => !after.ContainsKey(s)))
+        {
+            yield return new GrammarChange(GrammarChangeKind.Behavior, $"`{symbol}` no longer has a precedence level");
+        }
+
+        // Every pair of operators in both versions whose relative binding changed, named from the later one.
+        var shared = after.Keys.Where(before.ContainsKey).ToList();
+        for (var i = 0; i < shared.Count; i++)
+        {
+            for (var j = i + 1; j < shared.Count; j++)
+            {
+                var now = Relation(after[shared[j]].Index, after[shared[i]].Index);
+                var was = Relation(before[shared[j]].Index, before[shared[i]].Index);
+                if (now != was)
+                {
+                    yield return new GrammarChange(GrammarChangeKind.Behavior, $"`{shared[j]}` now binds {now} `{shared[i]}` (was {was})");
+                }
+            }
+        }
+    }
+
+    private static Dictionary<GrammarSymbol, (int Index, PrecedenceLevel Declaration)> Operators(GrammarPrecedence? precedence)
+    {
+        var operators = new Dictionary<GrammarSymbol, (int Index, PrecedenceLevel Declaration)>();
+        for (var i = 0; i < (precedence?.Levels.Count ?? 0); i++)
+        {
+            foreach (var symbol in precedence!.Levels[i].Operators)
+            {
+                operators[symbol] = (i, precedence.Levels[i]);
+            }
+        }
+
+        return operators;
+    }
+
+    private static string Relation(int level, int otherLevel)
+    {
+        return level > otherLevel ? "tighter than" : level < otherLevel ? "looser than" : "as tightly as";
+    }
+
+    private static string Associativity(PrecedenceLevel level)
+    {
+        return level.Associativity switch
+        {
+            OperatorFixity.InfixLeft => "left-associative",
+            OperatorFixity.InfixRight => "right-associative",
+            _ => "non-associative"
+        };
+    }
+
+    private static CompiledAlternative? Find(CompiledRule rule, CompiledAlternative alternative)
+    {
+        return rule.Alternatives.FirstOrDefault(a => a.Symbols.SequenceEqual(alternative.Symbols));
+    }
+
+    private static string Describe(CompiledAlternative alternative)
+    {
+        var symbols = alternative.Symbols.Count > 0 ? string.Join(" ", alternative.Symbols) : "ε";
+        return $"`<{alternative.Rule.Name}> ::= {symbols}`";
+    }
+
+    private static string DescribeDeprecation(GrammarDeprecation deprecation)
+    {
+        return deprecation.ReplaceWith != null ? $"{deprecation}; replace with `{deprecation.ReplaceWith}`" : deprecation.ToString();
+    }
+
+    private sealed class FirstChoiceRandom : Random
+    {
+        public override int Next(int maxValue)
+        {
+            return 0;
+        }
+    }
+}
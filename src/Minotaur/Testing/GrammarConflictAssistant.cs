@@ -0,0 +1,595 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using System.Text.RegularExpressions;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// A span of a sentence that a grammar derives in more than one way.
+/// </summary>
+public sealed class GrammarConflict
+{
+    internal GrammarConflict(string startRule, string sentence, string text, CompiledRule rule, CompiledAlternative first, CompiledAlternative second, string firstTree, string secondTree)
+    {
+        StartRule = startRule;
+        Sentence = sentence;
+        Text = text;
+        Rule = rule;
+        First = first;
+        Second = second;
+        FirstTree = firstTree;
+        SecondTree = secondTree;
+    }
+
+    /// <summary>
+    /// Gets the rule the sentence was parsed from.
+    /// </summary>
+    public string StartRule { get; }
+
+    /// <summary>
+    /// Gets the generated sentence the conflict was found in.
+    /// </summary>
+    public string Sentence { get; }
+
+    /// <summary>
+    /// Gets the ambiguous span of the sentence.
+    /// </summary>
+    public string Text { get; }
+
+    /// <summary>
+    /// Gets the rule deriving the span.
+    /// </summary>
+    public CompiledRule Rule { get; }
+
+    /// <summary>
+    /// Gets the alternative of the derivation the parser keeps: the one using the earliest alternatives.
+    /// </summary>
+    public CompiledAlternative First { get; }
+
+    /// <summary>
+    /// Gets the alternative of the competing derivation.
+    /// </summary>
+    public CompiledAlternative Second { get; }
+
+    /// <summary>
+    /// Gets the derivation the parser keeps as a compact S-expression, e.g. <c>(e (e 1 + 2) * 3)</c>.
+    /// </summary>
+    public string FirstTree { get; }
+
+    /// <summary>
+    /// Gets the competing derivation as a compact S-expression.
+    /// </summary>
+    public string SecondTree { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether the rule's <c>// @ambiguous</c> annotation accepts the conflict.
+    /// </summary>
+    public bool IsAccepted => Rule.AcceptedAmbiguity != null;
+
+    /// <summary>
+    /// Gets the text identifying the conflict across versions of the grammar: the rule and the text of both
+    /// alternatives.
+    /// </summary>
+    public string Key => $"<{Rule.Name}>: {First.Text} / {Second.Text}";
+
+    /// <summary>
+    /// Returns the conflict as "&lt;rule&gt;: 'text' derives as first or as second".
+    /// </summary>
+    /// <returns>The conflict on one line.</returns>
+    public override string ToString()
+    {
+        return $"<{Rule.Name}>: '{Text}' derives as {FirstTree} or as {SecondTree}";
+    }
+}
+
+/// <summary>
+/// The kinds of edit <see cref="GrammarConflictAssistant"/> proposes.
+/// </summary>
+public enum ConflictResolutionKind
+{
+    /// <summary>
+    /// Declares the precedence or associativity of the operators involved in the "Precedence" header.
+    /// </summary>
+    Precedence,
+
+    /// <summary>
+    /// Accepts the derivation the parser keeps with an <c>// @ambiguous</c> annotation on the rule.
+    /// </summary>
+    AcceptDefault,
+
+    /// <summary>
+    /// Left-factors the alternatives sharing the conflicting alternatives' prefix, merging the tails one of the
+    /// others already covers.
+    /// </summary>
+    LeftFactor
+}
+
+/// <summary>
+/// An edit of a grammar file proposed to resolve a conflict.
+/// </summary>
+public sealed class ConflictResolution
+{
+    private readonly Func<string, string> _apply;
+
+    internal ConflictResolution(ConflictResolutionKind kind, string description, Func<string, string> apply)
+    {
+        Kind = kind;
+        Description = description;
+        _apply = apply;
+    }
+
+    /// <summary>
+    /// Gets the kind of edit.
+    /// </summary>
+    public ConflictResolutionKind Kind { get; }
+
+    /// <summary>
+    /// Gets a description of the edit.
+    /// </summary>
+    public string Description { get; }
+
+    /// <summary>
+    /// Rewrites grammar file content with the edit.
+    /// </summary>
+    /// <param name="content">The content of the grammar file the conflict was found in.</param>
+    /// <returns>The rewritten content.</returns>
+    /// <exception cref="ArgumentException">The content does not define the rule the edit changes.</exception>
+    public string Apply(string content)
+    {
+        ArgumentNullException.ThrowIfNull(content);
+
+        return _apply(content);
+    }
+
+    /// <inheritdoc />
+    public override string ToString()
+    {
+        return Description;
+    }
+}
+
+/// <summary>
+/// The grammar after an edit and whether it did what it was meant to.
+/// </summary>
+/// <param name="Content">The rewritten grammar file content.</param>
+/// <param name="Resolved">Whether the conflict's sentence no longer has an unaccepted ambiguity.</param>
+/// <param name="NewConflicts">The unaccepted conflicts of the rewritten grammar that the original did not have.</param>
+public sealed record ConflictResolutionOutcome(string Content, bool Resolved, IReadOnlyList<GrammarConflict> NewConflicts)
+{
+    /// <summary>
+    /// Gets a value indicating whether the conflict is gone and no other took its place.
+    /// </summary>
+    public bool IsSuccess => Resolved && NewConflicts.Count == 0;
+}
+
+/// <summary>
+/// Finds the ambiguities of a grammar and proposes edits resolving them. The parser is generalized, so a conflict is
+/// not a table entry but a sentence with two derivations: sentences are generated through every alternative, plus one
+/// for every pair of literal operator alternatives of a rule nested in each other, and every ambiguous span of their
+/// parses is a conflict, reported once per pair of alternatives with its shortest example. Edits are text edits of
+/// the grammar file that leave everything else as written, and <see cref="Resolve"/> checks each one by searching
+/// the rewritten grammar again.
+/// </summary>
+public sealed class GrammarConflictAssistant
+{
+    private static readonly Regex RuleLine = new(@"^[ \t]*<[A-Za-z_][A-Za-z0-9_\-]*>\s*::=", RegexOptions.Multiline | RegexOptions.CultureInvariant);
+    private static readonly Regex HeaderLine = new(@"^[A-Za-z][A-Za-z0-9_]*\s*:.*$", RegexOptions.Multiline | RegexOptions.CultureInvariant);
+
+    private IReadOnlyList<GrammarConflict>? _conflicts;
+
+    /// <summary>
+    /// Initializes a new instance of the GrammarConflictAssistant class.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    public GrammarConflictAssistant(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        Grammar = grammar;
+    }
+
+    /// <summary>
+    /// Gets the grammar.
+    /// </summary>
+    public CompiledGrammar Grammar { get; }
+
+    /// <summary>
+    /// Gets or sets the number of sentences generated through every alternative.
+    /// </summary>
+    public int SamplesPerAlternative { get; init; } = 4;
+
+    /// <summary>
+    /// Gets or sets the seed of the sentence generator, so the same grammar always yields the same conflicts.
+    /// </summary>
+    public int Seed { get; init; } = 1;
+
+    /// <summary>
+    /// Finds the grammar's conflicts, including those its <c>// @ambiguous</c> annotations accept.
+    /// </summary>
+    /// <returns>The conflicts, in rule and alternative order.</returns>
+    public IReadOnlyList<GrammarConflict> FindConflicts()
+    {
+        if (_conflicts != null)
+        {
+            return _conflicts;
+        }
+
+        var parser = new GeneralizedParser(Grammar);
+        var generator = new SentenceGenerator(Grammar, new SentenceGeneratorOptions { MaxDepth = 3 });
+        var random = new Random(Seed);
+        var sentences = new List<(string Rule, string Sentence)>();
+        foreach (var alternative in Grammar.Rules.SelectMany(r => r.Alternatives))
+        {
+            for (var i = 0; i < SamplesPerAlternative; i++)
+            {
+                if (generator.GenerateThrough(alternative, random) is { } sentence)
+                {
+                    sentences.Add((Grammar.StartRule, sentence));
+                }
+            }
+        }
+
+        sentences.AddRange(GenerateOperatorSentences(random));
+
+        var found = new Dictionary<string, GrammarConflict>(StringComparer.Ordinal);
+        foreach (var (rule, sentence) in sentences)
+        {
+            var result = parser.Parse(sentence, new ParseOptions { StartRule = rule });
+            if (!result.IsSuccess || result.Forest == null)
+            {
+                continue;
+            }
+
+            foreach (var node in result.Forest.AmbiguousNodes)
+            {
+                var conflict = new GrammarConflict(
+                    rule,
+                    sentence,
+                    SpanText(result, node),
+                    node.Rule,
+                    node.Packed[0].Alternative,
+                    node.Packed[1].Alternative,
+                    Render(node, node.Packed[0]),
+                    Render(node, node.Packed[1]));
+                if (!found.TryGetValue(conflict.Key, out var known) || conflict.Text.Length < known.Text.Length)
+                {
+                    found[conflict.Key] = conflict;
+                }
+            }
+        }
+
+        return _conflicts = found.Values
+            .OrderBy(c => c.Rule.Index)
+            .ThenBy(c => c.First.Index)
+            .ThenBy(c => c.Second.Index)
+            .ThenBy(c => c.Text, StringComparer.Ordinal)
+            .ToList();
+    }
+
+    /// <summary>
+    /// Proposes edits resolving a conflict: precedence declarations when both derivations are operator
+    /// alternatives (see <see cref="GrammarPrecedence"/>) whose relation is not declared yet, left-factoring when the
+    /// two alternatives share a prefix, and always accepting the derivation the parser keeps.
+    /// </summary>
+    /// <param name="conflict">The conflict.</param>
+    /// <returns>The proposed edits.</returns>
+    public IReadOnlyList<ConflictResolution> Propose(GrammarConflict conflict)
+    {
+        ArgumentNullException.ThrowIfNull(conflict);
+
+        var resolutions = new List<ConflictResolution>();
+        resolutions.AddRange(ProposePrecedence(conflict));
+        if (ProposeLeftFactoring(conflict) is { } factoring)
+        {
+            resolutions.Add(factoring);
+        }
+
+        var reason = $"'{conflict.Text}' parses as {conflict.FirstTree}";
+        resolutions.Add(new ConflictResolution(
+            ConflictResolutionKind.AcceptDefault,
+            $"Accept the derivation the parser keeps with // @ambiguous on <{conflict.Rule.Name}>",
+            content => InsertAnnotation(content, conflict.Rule.Name, $"// @ambiguous(\"{reason.Replace("\"", "\\\"")}\")")));
+        return resolutions;
+    }
+
+    /// <summary>
+    /// Applies an edit and checks it: the rewritten grammar is compiled with the same start rule, the conflict's
+    /// sentence is parsed again, and the rewritten grammar is searched for conflicts this one does not have.
+    /// </summary>
+    /// <param name="content">The content of the grammar file the conflict was found in.</param>
+    /// <param name="conflict">The conflict.</param>
+    /// <param name="resolution">The edit.</param>
+    /// <returns>The rewritten content and what the check found.</returns>
+    /// <exception cref="ArgumentException">The rewritten grammar does not compile.</exception>
+    public ConflictResolutionOutcome Resolve(string content, GrammarConflict conflict, ConflictResolution resolution)
+    {
+        ArgumentNullException.ThrowIfNull(content);
+        ArgumentNullException.ThrowIfNull(conflict);
+        ArgumentNullException.ThrowIfNull(resolution);
+
+        var rewritten = resolution.Apply(content);
+        var grammar = CompiledGrammar.Compile(new GrammarFileReader().Read(rewritten), Grammar.StartRule);
+
+        var resolved = false;
+        if (grammar.GetRule(conflict.StartRule) != null)
+        {
+            var result = new GeneralizedParser(grammar).Parse(conflict.Sentence, new ParseOptions { StartRule = conflict.StartRule });
+            resolved = result.IsSuccess && result.Forest!.AmbiguousNodes.All(n => n.Rule.AcceptedAmbiguity != null);
+        }
+
+        var known = FindConflicts().Select(c => c.Key).ToHashSet(StringComparer.Ordinal);
+        var assistant = new GrammarConflictAssistant(grammar) { SamplesPerAlternative = SamplesPerAlternative, Seed = Seed };
+        var added = assistant.FindConflicts().Where(c => !c.IsAccepted && !known.Contains(c.Key)).ToList();
+        return new ConflictResolutionOutcome(rewritten, resolved, added);
+    }
+
+    private IEnumerable<(string Rule, string Sentence)> GenerateOperatorSentences(Random random)
+    {
+        // Every literal operator alternative nested as the right operand of every other, so each pair of operators
+        // meets once: "x + x * x", "- x * x", "x + x !".
+        var shortest = new SentenceGenerator(Grammar, new SentenceGeneratorOptions { MaxDepth = 0 });
+        foreach (var rule in Grammar.Rules)
+        {
+            var operators = rule.Alternatives
+                .Select(a => GrammarPrecedence.TryGetOperator(a, out var op, out var position) && op.Kind == GrammarSymbolKind.Literal ? (Op: op.Name, Position: position) : ((string Op, OperatorFixity Position)?)null)
+                .OfType<(string Op, OperatorFixity Position)>()
+                .ToList();
+            if (operators.Count == 0 || !shortest.CanGenerate(rule.Name))
+            {
+                continue;
+            }
+
+            var operand = shortest.Generate(random, rule.Name);
+            foreach (var outer in operators.Where(o => o.Position != OperatorFixity.Postfix))
+            {
+                foreach (var inner in operators)
+                {
+                    var text = new StringBuilder();
+                    if (outer.Position == OperatorFixity.Infix)
+                    {
+                        text.Append(operand).Append(' ');
+                    }
+
+                    text.Append(outer.Op).Append(' ');
+                    if (inner.Position != OperatorFixity.Prefix)
+                    {
+                        text.Append(operand).Append(' ');
+                    }
+
+                    text.Append(inner.Op);
+                    if (inner.Position != OperatorFixity.Postfix)
+                    {
+                        text.Append(' ').Append(operand);
+                    }
+
+                    yield return (rule.Name, text.ToString());
+                }
+            }
+        }
+    }
+
+    private IEnumerable<ConflictResolution> ProposePrecedence(GrammarConflict conflict)
+    {
+        if (!GrammarPrecedence.TryGetOperator(conflict.First, out var first, out _)
+            || !GrammarPrecedence.TryGetOperator(conflict.Second, out var second, out _))
+        {
+            yield break;
+        }
+
+        var levels = Grammar.Precedence?.Levels.ToList() ?? new List<PrecedenceLevel>();
+        var firstLevel = Grammar.Precedence?.GetLevel(first.Key);
+        var secondLevel = Grammar.Precedence?.GetLevel(second.Key);
+        if (first == second)
+        {
+            if (firstLevel == null)
+            {
+                foreach (var associativity in new[] { OperatorFixity.InfixLeft, OperatorFixity.InfixRight })
+                {
+                    var level = new PrecedenceLevel(associativity, new[] { first });
+                    yield return PrecedenceResolution($"Declare {level} as the tightest precedence level", levels.Append(level));
+                }
+            }
+
+            yield break;
+        }
+
+        if (firstLevel != null && secondLevel != null)
+        {
+            yield break;
+        }
+
+        // One operator binds tighter than the other; an undeclared one is placed next to a declared one, or both are
+        // added as the tightest levels.
+        foreach (var (tighter, looser) in new[] { (first, second), (second, first) })
+        {
+            var tighterLevel = Grammar.Precedence?.GetLevel(tighter.Key);
+            var looserLevel = Grammar.Precedence?.GetLevel(looser.Key);
+            var proposed = levels.ToList();
+            if (looserLevel is { } below)
+            {
+                proposed.Insert(below + 1, new PrecedenceLevel(OperatorFixity.InfixLeft, new[] { tighter }));
+            }
+            else if (tighterLevel is { } above)
+            {
+                proposed.Insert(above, new PrecedenceLevel(OperatorFixity.InfixLeft, new[] { looser }));
+            }
+            else
+            {
+                proposed.Add(new PrecedenceLevel(OperatorFixity.InfixLeft, new[] { looser }));
+                proposed.Add(new PrecedenceLevel(OperatorFixity.InfixLeft, new[] { tighter }));
+            }
+
+            yield return PrecedenceResolution($"Declare that {tighter} binds tighter than {looser}", proposed);
+        }
+    }
+
+    private static ConflictResolution PrecedenceResolution(string description, IEnumerable<PrecedenceLevel> levels)
+    {
+        var header = $"{CompiledGrammar.PrecedenceKey}: {new GrammarPrecedence(levels.ToList())}";
+        return new ConflictResolution(ConflictResolutionKind.Precedence, $"{description} ({header})", content => SetHeader(content, header));
+    }
+
+    private ConflictResolution? ProposeLeftFactoring(GrammarConflict conflict)
+    {
+        var rule = conflict.Rule;
+        var prefix = conflict.First.Symbols.Zip(conflict.Second.Symbols).TakeWhile(p => p.First == p.Second).Count();
+        if (conflict.First == conflict.Second || prefix == 0
+            || Grammar.Source.ProductionRules.Rules.Count(r => r.Name == rule.Name) > 1
+            || rule.Alternatives.Any(a => a.Action != null || a.Feature != null || a.Deprecation != null))
+        {
+            return null;
+        }
+
+        var shared = conflict.First.Symbols.Take(prefix).ToList();
+        var group = rule.Alternatives.Where(a => a.Symbols.Count >= prefix && a.Symbols.Take(prefix).SequenceEqual(shared)).ToList();
+
+        // Tails that another tail already derives add nothing but a second derivation: duplicates, and the empty
+        // tail when another tail is nullable.
+        var tails = new List<IReadOnlyList<GrammarSymbol>>();
+        foreach (var alternative in group)
+        {
+            var tail = alternative.Symbols.Skip(prefix).ToList();
+            if (!tails.Any(t => t.SequenceEqual(tail)))
+            {
+                tails.Add(tail);
+            }
+        }
+
+        if (tails.Any(t => t.Count > 0 && t.All(s => s.Kind == GrammarSymbolKind.Rule && Grammar.IsNullable(Grammar.GetRule(s.Name)!))))
+        {
+            tails.RemoveAll(t => t.Count == 0);
+        }
+
+        var tailRule = rule.Name + "_tail";
+        for (var n = 2; Grammar.GetRule(tailRule) != null; n++)
+        {
+            tailRule = $"{rule.Name}_tail{n}";
+        }
+
+        var factored = tails.Count == 1
+            ? Write(shared.Concat(tails[0]))
+            : Write(shared.Append(new GrammarSymbol(GrammarSymbolKind.Rule, tailRule)));
+        var description = tails.Count == 1
+            ? $"Left-factor the alternatives of <{rule.Name}> starting with {Write(shared)} into {factored}"
+            : $"Left-factor the alternatives of <{rule.Name}> starting with {Write(shared)} into {factored} with <{tailRule}> ::= {string.Join(" | ", tails.Select(Write))}";
+
+        return new ConflictResolution(ConflictResolutionKind.LeftFactor, description, content =>
+        {
+            if (!GrammarFileReader.FindAlternativeRanges(content).TryGetValue(rule.Name, out var ranges) || ranges.Count != rule.Alternatives.Count)
+            {
+                throw new ArgumentException($"The content does not define the {rule.Alternatives.Count} alternatives of <{rule.Name}>", nameof(content));
+            }
+
+            var alternatives = new List<string>();
+            foreach (var alternative in rule.Alternatives)
+            {
+                if (alternative == group[0])
+                {
+                    alternatives.Add(factored);
+                }
+                else if (!group.Contains(alternative))
+                {
+                    alternatives.Add(content.Substring(ranges[alternative.Index].Start, ranges[alternative.Index].Length));
+                }
+            }
+
+            // The tail rule goes before the next rule, after the annotations of this one.
+            var rewritten = content;
+            if (tails.Count > 1)
+            {
+                var definition = $"<{tailRule}> ::= {string.Join(" | ", tails.Select(Write))}\n";
+                var next = RuleLine.Match(content, ranges[ranges.Count - 1].End);
+                rewritten = next.Success
+                    ? rewritten.Insert(next.Index, definition + "\n")
+                    : rewritten.TrimEnd('\n') + "\n\n" + definition;
+            }
+
+            return rewritten[..ranges[0].Start] + string.Join(" | ", alternatives) + rewritten[ranges[ranges.Count - 1].End..];
+        });
+    }
+
+    private static string Write(IEnumerable<GrammarSymbol> symbols)
+    {
+        var text = string.Join(" ", symbols);
+        return text.Length > 0 ? text : "ε";
+    }
+
+    private static string SetHeader(string content, string header)
+    {
+        var firstRule = RuleLine.Match(content);
+        var end = firstRule.Success ? firstRule.Index : content.Length;
+        var headers = HeaderLine.Matches(content[..end]);
+        var existing = headers.FirstOrDefault(h => Regex.IsMatch(h.Value, $@"^{CompiledGrammar.PrecedenceKey}\s*:"));
+        if (existing != null)
+        {
+            var lineEnd = existing.Value.EndsWith('\r') ? "\r" : string.Empty;
+            return content[..existing.Index] + header + lineEnd + content[(existing.Index + existing.Length)..];
+        }
+
+        var at = headers.Count > 0 ? headers[^1].Index + headers[^1].Length + 1 : 0;
+        return content.Insert(Math.Min(at, content.Length), header + "\n");
+    }
+
+    private static string InsertAnnotation(string content, string rule, string annotation)
+    {
+        if (!GrammarFileReader.FindAlternativeRanges(content).TryGetValue(rule, out var ranges) || ranges.Count == 0)
+        {
+            throw new ArgumentException($"The content does not define <{rule}>", nameof(content));
+        }
+
+        // An annotation right after the line a rule starts on annotates the whole rule.
+        var lineEnd = content.IndexOf('\n', ranges[0].Start);
+        return lineEnd < 0 ? content + "\n" + annotation + "\n" : content.Insert(lineEnd + 1, annotation + "\n");
+    }
+
+    private string SpanText(ParseResult result, SymbolForestNode node)
+    {
+        if (Grammar.IsScannerless)
+        {
+            return result.Input[node.Start..node.End];
+        }
+
+        return node.Start == node.End ? string.Empty : result.Input[result.Tokens[node.Start].Offset..result.Tokens[node.End - 1].End];
+    }
+
+    private static string Render(SymbolForestNode node, PackedForestNode packed)
+    {
+        var text = new StringBuilder("(").Append(node.RuleName);
+        foreach (var child in packed.Children)
+        {
+            text.Append(' ').Append(Render(child));
+        }
+
+        return text.Append(')').ToString();
+    }
+
+    private static string Render(ForestNode node)
+    {
+        // Nodes deriving a single token are written as the token, so the trees stay readable.
+        return node switch
+        {
+            TokenForestNode leaf => leaf.Token.Text,
+            SymbolForestNode symbol when symbol.Packed.Count == 1 && symbol.Packed[0].Children.Count == 1 && symbol.Packed[0].Children[0] is TokenForestNode only => only.Token.Text,
+            SymbolForestNode symbol => Render(symbol, symbol.Packed[0]),
+            _ => string.Empty
+        };
+    }
+}
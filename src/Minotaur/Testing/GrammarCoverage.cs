@@ -0,0 +1,233 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// An alternative that no corpus input uses.
+/// </summary>
+/// <param name="Alternative">The alternative.</param>
+/// <param name="Span">Where the alternative is written in the grammar file, if known.</param>
+/// <param name="SuggestedInput">A generated input whose parse uses the alternative, if one was found.</param>
+public sealed record UncoveredAlternative(CompiledAlternative Alternative, SourcePosition? Span, string? SuggestedInput);
+
+/// <summary>
+/// The result of checking a grammar's coverage against its manifest.
+/// </summary>
+public sealed class CoverageReport
+{
+    /// <summary>
+    /// Gets the number of alternatives that count towards the percentage.
+    /// </summary>
+    public int Total { get; init; }
+
+    /// <summary>
+    /// Gets the number of those alternatives that at least one input uses.
+    /// </summary>
+    public int Covered { get; init; }
+
+    /// <summary>
+    /// Gets the percentage of alternatives covered; 100 if no alternative counts.
+    /// </summary>
+    public double Percentage => Total == 0 ? 100 : 100.0 * Covered / Total;
+
+    /// <summary>
+    /// Gets the percentage the manifest requires.
+    /// </summary>
+    public double MinimumPercentage { get; init; }
+
+    /// <summary>
+    /// Gets the uncovered alternatives outside the manifest's allowed rules, in grammar order.
+    /// </summary>
+    public IReadOnlyList<UncoveredAlternative> Uncovered { get; init; } = Array.Empty<UncoveredAlternative>();
+
+    /// <summary>
+    /// Gets a value indicating whether the coverage reaches the required percentage.
+    /// </summary>
+    public bool IsSatisfied => Percentage >= MinimumPercentage;
+}
+
+/// <summary>
+/// Counts how often the parses of a set of inputs use each alternative of a grammar. Every alternative packed in a
+/// parse forest counts, so an ambiguous input covers all of its derivations.
+/// </summary>
+public sealed class GrammarCoverage
+{
+    private const int SuggestionAttempts = 16;
+
+    private readonly Dictionary<CompiledAlternative, int> _counts = new();
+
+    /// <summary>
+    /// Initializes a new instance of the GrammarCoverage class with no inputs recorded.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    public GrammarCoverage(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        Grammar = grammar;
+    }
+
+    /// <summary>
+    /// Gets the grammar whose coverage is counted.
+    /// </summary>
+    public CompiledGrammar Grammar { get; }
+
+    /// <summary>
+    /// Gets the number of parses recorded.
+    /// </summary>
+    public int InputCount { get; private set; }
+
+    /// <summary>
+    /// Measures the coverage of the inputs in a corpus directory. Inputs expected to fail count for the part of
+    /// the forest the parser built.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="corpusDirectory">The corpus directory.</param>
+    /// <returns>The coverage.</returns>
+    public static GrammarCoverage Measure(CompiledGrammar grammar, string corpusDirectory)
+    {
+        var coverage = new GrammarCoverage(grammar);
+        var parser = new GeneralizedParser(grammar);
+        foreach (var corpusCase in RegressionCorpus.GetCases(corpusDirectory))
+        {
+            coverage.Record(parser.Parse(File.ReadAllText(corpusCase.InputPath)));
+        }
+
+        return coverage;
+    }
+
+    /// <summary>
+    /// Records the alternatives used by a parse.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    public void Record(ParseResult result)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+
+        InputCount++;
+        foreach (var packed in result.Forest?.Nodes.SelectMany(n => n.Packed) ?? Enumerable.Empty<PackedForestNode>())
+        {
+            _counts[packed.Alternative] = _counts.GetValueOrDefault(packed.Alternative) + 1;
+        }
+    }
+
+    /// <summary>
+    /// Gets how many times the recorded parses used an alternative.
+    /// </summary>
+    /// <param name="alternative">The alternative.</param>
+    /// <returns>The number of forest nodes deriving the alternative.</returns>
+    public int GetCount(CompiledAlternative alternative)
+    {
+        return _counts.GetValueOrDefault(alternative);
+    }
+
+    /// <summary>
+    /// Gets the alternatives no recorded parse used, in grammar order.
+    /// </summary>
+    /// <returns>The uncovered alternatives.</returns>
+    public IReadOnlyList<CompiledAlternative> GetUncovered()
+    {
+        return Grammar.Rules.SelectMany(r => r.Alternatives).Where(a => !_counts.ContainsKey(a)).ToList();
+    }
+
+    /// <summary>
+    /// Checks the coverage against a manifest, locating each uncovered alternative in the grammar file and
+    /// suggesting an input that would cover it.
+    /// </summary>
+    /// <param name="manifest">The coverage manifest.</param>
+    /// <param name="grammarPath">The grammar file, used to locate alternatives; null to leave spans out.</param>
+    /// <returns>The report.</returns>
+    public CoverageReport Check(CoverageManifest manifest, string? grammarPath = null)
+    {
+        ArgumentNullException.ThrowIfNull(manifest);
+
+        var counted = Grammar.Rules
+            .Where(r => !manifest.AllowedRules.Contains(r.Name))
+            .SelectMany(r => r.Alternatives)
+            .ToList();
+
+        IReadOnlyDictionary<string, IReadOnlyList<TextRange>>? ranges = null;
+        LineIndex? lines = null;
+        if (grammarPath != null && File.Exists(grammarPath))
+        {
+            var text = File.ReadAllText(grammarPath);
+            ranges = GrammarFileReader.FindAlternativeRanges(text);
+            lines = new LineIndex(text);
+        }
+
+        var generator = new SentenceGenerator(Grammar);
+        var uncovered = counted
+            .Where(a => !_counts.ContainsKey(a))
+            .Select(a =>
+            {
+                var span = ranges != null && ranges.TryGetValue(a.Rule.Name, out var rule) && a.Index < rule.Count
+                    ? lines!.GetPosition(rule[a.Index].Start, rule[a.Index].Length, grammarPath)
+                    : null;
+                return new UncoveredAlternative(a, span, SuggestInput(Grammar, generator, a));
+            })
+            .ToList();
+
+        return new CoverageReport
+        {
+            Total = counted.Count,
+            Covered = counted.Count - uncovered.Count,
+            MinimumPercentage = manifest.MinimumPercentage,
+            Uncovered = uncovered
+        };
+    }
+
+    /// <summary>
+    /// Generates an input whose parse uses an alternative. Candidates come from
+    /// <see cref="SentenceGenerator.GenerateThrough"/> and are only returned if they parse and their forest contains
+    /// the alternative.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="alternative">The alternative to cover.</param>
+    /// <returns>The input, or null if none was found.</returns>
+    public static string? SuggestInput(CompiledGrammar grammar, CompiledAlternative alternative)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        ArgumentNullException.ThrowIfNull(alternative);
+
+        return SuggestInput(grammar, new SentenceGenerator(grammar), alternative);
+    }
+
+    private static string? SuggestInput(CompiledGrammar grammar, SentenceGenerator generator, CompiledAlternative alternative)
+    {
+        var parser = new GeneralizedParser(grammar);
+        for (var seed = 0; seed < SuggestionAttempts; seed++)
+        {
+            var input = generator.GenerateThrough(alternative, new Random(seed));
+            if (input == null)
+            {
+                return null;
+            }
+
+            var result = parser.Parse(input);
+            if (result.IsSuccess && result.Forest!.Nodes.Any(n => n.Packed.Any(p => p.Alternative == alternative)))
+            {
+                return input;
+            }
+        }
+
+        return null;
+    }
+}
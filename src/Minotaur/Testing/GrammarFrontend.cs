@@ -0,0 +1,164 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Summarizes files with a Minotaur grammar. Items are the nodes of rules listed in the grammar's
+/// "DifferentialItems" metadata (<c>DifferentialItems: function=fn, struct=struct</c>), which maps rule names
+/// to the reference frontend's item kinds; an item's name is its first identifier outside nested items.
+/// Identifiers are IDENTIFIER tokens, or the nodes of the token kind or rule named by the grammar's
+/// "DifferentialIdentifier" metadata.
+/// </summary>
+public sealed class GrammarFrontend : IDifferentialFrontend
+{
+    /// <summary>
+    /// The metadata key mapping rule names to item kinds.
+    /// </summary>
+    public const string ItemsKey = "DifferentialItems";
+
+    /// <summary>
+    /// The metadata key naming the token kind or rule that identifiers are.
+    /// </summary>
+    public const string IdentifierKey = "DifferentialIdentifier";
+
+    private readonly GeneralizedParser _parser;
+    private readonly IReadOnlyDictionary<string, string> _itemKinds;
+    private readonly string _identifier;
+
+    /// <summary>
+    /// Initializes a new instance of the GrammarFrontend class.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <param name="itemKinds">Rule names mapped to item kinds, or null to read them from the grammar's metadata.</param>
+    public GrammarFrontend(CompiledGrammar grammar, IReadOnlyDictionary<string, string>? itemKinds = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        _parser = new GeneralizedParser(grammar);
+        _itemKinds = itemKinds ?? ParseItemKinds(grammar.Source.Metadata.GetValueOrDefault(ItemsKey) ?? string.Empty);
+        _identifier = grammar.Source.Metadata.GetValueOrDefault(IdentifierKey) ?? "IDENTIFIER";
+    }
+
+    /// <inheritdoc />
+    public string Name => "minotaur";
+
+    /// <summary>
+    /// Parses an item mapping of the form "rule=kind, rule=kind".
+    /// </summary>
+    /// <param name="mapping">The mapping text.</param>
+    /// <returns>Rule names mapped to item kinds.</returns>
+    /// <exception cref="FormatException">An entry is not of the form "rule=kind".</exception>
+    public static IReadOnlyDictionary<string, string> ParseItemKinds(string mapping)
+    {
+        ArgumentNullException.ThrowIfNull(mapping);
+
+        var kinds = new Dictionary<string, string>(StringComparer.Ordinal);
+        foreach (var entry in mapping.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var parts = entry.Split('=', StringSplitOptions.TrimEntries);
+            if (parts.Length != 2 || parts[0].Length == 0 || parts[1].Length == 0)
+            {
+                throw new FormatException($"Invalid item mapping '{entry}'; expected rule=kind");
+            }
+
+            kinds[parts[0]] = parts[1];
+        }
+
+        return kinds;
+    }
+
+    /// <inheritdoc />
+    public FrontendSummary Summarize(string path, string source)
+    {
+        ArgumentNullException.ThrowIfNull(source);
+
+        var result = _parser.Parse(source, new ParseOptions { SourceFile = path });
+        if (result.Tree == null)
+        {
+            return FrontendSummary.Failed(result.Diagnostics.FirstOrDefault()?.ToString() ?? "Parse failed");
+        }
+
+        var items = new List<SummaryItem>();
+        var identifiers = new HashSet<string>(StringComparer.Ordinal);
+        Collect(result.Tree, source, items, identifiers);
+        return new FrontendSummary { Items = items, Identifiers = identifiers };
+    }
+
+    private void Collect(CognitiveGraphNode node, string source, List<SummaryItem> items, HashSet<string> identifiers)
+    {
+        if (node is NonTerminalNode rule && _itemKinds.TryGetValue(rule.RuleName, out var kind))
+        {
+            var position = node.SourcePosition;
+            items.Add(new SummaryItem(kind, FindName(node, source), position?.Line ?? 0, position?.Column ?? 0));
+        }
+
+        if (IsIdentifier(node))
+        {
+            identifiers.Add(TextOf(node, source));
+            return;
+        }
+
+        foreach (var child in node.Children)
+        {
+            Collect(child, source, items, identifiers);
+        }
+    }
+
+    private string? FindName(CognitiveGraphNode item, string source)
+    {
+        foreach (var child in item.Children)
+        {
+            if (IsIdentifier(child))
+            {
+                return TextOf(child, source);
+            }
+
+            if (child is NonTerminalNode nested && _itemKinds.ContainsKey(nested.RuleName))
+            {
+                continue;
+            }
+
+            if (FindName(child, source) is { } name)
+            {
+                return name;
+            }
+        }
+
+        return null;
+    }
+
+    private bool IsIdentifier(CognitiveGraphNode node)
+    {
+        return node switch
+        {
+            TerminalNode terminal => terminal.TokenType == _identifier,
+            NonTerminalNode rule => rule.RuleName == _identifier,
+            _ => false
+        };
+    }
+
+    private static string TextOf(CognitiveGraphNode node, string source)
+    {
+        return node is TerminalNode terminal
+            ? terminal.Text
+            : node.SourcePosition is { } position ? source.Substring(position.Offset, position.Length) : string.Empty;
+    }
+}
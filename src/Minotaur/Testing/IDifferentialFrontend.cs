@@ -0,0 +1,37 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// A parser frontend that can summarize source files for differential testing.
+/// </summary>
+public interface IDifferentialFrontend
+{
+    /// <summary>
+    /// Gets the frontend name used in reports, such as "minotaur" or "syn".
+    /// </summary>
+    string Name { get; }
+
+    /// <summary>
+    /// Summarizes a source file.
+    /// </summary>
+    /// <param name="path">The file path.</param>
+    /// <param name="source">The file content.</param>
+    /// <returns>The summary, or a failed summary if the file could not be processed.</returns>
+    FrontendSummary Summarize(string path, string source);
+}
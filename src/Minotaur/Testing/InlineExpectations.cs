@@ -0,0 +1,816 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Text;
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// What an <see cref="InlineExpectation"/> checks.
+/// </summary>
+public enum InlineExpectationKind
+{
+    /// <summary>
+    /// A node of the given kind spans the marked text, or starts on the line.
+    /// </summary>
+    Node,
+
+    /// <summary>
+    /// A diagnostic of the given severity and code is reported at the marked text, or on the line.
+    /// </summary>
+    Diagnostic,
+
+    /// <summary>
+    /// No diagnostic of the given severity, and code if one is given, is reported at the marked text, or on the line.
+    /// </summary>
+    NoDiagnostic
+}
+
+/// <summary>
+/// An expectation read from an annotation comment of an annotated source file.
+/// </summary>
+/// <param name="Kind">What is checked.</param>
+/// <param name="Value">The node kind, the diagnostic code, or null for a <see cref="InlineExpectationKind.NoDiagnostic"/>
+/// expectation about every code.</param>
+/// <param name="Severity">The severity of the diagnostic, or null for a node expectation.</param>
+/// <param name="Line">The 1-based line the expectation is about.</param>
+/// <param name="Start">The offset of the first character the carets mark, or null if the whole line is meant.</param>
+/// <param name="End">The offset just past the last character the carets mark, or null if the whole line is meant.</param>
+/// <param name="Annotation">Where the annotation comment is.</param>
+public sealed record InlineExpectation(
+    InlineExpectationKind Kind,
+    string? Value,
+    DiagnosticSeverity? Severity,
+    int Line,
+    int? Start,
+    int? End,
+    SourcePosition Annotation);
+
+/// <summary>
+/// An inline expectation that was not met, a malformed annotation, or an error no annotation expects.
+/// </summary>
+/// <param name="Location">The text the expectation is about, or where the unexpected error is.</param>
+/// <param name="AnnotationLine">The line of the annotation, or null for an unexpected error.</param>
+/// <param name="Message">What was expected and what was found.</param>
+public sealed record InlineMismatch(SourcePosition Location, int? AnnotationLine, string Message)
+{
+    /// <summary>
+    /// Returns the mismatch as a compiler-style message.
+    /// </summary>
+    /// <returns>"file:line:column: message".</returns>
+    public override string ToString()
+    {
+        var text = new StringBuilder();
+        if (Location.SourceFile != null)
+        {
+            text.Append(Location.SourceFile).Append(':');
+        }
+
+        text.Append(Location.Line).Append(':').Append(Location.Column).Append(": ").Append(Message);
+        if (AnnotationLine != null)
+        {
+            text.Append(" (annotation on line ").Append(AnnotationLine).Append(')');
+        }
+
+        return text.ToString();
+    }
+}
+
+/// <summary>
+/// The outcome of checking an annotated source file.
+/// </summary>
+public sealed class InlineExpectationReport
+{
+    internal InlineExpectationReport(string? path, IReadOnlyList<InlineExpectation> expectations, IReadOnlyList<InlineMismatch> mismatches, string? blessed)
+    {
+        Path = path;
+        Expectations = expectations;
+        Mismatches = mismatches;
+        Blessed = blessed;
+    }
+
+    /// <summary>
+    /// Gets the file checked, or null if the input did not come from a file.
+    /// </summary>
+    public string? Path { get; }
+
+    /// <summary>
+    /// Gets the expectations read from the annotations, in source order.
+    /// </summary>
+    public IReadOnlyList<InlineExpectation> Expectations { get; }
+
+    /// <summary>
+    /// Gets the unmet expectations, malformed annotations and unexpected errors, in source order.
+    /// </summary>
+    public IReadOnlyList<InlineMismatch> Mismatches { get; }
+
+    /// <summary>
+    /// Gets the input with its annotations rewritten from the actual results when blessing, otherwise null.
+    /// </summary>
+    public string? Blessed { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether every expectation was met and every error was expected.
+    /// </summary>
+    public bool IsSuccess => Mismatches.Count == 0;
+
+    /// <summary>
+    /// Describes the mismatches, one per line.
+    /// </summary>
+    /// <returns>The description.</returns>
+    public override string ToString()
+    {
+        var text = new StringBuilder($"{Path ?? "input"}: {Expectations.Count} expectations, {Mismatches.Count} mismatches\n");
+        foreach (var mismatch in Mismatches)
+        {
+            text.Append(mismatch).Append('\n');
+        }
+
+        return text.ToString();
+    }
+}
+
+/// <summary>
+/// Options for checking annotated source files.
+/// </summary>
+public class InlineExpectationOptions
+{
+    /// <summary>
+    /// Gets or sets the comment prefix that introduces an annotation. The grammar must declare comments it starts.
+    /// </summary>
+    public string Prefix { get; set; } = "//";
+
+    /// <summary>
+    /// Gets or sets the number of columns between tab stops used to line carets up with the annotated line.
+    /// </summary>
+    public int TabWidth { get; set; } = LineIndex.DefaultTabWidth;
+
+    /// <summary>
+    /// Gets or sets a value indicating whether annotations are rewritten from the actual results, like the
+    /// <c>--bless</c> flag of compiler UI tests; on by default when snapshots are being updated.
+    /// </summary>
+    public bool Bless { get; set; } = Snapshot.IsUpdating;
+
+    /// <summary>
+    /// Gets or sets the options for each parse, such as the start rule.
+    /// </summary>
+    public ParseOptions? ParseOptions { get; set; }
+}
+
+/// <summary>
+/// Checks source files that carry their expected parse in comments, so a grammar can be tested without a corpus of
+/// snapshots. Annotations are comments the grammar declares, introduced by <see cref="InlineExpectationOptions.Prefix"/>
+/// and read with the directive framework:
+/// <code>
+/// total + "名前";
+/// //    ^ expect: binary_expr
+/// //      ^^^^^^ expect: primary
+/// total + ;  // error: E0001
+/// // no-warning: W0002
+/// </code>
+/// Carets mark the characters above them, which a node of the expected kind must span or the expected diagnostic
+/// must start in; without carets an annotation is about a whole line, where the node must start or the diagnostic
+/// be reported. The keywords are <c>expect</c>, <c>error</c>, <c>warning</c>, <c>info</c> and <c>hint</c>, the
+/// diagnostic ones optionally preceded by <c>no-</c> to expect their absence, of any code if none is given. An
+/// annotation on a line of its own is about the nearest line above that is not an annotation. Carets are lined up
+/// by what an editor shows: tabs advance to the next tab stop, a character made of several code points counts once,
+/// and East Asian wide characters and emoji take two columns. Every error must be expected by an annotation; other
+/// diagnostics are only checked where annotated. A diagnostic on a line holding nothing but annotations or
+/// whitespace, such as a missing token at the end of the input, counts as being just past the token before it.
+/// </summary>
+public static class InlineExpectations
+{
+    private static readonly Regex AnnotationStart = new(@"^(?:\^|(?:no-)?(?:expect|error|warning|info|hint)\s*:)", RegexOptions.CultureInvariant);
+    private static readonly Regex AnnotationPattern = new(@"^(?<carets>\^+)?\s*(?<negated>no-)?(?<keyword>expect|error|warning|info|hint)\s*:\s*(?<value>\S+)?$", RegexOptions.CultureInvariant);
+
+    /// <summary>
+    /// Parses an annotated input and checks it against its annotations.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="input">The annotated input.</param>
+    /// <param name="options">The options, or null for the defaults.</param>
+    /// <returns>The report.</returns>
+    public static InlineExpectationReport Check(CompiledGrammar grammar, string input, InlineExpectationOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        ArgumentNullException.ThrowIfNull(input);
+
+        options ??= new InlineExpectationOptions();
+        return Check(new GeneralizedParser(grammar).Parse(input, options.ParseOptions), options, null);
+    }
+
+    /// <summary>
+    /// Checks a parse of an annotated input against its annotations.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    /// <param name="options">The options, or null for the defaults.</param>
+    /// <returns>The report.</returns>
+    public static InlineExpectationReport Check(ParseResult result, InlineExpectationOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+        return Check(result, options ?? new InlineExpectationOptions(), null);
+    }
+
+    /// <summary>
+    /// Checks an annotated file, rewriting its annotations when blessing.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="path">The file.</param>
+    /// <param name="options">The options, or null for the defaults.</param>
+    /// <returns>The report; when blessing, the mismatches are those the file had before it was rewritten.</returns>
+    public static InlineExpectationReport RunFile(CompiledGrammar grammar, string path, InlineExpectationOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        return RunFile(new GeneralizedParser(grammar), path, options ?? new InlineExpectationOptions());
+    }
+
+    /// <summary>
+    /// Checks every annotated file in a directory, rewriting their annotations when blessing.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="directory">The directory.</param>
+    /// <param name="searchPattern">The pattern the file names match, e.g. <c>*.toy</c>.</param>
+    /// <param name="options">The options, or null for the defaults.</param>
+    /// <returns>The number of files checked.</returns>
+    /// <exception cref="SnapshotMismatchException">Not blessing, and one or more files have mismatches; the message
+    /// lists all of them.</exception>
+    public static int Verify(CompiledGrammar grammar, string directory, string searchPattern = "*", InlineExpectationOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        ArgumentNullException.ThrowIfNull(directory);
+
+        options ??= new InlineExpectationOptions();
+        var parser = new GeneralizedParser(grammar);
+        var paths = Directory.EnumerateFiles(directory, searchPattern).OrderBy(p => p, StringComparer.Ordinal).ToList();
+        var failures = paths.Select(p => RunFile(parser, p, options)).Where(r => !r.IsSuccess).ToList();
+        if (failures.Count > 0 && !options.Bless)
+        {
+            throw new SnapshotMismatchException(
+                $"{failures.Count} of {paths.Count} annotated files failed; run with {Snapshot.UpdateVariable}=1 to bless them.\n{string.Concat(failures)}",
+                directory);
+        }
+
+        return paths.Count;
+    }
+
+    /// <summary>
+    /// Gets the 1-based column at which a character of a line is shown.
+    /// </summary>
+    /// <param name="line">The line, without its terminator.</param>
+    /// <param name="index">The index of the character; the line's length for the column just past its end.</param>
+    /// <param name="tabWidth">The number of columns between tab stops.</param>
+    /// <returns>The display column.</returns>
+    public static int GetDisplayColumn(string line, int index, int tabWidth = LineIndex.DefaultTabWidth)
+    {
+        ArgumentNullException.ThrowIfNull(line);
+
+        var column = 1;
+        foreach (var (start, _, width) in GetTextElements(line, tabWidth))
+        {
+            if (start >= index)
+            {
+                break;
+            }
+
+            column += width;
+        }
+
+        return column;
+    }
+
+    /// <summary>
+    /// Finds the characters of a line shown in a range of display columns.
+    /// </summary>
+    /// <param name="line">The line, without its terminator.</param>
+    /// <param name="column">The 1-based display column of the first caret.</param>
+    /// <param name="width">The number of carets.</param>
+    /// <param name="tabWidth">The number of columns between tab stops.</param>
+    /// <returns>The index of the first character shown in the range and the index just past the last, covering whole
+    /// characters; the line's length twice for the column just past its end; or null if the range starts further
+    /// out.</returns>
+    public static (int Start, int End)? FindDisplayColumns(string line, int column, int width, int tabWidth = LineIndex.DefaultTabWidth)
+    {
+        ArgumentNullException.ThrowIfNull(line);
+
+        int? start = null;
+        var end = 0;
+        var current = 1;
+        foreach (var (index, length, elementWidth) in GetTextElements(line, tabWidth))
+        {
+            var next = current + elementWidth;
+            if (next > column && current < column + Math.Max(width, 1))
+            {
+                start ??= index;
+                end = index + length;
+            }
+
+            current = next;
+        }
+
+        if (start != null)
+        {
+            return (start.Value, end);
+        }
+
+        return column == current ? (line.Length, line.Length) : null;
+    }
+
+    private static InlineExpectationReport RunFile(GeneralizedParser parser, string path, InlineExpectationOptions options)
+    {
+        ArgumentNullException.ThrowIfNull(path);
+
+        var input = File.ReadAllText(path);
+        var report = Check(parser.Parse(input, options.ParseOptions ?? new ParseOptions { SourceFile = path }), options, path);
+        if (report.Blessed != null && report.Blessed != input)
+        {
+            File.WriteAllText(path, report.Blessed);
+        }
+
+        return report;
+    }
+
+    private static InlineExpectationReport Check(ParseResult result, InlineExpectationOptions options, string? path)
+    {
+        if (options.TabWidth < 1)
+        {
+            throw new ArgumentOutOfRangeException(nameof(options), options.TabWidth, "Tab width must be at least 1.");
+        }
+
+        var sourceFile = path ?? result.Tree?.SourcePosition?.SourceFile;
+        var lineIndex = new LineIndex(result.Input);
+        var tokens = result.Tokens.Where(t => !t.IsSynthetic).ToList();
+        var mismatches = new List<InlineMismatch>();
+        var annotations = ReadAnnotations(result, options, lineIndex, sourceFile, mismatches, out var annotationLines);
+
+        // Expectations are checked in order, each diagnostic one taking the first diagnostic it matches.
+        var diagnostics = result.Diagnostics
+            .Select(d => (Diagnostic: d, Offset: GetEffectiveOffset(d, lineIndex, tokens, annotationLines)))
+            .ToList();
+        var taken = new bool[diagnostics.Count];
+        var nodes = result.Tree != null ? CollectNodes(result.Tree) : new List<CognitiveGraphNode>();
+        var blessings = new List<(Annotation Annotation, string? Kind)>();
+        foreach (var annotation in annotations)
+        {
+            var expectation = annotation.Expectation;
+            var location = GetLocation(expectation, lineIndex, sourceFile);
+            var at = expectation.Start != null ? $"{location.Line}:{location.Column}" : $"line {expectation.Line}";
+            if (expectation.Kind == InlineExpectationKind.Node && result.Tree == null)
+            {
+                // Without a tree there is nothing to correct the expectation to, so blessing keeps it.
+                mismatches.Add(new InlineMismatch(location, expectation.Annotation.Line, $"expected {expectation.Value} at {at}, but the parse produced no tree"));
+            }
+            else if (expectation.Kind == InlineExpectationKind.Node)
+            {
+                var kinds = GetNodes(expectation, nodes).Select(GetKind).Distinct().ToList();
+                if (!kinds.Contains(expectation.Value!))
+                {
+                    var found = kinds.Count > 0 ? string.Join(", ", kinds) : "no node";
+                    mismatches.Add(new InlineMismatch(location, expectation.Annotation.Line, $"expected {expectation.Value} at {at}, found {found}"));
+                    blessings.Add((annotation, GetNodes(expectation, nodes).OfType<NonTerminalNode>().Select(GetKind).FirstOrDefault()));
+                }
+            }
+            else if (expectation.Kind == InlineExpectationKind.Diagnostic)
+            {
+                var index = Enumerable.Range(0, diagnostics.Count)
+                    .FirstOrDefault(i => !taken[i] && Matches(expectation, diagnostics[i].Diagnostic, diagnostics[i].Offset, lineIndex), -1);
+                if (index >= 0)
+                {
+                    taken[index] = true;
+                    continue;
+                }
+
+                var codes = diagnostics.Where(d => IsAt(expectation, d.Offset, lineIndex)).Select(d => d.Diagnostic.Code).ToList();
+                var found = codes.Count > 0 ? string.Join(", ", codes) : "none";
+                mismatches.Add(new InlineMismatch(location, expectation.Annotation.Line, $"expected {Keyword(expectation.Severity!.Value)} {expectation.Value} at {at}, found {found}"));
+                blessings.Add((annotation, null));
+            }
+            else
+            {
+                var code = expectation.Value != null ? $" {expectation.Value}" : string.Empty;
+                var reported = Enumerable.Range(0, diagnostics.Count)
+                    .Where(i => Matches(expectation, diagnostics[i].Diagnostic, diagnostics[i].Offset, lineIndex))
+                    .ToList();
+                foreach (var i in reported)
+                {
+                    taken[i] = true;
+                    var diagnostic = diagnostics[i].Diagnostic;
+                    mismatches.Add(new InlineMismatch(location, expectation.Annotation.Line,
+                        $"expected no {Keyword(expectation.Severity!.Value)}{code} at {at}, found {diagnostic.Code}: {diagnostic.Message}"));
+                }
+
+                if (reported.Count > 0)
+                {
+                    blessings.Add((annotation, null));
+                }
+            }
+        }
+
+        var unexpected = new List<(int Offset, Diagnostic Diagnostic)>();
+        for (var i = 0; i < diagnostics.Count; i++)
+        {
+            var (diagnostic, offset) = diagnostics[i];
+            if (!taken[i] && diagnostic.Severity == DiagnosticSeverity.Error)
+            {
+                unexpected.Add((offset, diagnostic));
+                mismatches.Add(new InlineMismatch(
+                    lineIndex.GetPosition(diagnostic.Location?.Offset ?? offset, diagnostic.Location?.Length ?? 0, sourceFile),
+                    null,
+                    $"unexpected error {diagnostic.Code}: {diagnostic.Message}"));
+            }
+        }
+
+        mismatches.Sort((a, b) => a.Location.Offset != b.Location.Offset
+            ? a.Location.Offset.CompareTo(b.Location.Offset)
+            : (a.AnnotationLine ?? 0).CompareTo(b.AnnotationLine ?? 0));
+        var blessed = options.Bless ? Bless(lineIndex, options, annotationLines, blessings, unexpected) : null;
+        return new InlineExpectationReport(path, annotations.Select(a => a.Expectation).ToList(), mismatches, blessed);
+    }
+
+    private static List<Annotation> ReadAnnotations(
+        ParseResult result,
+        InlineExpectationOptions options,
+        LineIndex lineIndex,
+        string? sourceFile,
+        List<InlineMismatch> mismatches,
+        out HashSet<int> annotationLines)
+    {
+        var annotations = new List<Annotation>();
+        annotationLines = new HashSet<int>();
+        if (result.Grammar == null)
+        {
+            return annotations;
+        }
+
+        var syntax = DirectiveSyntax.ForPrefix(options.Prefix);
+        var tokens = result.Tokens.Where(t => !t.IsSynthetic).ToList();
+        var found = new List<(int Offset, string Text, string Body, bool OwnLine)>();
+        foreach (var (offset, text) in DirectiveSet.ReadComments(result.Grammar, result.Input, tokens))
+        {
+            var body = syntax.Match(text).FirstOrDefault();
+            if (body != null && AnnotationStart.IsMatch(body))
+            {
+                var (line, column) = lineIndex.GetLineColumn(offset);
+                found.Add((offset, text, body, string.IsNullOrWhiteSpace(lineIndex.GetLineText(line)[..(column - 1)])));
+            }
+        }
+
+        annotationLines.UnionWith(found.Where(f => f.OwnLine).Select(f => lineIndex.GetLineColumn(f.Offset).Line));
+        foreach (var (offset, text, body, ownLine) in found)
+        {
+            var position = lineIndex.GetPosition(offset, text.TrimEnd().Length, sourceFile);
+            var match = AnnotationPattern.Match(body);
+            string? problem = null;
+            if (!match.Success)
+            {
+                problem = "is not '[^] keyword: value'";
+            }
+            else if (match.Groups["keyword"].Value == "expect" && match.Groups["negated"].Success)
+            {
+                problem = "negates 'expect'";
+            }
+            else if (!match.Groups["value"].Success && !match.Groups["negated"].Success)
+            {
+                problem = $"gives no {(match.Groups["keyword"].Value == "expect" ? "node kind" : "code")}";
+            }
+            else if (match.Groups["carets"].Success && !ownLine)
+            {
+                problem = "has carets but follows code on its line";
+            }
+
+            // An annotation of its own is about the nearest line above it that is code.
+            var line = position.Line;
+            while (ownLine && annotationLines.Contains(line))
+            {
+                line--;
+            }
+
+            if (problem == null && line == 0)
+            {
+                problem = "has no line above it";
+            }
+
+            int? start = null, end = null;
+            if (problem == null && match.Groups["carets"].Success)
+            {
+                var caret = position.Column - 1 + text.IndexOf('^', options.Prefix.Length);
+                var column = GetDisplayColumn(lineIndex.GetLineText(position.Line), caret, options.TabWidth);
+                var marked = FindDisplayColumns(lineIndex.GetLineText(line), column, match.Groups["carets"].Length, options.TabWidth);
+                if (marked is { } span)
+                {
+                    (start, end) = (lineIndex.GetLineStart(line) + span.Start, lineIndex.GetLineStart(line) + span.End);
+                }
+                else
+                {
+                    problem = $"points at display column {column}, past the end of line {line}";
+                }
+            }
+
+            if (problem != null)
+            {
+                mismatches.Add(new InlineMismatch(position, position.Line, $"annotation '{text.Trim()}' {problem}"));
+                continue;
+            }
+
+            var keyword = match.Groups["keyword"].Value;
+            var value = match.Groups["value"].Success ? match.Groups["value"].Value : null;
+            var expectation = keyword == "expect"
+                ? new InlineExpectation(InlineExpectationKind.Node, value, null, line, start, end, position)
+                : new InlineExpectation(
+                    match.Groups["negated"].Success ? InlineExpectationKind.NoDiagnostic : InlineExpectationKind.Diagnostic,
+                    value,
+                    keyword switch
+                    {
+                        "error" => DiagnosticSeverity.Error,
+                        "warning" => DiagnosticSeverity.Warning,
+                        "info" => DiagnosticSeverity.Information,
+                        _ => DiagnosticSeverity.Hint
+                    },
+                    line,
+                    start,
+                    end,
+                    position);
+            annotations.Add(new Annotation(expectation, offset, text, ownLine, match.Groups["carets"].Length));
+        }
+
+        return annotations;
+    }
+
+    // Text left after the last token, or on lines of annotations, belongs to no line an annotation can point at.
+    private static int GetEffectiveOffset(Diagnostic diagnostic, LineIndex lineIndex, List<Token> tokens, HashSet<int> annotationLines)
+    {
+        var offset = diagnostic.Location?.Offset ?? 0;
+        var line = lineIndex.GetLineColumn(offset).Line;
+        if (!annotationLines.Contains(line) && !string.IsNullOrWhiteSpace(lineIndex.GetLineText(line)))
+        {
+            return offset;
+        }
+
+        return tokens.LastOrDefault(t => t.End <= offset)?.End ?? offset;
+    }
+
+    private static bool Matches(InlineExpectation expectation, Diagnostic diagnostic, int offset, LineIndex lineIndex)
+    {
+        return diagnostic.Severity == expectation.Severity
+            && (expectation.Value == null || diagnostic.Code == expectation.Value)
+            && IsAt(expectation, offset, lineIndex);
+    }
+
+    private static bool IsAt(InlineExpectation expectation, int offset, LineIndex lineIndex)
+    {
+        return expectation.Start is { } start
+            ? offset >= start && offset < Math.Max(expectation.End!.Value, start + 1)
+            : lineIndex.GetLineColumn(offset).Line == expectation.Line;
+    }
+
+    // The nodes spanning the marked text, innermost first, or those starting on the line, outermost first; either
+    // way the first rule is what blessing writes.
+    private static IEnumerable<CognitiveGraphNode> GetNodes(InlineExpectation expectation, List<CognitiveGraphNode> nodes)
+    {
+        if (expectation.Start is not { } start)
+        {
+            return nodes.Where(n => n.SourcePosition!.Line == expectation.Line).OrderByDescending(n => n.SourcePosition!.Length);
+        }
+
+        var end = Math.Max(expectation.End!.Value, start + 1);
+        return nodes
+            .Where(n => n.SourcePosition!.Offset <= start && n.SourcePosition.Offset + n.SourcePosition.Length >= end)
+            .OrderBy(n => n.SourcePosition!.Length);
+    }
+
+    private static string GetKind(CognitiveGraphNode node)
+    {
+        return node switch
+        {
+            NonTerminalNode rule => rule.RuleName,
+            TerminalNode terminal => terminal.TokenType,
+            _ => node.NodeType
+        };
+    }
+
+    private static List<CognitiveGraphNode> CollectNodes(CognitiveGraphNode root)
+    {
+        // Long lists nest as deeply as they are long, so the walk keeps its own stack.
+        var nodes = new List<CognitiveGraphNode>();
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(root);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            if (node.SourcePosition is { Length: > 0 })
+            {
+                nodes.Add(node);
+            }
+
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+        }
+
+        return nodes;
+    }
+
+    private static SourcePosition GetLocation(InlineExpectation expectation, LineIndex lineIndex, string? sourceFile)
+    {
+        if (expectation.Start is { } start)
+        {
+            return lineIndex.GetPosition(start, expectation.End!.Value - start, sourceFile);
+        }
+
+        var lineStart = lineIndex.GetLineStart(expectation.Line);
+        return lineIndex.GetPosition(lineStart, lineIndex.GetLineContentEnd(expectation.Line) - lineStart, sourceFile);
+    }
+
+    private static string Keyword(DiagnosticSeverity severity)
+    {
+        return severity == DiagnosticSeverity.Information ? "info" : severity.ToString().ToLowerInvariant();
+    }
+
+    // Unmet expectations are corrected to the innermost rule at their carets, or dropped, and each unexpected error
+    // gets an annotation of its own under its line: with a caret where the prefix leaves room for one, otherwise
+    // about the whole line.
+    private static string Bless(
+        LineIndex lineIndex,
+        InlineExpectationOptions options,
+        HashSet<int> annotationLines,
+        List<(Annotation Annotation, string? Kind)> blessings,
+        List<(int Offset, Diagnostic Diagnostic)> unexpected)
+    {
+        var lines = Enumerable.Range(1, lineIndex.LineCount).Select(lineIndex.GetLineText).ToList();
+        var newline = lineIndex.DominantLineEnding switch
+        {
+            LineEnding.CrLf => "\r\n",
+            LineEnding.Cr => "\r",
+            _ => "\n"
+        };
+        var endings = lineIndex.LineEndings
+            .Select(e => e switch { LineEnding.Lf => "\n", LineEnding.CrLf => "\r\n", LineEnding.Cr => "\r", _ => string.Empty })
+            .ToList();
+
+        var emptied = new HashSet<int>();
+        foreach (var (annotation, kind) in blessings.OrderByDescending(b => b.Annotation.Offset))
+        {
+            var line = annotation.Expectation.Annotation.Line;
+            var text = lines[line - 1];
+            var start = annotation.Offset - lineIndex.GetLineStart(line);
+            var length = Math.Min(annotation.Text.Length, text.Length - start);
+            string replacement;
+            if (kind != null)
+            {
+                var comment = annotation.Text[..length];
+                var head = annotation.Carets > 0
+                    ? comment[..(comment.IndexOf('^', options.Prefix.Length) + annotation.Carets)]
+                    : options.Prefix;
+                replacement = $"{head} expect: {kind}{(comment.TrimEnd().EndsWith("*/", StringComparison.Ordinal) ? " */" : string.Empty)}";
+            }
+            else
+            {
+                replacement = string.Empty;
+                while (start > 0 && char.IsWhiteSpace(text[start - 1]))
+                {
+                    start--;
+                    length++;
+                }
+
+                emptied.Add(line);
+            }
+
+            lines[line - 1] = text[..start] + replacement + text[(start + length)..];
+        }
+
+        var added = new Dictionary<int, List<string>>();
+        foreach (var (offset, diagnostic) in unexpected.OrderBy(u => u.Offset))
+        {
+            var (line, column) = lineIndex.GetLineColumn(offset);
+            var target = lineIndex.GetLineText(line);
+            var indent = target[..(target.Length - target.TrimStart().Length)];
+            var head = $"{indent}{options.Prefix} ";
+            var caret = GetDisplayColumn(target, column - 1, options.TabWidth);
+            var room = caret - GetDisplayColumn(head, head.Length, options.TabWidth);
+            var annotation = room >= 0
+                ? $"{head}{new string(' ', room)}^ error: {diagnostic.Code}"
+                : $"{head}error: {diagnostic.Code}";
+
+            // Below any annotations the line already has.
+            var after = line;
+            while (annotationLines.Contains(after + 1))
+            {
+                after++;
+            }
+
+            if (!added.TryGetValue(after, out var list))
+            {
+                added[after] = list = new List<string>();
+            }
+
+            list.Add(annotation);
+        }
+
+        var output = new List<(string Text, string Ending)>();
+        for (var i = 0; i < lines.Count; i++)
+        {
+            // A line that held only dropped annotations goes, keeping whether the text ends with a line break.
+            if (emptied.Contains(i + 1) && annotationLines.Contains(i + 1) && string.IsNullOrWhiteSpace(lines[i]))
+            {
+                if (endings[i].Length == 0 && output.Count > 0)
+                {
+                    output[^1] = (output[^1].Text, string.Empty);
+                }
+            }
+            else
+            {
+                output.Add((lines[i], endings[i]));
+            }
+
+            if (added.TryGetValue(i + 1, out var list))
+            {
+                foreach (var annotation in list)
+                {
+                    if (output.Count > 0 && output[^1].Ending.Length == 0)
+                    {
+                        output[^1] = (output[^1].Text, newline);
+                    }
+
+                    output.Add((annotation, newline));
+                }
+
+                if (endings[i].Length == 0)
+                {
+                    output[^1] = (output[^1].Text, string.Empty);
+                }
+            }
+        }
+
+        return string.Concat(output.Select(l => l.Text + l.Ending));
+    }
+
+    // Tabs advance to the next tab stop, a grapheme cluster is one character, and East Asian wide characters and
+    // emoji take two columns, as terminals and most editors show them.
+    private static IEnumerable<(int Index, int Length, int Width)> GetTextElements(string line, int tabWidth)
+    {
+        if (tabWidth < 1)
+        {
+            throw new ArgumentOutOfRangeException(nameof(tabWidth), tabWidth, "Tab width must be at least 1.");
+        }
+
+        var column = 0;
+        var index = 0;
+        while (index < line.Length)
+        {
+            int length, width;
+            if (line[index] == '\t')
+            {
+                length = 1;
+                width = tabWidth - column % tabWidth;
+            }
+            else
+            {
+                length = StringInfo.GetNextTextElementLength(line, index);
+                width = Rune.DecodeFromUtf16(line.AsSpan(index), out var rune, out _) == System.Buffers.OperationStatus.Done && IsWide(rune.Value) ? 2 : 1;
+            }
+
+            yield return (index, length, width);
+            column += width;
+            index += length;
+        }
+    }
+
+    private static bool IsWide(int codePoint)
+    {
+        return codePoint is >= 0x1100 and <= 0x115F
+            or >= 0x2E80 and <= 0x303E
+            or >= 0x3041 and <= 0x33FF
+            or >= 0x3400 and <= 0x4DBF
+            or >= 0x4E00 and <= 0x9FFF
+            or >= 0xA000 and <= 0xA4CF
+            or >= 0xAC00 and <= 0xD7A3
+            or >= 0xF900 and <= 0xFAFF
+            or >= 0xFE30 and <= 0xFE4F
+            or >= 0xFF00 and <= 0xFF60
+            or >= 0xFFE0 and <= 0xFFE6
+            or >= 0x1F300 and <= 0x1F64F
+            or >= 0x1F900 and <= 0x1F9FF
+            or >= 0x20000 and <= 0x3FFFD;
+    }
+
+    private sealed record Annotation(InlineExpectation Expectation, int Offset, string Text, bool OwnLine, int Carets);
+}
@@ -0,0 +1,288 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using System.Text.RegularExpressions;
+using Minotaur.Core;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// An input with identifiers, string literals and comments scrubbed.
+/// </summary>
+public sealed class AnonymizedInput
+{
+    /// <summary>
+    /// Gets the scrubbed text.
+    /// </summary>
+    public string Text { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Gets the number of distinct identifiers that were renamed.
+    /// </summary>
+    public int RenamedIdentifiers { get; init; }
+
+    /// <summary>
+    /// Gets the number of string literals whose contents were replaced.
+    /// </summary>
+    public int ScrubbedStrings { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether text between tokens, such as comments, was dropped.
+    /// </summary>
+    public bool ScrubbedTrivia { get; init; }
+}
+
+/// <summary>
+/// Removes user content from an input while keeping how it parses. Identifier tokens are renamed consistently
+/// (every occurrence of a name gets the same replacement), the contents of string literal tokens are replaced with
+/// 'x', and text between tokens is reduced to its line breaks. Literal tokens are kept. Each replacement must lex
+/// back as the same token kind, and the result must parse to a tree of the same shape, or fail with the same
+/// diagnostics at the same tokens; if scrubbing between tokens changes that, it is retried keeping that text.
+/// </summary>
+public static class InputAnonymizer
+{
+    private static readonly Regex IdentifierText = new("^[A-Za-z_][A-Za-z0-9_]*$", RegexOptions.CultureInvariant);
+
+    /// <summary>
+    /// Anonymizes an input.
+    /// </summary>
+    /// <param name="input">The input.</param>
+    /// <param name="grammar">The grammar the input is parsed with.</param>
+    /// <param name="options">Optional parse options.</param>
+    /// <returns>The anonymized input.</returns>
+    /// <exception cref="InvalidOperationException">No scrubbed form parses the same way as the input.</exception>
+    public static AnonymizedInput Anonymize(string input, CompiledGrammar grammar, ParseOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var parser = new GeneralizedParser(grammar);
+        var expected = Shape(parser.Parse(input, options));
+
+        foreach (var scrubTrivia in new[] { true, false })
+        {
+            var result = Scrub(input, grammar, scrubTrivia);
+            if (Shape(parser.Parse(result.Text, options)) == expected)
+            {
+                return result;
+            }
+        }
+
+        throw new InvalidOperationException("Anonymizing the input changes how it parses; capture it without anonymization instead");
+    }
+
+    /// <summary>
+    /// Describes how an input parses without its text: the rule names and token kinds of the tree, or the
+    /// diagnostic codes, token indices and expected terminals of a failed parse.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    /// <returns>The shape.</returns>
+    public static string Shape(ParseResult result)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+
+        var text = new StringBuilder();
+        if (result.Tree != null)
+        {
+            WriteShape(text, result.Tree);
+            text.Append('\n');
+        }
+
+        foreach (var diagnostic in result.Diagnostics.Where(d => d.Severity == DiagnosticSeverity.Error))
+        {
+            text.Append(diagnostic.Code);
+            if (diagnostic.Data.TryGetValue("tokenIndex", out var index))
+            {
+                text.Append(" @").Append(index);
+            }
+            else
+            {
+                text.Append(' ').Append(diagnostic.Message);
+            }
+
+            if (diagnostic.Data.TryGetValue("expected", out var expected) && expected is IEnumerable<string> terminals)
+            {
+                text.Append(" expected ").Append(string.Join(", ", terminals));
+            }
+
+            text.Append('\n');
+        }
+
+        return text.ToString();
+    }
+
+    private static AnonymizedInput Scrub(string input, CompiledGrammar grammar, bool scrubTrivia)
+    {
+        var lexer = new GrammarLexer(grammar);
+        var lexed = lexer.Tokenize(input);
+        var literals = grammar.GetTerminals().Where(t => t.Kind == GrammarSymbolKind.Literal).Select(t => t.Name).ToHashSet();
+        var names = new Dictionary<string, string>();
+        var used = new HashSet<string>(literals);
+        var unrecognized = lexed.Diagnostics
+            .Where(d => d.Code == DiagnosticCodes.UnrecognizedCharacter && d.Location != null)
+            .Select(d => d.Location!.Offset)
+            .ToHashSet();
+
+        var text = new StringBuilder();
+        var strings = 0;
+        var position = 0;
+
+        foreach (var token in lexed.Tokens)
+        {
+            var gap = input.Substring(position, token.Offset - position);
+            text.Append(scrubTrivia ? ScrubTrivia(gap, position, unrecognized) : gap);
+            position = token.End;
+
+            if (token.Kind.StartsWith('"'))
+            {
+                text.Append(token.Text);
+            }
+            else if (IsStringLiteral(token.Text) && ScrubString(token, lexer) is { } scrubbed)
+            {
+                text.Append(scrubbed);
+                strings++;
+            }
+            else if (IdentifierText.IsMatch(token.Text))
+            {
+                if (!names.TryGetValue(token.Text, out var name))
+                {
+                    name = NewName(token, lexer, used) ?? token.Text;
+                    names[token.Text] = name;
+                    used.Add(name);
+                }
+
+                text.Append(name);
+            }
+            else
+            {
+                text.Append(token.Text);
+            }
+        }
+
+        var tail = input[position..];
+        text.Append(scrubTrivia ? ScrubTrivia(tail, position, unrecognized) : tail);
+
+        return new AnonymizedInput
+        {
+            Text = text.ToString(),
+            RenamedIdentifiers = names.Count(n => n.Key != n.Value),
+            ScrubbedStrings = strings,
+            ScrubbedTrivia = scrubTrivia
+        };
+    }
+
+    // Keeps line breaks and characters the lexer rejected, so lexical errors survive; everything else, which the
+    // lexer skipped as whitespace or comments, becomes a single space.
+    private static string ScrubTrivia(string gap, int offset, HashSet<int> unrecognized)
+    {
+        var text = new StringBuilder();
+        var pendingSpace = false;
+        for (var i = 0; i < gap.Length; i++)
+        {
+            var c = gap[i];
+            if (c == '\n' || unrecognized.Contains(offset + i))
+            {
+                if (pendingSpace && c != '\n')
+                {
+                    text.Append(' ');
+                }
+
+                text.Append(c);
+                pendingSpace = false;
+            }
+            else if (c != '\r')
+            {
+                pendingSpace = true;
+            }
+        }
+
+        if (pendingSpace)
+        {
+            text.Append(' ');
+        }
+
+        return text.ToString();
+    }
+
+    private static bool IsStringLiteral(string text)
+    {
+        return text.Length >= 2 && text[0] is ('"' or '\'' or '`') && text[^1] == text[0];
+    }
+
+    private static string? ScrubString(Token token, GrammarLexer lexer)
+    {
+        var quote = token.Text[0];
+        var candidate = quote + new string('x', token.Text.Length - 2) + quote;
+        return LexesAs(candidate, token.Kind, lexer) ? candidate : null;
+    }
+
+    private static string? NewName(Token token, GrammarLexer lexer, HashSet<string> used)
+    {
+        // Names are a, b, ..., z, aa, ab, ... in lower, capitalized or upper case, whichever the token accepts.
+        for (var i = 0; i < 10_000; i++)
+        {
+            var name = Letters(i);
+            foreach (var candidate in new[] { name, char.ToUpperInvariant(name[0]) + name[1..], name.ToUpperInvariant() })
+            {
+                if (!used.Contains(candidate) && LexesAs(candidate, token.Kind, lexer))
+                {
+                    return candidate;
+                }
+            }
+        }
+
+        return null;
+    }
+
+    private static string Letters(int index)
+    {
+        var text = new StringBuilder();
+        for (index++; index > 0; index = (index - 1) / 26)
+        {
+            text.Insert(0, (char)('a' + ((index - 1) % 26)));
+        }
+
+        return text.ToString();
+    }
+
+    private static bool LexesAs(string text, string kind, GrammarLexer lexer)
+    {
+        var result = lexer.Tokenize(text);
+        return result.Diagnostics.Count == 0 && result.Tokens.Count == 1 && result.Tokens[0].Kind == kind;
+    }
+
+    private static void WriteShape(StringBuilder text, CognitiveGraphNode node)
+    {
+        if (node is TerminalNode terminal)
+        {
+            text.Append(terminal.TokenType);
+            return;
+        }
+
+        text.Append('(').Append(node is NonTerminalNode rule ? rule.RuleName : node.NodeType);
+        foreach (var child in node.Children)
+        {
+            text.Append(' ');
+            WriteShape(text, child);
+        }
+
+        text.Append(')');
+    }
+}
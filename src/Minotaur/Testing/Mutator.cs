@@ -0,0 +1,468 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using Minotaur.Core;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Kinds of mutations.
+/// </summary>
+public enum MutationKind
+{
+    /// <summary>
+    /// The two operands of a binary alternative such as <c>&lt;sum&gt; "+" &lt;term&gt;</c> trade places.
+    /// </summary>
+    SwapOperands,
+
+    /// <summary>
+    /// A literal is replaced by another member of its operator group.
+    /// </summary>
+    ReplaceOperator,
+
+    /// <summary>
+    /// The last element of a list rule is repeated.
+    /// </summary>
+    DuplicateElement,
+
+    /// <summary>
+    /// Symbols that another alternative of the rule goes without are removed.
+    /// </summary>
+    RemoveOptional
+}
+
+/// <summary>
+/// A mutation applicable to a parse: the replacement of one node's text.
+/// </summary>
+/// <param name="Kind">The kind of mutation.</param>
+/// <param name="Rule">The rule of the replaced node.</param>
+/// <param name="Alternative">The index of the replaced node's alternative.</param>
+/// <param name="Location">The span of the changed part of the input.</param>
+/// <param name="Description">What the mutation changes, such as <c>replace '&gt;' with '&lt;' in &lt;expr&gt;</c>.</param>
+public sealed record Mutation(MutationKind Kind, string Rule, int Alternative, SourcePosition Location, string Description)
+{
+    /// <summary>
+    /// Gets the id of the replaced node.
+    /// </summary>
+    internal Guid Target { get; init; }
+
+    /// <summary>
+    /// Gets the text that replaces the node.
+    /// </summary>
+    internal string Replacement { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Returns the description with its location.
+    /// </summary>
+    /// <returns>The mutation description.</returns>
+    public override string ToString()
+    {
+        return $"{Location.Line}:{Location.Column}: {Description}";
+    }
+}
+
+/// <summary>
+/// A mutated input.
+/// </summary>
+/// <param name="Mutation">The mutation that produced it.</param>
+/// <param name="Text">The mutated input.</param>
+/// <param name="Parse">The parse of the mutated input.</param>
+/// <param name="Map">The map from the original input to the mutated one.</param>
+public sealed record Mutant(Mutation Mutation, string Text, ParseResult Parse, PositionMap Map);
+
+/// <summary>
+/// Mutates valid inputs of a grammar in ways the grammar allows, for mutation testing of tools that consume parse
+/// trees. Every mutation is derived from the alternative of the node it changes and replaces that node's text
+/// through the <see cref="TreeEditor"/>, so it is checked against the node's rule, and a mutant is only kept if
+/// the whole mutated input reparses without errors into a tree that differs from the original one.
+/// </summary>
+/// <remarks>
+/// <para>
+/// Operator groups are the literals at the same position of alternatives of a rule that are otherwise the same,
+/// such as <c>"&gt;"</c> and <c>"&lt;"</c> in <c>&lt;sum&gt; "&gt;" &lt;sum&gt; | &lt;sum&gt; "&lt;" &lt;sum&gt;</c>,
+/// together with the groups listed in the grammar's "OperatorGroups" metadata as quoted literals, groups separated
+/// by commas: <c>OperatorGroups: "+" "-", "*" "/" "%"</c>.
+/// </para>
+/// <para>
+/// A list rule is a rule with an alternative that starts with the rule itself, such as
+/// <c>&lt;statements&gt; ::= &lt;statement&gt; | &lt;statements&gt; &lt;statement&gt;</c>; its last element is
+/// duplicated with the separator written before it, or with the separator literals of the recursive alternative for
+/// a single element. Symbols are optional where the rule has another alternative without a contiguous run of them.
+/// </para>
+/// </remarks>
+public sealed class Mutator
+{
+    private readonly GeneralizedParser _parser;
+    private readonly Dictionary<(string Rule, int Alternative, int Position), List<string>> _groups = new();
+    private readonly List<IReadOnlyList<string>> _declaredGroups = new();
+
+    /// <summary>
+    /// Initializes a new instance of the Mutator class.
+    /// </summary>
+    /// <param name="parser">The parser of the inputs, used to validate mutants.</param>
+    public Mutator(GeneralizedParser parser)
+    {
+        ArgumentNullException.ThrowIfNull(parser);
+
+        _parser = parser;
+        InferGroups(parser.Grammar);
+        if (parser.Grammar.Source.Metadata.GetValueOrDefault("OperatorGroups") is { } groups)
+        {
+            _declaredGroups.AddRange(SplitGroups(groups));
+        }
+    }
+
+    /// <summary>
+    /// Gets the operator groups of the grammar, inferred from its alternatives and declared in its metadata.
+    /// </summary>
+    public IReadOnlyList<IReadOnlyList<string>> OperatorGroups => _groups.Values
+        .Select(g => (IReadOnlyList<string>)g)
+        .Concat(_declaredGroups)
+        .DistinctBy(g => string.Join("\u0001", g))
+        .ToList();
+
+    /// <summary>
+    /// Enumerates the mutations applicable to a parse, in source order. They are not validated.
+    /// </summary>
+    /// <param name="parse">The parse of a valid input.</param>
+    /// <returns>The mutations.</returns>
+    /// <exception cref="ArgumentException">The parse has no tree.</exception>
+    public IReadOnlyList<Mutation> Enumerate(ParseResult parse)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+
+        if (parse.Tree == null)
+        {
+            throw new ArgumentException("Only a parse result with a tree can be mutated", nameof(parse));
+        }
+
+        var mutations = new List<Mutation>();
+        var pending = new Stack<CognitiveGraphNode>();
+        pending.Push(parse.Tree);
+        while (pending.Count > 0)
+        {
+            var node = pending.Pop();
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push(node.Children[i]);
+            }
+
+            if (node is NonTerminalNode nonTerminal
+                && node.SourcePosition is { Length: > 0 }
+                && _parser.Grammar.GetRule(nonTerminal.RuleName) is { } rule
+                && nonTerminal.ProductionIndex < rule.Alternatives.Count
+                && rule.Alternatives[nonTerminal.ProductionIndex].Symbols.Count == node.Children.Count)
+            {
+                var alternative = rule.Alternatives[nonTerminal.ProductionIndex];
+                var site = new Site(parse.Input, nonTerminal, alternative);
+                AddSwap(site, mutations);
+                AddOperatorReplacements(site, mutations);
+                AddDuplicate(site, mutations);
+                AddRemovals(site, mutations);
+            }
+        }
+
+        return mutations;
+    }
+
+    /// <summary>
+    /// Applies a mutation and validates the mutant.
+    /// </summary>
+    /// <param name="parse">The parse the mutation was enumerated from.</param>
+    /// <param name="mutation">The mutation.</param>
+    /// <returns>The mutant, or null if the mutated input does not parse or parses into the original tree.</returns>
+    public Mutant? TryApply(ParseResult parse, Mutation mutation)
+    {
+        ArgumentNullException.ThrowIfNull(parse);
+        ArgumentNullException.ThrowIfNull(mutation);
+
+        TreeEditResult result;
+        try
+        {
+            result = new TreeEditor(_parser, parse).ReplaceNode(mutation.Target, mutation.Replacement, reindent: false).Apply();
+        }
+        catch (ArgumentException)
+        {
+            return null;
+        }
+
+        if (!result.IsSuccess || result.Parse.Tree == null || SExpression.Format(result.Parse.Tree) == SExpression.Format(parse.Tree!))
+        {
+            return null;
+        }
+
+        return new Mutant(mutation, result.Text, result.Parse, result.Map);
+    }
+
+    /// <summary>
+    /// Generates mutants of a parse, each from one mutation. The mutations are tried in an order shuffled by the
+    /// seed, so a seed always gives the same mutants; mutants with the same text are kept once.
+    /// </summary>
+    /// <param name="parse">The parse of a valid input.</param>
+    /// <param name="count">The maximum number of mutants.</param>
+    /// <param name="seed">The seed of the order in which mutations are tried.</param>
+    /// <returns>The valid mutants, in the order they were found.</returns>
+    public IReadOnlyList<Mutant> Generate(ParseResult parse, int count, int seed)
+    {
+        ArgumentOutOfRangeException.ThrowIfNegative(count);
+
+        var candidates = Enumerate(parse).ToList();
+        var random = new Random(seed);
+        for (var i = candidates.Count - 1; i > 0; i--)
+        {
+            var j = random.Next(i + 1);
+            (candidates[i], candidates[j]) = (candidates[j], candidates[i]);
+        }
+
+        var mutants = new List<Mutant>();
+        var texts = new HashSet<string>(StringComparer.Ordinal);
+        foreach (var candidate in candidates)
+        {
+            if (mutants.Count == count)
+            {
+                break;
+            }
+
+            if (TryApply(parse, candidate) is { } mutant && texts.Add(mutant.Text))
+            {
+                mutants.Add(mutant);
+            }
+        }
+
+        return mutants;
+    }
+
+    private void AddSwap(Site site, List<Mutation> mutations)
+    {
+        var symbols = site.Alternative.Symbols;
+        if (symbols.Count != 3 || symbols[0].IsTerminal || !symbols[1].IsTerminal || symbols[2].IsTerminal
+            || site.Node.Children[0].SourcePosition == null || site.Node.Children[2].SourcePosition == null)
+        {
+            return;
+        }
+
+        var (leftStart, leftEnd) = Span(site.Node.Children[0]);
+        var (rightStart, rightEnd) = Span(site.Node.Children[2]);
+        var left = site.Input[leftStart..leftEnd];
+        var right = site.Input[rightStart..rightEnd];
+        if (left != right)
+        {
+            mutations.Add(site.Mutate(MutationKind.SwapOperands, site.Node.SourcePosition!, $"swap the operands of <{site.Rule}>", leftStart, rightEnd, right + site.Input[leftEnd..rightStart] + left));
+        }
+    }
+
+    private void AddOperatorReplacements(Site site, List<Mutation> mutations)
+    {
+        for (var i = 0; i < site.Alternative.Symbols.Count; i++)
+        {
+            var symbol = site.Alternative.Symbols[i];
+            if (symbol.Kind != GrammarSymbolKind.Literal || site.Node.Children[i] is not TerminalNode { SourcePosition: { } position })
+            {
+                continue;
+            }
+
+            var members = _groups.GetValueOrDefault((site.Rule, site.Alternative.Index, i)) ?? Enumerable.Empty<string>();
+            foreach (var member in members.Concat(_declaredGroups.Where(g => g.Contains(symbol.Name)).SelectMany(g => g)).Distinct())
+            {
+                if (member != symbol.Name)
+                {
+                    mutations.Add(site.Mutate(MutationKind.ReplaceOperator, position, $"replace '{symbol.Name}' with '{member}' in <{site.Rule}>", position.Offset, position.Offset + position.Length, member));
+                }
+            }
+        }
+    }
+
+    private void AddDuplicate(Site site, List<Mutation> mutations)
+    {
+        var rule = site.Alternative.Rule;
+        var symbols = site.Alternative.Symbols;
+        var (start, end) = Span(site.Node);
+        if (symbols[0].Kind == GrammarSymbolKind.Rule && symbols[0].Name == rule.Name)
+        {
+            if (site.Node.Children[0].SourcePosition == null)
+            {
+                return;
+            }
+
+            // The text after the nested list is the separator and the last element.
+            var unit = site.Input[Span(site.Node.Children[0]).End..end];
+            if (unit.Trim().Length > 0)
+            {
+                mutations.Add(site.Mutate(MutationKind.DuplicateElement, site.Node.SourcePosition!, $"duplicate the last element of <{site.Rule}>", end, end, unit));
+            }
+
+            return;
+        }
+
+        // A single element is separated from its copy by the literals a recursive alternative puts before it.
+        var recursive = rule.Alternatives.FirstOrDefault(a =>
+            a.Symbols.Count > symbols.Count
+            && a.Symbols[0].Kind == GrammarSymbolKind.Rule
+            && a.Symbols[0].Name == rule.Name
+            && a.Symbols.Skip(a.Symbols.Count - symbols.Count).SequenceEqual(symbols)
+            && a.Symbols.Skip(1).Take(a.Symbols.Count - symbols.Count - 1).All(s => s.Kind == GrammarSymbolKind.Literal));
+        if (recursive == null)
+        {
+            return;
+        }
+
+        var separator = string.Concat(recursive.Symbols.Skip(1).Take(recursive.Symbols.Count - symbols.Count - 1).Select(s => s.Name));
+        var lineStart = site.Input.LastIndexOf('\n', Math.Max(start - 1, 0)) + 1;
+        var indentation = site.Input[lineStart..start];
+        var gap = start > 0 && indentation.Trim().Length == 0 ? "\n" + indentation : " ";
+        mutations.Add(site.Mutate(MutationKind.DuplicateElement, site.Node.SourcePosition!, $"duplicate the last element of <{site.Rule}>", end, end, separator + gap + site.Input[start..end]));
+    }
+
+    private void AddRemovals(Site site, List<Mutation> mutations)
+    {
+        var symbols = site.Alternative.Symbols;
+        foreach (var other in site.Alternative.Rule.Alternatives)
+        {
+            var removed = symbols.Count - other.Symbols.Count;
+            if (removed <= 0 || other.Symbols.Count == 0)
+            {
+                continue;
+            }
+
+            // The run removed is where the alternatives first differ; the rest must match after it.
+            var k = 0;
+            while (k < other.Symbols.Count && symbols[k] == other.Symbols[k])
+            {
+                k++;
+            }
+
+            if (!symbols.Skip(k + removed).SequenceEqual(other.Symbols.Skip(k)))
+            {
+                continue;
+            }
+
+            var first = site.Node.Children[k].SourcePosition;
+            var last = site.Node.Children[k + removed - 1].SourcePosition;
+            if (first == null || last == null || last.Offset + last.Length == first.Offset)
+            {
+                continue;
+            }
+
+            var description = $"remove {string.Join(" ", symbols.Skip(k).Take(removed))} from <{site.Rule}>";
+            mutations.Add(site.Mutate(MutationKind.RemoveOptional, first, description, first.Offset, last.Offset + last.Length, string.Empty));
+        }
+    }
+
+    private void InferGroups(CompiledGrammar grammar)
+    {
+        foreach (var rule in grammar.Rules)
+        {
+            var byShape = new Dictionary<string, List<(CompiledAlternative Alternative, int Position)>>(StringComparer.Ordinal);
+            foreach (var alternative in rule.Alternatives)
+            {
+                for (var i = 0; i < alternative.Symbols.Count; i++)
+                {
+                    if (alternative.Symbols[i].Kind != GrammarSymbolKind.Literal)
+                    {
+                        continue;
+                    }
+
+                    var shape = $"{i}:{string.Join(" ", alternative.Symbols.Select((s, j) => j == i ? "*" : s.ToString()))}";
+                    if (!byShape.TryGetValue(shape, out var members))
+                    {
+                        byShape[shape] = members = new List<(CompiledAlternative, int)>();
+                    }
+
+                    members.Add((alternative, i));
+                }
+            }
+
+            foreach (var members in byShape.Values.Where(m => m.Count > 1))
+            {
+                var group = members.Select(m => m.Alternative.Symbols[m.Position].Name).Distinct().ToList();
+                foreach (var (alternative, position) in members)
+                {
+                    _groups[(rule.Name, alternative.Index, position)] = group;
+                }
+            }
+        }
+    }
+
+    private static IEnumerable<IReadOnlyList<string>> SplitGroups(string value)
+    {
+        var group = new List<string>();
+        for (var i = 0; i < value.Length; i++)
+        {
+            if (value[i] == '"')
+            {
+                var close = value.IndexOf('"', i + 1);
+                if (close < 0)
+                {
+                    break;
+                }
+
+                group.Add(value[(i + 1)..close]);
+                i = close;
+            }
+            else if (value[i] == ',')
+            {
+                if (group.Count > 1)
+                {
+                    yield return group;
+                }
+
+                group = new List<string>();
+            }
+        }
+
+        if (group.Count > 1)
+        {
+            yield return group;
+        }
+    }
+
+    private static (int Start, int End) Span(CognitiveGraphNode node)
+    {
+        var position = node.SourcePosition;
+        return position == null ? (0, 0) : (position.Offset, position.Offset + position.Length);
+    }
+
+    private sealed record Site(string Input, NonTerminalNode Node, CompiledAlternative Alternative)
+    {
+        public string Rule => Node.RuleName;
+
+        // Replaces the node's text with the change from start to end applied, keeping one space where the removed
+        // text was surrounded by whitespace.
+        public Mutation Mutate(MutationKind kind, SourcePosition location, string description, int start, int end, string text)
+        {
+            var (nodeStart, nodeEnd) = Span(Node);
+            var before = Input[nodeStart..start];
+            var after = Input[end..nodeEnd];
+            if (text.Length == 0 && (after.Length == 0 || char.IsWhiteSpace(after[0])))
+            {
+                before = before.TrimEnd();
+            }
+
+            if (text.Length == 0 && before.Length == 0)
+            {
+                after = after.TrimStart();
+            }
+
+            return new Mutation(kind, Rule, Alternative.Index, location, description)
+            {
+                Target = Node.Id,
+                Replacement = before + text + after
+            };
+        }
+    }
+}
@@ -0,0 +1,234 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Diagnostics;
+using Minotaur.GrammarGeneration;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Snapshot assertions for grammar authors. The tree of a successful parse is stored as an s-expression in
+/// "&lt;input&gt;.snap" and the diagnostics of a failing parse in "&lt;input&gt;.diagnostics.snap", next to the
+/// input file. See <see cref="Snapshot"/> for how snapshots are created and updated. Negative tests can instead
+/// state the diagnostics they expect in "&lt;input&gt;.expected", see <see cref="DiagnosticExpectations"/>.
+/// </summary>
+public static class ParseSnapshot
+{
+    /// <summary>
+    /// The extension appended to an input file to name its tree snapshot.
+    /// </summary>
+    public const string TreeExtension = ".snap";
+
+    /// <summary>
+    /// The extension appended to an input file to name its diagnostics snapshot.
+    /// </summary>
+    public const string DiagnosticsExtension = ".diagnostics.snap";
+
+    /// <summary>
+    /// Parses an input file and asserts that it succeeds and its tree matches the snapshot.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="inputPath">The input file.</param>
+    /// <param name="startRule">The start rule, or null for the grammar's default.</param>
+    /// <exception cref="SnapshotMismatchException">The parse failed or the tree differs from the snapshot.</exception>
+    public static void AssertParse(CompiledGrammar grammar, string inputPath, string? startRule = null)
+    {
+        var result = Parse(grammar, inputPath, startRule);
+        var snapshotPath = inputPath + TreeExtension;
+        if (!result.IsSuccess)
+        {
+            throw new SnapshotMismatchException(
+                $"{inputPath} did not parse with {grammar.Name}:\n{FormatDiagnostics(result.Diagnostics)}",
+                snapshotPath);
+        }
+
+        Snapshot.AssertMatches(SExpression.Format(result.Tree!), snapshotPath);
+    }
+
+    /// <summary>
+    /// Parses an input file with the grammar in a grammar file and asserts that its tree matches the snapshot.
+    /// </summary>
+    /// <param name="grammarPath">The grammar file.</param>
+    /// <param name="inputPath">The input file.</param>
+    /// <param name="startRule">The start rule, or null for the grammar's default.</param>
+    /// <returns>A task that completes when the assertion has run.</returns>
+    public static async Task AssertParseAsync(string grammarPath, string inputPath, string? startRule = null)
+    {
+        AssertParse(await LoadAsync(grammarPath), inputPath, startRule);
+    }
+
+    /// <summary>
+    /// Parses an input file with a grammar from a container and asserts that its tree matches the snapshot.
+    /// </summary>
+    /// <param name="container">The loaded grammars.</param>
+    /// <param name="grammarName">The grammar name.</param>
+    /// <param name="inputPath">The input file.</param>
+    /// <param name="startRule">The start rule, or null for the grammar's default.</param>
+    public static void AssertParse(GrammarContainer container, string grammarName, string inputPath, string? startRule = null)
+    {
+        AssertParse(GetGrammar(container, grammarName), inputPath, startRule);
+    }
+
+    /// <summary>
+    /// Parses an input file and asserts that it fails and its diagnostics match the snapshot.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="inputPath">The input file.</param>
+    /// <param name="startRule">The start rule, or null for the grammar's default.</param>
+    /// <exception cref="SnapshotMismatchException">The parse succeeded or the diagnostics differ from the snapshot.</exception>
+    public static void AssertDiagnostics(CompiledGrammar grammar, string inputPath, string? startRule = null)
+    {
+        var result = Parse(grammar, inputPath, startRule);
+        var snapshotPath = inputPath + DiagnosticsExtension;
+        if (result.IsSuccess)
+        {
+            throw new SnapshotMismatchException($"{inputPath} parsed with {grammar.Name}; expected diagnostics.", snapshotPath);
+        }
+
+        Snapshot.AssertMatches(FormatDiagnostics(result.Diagnostics), snapshotPath);
+    }
+
+    /// <summary>
+    /// Parses an input file with the grammar in a grammar file and asserts that its diagnostics match the snapshot.
+    /// </summary>
+    /// <param name="grammarPath">The grammar file.</param>
+    /// <param name="inputPath">The input file.</param>
+    /// <param name="startRule">The start rule, or null for the grammar's default.</param>
+    /// <returns>A task that completes when the assertion has run.</returns>
+    public static async Task AssertDiagnosticsAsync(string grammarPath, string inputPath, string? startRule = null)
+    {
+        AssertDiagnostics(await LoadAsync(grammarPath), inputPath, startRule);
+    }
+
+    /// <summary>
+    /// Parses an input file with a grammar from a container and asserts that its diagnostics match the snapshot.
+    /// </summary>
+    /// <param name="container">The loaded grammars.</param>
+    /// <param name="grammarName">The grammar name.</param>
+    /// <param name="inputPath">The input file.</param>
+    /// <param name="startRule">The start rule, or null for the grammar's default.</param>
+    public static void AssertDiagnostics(GrammarContainer container, string grammarName, string inputPath, string? startRule = null)
+    {
+        AssertDiagnostics(GetGrammar(container, grammarName), inputPath, startRule);
+    }
+
+    /// <summary>
+    /// Parses an input file and asserts that its diagnostics meet the expectations in "&lt;input&gt;.expected". In
+    /// update mode the expectations are regenerated from the diagnostics, with their whole messages, keeping the mode
+    /// and whether the number of error nodes is checked.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="inputPath">The input file.</param>
+    /// <param name="startRule">The start rule, or null for the grammar's default.</param>
+    /// <exception cref="SnapshotMismatchException">The diagnostics do not meet the expectations, or there are none.</exception>
+    /// <exception cref="FormatException">The expectations file is malformed.</exception>
+    public static void AssertExpectedDiagnostics(CompiledGrammar grammar, string inputPath, string? startRule = null)
+    {
+        var result = Parse(grammar, inputPath, startRule);
+        var expectationsPath = inputPath + DiagnosticExpectations.Extension;
+        var expectations = File.Exists(expectationsPath) ? DiagnosticExpectations.Load(expectationsPath) : null;
+
+        if (Snapshot.IsUpdating)
+        {
+            var updated = DiagnosticExpectations.FromResult(result, expectations?.Mode ?? ExpectationMode.Strict, expectations?.Recovered != null);
+            File.WriteAllText(expectationsPath, updated.Format());
+            return;
+        }
+
+        if (expectations == null)
+        {
+            throw new SnapshotMismatchException(
+                $"Expectations {expectationsPath} do not exist; run with {Snapshot.UpdateVariable}=1 to create them.\n{FormatDiagnostics(result.Diagnostics)}",
+                expectationsPath);
+        }
+
+        if (expectations.Check(result) is { } report)
+        {
+            throw new SnapshotMismatchException(
+                $"{inputPath} does not meet the expectations in {expectationsPath}; run with {Snapshot.UpdateVariable}=1 to regenerate them.\n{report}",
+                expectationsPath);
+        }
+    }
+
+    /// <summary>
+    /// Parses an input file with the grammar in a grammar file and asserts that its diagnostics meet the expectations.
+    /// </summary>
+    /// <param name="grammarPath">The grammar file.</param>
+    /// <param name="inputPath">The input file.</param>
+    /// <param name="startRule">The start rule, or null for the grammar's default.</param>
+    /// <returns>A task that completes when the assertion has run.</returns>
+    public static async Task AssertExpectedDiagnosticsAsync(string grammarPath, string inputPath, string? startRule = null)
+    {
+        AssertExpectedDiagnostics(await LoadAsync(grammarPath), inputPath, startRule);
+    }
+
+    /// <summary>
+    /// Formats diagnostics one per line as "severity code line:column: message", the form used in snapshots.
+    /// </summary>
+    /// <param name="diagnostics">The diagnostics.</param>
+    /// <returns>The formatted diagnostics, each ending with a newline.</returns>
+    public static string FormatDiagnostics(IEnumerable<Diagnostic> diagnostics)
+    {
+        ArgumentNullException.ThrowIfNull(diagnostics);
+
+        var text = new StringBuilder();
+        foreach (var diagnostic in diagnostics)
+        {
+            text.Append(FormatDiagnostic(diagnostic)).Append('\n');
+        }
+
+        return text.ToString();
+    }
+
+    internal static string FormatDiagnostic(Diagnostic diagnostic)
+    {
+        var text = new StringBuilder(diagnostic.Severity.ToString().ToLowerInvariant()).Append(' ').Append(diagnostic.Code);
+        if (diagnostic.Location != null)
+        {
+            text.Append(' ').Append(diagnostic.Location.Line).Append(':').Append(diagnostic.Location.Column);
+        }
+
+        return text.Append(": ").Append(diagnostic.Message).ToString();
+    }
+
+    private static ParseResult Parse(CompiledGrammar grammar, string inputPath, string? startRule)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        ArgumentNullException.ThrowIfNull(inputPath);
+
+        // Snapshots must not depend on how the input was checked out.
+        var input = File.ReadAllText(inputPath).ReplaceLineEndings("\n");
+        return new GeneralizedParser(grammar).Parse(input, new ParseOptions { StartRule = startRule });
+    }
+
+    private static async Task<CompiledGrammar> LoadAsync(string grammarPath)
+    {
+        ArgumentNullException.ThrowIfNull(grammarPath);
+        return CompiledGrammar.Compile(await new GrammarFileReader().ReadFileAsync(grammarPath));
+    }
+
+    private static CompiledGrammar GetGrammar(GrammarContainer container, string grammarName)
+    {
+        ArgumentNullException.ThrowIfNull(container);
+        ArgumentNullException.ThrowIfNull(grammarName);
+
+        return container.GetGrammar(grammarName)
+            ?? throw new ArgumentException($"Grammar '{grammarName}' is not loaded in the container.", nameof(grammarName));
+    }
+}
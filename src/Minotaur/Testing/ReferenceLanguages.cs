@@ -0,0 +1,96 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Collections.Concurrent;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// A language with a reference frontend for differential testing.
+/// </summary>
+/// <param name="Name">The language name used on the command line, such as "rust".</param>
+/// <param name="SearchPattern">The corpus file pattern, such as "*.rs".</param>
+/// <param name="GrammarFile">The grammar under test, relative to the repository root.</param>
+/// <param name="AllowlistFile">The tolerated differences, relative to the repository root, if any.</param>
+/// <param name="CreateReference">Creates the reference frontend, given the repository root.</param>
+public sealed record ReferenceLanguage(
+    string Name,
+    string SearchPattern,
+    string GrammarFile,
+    string? AllowlistFile,
+    Func<string, IDifferentialFrontend> CreateReference);
+
+/// <summary>
+/// The languages <c>xtest</c> can check against a reference frontend. Rust is built in and compared with
+/// <c>syn</c> through tools/syn-dump; other grammars register their own reference frontends.
+/// </summary>
+public static class ReferenceLanguages
+{
+    /// <summary>
+    /// The environment variable holding the command that runs syn-dump. When set, it replaces
+    /// <see cref="DefaultSynCommand"/> and enables the Rust differential tests.
+    /// </summary>
+    public const string SynCommandVariable = "MINOTAUR_SYN_DUMP";
+
+    /// <summary>
+    /// The command that builds and runs syn-dump from the repository root.
+    /// </summary>
+    public const string DefaultSynCommand = "cargo run --quiet --release --locked --manifest-path tools/syn-dump/Cargo.toml --";
+
+    private static readonly ConcurrentDictionary<string, ReferenceLanguage> Languages = new(StringComparer.OrdinalIgnoreCase);
+
+    static ReferenceLanguages()
+    {
+        Register(Rust);
+    }
+
+    /// <summary>
+    /// Gets Rust, checked against syn.
+    /// </summary>
+    public static ReferenceLanguage Rust { get; } = new(
+        "rust",
+        "*.rs",
+        "grammars/Rust2021.grammar",
+        "tools/syn-dump/rust.allowlist",
+        root => new CommandFrontend("syn", Environment.GetEnvironmentVariable(SynCommandVariable) ?? DefaultSynCommand, root));
+
+    /// <summary>
+    /// Gets the names of the registered languages.
+    /// </summary>
+    public static IEnumerable<string> Names => Languages.Keys.Order(StringComparer.Ordinal);
+
+    /// <summary>
+    /// Registers a language, replacing any registration with the same name.
+    /// </summary>
+    /// <param name="language">The language.</param>
+    public static void Register(ReferenceLanguage language)
+    {
+        ArgumentNullException.ThrowIfNull(language);
+        Languages[language.Name] = language;
+    }
+
+    /// <summary>
+    /// Gets a registered language.
+    /// </summary>
+    /// <param name="name">The language name.</param>
+    /// <returns>The language, or null if none is registered under the name.</returns>
+    public static ReferenceLanguage? Get(string name)
+    {
+        ArgumentNullException.ThrowIfNull(name);
+        return Languages.TryGetValue(name, out var language) ? language : null;
+    }
+}
@@ -0,0 +1,226 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Security.Cryptography;
+using System.Text;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Options for capturing an input into a regression corpus.
+/// </summary>
+public class CorpusCaptureOptions
+{
+    /// <summary>
+    /// Gets or sets a value indicating whether a failing input is reduced before it is stored.
+    /// </summary>
+    public bool Reduce { get; set; } = true;
+
+    /// <summary>
+    /// Gets or sets a value indicating whether identifiers, strings and comments are scrubbed before storing.
+    /// </summary>
+    public bool Anonymize { get; set; } = true;
+
+    /// <summary>
+    /// Gets or sets the maximum number of parses made while reducing.
+    /// </summary>
+    public int MaxTests { get; set; } = 10_000;
+}
+
+/// <summary>
+/// A regression test case in a corpus directory.
+/// </summary>
+/// <param name="Name">The case name, derived from a hash of the input.</param>
+/// <param name="InputPath">The input file.</param>
+/// <param name="SnapshotPath">The expected result: a tree snapshot, a diagnostics snapshot or diagnostic expectations.</param>
+/// <param name="ExpectsDiagnostics">True if the input is expected to fail with the diagnostics in the snapshot.</param>
+public sealed record CorpusCase(string Name, string InputPath, string SnapshotPath, bool ExpectsDiagnostics);
+
+/// <summary>
+/// Captures inputs as regression tests. A case is an input file "&lt;hash&gt;.txt" stored with the snapshot
+/// <see cref="ParseSnapshot"/> would check: the diagnostics for an input that fails, or the tree for one that
+/// parses. Failing inputs are reduced to the smallest input failing with the same first error code, then
+/// anonymized with <see cref="InputAnonymizer"/>, and the snapshot is taken from the stored text. A case whose input
+/// has a "&lt;input&gt;.expected" file is checked against those <see cref="DiagnosticExpectations"/> instead of a snapshot.
+/// </summary>
+public static class RegressionCorpus
+{
+    /// <summary>
+    /// The name of the directory next to a grammar file that holds its corpora.
+    /// </summary>
+    public const string DirectoryName = "corpus";
+
+    /// <summary>
+    /// The extension of corpus input files.
+    /// </summary>
+    public const string InputExtension = ".txt";
+
+    /// <summary>
+    /// Gets the default corpus directory of a grammar file: "corpus/&lt;grammar file name&gt;" next to the grammar.
+    /// </summary>
+    /// <param name="grammarPath">The grammar file.</param>
+    /// <returns>The corpus directory.</returns>
+    public static string GetDirectory(string grammarPath)
+    {
+        ArgumentNullException.ThrowIfNull(grammarPath);
+
+        var directory = Path.GetDirectoryName(Path.GetFullPath(grammarPath))!;
+        return Path.Combine(directory, DirectoryName, Path.GetFileNameWithoutExtension(grammarPath));
+    }
+
+    /// <summary>
+    /// Captures an input as a regression case.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="input">The input text.</param>
+    /// <param name="corpusDirectory">The corpus directory.</param>
+    /// <param name="options">Optional capture options.</param>
+    /// <returns>The stored case. Capturing an input already in the corpus returns the existing case.</returns>
+    /// <exception cref="InvalidOperationException">The input cannot be anonymized without changing how it parses.</exception>
+    public static CorpusCase Add(CompiledGrammar grammar, string input, string corpusDirectory, CorpusCaptureOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        ArgumentNullException.ThrowIfNull(input);
+        ArgumentNullException.ThrowIfNull(corpusDirectory);
+
+        options ??= new CorpusCaptureOptions();
+        var parser = new GeneralizedParser(grammar);
+        input = input.ReplaceLineEndings("\n");
+
+        var result = parser.Parse(input);
+        if (!result.IsSuccess && options.Reduce)
+        {
+            var code = FirstErrorCode(result);
+            input = InputReducer.Reduce(
+                input,
+                grammar,
+                candidate => FirstErrorCode(parser.Parse(candidate)) == code,
+                new ReductionOptions { MaxTests = options.MaxTests }).Input;
+        }
+
+        if (options.Anonymize)
+        {
+            input = InputAnonymizer.Anonymize(input, grammar).Text;
+        }
+
+        result = parser.Parse(input);
+
+        var name = Hash(input);
+        var inputPath = Path.Combine(corpusDirectory, name + InputExtension);
+        var corpusCase = result.IsSuccess
+            ? new CorpusCase(name, inputPath, inputPath + ParseSnapshot.TreeExtension, false)
+            : new CorpusCase(name, inputPath, inputPath + ParseSnapshot.DiagnosticsExtension, true);
+
+        Directory.CreateDirectory(corpusDirectory);
+        File.WriteAllText(inputPath, input);
+        File.WriteAllText(
+            corpusCase.SnapshotPath,
+            result.IsSuccess ? SExpression.Format(result.Tree!) : ParseSnapshot.FormatDiagnostics(result.Diagnostics));
+
+        return corpusCase;
+    }
+
+    /// <summary>
+    /// Lists the cases in a corpus directory, ordered by name.
+    /// </summary>
+    /// <param name="corpusDirectory">The corpus directory.</param>
+    /// <returns>The cases; empty if the directory does not exist.</returns>
+    public static IReadOnlyList<CorpusCase> GetCases(string corpusDirectory)
+    {
+        ArgumentNullException.ThrowIfNull(corpusDirectory);
+
+        if (!Directory.Exists(corpusDirectory))
+        {
+            return Array.Empty<CorpusCase>();
+        }
+
+        return Directory.EnumerateFiles(corpusDirectory, "*" + InputExtension)
+            .OrderBy(path => path, StringComparer.Ordinal)
+            .Select(path =>
+            {
+                var name = Path.GetFileNameWithoutExtension(path);
+                var expectations = path + DiagnosticExpectations.Extension;
+                var diagnostics = path + ParseSnapshot.DiagnosticsExtension;
+                return File.Exists(expectations) ? new CorpusCase(name, path, expectations, true)
+                    : File.Exists(diagnostics) ? new CorpusCase(name, path, diagnostics, true)
+                    : new CorpusCase(name, path, path + ParseSnapshot.TreeExtension, false);
+            })
+            .ToList();
+    }
+
+    /// <summary>
+    /// Checks every case in a corpus directory against its snapshot.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="corpusDirectory">The corpus directory.</param>
+    /// <returns>The number of cases checked.</returns>
+    /// <exception cref="SnapshotMismatchException">One or more cases no longer match; the message lists all of them.</exception>
+    public static int Verify(CompiledGrammar grammar, string corpusDirectory)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var cases = GetCases(corpusDirectory);
+        var failures = new List<string>();
+        foreach (var corpusCase in cases)
+        {
+            try
+            {
+                if (corpusCase.SnapshotPath.EndsWith(DiagnosticExpectations.Extension, StringComparison.Ordinal))
+                {
+                    ParseSnapshot.AssertExpectedDiagnostics(grammar, corpusCase.InputPath);
+                }
+                else if (corpusCase.ExpectsDiagnostics)
+                {
+                    ParseSnapshot.AssertDiagnostics(grammar, corpusCase.InputPath);
+                }
+                else
+                {
+                    ParseSnapshot.AssertParse(grammar, corpusCase.InputPath);
+                }
+            }
+            catch (SnapshotMismatchException ex)
+            {
+                failures.Add(ex.Message);
+            }
+            catch (FormatException ex)
+            {
+                failures.Add($"{corpusCase.SnapshotPath}: {ex.Message}");
+            }
+        }
+
+        if (failures.Count > 0)
+        {
+            throw new SnapshotMismatchException(
+                $"{failures.Count} of {cases.Count} corpus cases failed:\n{string.Join("\n", failures)}",
+                corpusDirectory);
+        }
+
+        return cases.Count;
+    }
+
+    private static string? FirstErrorCode(ParseResult result)
+    {
+        return result.Diagnostics.FirstOrDefault(d => d.Severity == DiagnosticSeverity.Error)?.Code;
+    }
+
+    private static string Hash(string input)
+    {
+        return Convert.ToHexString(SHA256.HashData(Encoding.UTF8.GetBytes(input)))[..12].ToLowerInvariant();
+    }
+}
@@ -0,0 +1,298 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+using Minotaur.Parser;
+using Minotaur.Unparser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Options for round-trip property checks.
+/// </summary>
+public class RoundTripOptions
+{
+    /// <summary>
+    /// Gets or sets the rules excluded from generated inputs, for constructs known not to round-trip.
+    /// </summary>
+    public ISet<string> ExcludedRules { get; set; } = new HashSet<string>();
+
+    /// <summary>
+    /// Gets or sets the rule depth after which generated inputs are completed as quickly as possible.
+    /// </summary>
+    public int MaxDepth { get; set; } = 12;
+
+    /// <summary>
+    /// Gets or sets the start rule, or null for the grammar's start rule.
+    /// </summary>
+    public string? StartRule { get; set; }
+
+    /// <summary>
+    /// Gets or sets the pretty-printer under test. Defaults to <see cref="RoundTripProperty.Print"/>.
+    /// </summary>
+    public Func<CognitiveGraphNode, string>? Printer { get; set; }
+
+    /// <summary>
+    /// Gets or sets a value indicating whether a failing input is shrunk with <see cref="InputReducer"/>.
+    /// </summary>
+    public bool Shrink { get; set; } = true;
+
+    /// <summary>
+    /// Gets or sets the maximum number of checks made while shrinking.
+    /// </summary>
+    public int MaxShrinkTests { get; set; } = 1_000;
+}
+
+/// <summary>
+/// The stage of the round trip that failed.
+/// </summary>
+public enum RoundTripStage
+{
+    /// <summary>
+    /// The generated input did not parse.
+    /// </summary>
+    Parse,
+
+    /// <summary>
+    /// The printed tree did not parse.
+    /// </summary>
+    Reparse,
+
+    /// <summary>
+    /// The reparsed tree differs from the original tree.
+    /// </summary>
+    Compare
+}
+
+/// <summary>
+/// A failed round trip.
+/// </summary>
+public sealed class RoundTripFailure
+{
+    /// <summary>
+    /// Gets the iteration that failed, counted from zero.
+    /// </summary>
+    public int Iteration { get; init; }
+
+    /// <summary>
+    /// Gets the seed that generates the failing input as the first iteration of a run.
+    /// </summary>
+    public int Seed { get; init; }
+
+    /// <summary>
+    /// Gets the stage that failed.
+    /// </summary>
+    public RoundTripStage Stage { get; init; }
+
+    /// <summary>
+    /// Gets the generated input.
+    /// </summary>
+    public string Input { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Gets the shrunk input that fails at the same stage, or null if the input was not shrunk.
+    /// </summary>
+    public string? ReducedInput { get; init; }
+
+    /// <summary>
+    /// Gets the printed form of the input's tree, or null if the input did not parse.
+    /// </summary>
+    public string? Printed { get; init; }
+
+    /// <summary>
+    /// Gets the failure details: diagnostics, or a diff of the two trees.
+    /// </summary>
+    public string Message { get; init; } = string.Empty;
+
+    /// <summary>
+    /// Returns a report of the failure including the seed to reproduce it.
+    /// </summary>
+    /// <returns>The report.</returns>
+    public override string ToString()
+    {
+        var text = new StringBuilder()
+            .Append($"Round trip failed at iteration {Iteration} ({Stage}); reproduce with seed {Seed} and 1 iteration\n")
+            .Append($"input: {Input}\n");
+
+        if (ReducedInput != null)
+        {
+            text.Append($"reduced: {ReducedInput}\n");
+        }
+
+        if (Printed != null)
+        {
+            text.Append($"printed: {Printed}\n");
+        }
+
+        return text.Append(Message).ToString();
+    }
+}
+
+/// <summary>
+/// The result of a round-trip property check.
+/// </summary>
+public sealed class RoundTripResult
+{
+    /// <summary>
+    /// Gets the seed of the run.
+    /// </summary>
+    public int Seed { get; init; }
+
+    /// <summary>
+    /// Gets the number of iterations that ran, including a failing one.
+    /// </summary>
+    public int Iterations { get; init; }
+
+    /// <summary>
+    /// Gets the first failure, or null if every iteration passed.
+    /// </summary>
+    public RoundTripFailure? Failure { get; init; }
+
+    /// <summary>
+    /// Gets a value indicating whether every iteration passed.
+    /// </summary>
+    public bool IsSuccess => Failure == null;
+}
+
+/// <summary>
+/// Checks that a grammar, its parser and a pretty-printer agree: random sentences of the grammar are parsed,
+/// printed, reparsed, and the two trees compared structurally. Iteration i uses seed + i, so a failure can be
+/// reproduced alone from the seed it reports.
+/// </summary>
+public static class RoundTripProperty
+{
+    /// <summary>
+    /// Runs the property.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="iterations">The number of generated inputs.</param>
+    /// <param name="seed">The seed of the first iteration.</param>
+    /// <param name="options">Optional round-trip options.</param>
+    /// <returns>The result, with the first failure if any.</returns>
+    public static RoundTripResult Check(CompiledGrammar grammar, int iterations, int seed, RoundTripOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        ArgumentOutOfRangeException.ThrowIfNegative(iterations);
+
+        options ??= new RoundTripOptions();
+        var generator = new SentenceGenerator(grammar, new SentenceGeneratorOptions
+        {
+            MaxDepth = options.MaxDepth,
+            ExcludedRules = options.ExcludedRules
+        });
+        var parser = new GeneralizedParser(grammar);
+        var parseOptions = new ParseOptions { StartRule = options.StartRule };
+        var printer = options.Printer ?? Print;
+
+        for (var i = 0; i < iterations; i++)
+        {
+            var iterationSeed = unchecked(seed + i);
+            var input = generator.Generate(new Random(iterationSeed), options.StartRule);
+            var failure = Verify(parser, parseOptions, printer, input);
+            if (failure == null)
+            {
+                continue;
+            }
+
+            string? reduced = null;
+            if (options.Shrink)
+            {
+                var stage = failure.Value.Stage;
+                reduced = InputReducer.Reduce(
+                    input,
+                    grammar,
+                    candidate => Verify(parser, parseOptions, printer, candidate)?.Stage == stage,
+                    new ReductionOptions { MaxTests = options.MaxShrinkTests, ParseOptions = parseOptions }).Input;
+            }
+
+            return new RoundTripResult
+            {
+                Seed = seed,
+                Iterations = i + 1,
+                Failure = new RoundTripFailure
+                {
+                    Iteration = i,
+                    Seed = iterationSeed,
+                    Stage = failure.Value.Stage,
+                    Input = input,
+                    ReducedInput = reduced,
+                    Printed = failure.Value.Printed,
+                    Message = failure.Value.Message
+                }
+            };
+        }
+
+        return new RoundTripResult { Seed = seed, Iterations = iterations };
+    }
+
+    /// <summary>
+    /// The default pretty-printer: unparses the tree with <see cref="GraphUnparser"/>, separating tokens with spaces.
+    /// </summary>
+    /// <param name="tree">The tree.</param>
+    /// <returns>The printed source.</returns>
+    public static string Print(CognitiveGraphNode tree)
+    {
+        ArgumentNullException.ThrowIfNull(tree);
+
+        using var unparser = new GraphUnparser();
+        unparser.RegisterStrategy("terminal", new SpacedTerminalUnparseStrategy());
+        return unparser.Unparse(tree);
+    }
+
+    private static (RoundTripStage Stage, string? Printed, string Message)? Verify(
+        GeneralizedParser parser, ParseOptions parseOptions, Func<CognitiveGraphNode, string> printer, string input)
+    {
+        var original = parser.Parse(input, parseOptions);
+        if (!original.IsSuccess)
+        {
+            return (RoundTripStage.Parse, null, ParseSnapshot.FormatDiagnostics(original.Diagnostics));
+        }
+
+        var printed = printer(original.Tree!);
+        var reparsed = parser.Parse(printed, parseOptions);
+        if (!reparsed.IsSuccess)
+        {
+            return (RoundTripStage.Reparse, printed, ParseSnapshot.FormatDiagnostics(reparsed.Diagnostics));
+        }
+
+        var diff = Snapshot.Diff(SExpression.Format(original.Tree!), SExpression.Format(reparsed.Tree!));
+        return diff.Length == 0 ? null : (RoundTripStage.Compare, printed, diff);
+    }
+
+    private sealed class SpacedTerminalUnparseStrategy : UnparseStrategyBase
+    {
+        private bool _started;
+
+        public override void UnparseNode(CognitiveGraphNode node, UnparseContext context)
+        {
+            var text = GetNodeText(node);
+            if (string.IsNullOrEmpty(text))
+            {
+                return;
+            }
+
+            if (_started)
+            {
+                context.Write(" ");
+            }
+
+            context.Write(text);
+            _started = true;
+        }
+    }
+}
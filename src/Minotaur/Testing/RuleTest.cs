@@ -0,0 +1,335 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Thrown when a snippet of a <see cref="RuleTest"/> does not parse as expected.
+/// </summary>
+public class RuleTestException : Exception
+{
+    /// <summary>
+    /// Initializes a new instance of the RuleTestException class.
+    /// </summary>
+    /// <param name="report">The report listing the failed cases.</param>
+    public RuleTestException(RuleTestReport report)
+        : base(report.ToString())
+    {
+        Report = report;
+    }
+
+    /// <summary>
+    /// Gets the report listing the failed cases.
+    /// </summary>
+    public RuleTestReport Report { get; }
+}
+
+/// <summary>
+/// A snippet of a <see cref="RuleTest"/> that did not parse as expected, with what the parser saw.
+/// </summary>
+/// <param name="Input">The snippet.</param>
+/// <param name="ExpectedSuccess">Whether the snippet was expected to parse.</param>
+/// <param name="Reason">What went wrong, e.g. that the snippet parsed although it should not have.</param>
+/// <param name="Details">The token stream, the diagnostics and the tree of the longest prefix the rule parsed.</param>
+public sealed record RuleTestFailure(string Input, bool ExpectedSuccess, string Reason, string Details);
+
+/// <summary>
+/// The outcome of a <see cref="RuleTest"/>.
+/// </summary>
+public sealed class RuleTestReport
+{
+    internal RuleTestReport(string grammar, string rule, int caseCount, IReadOnlyList<RuleTestFailure> failures)
+    {
+        Grammar = grammar;
+        Rule = rule;
+        CaseCount = caseCount;
+        Failures = failures;
+    }
+
+    /// <summary>
+    /// Gets the name of the grammar the rule belongs to.
+    /// </summary>
+    public string Grammar { get; }
+
+    /// <summary>
+    /// Gets the rule the snippets were parsed with.
+    /// </summary>
+    public string Rule { get; }
+
+    /// <summary>
+    /// Gets the number of snippets parsed.
+    /// </summary>
+    public int CaseCount { get; }
+
+    /// <summary>
+    /// Gets the snippets that did not parse as expected, in the order they were added.
+    /// </summary>
+    public IReadOnlyList<RuleTestFailure> Failures { get; }
+
+    /// <summary>
+    /// Gets a value indicating whether every snippet parsed as expected.
+    /// </summary>
+    public bool IsSuccess => Failures.Count == 0;
+
+    /// <summary>
+    /// Describes the failures with what the parser saw for each.
+    /// </summary>
+    /// <returns>The description.</returns>
+    public override string ToString()
+    {
+        var text = new StringBuilder($"<{Rule}> of {Grammar}: {CaseCount - Failures.Count} of {CaseCount} cases passed\n");
+        foreach (var failure in Failures)
+        {
+            text.Append('\n').Append(SExpression.Quote(failure.Input)).Append(": ").Append(failure.Reason).Append('\n');
+            text.Append(failure.Details);
+        }
+
+        return text.ToString();
+    }
+}
+
+/// <summary>
+/// Tests a single grammar rule against snippets, without a corpus: snippets that must parse as a whole with the rule,
+/// optionally to a given s-expression tree, and snippets that must not. Each snippet is parsed with
+/// <see cref="GeneralizedParser.ForRule"/>, so the rule's entry table is built once and shared by every snippet. A
+/// failure shows the snippet's tokens, its diagnostics and the tree of the longest prefix the rule did parse.
+/// </summary>
+/// <example>
+/// <code>
+/// RuleTest.Check(BuiltInGrammars.Json, "value", ok: new[] { "\"a\"", "-0" }, err: new[] { "\"unterminated", "01" });
+///
+/// RuleTest.For(grammar, "array")
+///     .Ok("[]")
+///     .OkWithTree("[1]", """(array "[" (elements (value (NUMBER "1"))) "]")""")
+///     .Err("[1,]")
+///     .Assert();
+/// </code>
+/// </example>
+public sealed class RuleTest
+{
+    private readonly GeneralizedParser _parser;
+    private readonly List<(string Input, bool Ok, string? Tree)> _cases = new();
+
+    private RuleTest(GeneralizedParser parser, string rule)
+    {
+        _parser = parser;
+        Rule = rule;
+    }
+
+    /// <summary>
+    /// Gets the rule under test.
+    /// </summary>
+    public string Rule { get; }
+
+    /// <summary>
+    /// Starts a test of a rule.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <param name="rule">The rule name.</param>
+    /// <returns>The test, to add snippets to.</returns>
+    /// <exception cref="ArgumentException">The grammar does not define the rule.</exception>
+    public static RuleTest For(CompiledGrammar grammar, string rule)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+        ArgumentNullException.ThrowIfNull(rule);
+
+        return new RuleTest(new GeneralizedParser(grammar).ForRule(rule), rule);
+    }
+
+    /// <summary>
+    /// Asserts in one call that a rule parses every <paramref name="ok"/> snippet as a whole and none of the
+    /// <paramref name="err"/> snippets.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <param name="rule">The rule name.</param>
+    /// <param name="ok">The snippets that must parse.</param>
+    /// <param name="err">The snippets that must not parse, if any.</param>
+    /// <exception cref="RuleTestException">A snippet did not parse as expected.</exception>
+    public static void Check(CompiledGrammar grammar, string rule, IEnumerable<string> ok, IEnumerable<string>? err = null)
+    {
+        ArgumentNullException.ThrowIfNull(ok);
+
+        For(grammar, rule).Ok(ok.ToArray()).Err((err ?? Array.Empty<string>()).ToArray()).Assert();
+    }
+
+    /// <summary>
+    /// Adds snippets the rule must parse, consuming all of each without errors.
+    /// </summary>
+    /// <param name="inputs">The snippets.</param>
+    /// <returns>This test, for chaining.</returns>
+    public RuleTest Ok(params string[] inputs)
+    {
+        ArgumentNullException.ThrowIfNull(inputs);
+
+        _cases.AddRange(inputs.Select(i => (i, true, (string?)null)));
+        return this;
+    }
+
+    /// <summary>
+    /// Adds a snippet the rule must parse to a given tree. The tree is written as <see cref="SExpression"/> formats
+    /// it, on one line or several; whitespace between its parts is not compared.
+    /// </summary>
+    /// <param name="input">The snippet.</param>
+    /// <param name="tree">The expected s-expression, e.g. <c>(value (NUMBER "1"))</c>.</param>
+    /// <returns>This test, for chaining.</returns>
+    public RuleTest OkWithTree(string input, string tree)
+    {
+        ArgumentNullException.ThrowIfNull(input);
+        ArgumentNullException.ThrowIfNull(tree);
+
+        _cases.Add((input, true, tree));
+        return this;
+    }
+
+    /// <summary>
+    /// Adds snippets the rule must not parse: the parse fails, reports an error or leaves input over.
+    /// </summary>
+    /// <param name="inputs">The snippets.</param>
+    /// <returns>This test, for chaining.</returns>
+    public RuleTest Err(params string[] inputs)
+    {
+        ArgumentNullException.ThrowIfNull(inputs);
+
+        _cases.AddRange(inputs.Select(i => (i, false, (string?)null)));
+        return this;
+    }
+
+    /// <summary>
+    /// Parses every snippet and reports those that did not parse as expected.
+    /// </summary>
+    /// <returns>The report.</returns>
+    public RuleTestReport Run()
+    {
+        var failures = new List<RuleTestFailure>();
+        foreach (var (input, ok, tree) in _cases)
+        {
+            var result = _parser.Parse(input);
+            string? reason = null;
+            if (ok && !result.IsSuccess)
+            {
+                reason = "expected to parse, but did not";
+            }
+            else if (!ok && result.IsSuccess)
+            {
+                reason = "expected not to parse, but did";
+            }
+            else if (tree != null)
+            {
+                var actual = Normalize(SExpression.Format(result.Tree!));
+                if (actual != Normalize(tree))
+                {
+                    reason = $"parsed to {actual}, expected {Normalize(tree)}";
+                }
+            }
+
+            if (reason != null)
+            {
+                failures.Add(new RuleTestFailure(input, ok, reason, Describe(input, result)));
+            }
+        }
+
+        return new RuleTestReport(_parser.Grammar.Name, Rule, _cases.Count, failures);
+    }
+
+    /// <summary>
+    /// Parses every snippet and throws if any did not parse as expected.
+    /// </summary>
+    /// <exception cref="RuleTestException">A snippet did not parse as expected; the message shows what the parser
+    /// saw for each.</exception>
+    public void Assert()
+    {
+        var report = Run();
+        if (!report.IsSuccess)
+        {
+            throw new RuleTestException(report);
+        }
+    }
+
+    // What the parser saw: the tokens, the diagnostics, and how far the rule got when it may stop early.
+    private string Describe(string input, ParseResult result)
+    {
+        var text = new StringBuilder("  tokens:\n");
+        foreach (var token in result.Tokens)
+        {
+            text.Append("    ").Append(token.Kind).Append(' ').Append(SExpression.Quote(token.Text)).Append(" at ").Append(token.Offset).Append('\n');
+        }
+
+        var diagnostics = result.Diagnostics.Where(d => d.Severity >= DiagnosticSeverity.Warning).ToList();
+        if (diagnostics.Count > 0)
+        {
+            text.Append("  diagnostics:\n");
+            foreach (var diagnostic in diagnostics)
+            {
+                text.Append("    ").Append(ParseSnapshot.FormatDiagnostic(diagnostic)).Append('\n');
+            }
+        }
+
+        var partial = result.Tree != null ? result : _parser.Parse(input, new ParseOptions { End = EntryPointEnd.Prefix });
+        if (partial.Tree == null)
+        {
+            return text.Append("  tree: none, the rule parses no prefix of the input\n").ToString();
+        }
+
+        var covered = ReferenceEquals(partial, result) ? "the whole input" : $"the first {partial.ParsedLength} of {input.Length} characters";
+        text.Append("  tree of ").Append(covered).Append(":\n");
+        foreach (var line in SExpression.Format(partial.Tree).TrimEnd('\n').Split('\n'))
+        {
+            text.Append("    ").Append(line).Append('\n');
+        }
+
+        return text.ToString();
+    }
+
+    // Collapses whitespace outside quoted strings, so trees compare the same however they are laid out.
+    private static string Normalize(string tree)
+    {
+        var text = new StringBuilder();
+        var quoted = false;
+        var space = false;
+        for (var i = 0; i < tree.Length; i++)
+        {
+            var c = tree[i];
+            if (!quoted && char.IsWhiteSpace(c))
+            {
+                space = true;
+                continue;
+            }
+
+            if (space && text.Length > 0 && text[^1] != '(' && c != ')')
+            {
+                text.Append(' ');
+            }
+
+            space = false;
+            text.Append(c);
+            if (c == '\\' && quoted && i + 1 < tree.Length)
+            {
+                text.Append(tree[++i]);
+            }
+            else if (c == '"')
+            {
+                quoted = !quoted;
+            }
+        }
+
+        return text.ToString();
+    }
+}
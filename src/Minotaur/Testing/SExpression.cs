@@ -0,0 +1,118 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+using Minotaur.Core;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Writes parse trees in a canonical s-expression form for snapshots: one node per line, indented two spaces
+/// per level. Rules are <c>(rule ...)</c>, literal tokens are their quoted text and other tokens are
+/// <c>(KIND "text")</c>. Ambiguous nodes are marked <c>:ambiguous n</c>. Positions are left out so snapshots
+/// only change when the structure does.
+/// </summary>
+public static class SExpression
+{
+    /// <summary>
+    /// Formats a tree.
+    /// </summary>
+    /// <param name="root">The root node.</param>
+    /// <returns>The s-expression, ending with a newline.</returns>
+    public static string Format(CognitiveGraphNode root)
+    {
+        ArgumentNullException.ThrowIfNull(root);
+
+        var text = new StringBuilder();
+        Write(text, root, 0);
+        return text.Append('\n').ToString();
+    }
+
+    /// <summary>
+    /// Quotes a string with the escapes used in s-expressions.
+    /// </summary>
+    /// <param name="value">The string.</param>
+    /// <returns>The quoted string.</returns>
+    public static string Quote(string value)
+    {
+        ArgumentNullException.ThrowIfNull(value);
+
+        var text = new StringBuilder(value.Length + 2).Append('"');
+        foreach (var c in value)
+        {
+            text.Append(c switch
+            {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                _ => c.ToString()
+            });
+        }
+
+        return text.Append('"').ToString();
+    }
+
+    // Nodes are written from a heap stack, so deep trees cannot overflow the thread's stack. A null node
+    // closes the rule opened before its children.
+    private static void Write(StringBuilder text, CognitiveGraphNode root, int depth)
+    {
+        var pending = new Stack<(CognitiveGraphNode? Node, int Depth)>();
+        pending.Push((root, depth));
+
+        while (pending.Count > 0)
+        {
+            var (node, level) = pending.Pop();
+            if (node == null)
+            {
+                text.Append(')');
+                continue;
+            }
+
+            if (level > depth)
+            {
+                text.Append('\n');
+            }
+
+            text.Append(' ', level * 2);
+
+            switch (node)
+            {
+                case TerminalNode terminal when terminal.TokenType.StartsWith('"'):
+                    text.Append(Quote(terminal.Text));
+                    continue;
+
+                case TerminalNode terminal:
+                    text.Append('(').Append(terminal.TokenType).Append(' ').Append(Quote(terminal.Text)).Append(')');
+                    continue;
+            }
+
+            text.Append('(').Append(node is NonTerminalNode rule ? rule.RuleName : node.NodeType);
+            if (node.Metadata.TryGetValue("ambiguous", out var count))
+            {
+                text.Append(" :ambiguous ").Append(count);
+            }
+
+            pending.Push((null, level));
+            for (var i = node.Children.Count - 1; i >= 0; i--)
+            {
+                pending.Push((node.Children[i], level + 1));
+            }
+        }
+    }
+}
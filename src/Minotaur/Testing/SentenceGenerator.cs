@@ -0,0 +1,329 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text.RegularExpressions;
+using Minotaur.Parser;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Options for generating sentences from a grammar.
+/// </summary>
+public class SentenceGeneratorOptions
+{
+    /// <summary>
+    /// Gets or sets the rule depth after which the generator only picks the shortest way to finish a sentence.
+    /// </summary>
+    public int MaxDepth { get; set; } = 12;
+
+    /// <summary>
+    /// Gets or sets the rules that must not appear in generated sentences.
+    /// </summary>
+    public ISet<string> ExcludedRules { get; set; } = new HashSet<string>();
+}
+
+/// <summary>
+/// Generates random sentences of a grammar. Literal terminals are written as themselves; tokens and inline
+/// patterns use the examples of their token definition or a built-in sample that the grammar's lexer reads back
+/// as exactly that token. Tokens are separated by single spaces. Sentences are reproducible for a given seed.
+/// </summary>
+public sealed class SentenceGenerator
+{
+    private static readonly string[] Samples =
+    {
+        "a", "b", "x", "y", "foo", "bar", "Foo", "_", "n1", "0", "1", "42", "3.5", "\"s\"", "'c'", "+", "-", "*", "/"
+    };
+
+    private readonly CompiledGrammar _grammar;
+    private readonly SentenceGeneratorOptions _options;
+    private readonly Dictionary<string, IReadOnlyList<string>> _terminalSamples = new();
+    private readonly Dictionary<CompiledAlternative, int> _heights = new();
+
+    /// <summary>
+    /// Initializes a new instance of the SentenceGenerator class.
+    /// </summary>
+    /// <param name="grammar">The compiled grammar.</param>
+    /// <param name="options">Optional generator options.</param>
+    public SentenceGenerator(CompiledGrammar grammar, SentenceGeneratorOptions? options = null)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        _grammar = grammar;
+        _options = options ?? new SentenceGeneratorOptions();
+
+        var lexer = new GrammarLexer(grammar);
+        foreach (var terminal in grammar.GetTerminals())
+        {
+            _terminalSamples[terminal.Key] = terminal.Kind == GrammarSymbolKind.Literal
+                ? new[] { terminal.Name }
+                : FindSamples(grammar, lexer, terminal);
+        }
+
+        ComputeHeights();
+    }
+
+    /// <summary>
+    /// Gets the terminals for which no sample text was found. Alternatives using them are never generated.
+    /// </summary>
+    public IReadOnlyList<string> UnsupportedTerminals => _terminalSamples.Where(t => t.Value.Count == 0).Select(t => t.Key).ToList();
+
+    /// <summary>
+    /// Determines whether a rule can be generated without excluded rules and unsupported terminals.
+    /// </summary>
+    /// <param name="ruleName">The rule name.</param>
+    /// <returns>True if the rule has at least one finite derivation.</returns>
+    public bool CanGenerate(string ruleName)
+    {
+        return _grammar.GetRule(ruleName) is { } rule && Height(rule) < int.MaxValue;
+    }
+
+    /// <summary>
+    /// Generates a sentence.
+    /// </summary>
+    /// <param name="random">The random source.</param>
+    /// <param name="startRule">The rule to generate, or null for the grammar's start rule.</param>
+    /// <returns>The sentence.</returns>
+    /// <exception cref="InvalidOperationException">The rule has no derivation without excluded rules and unsupported terminals.</exception>
+    public string Generate(Random random, string? startRule = null)
+    {
+        ArgumentNullException.ThrowIfNull(random);
+
+        var name = startRule ?? _grammar.StartRule;
+        var rule = _grammar.GetRule(name) ?? throw new ArgumentException($"Rule '{name}' is not defined", nameof(startRule));
+        if (Height(rule) == int.MaxValue)
+        {
+            throw new InvalidOperationException(
+                $"Rule '{name}' cannot be generated without excluded rules or unsupported terminals ({string.Join(", ", UnsupportedTerminals)})");
+        }
+
+        var tokens = new List<string>();
+        Expand(rule, 0, random, tokens);
+        return string.Join(" ", tokens);
+    }
+
+    /// <summary>
+    /// Generates a sentence whose derivation uses a specific alternative. From the start rule the generator follows
+    /// the shortest chain of rule references that reaches the alternative's rule, expands the alternative there, and
+    /// finishes everything else as <see cref="Generate"/> would.
+    /// </summary>
+    /// <param name="target">The alternative the derivation must use.</param>
+    /// <param name="random">The random source.</param>
+    /// <param name="startRule">The rule to generate, or null for the grammar's start rule.</param>
+    /// <returns>The sentence, or null if the alternative cannot be reached from the start rule or has no derivation
+    /// without excluded rules and unsupported terminals.</returns>
+    public string? GenerateThrough(CompiledAlternative target, Random random, string? startRule = null)
+    {
+        ArgumentNullException.ThrowIfNull(target);
+        ArgumentNullException.ThrowIfNull(random);
+
+        var name = startRule ?? _grammar.StartRule;
+        var rule = _grammar.GetRule(name) ?? throw new ArgumentException($"Rule '{name}' is not defined", nameof(startRule));
+        if (!_heights.TryGetValue(target, out var height) || height == int.MaxValue)
+        {
+            return null;
+        }
+
+        var distances = ComputeDistances(target.Rule);
+        if (!distances.ContainsKey(rule.Name))
+        {
+            return null;
+        }
+
+        var tokens = new List<string>();
+        ExpandThrough(rule, target, distances, 0, random, tokens);
+        return string.Join(" ", tokens);
+    }
+
+    private void ExpandThrough(CompiledRule rule, CompiledAlternative target, Dictionary<string, int> distances, int depth, Random random, List<string> tokens)
+    {
+        CompiledAlternative alternative;
+        var step = -1;
+        if (rule == target.Rule)
+        {
+            alternative = target;
+        }
+        else
+        {
+            // Any alternative with a finite derivation that references a rule one step closer to the target will do.
+            var distance = distances[rule.Name];
+            var candidates = rule.Alternatives
+                .Where(a => _heights[a] < int.MaxValue)
+                .Select(a => (Alternative: a, Steps: Enumerable.Range(0, a.Symbols.Count)
+                    .Where(i => a.Symbols[i].Kind == GrammarSymbolKind.Rule && distances.GetValueOrDefault(a.Symbols[i].Name, int.MaxValue) == distance - 1)
+                    .ToList()))
+                .Where(c => c.Steps.Count > 0)
+                .ToList();
+
+            var candidate = candidates[random.Next(candidates.Count)];
+            alternative = candidate.Alternative;
+            step = candidate.Steps[random.Next(candidate.Steps.Count)];
+        }
+
+        for (var i = 0; i < alternative.Symbols.Count; i++)
+        {
+            var symbol = alternative.Symbols[i];
+            if (i == step)
+            {
+                ExpandThrough(_grammar.GetRule(symbol.Name)!, target, distances, depth + 1, random, tokens);
+            }
+            else if (symbol.Kind == GrammarSymbolKind.Rule)
+            {
+                Expand(_grammar.GetRule(symbol.Name)!, depth + 1, random, tokens);
+            }
+            else
+            {
+                var samples = _terminalSamples[symbol.Key];
+                tokens.Add(samples[random.Next(samples.Count)]);
+            }
+        }
+    }
+
+    private Dictionary<string, int> ComputeDistances(CompiledRule targetRule)
+    {
+        // Breadth-first search backwards over rule references, using only alternatives that can be finished.
+        var referencedBy = new Dictionary<string, List<string>>();
+        foreach (var alternative in _grammar.Rules.SelectMany(r => r.Alternatives).Where(a => _heights[a] < int.MaxValue))
+        {
+            foreach (var symbol in alternative.Symbols.Where(s => s.Kind == GrammarSymbolKind.Rule))
+            {
+                if (!referencedBy.TryGetValue(symbol.Name, out var parents))
+                {
+                    parents = new List<string>();
+                    referencedBy[symbol.Name] = parents;
+                }
+
+                parents.Add(alternative.Rule.Name);
+            }
+        }
+
+        var distances = new Dictionary<string, int> { [targetRule.Name] = 0 };
+        var queue = new Queue<string>();
+        queue.Enqueue(targetRule.Name);
+        while (queue.Count > 0)
+        {
+            var name = queue.Dequeue();
+            foreach (var parent in referencedBy.GetValueOrDefault(name) ?? new List<string>())
+            {
+                if (distances.TryAdd(parent, distances[name] + 1))
+                {
+                    queue.Enqueue(parent);
+                }
+            }
+        }
+
+        return distances;
+    }
+
+    private void Expand(CompiledRule rule, int depth, Random random, List<string> tokens)
+    {
+        var candidates = rule.Alternatives.Where(a => _heights[a] < int.MaxValue).ToList();
+
+        // Past the depth limit, only alternatives that finish soonest are allowed so generation terminates.
+        if (depth >= _options.MaxDepth)
+        {
+            var shortest = candidates.Min(a => _heights[a]);
+            candidates = candidates.Where(a => _heights[a] == shortest).ToList();
+        }
+
+        var alternative = candidates[random.Next(candidates.Count)];
+        foreach (var symbol in alternative.Symbols)
+        {
+            if (symbol.Kind == GrammarSymbolKind.Rule)
+            {
+                Expand(_grammar.GetRule(symbol.Name)!, depth + 1, random, tokens);
+            }
+            else
+            {
+                var samples = _terminalSamples[symbol.Key];
+                tokens.Add(samples[random.Next(samples.Count)]);
+            }
+        }
+    }
+
+    private int Height(CompiledRule rule)
+    {
+        return rule.Alternatives.Count == 0 ? int.MaxValue : rule.Alternatives.Min(a => _heights[a]);
+    }
+
+    private void ComputeHeights()
+    {
+        var alternatives = _grammar.Rules.SelectMany(r => r.Alternatives).ToList();
+        foreach (var alternative in alternatives)
+        {
+            _heights[alternative] = int.MaxValue;
+        }
+
+        // Fixed point over the shortest derivation height of each alternative; alternatives that reference excluded
+        // rules or terminals without samples keep an infinite height.
+        bool changed;
+        do
+        {
+            changed = false;
+            foreach (var alternative in alternatives)
+            {
+                if (_options.ExcludedRules.Contains(alternative.Rule.Name))
+                {
+                    continue;
+                }
+
+                var height = 1;
+                foreach (var symbol in alternative.Symbols)
+                {
+                    var symbolHeight = symbol.Kind switch
+                    {
+                        GrammarSymbolKind.Rule when _options.ExcludedRules.Contains(symbol.Name) => int.MaxValue,
+                        GrammarSymbolKind.Rule => _grammar.GetRule(symbol.Name) is { } rule ? Height(rule) : int.MaxValue,
+                        _ => _terminalSamples.GetValueOrDefault(symbol.Key)?.Count > 0 ? 0 : int.MaxValue
+                    };
+
+                    height = symbolHeight == int.MaxValue ? int.MaxValue : Math.Max(height, symbolHeight + 1);
+                    if (height == int.MaxValue)
+                    {
+                        break;
+                    }
+                }
+
+                if (height < _heights[alternative])
+                {
+                    _heights[alternative] = height;
+                    changed = true;
+                }
+            }
+        }
+        while (changed);
+    }
+
+    private static IReadOnlyList<string> FindSamples(CompiledGrammar grammar, GrammarLexer lexer, GrammarSymbol terminal)
+    {
+        var literals = grammar.GetTerminals().Where(t => t.Kind == GrammarSymbolKind.Literal).Select(t => t.Name).ToHashSet();
+        var examples = terminal.Kind == GrammarSymbolKind.Token
+            ? grammar.Source.TokenRules.Patterns.FirstOrDefault(p => p.Name == terminal.Name)?.Examples ?? new List<string>()
+            : new List<string>();
+
+        // A sample is only usable if the lexer reads it back as this terminal and nothing else.
+        return examples
+            .Concat(Samples)
+            .Distinct()
+            .Where(sample => !literals.Contains(sample))
+            .Where(sample =>
+            {
+                var result = lexer.Tokenize(sample);
+                return result.Diagnostics.Count == 0 && result.Tokens.Count == 1 && result.Tokens[0].Kind == terminal.Key;
+            })
+            .ToList();
+    }
+}
@@ -0,0 +1,206 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Text;
+
+namespace Minotaur.Testing;
+
+/// <summary>
+/// Thrown when a value does not match its stored snapshot, or the snapshot does not exist.
+/// </summary>
+public class SnapshotMismatchException : Exception
+{
+    /// <summary>
+    /// Initializes a new instance of the SnapshotMismatchException class.
+    /// </summary>
+    /// <param name="message">The message, including the diff.</param>
+    /// <param name="snapshotPath">The snapshot file.</param>
+    public SnapshotMismatchException(string message, string snapshotPath)
+        : base(message)
+    {
+        SnapshotPath = snapshotPath;
+    }
+
+    /// <summary>
+    /// Gets the snapshot file.
+    /// </summary>
+    public string SnapshotPath { get; }
+}
+
+/// <summary>
+/// Compares text with snapshot files. Setting the MINOTAUR_UPDATE_SNAPSHOTS environment variable to 1 writes the
+/// actual text to the snapshot instead of comparing, which is how snapshots are created and accepted.
+/// Line endings are normalized to "\n" on both sides.
+/// </summary>
+public static class Snapshot
+{
+    /// <summary>
+    /// The environment variable that switches snapshot assertions to update mode when set to 1.
+    /// </summary>
+    public const string UpdateVariable = "MINOTAUR_UPDATE_SNAPSHOTS";
+
+    private const int DiffContext = 3;
+
+    /// <summary>
+    /// Gets a value indicating whether snapshots are being updated rather than compared.
+    /// </summary>
+    public static bool IsUpdating => Environment.GetEnvironmentVariable(UpdateVariable) == "1";
+
+    /// <summary>
+    /// Asserts that text matches a snapshot file, or writes it in update mode.
+    /// </summary>
+    /// <param name="actual">The text produced by the test.</param>
+    /// <param name="snapshotPath">The snapshot file.</param>
+    /// <exception cref="SnapshotMismatchException">The text differs from the snapshot, or there is no snapshot.</exception>
+    public static void AssertMatches(string actual, string snapshotPath)
+    {
+        ArgumentNullException.ThrowIfNull(actual);
+        ArgumentNullException.ThrowIfNull(snapshotPath);
+
+        actual = actual.ReplaceLineEndings("\n");
+
+        if (IsUpdating)
+        {
+            Directory.CreateDirectory(Path.GetDirectoryName(Path.GetFullPath(snapshotPath))!);
+            File.WriteAllText(snapshotPath, actual);
+            return;
+        }
+
+        if (!File.Exists(snapshotPath))
+        {
+            throw new SnapshotMismatchException(
+                $"Snapshot {snapshotPath} does not exist; run with {UpdateVariable}=1 to create it.\n{Diff(string.Empty, actual)}",
+                snapshotPath);
+        }
+
+        var expected = File.ReadAllText(snapshotPath).ReplaceLineEndings("\n");
+        if (expected != actual)
+        {
+            throw new SnapshotMismatchException(
+                $"Snapshot {snapshotPath} does not match; run with {UpdateVariable}=1 to accept the new output.\n{Diff(expected, actual)}",
+                snapshotPath);
+        }
+    }
+
+    /// <summary>
+    /// Renders a line-based diff in unified format, with three lines of context around each change.
+    /// </summary>
+    /// <param name="expected">The expected text.</param>
+    /// <param name="actual">The actual text.</param>
+    /// <param name="expectedName">The name of the expected side in the diff header.</param>
+    /// <param name="actualName">The name of the actual side in the diff header.</param>
+    /// <returns>The diff, or an empty string if the texts are equal.</returns>
+    public static string Diff(string expected, string actual, string expectedName = "expected", string actualName = "actual")
+    {
+        ArgumentNullException.ThrowIfNull(expected);
+        ArgumentNullException.ThrowIfNull(actual);
+
+        var a = SplitLines(expected);
+        var b = SplitLines(actual);
+        var edits = LineEdits(a, b);
+        if (edits.All(e => e.Op == ' '))
+        {
+            return string.Empty;
+        }
+
+        var text = new StringBuilder($"--- {expectedName}\n+++ {actualName}\n");
+        var i = 0;
+        while (i < edits.Count)
+        {
+            if (edits[i].Op == ' ')
+            {
+                i++;
+                continue;
+            }
+
+            // Grow the hunk while the next change is within twice the context of the last one.
+            var start = Math.Max(0, i - DiffContext);
+            var end = i;
+            while (end < edits.Count)
+            {
+                var next = edits.FindIndex(end + 1, e => e.Op != ' ');
+                if (next < 0 || next - end > 2 * DiffContext)
+                {
+                    break;
+                }
+
+                end = next;
+            }
+
+            end = Math.Min(edits.Count - 1, end + DiffContext);
+            var hunk = edits.GetRange(start, end - start + 1);
+            text.Append($"@@ -{edits[start].OldLine + 1},{hunk.Count(e => e.Op != '+')} ")
+                .Append($"+{edits[start].NewLine + 1},{hunk.Count(e => e.Op != '-')} @@\n");
+            foreach (var edit in hunk)
+            {
+                text.Append(edit.Op).Append(' ').Append(edit.Text).Append('\n');
+            }
+
+            i = end + 1;
+        }
+
+        return text.ToString();
+    }
+
+    internal static string[] SplitLines(string text)
+    {
+        text = text.ReplaceLineEndings("\n");
+        if (text.EndsWith('\n'))
+        {
+            text = text[..^1];
+        }
+
+        return text.Length == 0 ? Array.Empty<string>() : text.Split('\n');
+    }
+
+    internal static List<(char Op, string Text, int OldLine, int NewLine)> LineEdits(string[] a, string[] b)
+    {
+        // Longest common subsequence over lines; snapshots are small enough for the quadratic table.
+        var lcs = new int[a.Length + 1, b.Length + 1];
+        for (var i = a.Length - 1; i >= 0; i--)
+        {
+            for (var j = b.Length - 1; j >= 0; j--)
+            {
+                lcs[i, j] = a[i] == b[j] ? lcs[i + 1, j + 1] + 1 : Math.Max(lcs[i + 1, j], lcs[i, j + 1]);
+            }
+        }
+
+        var edits = new List<(char, string, int, int)>();
+        int x = 0, y = 0;
+        while (x < a.Length || y < b.Length)
+        {
+            if (x < a.Length && y < b.Length && a[x] == b[y])
+            {
+                edits.Add((' ', a[x], x, y));
+                x++;
+                y++;
+            }
+            else if (x < a.Length && (y == b.Length || lcs[x + 1, y] >= lcs[x, y + 1]))
+            {
+                edits.Add(('-', a[x], x, y));
+                x++;
+            }
+            else
+            {
+                edits.Add(('+', b[y], x, y));
+                y++;
+            }
+        }
+
+        return edits;
+    }
+}
@@ -285,9 +285,9 @@ internal class UnparseVisitor : CognitiveGraphVisitorBase
         _context = context;
     }
 
-    public override void Visit(CognitiveGraphNode node)
+    // Unparses the node before the base traversal visits its children.
+    protected override void BeforeVisitNode(CognitiveGraphNode node)
     {
-        // First unparse this node
         if (_strategies.TryGetValue(node.NodeType, out var strategy))
         {
             strategy.UnparseNode(node, _context);
@@ -297,8 +297,5 @@ internal class UnparseVisitor : CognitiveGraphVisitorBase
             // Fallback strategy for unknown node types
             _context.Write($"/* Unknown node type: {node.NodeType} */");
         }
-
-        // Then visit children
-        VisitChildren(node);
     }
 }
\ No newline at end of file
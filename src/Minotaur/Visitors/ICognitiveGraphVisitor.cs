@@ -38,8 +38,22 @@ public interface ICognitiveGraphVisitor
 /// <summary>
 /// Base implementation of cognitive graph visitor with depth-first traversal.
 /// </summary>
+/// <remarks>
+/// The traversal keeps the nodes still to visit on a heap stack rather than recursing, so trees nested deeper
+/// than the thread's stack allows can be visited; the cost is one stack entry per pending node. While a
+/// traversal runs, <see cref="Visit"/> calls made from <see cref="VisitChildren"/> schedule the child instead
+/// of visiting it at once. A visitor that overrides <see cref="Visit"/> or <see cref="VisitChildren"/> is
+/// traversed recursively as before instead, so each node is finished, descendants included, before its
+/// override continues, and its depth is limited by the thread's stack.
+/// </remarks>
 public abstract class CognitiveGraphVisitorBase : ICognitiveGraphVisitor
 {
+    // The children scheduled by the VisitChildren call in progress, or null outside one.
+    private List<Core.CognitiveGraphNode>? _scheduled;
+
+    // Whether the visitor overrides Visit or VisitChildren, found on the first visit.
+    private bool? _recursive;
+
     /// <summary>
     /// Visits a cognitive graph node with depth-first traversal.
     /// </summary>
@@ -48,9 +62,54 @@ public abstract class CognitiveGraphVisitorBase : ICognitiveGraphVisitor
     {
         ArgumentNullException.ThrowIfNull(node);
 
-        BeforeVisitNode(node);
-        VisitChildren(node);
-        AfterVisitNode(node);
+        if (_scheduled != null)
+        {
+            _scheduled.Add(node);
+            return;
+        }
+
+        // An override of Visit or VisitChildren may rely on each node being finished when Visit returns.
+        _recursive ??= Overrides(nameof(Visit)) || Overrides(nameof(VisitChildren));
+        if (_recursive.Value)
+        {
+            BeforeVisitNode(node);
+            VisitChildren(node);
+            AfterVisitNode(node);
+            return;
+        }
+
+        // A leaving entry sits below a node's children and runs AfterVisitNode once they are done.
+        var pending = new Stack<(Core.CognitiveGraphNode Node, bool Leaving)>();
+        pending.Push((node, false));
+
+        while (pending.Count > 0)
+        {
+            var (current, leaving) = pending.Pop();
+            if (leaving)
+            {
+                AfterVisitNode(current);
+                continue;
+            }
+
+            BeforeVisitNode(current);
+            pending.Push((current, true));
+
+            var children = new List<Core.CognitiveGraphNode>();
+            _scheduled = children;
+            try
+            {
+                VisitChildren(current);
+            }
+            finally
+            {
+                _scheduled = null;
+            }
+
+            for (var i = children.Count - 1; i >= 0; i--)
+            {
+                pending.Push((children[i], false));
+            }
+        }
     }
 
     /// <summary>
@@ -78,4 +137,10 @@ public abstract class CognitiveGraphVisitorBase : ICognitiveGraphVisitor
     /// </summary>
     /// <param name="node">The node that was visited.</param>
     protected virtual void AfterVisitNode(Core.CognitiveGraphNode node) { }
+
+    private bool Overrides(string methodName)
+    {
+        var method = GetType().GetMethod(methodName, new[] { typeof(Core.CognitiveGraphNode) })!;
+        return method.DeclaringType != typeof(CognitiveGraphVisitorBase);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,134 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Net;
+using System.Text;
+using Minotaur.Parser;
+
+namespace Minotaur.Visualization;
+
+/// <summary>
+/// Renders the documentation of a grammar as a self-contained HTML page: one section per documented rule or
+/// token pattern with its description, deprecations, snippets and examples, plus an index linking to each section.
+/// </summary>
+public class GrammarDocsHtmlRenderer
+{
+    private const string Styles = @"body { font-family: sans-serif; margin: 1.5em; }
+pre { background: #f6f8fa; padding: 0.5em 0.75em; border: 1px solid #d0d7de; }
+.rule { font-weight: bold; color: #0550ae; }
+.token { font-family: monospace; color: #116329; }
+.label { color: #6e7781; font-size: 0.85em; text-transform: uppercase; }
+.deprecated { color: #9a6700; }";
+
+    /// <summary>
+    /// Renders the documentation of a grammar.
+    /// </summary>
+    /// <param name="grammar">The grammar.</param>
+    /// <returns>The HTML document.</returns>
+    public string Render(CompiledGrammar grammar)
+    {
+        ArgumentNullException.ThrowIfNull(grammar);
+
+        var symbols = grammar.Docs.Symbols;
+        var title = string.IsNullOrEmpty(grammar.Name) ? "Grammar" : grammar.Name;
+        var html = new StringBuilder();
+
+        html.AppendLine("<!DOCTYPE html>");
+        html.AppendLine("<html>");
+        html.AppendLine("<head>");
+        html.AppendLine("<meta charset=\"utf-8\">");
+        html.AppendLine($"<title>{Encode(title)} reference</title>");
+        html.AppendLine("<style>");
+        html.AppendLine(Styles);
+        html.AppendLine("</style>");
+        html.AppendLine("</head>");
+        html.AppendLine("<body>");
+        html.AppendLine($"<h1>{Encode(title)} reference</h1>");
+
+        html.AppendLine("<ul>");
+        foreach (var symbol in symbols)
+        {
+            html.AppendLine($"<li><a href=\"#{Anchor(symbol)}\">{RenderName(symbol)}</a></li>");
+        }
+
+        html.AppendLine("</ul>");
+
+        foreach (var symbol in symbols)
+        {
+            html.AppendLine($"<h2 id=\"{Anchor(symbol)}\">{RenderName(symbol)}</h2>");
+            if (symbol.Description != null)
+            {
+                html.AppendLine($"<p>{Encode(symbol.Description)}</p>");
+            }
+
+            if (grammar.GetRule(symbol.Name) is { } rule)
+            {
+                RenderDeprecations(html, rule);
+            }
+
+            RenderBlocks(html, "Snippet", symbol.Snippets);
+            RenderBlocks(html, "Example", symbol.Examples);
+        }
+
+        html.AppendLine("</body>");
+        html.AppendLine("</html>");
+        return html.ToString();
+    }
+
+    private static void RenderDeprecations(StringBuilder html, CompiledRule rule)
+    {
+        // A whole-rule deprecation is shared by every alternative it covers, so it is shown once.
+        foreach (var group in rule.Alternatives.Where(a => a.Deprecation != null).GroupBy(a => a.Deprecation!))
+        {
+            var deprecation = group.Key;
+            var alternatives = deprecation.AppliesToRule
+                ? string.Empty
+                : $" ({string.Join(" | ", group.Select(a => $"<code>{Encode(a.Text)}</code>"))})";
+            html.AppendLine($"<p class=\"deprecated\">{Encode(deprecation.ToString())}{alternatives}</p>");
+            if (deprecation.ReplaceWith != null)
+            {
+                RenderBlocks(html, "Replacement", new[] { deprecation.ReplaceWith });
+            }
+        }
+    }
+
+    private static void RenderBlocks(StringBuilder html, string label, IReadOnlyList<string> blocks)
+    {
+        foreach (var block in blocks)
+        {
+            html.AppendLine($"<div class=\"label\">{label}</div>");
+            html.AppendLine($"<pre>{Encode(block)}</pre>");
+        }
+    }
+
+    private static string RenderName(SymbolDocs symbol)
+    {
+        return symbol.IsRule
+            ? $"<span class=\"rule\">&lt;{Encode(symbol.Name)}&gt;</span>"
+            : $"<span class=\"token\">{Encode(symbol.Name)}</span>";
+    }
+
+    private static string Anchor(SymbolDocs symbol)
+    {
+        return (symbol.IsRule ? "rule-" : "token-") + Uri.EscapeDataString(symbol.Name);
+    }
+
+    private static string Encode(string text)
+    {
+        return WebUtility.HtmlEncode(text);
+    }
+}
@@ -0,0 +1,283 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Net;
+using System.Text;
+using Minotaur.Parser;
+
+namespace Minotaur.Visualization;
+
+/// <summary>
+/// Renders a parse forest as a self-contained HTML page. Ambiguous nodes show each derivation in a tab,
+/// the source region where the derivations differ is highlighted, and subtrees shared between
+/// derivations are rendered once and linked from every place they occur.
+/// </summary>
+public class ParseForestHtmlRenderer
+{
+    private const string Styles = @"body { font-family: sans-serif; margin: 1.5em; }
+pre.source { background: #f6f8fa; padding: 0.75em; border: 1px solid #d0d7de; }
+mark { background: #ffe08a; }
+ul.children { list-style: none; margin: 0; padding-left: 1.25em; border-left: 1px dotted #aaa; }
+.rule { font-weight: bold; color: #0550ae; }
+.token { font-family: monospace; color: #116329; }
+.span { color: #6e7781; font-size: 0.85em; }
+a.ref { font-family: monospace; }
+.ambiguity { border: 2px solid #cf222e; margin: 0.25em 0; padding: 0.25em; }
+.tabs { display: flex; flex-wrap: wrap; }
+.tabs > input { display: none; }
+.tabs > label { order: 0; padding: 0.2em 0.6em; border: 1px solid #d0d7de; cursor: pointer; }
+.tabs > input:checked + label { background: #ddf4ff; }
+.tabs > .panel { order: 1; width: 100%; display: none; }
+.tabs > input:checked + label + .panel { display: block; }
+.parents { color: #6e7781; font-size: 0.85em; }";
+
+    /// <summary>
+    /// Renders the forest of a successful parse result.
+    /// </summary>
+    /// <param name="result">The parse result.</param>
+    /// <returns>The HTML document.</returns>
+    public string Render(ParseResult result)
+    {
+        ArgumentNullException.ThrowIfNull(result);
+
+        if (result.Forest == null)
+        {
+            throw new ArgumentException("Parse result has no forest", nameof(result));
+        }
+
+        var context = new RenderContext(result);
+        var html = new StringBuilder();
+
+        html.AppendLine("<!DOCTYPE html>");
+        html.AppendLine("<html>");
+        html.AppendLine("<head>");
+        html.AppendLine("<meta charset=\"utf-8\">");
+        html.AppendLine($"<title>Parse forest: {Encode(result.StartRule)}</title>");
+        html.AppendLine("<style>");
+        html.AppendLine(Styles);
+        html.AppendLine("</style>");
+        html.AppendLine("</head>");
+        html.AppendLine("<body>");
+        html.AppendLine($"<h1>Parse forest for <span class=\"rule\">&lt;{Encode(result.StartRule)}&gt;</span></h1>");
+        html.AppendLine($"<p>{result.Forest.Nodes.Count} nodes, {result.Forest.AmbiguityCount} ambiguous, {context.SharedNodes.Count} shared.</p>");
+
+        html.AppendLine("<h2>Source</h2>");
+        html.AppendLine($"<pre class=\"source\">{RenderSource(result.Input, context.AmbiguousRegions())}</pre>");
+
+        html.AppendLine("<h2>Forest</h2>");
+        RenderSymbol(html, context, result.Forest.Root, inline: true);
+
+        if (context.SharedNodes.Count > 0)
+        {
+            html.AppendLine("<h2>Shared nodes</h2>");
+            foreach (var node in context.SharedNodes)
+            {
+                var parents = context.Parents[node].Select(p => $"<a class=\"ref\" href=\"#n{p.Id}\">#{p.Id}</a>");
+                html.AppendLine($"<div class=\"parents\">used by {string.Join(", ", parents)}</div>");
+                RenderSymbol(html, context, node, inline: true);
+            }
+        }
+
+        html.AppendLine("</body>");
+        html.AppendLine("</html>");
+        return html.ToString();
+    }
+
+    private static void RenderSymbol(StringBuilder html, RenderContext context, SymbolForestNode node, bool inline)
+    {
+        if (!inline && context.SharedNodes.Contains(node))
+        {
+            html.AppendLine($"<a class=\"ref\" href=\"#n{node.Id}\">&lt;{Encode(node.RuleName)}&gt; #{node.Id}</a> {RenderSpan(context, node)}");
+            return;
+        }
+
+        html.AppendLine($"<div class=\"node\" id=\"n{node.Id}\"><span class=\"rule\">&lt;{Encode(node.RuleName)}&gt;</span> {RenderSpan(context, node)}");
+
+        if (node.IsAmbiguous)
+        {
+            var (start, end) = context.DifferingRegion(node);
+            html.AppendLine($"<div class=\"ambiguity\"><div>{node.Packed.Count} derivations</div>");
+            html.AppendLine("<div class=\"tabs\">");
+            for (var i = 0; i < node.Packed.Count; i++)
+            {
+                var packed = node.Packed[i];
+                var tabId = $"t{node.Id}-{i}";
+                var isChecked = i == 0 ? " checked" : string.Empty;
+                html.AppendLine($"<input type=\"radio\" name=\"t{node.Id}\" id=\"{tabId}\"{isChecked}>");
+                html.AppendLine($"<label for=\"{tabId}\">{Encode(packed.Alternative.Text)}</label>");
+                html.AppendLine("<div class=\"panel\">");
+                html.AppendLine($"<pre class=\"source\">{RenderSource(context.Text(node), new[] { (start - context.Offset(node.Start), end - context.Offset(node.Start)) })}</pre>");
+                RenderChildren(html, context, packed);
+                html.AppendLine("</div>");
+            }
+
+            html.AppendLine("</div>");
+            html.AppendLine("</div>");
+        }
+        else
+        {
+            RenderChildren(html, context, node.Packed[0]);
+        }
+
+        html.AppendLine("</div>");
+    }
+
+    private static void RenderChildren(StringBuilder html, RenderContext context, PackedForestNode packed)
+    {
+        if (packed.Children.Count == 0)
+        {
+            return;
+        }
+
+        html.AppendLine("<ul class=\"children\">");
+        foreach (var child in packed.Children)
+        {
+            html.Append("<li>");
+            if (child is SymbolForestNode symbol)
+            {
+                RenderSymbol(html, context, symbol, inline: false);
+            }
+            else if (child is TokenForestNode leaf)
+            {
+                html.Append($"<span class=\"token\">{Encode(leaf.Token.Text)}</span> <span class=\"span\">{Encode(leaf.Token.Kind)}</span>");
+            }
+
+            html.AppendLine("</li>");
+        }
+
+        html.AppendLine("</ul>");
+    }
+
+    private static string RenderSpan(RenderContext context, SymbolForestNode node)
+    {
+        return $"<span class=\"span\">[{node.Start}, {node.End}) {Encode(context.Text(node))}</span>";
+    }
+
+    private static string RenderSource(string text, IEnumerable<(int Start, int End)> regions)
+    {
+        var merged = new List<(int Start, int End)>();
+        foreach (var region in regions.Where(r => r.End > r.Start).OrderBy(r => r.Start))
+        {
+            if (merged.Count > 0 && region.Start <= merged[^1].End)
+            {
+                merged[^1] = (merged[^1].Start, Math.Max(merged[^1].End, region.End));
+            }
+            else
+            {
+                merged.Add(region);
+            }
+        }
+
+        var builder = new StringBuilder();
+        var position = 0;
+        foreach (var (start, end) in merged)
+        {
+            builder.Append(Encode(text[position..start]));
+            builder.Append("<mark>").Append(Encode(text[start..end])).Append("</mark>");
+            position = end;
+        }
+
+        builder.Append(Encode(text[position..]));
+        return builder.ToString();
+    }
+
+    private static string Encode(string text)
+    {
+        return WebUtility.HtmlEncode(text);
+    }
+
+    private sealed class RenderContext
+    {
+        private readonly ParseResult _result;
+
+        public RenderContext(ParseResult result)
+        {
+            _result = result;
+            var occurrences = new Dictionary<SymbolForestNode, int>();
+
+            foreach (var node in result.Forest!.Nodes)
+            {
+                foreach (var child in node.Packed.SelectMany(p => p.Children).OfType<SymbolForestNode>())
+                {
+                    occurrences[child] = occurrences.GetValueOrDefault(child) + 1;
+
+                    if (!Parents.TryGetValue(child, out var parents))
+                    {
+                        parents = new List<SymbolForestNode>();
+                        Parents[child] = parents;
+                    }
+
+                    if (!parents.Contains(node))
+                    {
+                        parents.Add(node);
+                    }
+                }
+            }
+
+            // A node is shared when it occurs in more than one packed node; it is rendered once and linked elsewhere.
+            SharedNodes = result.Forest.Nodes
+                .Where(n => occurrences.GetValueOrDefault(n) > 1)
+                .ToList();
+        }
+
+        public Dictionary<SymbolForestNode, List<SymbolForestNode>> Parents { get; } = new();
+
+        public List<SymbolForestNode> SharedNodes { get; }
+
+        public int Offset(int tokenIndex)
+        {
+            var tokens = _result.Tokens;
+            return tokenIndex < tokens.Count ? tokens[tokenIndex].Offset : _result.Input.Length;
+        }
+
+        public int EndOffset(int tokenEnd, int tokenStart)
+        {
+            return tokenEnd > tokenStart ? _result.Tokens[tokenEnd - 1].End : Offset(tokenStart);
+        }
+
+        public string Text(SymbolForestNode node)
+        {
+            return _result.Input[Offset(node.Start)..EndOffset(node.End, node.Start)];
+        }
+
+        public (int Start, int End) DifferingRegion(SymbolForestNode node)
+        {
+            var common = node.Packed
+                .Select(p => p.Children.ToHashSet())
+                .Aggregate((a, b) => { a.IntersectWith(b); return a; });
+
+            var differing = node.Packed
+                .SelectMany(p => p.Children)
+                .Where(c => !common.Contains(c) && c.End > c.Start)
+                .ToList();
+
+            if (differing.Count == 0)
+            {
+                return (Offset(node.Start), EndOffset(node.End, node.Start));
+            }
+
+            var start = differing.Min(c => c.Start);
+            var end = differing.Max(c => c.End);
+            return (Offset(start), EndOffset(end, start));
+        }
+
+        public IEnumerable<(int Start, int End)> AmbiguousRegions()
+        {
+            return _result.Forest!.AmbiguousNodes.Select(DifferingRegion);
+        }
+    }
+}
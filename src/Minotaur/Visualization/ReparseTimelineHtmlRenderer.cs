@@ -0,0 +1,130 @@
+/*
+ * This file is part of Minotaur.
+ * 
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * 
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ * 
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Globalization;
+using System.Net;
+using System.Text;
+using Minotaur.Parser;
+
+namespace Minotaur.Visualization;
+
+/// <summary>
+/// Renders a sequence of incremental reparses as an HTML timeline. Each row is an edit, drawn over a
+/// miniature map of the document with the damaged and re-lexed ranges marked, followed by the token
+/// and node reuse counts and the reparse time.
+/// </summary>
+public class ReparseTimelineHtmlRenderer
+{
+    private const string Styles = @"body { font-family: sans-serif; margin: 1.5em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.2em 0.6em; border-bottom: 1px solid #d0d7de; text-align: right; }
+td.edit { text-align: left; font-family: monospace; white-space: pre; }
+td.map { width: 24em; }
+.map { position: relative; height: 1em; background: #f6f8fa; border: 1px solid #d0d7de; }
+.map span { position: absolute; top: 0; bottom: 0; min-width: 2px; }
+.relexed { background: #9ecbff; }
+.damaged { background: #cf222e; }
+tr.failed td { color: #cf222e; }
+.legend span { display: inline-block; width: 1em; height: 1em; vertical-align: middle; }";
+
+    /// <summary>
+    /// Renders the reparse history as a timeline.
+    /// </summary>
+    /// <param name="history">The reparse statistics in edit order.</param>
+    /// <param name="title">An optional title, such as the document name.</param>
+    /// <returns>The HTML document.</returns>
+    public string Render(IReadOnlyList<ReparseStats> history, string? title = null)
+    {
+        ArgumentNullException.ThrowIfNull(history);
+
+        var heading = string.IsNullOrEmpty(title) ? "Incremental reparse timeline" : $"Incremental reparse timeline: {title}";
+        var maxLength = Math.Max(1, history.Select(s => s.DocumentLength).DefaultIfEmpty(0).Max());
+        var html = new StringBuilder();
+
+        html.AppendLine("<!DOCTYPE html>");
+        html.AppendLine("<html>");
+        html.AppendLine("<head>");
+        html.AppendLine("<meta charset=\"utf-8\">");
+        html.AppendLine($"<title>{Encode(heading)}</title>");
+        html.AppendLine("<style>");
+        html.AppendLine(Styles);
+        html.AppendLine("</style>");
+        html.AppendLine("</head>");
+        html.AppendLine("<body>");
+        html.AppendLine($"<h1>{Encode(heading)}</h1>");
+        html.AppendLine("<p class=\"legend\"><span class=\"damaged\"></span> damaged <span class=\"relexed\"></span> re-lexed</p>");
+        html.AppendLine("<table>");
+        html.AppendLine("<tr><th>#</th><th>Edit</th><th>Document</th><th>Re-lexed tokens</th><th>Reused nodes</th><th>Rebuilt nodes</th><th>Reuse</th><th>Lex (ms)</th><th>Parse (ms)</th><th>Total (ms)</th></tr>");
+
+        foreach (var stats in history)
+        {
+            var rowClass = stats.IsSuccess ? string.Empty : " class=\"failed\"";
+            var totalNodes = stats.ReusedNodeCount + stats.RebuiltNodeCount;
+            var reuse = totalNodes == 0 ? 0.0 : 100.0 * stats.ReusedNodeCount / totalNodes;
+
+            html.Append($"<tr{rowClass}>");
+            html.Append($"<td>{stats.Version}</td>");
+            html.Append($"<td class=\"edit\">{Encode(stats.Edit.ToString())}</td>");
+            html.Append($"<td class=\"map\">{RenderMap(stats, maxLength)}</td>");
+            html.Append($"<td>{stats.RelexedTokenCount} / {stats.TokenCount}</td>");
+            html.Append($"<td>{stats.ReusedNodeCount}</td>");
+            html.Append($"<td>{stats.RebuiltNodeCount}</td>");
+            html.Append($"<td>{Format(reuse, "F1")}%</td>");
+            html.Append($"<td>{Format(stats.LexTime.TotalMilliseconds, "F3")}</td>");
+            html.Append($"<td>{Format(stats.ParseTime.TotalMilliseconds, "F3")}</td>");
+            html.Append($"<td>{Format(stats.TotalTime.TotalMilliseconds, "F3")}</td>");
+            html.AppendLine("</tr>");
+        }
+
+        html.AppendLine("</table>");
+        html.AppendLine("</body>");
+        html.AppendLine("</html>");
+        return html.ToString();
+    }
+
+    private static string RenderMap(ReparseStats stats, int maxLength)
+    {
+        // The map is scaled to the longest document in the timeline so growth and shrinkage stay visible.
+        var width = Percent(stats.DocumentLength, maxLength);
+        var relexed = Span("relexed", stats.RelexedRange, stats.DocumentLength);
+        var damaged = Span("damaged", stats.DamagedRange, stats.DocumentLength);
+        var title = $"damaged {stats.DamagedRange}, re-lexed {stats.RelexedRange} of {stats.DocumentLength} chars";
+
+        return $"<div class=\"map\" style=\"width: {width}%\" title=\"{Encode(title)}\">{relexed}{damaged}</div>";
+    }
+
+    private static string Span(string cssClass, TextRange range, int documentLength)
+    {
+        var length = Math.Max(1, documentLength);
+        return $"<span class=\"{cssClass}\" style=\"left: {Percent(range.Start, length)}%; width: {Percent(range.Length, length)}%\"></span>";
+    }
+
+    private static string Percent(int value, int total)
+    {
+        return Format(100.0 * value / total, "F2");
+    }
+
+    private static string Format(double value, string format)
+    {
+        return value.ToString(format, CultureInfo.InvariantCulture);
+    }
+
+    private static string Encode(string text)
+    {
+        return WebUtility.HtmlEncode(text);
+    }
+}
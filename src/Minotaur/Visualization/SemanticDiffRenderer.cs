@@ -0,0 +1,269 @@
+/*
+ * This file is part of Minotaur.
+ *
+ * Minotaur is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published
+ * by the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Minotaur is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with Minotaur. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+using System.Net;
+using System.Text;
+using Minotaur.Diagnostics;
+using Minotaur.Parser;
+
+namespace Minotaur.Visualization;
+
+/// <summary>
+/// Renders a <see cref="SemanticDiff"/> as terminal text with ANSI colors or as an HTML page. Deleted and inserted
+/// lines are marked with <c>-</c> and <c>+</c> and their changed tokens are highlighted; the lines of a move are
+/// marked with <c>&lt;</c> where the subtree was and <c>&gt;</c> where it is, under a header naming the other end.
+/// </summary>
+public class SemanticDiffRenderer
+{
+    private const string Reset = "\u001b[0m";
+    private const string Dim = "\u001b[2m";
+    private const string Red = "\u001b[31m";
+    private const string Green = "\u001b[32m";
+    private const string Magenta = "\u001b[35m";
+    private const string Cyan = "\u001b[36m";
+    private const string Reverse = "\u001b[7m";
+    private const string NoReverse = "\u001b[27m";
+
+    private const string Styles = @"body { font-family: sans-serif; margin: 1.5em; }
+.diff { font-family: monospace; white-space: pre; border: 1px solid #d0d7de; }
+.line { display: flex; }
+.line .number { width: 4em; padding-right: 0.5em; text-align: right; color: #6e7781; user-select: none; }
+.line .marker { width: 1.5em; text-align: center; user-select: none; }
+.line .text { flex: 1; }
+.deleted { background: #ffebe9; }
+.deleted .change { background: #ff818266; }
+.inserted { background: #e6ffec; }
+.inserted .change { background: #abf2bc; }
+.moved-from { background: #fbefff; }
+.moved-from .change { background: #e8b5f7; }
+.moved-to { background: #ddf4ff; }
+.moved-to .change { background: #9ecbff; }
+.move { padding: 0.2em 0.5em; color: #6e7781; font-style: italic; }";
+
+    /// <summary>
+    /// Renders the diff as terminal text, showing changed lines with some unchanged lines around them.
+    /// </summary>
+    /// <param name="diff">The diff.</param>
+    /// <param name="context">The number of unchanged lines shown around each change.</param>
+    /// <param name="color">Whether to color the lines and highlight changed tokens with ANSI escapes.</param>
+    /// <returns>The text, or an empty string if nothing changed.</returns>
+    public string RenderTerminal(SemanticDiff diff, int context = 3, bool color = true)
+    {
+        ArgumentNullException.ThrowIfNull(diff);
+        ArgumentOutOfRangeException.ThrowIfNegative(context);
+
+        var text = new StringBuilder();
+        foreach (var hunk in Hunks(diff, context))
+        {
+            var first = diff.Lines[hunk.Start];
+            text.Append(color ? Cyan : string.Empty)
+                .Append($"@@ -{first.OldLine ?? OldLineBefore(diff, hunk.Start)} +{first.NewLine ?? NewLineBefore(diff, hunk.Start)} @@")
+                .Append(color ? Reset : string.Empty)
+                .Append('\n');
+
+            for (var i = hunk.Start; i < hunk.End; i++)
+            {
+                var line = diff.Lines[i];
+                if (MoveHeader(diff, i) is { } header)
+                {
+                    text.Append(color ? Dim : string.Empty).Append(header).Append(color ? Reset : string.Empty).Append('\n');
+                }
+
+                var (marker, lineColor) = line.Kind switch
+                {
+                    SemanticDiffLineKind.Deleted => ('-', Red),
+                    SemanticDiffLineKind.Inserted => ('+', Green),
+                    SemanticDiffLineKind.MovedFrom => ('<', Magenta),
+                    SemanticDiffLineKind.MovedTo => ('>', Cyan),
+                    _ => (' ', string.Empty)
+                };
+
+                // Every physical line of a line joined by a multi-line token gets the marker.
+                var start = color ? lineColor + marker : marker.ToString();
+                var end = color && lineColor.Length > 0 ? Reset : string.Empty;
+                text.Append(start);
+                foreach (var (segment, highlighted) in Segments(line))
+                {
+                    if (segment == "\n")
+                    {
+                        text.Append(end).Append('\n').Append(start);
+                        continue;
+                    }
+
+                    text.Append(color && highlighted ? Reverse : string.Empty)
+                        .Append(segment)
+                        .Append(color && highlighted ? NoReverse : string.Empty);
+                }
+
+                text.Append(end).Append('\n');
+            }
+        }
+
+        return text.ToString();
+    }
+
+    /// <summary>
+    /// Renders the whole diff as an HTML page.
+    /// </summary>
+    /// <param name="diff">The diff.</param>
+    /// <param name="title">An optional title, such as the file name.</param>
+    /// <returns>The HTML document.</returns>
+    public string RenderHtml(SemanticDiff diff, string? title = null)
+    {
+        ArgumentNullException.ThrowIfNull(diff);
+
+        var heading = string.IsNullOrEmpty(title) ? "Semantic diff" : $"Semantic diff: {title}";
+        var html = new StringBuilder();
+
+        html.AppendLine("<!DOCTYPE html>");
+        html.AppendLine("<html>");
+        html.AppendLine("<head>");
+        html.AppendLine("<meta charset=\"utf-8\">");
+        html.AppendLine($"<title>{Encode(heading)}</title>");
+        html.AppendLine("<style>");
+        html.AppendLine(Styles);
+        html.AppendLine("</style>");
+        html.AppendLine("</head>");
+        html.AppendLine("<body>");
+        html.AppendLine($"<h1>{Encode(heading)}</h1>");
+        html.AppendLine("<div class=\"diff\">");
+
+        for (var i = 0; i < diff.Lines.Count; i++)
+        {
+            var line = diff.Lines[i];
+            if (MoveHeader(diff, i) is { } header)
+            {
+                var end = line.Kind == SemanticDiffLineKind.MovedFrom ? "to" : "from";
+                var other = line.Kind == SemanticDiffLineKind.MovedFrom ? "from" : "to";
+                html.AppendLine($"<div class=\"move\" id=\"move-{line.Move}-{other}\"><a href=\"#move-{line.Move}-{end}\">{Encode(header)}</a></div>");
+            }
+
+            var (cssClass, marker) = line.Kind switch
+            {
+                SemanticDiffLineKind.Deleted => ("deleted", "-"),
+                SemanticDiffLineKind.Inserted => ("inserted", "+"),
+                SemanticDiffLineKind.MovedFrom => ("moved-from", "&lt;"),
+                SemanticDiffLineKind.MovedTo => ("moved-to", "&gt;"),
+                _ => ("unchanged", string.Empty)
+            };
+
+            html.Append($"<div class=\"line {cssClass}\">");
+            html.Append($"<span class=\"number\">{line.OldLine}</span><span class=\"number\">{line.NewLine}</span>");
+            html.Append($"<span class=\"marker\">{marker}</span><span class=\"text\">");
+            foreach (var (segment, highlighted) in Segments(line))
+            {
+                html.Append(highlighted ? $"<span class=\"change\">{Encode(segment)}</span>" : Encode(segment));
+            }
+
+            html.AppendLine("</span></div>");
+        }
+
+        html.AppendLine("</div>");
+        html.AppendLine("</body>");
+        html.AppendLine("</html>");
+        return html.ToString();
+    }
+
+    private static IEnumerable<(int Start, int End)> Hunks(SemanticDiff diff, int context)
+    {
+        var i = 0;
+        while (i < diff.Lines.Count)
+        {
+            if (diff.Lines[i].Kind == SemanticDiffLineKind.Unchanged)
+            {
+                i++;
+                continue;
+            }
+
+            // Changes closer than twice the context share a hunk, as in unified diffs.
+            var start = Math.Max(0, i - context);
+            var end = i;
+            for (var next = i + 1; next < diff.Lines.Count && next - end <= 2 * context; next++)
+            {
+                if (diff.Lines[next].Kind != SemanticDiffLineKind.Unchanged)
+                {
+                    end = next;
+                }
+            }
+
+            end = Math.Min(diff.Lines.Count, end + context + 1);
+            yield return (start, end);
+            i = end;
+        }
+    }
+
+    private static string? MoveHeader(SemanticDiff diff, int index)
+    {
+        var line = diff.Lines[index];
+        if (line.Move is not { } move || (index > 0 && diff.Lines[index - 1].Move == move && diff.Lines[index - 1].Kind == line.Kind))
+        {
+            return null;
+        }
+
+        var moved = diff.Moves[move];
+        return line.Kind == SemanticDiffLineKind.MovedFrom
+            ? $"<<< <{moved.Rule}> moved to line {moved.NewLocation.Line}"
+            : $">>> <{moved.Rule}> moved from line {moved.OldLocation.Line}";
+    }
+
+    private static int OldLineBefore(SemanticDiff diff, int index)
+    {
+        return diff.Lines.Take(index).LastOrDefault(l => l.OldLine != null)?.OldLine ?? 0;
+    }
+
+    private static int NewLineBefore(SemanticDiff diff, int index)
+    {
+        return diff.Lines.Take(index).LastOrDefault(l => l.NewLine != null)?.NewLine ?? 0;
+    }
+
+    // Splits a line into runs of highlighted and plain text, with each line break as a run of its own.
+    private static List<(string Text, bool Highlighted)> Segments(SemanticDiffLine line)
+    {
+        var segments = new List<(string, bool)>();
+        var position = 0;
+        foreach (var range in line.Highlights.Append(new TextRange(line.Text.Length, 0)))
+        {
+            AddSegments(segments, line.Text[position..range.Start], false);
+            AddSegments(segments, line.Text.Substring(range.Start, range.Length), true);
+            position = range.End;
+        }
+
+        return segments;
+    }
+
+    private static void AddSegments(List<(string, bool)> segments, string text, bool highlighted)
+    {
+        var parts = text.ReplaceLineEndings("\n").Split('\n');
+        for (var i = 0; i < parts.Length; i++)
+        {
+            if (i > 0)
+            {
+                segments.Add(("\n", false));
+            }
+
+            if (parts[i].Length > 0)
+            {
+                segments.Add((parts[i], highlighted));
+            }
+        }
+    }
+
+    private static string Encode(string text)
+    {
+        return WebUtility.HtmlEncode(text);
+    }
+}
@@ -0,0 +1,134 @@
+//! Prints the summary Minotaur's differential harness compares against:
+//! `{ "items": [{ "kind", "name", "line", "column" }], "identifiers": [...] }`.
+//!
+//! Items are reported where Minotaur's Rust grammar starts them: after outer
+//! attributes and visibility. Columns are 1-based.
+
+use std::collections::BTreeSet;
+use std::{env, fs, process};
+
+use proc_macro2::Span;
+use serde_json::{json, Value};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+#[derive(Default)]
+struct Collector {
+    items: Vec<Value>,
+    identifiers: BTreeSet<String>,
+}
+
+impl Collector {
+    fn push(&mut self, kind: &str, name: Option<String>, start: Span) {
+        let location = start.start();
+        self.items.push(json!({
+            "kind": kind,
+            "name": name,
+            "line": location.line,
+            "column": location.column + 1,
+        }));
+    }
+}
+
+impl<'ast> Visit<'ast> for Collector {
+    fn visit_item(&mut self, item: &'ast syn::Item) {
+        use syn::Item;
+
+        let (kind, name, start) = match item {
+            Item::Const(i) => ("const", Some(i.ident.to_string()), i.const_token.span),
+            Item::Enum(i) => ("enum", Some(i.ident.to_string()), i.enum_token.span),
+            Item::ExternCrate(i) => ("extern_crate", Some(i.ident.to_string()), i.extern_token.span),
+            Item::Fn(i) => ("fn", Some(i.sig.ident.to_string()), i.sig.span()),
+            Item::ForeignMod(i) => ("foreign_mod", None, i.unsafety.map_or(i.abi.extern_token.span, |u| u.span)),
+            Item::Impl(i) => ("impl", None, i.unsafety.map_or(i.impl_token.span, |u| u.span)),
+            Item::Macro(i) => ("macro", i.ident.as_ref().map(|n| n.to_string()), i.mac.path.span()),
+            Item::Mod(i) => ("mod", Some(i.ident.to_string()), i.unsafety.map_or(i.mod_token.span, |u| u.span)),
+            Item::Static(i) => ("static", Some(i.ident.to_string()), i.static_token.span),
+            Item::Struct(i) => ("struct", Some(i.ident.to_string()), i.struct_token.span),
+            Item::Trait(i) => ("trait", Some(i.ident.to_string()), i.unsafety.map_or(i.trait_token.span, |u| u.span)),
+            Item::TraitAlias(i) => ("trait_alias", Some(i.ident.to_string()), i.trait_token.span),
+            Item::Type(i) => ("type", Some(i.ident.to_string()), i.type_token.span),
+            Item::Union(i) => ("union", Some(i.ident.to_string()), i.union_token.span),
+            Item::Use(i) => ("use", None, i.use_token.span),
+            _ => ("verbatim", None, item.span()),
+        };
+
+        self.push(kind, name, start);
+        visit::visit_item(self, item);
+    }
+
+    // Associated and foreign items use Minotaur's item rules too, so they are counted as items.
+    fn visit_impl_item(&mut self, item: &'ast syn::ImplItem) {
+        use syn::ImplItem;
+
+        match item {
+            ImplItem::Const(i) => self.push("const", Some(i.ident.to_string()), i.const_token.span),
+            ImplItem::Fn(i) => self.push("fn", Some(i.sig.ident.to_string()), i.sig.span()),
+            ImplItem::Type(i) => self.push("type", Some(i.ident.to_string()), i.type_token.span),
+            ImplItem::Macro(i) => self.push("macro", None, i.mac.path.span()),
+            _ => {}
+        }
+
+        visit::visit_impl_item(self, item);
+    }
+
+    fn visit_trait_item(&mut self, item: &'ast syn::TraitItem) {
+        use syn::TraitItem;
+
+        match item {
+            TraitItem::Const(i) => self.push("const", Some(i.ident.to_string()), i.const_token.span),
+            TraitItem::Fn(i) => self.push("fn", Some(i.sig.ident.to_string()), i.sig.span()),
+            TraitItem::Type(i) => self.push("type", Some(i.ident.to_string()), i.type_token.span),
+            TraitItem::Macro(i) => self.push("macro", None, i.mac.path.span()),
+            _ => {}
+        }
+
+        visit::visit_trait_item(self, item);
+    }
+
+    fn visit_foreign_item(&mut self, item: &'ast syn::ForeignItem) {
+        use syn::ForeignItem;
+
+        match item {
+            ForeignItem::Fn(i) => self.push("fn", Some(i.sig.ident.to_string()), i.sig.span()),
+            ForeignItem::Static(i) => self.push("static", Some(i.ident.to_string()), i.static_token.span),
+            ForeignItem::Macro(i) => self.push("macro", None, i.mac.path.span()),
+            _ => {}
+        }
+
+        visit::visit_foreign_item(self, item);
+    }
+
+    fn visit_ident(&mut self, ident: &'ast proc_macro2::Ident) {
+        self.identifiers.insert(ident.to_string());
+    }
+}
+
+fn main() {
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("usage: syn-dump <file.rs>");
+        process::exit(2);
+    };
+
+    let source = match fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("{path}: {err}");
+            process::exit(2);
+        }
+    };
+
+    let summary = match syn::parse_file(&source) {
+        Ok(file) => {
+            let mut collector = Collector::default();
+            collector.visit_file(&file);
+            json!({ "items": collector.items, "identifiers": collector.identifiers })
+        }
+        Err(err) => {
+            let location = err.span().start();
+            json!({ "error": format!("{}:{}: {}", location.line, location.column + 1, err) })
+        }
+    };
+
+    println!("{summary}");
+}